@@ -0,0 +1,747 @@
+//! `roc daemon`: a compiler that keeps running between builds, so that editors and scripts
+//! calling it over and over don't pay for starting one up every time.
+//!
+//! The daemon listens on a Unix socket. A client sends a single request per connection, and
+//! gets a single response back. Both are frames: a length in bytes, as a little-endian `u32`,
+//! followed by that many bytes of JSON.
+//!
+//! ```json
+//! { "args": ["check", "main.roc", "--time"], "cwd": "/home/me/project" }
+//! ```
+//!
+//! The `args` are what would follow `roc` on the command line: `build`, `check`, or `test`
+//! and their arguments, or just `shutdown` to stop the daemon. Paths are relative to `cwd`.
+//!
+//! ```json
+//! { "exit_code": 0, "stdout": "0 errors and 0 warnings found in 84 ms.\n", "stderr": "" }
+//! ```
+//!
+//! Like `--watch` does (see [crate::watch]), the daemon keeps the types of every program it
+//! has seen, per command and target, so the next request for the same program only
+//! type-checks the modules that changed. Requests are handled one at a time: each of them
+//! already uses every thread available, and the current directory and the output being
+//! captured belong to the whole process.
+//!
+//! The requests run in a worker process, which the daemon starts with `roc daemon --worker`
+//! and sends them to over its stdin. Many errors in the compiler still end the process, so
+//! when a request ends the worker, the daemon replies with what the worker printed and its
+//! exit code, and starts a new worker for the next request. That worker starts out without
+//! any types, like the daemon did.
+#![cfg_attr(not(unix), allow(dead_code, unused_imports))]
+
+use std::ffi::OsString;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ArgMatches;
+use serde_json::{json, Value};
+
+use crate::{DAEMON_REQUEST, FLAG_SOCKET, FLAG_WORKER};
+
+/// Anything longer is more likely garbage than a message
+const MAX_FRAME_BYTES: u32 = 1024 * 1024 * 1024;
+
+pub fn write_frame(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let bytes = message.to_string().into_bytes();
+    let len = (u32::try_from(bytes.len()).ok())
+        .filter(|len| *len <= MAX_FRAME_BYTES)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "message too big to send"))?;
+
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()
+}
+
+pub fn read_frame(reader: &mut impl Read) -> io::Result<Value> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len);
+
+    if len > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("a frame of {len} bytes is too big"),
+        ));
+    }
+
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes)?;
+
+    serde_json::from_slice(&bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+pub fn default_socket_path() -> PathBuf {
+    roc_packaging::cache::roc_cache_dir().join("daemon.sock")
+}
+
+#[cfg(not(unix))]
+pub fn main(_matches: &ArgMatches) -> io::Result<i32> {
+    eprintln!("`roc daemon` listens on a Unix socket, which this platform doesn't have.");
+
+    Ok(1)
+}
+
+/// Starts a daemon, or sends a request to the running one if the command line has one.
+#[cfg(unix)]
+pub fn main(matches: &ArgMatches) -> io::Result<i32> {
+    if matches.get_flag(FLAG_WORKER) {
+        return work();
+    }
+
+    let socket = match matches.get_one::<PathBuf>(FLAG_SOCKET) {
+        Some(socket) => socket.clone(),
+        None => default_socket_path(),
+    };
+
+    match matches.get_many::<OsString>(DAEMON_REQUEST) {
+        Some(args) => send(&socket, args.cloned().collect()),
+        None => serve(&socket),
+    }
+}
+
+/// Has the daemon listening on the socket run these arguments, and prints what they printed.
+/// Returns their exit code.
+#[cfg(unix)]
+pub fn send(socket: &Path, args: Vec<OsString>) -> io::Result<i32> {
+    use std::os::unix::net::UnixStream;
+
+    let args = (args.into_iter())
+        .map(OsString::into_string)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|arg| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("The daemon only takes UTF-8 arguments, which {arg:?} isn't."),
+            )
+        })?;
+
+    let mut stream = match UnixStream::connect(socket) {
+        Ok(stream) => stream,
+        Err(_) => {
+            eprintln!(
+                "No roc daemon is listening on {}. You can start one with `roc daemon`.",
+                socket.display()
+            );
+
+            return Ok(1);
+        }
+    };
+
+    let cwd = std::env::current_dir()?;
+    write_frame(
+        &mut stream,
+        &json!({ "args": args, "cwd": cwd.to_string_lossy() }),
+    )?;
+
+    let response = read_frame(&mut stream)?;
+
+    print!("{}", response["stdout"].as_str().unwrap_or_default());
+    eprint!("{}", response["stderr"].as_str().unwrap_or_default());
+
+    Ok(response["exit_code"].as_i64().map_or(1, |code| code as i32))
+}
+
+/// Listens on the socket until a client asks the daemon to shut down.
+#[cfg(unix)]
+pub fn serve(socket: &Path) -> io::Result<i32> {
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if UnixStream::connect(socket).is_ok() {
+        eprintln!("A roc daemon is already listening on {}.", socket.display());
+
+        return Ok(1);
+    }
+
+    // Left behind by a daemon that didn't get to shut down
+    let _ = std::fs::remove_file(socket);
+
+    if let Some(dir) = socket.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let listener = UnixListener::bind(socket)?;
+
+    println!("Listening on {}…", socket.display());
+
+    let mut worker = None;
+
+    loop {
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                eprintln!("Failed to accept a connection: {err}");
+
+                continue;
+            }
+        };
+
+        let request = match read_frame(&mut stream) {
+            Ok(request) => request,
+            Err(err) => {
+                eprintln!("Failed to read a request: {err}");
+
+                continue;
+            }
+        };
+
+        let shutdown = request["args"] == json!(["shutdown"]);
+
+        let reply = match shutdown {
+            true => response(0, String::new(), String::new()),
+            false => run_in_worker(&mut worker, worker_command, &request).unwrap_or_else(|err| {
+                response(
+                    1,
+                    String::new(),
+                    format!("The daemon failed to run this: {err}\n"),
+                )
+            }),
+        };
+
+        if let Err(err) = write_frame(&mut stream, &reply) {
+            eprintln!("Failed to send a response: {err}");
+        }
+
+        if shutdown {
+            if let Some(worker) = worker {
+                worker.stop()?;
+            }
+
+            std::fs::remove_file(socket)?;
+
+            return Ok(0);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn worker_command() -> std::process::Command {
+    use crate::CMD_DAEMON;
+
+    let roc = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("roc"));
+
+    let mut command = std::process::Command::new(roc);
+    command.args([CMD_DAEMON, &format!("--{FLAG_WORKER}")]);
+    command
+}
+
+/// The process that runs the requests of a daemon.
+#[cfg(unix)]
+struct Worker {
+    process: std::process::Child,
+    requests: std::process::ChildStdin,
+    responses: std::process::ChildStdout,
+}
+
+#[cfg(unix)]
+impl Worker {
+    fn spawn(mut command: std::process::Command) -> io::Result<Self> {
+        use std::process::Stdio;
+
+        let mut process = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        Ok(Self {
+            requests: process.stdin.take().unwrap(),
+            responses: process.stdout.take().unwrap(),
+            process,
+        })
+    }
+
+    /// Lets the worker finish, by closing the pipe its requests come from.
+    fn stop(self) -> io::Result<()> {
+        let Worker {
+            mut process,
+            requests,
+            ..
+        } = self;
+
+        drop(requests);
+        process.wait()?;
+
+        Ok(())
+    }
+}
+
+/// Has the worker run the request, starting one if there is none, and returns the reply.
+/// When the request ends the worker's process, the reply is what it printed until then.
+#[cfg(unix)]
+fn run_in_worker(
+    worker: &mut Option<Worker>,
+    new_worker: impl FnOnce() -> std::process::Command,
+    request: &Value,
+) -> io::Result<Value> {
+    let stdout = tempfile::NamedTempFile::new()?;
+    let stderr = tempfile::NamedTempFile::new()?;
+
+    let running = match worker {
+        Some(running) => running,
+        None => worker.insert(Worker::spawn(new_worker())?),
+    };
+
+    let message = json!({
+        "request": request,
+        "stdout": stdout.path().to_string_lossy(),
+        "stderr": stderr.path().to_string_lossy(),
+    });
+
+    let sent = write_frame(&mut running.requests, &message);
+
+    match sent.and_then(|()| read_frame(&mut running.responses)) {
+        Ok(reply) => Ok(reply),
+        Err(_) => {
+            let status = worker.take().unwrap().process.wait()?;
+
+            Ok(response(
+                // Only a signal leaves no exit code, and a panic would have been caught
+                status.code().unwrap_or(1),
+                std::fs::read_to_string(stdout.path())?,
+                std::fs::read_to_string(stderr.path())?,
+            ))
+        }
+    }
+}
+
+/// Runs the requests a daemon sends over stdin, until it closes stdin. The output of a request
+/// goes to the files the daemon sent with it.
+#[cfg(unix)]
+fn work() -> io::Result<i32> {
+    use std::os::unix::io::FromRawFd;
+
+    use bumpalo::Bump;
+    use roc_collections::MutMap;
+
+    use crate::watch::ARENA_RESET_BYTES;
+
+    // Keep stdout for the responses, and let anything printed outside of a request go to
+    // stderr instead of in between them.
+    let responses = unsafe { libc::dup(libc::STDOUT_FILENO) };
+
+    if responses < 0 || unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut responses = unsafe { std::fs::File::from_raw_fd(responses) };
+    let mut requests = io::stdin().lock();
+
+    let mut caches = MutMap::default();
+
+    loop {
+        let arena = Bump::new();
+        let mut layout_interners = MutMap::default();
+
+        while arena.allocated_bytes() < ARENA_RESET_BYTES {
+            let message = match read_frame(&mut requests) {
+                Ok(message) => message,
+                // The daemon is shutting down
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(0),
+                Err(err) => return Err(err),
+            };
+
+            let (Some(stdout), Some(stderr)) =
+                (message["stdout"].as_str(), message["stderr"].as_str())
+            else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "a request needs files for its stdout and stderr",
+                ));
+            };
+
+            let reply = handle_request(
+                &arena,
+                &mut caches,
+                &mut layout_interners,
+                &message["request"],
+                (Path::new(stdout), Path::new(stderr)),
+            );
+
+            write_frame(&mut responses, &reply)?;
+        }
+    }
+}
+
+fn response(exit_code: i32, stdout: String, stderr: String) -> Value {
+    json!({ "exit_code": exit_code, "stdout": stdout, "stderr": stderr })
+}
+
+/// The programs whose types the daemon keeps. `roc test` type-checks expects that the other
+/// commands skip, and the target decides layouts, so each of those gets types of its own.
+#[cfg(unix)]
+type SessionKey = (&'static str, PathBuf, roc_target::Target);
+
+#[cfg(unix)]
+fn handle_request<'a>(
+    arena: &'a bumpalo::Bump,
+    caches: &mut roc_collections::MutMap<SessionKey, roc_load::SharedIncrementalCache>,
+    layout_interners: &mut roc_collections::MutMap<
+        SessionKey,
+        roc_mono::layout::GlobalLayoutInterner<'a>,
+    >,
+    request: &Value,
+    output: (&Path, &Path),
+) -> Value {
+    use roc_load::{Incremental, IncrementalCache};
+
+    use crate::{CMD_BUILD, CMD_CHECK, CMD_TEST, ROC_FILE};
+
+    let (Some(args), Some(cwd)) = (request["args"].as_array(), request["cwd"].as_str()) else {
+        let message = "A request needs `args`, and a `cwd` to run them in.\n";
+
+        return response(1, String::new(), message.to_string());
+    };
+
+    if let Err(err) = std::env::set_current_dir(cwd) {
+        return response(1, String::new(), format!("Cannot run in {cwd}: {err}\n"));
+    }
+
+    let args = args.iter().filter_map(Value::as_str);
+    let matches = match crate::build_app().try_get_matches_from(["roc"].into_iter().chain(args)) {
+        Ok(matches) => matches,
+        Err(err) => {
+            // e.g. the output of `--help`
+            return match err.use_stderr() {
+                true => response(err.exit_code(), String::new(), err.to_string()),
+                false => response(err.exit_code(), err.to_string(), String::new()),
+            };
+        }
+    };
+
+    let (command, matches) = match matches.subcommand() {
+        Some((CMD_BUILD, matches)) => (CMD_BUILD, matches),
+        Some((CMD_CHECK, matches)) => (CMD_CHECK, matches),
+        Some((CMD_TEST, matches)) => (CMD_TEST, matches),
+        _ => {
+            let message =
+                "The daemon can only build, check, and test, e.g. `roc daemon check main.roc`.\n";
+
+            return response(1, String::new(), message.to_string());
+        }
+    };
+
+    if let Some(problem) = unsupported(command, matches) {
+        return response(1, String::new(), format!("{problem}\n"));
+    }
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let root = Path::new(cwd).join(path);
+    let key = (
+        command,
+        root.canonicalize().unwrap_or(root),
+        target(command, matches),
+    );
+
+    let incremental = Incremental {
+        cache: (caches.entry(key.clone()))
+            .or_insert_with(IncrementalCache::new_shared)
+            .clone(),
+        layout_interner: layout_interners.remove(&key),
+    };
+
+    let captured = capture_output(output, || run(arena, command, matches, key.2, incremental));
+
+    match captured {
+        Ok((Ok(Ok((exit_code, layout_interner))), stdout, stderr)) => {
+            if let Some(layout_interner) = layout_interner {
+                layout_interners.insert(key, layout_interner);
+            }
+
+            response(exit_code, stdout, stderr)
+        }
+        Ok((Ok(Err(err)), stdout, stderr)) => response(1, stdout, format!("{stderr}{err}\n")),
+        Ok((Err(_panic), stdout, stderr)) => {
+            // Whatever the load was in the middle of is better not reused
+            caches.remove(&key);
+
+            response(101, stdout, stderr)
+        }
+        Err(err) => response(
+            1,
+            String::new(),
+            format!("Failed to capture the output: {err}\n"),
+        ),
+    }
+}
+
+/// What the daemon won't do for a command, and would rather say so than have the worker's
+/// process end over it.
+#[cfg(unix)]
+fn unsupported(command: &str, matches: &ArgMatches) -> Option<String> {
+    use crate::{
+        CMD_BUILD, FLAG_BUNDLE, FLAG_LIB, FLAG_MAX_THREADS, FLAG_NO_LINK, FLAG_PROFILE_COMPILER,
+        ROC_FILE,
+    };
+
+    let Some(path) = matches.get_one::<PathBuf>(ROC_FILE) else {
+        return Some(format!("Which .roc file should the daemon {command}?"));
+    };
+
+    if !path.exists() {
+        return Some(format!("This file was not found: {}", path.display()));
+    }
+
+    if matches.get_one::<usize>(FLAG_MAX_THREADS) == Some(&0) {
+        return Some("cannot build with at most 0 threads".to_string());
+    }
+
+    // The profile covers a whole process, and the worker handles many requests.
+    if matches.contains_id(FLAG_PROFILE_COMPILER) {
+        return Some(format!(
+            "The daemon doesn't profile the compiler; use `roc {command} --{FLAG_PROFILE_COMPILER}` for that."
+        ));
+    }
+
+    if command == CMD_BUILD {
+        if matches.contains_id(FLAG_BUNDLE) {
+            return Some(format!(
+                "The daemon doesn't bundle packages; use `roc build --{FLAG_BUNDLE}` for that."
+            ));
+        }
+
+        if matches.get_flag(FLAG_LIB) && matches.get_flag(FLAG_NO_LINK) {
+            return Some("build can only be one of `--lib` or `--no-link`".to_string());
+        }
+    }
+
+    None
+}
+
+#[cfg(unix)]
+fn target(command: &str, matches: &ArgMatches) -> roc_target::Target {
+    use std::str::FromStr;
+
+    use roc_target::Target;
+    use target_lexicon::Triple;
+
+    use crate::{CMD_TEST, FLAG_TARGET};
+
+    match command {
+        CMD_TEST => Triple::host().into(),
+        _ => (matches.get_one::<String>(FLAG_TARGET))
+            .and_then(|target| Target::from_str(target).ok())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(unix)]
+fn run<'a>(
+    arena: &'a bumpalo::Bump,
+    command: &str,
+    matches: &ArgMatches,
+    target: roc_target::Target,
+    incremental: roc_load::Incremental<'a>,
+) -> io::Result<(i32, Option<roc_mono::layout::GlobalLayoutInterner<'a>>)> {
+    use roc_build::link::LinkType;
+    use roc_build::program::{handle_error_module, handle_loading_problem};
+    use roc_build::program::{BuildFileError, BuiltFile};
+    use roc_packaging::cache::{self, RocCacheDir};
+    use roc_reporting::report::to_backend_failure_report_string;
+
+    use crate::{BuildConfig, CMD_BUILD, CMD_CHECK, CMD_TEST};
+    use crate::{FLAG_LIB, FLAG_NO_LINK, FLAG_OUTPUT, FLAG_RUN_EXPECTS, ROC_FILE};
+
+    match command {
+        CMD_CHECK if matches.get_flag(FLAG_RUN_EXPECTS) => {
+            Ok((crate::check_and_run_expects(matches, target)?, None))
+        }
+        CMD_CHECK => Ok((crate::check(arena, matches, Some(incremental))?, None)),
+        CMD_TEST => crate::run_tests(arena, matches, target, Some(incremental)),
+        CMD_BUILD => {
+            let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let link_type = match (matches.get_flag(FLAG_LIB), matches.get_flag(FLAG_NO_LINK)) {
+                (true, _) => LinkType::Dylib,
+                (false, true) => LinkType::None,
+                (false, false) => LinkType::Executable,
+            };
+            let out_path = matches.get_one::<OsString>(FLAG_OUTPUT).map(Path::new);
+            let roc_cache_dir = cache::roc_cache_dir();
+
+            let built = crate::build_with_flags(
+                arena,
+                matches,
+                &BuildConfig::BuildOnly,
+                target,
+                out_path,
+                RocCacheDir::Persistent(roc_cache_dir.as_path()),
+                link_type,
+                Some(incremental),
+            );
+
+            match built {
+                Ok(BuiltFile {
+                    binary_path,
+                    problems,
+                    total_time,
+                    expect_metadata,
+                }) => {
+                    let exit_code = crate::report_built_file(&binary_path, &problems, total_time);
+
+                    Ok((
+                        exit_code,
+                        Some(expect_metadata.layout_interner.into_global()),
+                    ))
+                }
+                Err(BuildFileError::ErrorModule { module, total_time }) => {
+                    let exit_code =
+                        handle_error_module(module, total_time, path.as_os_str(), true)?;
+
+                    Ok((exit_code, None))
+                }
+                Err(BuildFileError::LoadingProblem(problem)) => {
+                    Ok((handle_loading_problem(problem)?, None))
+                }
+                Err(BuildFileError::BackendFailure { filename, failure }) => {
+                    eprintln!("{}", to_backend_failure_report_string(filename, failure));
+
+                    Ok((1, None))
+                }
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Runs `f` with the stdout and stderr of this process going to these files instead, and
+/// returns what ended up in them. A panic in `f` is caught, after the panic hook printed it.
+#[cfg(unix)]
+fn capture_output<T>(
+    (stdout, stderr): (&Path, &Path),
+    f: impl FnOnce() -> T,
+) -> io::Result<(std::thread::Result<T>, String, String)> {
+    use std::io::{Seek, SeekFrom};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let open = |path| {
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+    };
+    let mut stdout = open(stdout)?;
+    let mut stderr = open(stderr)?;
+
+    let result = {
+        let _stdout = Redirect::new(libc::STDOUT_FILENO, &stdout)?;
+        let _stderr = Redirect::new(libc::STDERR_FILENO, &stderr)?;
+
+        catch_unwind(AssertUnwindSafe(f))
+    };
+
+    let read = |file: &mut std::fs::File| -> io::Result<String> {
+        let mut bytes = Vec::new();
+        file.seek(SeekFrom::Start(0))?;
+        file.read_to_end(&mut bytes)?;
+
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    };
+
+    Ok((result, read(&mut stdout)?, read(&mut stderr)?))
+}
+
+/// Points a file descriptor of this process to a file, until dropped.
+#[cfg(unix)]
+struct Redirect {
+    fd: libc::c_int,
+    original: libc::c_int,
+}
+
+#[cfg(unix)]
+impl Redirect {
+    fn new(fd: libc::c_int, to: &std::fs::File) -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        flush_std_streams();
+
+        let original = unsafe { libc::dup(fd) };
+
+        if original < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        if unsafe { libc::dup2(to.as_raw_fd(), fd) } < 0 {
+            let err = io::Error::last_os_error();
+            unsafe { libc::close(original) };
+
+            return Err(err);
+        }
+
+        Ok(Self { fd, original })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Redirect {
+    fn drop(&mut self) {
+        flush_std_streams();
+
+        unsafe {
+            libc::dup2(self.original, self.fd);
+            libc::close(self.original);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn flush_std_streams() {
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_round_trip() {
+        let message = json!({ "args": ["check", "main.roc"], "cwd": "/tmp" });
+
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &message).unwrap();
+
+        assert_eq!(&buf[..4], &(buf.len() as u32 - 4).to_le_bytes());
+        assert_eq!(read_frame(&mut buf.as_slice()).unwrap(), message);
+    }
+
+    #[test]
+    fn frame_too_big() {
+        let buf = (MAX_FRAME_BYTES + 1).to_le_bytes();
+        let err = read_frame(&mut buf.as_slice()).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn worker_ended_by_a_request() {
+        let exits_with = |code: i32| {
+            move || {
+                let mut command = std::process::Command::new("sh");
+                command.args(["-c", &format!("exit {code}")]);
+                command
+            }
+        };
+        let request = json!({ "args": ["check", "main.roc"], "cwd": "/tmp" });
+
+        let mut worker = None;
+
+        let reply = run_in_worker(&mut worker, exits_with(3), &request).unwrap();
+        assert_eq!(reply, response(3, String::new(), String::new()));
+        assert!(worker.is_none());
+
+        // the next request gets a new worker
+        let reply = run_in_worker(&mut worker, exits_with(4), &request).unwrap();
+        assert_eq!(reply["exit_code"], 4);
+    }
+
+    #[test]
+    fn frame_cut_short() {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &json!({ "exit_code": 0 })).unwrap();
+        buf.pop();
+
+        let err = read_frame(&mut buf.as_slice()).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
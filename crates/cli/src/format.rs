@@ -6,7 +6,7 @@ use bumpalo::Bump;
 use roc_error_macros::{internal_error, user_error};
 use roc_fmt::def::fmt_defs;
 use roc_fmt::module::fmt_module;
-use roc_fmt::{Ast, Buf};
+use roc_fmt::{Ast, Buf, FormatConfig};
 use roc_parse::module::parse_module_defs;
 use roc_parse::remove_spaces::RemoveSpaces;
 use roc_parse::{module, parser::SyntaxError, state::State};
@@ -62,21 +62,29 @@ fn is_roc_file(path: &Path) -> bool {
     matches!(path.extension().and_then(OsStr::to_str), Some("roc"))
 }
 
-pub fn format_files(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(), String> {
+pub fn format_files(
+    files: std::vec::Vec<PathBuf>,
+    mode: FormatMode,
+    config: FormatConfig,
+) -> Result<(), String> {
     let arena = Bump::new();
     let mut files_to_reformat = Vec::new(); // to track which files failed `roc format --check`
 
     for file in flatten_directories(files) {
         let src = std::fs::read_to_string(&file).unwrap();
 
-        match format_src(&arena, &src) {
+        match format_src(&arena, &src, config) {
             Ok(buf) => {
                 match mode {
                     FormatMode::CheckOnly => {
-                        // If a file fails `format --check`, add it to the file
-                        // list for reporting afterwards.
+                        // If a file fails `format --check`, show what would change and add it
+                        // to the file list for reporting afterwards.
                         if buf.as_str() != src {
-                            files_to_reformat.push(file.display().to_string());
+                            let name = file.display().to_string();
+
+                            print!("{}", unified_diff(&name, &src, &buf));
+
+                            files_to_reformat.push(name);
                         }
                     }
                     FormatMode::WriteToFile => {
@@ -165,6 +173,15 @@ pub fn format_files(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(
     Ok(())
 }
 
+/// A unified diff from `before` to `after`, like `diff -u` prints, with `name` in the headers.
+/// Empty if the two are the same.
+pub fn unified_diff(name: &str, before: &str, after: &str) -> String {
+    similar::TextDiff::from_lines(before, after)
+        .unified_diff()
+        .header(name, name)
+        .to_string()
+}
+
 #[derive(Debug)]
 pub enum FormatProblem {
     ParsingFailed {
@@ -182,11 +199,11 @@ pub enum FormatProblem {
     },
 }
 
-pub fn format_src(arena: &Bump, src: &str) -> Result<String, FormatProblem> {
+pub fn format_src(arena: &Bump, src: &str, config: FormatConfig) -> Result<String, FormatProblem> {
     let ast = arena.alloc(parse_all(arena, src).unwrap_or_else(|e| {
         user_error!("Unexpected parse failure when parsing this formatting:\n\n{:?}\n\nParse error was:\n\n{:?}\n\n", src, e)
     }));
-    let mut buf = Buf::new_in(arena);
+    let mut buf = Buf::new_in_with_config(arena, config);
     fmt_all(&mut buf, ast);
 
     let reparsed_ast = match arena.alloc(parse_all(arena, buf.as_str())) {
@@ -216,7 +233,7 @@ pub fn format_src(arena: &Bump, src: &str) -> Result<String, FormatProblem> {
     }
 
     // Now verify that the resultant formatting is _stable_ - i.e. that it doesn't change again if re-formatted
-    let mut reformatted_buf = Buf::new_in(arena);
+    let mut reformatted_buf = Buf::new_in_with_config(arena, config);
 
     fmt_all(&mut reformatted_buf, reparsed_ast);
 
@@ -293,7 +310,11 @@ main =
         let dir = tempdir().unwrap();
         let file_path = setup_test_file(dir.path(), "test1.roc", UNFORMATTED_ROC);
 
-        let result = format_files(vec![file_path.clone()], FormatMode::CheckOnly);
+        let result = format_files(
+            vec![file_path.clone()],
+            FormatMode::CheckOnly,
+            FormatConfig::default(),
+        );
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
@@ -312,7 +333,11 @@ main =
         let file1 = setup_test_file(dir.path(), "test1.roc", UNFORMATTED_ROC);
         let file2 = setup_test_file(dir.path(), "test2.roc", UNFORMATTED_ROC);
 
-        let result = format_files(vec![file1, file2], FormatMode::CheckOnly);
+        let result = format_files(
+            vec![file1, file2],
+            FormatMode::CheckOnly,
+            FormatConfig::default(),
+        );
         assert!(result.is_err());
         let error_message = result.unwrap_err();
         assert!(error_message.contains("test1.roc") && error_message.contains("test2.roc"));
@@ -325,7 +350,11 @@ main =
         let dir = tempdir().unwrap();
         let file_path = setup_test_file(dir.path(), "formatted.roc", FORMATTED_ROC);
 
-        let result = format_files(vec![file_path], FormatMode::CheckOnly);
+        let result = format_files(
+            vec![file_path],
+            FormatMode::CheckOnly,
+            FormatConfig::default(),
+        );
         assert!(result.is_ok());
 
         cleanup_temp_dir(dir);
@@ -341,6 +370,7 @@ main =
         let result = format_files(
             vec![file_formatted, file1_unformated, file2_unformated],
             FormatMode::CheckOnly,
+            FormatConfig::default(),
         );
         assert!(result.is_err());
         let error_message = result.unwrap_err();
@@ -349,4 +379,13 @@ main =
 
         cleanup_temp_dir(dir);
     }
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(
+            unified_diff("test.roc", "x = 1\ny =  2\n", "x = 1\ny = 2\n"),
+            "--- test.roc\n+++ test.roc\n@@ -1,2 +1,2 @@\n x = 1\n-y =  2\n+y = 2\n"
+        );
+        assert_eq!(unified_diff("test.roc", "x = 1\n", "x = 1\n"), "");
+    }
 }
@@ -14,18 +14,21 @@ use roc_build::program::{
     BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, DEFAULT_ROC_FILENAME,
 };
 #[cfg(not(windows))]
-use roc_collections::MutMap;
+use roc_collections::{MutMap, VecMap};
 use roc_error_macros::{internal_error, user_error};
-use roc_gen_dev::AssemblyBackendMode;
+use roc_gen_dev::{AssemblyBackendMode, Hardening};
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
-use roc_load::{ExpectMetadata, Threading};
+use roc_load::{ExpectMetadata, Incremental, Threading};
 #[cfg(not(windows))]
 use roc_module::symbol::ModuleId;
 use roc_mono::ir::OptLevel;
+#[cfg(not(windows))]
+use roc_mono::layout::GlobalLayoutInterner;
 use roc_packaging::cache::RocCacheDir;
 use roc_packaging::tarball::Compression;
-#[cfg(not(windows))]
-use roc_reporting::report::ANSI_STYLE_CODES;
+use roc_repl_eval::snapshot::SnapshotMode;
+use roc_reporting::cli::Problems;
+use roc_reporting::report::{to_backend_failure_report_string, ANSI_STYLE_CODES};
 use roc_target::{Architecture, Target};
 use std::env;
 use std::ffi::{CString, OsStr, OsString};
@@ -34,7 +37,6 @@ use std::mem::ManuallyDrop;
 use std::os::raw::{c_char, c_int};
 use std::path::{Path, PathBuf};
 use std::process;
-#[cfg(not(windows))]
 use std::time::Duration;
 use std::time::Instant;
 use strum::IntoEnumIterator;
@@ -42,7 +44,10 @@ use strum::IntoEnumIterator;
 use tempfile::TempDir;
 
 mod format;
-pub use format::{format_files, format_src, FormatMode};
+pub use format::{format_files, format_src, unified_diff, FormatMode};
+
+pub mod daemon;
+pub mod watch;
 
 pub const CMD_BUILD: &str = "build";
 pub const CMD_RUN: &str = "run";
@@ -50,14 +55,25 @@ pub const CMD_DEV: &str = "dev";
 pub const CMD_REPL: &str = "repl";
 pub const CMD_DOCS: &str = "docs";
 pub const CMD_CHECK: &str = "check";
+pub const CMD_EXPLAIN: &str = "explain";
 pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
+pub const CMD_BENCH: &str = "bench";
+pub const CMD_DAEMON: &str = "daemon";
 pub const CMD_GLUE: &str = "glue";
 pub const CMD_GEN_STUB_LIB: &str = "gen-stub-lib";
 pub const CMD_PREPROCESS_HOST: &str = "preprocess-host";
 
 pub const FLAG_EMIT_LLVM_IR: &str = "emit-llvm-ir";
+pub const FLAG_EMIT_REUSE_REPORT: &str = "emit-reuse-report";
+pub const FLAG_WARN_UNUSED: &str = "warn-unused";
+pub const FLAG_ATOMIC_REFCOUNTS: &str = "atomic-refcounts";
+pub const FLAG_BRANCH_TARGETS: &str = "branch-targets";
+pub const FLAG_RETPOLINES: &str = "retpolines";
+pub const FLAG_CANONICALIZE_NANS: &str = "canonicalize-nans";
+pub const FLAG_PATCHABLE_CALLS: &str = "patchable-calls";
+pub const FLAG_HEAP_PROFILING: &str = "heap-profiling";
 pub const FLAG_PROFILING: &str = "profiling";
 pub const FLAG_BUNDLE: &str = "bundle";
 pub const FLAG_DEV: &str = "dev";
@@ -74,16 +90,33 @@ pub const FLAG_PREBUILT: &str = "prebuilt-platform";
 pub const FLAG_CHECK: &str = "check";
 pub const FLAG_STDIN: &str = "stdin";
 pub const FLAG_STDOUT: &str = "stdout";
+pub const FLAG_MAX_WIDTH: &str = "max-width";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_WASM_ALLOCATOR: &str = "wasm-allocator";
 pub const FLAG_OUTPUT: &str = "output";
+pub const FLAG_DOCS_FORMAT: &str = "format";
 pub const FLAG_FUZZ: &str = "fuzz";
 pub const FLAG_MAIN: &str = "main";
+pub const FLAG_RUN_EXPECTS: &str = "run-expects";
+pub const FLAG_UPDATE_SNAPSHOTS: &str = "update-snapshots";
+pub const FLAG_COVERAGE: &str = "coverage";
+pub const FLAG_BASELINE: &str = "baseline";
+pub const FLAG_SAVE_BASELINE: &str = "save-baseline";
+pub const FLAG_WATCH: &str = "watch";
+pub const FLAG_EMIT_DEPS: &str = "emit-deps";
+pub const FLAG_PROFILE_COMPILER: &str = "profile-compiler";
+pub const FLAG_SOCKET: &str = "socket";
+pub const FLAG_WORKER: &str = "worker";
+pub const FLAG_WASM: &str = "wasm";
+pub const FLAG_INTERP: &str = "interp";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_DIR: &str = "GLUE_DIR";
 pub const GLUE_SPEC: &str = "GLUE_SPEC";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
+pub const DAEMON_REQUEST: &str = "DAEMON_REQUEST";
+pub const ERROR_CODE: &str = "ERROR_CODE";
 pub const FLAG_PP_HOST: &str = "host";
 pub const FLAG_PP_PLATFORM: &str = "platform";
 pub const FLAG_PP_DYLIB: &str = "lib";
@@ -122,6 +155,54 @@ pub fn build_app() -> Command {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_emit_reuse_report = Arg::new(FLAG_EMIT_REUSE_REPORT)
+        .long(FLAG_EMIT_REUSE_REPORT)
+        .help("Print which heap allocations reuse the memory of a value that was dropped before them, and why the others could not")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_warn_unused = Arg::new(FLAG_WARN_UNUSED)
+        .long(FLAG_WARN_UNUSED)
+        .help("Print the top-level definitions of the app that the program never uses")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_atomic_refcounts = Arg::new(FLAG_ATOMIC_REFCOUNTS)
+        .long(FLAG_ATOMIC_REFCOUNTS)
+        .help("Update refcounts with atomic instructions, for platforms that share Roc values between threads\n(Only supported by the dev backend, on x86_64 and aarch64)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_branch_targets = Arg::new(FLAG_BRANCH_TARGETS)
+        .long(FLAG_BRANCH_TARGETS)
+        .help("Start every function with a landing pad, for hosts that enforce indirect branch tracking (CET IBT on x86_64, BTI on aarch64)\n(Only supported by the dev backend)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_retpolines = Arg::new(FLAG_RETPOLINES)
+        .long(FLAG_RETPOLINES)
+        .help("Never emit an indirect jump, and use a retpoline instead\n(Only supported by the dev backend, on x86_64)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_canonicalize_nans = Arg::new(FLAG_CANONICALIZE_NANS)
+        .long(FLAG_CANONICALIZE_NANS)
+        .help("Replace every NaN produced by float arithmetic with the same canonical NaN, so that all Wasm engines give bit-for-bit identical results\n(Only supported by the Wasm dev backend)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_patchable_calls = Arg::new(FLAG_PATCHABLE_CALLS)
+        .long(FLAG_PATCHABLE_CALLS)
+        .help("Call Roc functions through the function table, so that the host can swap in new versions of them while the program runs\n(Only supported by the Wasm dev backend)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_heap_profiling = Arg::new(FLAG_HEAP_PROFILING)
+        .long(FLAG_HEAP_PROFILING)
+        .help("Tell roc_alloc which line of Roc code each heap allocation comes from, for heap profilers in the host\n(Only supported by the Wasm dev backend. The IDs are in the roc_alloc_site global, and the locations in the roc_alloc_sites custom section.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let flag_profiling = Arg::new(FLAG_PROFILING)
         .long(FLAG_PROFILING)
         .help("Keep debug info in the final generated program even in optimized builds")
@@ -134,6 +215,12 @@ pub fn build_app() -> Command {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_profile_compiler = Arg::new(FLAG_PROFILE_COMPILER)
+        .long(FLAG_PROFILE_COMPILER)
+        .help("Write a trace of each compilation phase, per module and per thread, to the given .json file\n(Open it in chrome://tracing or ui.perfetto.dev.)")
+        .value_parser(value_parser!(PathBuf))
+        .required(false);
+
     let flag_linker = Arg::new(FLAG_LINKER)
         .long(FLAG_LINKER)
         .help("Set which linker to use\n(The surgical linker is enabled by default only when building for wasm32 or x86_64 Linux, because those are the only targets it currently supports. Otherwise the legacy linker is used by default.)")
@@ -152,12 +239,24 @@ pub fn build_app() -> Command {
         .value_parser(value_parser!(u32))
         .required(false);
 
+    let flag_wasm_allocator = Arg::new(FLAG_WASM_ALLOCATOR)
+        .long(FLAG_WASM_ALLOCATOR)
+        .help("Where Roc code gets heap memory from: the platform's roc_alloc, a bump allocator that never frees, or a bump allocator that frees everything at the start of each call into Roc\n(Only supported by the Wasm dev backend. The platform must leave roc_alloc, roc_realloc and roc_dealloc undefined, unless this is \"host\".)")
+        .value_parser(["host", "bump", "arena-per-call"])
+        .required(false);
+
     let flag_fuzz = Arg::new(FLAG_FUZZ)
         .long(FLAG_FUZZ)
         .help("Instrument the roc binary for fuzzing with roc-fuzz")
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_watch = Arg::new(FLAG_WATCH)
+        .long(FLAG_WATCH)
+        .help("Do it all again every time a source file changes\n(Only the modules affected by a change get type-checked again.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let flag_main = Arg::new(FLAG_MAIN)
         .long(FLAG_MAIN)
         .help("The .roc file of the main app/package module to resolve dependencies from")
@@ -196,8 +295,18 @@ pub fn build_app() -> Command {
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_emit_llvm_ir.clone())
+            .arg(flag_emit_reuse_report.clone())
+            .arg(flag_warn_unused.clone())
+            .arg(flag_atomic_refcounts.clone())
+            .arg(flag_branch_targets.clone())
+            .arg(flag_retpolines.clone())
+            .arg(flag_canonicalize_nans.clone())
+            .arg(flag_patchable_calls.clone())
+            .arg(flag_heap_profiling.clone())
+            .arg(flag_wasm_allocator.clone())
             .arg(flag_profiling.clone())
             .arg(flag_time.clone())
+            .arg(flag_profile_compiler.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
@@ -213,7 +322,7 @@ pub fn build_app() -> Command {
             .arg(
                 Arg::new(FLAG_LIB)
                     .long(FLAG_LIB)
-                    .help("Build a C library instead of an executable")
+                    .help("Build a shared library (.so, .dylib or .dll) instead of an executable")
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
@@ -250,9 +359,11 @@ pub fn build_app() -> Command {
             .arg(flag_emit_llvm_ir.clone())
             .arg(flag_profiling.clone())
             .arg(flag_time.clone())
+            .arg(flag_profile_compiler.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_watch.clone())
             .arg(
                 Arg::new(FLAG_VERBOSE)
                     .long(FLAG_VERBOSE)
@@ -260,6 +371,20 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false)
             )
+            .arg(
+                Arg::new(FLAG_UPDATE_SNAPSHOTS)
+                    .long(FLAG_UPDATE_SNAPSHOTS)
+                    .help("Write the values of failing expects to their snapshot files, instead of reporting them\n(Only expects whose values are too large to check by eye are snapshotted.)")
+                    .action(ArgAction::SetTrue)
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_COVERAGE)
+                    .long(FLAG_COVERAGE)
+                    .help("After running the tests, list the functions and branches they never ran")
+                    .action(ArgAction::SetTrue)
+                    .required(false)
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file to test")
@@ -269,8 +394,74 @@ pub fn build_app() -> Command {
             )
             .arg(args_for_app.clone().last(true))
         )
+        .subcommand(Command::new(CMD_BENCH)
+            .about("Time all top-level benchmarks in a main module and any modules it imports\n(A benchmark is any top-level value whose name is `bench` followed by an uppercase letter or a digit, like `benchSort` or `bench2`. Benchmarks are always compiled with optimizations.)")
+            .arg(flag_main.clone())
+            .arg(flag_max_threads.clone())
+            .arg(
+                Arg::new(FLAG_BASELINE)
+                    .long(FLAG_BASELINE)
+                    .help("Compare the benchmarks against the times saved in this file by `--save-baseline`")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_SAVE_BASELINE)
+                    .long(FLAG_SAVE_BASELINE)
+                    .help("Save the benchmarks' times to this file, to compare against later with `--baseline`")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file to benchmark")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME)
+            )
+        )
+        .subcommand(Command::new(CMD_DAEMON)
+            .about("Keep a compiler running, so that building, checking, and testing the same programs again skips the startup and the unchanged modules\nGiven a command, has the running daemon run it instead, e.g. `roc daemon check main.roc`")
+            .arg(
+                Arg::new(FLAG_SOCKET)
+                    .long(FLAG_SOCKET)
+                    .help("The socket the daemon listens on\n(Defaults to daemon.sock in the roc cache directory.)")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_WORKER)
+                    .long(FLAG_WORKER)
+                    .help("Run the requests of a daemon, which sends them over stdin")
+                    .action(ArgAction::SetTrue)
+                    .hide(true)
+            )
+            .arg(
+                Arg::new(DAEMON_REQUEST)
+                    .help("A build, check, or test command for the running daemon, with its arguments, or shutdown to stop it")
+                    .value_parser(value_parser!(OsString))
+                    .num_args(0..)
+                    .trailing_var_arg(true)
+                    .allow_hyphen_values(true)
+            )
+        )
         .subcommand(Command::new(CMD_REPL)
             .about("Launch the interactive Read Eval Print Loop (REPL)")
+            .arg(
+                Arg::new(FLAG_WASM)
+                    .long(FLAG_WASM)
+                    .help("Compile expressions to WebAssembly, and run them in an interpreter like the web REPL does")
+                    .action(ArgAction::SetTrue)
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_INTERP)
+                    .long(FLAG_INTERP)
+                    .help("Run expressions with the mono IR interpreter instead of compiling them")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with(FLAG_WASM)
+                    .required(false)
+            )
         )
         .subcommand(Command::new(CMD_RUN)
             .about("Run a .roc file even if it has build errors")
@@ -279,8 +470,18 @@ pub fn build_app() -> Command {
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_emit_llvm_ir.clone())
+            .arg(flag_emit_reuse_report.clone())
+            .arg(flag_warn_unused.clone())
+            .arg(flag_atomic_refcounts.clone())
+            .arg(flag_branch_targets.clone())
+            .arg(flag_retpolines.clone())
+            .arg(flag_canonicalize_nans.clone())
+            .arg(flag_patchable_calls.clone())
+            .arg(flag_heap_profiling.clone())
+            .arg(flag_wasm_allocator.clone())
             .arg(flag_profiling.clone())
             .arg(flag_time.clone())
+            .arg(flag_profile_compiler.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
@@ -294,11 +495,22 @@ pub fn build_app() -> Command {
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_emit_llvm_ir.clone())
+            .arg(flag_emit_reuse_report.clone())
+            .arg(flag_warn_unused.clone())
+            .arg(flag_atomic_refcounts.clone())
+            .arg(flag_branch_targets.clone())
+            .arg(flag_retpolines.clone())
+            .arg(flag_canonicalize_nans.clone())
+            .arg(flag_patchable_calls.clone())
+            .arg(flag_heap_profiling.clone())
+            .arg(flag_wasm_allocator.clone())
             .arg(flag_profiling.clone())
             .arg(flag_time.clone())
+            .arg(flag_profile_compiler.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_watch.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -313,14 +525,14 @@ pub fn build_app() -> Command {
             .arg(
                 Arg::new(FLAG_CHECK)
                     .long(FLAG_CHECK)
-                    .help("Checks that specified files are formatted\n(If formatting is needed, return a non-zero exit code.)")
+                    .help("Checks that specified files are formatted\n(If formatting is needed, print a diff of the changes and return a non-zero exit code.)")
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
             .arg(
                 Arg::new(FLAG_STDIN)
                     .long(FLAG_STDIN)
-                    .help("Read file to format from stdin")
+                    .help("Read file to format from stdin\n(Unless --check is given, the formatted file is printed to stdout.)")
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
@@ -331,6 +543,13 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
+            .arg(
+                Arg::new(FLAG_MAX_WIDTH)
+                    .long(FLAG_MAX_WIDTH)
+                    .help("Break collections and operator chains that run past this column")
+                    .value_parser(value_parser!(usize))
+                    .required(false),
+            )
             .after_help("If DIRECTORY_OR_FILES is omitted, the .roc files in the current working\ndirectory are formatted.")
         )
         .subcommand(Command::new(CMD_VERSION)
@@ -339,7 +558,40 @@ pub fn build_app() -> Command {
             .about("Check the code for problems, but don’t build or run it")
             .arg(flag_main.clone())
             .arg(flag_time.clone())
+            .arg(flag_profile_compiler.clone())
             .arg(flag_max_threads.clone())
+            .arg(
+                Arg::new(FLAG_OUTPUT)
+                    .long(FLAG_OUTPUT)
+                    .help("Print problems as text for a terminal, or as JSON for editors and CI")
+                    .value_parser(["terminal", "json"])
+                    .required(false)
+                    .default_value("terminal"),
+            )
+            .arg(
+                Arg::new(FLAG_EMIT_DEPS)
+                    .long(FLAG_EMIT_DEPS)
+                    .help("Print which modules import which, with the size and build time of each module, as a Graphviz digraph or as JSON")
+                    .value_parser(["dot", "json"])
+                    .conflicts_with_all([FLAG_OUTPUT, FLAG_RUN_EXPECTS])
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_RUN_EXPECTS)
+                    .long(FLAG_RUN_EXPECTS)
+                    .help("Also run the top-level expects, with the interpreter instead of a backend")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_TARGET)
+                    .long(FLAG_TARGET)
+                    .help("Check the code as it would be built for a different target")
+                    .default_value(Into::<&'static str>::into(Target::default()))
+                    .value_parser(build_target_values_parser.clone())
+                    .conflicts_with(FLAG_RUN_EXPECTS)
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file to check")
@@ -348,6 +600,14 @@ pub fn build_app() -> Command {
                     .default_value(DEFAULT_ROC_FILENAME),
             )
             )
+        .subcommand(Command::new(CMD_EXPLAIN)
+            .about("Explain an error code from a report, like the E001 in `TYPE MISMATCH [E001]`")
+            .arg(
+                Arg::new(ERROR_CODE)
+                    .help("The code to explain")
+                    .required(true),
+            )
+        )
         .subcommand(
             Command::new(CMD_DOCS)
                 .about("Generate documentation for a Roc package")
@@ -358,6 +618,13 @@ pub fn build_app() -> Command {
                     .required(false)
                     .default_value(DEFAULT_GENERATED_DOCS_DIR),
                 )
+                .arg(Arg::new(FLAG_DOCS_FORMAT)
+                    .long(FLAG_DOCS_FORMAT)
+                    .help("Generate HTML pages, or a JSON description of the package's API for other tools to use.")
+                    .value_parser(["html", "json"])
+                    .required(false)
+                    .default_value("html"),
+                )
                 .arg(Arg::new(ROC_FILE)
                     .help("The package's main .roc file")
                     .value_parser(value_parser!(PathBuf))
@@ -421,9 +688,9 @@ pub fn build_app() -> Command {
             )
             .arg(
                 Arg::new(FLAG_PP_DYLIB)
-                    .help("Path to a stubbed app dynamic library (e.g. roc build --lib app.roc)")
+                    .help("Path to a stubbed app dynamic library (e.g. roc build --lib app.roc). Not needed for wasm32 hosts.")
                     .value_parser(value_parser!(PathBuf))
-                    .required(true)
+                    .required(false)
             )
             .arg(
                 Arg::new(FLAG_VERBOSE)
@@ -438,8 +705,18 @@ pub fn build_app() -> Command {
         .arg(flag_opt_size)
         .arg(flag_dev)
         .arg(flag_emit_llvm_ir)
+        .arg(flag_emit_reuse_report)
+        .arg(flag_warn_unused)
+        .arg(flag_atomic_refcounts)
+        .arg(flag_branch_targets)
+        .arg(flag_retpolines)
+        .arg(flag_canonicalize_nans)
+        .arg(flag_patchable_calls)
+        .arg(flag_heap_profiling)
+        .arg(flag_wasm_allocator)
         .arg(flag_profiling)
         .arg(flag_time)
+        .arg(flag_profile_compiler)
         .arg(flag_linker)
         .arg(flag_prebuilt)
         .arg(flag_fuzz)
@@ -483,21 +760,6 @@ struct ModuleTestResults {
 
 #[cfg(not(windows))]
 pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
-    use roc_build::program::report_problems_monomorphized;
-    use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError};
-    use roc_packaging::cache;
-
-    let start_time = Instant::now();
-    let arena = Bump::new();
-    let opt_level = opt_level_from_flags(matches);
-
-    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
-        None => Threading::AllAvailable,
-        Some(0) => user_error!("cannot build with at most 0 threads"),
-        Some(1) => Threading::Single,
-        Some(n) => Threading::AtMost(*n),
-    };
-
     let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
 
     // Spawn the root task
@@ -521,7 +783,39 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         process::exit(1);
     }
 
-    let arena = &arena;
+    let arena = Bump::new();
+    let (exit_code, _) = run_tests(&arena, matches, target, None)?;
+
+    Ok(exit_code)
+}
+
+/// Compiles and runs the tests, returning the exit code for `roc test` and the layout
+/// interner the tests were run with (unless they couldn't be), for the next run to reuse.
+#[cfg(not(windows))]
+fn run_tests<'a>(
+    arena: &'a Bump,
+    matches: &ArgMatches,
+    target: Target,
+    incremental: Option<Incremental<'a>>,
+) -> io::Result<(i32, Option<GlobalLayoutInterner<'a>>)> {
+    use roc_build::program::report_problems_monomorphized;
+    use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError};
+    use roc_mono::coverage::Coverage;
+    use roc_packaging::cache;
+    use roc_repl_expect::run::CoverageCounters;
+    use std::cell::RefCell;
+
+    let start_time = Instant::now();
+    let opt_level = opt_level_from_flags(matches);
+
+    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
+    };
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
     let function_kind = FunctionKind::from_env();
 
     let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
@@ -536,21 +830,36 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         threading,
         exec_mode: ExecutionMode::Test,
     };
-    let load_result = roc_load::load_and_monomorphize(
-        arena,
-        path.to_path_buf(),
-        opt_main_path.cloned(),
-        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
-        load_config,
-    );
+    let roc_cache_dir = cache::roc_cache_dir();
+    let roc_cache_dir = RocCacheDir::Persistent(roc_cache_dir.as_path());
+    let load_result = match incremental {
+        None => roc_load::load_and_monomorphize(
+            arena,
+            path.to_path_buf(),
+            opt_main_path.cloned(),
+            roc_cache_dir,
+            load_config,
+        ),
+        Some(incremental) => roc_load::load_and_monomorphize_incremental(
+            arena,
+            path.to_path_buf(),
+            opt_main_path.cloned(),
+            roc_cache_dir,
+            load_config,
+            incremental,
+        ),
+    };
 
     let mut loaded = match load_result {
         Ok(loaded) => loaded,
         Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
-            return handle_loading_problem(problem);
+            return Ok((handle_loading_problem(problem)?, None));
         }
         Err(LoadMonomorphizedError::ErrorModule(module)) => {
-            return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
+            let exit_code =
+                handle_error_module(module, start_time.elapsed(), path.as_os_str(), false)?;
+
+            return Ok((exit_code, None));
         }
     };
     let problems = report_problems_monomorphized(&mut loaded);
@@ -560,6 +869,14 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
     let interns = loaded.interns.clone();
     let sources = loaded.sources.clone();
 
+    let skipped_properties: usize = (loaded.toplevel_expects.values())
+        .map(|expects| expects.properties.len())
+        .sum();
+
+    let coverage = matches
+        .get_flag(FLAG_COVERAGE)
+        .then(|| RefCell::new(Coverage::new(loaded.source_locations())));
+
     let (dyn_lib, expects_by_module, layout_interner) =
         roc_repl_expect::run::expect_mono_module_to_dylib(
             arena,
@@ -567,9 +884,16 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             loaded,
             opt_level,
             LlvmBackendMode::CliTest,
+            coverage.as_ref(),
         )
         .unwrap();
 
+    let coverage = coverage.map(RefCell::into_inner);
+    let coverage_counters = match &coverage {
+        Some(coverage) => Some(CoverageCounters::new(&dyn_lib, coverage.len())?),
+        None => None,
+    };
+
     // Print warnings before running tests.
     {
         debug_assert_eq!(
@@ -583,7 +907,6 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
     }
 
     // Run the tests.
-    let arena = &bumpalo::Bump::new();
     let interns = arena.alloc(interns);
 
     let mut writer = std::io::stdout();
@@ -596,6 +919,19 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
 
     let compilation_duration = start_time.elapsed();
 
+    let snapshot_mode = if matches.get_flag(FLAG_UPDATE_SNAPSHOTS) {
+        SnapshotMode::Update
+    } else {
+        SnapshotMode::Compare
+    };
+
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let max_workers = match threading {
+        Threading::Single => 1,
+        Threading::AllAvailable => available,
+        Threading::AtMost(at_most) => available.min(at_most),
+    };
+
     for (module_id, expects) in expects_by_module.into_iter() {
         let test_start_time = Instant::now();
 
@@ -608,6 +944,8 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             &dyn_lib,
             &mut expectations,
             expects,
+            snapshot_mode,
+            max_workers,
         )
         .unwrap();
 
@@ -626,16 +964,28 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
 
     let total_duration = start_time.elapsed();
 
+    if let (Some(coverage), Some(counters)) = (&coverage, &coverage_counters) {
+        print_coverage(coverage, counters.counts(), interns);
+    }
+
+    if skipped_properties > 0 {
+        println!("{}", skipped_properties_warning(skipped_properties));
+    }
+
     if total_failed_count == 0 && total_passed_count == 0 {
         // TODO print this in a more nicely formatted way!
-        println!("No expectations were found.");
+        if skipped_properties == 0 {
+            println!("No expectations were found.");
+        } else {
+            println!("No expectations were run.");
+        }
 
         // If no tests ran, treat that as an error. This is perhaps
         // briefly annoying at the very beginning of a project when
         // you actually have zero tests, but it can save you from
         // having a change to your CI script accidentally stop
         // running tests altogether!
-        Ok(2)
+        Ok((2, Some(global_layout_interner)))
     } else {
         if matches.get_flag(FLAG_VERBOSE) {
             println!("Compiled in {} ms.", compilation_duration.as_millis());
@@ -648,167 +998,668 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             println!("{test_summary_str}");
         }
 
-        Ok((total_failed_count > 0) as i32)
+        Ok((
+            (total_failed_count > 0) as i32,
+            Some(global_layout_interner),
+        ))
     }
 }
 
-#[cfg(not(windows))]
-fn print_test_results(
-    module_test_results: ModuleTestResults,
-    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
-) {
-    let ModuleTestResults {
-        module_id,
-        failed_count,
-        passed_count,
-        tests_duration,
-    } = module_test_results;
-
-    let test_summary_str = test_summary(failed_count, passed_count, tests_duration);
-
-    let (module_path, _) = sources.get(&module_id).unwrap();
-    let module_name = module_path.file_name().unwrap().to_str().unwrap();
+#[cfg(windows)]
+pub fn bench(_matches: &ArgMatches, _target: Target) -> io::Result<i32> {
+    eprintln!("`roc bench` is not supported on Windows yet.");
 
-    println!("\n{module_name}:\n    {test_summary_str}",);
+    Ok(1)
 }
 
+/// Compiles the top-level benchmarks with optimizations, then times them one after another.
 #[cfg(not(windows))]
-fn test_summary(failed_count: usize, passed_count: usize, tests_duration: Duration) -> String {
-    let failed_color = if failed_count == 0 {
-        ANSI_STYLE_CODES.green
-    } else {
-        ANSI_STYLE_CODES.red
+pub fn bench(matches: &ArgMatches, target: Target) -> io::Result<i32> {
+    use roc_build::program::report_problems_monomorphized;
+    use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError};
+    use roc_packaging::cache;
+    use roc_repl_expect::bench::{run_benches, Baseline, BenchConfig};
+
+    let start_time = Instant::now();
+    let arena = Bump::new();
+
+    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
     };
-    let passed_color = ANSI_STYLE_CODES.green;
-    let reset = ANSI_STYLE_CODES.reset;
 
-    format!(
-        "{failed_color}{failed_count}{reset} failed and {passed_color}{passed_count}{reset} passed in {} ms.",
-        tests_duration.as_millis()
-    )
-}
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
 
-/// Find the element of `options` with the smallest edit distance to
-/// `reference`. Returns a tuple containing the element and the distance, or
-/// `None` if the `options` `Vec` is empty.
-fn nearest_match<'a>(reference: &str, options: &'a [String]) -> Option<(&'a String, usize)> {
-    options
-        .iter()
-        .map(|s| (s, distance::damerau_levenshtein(reference, s)))
-        .min_by(|(_, a), (_, b)| a.cmp(b))
-}
+    if !path.exists() {
+        eprintln!("\nThis file was not found: {}\n\nYou can run `roc help` for more information on how to provide a .roc file.\n", path.display());
 
-pub fn build(
-    matches: &ArgMatches,
-    subcommands: &[String],
-    config: BuildConfig,
-    target: Target,
-    out_path: Option<&Path>,
-    roc_cache_dir: RocCacheDir<'_>,
-    link_type: LinkType,
-) -> io::Result<i32> {
-    use roc_build::program::build_file;
-    use BuildConfig::*;
+        process::exit(1);
+    }
 
-    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
-    {
-        // Spawn the root task
-        if !path.exists() {
-            let current_dir = env::current_dir().unwrap();
-            let expected_file_path = current_dir.join(path);
-
-            let current_dir_string = current_dir.display();
-            let expected_file_path_string = expected_file_path.display();
-
-            // TODO these should use roc_reporting to display nicer error messages.
-            match matches.value_source(ROC_FILE) {
-                Some(ValueSource::DefaultValue) => {
-                    eprintln!(
-                        "\nThe current directory ({current_dir_string}) does not contain a {DEFAULT_ROC_FILENAME} file to use as a default.\n\nYou can run `roc help` for more information on how to provide a .roc file.\n"
-                    )
-                }
-                _ => {
-                    let mut error_lines = Vec::new();
-                    error_lines.push(format!(
-                        "This file was not found: {expected_file_path_string}"
-                    ));
-                    // Add some additional hints if run as `roc [FILENAME]`.
-                    if matches.subcommand().is_none() {
-                        match path.to_str() {
-                            Some(possible_typo) if !possible_typo.ends_with(".roc") => {
-                                if let Some((nearest_command, _)) =
-                                    nearest_match(possible_typo, subcommands)
-                                {
-                                    error_lines.push(format!(
-                                        "Did you mean to use the {nearest_command} subcommand?"
-                                    ));
-                                }
-                            }
-                            _ => (),
-                        }
-                    }
-                    error_lines.push("You can run `roc help` to see the list of available subcommands and for more information on how to provide a .roc file.".to_string());
+    // Read the baseline before spending time on compiling
+    let baseline = match matches.get_one::<PathBuf>(FLAG_BASELINE) {
+        None => None,
+        Some(baseline_path) => match Baseline::read(baseline_path) {
+            Ok(baseline) => Some(baseline),
+            Err(err) => {
+                eprintln!(
+                    "\nI could not read the baseline file {}: {err}\n\nYou can make one with `roc bench --save-baseline {}`.\n",
+                    baseline_path.display(),
+                    baseline_path.display(),
+                );
 
-                    eprintln!("\n{}\n", error_lines.join("\n\n"));
-                }
+                return Ok(1);
             }
+        },
+    };
 
-            process::exit(1);
-        }
+    let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
 
-        if config == BuildConfig::BuildOnly && matches.contains_id(FLAG_BUNDLE) {
+    let load_config = LoadConfig {
+        target,
+        function_kind: FunctionKind::from_env(),
+        render: roc_reporting::report::RenderTarget::ColorTerminal,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Test,
+    };
+    let load_result = roc_load::load_and_monomorphize(
+        &arena,
+        path.to_path_buf(),
+        opt_main_path.cloned(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    );
+
+    let mut loaded = match load_result {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return handle_loading_problem(problem);
+        }
+        Err(LoadMonomorphizedError::ErrorModule(module)) => {
+            return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
+        }
+    };
+    let problems = report_problems_monomorphized(&mut loaded);
+
+    let interns = loaded.interns.clone();
+
+    let (dyn_lib, expects_by_module, layout_interner) =
+        roc_repl_expect::run::expect_mono_module_to_dylib(
+            &arena,
+            target,
+            loaded,
+            OptLevel::Optimize,
+            LlvmBackendMode::CliTest,
+            None,
+        )
+        .unwrap();
+
+    if problems.warnings > 0 {
+        problems.print_error_warning_count(start_time.elapsed());
+        println!(".\n");
+    }
+
+    println!(
+        "Compiled in {} ms. Running benchmarks…\n",
+        start_time.elapsed().as_millis()
+    );
+
+    // Run the benchmarks of one module after another, in a stable order
+    let mut benches_by_module: Vec<_> = expects_by_module
+        .into_iter()
+        .filter(|(_, expects)| !expects.benches.is_empty())
+        .collect();
+    benches_by_module.sort_by_key(|(module_id, _)| module_id.to_ident_str(&interns).to_string());
+
+    let mut writer = std::io::stdout();
+    let mut results = Vec::new();
+    let mut total_crashed = 0;
+
+    for (_, expects) in benches_by_module {
+        let (module_results, crashed) = run_benches(
+            &mut writer,
+            &interns,
+            &layout_interner,
+            &dyn_lib,
+            &expects.benches,
+            BenchConfig::default(),
+            baseline.as_ref(),
+        )?;
+
+        results.extend(module_results);
+        total_crashed += crashed;
+    }
+
+    if results.is_empty() && total_crashed == 0 {
+        println!(
+            "No benchmarks were found. Benchmarks are top-level values named `bench` followed by an uppercase letter or a digit, like `benchSort`."
+        );
+
+        // Like `roc test`, treat finding nothing as an error, so CI doesn't silently stop benchmarking
+        return Ok(2);
+    }
+
+    if let Some(baseline_path) = matches.get_one::<PathBuf>(FLAG_SAVE_BASELINE) {
+        Baseline::write(baseline_path, &results)?;
+
+        println!("\nSaved the baseline to {}", baseline_path.display());
+    }
+
+    Ok((total_crashed > 0) as i32)
+}
+
+/// `roc check`, without `--run-expects`: reports the problems in the file and the modules it
+/// imports, without generating any code.
+pub fn check<'a>(
+    arena: &'a Bump,
+    matches: &ArgMatches,
+    incremental: Option<Incremental<'a>>,
+) -> io::Result<i32> {
+    use roc_build::program::check_file;
+    use roc_load::{DepsFormat, LoadingProblem};
+    use roc_packaging::cache;
+    use roc_reporting::cli::OutputFormat;
+    use roc_reporting::json::loading_problem_json;
+    use std::str::FromStr;
+
+    let emit_timings = matches.get_flag(FLAG_TIME);
+    let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
+    };
+
+    let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
+
+    let output_format = match matches.get_one::<String>(FLAG_OUTPUT).map(|s| s.as_str()) {
+        Some("json") => OutputFormat::Json,
+        _ => OutputFormat::Terminal,
+    };
+
+    let emit_deps = match matches
+        .get_one::<String>(FLAG_EMIT_DEPS)
+        .map(|s| s.as_str())
+    {
+        Some("dot") => Some(DepsFormat::Dot),
+        Some("json") => Some(DepsFormat::Json),
+        _ => None,
+    };
+
+    let target = matches
+        .get_one::<String>(FLAG_TARGET)
+        .and_then(|s| Target::from_str(s).ok())
+        .unwrap_or_default();
+
+    match check_file(
+        arena,
+        roc_file_path.to_owned(),
+        opt_main_path.cloned(),
+        target,
+        emit_timings,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        threading,
+        output_format,
+        emit_deps,
+        incremental,
+    ) {
+        Ok((problems, total_time)) => {
+            // Keep stdout to the graph, for piping it into `dot` and such
+            if output_format == OutputFormat::Terminal && emit_deps.is_none() {
+                problems.print_error_warning_count(total_time);
+            }
+
+            Ok(problems.exit_code())
+        }
+
+        Err(LoadingProblem::FormattedReport(report)) => {
+            match output_format {
+                OutputFormat::Terminal => print!("{report}"),
+                OutputFormat::Json => {
+                    println!("{}", loading_problem_json(Some(roc_file_path), &report))
+                }
+            }
+
+            Ok(1)
+        }
+        Err(other) => {
+            panic!("build_file failed with error:\n{other:?}");
+        }
+    }
+}
+
+/// Checks the file, then runs its top-level expects with the mono IR interpreter. Nothing is
+/// compiled or linked, so this gives feedback quickly, at the cost of running slowly.
+pub fn check_and_run_expects(matches: &ArgMatches, target: Target) -> io::Result<i32> {
+    use roc_build::program::report_problems_monomorphized;
+    use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError};
+    use roc_packaging::cache;
+
+    let start_time = Instant::now();
+    let arena = Bump::new();
+
+    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
+    };
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
+
+    let load_config = LoadConfig {
+        target,
+        function_kind: FunctionKind::from_env(),
+        render: roc_reporting::report::RenderTarget::ColorTerminal,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Test,
+    };
+    let load_result = roc_load::load_and_monomorphize(
+        &arena,
+        path.to_path_buf(),
+        opt_main_path.cloned(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    );
+
+    let mut loaded = match load_result {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return handle_loading_problem(problem);
+        }
+        Err(LoadMonomorphizedError::ErrorModule(module)) => {
+            return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
+        }
+    };
+
+    let problems = report_problems_monomorphized(&mut loaded);
+
+    if problems.errors > 0 {
+        problems.print_error_warning_count(start_time.elapsed());
+        return Ok(problems.exit_code());
+    }
+
+    let (failed_count, passed_count) = roc_repl_eval::interp::run_toplevel_expects(
+        &mut std::io::stdout(),
+        roc_reporting::report::RenderTarget::ColorTerminal,
+        loaded,
+        target,
+        SnapshotMode::Compare,
+        threading,
+    )?;
+
+    let test_summary_str = test_summary(failed_count, passed_count, start_time.elapsed());
+    println!("{test_summary_str}");
+
+    if failed_count > 0 {
+        Ok(1)
+    } else {
+        Ok(problems.exit_code())
+    }
+}
+
+#[cfg(not(windows))]
+fn print_test_results(
+    module_test_results: ModuleTestResults,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+) {
+    let ModuleTestResults {
+        module_id,
+        failed_count,
+        passed_count,
+        tests_duration,
+    } = module_test_results;
+
+    let test_summary_str = test_summary(failed_count, passed_count, tests_duration);
+
+    let (module_path, _) = sources.get(&module_id).unwrap();
+    let module_name = module_path.file_name().unwrap().to_str().unwrap();
+
+    println!("\n{module_name}:\n    {test_summary_str}",);
+}
+
+/// Lists the procs and branches of `coverage` whose counters are all zero.
+#[cfg(not(windows))]
+fn print_coverage(
+    coverage: &roc_mono::coverage::Coverage,
+    counters: &[u64],
+    interns: &roc_module::symbol::Interns,
+) {
+    use roc_mono::coverage::CoverageKind;
+
+    let summary = coverage.summarize(counters);
+    let ran = summary.points - summary.missed.len();
+    let color = if summary.missed.is_empty() {
+        ANSI_STYLE_CODES.green
+    } else {
+        ANSI_STYLE_CODES.yellow
+    };
+    let reset = ANSI_STYLE_CODES.reset;
+
+    print!(
+        "\nCoverage: {color}{ran}{reset} of {} functions and branches ran",
+        summary.points
+    );
+
+    if summary.missed.is_empty() {
+        println!(".\n");
+        return;
+    }
+
+    println!(". These never did:\n");
+
+    for point in summary.missed {
+        let proc_name = point.proc_name.as_str(interns);
+        let what = match point.kind {
+            CoverageKind::ProcEntry => format!("`{proc_name}`"),
+            CoverageKind::Branch => format!("a branch in `{proc_name}`"),
+        };
+
+        println!("    {}  {what}", point.location);
+    }
+
+    println!();
+}
+
+fn test_summary(failed_count: usize, passed_count: usize, tests_duration: Duration) -> String {
+    let failed_color = if failed_count == 0 {
+        ANSI_STYLE_CODES.green
+    } else {
+        ANSI_STYLE_CODES.red
+    };
+    let passed_color = ANSI_STYLE_CODES.green;
+    let reset = ANSI_STYLE_CODES.reset;
+
+    format!(
+        "{failed_color}{failed_count}{reset} failed and {passed_color}{passed_count}{reset} passed in {} ms.",
+        tests_duration.as_millis()
+    )
+}
+
+/// Compiled tests can't generate the arguments of property expects, so `roc test` leaves them
+/// to the interpreter, and says how many it didn't run.
+fn skipped_properties_warning(skipped_count: usize) -> String {
+    let warning_color = ANSI_STYLE_CODES.yellow;
+    let reset = ANSI_STYLE_CODES.reset;
+
+    format!(
+        "{warning_color}Warning:{reset} skipped {warning_color}{skipped_count}{reset} property expect(s), because compiled tests can't generate their arguments yet. Run them with `roc check --run-expects`."
+    )
+}
+
+/// Find the element of `options` with the smallest edit distance to
+/// `reference`. Returns a tuple containing the element and the distance, or
+/// `None` if the `options` `Vec` is empty.
+fn nearest_match<'a>(reference: &str, options: &'a [String]) -> Option<(&'a String, usize)> {
+    options
+        .iter()
+        .map(|s| (s, distance::damerau_levenshtein(reference, s)))
+        .min_by(|(_, a), (_, b)| a.cmp(b))
+}
+
+pub fn build(
+    matches: &ArgMatches,
+    subcommands: &[String],
+    config: BuildConfig,
+    target: Target,
+    out_path: Option<&Path>,
+    roc_cache_dir: RocCacheDir<'_>,
+    link_type: LinkType,
+) -> io::Result<i32> {
+    use BuildConfig::*;
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    {
+        exit_if_roc_file_missing(matches, subcommands);
+
+        if config == BuildConfig::BuildOnly && matches.contains_id(FLAG_BUNDLE) {
             let start_time = Instant::now();
 
-            let compression =
-                Compression::try_from(matches.get_one::<String>(FLAG_BUNDLE).unwrap().as_str())
-                    .unwrap();
+            let compression =
+                Compression::try_from(matches.get_one::<String>(FLAG_BUNDLE).unwrap().as_str())
+                    .unwrap();
+
+            // Print a note of advice. This is mainly here because brotli takes so long but produces
+            // such smaller output files; the idea is to encourage people to wait for brotli,
+            // so that downloads go faster. The compression only happens once, but the network
+            // transfer and decompression will happen many more times!
+            match compression {
+                Compression::Brotli => {
+                    println!("Compressing with Brotli at maximum quality level…\n\n(Note: Brotli compression can take awhile! Using --{FLAG_BUNDLE} .tar.gz takes less time, but usually produces a significantly larger output file. Brotli is generally worth the up-front wait if this is a file people will be downloading!)\n");
+                }
+                Compression::Gzip => {
+                    println!("Compressing with gzip at minimum quality…\n\n(Note: Gzip usually runs faster than Brotli but typically produces significantly larger output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
+                }
+                Compression::Uncompressed => {
+                    println!("Building .tar archive without compression…\n\n(Note: Compression takes more time to run but typically produces much smaller output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
+                }
+            }
+
+            // Rather than building an executable or library, we're building
+            // a tarball so this code can be distributed via HTTPS
+            let filename = roc_packaging::tarball::build(path, compression)?;
+            let total_time_ms = start_time.elapsed().as_millis();
+            let total_time = if total_time_ms > 1000 {
+                format!("{}s {}ms", total_time_ms / 1000, total_time_ms % 1000)
+            } else {
+                format!("{total_time_ms} ms")
+            };
+            let created_path = path.with_file_name(&filename);
+
+            println!(
+                "\nBundled \x1B[33m{}\x1B[39m and its dependent files into the following archive in {total_time}:\n\n\t\x1B[33m{}\x1B[39m\n\nTo distribute this archive as a package, upload this to some URL and then add it as a dependency with:\n\n\t\x1B[32m\"https://your-url-goes-here/{filename}\"\x1B[39m\n",
+                path.to_string_lossy(),
+                created_path.to_string_lossy()
+            );
+
+            return Ok(0);
+        }
+    }
+
+    // the process will end after this function,
+    // so we don't want to spend time freeing these values
+    let arena = ManuallyDrop::new(Bump::new());
+
+    let opt_level = build_opt_level(matches, &config);
+    let res_binary_path = build_with_flags(
+        &arena,
+        matches,
+        &config,
+        target,
+        out_path,
+        roc_cache_dir,
+        link_type,
+        None,
+    );
+
+    match res_binary_path {
+        Ok(BuiltFile {
+            binary_path,
+            problems,
+            total_time,
+            expect_metadata,
+        }) => {
+            match config {
+                BuildOnly => {
+                    // No need to waste time freeing this memory,
+                    // since the process is about to exit anyway.
+                    // std::mem::forget(arena);
+
+                    Ok(report_built_file(&binary_path, &problems, total_time))
+                }
+                BuildAndRun => {
+                    if problems.fatally_errored {
+                        problems.print_error_warning_count(total_time);
+                        println!(
+                            ".\n\nCannot run program due to fatal error…\n\n\x1B[36m{}\x1B[39m",
+                            "─".repeat(80)
+                        );
+
+                        // Return a nonzero exit code due to fatal problem
+                        return Ok(problems.exit_code());
+                    }
+                    if problems.errors > 0 || problems.warnings > 0 {
+                        problems.print_error_warning_count(total_time);
+                        println!(
+                            ".\n\nRunning program anyway…\n\n\x1B[36m{}\x1B[39m",
+                            "─".repeat(80)
+                        );
+                    }
+
+                    let args = matches
+                        .get_many::<OsString>(ARGS_FOR_APP)
+                        .unwrap_or_default()
+                        .map(|s| s.as_os_str());
+
+                    // don't waste time deallocating; the process ends anyway
+                    // ManuallyDrop will leak the bytes because we don't drop manually
+                    let bytes = &ManuallyDrop::new(std::fs::read(&binary_path).unwrap());
+
+                    roc_run(&arena, opt_level, target, args, bytes, expect_metadata)
+                }
+                BuildAndRunIfNoErrors => {
+                    if problems.fatally_errored {
+                        problems.print_error_warning_count(total_time);
+                        println!(
+                            ".\n\nCannot run program due to fatal error…\n\n\x1B[36m{}\x1B[39m",
+                            "─".repeat(80)
+                        );
+
+                        // Return a nonzero exit code due to fatal problem
+                        return Ok(problems.exit_code());
+                    }
+                    debug_assert_eq!(
+                        problems.errors, 0,
+                        "if there are non-fatal errors, they should have been returned as an error variant"
+                    );
+
+                    if problems.warnings > 0 {
+                        problems.print_error_warning_count(total_time);
+                        println!(
+                            ".\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
+                            "─".repeat(80)
+                        );
+                    }
 
-            // Print a note of advice. This is mainly here because brotli takes so long but produces
-            // such smaller output files; the idea is to encourage people to wait for brotli,
-            // so that downloads go faster. The compression only happens once, but the network
-            // transfer and decompression will happen many more times!
-            match compression {
-                Compression::Brotli => {
-                    println!("Compressing with Brotli at maximum quality level…\n\n(Note: Brotli compression can take awhile! Using --{FLAG_BUNDLE} .tar.gz takes less time, but usually produces a significantly larger output file. Brotli is generally worth the up-front wait if this is a file people will be downloading!)\n");
-                }
-                Compression::Gzip => {
-                    println!("Compressing with gzip at minimum quality…\n\n(Note: Gzip usually runs faster than Brotli but typically produces significantly larger output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
-                }
-                Compression::Uncompressed => {
-                    println!("Building .tar archive without compression…\n\n(Note: Compression takes more time to run but typically produces much smaller output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
+                    let args = matches
+                        .get_many::<OsString>(ARGS_FOR_APP)
+                        .unwrap_or_default()
+                        .map(|s| s.as_os_str());
+
+                    // don't waste time deallocating; the process ends anyway
+                    // ManuallyDrop will leak the bytes because we don't drop manually
+                    let bytes = &ManuallyDrop::new(std::fs::read(&binary_path).unwrap());
+
+                    roc_run(&arena, opt_level, target, args, bytes, expect_metadata)
                 }
             }
+        }
+        Err(BuildFileError::ErrorModule { module, total_time }) => {
+            handle_error_module(module, total_time, path.as_os_str(), true)
+        }
+        Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
+        Err(BuildFileError::BackendFailure { filename, failure }) => {
+            eprintln!("{}", to_backend_failure_report_string(filename, failure));
 
-            // Rather than building an executable or library, we're building
-            // a tarball so this code can be distributed via HTTPS
-            let filename = roc_packaging::tarball::build(path, compression)?;
-            let total_time_ms = start_time.elapsed().as_millis();
-            let total_time = if total_time_ms > 1000 {
-                format!("{}s {}ms", total_time_ms / 1000, total_time_ms % 1000)
-            } else {
-                format!("{total_time_ms} ms")
-            };
-            let created_path = path.with_file_name(&filename);
+            Ok(1)
+        }
+    }
+}
 
-            println!(
-                "\nBundled \x1B[33m{}\x1B[39m and its dependent files into the following archive in {total_time}:\n\n\t\x1B[33m{}\x1B[39m\n\nTo distribute this archive as a package, upload this to some URL and then add it as a dependency with:\n\n\t\x1B[32m\"https://your-url-goes-here/{filename}\"\x1B[39m\n",
-                path.to_string_lossy(),
-                created_path.to_string_lossy()
-            );
+/// Tells where `roc build` put what it built, and returns the exit code for the problems it ran into.
+fn report_built_file(binary_path: &Path, problems: &Problems, total_time: Duration) -> i32 {
+    // If possible, report the generated executable name relative to the current dir.
+    let generated_filename = binary_path
+        .strip_prefix(env::current_dir().unwrap())
+        .unwrap_or(binary_path)
+        .to_str()
+        .unwrap();
 
-            return Ok(0);
+    problems.print_error_warning_count(total_time);
+    println!(" while successfully building:\n\n    {generated_filename}");
+
+    // Return a nonzero exit code if there were problems
+    problems.exit_code()
+}
+
+fn exit_if_roc_file_missing(matches: &ArgMatches, subcommands: &[String]) {
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+
+    if !path.exists() {
+        let current_dir = env::current_dir().unwrap();
+        let expected_file_path = current_dir.join(path);
+
+        let current_dir_string = current_dir.display();
+        let expected_file_path_string = expected_file_path.display();
+
+        // TODO these should use roc_reporting to display nicer error messages.
+        match matches.value_source(ROC_FILE) {
+            Some(ValueSource::DefaultValue) => {
+                eprintln!(
+                    "\nThe current directory ({current_dir_string}) does not contain a {DEFAULT_ROC_FILENAME} file to use as a default.\n\nYou can run `roc help` for more information on how to provide a .roc file.\n"
+                )
+            }
+            _ => {
+                let mut error_lines = Vec::new();
+                error_lines.push(format!(
+                    "This file was not found: {expected_file_path_string}"
+                ));
+                // Add some additional hints if run as `roc [FILENAME]`.
+                if matches.subcommand().is_none() {
+                    match path.to_str() {
+                        Some(possible_typo) if !possible_typo.ends_with(".roc") => {
+                            if let Some((nearest_command, _)) =
+                                nearest_match(possible_typo, subcommands)
+                            {
+                                error_lines.push(format!(
+                                    "Did you mean to use the {nearest_command} subcommand?"
+                                ));
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                error_lines.push("You can run `roc help` to see the list of available subcommands and for more information on how to provide a .roc file.".to_string());
+
+                eprintln!("\n{}\n", error_lines.join("\n\n"));
+            }
         }
-    }
 
-    // the process will end after this function,
-    // so we don't want to spend time freeing these values
-    let arena = ManuallyDrop::new(Bump::new());
+        process::exit(1);
+    }
+}
 
-    let opt_level = if let BuildConfig::BuildAndRunIfNoErrors = config {
+fn build_opt_level(matches: &ArgMatches, config: &BuildConfig) -> OptLevel {
+    if let BuildConfig::BuildAndRunIfNoErrors = config {
         OptLevel::Development
     } else {
         opt_level_from_flags(matches)
-    };
+    }
+}
+
+/// Builds the .roc file given on the command line, with the options given there.
+#[allow(clippy::too_many_arguments)]
+fn build_with_flags<'a>(
+    arena: &'a Bump,
+    matches: &ArgMatches,
+    config: &BuildConfig,
+    target: Target,
+    out_path: Option<&Path>,
+    roc_cache_dir: RocCacheDir<'_>,
+    link_type: LinkType,
+    incremental: Option<Incremental<'a>>,
+) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
+    use roc_build::program::build_file;
+    use BuildConfig::*;
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let opt_level = build_opt_level(matches, config);
 
     // Note: This allows using `--dev` with `--optimize`.
     // This means frontend optimizations and dev backend.
@@ -832,6 +1683,57 @@ pub fn build(
         user_error!("Cannot emit llvm ir while using a dev backend.");
     }
 
+    let emit_reuse_report = matches.get_flag(FLAG_EMIT_REUSE_REPORT);
+    let warn_unused = matches.get_flag(FLAG_WARN_UNUSED);
+
+    let atomic_refcounts = matches.get_flag(FLAG_ATOMIC_REFCOUNTS);
+    if atomic_refcounts && !matches!(code_gen_backend, CodeGenBackend::Assembly(_)) {
+        user_error!(
+            "Atomic refcounts are only supported by the dev backend, on x86_64 and aarch64."
+        );
+    }
+
+    let hardening = Hardening {
+        branch_targets: matches.get_flag(FLAG_BRANCH_TARGETS),
+        retpolines: matches.get_flag(FLAG_RETPOLINES),
+    };
+    if (hardening.branch_targets || hardening.retpolines)
+        && !matches!(code_gen_backend, CodeGenBackend::Assembly(_))
+    {
+        user_error!("Branch targets and retpolines are only supported by the dev backend.");
+    }
+    if hardening.retpolines && !matches!(target.architecture(), Architecture::X86_64) {
+        user_error!("Retpolines are only supported when targeting x86_64.");
+    }
+
+    let canonicalize_nans = matches.get_flag(FLAG_CANONICALIZE_NANS);
+    if canonicalize_nans && !matches!(code_gen_backend, CodeGenBackend::Wasm) {
+        user_error!("NaN canonicalization is only supported by the Wasm dev backend.");
+    }
+
+    let patchable_calls = matches.get_flag(FLAG_PATCHABLE_CALLS);
+    if patchable_calls && !matches!(code_gen_backend, CodeGenBackend::Wasm) {
+        user_error!("Patchable calls are only supported by the Wasm dev backend.");
+    }
+
+    let heap_profiling = matches.get_flag(FLAG_HEAP_PROFILING);
+    if heap_profiling && !matches!(code_gen_backend, CodeGenBackend::Wasm) {
+        user_error!("Heap profiling is only supported by the Wasm dev backend.");
+    }
+
+    let wasm_allocator = match matches.get_one::<String>(FLAG_WASM_ALLOCATOR) {
+        None => roc_gen_wasm::Allocator::Host,
+        Some(name) if !matches!(code_gen_backend, CodeGenBackend::Wasm) => {
+            user_error!("The {name} allocator is only supported by the Wasm dev backend.")
+        }
+        Some(name) => match name.as_str() {
+            "host" => roc_gen_wasm::Allocator::Host,
+            "bump" => roc_gen_wasm::Allocator::Bump,
+            "arena-per-call" => roc_gen_wasm::Allocator::ArenaPerCall,
+            _ => unreachable!(),
+        },
+    };
+
     let emit_debug_info = matches.get_flag(FLAG_PROFILING)
         || matches!(opt_level, OptLevel::Development | OptLevel::Normal);
     let emit_timings = matches.get_flag(FLAG_TIME);
@@ -888,13 +1790,21 @@ pub fn build(
         opt_level,
         emit_debug_info,
         emit_llvm_ir,
+        emit_reuse_report,
+        warn_unused,
         fuzz,
+        atomic_refcounts,
+        hardening,
+        canonicalize_nans,
+        patchable_calls,
+        wasm_allocator,
+        heap_profiling,
     };
 
     let load_config = standard_load_config(target, build_ordering, threading);
 
-    let res_binary_path = build_file(
-        &arena,
+    build_file(
+        arena,
         target,
         path.to_owned(),
         code_gen_options,
@@ -906,106 +1816,8 @@ pub fn build(
         roc_cache_dir,
         load_config,
         out_path,
-    );
-
-    match res_binary_path {
-        Ok(BuiltFile {
-            binary_path,
-            problems,
-            total_time,
-            expect_metadata,
-        }) => {
-            match config {
-                BuildOnly => {
-                    // If possible, report the generated executable name relative to the current dir.
-                    let generated_filename = binary_path
-                        .strip_prefix(env::current_dir().unwrap())
-                        .unwrap_or(&binary_path)
-                        .to_str()
-                        .unwrap();
-
-                    // No need to waste time freeing this memory,
-                    // since the process is about to exit anyway.
-                    // std::mem::forget(arena);
-
-                    problems.print_error_warning_count(total_time);
-                    println!(" while successfully building:\n\n    {generated_filename}");
-
-                    // Return a nonzero exit code if there were problems
-                    Ok(problems.exit_code())
-                }
-                BuildAndRun => {
-                    if problems.fatally_errored {
-                        problems.print_error_warning_count(total_time);
-                        println!(
-                            ".\n\nCannot run program due to fatal error…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
-                        );
-
-                        // Return a nonzero exit code due to fatal problem
-                        return Ok(problems.exit_code());
-                    }
-                    if problems.errors > 0 || problems.warnings > 0 {
-                        problems.print_error_warning_count(total_time);
-                        println!(
-                            ".\n\nRunning program anyway…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
-                        );
-                    }
-
-                    let args = matches
-                        .get_many::<OsString>(ARGS_FOR_APP)
-                        .unwrap_or_default()
-                        .map(|s| s.as_os_str());
-
-                    // don't waste time deallocating; the process ends anyway
-                    // ManuallyDrop will leak the bytes because we don't drop manually
-                    let bytes = &ManuallyDrop::new(std::fs::read(&binary_path).unwrap());
-
-                    roc_run(&arena, opt_level, target, args, bytes, expect_metadata)
-                }
-                BuildAndRunIfNoErrors => {
-                    if problems.fatally_errored {
-                        problems.print_error_warning_count(total_time);
-                        println!(
-                            ".\n\nCannot run program due to fatal error…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
-                        );
-
-                        // Return a nonzero exit code due to fatal problem
-                        return Ok(problems.exit_code());
-                    }
-                    debug_assert_eq!(
-                        problems.errors, 0,
-                        "if there are non-fatal errors, they should have been returned as an error variant"
-                    );
-
-                    if problems.warnings > 0 {
-                        problems.print_error_warning_count(total_time);
-                        println!(
-                            ".\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
-                        );
-                    }
-
-                    let args = matches
-                        .get_many::<OsString>(ARGS_FOR_APP)
-                        .unwrap_or_default()
-                        .map(|s| s.as_os_str());
-
-                    // don't waste time deallocating; the process ends anyway
-                    // ManuallyDrop will leak the bytes because we don't drop manually
-                    let bytes = &ManuallyDrop::new(std::fs::read(&binary_path).unwrap());
-
-                    roc_run(&arena, opt_level, target, args, bytes, expect_metadata)
-                }
-            }
-        }
-        Err(BuildFileError::ErrorModule { module, total_time }) => {
-            handle_error_module(module, total_time, path.as_os_str(), true)
-        }
-        Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
-    }
+        incremental,
+    )
 }
 
 fn roc_run<'a, I: IntoIterator<Item = &'a OsStr>>(
@@ -1016,6 +1828,9 @@ fn roc_run<'a, I: IntoIterator<Item = &'a OsStr>>(
     binary_bytes: &[u8],
     expect_metadata: ExpectMetadata,
 ) -> io::Result<i32> {
+    // Running the program can replace this process, so there may be no later chance to write it
+    roc_tracing::profile::finish()?;
+
     match target.architecture() {
         Architecture::Wasm32 => {
             let executable = roc_run_executable_file_path(binary_bytes)?;
@@ -1212,6 +2027,18 @@ impl ExecutableFile {
     }
 }
 
+// Only `roc dev --watch` runs more than one executable, but it does run a lot of them
+#[cfg(target_os = "linux")]
+impl Drop for ExecutableFile {
+    fn drop(&mut self) {
+        match self {
+            ExecutableFile::MemFd(fd, _) => unsafe {
+                libc::close(*fd);
+            },
+        }
+    }
+}
+
 // with Expect
 #[cfg(target_family = "unix")]
 fn roc_dev_native(
@@ -1223,7 +2050,7 @@ fn roc_dev_native(
 ) -> ! {
     use std::sync::{atomic::AtomicBool, Arc};
 
-    use roc_repl_expect::run::{ChildProcessMsg, ExpectMemory};
+    use roc_repl_expect::run::ExpectMemory;
 
     let ExpectMetadata {
         mut expectations,
@@ -1236,11 +2063,38 @@ fn roc_dev_native(
 
     let layout_interner = layout_interner.into_global();
 
+    let pid = spawn_native(&executable, &argv, &envp);
+
+    let sigchld = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGCHLD, Arc::clone(&sigchld)).unwrap();
+
+    let exit_status = wait_for_dev_child(
+        pid,
+        &sigchld,
+        &AtomicBool::new(false),
+        &mut memory,
+        arena,
+        &mut expectations,
+        &interns,
+        &layout_interner,
+    );
+
+    // we don't have an exit code if the child was killed, so we probably shouldn't make one up
+    std::process::exit(exit_status.unwrap_or(0))
+}
+
+/// Forks, and runs the executable in the child process. Returns the child's pid.
+#[cfg(target_family = "unix")]
+fn spawn_native(
+    executable: &ExecutableFile,
+    argv: &[*const c_char],
+    envp: &[*const c_char],
+) -> libc::pid_t {
     match unsafe { libc::fork() } {
         0 => unsafe {
             // we are the child
 
-            executable.execve(&argv, &envp);
+            executable.execve(argv, envp);
 
             // Display a human-friendly error message
             println!("Error {:?}", std::io::Error::last_os_error());
@@ -1255,46 +2109,58 @@ fn roc_dev_native(
 
             std::process::exit(1)
         }
-        pid @ 1.. => {
-            let sigchld = Arc::new(AtomicBool::new(false));
-            signal_hook::flag::register(signal_hook::consts::SIGCHLD, Arc::clone(&sigchld))
-                .unwrap();
-
-            let exit_code = loop {
-                match memory.wait_for_child(sigchld.clone()) {
-                    ChildProcessMsg::Terminate => {
-                        let mut status = 0;
-                        let options = 0;
-                        unsafe { libc::waitpid(pid, &mut status, options) };
-
-                        // if `WIFEXITED` returns false, `WEXITSTATUS` will just return junk
-                        break if libc::WIFEXITED(status) {
-                            libc::WEXITSTATUS(status)
-                        } else {
-                            // we don't have an exit code, so we probably shouldn't make one up
-                            0
-                        };
-                    }
-                    ChildProcessMsg::Expect => {
-                        let mut writer = std::io::stdout();
-                        roc_repl_expect::run::render_expects_in_memory(
-                            &mut writer,
-                            arena,
-                            &mut expectations,
-                            &interns,
-                            &layout_interner,
-                            &memory,
-                        )
-                        .unwrap();
+        pid => pid,
+    }
+}
 
-                        memory.reset();
-                    }
+/// Renders the expects that fail in a program built in development mode while it runs, until
+/// it terminates. Returns its exit code, unless it didn't exit by itself.
+///
+/// Another thread can cut the run short by setting `stop_requested` and then `sigchld`.
+#[cfg(target_family = "unix")]
+#[allow(clippy::too_many_arguments)]
+fn wait_for_dev_child<'a>(
+    pid: libc::pid_t,
+    sigchld: &std::sync::Arc<std::sync::atomic::AtomicBool>,
+    stop_requested: &std::sync::atomic::AtomicBool,
+    memory: &mut roc_repl_expect::run::ExpectMemory,
+    arena: &'a Bump,
+    expectations: &mut VecMap<ModuleId, roc_load::Expectations>,
+    interns: &'a roc_module::symbol::Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+) -> Option<i32> {
+    use roc_repl_expect::run::ChildProcessMsg;
+
+    loop {
+        match memory.wait_for_child(sigchld.clone()) {
+            ChildProcessMsg::Terminate => {
+                if stop_requested.load(std::sync::atomic::Ordering::Relaxed) {
+                    // it hasn't been waited for yet, so the pid still belongs to the child
+                    unsafe { libc::kill(pid, libc::SIGTERM) };
                 }
-            };
 
-            std::process::exit(exit_code)
+                let mut status = 0;
+                let options = 0;
+                unsafe { libc::waitpid(pid, &mut status, options) };
+
+                // if `WIFEXITED` returns false, `WEXITSTATUS` will just return junk
+                break libc::WIFEXITED(status).then(|| libc::WEXITSTATUS(status));
+            }
+            ChildProcessMsg::Expect => {
+                let mut writer = std::io::stdout();
+                roc_repl_expect::run::render_expects_in_memory(
+                    &mut writer,
+                    arena,
+                    expectations,
+                    interns,
+                    layout_interner,
+                    memory,
+                )
+                .unwrap();
+
+                memory.reset();
+            }
         }
-        _ => unreachable!(),
     }
 }
 
@@ -1,21 +1,25 @@
 //! The `roc` binary that brings together all functionality in the Roc toolset.
 use bumpalo::Bump;
 use roc_build::link::LinkType;
-use roc_build::program::{check_file, CodeGenBackend};
+use roc_build::program::CodeGenBackend;
 use roc_cli::{
-    build_app, format_files, format_src, test, BuildConfig, FormatMode, CMD_BUILD, CMD_CHECK,
-    CMD_DEV, CMD_DOCS, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_PREPROCESS_HOST, CMD_REPL,
-    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_LIB, FLAG_MAIN,
-    FLAG_NO_LINK, FLAG_OUTPUT, FLAG_PP_DYLIB, FLAG_PP_HOST, FLAG_PP_PLATFORM, FLAG_STDIN,
-    FLAG_STDOUT, FLAG_TARGET, FLAG_TIME, GLUE_DIR, GLUE_SPEC, ROC_FILE,
+    bench, build_app, check, format_files, format_src, test, unified_diff, BuildConfig, FormatMode,
+    CMD_BENCH, CMD_BUILD, CMD_CHECK, CMD_DAEMON, CMD_DEV, CMD_DOCS, CMD_EXPLAIN, CMD_FORMAT,
+    CMD_GEN_STUB_LIB, CMD_GLUE, CMD_PREPROCESS_HOST, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VERSION,
+    DIRECTORY_OR_FILES, ERROR_CODE, FLAG_CHECK, FLAG_DEV, FLAG_DOCS_FORMAT, FLAG_INTERP, FLAG_LIB,
+    FLAG_MAX_WIDTH, FLAG_NO_LINK, FLAG_OUTPUT, FLAG_PP_DYLIB, FLAG_PP_HOST, FLAG_PP_PLATFORM,
+    FLAG_PROFILE_COMPILER, FLAG_RUN_EXPECTS, FLAG_STDIN, FLAG_STDOUT, FLAG_TARGET, FLAG_WASM,
+    FLAG_WATCH, GLUE_DIR, GLUE_SPEC, ROC_FILE,
 };
-use roc_docs::generate_docs_html;
+use roc_docs::{generate_docs_html, generate_docs_json};
 use roc_error_macros::user_error;
+use roc_fmt::FormatConfig;
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
-use roc_load::{FunctionKind, LoadingProblem, Threading};
+use roc_load::FunctionKind;
 use roc_packaging::cache::{self, RocCacheDir};
-use roc_target::Target;
+use roc_repl_cli::ReplBackend;
+use roc_target::{Architecture, Target};
 use std::fs::{self, FileType};
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
@@ -42,6 +46,14 @@ fn main() -> io::Result<()> {
         .collect();
     let matches = app.get_matches();
 
+    let subcommand_matches = matches
+        .subcommand()
+        .map_or(&matches, |(_, matches)| matches);
+
+    if let Ok(Some(path)) = subcommand_matches.try_get_one::<PathBuf>(FLAG_PROFILE_COMPILER) {
+        roc_tracing::profile::start(path.clone());
+    }
+
     let exit_code = match matches.subcommand() {
         None => {
             if matches.contains_id(ROC_FILE) {
@@ -77,24 +89,42 @@ fn main() -> io::Result<()> {
         }
         Some((CMD_TEST, matches)) => {
             if matches.contains_id(ROC_FILE) {
-                test(matches, Triple::host().into())
+                if matches.get_flag(FLAG_WATCH) {
+                    roc_cli::watch::test(matches, Triple::host().into())
+                } else {
+                    test(matches, Triple::host().into())
+                }
             } else {
                 eprintln!("What .roc file do you want to test? Specify it at the end of the `roc test` command.");
 
                 Ok(1)
             }
         }
+        Some((CMD_DAEMON, matches)) => roc_cli::daemon::main(matches),
+        Some((CMD_BENCH, matches)) => {
+            if matches.contains_id(ROC_FILE) {
+                bench(matches, Triple::host().into())
+            } else {
+                eprintln!("What .roc file do you want to benchmark? Specify it at the end of the `roc bench` command.");
+
+                Ok(1)
+            }
+        }
         Some((CMD_DEV, matches)) => {
             if matches.contains_id(ROC_FILE) {
-                build(
-                    matches,
-                    &subcommands,
-                    BuildConfig::BuildAndRunIfNoErrors,
-                    Triple::host().into(),
-                    None,
-                    RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
-                    LinkType::Executable,
-                )
+                if matches.get_flag(FLAG_WATCH) {
+                    roc_cli::watch::dev(matches, Triple::host().into())
+                } else {
+                    build(
+                        matches,
+                        &subcommands,
+                        BuildConfig::BuildAndRunIfNoErrors,
+                        Triple::host().into(),
+                        None,
+                        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                        LinkType::Executable,
+                    )
+                }
             } else {
                 eprintln!("What .roc file do you want to build? Specify it at the end of the `roc run` command.");
 
@@ -155,25 +185,36 @@ fn main() -> io::Result<()> {
                 ));
             }
 
-            let dylib_path = matches.get_one::<PathBuf>(FLAG_PP_DYLIB).unwrap();
-            if !dylib_path.is_file() {
-                preprocess_host_err(format!(
-                    "Expected to find the app stub dynamic library file at {}",
-                    dylib_path.display()
-                ));
-            }
             let target = matches
                 .get_one::<String>(FLAG_TARGET)
                 .and_then(|s| Target::from_str(s).ok())
                 .unwrap_or_default();
 
+            // The wasm backend splices the app straight into the host, so there's no stub to link against.
+            let dylib_path = match matches.get_one::<PathBuf>(FLAG_PP_DYLIB) {
+                Some(dylib_path) => {
+                    if !dylib_path.is_file() {
+                        preprocess_host_err(format!(
+                            "Expected to find the app stub dynamic library file at {}",
+                            dylib_path.display()
+                        ));
+                    }
+                    dylib_path.clone()
+                }
+                None if target.architecture() == Architecture::Wasm32 => PathBuf::new(),
+                None => user_error!(
+                    "\n\n ERROR PRE-PROCESSING HOST: {}\n\n",
+                    "Expected the path to a stubbed app dynamic library"
+                ),
+            };
+
             let verbose_and_time = matches.get_one::<bool>(roc_cli::FLAG_VERBOSE).unwrap();
 
             roc_linker::preprocess_host(
                 target,
                 host_path,
                 platform_path,
-                dylib_path,
+                &dylib_path,
                 *verbose_and_time,
                 *verbose_and_time,
             );
@@ -205,53 +246,46 @@ fn main() -> io::Result<()> {
                 link_type,
             )?)
         }
+        Some((CMD_CHECK, matches)) if matches.get_flag(FLAG_RUN_EXPECTS) => {
+            roc_cli::check_and_run_expects(matches, Triple::host().into())
+        }
         Some((CMD_CHECK, matches)) => {
             let arena = Bump::new();
 
-            let emit_timings = matches.get_flag(FLAG_TIME);
-            let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
-            let threading = match matches.get_one::<usize>(roc_cli::FLAG_MAX_THREADS) {
-                None => Threading::AllAvailable,
-                Some(0) => user_error!("cannot build with at most 0 threads"),
-                Some(1) => Threading::Single,
-                Some(n) => Threading::AtMost(*n),
+            check(&arena, matches, None)
+        }
+        Some((CMD_REPL, matches)) => {
+            let backend = if matches.get_flag(FLAG_WASM) {
+                ReplBackend::Wasm
+            } else if matches.get_flag(FLAG_INTERP) {
+                ReplBackend::Interp
+            } else {
+                ReplBackend::Native
             };
 
-            let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
-
-            match check_file(
-                &arena,
-                roc_file_path.to_owned(),
-                opt_main_path.cloned(),
-                emit_timings,
-                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
-                threading,
-            ) {
-                Ok((problems, total_time)) => {
-                    problems.print_error_warning_count(total_time);
-                    Ok(problems.exit_code())
-                }
-
-                Err(LoadingProblem::FormattedReport(report)) => {
-                    print!("{report}");
-
-                    Ok(1)
-                }
-                Err(other) => {
-                    panic!("build_file failed with error:\n{other:?}");
-                }
-            }
+            Ok(roc_repl_cli::main(backend))
         }
-        Some((CMD_REPL, _)) => Ok(roc_repl_cli::main()),
         Some((CMD_DOCS, matches)) => {
             let root_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
             let out_dir = matches.get_one::<OsString>(FLAG_OUTPUT).unwrap();
 
-            generate_docs_html(root_path.to_owned(), out_dir.as_ref());
+            match matches
+                .get_one::<String>(FLAG_DOCS_FORMAT)
+                .map(|s| s.as_str())
+            {
+                Some("json") => generate_docs_json(root_path.to_owned(), out_dir.as_ref()),
+                _ => generate_docs_html(root_path.to_owned(), out_dir.as_ref()),
+            }
 
             Ok(0)
         }
         Some((CMD_FORMAT, matches)) => {
+            let format_config = match matches.get_one::<usize>(FLAG_MAX_WIDTH) {
+                Some(max_width) => FormatConfig {
+                    max_width: *max_width,
+                },
+                None => FormatConfig::default(),
+            };
             let from_stdin = matches.get_flag(FLAG_STDIN);
             let to_stdout = matches.get_flag(FLAG_STDOUT);
             let format_mode = if to_stdout {
@@ -259,15 +293,12 @@ fn main() -> io::Result<()> {
             } else {
                 match matches.get_flag(FLAG_CHECK) {
                     true => FormatMode::CheckOnly,
+                    // There's no file to write to, so formatting stdin prints to stdout.
+                    false if from_stdin => FormatMode::WriteToStdout,
                     false => FormatMode::WriteToFile,
                 }
             };
 
-            if from_stdin && matches!(format_mode, FormatMode::WriteToFile) {
-                eprintln!("When using the --stdin flag, either the --check or the --stdout flag must also be specified. (Otherwise, it's unclear what filename to write to!)");
-                std::process::exit(1);
-            }
-
             let roc_files = {
                 let mut roc_files = Vec::new();
 
@@ -314,15 +345,16 @@ fn main() -> io::Result<()> {
                     std::process::exit(1);
                 });
 
-                match format_src(&arena, src) {
+                match format_src(&arena, src, format_config) {
                     Ok(formatted_src) => {
                         match format_mode {
                             FormatMode::CheckOnly => {
                                 if src == formatted_src {
+                                    0
+                                } else {
+                                    print!("{}", unified_diff("<stdin>", src, &formatted_src));
                                     eprintln!("One or more files need to be reformatted.");
                                     1
-                                } else {
-                                    0
                                 }
                             }
                             FormatMode::WriteToStdout => {
@@ -334,8 +366,7 @@ fn main() -> io::Result<()> {
                                 0
                             }
                             FormatMode::WriteToFile => {
-                                // We would have errored out already if you specified --stdin
-                                // without either --stdout or --check specified as well.
+                                // Formatting stdin without --check always prints to stdout.
                                 unreachable!()
                             }
                         }
@@ -346,7 +377,7 @@ fn main() -> io::Result<()> {
                     }
                 }
             } else {
-                match format_files(roc_files, format_mode) {
+                match format_files(roc_files, format_mode, format_config) {
                     Ok(()) => 0,
                     Err(message) => {
                         eprintln!("{message}");
@@ -357,6 +388,22 @@ fn main() -> io::Result<()> {
 
             Ok(format_exit_code)
         }
+        Some((CMD_EXPLAIN, matches)) => {
+            let code = matches.get_one::<String>(ERROR_CODE).unwrap();
+
+            match roc_reporting::code::explain(code) {
+                Some(explanation) => {
+                    print!("{explanation}");
+
+                    Ok(0)
+                }
+                None => {
+                    eprintln!("{code} is not a Roc error code. Codes look like E001, and are printed next to the title of each report.");
+
+                    Ok(1)
+                }
+            }
+        }
         Some((CMD_VERSION, _)) => {
             print!(
                 "{}",
@@ -368,6 +415,8 @@ fn main() -> io::Result<()> {
         _ => unreachable!(),
     }?;
 
+    roc_tracing::profile::finish()?;
+
     std::process::exit(exit_code);
 }
 
@@ -0,0 +1,521 @@
+//! `roc dev --watch` and `roc test --watch`: build and run the program (or its tests) again
+//! every time one of its source files changes.
+//!
+//! Every rebuild goes through the same incremental cache, so only the modules affected by a
+//! change get type-checked again (see `roc_load::Incremental`). The arena and the layout
+//! interner of one build are handed to the next one too, until the arena has grown big
+//! enough that starting over from an empty one is worth it.
+#![cfg_attr(windows, allow(dead_code, unused_imports))]
+
+use std::collections::hash_map::Entry;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use clap::ArgMatches;
+use roc_collections::{MutMap, MutSet};
+use roc_load::{IncrementalCache, SharedIncrementalCache};
+use roc_target::Target;
+
+use crate::ROC_FILE;
+
+/// How often the source files get checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Editors tend to write a file in several steps, and to save several files at once
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Once an arena holds this much, the next build starts from a fresh one
+#[cfg(not(windows))]
+pub(crate) const ARENA_RESET_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileState {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+impl FileState {
+    /// Reads the file again only if its metadata says it may have changed.
+    fn read(path: &Path, previous: Option<&FileState>) -> Option<FileState> {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let metadata = std::fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok();
+        let len = metadata.len();
+
+        match previous {
+            Some(previous) if previous.modified == modified && previous.len == len => {
+                Some(previous.clone())
+            }
+            _ => {
+                let mut hasher = DefaultHasher::new();
+                std::fs::read(path).ok()?.hash(&mut hasher);
+
+                Some(FileState {
+                    modified,
+                    len,
+                    hash: hasher.finish(),
+                })
+            }
+        }
+    }
+}
+
+/// Keeps an eye on the source files of a program, and on the .roc files next to them, which
+/// the program might start to import. A file that doesn't exist has no state; creating or
+/// deleting one counts as a change.
+#[derive(Debug, Default)]
+struct SourceWatcher {
+    files: MutMap<PathBuf, Option<FileState>>,
+    dirs: MutSet<PathBuf>,
+}
+
+impl SourceWatcher {
+    fn new(root: &Path) -> Self {
+        let mut watcher = Self::default();
+        watcher.watch([root]);
+
+        watcher
+    }
+
+    /// Starts watching these files, as they are now, and the directories they are in.
+    fn watch<'p>(&mut self, paths: impl IntoIterator<Item = &'p Path>) {
+        for path in paths {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+
+            if self.dirs.insert(dir.to_path_buf()) {
+                for roc_file in roc_files_in(dir) {
+                    self.watch_file(roc_file);
+                }
+            }
+
+            self.watch_file(path.to_path_buf());
+        }
+    }
+
+    fn watch_file(&mut self, path: PathBuf) {
+        if let Entry::Vacant(entry) = self.files.entry(path) {
+            let state = FileState::read(entry.key(), None);
+            entry.insert(state);
+        }
+    }
+
+    fn changed_files(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+
+        let new_files: Vec<_> = (self.dirs.iter())
+            .flat_map(|dir| roc_files_in(dir))
+            .filter(|path| !self.files.contains_key(path))
+            .collect();
+
+        for path in new_files {
+            self.files.insert(path.clone(), None);
+        }
+
+        for (path, state) in self.files.iter_mut() {
+            let new_state = FileState::read(path, state.as_ref());
+
+            if new_state != *state {
+                *state = new_state;
+                changed.push(path.clone());
+            }
+        }
+
+        changed
+    }
+
+    /// Blocks until some of the files change, and returns those.
+    fn wait_for_change(&mut self) -> Vec<PathBuf> {
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let mut changed = self.changed_files();
+
+            if !changed.is_empty() {
+                std::thread::sleep(DEBOUNCE);
+
+                for path in self.changed_files() {
+                    if !changed.contains(&path) {
+                        changed.push(path);
+                    }
+                }
+
+                changed.sort();
+
+                return changed;
+            }
+        }
+    }
+}
+
+fn roc_files_in(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    (std::fs::read_dir(dir).into_iter().flatten())
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().and_then(OsStr::to_str) == Some("roc"))
+}
+
+fn print_reuse(cache: &SharedIncrementalCache) {
+    let stats = cache.lock().last_load();
+
+    if stats.reused > 0 {
+        println!(
+            "\x1B[2m(Type-checked {} module(s), reused the types of {} unchanged one(s).)\x1B[22m",
+            stats.solved, stats.reused
+        );
+    }
+}
+
+fn print_changes(changed: &[PathBuf]) {
+    let current_dir = std::env::current_dir().unwrap_or_default();
+
+    let names: Vec<_> = changed
+        .iter()
+        .map(|path| {
+            path.strip_prefix(&current_dir)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        })
+        .collect();
+
+    println!(
+        "\n\x1B[36m{}\x1B[39m\n\nChanged: {}\n",
+        "─".repeat(80),
+        names.join(", ")
+    );
+}
+
+fn wait_for_change(watcher: &mut SourceWatcher, cache: &SharedIncrementalCache) {
+    watcher.watch(cache.lock().sources());
+
+    println!("\n\x1B[2mWaiting for changes…\x1B[22m");
+
+    print_changes(&watcher.wait_for_change());
+}
+
+#[cfg(windows)]
+pub fn test(_matches: &ArgMatches, _target: Target) -> std::io::Result<i32> {
+    Err(unsupported("roc test --watch"))
+}
+
+#[cfg(not(windows))]
+pub fn test(matches: &ArgMatches, target: Target) -> std::io::Result<i32> {
+    use bumpalo::Bump;
+    use roc_load::Incremental;
+
+    crate::exit_if_roc_file_missing(matches, &[]);
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let mut watcher = SourceWatcher::new(path);
+    let cache = IncrementalCache::new_shared();
+
+    loop {
+        let arena = Bump::new();
+        let mut layout_interner = None;
+
+        while arena.allocated_bytes() < ARENA_RESET_BYTES {
+            let incremental = Incremental {
+                cache: cache.clone(),
+                layout_interner: layout_interner.take(),
+            };
+
+            let (_, reusable_interner) =
+                crate::run_tests(&arena, matches, target, Some(incremental))?;
+            layout_interner = reusable_interner;
+
+            print_reuse(&cache);
+            wait_for_change(&mut watcher, &cache);
+        }
+    }
+}
+
+#[cfg(not(target_family = "unix"))]
+pub fn dev(_matches: &ArgMatches, _target: Target) -> std::io::Result<i32> {
+    Err(unsupported("roc dev --watch"))
+}
+
+#[cfg(not(target_family = "unix"))]
+fn unsupported(command: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("`{command}` isn't supported on this platform yet."),
+    )
+}
+
+#[cfg(target_family = "unix")]
+pub fn dev(matches: &ArgMatches, target: Target) -> std::io::Result<i32> {
+    use std::os::raw::c_char;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use bumpalo::collections::CollectIn;
+    use bumpalo::Bump;
+    use roc_build::link::LinkType;
+    use roc_build::program::{handle_error_module, handle_loading_problem};
+    use roc_build::program::{BuildFileError, BuiltFile};
+    use roc_load::Incremental;
+    use roc_packaging::cache::{self, RocCacheDir};
+    use roc_repl_expect::run::ExpectMemory;
+    use roc_reporting::report::to_backend_failure_report_string;
+
+    use crate::{BuildConfig, ARGS_FOR_APP};
+
+    crate::exit_if_roc_file_missing(matches, &[]);
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let args: Vec<OsString> = matches
+        .get_many::<OsString>(ARGS_FOR_APP)
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+
+    let roc_cache_dir = cache::roc_cache_dir();
+    let mut watcher = SourceWatcher::new(path);
+    let cache = IncrementalCache::new_shared();
+
+    // The program finds the memory for its expects through the pid of its parent: this process
+    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
+    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+
+    let sigchld = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGCHLD, Arc::clone(&sigchld))?;
+
+    loop {
+        let arena = Bump::new();
+        let mut layout_interner = None;
+
+        while arena.allocated_bytes() < ARENA_RESET_BYTES {
+            let incremental = Incremental {
+                cache: cache.clone(),
+                layout_interner: layout_interner.take(),
+            };
+
+            let built = crate::build_with_flags(
+                &arena,
+                matches,
+                &BuildConfig::BuildAndRunIfNoErrors,
+                target,
+                None,
+                RocCacheDir::Persistent(roc_cache_dir.as_path()),
+                LinkType::Executable,
+                Some(incremental),
+            );
+
+            print_reuse(&cache);
+
+            let BuiltFile {
+                binary_path,
+                problems,
+                total_time,
+                expect_metadata,
+            } = match built {
+                Ok(built_file) => built_file,
+                Err(BuildFileError::ErrorModule { module, total_time }) => {
+                    handle_error_module(module, total_time, path.as_os_str(), false)?;
+                    wait_for_change(&mut watcher, &cache);
+
+                    continue;
+                }
+                Err(BuildFileError::LoadingProblem(problem)) => {
+                    handle_loading_problem(problem)?;
+                    wait_for_change(&mut watcher, &cache);
+
+                    continue;
+                }
+                Err(BuildFileError::BackendFailure { filename, failure }) => {
+                    eprintln!("{}", to_backend_failure_report_string(filename, failure));
+                    wait_for_change(&mut watcher, &cache);
+
+                    continue;
+                }
+            };
+
+            watcher.watch(cache.lock().sources());
+
+            let mut expectations = expect_metadata.expectations;
+            let interns = arena.alloc(expect_metadata.interns);
+            let global_interner = expect_metadata.layout_interner.into_global();
+
+            if problems.fatally_errored {
+                problems.print_error_warning_count(total_time);
+                println!(".\n\nCannot run program due to fatal error…");
+
+                wait_for_change(&mut watcher, &cache);
+            } else {
+                if problems.warnings > 0 {
+                    problems.print_error_warning_count(total_time);
+                    println!(
+                        ".\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
+                        "─".repeat(80)
+                    );
+                }
+
+                let executable = crate::roc_run_executable_file_path(&std::fs::read(binary_path)?)?;
+                let (argv_cstrings, envp_cstrings) =
+                    crate::make_argv_envp(&arena, &executable, &args);
+
+                let argv: bumpalo::collections::Vec<*const c_char> = argv_cstrings
+                    .iter()
+                    .map(|s| s.as_ptr())
+                    .chain([std::ptr::null()])
+                    .collect_in(&arena);
+
+                let envp: bumpalo::collections::Vec<*const c_char> = envp_cstrings
+                    .iter()
+                    .map(|s| s.as_ptr())
+                    .chain([std::ptr::null()])
+                    .collect_in(&arena);
+
+                // Building may have run child processes of its own
+                sigchld.store(false, Ordering::Relaxed);
+                memory.reset();
+
+                let pid = crate::spawn_native(&executable, &argv, &envp);
+
+                // Watch for changes while the program runs, and stop it when there is one
+                let stop_requested = Arc::new(AtomicBool::new(false));
+                let watching = {
+                    let sigchld = Arc::clone(&sigchld);
+                    let stop_requested = Arc::clone(&stop_requested);
+                    let mut watcher = std::mem::take(&mut watcher);
+
+                    std::thread::spawn(move || {
+                        let changed = watcher.wait_for_change();
+
+                        stop_requested.store(true, Ordering::Relaxed);
+                        sigchld.store(true, Ordering::Relaxed);
+
+                        (watcher, changed)
+                    })
+                };
+
+                let exit_code = crate::wait_for_dev_child(
+                    pid,
+                    &sigchld,
+                    &stop_requested,
+                    &mut memory,
+                    &arena,
+                    &mut expectations,
+                    interns,
+                    &global_interner,
+                );
+
+                if !stop_requested.load(Ordering::Relaxed) {
+                    match exit_code {
+                        Some(code) => {
+                            println!("\n\x1B[2mThe program exited with code {code}.\x1B[22m")
+                        }
+                        None => println!("\n\x1B[2mThe program was terminated.\x1B[22m"),
+                    }
+
+                    println!("\n\x1B[2mWaiting for changes…\x1B[22m");
+                }
+
+                let (returned_watcher, changed) = watching.join().unwrap();
+                watcher = returned_watcher;
+
+                print_changes(&changed);
+            }
+
+            layout_interner = Some(global_interner);
+        }
+    }
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+    use bumpalo::Bump;
+    use roc_load::{ExecutionMode, FunctionKind, Incremental, LoadConfig, ReuseStats, Threading};
+    use roc_packaging::cache::RocCacheDir;
+    use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+
+    fn check(main: &Path, cache: &SharedIncrementalCache) -> ReuseStats {
+        let arena = Bump::new();
+        let load_config = LoadConfig {
+            target: Target::LinuxX64,
+            function_kind: FunctionKind::LambdaSet,
+            render: RenderTarget::Generic,
+            palette: DEFAULT_PALETTE,
+            threading: Threading::Single,
+            exec_mode: ExecutionMode::Check,
+        };
+        let incremental = Incremental {
+            cache: cache.clone(),
+            layout_interner: None,
+        };
+
+        roc_load::load_and_typecheck_incremental(
+            &arena,
+            main.to_path_buf(),
+            None,
+            RocCacheDir::Disallowed,
+            load_config,
+            incremental,
+        )
+        .unwrap();
+
+        cache.lock().last_load()
+    }
+
+    #[test]
+    fn edit_only_solves_dependents_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, src: &str| std::fs::write(dir.path().join(name), src).unwrap();
+
+        write(
+            "Main.roc",
+            "module [greeting]\n\nimport Name\nimport Mark\n\ngreeting = Str.concat Name.name Mark.mark\n",
+        );
+        write("Name.roc", "module [name]\n\nname = \"World\"\n");
+        write("Mark.roc", "module [mark]\n\nmark = \"!\"\n");
+
+        let main = dir.path().join("Main.roc");
+        let mut watcher = SourceWatcher::new(&main);
+        let cache = IncrementalCache::new_shared();
+
+        assert_eq!(
+            check(&main, &cache),
+            ReuseStats {
+                reused: 0,
+                solved: 3
+            }
+        );
+        watcher.watch(cache.lock().sources());
+        assert!(watcher.changed_files().is_empty());
+
+        // Name exposes something new, so Main has to be solved again, but not Mark
+        write(
+            "Name.roc",
+            "module [name, other]\n\nname = \"World\"\n\nother = 1\n",
+        );
+        assert_eq!(watcher.changed_files(), [dir.path().join("Name.roc")]);
+        assert_eq!(
+            check(&main, &cache),
+            ReuseStats {
+                reused: 1,
+                solved: 2
+            }
+        );
+
+        // A new body with the same interface only needs Name itself solved again
+        write(
+            "Name.roc",
+            "module [name, other]\n\nname = \"Roc\"\n\nother = 1\n",
+        );
+        assert_eq!(watcher.changed_files(), [dir.path().join("Name.roc")]);
+        assert_eq!(
+            check(&main, &cache),
+            ReuseStats {
+                reused: 2,
+                solved: 1
+            }
+        );
+    }
+}
@@ -17,7 +17,7 @@ mod cli_run {
     use const_format::concatcp;
     use indoc::indoc;
     use regex::Regex;
-    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_FORMAT, CMD_RUN, CMD_TEST};
+    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_EXPLAIN, CMD_FORMAT, CMD_RUN, CMD_TEST};
     use roc_reporting::report::strip_colors;
     use roc_test_utils::assert_multiline_str_eq;
     use serial_test::serial;
@@ -54,6 +54,7 @@ mod cli_run {
     const OPTIMIZE_FLAG: &str = concatcp!("--", roc_cli::FLAG_OPTIMIZE);
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
+    const STDIN_FLAG: &str = concatcp!("--", roc_cli::FLAG_STDIN);
     #[allow(dead_code)]
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT);
     #[allow(dead_code)]
@@ -555,7 +556,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── EXPECT FAILED in tests/expects/expects.roc ──────────────────────────────────
+                ── EXPECT FAILED [E401] in tests/expects/expects.roc ───────────────────────────
 
                 This expectation failed:
 
@@ -588,7 +589,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── EXPECT FAILED in tests/expects/expects.roc ──────────────────────────────────
+                ── EXPECT FAILED [E401] in tests/expects/expects.roc ───────────────────────────
 
                 This expectation failed:
 
@@ -600,7 +601,7 @@ mod cli_run {
                 a : Num *
                 a = 1
 
-                ── EXPECT FAILED in tests/expects/expects.roc ──────────────────────────────────
+                ── EXPECT FAILED [E401] in tests/expects/expects.roc ───────────────────────────
 
                 This expectation failed:
 
@@ -612,7 +613,7 @@ mod cli_run {
                 a : Num *
                 a = 1
 
-                ── EXPECT FAILED in tests/expects/expects.roc ──────────────────────────────────
+                ── EXPECT FAILED [E401] in tests/expects/expects.roc ───────────────────────────
 
                 This expectation failed:
 
@@ -631,6 +632,7 @@ mod cli_run {
                 b = 2
 
 
+                Warning: skipped 1 property expect(s), because compiled tests can't generate their arguments yet. Run them with `roc check --run-expects`.
                 1 failed and 0 passed in <ignored for test> ms.
                 "#
             ),
@@ -663,7 +665,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── UNRECOGNIZED PACKAGE in tests/module_imports_pkg/Module.roc ─────────────────
+                ── UNRECOGNIZED PACKAGE [E311] in tests/module_imports_pkg/Module.roc ──────────
 
                 This module is trying to import from `pkg`:
 
@@ -690,7 +692,7 @@ mod cli_run {
             &["--main", "tests/module_imports_pkg/app.roc"],
             indoc!(
                 r#"
-                ── UNRECOGNIZED PACKAGE in tests/module_imports_pkg/ImportsUnknownPkg.roc ──────
+                ── UNRECOGNIZED PACKAGE [E311] in .../module_imports_pkg/ImportsUnknownPkg.roc ─
 
                 This module is trying to import from `cli`:
 
@@ -813,6 +815,19 @@ mod cli_run {
         assert!(out.status.success());
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    #[serial(cli_platform)]
+    fn cli_countdown_check_wasm32() {
+        let path = file_path_from_root("crates/cli/tests/cli", "countdown.roc");
+        let out = run_roc(
+            [CMD_CHECK, path.to_str().unwrap(), "--target=wasm32"],
+            &[],
+            &[],
+        );
+        assert!(out.status.success());
+    }
+
     #[test]
     #[cfg_attr(windows, ignore)]
     #[serial(cli_platform)]
@@ -1467,7 +1482,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── TYPE MISMATCH in tests/known_bad/TypeError.roc ──────────────────────────────
+                ── TYPE MISMATCH [E001] in tests/known_bad/TypeError.roc ───────────────────────
 
                 Something is off with the body of the main definition:
 
@@ -1501,7 +1516,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── UNUSED IMPORT in ...nown_bad/UnusedImportButWithALongFileNameForTesting.roc ─
+                ── UNUSED IMPORT [E104] in ...d/UnusedImportButWithALongFileNameForTesting.roc ─
 
                 Symbol is imported but not used.
 
@@ -1524,7 +1539,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── MISSING DEFINITION in tests/known_bad/ExposedNotDefined.roc ─────────────────
+                ── MISSING DEFINITION [E112] in tests/known_bad/ExposedNotDefined.roc ──────────
 
                 bar is listed as exposed, but it isn't defined in this module.
 
@@ -1545,7 +1560,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── UNUSED IMPORT in tests/known_bad/UnusedImport.roc ───────────────────────────
+                ── UNUSED IMPORT [E104] in tests/known_bad/UnusedImport.roc ────────────────────
 
                 Symbol is imported but not used.
 
@@ -1561,6 +1576,50 @@ mod cli_run {
         );
     }
 
+    #[test]
+    fn unused_import_json() {
+        let file = known_bad_file("UnusedImport.roc");
+        let out = run_roc(
+            [CMD_CHECK, file.to_str().unwrap(), "--output=json"],
+            &[],
+            &[],
+        );
+
+        // 2 means there were only warnings
+        assert_eq!(out.status.code(), Some(2), "{}", out.stderr);
+
+        let json: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+        let diagnostic = &json["diagnostics"][0];
+
+        assert_eq!(json["warnings"], 1);
+        assert_eq!(diagnostic["severity"], "warning");
+        assert_eq!(diagnostic["code"], "E104");
+        assert_eq!(
+            diagnostic["region"],
+            serde_json::json!({
+                "start": { "line": 2, "column": 13 },
+                "end": { "line": 2, "column": 29 },
+            })
+        );
+        assert!(diagnostic["file"]
+            .as_str()
+            .unwrap()
+            .ends_with("UnusedImport.roc"));
+    }
+
+    #[test]
+    fn explain_error_code() {
+        let out = run_roc([CMD_EXPLAIN, "E104"], &[], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert!(out.stdout.starts_with("E104: UNUSED IMPORT\n"));
+
+        let out = run_roc([CMD_EXPLAIN, "E999"], &[], &[]);
+
+        assert_eq!(out.status.code(), Some(1));
+        assert!(out.stderr.contains("E999 is not a Roc error code"));
+    }
+
     #[test]
     fn unknown_generates_with() {
         check_compile_error(
@@ -1568,7 +1627,7 @@ mod cli_run {
             &[],
             indoc!(
                 r#"
-                ── UNKNOWN GENERATES FUNCTION in tests/known_bad/UnknownGeneratesWith.roc ──────
+                ── UNKNOWN GENERATES FUNCTION [E113] in .../known_bad/UnknownGeneratesWith.roc ─
 
                 I don't know how to generate the foobar function.
 
@@ -1603,6 +1662,51 @@ mod cli_run {
         // This doesn't fail, since only "Formatted.roc" and non-roc files are present in this folder
         check_format_check_as_expected(&fixtures_dir("format/formatted_directory"), true);
     }
+
+    #[test]
+    fn format_check_prints_diff() {
+        let file = fixture_file("format", "NotFormatted.roc");
+        let out = run_roc([CMD_FORMAT, file.to_str().unwrap(), CHECK_FLAG], &[], &[]);
+
+        assert!(!out.status.success());
+        assert!(
+            out.stdout.contains(indoc!(
+                r#"
+                -app [main]    { pf: "platform/main.roc" }
+                +app [main] { pf: "platform/main.roc" }
+                "#
+            )),
+            "unexpected diff:\n{}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    fn format_stdin_to_stdout() {
+        let unformatted =
+            std::fs::read_to_string(fixture_file("format", "NotFormatted.roc")).unwrap();
+        let formatted = std::fs::read_to_string(fixture_file("format", "Formatted.roc")).unwrap();
+
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG], &[unformatted.as_str()], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_eq!(out.stdout, formatted);
+
+        let out = run_roc(
+            [CMD_FORMAT, STDIN_FLAG, CHECK_FLAG],
+            &[formatted.as_str()],
+            &[],
+        );
+        assert!(out.status.success(), "{}", out.stderr);
+
+        let out = run_roc(
+            [CMD_FORMAT, STDIN_FLAG, CHECK_FLAG],
+            &[unformatted.as_str()],
+            &[],
+        );
+        assert!(!out.status.success());
+        assert!(out.stdout.starts_with("--- <stdin>\n+++ <stdin>\n"));
+    }
 }
 
 #[cfg(feature = "wasm32-cli-run")]
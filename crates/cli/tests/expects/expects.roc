@@ -38,3 +38,5 @@ main =
 
     when r is
         _ -> "Program finished!\n"
+
+expect \n -> n + 0 == n
@@ -276,9 +276,14 @@ where
 
                 let host_exposed: Vec<_> = symbols
                     .iter()
-                    .map(|symbol| {
+                    .map(|(symbol, proc_layout)| {
                         (
-                            func_name_bytes_help(*symbol, [], Niche::NONE, layout.result),
+                            func_name_bytes_help(
+                                *symbol,
+                                [],
+                                proc_layout.niche,
+                                proc_layout.result,
+                            ),
                             [].as_slice(),
                         )
                     })
@@ -8,9 +8,9 @@ use roc_target::{Architecture, OperatingSystem, Target};
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::fs::DirEntry;
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::process::{self, Child, Command};
+use std::process::{self, Child, Command, Stdio};
 use std::{env, fs};
 use wasi_libc_sys::{WASI_COMPILER_RT_PATH, WASI_LIBC_PATH};
 
@@ -118,6 +118,15 @@ fn find_zig_glue_path() -> PathBuf {
     internal_error!("cannot find `glue.zig`. Check the source code in find_zig_glue_path() to show all the paths I tried.")
 }
 
+/// The `build_options` module that the builtins imported by `glue.zig` read their settings from.
+/// Hosts get the defaults from the file next to `glue.zig`; only the builtins' own build.zig
+/// changes them.
+fn zig_build_options_module() -> String {
+    let path = find_zig_glue_path().with_file_name("build_options.zig");
+
+    format!("build_options::{}", path.to_str().unwrap())
+}
+
 fn find_wasi_libc_path() -> PathBuf {
     // This path is available when built and run from source
     // Environment variable defined in wasi-libc-sys/build.rs
@@ -172,7 +181,12 @@ pub fn build_zig_host_native(
         zig_host_src,
         &format!("-femit-bin={emit_bin}"),
         "--mod",
-        &format!("glue::{}", find_zig_glue_path().to_str().unwrap()),
+        &zig_build_options_module(),
+        "--mod",
+        &format!(
+            "glue:build_options:{}",
+            find_zig_glue_path().to_str().unwrap()
+        ),
         "--deps",
         "glue",
         // include libc
@@ -244,7 +258,12 @@ pub fn build_zig_host_native(
         zig_host_src,
         &format!("-femit-bin={}", emit_bin),
         "--mod",
-        &format!("glue::{}", find_zig_glue_path().to_str().unwrap()),
+        &zig_build_options_module(),
+        "--mod",
+        &format!(
+            "glue:build_options:{}",
+            find_zig_glue_path().to_str().unwrap()
+        ),
         "--deps",
         "glue",
         // include the zig runtime
@@ -291,7 +310,12 @@ pub fn build_zig_host_wasm32(
             zig_host_src,
             emit_bin,
             "--mod",
-            &format!("glue::{}", find_zig_glue_path().to_str().unwrap()),
+            &zig_build_options_module(),
+            "--mod",
+            &format!(
+                "glue:build_options:{}",
+                find_zig_glue_path().to_str().unwrap()
+            ),
             "--deps",
             "glue",
             // include the zig runtime
@@ -984,19 +1008,38 @@ fn link_linux(
 
     let ld_linux_path_str = &ld_linux_path.to_string_lossy();
 
-    let (base_args, output_path) = match link_type {
-        LinkType::Executable => (
+    let mut output_path = output_path;
+    if let LinkType::Dylib = link_type {
+        output_path.set_extension("so");
+    }
+
+    // A host that links against the library records its soname, so use the bare file name
+    // rather than wherever we happened to build it.
+    let soname = output_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+
+    let base_args = match link_type {
+        LinkType::Executable => vec![
+            "-pie",
             // Presumably this S stands for Static, since if we include Scrt1.o
             // in the linking for dynamic builds, linking fails.
-            [scrt1_path_str.as_ref()],
-            output_path,
-        ),
-        LinkType::Dylib => {
-            let mut output_path = output_path;
-            output_path.set_extension("so");
-
-            (["-shared"], output_path)
-        }
+            scrt1_path_str.as_ref(),
+            "-dynamic-linker",
+            ld_linux_path_str.as_ref(),
+        ],
+        // `-pie` and the interpreter only apply to executables; a library is loaded by its host.
+        // The version script keeps the builtins out of the library's exports, so that
+        // `--gc-sections` can drop the ones the app doesn't call. We write it to ld's stdin.
+        LinkType::Dylib => vec![
+            "-shared",
+            "-soname",
+            soname.as_str(),
+            "--version-script",
+            "/dev/stdin",
+        ],
         LinkType::None => internal_error!("link_linux should not be called with link type of none"),
     };
 
@@ -1022,12 +1065,10 @@ fn link_linux(
             "--eh-frame-hdr",
             "-A",
             arch_str(target),
-            "-pie",
             &crti_path_str,
             &crtn_path_str,
         ])
         .args(base_args)
-        .args(["-dynamic-linker", ld_linux_path_str])
         .args(input_paths)
         .args(extra_link_flags())
         // ld.lld requires this argument, and does not accept --arch
@@ -1049,11 +1090,23 @@ fn link_linux(
         ]);
     debug_print_command(&ld_command);
 
-    let ld_output = ld_command.spawn()?;
+    if let LinkType::Dylib = link_type {
+        ld_command.stdin(Stdio::piped());
+    }
+
+    let mut ld_output = ld_command.spawn()?;
+
+    if let Some(mut stdin) = ld_output.stdin.take() {
+        stdin.write_all(HIDE_BUILTINS_VERSION_SCRIPT.as_bytes())?;
+    }
 
     Ok((ld_output, output_path))
 }
 
+/// Makes the builtins local to a shared library. Every builtin is a global symbol in the
+/// builtins object, and a shared library exports all of its globals by default.
+const HIDE_BUILTINS_VERSION_SCRIPT: &str = "{ local: roc_builtins.*; };";
+
 fn link_macos(
     target: Target,
     output_path: PathBuf,
@@ -1061,13 +1114,39 @@ fn link_macos(
     link_type: LinkType,
 ) -> io::Result<(Child, PathBuf)> {
     let (link_type_args, output_path) = match link_type {
-        LinkType::Executable => (vec!["-execute"], output_path),
+        LinkType::Executable => (
+            vec!["-execute".to_string(), "-dead_strip".to_string()],
+            output_path,
+        ),
         LinkType::Dylib => {
             let mut output_path = output_path;
 
             output_path.set_extension("dylib");
 
-            (vec!["-dylib", "-undefined", "dynamic_lookup"], output_path)
+            // Like the soname on linux: hosts find the library on their rpath, not at the path
+            // it was built at.
+            let install_name = format!(
+                "@rpath/{}",
+                output_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            );
+
+            (
+                vec![
+                    "-dylib".to_string(),
+                    "-undefined".to_string(),
+                    "dynamic_lookup".to_string(),
+                    "-install_name".to_string(),
+                    install_name,
+                    // Exported symbols are never dead, so keep the builtins out of the exports.
+                    "-dead_strip".to_string(),
+                    "-unexported_symbol".to_string(),
+                    "_roc_builtins.*".to_string(),
+                ],
+                output_path,
+            )
         }
         LinkType::None => internal_error!("link_macos should not be called with link type of none"),
     };
@@ -1086,11 +1165,6 @@ fn link_macos(
         .env_clear()
         .args(&link_type_args)
         .args([
-            // NOTE: we don't do --gc-sections on macOS because the default
-            // macOS linker doesn't support it, but it's a performance
-            // optimization, so if we ever switch to a different linker,
-            // we'd like to re-enable it on macOS!
-            // "--gc-sections",
             "-arch",
             &arch,
             // Suppress warnings, because otherwise it prints:
@@ -1229,7 +1303,12 @@ fn link_wasm32(
             "-target",
             "wasm32-wasi-musl",
             "--mod",
-            &format!("glue::{}", find_zig_glue_path().to_str().unwrap()),
+            &zig_build_options_module(),
+            "--mod",
+            &format!(
+                "glue:build_options:{}",
+                find_zig_glue_path().to_str().unwrap()
+            ),
             "--deps",
             "glue",
             "-fstrip",
@@ -1251,6 +1330,9 @@ fn link_windows(
 ) -> io::Result<(Child, PathBuf)> {
     match link_type {
         LinkType::Dylib => {
+            let mut output_path = output_path;
+            output_path.set_extension("dll");
+
             let child = zig()
                 .args(["build-lib"])
                 .args(input_paths)
@@ -1260,7 +1342,12 @@ fn link_windows(
                     "-target",
                     "native",
                     "--mod",
-                    &format!("glue::{}", find_zig_glue_path().to_str().unwrap()),
+                    &zig_build_options_module(),
+                    "--mod",
+                    &format!(
+                        "glue:build_options:{}",
+                        find_zig_glue_path().to_str().unwrap()
+                    ),
                     "--deps",
                     "glue",
                     "-O",
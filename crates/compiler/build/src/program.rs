@@ -3,21 +3,26 @@ use crate::link::{
 };
 use bumpalo::Bump;
 use inkwell::memory_buffer::MemoryBuffer;
+use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
-use roc_gen_dev::AssemblyBackendMode;
+use roc_gen_dev::{AssemblyBackendMode, Hardening};
 use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
 use roc_load::{
-    EntryPoint, ExecutionMode, ExpectMetadata, FunctionKind, LoadConfig, LoadMonomorphizedError,
-    LoadedModule, LoadingProblem, MonomorphizedModule, Threading,
+    DepsFormat, EntryPoint, ExecutionMode, ExpectMetadata, FunctionKind, Incremental, LoadConfig,
+    LoadMonomorphizedError, LoadedModule, LoadingProblem, ModuleGraph, MonomorphizedModule,
+    Threading,
 };
+use roc_module::symbol::ModuleId;
 use roc_mono::ir::{OptLevel, SingleEntryPoint};
 use roc_packaging::cache::RocCacheDir;
+use roc_problem::backend::{catch_unimplemented, BackendFailure, DevBackend, Unimplemented};
 use roc_reporting::{
-    cli::{report_problems, Problems},
-    report::{RenderTarget, DEFAULT_PALETTE},
+    cli::{report_problems, OutputFormat, Problems},
+    json::problems_json,
+    report::{to_unimplemented_report_string, RenderTarget, DEFAULT_PALETTE},
 };
-use roc_target::{Architecture, Target};
+use roc_target::{Architecture, OperatingSystem, Target};
 use std::ffi::OsStr;
 use std::ops::Deref;
 use std::{
@@ -84,7 +89,26 @@ pub struct CodeGenOptions {
     pub opt_level: OptLevel,
     pub emit_debug_info: bool,
     pub emit_llvm_ir: bool,
+    /// Print the reset/reuse decisions of the mono IR to stdout
+    pub emit_reuse_report: bool,
+    /// Print the top-level defs of the app that are never used
+    pub warn_unused: bool,
     pub fuzz: bool,
+    /// Update refcounts with atomic instructions in the dev backend, for platforms that share
+    /// Roc values between threads
+    pub atomic_refcounts: bool,
+    /// Landing pads and retpolines for hosts that require hardened machine code, in the dev backend
+    pub hardening: Hardening,
+    /// Replace every NaN produced by float arithmetic with the same canonical NaN, in the Wasm
+    /// dev backend
+    pub canonicalize_nans: bool,
+    /// Call Roc procedures through the function table, so that a host can hot-reload them, in
+    /// the Wasm dev backend
+    pub patchable_calls: bool,
+    /// Where Roc code gets heap memory from, in the Wasm dev backend
+    pub wasm_allocator: roc_gen_wasm::Allocator,
+    /// Tell the host which source location made each heap allocation, in the Wasm dev backend
+    pub heap_profiling: bool,
 }
 
 type GenFromMono<'a> = (CodeObject, CodeGenTiming, ExpectMetadata<'a>);
@@ -92,36 +116,64 @@ type GenFromMono<'a> = (CodeObject, CodeGenTiming, ExpectMetadata<'a>);
 #[allow(clippy::too_many_arguments)]
 pub fn gen_from_mono_module<'a>(
     arena: &'a bumpalo::Bump,
-    loaded: MonomorphizedModule<'a>,
+    mut loaded: MonomorphizedModule<'a>,
     roc_file_path: &Path,
     target: Target,
     code_gen_options: CodeGenOptions,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
-) -> GenFromMono<'a> {
+) -> Result<GenFromMono<'a>, BackendFailure> {
     let path = roc_file_path;
     let debug = code_gen_options.emit_debug_info;
     let emit_llvm_ir = code_gen_options.emit_llvm_ir;
     let fuzz = code_gen_options.fuzz;
     let opt = code_gen_options.opt_level;
 
-    match code_gen_options.backend {
+    if code_gen_options.emit_reuse_report {
+        print!(
+            "{}",
+            loaded.reuse_report.render(
+                &loaded.interns,
+                loaded.module_id,
+                &loaded.source_locations()
+            )
+        );
+    }
+
+    if matches!(opt, OptLevel::Optimize | OptLevel::Size) {
+        roc_mono::const_fold::fold_constants(
+            arena,
+            &loaded.layout_interner,
+            &mut loaded.procedures,
+        );
+    }
+
+    // Constant folding can leave specializations that nothing calls anymore
+    loaded.remove_unreachable_procs();
+
+    if code_gen_options.warn_unused {
+        print!("{}", loaded.render_unused_defs());
+    }
+
+    let gen_from_mono = match code_gen_options.backend {
         CodeGenBackend::Wasm => gen_from_mono_module_dev(
             arena,
             loaded,
             target,
+            code_gen_options,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
             AssemblyBackendMode::Binary, // dummy value, unused in practice
-        ),
+        )?,
         CodeGenBackend::Assembly(backend_mode) => gen_from_mono_module_dev(
             arena,
             loaded,
             target,
+            code_gen_options,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
             backend_mode,
-        ),
+        )?,
         CodeGenBackend::Llvm(backend_mode) => gen_from_mono_module_llvm(
             arena,
             loaded,
@@ -133,7 +185,14 @@ pub fn gen_from_mono_module<'a>(
             emit_llvm_ir,
             fuzz,
         ),
-    }
+    };
+
+    roc_debug_flags::dbg_do!(roc_debug_flags::ROC_PRINT_LAYOUT_INTERNER_STATS, {
+        let (_, _, expect_metadata) = &gen_from_mono;
+        eprintln!("{:#?}", expect_metadata.layout_interner.stats());
+    });
+
+    Ok(gen_from_mono)
 }
 
 // TODO how should imported modules factor into this? What if those use builtins too?
@@ -156,6 +215,7 @@ fn gen_from_mono_module_llvm<'a>(
     use inkwell::context::Context;
     use inkwell::module::Linkage;
     use inkwell::targets::{FileType, RelocMode};
+    use inkwell::DLLStorageClass;
 
     let all_code_gen_start = Instant::now();
 
@@ -216,6 +276,7 @@ fn gen_from_mono_module_llvm<'a>(
             .keys()
             .copied()
             .collect(),
+        coverage: None,
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
@@ -262,6 +323,19 @@ fn gen_from_mono_module_llvm<'a>(
 
     mpm.run_on(module);
 
+    // A .dll only exports the symbols marked dllexport, so mark the entry points the host calls.
+    if target.operating_system() == OperatingSystem::Windows {
+        for function in module.get_functions() {
+            let is_entry_point = function.get_name().to_bytes().starts_with(b"roc__");
+
+            if is_entry_point && function.count_basic_blocks() > 0 {
+                function
+                    .as_global_value()
+                    .set_dll_storage_class(DLLStorageClass::Export);
+            }
+        }
+    }
+
     // Verify the module
     if let Err(errors) = env.module.verify() {
         // write the ll code to a file, so we can modify it
@@ -429,19 +503,21 @@ fn gen_from_mono_module_dev<'a>(
     arena: &'a bumpalo::Bump,
     loaded: MonomorphizedModule<'a>,
     target: Target,
+    code_gen_options: CodeGenOptions,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
     backend_mode: AssemblyBackendMode,
-) -> GenFromMono<'a> {
+) -> Result<GenFromMono<'a>, BackendFailure> {
     match target.architecture() {
-        Architecture::Wasm32 => gen_from_mono_module_dev_wasm32(
+        Architecture::Wasm32 => Ok(gen_from_mono_module_dev_wasm32(
             arena,
             loaded,
+            code_gen_options,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
-        ),
+        )),
         Architecture::X86_64 | Architecture::Aarch64 => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode, code_gen_options)
         }
         _ => todo!(),
     }
@@ -452,13 +528,14 @@ pub fn gen_from_mono_module_dev<'a>(
     arena: &'a bumpalo::Bump,
     loaded: MonomorphizedModule<'a>,
     target: Target,
+    code_gen_options: CodeGenOptions,
     _host_input_path: &Path,
     _wasm_dev_stack_bytes: Option<u32>,
     backend_mode: AssemblyBackendMode,
-) -> GenFromMono<'a> {
+) -> Result<GenFromMono<'a>, BackendFailure> {
     match target.architecture() {
         Architecture::X86_64 | Architecture::Aarch64 => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode, code_gen_options)
         }
         _ => todo!(),
     }
@@ -468,10 +545,14 @@ pub fn gen_from_mono_module_dev<'a>(
 fn gen_from_mono_module_dev_wasm32<'a>(
     arena: &'a bumpalo::Bump,
     loaded: MonomorphizedModule<'a>,
+    code_gen_options: CodeGenOptions,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
 ) -> GenFromMono<'a> {
     let all_code_gen_start = Instant::now();
+    let source_locations = code_gen_options
+        .heap_profiling
+        .then(|| &*arena.alloc(loaded.source_locations()));
     let MonomorphizedModule {
         module_id,
         procedures,
@@ -492,6 +573,17 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         module_id,
         exposed_to_host,
         stack_bytes: wasm_dev_stack_bytes.unwrap_or(roc_gen_wasm::Env::DEFAULT_STACK_BYTES),
+        canonicalize_nans: code_gen_options.canonicalize_nans,
+        heap_profiling: source_locations,
+        max_function_stmts: roc_gen_wasm::Env::DEFAULT_MAX_FUNCTION_STMTS,
+        max_inline_stmts: match code_gen_options.opt_level {
+            OptLevel::Development | OptLevel::Normal | OptLevel::Size => 0,
+            OptLevel::Optimize => roc_gen_wasm::Env::DEFAULT_MAX_INLINE_STMTS,
+        },
+        multi_value_returns: true,
+        patchable_calls: code_gen_options.patchable_calls,
+        allocator: code_gen_options.wasm_allocator,
+        codegen_threads: roc_gen_wasm::Env::available_codegen_threads(),
     };
 
     let host_bytes = std::fs::read(preprocessed_host_path).unwrap_or_else(|_| {
@@ -510,13 +602,16 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         )
     });
 
-    let final_binary_bytes = roc_gen_wasm::build_app_binary(
-        &env,
-        &mut layout_interner,
-        &mut interns,
-        host_module,
-        procedures,
-    );
+    let final_binary_bytes = catch_unimplemented(DevBackend::Wasm, || {
+        roc_gen_wasm::build_app_binary(
+            &env,
+            &mut layout_interner,
+            &mut interns,
+            host_module,
+            procedures,
+        )
+    })
+    .unwrap_or_else(|problem| report_unimplemented(&loaded.sources, module_id, problem));
 
     let generate_final_ir = all_code_gen_start.elapsed();
     let code_gen_object_start = Instant::now();
@@ -543,7 +638,8 @@ fn gen_from_mono_module_dev_assembly<'a>(
     loaded: MonomorphizedModule<'a>,
     target: Target,
     backend_mode: AssemblyBackendMode,
-) -> GenFromMono<'a> {
+    code_gen_options: CodeGenOptions,
+) -> Result<GenFromMono<'a>, BackendFailure> {
     let all_code_gen_start = Instant::now();
 
     let lazy_literals = true;
@@ -563,10 +659,20 @@ fn gen_from_mono_module_dev_assembly<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: backend_mode,
+        atomic_refcounts: code_gen_options.atomic_refcounts,
+        hardening: code_gen_options.hardening,
+        stack_maps: false,
     };
 
-    let module_object =
-        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures);
+    let module_object = catch_unimplemented(DevBackend::Assembly, || {
+        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures)
+    })
+    .unwrap_or_else(|problem| report_unimplemented(&loaded.sources, module_id, problem));
+
+    let module_object = module_object.map_err(|error| BackendFailure {
+        backend: DevBackend::Assembly,
+        reason: error.to_string(),
+    })?;
 
     let generate_final_ir = all_code_gen_start.elapsed();
     let code_gen_object_start = Instant::now();
@@ -578,7 +684,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
     let code_gen_object = code_gen_object_start.elapsed();
     let total = all_code_gen_start.elapsed();
 
-    (
+    Ok((
         CodeObject::Vector(module_out),
         CodeGenTiming {
             generate_final_ir,
@@ -590,7 +696,22 @@ fn gen_from_mono_module_dev_assembly<'a>(
             layout_interner,
             expectations: loaded.expectations,
         },
-    )
+    ))
+}
+
+/// Tells the user about something the dev backend they picked can't compile yet, and exits.
+fn report_unimplemented(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    module_id: ModuleId,
+    problem: Unimplemented,
+) -> ! {
+    let filename = match sources.get(&module_id) {
+        Some((path, _)) => path.clone(),
+        None => PathBuf::new(),
+    };
+
+    eprintln!("{}", to_unimplemented_report_string(filename, problem));
+    std::process::exit(1);
 }
 
 fn report_timing(buf: &mut String, label: &str, duration: Duration) {
@@ -627,6 +748,11 @@ pub enum BuildFileError<'a> {
         module: LoadedModule,
         total_time: Duration,
     },
+    /// A dev backend couldn't generate code for the app in this file.
+    BackendFailure {
+        filename: PathBuf,
+        failure: BackendFailure,
+    },
 }
 
 impl<'a> BuildFileError<'a> {
@@ -719,18 +845,31 @@ pub fn build_file<'a>(
     roc_cache_dir: RocCacheDir<'_>,
     load_config: LoadConfig,
     out_path: Option<&Path>,
+    incremental: Option<Incremental<'a>>,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let compilation_start = Instant::now();
 
     // Step 1: compile the app and generate the .o file
-    let loaded = roc_load::load_and_monomorphize(
-        arena,
-        app_module_path.clone(),
-        None,
-        roc_cache_dir,
-        load_config,
-    )
+    let load_span = roc_tracing::profile::span("load");
+    let loaded = match incremental {
+        None => roc_load::load_and_monomorphize(
+            arena,
+            app_module_path.clone(),
+            None,
+            roc_cache_dir,
+            load_config,
+        ),
+        Some(incremental) => roc_load::load_and_monomorphize_incremental(
+            arena,
+            app_module_path.clone(),
+            None,
+            roc_cache_dir,
+            load_config,
+            incremental,
+        ),
+    }
     .map_err(|e| BuildFileError::from_mono_error(e, compilation_start))?;
+    drop(load_span);
 
     build_loaded_file(
         arena,
@@ -921,6 +1060,7 @@ fn build_loaded_file<'a>(
         None
     };
 
+    let code_gen_span = roc_tracing::profile::span("code gen");
     let (roc_app_bytes, code_gen_timing, expect_metadata) = gen_from_mono_module(
         arena,
         loaded,
@@ -929,7 +1069,12 @@ fn build_loaded_file<'a>(
         code_gen_options,
         &preprocessed_host_path,
         wasm_dev_stack_bytes,
-    );
+    )
+    .map_err(|failure| BuildFileError::BackendFailure {
+        filename: app_module_path.clone(),
+        failure,
+    })?;
+    drop(code_gen_span);
 
     buf.push('\n');
     buf.push_str("    ");
@@ -970,6 +1115,7 @@ fn build_loaded_file<'a>(
 
     // Step 2: link the prebuilt platform and compiled app
     let link_start = Instant::now();
+    let link_span = roc_tracing::profile::span("link");
 
     match (linking_strategy, link_type) {
         (LinkingStrategy::Surgical, _) => {
@@ -1037,6 +1183,7 @@ fn build_loaded_file<'a>(
     }
 
     let linking_time = link_start.elapsed();
+    drop(link_span);
 
     if emit_timings {
         println!("Finished linking in {} ms\n", linking_time.as_millis());
@@ -1101,6 +1248,7 @@ fn spawn_rebuild_thread(
         eprintln!("🔨 Rebuilding platform...");
 
         let rebuild_host_start = Instant::now();
+        let _rebuild_span = roc_tracing::profile::span("rebuild platform");
 
         match linking_strategy {
             LinkingStrategy::Additive => {
@@ -1177,34 +1325,51 @@ pub fn check_file<'a>(
     arena: &'a Bump,
     roc_file_path: PathBuf,
     opt_main_path: Option<PathBuf>,
+    target: Target,
     emit_timings: bool,
     roc_cache_dir: RocCacheDir<'_>,
     threading: Threading,
+    output_format: OutputFormat,
+    emit_deps: Option<DepsFormat>,
+    incremental: Option<Incremental<'a>>,
 ) -> Result<(Problems, Duration), LoadingProblem<'a>> {
     let compilation_start = Instant::now();
 
-    // only used for generating errors. We don't do code generation, so hardcoding should be fine
-    // we need monomorphization for when exhaustiveness checking
-    let target = Target::LinuxX64;
+    // We don't do code generation, but layouts (and so some of the problems we report)
+    // depend on the target, e.g. its pointer width.
 
     // Step 1: compile the app and generate the .o file
 
     let load_config = LoadConfig {
         target,
         function_kind: FunctionKind::from_env(),
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
+        render: match output_format {
+            OutputFormat::Terminal => RenderTarget::ColorTerminal,
+            OutputFormat::Json => RenderTarget::Generic,
+        },
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Check,
     };
-    let mut loaded = roc_load::load_and_typecheck(
-        arena,
-        roc_file_path,
-        opt_main_path,
-        roc_cache_dir,
-        load_config,
-    )?;
+    let load_span = roc_tracing::profile::span("load");
+    let mut loaded = match incremental {
+        None => roc_load::load_and_typecheck(
+            arena,
+            roc_file_path,
+            opt_main_path,
+            roc_cache_dir,
+            load_config,
+        ),
+        Some(incremental) => roc_load::load_and_typecheck_incremental(
+            arena,
+            roc_file_path,
+            opt_main_path,
+            roc_cache_dir,
+            load_config,
+            incremental,
+        ),
+    }?;
+    drop(load_span);
 
     let buf = &mut String::with_capacity(1024);
 
@@ -1248,7 +1413,27 @@ pub fn check_file<'a>(
         println!("Finished checking in {} ms\n", compilation_end.as_millis(),);
     }
 
-    Ok((report_problems_typechecked(&mut loaded), compilation_end))
+    if let Some(format) = emit_deps {
+        print!("{}", ModuleGraph::from_loaded(&loaded).render(format));
+    }
+
+    let problems = match output_format {
+        OutputFormat::Terminal => report_problems_typechecked(&mut loaded),
+        OutputFormat::Json => {
+            let (json, problems) = problems_json(
+                &loaded.sources,
+                &loaded.interns,
+                &mut loaded.can_problems,
+                &mut loaded.type_problems,
+            );
+
+            println!("{json}");
+
+            problems
+        }
+    };
+
+    Ok((problems, compilation_end))
 }
 
 pub fn build_str_test<'a>(
@@ -1264,7 +1449,15 @@ pub fn build_str_test<'a>(
         opt_level: OptLevel::Normal,
         emit_debug_info: false,
         emit_llvm_ir: false,
+        emit_reuse_report: false,
+        warn_unused: false,
         fuzz: false,
+        atomic_refcounts: false,
+        hardening: Hardening::default(),
+        canonicalize_nans: false,
+        patchable_calls: false,
+        wasm_allocator: roc_gen_wasm::Allocator::Host,
+        heap_profiling: false,
     };
 
     let emit_timings = false;
@@ -1319,6 +1512,7 @@ fn with_output_extension(
             // Additive linking and no linking both output the object file type.
             path.with_extension(target.object_file_ext())
         }
+        (_, LinkType::Dylib) => path.with_extension(target.dynamic_library_file_ext()),
         _ => path.with_extension(target.executable_file_ext().unwrap_or_default()),
     }
 }
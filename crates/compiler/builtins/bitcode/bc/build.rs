@@ -12,8 +12,12 @@ use tempfile::tempdir;
 /// To debug the zig code with debug prints, we need to disable the wasm code gen
 const DEBUG: bool = false;
 
+/// Set to build the builtins with heap canaries, see `DEBUG_HEAP_CANARIES` in utils.zig
+const HEAP_CANARIES_VAR: &str = "ROC_HEAP_CANARIES";
+
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed={HEAP_CANARIES_VAR}");
 
     // "." is relative to where "build.rs" is
     // dunce can be removed once ziglang/zig#5109 is fixed
@@ -79,6 +83,10 @@ fn generate_bc_file(bitcode_path: &Path, zig_object: &str, file_name: &str) {
         .current_dir(bitcode_path)
         .args(["build", zig_object, "-Drelease=true"]);
 
+    if env::var_os(HEAP_CANARIES_VAR).is_some() {
+        zig_cmd.arg("-Dheap-canaries=true");
+    }
+
     run_command(zig_cmd, 0);
 }
 
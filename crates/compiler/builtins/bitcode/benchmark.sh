@@ -3,5 +3,5 @@
 # https://vaneyckt.io/posts/safer_bash_scripts_with_set_euxo_pipefail/
 set -euxo pipefail
 
-zig build-exe benchmark/dec.zig -O ReleaseFast --main-pkg-path .
+zig build-exe -O ReleaseFast --main-pkg-path . --mod build_options::src/build_options.zig --deps build_options benchmark/dec.zig
 ./dec
@@ -15,11 +15,23 @@ pub fn build(b: *Build) void {
     const main_path_desc = b.fmt("Override path to main.zig. Used by \"ir\" and \"test\". Defaults to \"{s}\". ", .{fallback_main_path});
     const main_path = .{ .path = b.option([]const u8, "main-path", main_path_desc) orelse fallback_main_path };
 
+    const heap_canaries = b.option(bool, "heap-canaries", "Put a canary word in front of the refcount of every heap allocation, and check it on every decrement and free. Hosts that free Roc values themselves don't support this.") orelse false;
+    const build_options = b.addOptions();
+    build_options.addOption(bool, "heap_canaries", heap_canaries);
+
     // Tests
     const main_tests = b.addTest(.{ .root_source_file = main_path, .link_libc = true });
+    main_tests.addOptions("build_options", build_options);
     const test_step = b.step("test", "Run tests");
     test_step.dependOn(&b.addRunArtifact(main_tests).step);
 
+    // The tests again, with the canaries on
+    const canary_options = b.addOptions();
+    canary_options.addOption(bool, "heap_canaries", true);
+    const canary_tests = b.addTest(.{ .root_source_file = main_path, .link_libc = true });
+    canary_tests.addOptions("build_options", canary_options);
+    test_step.dependOn(&b.addRunArtifact(canary_tests).step);
+
     // Targets
     const host_target = b.standardTargetOptions(.{
         .default_target = CrossTarget{
@@ -34,29 +46,31 @@ pub fn build(b: *Build) void {
     const wasm32_target = makeWasm32Target();
 
     // LLVM IR
-    generateLlvmIrFile(b, mode, host_target, main_path, "ir", "builtins-host");
-    generateLlvmIrFile(b, mode, linux32_target, main_path, "ir-x86", "builtins-x86");
-    generateLlvmIrFile(b, mode, linux_x64_target, main_path, "ir-x86_64", "builtins-x86_64");
-    generateLlvmIrFile(b, mode, linux_aarch64_target, main_path, "ir-aarch64", "builtins-aarch64");
-    generateLlvmIrFile(b, mode, windows64_target, main_path, "ir-windows-x86_64", "builtins-windows-x86_64");
-    generateLlvmIrFile(b, mode, wasm32_target, main_path, "ir-wasm32", "builtins-wasm32");
+    generateLlvmIrFile(b, mode, build_options, host_target, main_path, "ir", "builtins-host");
+    generateLlvmIrFile(b, mode, build_options, linux32_target, main_path, "ir-x86", "builtins-x86");
+    generateLlvmIrFile(b, mode, build_options, linux_x64_target, main_path, "ir-x86_64", "builtins-x86_64");
+    generateLlvmIrFile(b, mode, build_options, linux_aarch64_target, main_path, "ir-aarch64", "builtins-aarch64");
+    generateLlvmIrFile(b, mode, build_options, windows64_target, main_path, "ir-windows-x86_64", "builtins-windows-x86_64");
+    generateLlvmIrFile(b, mode, build_options, wasm32_target, main_path, "ir-wasm32", "builtins-wasm32");
 
     // Generate Object Files
-    generateObjectFile(b, mode, host_target, main_path, "object", "builtins-host");
-    generateObjectFile(b, mode, windows64_target, main_path, "windows-x86_64-object", "builtins-windows-x86_64");
-    generateObjectFile(b, mode, wasm32_target, main_path, "wasm32-object", "builtins-wasm32");
+    generateObjectFile(b, mode, build_options, host_target, main_path, "object", "builtins-host");
+    generateObjectFile(b, mode, build_options, windows64_target, main_path, "windows-x86_64-object", "builtins-windows-x86_64");
+    generateObjectFile(b, mode, build_options, wasm32_target, main_path, "wasm32-object", "builtins-wasm32");
 }
 
 // TODO zig 0.9 can generate .bc directly, switch to that when it is released!
 fn generateLlvmIrFile(
     b: *Build,
     mode: std.builtin.Mode,
+    build_options: *Build.Step.Options,
     target: CrossTarget,
     main_path: LazyPath,
     step_name: []const u8,
     object_name: []const u8,
 ) void {
     const obj = b.addObject(.{ .name = object_name, .root_source_file = main_path, .optimize = mode, .target = target, .use_llvm = true });
+    obj.addOptions("build_options", build_options);
     obj.strip = true;
     obj.disable_stack_probing = true;
     if (target.cpu_arch != .wasm32)
@@ -83,12 +97,14 @@ fn generateLlvmIrFile(
 fn generateObjectFile(
     b: *Build,
     mode: std.builtin.Mode,
+    build_options: *Build.Step.Options,
     target: CrossTarget,
     main_path: LazyPath,
     step_name: []const u8,
     object_name: []const u8,
 ) void {
     const obj = b.addObject(.{ .name = object_name, .root_source_file = main_path, .optimize = mode, .target = target, .use_llvm = true });
+    obj.addOptions("build_options", build_options);
     obj.strip = true;
     obj.link_function_sections = true;
     obj.force_pic = true;
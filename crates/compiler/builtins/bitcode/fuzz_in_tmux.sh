@@ -6,7 +6,7 @@ SCRIPT_RELATIVE_DIR=`dirname "${BASH_SOURCE[0]}"`
 cd $SCRIPT_RELATIVE_DIR
 
 # First compile the fuzz target.
-zig build-lib -static -fcompiler-rt -flto -fPIC src/fuzz_sort.zig
+zig build-lib -static -fcompiler-rt -flto -fPIC --mod build_options::src/build_options.zig --deps build_options src/fuzz_sort.zig
 afl-clang-lto -o fuzz libfuzz_sort.a
 AFL_LLVM_CMPLOG=1 afl-clang-lto -o fuzz-cmplog libfuzz_sort.a
 AFL_LLVM_LAF_ALL=1 afl-clang-lto -o fuzz-cmpcov libfuzz_sort.a
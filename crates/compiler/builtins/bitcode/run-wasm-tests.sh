@@ -5,4 +5,11 @@ set -euxo pipefail
 
 # For non-native binaries, Zig test needs a "test command" it can use
 cargo build --locked --release -p roc_wasm_interp
-zig test -target wasm32-wasi-musl -O ReleaseFast src/main.zig --test-cmd ../../../../target/release/roc_wasm_interp --test-cmd-bin
+
+# build.zig normally provides the build options. Run the tests with the defaults, and with the heap canaries on.
+zig test -target wasm32-wasi-musl -O ReleaseFast --mod build_options::src/build_options.zig --deps build_options src/main.zig --test-cmd ../../../../target/release/roc_wasm_interp --test-cmd-bin
+
+canary_options_dir=$(mktemp -d)
+trap 'rm -rf "$canary_options_dir"' EXIT
+echo "pub const heap_canaries = true;" > "$canary_options_dir/build_options.zig"
+zig test -target wasm32-wasi-musl -O ReleaseFast --mod "build_options::$canary_options_dir/build_options.zig" --deps build_options src/main.zig --test-cmd ../../../../target/release/roc_wasm_interp --test-cmd-bin
@@ -0,0 +1,3 @@
+// The defaults of the options in build.zig, for Zig hosts that use the builtins through glue.zig
+// without going through build.zig. See `DEBUG_HEAP_CANARIES` in utils.zig.
+pub const heap_canaries = false;
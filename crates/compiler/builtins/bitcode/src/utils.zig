@@ -6,6 +6,17 @@ const DEBUG_INCDEC = false;
 const DEBUG_TESTING_ALLOC = false;
 const DEBUG_ALLOC = false;
 
+// Puts a canary word in front of the refcount of every heap allocation, and checks it on every
+// decrement and free. When it was overwritten, or the allocation was already freed, we roc_panic
+// instead of corrupting the heap any further. This works with every backend, since they all
+// allocate and free through these builtins. Hosts that free Roc values themselves (e.g. with
+// roc_std) don't know about the canary though, so they need to be built with it turned off.
+// Turn it on with `zig build -Dheap-canaries=true`, or by setting ROC_HEAP_CANARIES when building the compiler.
+const DEBUG_HEAP_CANARIES = @import("build_options").heap_canaries;
+
+const HEAP_CANARY: usize = if (@sizeOf(usize) == 8) 0x5AFE_CA4A_5AFE_CA4A else 0x5AFE_CA4A;
+const FREED_CANARY: usize = if (@sizeOf(usize) == 8) 0xDEAD_CA4A_DEAD_CA4A else 0xDEAD_CA4A;
+
 pub fn WithOverflow(comptime T: type) type {
     return extern struct { value: T, has_overflowed: bool };
 }
@@ -125,6 +136,8 @@ fn testing_roc_panic(c_ptr: *anyopaque, tag_id: u32) callconv(.C) void {
     @panic("Roc panicked");
 }
 
+const panic_help = @import("panic.zig").panic_help;
+
 pub fn alloc(size: usize, alignment: u32) ?[*]u8 {
     return @as(?[*]u8, @ptrCast(roc_alloc(size, alignment)));
 }
@@ -322,9 +335,13 @@ inline fn free_ptr_to_refcount(
     elements_refcounted: bool,
 ) void {
     if (RC_TYPE == Refcount.none) return;
-    const ptr_width = @sizeOf(usize);
-    const required_space: usize = if (elements_refcounted) (2 * ptr_width) else ptr_width;
-    const extra_bytes = @max(required_space, alignment);
+
+    if (DEBUG_HEAP_CANARIES) {
+        checkCanary(refcount_ptr, elements_refcounted);
+        canaryPtr(refcount_ptr, elements_refcounted).* = FREED_CANARY;
+    }
+
+    const extra_bytes = headerBytes(alignment, elements_refcounted);
     const allocation_ptr = @as([*]u8, @ptrCast(refcount_ptr)) - (extra_bytes - @sizeOf(usize));
 
     // NOTE: we don't even check whether the refcount is "infinity" here!
@@ -353,6 +370,10 @@ inline fn decref_ptr_to_refcount(
     // Ensure that the refcount is not whole program lifetime.
     const refcount: isize = refcount_ptr[0];
     if (refcount != REFCOUNT_MAX_ISIZE) {
+        if (DEBUG_HEAP_CANARIES) {
+            checkCanary(refcount_ptr, elements_refcounted);
+        }
+
         switch (RC_TYPE) {
             Refcount.normal => {
                 const old = @as(usize, @bitCast(refcount));
@@ -401,6 +422,32 @@ pub fn isUnique(
     return refcount == REFCOUNT_ONE_ISIZE;
 }
 
+// The bytes in front of the data of an allocation: the refcount, the element count if the
+// elements are refcounted, and the canary if enabled, padded to the alignment of the data.
+fn headerBytes(alignment: u32, elements_refcounted: bool) usize {
+    const header_words = 1 + @as(usize, @intFromBool(elements_refcounted)) + @as(usize, @intFromBool(DEBUG_HEAP_CANARIES));
+    return std.mem.alignForward(usize, header_words * @sizeOf(usize), @max(alignment, 1));
+}
+
+// The canary is the first word in front of the refcount that isn't the element count.
+fn canaryPtr(refcount_ptr: [*]isize, elements_refcounted: bool) *usize {
+    const offset: usize = if (elements_refcounted) 2 else 1;
+    return @as(*usize, @ptrCast(refcount_ptr - offset));
+}
+
+fn checkCanary(refcount_ptr: [*]isize, elements_refcounted: bool) void {
+    const canary = canaryPtr(refcount_ptr, elements_refcounted).*;
+    if (canary == HEAP_CANARY) return;
+
+    var buf: [128]u8 = undefined;
+    const msg = if (canary == FREED_CANARY)
+        std.fmt.bufPrint(&buf, "Roc heap: the allocation with its refcount at {*} was already freed", .{refcount_ptr})
+    else
+        std.fmt.bufPrint(&buf, "Roc heap: the canary in front of the refcount at {*} was overwritten", .{refcount_ptr});
+
+    panic_help(msg catch unreachable, 0);
+}
+
 // We follow roughly the [fbvector](https://github.com/facebook/folly/blob/main/folly/docs/FBVector.md) when it comes to growing a RocList.
 // Here is [their growth strategy](https://github.com/facebook/folly/blob/3e0525988fd444201b19b76b390a5927c15cb697/folly/FBVector.h#L1128) for push_back:
 //
@@ -466,8 +513,7 @@ pub fn allocateWithRefcount(
     // This is used so that a seamless slice can de-allocate the underlying list type.
     const ptr_width = @sizeOf(usize);
     const alignment = @max(ptr_width, element_alignment);
-    const required_space: usize = if (elements_refcounted) (2 * ptr_width) else ptr_width;
-    const extra_bytes = @max(required_space, element_alignment);
+    const extra_bytes = headerBytes(element_alignment, elements_refcounted);
     const length = extra_bytes + data_bytes;
 
     var new_bytes: [*]u8 = alloc(length, alignment) orelse unreachable;
@@ -480,6 +526,10 @@ pub fn allocateWithRefcount(
     const refcount_ptr = @as([*]usize, @ptrCast(@as([*]align(ptr_width) u8, @alignCast(data_ptr)) - ptr_width));
     refcount_ptr[0] = if (RC_TYPE == Refcount.none) REFCOUNT_MAX_ISIZE else REFCOUNT_ONE;
 
+    if (DEBUG_HEAP_CANARIES) {
+        canaryPtr(@as([*]isize, @ptrCast(refcount_ptr)), elements_refcounted).* = HEAP_CANARY;
+    }
+
     return data_ptr;
 }
 
@@ -496,9 +546,7 @@ pub fn unsafeReallocate(
     element_width: usize,
     elements_refcounted: bool,
 ) [*]u8 {
-    const ptr_width: usize = @sizeOf(usize);
-    const required_space: usize = if (elements_refcounted) (2 * ptr_width) else ptr_width;
-    const extra_bytes = @max(required_space, alignment);
+    const extra_bytes = headerBytes(alignment, elements_refcounted);
 
     const old_width = extra_bytes + old_length * element_width;
     const new_width = extra_bytes + new_length * element_width;
@@ -534,6 +582,38 @@ test "increfC, refcounted data" {
     try std.testing.expectEqual(mock_rc, REFCOUNT_ONE_ISIZE + 19);
 }
 
+test "allocateWithRefcount, decref frees the allocation" {
+    const data_bytes = 3 * @sizeOf(usize);
+    const data_ptr = allocateWithRefcount(data_bytes, @alignOf(usize), true);
+    const rc_ptr = @as([*]isize, @ptrCast(@as([*]align(@alignOf(isize)) u8, @alignCast(data_ptr)))) - 1;
+
+    try std.testing.expectEqual(rc_ptr[0], REFCOUNT_ONE_ISIZE);
+
+    // The testing allocator fails the test if free doesn't find the start of the allocation.
+    decref(data_ptr, data_bytes, @alignOf(usize), true);
+}
+
+test "headerBytes keeps the data aligned" {
+    const alignments = [_]u32{ 1, 2, 4, 8, 16, 32 };
+    for (alignments) |alignment| {
+        for ([_]bool{ false, true }) |elements_refcounted| {
+            const header_bytes = headerBytes(alignment, elements_refcounted);
+            const header_words = 1 + @as(usize, @intFromBool(elements_refcounted)) + @as(usize, @intFromBool(DEBUG_HEAP_CANARIES));
+
+            try std.testing.expect(header_bytes % alignment == 0);
+            try std.testing.expect(header_bytes >= header_words * @sizeOf(usize));
+        }
+    }
+}
+
+test "allocateWithRefcount, decref frees 16-aligned data" {
+    const data_bytes = 32;
+    const data_ptr = allocateWithRefcount(data_bytes, 16, true);
+
+    // The testing allocator fails the test if free doesn't find the start of the allocation.
+    decref(data_ptr, data_bytes, 16, true);
+}
+
 test "increfC, static data" {
     var mock_rc: isize = REFCOUNT_MAX_ISIZE;
     var ptr_to_refcount: *isize = &mock_rc;
@@ -1378,7 +1378,11 @@ hashBytesHelper48 = \seed, see1, see2, list, index, remaining ->
     else
         finalSeed = Num.bitwiseXor newSee2 (Num.bitwiseXor newSee1 newSeed)
 
-        { a: wyr8 list (Num.subWrap newRemaining 16 |> Num.addWrap newIndex), b: wyr8 list (Num.subWrap newRemaining 8 |> Num.addWrap newIndex), seed: finalSeed }
+        {
+            a: wyr8 list (Num.subWrap newRemaining 16 |> Num.addWrap newIndex),
+            b: wyr8 list (Num.subWrap newRemaining 8 |> Num.addWrap newIndex),
+            seed: finalSeed,
+        }
 
 hashBytesHelper16 : U64, List U8, U64, U64 -> { a : U64, b : U64, seed : U64 }
 hashBytesHelper16 = \seed, list, index, remaining ->
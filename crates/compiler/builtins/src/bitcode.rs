@@ -126,15 +126,10 @@ impl IntWidth {
                 Architecture::X86_32 => 4,
             },
             U128 | I128 => {
-                // the C ABI defines 128-bit integers to always be 16B aligned,
-                // according to https://reviews.llvm.org/D28990#655487
-                //
-                // however, rust does not always think that this is true
+                // the C ABI aligns 128-bit integers to 16B where it has them,
+                // however, rust does not always think that this is true.
                 // Our alignmets here are correct, but they will not match rust/zig/llvm until they update to llvm version 18.
-                match target.architecture() {
-                    Architecture::X86_64 | Architecture::Aarch64 | Architecture::X86_32 => 16,
-                    Architecture::Aarch32 | Architecture::Wasm32 => 8,
-                }
+                target.i128_alignment_bytes() as u32
             }
         }
     }
@@ -11,6 +11,7 @@ use crate::annotation::OwnedNamedOrAble;
 use crate::derive;
 use crate::env::Env;
 use crate::expr::get_lookup_symbols;
+use crate::expr::is_toplevel_property;
 use crate::expr::AnnotatedMark;
 use crate::expr::ClosureData;
 use crate::expr::Declarations;
@@ -1652,7 +1653,14 @@ pub(crate) fn sort_can_defs_new(
         // an `expect` does not have a user-defined name, but we'll need a name to call the expectation
         let name = scope.gen_unique_symbol();
 
-        declarations.push_expect(preceding_comment, name, Loc::at(region, condition));
+        match condition {
+            Closure(closure_data) if is_toplevel_property(&closure_data) => {
+                declarations.push_property(preceding_comment, name, Loc::at(region, closure_data));
+            }
+            condition => {
+                declarations.push_expect(preceding_comment, name, Loc::at(region, condition));
+            }
+        }
     }
 
     let it = expects_fx
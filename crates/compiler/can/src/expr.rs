@@ -2706,6 +2706,10 @@ pub struct Declarations {
 
     pub host_exposed_annotations: VecMap<usize, (Variable, crate::def::Annotation)>,
 
+    /// Functions made from property expects, like `expect \n -> n + 0 == n`, with the region
+    /// of the whole expect
+    pub properties: VecMap<usize, Region>,
+
     pub function_bodies: Vec<Loc<FunctionDef>>,
     pub expressions: Vec<Loc<Expr>>,
     pub destructs: Vec<DestructureDef>,
@@ -2728,6 +2732,7 @@ impl Declarations {
             symbols: Vec::with_capacity(capacity),
             annotations: Vec::with_capacity(capacity),
             host_exposed_annotations: VecMap::new(),
+            properties: VecMap::new(),
             function_bodies: Vec::with_capacity(capacity),
             expressions: Vec::with_capacity(capacity),
             specializes: VecMap::default(), // number of specializations is probably low
@@ -2860,6 +2865,33 @@ impl Declarations {
         index
     }
 
+    /// Pushes a property expect, whose condition is a lambda, as a function that runs the
+    /// lambda's body as an inline expect. The test runner calls it with generated arguments.
+    pub fn push_property(
+        &mut self,
+        preceding_comment: Region,
+        name: Symbol,
+        mut loc_closure_data: Loc<ClosureData>,
+    ) -> usize {
+        toplevel_property_to_inline_expect(&mut loc_closure_data.value);
+
+        let region = Region::span_across(&preceding_comment, &loc_closure_data.region);
+        let expr_var = loc_closure_data.value.function_type;
+
+        let index = self.push_function_def(
+            Loc::at(preceding_comment, name),
+            loc_closure_data,
+            expr_var,
+            None,
+            None,
+            None,
+        );
+
+        self.properties.insert(index, region);
+
+        index
+    }
+
     pub fn push_expect_fx(
         &mut self,
         preceding_comment: Region,
@@ -3295,21 +3327,55 @@ pub(crate) fn get_lookup_symbols(expr: &Expr) -> Vec<ExpectLookup> {
 /// all type errors and such are generated from the user source,
 /// but this transformation means that we don't need special codegen for toplevel expects
 pub fn toplevel_expect_to_inline_expect_pure(loc_expr: Loc<Expr>) -> Loc<Expr> {
-    toplevel_expect_to_inline_expect_help(loc_expr, false)
+    toplevel_expect_to_inline_expect_help(loc_expr, false, vec![])
 }
 
 pub fn toplevel_expect_to_inline_expect_fx(loc_expr: Loc<Expr>) -> Loc<Expr> {
-    toplevel_expect_to_inline_expect_help(loc_expr, true)
+    toplevel_expect_to_inline_expect_help(loc_expr, true, vec![])
+}
+
+/// Whether a lambda in the condition of a top-level expect makes it a property, like
+/// `expect \n -> n + 0 == n`. That takes arguments that are all identifiers.
+pub fn is_toplevel_property(closure_data: &ClosureData) -> bool {
+    !closure_data.arguments.is_empty()
+        && closure_data
+            .arguments
+            .iter()
+            .all(|(_, _, loc_pattern)| matches!(loc_pattern.value, Pattern::Identifier(_)))
+}
+
+/// Turns the body of a property's lambda into an inline expect, which reports the arguments
+/// along with the variables defined in the body.
+fn toplevel_property_to_inline_expect(closure_data: &mut ClosureData) {
+    let lookups_in_cond = closure_data
+        .arguments
+        .iter()
+        .filter_map(|(var, _, loc_pattern)| match loc_pattern.value {
+            Pattern::Identifier(symbol) => Some(ExpectLookup {
+                symbol,
+                var: *var,
+                ability_info: None,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    let loc_body = std::mem::replace(&mut *closure_data.loc_body, Loc::at_zero(Expr::EmptyRecord));
+    *closure_data.loc_body =
+        toplevel_expect_to_inline_expect_help(loc_body, false, lookups_in_cond);
 }
 
-fn toplevel_expect_to_inline_expect_help(mut loc_expr: Loc<Expr>, has_effects: bool) -> Loc<Expr> {
+fn toplevel_expect_to_inline_expect_help(
+    mut loc_expr: Loc<Expr>,
+    has_effects: bool,
+    mut lookups_in_cond: Vec<ExpectLookup>,
+) -> Loc<Expr> {
     enum StoredDef {
         NonRecursive(Region, Box<Def>),
         Recursive(Region, Vec<Def>, IllegalCycleMark),
     }
 
     let mut stack = vec![];
-    let mut lookups_in_cond = vec![];
 
     loop {
         match loc_expr.value {
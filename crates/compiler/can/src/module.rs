@@ -1256,7 +1256,7 @@ fn fix_values_captured_in_closure_expr(
 }
 
 /// Type state for a single module.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TypeState {
     pub subs: Subs,
     pub exposed_vars_by_symbol: Vec<(Symbol, Variable)>,
@@ -153,6 +153,14 @@ flags! {
     /// void layout.
     ROC_NO_UNBOUND_LAYOUT
 
+    /// Passes every procedure argument owned, instead of inferring which arguments are only
+    /// borrowed. Useful to rule out borrow inference when chasing a refcounting bug; release builds
+    /// ignore it.
+    ROC_NO_BORROW_INFERENCE
+
+    /// Prints statistics about the layout interner to stderr after code generation.
+    ROC_PRINT_LAYOUT_INTERNER_STATS
+
     // ===LLVM Gen===
 
     /// Prints LLVM function verification output.
@@ -166,6 +174,12 @@ flags! {
     /// Prints Wasm interpreter debug log in test_gen
     ROC_LOG_WASM_INTERP
 
+    // ===Dev Backends===
+
+    /// Panics with a stack trace when a development backend hits something it can't generate
+    /// code for yet, instead of reporting it to the user.
+    ROC_PANIC_ON_UNIMPLEMENTED
+
     // ===Load===
 
     /// Print load phases as they complete.
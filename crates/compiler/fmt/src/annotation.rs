@@ -70,6 +70,17 @@ pub trait Formattable {
     fn format(&self, buf: &mut Buf, indent: u16) {
         self.format_with_options(buf, Parens::NotNeeded, Newlines::No, indent);
     }
+
+    /// Formats at the start of a line, breaking over multiple lines if that's what it takes
+    /// to stay within the configured max width along with `trailing` more characters.
+    ///
+    /// Only call this where the surrounding code is laid out the same whether or not this
+    /// turns out multiline, or else formatting the output again would change it.
+    fn format_reflowed(&self, buf: &mut Buf, newlines: Newlines, indent: u16, trailing: usize) {
+        let _ = trailing;
+
+        self.format_with_options(buf, Parens::NotNeeded, newlines, indent);
+    }
 }
 
 /// A reference to a formattable value is also formattable
@@ -88,6 +99,10 @@ where
     fn format(&self, buf: &mut Buf, indent: u16) {
         (*self).format(buf, indent)
     }
+
+    fn format_reflowed(&self, buf: &mut Buf, newlines: Newlines, indent: u16, trailing: usize) {
+        (*self).format_reflowed(buf, newlines, indent, trailing)
+    }
 }
 
 pub fn is_collection_multiline<T: Formattable>(collection: &Collection<'_, T>) -> bool {
@@ -116,6 +131,10 @@ where
     fn format(&self, buf: &mut Buf, indent: u16) {
         self.value.format(buf, indent)
     }
+
+    fn format_reflowed(&self, buf: &mut Buf, newlines: Newlines, indent: u16, trailing: usize) {
+        self.value.format_reflowed(buf, newlines, indent, trailing)
+    }
 }
 
 impl<'a> Formattable for UppercaseIdent<'a> {
@@ -21,6 +21,22 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
     newline: Newlines,
 ) where
     <T as ExtractSpaces<'a>>::Item: Formattable,
+{
+    let is_multiline = is_collection_multiline(&items);
+
+    fmt_collection_help(buf, indent, braces, items, newline, is_multiline)
+}
+
+/// Formats a collection, putting every item on its own line if `is_multiline` is set.
+pub(crate) fn fmt_collection_help<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
+    buf: &mut Buf<'buf>,
+    indent: u16,
+    braces: Braces,
+    items: Collection<'a, T>,
+    newline: Newlines,
+    is_multiline: bool,
+) where
+    <T as ExtractSpaces<'a>>::Item: Formattable,
 {
     let start = match braces {
         Braces::Round => '(',
@@ -34,7 +50,7 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
         Braces::Square => ']',
     };
 
-    if is_collection_multiline(&items) {
+    if is_multiline {
         let braces_indent = indent;
         let item_indent = braces_indent + INDENT;
         if newline == Newlines::Yes {
@@ -86,7 +102,8 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
             }
 
             buf.indent(item_indent);
-            item.item.format(buf, item_indent);
+            // Each item gets its own line, followed by just a comma.
+            item.item.format_reflowed(buf, Newlines::No, item_indent, 1);
 
             buf.push(',');
 
@@ -640,27 +640,27 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
         buf.push_str(" =");
     }
 
-    if body.is_multiline() {
+    // A body that is too long for the line gets broken up the same way as if it had been
+    // written over multiple lines to begin with.
+    let reflow = !body.is_multiline() && is_reflowable(body) && !buf.fits(body, indent, 1);
+
+    if body.is_multiline() || reflow {
         match body {
             Expr::SpaceBefore(sub_def, spaces) => {
                 let should_outdent = match sub_def {
                     Expr::Record { .. } | Expr::List { .. } => {
                         let is_only_newlines = spaces.iter().all(|s| s.is_newline());
-                        is_only_newlines && sub_def.is_multiline()
+                        is_only_newlines
+                            && (sub_def.is_multiline() || !buf.fits(sub_def, indent, 1))
                     }
                     _ => false,
                 };
 
                 if should_outdent {
                     buf.spaces(1);
-                    sub_def.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
+                    sub_def.format_reflowed(buf, Newlines::Yes, indent, 0);
                 } else {
-                    body.format_with_options(
-                        buf,
-                        Parens::NotNeeded,
-                        Newlines::Yes,
-                        indent + INDENT,
-                    );
+                    body.format_reflowed(buf, Newlines::Yes, indent + INDENT, 0);
                 }
             }
             Expr::Defs(..) | Expr::BinOps(_, _) | Expr::Backpassing(..) => {
@@ -677,7 +677,7 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
                 //
                 // This makes it clear what the binop is applying to!
                 buf.newline();
-                body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT);
+                body.format_reflowed(buf, Newlines::Yes, indent + INDENT, 0);
             }
             Expr::When(..) | Expr::Str(StrLiteral::Block(_)) => {
                 buf.ensure_ends_with_newline();
@@ -685,7 +685,7 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
             }
             _ => {
                 buf.spaces(1);
-                body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
+                body.format_reflowed(buf, Newlines::Yes, indent, 0);
             }
         }
     } else {
@@ -694,6 +694,17 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
     }
 }
 
+/// Whether a body can be broken over multiple lines without changing how it parses.
+fn is_reflowable(body: &Expr) -> bool {
+    match body {
+        Expr::List(items) => !items.is_empty(),
+        Expr::Tuple(items) => !items.is_empty(),
+        Expr::Record(fields) | Expr::RecordUpdate { fields, .. } => !fields.is_empty(),
+        Expr::BinOps(..) => true,
+        _ => false,
+    }
+}
+
 impl<'a> Formattable for AbilityMember<'a> {
     fn is_multiline(&self) -> bool {
         self.name.value.is_multiline() || self.typ.is_multiline()
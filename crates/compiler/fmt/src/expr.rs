@@ -1,5 +1,5 @@
 use crate::annotation::{except_last, is_collection_multiline, Formattable, Newlines, Parens};
-use crate::collection::{fmt_collection, Braces};
+use crate::collection::{fmt_collection, fmt_collection_help, Braces};
 use crate::def::fmt_defs;
 use crate::pattern::fmt_pattern;
 use crate::spaces::{
@@ -117,6 +117,55 @@ impl<'a> Formattable for Expr<'a> {
         }
     }
 
+    fn format_reflowed(&self, buf: &mut Buf, newlines: Newlines, indent: u16, trailing: usize) {
+        use self::Expr::*;
+
+        match self {
+            SpaceBefore(sub_expr, spaces) => {
+                format_spaces(buf, spaces, newlines, indent);
+                sub_expr.format_reflowed(buf, newlines, indent, trailing);
+            }
+            SpaceAfter(..) => {
+                self.format_with_options(buf, Parens::NotNeeded, newlines, indent);
+            }
+            _ if self.is_multiline() || buf.fits(self, indent, trailing) => {
+                self.format_with_options(buf, Parens::NotNeeded, newlines, indent);
+            }
+            List(items) if !items.is_empty() => {
+                fmt_collection_help(buf, indent, Braces::Square, *items, Newlines::No, true);
+            }
+            Tuple(items) if !items.is_empty() => {
+                fmt_collection_help(buf, indent, Braces::Round, *items, Newlines::No, true);
+            }
+            Record(fields) if !fields.is_empty() => {
+                fmt_record_like(
+                    buf,
+                    None,
+                    *fields,
+                    indent,
+                    format_assigned_field_multiline,
+                    assigned_field_to_space_before,
+                    true,
+                );
+            }
+            RecordUpdate { update, fields } if !fields.is_empty() => {
+                fmt_record_like(
+                    buf,
+                    Some(RecordPrefix::Update(update)),
+                    *fields,
+                    indent,
+                    format_assigned_field_multiline,
+                    assigned_field_to_space_before,
+                    true,
+                );
+            }
+            BinOps(lefts, right) => fmt_binops(buf, lefts, right, true, indent),
+            _ => {
+                self.format_with_options(buf, Parens::NotNeeded, newlines, indent);
+            }
+        }
+    }
+
     fn format_with_options(&self, buf: &mut Buf, parens: Parens, newlines: Newlines, indent: u16) {
         use self::Expr::*;
 
@@ -364,6 +413,7 @@ impl<'a> Formattable for Expr<'a> {
                     indent,
                     format_assigned_field_multiline,
                     assigned_field_to_space_before,
+                    false,
                 );
             }
             RecordUpdate { update, fields } => {
@@ -374,6 +424,7 @@ impl<'a> Formattable for Expr<'a> {
                     indent,
                     format_assigned_field_multiline,
                     assigned_field_to_space_before,
+                    false,
                 );
             }
             RecordBuilder { mapper, fields } => {
@@ -384,6 +435,7 @@ impl<'a> Formattable for Expr<'a> {
                     indent,
                     format_assigned_field_multiline,
                     assigned_field_to_space_before,
+                    false,
                 );
             }
             OldRecordBuilder(fields) => {
@@ -394,6 +446,7 @@ impl<'a> Formattable for Expr<'a> {
                     indent,
                     format_record_builder_field_multiline,
                     record_builder_field_to_space_before,
+                    false,
                 );
             }
             Closure(loc_patterns, loc_ret) => {
@@ -426,19 +479,14 @@ impl<'a> Formattable for Expr<'a> {
 
                             buf.indent(indent);
 
-                            sub_expr.format_with_options(
-                                buf,
-                                Parens::NotNeeded,
-                                Newlines::Yes,
-                                indent,
-                            );
+                            sub_expr.format_reflowed(buf, Newlines::Yes, indent, 0);
                         }
                         _ => {
                             buf.ensure_ends_with_newline();
                             buf.indent(indent);
                             // Even if there were no defs, which theoretically should never happen,
                             // still print the return value.
-                            ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
+                            ret.format_reflowed(buf, Newlines::Yes, indent, 0);
                         }
                     }
                 }
@@ -1391,6 +1439,7 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
     indent: u16,
     format_field_multiline: Format,
     to_space_before: ToSpaceBefore,
+    force_multiline: bool,
 ) where
     Field: Formattable,
     Format: Fn(&mut Buf, &Field, u16, &str),
@@ -1422,7 +1471,8 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
             }
         }
 
-        let is_multiline = loc_fields.iter().any(|loc_field| loc_field.is_multiline())
+        let is_multiline = force_multiline
+            || loc_fields.iter().any(|loc_field| loc_field.is_multiline())
             || !final_comments.is_empty();
 
         if is_multiline {
@@ -10,6 +10,7 @@ pub mod module;
 pub mod pattern;
 pub mod spaces;
 
+use annotation::Formattable;
 use bumpalo::{collections::String, Bump};
 use roc_parse::ast::Module;
 
@@ -19,21 +20,88 @@ pub struct Ast<'a> {
     pub defs: roc_parse::ast::Defs<'a>,
 }
 
+/// The column that code gets reflowed at when no other width is configured.
+pub const DEFAULT_MAX_WIDTH: usize = 100;
+
+/// Settings that change how code gets formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatConfig {
+    /// Collection literals and operator chains that would run past this column get broken over
+    /// multiple lines. Code that is already multiline is never joined back onto one line, so
+    /// the formatter stays stable whatever the width.
+    pub max_width: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            max_width: DEFAULT_MAX_WIDTH,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Buf<'a> {
+    arena: &'a Bump,
     text: String<'a>,
     spaces_to_flush: usize,
     newlines_to_flush: usize,
     beginning_of_line: bool,
+    config: FormatConfig,
 }
 
 impl<'a> Buf<'a> {
     pub fn new_in(arena: &'a Bump) -> Buf<'a> {
+        Self::new_in_with_config(arena, FormatConfig::default())
+    }
+
+    pub fn new_in_with_config(arena: &'a Bump, config: FormatConfig) -> Buf<'a> {
         Buf {
+            arena,
             text: String::new_in(arena),
             spaces_to_flush: 0,
             newlines_to_flush: 0,
             beginning_of_line: true,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> FormatConfig {
+        self.config
+    }
+
+    /// Whether `item`, formatted on a single line, fits on the current line along with
+    /// `extra` more characters around it. If the current line is empty, it gets indented
+    /// by `indent` first.
+    pub fn fits(&self, item: &impl Formattable, indent: u16, extra: usize) -> bool {
+        let mut line = Buf::new_in_with_config(
+            self.arena,
+            FormatConfig {
+                max_width: usize::MAX,
+            },
+        );
+
+        item.format(&mut line, 0);
+
+        if line.text.contains('\n') {
+            return false;
+        }
+
+        let width = line.text.chars().count() + extra;
+
+        self.column(indent).saturating_add(width) <= self.config.max_width
+    }
+
+    /// The column that the next character pushed will land on.
+    fn column(&self, indent: u16) -> usize {
+        if self.beginning_of_line {
+            indent as usize
+        } else if self.newlines_to_flush > 0 {
+            self.spaces_to_flush
+        } else {
+            let line = self.text.rsplit('\n').next().unwrap_or_default();
+
+            line.chars().count() + self.spaces_to_flush
         }
     }
 
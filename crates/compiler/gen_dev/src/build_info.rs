@@ -0,0 +1,147 @@
+//! Records which compiler produced an object file, and a build ID for the code in it, so that a
+//! native binary from a bug report can be matched to the exact compiler build that made it.
+//!
+//! ELF objects get the usual `.comment` string, plus a `.note.roc` section with a GNU build ID
+//! note and a Roc version note, which `readelf -n` and `file` can show. We don't write into
+//! `.note.gnu.build-id` itself, because linkers fill that in with an ID of their own.
+//! COFF objects get a `.rocinfo` section with the same information as plain text.
+//! Mach-O objects get nothing yet (see https://github.com/roc-lang/roc/pull/1323).
+use object::write::Object;
+use object::{BinaryFormat, SectionFlags, SectionKind};
+
+pub const VERSION: &str = include_str!("../../../../version.txt");
+
+/// Name of the notes we own in `.note.roc`.
+const ELF_NOTE_ROC: &[u8] = b"Roc";
+
+/// Note type for the version of the compiler that wrote the object.
+pub const NT_ROC_VERSION: u32 = 1;
+
+/// Build IDs are as long as the SHA-1 ones linkers generate by default.
+pub const BUILD_ID_BYTES: usize = 20;
+
+pub struct BuildInfo {
+    hasher: blake3::Hasher,
+}
+
+impl Default for BuildInfo {
+    fn default() -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(VERSION.trim().as_bytes());
+        Self { hasher }
+    }
+}
+
+impl BuildInfo {
+    /// Includes the machine code of a procedure in the build ID.
+    pub fn add_procedure(&mut self, name: &str, code: &[u8]) {
+        self.hasher.update(name.as_bytes());
+        self.hasher.update(&(code.len() as u64).to_le_bytes());
+        self.hasher.update(code);
+    }
+
+    pub fn build_id(&self) -> [u8; BUILD_ID_BYTES] {
+        let mut build_id = [0; BUILD_ID_BYTES];
+        self.hasher.finalize_xof().fill(&mut build_id);
+        build_id
+    }
+
+    /// Adds the version and build ID sections for the object's format.
+    pub fn write(&self, output: &mut Object) {
+        let version = VERSION.trim();
+        let build_id = self.build_id();
+
+        match output.format() {
+            BinaryFormat::Elf => {
+                let comment =
+                    output.add_section(vec![], b".comment".to_vec(), SectionKind::OtherString);
+                let text = format!("\0roc dev backend {version}\0");
+                output.append_section_data(comment, text.as_bytes(), 1);
+
+                let mut notes = std::vec::Vec::new();
+                write_elf_note(
+                    &mut notes,
+                    object::elf::ELF_NOTE_GNU,
+                    object::elf::NT_GNU_BUILD_ID,
+                    &build_id,
+                );
+                write_elf_note(&mut notes, ELF_NOTE_ROC, NT_ROC_VERSION, version.as_bytes());
+
+                let section = output.add_section(vec![], b".note.roc".to_vec(), SectionKind::Note);
+                // Allocated, so that the notes end up in a PT_NOTE segment of the executable.
+                output.section_mut(section).flags = SectionFlags::Elf {
+                    sh_flags: object::elf::SHF_ALLOC as u64,
+                };
+                output.append_section_data(section, &notes, 4);
+            }
+            BinaryFormat::Coff => {
+                let section =
+                    output.add_section(vec![], b".rocinfo".to_vec(), SectionKind::ReadOnlyData);
+                let text = format!(
+                    "roc dev backend {version}\0build id {}\0",
+                    to_hex(&build_id)
+                );
+                output.append_section_data(section, text.as_bytes(), 1);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Appends an ELF note. The layout is the same for 32-bit and 64-bit ELF:
+/// name size, description size and type as 4 byte words, then the name and the description,
+/// each padded to 4 bytes.
+fn write_elf_note(buf: &mut std::vec::Vec<u8>, name: &[u8], note_type: u32, desc: &[u8]) {
+    let name_size = name.len() + 1; // null terminated
+
+    buf.extend((name_size as u32).to_le_bytes());
+    buf.extend((desc.len() as u32).to_le_bytes());
+    buf.extend(note_type.to_le_bytes());
+    buf.extend(name);
+    buf.push(0);
+    pad_to_word(buf);
+    buf.extend(desc);
+    pad_to_word(buf);
+}
+
+fn pad_to_word(buf: &mut std::vec::Vec<u8>) {
+    buf.resize(buf.len().next_multiple_of(4), 0);
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elf_note_layout() {
+        let mut buf = std::vec::Vec::new();
+        write_elf_note(&mut buf, b"GNU", 3, &[1, 2, 3, 4, 5]);
+
+        assert_eq!(
+            buf,
+            [
+                4, 0, 0, 0, // name size
+                5, 0, 0, 0, // description size
+                3, 0, 0, 0, // type
+                b'G', b'N', b'U', 0, // name
+                1, 2, 3, 4, 5, 0, 0, 0, // description
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_id_depends_on_code() {
+        let build_id = |code: &[u8]| {
+            let mut build_info = BuildInfo::default();
+            build_info.add_procedure("main", code);
+            build_info.build_id()
+        };
+
+        assert_eq!(build_id(&[0xc3]), build_id(&[0xc3]));
+        assert_ne!(build_id(&[0xc3]), build_id(&[0x90, 0xc3]));
+    }
+}
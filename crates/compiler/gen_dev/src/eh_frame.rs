@@ -0,0 +1,271 @@
+//! Writes `.eh_frame` call frame information for the procedures we generate, so that native
+//! debuggers, profilers, and backtraces can unwind through Roc frames.
+//!
+//! The format is the DWARF `.debug_frame` format with the changes described in the LSB:
+//! one common information entry (CIE) at the start of the section, followed by one frame
+//! description entry (FDE) per procedure.
+use object::write::{self, Object, SectionId, SymbolId};
+use object::{RelocationEncoding, RelocationKind, SectionKind};
+use roc_target::Target;
+
+use crate::{UnwindRow, UnwindRule};
+
+const DW_CFA_ADVANCE_LOC: u8 = 0x40;
+const DW_CFA_OFFSET: u8 = 0x80;
+const DW_CFA_ADVANCE_LOC1: u8 = 0x02;
+const DW_CFA_ADVANCE_LOC2: u8 = 0x03;
+const DW_CFA_ADVANCE_LOC4: u8 = 0x04;
+const DW_CFA_OFFSET_EXTENDED: u8 = 0x05;
+const DW_CFA_DEF_CFA: u8 = 0x0c;
+const DW_CFA_NOP: u8 = 0x00;
+
+/// Pointers to the start of a procedure are 4 byte signed offsets from the pointer itself.
+const DW_EH_PE_PCREL_SDATA4: u8 = 0x1b;
+
+/// Every saved register sits at a multiple of 8 from the canonical frame address.
+const DATA_ALIGNMENT_FACTOR: i32 = -8;
+
+pub struct EhFrame {
+    section: SectionId,
+}
+
+impl EhFrame {
+    /// Adds an `.eh_frame` section starting with the CIE shared by all procedures.
+    /// Returns `None` for targets that don't use `.eh_frame`: Windows gets `.pdata`/`.xdata` from
+    /// [crate::pdata::Pdata] instead, and Mach-O wants compact unwind info, which we don't
+    /// generate yet.
+    pub fn new(output: &mut Object, target: Target) -> Option<Self> {
+        // (return address column, initial instructions)
+        let (return_address, initial_instructions): (u8, &[u8]) = match target {
+            // The CFA is rsp + 8 right after a call, and the return address is right below it.
+            Target::LinuxX64 => (16, &[DW_CFA_DEF_CFA, 7, 8, DW_CFA_OFFSET | 16, 1]),
+            // The CFA is sp, and the return address is in the link register.
+            Target::LinuxArm64 => (30, &[DW_CFA_DEF_CFA, 31, 0]),
+            _ => return None,
+        };
+
+        let mut cie = std::vec::Vec::new();
+        cie.extend(0u32.to_le_bytes()); // CIE id
+        cie.push(1); // version
+        cie.extend(b"zR\0");
+        write_uleb128(&mut cie, 1); // code alignment factor
+        write_sleb128(&mut cie, DATA_ALIGNMENT_FACTOR as i64);
+        write_uleb128(&mut cie, return_address as u64);
+        write_uleb128(&mut cie, 1); // augmentation data length
+        cie.push(DW_EH_PE_PCREL_SDATA4);
+        cie.extend(initial_instructions);
+
+        let section = output.add_section(vec![], b".eh_frame".to_vec(), SectionKind::ReadOnlyData);
+        output.append_section_data(section, &with_length(cie), 8);
+
+        Some(Self { section })
+    }
+
+    /// Adds an FDE for the `size` bytes of code starting at `symbol`.
+    pub fn add_procedure(
+        &self,
+        output: &mut Object,
+        symbol: SymbolId,
+        size: u32,
+        rows: &[UnwindRow],
+    ) {
+        let start = output.section(self.section).data().len() as u32;
+
+        let mut fde = std::vec::Vec::new();
+        // Offset from this field back to the CIE at the start of the section.
+        fde.extend((start + 4).to_le_bytes());
+        // The start of the procedure, filled in by the relocation below.
+        fde.extend(0i32.to_le_bytes());
+        fde.extend(size.to_le_bytes());
+        write_uleb128(&mut fde, 0); // augmentation data length
+        encode_rows(&mut fde, rows);
+
+        let offset = output.append_section_data(self.section, &with_length(fde), 8);
+        debug_assert_eq!(offset, start as u64);
+
+        let relocation = write::Relocation {
+            offset: offset + 8,
+            size: 32,
+            kind: RelocationKind::Relative,
+            encoding: RelocationEncoding::Generic,
+            symbol,
+            addend: 0,
+        };
+        if let Err(e) = output.add_relocation(self.section, relocation) {
+            roc_error_macros::internal_error!("{:?}", e);
+        }
+    }
+}
+
+/// Turns rows sorted by offset into call frame instructions.
+fn encode_rows(buf: &mut std::vec::Vec<u8>, rows: &[UnwindRow]) {
+    let mut location = 0;
+
+    for row in rows {
+        debug_assert!(
+            row.offset >= location,
+            "unwind rows must be sorted by offset"
+        );
+
+        // Moving the stack pointer doesn't move the canonical frame address.
+        if let UnwindRule::StackPointer { .. } = row.rule {
+            continue;
+        }
+
+        match row.offset - location {
+            0 => {}
+            delta @ 1..=0x3f => buf.push(DW_CFA_ADVANCE_LOC | delta as u8),
+            delta @ 0x40..=0xff => buf.extend([DW_CFA_ADVANCE_LOC1, delta as u8]),
+            delta @ 0x100..=0xffff => {
+                buf.push(DW_CFA_ADVANCE_LOC2);
+                buf.extend((delta as u16).to_le_bytes());
+            }
+            delta => {
+                buf.push(DW_CFA_ADVANCE_LOC4);
+                buf.extend(delta.to_le_bytes());
+            }
+        }
+        location = row.offset;
+
+        match row.rule {
+            UnwindRule::Cfa { register, offset } => {
+                debug_assert!(offset >= 0);
+                buf.push(DW_CFA_DEF_CFA);
+                write_uleb128(buf, register as u64);
+                write_uleb128(buf, offset as u64);
+            }
+            UnwindRule::SavedAt { register, offset } => {
+                debug_assert!(offset < 0 && offset % DATA_ALIGNMENT_FACTOR == 0);
+                let factored = (offset / DATA_ALIGNMENT_FACTOR) as u64;
+                if register < 0x40 {
+                    buf.push(DW_CFA_OFFSET | register as u8);
+                } else {
+                    buf.push(DW_CFA_OFFSET_EXTENDED);
+                    write_uleb128(buf, register as u64);
+                }
+                write_uleb128(buf, factored);
+            }
+            UnwindRule::StackPointer { .. } => unreachable!(),
+        }
+    }
+}
+
+/// Prefixes an entry with its length, padded so the next entry stays 8 byte aligned.
+fn with_length(mut entry: std::vec::Vec<u8>) -> std::vec::Vec<u8> {
+    while (entry.len() + 4) % 8 != 0 {
+        entry.push(DW_CFA_NOP);
+    }
+
+    let mut out = std::vec::Vec::with_capacity(entry.len() + 4);
+    out.extend((entry.len() as u32).to_le_bytes());
+    out.extend(entry);
+    out
+}
+
+fn write_uleb128(buf: &mut std::vec::Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn write_sleb128(buf: &mut std::vec::Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_x86_64_frame() {
+        // push rbp; mov rbp, rsp; ...; pop rbp; ret, with rbx saved at rbp - 8.
+        let rows = [
+            UnwindRow {
+                offset: 1,
+                rule: UnwindRule::Cfa {
+                    register: 7,
+                    offset: 16,
+                },
+            },
+            UnwindRow {
+                offset: 1,
+                rule: UnwindRule::SavedAt {
+                    register: 6,
+                    offset: -16,
+                },
+            },
+            UnwindRow {
+                offset: 4,
+                rule: UnwindRule::Cfa {
+                    register: 6,
+                    offset: 16,
+                },
+            },
+            UnwindRow {
+                offset: 15,
+                rule: UnwindRule::SavedAt {
+                    register: 3,
+                    offset: -24,
+                },
+            },
+            UnwindRow {
+                offset: 200,
+                rule: UnwindRule::Cfa {
+                    register: 7,
+                    offset: 8,
+                },
+            },
+        ];
+
+        let mut buf = std::vec::Vec::new();
+        encode_rows(&mut buf, &rows);
+
+        assert_eq!(
+            buf,
+            [
+                0x41, 0x0c, 7, 16, // advance 1; def_cfa rsp, 16
+                0x86, 2, // offset rbp, cfa - 16
+                0x43, 0x0c, 6, 16, // advance 3; def_cfa rbp, 16
+                0x4b, 0x83, 3, // advance 11; offset rbx, cfa - 24
+                0x02, 185, 0x0c, 7, 8, // advance 185; def_cfa rsp, 8
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_extended_register() {
+        let rows = [UnwindRow {
+            offset: 0,
+            rule: UnwindRule::SavedAt {
+                register: 72,
+                offset: -32,
+            },
+        }];
+
+        let mut buf = std::vec::Vec::new();
+        encode_rows(&mut buf, &rows);
+
+        assert_eq!(buf, [0x05, 72, 4]);
+    }
+
+    #[test]
+    fn test_entries_stay_aligned() {
+        let entry = with_length(vec![1, 2, 3]);
+
+        assert_eq!(entry, [4, 0, 0, 0, 1, 2, 3, DW_CFA_NOP]);
+    }
+}
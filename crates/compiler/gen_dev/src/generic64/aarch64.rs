@@ -1,10 +1,11 @@
 #![allow(clippy::redundant_closure_call)]
 //|> clippy false positive: https://github.com/rust-lang/rust-clippy/issues/1553
 
-use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait};
+use crate::generic64::{storage::StorageManager, Assembler, CallConv, FrameOffsets, RegTrait};
 use crate::{
     pointer_layouts, single_register_floats, single_register_int_builtins,
-    single_register_integers, single_register_layouts, Relocation,
+    single_register_integers, single_register_layouts, BackendError, Hardening, Relocation,
+    UnwindRow, UnwindRule,
 };
 use bumpalo::collections::Vec;
 use packed_struct::prelude::*;
@@ -14,6 +15,7 @@ use roc_module::symbol::Symbol;
 use roc_mono::layout::{
     Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
 };
+use roc_target::{OperatingSystem, Target};
 
 use super::{CompareOperation, RegisterWidth};
 
@@ -136,6 +138,11 @@ impl RegTrait for AArch64GeneralReg {
     fn value(&self) -> u8 {
         *self as u8
     }
+
+    fn dwarf_number(&self) -> u16 {
+        // ZRSP is only ever the stack pointer in unwind tables, which DWARF numbers 31 as well.
+        *self as u16
+    }
 }
 
 impl std::fmt::Display for AArch64GeneralReg {
@@ -191,6 +198,10 @@ impl RegTrait for AArch64FloatReg {
     fn value(&self) -> u8 {
         *self as u8
     }
+
+    fn dwarf_number(&self) -> u16 {
+        64 + *self as u16
+    }
 }
 impl std::fmt::Display for AArch64FloatReg {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -245,8 +256,10 @@ impl AArch64FloatReg {
 #[derive(Copy, Clone)]
 pub struct AArch64Assembler {}
 
-// AArch64Call may need to eventually be split by OS,
-// but I think with how we use it, they may all be the same.
+/// The AAPCS64 calling convention, used on both Linux and macOS.
+///
+/// Apple's variant only differs in how arguments that don't fit in registers are laid out on the
+/// stack, so instead of a separate type that is decided by the target, see `claim_stack_arg`.
 #[derive(Copy, Clone)]
 pub struct AArch64Call {}
 
@@ -408,20 +421,28 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         saved_float_regs: &[AArch64FloatReg],
         requested_stack_size: i32,
         fn_call_stack_size: i32,
-    ) -> i32 {
+    ) -> Result<i32, BackendError> {
         let frame_pointer_link_register = 16;
 
-        // Full size is upcast to i64 to make sure we don't overflow here.
-        let full_stack_size = match requested_stack_size
-            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32)
+        // The frame is allocated with a single `sub sp, sp, #imm12`.
+        const MAX_STACK_SIZE: i32 = 0xFF0;
+
+        let saved_regs_size = 8 * (saved_general_regs.len() + saved_float_regs.len()) as i32;
+        let stack_too_large = || BackendError::StackTooLarge {
+            requested: requested_stack_size as i64
+                + saved_regs_size as i64
+                + frame_pointer_link_register as i64
+                + fn_call_stack_size as i64,
+            max: MAX_STACK_SIZE as i64,
+        };
+
+        let full_stack_size = requested_stack_size
+            .checked_add(saved_regs_size)
             // space for the frame pointer FP and the link register LR
             .and_then(|size| size.checked_add(frame_pointer_link_register))
             // extra space for arguments that did not fit into registers
             .and_then(|size| size.checked_add(fn_call_stack_size))
-        {
-            Some(size) => size,
-            _ => internal_error!("Ran out of stack space"),
-        };
+            .ok_or_else(stack_too_large)?;
 
         const fn next_multiple_of(lhs: i32, rhs: i32) -> i32 {
             match lhs % rhs {
@@ -431,6 +452,9 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         }
 
         let aligned_stack_size = next_multiple_of(full_stack_size, STACK_ALIGNMENT as i32);
+        if aligned_stack_size > MAX_STACK_SIZE {
+            return Err(stack_too_large());
+        }
 
         if aligned_stack_size > 0 {
             // sub     sp, sp, #0x10
@@ -463,9 +487,9 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
                 AArch64Assembler::mov_base32_freg64(buf, -offset, *reg);
                 offset -= 8;
             }
-            aligned_stack_size
+            Ok(aligned_stack_size)
         } else {
-            0
+            Ok(0)
         }
     }
 
@@ -506,6 +530,89 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         }
     }
 
+    #[inline(always)]
+    fn unwind_rows(
+        rows: &mut Vec<'_, UnwindRow>,
+        frame: FrameOffsets,
+        saved_general_regs: &[AArch64GeneralReg],
+        saved_float_regs: &[AArch64FloatReg],
+        aligned_stack_size: i32,
+        fn_call_stack_size: i32,
+    ) {
+        use AArch64GeneralReg::*;
+
+        // Without a frame, neither sp nor lr is ever touched.
+        if aligned_stack_size == 0 {
+            return;
+        }
+
+        let frame_pointer_link_register = 16;
+        let w = aligned_stack_size;
+
+        // After the 4 byte `sub sp, sp, #w`.
+        rows.push(UnwindRow {
+            offset: frame.prologue + 4,
+            rule: UnwindRule::Cfa {
+                register: ZRSP.dwarf_number(),
+                offset: w,
+            },
+        });
+
+        // The stores of fp and lr can take a variable number of instructions, so they are only
+        // described once the whole prologue is done.
+        let body_rules = [
+            UnwindRule::SavedAt {
+                register: FP.dwarf_number(),
+                offset: -0x10,
+            },
+            UnwindRule::SavedAt {
+                register: LR.dwarf_number(),
+                offset: -0x08,
+            },
+            UnwindRule::Cfa {
+                register: FP.dwarf_number(),
+                offset: frame_pointer_link_register,
+            },
+        ];
+        rows.extend(body_rules.into_iter().map(|rule| UnwindRow {
+            offset: frame.body,
+            rule,
+        }));
+
+        // Mirrors the stores at the end of `setup_stack`, relative to fp = cfa - 16.
+        let saved_regs = saved_general_regs
+            .iter()
+            .map(RegTrait::dwarf_number)
+            .chain(saved_float_regs.iter().map(RegTrait::dwarf_number));
+        let mut offset = w - fn_call_stack_size - frame_pointer_link_register;
+        for register in saved_regs {
+            rows.push(UnwindRow {
+                offset: frame.body,
+                rule: UnwindRule::SavedAt {
+                    register,
+                    offset: -frame_pointer_link_register - offset,
+                },
+            });
+            offset -= 8;
+        }
+
+        // The epilogue reloads fp before freeing the frame, so switch back to sp.
+        rows.push(UnwindRow {
+            offset: frame.epilogue,
+            rule: UnwindRule::Cfa {
+                register: ZRSP.dwarf_number(),
+                offset: w,
+            },
+        });
+        rows.push(UnwindRow {
+            offset: frame.ret,
+            rule: UnwindRule::Cfa {
+                register: ZRSP.dwarf_number(),
+                offset: 0,
+            },
+        });
+    }
+
     #[inline(always)]
     fn load_args<'a>(
         buf: &mut Vec<'a, u8>,
@@ -513,13 +620,14 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         layout_interner: &mut STLayoutInterner<'a>,
         args: &'a [(InLayout<'a>, Symbol)],
         ret_layout: &InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         // loading arguments occurs at an offset (but storing arguments does not)
         let mut state = AArch64CallLoadArgs {
             general_i: 0,
             float_i: 0,
             // 16 is the size of the pushed return address and base pointer.
             argument_offset: AArch64Call::SHADOW_SPACE_SIZE as i32,
+            target: layout_interner.target(),
         };
 
         if AArch64Call::returns_via_arg_pointer(layout_interner, ret_layout) {
@@ -527,8 +635,10 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         }
 
         for (in_layout, sym) in args.iter() {
-            state.load_arg(buf, storage_manager, layout_interner, *sym, *in_layout);
+            state.load_arg(buf, storage_manager, layout_interner, *sym, *in_layout)?;
         }
+
+        Ok(())
     }
 
     #[inline(always)]
@@ -540,7 +650,7 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         args: &[Symbol],
         arg_layouts: &[InLayout<'a>],
         ret_layout: &InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         if Self::returns_via_arg_pointer(layout_interner, ret_layout) {
             // Save space on the stack for the result we will be return.
             let base_offset =
@@ -560,13 +670,18 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
             general_i: 0,
             float_i: 0,
             tmp_stack_offset: 0,
+            target: layout_interner.target(),
         };
 
         for (sym, in_layout) in args.iter().zip(arg_layouts.iter()) {
-            state.store_arg(buf, storage_manager, layout_interner, *sym, *in_layout);
+            state.store_arg(buf, storage_manager, layout_interner, *sym, *in_layout)?;
         }
 
-        storage_manager.update_fn_call_stack_size(state.tmp_stack_offset as u32);
+        // Packed stack arguments can end anywhere, but the area for them must stay 8-byte aligned.
+        let fn_call_stack_size = next_multiple_of(state.tmp_stack_offset as u32, 8);
+        storage_manager.update_fn_call_stack_size(fn_call_stack_size);
+
+        Ok(())
     }
 
     fn return_complex_symbol<'a>(
@@ -708,7 +823,7 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         ASM::ret(buf)
     }
 
-    fn longjmp(buf: &mut Vec<'_, u8>) {
+    fn longjmp(buf: &mut Vec<'_, u8>, hardening: Hardening) {
         use AArch64GeneralReg::*;
         type ASM = AArch64Assembler;
 
@@ -762,11 +877,16 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         ASM::mov_reg64_reg64(buf, X0, X1);
         ASM::mov_reg64_reg64(buf, X1, X10);
 
-        // Break to the value of the link register
-        jmp_reg64(buf, LR);
+        // Break to the value of the link register. The return address does not start with a
+        // landing pad, so with branch target identification we must use `ret`, which BTI does not check.
+        if hardening.branch_targets {
+            ASM::ret(buf);
+        } else {
+            jmp_reg64(buf, LR);
+        }
     }
 
-    fn roc_panic(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>) {
+    fn roc_panic(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, hardening: Hardening) {
         use AArch64GeneralReg::*;
         type ASM = AArch64Assembler;
 
@@ -778,7 +898,7 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         ASM::add_reg64_reg64_imm32(buf, X10, X1, 1);
 
         // the setlongjmp_buffer
-        ASM::data_pointer(buf, relocs, String::from("setlongjmp_buffer"), X0);
+        ASM::local_data_pointer(buf, relocs, String::from("setlongjmp_buffer"), X0);
 
         // the value to return from the longjmp. It is a pointer to the last 3 words of the setlongjmp_buffer
         // they represent the error message. (168 + 8) which is after V15 register.
@@ -790,7 +910,7 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
             ASM::mov_mem64_offset32_reg64(buf, X1, offset, X11);
         }
 
-        Self::longjmp(buf)
+        Self::longjmp(buf, hardening)
     }
 }
 
@@ -855,7 +975,61 @@ impl AArch64Call {
     ) -> bool {
         // TODO: This will need to be more complex/extended to fully support the calling convention.
         // details here: https://github.com/hjl-tools/x86-psABI/wiki/x86-64-psABI-1.0.pdf
-        interner.stack_size(*ret_layout) > 16
+        interner.stack_size(*ret_layout) as usize > interner.target().max_return_in_registers_size()
+    }
+
+    /// Claims the space for an argument that is passed on the stack, and returns its offset.
+    ///
+    /// AAPCS64 gives every stack argument a slot of at least 8 bytes. Apple's arm64 ABI packs
+    /// integers and floats smaller than that at their natural alignment instead, so for example
+    /// two `U8` arguments take up 2 bytes rather than 16. Composites are rounded up to 8 bytes on
+    /// both.
+    fn claim_stack_arg(
+        next_offset: &mut i32,
+        target: Target,
+        size: u32,
+        alignment: u32,
+        composite: bool,
+    ) -> i32 {
+        let packed = !composite && target.operating_system() == OperatingSystem::Mac;
+        let (size, alignment) = if packed {
+            (size, alignment.max(1))
+        } else {
+            (next_multiple_of(size, 8), alignment.max(8))
+        };
+
+        let offset = next_multiple_of(*next_offset as u32, alignment) as i32;
+        *next_offset = offset + size as i32;
+
+        offset
+    }
+
+    /// The first of the two registers for a 16-byte argument. Values that are 16-byte aligned
+    /// start at an even register, so an `I128` after one `U64` goes in x2 and x3, not x1 and x2.
+    fn register_pair_start(general_i: usize, alignment: u32) -> usize {
+        if alignment == 16 {
+            next_multiple_of(general_i as u32, 2) as usize
+        } else {
+            general_i
+        }
+    }
+}
+
+const fn next_multiple_of(lhs: u32, rhs: u32) -> u32 {
+    match lhs % rhs {
+        0 => lhs,
+        r => lhs + (rhs - r),
+    }
+}
+
+/// The width of a load or store that moves exactly `size` bytes.
+fn register_width_for_size(size: u32) -> RegisterWidth {
+    match size {
+        1 => RegisterWidth::W8,
+        2 => RegisterWidth::W16,
+        4 => RegisterWidth::W32,
+        8 => RegisterWidth::W64,
+        _ => internal_error!("no register is {size} bytes wide"),
     }
 }
 
@@ -866,6 +1040,7 @@ struct AArch64CallLoadArgs {
     general_i: usize,
     float_i: usize,
     argument_offset: i32,
+    target: Target,
 }
 
 impl AArch64CallLoadArgs {
@@ -876,14 +1051,16 @@ impl AArch64CallLoadArgs {
         layout_interner: &mut STLayoutInterner<'a>,
         sym: Symbol,
         in_layout: InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         use Builtin::{Decimal, Int};
 
         let stack_size = layout_interner.stack_size(in_layout);
         match layout_interner.get_repr(in_layout) {
-            single_register_integers!() => self.load_arg_general(storage_manager, sym),
-            pointer_layouts!() => self.load_arg_general(storage_manager, sym),
-            single_register_floats!() => self.load_arg_float(storage_manager, sym),
+            single_register_integers!() => {
+                self.load_arg_general(buf, storage_manager, sym, stack_size)
+            }
+            pointer_layouts!() => self.load_arg_general(buf, storage_manager, sym, stack_size),
+            single_register_floats!() => self.load_arg_float(buf, storage_manager, sym, stack_size),
             LayoutRepr::Builtin(Int(IntWidth::U128 | IntWidth::I128) | Decimal) => {
                 self.load_arg_general_128bit(buf, storage_manager, layout_interner, sym, in_layout);
             }
@@ -915,8 +1092,15 @@ impl AArch64CallLoadArgs {
                     }
                     None => {
                         // else, pass the value implicitly by copying to the stack (of the new frame)
-                        storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
-                        self.argument_offset += stack_size as i32;
+                        let alignment = layout_interner.alignment_bytes(in_layout);
+                        let offset = AArch64Call::claim_stack_arg(
+                            &mut self.argument_offset,
+                            self.target,
+                            stack_size,
+                            alignment,
+                            true,
+                        );
+                        storage_manager.complex_stack_arg(&sym, offset, stack_size);
                     }
                 }
             }
@@ -926,7 +1110,7 @@ impl AArch64CallLoadArgs {
                 layout_interner,
                 sym,
                 lambda_set.runtime_representation(),
-            ),
+            )?,
             LayoutRepr::Struct { .. } | LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
                 if stack_size <= 8 {
                     self.load_arg_general_64bit(
@@ -949,25 +1133,53 @@ impl AArch64CallLoadArgs {
                 }
             }
             _ => {
-                todo!(
-                    "Loading args with layout {:?}",
-                    layout_interner.dbg(in_layout)
-                );
+                return Err(BackendError::UnsupportedLayout {
+                    layout: format!("{:?}", layout_interner.dbg(in_layout)),
+                });
             }
         }
+
+        Ok(())
     }
 
     fn load_arg_general(
         &mut self,
+        buf: &mut Vec<u8>,
         storage_manager: &mut AArch64StorageManager<'_, '_>,
         sym: Symbol,
+        size: u32,
     ) {
         if let Some(reg) = AArch64Call::GENERAL_PARAM_REGS.get(self.general_i) {
             storage_manager.general_reg_arg(&sym, *reg);
             self.general_i += 1;
         } else {
-            storage_manager.primitive_stack_arg(&sym, self.argument_offset);
-            self.argument_offset += 8;
+            self.load_primitive_stack_arg(buf, storage_manager, sym, size);
+        }
+    }
+
+    /// A primitive that was passed on the stack. If it was packed together with its neighbours,
+    /// it is copied into a slot of its own, so that it can be loaded and stored as 8 bytes like
+    /// every other primitive.
+    fn load_primitive_stack_arg(
+        &mut self,
+        buf: &mut Vec<u8>,
+        storage_manager: &mut AArch64StorageManager<'_, '_>,
+        sym: Symbol,
+        size: u32,
+    ) {
+        type ASM = AArch64Assembler;
+
+        let offset =
+            AArch64Call::claim_stack_arg(&mut self.argument_offset, self.target, size, size, false);
+
+        if self.argument_offset - offset == 8 {
+            storage_manager.primitive_stack_arg(&sym, offset);
+        } else {
+            let tmp = AArch64GeneralReg::X15;
+            ASM::mov_reg_base32(buf, register_width_for_size(size), tmp, offset);
+
+            let slot = storage_manager.claim_primitive_stack_area(sym);
+            ASM::mov_base32_reg64(buf, slot, tmp);
         }
     }
 
@@ -993,8 +1205,14 @@ impl AArch64CallLoadArgs {
                 self.general_i += 1;
             }
             None => {
-                storage_manager.complex_stack_arg(&sym, self.argument_offset, 8);
-                self.argument_offset += 8;
+                let offset = AArch64Call::claim_stack_arg(
+                    &mut self.argument_offset,
+                    self.target,
+                    8,
+                    8,
+                    true,
+                );
+                storage_manager.complex_stack_arg(&sym, offset, 8);
             }
         }
     }
@@ -1009,8 +1227,11 @@ impl AArch64CallLoadArgs {
     ) {
         type ASM = AArch64Assembler;
 
-        let reg1 = AArch64Call::GENERAL_PARAM_REGS.get(self.general_i);
-        let reg2 = AArch64Call::GENERAL_PARAM_REGS.get(self.general_i + 1);
+        let alignment = layout_interner.alignment_bytes(in_layout);
+        let general_i = AArch64Call::register_pair_start(self.general_i, alignment);
+
+        let reg1 = AArch64Call::GENERAL_PARAM_REGS.get(general_i);
+        let reg2 = AArch64Call::GENERAL_PARAM_REGS.get(general_i + 1);
 
         match (reg1, reg2) {
             (Some(reg1), Some(reg2)) => {
@@ -1020,22 +1241,36 @@ impl AArch64CallLoadArgs {
                 ASM::mov_base32_reg64(buf, offset, *reg1);
                 ASM::mov_base32_reg64(buf, offset + 8, *reg2);
 
-                self.general_i += 2;
+                self.general_i = general_i + 2;
             }
             _ => {
-                storage_manager.complex_stack_arg(&sym, self.argument_offset, 16);
-                self.argument_offset += 16;
+                // Once a value goes on the stack, the remaining registers stay unused.
+                self.general_i = AArch64Call::GENERAL_PARAM_REGS.len();
+
+                let offset = AArch64Call::claim_stack_arg(
+                    &mut self.argument_offset,
+                    self.target,
+                    16,
+                    alignment,
+                    true,
+                );
+                storage_manager.complex_stack_arg(&sym, offset, 16);
             }
         }
     }
 
-    fn load_arg_float(&mut self, storage_manager: &mut AArch64StorageManager<'_, '_>, sym: Symbol) {
+    fn load_arg_float(
+        &mut self,
+        buf: &mut Vec<u8>,
+        storage_manager: &mut AArch64StorageManager<'_, '_>,
+        sym: Symbol,
+        size: u32,
+    ) {
         if let Some(reg) = AArch64Call::FLOAT_PARAM_REGS.get(self.float_i) {
             storage_manager.float_reg_arg(&sym, *reg);
             self.float_i += 1;
         } else {
-            storage_manager.primitive_stack_arg(&sym, self.argument_offset);
-            self.argument_offset += 8;
+            self.load_primitive_stack_arg(buf, storage_manager, sym, size);
         }
     }
 }
@@ -1044,6 +1279,7 @@ struct AArch64CallStoreArgs {
     general_i: usize,
     float_i: usize,
     tmp_stack_offset: i32,
+    target: Target,
 }
 
 impl AArch64CallStoreArgs {
@@ -1059,7 +1295,7 @@ impl AArch64CallStoreArgs {
         layout_interner: &mut STLayoutInterner<'a>,
         sym: Symbol,
         in_layout: InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         type CC = AArch64Call;
         type ASM = AArch64Assembler;
 
@@ -1067,11 +1303,17 @@ impl AArch64CallStoreArgs {
         let tmp_reg = AArch64GeneralReg::X15;
 
         match layout_interner.get_repr(in_layout) {
-            single_register_integers!() => self.store_arg_general(buf, storage_manager, sym),
-            pointer_layouts!() => self.store_arg_general(buf, storage_manager, sym),
-            single_register_floats!() => self.store_arg_float(buf, storage_manager, sym),
+            single_register_integers!() | pointer_layouts!() => {
+                let size = layout_interner.stack_size(in_layout);
+                self.store_arg_general(buf, storage_manager, sym, size)
+            }
+            single_register_floats!() => {
+                let size = layout_interner.stack_size(in_layout);
+                self.store_arg_float(buf, storage_manager, sym, size)
+            }
             LayoutRepr::I128 | LayoutRepr::U128 | LayoutRepr::DEC => {
-                self.store_arg_128bit(buf, storage_manager, sym)
+                let alignment = layout_interner.alignment_bytes(in_layout);
+                self.store_arg_128bit(buf, storage_manager, sym, alignment)
             }
             _ if layout_interner.stack_size(in_layout) == 0 => {}
             _ if layout_interner.stack_size(in_layout) > 16 => {
@@ -1086,17 +1328,22 @@ impl AArch64CallStoreArgs {
                     }
                     None => {
                         // else, pass the value implicitly by copying to the stack (of the new frame)
-                        let stack_offset = self.tmp_stack_offset;
+                        let (size, alignment) = layout_interner.stack_size_and_alignment(in_layout);
+                        let stack_offset = AArch64Call::claim_stack_arg(
+                            &mut self.tmp_stack_offset,
+                            self.target,
+                            size,
+                            alignment,
+                            true,
+                        );
 
-                        let size = copy_symbol_to_stack_offset::<CC>(
+                        copy_symbol_to_stack_offset::<CC>(
                             buf,
                             storage_manager,
                             sym,
                             tmp_reg,
                             stack_offset,
                         );
-
-                        self.tmp_stack_offset += size as i32;
                     }
                 }
             }
@@ -1106,24 +1353,25 @@ impl AArch64CallStoreArgs {
                 layout_interner,
                 sym,
                 lambda_set.runtime_representation(),
-            ),
+            )?,
             LayoutRepr::Struct { .. } | LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
-                let stack_size = layout_interner.stack_size(in_layout);
+                let (stack_size, alignment) = layout_interner.stack_size_and_alignment(in_layout);
                 if stack_size <= 8 {
                     self.store_arg_64bit(buf, storage_manager, sym);
                 } else if stack_size <= 16 {
-                    self.store_arg_128bit(buf, storage_manager, sym);
+                    self.store_arg_128bit(buf, storage_manager, sym, alignment);
                 } else {
                     unreachable!("covered by earlier branch");
                 }
             }
             _ => {
-                todo!(
-                    "calling with arg type, {:?}",
-                    layout_interner.dbg(in_layout)
-                );
+                return Err(BackendError::UnsupportedLayout {
+                    layout: format!("{:?}", layout_interner.dbg(in_layout)),
+                });
             }
         }
+
+        Ok(())
     }
 
     fn store_arg_64bit<'a>(
@@ -1143,11 +1391,17 @@ impl AArch64CallStoreArgs {
                 self.general_i += 1;
             }
             None => {
+                let stack_offset = AArch64Call::claim_stack_arg(
+                    &mut self.tmp_stack_offset,
+                    self.target,
+                    8,
+                    8,
+                    true,
+                );
+
                 let tmp = AArch64GeneralReg::X15;
                 ASM::mov_reg64_base32(buf, tmp, offset);
-                ASM::mov_stack32_reg64(buf, self.tmp_stack_offset, tmp);
-
-                self.tmp_stack_offset += 8;
+                ASM::mov_stack32_reg64(buf, stack_offset, tmp);
             }
         }
     }
@@ -1157,28 +1411,39 @@ impl AArch64CallStoreArgs {
         buf: &mut Vec<'a, u8>,
         storage_manager: &mut AArch64StorageManager<'a, '_>,
         sym: Symbol,
+        alignment: u32,
     ) {
         type ASM = AArch64Assembler;
 
         let (offset, _) = storage_manager.stack_offset_and_size(&sym);
+        let general_i = AArch64Call::register_pair_start(self.general_i, alignment);
 
-        if self.general_i + 1 < Self::GENERAL_PARAM_REGS.len() {
-            let reg1 = Self::GENERAL_PARAM_REGS[self.general_i];
-            let reg2 = Self::GENERAL_PARAM_REGS[self.general_i + 1];
+        if general_i + 1 < Self::GENERAL_PARAM_REGS.len() {
+            let reg1 = Self::GENERAL_PARAM_REGS[general_i];
+            let reg2 = Self::GENERAL_PARAM_REGS[general_i + 1];
 
             ASM::mov_reg64_base32(buf, reg1, offset);
             ASM::mov_reg64_base32(buf, reg2, offset + 8);
 
-            self.general_i += 2;
+            self.general_i = general_i + 2;
         } else {
+            // Once a value goes on the stack, the remaining registers stay unused.
+            self.general_i = Self::GENERAL_PARAM_REGS.len();
+
+            let stack_offset = AArch64Call::claim_stack_arg(
+                &mut self.tmp_stack_offset,
+                self.target,
+                16,
+                alignment,
+                true,
+            );
+
             let reg = AArch64GeneralReg::X15;
             ASM::mov_reg64_base32(buf, reg, offset);
-            ASM::mov_stack32_reg64(buf, self.tmp_stack_offset, reg);
+            ASM::mov_stack32_reg64(buf, stack_offset, reg);
 
             ASM::mov_reg64_base32(buf, reg, offset + 8);
-            ASM::mov_stack32_reg64(buf, self.tmp_stack_offset + 8, reg);
-
-            self.tmp_stack_offset += 16;
+            ASM::mov_stack32_reg64(buf, stack_offset + 8, reg);
         }
     }
 
@@ -1187,6 +1452,7 @@ impl AArch64CallStoreArgs {
         buf: &mut Vec<'a, u8>,
         storage_manager: &mut AArch64StorageManager<'a, '_>,
         sym: Symbol,
+        size: u32,
     ) {
         match Self::GENERAL_PARAM_REGS.get(self.general_i) {
             Some(reg) => {
@@ -1194,12 +1460,26 @@ impl AArch64CallStoreArgs {
                 self.general_i += 1;
             }
             None => {
+                let stack_offset = AArch64Call::claim_stack_arg(
+                    &mut self.tmp_stack_offset,
+                    self.target,
+                    size,
+                    size,
+                    false,
+                );
+
+                // A full 8-byte slot, unless the argument was packed.
+                let slot_size = (self.tmp_stack_offset - stack_offset) as u32;
+
                 let tmp = AArch64GeneralReg::X15;
 
                 storage_manager.load_to_specified_general_reg(buf, &sym, tmp);
-                AArch64Assembler::mov_stack32_reg64(buf, self.tmp_stack_offset, tmp);
-
-                self.tmp_stack_offset += 8;
+                AArch64Assembler::mov_stack32_reg(
+                    buf,
+                    register_width_for_size(slot_size),
+                    stack_offset,
+                    tmp,
+                );
             }
         }
     }
@@ -1209,6 +1489,7 @@ impl AArch64CallStoreArgs {
         buf: &mut Vec<'a, u8>,
         storage_manager: &mut AArch64StorageManager<'a, '_>,
         sym: Symbol,
+        size: u32,
     ) {
         match Self::FLOAT_PARAM_REGS.get(self.float_i) {
             Some(reg) => {
@@ -1216,13 +1497,33 @@ impl AArch64CallStoreArgs {
                 self.float_i += 1;
             }
             None => {
+                let stack_offset = AArch64Call::claim_stack_arg(
+                    &mut self.tmp_stack_offset,
+                    self.target,
+                    size,
+                    size,
+                    false,
+                );
+                let slot_size = (self.tmp_stack_offset - stack_offset) as u32;
+
                 // Copy to stack using return reg as buffer.
                 let tmp = Self::FLOAT_RETURN_REGS[0];
 
                 storage_manager.load_to_specified_float_reg(buf, &sym, tmp);
-                AArch64Assembler::mov_stack32_freg64(buf, self.tmp_stack_offset, tmp);
 
-                self.tmp_stack_offset += 8;
+                if slot_size == 4 {
+                    // A packed F32: there is no 32-bit float store, so go through X15.
+                    let tmp_general = AArch64GeneralReg::X15;
+                    AArch64Assembler::mov_reg32_freg32(buf, tmp_general, tmp);
+                    AArch64Assembler::mov_stack32_reg(
+                        buf,
+                        RegisterWidth::W32,
+                        stack_offset,
+                        tmp_general,
+                    );
+                } else {
+                    AArch64Assembler::mov_stack32_freg64(buf, stack_offset, tmp);
+                }
             }
         }
     }
@@ -1318,6 +1619,49 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         fsub_freg_freg_freg(buf, FloatWidth::F64, dst, src1, src2);
     }
 
+    #[inline(always)]
+    fn indirect_branch_target(buf: &mut Vec<'_, u8>) {
+        bti_c(buf);
+    }
+
+    #[inline(always)]
+    fn atomic_add_mem64_imm32(
+        buf: &mut Vec<'_, u8>,
+        ptr: AArch64GeneralReg,
+        offset: i32,
+        imm32: i32,
+    ) {
+        let addr = if offset == 0 {
+            ptr
+        } else {
+            Self::add_reg64_reg64_imm32(buf, AArch64GeneralReg::IP0, ptr, offset);
+            AArch64GeneralReg::IP0
+        };
+
+        Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP1, imm32 as i64);
+        ldadd_reg64_reg64_reg64(buf, AArch64GeneralReg::IP1, AArch64GeneralReg::ZRSP, addr);
+    }
+
+    #[inline(always)]
+    fn atomic_fetch_add_reg64_mem64_offset32(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        ptr: AArch64GeneralReg,
+        offset: i32,
+        src: AArch64GeneralReg,
+    ) {
+        debug_assert_ne!(dst, ptr);
+
+        let addr = if offset == 0 {
+            ptr
+        } else {
+            Self::add_reg64_reg64_imm32(buf, AArch64GeneralReg::IP0, ptr, offset);
+            AArch64GeneralReg::IP0
+        };
+
+        ldadd_reg64_reg64_reg64(buf, src, dst, addr);
+    }
+
     #[inline(always)]
     fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String) {
         let inst = 0b1001_0100_0000_0000_0000_0000_0000_0000u32;
@@ -1370,6 +1714,23 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         });
     }
 
+    #[inline(always)]
+    fn local_data_pointer(
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        data_name: String,
+        dst: AArch64GeneralReg,
+    ) {
+        // the same `adrp` and `add` as data_pointer, which are already pc-relative
+        buf.extend((0x9000_0000u32 | dst.id() as u32).to_le_bytes());
+        Self::add_reg64_reg64_imm32(buf, dst, dst, 0);
+
+        relocs.push(Relocation::LocalDataAddress {
+            offset: buf.len() as u64 - 8,
+            name: data_name,
+        });
+    }
+
     #[inline(always)]
     fn imul_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
@@ -1687,8 +2048,10 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         if (-256..256).contains(&offset) {
             ldur_reg_reg_imm9(buf, register_width, dst, src, offset as i16);
         } else if (0..=u16::MAX as i32).contains(&offset) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg_reg_imm12(buf, register_width, dst, src, (offset as u16) >> 3);
+            // The unsigned offset is scaled by the access size.
+            let scale = register_width as u8;
+            debug_assert_eq!(offset % (1 << scale), 0);
+            ldr_reg_reg_imm12(buf, register_width, dst, src, (offset as u16) >> scale);
         } else {
             let tmp = AArch64GeneralReg::X15;
             Self::mov_reg64_imm64(buf, tmp, offset as i64);
@@ -1745,8 +2108,10 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         if (-256..256).contains(&offset) {
             stur_reg_reg_imm9(buf, register_width, src, dst, offset as i16);
         } else if (0..=u16::MAX as i32).contains(&offset) {
-            debug_assert_eq!(offset % 8, 0);
-            str_reg_reg_imm12(buf, register_width, src, dst, (offset as u16) >> 3);
+            // The unsigned offset is scaled by the access size.
+            let scale = register_width as u8;
+            debug_assert_eq!(offset % (1 << scale), 0);
+            str_reg_reg_imm12(buf, register_width, src, dst, (offset as u16) >> scale);
         } else {
             let tmp = AArch64GeneralReg::X15;
             Self::mov_reg64_imm64(buf, tmp, offset as i64);
@@ -1835,11 +2200,11 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     #[inline(always)]
     fn mov_stack32_reg(
         buf: &mut Vec<'_, u8>,
-        _register_width: RegisterWidth,
+        register_width: RegisterWidth,
         offset: i32,
         src: AArch64GeneralReg,
     ) {
-        Self::mov_mem64_offset32_reg64(buf, AArch64GeneralReg::ZRSP, offset, src)
+        Self::mov_mem_offset32_reg(buf, register_width, AArch64GeneralReg::ZRSP, offset, src)
     }
     #[inline(always)]
     fn neg_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
@@ -1978,6 +2343,18 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         ret_reg64(buf, AArch64GeneralReg::LR)
     }
 
+    #[inline(always)]
+    fn breakpoint(buf: &mut Vec<'_, u8>) {
+        // what `__builtin_debugtrap` emits
+        brk_imm16(buf, 0xF000)
+    }
+
+    #[inline(always)]
+    fn trap(buf: &mut Vec<'_, u8>) {
+        // what `__builtin_trap` emits
+        brk_imm16(buf, 1)
+    }
+
     fn and_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
         dst: AArch64GeneralReg,
@@ -2877,6 +3254,58 @@ impl LoadStoreRegisterImmediate {
     }
 }
 
+// Atomic memory operations are part of the Large System Extensions (LSE), introduced in ARMv8.1.
+// opc = 0b000 means add
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct AtomicMemoryOperation {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
+    v: bool,
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    a: bool,
+    r: bool,
+    fixed3: bool, // = 1
+    rs: Integer<u8, packed_bits::Bits<5>>,
+    o3: bool,
+    opc: Integer<u8, packed_bits::Bits<3>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for AtomicMemoryOperation {}
+
+pub struct AtomicMemoryOperationParams {
+    opc: u8,
+    rs: AArch64GeneralReg,
+    rn: AArch64GeneralReg,
+    rt: AArch64GeneralReg,
+}
+
+impl AtomicMemoryOperation {
+    #[inline(always)]
+    fn new(AtomicMemoryOperationParams { opc, rs, rn, rt }: AtomicMemoryOperationParams) -> Self {
+        debug_assert!(opc <= 0b111);
+
+        Self {
+            size: 0b11.into(),
+            fixed: 0b111.into(),
+            v: false,
+            fixed2: 0b00.into(),
+            a: false,
+            r: false,
+            fixed3: true,
+            rs: rs.id().into(),
+            o3: false,
+            opc: opc.into(),
+            fixed4: 0b00.into(),
+            rn: rn.id().into(),
+            rt: rt.id().into(),
+        }
+    }
+}
+
 #[derive(PackedStruct)]
 #[packed_struct(endian = "msb")]
 pub struct AdvancedSimdModifiedImmediate {
@@ -3426,6 +3855,25 @@ fn eor_reg64_reg64_reg64(
     buf.extend(inst.bytes());
 }
 
+/// `LDADD Xs, Xt, [Xn]` -> Atomically add Xs to the value at Xn, and load the previous value into Xt.
+/// With Xt as ZRSP (the zero register here), this is `STADD Xs, [Xn]`. Xn of ZRSP is SP.
+#[inline(always)]
+fn ldadd_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64GeneralReg,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+) {
+    let inst = AtomicMemoryOperation::new(AtomicMemoryOperationParams {
+        opc: 0b000,
+        rs: src,
+        rn: base,
+        rt: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `LDR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
 /// Note: imm12 is the offest divided by 8.
 #[inline(always)]
@@ -3870,6 +4318,14 @@ fn ret_reg64(buf: &mut Vec<'_, u8>, xn: AArch64GeneralReg) {
     buf.extend(inst.bytes());
 }
 
+/// `BRK #imm16` -> Raise a breakpoint exception, with imm16 available to the debugger.
+#[inline(always)]
+fn brk_imm16(buf: &mut Vec<'_, u8>, imm16: u16) {
+    let inst = 0xD420_0000u32 | ((imm16 as u32) << 5);
+
+    buf.extend(inst.to_le_bytes());
+}
+
 /// `UDIV Xd, Xn, Xm` -> Divide Xn by Xm and place the result into Xd.
 /// Xn, Xm, and Xd are unsigned integers.
 #[inline(always)]
@@ -4293,6 +4749,12 @@ fn jmp_reg64(buf: &mut Vec<'_, u8>, base: AArch64GeneralReg) {
     buf.extend(inst.to_le_bytes());
 }
 
+/// `BTI C`: a landing pad for indirect calls, with branch target identification enabled.
+#[inline(always)]
+fn bti_c(buf: &mut Vec<'_, u8>) {
+    buf.extend(0xD503245Fu32.to_le_bytes());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4560,6 +5022,12 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bti_c() {
+        // our capstone predates BTI, and shows the HINT instruction that `bti c` is an alias of
+        disassembler_test!(bti_c, || "hint #0x22");
+    }
+
     #[test]
     fn test_cmp_reg64_imm12() {
         disassembler_test!(
@@ -4774,6 +5242,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ldadd_reg64_reg64_reg64() {
+        disassembler_test!(
+            ldadd_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg2 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "stadd {}, [{}]",
+                        reg1.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesSP)
+                    )
+                } else {
+                    format!(
+                        "ldadd {}, {}, [{}]",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesSP)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_ldur_reg64_reg64_imm9() {
         disassembler_test!(
@@ -5166,6 +5660,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_returns_via_arg_pointer_agrees_with_target() {
+        use roc_mono::layout::Layout;
+
+        let arena = bumpalo::Bump::new();
+
+        for target in [Target::LinuxArm64, Target::MacArm64] {
+            let mut interner = STLayoutInterner::with_capacity(4, target);
+            let max = target.max_return_in_registers_size();
+
+            let mut words = |n| {
+                interner.insert_direct_no_semantic(LayoutRepr::Struct(
+                    arena.alloc_slice_fill_copy(n, Layout::U64),
+                ))
+            };
+            let fits = words(max / 8);
+            let too_big = words(max / 8 + 1);
+
+            assert!(!AArch64Call::returns_via_arg_pointer(&interner, &fits));
+            assert!(AArch64Call::returns_via_arg_pointer(&interner, &too_big));
+        }
+    }
+
+    #[test]
+    fn test_claim_stack_arg() {
+        let claim_all = |target, args: &[(u32, u32, bool)]| {
+            let mut next_offset = 0;
+            let offsets: std::vec::Vec<i32> = args
+                .iter()
+                .map(|&(size, alignment, composite)| {
+                    AArch64Call::claim_stack_arg(
+                        &mut next_offset,
+                        target,
+                        size,
+                        alignment,
+                        composite,
+                    )
+                })
+                .collect();
+
+            (offsets, next_offset)
+        };
+
+        // a U8, a U8, a U32, a U16, an F64
+        let primitives = [
+            (1, 1, false),
+            (1, 1, false),
+            (4, 4, false),
+            (2, 2, false),
+            (8, 8, false),
+        ];
+        assert_eq!(
+            claim_all(Target::LinuxArm64, &primitives),
+            (vec![0, 8, 16, 24, 32], 40)
+        );
+        assert_eq!(
+            claim_all(Target::MacArm64, &primitives),
+            (vec![0, 1, 4, 8, 16], 24)
+        );
+
+        // a U8, then a 12-byte struct, then an I128
+        let mixed = [(1, 1, false), (12, 4, true), (16, 16, true)];
+        assert_eq!(claim_all(Target::LinuxArm64, &mixed), (vec![0, 8, 32], 48));
+        assert_eq!(claim_all(Target::MacArm64, &mixed), (vec![0, 8, 32], 48));
+    }
+
+    #[test]
+    fn test_register_pair_start() {
+        assert_eq!(AArch64Call::register_pair_start(0, 16), 0);
+        assert_eq!(AArch64Call::register_pair_start(1, 16), 2);
+        assert_eq!(AArch64Call::register_pair_start(6, 16), 6);
+        assert_eq!(AArch64Call::register_pair_start(7, 16), 8);
+        assert_eq!(AArch64Call::register_pair_start(1, 8), 1);
+    }
+
+    #[test]
+    fn test_brk_imm16() {
+        disassembler_test!(
+            brk_imm16,
+            |imm| if imm < 10 {
+                format!("brk #{imm}")
+            } else {
+                format!("brk #0x{imm:x}")
+            },
+            [0u16, 1, 0xF000, u16::MAX]
+        );
+    }
+
     #[test]
     fn test_ret_reg64() {
         disassembler_test!(
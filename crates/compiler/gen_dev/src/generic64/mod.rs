@@ -1,10 +1,12 @@
+use crate::stack_map::{self, CallSite};
 use crate::{
     pointer_layouts, single_register_floats, single_register_int_builtins,
-    single_register_integers, Backend, Env, Relocation,
+    single_register_integers, Backend, BackendError, Env, Hardening, RefcountPointer, Relocation,
+    UnwindRow,
 };
 use bumpalo::collections::{CollectIn, Vec};
 use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
-use roc_collections::all::MutMap;
+use roc_collections::all::{MutMap, MutSet};
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::{CallerProc, CodeGenHelp, HelperOp};
@@ -33,6 +35,9 @@ use storage::{RegStorage, StorageManager};
 // NOTE: must fit in 27 bits and aligned to 4 for aarch64
 const JUMP_PLACEHOLDER: i32 = 0x0011_1100;
 
+// The refcount of a value with a single reference, see utils.zig
+const REFCOUNT_ONE: i64 = i64::MIN;
+
 #[derive(Debug, Clone, Copy)]
 pub enum RegisterWidth {
     W8 = 0b00,
@@ -86,7 +91,7 @@ pub trait CallConv<GeneralReg: RegTrait, FloatReg: RegTrait, ASM: Assembler<Gene
         saved_float_regs: &[FloatReg],
         requested_stack_size: i32,
         fn_call_stack_size: i32,
-    ) -> i32;
+    ) -> Result<i32, BackendError>;
     fn cleanup_stack(
         buf: &mut Vec<'_, u8>,
         general_saved_regs: &[GeneralReg],
@@ -95,6 +100,17 @@ pub trait CallConv<GeneralReg: RegTrait, FloatReg: RegTrait, ASM: Assembler<Gene
         fn_call_stack_size: i32,
     );
 
+    /// Describes for unwinders how the frame built by `setup_stack` and torn down by
+    /// `cleanup_stack` moves the canonical frame address and where it saves registers.
+    fn unwind_rows(
+        rows: &mut Vec<'_, UnwindRow>,
+        frame: FrameOffsets,
+        general_saved_regs: &[GeneralReg],
+        float_saved_regs: &[FloatReg],
+        aligned_stack_size: i32,
+        fn_call_stack_size: i32,
+    );
+
     /// load_args updates the storage manager to know where every arg is stored.
     fn load_args<'a>(
         buf: &mut Vec<'a, u8>,
@@ -103,7 +119,7 @@ pub trait CallConv<GeneralReg: RegTrait, FloatReg: RegTrait, ASM: Assembler<Gene
         args: &'a [(InLayout<'a>, Symbol)],
         // ret_layout is needed because if it is a complex type, we pass a pointer as the first arg.
         ret_layout: &InLayout<'a>,
-    );
+    ) -> Result<(), BackendError>;
 
     /// store_args stores the args in registers and on the stack for function calling.
     /// It also updates the amount of temporary stack space needed in the storage manager.
@@ -116,7 +132,7 @@ pub trait CallConv<GeneralReg: RegTrait, FloatReg: RegTrait, ASM: Assembler<Gene
         arg_layouts: &[InLayout<'a>],
         // ret_layout is needed because if it is a complex type, we pass a pointer as the first arg.
         ret_layout: &InLayout<'a>,
-    );
+    ) -> Result<(), BackendError>;
 
     /// return_complex_symbol returns the specified complex/non-primative symbol.
     /// It uses the layout to determine how the data should be returned.
@@ -139,8 +155,8 @@ pub trait CallConv<GeneralReg: RegTrait, FloatReg: RegTrait, ASM: Assembler<Gene
     );
 
     fn setjmp(buf: &mut Vec<'_, u8>);
-    fn longjmp(buf: &mut Vec<'_, u8>);
-    fn roc_panic(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>);
+    fn longjmp(buf: &mut Vec<'_, u8>, hardening: Hardening);
+    fn roc_panic(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, hardening: Hardening);
 }
 
 pub enum CompareOperation {
@@ -243,6 +259,22 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         ASM: Assembler<GeneralReg, FloatReg>,
         CC: CallConv<GeneralReg, FloatReg, ASM>;
 
+    /// Marks the start of a function as a valid target for indirect calls (`endbr64` on x86_64).
+    fn indirect_branch_target(buf: &mut Vec<'_, u8>);
+
+    /// Atomically adds imm32 (sign extended) to the 64-bit value at ptr + offset.
+    fn atomic_add_mem64_imm32(buf: &mut Vec<'_, u8>, ptr: GeneralReg, offset: i32, imm32: i32);
+
+    /// Atomically adds src to the 64-bit value at ptr + offset, and loads the previous value into dst.
+    /// dst must not be the same register as ptr.
+    fn atomic_fetch_add_reg64_mem64_offset32(
+        buf: &mut Vec<'_, u8>,
+        dst: GeneralReg,
+        ptr: GeneralReg,
+        offset: i32,
+        src: GeneralReg,
+    );
+
     fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String);
 
     fn function_pointer(
@@ -259,6 +291,14 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         dst: GeneralReg,
     );
 
+    /// Loads the address of a data symbol defined in this object, without going through the GOT.
+    fn local_data_pointer(
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        data_name: String,
+        dst: GeneralReg,
+    );
+
     /// Jumps by an offset of offset bytes unconditionally.
     /// It should always generate the same number of bytes to enable replacement if offset changes.
     /// It returns the base offset to calculate the jump from (generally the instruction after the jump).
@@ -739,12 +779,34 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
     fn set_if_overflow(buf: &mut Vec<'_, u8>, dst: GeneralReg);
 
     fn ret(buf: &mut Vec<'_, u8>);
+
+    /// Stops the program here when it runs under a debugger.
+    fn breakpoint(buf: &mut Vec<'_, u8>);
+
+    /// Crashes the program with the same instruction a C compiler emits for `__builtin_trap`.
+    fn trap(buf: &mut Vec<'_, u8>);
 }
 
 pub trait RegTrait:
     Copy + PartialEq + Eq + std::hash::Hash + std::fmt::Debug + std::fmt::Display + 'static
 {
     fn value(&self) -> u8;
+
+    /// The number of this register in unwind tables.
+    fn dwarf_number(&self) -> u16;
+}
+
+/// Where the parts of a function generated by `finalize` start, in bytes from the function start.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameOffsets {
+    /// The first instruction emitted by `setup_stack`.
+    pub prologue: u32,
+    /// The first instruction after `setup_stack`.
+    pub body: u32,
+    /// The first instruction emitted by `cleanup_stack`.
+    pub epilogue: u32,
+    /// The final `ret` instruction.
+    pub ret: u32,
 }
 
 pub struct Backend64Bit<
@@ -767,6 +829,8 @@ pub struct Backend64Bit<
     caller_procs: Vec<'a, CallerProc<'a>>,
     buf: Vec<'a, u8>,
     relocs: Vec<'a, Relocation>,
+    unwind_rows: Vec<'a, UnwindRow>,
+    stack_map: Vec<'a, CallSite>,
     proc_name: Option<String>,
     is_self_recursive: Option<SelfRecursive>,
 
@@ -776,6 +840,11 @@ pub struct Backend64Bit<
 
     literal_map: MutMap<Symbol, (*const Literal<'a>, *const InLayout<'a>)>,
     join_map: MutMap<JoinPointId, Vec<'a, (u64, u64)>>,
+    /// Join point parameters that have a stack location, but nothing jumped there to fill it yet.
+    unfilled_join_params: MutSet<Symbol>,
+    /// The first call whose arguments could not be passed. Reported by finalize, because the
+    /// statements that make calls don't return errors.
+    call_error: Option<BackendError>,
 
     storage_manager: StorageManager<'a, 'r, GeneralReg, FloatReg, ASM, CC>,
 }
@@ -807,11 +876,15 @@ pub fn new_backend_64bit<
         is_self_recursive: None,
         buf: bumpalo::vec![in env.arena],
         relocs: bumpalo::vec![in env.arena],
+        unwind_rows: bumpalo::vec![in env.arena],
+        stack_map: bumpalo::vec![in env.arena],
         last_seen_map: MutMap::default(),
         layout_map: MutMap::default(),
         free_map: MutMap::default(),
         literal_map: MutMap::default(),
         join_map: MutMap::default(),
+        unfilled_join_params: MutSet::default(),
+        call_error: None,
         storage_manager: storage::new_storage_manager(env, target),
     }
 }
@@ -853,6 +926,12 @@ impl<
     fn relocations_mut(&mut self) -> &mut Vec<'a, Relocation> {
         &mut self.relocs
     }
+    fn unwind_rows(&self) -> &[UnwindRow] {
+        &self.unwind_rows
+    }
+    fn stack_map(&self) -> &[CallSite] {
+        &self.stack_map
+    }
     fn target(&self) -> Target {
         self.storage_manager.target
     }
@@ -892,8 +971,11 @@ impl<
         self.last_seen_map.clear();
         self.layout_map.clear();
         self.join_map.clear();
+        self.unfilled_join_params.clear();
         self.free_map.clear();
         self.buf.clear();
+        self.stack_map.clear();
+        self.call_error = None;
         self.storage_manager.reset();
     }
 
@@ -917,8 +999,14 @@ impl<
         &mut self.free_map
     }
 
-    fn finalize(&mut self) -> (Vec<u8>, Vec<Relocation>) {
+    fn finalize(&mut self) -> Result<(Vec<'a, u8>, Vec<'a, Relocation>), BackendError> {
+        if let Some(error) = self.call_error.take() {
+            return Err(error);
+        }
+
         let mut out = bumpalo::vec![in self.env.arena];
+        self.build_function_entry(&mut out);
+        let prologue_offset = out.len();
 
         // Setup stack.
         let (used_general_regs, used_float_regs) = self
@@ -932,7 +1020,7 @@ impl<
             &used_float_regs,
             self.storage_manager.stack_size() as i32,
             self.storage_manager.fn_call_stack_size() as i32,
-        );
+        )?;
         let setup_offset = out.len();
 
         // Deal with jumps to the return address.
@@ -984,6 +1072,7 @@ impl<
         out.extend(&self.buf[..self.buf.len() - end_jmp_size]);
 
         // Cleanup stack.
+        let epilogue_offset = out.len();
         CC::cleanup_stack(
             &mut out,
             &used_general_regs,
@@ -991,8 +1080,28 @@ impl<
             aligned_stack_size,
             self.storage_manager.fn_call_stack_size() as i32,
         );
+        let ret_offset = out.len();
         ASM::ret(&mut out);
 
+        self.unwind_rows.clear();
+        CC::unwind_rows(
+            &mut self.unwind_rows,
+            FrameOffsets {
+                prologue: prologue_offset as u32,
+                body: setup_offset as u32,
+                epilogue: epilogue_offset as u32,
+                ret: ret_offset as u32,
+            },
+            &used_general_regs,
+            &used_float_regs,
+            aligned_stack_size,
+            self.storage_manager.fn_call_stack_size() as i32,
+        );
+
+        for call_site in self.stack_map.iter_mut() {
+            call_site.return_offset += setup_offset as u32;
+        }
+
         // Update other relocs to include stack setup offset.
         let mut out_relocs = bumpalo::vec![in self.env.arena];
         out_relocs.extend(
@@ -1008,6 +1117,10 @@ impl<
                         offset: offset + setup_offset as u64,
                         name,
                     },
+                    Relocation::LocalDataAddress { offset, name } => Relocation::LocalDataAddress {
+                        offset: offset + setup_offset as u64,
+                        name,
+                    },
                     Relocation::LinkedFunction { offset, name } => Relocation::LinkedFunction {
                         offset: offset + setup_offset as u64,
                         name,
@@ -1015,22 +1128,27 @@ impl<
                     Relocation::JmpToReturn { .. } => unreachable!(),
                 }),
         );
-        (out, out_relocs)
+        Ok((out, out_relocs))
     }
 
-    fn load_args(&mut self, args: &'a [(InLayout<'a>, Symbol)], ret_layout: &InLayout<'a>) {
+    fn load_args(
+        &mut self,
+        args: &'a [(InLayout<'a>, Symbol)],
+        ret_layout: &InLayout<'a>,
+    ) -> Result<(), BackendError> {
         CC::load_args(
             &mut self.buf,
             &mut self.storage_manager,
             self.layout_interner,
             args,
             ret_layout,
-        );
+        )
     }
 
     /// Used for generating wrappers for malloc/realloc/free
     fn build_wrapped_jmp(&mut self) -> (&'a [u8], u64) {
         let mut out = bumpalo::vec![in self.env.arena];
+        self.build_function_entry(&mut out);
         let offset = ASM::tail_call(&mut out);
 
         (out.into_bump_slice(), offset)
@@ -1038,6 +1156,7 @@ impl<
 
     fn build_roc_setjmp(&mut self) -> &'a [u8] {
         let mut out = bumpalo::vec![in self.env.arena];
+        self.build_function_entry(&mut out);
 
         CC::setjmp(&mut out);
 
@@ -1046,8 +1165,9 @@ impl<
 
     fn build_roc_longjmp(&mut self) -> &'a [u8] {
         let mut out = bumpalo::vec![in self.env.arena];
+        self.build_function_entry(&mut out);
 
-        CC::longjmp(&mut out);
+        CC::longjmp(&mut out, self.env.hardening);
 
         out.into_bump_slice()
     }
@@ -1055,12 +1175,22 @@ impl<
     fn build_roc_panic(&mut self) -> (&'a [u8], Vec<'a, Relocation>) {
         let mut out = bumpalo::vec![in self.env.arena];
         let mut relocs = bumpalo::vec![in self.env.arena];
+        self.build_function_entry(&mut out);
 
-        CC::roc_panic(&mut out, &mut relocs);
+        CC::roc_panic(&mut out, &mut relocs, self.env.hardening);
 
         (out.into_bump_slice(), relocs)
     }
 
+    fn build_roc_dbg(&mut self) -> &'a [u8] {
+        let mut out = bumpalo::vec![in self.env.arena];
+        self.build_function_entry(&mut out);
+
+        ASM::ret(&mut out);
+
+        out.into_bump_slice()
+    }
+
     fn build_fn_pointer(&mut self, dst: &Symbol, fn_name: String) {
         let reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
 
@@ -1071,7 +1201,7 @@ impl<
         let reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
 
         // now, this gives a pointer to the value
-        ASM::data_pointer(&mut self.buf, &mut self.relocs, data_name, reg);
+        ASM::local_data_pointer(&mut self.buf, &mut self.relocs, data_name, reg);
     }
 
     fn build_fn_call(
@@ -1089,7 +1219,7 @@ impl<
             .push_used_caller_saved_regs_to_stack(&mut self.buf);
 
         // Put values in param regs or on top of the stack.
-        CC::store_args(
+        if let Err(error) = CC::store_args(
             &mut self.buf,
             &mut self.storage_manager,
             self.layout_interner,
@@ -1097,11 +1227,17 @@ impl<
             args,
             arg_layouts,
             ret_layout,
-        );
+        ) {
+            self.call_error.get_or_insert(error);
+        }
 
         // Call function and generate reloc.
         ASM::call(&mut self.buf, &mut self.relocs, fn_name);
 
+        if self.env.stack_maps {
+            self.record_call_site();
+        }
+
         self.move_return_value(dst, ret_layout)
     }
 
@@ -1272,7 +1408,12 @@ impl<
         self.join_map.insert(*id, bumpalo::vec![in self.env.arena]);
 
         // Build remainder of function first. It is what gets run and jumps to join.
+        self.unfilled_join_params
+            .extend(parameters.iter().map(|param| param.symbol));
         self.build_stmt(layout_ids, remainder, ret_layout);
+        for param in parameters {
+            self.unfilled_join_params.remove(&param.symbol);
+        }
 
         let join_location = self.buf.len() as u64;
 
@@ -3134,6 +3275,158 @@ impl<
         ASM::mov_reg64_reg64(&mut self.buf, dst_reg, src_reg)
     }
 
+    fn build_atomic_refcount_inc(
+        &mut self,
+        dst: &Symbol,
+        ptr: Symbol,
+        pointer: RefcountPointer,
+        amount: Symbol,
+    ) {
+        // A literal amount is added as an immediate, so it never needs a register.
+        let amount_imm = self.literal_map.get(&amount).and_then(|(lit, _)| {
+            // The map is reset when building a procedure, so the literal outlives this lookup.
+            match unsafe { **lit } {
+                Literal::Int(bytes) => i32::try_from(i128::from_ne_bytes(bytes)).ok(),
+                _ => None,
+            }
+        });
+
+        // Everything we read must be in a register before the first branch,
+        // so the storage manager agrees with the machine state on every path.
+        let amount_reg = match amount_imm {
+            Some(_) => None,
+            None => {
+                self.load_literal_symbols(&[amount]);
+                Some(
+                    self.storage_manager
+                        .load_to_general_reg(&mut self.buf, &amount),
+                )
+            }
+        };
+
+        let mut end_jumps = bumpalo::vec![in self.env.arena];
+
+        let rc_ptr = self.debug_symbol("rc_ptr");
+        let rc_ptr_reg = self.load_refcount_ptr(rc_ptr, ptr, pointer, &mut end_jumps);
+
+        // a refcount of 0 marks a constant, which is never modified
+        let old = self.debug_symbol("old_refcount");
+        let old_reg = self.storage_manager.claim_general_reg(&mut self.buf, &old);
+        ASM::mov_reg64_mem64_offset32(&mut self.buf, old_reg, rc_ptr_reg, 0);
+        end_jumps.push(self.jmp_if_eq_placeholder(old_reg, 0));
+
+        match (amount_imm, amount_reg) {
+            (Some(imm32), _) => ASM::atomic_add_mem64_imm32(&mut self.buf, rc_ptr_reg, 0, imm32),
+            (None, Some(amount_reg)) => ASM::atomic_fetch_add_reg64_mem64_offset32(
+                &mut self.buf,
+                old_reg,
+                rc_ptr_reg,
+                0,
+                amount_reg,
+            ),
+            (None, None) => {
+                internal_error!("the refcount amount is neither a literal nor in a register")
+            }
+        }
+
+        self.update_jumps_to_here(end_jumps);
+
+        self.free_symbol(&rc_ptr);
+        self.free_symbol(&old);
+
+        self.create_struct(dst, &Layout::UNIT, &[]);
+    }
+
+    fn build_atomic_refcount_dec(
+        &mut self,
+        dst: &Symbol,
+        ptr: Symbol,
+        pointer: RefcountPointer,
+        alignment: Symbol,
+        elements_refcounted: Symbol,
+    ) {
+        // The last reference frees the allocation with a call, but only on one path.
+        // Spill up front, so no branch leaves a live value only in a clobbered register.
+        self.storage_manager
+            .push_used_caller_saved_regs_to_stack(&mut self.buf);
+
+        let mut end_jumps = bumpalo::vec![in self.env.arena];
+
+        let rc_ptr = self.debug_symbol("rc_ptr");
+        let rc_ptr_reg = self.load_refcount_ptr(rc_ptr, ptr, pointer, &mut end_jumps);
+
+        // a refcount of 0 marks a constant, which is never modified
+        let old = self.debug_symbol("old_refcount");
+        let old_reg = self.storage_manager.claim_general_reg(&mut self.buf, &old);
+        ASM::mov_reg64_mem64_offset32(&mut self.buf, old_reg, rc_ptr_reg, 0);
+        end_jumps.push(self.jmp_if_eq_placeholder(old_reg, 0));
+
+        self.storage_manager
+            .with_tmp_general_reg(&mut self.buf, |_, buf, minus_one| {
+                ASM::mov_reg64_imm64(buf, minus_one, -1);
+                ASM::atomic_fetch_add_reg64_mem64_offset32(buf, old_reg, rc_ptr_reg, 0, minus_one);
+            });
+
+        // only the thread that dropped the last reference frees the allocation
+        self.storage_manager
+            .push_used_caller_saved_regs_to_stack(&mut self.buf);
+        let old_reg = self
+            .storage_manager
+            .load_to_general_reg(&mut self.buf, &old);
+
+        let base_storage = self.storage_manager.clone();
+        let base_literal_map = self.literal_map.clone();
+
+        let jne_location = self.buf.len();
+        let start_offset = ASM::jne_reg64_imm64_imm32(
+            &mut self.buf,
+            &mut self.storage_manager,
+            old_reg,
+            REFCOUNT_ONE as u64,
+            0,
+        );
+
+        let unit = self.debug_symbol("unit");
+        self.build_fn_call(
+            &unit,
+            bitcode::UTILS_FREE_RC_PTR.to_string(),
+            &[rc_ptr, alignment, elements_refcounted],
+            &[Layout::U64, Layout::U32, Layout::BOOL],
+            &Layout::UNIT,
+        );
+
+        let mut tmp = bumpalo::vec![in self.env.arena];
+        ASM::jne_reg64_imm64_imm32(
+            &mut tmp,
+            &mut self.storage_manager,
+            old_reg,
+            REFCOUNT_ONE as u64,
+            (self.buf.len() - start_offset) as i32,
+        );
+        self.buf[jne_location..][..tmp.len()].copy_from_slice(tmp.as_slice());
+
+        // Continue with the storage from before the call, which is valid on both paths.
+        let branch_stack_size = self.storage_manager.stack_size();
+        let branch_fn_call_stack_size = self.storage_manager.fn_call_stack_size();
+        let branch_callee_saved_regs = self.storage_manager.used_callee_saved_regs.clone();
+
+        self.storage_manager = base_storage;
+        self.literal_map = base_literal_map;
+        self.storage_manager.update_stack_size(branch_stack_size);
+        self.storage_manager
+            .update_fn_call_stack_size(branch_fn_call_stack_size);
+        self.storage_manager
+            .used_callee_saved_regs
+            .extend(&branch_callee_saved_regs);
+
+        self.update_jumps_to_here(end_jumps);
+
+        self.free_symbol(&rc_ptr);
+        self.free_symbol(&old);
+
+        self.create_struct(dst, &Layout::UNIT, &[]);
+    }
+
     fn create_empty_array(&mut self, sym: &Symbol) {
         let base_offset = self
             .storage_manager
@@ -3577,12 +3870,13 @@ impl<
     }
 
     fn build_ptr_clear_tag_id(&mut self, sym: Symbol, ptr: Symbol) {
+        let mask = self.target().tag_id_pointer_mask() as i64;
         let buf = &mut self.buf;
 
         let ptr_reg = self.storage_manager.load_to_general_reg(buf, &ptr);
         let sym_reg = self.storage_manager.claim_general_reg(buf, &sym);
 
-        ASM::mov_reg64_imm64(buf, sym_reg, !0b111);
+        ASM::mov_reg64_imm64(buf, sym_reg, !mask);
         ASM::and_reg64_reg64_reg64(buf, sym_reg, sym_reg, ptr_reg);
     }
 
@@ -3748,10 +4042,11 @@ impl<
 
                 self.free_symbol(&table);
 
-                // mask the 3 lowest bits
+                // mask the tag id bits
+                let mask = self.target().tag_id_pointer_mask() as i64;
                 let tmp = Symbol::DEV_TMP5;
                 let reg = self.storage_manager.claim_general_reg(&mut self.buf, &tmp);
-                ASM::mov_reg64_imm64(&mut self.buf, reg, 0b111);
+                ASM::mov_reg64_imm64(&mut self.buf, reg, mask);
 
                 let src1_reg = reg;
                 let src2_reg = self
@@ -3807,10 +4102,11 @@ impl<
                         _ => unreachable!(),
                     }
                 } else {
-                    // mask the 3 lowest bits
+                    // mask the tag id bits
+                    let mask = target.tag_id_pointer_mask() as i64;
                     let tmp = Symbol::DEV_TMP5;
                     let reg = self.storage_manager.claim_general_reg(&mut self.buf, &tmp);
-                    ASM::mov_reg64_imm64(&mut self.buf, reg, 0b111);
+                    ASM::mov_reg64_imm64(&mut self.buf, reg, mask);
 
                     let src1_reg = reg;
                     let src2_reg = self
@@ -4756,6 +5052,28 @@ impl<
         CC: CallConv<GeneralReg, FloatReg, ASM>,
     > Backend64Bit<'a, 'r, GeneralReg, FloatReg, ASM, CC>
 {
+    /// Stop here when running under a debugger.
+    /// Handy for stepping through the code generated for one statement.
+    #[allow(dead_code)]
+    fn debug_breakpoint(&mut self) {
+        ASM::breakpoint(&mut self.buf);
+    }
+
+    /// Crash if `reg` does not hold `imm` at runtime.
+    /// The crash is the same trap instruction as `__builtin_trap`, so a debugger stops right on it.
+    #[allow(dead_code)]
+    fn debug_assert_reg_eq(&mut self, reg: GeneralReg, imm: u64) {
+        let jump = self.jmp_if_eq_placeholder(reg, imm);
+        ASM::trap(&mut self.buf);
+        self.update_jumps_to_here(bumpalo::vec![in self.env.arena; jump]);
+    }
+
+    /// Check the name of the procedure being generated, to add debug code to just that one.
+    #[allow(dead_code)]
+    fn debug_current_proc_is(&self, name: &str) -> bool {
+        self.proc_name.as_deref() == Some(name)
+    }
+
     fn build_fn_call_stack_return<const N: usize>(
         &mut self,
         function_name: String,
@@ -4791,13 +5109,39 @@ impl<
         self.free_symbol(&tmp);
     }
 
+    /// Records which stack slots hold refcounted pointers during the call that was just emitted.
+    /// Arguments are included, even if the callee takes ownership of them.
+    fn record_call_site(&mut self) {
+        let mut slots = std::vec::Vec::new();
+        for (symbol, base_offset) in self.storage_manager.stack_symbols() {
+            if self.unfilled_join_params.contains(&symbol) {
+                continue;
+            }
+            // Symbols get their layout once they are defined, so this also skips the
+            // destination of the call.
+            if let Some(layout) = self.layout_map.get(&symbol) {
+                stack_map::refcounted_slots(self.layout_interner, *layout, base_offset, &mut slots);
+            }
+        }
+
+        // A field loaded from a struct on the stack shares its slot with the struct.
+        slots.sort_by_key(|slot| slot.frame_offset);
+        slots.dedup();
+
+        self.stack_map.push(CallSite {
+            return_offset: self.buf.len() as u32,
+            slots,
+        });
+    }
+
     fn clear_tag_id(&mut self, ptr_reg: GeneralReg) -> (Symbol, GeneralReg) {
         let unmasked_symbol = self.debug_symbol("unmasked");
         let unmasked_reg = self
             .storage_manager
             .claim_general_reg(&mut self.buf, &unmasked_symbol);
 
-        ASM::mov_reg64_imm64(&mut self.buf, unmasked_reg, (!0b111) as _);
+        let mask = self.target().tag_id_pointer_mask() as i64;
+        ASM::mov_reg64_imm64(&mut self.buf, unmasked_reg, !mask);
 
         ASM::and_reg64_reg64_reg64(&mut self.buf, unmasked_reg, ptr_reg, unmasked_reg);
 
@@ -5033,6 +5377,80 @@ impl<
         self.buf[mov_start_index..][..tmp.len()].copy_from_slice(tmp.as_slice());
     }
 
+    /// Code that must come first in every function we generate.
+    fn build_function_entry(&self, out: &mut Vec<'a, u8>) {
+        if self.env.hardening.branch_targets {
+            ASM::indirect_branch_target(out);
+        }
+    }
+
+    /// Load the address of the refcount that `ptr` refers to into a fresh register.
+    /// A null data pointer has no refcount: that case jumps away, and the jump is added to `end_jumps`.
+    fn load_refcount_ptr(
+        &mut self,
+        rc_ptr: Symbol,
+        ptr: Symbol,
+        pointer: RefcountPointer,
+        end_jumps: &mut Vec<'a, (usize, usize)>,
+    ) -> GeneralReg {
+        let ptr_reg = self
+            .storage_manager
+            .load_to_general_reg(&mut self.buf, &ptr);
+        let rc_ptr_reg = self
+            .storage_manager
+            .claim_general_reg(&mut self.buf, &rc_ptr);
+        ASM::mov_reg64_reg64(&mut self.buf, rc_ptr_reg, ptr_reg);
+
+        if let RefcountPointer::ToData = pointer {
+            end_jumps.push(self.jmp_if_eq_placeholder(rc_ptr_reg, 0));
+
+            // clear the tag id bits, then step back from the data to the refcount
+            let tag_id_mask = self.target().tag_id_pointer_mask() as i64;
+            self.storage_manager
+                .with_tmp_general_reg(&mut self.buf, |_, buf, mask| {
+                    ASM::mov_reg64_imm64(buf, mask, !tag_id_mask);
+                    ASM::and_reg64_reg64_reg64(buf, rc_ptr_reg, rc_ptr_reg, mask);
+                });
+            ASM::sub_reg64_reg64_imm32(&mut self.buf, rc_ptr_reg, rc_ptr_reg, 8);
+        }
+
+        rc_ptr_reg
+    }
+
+    /// Emit a jump that is taken if `reg` equals `imm`. The target is not known yet:
+    /// returns the jump location and base offset to pass to `update_jmp_imm32_offset`.
+    fn jmp_if_eq_placeholder(&mut self, reg: GeneralReg, imm: u64) -> (usize, usize) {
+        let mut tmp = bumpalo::vec![in self.env.arena];
+        ASM::jmp_imm32(&mut tmp, JUMP_PLACEHOLDER);
+
+        // skip over the unconditional jump if not equal
+        ASM::jne_reg64_imm64_imm32(
+            &mut self.buf,
+            &mut self.storage_manager,
+            reg,
+            imm,
+            tmp.len() as i32,
+        );
+
+        let jmp_location = self.buf.len();
+        let base_offset = ASM::jmp_imm32(&mut self.buf, JUMP_PLACEHOLDER);
+
+        (jmp_location, base_offset)
+    }
+
+    fn update_jumps_to_here(&mut self, jumps: Vec<'a, (usize, usize)>) {
+        let target_offset = self.buf.len();
+
+        for (jmp_location, base_offset) in jumps {
+            ASM::update_jmp_imm32_offset(
+                &mut self.buf,
+                jmp_location as u64,
+                base_offset as u64,
+                target_offset as u64,
+            );
+        }
+    }
+
     fn unbox_str_or_list(
         buf: &mut Vec<'a, u8>,
         storage_manager: &mut StorageManager<'a, 'r, GeneralReg, FloatReg, ASM, CC>,
@@ -5070,8 +5488,10 @@ impl<
             return;
         }
 
-        // 16 is a guess here. In practice this is the highest alignment we encounter in roc datastructures
-        let base_offset = storage_manager.claim_stack_area_with_alignment(dst, stack_size, 16);
+        // 128-bit numbers have the highest alignment we encounter in roc datastructures
+        let alignment = storage_manager.target.i128_alignment_bytes() as u32;
+        let base_offset =
+            storage_manager.claim_stack_area_with_alignment(dst, stack_size, alignment);
 
         if size - copied >= 8 {
             for _ in (0..(size - copied)).step_by(8) {
@@ -241,6 +241,20 @@ impl<
         self.fn_call_stack_size
     }
 
+    /// Every symbol that has its value on the stack, with the offset of that value.
+    pub fn stack_symbols(&self) -> impl Iterator<Item = (Symbol, i32)> + '_ {
+        self.symbol_storage_map
+            .iter()
+            .filter_map(|(symbol, storage)| match storage {
+                Stack(
+                    Primitive { base_offset, .. }
+                    | ReferencedPrimitive { base_offset, .. }
+                    | Complex { base_offset, .. },
+                ) => Some((*symbol, *base_offset)),
+                Reg(_) | NoData => None,
+            })
+    }
+
     /// Returns true if the symbol is storing a primitive value.
     pub fn is_stored_primitive(&self, sym: &Symbol) -> bool {
         matches!(
@@ -1377,6 +1391,11 @@ impl<
 
     pub fn claim_pointer_stack_area(&mut self, sym: Symbol) -> i32 {
         // pointers are 8 bytes wide with an alignment of 8
+        self.claim_primitive_stack_area(sym)
+    }
+
+    /// Claims an 8-byte slot for a primitive that lives on the stack of this frame.
+    pub fn claim_primitive_stack_area(&mut self, sym: Symbol) -> i32 {
         let base_offset = self.claim_stack_size_with_alignment(8, 8);
 
         self.symbol_storage_map.insert(
@@ -1697,4 +1716,21 @@ mod tests {
             (16, -8, vec![in &arena; ])
         );
     }
+
+    #[test]
+    fn claim_wide_numbers_with_target_alignment() {
+        use bumpalo::vec;
+        use roc_mono::layout::{LayoutInterner, STLayoutInterner};
+        let arena = bumpalo::Bump::new();
+
+        for target in [Target::LinuxX64, Target::WinX64, Target::MacArm64] {
+            let interner = STLayoutInterner::with_capacity(4, target);
+            let alignment = interner.align_of_i128();
+            assert_eq!(alignment, 16, "{target}");
+
+            let (stack_size, offset, _) = claim_helper(vec![in &arena;], 8, 16, alignment);
+            assert_eq!(offset % alignment as i32, 0, "{target}");
+            assert_eq!((stack_size, offset), (32, -32), "{target}");
+        }
+    }
 }
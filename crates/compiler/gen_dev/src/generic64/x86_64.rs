@@ -1,10 +1,11 @@
 #![allow(clippy::redundant_closure_call)]
 //|> clippy false positive: https://github.com/rust-lang/rust-clippy/issues/1553
 
-use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait};
+use crate::generic64::{storage::StorageManager, Assembler, CallConv, FrameOffsets, RegTrait};
 use crate::{
     pointer_layouts, single_register_floats, single_register_int_builtins,
-    single_register_integers, single_register_layouts, Relocation,
+    single_register_integers, single_register_layouts, BackendError, Hardening, Relocation,
+    UnwindRow, UnwindRule,
 };
 use bumpalo::collections::Vec;
 use roc_builtins::bitcode::{FloatWidth, IntWidth};
@@ -41,6 +42,21 @@ impl RegTrait for X86_64GeneralReg {
     fn value(&self) -> u8 {
         *self as u8
     }
+
+    fn dwarf_number(&self) -> u16 {
+        // The System V psABI numbers the first eight registers in a different order.
+        match self {
+            X86_64GeneralReg::RAX => 0,
+            X86_64GeneralReg::RDX => 1,
+            X86_64GeneralReg::RCX => 2,
+            X86_64GeneralReg::RBX => 3,
+            X86_64GeneralReg::RSI => 4,
+            X86_64GeneralReg::RDI => 5,
+            X86_64GeneralReg::RBP => 6,
+            X86_64GeneralReg::RSP => 7,
+            _ => *self as u16,
+        }
+    }
 }
 impl std::fmt::Display for X86_64GeneralReg {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -92,6 +108,10 @@ impl RegTrait for X86_64FloatReg {
     fn value(&self) -> u8 {
         *self as u8
     }
+
+    fn dwarf_number(&self) -> u16 {
+        17 + *self as u16
+    }
 }
 impl std::fmt::Display for X86_64FloatReg {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -129,6 +149,9 @@ pub struct X86_64SystemV {}
 
 const STACK_ALIGNMENT: u8 = 16;
 
+// Frames bigger than a page are allocated one page at a time, see `x86_64_allocate_stack`.
+const PAGE_SIZE: i32 = 4096;
+
 impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64SystemV {
     const BASE_PTR_REG: X86_64GeneralReg = X86_64GeneralReg::RBP;
     const STACK_PTR_REG: X86_64GeneralReg = X86_64GeneralReg::RSP;
@@ -228,7 +251,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         saved_float_regs: &[X86_64FloatReg],
         requested_stack_size: i32,
         fn_call_stack_size: i32,
-    ) -> i32 {
+    ) -> Result<i32, BackendError> {
         x86_64_generic_setup_stack(
             buf,
             saved_general_regs,
@@ -255,6 +278,25 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         )
     }
 
+    #[inline(always)]
+    fn unwind_rows(
+        rows: &mut Vec<'_, UnwindRow>,
+        frame: FrameOffsets,
+        saved_general_regs: &[X86_64GeneralReg],
+        saved_float_regs: &[X86_64FloatReg],
+        aligned_stack_size: i32,
+        fn_call_stack_size: i32,
+    ) {
+        x86_64_generic_unwind_rows(
+            rows,
+            frame,
+            saved_general_regs,
+            saved_float_regs,
+            aligned_stack_size,
+            fn_call_stack_size,
+        )
+    }
+
     #[inline(always)]
     fn load_args<'a>(
         buf: &mut Vec<'a, u8>,
@@ -269,7 +311,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         layout_interner: &mut STLayoutInterner<'a>,
         args: &'a [(InLayout<'a>, Symbol)],
         ret_layout: &InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         let returns_via_pointer =
             X86_64SystemV::returns_via_arg_pointer(layout_interner, ret_layout);
 
@@ -285,8 +327,10 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         }
 
         for (in_layout, sym) in args.iter() {
-            state.load_arg(buf, storage_manager, layout_interner, *sym, *in_layout);
+            state.load_arg(buf, storage_manager, layout_interner, *sym, *in_layout)?;
         }
+
+        Ok(())
     }
 
     #[inline(always)]
@@ -305,7 +349,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         args: &[Symbol],
         arg_layouts: &[InLayout<'a>],
         ret_layout: &InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         let mut general_i = 0;
 
         if Self::returns_via_arg_pointer(layout_interner, ret_layout) {
@@ -330,10 +374,12 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         };
 
         for (sym, in_layout) in args.iter().zip(arg_layouts.iter()) {
-            state.store_arg(buf, storage_manager, layout_interner, *sym, *in_layout);
+            state.store_arg(buf, storage_manager, layout_interner, *sym, *in_layout)?;
         }
 
         storage_manager.update_fn_call_stack_size(state.tmp_stack_offset as u32);
+
+        Ok(())
     }
 
     fn return_complex_symbol<'a>(
@@ -356,29 +402,31 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
             }
             _ if layout_interner.stack_size(*layout) == 0 => {}
             _ if !Self::returns_via_arg_pointer(layout_interner, layout) => {
-                let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
-
-                if size <= 8 {
-                    X86_64Assembler::mov_reg64_base32(
-                        buf,
-                        Self::GENERAL_RETURN_REGS[0],
-                        base_offset,
-                    );
-                } else if size <= 16 {
-                    X86_64Assembler::mov_reg64_base32(
-                        buf,
-                        Self::GENERAL_RETURN_REGS[0],
-                        base_offset,
-                    );
-                    X86_64Assembler::mov_reg64_base32(
-                        buf,
-                        Self::GENERAL_RETURN_REGS[1],
-                        base_offset + 8,
-                    );
-                } else {
+                let (base_offset, _) = storage_manager.stack_offset_and_size(sym);
+                let Some(classes) = Self::classify(layout_interner, *layout) else {
                     internal_error!(
                         "types that don't return via arg pointer must be less than 16 bytes"
                     );
+                };
+
+                // Each eightbyte goes in the next free register of its class: rax and rdx for
+                // INTEGER, xmm0 and xmm1 for SSE.
+                let (mut general_i, mut float_i) = (0, 0);
+                for (i, class) in classes.iter().enumerate() {
+                    let offset = base_offset + 8 * i as i32;
+
+                    match class {
+                        SystemVClass::Integer => {
+                            let reg = Self::GENERAL_RETURN_REGS[general_i];
+                            X86_64Assembler::mov_reg64_base32(buf, reg, offset);
+                            general_i += 1;
+                        }
+                        SystemVClass::Sse => {
+                            let reg = Self::FLOAT_RETURN_REGS[float_i];
+                            X86_64Assembler::mov_freg64_base32(buf, reg, offset);
+                            float_i += 1;
+                        }
+                    }
                 }
             }
             _ => {
@@ -416,22 +464,30 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
                 storage_manager.no_data(sym);
             }
             _ if !Self::returns_via_arg_pointer(layout_interner, layout) => {
-                let size = layout_interner.stack_size(*layout);
-                let offset =
-                    storage_manager.claim_stack_area_layout(layout_interner, *sym, *layout);
-                if size <= 8 {
-                    X86_64Assembler::mov_base32_reg64(buf, offset, Self::GENERAL_RETURN_REGS[0]);
-                } else if size <= 16 {
-                    X86_64Assembler::mov_base32_reg64(buf, offset, Self::GENERAL_RETURN_REGS[0]);
-                    X86_64Assembler::mov_base32_reg64(
-                        buf,
-                        offset + 8,
-                        Self::GENERAL_RETURN_REGS[1],
-                    );
-                } else {
+                let Some(classes) = Self::classify(layout_interner, *layout) else {
                     internal_error!(
                         "types that don't return via arg pointer must be less than 16 bytes"
                     );
+                };
+                let base_offset =
+                    storage_manager.claim_stack_area_layout(layout_interner, *sym, *layout);
+
+                let (mut general_i, mut float_i) = (0, 0);
+                for (i, class) in classes.iter().enumerate() {
+                    let offset = base_offset + 8 * i as i32;
+
+                    match class {
+                        SystemVClass::Integer => {
+                            let reg = Self::GENERAL_RETURN_REGS[general_i];
+                            X86_64Assembler::mov_base32_reg64(buf, offset, reg);
+                            general_i += 1;
+                        }
+                        SystemVClass::Sse => {
+                            let reg = Self::FLOAT_RETURN_REGS[float_i];
+                            X86_64Assembler::mov_base32_freg64(buf, offset, reg);
+                            float_i += 1;
+                        }
+                    }
                 }
             }
             _ => {
@@ -488,7 +544,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         ASM::ret(buf)
     }
 
-    fn longjmp(buf: &mut Vec<'_, u8>) {
+    fn longjmp(buf: &mut Vec<'_, u8>, hardening: Hardening) {
         use X86_64GeneralReg::*;
         type ASM = X86_64Assembler;
 
@@ -519,10 +575,11 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         // value of rsp before the setjmp call
         ASM::mov_reg64_mem64_offset32(buf, RSP, env, 0x30);
 
-        jmp_reg64_offset8(buf, env, 0x38)
+        // rdx may hold the second return value, but r11 is free
+        jmp_mem64_offset8(buf, env, 0x38, R11, hardening)
     }
 
-    fn roc_panic(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>) {
+    fn roc_panic(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, hardening: Hardening) {
         use X86_64GeneralReg::*;
         type ASM = X86_64Assembler;
 
@@ -534,7 +591,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         ASM::add_reg64_reg64_imm32(buf, RDX, RSI, 1);
 
         // the setlongjmp_buffer
-        ASM::data_pointer(buf, relocs, String::from("setlongjmp_buffer"), RDI);
+        ASM::local_data_pointer(buf, relocs, String::from("setlongjmp_buffer"), RDI);
 
         // the value to return from the longjmp. It is a pointer to the last 3 words of the setlongjmp_buffer
         // they represent the errore message.
@@ -546,7 +603,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
             ASM::mov_mem64_offset32_reg64(buf, RSI, offset, R9);
         }
 
-        Self::longjmp(buf)
+        Self::longjmp(buf, hardening)
     }
 }
 
@@ -677,7 +734,7 @@ impl X64_64SystemVStoreArgs {
         layout_interner: &mut STLayoutInterner<'a>,
         sym: Symbol,
         in_layout: InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         type ASM = X86_64Assembler;
 
         // we use the return register as a temporary register; it will be overwritten anyway
@@ -713,14 +770,13 @@ impl X64_64SystemVStoreArgs {
             }
             _ if layout_interner.stack_size(in_layout) == 0 => {}
             _ if layout_interner.stack_size(in_layout) > 16 => {
-                // TODO: Double check this.
-                // Just copy onto the stack.
+                // The MEMORY class: copy the whole value onto the stack.
                 let stack_offset = self.tmp_stack_offset;
 
                 let size =
                     copy_symbol_to_stack_offset(buf, storage_manager, sym, tmp_reg, stack_offset);
 
-                self.tmp_stack_offset += size as i32;
+                self.tmp_stack_offset += size.next_multiple_of(8) as i32;
             }
             LayoutRepr::LambdaSet(lambda_set) => self.store_arg(
                 buf,
@@ -728,30 +784,64 @@ impl X64_64SystemVStoreArgs {
                 layout_interner,
                 sym,
                 lambda_set.runtime_representation(),
-            ),
-            LayoutRepr::Struct { .. } => {
-                let stack_size = layout_interner.stack_size(in_layout);
-                if stack_size <= 8 {
-                    self.store_arg_64bit(buf, storage_manager, sym);
-                } else if stack_size <= 16 {
-                    self.store_arg_128bit(buf, storage_manager, sym);
-                } else {
-                    unreachable!("covered by earlier branch");
+            )?,
+            LayoutRepr::Struct { .. } | LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
+                self.store_arg_eightbytes(buf, storage_manager, layout_interner, sym, in_layout);
+            }
+            _ => {
+                return Err(BackendError::UnsupportedLayout {
+                    layout: format!("{:?}", layout_interner.dbg(in_layout)),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn store_arg_eightbytes<'a>(
+        &mut self,
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut X86_64StorageManager<'a, '_, X86_64SystemV>,
+        layout_interner: &STLayoutInterner<'a>,
+        sym: Symbol,
+        in_layout: InLayout<'a>,
+    ) {
+        type ASM = X86_64Assembler;
+
+        let classes = X86_64SystemV::classify(layout_interner, in_layout)
+            .filter(|classes| X86_64SystemV::eightbytes_fit(classes, self.general_i, self.float_i));
+
+        match classes {
+            Some(classes) => {
+                let (base_offset, _) = storage_manager.stack_offset_and_size(&sym);
+
+                for (i, class) in classes.iter().enumerate() {
+                    let offset = base_offset + 8 * i as i32;
+
+                    match class {
+                        SystemVClass::Integer => {
+                            let reg = Self::GENERAL_PARAM_REGS[self.general_i];
+                            ASM::mov_reg64_base32(buf, reg, offset);
+                            self.general_i += 1;
+                        }
+                        SystemVClass::Sse => {
+                            let reg = Self::FLOAT_PARAM_REGS[self.float_i];
+                            ASM::mov_freg64_base32(buf, reg, offset);
+                            self.float_i += 1;
+                        }
+                    }
                 }
             }
-            LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
+            None => {
+                // Copy to stack using return reg as buffer.
+                let tmp_reg = Self::GENERAL_RETURN_REGS[0];
                 let stack_offset = self.tmp_stack_offset;
 
                 let size =
                     copy_symbol_to_stack_offset(buf, storage_manager, sym, tmp_reg, stack_offset);
 
-                self.tmp_stack_offset += size as i32;
-            }
-            _ => {
-                todo!(
-                    "calling with arg type, {:?}",
-                    layout_interner.dbg(in_layout)
-                );
+                // Every stack argument takes a multiple of 8 bytes.
+                self.tmp_stack_offset += size.next_multiple_of(8) as i32;
             }
         }
     }
@@ -801,66 +891,6 @@ impl X64_64SystemVStoreArgs {
             }
         }
     }
-
-    fn store_arg_64bit<'a>(
-        &mut self,
-        buf: &mut Vec<'a, u8>,
-        storage_manager: &mut X86_64StorageManager<'a, '_, X86_64SystemV>,
-        sym: Symbol,
-    ) {
-        type ASM = X86_64Assembler;
-
-        let (offset, _) = storage_manager.stack_offset_and_size(&sym);
-
-        match Self::GENERAL_PARAM_REGS.get(self.general_i) {
-            Some(reg) => {
-                ASM::mov_reg64_base32(buf, *reg, offset);
-
-                self.general_i += 1;
-            }
-            None => {
-                // Copy to stack using return reg as buffer.
-                let reg = X86_64GeneralReg::RAX;
-
-                ASM::mov_reg64_base32(buf, reg, offset);
-                ASM::mov_stack32_reg64(buf, self.tmp_stack_offset, reg);
-
-                self.tmp_stack_offset += 8;
-            }
-        }
-    }
-
-    fn store_arg_128bit<'a>(
-        &mut self,
-        buf: &mut Vec<'a, u8>,
-        storage_manager: &mut X86_64StorageManager<'a, '_, X86_64SystemV>,
-        sym: Symbol,
-    ) {
-        type ASM = X86_64Assembler;
-
-        let (offset, _) = storage_manager.stack_offset_and_size(&sym);
-
-        if self.general_i + 1 < Self::GENERAL_PARAM_REGS.len() {
-            let reg1 = Self::GENERAL_PARAM_REGS[self.general_i];
-            let reg2 = Self::GENERAL_PARAM_REGS[self.general_i + 1];
-
-            ASM::mov_reg64_base32(buf, reg1, offset);
-            ASM::mov_reg64_base32(buf, reg2, offset + 8);
-
-            self.general_i += 2;
-        } else {
-            // Copy to stack using return reg as buffer.
-            let reg = X86_64GeneralReg::RAX;
-
-            ASM::mov_reg64_base32(buf, reg, offset);
-            ASM::mov_stack32_reg64(buf, self.tmp_stack_offset, reg);
-
-            ASM::mov_reg64_base32(buf, reg, offset + 8);
-            ASM::mov_stack32_reg64(buf, self.tmp_stack_offset + 8, reg);
-
-            self.tmp_stack_offset += 16;
-        }
-    }
 }
 
 struct X64_64WindowsFastCallStoreArgs {
@@ -885,12 +915,9 @@ impl X64_64WindowsFastCallStoreArgs {
         layout_interner: &mut STLayoutInterner<'a>,
         sym: Symbol,
         in_layout: InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         type ASM = X86_64Assembler;
 
-        // we use the return register as a temporary register; it will be overwritten anyway
-        let tmp_reg = Self::GENERAL_RETURN_REGS[0];
-
         match layout_interner.get_repr(in_layout) {
             single_register_integers!() => self.store_arg_general(buf, storage_manager, sym),
             pointer_layouts!() => self.store_arg_general(buf, storage_manager, sym),
@@ -921,31 +948,7 @@ impl X64_64WindowsFastCallStoreArgs {
             }
             _ if layout_interner.stack_size(in_layout) == 0 => {}
             _ if layout_interner.stack_size(in_layout) > 16 => {
-                // Reference: https://learn.microsoft.com/en-us/cpp/build/x64-calling-convention?view=msvc-170#parameter-passing
-                match Self::GENERAL_PARAM_REGS.get(self.general_i) {
-                    Some(reg) => {
-                        // if there is a general purpose register available, use it to store a pointer to the value
-                        let (base_offset, _size) = storage_manager.stack_offset_and_size(&sym);
-
-                        ASM::add_reg64_reg64_imm32(buf, *reg, X86_64GeneralReg::RBP, base_offset);
-
-                        self.general_i += 1;
-                    }
-                    None => {
-                        // else, pass the value implicitly by copying to the stack (of the new frame)
-                        let stack_offset = self.tmp_stack_offset;
-
-                        let size = copy_symbol_to_stack_offset(
-                            buf,
-                            storage_manager,
-                            sym,
-                            tmp_reg,
-                            stack_offset,
-                        );
-
-                        self.tmp_stack_offset += size as i32;
-                    }
-                }
+                self.store_arg_by_reference(buf, storage_manager, sym);
             }
             LayoutRepr::LambdaSet(lambda_set) => self.store_arg(
                 buf,
@@ -953,29 +956,79 @@ impl X64_64WindowsFastCallStoreArgs {
                 layout_interner,
                 sym,
                 lambda_set.runtime_representation(),
-            ),
-            LayoutRepr::Struct { .. } => {
-                // for now, just also store this on the stack
-                let stack_offset = self.tmp_stack_offset;
+            )?,
+            LayoutRepr::Struct { .. } | LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
+                // Reference: https://learn.microsoft.com/en-us/cpp/build/x64-calling-convention?view=msvc-170#parameter-passing
+                if X86_64WindowsFastcall::passed_by_value(layout_interner, in_layout) {
+                    self.store_arg_64bit(buf, storage_manager, sym);
+                } else {
+                    self.store_arg_by_reference(buf, storage_manager, sym);
+                }
+            }
+            _ => {
+                return Err(BackendError::UnsupportedLayout {
+                    layout: format!("{:?}", layout_interner.dbg(in_layout)),
+                });
+            }
+        }
 
-                let size =
-                    copy_symbol_to_stack_offset(buf, storage_manager, sym, tmp_reg, stack_offset);
+        Ok(())
+    }
 
-                self.tmp_stack_offset += size as i32;
+    /// Passes a pointer to the value, in a register or on the stack.
+    fn store_arg_by_reference<'a>(
+        &mut self,
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut X86_64StorageManager<'a, '_, X86_64WindowsFastcall>,
+        sym: Symbol,
+    ) {
+        type ASM = X86_64Assembler;
+
+        // Roc values are immutable, so the callee can use ours rather than a copy.
+        let (base_offset, _size) = storage_manager.stack_offset_and_size(&sym);
+
+        match Self::GENERAL_PARAM_REGS.get(self.general_i) {
+            Some(reg) => {
+                ASM::add_reg64_reg64_imm32(buf, *reg, X86_64GeneralReg::RBP, base_offset);
+
+                self.general_i += 1;
             }
-            LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
-                let stack_offset = self.tmp_stack_offset;
+            None => {
+                // Copy the pointer to stack using return reg as buffer.
+                let tmp = Self::GENERAL_RETURN_REGS[0];
 
-                let size =
-                    copy_symbol_to_stack_offset(buf, storage_manager, sym, tmp_reg, stack_offset);
+                ASM::add_reg64_reg64_imm32(buf, tmp, X86_64GeneralReg::RBP, base_offset);
+                ASM::mov_stack32_reg64(buf, self.tmp_stack_offset, tmp);
 
-                self.tmp_stack_offset += size as i32;
+                self.tmp_stack_offset += 8;
             }
-            _ => {
-                todo!(
-                    "calling with arg type, {:?}",
-                    layout_interner.dbg(in_layout)
-                );
+        }
+    }
+
+    fn store_arg_64bit<'a>(
+        &mut self,
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut X86_64StorageManager<'a, '_, X86_64WindowsFastcall>,
+        sym: Symbol,
+    ) {
+        type ASM = X86_64Assembler;
+
+        let (offset, _) = storage_manager.stack_offset_and_size(&sym);
+
+        match Self::GENERAL_PARAM_REGS.get(self.general_i) {
+            Some(reg) => {
+                ASM::mov_reg64_base32(buf, *reg, offset);
+
+                self.general_i += 1;
+            }
+            None => {
+                // Copy to stack using return reg as buffer.
+                let reg = Self::GENERAL_RETURN_REGS[0];
+
+                ASM::mov_reg64_base32(buf, reg, offset);
+                ASM::mov_stack32_reg64(buf, self.tmp_stack_offset, reg);
+
+                self.tmp_stack_offset += 8;
             }
         }
     }
@@ -1044,7 +1097,7 @@ impl X64_64SystemVLoadArgs {
         layout_interner: &mut STLayoutInterner<'a>,
         sym: Symbol,
         in_layout: InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         let stack_size = layout_interner.stack_size(in_layout);
         match layout_interner.get_repr(in_layout) {
             single_register_integers!() => self.load_arg_general(storage_manager, sym),
@@ -1054,9 +1107,9 @@ impl X64_64SystemVLoadArgs {
                 storage_manager.no_data(&sym);
             }
             _ if stack_size > 16 => {
-                // TODO: Double check this.
+                // The MEMORY class: the caller copied the whole value onto the stack.
                 storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
-                self.argument_offset += stack_size as i32;
+                self.argument_offset += stack_size.next_multiple_of(8) as i32;
             }
             LayoutRepr::LambdaSet(lambda_set) => self.load_arg(
                 buf,
@@ -1064,27 +1117,9 @@ impl X64_64SystemVLoadArgs {
                 layout_interner,
                 sym,
                 lambda_set.runtime_representation(),
-            ),
-            LayoutRepr::Struct { .. } => {
-                if stack_size <= 8 {
-                    self.load_arg_general_64bit(
-                        buf,
-                        storage_manager,
-                        layout_interner,
-                        sym,
-                        in_layout,
-                    );
-                } else if stack_size <= 16 {
-                    self.load_arg_general_128bit(
-                        buf,
-                        storage_manager,
-                        layout_interner,
-                        sym,
-                        in_layout,
-                    );
-                } else {
-                    unreachable!("covered by an earlier branch")
-                }
+            )?,
+            LayoutRepr::Struct { .. } | LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
+                self.load_arg_eightbytes(buf, storage_manager, layout_interner, sym, in_layout);
             }
             LayoutRepr::Builtin(Builtin::Int(IntWidth::U128 | IntWidth::I128)) => {
                 self.load_arg_general_128bit(buf, storage_manager, layout_interner, sym, in_layout);
@@ -1092,18 +1127,14 @@ impl X64_64SystemVLoadArgs {
             LayoutRepr::Builtin(Builtin::Decimal) => {
                 self.load_arg_general_128bit(buf, storage_manager, layout_interner, sym, in_layout);
             }
-            LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
-                // for now, just also store this on the stack
-                storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
-                self.argument_offset += stack_size as i32;
-            }
             _ => {
-                todo!(
-                    "Loading args with layout {:?}",
-                    layout_interner.dbg(in_layout)
-                );
+                return Err(BackendError::UnsupportedLayout {
+                    layout: format!("{:?}", layout_interner.dbg(in_layout)),
+                });
             }
         }
+
+        Ok(())
     }
 
     fn load_arg_general(
@@ -1120,30 +1151,47 @@ impl X64_64SystemVLoadArgs {
         }
     }
 
-    fn load_arg_general_64bit(
+    fn load_arg_eightbytes<'a>(
         &mut self,
         buf: &mut Vec<u8>,
-        storage_manager: &mut X86_64StorageManager<'_, '_, X86_64SystemV>,
-        layout_interner: &mut STLayoutInterner<'_>,
+        storage_manager: &mut X86_64StorageManager<'a, '_, X86_64SystemV>,
+        layout_interner: &mut STLayoutInterner<'a>,
         sym: Symbol,
-        in_layout: InLayout<'_>,
+        in_layout: InLayout<'a>,
     ) {
         type ASM = X86_64Assembler;
 
-        let reg1 = X86_64SystemV::GENERAL_PARAM_REGS.get(self.general_i);
+        let classes = X86_64SystemV::classify(layout_interner, in_layout)
+            .filter(|classes| X86_64SystemV::eightbytes_fit(classes, self.general_i, self.float_i));
 
-        match reg1 {
-            Some(reg1) => {
-                let offset =
+        match classes {
+            Some(classes) => {
+                let base_offset =
                     storage_manager.claim_stack_area_layout(layout_interner, sym, in_layout);
 
-                ASM::mov_base32_reg64(buf, offset, *reg1);
-
-                self.general_i += 1;
+                for (i, class) in classes.iter().enumerate() {
+                    let offset = base_offset + 8 * i as i32;
+
+                    match class {
+                        SystemVClass::Integer => {
+                            let reg = X86_64SystemV::GENERAL_PARAM_REGS[self.general_i];
+                            ASM::mov_base32_reg64(buf, offset, reg);
+                            self.general_i += 1;
+                        }
+                        SystemVClass::Sse => {
+                            let reg = X86_64SystemV::FLOAT_PARAM_REGS[self.float_i];
+                            ASM::mov_base32_freg64(buf, offset, reg);
+                            self.float_i += 1;
+                        }
+                    }
+                }
             }
             None => {
-                storage_manager.complex_stack_arg(&sym, self.argument_offset, 8);
-                self.argument_offset += 8;
+                let stack_size = layout_interner.stack_size(in_layout);
+                storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
+
+                // Every stack argument takes a multiple of 8 bytes.
+                self.argument_offset += stack_size.next_multiple_of(8) as i32;
             }
         }
     }
@@ -1207,9 +1255,7 @@ impl X64_64WindowsFastCallLoadArgs {
         layout_interner: &mut STLayoutInterner<'a>,
         sym: Symbol,
         in_layout: InLayout<'a>,
-    ) {
-        type ASM = X86_64Assembler;
-
+    ) -> Result<(), BackendError> {
         let stack_size = layout_interner.stack_size(in_layout);
         match layout_interner.get_repr(in_layout) {
             single_register_integers!() => self.load_arg_general(storage_manager, sym),
@@ -1219,34 +1265,7 @@ impl X64_64WindowsFastCallLoadArgs {
                 storage_manager.no_data(&sym);
             }
             _ if stack_size > 16 => {
-                // Reference: https://learn.microsoft.com/en-us/cpp/build/x64-calling-convention?view=msvc-170#parameter-passing
-                match X86_64WindowsFastcall::GENERAL_PARAM_REGS.get(self.general_i) {
-                    Some(ptr_reg) => {
-                        // if there is a general purpose register available, use it to store a pointer to the value
-                        let base_offset = storage_manager.claim_stack_area_layout(
-                            layout_interner,
-                            sym,
-                            in_layout,
-                        );
-                        let tmp_reg = X86_64WindowsFastcall::GENERAL_RETURN_REGS[0];
-
-                        copy_to_base_offset::<_, _, ASM>(
-                            buf,
-                            base_offset,
-                            stack_size,
-                            *ptr_reg,
-                            tmp_reg,
-                            0,
-                        );
-
-                        self.general_i += 1;
-                    }
-                    None => {
-                        // else, pass the value implicitly by copying to the stack (of the new frame)
-                        storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
-                        self.argument_offset += stack_size as i32;
-                    }
-                }
+                self.load_arg_by_reference(buf, storage_manager, layout_interner, sym, in_layout);
             }
             LayoutRepr::LambdaSet(lambda_set) => self.load_arg(
                 buf,
@@ -1254,11 +1273,26 @@ impl X64_64WindowsFastCallLoadArgs {
                 layout_interner,
                 sym,
                 lambda_set.runtime_representation(),
-            ),
-            LayoutRepr::Struct { .. } => {
-                // for now, just also store this on the stack
-                storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
-                self.argument_offset += stack_size as i32;
+            )?,
+            LayoutRepr::Struct { .. } | LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
+                // Reference: https://learn.microsoft.com/en-us/cpp/build/x64-calling-convention?view=msvc-170#parameter-passing
+                if X86_64WindowsFastcall::passed_by_value(layout_interner, in_layout) {
+                    self.load_arg_general_64bit(
+                        buf,
+                        storage_manager,
+                        layout_interner,
+                        sym,
+                        in_layout,
+                    );
+                } else {
+                    self.load_arg_by_reference(
+                        buf,
+                        storage_manager,
+                        layout_interner,
+                        sym,
+                        in_layout,
+                    );
+                }
             }
             LayoutRepr::Builtin(Builtin::Int(IntWidth::U128 | IntWidth::I128)) => {
                 self.load_arg_general_128bit(buf, storage_manager, sym);
@@ -1266,18 +1300,14 @@ impl X64_64WindowsFastCallLoadArgs {
             LayoutRepr::Builtin(Builtin::Decimal) => {
                 self.load_arg_general_128bit(buf, storage_manager, sym);
             }
-            LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
-                // for now, just also store this on the stack
-                storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
-                self.argument_offset += stack_size as i32;
-            }
             _ => {
-                todo!(
-                    "Loading args with layout {:?}",
-                    layout_interner.dbg(in_layout)
-                );
+                return Err(BackendError::UnsupportedLayout {
+                    layout: format!("{:?}", layout_interner.dbg(in_layout)),
+                });
             }
         }
+
+        Ok(())
     }
 
     fn load_arg_general(
@@ -1294,6 +1324,64 @@ impl X64_64WindowsFastCallLoadArgs {
         }
     }
 
+    /// Copies a value that the caller passed a pointer to, in a register or on the stack.
+    fn load_arg_by_reference<'a>(
+        &mut self,
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut X86_64StorageManager<'a, '_, X86_64WindowsFastcall>,
+        layout_interner: &mut STLayoutInterner<'a>,
+        sym: Symbol,
+        in_layout: InLayout<'a>,
+    ) {
+        type ASM = X86_64Assembler;
+
+        let ptr_reg = match X86_64WindowsFastcall::GENERAL_PARAM_REGS.get(self.general_i) {
+            Some(ptr_reg) => {
+                self.general_i += 1;
+                *ptr_reg
+            }
+            None => {
+                // r11 is volatile and not used for arguments, so it is free at this point.
+                let ptr_reg = X86_64GeneralReg::R11;
+                ASM::mov_reg64_base32(buf, ptr_reg, self.argument_offset);
+                self.argument_offset += 8;
+                ptr_reg
+            }
+        };
+
+        let stack_size = layout_interner.stack_size(in_layout);
+        let base_offset = storage_manager.claim_stack_area_layout(layout_interner, sym, in_layout);
+        let tmp_reg = X86_64WindowsFastcall::GENERAL_RETURN_REGS[0];
+
+        copy_to_base_offset::<_, _, ASM>(buf, base_offset, stack_size, ptr_reg, tmp_reg, 0);
+    }
+
+    fn load_arg_general_64bit(
+        &mut self,
+        buf: &mut Vec<u8>,
+        storage_manager: &mut X86_64StorageManager<'_, '_, X86_64WindowsFastcall>,
+        layout_interner: &mut STLayoutInterner<'_>,
+        sym: Symbol,
+        in_layout: InLayout<'_>,
+    ) {
+        type ASM = X86_64Assembler;
+
+        match X86_64WindowsFastcall::GENERAL_PARAM_REGS.get(self.general_i) {
+            Some(reg) => {
+                let offset =
+                    storage_manager.claim_stack_area_layout(layout_interner, sym, in_layout);
+
+                ASM::mov_base32_reg64(buf, offset, *reg);
+
+                self.general_i += 1;
+            }
+            None => {
+                storage_manager.complex_stack_arg(&sym, self.argument_offset, 8);
+                self.argument_offset += 8;
+            }
+        }
+    }
+
     fn load_arg_general_128bit(
         &mut self,
         buf: &mut Vec<u8>,
@@ -1307,7 +1395,8 @@ impl X64_64WindowsFastCallLoadArgs {
 
         match (reg1, reg2) {
             (Some(reg1), Some(reg2)) => {
-                let offset = storage_manager.claim_stack_area_with_alignment(sym, 16, 16);
+                let alignment = storage_manager.target.i128_alignment_bytes() as u32;
+                let offset = storage_manager.claim_stack_area_with_alignment(sym, 16, alignment);
 
                 ASM::mov_base32_reg64(buf, offset, *reg1);
                 ASM::mov_base32_reg64(buf, offset + 8, *reg2);
@@ -1336,14 +1425,136 @@ impl X64_64WindowsFastCallLoadArgs {
     }
 }
 
-impl X86_64SystemV {
-    fn returns_via_arg_pointer<'a>(
-        interner: &STLayoutInterner<'a>,
-        ret_layout: &InLayout<'a>,
-    ) -> bool {
-        // TODO: This will need to be more complex/extended to fully support the calling convention.
-        // details here: https://github.com/hjl-tools/x86-psABI/wiki/x86-64-psABI-1.0.pdf
-        interner.stack_size(*ret_layout) > 16
+impl X86_64SystemV {
+    fn returns_via_arg_pointer<'a>(
+        interner: &STLayoutInterner<'a>,
+        ret_layout: &InLayout<'a>,
+    ) -> bool {
+        // TODO: This will need to be more complex/extended to fully support the calling convention.
+        // details here: https://github.com/hjl-tools/x86-psABI/wiki/x86-64-psABI-1.0.pdf
+        interner.stack_size(*ret_layout) as usize > interner.target().max_return_in_registers_size()
+    }
+
+    /// Classifies the eightbytes of a value per section 3.2.3 of the psABI. Returns `None` for
+    /// the MEMORY class: values bigger than 16 bytes, which are passed on the stack and returned
+    /// through a hidden pointer.
+    fn classify<'a>(
+        interner: &STLayoutInterner<'a>,
+        layout: InLayout<'a>,
+    ) -> Option<&'static [SystemVClass]> {
+        use SystemVClass::*;
+
+        let size = interner.stack_size(layout);
+        if size > 16 {
+            return None;
+        }
+
+        let mut classes = [None; 2];
+        classify_help(interner, layout, 0, &mut classes);
+
+        // An eightbyte that is only padding has no class; any register will do.
+        let [first, second] = classes.map(|class| class.unwrap_or(Integer));
+
+        let classes: &[SystemVClass] = match (size.div_ceil(8), first, second) {
+            (0, _, _) => &[],
+            (1, Integer, _) => &[Integer],
+            (1, Sse, _) => &[Sse],
+            (_, Integer, Integer) => &[Integer, Integer],
+            (_, Integer, Sse) => &[Integer, Sse],
+            (_, Sse, Integer) => &[Sse, Integer],
+            (_, Sse, Sse) => &[Sse, Sse],
+        };
+
+        Some(classes)
+    }
+
+    /// Whether a value with these eightbytes still fits in the parameter registers. If it does
+    /// not, the whole value goes on the stack, even when some registers are left.
+    fn eightbytes_fit(classes: &[SystemVClass], general_i: usize, float_i: usize) -> bool {
+        let general = classes
+            .iter()
+            .filter(|class| **class == SystemVClass::Integer)
+            .count();
+        let float = classes.len() - general;
+
+        general_i + general <= Self::GENERAL_PARAM_REGS.len()
+            && float_i + float <= Self::FLOAT_PARAM_REGS.len()
+    }
+}
+
+/// The class of one eightbyte of a value that System V passes in registers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum SystemVClass {
+    /// Passed in a general purpose register.
+    Integer,
+    /// Passed in an xmm register.
+    Sse,
+}
+
+fn classify_help<'a>(
+    interner: &STLayoutInterner<'a>,
+    layout: InLayout<'a>,
+    offset: u32,
+    classes: &mut [Option<SystemVClass>; 2],
+) {
+    let size = interner.stack_size(layout);
+
+    match interner.get_repr(layout) {
+        _ if size == 0 => {}
+        LayoutRepr::Builtin(Builtin::Float(_)) => {
+            merge_class(classes, SystemVClass::Sse, offset, size);
+        }
+        LayoutRepr::Struct(field_layouts) => {
+            let mut field_offset = offset;
+            for field_layout in field_layouts {
+                classify_help(interner, *field_layout, field_offset, classes);
+                field_offset += interner.stack_size(*field_layout);
+            }
+        }
+        LayoutRepr::Union(union_layout @ UnionLayout::NonRecursive(tags)) => {
+            // Like a C struct with a union of the payloads, followed by the tag id.
+            for field_layouts in tags {
+                let mut field_offset = offset;
+                for field_layout in field_layouts.iter() {
+                    classify_help(interner, *field_layout, field_offset, classes);
+                    field_offset += interner.stack_size(*field_layout);
+                }
+            }
+
+            if let Some(tag_id_offset) = union_layout.tag_id_offset(interner) {
+                // The tag id is aligned, so its first byte is in the same eightbyte as the rest.
+                merge_class(classes, SystemVClass::Integer, offset + tag_id_offset, 1);
+            }
+        }
+        LayoutRepr::LambdaSet(lambda_set) => {
+            classify_help(
+                interner,
+                lambda_set.runtime_representation(),
+                offset,
+                classes,
+            );
+        }
+        _ => {
+            // Integers, pointers and anything built from them.
+            merge_class(classes, SystemVClass::Integer, offset, size);
+        }
+    }
+}
+
+/// Merges the class of a field into every eightbyte it overlaps. INTEGER wins over SSE.
+fn merge_class(
+    classes: &mut [Option<SystemVClass>; 2],
+    class: SystemVClass,
+    offset: u32,
+    size: u32,
+) {
+    for eightbyte in (offset / 8)..=((offset + size - 1) / 8) {
+        let merged = &mut classes[eightbyte as usize];
+
+        *merged = match (*merged, class) {
+            (Some(SystemVClass::Integer), _) => Some(SystemVClass::Integer),
+            _ => Some(class),
+        };
     }
 }
 
@@ -1461,7 +1672,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
         saved_float_regs: &[X86_64FloatReg],
         requested_stack_size: i32,
         fn_call_stack_size: i32,
-    ) -> i32 {
+    ) -> Result<i32, BackendError> {
         x86_64_generic_setup_stack(
             buf,
             saved_general_regs,
@@ -1488,6 +1699,25 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
         )
     }
 
+    #[inline(always)]
+    fn unwind_rows(
+        rows: &mut Vec<'_, UnwindRow>,
+        frame: FrameOffsets,
+        saved_general_regs: &[X86_64GeneralReg],
+        saved_float_regs: &[X86_64FloatReg],
+        aligned_stack_size: i32,
+        fn_call_stack_size: i32,
+    ) {
+        x86_64_generic_unwind_rows(
+            rows,
+            frame,
+            saved_general_regs,
+            saved_float_regs,
+            aligned_stack_size,
+            fn_call_stack_size,
+        )
+    }
+
     #[inline(always)]
     fn load_args<'a>(
         buf: &mut Vec<'a, u8>,
@@ -1495,7 +1725,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
         layout_interner: &mut STLayoutInterner<'a>,
         args: &'a [(InLayout<'a>, Symbol)],
         ret_layout: &InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         let returns_via_pointer =
             X86_64WindowsFastcall::returns_via_arg_pointer(layout_interner, ret_layout);
 
@@ -1511,8 +1741,10 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
         }
 
         for (in_layout, sym) in args.iter() {
-            state.load_arg(buf, storage_manager, layout_interner, *sym, *in_layout);
+            state.load_arg(buf, storage_manager, layout_interner, *sym, *in_layout)?;
         }
+
+        Ok(())
     }
 
     #[inline(always)]
@@ -1531,7 +1763,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
         args: &[Symbol],
         arg_layouts: &[InLayout<'a>],
         ret_layout: &InLayout<'a>,
-    ) {
+    ) -> Result<(), BackendError> {
         let mut general_i = 0;
 
         if Self::returns_via_arg_pointer(layout_interner, ret_layout) {
@@ -1557,10 +1789,12 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
         };
 
         for (sym, in_layout) in args.iter().zip(arg_layouts.iter()) {
-            state.store_arg(buf, storage_manager, layout_interner, *sym, *in_layout);
+            state.store_arg(buf, storage_manager, layout_interner, *sym, *in_layout)?;
         }
 
         storage_manager.update_fn_call_stack_size(state.tmp_stack_offset as u32);
+
+        Ok(())
     }
 
     fn return_complex_symbol<'a>(
@@ -1751,11 +1985,11 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
         ASM::ret(buf)
     }
 
-    fn longjmp(_buf: &mut Vec<'_, u8>) {
+    fn longjmp(_buf: &mut Vec<'_, u8>, _hardening: Hardening) {
         // do nothing, longjmp is part of roc_panic
     }
 
-    fn roc_panic(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>) {
+    fn roc_panic(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, hardening: Hardening) {
         use X86_64GeneralReg::*;
         type ASM = X86_64Assembler;
 
@@ -1773,7 +2007,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
 
         // the setlongjmp_buffer
         let env = R8;
-        ASM::data_pointer(buf, relocs, String::from("setlongjmp_buffer"), env);
+        ASM::local_data_pointer(buf, relocs, String::from("setlongjmp_buffer"), env);
 
         // move the roc_str bytes into the setlongjmp_buffer
         for offset in [0, 8, 16] {
@@ -1810,7 +2044,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Windo
         // write the panic tag (now in R10) into the result_pointer
         ASM::mov_mem64_offset32_reg64(buf, result_pointer, 0x08, R10);
 
-        jmp_reg64_offset8(buf, env, 0x50)
+        jmp_mem64_offset8(buf, env, 0x50, R11, hardening)
     }
 }
 
@@ -1823,9 +2057,21 @@ impl X86_64WindowsFastcall {
         // details here: https://docs.microsoft.com/en-us/cpp/build/x64-calling-convention?view=msvc-160#return-values
         match *ret_layout {
             Layout::I128 | Layout::U128 => false,
-            _ => interner.stack_size(*ret_layout) > 8,
+            _ => {
+                let size = interner.stack_size(*ret_layout);
+
+                // Only values that fit a register exactly are returned in rax.
+                size as usize > interner.target().max_return_in_registers_size()
+                    || !(size == 0 || size.is_power_of_two())
+            }
         }
     }
+
+    /// Whether a struct or tag union argument is passed by value. Only those of 1, 2, 4 or 8
+    /// bytes are; everything else is passed as a pointer to the value.
+    fn passed_by_value<'a>(interner: &STLayoutInterner<'a>, layout: InLayout<'a>) -> bool {
+        matches!(interner.stack_size(layout), 1 | 2 | 4 | 8)
+    }
 }
 
 #[inline(always)]
@@ -1835,17 +2081,20 @@ fn x86_64_generic_setup_stack(
     saved_float_regs: &[X86_64FloatReg],
     requested_stack_size: i32,
     fn_call_stack_size: i32,
-) -> i32 {
+) -> Result<i32, BackendError> {
     X86_64Assembler::push_reg64(buf, X86_64GeneralReg::RBP);
     X86_64Assembler::mov_reg64_reg64(buf, X86_64GeneralReg::RBP, X86_64GeneralReg::RSP);
 
-    let full_stack_size = match requested_stack_size
-        .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32)
-        .and_then(|size| size.checked_add(fn_call_stack_size))
-    {
-        Some(size) => size,
-        _ => internal_error!("Ran out of stack space"),
+    let saved_regs_size = 8 * (saved_general_regs.len() + saved_float_regs.len()) as i32;
+    let stack_too_large = || BackendError::StackTooLarge {
+        requested: requested_stack_size as i64 + saved_regs_size as i64 + fn_call_stack_size as i64,
+        max: i32::MAX as i64,
     };
+
+    let full_stack_size = requested_stack_size
+        .checked_add(saved_regs_size)
+        .and_then(|size| size.checked_add(fn_call_stack_size))
+        .ok_or_else(stack_too_large)?;
     let alignment = if full_stack_size <= 0 {
         0
     } else {
@@ -1856,31 +2105,135 @@ fn x86_64_generic_setup_stack(
     } else {
         STACK_ALIGNMENT - alignment as u8
     };
-    if let Some(aligned_stack_size) = full_stack_size.checked_add(offset as i32) {
-        if aligned_stack_size > 0 {
-            X86_64Assembler::sub_reg64_reg64_imm32(
-                buf,
-                X86_64GeneralReg::RSP,
-                X86_64GeneralReg::RSP,
-                aligned_stack_size,
-            );
+    let aligned_stack_size = full_stack_size
+        .checked_add(offset as i32)
+        .ok_or_else(stack_too_large)?;
+    if aligned_stack_size > 0 {
+        x86_64_allocate_stack(buf, aligned_stack_size);
 
-            // Put values at the top of the stack to avoid conflicts with previously saved variables.
-            let mut offset = aligned_stack_size - fn_call_stack_size;
-            for reg in saved_general_regs {
-                X86_64Assembler::mov_base32_reg64(buf, -offset, *reg);
-                offset -= 8;
-            }
-            for reg in saved_float_regs {
-                X86_64Assembler::mov_base32_freg64(buf, -offset, *reg);
-                offset -= 8;
-            }
-            aligned_stack_size
-        } else {
-            0
+        // Put values at the top of the stack to avoid conflicts with previously saved variables.
+        let mut offset = aligned_stack_size - fn_call_stack_size;
+        for reg in saved_general_regs {
+            X86_64Assembler::mov_base32_reg64(buf, -offset, *reg);
+            offset -= 8;
+        }
+        for reg in saved_float_regs {
+            X86_64Assembler::mov_base32_freg64(buf, -offset, *reg);
+            offset -= 8;
         }
+        Ok(aligned_stack_size)
     } else {
-        internal_error!("Ran out of stack space");
+        Ok(0)
+    }
+}
+
+/// `push rbp; mov rbp, rsp` starts every frame, and rbp is left alone until the `pop rbp` right
+/// before the `ret`, so everywhere in between the canonical frame address is found through rbp.
+#[inline(always)]
+fn x86_64_generic_unwind_rows(
+    rows: &mut Vec<'_, UnwindRow>,
+    frame: FrameOffsets,
+    saved_general_regs: &[X86_64GeneralReg],
+    saved_float_regs: &[X86_64FloatReg],
+    aligned_stack_size: i32,
+    fn_call_stack_size: i32,
+) {
+    use X86_64GeneralReg::*;
+
+    // After the 1 byte `push rbp`.
+    let pushed = frame.prologue + 1;
+    rows.push(UnwindRow {
+        offset: pushed,
+        rule: UnwindRule::Cfa {
+            register: RSP.dwarf_number(),
+            offset: 16,
+        },
+    });
+    rows.push(UnwindRow {
+        offset: pushed,
+        rule: UnwindRule::SavedAt {
+            register: RBP.dwarf_number(),
+            offset: -16,
+        },
+    });
+
+    // After the 3 byte `mov rbp, rsp`.
+    rows.push(UnwindRow {
+        offset: pushed + 3,
+        rule: UnwindRule::Cfa {
+            register: RBP.dwarf_number(),
+            offset: 16,
+        },
+    });
+
+    if aligned_stack_size > 0 {
+        // After the stack is allocated. Large frames are allocated a page at a time, and only
+        // count as allocated once the last page is.
+        let arena = bumpalo::Bump::new();
+        let mut allocation = bumpalo::vec![in &arena];
+        x86_64_allocate_stack(&mut allocation, aligned_stack_size);
+        rows.push(UnwindRow {
+            offset: pushed + 3 + allocation.len() as u32,
+            rule: UnwindRule::StackPointer {
+                offset: 16 + aligned_stack_size,
+            },
+        });
+
+        // Mirrors the stores at the end of `x86_64_generic_setup_stack`. The return address and
+        // the saved rbp sit between the canonical frame address and rbp.
+        let saved_regs = saved_general_regs
+            .iter()
+            .map(RegTrait::dwarf_number)
+            .chain(saved_float_regs.iter().map(RegTrait::dwarf_number));
+        let mut offset = aligned_stack_size - fn_call_stack_size;
+        for register in saved_regs {
+            rows.push(UnwindRow {
+                offset: frame.body,
+                rule: UnwindRule::SavedAt {
+                    register,
+                    offset: -16 - offset,
+                },
+            });
+            offset -= 8;
+        }
+    }
+
+    // After `pop rbp`, only the return address is left on the stack.
+    rows.push(UnwindRow {
+        offset: frame.ret,
+        rule: UnwindRule::Cfa {
+            register: RSP.dwarf_number(),
+            offset: 8,
+        },
+    });
+}
+
+/// Moves the stack pointer down by size bytes. Large frames touch every page on the way down, so
+/// that the guard page below the stack is hit rather than skipped: Windows only commits more stack
+/// when its guard page is touched, and on Linux we could silently land in another mapping.
+#[inline(always)]
+fn x86_64_allocate_stack(buf: &mut Vec<'_, u8>, size: i32) {
+    use X86_64GeneralReg::*;
+
+    if size <= PAGE_SIZE {
+        sub_reg64_imm32(buf, RSP, size);
+        return;
+    }
+
+    // r11 is a scratch register that is never used for arguments
+    mov_reg64_imm64(buf, R11, (size / PAGE_SIZE) as i64);
+
+    let loop_start = buf.len();
+    sub_reg64_imm32(buf, RSP, PAGE_SIZE);
+    mov_base64_offset32_reg64(buf, RSP, 0, R11);
+    sub_reg64_imm32(buf, R11, 1);
+
+    // the jne is 6 bytes, and jumps are relative to the end of the instruction
+    jne_imm32(buf, loop_start as i32 - (buf.len() + 6) as i32);
+
+    let remainder = size % PAGE_SIZE;
+    if remainder > 0 {
+        sub_reg64_imm32(buf, RSP, remainder);
     }
 }
 
@@ -2058,6 +2411,35 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         }
     }
 
+    #[inline(always)]
+    fn indirect_branch_target(buf: &mut Vec<'_, u8>) {
+        endbr64(buf);
+    }
+
+    #[inline(always)]
+    fn atomic_add_mem64_imm32(
+        buf: &mut Vec<'_, u8>,
+        ptr: X86_64GeneralReg,
+        offset: i32,
+        imm32: i32,
+    ) {
+        lock_add_base64_offset32_imm32(buf, ptr, offset, imm32);
+    }
+
+    #[inline(always)]
+    fn atomic_fetch_add_reg64_mem64_offset32(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64GeneralReg,
+        ptr: X86_64GeneralReg,
+        offset: i32,
+        src: X86_64GeneralReg,
+    ) {
+        debug_assert_ne!(dst, ptr);
+
+        mov_reg64_reg64(buf, dst, src);
+        lock_xadd_base64_offset32_reg64(buf, ptr, offset, dst);
+    }
+
     #[inline(always)]
     fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String) {
         buf.extend([0xE8, 0x00, 0x00, 0x00, 0x00]);
@@ -2074,7 +2456,7 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         fn_name: String,
         dst: X86_64GeneralReg,
     ) {
-        lea_reg64(buf, dst);
+        lea_reg64_rip_offset32(buf, dst, 0);
 
         relocs.push(Relocation::LinkedFunction {
             offset: buf.len() as u64 - 4,
@@ -2089,7 +2471,7 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         fn_name: String,
         dst: X86_64GeneralReg,
     ) {
-        lea_reg64(buf, dst);
+        lea_reg64_rip_offset32(buf, dst, 0);
 
         relocs.push(Relocation::LinkedData {
             offset: buf.len() as u64 - 4,
@@ -2101,6 +2483,21 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         X86_64Assembler::mov_reg64_mem64_offset32(buf, dst, dst, 0);
     }
 
+    #[inline(always)]
+    fn local_data_pointer(
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        data_name: String,
+        dst: X86_64GeneralReg,
+    ) {
+        lea_reg64_rip_offset32(buf, dst, 0);
+
+        relocs.push(Relocation::LocalDataAddress {
+            offset: buf.len() as u64 - 4,
+            name: data_name,
+        });
+    }
+
     #[inline(always)]
     fn imul_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
@@ -2781,6 +3178,16 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         ret(buf);
     }
 
+    #[inline(always)]
+    fn breakpoint(buf: &mut Vec<'_, u8>) {
+        int3(buf);
+    }
+
+    #[inline(always)]
+    fn trap(buf: &mut Vec<'_, u8>) {
+        ud2(buf);
+    }
+
     fn set_if_overflow(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg) {
         seto_reg64(buf, dst);
     }
@@ -3429,6 +3836,46 @@ fn jmp_imm32(buf: &mut Vec<'_, u8>, imm: i32) {
     buf.extend(imm.to_le_bytes());
 }
 
+/// Jump to the address stored at base + offset. Uses a retpoline if the hardening options ask for
+/// it, in which case the address is first loaded into scratch.
+#[inline(always)]
+fn jmp_mem64_offset8(
+    buf: &mut Vec<'_, u8>,
+    base: X86_64GeneralReg,
+    offset: i8,
+    scratch: X86_64GeneralReg,
+    hardening: Hardening,
+) {
+    if hardening.needs_direct_jumps() {
+        mov_reg64_base64_offset32(buf, scratch, base, offset as i32);
+        retpoline_jmp_reg64(buf, scratch);
+    } else {
+        jmp_reg64_offset8(buf, base, offset);
+    }
+}
+
+/// `ENDBR64` -> Terminate an indirect branch in 64-bit mode, a valid target under Intel CET.
+#[inline(always)]
+fn endbr64(buf: &mut Vec<'_, u8>) {
+    buf.extend([0xF3, 0x0F, 0x1E, 0xFA]);
+}
+
+/// Jump to the address in target with a retpoline: the indirect jump becomes a `ret` to a
+/// patched return address, and any speculation of that `ret` is captured in a `pause` loop.
+/// `call 1f; 2: pause; lfence; jmp 2b; 1: mov [rsp], target; ret`
+#[inline(always)]
+fn retpoline_jmp_reg64(buf: &mut Vec<'_, u8>, target: X86_64GeneralReg) {
+    // call over the speculation trap, which is 7 bytes long
+    buf.extend([0xE8, 0x07, 0x00, 0x00, 0x00]);
+
+    // pause; lfence; jmp back to the pause
+    buf.extend([0xF3, 0x90, 0x0F, 0xAE, 0xE8, 0xEB, 0xF9]);
+
+    // overwrite the return address pushed by the call
+    mov_base64_offset32_reg64(buf, X86_64GeneralReg::RSP, 0, target);
+    ret(buf);
+}
+
 #[inline(always)]
 fn jmp_reg64_offset8(buf: &mut Vec<'_, u8>, base: X86_64GeneralReg, offset: i8) {
     let rex = add_rm_extension(base, REX_W);
@@ -3477,23 +3924,16 @@ fn mov_reg64_imm64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, imm: i64) {
     }
 }
 
-/// `LEA r64, m` -> Store effective address for m in register r64.
+/// `LEA r64, m` -> Store effective address for m in register r64, where m is RIP + offset.
 #[inline(always)]
-fn lea_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg) {
+fn lea_reg64_rip_offset32(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, offset: i32) {
     let rex = add_opcode_extension(dst, REX_W);
     let rex = add_reg_extension(dst, rex);
     let dst_mod = dst as u8 % 8;
 
     #[allow(clippy::unusual_byte_groupings)]
-    buf.extend([
-        rex,
-        0x8d,
-        0b00_000_101 | (dst_mod << 3),
-        0x00,
-        0x00,
-        0x00,
-        0x00,
-    ])
+    buf.extend([rex, 0x8d, 0b00_000_101 | (dst_mod << 3)]);
+    buf.extend(offset.to_le_bytes());
 }
 
 /// `LEA r64, m` -> Store effective address for m in register r64.
@@ -3522,6 +3962,52 @@ fn lea_reg64_offset8(
     buf.push(offset as u8);
 }
 
+/// Asserts LOCK# while executing the following instruction, making its read-modify-write atomic.
+const LOCK: u8 = 0xF0;
+
+/// `LOCK ADD r/m64, imm32` -> Atomically add imm32 sign-extended to 64-bits to r/m64,
+/// where m64 references a base + offset.
+#[inline(always)]
+fn lock_add_base64_offset32_imm32(
+    buf: &mut Vec<'_, u8>,
+    base: X86_64GeneralReg,
+    offset: i32,
+    imm: i32,
+) {
+    let rex = add_rm_extension(base, REX_W);
+    let base_mod = base as u8 % 8;
+    buf.reserve(13);
+    buf.extend([LOCK, rex, 0x81, 0x80 | base_mod]);
+    // Using RSP or R12 requires a secondary index byte.
+    if base == X86_64GeneralReg::RSP || base == X86_64GeneralReg::R12 {
+        buf.push(0x24);
+    }
+    buf.extend(offset.to_le_bytes());
+    buf.extend(imm.to_le_bytes());
+}
+
+/// `LOCK XADD r/m64, r64` -> Atomically exchange r64 and r/m64; load their sum into r/m64.
+/// m64 references a base + offset. Afterwards, r64 holds the previous value of r/m64.
+#[inline(always)]
+fn lock_xadd_base64_offset32_reg64(
+    buf: &mut Vec<'_, u8>,
+    base: X86_64GeneralReg,
+    offset: i32,
+    src: X86_64GeneralReg,
+) {
+    let rex = add_rm_extension(base, REX_W);
+    let rex = add_reg_extension(src, rex);
+    let src_mod = (src as u8 % 8) << 3;
+    let base_mod = base as u8 % 8;
+    buf.reserve(10);
+    buf.extend([LOCK, rex, 0x0F, 0xC1, 0x80 | src_mod | base_mod]);
+    // Using RSP or R12 requires a secondary index byte.
+    if base == X86_64GeneralReg::RSP || base == X86_64GeneralReg::R12 {
+        buf.push(0x24);
+    }
+    buf.extend(offset.to_le_bytes());
+}
+
 fn raw_mov_reg_reg(
     buf: &mut Vec<'_, u8>,
     register_width: RegisterWidth,
@@ -4290,6 +4776,18 @@ fn ret(buf: &mut Vec<'_, u8>) {
     buf.push(0xC3);
 }
 
+/// `INT3` -> Trap to the debugger.
+#[inline(always)]
+fn int3(buf: &mut Vec<'_, u8>) {
+    buf.push(0xCC);
+}
+
+/// `UD2` -> Raise an invalid opcode exception.
+#[inline(always)]
+fn ud2(buf: &mut Vec<'_, u8>) {
+    buf.extend([0x0F, 0x0B]);
+}
+
 /// `SUB r/m64, imm32` -> Subtract imm32 sign-extended to 64-bits from r/m64.
 #[inline(always)]
 fn sub_reg64_imm32(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, imm: i32) {
@@ -4716,6 +5214,212 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_endbr64() {
+        disassembler_test!(endbr64, || "endbr64");
+    }
+
+    #[test]
+    fn test_retpoline_jmp_reg64() {
+        disassembler_test!(
+            retpoline_jmp_reg64,
+            |reg| format!("call 0xc\npause\nlfence\njmp 5\nmov qword ptr [rsp], {reg}\nret"),
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_allocate_stack() {
+        disassembler_test!(
+            x86_64_allocate_stack,
+            |size| match size {
+                0x2800 => [
+                    "mov r11, 2",
+                    "sub rsp, 0x1000",
+                    "mov qword ptr [rsp], r11",
+                    "sub r11, 1",
+                    "jne 7",
+                    "sub rsp, 0x800",
+                ]
+                .join("\n"),
+                _ => format!("sub rsp, 0x{size:x}"),
+            },
+            [0x10, 0x1000, 0x2800]
+        );
+    }
+
+    #[test]
+    fn test_setup_stack_too_large() {
+        let arena = bumpalo::Bump::new();
+        let mut buf = bumpalo::vec![in &arena];
+
+        let result =
+            x86_64_generic_setup_stack(&mut buf, &[X86_64GeneralReg::RBX], &[], i32::MAX - 4, 0);
+
+        assert_eq!(
+            result,
+            Err(BackendError::StackTooLarge {
+                requested: i32::MAX as i64 + 4,
+                max: i32::MAX as i64,
+            })
+        );
+    }
+
+    #[test]
+    fn test_store_args_unsupported_layout() {
+        use crate::generic64::storage::new_storage_manager;
+        use crate::{AssemblyBackendMode, Env};
+        use roc_module::symbol::ModuleId;
+        use roc_target::Target;
+
+        let arena = bumpalo::Bump::new();
+        let mut buf = bumpalo::vec![in &arena];
+
+        let env = Env {
+            arena: &arena,
+            module_id: ModuleId::ATTR,
+            exposed_to_host: Default::default(),
+            lazy_literals: false,
+            mode: AssemblyBackendMode::Test,
+            atomic_refcounts: false,
+            hardening: Default::default(),
+            stack_maps: false,
+        };
+        let mut storage_manager = new_storage_manager::<_, _, X86_64Assembler, X86_64WindowsFastcall>(
+            &env,
+            Target::WinX64,
+        );
+        let mut interner = STLayoutInterner::with_capacity(4, Target::WinX64);
+
+        // Windows fastcall has no rule for passing a Dec yet
+        let result = X86_64WindowsFastcall::store_args(
+            &mut buf,
+            &mut storage_manager,
+            &mut interner,
+            &Symbol::DEV_TMP,
+            &[Symbol::DEV_TMP2],
+            &[Layout::DEC],
+            &Layout::UNIT,
+        );
+
+        assert!(
+            matches!(result, Err(BackendError::UnsupportedLayout { .. })),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_returns_via_arg_pointer_agrees_with_target() {
+        use roc_target::Target;
+
+        let arena = bumpalo::Bump::new();
+
+        for target in [Target::LinuxX64, Target::MacX64, Target::WinX64] {
+            let mut interner = STLayoutInterner::with_capacity(4, target);
+            let max = target.max_return_in_registers_size();
+
+            let mut words = |n| {
+                interner.insert_direct_no_semantic(LayoutRepr::Struct(
+                    arena.alloc_slice_fill_copy(n, Layout::U64),
+                ))
+            };
+            let fits = words(max / 8);
+            let too_big = words(max / 8 + 1);
+
+            let (via_arg_pointer, return_regs): (fn(_, _) -> _, _) = match target {
+                Target::WinX64 => (
+                    X86_64WindowsFastcall::returns_via_arg_pointer,
+                    X86_64WindowsFastcall::GENERAL_RETURN_REGS,
+                ),
+                _ => (
+                    X86_64SystemV::returns_via_arg_pointer,
+                    X86_64SystemV::GENERAL_RETURN_REGS,
+                ),
+            };
+
+            assert!(!via_arg_pointer(&interner, &fits), "{target}");
+            assert!(via_arg_pointer(&interner, &too_big), "{target}");
+            assert!(max <= return_regs.len() * 8, "{target}");
+        }
+    }
+
+    #[test]
+    fn test_system_v_classify() {
+        use roc_target::Target;
+        use SystemVClass::*;
+
+        let arena = bumpalo::Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, Target::LinuxX64);
+
+        let mut classify = |repr| {
+            let layout = interner.insert_direct_no_semantic(repr);
+            X86_64SystemV::classify(&interner, layout)
+        };
+        let mut struct_of = |fields: &[InLayout<'static>]| {
+            classify(LayoutRepr::Struct(arena.alloc_slice_copy(fields)))
+        };
+
+        assert_eq!(
+            struct_of(&[Layout::F64, Layout::F64]),
+            Some(&[Sse, Sse][..])
+        );
+        assert_eq!(
+            struct_of(&[Layout::U64, Layout::F64]),
+            Some(&[Integer, Sse][..])
+        );
+        assert_eq!(struct_of(&[Layout::F32, Layout::F32]), Some(&[Sse][..]));
+        assert_eq!(struct_of(&[Layout::F32, Layout::U32]), Some(&[Integer][..]));
+        assert_eq!(
+            struct_of(&[Layout::F64, Layout::U8]),
+            Some(&[Sse, Integer][..])
+        );
+        assert_eq!(struct_of(&[Layout::U128]), Some(&[Integer, Integer][..]));
+        assert_eq!(struct_of(&[Layout::F64, Layout::F64, Layout::F64]), None);
+        assert_eq!(struct_of(&[Layout::STR]), None);
+
+        // [A F64, B F64] is the payload in the first eightbyte, and the tag id in the second.
+        let tags: &[&[InLayout]] = arena.alloc_slice_copy(&[&[Layout::F64][..], &[Layout::F64]]);
+        assert_eq!(
+            classify(LayoutRepr::Union(UnionLayout::NonRecursive(tags))),
+            Some(&[Sse, Integer][..])
+        );
+
+        assert!(X86_64SystemV::eightbytes_fit(&[Integer, Sse], 5, 7));
+        assert!(!X86_64SystemV::eightbytes_fit(&[Integer, Integer], 5, 0));
+        assert!(!X86_64SystemV::eightbytes_fit(&[Sse, Sse], 0, 7));
+    }
+
+    #[test]
+    fn test_windows_fastcall_aggregates() {
+        use roc_target::Target;
+
+        let arena = bumpalo::Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, Target::WinX64);
+
+        let mut bytes = |n| {
+            interner.insert_direct_no_semantic(LayoutRepr::Struct(
+                arena.alloc_slice_fill_copy(n, Layout::U8),
+            ))
+        };
+        let four = bytes(4);
+        let three = bytes(3);
+        let twelve = bytes(12);
+
+        assert!(X86_64WindowsFastcall::passed_by_value(&interner, four));
+        assert!(!X86_64WindowsFastcall::passed_by_value(&interner, three));
+        assert!(!X86_64WindowsFastcall::passed_by_value(&interner, twelve));
+
+        assert!(!X86_64WindowsFastcall::returns_via_arg_pointer(
+            &interner, &four
+        ));
+        assert!(X86_64WindowsFastcall::returns_via_arg_pointer(
+            &interner, &three
+        ));
+        assert!(X86_64WindowsFastcall::returns_via_arg_pointer(
+            &interner, &twelve
+        ));
+    }
+
     #[test]
     fn test_jne_imm32() {
         const INST_SIZE: i32 = 6;
@@ -4753,11 +5457,12 @@ mod tests {
     }
 
     #[test]
-    fn test_lea_reg64() {
+    fn test_lea_reg64_rip_offset32() {
         disassembler_test!(
-            lea_reg64,
-            |reg| format!("lea {reg}, [rip]"),
-            ALL_GENERAL_REGS
+            lea_reg64_rip_offset32,
+            |reg, offset| format!("lea {reg}, [rip + 0x{offset:x}]"),
+            ALL_GENERAL_REGS,
+            [TEST_I32]
         );
     }
 
@@ -4778,6 +5483,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lock_add_base64_offset32_imm32() {
+        disassembler_test!(
+            lock_add_base64_offset32_imm32,
+            |base, offset, imm| format!("lock add qword ptr [{base} + 0x{offset:x}], 0x{imm:x}"),
+            ALL_GENERAL_REGS,
+            [TEST_I32],
+            [TEST_I32]
+        );
+    }
+
+    #[test]
+    fn test_lock_xadd_base64_offset32_reg64() {
+        disassembler_test!(
+            lock_xadd_base64_offset32_reg64,
+            |base, offset, src| format!("lock xadd qword ptr [{base} + 0x{offset:x}], {src}"),
+            ALL_GENERAL_REGS,
+            [TEST_I32],
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_mov_reg64_reg64() {
         disassembler_test!(
@@ -5189,6 +5916,16 @@ mod tests {
         disassembler_test!(ret, || "ret");
     }
 
+    #[test]
+    fn test_int3() {
+        disassembler_test!(int3, || "int3");
+    }
+
+    #[test]
+    fn test_ud2() {
+        disassembler_test!(ud2, || "ud2");
+    }
+
     #[test]
     fn test_sub_reg64_imm32() {
         disassembler_test!(
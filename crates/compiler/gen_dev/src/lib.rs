@@ -25,11 +25,17 @@ use roc_mono::layout::{
 };
 use roc_mono::list_element_layout;
 
+mod build_info;
+mod eh_frame;
 mod generic64;
 mod object_builder;
+mod pdata;
 pub use object_builder::build_module;
 use roc_target::Target;
 mod run_roc;
+pub mod stack_map;
+
+use stack_map::CallSite;
 
 #[derive(Debug, Clone, Copy)]
 pub enum AssemblyBackendMode {
@@ -67,12 +73,38 @@ impl AssemblyBackendMode {
     }
 }
 
+/// Code generation options for hosts that require hardened machine code.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hardening {
+    /// Start every function with a landing pad, and mark the object as compatible with
+    /// indirect branch tracking: `endbr64` for Intel CET IBT on x86_64, and `bti c` for
+    /// branch target identification (BTI) on aarch64.
+    pub branch_targets: bool,
+    /// Never emit an indirect jump: use a retpoline instead, which is safe against
+    /// branch target injection and does not need a `notrack` prefix to pass IBT.
+    /// Only supported when targeting x86_64.
+    pub retpolines: bool,
+}
+
+impl Hardening {
+    fn needs_direct_jumps(self) -> bool {
+        // an indirect jump to a return address would not land on an endbr64
+        self.branch_targets || self.retpolines
+    }
+}
+
 pub struct Env<'a> {
     pub arena: &'a Bump,
     pub module_id: ModuleId,
     pub exposed_to_host: MutSet<Symbol>,
     pub lazy_literals: bool,
     pub mode: AssemblyBackendMode,
+    /// Update refcounts with atomic instructions, for platforms that share Roc values between threads.
+    pub atomic_refcounts: bool,
+    pub hardening: Hardening,
+    /// Describe which stack slots hold refcounted pointers at every call, for memory tools.
+    /// See [stack_map].
+    pub stack_maps: bool,
 }
 
 // These relocations likely will need a length.
@@ -90,10 +122,17 @@ pub enum Relocation {
         offset: u64,
         name: String,
     },
+    /// The address of a data symbol, loaded through the GOT.
     LinkedData {
         offset: u64,
         name: String,
     },
+    /// The address of a data symbol defined in this object, relative to the instruction pointer.
+    /// Position independent without a GOT entry, but the symbol must not be interposable.
+    LocalDataAddress {
+        offset: u64,
+        name: String,
+    },
     JmpToReturn {
         inst_loc: u64,
         inst_size: u64,
@@ -101,6 +140,70 @@ pub enum Relocation {
     },
 }
 
+/// The machine code of a procedure, its relocations, and the names of the refcounting functions
+/// it references.
+type BuiltProc<'a> = (Vec<'a, u8>, Vec<'a, Relocation>, Vec<'a, (Symbol, String)>);
+
+/// Why the backend could not generate a procedure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendError {
+    /// The stack frame of a procedure is larger than the target lets us address.
+    StackTooLarge { requested: i64, max: i64 },
+    /// A [Hardening] option that the target has no equivalent for.
+    UnsupportedHardening {
+        option: &'static str,
+        target: Target,
+    },
+    /// An argument with a layout the calling convention doesn't know how to pass.
+    UnsupportedLayout { layout: String },
+}
+
+impl std::fmt::Display for BackendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackendError::StackTooLarge { requested, max } => write!(
+                f,
+                "a procedure needs a {requested} byte stack frame, but at most {max} bytes are supported"
+            ),
+            BackendError::UnsupportedHardening { option, target } => {
+                write!(f, "{option} are not supported when targeting {target}")
+            }
+            BackendError::UnsupportedLayout { layout } => {
+                write!(f, "passing an argument with layout {layout} is not supported")
+            }
+        }
+    }
+}
+
+/// One change to the rules an unwinder uses to find a function's caller, taking effect `offset`
+/// bytes into the function. The rules start out as the ones that hold right after a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnwindRow {
+    pub offset: u32,
+    pub rule: UnwindRule,
+}
+
+/// Registers are numbered as in the target's DWARF register mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnwindRule {
+    /// The canonical frame address (the stack pointer right before the call) is `register + offset`.
+    Cfa { register: u16, offset: i32 },
+    /// The caller's value of `register` is saved at the canonical frame address plus `offset`.
+    SavedAt { register: u16, offset: i32 },
+    /// The stack pointer is `offset` bytes below the canonical frame address. DWARF unwinders
+    /// don't need this, but the Windows unwinder finds saved registers through the stack pointer.
+    StackPointer { offset: i32 },
+}
+
+/// What a pointer passed to one of the refcounting lowlevels points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefcountPointer {
+    /// Points directly at the refcount.
+    ToRefcount,
+    /// Points at the data, which starts right after the refcount. May be null, and may carry a tag id in its low bits.
+    ToData,
+}
+
 #[repr(u8)]
 enum UpdateMode {
     Immutable = 0,
@@ -277,7 +380,12 @@ impl<'a> LastSeenMap<'a> {
                 }
             }
 
-            Stmt::Dbg { .. } => todo!("dbg not implemented in the dev backend"),
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                self.set_last_seen(*symbol, stmt);
+                self.scan_ast_help(remainder);
+            }
             Stmt::Expect { .. } => todo!("expect is not implemented in the dev backend"),
             Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the dev backend"),
 
@@ -313,6 +421,11 @@ trait Backend<'a> {
     fn interns_mut(&mut self) -> &mut Interns;
     fn interner(&self) -> &STLayoutInterner<'a>;
     fn relocations_mut(&mut self) -> &mut Vec<'a, Relocation>;
+    /// How to unwind through the procedure last built by `finalize`.
+    fn unwind_rows(&self) -> &[UnwindRow];
+    /// The refcounted stack slots at each call in the procedure last built by `finalize`.
+    /// Empty unless [Env::stack_maps] is set.
+    fn stack_map(&self) -> &[CallSite];
     fn target(&self) -> Target;
 
     fn interner_mut(&mut self) -> &mut STLayoutInterner<'a> {
@@ -520,11 +633,15 @@ trait Backend<'a> {
     /// finalize does setup because things like stack size and jump locations are not know until the function is written.
     /// For example, this can store the frame pointer and setup stack space.
     /// finalize is run at the end of build_proc when all internal code is finalized.
-    fn finalize(&mut self) -> (Vec<u8>, Vec<Relocation>);
+    fn finalize(&mut self) -> Result<(Vec<'a, u8>, Vec<'a, Relocation>), BackendError>;
 
     // load_args is used to let the backend know what the args are.
     // The backend should track these args so it can use them as needed.
-    fn load_args(&mut self, args: &'a [(InLayout<'a>, Symbol)], ret_layout: &InLayout<'a>);
+    fn load_args(
+        &mut self,
+        args: &'a [(InLayout<'a>, Symbol)],
+        ret_layout: &InLayout<'a>,
+    ) -> Result<(), BackendError>;
 
     /// Used for generating wrappers for malloc/realloc/free
     fn build_wrapped_jmp(&mut self) -> (&'a [u8], u64);
@@ -534,13 +651,15 @@ trait Backend<'a> {
     fn build_roc_longjmp(&mut self) -> &'a [u8];
     fn build_roc_panic(&mut self) -> (&'a [u8], Vec<'a, Relocation>);
 
+    /// A roc_dbg for tests, which ignores its arguments
+    fn build_roc_dbg(&mut self) -> &'a [u8];
+
     /// build_proc creates a procedure and outputs it to the wrapped object writer.
-    /// Returns the procedure bytes, its relocations, and the names of the refcounting functions it references.
     fn build_proc(
         &mut self,
         proc: Proc<'a>,
         layout_ids: &mut LayoutIds<'a>,
-    ) -> (Vec<u8>, Vec<Relocation>, Vec<'a, (Symbol, String)>) {
+    ) -> Result<BuiltProc<'a>, BackendError> {
         let proc_name = self.lambda_name_to_string(
             proc.name,
             proc.args.iter().map(|t| t.0),
@@ -551,7 +670,7 @@ trait Backend<'a> {
         let body = self.env().arena.alloc(proc.body);
 
         self.reset(proc_name, proc.is_self_recursive);
-        self.load_args(proc.args, &proc.ret_layout);
+        self.load_args(proc.args, &proc.ret_layout)?;
         for (layout, sym) in proc.args {
             self.set_layout_map(*sym, layout);
         }
@@ -579,8 +698,8 @@ trait Backend<'a> {
             helper_proc_names.push((proc_symbol, name));
         }
 
-        let (bytes, relocs) = self.finalize();
-        (bytes, relocs, helper_proc_names)
+        let (bytes, relocs) = self.finalize()?;
+        Ok((bytes, relocs, helper_proc_names))
     }
 
     /// build_stmt builds a statement and outputs at the end of the buffer.
@@ -705,11 +824,59 @@ trait Backend<'a> {
                 self.build_jump(id, args, arg_layouts.into_bump_slice(), ret_layout);
                 self.free_symbols(stmt);
             }
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                remainder,
+                ..
+            } => {
+                self.roc_dbg(source_location, source, *symbol);
+                self.free_symbols(stmt);
+                self.build_stmt(layout_ids, remainder, ret_layout);
+            }
             Stmt::Crash(msg, crash_tag) => self.roc_panic(*msg, *crash_tag),
             x => todo!("the statement, {:?}", x),
         }
     }
 
+    fn roc_dbg(&mut self, source_location: &'a str, source: &'a str, msg: Symbol) {
+        let location = self.debug_symbol("dbg_location");
+        let src = self.debug_symbol("dbg_source");
+        self.load_literal(&location, &Layout::STR, &Literal::Str(source_location));
+        self.load_literal(&src, &Layout::STR, &Literal::Str(source));
+        self.load_literal_symbols(&[msg]);
+
+        // roc_dbg takes a `*RocStr` for each of its arguments, so put them on the stack
+        let location_ptr = self.debug_symbol("dbg_location_ptr");
+        let msg_ptr = self.debug_symbol("dbg_msg_ptr");
+        let src_ptr = self.debug_symbol("dbg_source_ptr");
+        self.build_alloca(location_ptr, Some(location), Layout::STR);
+        self.build_alloca(msg_ptr, Some(msg), Layout::STR);
+        self.build_alloca(src_ptr, Some(src), Layout::STR);
+
+        // the order of the arguments is (location, message, source)
+        let arguments = &[location_ptr, msg_ptr, src_ptr];
+        self.build_fn_call(
+            &Symbol::DEV_TMP2,
+            String::from("roc_dbg"),
+            arguments,
+            &[Layout::U64, Layout::U64, Layout::U64],
+            &Layout::UNIT,
+        );
+
+        for sym in [
+            location,
+            src,
+            location_ptr,
+            msg_ptr,
+            src_ptr,
+            Symbol::DEV_TMP2,
+        ] {
+            self.free_symbol(&sym);
+        }
+    }
+
     fn roc_panic(&mut self, msg: Symbol, crash_tag: CrashTag) {
         let error_message = self.debug_symbol("error_message");
 
@@ -1017,7 +1184,15 @@ trait Backend<'a> {
         ret_layout: &InLayout<'a>,
     ) {
         // Now that the arguments are needed, load them if they are literals.
-        self.load_literal_symbols(args);
+        // An atomic refcount increment adds a literal amount as an immediate instead.
+        match lowlevel {
+            LowLevel::RefCountIncRcPtr | LowLevel::RefCountIncDataPtr
+                if self.env().atomic_refcounts =>
+            {
+                self.load_literal_symbols(&args[..1])
+            }
+            _ => self.load_literal_symbols(args),
+        }
         match lowlevel {
             LowLevel::NumAbs => {
                 debug_assert_eq!(
@@ -1332,11 +1507,10 @@ trait Backend<'a> {
             LowLevel::Eq => {
                 debug_assert_eq!(2, args.len(), "Eq: expected to have exactly two argument");
 
-                let a = Layout::runtime_representation_in(arg_layouts[0], self.interner());
-                let b = Layout::runtime_representation_in(arg_layouts[1], self.interner());
+                let (a, b) = (arg_layouts[0], arg_layouts[1]);
 
                 debug_assert!(
-                    self.interner().eq_repr(a, b),
+                    self.interner().equiv_runtime(a, b),
                     "Eq: expected all arguments to have the same layout, but {} != {}",
                     self.interner().dbg(a),
                     self.interner().dbg(b),
@@ -1351,11 +1525,10 @@ trait Backend<'a> {
                     "NotEq: expected to have exactly two argument"
                 );
 
-                let a = Layout::runtime_representation_in(arg_layouts[0], self.interner());
-                let b = Layout::runtime_representation_in(arg_layouts[1], self.interner());
+                let (a, b) = (arg_layouts[0], arg_layouts[1]);
 
                 debug_assert!(
-                    self.interner().eq_repr(a, b),
+                    self.interner().equiv_runtime(a, b),
                     "NotEq: expected all arguments to have the same layout, but {} != {}",
                     self.interner().dbg(a),
                     self.interner().dbg(b),
@@ -1816,6 +1989,26 @@ trait Backend<'a> {
                 self.build_ptr_clear_tag_id(*sym, args[0]);
             }
 
+            LowLevel::RefCountIncRcPtr | LowLevel::RefCountIncDataPtr
+                if self.env().atomic_refcounts =>
+            {
+                let pointer = match lowlevel {
+                    LowLevel::RefCountIncRcPtr => RefcountPointer::ToRefcount,
+                    _ => RefcountPointer::ToData,
+                };
+
+                self.build_atomic_refcount_inc(sym, args[0], pointer, args[1]);
+            }
+            LowLevel::RefCountDecRcPtr | LowLevel::RefCountDecDataPtr
+                if self.env().atomic_refcounts =>
+            {
+                let pointer = match lowlevel {
+                    LowLevel::RefCountDecRcPtr => RefcountPointer::ToRefcount,
+                    _ => RefcountPointer::ToData,
+                };
+
+                self.build_atomic_refcount_dec(sym, args[0], pointer, args[1], args[2]);
+            }
             LowLevel::RefCountDecRcPtr => self.build_fn_call(
                 sym,
                 bitcode::UTILS_DECREF_RC_PTR.to_string(),
@@ -2338,6 +2531,7 @@ trait Backend<'a> {
     );
 
     fn build_fn_pointer(&mut self, dst: &Symbol, fn_name: String);
+    /// build_data_pointer loads the address of a data symbol defined in this object into dst.
     fn build_data_pointer(&mut self, dst: &Symbol, data_name: String);
 
     /// Move a returned value into `dst`
@@ -2684,6 +2878,27 @@ trait Backend<'a> {
     /// build_refcount_getptr loads the pointer to the reference count of src into dst.
     fn build_ptr_cast(&mut self, dst: &Symbol, src: &Symbol);
 
+    /// build_atomic_refcount_inc atomically increments the refcount of ptr by amount.
+    /// Constant refcounts are left untouched, matching the zig builtins.
+    fn build_atomic_refcount_inc(
+        &mut self,
+        dst: &Symbol,
+        ptr: Symbol,
+        pointer: RefcountPointer,
+        amount: Symbol,
+    );
+
+    /// build_atomic_refcount_dec atomically decrements the refcount of ptr,
+    /// and frees the allocation if that was the last reference.
+    fn build_atomic_refcount_dec(
+        &mut self,
+        dst: &Symbol,
+        ptr: Symbol,
+        pointer: RefcountPointer,
+        alignment: Symbol,
+        elements_refcounted: Symbol,
+    );
+
     fn build_ptr_store(
         &mut self,
         sym: Symbol,
@@ -1,5 +1,9 @@
+use crate::build_info::BuildInfo;
+use crate::eh_frame::EhFrame;
 use crate::generic64::{aarch64, new_backend_64bit, x86_64};
-use crate::{AssemblyBackendMode, Backend, Env, Relocation};
+use crate::pdata::Pdata;
+use crate::stack_map::StackMaps;
+use crate::{AssemblyBackendMode, Backend, BackendError, Env, Relocation};
 use bumpalo::collections::Vec;
 use object::write::{self, SectionId, SymbolId};
 use object::write::{Object, StandardSection, StandardSegment, Symbol, SymbolSection};
@@ -11,25 +15,22 @@ use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
 use roc_module::symbol;
 use roc_module::symbol::Interns;
+use roc_mono::ir::{procedures_in_stable_order, Proc, ProcLayout, Stmt};
 use roc_mono::ir::{Call, CallSpecId, Expr, UpdateModeId};
-use roc_mono::ir::{Proc, ProcLayout, Stmt};
 use roc_mono::layout::{LambdaName, Layout, LayoutIds, LayoutInterner, STLayoutInterner};
 use roc_target::Target;
 
-// This is used by some code below which is currently commented out.
-// See that code for more details!
-// const VERSION: &str = env!("CARGO_PKG_VERSION");
-
 /// build_module is the high level builder/delegator.
 /// It takes the request to build a module and output the object file for the module.
+/// Fails if the module uses something this backend cannot generate code for.
 pub fn build_module<'a, 'r>(
     env: &'r Env<'a>,
     interns: &'r mut Interns,
     layout_interner: &'r mut STLayoutInterner<'a>,
     target: Target,
     procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
-) -> Object<'a> {
-    let module_object = build_module_help(env, interns, layout_interner, target, procedures);
+) -> Result<Object<'a>, BackendError> {
+    let module_object = build_module_help(env, interns, layout_interner, target, procedures)?;
 
     if std::env::var("ROC_DEV_WRITE_OBJ").is_ok() {
         let module_out = module_object
@@ -41,7 +42,7 @@ pub fn build_module<'a, 'r>(
         std::fs::write(&file_path, module_out).expect("failed to write object to file");
     }
 
-    module_object
+    Ok(module_object)
 }
 
 fn build_module_help<'a, 'r>(
@@ -50,7 +51,7 @@ fn build_module_help<'a, 'r>(
     layout_interner: &'r mut STLayoutInterner<'a>,
     target: Target,
     procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
-) -> Object<'a> {
+) -> Result<Object<'a>, BackendError> {
     match target {
         Target::LinuxX64 if cfg!(feature = "target-x86_64") => {
             let backend = new_backend_64bit::<
@@ -68,6 +69,14 @@ fn build_module_help<'a, 'r>(
                 b".note.GNU-stack".to_vec(),
                 SectionKind::Elf(object::elf::SHT_PROGBITS),
             );
+            if env.hardening.branch_targets {
+                // Tell the linker that our code is compatible with indirect branch tracking.
+                // We do not claim shadow stack support: longjmp skips over return addresses.
+                object.add_elf_gnu_property_u32(
+                    object::elf::GNU_PROPERTY_X86_FEATURE_1_AND,
+                    object::elf::GNU_PROPERTY_X86_FEATURE_1_IBT,
+                );
+            }
             build_object(procedures, backend, object)
         }
         Target::MacX64 if cfg!(feature = "target-x86_64") => {
@@ -101,19 +110,36 @@ fn build_module_help<'a, 'r>(
             )
         }
         Target::LinuxArm64 if cfg!(feature = "target-aarch64") => {
+            if env.hardening.retpolines {
+                return Err(BackendError::UnsupportedHardening {
+                    option: "retpolines",
+                    target,
+                });
+            }
             let backend = new_backend_64bit::<
                 aarch64::AArch64GeneralReg,
                 aarch64::AArch64FloatReg,
                 aarch64::AArch64Assembler,
                 aarch64::AArch64Call,
             >(env, target, interns, layout_interner);
-            build_object(
-                procedures,
-                backend,
-                Object::new(BinaryFormat::Elf, Architecture::Aarch64, Endianness::Little),
-            )
+            let mut object =
+                Object::new(BinaryFormat::Elf, Architecture::Aarch64, Endianness::Little);
+            if env.hardening.branch_targets {
+                // Tell the linker that our code is compatible with branch target identification.
+                object.add_elf_gnu_property_u32(
+                    object::elf::GNU_PROPERTY_AARCH64_FEATURE_1_AND,
+                    object::elf::GNU_PROPERTY_AARCH64_FEATURE_1_BTI,
+                );
+            }
+            build_object(procedures, backend, object)
         }
         Target::MacArm64 if cfg!(feature = "target-aarch64") => {
+            if env.hardening.retpolines {
+                return Err(BackendError::UnsupportedHardening {
+                    option: "retpolines",
+                    target,
+                });
+            }
             let backend = new_backend_64bit::<
                 aarch64::AArch64GeneralReg,
                 aarch64::AArch64FloatReg,
@@ -236,90 +262,98 @@ fn generate_roc_panic<'a, B: Backend<'a>>(backend: &mut B, output: &mut Object)
 
     let proc_offset = output.add_symbol_data(proc_id, text_section, proc_data, 16);
 
+    let target = backend.target();
+
     for r in relocs {
-        let relocation = match r {
-            Relocation::LinkedData { offset, name } => {
+        match r {
+            Relocation::LocalDataAddress { offset, name } => {
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    if cfg!(all(target_arch = "aarch64", target_os = "linux")) {
-                        //     700: 90000001        adrp    x1, 0x0 <std.builtin.default_panic>
-                        //      0000000000000700:  R_AARCH64_ADR_PREL_PG_HI21   .rodata+0x650
-                        let relocation = write::Relocation {
-                            offset: offset + proc_offset,
-                            size: 21,
-                            kind: RelocationKind::Elf(object::elf::R_AARCH64_ADR_PREL_PG_HI21),
-                            encoding: RelocationEncoding::Generic,
-                            symbol: sym_id,
-                            addend: 0,
-                        };
-
+                    for relocation in local_data_relocations(target, sym_id, offset + proc_offset) {
                         output.add_relocation(text_section, relocation).unwrap();
-
-                        //     704: 91000021        add x1, x1, #0x0
-                        //      0000000000000704:  R_AARCH64_ADD_ABS_LO12_NC    .rodata+0x650
-                        write::Relocation {
-                            offset: offset + proc_offset + 4,
-                            size: 12,
-                            kind: RelocationKind::Elf(object::elf::R_AARCH64_ADD_ABS_LO12_NC),
-                            encoding: RelocationEncoding::Generic,
-                            symbol: sym_id,
-                            addend: 0,
-                        }
-                    } else if cfg!(all(target_arch = "aarch64", target_os = "macos")) {
-                        //     4dc: 90000001        adrp    x1, 0x0 <ltmp0>
-                        //      00000000000004dc:  ARM64_RELOC_PAGE21   ___unnamed_6
-                        let relocation = write::Relocation {
-                            offset: offset + proc_offset,
-                            size: 32,
-                            kind: RelocationKind::MachO {
-                                value: object::macho::ARM64_RELOC_PAGE21,
-                                relative: true,
-                            },
-                            encoding: RelocationEncoding::Generic,
-                            symbol: sym_id,
-                            addend: 0,
-                        };
-
-                        output.add_relocation(text_section, relocation).unwrap();
-
-                        //     4e0: 91000021        add x1, x1, #0x0
-                        //      00000000000004e0:  ARM64_RELOC_PAGEOFF12    ___unnamed_6
-                        write::Relocation {
-                            offset: offset + proc_offset + 4,
-                            size: 32,
-                            kind: RelocationKind::MachO {
-                                value: object::macho::ARM64_RELOC_PAGEOFF12,
-                                relative: false,
-                            },
-                            encoding: RelocationEncoding::Generic,
-                            symbol: sym_id,
-                            addend: 0,
-                        }
-                    } else {
-                        write::Relocation {
-                            offset: offset + proc_offset,
-                            size: 32,
-                            kind: RelocationKind::GotRelative,
-                            encoding: RelocationEncoding::Generic,
-                            symbol: sym_id,
-                            addend: -4,
-                        }
                     }
                 } else {
                     internal_error!("failed to find data symbol for {:?}", name);
                 }
             }
             Relocation::LocalData { .. }
+            | Relocation::LinkedData { .. }
             | Relocation::LinkedFunction { .. }
             | Relocation::JmpToReturn { .. } => {
                 unreachable!("not currently created by build_roc_panic")
             }
-        };
+        }
+    }
+}
 
-        output.add_relocation(text_section, relocation).unwrap();
+/// The relocations for the instructions emitted by `Assembler::local_data_pointer`.
+/// They are all relative to the instruction pointer, so the code stays position independent.
+fn local_data_relocations(
+    target: Target,
+    symbol: SymbolId,
+    offset: u64,
+) -> std::vec::Vec<write::Relocation> {
+    match target.arch_os() {
+        (roc_target::Architecture::X86_64, _) => {
+            //      0: 48 8d 3d 00 00 00 00    lea    rdi,[rip+0x0]
+            //              3: R_X86_64_PC32    setlongjmp_buffer-0x4
+            vec![write::Relocation {
+                offset,
+                size: 32,
+                kind: RelocationKind::Relative,
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend: -4,
+            }]
+        }
+        (roc_target::Architecture::Aarch64, roc_target::OperatingSystem::Mac) => vec![
+            // mach-o records the size of the whole instruction
+            write::Relocation {
+                offset,
+                size: 32,
+                kind: RelocationKind::MachO {
+                    value: object::macho::ARM64_RELOC_PAGE21,
+                    relative: true,
+                },
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend: 0,
+            },
+            write::Relocation {
+                offset: offset + 4,
+                size: 32,
+                kind: RelocationKind::MachO {
+                    value: object::macho::ARM64_RELOC_PAGEOFF12,
+                    relative: false,
+                },
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend: 0,
+            },
+        ],
+        (roc_target::Architecture::Aarch64, _) => vec![
+            write::Relocation {
+                offset,
+                size: 21,
+                kind: RelocationKind::Elf(object::elf::R_AARCH64_ADR_PREL_PG_HI21),
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend: 0,
+            },
+            write::Relocation {
+                offset: offset + 4,
+                size: 12,
+                kind: RelocationKind::Elf(object::elf::R_AARCH64_ADD_ABS_LO12_NC),
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend: 0,
+            },
+        ],
+        (architecture, _) => todo!("local data relocations for {architecture}"),
     }
 }
 
-fn generate_roc_dbg<'a, B: Backend<'a>>(_backend: &mut B, output: &mut Object) {
+// a roc_dbg to be used in tests; it does not print anything
+fn generate_roc_dbg<'a, B: Backend<'a>>(backend: &mut B, output: &mut Object) {
     let text_section = output.section_id(StandardSection::Text);
     let proc_symbol = Symbol {
         name: "roc_dbg".as_bytes().to_vec(),
@@ -331,9 +365,10 @@ fn generate_roc_dbg<'a, B: Backend<'a>>(_backend: &mut B, output: &mut Object) {
         section: SymbolSection::Section(text_section),
         flags: SymbolFlags::None,
     };
-    let _proc_id = output.add_symbol(proc_symbol);
-    // TODO: Actually generate an impl instead of just an empty symbol.
-    // At a minimum, it should just be a ret.
+    let proc_id = output.add_symbol(proc_symbol);
+    let proc_data = backend.build_roc_dbg();
+
+    output.add_symbol_data(proc_id, text_section, proc_data, 16);
 }
 
 fn generate_wrapper<'a, B: Backend<'a>>(
@@ -429,20 +464,19 @@ fn build_object<'a, B: Backend<'a>>(
     procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
     mut backend: B,
     mut output: Object<'a>,
-) -> Object<'a> {
+) -> Result<Object<'a>, BackendError> {
     let data_section = output.section_id(StandardSection::Data);
+    let eh_frame = EhFrame::new(&mut output, backend.target());
+    let pdata = Pdata::new(&mut output, backend.target());
+    let stack_maps = if backend.env().stack_maps {
+        StackMaps::new(&mut output, backend.target())
+    } else {
+        None
+    };
 
     let arena = backend.env().arena;
 
-    /*
-    // Commented out because we couldn't figure out how to get it to work on mac - see https://github.com/roc-lang/roc/pull/1323
-    let comment = output.add_section(vec![], b".comment".to_vec(), SectionKind::OtherString);
-    output.append_section_data(
-        comment,
-        format!("\0roc dev backend version {} \0", VERSION).as_bytes(),
-        1,
-    );
-    */
+    let mut build_info = BuildInfo::default();
 
     if backend.env().mode.generate_roc_panic() {
         define_panic_msg(&mut output);
@@ -508,6 +542,8 @@ fn build_object<'a, B: Backend<'a>>(
     let mut layout_ids = LayoutIds::default();
     let mut procs = Vec::with_capacity_in(procedures.len(), arena);
 
+    let procedures = procedures_in_stable_order(procedures, backend.interns(), backend.interner());
+
     // Names and linker data for user procedures
     for ((sym, layout), proc) in procedures {
         debug_assert_eq!(sym, proc.name.name());
@@ -621,6 +657,15 @@ fn build_object<'a, B: Backend<'a>>(
         )
     }
 
+    if output.format() == BinaryFormat::Coff {
+        let exposed = procs
+            .iter()
+            .map(|(fn_name, ..)| fn_name.as_str())
+            .filter(|fn_name| fn_name.starts_with("roc__"));
+
+        define_dll_exports(&mut output, exposed);
+    }
+
     // Build procedures from user code
     let mut relocations = bumpalo::vec![in arena];
     for (fn_name, section_id, proc_id, proc) in procs {
@@ -630,11 +675,15 @@ fn build_object<'a, B: Backend<'a>>(
             &mut relocations,
             &mut layout_ids,
             data_section,
+            eh_frame.as_ref(),
+            pdata.as_ref(),
+            stack_maps.as_ref(),
+            &mut build_info,
             fn_name,
             section_id,
             proc_id,
             proc,
-        )
+        )?;
     }
 
     // Generate IR for specialized helper procs (refcounting & equality)
@@ -721,11 +770,15 @@ fn build_object<'a, B: Backend<'a>>(
             &mut relocations,
             &mut layout_ids,
             data_section,
+            eh_frame.as_ref(),
+            pdata.as_ref(),
+            stack_maps.as_ref(),
+            &mut build_info,
             fn_name,
             section_id,
             proc_id,
             proc,
-        )
+        )?;
     }
 
     // Relocations for all procedures (user code & helpers)
@@ -735,7 +788,21 @@ fn build_object<'a, B: Backend<'a>>(
             Err(e) => internal_error!("{:?}", e),
         }
     }
-    output
+
+    build_info.write(&mut output);
+
+    Ok(output)
+}
+
+/// A `.dll` only exports the symbols its objects ask for in their `.drectve` section, which is
+/// what `__declspec(dllexport)` does in C. The scope of a COFF symbol is not enough.
+fn define_dll_exports<'n>(output: &mut Object, names: impl Iterator<Item = &'n str>) {
+    let directives: String = names.map(|name| format!(" /EXPORT:{name}")).collect();
+
+    if !directives.is_empty() {
+        let section_id = output.add_section(vec![], b".drectve".to_vec(), SectionKind::Linker);
+        output.append_section_data(section_id, directives.as_bytes(), 1);
+    }
 }
 
 fn build_exposed_proc<'a, B: Backend<'a>>(backend: &mut B, proc: &Proc<'a>) -> Proc<'a> {
@@ -907,8 +974,9 @@ fn build_proc_symbol<'a, B: Backend<'a>>(
         value: 0,
         size: 0,
         kind: SymbolKind::Text,
-        // TODO: Depending on whether we are building a static or dynamic lib, this should change.
-        // We should use Dynamic -> anyone, Linkage -> static link, Compilation -> this module only.
+        // Dynamic -> anyone, including hosts that dlopen a shared library build of the app;
+        // Linkage -> static link; Compilation -> this module only.
+        // COFF ignores this for dlls, see `define_dll_exports`.
         scope: match exposed {
             Exposed::ExposedGeneric | Exposed::Exposed | Exposed::TestMain => SymbolScope::Dynamic,
             Exposed::NotExposed => SymbolScope::Linkage,
@@ -928,15 +996,21 @@ fn build_proc<'a, B: Backend<'a>>(
     relocations: &mut Vec<'a, (SectionId, object::write::Relocation)>,
     layout_ids: &mut LayoutIds<'a>,
     data_section: SectionId,
+    eh_frame: Option<&EhFrame>,
+    pdata: Option<&Pdata>,
+    stack_maps: Option<&StackMaps>,
+    build_info: &mut BuildInfo,
     fn_name: String,
     section_id: SectionId,
     proc_id: SymbolId,
     proc: Proc<'a>,
-) {
+) -> Result<(), BackendError> {
     let mut local_data_index = 0;
     let target = backend.target();
-    let (proc_data, relocs, rc_proc_names) = backend.build_proc(proc, layout_ids);
+    let (proc_data, relocs, rc_proc_names) = backend.build_proc(proc, layout_ids)?;
     let proc_offset = output.add_symbol_data(proc_id, section_id, &proc_data, 16);
+    let proc_size = proc_data.len() as u32;
+    build_info.add_procedure(&fn_name, &proc_data);
     for reloc in relocs.iter() {
         let elfreloc = match reloc {
             Relocation::LocalData { offset, data } => {
@@ -1060,10 +1134,35 @@ fn build_proc<'a, B: Backend<'a>>(
                     internal_error!("failed to find fn symbol for {:?}", name);
                 }
             }
+            Relocation::LocalDataAddress { offset, name } => {
+                if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
+                    for r in local_data_relocations(target, sym_id, offset + proc_offset) {
+                        relocations.push((section_id, r));
+                    }
+
+                    continue;
+                } else {
+                    internal_error!("failed to find data symbol for {:?}", name);
+                }
+            }
             Relocation::JmpToReturn { .. } => unreachable!(),
         };
         relocations.push((section_id, elfreloc));
     }
+
+    if let Some(eh_frame) = eh_frame {
+        eh_frame.add_procedure(output, proc_id, proc_size, backend.unwind_rows());
+    }
+
+    if let Some(pdata) = pdata {
+        pdata.add_procedure(output, proc_id, proc_size, backend.unwind_rows());
+    }
+
+    if let Some(stack_maps) = stack_maps {
+        stack_maps.add_procedure(output, proc_id, backend.stack_map());
+    }
+
+    Ok(())
 }
 
 fn add_undefined_rc_proc(
@@ -0,0 +1,281 @@
+//! Writes the `.pdata` and `.xdata` sections Windows uses to unwind through the procedures we
+//! generate on x86_64: one `RUNTIME_FUNCTION` in `.pdata` per procedure, pointing at an
+//! `UNWIND_INFO` in `.xdata` that describes its prologue. Epilogues aren't described, because the
+//! Windows unwinder recognizes `add rsp, n; pop rbp; ret` from the instructions themselves.
+use object::write::{self, Object, SectionId, SymbolId};
+use object::{RelocationEncoding, RelocationKind, SectionKind};
+use roc_target::Target;
+
+use crate::{UnwindRow, UnwindRule};
+
+const UNW_VERSION: u8 = 1;
+
+const UWOP_PUSH_NONVOL: u8 = 0;
+const UWOP_ALLOC_LARGE: u8 = 1;
+const UWOP_ALLOC_SMALL: u8 = 2;
+const UWOP_SAVE_NONVOL: u8 = 4;
+const UWOP_SAVE_NONVOL_FAR: u8 = 5;
+
+/// The DWARF number of rsp.
+const DWARF_RSP: u16 = 7;
+
+pub struct Pdata {
+    pdata: SectionId,
+    xdata: SectionId,
+}
+
+impl Pdata {
+    /// Adds the `.pdata` and `.xdata` sections. Returns `None` for targets other than Windows.
+    pub fn new(output: &mut Object, target: Target) -> Option<Self> {
+        if !matches!(target, Target::WinX64) {
+            return None;
+        }
+
+        let pdata = output.add_section(vec![], b".pdata".to_vec(), SectionKind::ReadOnlyData);
+        let xdata = output.add_section(vec![], b".xdata".to_vec(), SectionKind::ReadOnlyData);
+
+        Some(Self { pdata, xdata })
+    }
+
+    /// Adds a `RUNTIME_FUNCTION` for the `size` bytes of code starting at `symbol`.
+    pub fn add_procedure(
+        &self,
+        output: &mut Object,
+        symbol: SymbolId,
+        size: u32,
+        rows: &[UnwindRow],
+    ) {
+        let unwind_info = output.append_section_data(self.xdata, &encode_unwind_info(rows), 4);
+
+        // The start, end, and unwind info of the procedure, all filled in by the relocations below.
+        let offset = output.append_section_data(self.pdata, &[0; 12], 4);
+
+        let xdata_symbol = output.section_symbol(self.xdata);
+        let fields = [
+            (0, symbol, 0),
+            (4, symbol, size as i64),
+            (8, xdata_symbol, unwind_info as i64),
+        ];
+        for (field, symbol, addend) in fields {
+            let relocation = write::Relocation {
+                offset: offset + field,
+                size: 32,
+                kind: RelocationKind::ImageOffset,
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend,
+            };
+            if let Err(e) = output.add_relocation(self.pdata, relocation) {
+                roc_error_macros::internal_error!("{:?}", e);
+            }
+        }
+    }
+}
+
+/// Turns rows sorted by offset into an `UNWIND_INFO` for the prologue they describe.
+///
+/// Saved float registers are left out. `UWOP_SAVE_XMM128` restores all 128 bits from a 16 byte
+/// aligned slot, while the prologue only stores the low 64 bits wherever they fit. That matters
+/// for resuming execution in a caller, but not for walking the stack.
+fn encode_unwind_info(rows: &[UnwindRow]) -> std::vec::Vec<u8> {
+    // How far the stack pointer is below the canonical frame address. Right after the call, only
+    // the return address is in between.
+    let mut depth = 8;
+    let mut last_push = None;
+    let mut prologue_size = 0;
+    // The unwind codes in prologue order, each a list of 16 bit slots.
+    let mut codes = std::vec::Vec::new();
+
+    for row in rows {
+        let code = |op: u8, info: u8| {
+            let offset = u8::try_from(row.offset).unwrap_or_else(|_| {
+                roc_error_macros::internal_error!(
+                    "a prologue of {} bytes is too long to describe to the Windows unwinder",
+                    row.offset
+                )
+            });
+            u16::from_le_bytes([offset, op | (info << 4)])
+        };
+
+        match row.rule {
+            UnwindRule::Cfa {
+                register: DWARF_RSP,
+                offset,
+            } => {
+                if offset < depth {
+                    // The epilogue started freeing the frame.
+                    break;
+                }
+                // A push, whose register is saved right at the new stack pointer.
+                depth = offset;
+                last_push = Some(row.offset);
+            }
+            UnwindRule::Cfa { .. } => {
+                // The frame pointer was set up. The stack pointer keeps working just as well.
+            }
+            UnwindRule::StackPointer { offset } => {
+                let size = (offset - depth) as u32;
+                depth = offset;
+                codes.push(match size {
+                    8..=128 => vec![code(UWOP_ALLOC_SMALL, (size / 8 - 1) as u8)],
+                    129..=0x7fff8 => vec![code(UWOP_ALLOC_LARGE, 0), (size / 8) as u16],
+                    _ => vec![code(UWOP_ALLOC_LARGE, 1), size as u16, (size >> 16) as u16],
+                });
+            }
+            UnwindRule::SavedAt { register, offset } => {
+                let Some(register) = windows_register(register) else {
+                    continue;
+                };
+                if last_push == Some(row.offset) && offset == -depth {
+                    codes.push(vec![code(UWOP_PUSH_NONVOL, register)]);
+                } else {
+                    let from_stack_pointer = (depth + offset) as u32;
+                    debug_assert!(from_stack_pointer % 8 == 0);
+                    codes.push(match from_stack_pointer / 8 {
+                        scaled @ 0..=0xffff => {
+                            vec![code(UWOP_SAVE_NONVOL, register), scaled as u16]
+                        }
+                        _ => vec![
+                            code(UWOP_SAVE_NONVOL_FAR, register),
+                            from_stack_pointer as u16,
+                            (from_stack_pointer >> 16) as u16,
+                        ],
+                    });
+                }
+            }
+        }
+
+        prologue_size = row.offset;
+    }
+
+    // The unwinder undoes the codes from the end of the prologue back to its start.
+    let slots: std::vec::Vec<u16> = codes.into_iter().rev().flatten().collect();
+
+    let mut buf = vec![
+        UNW_VERSION,
+        prologue_size as u8,
+        slots.len() as u8,
+        // No frame register: the stack pointer doesn't move outside of the prologue and epilogue.
+        0,
+    ];
+    for slot in &slots {
+        buf.extend(slot.to_le_bytes());
+    }
+    // The slots are padded to a multiple of 4 bytes.
+    if slots.len() % 2 == 1 {
+        buf.extend([0, 0]);
+    }
+
+    buf
+}
+
+/// Windows numbers general purpose registers the way their instruction encoding does,
+/// where DWARF swaps some of the first eight. Float registers have no number here.
+fn windows_register(dwarf_register: u16) -> Option<u8> {
+    const RAX: u8 = 0;
+    const RCX: u8 = 1;
+    const RDX: u8 = 2;
+    const RBX: u8 = 3;
+    const RSP: u8 = 4;
+    const RBP: u8 = 5;
+    const RSI: u8 = 6;
+    const RDI: u8 = 7;
+
+    match dwarf_register {
+        0 => Some(RAX),
+        1 => Some(RDX),
+        2 => Some(RCX),
+        3 => Some(RBX),
+        4 => Some(RSI),
+        5 => Some(RDI),
+        6 => Some(RBP),
+        7 => Some(RSP),
+        8..=15 => Some(dwarf_register as u8),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_x86_64_prologue() {
+        // push rbp; mov rbp, rsp; sub rsp, 48; mov [rbp - 32], rbx; mov [rbp - 40], xmm6;
+        // ...; add rsp, 48; pop rbp; ret
+        let rows = [
+            UnwindRow {
+                offset: 1,
+                rule: UnwindRule::Cfa {
+                    register: 7,
+                    offset: 16,
+                },
+            },
+            UnwindRow {
+                offset: 1,
+                rule: UnwindRule::SavedAt {
+                    register: 6,
+                    offset: -16,
+                },
+            },
+            UnwindRow {
+                offset: 4,
+                rule: UnwindRule::Cfa {
+                    register: 6,
+                    offset: 16,
+                },
+            },
+            UnwindRow {
+                offset: 11,
+                rule: UnwindRule::StackPointer { offset: 64 },
+            },
+            UnwindRow {
+                offset: 26,
+                rule: UnwindRule::SavedAt {
+                    register: 3,
+                    offset: -48,
+                },
+            },
+            UnwindRow {
+                offset: 26,
+                rule: UnwindRule::SavedAt {
+                    register: 23,
+                    offset: -56,
+                },
+            },
+            UnwindRow {
+                offset: 200,
+                rule: UnwindRule::Cfa {
+                    register: 7,
+                    offset: 8,
+                },
+            },
+        ];
+
+        assert_eq!(
+            encode_unwind_info(&rows),
+            [
+                1, 26, 4, 0, // version 1, 26 byte prologue, 4 slots, no frame register
+                26, 0x34, 2, 0, // at 26: save rbx at rsp + 2 * 8
+                11, 0x52, // at 11: allocate 6 * 8 bytes
+                1, 0x50, // at 1: push rbp
+                0, 0, // padding
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_large_allocation() {
+        let rows = [UnwindRow {
+            offset: 40,
+            rule: UnwindRule::StackPointer {
+                offset: 8 + 0x10_0000,
+            },
+        }];
+
+        assert_eq!(
+            encode_unwind_info(&rows),
+            [1, 40, 3, 0, 40, 0x11, 0x00, 0x00, 0x10, 0x00, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! Stack maps: for every call a procedure makes, the stack slots that hold refcounted pointers
+//! while the callee runs. Memory tools (leak detectors, heap snapshots) can walk the stack with
+//! the unwind tables and use these to find every heap allocation a Roc frame keeps alive.
+//!
+//! They are only generated when [Env::stack_maps](crate::Env) is set, into a `.roc_stack_maps`
+//! section. The section starts with a 4 byte version, followed by one entry per procedure:
+//!
+//! ```text
+//! u32  length of the rest of the entry
+//! i32  start of the procedure, relative to this field
+//! u32  number of call sites
+//! for each call site:
+//!     u32  offset of the return address from the start of the procedure
+//!     u32  number of slots
+//!     for each slot:
+//!         i32  offset from the frame pointer (rbp on x86_64, x29 on aarch64)
+//!         u8   SlotKind
+//! zero padding up to a multiple of 4 bytes
+//! ```
+//!
+//! All integers are little endian. Only slots in the frame are described: a pointer that stays
+//! in a callee-saved register across the call is not.
+use object::write::{self, Object, SectionId, SymbolId};
+use object::{RelocationEncoding, RelocationKind, SectionKind};
+use roc_mono::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr};
+use roc_target::Target;
+
+pub const STACK_MAPS_SECTION_NAME: &[u8] = b".roc_stack_maps";
+pub const STACK_MAPS_VERSION: u32 = 1;
+
+/// How to interpret the pointer-sized value in a slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SlotKind {
+    /// The first word of a `Str`. Not a pointer if the string is small.
+    Str = 0,
+    /// The first word of a `List`. May be null, and points into the middle of an allocation for
+    /// seamless slices.
+    List = 1,
+    /// A pointer to the data of a recursive tag union. May be null, and may carry a tag id in its
+    /// low bits.
+    Union = 2,
+}
+
+impl SlotKind {
+    fn from_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(SlotKind::Str),
+            1 => Some(SlotKind::List),
+            2 => Some(SlotKind::Union),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    /// Offset from the frame pointer.
+    pub frame_offset: i32,
+    pub kind: SlotKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    /// Offset of the return address from the start of the procedure.
+    pub return_offset: u32,
+    pub slots: std::vec::Vec<Slot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcStackMap {
+    /// Where the procedure starts, relative to the start of the section.
+    /// Only meaningful once the object has been linked.
+    pub proc_offset: i64,
+    pub call_sites: std::vec::Vec<CallSite>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackMapError {
+    UnsupportedVersion(u32),
+    UnexpectedEnd,
+    UnknownSlotKind { offset: usize, kind: u8 },
+}
+
+/// The refcounted pointers stored in a value of this layout, as (offset into the value, kind).
+/// Non-recursive tag unions are skipped: where their pointers are depends on the tag.
+pub(crate) fn refcounted_slots<'a>(
+    interner: &impl LayoutInterner<'a>,
+    layout: InLayout<'a>,
+    offset: i32,
+    slots: &mut std::vec::Vec<Slot>,
+) {
+    let kind = match interner.get_repr(layout) {
+        LayoutRepr::Builtin(Builtin::Str) => SlotKind::Str,
+        LayoutRepr::Builtin(Builtin::List(_)) => SlotKind::List,
+        LayoutRepr::Union(union_layout) if union_layout.is_recursive() => SlotKind::Union,
+        LayoutRepr::RecursivePointer(_) => SlotKind::Union,
+        LayoutRepr::Struct(field_layouts) => {
+            let mut field_offset = offset;
+            for field_layout in field_layouts {
+                refcounted_slots(interner, *field_layout, field_offset, slots);
+                field_offset += interner.stack_size(*field_layout) as i32;
+            }
+            return;
+        }
+        LayoutRepr::LambdaSet(lambda_set) => {
+            return refcounted_slots(interner, lambda_set.runtime_representation(), offset, slots);
+        }
+        LayoutRepr::Builtin(_)
+        | LayoutRepr::Union(_)
+        | LayoutRepr::Ptr(_)
+        | LayoutRepr::FunctionPointer(_)
+        | LayoutRepr::Erased(_) => return,
+    };
+
+    slots.push(Slot {
+        frame_offset: offset,
+        kind,
+    });
+}
+
+pub(crate) struct StackMaps {
+    section: SectionId,
+}
+
+impl StackMaps {
+    /// Adds an empty `.roc_stack_maps` section.
+    /// Returns `None` for targets we don't generate stack maps for yet, which are the same ones
+    /// we have no unwind tables for.
+    pub fn new(output: &mut Object, target: Target) -> Option<Self> {
+        if !matches!(target, Target::LinuxX64 | Target::LinuxArm64) {
+            return None;
+        }
+
+        let section = output.add_section(
+            vec![],
+            STACK_MAPS_SECTION_NAME.to_vec(),
+            SectionKind::ReadOnlyData,
+        );
+        output.append_section_data(section, &STACK_MAPS_VERSION.to_le_bytes(), 4);
+
+        Some(Self { section })
+    }
+
+    /// Adds the stack map of the procedure starting at `symbol`.
+    pub fn add_procedure(&self, output: &mut Object, symbol: SymbolId, call_sites: &[CallSite]) {
+        if call_sites.is_empty() {
+            return;
+        }
+
+        let mut entry = std::vec::Vec::new();
+        // The start of the procedure, filled in by the relocation below.
+        entry.extend(0i32.to_le_bytes());
+        encode_call_sites(&mut entry, call_sites);
+        // Slots are 5 bytes, so pad the entry ourselves: padding added by `object` to align the
+        // next entry would not be counted in the length.
+        entry.resize(entry.len().next_multiple_of(4), 0);
+
+        let mut data = std::vec::Vec::with_capacity(entry.len() + 4);
+        data.extend((entry.len() as u32).to_le_bytes());
+        data.extend(entry);
+        let offset = output.append_section_data(self.section, &data, 4);
+
+        let relocation = write::Relocation {
+            offset: offset + 4,
+            size: 32,
+            kind: RelocationKind::Relative,
+            encoding: RelocationEncoding::Generic,
+            symbol,
+            addend: 0,
+        };
+        if let Err(e) = output.add_relocation(self.section, relocation) {
+            roc_error_macros::internal_error!("{:?}", e);
+        }
+    }
+}
+
+fn encode_call_sites(buf: &mut std::vec::Vec<u8>, call_sites: &[CallSite]) {
+    buf.extend((call_sites.len() as u32).to_le_bytes());
+    for call_site in call_sites {
+        buf.extend(call_site.return_offset.to_le_bytes());
+        buf.extend((call_site.slots.len() as u32).to_le_bytes());
+        for slot in call_site.slots.iter() {
+            buf.extend(slot.frame_offset.to_le_bytes());
+            buf.push(slot.kind as u8);
+        }
+    }
+}
+
+/// Reads the contents of a linked `.roc_stack_maps` section.
+pub fn read(section: &[u8]) -> Result<std::vec::Vec<ProcStackMap>, StackMapError> {
+    let mut reader = Reader {
+        bytes: section,
+        pos: 0,
+    };
+
+    let version = reader.u32()?;
+    if version != STACK_MAPS_VERSION {
+        return Err(StackMapError::UnsupportedVersion(version));
+    }
+
+    let mut procs = std::vec::Vec::new();
+    while reader.pos < section.len() {
+        let length = reader.u32()? as usize;
+        if length > section.len() - reader.pos {
+            return Err(StackMapError::UnexpectedEnd);
+        }
+
+        let proc_field = reader.pos;
+        let proc_offset = proc_field as i64 + reader.i32()? as i64;
+
+        let call_site_count = reader.u32()?;
+        let mut call_sites = std::vec::Vec::new();
+        for _ in 0..call_site_count {
+            let return_offset = reader.u32()?;
+            let slot_count = reader.u32()?;
+            let mut slots = std::vec::Vec::new();
+            for _ in 0..slot_count {
+                let frame_offset = reader.i32()?;
+                let kind_offset = reader.pos;
+                let kind = reader.u8()?;
+                let kind = SlotKind::from_u8(kind).ok_or(StackMapError::UnknownSlotKind {
+                    offset: kind_offset,
+                    kind,
+                })?;
+                slots.push(Slot { frame_offset, kind });
+            }
+            call_sites.push(CallSite {
+                return_offset,
+                slots,
+            });
+        }
+
+        // Skips the padding, and anything later versions add to an entry.
+        reader.pos = proc_field + length;
+        procs.push(ProcStackMap {
+            proc_offset,
+            call_sites,
+        });
+    }
+
+    Ok(procs)
+}
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], StackMapError> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or(StackMapError::UnexpectedEnd)?;
+        self.pos += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, StackMapError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, StackMapError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, StackMapError> {
+        Ok(i32::from_le_bytes(self.take()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bumpalo::Bump;
+    use roc_mono::layout::{Layout, STLayoutInterner, UnionLayout};
+
+    use super::*;
+
+    fn example_call_sites() -> std::vec::Vec<CallSite> {
+        vec![
+            CallSite {
+                return_offset: 0x20,
+                slots: vec![
+                    Slot {
+                        frame_offset: -24,
+                        kind: SlotKind::Str,
+                    },
+                    Slot {
+                        frame_offset: 16,
+                        kind: SlotKind::Union,
+                    },
+                ],
+            },
+            CallSite {
+                return_offset: 0x4c,
+                slots: vec![],
+            },
+        ]
+    }
+
+    fn entry(proc_field: i32, call_sites: &[CallSite]) -> std::vec::Vec<u8> {
+        let mut entry = proc_field.to_le_bytes().to_vec();
+        encode_call_sites(&mut entry, call_sites);
+
+        let mut data = (entry.len() as u32).to_le_bytes().to_vec();
+        data.extend(entry);
+        data
+    }
+
+    fn odd_call_sites() -> std::vec::Vec<CallSite> {
+        vec![CallSite {
+            return_offset: 0x10,
+            slots: vec![Slot {
+                frame_offset: -8,
+                kind: SlotKind::List,
+            }],
+        }]
+    }
+
+    #[test]
+    fn test_refcounted_slots() {
+        let arena = &Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, Target::LinuxX64);
+
+        let list =
+            interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::U8)));
+        let linked_list = interner.insert_direct_no_semantic(LayoutRepr::Union(
+            UnionLayout::NonNullableUnwrapped(arena.alloc([Layout::I64])),
+        ));
+        let tag_union = interner.insert_direct_no_semantic(LayoutRepr::Union(
+            UnionLayout::NonRecursive(arena.alloc([&*arena.alloc([Layout::STR]) as &[_]])),
+        ));
+        let inner = interner
+            .insert_direct_no_semantic(LayoutRepr::struct_(arena.alloc([Layout::I64, list])));
+        let outer = interner.insert_direct_no_semantic(LayoutRepr::struct_(arena.alloc([
+            Layout::STR,
+            inner,
+            tag_union,
+            linked_list,
+        ])));
+
+        let mut slots = std::vec::Vec::new();
+        refcounted_slots(&interner, outer, -96, &mut slots);
+
+        let slot = |frame_offset, kind| Slot { frame_offset, kind };
+        assert_eq!(
+            slots,
+            [
+                slot(-96, SlotKind::Str),
+                slot(-96 + 24 + 8, SlotKind::List),
+                // the Str in the tag union is skipped
+                slot(-96 + 24 + 32 + 24, SlotKind::Union),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_call_sites() {
+        let mut buf = std::vec::Vec::new();
+        encode_call_sites(&mut buf, &example_call_sites());
+
+        #[rustfmt::skip]
+        assert_eq!(
+            buf,
+            [
+                2, 0, 0, 0, // call sites
+                0x20, 0, 0, 0, 2, 0, 0, 0, // return offset, slots
+                0xe8, 0xff, 0xff, 0xff, 0, // rbp - 24, Str
+                0x10, 0, 0, 0, 2, // rbp + 16, Union
+                0x4c, 0, 0, 0, 0, 0, 0, 0, // return offset, no slots
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_round_trip() {
+        use object::{Object as _, ObjectSection, ObjectSymbol, RelocationTarget};
+
+        let mut output = Object::new(
+            object::BinaryFormat::Elf,
+            object::Architecture::X86_64,
+            object::Endianness::Little,
+        );
+        let text = output.section_id(write::StandardSection::Text);
+        output.append_section_data(text, &[0xc3; 0x80], 16);
+
+        let mut add_symbol = |name: &[u8], value| {
+            output.add_symbol(write::Symbol {
+                name: name.to_vec(),
+                value,
+                size: 0x20,
+                kind: object::SymbolKind::Text,
+                scope: object::SymbolScope::Linkage,
+                weak: false,
+                section: write::SymbolSection::Section(text),
+                flags: object::SymbolFlags::None,
+            })
+        };
+        let first = add_symbol(b"first", 0);
+        let second = add_symbol(b"second", 0x20);
+        let third = add_symbol(b"third", 0x40);
+
+        let stack_maps = StackMaps::new(&mut output, Target::LinuxX64).unwrap();
+        // An odd number of slots, so these entries need padding.
+        stack_maps.add_procedure(&mut output, first, &odd_call_sites());
+        stack_maps.add_procedure(&mut output, second, &example_call_sites());
+        stack_maps.add_procedure(&mut output, third, &odd_call_sites());
+
+        let bytes = output.write().unwrap();
+        let file = object::File::parse(bytes.as_slice()).unwrap();
+        let section = file.section_by_name_bytes(STACK_MAPS_SECTION_NAME).unwrap();
+
+        let procs = read(section.data().unwrap()).unwrap();
+        let call_sites: std::vec::Vec<_> = procs.iter().map(|proc| &proc.call_sites).collect();
+        assert_eq!(
+            call_sites,
+            [&odd_call_sites(), &example_call_sites(), &odd_call_sites()]
+        );
+
+        // Until the object is linked, where each procedure starts is only in the relocations.
+        let relocations: std::vec::Vec<_> = section
+            .relocations()
+            .map(|(offset, relocation)| {
+                let RelocationTarget::Symbol(index) = relocation.target() else {
+                    panic!("unexpected relocation target {:?}", relocation.target());
+                };
+                let name = file.symbol_by_index(index).unwrap().name().unwrap();
+
+                (offset as i64, name.to_string())
+            })
+            .collect();
+        let expected: std::vec::Vec<_> = procs
+            .iter()
+            .zip(["first", "second", "third"])
+            .map(|(proc, name)| (proc.proc_offset, name.to_string()))
+            .collect();
+        assert_eq!(relocations, expected);
+    }
+
+    #[test]
+    fn test_read_rejects_malformed_sections() {
+        let mut section = STACK_MAPS_VERSION.to_le_bytes().to_vec();
+        section.extend(entry(0, &example_call_sites()));
+
+        for len in 0..section.len() {
+            let expected = if len == 4 {
+                // just the version
+                None
+            } else {
+                Some(StackMapError::UnexpectedEnd)
+            };
+            assert_eq!(read(&section[..len]).err(), expected, "truncated to {len}");
+        }
+
+        let mut unknown_kind = section.clone();
+        unknown_kind[28] = 7;
+        assert_eq!(
+            read(&unknown_kind),
+            Err(StackMapError::UnknownSlotKind {
+                offset: 28,
+                kind: 7
+            })
+        );
+
+        section[0] = 2;
+        assert_eq!(read(&section), Err(StackMapError::UnsupportedVersion(2)));
+    }
+}
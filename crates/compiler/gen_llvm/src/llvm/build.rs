@@ -33,7 +33,7 @@ use inkwell::values::{
     FunctionValue, InstructionOpcode, InstructionValue, IntMathValue, IntValue, PhiValue,
     PointerMathValue, PointerValue, StructValue,
 };
-use inkwell::{AddressSpace, IntPredicate};
+use inkwell::{AddressSpace, AtomicOrdering, AtomicRMWBinOp, IntPredicate};
 use inkwell::{FloatPredicate, OptimizationLevel};
 use morphic_lib::{
     CalleeSpecVar, FuncName, FuncSpec, FuncSpecSolutions, ModSolutions, UpdateMode, UpdateModeVar,
@@ -45,9 +45,11 @@ use roc_debug_flags::dbg_do;
 use roc_debug_flags::ROC_PRINT_LLVM_FN_VERIFICATION;
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::coverage::Coverage;
 use roc_mono::ir::{
-    BranchInfo, CallType, CrashTag, EntryPoint, GlueLayouts, HostExposedLambdaSet,
-    HostExposedLambdaSets, ListLiteralElement, ModifyRc, OptLevel, ProcLayout, SingleEntryPoint,
+    procedures_in_stable_order, BranchInfo, CallType, CrashTag, EntryPoint, GlueLayouts,
+    HostExposedLambdaSet, HostExposedLambdaSets, ListLiteralElement, ModifyRc, OptLevel,
+    ProcLayout, SingleEntryPoint,
 };
 use roc_mono::layout::{
     Builtin, InLayout, LambdaName, LambdaSet, Layout, LayoutIds, LayoutInterner, LayoutRepr, Niche,
@@ -55,6 +57,7 @@ use roc_mono::layout::{
 };
 use roc_std::RocDec;
 use roc_target::{PtrWidth, Target};
+use std::cell::RefCell;
 use std::convert::TryInto;
 use std::path::Path;
 
@@ -741,6 +744,8 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target: Target,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    /// Counts which procs and branches run, for `roc test --coverage`
+    pub coverage: Option<&'env RefCell<Coverage>>,
 }
 
 impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
@@ -771,7 +776,7 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     }
 
     pub fn small_str_bytes(&self) -> u32 {
-        self.target.ptr_width() as u32 * 3
+        self.target.str_list_size() as u32
     }
 
     pub fn build_intrinsic_call(
@@ -2538,10 +2543,10 @@ fn tag_pointer_set_tag_id<'ctx>(
 }
 
 pub fn tag_pointer_tag_id_bits_and_mask(target: Target) -> (u64, u64) {
-    match target.ptr_width() {
-        roc_target::PtrWidth::Bytes8 => (3, 0b0000_0111),
-        roc_target::PtrWidth::Bytes4 => (2, 0b0000_0011),
-    }
+    (
+        target.tag_id_pointer_bits() as u64,
+        target.tag_id_pointer_mask(),
+    )
 }
 
 pub fn tag_pointer_read_tag_id<'ctx>(
@@ -4183,6 +4188,7 @@ fn build_switch_ir<'a, 'ctx>(
 
                 {
                     builder.position_at_end(then_block);
+                    count_branch(env, true_branch);
 
                     let branch_val = build_exp_stmt(
                         env,
@@ -4202,6 +4208,7 @@ fn build_switch_ir<'a, 'ctx>(
 
                 {
                     builder.position_at_end(else_block);
+                    count_branch(env, false_branch);
 
                     let branch_val = build_exp_stmt(
                         env,
@@ -4256,6 +4263,7 @@ fn build_switch_ir<'a, 'ctx>(
 
         for ((_, _, branch_expr), (_, block)) in branches.iter().zip(cases) {
             builder.position_at_end(block);
+            count_branch(env, branch_expr);
 
             let branch_val = build_exp_stmt(
                 env,
@@ -4275,6 +4283,7 @@ fn build_switch_ir<'a, 'ctx>(
 
         // The block for the conditional's default branch.
         builder.position_at_end(default_block);
+        count_branch(env, default_branch);
 
         let default_val = build_exp_stmt(
             env,
@@ -4312,6 +4321,59 @@ fn build_switch_ir<'a, 'ctx>(
     }
 }
 
+/// Counts how often `branch` runs, if `roc test --coverage` asked for it.
+fn count_branch(env: &Env<'_, '_, '_>, branch: &roc_mono::ir::Stmt<'_>) {
+    if let Some(coverage) = env.coverage {
+        let counter = coverage.borrow_mut().branch(branch);
+        if let Some(counter) = counter {
+            build_coverage_increment(env, counter);
+        }
+    }
+}
+
+/// The global that `roc test --coverage` points at its array of `u64` counters
+pub const COVERAGE_COUNTERS: &str = "roc_coverage_counters";
+
+fn build_coverage_increment(env: &Env<'_, '_, '_>, counter: u32) {
+    let counter_type = env.context.i64_type();
+    let counters_type = counter_type.ptr_type(AddressSpace::default());
+
+    let global = env.module.get_global(COVERAGE_COUNTERS).unwrap_or_else(|| {
+        let global = env
+            .module
+            .add_global(counters_type, None, COVERAGE_COUNTERS);
+        global.set_initializer(&counters_type.const_null());
+        global
+    });
+
+    let counters = env
+        .builder
+        .new_build_load(
+            counters_type,
+            global.as_pointer_value(),
+            "coverage_counters",
+        )
+        .into_pointer_value();
+    let counter_ptr = unsafe {
+        env.builder.new_build_in_bounds_gep(
+            counter_type,
+            counters,
+            &[env.context.i32_type().const_int(counter as u64, false)],
+            "coverage_counter",
+        )
+    };
+
+    // The tests may run in several processes at once, all sharing the counters
+    env.builder
+        .build_atomicrmw(
+            AtomicRMWBinOp::Add,
+            counter_ptr,
+            counter_type.const_int(1, false),
+            AtomicOrdering::Monotonic,
+        )
+        .unwrap();
+}
+
 /// Creates a new stack allocation instruction in the entry block of the function.
 pub fn create_entry_block_alloca<'ctx, T: BasicType<'ctx>>(
     env: &Env<'_, 'ctx, '_>,
@@ -5504,6 +5566,8 @@ pub(crate) fn build_proc_headers<'a, 'r, 'ctx>(
     roc_mono::ir::Proc<'a>,
     std::vec::Vec<(&'a FuncSpecSolutions, FunctionValue<'ctx>)>,
 )> {
+    let procedures = procedures_in_stable_order(procedures, &env.interns, layout_interner);
+
     // Populate Procs further and get the low-level Expr from the canonical Expr
     let mut headers = std::vec::Vec::with_capacity(procedures.len());
     for ((symbol, layout), proc) in procedures {
@@ -5661,8 +5725,36 @@ pub fn build_procedures_expose_expects<'a>(
     expects_by_module: MutMap<ModuleId, Vec<'a, Symbol>>,
     procedures: MutMap<(Symbol, ProcLayout<'a>), roc_mono::ir::Proc<'a>>,
 ) -> MutMap<ModuleId, Vec<'a, &'a str>> {
-    // converts Vec<Vec<Symbol>> into Vec<Symbol>
-    let flattened_symbols: Vec<Symbol> =
+    // Expects return `{}`, but benchmarks return whatever they compute
+    let unit_thunk = ProcLayout {
+        arguments: &[],
+        result: Layout::UNIT,
+        niche: Niche::NONE,
+    };
+
+    let thunk_layout = |symbol: Symbol| {
+        procedures
+            .keys()
+            .find(|(s, layout)| *s == symbol && layout.arguments.is_empty())
+            .map_or(unit_thunk, |(_, layout)| *layout)
+    };
+
+    let expects_by_module: MutMap<ModuleId, Vec<'a, (Symbol, ProcLayout<'a>)>> = expects_by_module
+        .into_iter()
+        .map(|(module_id, symbols)| {
+            let with_layouts = Vec::from_iter_in(
+                symbols
+                    .iter()
+                    .map(|symbol| (*symbol, thunk_layout(*symbol))),
+                env.arena,
+            );
+
+            (module_id, with_layouts)
+        })
+        .collect();
+
+    // converts Vec<Vec<(Symbol, ProcLayout)>> into Vec<(Symbol, ProcLayout)>
+    let flattened_symbols: Vec<(Symbol, ProcLayout<'a>)> =
         Vec::from_iter_in(expects_by_module.values().flatten().copied(), env.arena);
 
     let entry_point = EntryPoint::Expects {
@@ -5679,26 +5771,18 @@ pub fn build_procedures_expose_expects<'a>(
         Some(&std::env::temp_dir().join("test.ll")),
     );
 
-    let captures_niche = Niche::NONE;
-
-    let top_level = ProcLayout {
-        arguments: &[],
-        result: Layout::UNIT,
-        niche: captures_niche,
-    };
-
     let mut expect_names_by_module = MutMap::default();
 
     for (module_id, expects) in expects_by_module {
         let mut expect_names = Vec::with_capacity_in(expects.len(), env.arena);
 
-        for symbol in expects.iter().copied() {
+        for (symbol, top_level) in expects.iter().copied() {
             let args_iter = top_level.arguments.iter().copied();
 
             let func_name_bytes = roc_alias_analysis::func_name_bytes_help(
                 symbol,
                 args_iter,
-                captures_niche,
+                top_level.niche,
                 top_level.result,
             );
 
@@ -6252,6 +6336,13 @@ fn build_proc<'a, 'ctx>(
 
     debug_info_init!(env, fn_val);
 
+    if let Some(coverage) = env.coverage {
+        let counter = coverage.borrow_mut().proc_entry(proc.name.name());
+        if let Some(counter) = counter {
+            build_coverage_increment(env, counter);
+        }
+    }
+
     // Add args to scope
     for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
         arg_val.set_name(arg_symbol.as_str(&env.interns));
@@ -1,57 +1,178 @@
 use bitvec::vec::BitVec;
 use bumpalo::collections::{String, Vec};
+use bumpalo::Bump;
 
 use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
 use roc_collections::all::MutMap;
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, Symbol};
-use roc_mono::code_gen_help::{CodeGenHelp, HelperOp, REFCOUNT_MAX};
+use roc_mono::code_gen_help::{CodeGenHelp, HelperOp};
 use roc_mono::ir::{
     BranchInfo, CallType, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc,
     Param, Proc, ProcLayout, Stmt,
 };
 use roc_mono::layout::{
-    Builtin, InLayout, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
-    TagIdIntType, UnionLayout,
+    Builtin, InLayout, LambdaSet, Layout, LayoutIds, LayoutInterner, LayoutRepr,
+    NeedsRecursionPointerFixup, Niche, STLayoutInterner, TLLayoutInterner, TagIdIntType,
+    UnionLayout,
 };
+use roc_mono::source_locations::SourceLocations;
 use roc_std::RocDec;
+use roc_target::Target;
 
-use roc_wasm_module::linking::{DataSymbol, WasmObjectSymbol};
+use roc_wasm_module::linking::{
+    DataSymbol, LinkingSegment, WasmObjectSymbol, WASM_SYM_BINDING_LOCAL,
+};
 use roc_wasm_module::sections::{
-    ConstExpr, DataMode, DataSegment, Export, Global, GlobalType, Import, ImportDesc, Limits,
-    MemorySection, NameSection,
+    ConstExpr, CustomSection, DataMode, DataSegment, Export, Global, GlobalType, Import,
+    ImportDesc, Limits, MemorySection, NameSection, ProducersSection, TargetFeaturesSection,
 };
 use roc_wasm_module::{
-    round_up_to_alignment, Align, ExportType, LocalId, Signature, SymInfo, ValueType, WasmModule,
+    round_up_to_alignment, Align, ExportType, LocalId, SerialBuffer, Serialize, Signature, SymInfo,
+    ValueType, WasmModule, STACK_POINTER_GLOBAL_ID,
 };
 
-use crate::code_builder::CodeBuilder;
-use crate::layout::{ReturnMethod, WasmLayout};
+use crate::code_builder::{CodeBuilder, SerializedFunction};
+use crate::constants::ModuleConstants;
+use crate::layout::{multi_value_return_fields, ReturnField, ReturnMethod, WasmLayout};
 use crate::low_level::{call_higher_order_lowlevel, LowLevelCall};
 use crate::storage::{AddressValue, Storage, StoredValue, StoredVarKind};
+use crate::string_pool::PooledStr;
 use crate::{
-    copy_memory, CopyMemoryConfig, Env, DEBUG_SETTINGS, MEMORY_NAME, PTR_SIZE, PTR_TYPE, TARGET,
+    copy_memory, Allocator, CopyMemoryConfig, Env, ALLOC_SITES_SECTION_NAME,
+    ALLOC_SITE_GLOBAL_NAME, DEBUG_SETTINGS, FLOAT_SEMANTICS_CANONICAL_NANS,
+    FLOAT_SEMANTICS_SECTION_NAME, FN_TABLE_NAME, MEMORY_NAME, PATCHABLE_FNS_SECTION_NAME,
+    PATCH_FN_NAME, PTR_SIZE, PTR_TYPE, ROC_VERSION, TAG_ID_MASK, TARGET,
 };
 
-#[derive(Clone, Copy, Debug)]
+/// Defined right after the stack pointer, when heap profiling is enabled
+const ALLOC_SITE_GLOBAL_ID: u32 = STACK_POINTER_GLOBAL_ID + 1;
+
+/// Top bit of a Str's length, marking it as a slice of another Str
+const SEAMLESS_SLICE_BIT: usize = 1 << 31;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ProcSource {
     Roc,
     Helper,
     /// Wrapper function for higher-order calls from Zig to Roc
     HigherOrderCompare(usize),
+    /// Byte-by-byte equality of two values in memory, shared by all layouts of this size
+    MemoryEquals {
+        size: u32,
+        alignment_bytes: u32,
+    },
+    /// `roc_alloc` for the app's own allocator, see `Allocator`
+    BumpAlloc,
+    /// `roc_realloc` for the app's own allocator
+    BumpRealloc,
+    /// `roc_dealloc` for the app's own allocator
+    BumpDealloc,
+    /// Frees the heap, then calls a proc exposed to the host, for `Allocator::ArenaPerCall`
+    ResetHeapAndCall(usize),
+    /// Calls a proc exposed to the host through the function table, for `Env::patchable_calls`
+    PatchableEntry(usize),
+    /// `PATCH_FN_NAME`, for `Env::patchable_calls`
+    PatchFn,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct ProcLookupData<'a> {
     pub name: Symbol,
     pub layout: ProcLayout<'a>,
     pub source: ProcSource,
 }
 
+/// The layout interner of a backend. Workers only read layouts, and leave anything that would
+/// intern new ones to the main thread, see [crate::parallel].
+pub enum BackendInterner<'a, 'r> {
+    Main(&'r mut STLayoutInterner<'a>),
+    Worker(TLLayoutInterner<'a>),
+}
+
+impl<'a> BackendInterner<'a, '_> {
+    /// The interner of the main thread, for interning layouts and generating helper procs
+    pub fn single_threaded(&mut self) -> &mut STLayoutInterner<'a> {
+        match self {
+            BackendInterner::Main(interner) => interner,
+            BackendInterner::Worker(_) => {
+                internal_error!(
+                    "A worker thread can't intern layouts, it must defer to the main thread"
+                )
+            }
+        }
+    }
+}
+
+impl<'a> LayoutInterner<'a> for BackendInterner<'a, '_> {
+    fn insert(&mut self, value: Layout<'a>) -> InLayout<'a> {
+        self.single_threaded().insert(value)
+    }
+
+    fn insert_lambda_set(
+        &mut self,
+        arena: &'a Bump,
+        args: &'a &'a [InLayout<'a>],
+        ret: InLayout<'a>,
+        set: &'a &'a [(Symbol, &'a [InLayout<'a>])],
+        needs_recursive_fixup: NeedsRecursionPointerFixup,
+        representation: InLayout<'a>,
+    ) -> LambdaSet<'a> {
+        self.single_threaded().insert_lambda_set(
+            arena,
+            args,
+            ret,
+            set,
+            needs_recursive_fixup,
+            representation,
+        )
+    }
+
+    fn insert_recursive(&mut self, arena: &'a Bump, normalized_layout: Layout<'a>) -> InLayout<'a> {
+        self.single_threaded()
+            .insert_recursive(arena, normalized_layout)
+    }
+
+    fn get(&self, key: InLayout<'a>) -> Layout<'a> {
+        match self {
+            BackendInterner::Main(interner) => interner.get(key),
+            BackendInterner::Worker(interner) => interner.get(key),
+        }
+    }
+
+    fn target(&self) -> Target {
+        match self {
+            BackendInterner::Main(interner) => interner.target(),
+            BackendInterner::Worker(interner) => interner.target(),
+        }
+    }
+}
+
+/// A proc generated by a worker thread, to be added to the module by the main thread
+pub struct GeneratedProc {
+    param_types: std::vec::Vec<ValueType>,
+    ret_types: std::vec::Vec<ValueType>,
+    /// Host functions the proc calls
+    called_fns: std::vec::Vec<u32>,
+    /// Debug names of the symbols the proc created, to create again in the main thread's interns
+    created_symbols: std::vec::Vec<std::string::String>,
+    code: SerializedFunction,
+}
+
+/// The state of the main thread's backend that workers read, see [WasmBackend::new_worker]
+pub struct WorkerSeed<'a, 'r> {
+    pub interns: &'r Interns,
+    proc_lookup: &'r [ProcLookupData<'a>],
+    host_lookup: &'r [(&'a str, u32)],
+    fn_index_offset: u32,
+    import_fn_count: u32,
+    can_relocate_heap: bool,
+}
+
 pub struct WasmBackend<'a, 'r> {
     pub env: &'r Env<'a>,
-    pub(crate) layout_interner: &'r mut STLayoutInterner<'a>,
+    pub(crate) layout_interner: BackendInterner<'a, 'r>,
     interns: &'r mut Interns,
 
     // Module-level data
@@ -64,14 +185,26 @@ pub struct WasmBackend<'a, 'r> {
     host_lookup: Vec<'a, (&'a str, u32)>,
     helper_proc_gen: CodeGenHelp<'a>,
     can_relocate_heap: bool,
+    /// The allocated symbol, enclosing procedure and kind of each heap allocation site,
+    /// when heap profiling is enabled
+    alloc_sites: Vec<'a, (Symbol, Symbol, &'static str)>,
+    /// Constants of each module whose code uses any, see [crate::constants]
+    constants: Vec<'a, ModuleConstants<'a>>,
 
     // Function-level data
+    proc_name: Option<Symbol>,
     pub code_builder: CodeBuilder<'a>,
     pub storage: Storage<'a>,
 
     /// how many blocks deep are we (used for jumps)
     block_depth: u32,
     joinpoint_label_map: MutMap<JoinPointId, (u32, Vec<'a, StoredValue>)>,
+
+    /// What a worker thread has generated of the current proc, apart from its code.
+    /// `None` on the main thread.
+    worker_proc: Option<GeneratedProc>,
+    /// Whether the current proc needs something only the main thread can do, see [Self::defer_to_main_thread]
+    deferred: bool,
 }
 
 impl<'a, 'r> WasmBackend<'a, 'r> {
@@ -121,7 +254,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
         WasmBackend {
             env,
-            layout_interner,
+            layout_interner: BackendInterner::Main(layout_interner),
             interns,
 
             // Module-level data
@@ -134,12 +267,67 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             host_lookup,
             helper_proc_gen,
             can_relocate_heap: has_heap_base && has_heap_end,
+            alloc_sites: Vec::new_in(env.arena),
+            constants: Vec::new_in(env.arena),
 
             // Function-level data
+            proc_name: None,
             block_depth: 0,
             joinpoint_label_map: MutMap::default(),
             code_builder: CodeBuilder::new(env.arena),
             storage: Storage::new(env.arena),
+            worker_proc: None,
+            deferred: false,
+        }
+    }
+
+    /// What a worker thread needs to generate procs the way this backend would
+    pub fn worker_seed(&self) -> WorkerSeed<'a, '_> {
+        WorkerSeed {
+            interns: &*self.interns,
+            proc_lookup: &self.proc_lookup,
+            host_lookup: &self.host_lookup,
+            fn_index_offset: self.fn_index_offset,
+            import_fn_count: self.import_fn_count,
+            can_relocate_heap: self.can_relocate_heap,
+        }
+    }
+
+    /// A backend for a worker thread, which only generates procs with [Self::build_proc_in_worker].
+    /// Its module is a placeholder. Everything that goes into the real module is in the
+    /// [GeneratedProc]s, which the main thread adds with [Self::add_generated_proc].
+    pub fn new_worker(
+        env: &'r Env<'a>,
+        layout_interner: TLLayoutInterner<'a>,
+        interns: &'r mut Interns,
+        seed: &WorkerSeed<'a, '_>,
+    ) -> Self {
+        WasmBackend {
+            env,
+            layout_interner: BackendInterner::Worker(layout_interner),
+            interns,
+
+            // Module-level data
+            module: WasmModule::new(env.arena),
+            layout_ids: LayoutIds::default(),
+            fn_index_offset: seed.fn_index_offset,
+            import_fn_count: seed.import_fn_count,
+            called_fns: BitVec::new(),
+            proc_lookup: Vec::from_iter_in(seed.proc_lookup.iter().copied(), env.arena),
+            host_lookup: Vec::from_iter_in(seed.host_lookup.iter().copied(), env.arena),
+            helper_proc_gen: CodeGenHelp::new(env.arena, TARGET, env.module_id),
+            can_relocate_heap: seed.can_relocate_heap,
+            alloc_sites: Vec::new_in(env.arena),
+            constants: Vec::new_in(env.arena),
+
+            // Function-level data
+            proc_name: None,
+            block_depth: 0,
+            joinpoint_label_map: MutMap::default(),
+            code_builder: CodeBuilder::new(env.arena),
+            storage: Storage::new(env.arena),
+            worker_proc: None,
+            deferred: false,
         }
     }
 
@@ -148,7 +336,8 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     /// Since they're all in one block, they can't grow independently. Only the highest one can grow.
     /// Also, there's no "invalid region" below the stack, so stack overflow will overwrite constants!
     /// TODO: Detect stack overflow in function prologue... at least in Roc code...
-    fn set_memory_layout(&mut self, stack_size: u32) {
+    /// Returns the address where the heap starts.
+    fn set_memory_layout(&mut self, stack_size: u32) -> u32 {
         let mut stack_heap_boundary = self.module.data.end_addr + stack_size;
         stack_heap_boundary = round_up_to_alignment!(stack_heap_boundary, MemorySection::PAGE_SIZE);
 
@@ -217,6 +406,54 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 )
                 .unwrap();
         }
+
+        stack_heap_boundary
+    }
+
+    /// Give each module's constants a data segment of their own, right after the host's data,
+    /// and fill in their addresses in the code. Modules are placed in order of name, so that the
+    /// layout doesn't depend on the order their procs were built in.
+    fn place_constants(&mut self) {
+        let interns = &*self.interns;
+        self.constants
+            .sort_by_key(|constants| interns.module_ids.get_name(constants.module_id));
+
+        let all_constants = std::mem::replace(&mut self.constants, Vec::new_in(self.env.arena));
+        for constants in all_constants {
+            let segment_addr = round_up_to_alignment!(self.module.data.end_addr, PTR_SIZE);
+            let size = constants.bytes.len() as u32;
+            self.module.data.end_addr = segment_addr + size;
+
+            let segment_index = self.module.data.append_segment(DataSegment {
+                mode: DataMode::active_at(segment_addr),
+                init: constants.bytes,
+            });
+
+            // Only name the segment if the host named all of its own
+            if self.module.linking.segment_info.len() == segment_index as usize {
+                self.module.linking.segment_info.push(LinkingSegment {
+                    name: constants.name,
+                    align_bytes_pow2: PTR_SIZE.trailing_zeros(),
+                    flags: 0,
+                });
+            }
+
+            if let SymInfo::Data(DataSymbol::Defined {
+                segment_index: symbol_segment,
+                size: symbol_size,
+                ..
+            }) = &mut self.module.linking.symbol_table[constants.symbol_index as usize]
+            {
+                *symbol_segment = segment_index;
+                *symbol_size = size;
+            }
+
+            self.module.reloc_code.apply_relocs_u32(
+                &mut self.module.code.bytes,
+                constants.symbol_index,
+                segment_addr,
+            );
+        }
     }
 
     /// If the host has some `extern` global variables, we need to create them in the final binary
@@ -256,6 +493,137 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         }
     }
 
+    /// Define the global that holds the current allocation site, and describe every site
+    /// in a custom section. Must directly follow the stack pointer, see `ALLOC_SITE_GLOBAL_ID`.
+    fn export_alloc_sites(&mut self, source_locations: &SourceLocations) {
+        if self.module.global.count != ALLOC_SITE_GLOBAL_ID {
+            internal_error!(
+                "The allocation site global must have ID {ALLOC_SITE_GLOBAL_ID}, but the module already has {} globals",
+                self.module.global.count
+            );
+        }
+        self.module.global.append(Global {
+            ty: GlobalType {
+                value_type: ValueType::I32,
+                is_mutable: true,
+            },
+            init: ConstExpr::I32(0),
+        });
+        self.module.export.append(Export {
+            name: ALLOC_SITE_GLOBAL_NAME,
+            ty: ExportType::Global,
+            index: ALLOC_SITE_GLOBAL_ID,
+        });
+
+        let mut bytes = std::vec::Vec::new();
+        bytes.encode_u32(self.alloc_sites.len() as u32);
+        for (sym, proc_name, kind) in self.alloc_sites.iter() {
+            let location = source_locations
+                .locate(*sym, *proc_name)
+                .unwrap_or_else(|| {
+                    let module_name = proc_name.module_string(self.interns);
+                    format!("{}.{}", module_name, proc_name.as_str(self.interns))
+                });
+            location.serialize(&mut bytes);
+            kind.serialize(&mut bytes);
+        }
+
+        self.module.custom.push(CustomSection {
+            name: ALLOC_SITES_SECTION_NAME,
+            bytes: self.env.arena.alloc_slice_copy(&bytes),
+        });
+    }
+
+    /// The heap pointer and heap base of the app's own allocator come after the globals for
+    /// heap profiling, if any.
+    fn heap_pointer_global_id(&self) -> u32 {
+        ALLOC_SITE_GLOBAL_ID + self.env.heap_profiling.is_some() as u32
+    }
+
+    fn heap_base_global_id(&self) -> u32 {
+        self.heap_pointer_global_id() + 1
+    }
+
+    /// Define the globals for the app's own allocator. The heap starts out empty.
+    fn define_heap_globals(&mut self, heap_base: u32) {
+        if self.module.global.count != self.heap_pointer_global_id() {
+            internal_error!(
+                "The heap pointer global must have ID {}, but the module already has {} globals",
+                self.heap_pointer_global_id(),
+                self.module.global.count
+            );
+        }
+        for is_mutable in [true, false] {
+            self.module.global.append(Global {
+                ty: GlobalType {
+                    value_type: ValueType::I32,
+                    is_mutable,
+                },
+                init: ConstExpr::I32(heap_base as i32),
+            });
+        }
+    }
+
+    /// Put every Roc proc in the function table, export the table so that the host can add
+    /// new versions of them, and describe their table indices in a custom section.
+    fn export_patchable_fns(&mut self) {
+        let roc_procs = Vec::from_iter_in(
+            self.proc_lookup
+                .iter()
+                .enumerate()
+                .filter(|(_, lookup)| lookup.source == ProcSource::Roc)
+                .map(|(proc_index, lookup)| (proc_index, lookup.name, lookup.layout)),
+            self.env.arena,
+        );
+
+        let mut bytes = std::vec::Vec::new();
+        bytes.encode_u32(roc_procs.len() as u32);
+        for (proc_index, name, layout) in roc_procs {
+            let table_index = self.get_fn_ptr(self.fn_index_offset + proc_index as u32);
+            bytes.encode_u32(table_index as u32);
+            self.layout_ids
+                .get_toplevel(name, &layout)
+                .to_symbol_string(name, self.interns)
+                .serialize(&mut bytes);
+        }
+
+        self.module.custom.push(CustomSection {
+            name: PATCHABLE_FNS_SECTION_NAME,
+            bytes: self.env.arena.alloc_slice_copy(&bytes),
+        });
+
+        self.module.export.append(Export {
+            name: FN_TABLE_NAME,
+            ty: ExportType::Table,
+            index: 0,
+        });
+    }
+
+    /// Add Roc to the languages and tools the host was made with, and the Wasm features the
+    /// Roc code needs to the host's. The Roc code itself only needs the MVP, unless some
+    /// `Env` option asks for more.
+    fn add_producers_and_features(&mut self) {
+        let producers = &mut self.module.producers;
+        producers.add(ProducersSection::LANGUAGE, "Roc", "");
+        producers.add(ProducersSection::PROCESSED_BY, "roc", ROC_VERSION.trim());
+
+        if self.env.heap_profiling.is_some() {
+            self.module
+                .target_features
+                .use_feature(TargetFeaturesSection::MUTABLE_GLOBALS);
+        }
+        if self.env.multi_value_returns {
+            self.module
+                .target_features
+                .use_feature(TargetFeaturesSection::MULTIVALUE);
+        }
+        if self.env.patchable_calls {
+            self.module
+                .target_features
+                .use_feature(TargetFeaturesSection::REFERENCE_TYPES);
+        }
+    }
+
     pub fn get_helpers(&mut self) -> Vec<'a, Proc<'a>> {
         self.helper_proc_gen.take_procs()
     }
@@ -294,12 +662,36 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     }
 
     pub fn finalize(mut self) -> (WasmModule<'a>, BitVec<usize>) {
-        self.set_memory_layout(self.env.stack_bytes);
+        self.place_constants();
+        let heap_base = self.set_memory_layout(self.env.stack_bytes);
+        if let Some(source_locations) = self.env.heap_profiling {
+            self.export_alloc_sites(source_locations);
+        }
+        if self.env.allocator != Allocator::Host {
+            self.define_heap_globals(heap_base);
+        }
         self.export_globals();
+        if self.env.patchable_calls {
+            self.export_patchable_fns();
+        }
+
+        self.add_producers_and_features();
+
+        if self.env.canonicalize_nans {
+            self.module.custom.push(CustomSection {
+                name: FLOAT_SEMANTICS_SECTION_NAME,
+                bytes: FLOAT_SEMANTICS_CANONICAL_NANS,
+            });
+        }
 
         self.maybe_call_host_main();
         let fn_table_size = 1 + self.module.element.max_table_index();
-        self.module.table.function_table.limits = Limits::MinMax(fn_table_size, fn_table_size);
+        self.module.table.function_table.limits = if self.env.patchable_calls {
+            // The host adds new versions of procs to the table
+            Limits::Min(fn_table_size)
+        } else {
+            Limits::MinMax(fn_table_size, fn_table_size)
+        };
         (self.module, self.called_fns)
     }
 
@@ -338,7 +730,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
         self.module.add_function_signature(Signature {
             param_types: bumpalo::vec![in self.env.arena],
-            ret_type: None,
+            ret_types: bumpalo::vec![in self.env.arena],
         });
 
         self.module.export.append(Export {
@@ -371,11 +763,30 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     pub fn register_symbol_debug_names(&self) {}
 
     pub fn get_fn_ptr(&mut self, fn_index: u32) -> i32 {
+        if self.defer_to_main_thread() {
+            return 0;
+        }
         self.module.element.get_or_insert_fn(fn_index)
     }
 
+    /// On a worker thread, give up on the current proc, because it needs something that only the
+    /// main thread can do, like adding to the module or generating helper procs. The main thread
+    /// generates the proc again once the workers are done. Returns whether this is a worker thread.
+    ///
+    /// The worker carries on to the end of the proc without generating any more statements,
+    /// so callers just return a placeholder.
+    pub fn defer_to_main_thread(&mut self) -> bool {
+        let is_worker = self.worker_proc.is_some();
+        self.deferred |= is_worker;
+        is_worker
+    }
+
     /// Create an IR Symbol for an anonymous value (such as ListLiteral)
     pub fn create_symbol(&mut self, debug_name: &str) -> Symbol {
+        if let Some(worker_proc) = self.worker_proc.as_mut() {
+            worker_proc.created_symbols.push(debug_name.to_string());
+        }
+
         let ident_ids = self
             .interns
             .all_ident_ids
@@ -389,6 +800,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     /// Reset function-level data
     fn reset(&mut self) {
         self.code_builder.insert_into_module(&mut self.module);
+        self.clear_proc();
+    }
+
+    fn clear_proc(&mut self) {
         self.code_builder.clear();
         self.storage.clear();
         self.joinpoint_label_map.clear();
@@ -407,6 +822,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         }
 
         self.append_proc_debug_name(proc.name.name());
+        self.proc_name = Some(proc.name.name());
 
         self.start_proc(proc);
 
@@ -420,47 +836,165 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         }
     }
 
+    /// Generate a proc on a worker thread, or return `None` if it has to be generated on the main
+    /// thread instead, see [Self::defer_to_main_thread]
+    pub fn build_proc_in_worker(&mut self, proc: &Proc<'a>) -> Option<GeneratedProc> {
+        self.worker_proc = Some(GeneratedProc {
+            param_types: std::vec::Vec::new(),
+            ret_types: std::vec::Vec::new(),
+            called_fns: std::vec::Vec::new(),
+            created_symbols: std::vec::Vec::new(),
+            code: SerializedFunction::default(),
+        });
+        self.deferred = false;
+        self.proc_name = Some(proc.name.name());
+
+        self.start_proc(proc);
+
+        self.stmt(&proc.body);
+
+        self.finalize_proc();
+
+        let code = self.code_builder.serialize();
+        self.clear_proc();
+
+        let generated = self.worker_proc.take().unwrap();
+        if self.deferred {
+            return None;
+        }
+
+        Some(GeneratedProc { code, ..generated })
+    }
+
+    /// Add a proc that a worker thread generated, in the place `build_proc` would have generated it
+    pub fn add_generated_proc(&mut self, proc: &Proc<'a>, generated: GeneratedProc) {
+        self.append_proc_debug_name(proc.name.name());
+
+        // Give the symbols the same IDs as if this thread had generated the proc
+        for debug_name in generated.created_symbols.iter() {
+            self.create_symbol(debug_name);
+        }
+
+        let arena = self.env.arena;
+        self.module.add_function_signature(Signature {
+            param_types: Vec::from_iter_in(generated.param_types, arena),
+            ret_types: Vec::from_iter_in(generated.ret_types, arena),
+        });
+
+        for fn_index in generated.called_fns {
+            self.called_fns.set(fn_index as usize, true);
+        }
+
+        generated.code.insert_into_module(&mut self.module);
+    }
+
     fn start_proc(&mut self, proc: &Proc<'a>) {
-        use ReturnMethod::*;
-        let ret_layout = WasmLayout::new(self.layout_interner, proc.ret_layout);
+        let return_fields = self
+            .proc_lookup
+            .iter()
+            .find(|lookup| {
+                lookup.name == proc.name.name() && lookup.layout.result == proc.ret_layout
+            })
+            .and_then(|lookup| self.multi_value_return_fields(lookup));
 
-        let ret_type = match ret_layout.return_method() {
-            Primitive(ty, _) => Some(ty),
-            NoReturnValue => None,
-            WriteToPointerArg => {
-                self.storage.arg_types.push(PTR_TYPE);
-                None
-            }
-        };
+        let (ret_types, returns_through_ptr) =
+            self.proc_return_types(return_fields, proc.ret_layout);
+        if returns_through_ptr {
+            self.storage.arg_types.push(PTR_TYPE);
+        }
 
         // Create a block so we can exit the function without skipping stack frame "pop" code.
         // We never use the `return` instruction. Instead, we break from this block.
         self.start_block();
 
         self.storage.allocate_args(
-            self.layout_interner,
+            &self.layout_interner,
             proc.args,
             &mut self.code_builder,
             self.env.arena,
         );
 
-        if let Some(ty) = ret_type {
-            let ret_var = self.storage.create_anonymous_local(ty);
-            self.storage.return_var = Some(ret_var);
+        for ty in ret_types.iter() {
+            let ret_var = self.storage.create_anonymous_local(*ty);
+            self.storage.return_vars.push(ret_var);
         }
+        self.storage.return_fields = return_fields.unwrap_or(&[]);
 
-        self.module.add_function_signature(Signature {
-            param_types: self.storage.arg_types.clone(),
-            ret_type,
-        });
+        if let Some(worker_proc) = self.worker_proc.as_mut() {
+            worker_proc.param_types = self.storage.arg_types.to_vec();
+            worker_proc.ret_types = ret_types.to_vec();
+        } else {
+            self.module.add_function_signature(Signature {
+                param_types: self.storage.arg_types.clone(),
+                ret_types,
+            });
+        }
+    }
+
+    /// The Wasm result types of a proc, and whether it returns through a pointer argument instead
+    fn proc_return_types(
+        &self,
+        return_fields: Option<&[ReturnField]>,
+        ret_layout: InLayout<'a>,
+    ) -> (Vec<'a, ValueType>, bool) {
+        use ReturnMethod::*;
+        let arena = self.env.arena;
+        let ret_layout = WasmLayout::new(&self.layout_interner, ret_layout);
+
+        match (return_fields, ret_layout.return_method()) {
+            (Some(fields), _) => (
+                Vec::from_iter_in(fields.iter().map(|f| f.value_type), arena),
+                false,
+            ),
+            (None, Primitive(ty, _)) => (bumpalo::vec![in arena; ty], false),
+            (None, NoReturnValue) => (bumpalo::vec![in arena], false),
+            (None, WriteToPointerArg) => (bumpalo::vec![in arena], true),
+        }
+    }
+
+    /// The signature `start_proc` gives a proc, which may not be generated yet
+    fn proc_signature(&self, proc_index: usize) -> Signature<'a> {
+        let lookup = &self.proc_lookup[proc_index];
+        let return_fields = self.multi_value_return_fields(lookup);
+        let (ret_types, returns_through_ptr) =
+            self.proc_return_types(return_fields, lookup.layout.result);
+
+        let mut param_types =
+            Vec::with_capacity_in(lookup.layout.arguments.len() + 1, self.env.arena);
+        if returns_through_ptr {
+            param_types.push(PTR_TYPE);
+        }
+        for arg in lookup.layout.arguments {
+            param_types.extend_from_slice(WasmLayout::new(&self.layout_interner, *arg).arg_types());
+        }
+
+        Signature {
+            param_types,
+            ret_types,
+        }
+    }
+
+    /// Call a proc that `build_proc` generates, through the function table for `Env::patchable_calls`
+    fn call_proc(&mut self, proc_index: usize) {
+        let wasm_fn_index = self.fn_index_offset + proc_index as u32;
+
+        if self.env.patchable_calls {
+            let signature = self.proc_signature(proc_index);
+            let signature_index = self.module.types.insert(signature);
+            let table_index = self.get_fn_ptr(wasm_fn_index);
+            self.code_builder.i32_const(table_index);
+            self.code_builder.call_indirect(signature_index);
+        } else {
+            self.code_builder.call(wasm_fn_index);
+        }
     }
 
     fn finalize_proc(&mut self) {
         // end the block from start_proc, to ensure all paths pop stack memory (if any)
         self.end_block();
 
-        if let Some(ret_var) = self.storage.return_var {
-            self.code_builder.get_local(ret_var);
+        for ret_var in self.storage.return_vars.iter() {
+            self.code_builder.get_local(*ret_var);
         }
 
         // Write local declarations and stack frame push/pop code
@@ -525,21 +1059,295 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         self.dereference_boxed_value(inner_layout);
 
         // Call the wrapped inner function
-        let inner_wasm_fn_index = self.fn_index_offset + inner_lookup_idx as u32;
-        self.code_builder.call(inner_wasm_fn_index);
+        self.call_proc(inner_lookup_idx);
 
         // Write empty function header (local variables array with zero length)
         self.code_builder.build_fn_header_and_footer(&[], 0, None);
 
         self.module.add_function_signature(Signature {
             param_types: bumpalo::vec![in self.env.arena; I32; 3],
-            ret_type: Some(ValueType::I32),
+            ret_types: bumpalo::vec![in self.env.arena; I32],
         });
 
         self.append_proc_debug_name(wrapper_name);
         self.reset();
     }
 
+    /// Build a function that checks whether two values in memory contain the same bytes.
+    /// Signature is: a_ptr, b_ptr -> Bool
+    ///
+    /// The values must have no padding, so their size is a multiple of their alignment,
+    /// and we can compare them in chunks of one (at most 8-byte) aligned word at a time.
+    pub fn build_memory_equals(&mut self, lookup_idx: usize, size: u32, alignment_bytes: u32) {
+        let name = self.proc_lookup[lookup_idx].name;
+        let chunk_size = alignment_bytes.min(8);
+        let align = Align::from(chunk_size);
+        debug_assert!(size > 0);
+        debug_assert_eq!(size % chunk_size, 0);
+
+        for offset in (0..size).step_by(chunk_size as usize) {
+            for arg in [LocalId(0), LocalId(1)] {
+                self.code_builder.get_local(arg);
+                match chunk_size {
+                    1 => self.code_builder.i32_load8_u(align, offset),
+                    2 => self.code_builder.i32_load16_u(align, offset),
+                    4 => self.code_builder.i32_load(align, offset),
+                    _ => self.code_builder.i64_load(align, offset),
+                }
+            }
+            if chunk_size == 8 {
+                self.code_builder.i64_eq();
+            } else {
+                self.code_builder.i32_eq();
+            }
+            if offset > 0 {
+                self.code_builder.i32_and();
+            }
+        }
+
+        // Write empty function header (local variables array with zero length)
+        self.code_builder.build_fn_header_and_footer(&[], 0, None);
+
+        self.module.add_function_signature(Signature {
+            param_types: bumpalo::vec![in self.env.arena; ValueType::I32; 2],
+            ret_types: bumpalo::vec![in self.env.arena; ValueType::I32],
+        });
+
+        self.append_proc_debug_name(name);
+        self.reset();
+    }
+
+    /// Build `roc_alloc` for the app's own allocator. Signature is: size, alignment -> ptr
+    ///
+    /// Bumps the heap pointer past the new allocation, growing the memory if it doesn't fit.
+    /// Traps if the memory can't grow.
+    pub fn build_bump_alloc(&mut self, lookup_idx: usize) {
+        use ValueType::*;
+
+        let name = self.proc_lookup[lookup_idx].name;
+        let heap_pointer = self.heap_pointer_global_id();
+        let (size, alignment) = (LocalId(0), LocalId(1));
+        let (ptr, end) = (LocalId(2), LocalId(3));
+
+        // Round the heap pointer up to the alignment, which is a power of 2
+        self.code_builder.get_global(heap_pointer);
+        self.code_builder.get_local(alignment);
+        self.code_builder.i32_add();
+        self.code_builder.i32_const(1);
+        self.code_builder.i32_sub();
+        self.code_builder.i32_const(0);
+        self.code_builder.get_local(alignment);
+        self.code_builder.i32_sub();
+        self.code_builder.i32_and();
+        self.code_builder.tee_local(ptr);
+
+        self.code_builder.get_local(size);
+        self.code_builder.i32_add();
+        self.code_builder.set_local(end);
+
+        // Trap if the address space overflows
+        self.code_builder.block();
+        {
+            self.code_builder.get_local(end);
+            self.code_builder.get_local(ptr);
+            self.code_builder.i32_ge_u();
+            self.code_builder.br_if(0);
+            self.code_builder.unreachable_();
+        }
+        self.code_builder.end();
+
+        // Grow the memory if the allocation doesn't fit. Trap if it can't grow.
+        let page_bits = MemorySection::PAGE_SIZE.trailing_zeros() as i32;
+        self.code_builder.block();
+        {
+            self.code_builder.get_local(end);
+            self.code_builder.memory_size();
+            self.code_builder.i32_const(page_bits);
+            self.code_builder.i32_shl();
+            self.code_builder.i32_le_u();
+            self.code_builder.br_if(0);
+
+            // Pages needed to reach the end, minus the pages we already have
+            self.code_builder.get_local(end);
+            self.code_builder
+                .i32_const(MemorySection::PAGE_SIZE as i32 - 1);
+            self.code_builder.i32_add();
+            self.code_builder.i32_const(page_bits);
+            self.code_builder.i32_shr_u();
+            self.code_builder.memory_size();
+            self.code_builder.i32_sub();
+            self.code_builder.memory_grow();
+            self.code_builder.i32_const(-1);
+            self.code_builder.i32_ne();
+            self.code_builder.br_if(0);
+            self.code_builder.unreachable_();
+        }
+        self.code_builder.end();
+
+        self.code_builder.get_local(end);
+        self.code_builder.set_global(heap_pointer);
+        self.code_builder.get_local(ptr);
+
+        self.code_builder
+            .build_fn_header_and_footer(&[I32, I32], 0, None);
+
+        self.module.add_function_signature(Signature {
+            param_types: bumpalo::vec![in self.env.arena; I32; 2],
+            ret_types: bumpalo::vec![in self.env.arena; I32],
+        });
+
+        self.append_proc_debug_name(name);
+        self.reset();
+    }
+
+    /// Build `roc_realloc` for the app's own allocator.
+    /// Signature is: ptr, new_size, old_size, alignment -> new_ptr
+    ///
+    /// Always makes a new allocation and copies the old bytes into it, one at a time.
+    pub fn build_bump_realloc(&mut self, lookup_idx: usize) {
+        use ValueType::*;
+
+        let name = self.proc_lookup[lookup_idx].name;
+        let alloc_idx = self
+            .proc_lookup
+            .iter()
+            .position(|lookup| lookup.source == ProcSource::BumpAlloc)
+            .unwrap();
+        let (ptr, new_size, old_size, alignment) = (LocalId(0), LocalId(1), LocalId(2), LocalId(3));
+        let (new_ptr, count, i) = (LocalId(4), LocalId(5), LocalId(6));
+
+        self.code_builder.get_local(new_size);
+        self.code_builder.get_local(alignment);
+        self.code_builder
+            .call(self.fn_index_offset + alloc_idx as u32);
+        self.code_builder.set_local(new_ptr);
+
+        // Copy the smaller of the two sizes
+        self.code_builder.get_local(old_size);
+        self.code_builder.get_local(new_size);
+        self.code_builder.get_local(old_size);
+        self.code_builder.get_local(new_size);
+        self.code_builder.i32_lt_u();
+        self.code_builder.select();
+        self.code_builder.set_local(count);
+
+        self.code_builder.block();
+        self.code_builder.loop_();
+        {
+            self.code_builder.get_local(i);
+            self.code_builder.get_local(count);
+            self.code_builder.i32_ge_u();
+            self.code_builder.br_if(1);
+
+            self.code_builder.get_local(new_ptr);
+            self.code_builder.get_local(i);
+            self.code_builder.i32_add();
+            self.code_builder.get_local(ptr);
+            self.code_builder.get_local(i);
+            self.code_builder.i32_add();
+            self.code_builder.i32_load8_u(Align::Bytes1, 0);
+            self.code_builder.i32_store8(Align::Bytes1, 0);
+
+            self.code_builder.get_local(i);
+            self.code_builder.i32_const(1);
+            self.code_builder.i32_add();
+            self.code_builder.set_local(i);
+            self.code_builder.br(0);
+        }
+        self.code_builder.end();
+        self.code_builder.end();
+
+        self.code_builder.get_local(new_ptr);
+
+        self.code_builder
+            .build_fn_header_and_footer(&[I32, I32, I32], 0, None);
+
+        self.module.add_function_signature(Signature {
+            param_types: bumpalo::vec![in self.env.arena; I32; 4],
+            ret_types: bumpalo::vec![in self.env.arena; I32],
+        });
+
+        self.append_proc_debug_name(name);
+        self.reset();
+    }
+
+    /// Build `roc_dealloc` for the app's own allocator, which does nothing.
+    /// Signature is: ptr, alignment -> ()
+    pub fn build_bump_dealloc(&mut self, lookup_idx: usize) {
+        let name = self.proc_lookup[lookup_idx].name;
+
+        self.code_builder.build_fn_header_and_footer(&[], 0, None);
+
+        self.module.add_function_signature(Signature {
+            param_types: bumpalo::vec![in self.env.arena; ValueType::I32; 2],
+            ret_types: bumpalo::vec![in self.env.arena],
+        });
+
+        self.append_proc_debug_name(name);
+        self.reset();
+    }
+
+    /// Build a wrapper for a proc exposed to the host, which calls the proc with the same arguments.
+    /// It has the same signature as the proc. If `reset_heap` is set, it frees everything on the
+    /// heap first. Otherwise it's only there to call the proc through the function table.
+    pub fn build_host_entry(
+        &mut self,
+        wrapper_lookup_idx: usize,
+        inner_lookup_idx: usize,
+        reset_heap: bool,
+    ) {
+        let name = self.proc_lookup[wrapper_lookup_idx].name;
+        let inner_wasm_fn_index = self.fn_index_offset + inner_lookup_idx as u32;
+        let signature_index =
+            self.module.function.signatures[(inner_wasm_fn_index - self.import_fn_count) as usize];
+        let (param_types, _) = self.module.types.look_up(signature_index);
+        let param_count = param_types.len() as u32;
+
+        if reset_heap {
+            self.code_builder.get_global(self.heap_base_global_id());
+            self.code_builder.set_global(self.heap_pointer_global_id());
+        }
+
+        for param in 0..param_count {
+            self.code_builder.get_local(LocalId(param));
+        }
+        self.call_proc(inner_lookup_idx);
+
+        self.code_builder.build_fn_header_and_footer(&[], 0, None);
+
+        self.module.function.add_sig(signature_index);
+
+        self.append_proc_debug_name(name);
+        self.reset();
+    }
+
+    /// Build `PATCH_FN_NAME` and export it. It copies one function table entry to another.
+    /// Signature is: index, new_index -> ()
+    pub fn build_patch_fn(&mut self, lookup_idx: usize) {
+        let name = self.proc_lookup[lookup_idx].name;
+
+        self.code_builder.get_local(LocalId(0));
+        self.code_builder.get_local(LocalId(1));
+        self.code_builder.table_get(0);
+        self.code_builder.table_set(0);
+
+        self.code_builder.build_fn_header_and_footer(&[], 0, None);
+
+        self.module.add_function_signature(Signature {
+            param_types: bumpalo::vec![in self.env.arena; ValueType::I32; 2],
+            ret_types: bumpalo::vec![in self.env.arena],
+        });
+
+        self.module.export.append(Export {
+            name: PATCH_FN_NAME,
+            ty: ExportType::Func,
+            index: self.fn_index_offset + lookup_idx as u32,
+        });
+
+        self.append_proc_debug_name(name);
+        self.reset();
+    }
+
     fn dereference_boxed_value(&mut self, inner: InLayout) {
         use Align::*;
 
@@ -578,6 +1386,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     ***********************************************************/
 
     fn stmt(&mut self, stmt: &Stmt<'a>) {
+        if self.deferred {
+            return;
+        }
+
         match stmt {
             Stmt::Let(_, _, _, _) => self.stmt_let(stmt),
 
@@ -605,7 +1417,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 _ => self.stmt_refcounting(modify, following),
             },
 
-            Stmt::Dbg { .. } => todo!("dbg is not implemented in the wasm backend"),
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                remainder,
+                ..
+            } => self.stmt_dbg(source_location, source, *symbol, remainder),
             Stmt::Expect { .. } => todo!("expect is not implemented in the wasm backend"),
             Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the wasm backend"),
 
@@ -635,12 +1453,19 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     fn stmt_let(&mut self, stmt: &Stmt<'a>) {
         let mut current_stmt = stmt;
         while let Stmt::Let(sym, expr, layout, following) = current_stmt {
+            if self.deferred {
+                return;
+            }
+
             if DEBUG_SETTINGS.let_stmt_ir {
                 print!("\nlet {:?} = {}", sym, expr.to_pretty(200, true));
             }
 
+            // Multi-value returns have no pointer to write to. The fields are loaded from the stack frame.
             let kind = match following {
-                Stmt::Ret(ret_sym) if *sym == *ret_sym => StoredVarKind::ReturnValue,
+                Stmt::Ret(ret_sym) if *sym == *ret_sym && self.storage.return_fields.is_empty() => {
+                    StoredVarKind::ReturnValue
+                }
                 _ => StoredVarKind::Variable,
             };
 
@@ -661,7 +1486,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     ) {
         let sym_storage = self
             .storage
-            .allocate_var(self.layout_interner, layout, sym, kind);
+            .allocate_var(&self.layout_interner, layout, sym, kind);
 
         self.expr(sym, expr, layout, &sym_storage);
 
@@ -676,6 +1501,21 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         use crate::storage::StoredValue::*;
 
         match self.storage.get(&sym) {
+            StackMemory { location, .. } if !self.storage.return_fields.is_empty() => {
+                let (from_ptr, from_offset) =
+                    location.local_and_offset(self.storage.stack_frame_pointer);
+                let fields_and_vars = self
+                    .storage
+                    .return_fields
+                    .iter()
+                    .zip(self.storage.return_vars.iter());
+                for (field, ret_var) in fields_and_vars {
+                    self.code_builder.get_local(from_ptr);
+                    load_return_field(&mut self.code_builder, field, from_offset);
+                    self.code_builder.set_local(*ret_var);
+                }
+            }
+
             StackMemory {
                 location,
                 size,
@@ -702,8 +1542,8 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
                 // If we have a return value, store it to the return variable
                 // This avoids complications with block result types when returning from nested blocks
-                if let Some(ret_var) = self.storage.return_var {
-                    self.code_builder.set_local(ret_var);
+                if let Some(ret_var) = self.storage.return_vars.first() {
+                    self.code_builder.set_local(*ret_var);
                 }
             }
         }
@@ -728,7 +1568,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         }
 
         let is_bool = matches!(cond_layout, Layout::BOOL);
-        let cond_type = WasmLayout::new(self.layout_interner, cond_layout).arg_types()[0];
+        let cond_type = WasmLayout::new(&self.layout_interner, cond_layout).arg_types()[0];
 
         // then, we jump whenever the value under scrutiny is equal to the value of a branch
         for (i, (value, _, _)) in branches.iter().enumerate() {
@@ -790,7 +1630,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         let mut jp_param_storages = Vec::with_capacity_in(parameters.len(), self.env.arena);
         for parameter in parameters.iter() {
             let param_storage = self.storage.allocate_var(
-                self.layout_interner,
+                &self.layout_interner,
                 parameter.layout,
                 parameter.symbol,
                 StoredVarKind::Variable,
@@ -829,9 +1669,24 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     }
 
     fn stmt_refcounting(&mut self, modify: &ModifyRc, following: &'a Stmt<'a>) {
+        if self.defer_to_main_thread() {
+            return;
+        }
+
         let value = modify.get_symbol();
         let layout = self.storage.symbol_layouts[&value];
+        let rc_stmt = self.expand_refcounting(layout, modify, following);
 
+        self.stmt(rc_stmt);
+    }
+
+    /// Replace a refcounting statement with calls to helper procs that do it, registering any new ones
+    pub fn expand_refcounting(
+        &mut self,
+        layout: InLayout<'a>,
+        modify: &ModifyRc,
+        following: &'a Stmt<'a>,
+    ) -> &'a Stmt<'a> {
         let ident_ids = self
             .interns
             .all_ident_ids
@@ -840,7 +1695,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
         let (rc_stmt, new_specializations) = self.helper_proc_gen.expand_refcount_stmt(
             ident_ids,
-            self.layout_interner,
+            self.layout_interner.single_threaded(),
             layout,
             modify,
             following,
@@ -850,7 +1705,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             self.register_symbol_debug_names();
             println!(
                 "## rc_stmt:\n{}\n{:?}",
-                rc_stmt.to_pretty(self.layout_interner, 200, true),
+                rc_stmt.to_pretty(&self.layout_interner, 200, true),
                 rc_stmt
             );
         }
@@ -860,7 +1715,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             self.register_helper_proc(spec_sym, spec_layout, ProcSource::Helper);
         }
 
-        self.stmt(rc_stmt);
+        rc_stmt
     }
 
     fn stmt_refcounting_free(&mut self, value: Symbol, following: &'a Stmt<'a>) {
@@ -899,27 +1754,51 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         self.stmt(following);
     }
 
-    pub fn stmt_internal_error(&mut self, msg: &'a str) {
-        let msg_sym = self.create_symbol("panic_str");
-        let msg_storage = self.storage.allocate_var(
-            self.layout_interner,
+    /// Store a string literal as a RocStr on the stack, in a new variable
+    fn str_literal_var(&mut self, debug_name: &str, value: &'a str) -> Symbol {
+        let sym = self.create_symbol(debug_name);
+        let storage = self.storage.allocate_var(
+            &self.layout_interner,
             Layout::STR,
-            msg_sym,
+            sym,
             StoredVarKind::Variable,
         );
 
-        // Store the message as a RocStr on the stack
-        let (local_id, offset) = match msg_storage {
+        let (local_id, offset) = match storage {
             StoredValue::StackMemory { location, .. } => {
                 location.local_and_offset(self.storage.stack_frame_pointer)
             }
             _ => internal_error!("String must always have stack memory"),
         };
-        self.expr_string_literal(msg, local_id, offset);
+        self.expr_string_literal(value, local_id, offset);
+
+        sym
+    }
+
+    pub fn stmt_internal_error(&mut self, msg: &'a str) {
+        let msg_sym = self.str_literal_var("panic_str", msg);
 
         self.stmt_crash(msg_sym, CrashTag::Roc);
     }
 
+    fn stmt_dbg(
+        &mut self,
+        source_location: &'a str,
+        source: &'a str,
+        msg: Symbol,
+        remainder: &Stmt<'a>,
+    ) {
+        let location_sym = self.str_literal_var("dbg_location", source_location);
+        let source_sym = self.str_literal_var("dbg_source", source);
+
+        // Strings are in stack memory, so this loads a pointer to each one
+        self.storage
+            .load_symbols(&mut self.code_builder, &[location_sym, msg, source_sym]);
+        self.call_host_fn_after_loading_args("roc_dbg");
+
+        self.stmt(remainder);
+    }
+
     pub fn stmt_crash(&mut self, msg: Symbol, tag: CrashTag) {
         // load the pointer
         self.storage.load_symbols(&mut self.code_builder, &[msg]);
@@ -967,7 +1846,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 reuse,
             } => {
                 let reuse = reuse.map(|ru| ru.symbol);
-                self.expr_tag(union_layout, *tag_id, arguments, storage, reuse)
+                self.expr_tag(sym, union_layout, *tag_id, arguments, storage, reuse)
             }
 
             Expr::GetTagId {
@@ -1052,18 +1931,23 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 self.code_builder.set_local(*local_id);
             }
 
-            StoredValue::StackMemory { location, .. } => {
+            StoredValue::StackMemory {
+                location,
+                alignment_bytes,
+                ..
+            } => {
+                let align = Align::from(*alignment_bytes);
                 let mut write128 = |lower_bits, upper_bits| {
                     let (local_id, offset) =
                         location.local_and_offset(self.storage.stack_frame_pointer);
 
                     self.code_builder.get_local(local_id);
                     self.code_builder.i64_const(lower_bits);
-                    self.code_builder.i64_store(Align::Bytes8, offset);
+                    self.code_builder.i64_store(align, offset);
 
                     self.code_builder.get_local(local_id);
                     self.code_builder.i64_const(upper_bits);
-                    self.code_builder.i64_store(Align::Bytes8, offset + 8);
+                    self.code_builder.i64_store(align, offset + 8);
                 };
 
                 match lit {
@@ -1093,7 +1977,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         };
     }
 
-    fn expr_string_literal(&mut self, string: &str, local_id: LocalId, offset: u32) {
+    fn expr_string_literal(&mut self, string: &'a str, local_id: LocalId, offset: u32) {
         let len = string.len();
         if len < 12 {
             // Construct the bytes of the small string
@@ -1113,49 +1997,96 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             self.code_builder.get_local(local_id);
             self.code_builder.i32_const(int32);
             self.code_builder.i32_store(Align::Bytes4, offset + 8);
-        } else {
+        } else if !self.defer_to_main_thread() {
             let bytes = string.as_bytes();
-            let elements_addr = self.store_bytes_in_data_section(bytes);
+            let constants = self.module_constants();
+            let symbol_index = constants.symbol_index;
+            let (elements, len, parent_elements) = match constants.string_pool.find(bytes) {
+                Some(PooledStr::Elements(elements)) => (elements, len, None),
+                Some(PooledStr::Suffix {
+                    elements,
+                    parent_elements,
+                }) => (elements, len | SEAMLESS_SLICE_BIT, Some(parent_elements)),
+                None => {
+                    let elements = constants.store(bytes);
+                    constants.string_pool.insert(bytes, elements);
+                    (elements, len, None)
+                }
+            };
 
             // ptr
             self.code_builder.get_local(local_id);
-            self.code_builder.i32_const(elements_addr as i32);
+            self.code_builder.i32_const_mem_addr(symbol_index, elements);
             self.code_builder.i32_store(Align::Bytes4, offset);
 
             // len
             self.code_builder.get_local(local_id);
-            self.code_builder.i32_const(string.len() as i32);
+            self.code_builder.i32_const(len as i32);
             self.code_builder.i32_store(Align::Bytes4, offset + 4);
 
             // capacity
             self.code_builder.get_local(local_id);
-            self.code_builder.i32_const(string.len() as i32);
+            if let Some(parent_elements) = parent_elements {
+                // A seamless slice keeps a pointer to its parent's elements in place of
+                // the capacity, so that refcounting finds the parent's refcount
+                self.code_builder
+                    .i32_const_mem_addr(symbol_index, parent_elements);
+                self.code_builder.i32_const(1);
+                self.code_builder.i32_shr_u();
+            } else {
+                self.code_builder.i32_const(len as i32);
+            }
             self.code_builder.i32_store(Align::Bytes4, offset + 8);
         };
     }
 
-    /// Create a string constant in the module data section
-    /// Return the data we need for code gen: linker symbol index and memory address
-    fn store_bytes_in_data_section(&mut self, bytes: &[u8]) -> u32 {
-        // Place the segment at a 4-byte aligned offset
-        let segment_addr = round_up_to_alignment!(self.module.data.end_addr, PTR_SIZE);
-        let elements_addr = segment_addr + PTR_SIZE;
-        let length_with_refcount = 4 + bytes.len();
-        self.module.data.end_addr = segment_addr + length_with_refcount as u32;
+    /// The constants of the module the current proc belongs to.
+    /// The first time a module has any, this creates the data symbol that code refers to them by.
+    fn module_constants(&mut self) -> &mut ModuleConstants<'a> {
+        let module_id = self
+            .proc_name
+            .expect("constants outside of a procedure")
+            .module_id();
 
-        let mut segment = DataSegment {
-            mode: DataMode::active_at(segment_addr),
-            init: Vec::with_capacity_in(length_with_refcount, self.env.arena),
+        let index = match self
+            .constants
+            .iter()
+            .position(|constants| constants.module_id == module_id)
+        {
+            Some(index) => index,
+            None => {
+                let module_name = self
+                    .interns
+                    .module_ids
+                    .get_name(module_id)
+                    .expect("every module with procs has a name");
+                let name =
+                    bumpalo::format!(in self.env.arena, ".rodata.{}", module_name).into_bump_str();
+
+                // The segment and size are filled in by place_constants
+                let symbol_index = self.module.linking.symbol_table.len() as u32;
+                self.module
+                    .linking
+                    .symbol_table
+                    .push(SymInfo::Data(DataSymbol::Defined {
+                        flags: WASM_SYM_BINDING_LOCAL,
+                        name,
+                        segment_index: 0,
+                        segment_offset: 0,
+                        size: 0,
+                    }));
+
+                self.constants.push(ModuleConstants::new(
+                    self.env.arena,
+                    module_id,
+                    name,
+                    symbol_index,
+                ));
+                self.constants.len() - 1
+            }
         };
 
-        // Prefix the string bytes with "infinite" refcount
-        let refcount_max_bytes: [u8; 4] = (REFCOUNT_MAX as i32).to_le_bytes();
-        segment.init.extend_from_slice(&refcount_max_bytes);
-        segment.init.extend_from_slice(bytes);
-
-        self.module.data.append_segment(segment);
-
-        elements_addr
+        &mut self.constants[index]
     }
 
     fn expr_null_pointer(&mut self) {
@@ -1213,7 +2144,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 ret_layout,
             } => {
                 let name = foreign_symbol.as_str();
-                let wasm_layout = WasmLayout::new(self.layout_interner, *ret_layout);
+                let wasm_layout = WasmLayout::new(&self.layout_interner, *ret_layout);
                 self.storage.load_symbols_for_call(
                     &mut self.code_builder,
                     arguments,
@@ -1234,7 +2165,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         ret_layout: InLayout<'a>,
         ret_storage: &StoredValue,
     ) {
-        let wasm_layout = WasmLayout::new(self.layout_interner, ret_layout);
+        let wasm_layout = WasmLayout::new(&self.layout_interner, ret_layout);
 
         // If this function is just a lowlevel wrapper, then inline it
         if let LowLevelWrapperType::CanBeReplacedBy(lowlevel) =
@@ -1243,13 +2174,6 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             return self.expr_call_low_level(lowlevel, arguments, ret_sym, ret_layout, ret_storage);
         }
 
-        self.storage.load_symbols_for_call(
-            &mut self.code_builder,
-            arguments,
-            ret_sym,
-            &wasm_layout,
-        );
-
         let roc_proc_index = self
             .proc_lookup
             .iter()
@@ -1263,9 +2187,63 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 );
             });
 
-        let wasm_fn_index = self.fn_index_offset + roc_proc_index as u32;
+        let return_fields = self.multi_value_return_fields(&self.proc_lookup[roc_proc_index]);
+
+        if return_fields.is_some() {
+            self.storage.load_symbols(&mut self.code_builder, arguments);
+        } else {
+            self.storage.load_symbols_for_call(
+                &mut self.code_builder,
+                arguments,
+                ret_sym,
+                &wasm_layout,
+            );
+        }
+
+        self.call_proc(roc_proc_index);
+
+        if let Some(fields) = return_fields {
+            self.store_return_fields(ret_storage, fields);
+        }
+    }
+
+    /// The struct fields a proc returns as several Wasm values, if it doesn't return through a pointer.
+    /// Procs that the host or Zig builtins can call keep the C calling convention.
+    fn multi_value_return_fields(&self, lookup: &ProcLookupData<'a>) -> Option<&'a [ReturnField]> {
+        if !self.env.multi_value_returns
+            || lookup.source != ProcSource::Roc
+            || self.env.exposed_to_host.contains(&lookup.name)
+        {
+            return None;
+        }
+        multi_value_return_fields(self.env.arena, &self.layout_interner, lookup.layout.result)
+    }
+
+    /// Store the values returned from a multi-value call into the struct's stack memory
+    fn store_return_fields(&mut self, ret_storage: &StoredValue, fields: &[ReturnField]) {
+        let (to_ptr, to_offset) = match ret_storage {
+            StoredValue::StackMemory { location, .. } => {
+                location.local_and_offset(self.storage.stack_frame_pointer)
+            }
+            other => internal_error!(
+                "A struct should be in stack memory, but it's in {:?}",
+                other
+            ),
+        };
+
+        // The last field is on top of the VM stack, and each store needs the address below the value
+        let mut field_locals = Vec::with_capacity_in(fields.len(), self.env.arena);
+        for field in fields.iter().rev() {
+            let local = self.storage.create_anonymous_local(field.value_type);
+            self.code_builder.set_local(local);
+            field_locals.push(local);
+        }
 
-        self.code_builder.call(wasm_fn_index);
+        for (field, local) in fields.iter().zip(field_locals.iter().rev()) {
+            self.code_builder.get_local(to_ptr);
+            self.code_builder.get_local(*local);
+            store_return_field(&mut self.code_builder, field, to_offset);
+        }
     }
 
     fn expr_call_low_level(
@@ -1285,6 +2263,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             ret_storage: ret_storage.to_owned(),
         };
         low_level_call.generate(self);
+
+        if self.env.canonicalize_nans && low_level_call.may_produce_nan() {
+            low_level_call.canonicalize_nan(self);
+        }
     }
 
     /// Generate a call instruction to a host function or Zig builtin.
@@ -1295,7 +2277,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             .find(|(fn_name, _)| *fn_name == name)
             .unwrap_or_else(|| panic!("The Roc app tries to call `{name}` but I can't find it!"));
 
-        self.called_fns.set(*fn_index as usize, true);
+        match self.worker_proc.as_mut() {
+            Some(worker_proc) => worker_proc.called_fns.push(*fn_index),
+            None => self.called_fns.set(*fn_index as usize, true),
+        }
 
         if *fn_index < self.import_fn_count {
             self.code_builder.call_import(*fn_index);
@@ -1309,6 +2294,36 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     /// Call stack is expr_call_low_level -> LowLevelCall::generate -> call_eq_specialized
     /// It's a bit circuitous, but the alternative is to give low_level.rs `pub` access to
     /// interns, helper_proc_gen, and expr(). That just seemed all wrong.
+    /// Get the index of a function comparing the bytes of two values of this size.
+    /// Values of different layouts share the function, as long as size and alignment match.
+    pub fn get_memory_equals_fn_index(&mut self, size: u32, alignment_bytes: u32) -> u32 {
+        let source = ProcSource::MemoryEquals {
+            size,
+            alignment_bytes,
+        };
+        let existing = self
+            .proc_lookup
+            .iter()
+            .position(|lookup| lookup.source == source);
+        if let Some(proc_index) = existing {
+            return self.fn_index_offset + proc_index as u32;
+        }
+        if self.defer_to_main_thread() {
+            return 0;
+        }
+
+        let symbol = self.create_symbol(&format!("#memory_equals_{size}_{alignment_bytes}"));
+        let layout = ProcLayout {
+            arguments: self
+                .env
+                .arena
+                .alloc([Layout::OPAQUE_PTR, Layout::OPAQUE_PTR]),
+            result: Layout::BOOL,
+            niche: Niche::NONE,
+        };
+        self.register_helper_proc(symbol, layout, source)
+    }
+
     pub fn call_eq_specialized(
         &mut self,
         arguments: &'a [Symbol],
@@ -1316,6 +2331,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         ret_symbol: Symbol,
         ret_storage: &StoredValue,
     ) {
+        if self.defer_to_main_thread() {
+            return;
+        }
+
         let ident_ids = self
             .interns
             .all_ident_ids
@@ -1323,9 +2342,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             .unwrap();
 
         // Get an IR expression for the call to the specialized procedure
-        let (specialized_call_expr, new_specializations) = self
-            .helper_proc_gen
-            .call_specialized_equals(ident_ids, self.layout_interner, arg_layout, arguments);
+        let (specialized_call_expr, new_specializations) =
+            self.helper_proc_gen.call_specialized_equals(
+                ident_ids,
+                self.layout_interner.single_threaded(),
+                arg_layout,
+                arguments,
+            );
 
         // If any new specializations were created, register their symbol data
         for (spec_sym, spec_layout) in new_specializations.into_iter() {
@@ -1442,7 +2465,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             let heap_local_id = self.storage.create_anonymous_local(PTR_TYPE);
             let heap_alignment = self.layout_interner.alignment_bytes(elem_layout);
             let elems_refcounted = self.layout_interner.contains_refcounted(elem_layout);
-            self.allocate_with_refcount(size, heap_alignment, elems_refcounted);
+            self.allocate_with_refcount(
+                sym,
+                size,
+                heap_alignment,
+                elems_refcounted,
+                "list literal",
+            );
             self.code_builder.set_local(heap_local_id);
 
             let (stack_local_id, stack_offset) =
@@ -1524,6 +2553,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
     fn expr_tag(
         &mut self,
+        sym: Symbol,
         union_layout: &UnionLayout<'a>,
         tag_id: TagIdIntType,
         arguments: &'a [Symbol],
@@ -1538,7 +2568,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         let stores_tag_id_as_data = union_layout.stores_tag_id_as_data(TARGET);
         let stores_tag_id_in_pointer = union_layout.stores_tag_id_in_pointer(TARGET);
         let (data_size, data_alignment) =
-            union_layout.data_size_and_alignment(self.layout_interner);
+            union_layout.data_size_and_alignment(&self.layout_interner);
 
         let (local_id, data_offset) = match stored {
             StoredValue::StackMemory { location, .. } => {
@@ -1556,13 +2586,19 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                     }
                     self.code_builder.else_();
                     {
-                        self.allocate_with_refcount(data_size, data_alignment, false);
+                        self.allocate_with_refcount(
+                            sym,
+                            data_size,
+                            data_alignment,
+                            false,
+                            "tag union",
+                        );
                         self.code_builder.set_local(*local_id);
                     }
                     self.code_builder.end();
                 } else {
                     // Call the allocator to get a memory address.
-                    self.allocate_with_refcount(data_size, data_alignment, false);
+                    self.allocate_with_refcount(sym, data_size, data_alignment, false, "tag union");
                     self.code_builder.set_local(*local_id);
                 }
                 (*local_id, 0)
@@ -1582,7 +2618,8 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
         // Store the tag ID (if any)
         if stores_tag_id_as_data {
-            let id_offset = data_offset + union_layout.tag_id_offset(self.layout_interner).unwrap();
+            let id_offset =
+                data_offset + union_layout.tag_id_offset(&self.layout_interner).unwrap();
 
             let id_align = union_layout.discriminant().alignment_bytes();
             let id_align = Align::from(id_align);
@@ -1659,7 +2696,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         };
 
         if union_layout.stores_tag_id_as_data(TARGET) {
-            let id_offset = union_layout.tag_id_offset(self.layout_interner).unwrap();
+            let id_offset = union_layout.tag_id_offset(&self.layout_interner).unwrap();
 
             let id_align = union_layout.discriminant().alignment_bytes();
             let id_align = Align::from(id_align);
@@ -1675,7 +2712,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         } else if union_layout.stores_tag_id_in_pointer(TARGET) {
             self.storage
                 .load_symbols(&mut self.code_builder, &[structure]);
-            self.code_builder.i32_const(3);
+            self.code_builder.i32_const(TAG_ID_MASK);
             self.code_builder.i32_and();
         }
 
@@ -1735,7 +2772,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
         let from_addr_val = if stores_tag_id_in_pointer {
             self.code_builder.get_local(tag_local_id);
-            self.code_builder.i32_const(-4); // 11111111...1100
+            self.code_builder.i32_const(!TAG_ID_MASK);
             self.code_builder.i32_and();
             AddressValue::Loaded
         } else {
@@ -1803,7 +2840,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         self.code_builder.get_local(tag_local_id);
 
         if stores_tag_id_in_pointer {
-            self.code_builder.i32_const(-4); // 11111111...1100
+            self.code_builder.i32_const(!TAG_ID_MASK);
             self.code_builder.i32_and();
         }
 
@@ -1849,13 +2886,16 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     /// Leaves the *data* address on the VM stack
     ///
     /// elements_refcounted should only ever be set for lists.
+    /// `sym` and `kind` describe the allocation site in heap profiling builds.
     fn allocate_with_refcount(
         &mut self,
+        sym: Symbol,
         data_size: u32,
         alignment_bytes: u32,
         elements_refcounted: bool,
+        kind: &'static str,
     ) {
-        if !self.can_relocate_heap {
+        if !self.can_relocate_heap && self.env.allocator == Allocator::Host {
             // This will probably only happen for test hosts.
             panic!("The app tries to allocate heap memory but the host doesn't support that. It needs to export symbols __heap_base and __heap_end");
         }
@@ -1869,10 +2909,27 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         self.code_builder.i32_const(alignment_bytes as i32);
         self.code_builder.i32_const(elements_refcounted as i32);
 
-        self.call_host_fn_after_loading_args(bitcode::UTILS_ALLOCATE_WITH_REFCOUNT);
+        if self.env.heap_profiling.is_some() {
+            let proc_name = self.proc_name.expect("allocating outside of a procedure");
+            self.alloc_sites.push((sym, proc_name, kind));
+            self.code_builder.i32_const(self.alloc_sites.len() as i32);
+            self.code_builder.set_global(ALLOC_SITE_GLOBAL_ID);
+
+            self.call_host_fn_after_loading_args(bitcode::UTILS_ALLOCATE_WITH_REFCOUNT);
+
+            // Don't attribute allocations made by builtins to this site
+            self.code_builder.i32_const(0);
+            self.code_builder.set_global(ALLOC_SITE_GLOBAL_ID);
+        } else {
+            self.call_host_fn_after_loading_args(bitcode::UTILS_ALLOCATE_WITH_REFCOUNT);
+        }
     }
 
     fn expr_reset(&mut self, argument: Symbol, ret_symbol: Symbol, ret_storage: &StoredValue) {
+        if self.defer_to_main_thread() {
+            return;
+        }
+
         let ident_ids = self
             .interns
             .all_ident_ids
@@ -1881,9 +2938,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
         // Get an IR expression for the call to the specialized procedure
         let layout = self.storage.symbol_layouts[&argument];
-        let (specialized_call_expr, new_specializations) = self
-            .helper_proc_gen
-            .call_reset_refcount(ident_ids, self.layout_interner, layout, argument);
+        let (specialized_call_expr, new_specializations) =
+            self.helper_proc_gen.call_reset_refcount(
+                ident_ids,
+                self.layout_interner.single_threaded(),
+                layout,
+                argument,
+            );
 
         // If any new specializations were created, register their symbol data
         for (spec_sym, spec_layout) in new_specializations.into_iter() {
@@ -1900,6 +2961,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     }
 
     fn expr_resetref(&mut self, argument: Symbol, ret_symbol: Symbol, ret_storage: &StoredValue) {
+        if self.defer_to_main_thread() {
+            return;
+        }
+
         let ident_ids = self
             .interns
             .all_ident_ids
@@ -1908,9 +2973,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
         // Get an IR expression for the call to the specialized procedure
         let layout = self.storage.symbol_layouts[&argument];
-        let (specialized_call_expr, new_specializations) = self
-            .helper_proc_gen
-            .call_resetref_refcount(ident_ids, self.layout_interner, layout, argument);
+        let (specialized_call_expr, new_specializations) =
+            self.helper_proc_gen.call_resetref_refcount(
+                ident_ids,
+                self.layout_interner.single_threaded(),
+                layout,
+                argument,
+            );
 
         // If any new specializations were created, register their symbol data
         for (spec_sym, spec_layout) in new_specializations.into_iter() {
@@ -1966,15 +3035,22 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     /// Generate a refcount helper procedure and return a pointer (table index) to it
     /// This allows it to be indirectly called from Zig code
     pub fn get_refcount_fn_index(&mut self, layout: InLayout<'a>, op: HelperOp) -> u32 {
+        if self.defer_to_main_thread() {
+            return 0;
+        }
+
         let ident_ids = self
             .interns
             .all_ident_ids
             .get_mut(&self.env.module_id)
             .unwrap();
 
-        let (proc_symbol, new_specializations) =
-            self.helper_proc_gen
-                .gen_refcount_proc(ident_ids, self.layout_interner, layout, op);
+        let (proc_symbol, new_specializations) = self.helper_proc_gen.gen_refcount_proc(
+            ident_ids,
+            self.layout_interner.single_threaded(),
+            layout,
+            op,
+        );
 
         // If any new specializations were created, register their symbol data
         for (spec_sym, spec_layout) in new_specializations.into_iter() {
@@ -1987,15 +3063,21 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     /// Generate a copy helper procedure and return a pointer (table index) to it
     /// This allows it to be indirectly called from Zig code
     pub fn get_copy_fn_index(&mut self, layout: InLayout<'a>) -> u32 {
+        if self.defer_to_main_thread() {
+            return 0;
+        }
+
         let ident_ids = self
             .interns
             .all_ident_ids
             .get_mut(&self.env.module_id)
             .unwrap();
 
-        let (proc_symbol, new_specializations) =
-            self.helper_proc_gen
-                .gen_copy_proc(ident_ids, self.layout_interner, layout);
+        let (proc_symbol, new_specializations) = self.helper_proc_gen.gen_copy_proc(
+            ident_ids,
+            self.layout_interner.single_threaded(),
+            layout,
+        );
 
         // If any new specializations were created, register their symbol data
         for (spec_sym, spec_layout) in new_specializations.into_iter() {
@@ -2013,6 +3095,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         layout: InLayout<'a>,
         op: HelperOp,
     ) -> u32 {
+        if self.defer_to_main_thread() {
+            return 0;
+        }
+
         let layout_repr = self.layout_interner.runtime_representation(layout);
         let same_layout = |layout| {
             if op.is_indirect() {
@@ -2037,3 +3123,39 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         self.fn_index_offset + proc_index as u32
     }
 }
+
+fn load_return_field(code_builder: &mut CodeBuilder, field: &ReturnField, struct_offset: u32) {
+    use Align::*;
+    let offset = struct_offset + field.offset;
+    match (field.value_type, field.size) {
+        (ValueType::I64, 8) => code_builder.i64_load(Bytes8, offset),
+        (ValueType::I32, 4) => code_builder.i32_load(Bytes4, offset),
+        (ValueType::I32, 2) => code_builder.i32_load16_s(Bytes2, offset),
+        (ValueType::I32, 1) => code_builder.i32_load8_s(Bytes1, offset),
+        (ValueType::F32, 4) => code_builder.f32_load(Bytes4, offset),
+        (ValueType::F64, 8) => code_builder.f64_load(Bytes8, offset),
+        _ => internal_error!(
+            "Cannot load {:?} with size {}",
+            field.value_type,
+            field.size
+        ),
+    }
+}
+
+fn store_return_field(code_builder: &mut CodeBuilder, field: &ReturnField, struct_offset: u32) {
+    use Align::*;
+    let offset = struct_offset + field.offset;
+    match (field.value_type, field.size) {
+        (ValueType::I64, 8) => code_builder.i64_store(Bytes8, offset),
+        (ValueType::I32, 4) => code_builder.i32_store(Bytes4, offset),
+        (ValueType::I32, 2) => code_builder.i32_store16(Bytes2, offset),
+        (ValueType::I32, 1) => code_builder.i32_store8(Bytes1, offset),
+        (ValueType::F32, 4) => code_builder.f32_store(Bytes4, offset),
+        (ValueType::F64, 8) => code_builder.f64_store(Bytes8, offset),
+        _ => internal_error!(
+            "Cannot store {:?} with size {}",
+            field.value_type,
+            field.size
+        ),
+    }
+}
@@ -7,8 +7,8 @@ use roc_error_macros::internal_error;
 use roc_wasm_module::opcodes::{OpCode, OpCode::*};
 use roc_wasm_module::serialize::SerialBuffer;
 use roc_wasm_module::{
-    round_up_to_alignment, Align, LocalId, RelocationEntry, ValueType, WasmModule,
-    FRAME_ALIGNMENT_BYTES, STACK_POINTER_GLOBAL_ID,
+    round_up_to_alignment, Align, BlockType, LocalId, OffsetRelocType, RelocationEntry, Serialize,
+    ValueType, WasmModule, FRAME_ALIGNMENT_BYTES, STACK_POINTER_GLOBAL_ID,
 };
 use std::iter::repeat;
 
@@ -73,10 +73,68 @@ pub struct CodeBuilder<'a> {
     /// When we remove unused imports, the live ones are re-indexed
     import_relocations: Vec<'a, (usize, u32)>,
 
+    /// Relocations for addresses of constants, which are only known once every module's
+    /// constants have been placed in memory: code position, data symbol index, and offset
+    data_relocations: Vec<'a, (usize, u32, u32)>,
+
     /// Keep track of which local variables have been set
     set_locals: BitVec<u32>,
 }
 
+/// A function's code, serialized by a worker thread, with relocations at positions in `bytes`
+#[derive(Debug, Default)]
+pub struct SerializedFunction {
+    bytes: std::vec::Vec<u8>,
+    import_relocations: std::vec::Vec<(usize, u32)>,
+    data_relocations: std::vec::Vec<(usize, u32, u32)>,
+}
+
+impl SerializedFunction {
+    pub fn insert_into_module(&self, module: &mut WasmModule) {
+        let fn_offset = module.code.bytes.len();
+        module.code.function_count += 1;
+        module.code.function_offsets.push(fn_offset as u32);
+
+        module.code.bytes.extend_from_slice(&self.bytes);
+
+        add_relocations(
+            module,
+            self.import_relocations
+                .iter()
+                .map(|(pos, reloc_fn)| (fn_offset + pos, *reloc_fn)),
+            self.data_relocations
+                .iter()
+                .map(|(pos, symbol_index, addend)| (fn_offset + pos, *symbol_index, *addend)),
+        );
+    }
+}
+
+/// Create linker relocations for a function's calls to imported functions, whose indices may
+/// change during DCE, and for its addresses of constants. Offsets are in the Code section.
+fn add_relocations(
+    module: &mut WasmModule,
+    import_relocations: impl Iterator<Item = (usize, u32)>,
+    data_relocations: impl Iterator<Item = (usize, u32, u32)>,
+) {
+    for (offset, reloc_fn) in import_relocations {
+        let symbol_index = module.linking.find_imported_fn_sym_index(reloc_fn).unwrap();
+        module.reloc_code.entries.push(RelocationEntry::Index {
+            type_id: IndexRelocType::FunctionIndexLeb,
+            offset: offset as u32,
+            symbol_index,
+        });
+    }
+
+    for (offset, symbol_index, addend) in data_relocations {
+        module.reloc_code.entries.push(RelocationEntry::Offset {
+            type_id: OffsetRelocType::MemoryAddrSleb,
+            offset: offset as u32,
+            symbol_index,
+            addend: addend as i32,
+        });
+    }
+}
+
 #[allow(clippy::new_without_default)]
 impl<'a> CodeBuilder<'a> {
     pub fn new(arena: &'a Bump) -> Self {
@@ -88,6 +146,7 @@ impl<'a> CodeBuilder<'a> {
             preamble: Vec::with_capacity_in(32, arena),
             inner_length: Vec::with_capacity_in(5, arena),
             import_relocations: Vec::with_capacity_in(0, arena),
+            data_relocations: Vec::with_capacity_in(0, arena),
             set_locals: BitVec::with_capacity(64),
         }
     }
@@ -99,6 +158,7 @@ impl<'a> CodeBuilder<'a> {
         self.preamble.clear();
         self.inner_length.clear();
         self.import_relocations.clear();
+        self.data_relocations.clear();
         self.set_locals.clear();
     }
 
@@ -216,46 +276,81 @@ impl<'a> CodeBuilder<'a> {
         module.code.function_count += 1;
         module.code.function_offsets.push(fn_offset as u32);
 
+        let code_offset = self.serialize_code(&mut module.code.bytes);
+        let (import_relocations, data_relocations) = self.relocations(code_offset);
+        add_relocations(module, import_relocations, data_relocations);
+    }
+
+    /// Serialize the function into a buffer of its own, for a worker thread to send to the main
+    /// thread, which then inserts it into the module
+    pub fn serialize(&self) -> SerializedFunction {
+        let mut bytes = std::vec::Vec::with_capacity(self.size());
+        let code_offset = self.serialize_code(&mut bytes);
+        let (import_relocations, data_relocations) = self.relocations(code_offset);
+
+        SerializedFunction {
+            bytes,
+            import_relocations: import_relocations.collect(),
+            data_relocations: data_relocations.collect(),
+        }
+    }
+
+    /// Write the length, the preamble, and the code with our insertions to `buffer`.
+    /// Returns the position of the code in `buffer`.
+    fn serialize_code<T: SerialBuffer>(&self, buffer: &mut T) -> usize {
+        buffer.append_slice(&self.inner_length);
+        buffer.append_slice(&self.preamble);
+
         // Insertions are chunks of code we generated out-of-order.
         // Now insert them at the correct offsets.
-        let buffer = &mut module.code.bytes;
-        buffer.extend_from_slice(&self.inner_length);
-        buffer.extend_from_slice(&self.preamble);
-
-        let code_offset = buffer.len();
+        let code_offset = buffer.size();
         let mut code_pos = 0;
         for Insertion { at, start, end } in self.insertions.iter() {
-            buffer.extend_from_slice(&self.code[code_pos..*at]);
+            buffer.append_slice(&self.code[code_pos..*at]);
             code_pos = *at;
-            buffer.extend_from_slice(&self.insert_bytes[*start..*end]);
+            buffer.append_slice(&self.insert_bytes[*start..*end]);
         }
 
-        buffer.extend_from_slice(&self.code[code_pos..self.code.len()]);
-
-        // Create linker relocations for calls to imported functions, whose indices may change during DCE.
-        let relocs = &mut module.reloc_code.entries;
-        let mut skip = 0;
-        for (reloc_code_pos, reloc_fn) in self.import_relocations.iter() {
-            let mut insertion_bytes = 0;
-            for (i, insertion) in self.insertions.iter().enumerate().skip(skip) {
-                if insertion.at >= *reloc_code_pos {
-                    break;
-                }
-                insertion_bytes = insertion.end;
-                skip = i;
-            }
-            // Adjust for (1) the offset of this function in the Code section and (2) our own Insertions.
-            let offset = reloc_code_pos + code_offset + insertion_bytes;
-            let symbol_index = module
-                .linking
-                .find_imported_fn_sym_index(*reloc_fn)
-                .unwrap();
-            relocs.push(RelocationEntry::Index {
-                type_id: IndexRelocType::FunctionIndexLeb,
-                offset: offset as u32,
-                symbol_index,
-            });
-        }
+        buffer.append_slice(&self.code[code_pos..self.code.len()]);
+
+        code_offset
+    }
+
+    /// Our relocations, at their positions in a buffer where the code starts at `code_offset`
+    fn relocations(
+        &self,
+        code_offset: usize,
+    ) -> (
+        impl Iterator<Item = (usize, u32)> + '_,
+        impl Iterator<Item = (usize, u32, u32)> + '_,
+    ) {
+        let import_relocations = self
+            .import_relocations
+            .iter()
+            .map(move |(pos, reloc_fn)| (self.code_section_offset(*pos, code_offset), *reloc_fn));
+        let data_relocations =
+            self.data_relocations
+                .iter()
+                .map(move |(pos, symbol_index, addend)| {
+                    (
+                        self.code_section_offset(*pos, code_offset),
+                        *symbol_index,
+                        *addend,
+                    )
+                });
+        (import_relocations, data_relocations)
+    }
+
+    /// Adjust a position in `code` for (1) the offset of this function in the Code section
+    /// and (2) our own Insertions.
+    fn code_section_offset(&self, code_pos: usize, code_offset: usize) -> usize {
+        let insertion_bytes = self
+            .insertions
+            .iter()
+            .take_while(|insertion| insertion.at < code_pos)
+            .last()
+            .map_or(0, |insertion| insertion.end);
+        code_pos + code_offset + insertion_bytes
     }
 
     /**********************************************************
@@ -280,9 +375,10 @@ impl<'a> CodeBuilder<'a> {
     fn inst_block(&mut self, opcode: OpCode) {
         self.inst_base(opcode);
 
-        // We don't support block result types. Too hard to track types through arbitrary control flow.
+        // We don't use block result types. Too hard to track types through arbitrary control flow.
         // This results in slightly more instructions but not much. (Rust does the same thing!)
-        self.code.push(ValueType::VOID);
+        // Values leave blocks through locals instead, even when a function returns several.
+        BlockType::NoResult.serialize(&mut self.code);
 
         log_instruction!("{opcode:?}");
     }
@@ -353,11 +449,13 @@ impl<'a> CodeBuilder<'a> {
         self.call(function_index)
     }
 
-    #[allow(dead_code)]
-    fn call_indirect() {
-        unimplemented!(
-            "There is no plan to implement call_indirect. Roc doesn't use function pointers"
-        );
+    /// Call the function whose table index is on top of the VM stack.
+    /// Roc only does this for `Env::patchable_calls`. Otherwise it doesn't use function pointers.
+    pub fn call_indirect(&mut self, signature_index: u32) {
+        self.inst_base(CALLINDIRECT);
+        self.code.encode_u32(signature_index);
+        self.code.push(0); // table index
+        log_instruction!("{:10}\t{}", format!("{CALLINDIRECT:?}"), signature_index);
     }
 
     instruction_no_args!(drop_, DROP);
@@ -390,6 +488,12 @@ impl<'a> CodeBuilder<'a> {
     pub fn set_global(&mut self, id: u32) {
         self.inst_imm32(SETGLOBAL, id);
     }
+    pub fn table_get(&mut self, table: u32) {
+        self.inst_imm32(TABLEGET, table);
+    }
+    pub fn table_set(&mut self, table: u32) {
+        self.inst_imm32(TABLESET, table);
+    }
 
     instruction_memargs!(i32_load, I32LOAD);
     instruction_memargs!(i64_load, I64LOAD);
@@ -435,6 +539,20 @@ impl<'a> CodeBuilder<'a> {
         self.code.encode_i32(x);
         self.log_const(I32CONST, x);
     }
+    /// Push the address of the byte at `offset` in the data symbol `symbol_index`.
+    /// A relocation fills it in once the symbol's segment has been placed in memory.
+    pub fn i32_const_mem_addr(&mut self, symbol_index: u32, offset: u32) {
+        self.inst_base(I32CONST);
+        self.data_relocations
+            .push((self.code.len(), symbol_index, offset));
+        self.code.encode_padded_u32(0);
+        log_instruction!(
+            "{:10}\tsymbol {} + {}",
+            format!("{I32CONST:?}"),
+            symbol_index,
+            offset
+        );
+    }
     pub fn i64_const(&mut self, x: i64) {
         self.inst_base(I64CONST);
         self.code.encode_i64(x);
@@ -0,0 +1,75 @@
+//! Groups the constants we store in the data section by the Roc module whose code uses them.
+//!
+//! Each module's constants go into a data segment of their own, so that changing the constants of
+//! one module leaves the bytes of every other module's segment alone, which keeps them cacheable
+//! in content-addressed build pipelines. For the same reason, string literals are only shared
+//! within a module.
+//!
+//! The segments are only placed in memory once every procedure has been built, so code refers to a
+//! constant through a relocation against its module's data symbol.
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_error_macros::internal_error;
+use roc_module::symbol::ModuleId;
+use roc_mono::code_gen_help::REFCOUNT_MAX;
+use roc_wasm_module::round_up_to_alignment;
+
+use crate::string_pool::StringPool;
+use crate::PTR_SIZE;
+
+#[derive(Debug)]
+pub struct ModuleConstants<'a> {
+    pub module_id: ModuleId,
+    /// Name of the data segment, and of the symbol
+    pub name: &'a str,
+    /// Index of the data symbol in the linking section, for relocations
+    pub symbol_index: u32,
+    /// Contents of the data segment
+    pub bytes: Vec<'a, u8>,
+    /// Offsets of the string literals in `bytes`
+    pub string_pool: StringPool<'a>,
+}
+
+impl<'a> ModuleConstants<'a> {
+    pub fn new(arena: &'a Bump, module_id: ModuleId, name: &'a str, symbol_index: u32) -> Self {
+        ModuleConstants {
+            module_id,
+            name,
+            symbol_index,
+            bytes: Vec::new_in(arena),
+            string_pool: StringPool::new(arena),
+        }
+    }
+
+    /// Store `bytes` after an "infinite" refcount, and return their offset in the segment
+    pub fn store(&mut self, bytes: &[u8]) -> u32 {
+        // Keep the refcount aligned, assuming the segment is placed at an aligned address
+        let refcount_offset = round_up_to_alignment!(self.bytes.len(), PTR_SIZE as usize);
+        self.bytes.resize(refcount_offset, 0);
+
+        let refcount_max_bytes: [u8; 4] = (REFCOUNT_MAX as i32).to_le_bytes();
+        self.bytes.extend_from_slice(&refcount_max_bytes);
+
+        let elements_offset = self.bytes.len() as u32;
+        self.bytes.extend_from_slice(bytes);
+
+        elements_offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_keep_their_refcounts_aligned() {
+        let arena = Bump::new();
+        let mut constants = ModuleConstants::new(&arena, ModuleId::STR, ".rodata.Str", 0);
+
+        assert_eq!(constants.store(b"first string literal!"), 4);
+        assert_eq!(constants.store(b"second string literal"), 32);
+        assert_eq!(constants.bytes.len(), 32 + 21);
+        assert_eq!(constants.bytes[25..28], [0, 0, 0]);
+    }
+}
@@ -0,0 +1,777 @@
+//! Inlines calls to small procedures into their callers.
+//!
+//! A Wasm call has to pass its arguments and result through locals or the stack frame, and the
+//! baseline compilers of most engines never inline, so for a procedure of a few statements the
+//! call can cost more than the body. When optimizing, we copy such bodies into their callers.
+//!
+//! `let x = f a b in cont` becomes the join point `join jp x = cont in body`, where `body` is
+//! `f`'s body with `a` and `b` for its arguments and every `ret` turned into a jump to `jp`.
+//! Everything `body` defines gets a fresh name, since it can be inlined more than once per caller.
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+
+use roc_collections::all::MutMap;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_mono::ir::{
+    BranchInfo, Call, CallType, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement,
+    ModifyRc, Param, Proc, ProcLayout, ReuseToken, SelfRecursive, Stmt,
+};
+
+use crate::split::stmt_count;
+
+/// Inlines every call by name to a procedure of at most `max_stmts` statements.
+///
+/// Only the original bodies are inlined, so calls that were inlined from another procedure
+/// stay calls. Procedures that call themselves are never inlined.
+pub fn inline_small_procs<'a>(
+    arena: &'a Bump,
+    ident_ids: &mut IdentIds,
+    module_id: ModuleId,
+    max_stmts: usize,
+    procedures: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    if max_stmts == 0 {
+        return;
+    }
+
+    let inlinable = MutMap::from_iter(
+        procedures
+            .iter()
+            .filter(|(_, proc)| {
+                proc.is_self_recursive == SelfRecursive::NotSelfRecursive
+                    && !proc.is_erased
+                    && stmt_count(&proc.body) <= max_stmts
+            })
+            .filter(|(key, proc)| !calls_proc(&proc.body, key))
+            .map(|(key, proc)| (*key, proc.clone())),
+    );
+
+    if inlinable.is_empty() {
+        return;
+    }
+
+    for proc in procedures.values_mut() {
+        let mut inliner = Inliner {
+            arena,
+            ident_ids,
+            module_id,
+            inlinable: &inlinable,
+        };
+
+        proc.body = inliner.stmt(&proc.body);
+    }
+}
+
+/// The procedure an expression calls by name, and the arguments it passes
+fn called_proc<'a>(expr: &Expr<'a>) -> Option<((Symbol, ProcLayout<'a>), &'a [Symbol])> {
+    let Expr::Call(Call {
+        call_type:
+            CallType::ByName {
+                name,
+                ret_layout,
+                arg_layouts,
+                ..
+            },
+        arguments,
+    }) = expr
+    else {
+        return None;
+    };
+
+    let proc_layout = ProcLayout {
+        arguments: arg_layouts,
+        result: *ret_layout,
+        niche: name.niche(),
+    };
+
+    Some(((name.name(), proc_layout), arguments))
+}
+
+/// Whether `stmt` calls the procedure `key`. Recursion that isn't a tail call doesn't make a
+/// procedure `SelfRecursive`, so this is how we find out it can't be inlined.
+fn calls_proc(stmt: &Stmt, key: &(Symbol, ProcLayout)) -> bool {
+    match stmt {
+        Stmt::Let(_, expr, _, cont) => {
+            called_proc(expr).is_some_and(|(called, _)| called == *key) || calls_proc(cont, key)
+        }
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            branches
+                .iter()
+                .any(|(_, _, branch)| calls_proc(branch, key))
+                || calls_proc(default_branch.1, key)
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => calls_proc(body, key) || calls_proc(remainder, key),
+        Stmt::Refcounting(_, cont)
+        | Stmt::Expect {
+            remainder: cont, ..
+        }
+        | Stmt::ExpectFx {
+            remainder: cont, ..
+        }
+        | Stmt::Dbg {
+            remainder: cont, ..
+        } => calls_proc(cont, key),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => false,
+    }
+}
+
+struct Inliner<'a, 'i> {
+    arena: &'a Bump,
+    ident_ids: &'i mut IdentIds,
+    module_id: ModuleId,
+    inlinable: &'i MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+}
+
+impl<'a, 'i> Inliner<'a, 'i> {
+    fn callee(&self, expr: &Expr<'a>) -> Option<(&'i Proc<'a>, &'a [Symbol])> {
+        let (key, arguments) = called_proc(expr)?;
+        self.inlinable.get(&key).map(|proc| (proc, arguments))
+    }
+
+    fn stmt(&mut self, stmt: &Stmt<'a>) -> Stmt<'a> {
+        let arena = self.arena;
+
+        match stmt {
+            Stmt::Let(symbol, expr, layout, cont) => match self.callee(expr) {
+                Some((callee, arguments)) => {
+                    // A call in tail position can return straight from the inlined body
+                    if *cont == &Stmt::Ret(*symbol) {
+                        return self.renamer(None).inline(callee, arguments);
+                    }
+
+                    let id = JoinPointId(Symbol::new(self.module_id, self.ident_ids.gen_unique()));
+                    let body = self.stmt(cont);
+                    let remainder = self.renamer(Some(id)).inline(callee, arguments);
+
+                    Stmt::Join {
+                        id,
+                        parameters: arena.alloc_slice_copy(&[Param {
+                            symbol: *symbol,
+                            layout: *layout,
+                        }]),
+                        body: arena.alloc(body),
+                        remainder: arena.alloc(remainder),
+                    }
+                }
+                None => Stmt::Let(*symbol, expr.clone(), *layout, arena.alloc(self.stmt(cont))),
+            },
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let branches = Vec::from_iter_in(
+                    branches
+                        .iter()
+                        .map(|(value, info, branch)| (*value, info.clone(), self.stmt(branch))),
+                    arena,
+                );
+                let default_stmt = self.stmt(default_branch.1);
+
+                Stmt::Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches: branches.into_bump_slice(),
+                    default_branch: (default_branch.0.clone(), arena.alloc(default_stmt)),
+                    ret_layout: *ret_layout,
+                }
+            }
+            Stmt::Refcounting(modify, cont) => {
+                Stmt::Refcounting(*modify, arena.alloc(self.stmt(cont)))
+            }
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::Expect {
+                condition: *condition,
+                region: *region,
+                lookups,
+                variables,
+                remainder: arena.alloc(self.stmt(remainder)),
+            },
+            Stmt::ExpectFx {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::ExpectFx {
+                condition: *condition,
+                region: *region,
+                lookups,
+                variables,
+                remainder: arena.alloc(self.stmt(remainder)),
+            },
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable,
+                remainder,
+            } => Stmt::Dbg {
+                source_location,
+                source,
+                symbol: *symbol,
+                variable: *variable,
+                remainder: arena.alloc(self.stmt(remainder)),
+            },
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => Stmt::Join {
+                id: *id,
+                parameters,
+                body: arena.alloc(self.stmt(body)),
+                remainder: arena.alloc(self.stmt(remainder)),
+            },
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => stmt.clone(),
+        }
+    }
+
+    fn renamer(&mut self, ret: Option<JoinPointId>) -> Renamer<'a, '_> {
+        Renamer {
+            arena: self.arena,
+            ident_ids: self.ident_ids,
+            module_id: self.module_id,
+            ret,
+            symbols: MutMap::default(),
+            joins: MutMap::default(),
+        }
+    }
+}
+
+/// Copies a procedure body, giving everything it defines a fresh name.
+struct Renamer<'a, 'i> {
+    arena: &'a Bump,
+    ident_ids: &'i mut IdentIds,
+    module_id: ModuleId,
+    /// Where `ret` jumps to, or `None` to keep it a `ret`
+    ret: Option<JoinPointId>,
+    symbols: MutMap<Symbol, Symbol>,
+    joins: MutMap<JoinPointId, JoinPointId>,
+}
+
+impl<'a, 'i> Renamer<'a, 'i> {
+    fn inline(mut self, callee: &Proc<'a>, arguments: &[Symbol]) -> Stmt<'a> {
+        for ((_, param), argument) in callee.args.iter().zip(arguments) {
+            self.symbols.insert(*param, *argument);
+        }
+
+        self.stmt(&callee.body)
+    }
+
+    fn define(&mut self, symbol: Symbol) -> Symbol {
+        let fresh = Symbol::new(self.module_id, self.ident_ids.gen_unique());
+        self.symbols.insert(symbol, fresh);
+        fresh
+    }
+
+    fn symbol(&self, symbol: Symbol) -> Symbol {
+        self.symbols.get(&symbol).copied().unwrap_or(symbol)
+    }
+
+    fn symbols(&self, symbols: &[Symbol]) -> &'a [Symbol] {
+        self.arena
+            .alloc_slice_fill_iter(symbols.iter().map(|symbol| self.symbol(*symbol)))
+    }
+
+    fn stmt(&mut self, stmt: &Stmt<'a>) -> Stmt<'a> {
+        let arena = self.arena;
+
+        match stmt {
+            Stmt::Let(symbol, expr, layout, cont) => {
+                let expr = self.expr(expr);
+                let symbol = self.define(*symbol);
+                Stmt::Let(symbol, expr, *layout, arena.alloc(self.stmt(cont)))
+            }
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let branches = Vec::from_iter_in(
+                    branches.iter().map(|(value, info, branch)| {
+                        (*value, self.branch_info(info), self.stmt(branch))
+                    }),
+                    arena,
+                );
+                let default_info = self.branch_info(&default_branch.0);
+                let default_stmt = self.stmt(default_branch.1);
+
+                Stmt::Switch {
+                    cond_symbol: self.symbol(*cond_symbol),
+                    cond_layout: *cond_layout,
+                    branches: branches.into_bump_slice(),
+                    default_branch: (default_info, arena.alloc(default_stmt)),
+                    ret_layout: *ret_layout,
+                }
+            }
+            Stmt::Ret(symbol) => match self.ret {
+                Some(id) => Stmt::Jump(id, arena.alloc_slice_copy(&[self.symbol(*symbol)])),
+                None => Stmt::Ret(self.symbol(*symbol)),
+            },
+            Stmt::Refcounting(modify, cont) => {
+                let modify = match *modify {
+                    ModifyRc::Inc(symbol, count) => ModifyRc::Inc(self.symbol(symbol), count),
+                    ModifyRc::Dec(symbol) => ModifyRc::Dec(self.symbol(symbol)),
+                    ModifyRc::DecRef(symbol) => ModifyRc::DecRef(self.symbol(symbol)),
+                    ModifyRc::Free(symbol) => ModifyRc::Free(self.symbol(symbol)),
+                };
+                Stmt::Refcounting(modify, arena.alloc(self.stmt(cont)))
+            }
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::Expect {
+                condition: self.symbol(*condition),
+                region: *region,
+                lookups: self.symbols(lookups),
+                variables,
+                remainder: arena.alloc(self.stmt(remainder)),
+            },
+            Stmt::ExpectFx {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::ExpectFx {
+                condition: self.symbol(*condition),
+                region: *region,
+                lookups: self.symbols(lookups),
+                variables,
+                remainder: arena.alloc(self.stmt(remainder)),
+            },
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable,
+                remainder,
+            } => Stmt::Dbg {
+                source_location,
+                source,
+                symbol: self.symbol(*symbol),
+                variable: *variable,
+                remainder: arena.alloc(self.stmt(remainder)),
+            },
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let new_id = JoinPointId(self.define(id.0));
+                self.joins.insert(*id, new_id);
+
+                let parameters = Vec::from_iter_in(
+                    parameters.iter().map(|param| Param {
+                        symbol: self.define(param.symbol),
+                        layout: param.layout,
+                    }),
+                    arena,
+                );
+                let body = self.stmt(body);
+                let remainder = self.stmt(remainder);
+
+                Stmt::Join {
+                    id: new_id,
+                    parameters: parameters.into_bump_slice(),
+                    body: arena.alloc(body),
+                    remainder: arena.alloc(remainder),
+                }
+            }
+            Stmt::Jump(id, arguments) => Stmt::Jump(self.joins[id], self.symbols(arguments)),
+            Stmt::Crash(symbol, tag) => Stmt::Crash(self.symbol(*symbol), *tag),
+        }
+    }
+
+    fn branch_info(&self, info: &BranchInfo<'a>) -> BranchInfo<'a> {
+        match *info {
+            BranchInfo::None => BranchInfo::None,
+            BranchInfo::Constructor {
+                scrutinee,
+                layout,
+                tag_id,
+            } => BranchInfo::Constructor {
+                scrutinee: self.symbol(scrutinee),
+                layout,
+                tag_id,
+            },
+            BranchInfo::List { scrutinee, len } => BranchInfo::List {
+                scrutinee: self.symbol(scrutinee),
+                len,
+            },
+            BranchInfo::Unique { scrutinee, unique } => BranchInfo::Unique {
+                scrutinee: self.symbol(scrutinee),
+                unique,
+            },
+        }
+    }
+
+    fn expr(&self, expr: &Expr<'a>) -> Expr<'a> {
+        let arena = self.arena;
+
+        match expr {
+            Expr::Literal(_)
+            | Expr::NullPointer
+            | Expr::EmptyArray
+            | Expr::FunctionPointer { .. }
+            | Expr::RuntimeErrorFunction(_) => expr.clone(),
+            Expr::Call(call) => {
+                let call_type = match &call.call_type {
+                    CallType::ByPointer {
+                        pointer,
+                        ret_layout,
+                        arg_layouts,
+                    } => CallType::ByPointer {
+                        pointer: self.symbol(*pointer),
+                        ret_layout: *ret_layout,
+                        arg_layouts,
+                    },
+                    CallType::HigherOrder(higher_order) => {
+                        let mut passed_function = higher_order.passed_function;
+                        passed_function.captured_environment =
+                            self.symbol(passed_function.captured_environment);
+
+                        CallType::HigherOrder(arena.alloc(HigherOrderLowLevel {
+                            passed_function,
+                            ..(*higher_order).clone()
+                        }))
+                    }
+                    CallType::ByName { .. }
+                    | CallType::Foreign { .. }
+                    | CallType::LowLevel { .. } => call.call_type.clone(),
+                };
+
+                Expr::Call(Call {
+                    call_type,
+                    arguments: self.symbols(call.arguments),
+                })
+            }
+            Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+                reuse,
+            } => Expr::Tag {
+                tag_layout: *tag_layout,
+                tag_id: *tag_id,
+                arguments: self.symbols(arguments),
+                reuse: reuse.map(|token| ReuseToken {
+                    symbol: self.symbol(token.symbol),
+                    ..token
+                }),
+            },
+            Expr::Struct(fields) => Expr::Struct(self.symbols(fields)),
+            Expr::StructAtIndex {
+                index,
+                field_layouts,
+                structure,
+            } => Expr::StructAtIndex {
+                index: *index,
+                field_layouts,
+                structure: self.symbol(*structure),
+            },
+            Expr::GetTagId {
+                structure,
+                union_layout,
+            } => Expr::GetTagId {
+                structure: self.symbol(*structure),
+                union_layout: *union_layout,
+            },
+            Expr::UnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+            } => Expr::UnionAtIndex {
+                structure: self.symbol(*structure),
+                tag_id: *tag_id,
+                union_layout: *union_layout,
+                index: *index,
+            },
+            Expr::GetElementPointer {
+                structure,
+                union_layout,
+                indices,
+            } => Expr::GetElementPointer {
+                structure: self.symbol(*structure),
+                union_layout: *union_layout,
+                indices,
+            },
+            Expr::Array { elem_layout, elems } => Expr::Array {
+                elem_layout: *elem_layout,
+                elems: arena.alloc_slice_fill_iter(elems.iter().map(|elem| match elem {
+                    ListLiteralElement::Symbol(symbol) => {
+                        ListLiteralElement::Symbol(self.symbol(*symbol))
+                    }
+                    ListLiteralElement::Literal(_) => *elem,
+                })),
+            },
+            Expr::ErasedMake { value, callee } => Expr::ErasedMake {
+                value: value.map(|value| self.symbol(value)),
+                callee: self.symbol(*callee),
+            },
+            Expr::ErasedLoad { symbol, field } => Expr::ErasedLoad {
+                symbol: self.symbol(*symbol),
+                field: *field,
+            },
+            Expr::Alloca {
+                element_layout,
+                initializer,
+            } => Expr::Alloca {
+                element_layout: *element_layout,
+                initializer: initializer.map(|initializer| self.symbol(initializer)),
+            },
+            Expr::Reset {
+                symbol,
+                update_mode,
+            } => Expr::Reset {
+                symbol: self.symbol(*symbol),
+                update_mode: *update_mode,
+            },
+            Expr::ResetRef {
+                symbol,
+                update_mode,
+            } => Expr::ResetRef {
+                symbol: self.symbol(*symbol),
+                update_mode: *update_mode,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_mono::ir::{CallSpecId, Literal};
+    use roc_mono::layout::{LambdaName, Layout, Niche};
+
+    type Procs<'a> = MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>;
+
+    struct Test<'a> {
+        arena: &'a Bump,
+        ident_ids: IdentIds,
+    }
+
+    impl<'a> Test<'a> {
+        const HOME: ModuleId = ModuleId::ATTR;
+
+        fn symbol(&mut self) -> Symbol {
+            Symbol::new(Self::HOME, self.ident_ids.gen_unique())
+        }
+
+        fn proc_layout(&self, arity: usize) -> ProcLayout<'a> {
+            ProcLayout {
+                arguments: self.arena.alloc_slice_fill_copy(arity, Layout::I64),
+                result: Layout::I64,
+                niche: Niche::NONE,
+            }
+        }
+
+        fn add_proc(&self, procs: &mut Procs<'a>, name: Symbol, args: &[Symbol], body: Stmt<'a>) {
+            let proc = Proc {
+                name: LambdaName::no_niche(name),
+                args: self
+                    .arena
+                    .alloc_slice_fill_iter(args.iter().map(|arg| (Layout::I64, *arg))),
+                body,
+                closure_data_layout: None,
+                ret_layout: Layout::I64,
+                is_self_recursive: SelfRecursive::NotSelfRecursive,
+                is_erased: false,
+            };
+            procs.insert((name, self.proc_layout(args.len())), proc);
+        }
+
+        /// `result = name arguments` followed by `cont`
+        fn call(
+            &self,
+            result: Symbol,
+            name: Symbol,
+            arguments: &[Symbol],
+            cont: Stmt<'a>,
+        ) -> Stmt<'a> {
+            let call = Call {
+                call_type: CallType::ByName {
+                    name: LambdaName::no_niche(name),
+                    ret_layout: Layout::I64,
+                    arg_layouts: self.proc_layout(arguments.len()).arguments,
+                    specialization_id: CallSpecId::BACKEND_DUMMY,
+                },
+                arguments: self.arena.alloc_slice_copy(arguments),
+            };
+            Stmt::Let(
+                result,
+                Expr::Call(call),
+                Layout::I64,
+                self.arena.alloc(cont),
+            )
+        }
+
+        /// `symbol = 1` followed by `cont`
+        fn literal(&self, symbol: Symbol, cont: Stmt<'a>) -> Stmt<'a> {
+            let literal = Expr::Literal(Literal::Int(1i128.to_ne_bytes()));
+            Stmt::Let(symbol, literal, Layout::I64, self.arena.alloc(cont))
+        }
+
+        fn inline(&mut self, max_stmts: usize, procs: &mut Procs<'a>) {
+            inline_small_procs(
+                self.arena,
+                &mut self.ident_ids,
+                Self::HOME,
+                max_stmts,
+                procs,
+            );
+        }
+    }
+
+    fn proc_body<'p, 'a>(procs: &'p Procs<'a>, name: Symbol) -> &'p Stmt<'a> {
+        &procs.iter().find(|((n, _), _)| *n == name).unwrap().1.body
+    }
+
+    /// `ident(x) = tmp = 1; ret x`
+    fn add_ident<'a>(test: &mut Test<'a>, procs: &mut Procs<'a>) -> Symbol {
+        let name = test.symbol();
+        let arg = test.symbol();
+        let tmp = test.symbol();
+        let body = test.literal(tmp, Stmt::Ret(arg));
+        test.add_proc(procs, name, &[arg], body);
+        name
+    }
+
+    #[test]
+    fn inline_call_before_other_statements() {
+        let arena = Bump::new();
+        let mut test = Test {
+            arena: &arena,
+            ident_ids: IdentIds::default(),
+        };
+        let mut procs = Procs::default();
+        let ident = add_ident(&mut test, &mut procs);
+
+        // main = a = 1; x = ident a; y = 1; ret x
+        let main = test.symbol();
+        let [a, x, y] = [test.symbol(), test.symbol(), test.symbol()];
+        let after = test.literal(y, Stmt::Ret(x));
+        let body = test.literal(a, test.call(x, ident, &[a], after));
+        test.add_proc(&mut procs, main, &[], body);
+
+        test.inline(8, &mut procs);
+
+        let Stmt::Let(
+            _,
+            _,
+            _,
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            },
+        ) = proc_body(&procs, main)
+        else {
+            panic!("{:?}", proc_body(&procs, main));
+        };
+        assert_eq!(
+            parameters
+                .iter()
+                .map(|p| p.symbol)
+                .collect::<std::vec::Vec<_>>(),
+            [x]
+        );
+        assert!(matches!(body, Stmt::Let(s, _, _, Stmt::Ret(r)) if *s == y && *r == x));
+
+        // The inlined body defines a fresh symbol and jumps back with the argument
+        let Stmt::Let(tmp, _, _, Stmt::Jump(jump_id, arguments)) = remainder else {
+            panic!("{:?}", remainder);
+        };
+        assert!(*tmp != a && *tmp != x && *tmp != y);
+        assert_eq!((jump_id, *arguments), (id, [a].as_slice()));
+    }
+
+    #[test]
+    fn inline_tail_call_without_join_point() {
+        let arena = Bump::new();
+        let mut test = Test {
+            arena: &arena,
+            ident_ids: IdentIds::default(),
+        };
+        let mut procs = Procs::default();
+        let ident = add_ident(&mut test, &mut procs);
+
+        // main = a = 1; x = ident a; ret x
+        let main = test.symbol();
+        let [a, x] = [test.symbol(), test.symbol()];
+        let body = test.literal(a, test.call(x, ident, &[a], Stmt::Ret(x)));
+        test.add_proc(&mut procs, main, &[], body);
+
+        test.inline(8, &mut procs);
+
+        let main_body = proc_body(&procs, main);
+        assert!(
+            matches!(main_body, Stmt::Let(_, _, _, Stmt::Let(_, _, _, Stmt::Ret(r))) if *r == a),
+            "{:?}",
+            main_body
+        );
+    }
+
+    #[test]
+    fn keep_calls_to_big_and_recursive_procs() {
+        let arena = Bump::new();
+        let mut test = Test {
+            arena: &arena,
+            ident_ids: IdentIds::default(),
+        };
+        let mut procs = Procs::default();
+
+        // big(x) = 10 literals; ret x
+        let big = test.symbol();
+        let big_arg = test.symbol();
+        let mut big_body = Stmt::Ret(big_arg);
+        for _ in 0..10 {
+            let symbol = test.symbol();
+            big_body = test.literal(symbol, big_body);
+        }
+        test.add_proc(&mut procs, big, &[big_arg], big_body);
+
+        // rec(x) = y = rec x; ret y
+        let rec = test.symbol();
+        let [rec_arg, y] = [test.symbol(), test.symbol()];
+        let rec_body = test.call(y, rec, &[rec_arg], Stmt::Ret(y));
+        test.add_proc(&mut procs, rec, &[rec_arg], rec_body);
+
+        // main = a = 1; b = big a; c = rec b; ret c
+        let main = test.symbol();
+        let [a, b, c] = [test.symbol(), test.symbol(), test.symbol()];
+        let calls_stmt = test.call(b, big, &[a], test.call(c, rec, &[b], Stmt::Ret(c)));
+        let main_body = test.literal(a, calls_stmt);
+        test.add_proc(&mut procs, main, &[], main_body);
+
+        let expected = procs.clone();
+        test.inline(8, &mut procs);
+
+        // `rec` is small, but it isn't tail recursive, so only calling itself shows it's recursive
+        assert_eq!(procs, expected);
+    }
+}
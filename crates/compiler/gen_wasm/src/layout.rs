@@ -1,6 +1,8 @@
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
 use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_error_macros::todo_lambda_erasure;
-use roc_mono::layout::{InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout};
+use roc_mono::layout::{InLayout, LayoutInterner, LayoutRepr, UnionLayout};
 
 use crate::{PTR_SIZE, PTR_TYPE};
 use roc_wasm_module::ValueType;
@@ -39,7 +41,7 @@ pub enum WasmLayout {
 }
 
 impl WasmLayout {
-    pub fn new<'a>(interner: &STLayoutInterner<'a>, layout: InLayout<'a>) -> Self {
+    pub fn new<'a>(interner: &impl LayoutInterner<'a>, layout: InLayout<'a>) -> Self {
         use roc_mono::layout::Builtin::*;
         use UnionLayout::*;
         use ValueType::*;
@@ -163,3 +165,47 @@ pub fn stack_memory_return_method(size: u32, format: StackMemoryFormat) -> Retur
         }
     }
 }
+
+/// One field of a struct that is returned as multiple Wasm values, instead of through a pointer.
+/// Only procs that are called from our own generated code do this. Anything called by the host
+/// or by Zig builtins has to stick to the C calling convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReturnField {
+    pub value_type: ValueType,
+    pub size: u32,
+    pub offset: u32,
+}
+
+/// Bigger structs are cheaper to copy through memory than to shuffle through locals
+pub const MAX_RETURN_FIELDS: usize = 4;
+
+/// The fields to return as multiple Wasm values, if this layout is a small struct of numbers
+pub fn multi_value_return_fields<'a>(
+    arena: &'a Bump,
+    interner: &impl LayoutInterner<'a>,
+    layout: InLayout<'a>,
+) -> Option<&'a [ReturnField]> {
+    let field_layouts = match interner.get_repr(layout) {
+        LayoutRepr::Struct(field_layouts) => field_layouts,
+        _ => return None,
+    };
+    if !(2..=MAX_RETURN_FIELDS).contains(&field_layouts.len()) {
+        return None;
+    }
+
+    let mut fields = Vec::with_capacity_in(field_layouts.len(), arena);
+    let mut offset = 0;
+    for field_layout in field_layouts.iter() {
+        match WasmLayout::new(interner, *field_layout) {
+            WasmLayout::Primitive(value_type, size) => fields.push(ReturnField {
+                value_type,
+                size,
+                offset,
+            }),
+            WasmLayout::StackMemory { .. } => return None,
+        }
+        offset += interner.stack_size(*field_layout);
+    }
+
+    Some(fields.into_bump_slice())
+}
@@ -1,9 +1,14 @@
 //! Provides the WASM backend to generate Roc binaries.
 mod backend;
 mod code_builder;
+mod constants;
+mod inline;
 mod layout;
 mod low_level;
+mod parallel;
+mod split;
 mod storage;
+mod string_pool;
 
 // Helpers for interfacing to a Wasm module from outside
 pub mod wasm32_result;
@@ -16,10 +21,12 @@ use bumpalo::{self, Bump};
 use roc_collections::all::{MutMap, MutSet};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::CodeGenHelp;
-use roc_mono::ir::{Proc, ProcLayout};
-use roc_mono::layout::{LayoutIds, STLayoutInterner};
+use roc_mono::ir::{procedures_in_stable_order, Proc, ProcLayout};
+use roc_mono::layout::{Layout, LayoutIds, Niche, STLayoutInterner};
+use roc_mono::source_locations::SourceLocations;
 use roc_target::Target;
 use roc_wasm_module::parse::ParseError;
+use roc_wasm_module::sections::ImportDesc;
 use roc_wasm_module::{Align, LocalId, ValueType, WasmModule};
 
 use crate::backend::{ProcLookupData, ProcSource, WasmBackend};
@@ -36,20 +43,116 @@ const PTR_SIZE: u32 = {
     value
 };
 const PTR_TYPE: ValueType = ValueType::I32;
+/// Low bits of a heap pointer that hold the tag id of a recursive tag union
+const TAG_ID_MASK: i32 = TARGET.tag_id_pointer_mask() as i32;
 
 pub const MEMORY_NAME: &str = "memory";
 pub const BUILTINS_IMPORT_MODULE_NAME: &str = "env";
 pub const STACK_POINTER_NAME: &str = "__stack_pointer";
 
+/// Custom section describing the float semantics the module was compiled with,
+/// so that a host can check them before relying on deterministic floats.
+/// Only emitted when `Env::canonicalize_nans` is set, with the contents `FLOAT_SEMANTICS_CANONICAL_NANS`.
+pub const FLOAT_SEMANTICS_SECTION_NAME: &str = "roc_float_semantics";
+pub const FLOAT_SEMANTICS_CANONICAL_NANS: &[u8] = b"canonical-nans";
+
+/// Mutable i32 global, exported when `Env::heap_profiling` is set. It holds the ID of the
+/// allocation site that is currently calling `roc_alloc`, or 0 for allocations made by builtins.
+pub const ALLOC_SITE_GLOBAL_NAME: &str = "roc_alloc_site";
+/// Custom section describing each allocation site, emitted when `Env::heap_profiling` is set.
+/// It holds a vector of (source location, allocation kind) string pairs in the usual Wasm encoding.
+/// The location is `path:line:column` of the allocated value, or `Module.procName` for allocations
+/// with no source, like those in generated helper procedures.
+/// The entry at index `i` describes site ID `i + 1`.
+pub const ALLOC_SITES_SECTION_NAME: &str = "roc_alloc_sites";
+
+/// Exported when `Env::patchable_calls` is set. Takes two function table indices, and makes the
+/// first one call whatever function is at the second one.
+pub const PATCH_FN_NAME: &str = "roc_patch_fn";
+/// The function table, exported when `Env::patchable_calls` is set, so that the host can add
+/// functions to it.
+pub const FN_TABLE_NAME: &str = "__indirect_function_table";
+/// Custom section listing the function table index of every Roc procedure, emitted when
+/// `Env::patchable_calls` is set. It holds a vector of (table index, procedure name) pairs,
+/// with the index as a LEB-128 u32 and the name as a string, in the usual Wasm encoding.
+pub const PATCHABLE_FNS_SECTION_NAME: &str = "roc_patchable_fns";
+
+/// Recorded in the "producers" section, next to whatever compiled the host.
+const ROC_VERSION: &str = include_str!("../../../../version.txt");
+
 pub struct Env<'a> {
     pub arena: &'a Bump,
     pub module_id: ModuleId,
     pub exposed_to_host: MutSet<Symbol>,
     pub stack_bytes: u32,
+    /// Replace every NaN produced by float arithmetic with the same canonical NaN.
+    /// The Wasm spec lets engines pick any NaN bit pattern, so without this, hosts that need
+    /// bit-for-bit identical results across engines (lockstep simulations, consensus) can't get them.
+    pub canonicalize_nans: bool,
+    /// Tag every heap allocation made by Roc code with an allocation site ID, for host-side heap
+    /// profilers. The ID is in the `ALLOC_SITE_GLOBAL_NAME` global while `roc_alloc` runs, so the
+    /// host can record it against the returned pointer, and look it up in `ALLOC_SITES_SECTION_NAME`.
+    /// The sites are described by where the allocated values are in these source locations.
+    pub heap_profiling: Option<&'a SourceLocations>,
+    /// Outline parts of any procedure with more IR statements than this into separate functions.
+    /// Wasm engines reject or choke on very large functions, which big generated procedures can produce.
+    pub max_function_stmts: usize,
+    /// Inline calls to procedures with at most this many IR statements, or none if it's 0.
+    /// Calls are relatively expensive in Wasm, and engines' baseline compilers don't inline them.
+    pub max_inline_stmts: usize,
+    /// Return small structs of numbers from procedures as several Wasm values, instead of writing
+    /// them to the caller's stack memory. Needs an engine that supports the "multivalue" feature.
+    pub multi_value_returns: bool,
+    /// Call Roc procedures through the function table, so that a host can replace them in a
+    /// running instance with `PATCH_FN_NAME`, for hot reloading. Calls from the host go through
+    /// the table too. Turns off inlining, and needs an engine that supports "reference-types".
+    pub patchable_calls: bool,
+    /// Where Roc code gets heap memory from.
+    pub allocator: Allocator,
+    /// How many threads generate the app's procs. With 1, the calling thread does it all, which is
+    /// the only option when the compiler itself runs in Wasm, like the web REPL. The module is the
+    /// same for any number of threads.
+    pub codegen_threads: usize,
+}
+
+/// Which `roc_alloc`, `roc_realloc` and `roc_dealloc` the app calls.
+///
+/// Apart from `Host`, the app defines them itself. The host has to leave them undefined, and
+/// doesn't need to export `__heap_base` and `__heap_end`. Instead, the heap starts right after the stack.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Allocator {
+    /// The host's functions
+    #[default]
+    Host,
+    /// A bump allocator that never frees anything, for instances that are thrown away after
+    /// running for a short time.
+    Bump,
+    /// A bump allocator that frees everything at the start of each call from the host into the app,
+    /// so that memory use doesn't grow with the number of calls. The host must be done with the
+    /// heap data in one call's result before it makes the next call.
+    ArenaPerCall,
 }
 
+/// The allocation functions an app defines, when its `Allocator` isn't `Host`.
+const APP_ALLOCATOR_FNS: [(&str, ProcSource); 3] = [
+    ("roc_alloc", ProcSource::BumpAlloc),
+    ("roc_realloc", ProcSource::BumpRealloc),
+    ("roc_dealloc", ProcSource::BumpDealloc),
+];
+
 impl Env<'_> {
     pub const DEFAULT_STACK_BYTES: u32 = 1024 * 1024;
+    pub const DEFAULT_MAX_FUNCTION_STMTS: usize = 10_000;
+    pub const DEFAULT_MAX_INLINE_STMTS: usize = 8;
+
+    /// One thread for each core, or just the calling thread in Wasm, which has no threads
+    pub fn available_codegen_threads() -> usize {
+        if cfg!(target_family = "wasm") {
+            1
+        } else {
+            std::thread::available_parallelism().map_or(1, |n| n.get())
+        }
+    }
 }
 
 /// Parse the preprocessed host binary
@@ -90,13 +193,38 @@ pub fn build_app_module<'a, 'r>(
     layout_interner: &'r mut STLayoutInterner<'a>,
     interns: &'r mut Interns,
     host_module: WasmModule<'a>,
-    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    mut procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
 ) -> (WasmModule<'a>, BitVec<usize>, u32) {
+    inline::inline_small_procs(
+        env.arena,
+        interns.all_ident_ids.get_mut(&env.module_id).unwrap(),
+        env.module_id,
+        // An inlined call can't be patched
+        if env.patchable_calls {
+            0
+        } else {
+            env.max_inline_stmts
+        },
+        &mut procedures,
+    );
+
+    split::split_oversized_procs(
+        env.arena,
+        interns.all_ident_ids.get_mut(&env.module_id).unwrap(),
+        env.module_id,
+        env.max_function_stmts,
+        &mut procedures,
+    );
+
+    let procedures = procedures_in_stable_order(procedures, interns, layout_interner);
+
     let mut layout_ids = LayoutIds::default();
     let mut procs = Vec::with_capacity_in(procedures.len(), env.arena);
     let mut proc_lookup = Vec::with_capacity_in(procedures.len() * 2, env.arena);
     let mut host_to_app_map = Vec::with_capacity_in(env.exposed_to_host.len(), env.arena);
     let mut maybe_main_fn_index = None;
+    let procedures_len = procedures.len();
+    let mut host_wrappers = Vec::new_in(env.arena);
 
     // Adjust Wasm function indices to account for functions from the object file
     let fn_index_offset: u32 =
@@ -105,17 +233,23 @@ pub fn build_app_module<'a, 'r>(
     // Pre-pass over the procedure names & layouts
     // Create a lookup to tell us the final index of each proc in the output file
     for (i, ((sym, proc_layout), proc)) in procedures.into_iter().enumerate() {
-        let fn_index = fn_index_offset + i as u32;
+        let mut fn_index = fn_index_offset + i as u32;
         procs.push(proc);
         if env.exposed_to_host.contains(&sym) {
-            maybe_main_fn_index = Some(fn_index);
-
             let exposed_name = layout_ids
                 .get_toplevel(sym, &proc_layout)
                 .to_exposed_symbol_string(sym, interns);
 
             let exposed_name_bump: &'a str = env.arena.alloc_str(&exposed_name);
 
+            if env.allocator == Allocator::ArenaPerCall || env.patchable_calls {
+                // The host calls a wrapper that frees the heap first, or calls through the table.
+                // It goes after the procs, since the wrapped proc must be generated before it.
+                fn_index = fn_index_offset + procedures_len as u32 + host_wrappers.len() as u32;
+                host_wrappers.push(i);
+            }
+
+            maybe_main_fn_index = Some(fn_index);
             host_to_app_map.push((exposed_name_bump, fn_index));
         }
 
@@ -126,6 +260,59 @@ pub fn build_app_module<'a, 'r>(
         });
     }
 
+    for inner_idx in host_wrappers {
+        let (name, source) = if env.allocator == Allocator::ArenaPerCall {
+            (
+                "#reset_heap_and_call",
+                ProcSource::ResetHeapAndCall(inner_idx),
+            )
+        } else {
+            ("#patchable_entry", ProcSource::PatchableEntry(inner_idx))
+        };
+        proc_lookup.push(ProcLookupData {
+            name: create_symbol(interns, env.module_id, name),
+            layout: proc_lookup[inner_idx].layout,
+            source,
+        });
+    }
+
+    // Nothing looks the functions the app defines for the host up by layout
+    let host_fn_layout = ProcLayout {
+        arguments: &[],
+        result: Layout::UNIT,
+        niche: Niche::NONE,
+    };
+
+    if env.allocator != Allocator::Host {
+        for (name, source) in APP_ALLOCATOR_FNS {
+            let fn_index = fn_index_offset + proc_lookup.len() as u32;
+
+            proc_lookup.push(ProcLookupData {
+                name: create_symbol(interns, env.module_id, name),
+                layout: host_fn_layout,
+                source,
+            });
+
+            let is_imported = host_module.import.imports.iter().any(|import| {
+                import.name == name && matches!(import.description, ImportDesc::Func { .. })
+            });
+
+            if is_imported {
+                host_to_app_map.push((name, fn_index));
+            } else if host_module.linking.find_internal_symbol(name).is_ok() {
+                panic!("The app has its own {name}, for the {:?} allocator, but the host defines one too. The host needs to leave it undefined.", env.allocator);
+            }
+        }
+    }
+
+    if env.patchable_calls {
+        proc_lookup.push(ProcLookupData {
+            name: create_symbol(interns, env.module_id, PATCH_FN_NAME),
+            layout: host_fn_layout,
+            source: ProcSource::PatchFn,
+        });
+    }
+
     let mut backend = WasmBackend::new(
         env,
         layout_interner,
@@ -141,14 +328,19 @@ pub fn build_app_module<'a, 'r>(
     if DEBUG_SETTINGS.user_procs_ir {
         println!("## procs");
         for proc in procs.iter() {
-            println!("{}", proc.to_pretty(backend.layout_interner, 200, true));
+            println!("{}", proc.to_pretty(&backend.layout_interner, 200, true));
             // println!("{:?}", proc);
         }
     }
 
     // Generate procs from user code
-    for proc in procs.iter() {
-        backend.build_proc(proc);
+    parallel::expand_refcounting(&mut backend, &mut procs);
+    let generated_procs = parallel::generate_in_workers(&mut backend, &procs);
+    for (proc, generated) in procs.iter().zip(generated_procs) {
+        match generated {
+            Some(generated) => backend.add_generated_proc(proc, generated),
+            None => backend.build_proc(proc),
+        }
     }
 
     // Generate specialized helpers for refcounting & equality
@@ -159,7 +351,7 @@ pub fn build_app_module<'a, 'r>(
     if DEBUG_SETTINGS.helper_procs_ir {
         println!("## helper_procs");
         for proc in helper_procs.iter() {
-            println!("{}", proc.to_pretty(backend.layout_interner, 200, true));
+            println!("{}", proc.to_pretty(&backend.layout_interner, 200, true));
             // println!("{:#?}", proc);
         }
     }
@@ -179,6 +371,16 @@ pub fn build_app_module<'a, 'r>(
             Roc => { /* already generated */ }
             Helper => backend.build_proc(helper_iter.next().unwrap()),
             HigherOrderCompare(inner_idx) => backend.build_higher_order_compare(idx, *inner_idx),
+            MemoryEquals {
+                size,
+                alignment_bytes,
+            } => backend.build_memory_equals(idx, *size, *alignment_bytes),
+            BumpAlloc => backend.build_bump_alloc(idx),
+            BumpRealloc => backend.build_bump_realloc(idx),
+            BumpDealloc => backend.build_bump_dealloc(idx),
+            ResetHeapAndCall(inner_idx) => backend.build_host_entry(idx, *inner_idx, true),
+            PatchableEntry(inner_idx) => backend.build_host_entry(idx, *inner_idx, false),
+            PatchFn => backend.build_patch_fn(idx),
         }
     }
 
@@ -189,6 +391,12 @@ pub fn build_app_module<'a, 'r>(
     (module, called_fns, main_function_index)
 }
 
+fn create_symbol(interns: &mut Interns, module_id: ModuleId, debug_name: &str) -> Symbol {
+    let ident_ids = interns.all_ident_ids.get_mut(&module_id).unwrap();
+    let ident_id = ident_ids.add_str(debug_name);
+    Symbol::new(module_id, ident_id)
+}
+
 pub struct CopyMemoryConfig {
     from_ptr: LocalId,
     from_offset: u32,
@@ -1,5 +1,4 @@
 use bumpalo::collections::Vec;
-use bumpalo::Bump;
 use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::low_level::LowLevel;
@@ -12,7 +11,7 @@ use roc_mono::low_level::HigherOrder;
 use crate::backend::{ProcLookupData, ProcSource, WasmBackend};
 use crate::layout::{StackMemoryFormat, WasmLayout};
 use crate::storage::{AddressValue, StackMemoryLocation, StoredValue};
-use crate::PTR_TYPE;
+use crate::{PTR_TYPE, TAG_ID_MASK};
 use roc_wasm_module::{Align, LocalId, ValueType};
 
 /// Number types used for Wasm code gen
@@ -139,7 +138,7 @@ impl<'a> LowLevelCall<'a> {
             &mut backend.code_builder,
             self.arguments,
             self.ret_symbol,
-            &WasmLayout::new(backend.layout_interner, self.ret_layout),
+            &WasmLayout::new(&backend.layout_interner, self.ret_layout),
         );
     }
 
@@ -148,6 +147,71 @@ impl<'a> LowLevelCall<'a> {
         backend.call_host_fn_after_loading_args(name);
     }
 
+    /// Could this call return a NaN? Arithmetic NaNs in Wasm can have any bit pattern,
+    /// which differs between engines. Operations that only move or reinterpret bits are excluded,
+    /// since they return the bits they were given.
+    pub fn may_produce_nan(&self) -> bool {
+        use LowLevel::*;
+
+        matches!(self.ret_layout_raw, LayoutRepr::Builtin(Builtin::Float(_)))
+            && matches!(
+                self.lowlevel,
+                NumAdd
+                    | NumAddWrap
+                    | NumAddSaturated
+                    | NumSub
+                    | NumSubWrap
+                    | NumSubSaturated
+                    | NumMul
+                    | NumMulWrap
+                    | NumMulSaturated
+                    | NumDivFrac
+                    | NumRemUnchecked
+                    | NumSin
+                    | NumCos
+                    | NumTan
+                    | NumAsin
+                    | NumAcos
+                    | NumAtan
+                    | NumSqrtUnchecked
+                    | NumLogUnchecked
+                    | NumPow
+                    | NumToFrac
+                    | NumToFloatCast
+            )
+    }
+
+    /// Replace the float on top of the value stack with the canonical NaN if it is any NaN.
+    pub fn canonicalize_nan(&self, backend: &mut WasmBackend<'a, '_>) {
+        let width = match self.ret_layout_raw {
+            LayoutRepr::Builtin(Builtin::Float(width)) => width,
+            _ => internal_error!("Cannot canonicalize a NaN of layout {:?}", self.ret_layout),
+        };
+
+        let value_type = match width {
+            FloatWidth::F32 => ValueType::F32,
+            FloatWidth::F64 => ValueType::F64,
+        };
+        let value = backend.storage.create_anonymous_local(value_type);
+        backend.code_builder.set_local(value);
+
+        // select(canonical, value, value != value)
+        match width {
+            FloatWidth::F32 => backend.code_builder.f32_const(f32::from_bits(0x7fc0_0000)),
+            FloatWidth::F64 => backend
+                .code_builder
+                .f64_const(f64::from_bits(0x7ff8_0000_0000_0000)),
+        }
+        backend.code_builder.get_local(value);
+        backend.code_builder.get_local(value);
+        backend.code_builder.get_local(value);
+        match width {
+            FloatWidth::F32 => backend.code_builder.f32_ne(),
+            FloatWidth::F64 => backend.code_builder.f64_ne(),
+        }
+        backend.code_builder.select();
+    }
+
     /// Wrap an integer that should have less than 32 bits, but is represented in Wasm as i32.
     /// This may seem like deliberately introducing an error!
     /// But we want all targets to behave the same, and hash algos rely on wrapping.
@@ -240,7 +304,7 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     self.arguments,
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
                 backend.code_builder.i32_const(UPDATE_MODE_IMMUTABLE);
                 backend.call_host_fn_after_loading_args(bitcode::STR_FROM_UTF8);
@@ -278,7 +342,7 @@ impl<'a> LowLevelCall<'a> {
                 let elem_in_layout = unwrap_list_elem_layout(self.ret_layout_raw);
                 let elem_layout = backend.layout_interner.get_repr(elem_in_layout);
                 let (elem_width, elem_align) =
-                    elem_layout.stack_size_and_alignment(backend.layout_interner);
+                    elem_layout.stack_size_and_alignment(&backend.layout_interner);
 
                 let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
                 let inc_fn_ptr =
@@ -349,7 +413,7 @@ impl<'a> LowLevelCall<'a> {
                 let elem_in_layout = unwrap_list_elem_layout(list_layout);
                 let elem_layout = backend.layout_interner.get_repr(elem_in_layout);
                 let (elem_width, elem_align) =
-                    elem_layout.stack_size_and_alignment(backend.layout_interner);
+                    elem_layout.stack_size_and_alignment(&backend.layout_interner);
 
                 let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
                 let dec_fn = backend.get_existing_helper_fn_index(
@@ -443,13 +507,13 @@ impl<'a> LowLevelCall<'a> {
                             {
                                 let list_offset = 0;
                                 let elem_offset = LayoutRepr::Builtin(Builtin::List(list_elem))
-                                    .stack_size(backend.layout_interner);
+                                    .stack_size(&backend.layout_interner);
                                 (list_offset, elem_offset, f2)
                             }
                             (_, LayoutRepr::Builtin(Builtin::List(list_elem)))
                                 if l1 == backend.layout_interner.get_repr(list_elem) =>
                             {
-                                let list_offset = l1.stack_size(backend.layout_interner);
+                                let list_offset = l1.stack_size(&backend.layout_interner);
                                 let elem_offset = 0;
                                 (list_offset, elem_offset, f1)
                             }
@@ -464,12 +528,8 @@ impl<'a> LowLevelCall<'a> {
                     .stack_size_and_alignment(elem_in_layout);
 
                 // Ensure the new element is stored in memory so we can pass a pointer to Zig
-                let (new_elem_local, new_elem_offset, _) = ensure_symbol_is_in_memory(
-                    backend,
-                    new_elem,
-                    elem_in_layout,
-                    backend.env.arena,
-                );
+                let (new_elem_local, new_elem_offset) =
+                    ensure_symbol_is_in_memory(backend, new_elem, elem_in_layout);
 
                 let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
                 let inc_fn_ptr =
@@ -531,7 +591,7 @@ impl<'a> LowLevelCall<'a> {
                 let elem_in_layout = unwrap_list_elem_layout(self.ret_layout_raw);
                 let elem_layout = backend.layout_interner.get_repr(elem_in_layout);
                 let (elem_width, elem_align) =
-                    elem_layout.stack_size_and_alignment(backend.layout_interner);
+                    elem_layout.stack_size_and_alignment(&backend.layout_interner);
                 let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
                 let inc_fn_ptr =
                     build_refcount_element_fn(backend, elem_in_layout, HelperOp::IndirectInc);
@@ -562,14 +622,14 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     self.arguments,
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
 
                 // Load monomorphization constants
                 let elem_in_layout = unwrap_list_elem_layout(self.ret_layout_raw);
                 let elem_layout = backend.layout_interner.get_repr(elem_in_layout);
                 let (elem_width, elem_align) =
-                    elem_layout.stack_size_and_alignment(backend.layout_interner);
+                    elem_layout.stack_size_and_alignment(&backend.layout_interner);
                 let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
                 let inc_fn_ptr =
                     build_refcount_element_fn(backend, elem_in_layout, HelperOp::IndirectInc);
@@ -605,7 +665,7 @@ impl<'a> LowLevelCall<'a> {
                 let elem_in_layout = unwrap_list_elem_layout(self.ret_layout_raw);
                 let elem_layout = backend.layout_interner.get_repr(elem_in_layout);
                 let (elem_width, elem_align) =
-                    elem_layout.stack_size_and_alignment(backend.layout_interner);
+                    elem_layout.stack_size_and_alignment(&backend.layout_interner);
                 let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
                 let inc_fn_ptr =
                     build_refcount_element_fn(backend, elem_in_layout, HelperOp::IndirectInc);
@@ -625,7 +685,7 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     &[list],
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
 
                 backend.code_builder.i32_const(elem_align as i32);
@@ -650,7 +710,7 @@ impl<'a> LowLevelCall<'a> {
                 let elem_in_layout = unwrap_list_elem_layout(self.ret_layout_raw);
                 let elem_layout = backend.layout_interner.get_repr(elem_in_layout);
                 let (elem_width, elem_align) =
-                    elem_layout.stack_size_and_alignment(backend.layout_interner);
+                    elem_layout.stack_size_and_alignment(&backend.layout_interner);
 
                 let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
                 let inc_fn_ptr =
@@ -673,7 +733,7 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     &[list],
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
 
                 backend.code_builder.i32_const(elem_align as i32);
@@ -694,8 +754,8 @@ impl<'a> LowLevelCall<'a> {
 
                 let elem_in_layout = unwrap_list_elem_layout(self.ret_layout_raw);
                 let elem_width = backend.layout_interner.stack_size(elem_in_layout);
-                let (elem_local, elem_offset, _) =
-                    ensure_symbol_is_in_memory(backend, elem, elem_in_layout, backend.env.arena);
+                let (elem_local, elem_offset) =
+                    ensure_symbol_is_in_memory(backend, elem, elem_in_layout);
                 let copy_fn_ptr = build_copy_element_fn(backend, elem_in_layout);
 
                 // Zig arguments              Wasm types
@@ -710,7 +770,7 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     &[list],
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
 
                 backend.code_builder.get_local(elem_local);
@@ -734,8 +794,8 @@ impl<'a> LowLevelCall<'a> {
                 let (elem_width, elem_align) = backend
                     .layout_interner
                     .stack_size_and_alignment(elem_in_layout);
-                let (elem_local, elem_offset, _) =
-                    ensure_symbol_is_in_memory(backend, elem, elem_in_layout, backend.env.arena);
+                let (elem_local, elem_offset) =
+                    ensure_symbol_is_in_memory(backend, elem, elem_in_layout);
 
                 let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
                 let inc_fn_ptr =
@@ -757,7 +817,7 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     &[list],
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
 
                 backend.code_builder.i32_const(elem_align as i32);
@@ -805,7 +865,7 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     &[list],
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
 
                 backend.code_builder.i32_const(elem_align as i32);
@@ -849,7 +909,7 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     &[list],
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
 
                 backend.code_builder.i32_const(elem_align as i32);
@@ -899,7 +959,7 @@ impl<'a> LowLevelCall<'a> {
                     &mut backend.code_builder,
                     &[list],
                     self.ret_symbol,
-                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    &WasmLayout::new(&backend.layout_interner, self.ret_layout),
                 );
 
                 backend.code_builder.i32_const(elem_align as i32);
@@ -1930,7 +1990,7 @@ impl<'a> LowLevelCall<'a> {
                         // So the argument is implicitly converted to signed before the shift operator.
                         // We need to make that conversion explicit for i8 and i16, which use Wasm's i32 type.
                         let bit_width =
-                            8 * self.ret_layout_raw.stack_size(backend.layout_interner) as i32;
+                            8 * self.ret_layout_raw.stack_size(&backend.layout_interner) as i32;
                         if bit_width < 32 && !symbol_is_signed_int(backend, num) {
                             // Sign-extend the number by shifting left and right again
                             backend
@@ -1976,7 +2036,8 @@ impl<'a> LowLevelCall<'a> {
                         // In most languages this operation is for unsigned numbers, but Roc defines it on all integers.
                         // So the argument is implicitly converted to unsigned before the shift operator.
                         // We need to make that conversion explicit for i8 and i16, which use Wasm's i32 type.
-                        let bit_width = 8 * self.ret_layout_raw.stack_size(backend.layout_interner);
+                        let bit_width =
+                            8 * self.ret_layout_raw.stack_size(&backend.layout_interner);
                         if bit_width < 32 && symbol_is_signed_int(backend, num) {
                             let mask = (1 << bit_width) - 1;
 
@@ -2232,7 +2293,7 @@ impl<'a> LowLevelCall<'a> {
 
                 backend.code_builder.get_local(ptr_local_id);
 
-                backend.code_builder.i32_const(-4); // 11111111...1100
+                backend.code_builder.i32_const(!TAG_ID_MASK);
                 backend.code_builder.i32_and();
             }
 
@@ -2312,6 +2373,23 @@ impl<'a> LowLevelCall<'a> {
                 }
             }
 
+            // Records of plain integers are equal if their bytes are equal.
+            // Compare them with a helper function shared by all records of the same size.
+            LayoutRepr::Struct(_) if eq_is_bytewise(&backend.layout_interner, arg_layout) => {
+                backend
+                    .storage
+                    .load_symbols(&mut backend.code_builder, self.arguments);
+
+                let (size, alignment_bytes) =
+                    backend.layout_interner.stack_size_and_alignment(arg_layout);
+                let fn_index = backend.get_memory_equals_fn_index(size, alignment_bytes);
+                backend.code_builder.call(fn_index);
+
+                if invert_result {
+                    backend.code_builder.i32_eqz();
+                }
+            }
+
             // Empty record is always equal to empty record.
             // There are no runtime arguments to check, so just emit true or false.
             LayoutRepr::Struct([]) => {
@@ -2573,6 +2651,24 @@ fn num_is_infinite(backend: &mut WasmBackend<'_, '_>, argument: Symbol) {
     }
 }
 
+/// Whether `==` on values of this layout is the same as comparing their bytes.
+/// That's true for non-empty records of integers, as long as there is no padding that could contain garbage.
+fn eq_is_bytewise<'a>(interner: &impl LayoutInterner<'a>, layout: InLayout<'a>) -> bool {
+    fn help<'a>(interner: &impl LayoutInterner<'a>, layout: InLayout<'a>) -> bool {
+        match interner.get_repr(layout) {
+            LayoutRepr::Builtin(Builtin::Int(_) | Builtin::Bool | Builtin::Decimal) => true,
+            LayoutRepr::Struct(fields) => {
+                let fields_size: u32 = fields.iter().map(|f| interner.stack_size(*f)).sum();
+                fields_size == interner.stack_size(layout)
+                    && fields.iter().all(|f| help(interner, *f))
+            }
+            _ => false,
+        }
+    }
+
+    interner.stack_size(layout) > 0 && help(interner, layout)
+}
+
 /// Helper for NumIsFinite op, and also part of Eq/NotEq
 fn num_is_finite(backend: &mut WasmBackend<'_, '_>, argument: Symbol) {
     use StoredValue::*;
@@ -2624,6 +2720,11 @@ pub fn call_higher_order_lowlevel<'a>(
 ) {
     use HigherOrder::*;
 
+    // The wrapper around the passed function is a new proc, with new layouts
+    if backend.defer_to_main_thread() {
+        return;
+    }
+
     let HigherOrderLowLevel {
         op,
         passed_function,
@@ -2656,7 +2757,10 @@ pub fn call_higher_order_lowlevel<'a>(
         .get_repr(backend.storage.symbol_layouts[captured_environment])
     {
         LayoutRepr::LambdaSet(lambda_set) => {
-            if lambda_set.is_represented(backend.layout_interner).is_some() {
+            if lambda_set
+                .is_represented(&backend.layout_interner)
+                .is_some()
+            {
                 (lambda_set.runtime_representation(), true)
             } else {
                 // Closure data is a lambda set, which *itself* has no closure data!
@@ -2683,7 +2787,7 @@ pub fn call_higher_order_lowlevel<'a>(
         // make sure that the wrapping struct is available in stack memory, so we can hand out a
         // pointer to it.
         let wrapped_storage = backend.storage.allocate_var(
-            backend.layout_interner,
+            &backend.layout_interner,
             wrapped_captures_layout,
             wrapped_closure_data_sym,
             crate::storage::StoredVarKind::Variable,
@@ -2760,7 +2864,15 @@ pub fn call_higher_order_lowlevel<'a>(
                 result: *result_layout,
                 niche: fn_name.niche(),
             },
-            ProcSource::Roc | ProcSource::Helper => {
+            ProcSource::Roc
+            | ProcSource::Helper
+            | ProcSource::MemoryEquals { .. }
+            | ProcSource::BumpAlloc
+            | ProcSource::BumpRealloc
+            | ProcSource::BumpDealloc
+            | ProcSource::ResetHeapAndCall(_)
+            | ProcSource::PatchableEntry(_)
+            | ProcSource::PatchFn => {
                 internal_error!("Should never reach here for {:?}", helper_proc_source)
             }
         }
@@ -2789,7 +2901,7 @@ pub fn call_higher_order_lowlevel<'a>(
             );
             let elem_layout = backend.layout_interner.get_repr(elem_in_layout);
             let (element_width, alignment) =
-                elem_layout.stack_size_and_alignment(backend.layout_interner);
+                elem_layout.stack_size_and_alignment(&backend.layout_interner);
 
             let elem_refcounted = backend.layout_interner.contains_refcounted(elem_in_layout);
             let inc_fn_ptr =
@@ -2849,13 +2961,11 @@ fn ensure_symbol_is_in_memory<'a>(
     backend: &mut WasmBackend<'a, '_>,
     symbol: Symbol,
     layout: InLayout<'a>,
-    arena: &'a Bump,
-) -> (LocalId, u32, InLayout<'a>) {
+) -> (LocalId, u32) {
     // Ensure the new element is stored in memory so we can pass a pointer to Zig
     match backend.storage.get(&symbol) {
         StoredValue::StackMemory { location, .. } => {
-            let (local, offset) = location.local_and_offset(backend.storage.stack_frame_pointer);
-            (local, offset, layout)
+            location.local_and_offset(backend.storage.stack_frame_pointer)
         }
         _ => {
             let (width, alignment) = backend.layout_interner.stack_size_and_alignment(layout);
@@ -2868,10 +2978,7 @@ fn ensure_symbol_is_in_memory<'a>(
                 offset,
                 symbol,
             );
-            let in_memory_layout = backend
-                .layout_interner
-                .insert_direct_no_semantic(LayoutRepr::Struct(arena.alloc([layout])));
-            (frame_ptr, offset, in_memory_layout)
+            (frame_ptr, offset)
         }
     }
 }
@@ -0,0 +1,326 @@
+//! Generates the Roc procs of an app on several threads at once.
+//!
+//! Each worker thread has a backend of its own, with its own code builder and a fork of the layout
+//! interner. It turns procs into [GeneratedProc]s, which the main thread adds to the module in the
+//! usual order, creating their linker symbols and relocations as it goes. The module is the same
+//! whatever the number of threads.
+//!
+//! Workers only read what the main thread had set up before they started. A proc that would change
+//! it, by registering a helper proc, taking a function pointer, storing a string constant or
+//! interning a layout, is given up by its worker, and generated by the main thread afterwards.
+//! To keep that rare, refcounting statements are expanded into calls to helper procs beforehand,
+//! on the main thread, whatever the number of threads.
+//!
+//! Helper procs are always generated on the main thread, after the Roc procs.
+
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bumpalo::Bump;
+use roc_collections::all::{MutMap, MutSet};
+use roc_error_macros::internal_error;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_mono::ir::{ModifyRc, Proc, Stmt};
+use roc_mono::layout::{InLayout, STLayoutInterner, TLLayoutInterner};
+
+use crate::backend::{GeneratedProc, WasmBackend, WorkerSeed};
+use crate::{Allocator, Env, TARGET};
+
+/// Expand the refcounting statements of `procs` into calls to helper procs, as
+/// `WasmBackend::build_proc` would, so that workers can generate the calls without registering
+/// the helpers. `Free` is left as it is, since it calls a builtin.
+pub fn expand_refcounting<'a>(backend: &mut WasmBackend<'a, '_>, procs: &mut [Proc<'a>]) {
+    for proc in procs.iter_mut() {
+        let body: &'a Stmt<'a> = backend.env.arena.alloc(proc.body.clone());
+
+        let mut expander = RefcountExpander {
+            backend: &mut *backend,
+            layouts: MutMap::from_iter(proc.args.iter().map(|(layout, symbol)| (*symbol, *layout))),
+        };
+        let expanded = expander.stmt(body);
+        if !ptr::eq(expanded, body) {
+            proc.body = expanded.clone();
+        }
+    }
+}
+
+struct RefcountExpander<'a, 'b, 'r> {
+    backend: &'b mut WasmBackend<'a, 'r>,
+    /// Layouts of the symbols defined so far
+    layouts: MutMap<Symbol, InLayout<'a>>,
+}
+
+impl<'a> RefcountExpander<'a, '_, '_> {
+    /// Returns `stmt` itself if there is nothing to expand in it.
+    /// Visits statements in the order the backend generates them, so helpers keep that order.
+    fn stmt(&mut self, stmt: &'a Stmt<'a>) -> &'a Stmt<'a> {
+        let arena = self.backend.env.arena;
+
+        match stmt {
+            Stmt::Let(..) => {
+                // Long chains of `Let` are common, so go through them without recursion
+                let mut chain = std::vec::Vec::new();
+                let mut end = stmt;
+                while let Stmt::Let(symbol, _, layout, following) = end {
+                    self.layouts.insert(*symbol, *layout);
+                    chain.push(end);
+                    end = *following;
+                }
+
+                let mut expanded = self.stmt(end);
+                for let_stmt in chain.into_iter().rev() {
+                    let Stmt::Let(symbol, expr, layout, following) = let_stmt else {
+                        unreachable!()
+                    };
+                    expanded = if ptr::eq(expanded, *following) {
+                        let_stmt
+                    } else {
+                        arena.alloc(Stmt::Let(*symbol, expr.clone(), *layout, expanded))
+                    };
+                }
+                expanded
+            }
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let default_stmt = self.stmt(default_branch.1);
+                let branch_stmts = std::vec::Vec::from_iter(
+                    branches.iter().map(|(_, _, branch)| self.stmt(branch)),
+                );
+
+                let unchanged = ptr::eq(default_stmt, default_branch.1)
+                    && branches
+                        .iter()
+                        .zip(branch_stmts.iter())
+                        .all(|((_, _, branch), expanded)| ptr::eq(branch, *expanded));
+                if unchanged {
+                    return stmt;
+                }
+
+                let branches =
+                    arena.alloc_slice_fill_iter(branches.iter().zip(branch_stmts).map(
+                        |((value, info, _), expanded)| (*value, info.clone(), expanded.clone()),
+                    ));
+                arena.alloc(Stmt::Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches,
+                    default_branch: (default_branch.0.clone(), default_stmt),
+                    ret_layout: *ret_layout,
+                })
+            }
+            Stmt::Refcounting(modify, following) => match modify {
+                ModifyRc::Free(_) => {
+                    let expanded = self.stmt(following);
+                    if ptr::eq(expanded, *following) {
+                        stmt
+                    } else {
+                        arena.alloc(Stmt::Refcounting(*modify, expanded))
+                    }
+                }
+                _ => {
+                    let layout = self.layouts[&modify.get_symbol()];
+                    let rc_stmt = self.backend.expand_refcounting(layout, modify, following);
+                    // The expansion can have refcounting statements of its own
+                    self.stmt(rc_stmt)
+                }
+            },
+            // The backend doesn't generate these
+            Stmt::Expect { .. } | Stmt::ExpectFx { .. } => stmt,
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable,
+                remainder,
+            } => {
+                let expanded = self.stmt(remainder);
+                if ptr::eq(expanded, *remainder) {
+                    stmt
+                } else {
+                    arena.alloc(Stmt::Dbg {
+                        source_location,
+                        source,
+                        symbol: *symbol,
+                        variable: *variable,
+                        remainder: expanded,
+                    })
+                }
+            }
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                for param in parameters.iter() {
+                    self.layouts.insert(param.symbol, param.layout);
+                }
+                let expanded_remainder = self.stmt(remainder);
+                let expanded_body = self.stmt(body);
+                if ptr::eq(expanded_remainder, *remainder) && ptr::eq(expanded_body, *body) {
+                    stmt
+                } else {
+                    arena.alloc(Stmt::Join {
+                        id: *id,
+                        parameters,
+                        body: expanded_body,
+                        remainder: expanded_remainder,
+                    })
+                }
+            }
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => stmt,
+        }
+    }
+}
+
+/// Generate as many of `procs` as possible on worker threads. The main thread has to generate the
+/// ones that come back as `None` itself, with `WasmBackend::build_proc`.
+pub fn generate_in_workers<'a>(
+    backend: &mut WasmBackend<'a, '_>,
+    procs: &[Proc<'a>],
+) -> std::vec::Vec<Option<GeneratedProc>> {
+    let env = backend.env;
+    let threads = env.codegen_threads.min(procs.len());
+
+    // Allocation sites are numbered in the order they are generated, and patchable calls go
+    // through the function table, which only the main thread can add to.
+    if threads <= 1 || env.heap_profiling.is_some() || env.patchable_calls {
+        return procs.iter().map(|_| None).collect();
+    }
+
+    let worker_env = WorkerEnv::new(env);
+    let interner = backend.layout_interner.single_threaded();
+    let global_interner =
+        std::mem::replace(interner, STLayoutInterner::with_capacity(0, TARGET)).into_global();
+
+    let results = {
+        let seed = backend.worker_seed();
+        let next_proc = AtomicUsize::new(0);
+        let (worker_env, seed, next_proc) = (&worker_env, &seed, &next_proc);
+
+        std::thread::scope(|scope| {
+            let handles = std::vec::Vec::from_iter((0..threads).map(|_| {
+                let layout_interner = global_interner.fork();
+                scope.spawn(move || {
+                    generate_procs(worker_env, seed, layout_interner, procs, next_proc)
+                })
+            }));
+
+            // Join the workers here rather than at the end of the scope, so that a panic reaches
+            // the caller as it is, once the interner is back in place
+            std::vec::Vec::from_iter(handles.into_iter().map(|handle| handle.join()))
+        })
+    };
+
+    *backend.layout_interner.single_threaded() = global_interner.unwrap().unwrap_or_else(|_| {
+        internal_error!("A worker thread kept its fork of the layout interner")
+    });
+
+    let mut generated = std::vec::Vec::from_iter(procs.iter().map(|_| None));
+    for result in results {
+        match result {
+            Ok(worker_results) => {
+                for (index, generated_proc) in worker_results {
+                    generated[index] = generated_proc;
+                }
+            }
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+
+    generated
+}
+
+/// Generate procs until there are none left, taking the next one from `next_proc` each time
+fn generate_procs<'a>(
+    worker_env: &WorkerEnv,
+    seed: &WorkerSeed<'a, '_>,
+    layout_interner: TLLayoutInterner<'a>,
+    procs: &[Proc<'a>],
+    next_proc: &AtomicUsize,
+) -> std::vec::Vec<(usize, Option<GeneratedProc>)> {
+    let worker_arena = Bump::new();
+    // SAFETY: Nothing allocated in the arena outlives it. The backend and everything it refers to
+    // are dropped before the arena, and the generated procs own their data. The layout interner
+    // could keep layouts from the arena, but a worker backend never interns any.
+    let arena: &'a Bump = unsafe { &*(&worker_arena as *const Bump) };
+
+    let env = worker_env.env(arena);
+    let mut interns = seed.interns.clone();
+    let mut backend = WasmBackend::new_worker(&env, layout_interner, &mut interns, seed);
+
+    let mut generated = std::vec::Vec::new();
+    loop {
+        let index = next_proc.fetch_add(1, Ordering::Relaxed);
+        let Some(proc) = procs.get(index) else {
+            break;
+        };
+        generated.push((index, backend.build_proc_in_worker(proc)));
+    }
+
+    generated
+}
+
+/// The parts of [Env] that workers use. Unlike [Env], it can be shared between threads.
+struct WorkerEnv<'e> {
+    module_id: ModuleId,
+    exposed_to_host: &'e MutSet<Symbol>,
+    stack_bytes: u32,
+    canonicalize_nans: bool,
+    max_function_stmts: usize,
+    max_inline_stmts: usize,
+    multi_value_returns: bool,
+    allocator: Allocator,
+}
+
+impl<'e> WorkerEnv<'e> {
+    fn new(env: &'e Env<'_>) -> Self {
+        let Env {
+            arena: _,
+            module_id,
+            exposed_to_host,
+            stack_bytes,
+            canonicalize_nans,
+            heap_profiling: _,
+            max_function_stmts,
+            max_inline_stmts,
+            multi_value_returns,
+            patchable_calls: _,
+            allocator,
+            codegen_threads: _,
+        } = env;
+
+        WorkerEnv {
+            module_id: *module_id,
+            exposed_to_host,
+            stack_bytes: *stack_bytes,
+            canonicalize_nans: *canonicalize_nans,
+            max_function_stmts: *max_function_stmts,
+            max_inline_stmts: *max_inline_stmts,
+            multi_value_returns: *multi_value_returns,
+            allocator: *allocator,
+        }
+    }
+
+    fn env<'a>(&self, arena: &'a Bump) -> Env<'a> {
+        Env {
+            arena,
+            module_id: self.module_id,
+            exposed_to_host: self.exposed_to_host.clone(),
+            stack_bytes: self.stack_bytes,
+            canonicalize_nans: self.canonicalize_nans,
+            heap_profiling: None,
+            max_function_stmts: self.max_function_stmts,
+            max_inline_stmts: self.max_inline_stmts,
+            multi_value_returns: self.multi_value_returns,
+            patchable_calls: false,
+            allocator: self.allocator,
+            codegen_threads: 1,
+        }
+    }
+}
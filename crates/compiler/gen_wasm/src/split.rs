@@ -0,0 +1,728 @@
+//! Splits procedures that are too large for Wasm engines into several smaller ones.
+//!
+//! Engines limit the size and the number of locals of each function, and their baseline compilers
+//! track the whole value stack of a function at once. Generated code such as decoders can produce
+//! a single Roc procedure that goes past those limits, so before code generation we outline parts
+//! of oversized procedures into helper procedures, passing the values they use as arguments.
+//!
+//! The parts we outline are switch branches and the tails of let-chains. Either one ends in a
+//! `Ret` of the procedure's return value, unless it jumps to a join point defined outside of it,
+//! in which case we leave it where it is.
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+
+use roc_collections::all::{MutMap, MutSet};
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_mono::ir::{
+    Call, CallSpecId, CallType, Expr, JoinPointId, ListLiteralElement, ModifyRc, Proc, ProcLayout,
+    SelfRecursive, Stmt,
+};
+use roc_mono::layout::{InLayout, LambdaName, Niche};
+
+/// Splits every procedure with more than `max_stmts` statements, adding the outlined parts to
+/// `procedures` as new procedures.
+///
+/// This is best-effort: a procedure can stay oversized if it has no part that can be outlined,
+/// for example when it is a single switch with very many small branches.
+pub fn split_oversized_procs<'a>(
+    arena: &'a Bump,
+    ident_ids: &mut IdentIds,
+    module_id: ModuleId,
+    max_stmts: usize,
+    procedures: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    let oversized = std::vec::Vec::from_iter(
+        procedures
+            .iter()
+            .filter(|(_, proc)| stmt_count(&proc.body) > max_stmts)
+            .map(|(key, _)| *key),
+    );
+
+    for key in oversized {
+        let mut proc = procedures.remove(&key).unwrap();
+
+        let mut splitter = Splitter {
+            arena,
+            ident_ids,
+            module_id,
+            max_stmts,
+            layouts: symbol_layouts(&proc),
+            helpers: std::vec::Vec::new(),
+        };
+
+        proc.body = splitter.split(proc.body, proc.ret_layout);
+
+        let helpers = splitter.helpers;
+        procedures.insert(key, proc);
+        procedures.extend(helpers);
+    }
+}
+
+struct Splitter<'a, 'i> {
+    arena: &'a Bump,
+    ident_ids: &'i mut IdentIds,
+    module_id: ModuleId,
+    max_stmts: usize,
+    layouts: MutMap<Symbol, InLayout<'a>>,
+    helpers: std::vec::Vec<((Symbol, ProcLayout<'a>), Proc<'a>)>,
+}
+
+impl<'a, 'i> Splitter<'a, 'i> {
+    fn split(&mut self, mut body: Stmt<'a>, ret_layout: InLayout<'a>) -> Stmt<'a> {
+        while stmt_count(&body) > self.max_stmts {
+            let mut candidates = std::vec::Vec::new();
+            collect_candidates(&body, &mut candidates);
+
+            // Outline the biggest part that fits in a procedure of its own. Replacing it with a
+            // call takes two statements, so anything smaller is not worth it.
+            candidates.retain(|(_, count)| 2 < *count && *count <= self.max_stmts);
+            candidates.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+            let outlined = candidates.iter().find_map(|(candidate, _)| {
+                self.free_symbols(candidate)
+                    .map(|free_symbols| (*candidate, free_symbols))
+            });
+
+            let Some((candidate, free_symbols)) = outlined else {
+                break;
+            };
+
+            let call = self.outline(candidate, &free_symbols, ret_layout);
+            body = replace(self.arena, &body, candidate, &call).unwrap();
+        }
+
+        body
+    }
+
+    /// The symbols a statement uses but does not define, in order of first use,
+    /// or `None` if it jumps out of itself or uses a symbol we don't know the layout of.
+    fn free_symbols(&self, stmt: &Stmt<'a>) -> Option<std::vec::Vec<Symbol>> {
+        let mut usage = Usage::default();
+        usage.stmt(stmt);
+
+        if usage.jumps.iter().any(|id| !usage.joins.contains(id)) {
+            return None;
+        }
+
+        let free_symbols = std::vec::Vec::from_iter(
+            usage
+                .used
+                .into_iter()
+                .filter(|symbol| !usage.defined.contains(symbol)),
+        );
+
+        if free_symbols
+            .iter()
+            .all(|symbol| self.layouts.contains_key(symbol))
+        {
+            Some(free_symbols)
+        } else {
+            None
+        }
+    }
+
+    /// Moves `stmt` into a new procedure, returning the statement that calls it.
+    fn outline(
+        &mut self,
+        stmt: &Stmt<'a>,
+        free_symbols: &[Symbol],
+        ret_layout: InLayout<'a>,
+    ) -> Stmt<'a> {
+        let arena = self.arena;
+        let name = Symbol::new(self.module_id, self.ident_ids.add_str("#outlined"));
+        let result = Symbol::new(self.module_id, self.ident_ids.gen_unique());
+
+        let arg_layouts = arena.alloc_slice_fill_iter(free_symbols.iter().map(|s| self.layouts[s]));
+        let args = arena.alloc_slice_fill_iter(
+            free_symbols
+                .iter()
+                .map(|symbol| (self.layouts[symbol], *symbol)),
+        );
+
+        let proc_layout = ProcLayout {
+            arguments: arg_layouts,
+            result: ret_layout,
+            niche: Niche::NONE,
+        };
+
+        let helper = Proc {
+            name: LambdaName::no_niche(name),
+            args,
+            body: stmt.clone(),
+            closure_data_layout: None,
+            ret_layout,
+            is_self_recursive: SelfRecursive::NotSelfRecursive,
+            is_erased: false,
+        };
+
+        self.helpers.push(((name, proc_layout), helper));
+        self.layouts.insert(result, ret_layout);
+
+        let call = Call {
+            call_type: CallType::ByName {
+                name: LambdaName::no_niche(name),
+                ret_layout,
+                arg_layouts,
+                specialization_id: CallSpecId::BACKEND_DUMMY,
+            },
+            arguments: arena.alloc_slice_copy(free_symbols),
+        };
+
+        Stmt::Let(
+            result,
+            Expr::Call(call),
+            ret_layout,
+            arena.alloc(Stmt::Ret(result)),
+        )
+    }
+}
+
+fn symbol_layouts<'a>(proc: &Proc<'a>) -> MutMap<Symbol, InLayout<'a>> {
+    fn help<'a>(stmt: &Stmt<'a>, layouts: &mut MutMap<Symbol, InLayout<'a>>) {
+        match stmt {
+            Stmt::Let(symbol, _, layout, cont) => {
+                layouts.insert(*symbol, *layout);
+                help(cont, layouts);
+            }
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                for (_, _, branch) in branches.iter() {
+                    help(branch, layouts);
+                }
+                help(default_branch.1, layouts);
+            }
+            Stmt::Join {
+                parameters,
+                body,
+                remainder,
+                ..
+            } => {
+                for param in parameters.iter() {
+                    layouts.insert(param.symbol, param.layout);
+                }
+                help(body, layouts);
+                help(remainder, layouts);
+            }
+            Stmt::Refcounting(_, cont)
+            | Stmt::Expect {
+                remainder: cont, ..
+            }
+            | Stmt::ExpectFx {
+                remainder: cont, ..
+            }
+            | Stmt::Dbg {
+                remainder: cont, ..
+            } => help(cont, layouts),
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+        }
+    }
+
+    let mut layouts =
+        MutMap::from_iter(proc.args.iter().map(|(layout, symbol)| (*symbol, *layout)));
+    help(&proc.body, &mut layouts);
+    layouts
+}
+
+pub(crate) fn stmt_count(stmt: &Stmt) -> usize {
+    match stmt {
+        Stmt::Let(_, _, _, cont)
+        | Stmt::Refcounting(_, cont)
+        | Stmt::Expect {
+            remainder: cont, ..
+        }
+        | Stmt::ExpectFx {
+            remainder: cont, ..
+        }
+        | Stmt::Dbg {
+            remainder: cont, ..
+        } => 1 + stmt_count(cont),
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            let branches: usize = branches.iter().map(|(_, _, b)| stmt_count(b)).sum();
+            1 + branches + stmt_count(default_branch.1)
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => 1 + stmt_count(body) + stmt_count(remainder),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => 1,
+    }
+}
+
+/// Collects the parts of `stmt` that could be outlined, with their statement counts.
+/// Returns the statement count of `stmt` itself.
+fn collect_candidates<'a>(
+    stmt: &Stmt<'a>,
+    candidates: &mut std::vec::Vec<(&'a Stmt<'a>, usize)>,
+) -> usize {
+    let candidate = |stmt: &'a Stmt<'a>, candidates: &mut std::vec::Vec<_>| {
+        let count = collect_candidates(stmt, candidates);
+        candidates.push((stmt, count));
+        count
+    };
+
+    match stmt {
+        Stmt::Let(_, _, _, cont) => 1 + candidate(cont, candidates),
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            let mut count = 1;
+            for (_, _, branch) in branches.iter() {
+                count += candidate(branch, candidates);
+            }
+            count + candidate(default_branch.1, candidates)
+        }
+        Stmt::Refcounting(_, cont)
+        | Stmt::Expect {
+            remainder: cont, ..
+        }
+        | Stmt::ExpectFx {
+            remainder: cont, ..
+        }
+        | Stmt::Dbg {
+            remainder: cont, ..
+        } => 1 + collect_candidates(cont, candidates),
+        Stmt::Join {
+            body, remainder, ..
+        } => 1 + collect_candidates(body, candidates) + collect_candidates(remainder, candidates),
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => 1,
+    }
+}
+
+/// Copies `stmt` with `target` replaced by `replacement`,
+/// or returns `None` if `target` is not part of `stmt`.
+fn replace<'a>(
+    arena: &'a Bump,
+    stmt: &Stmt<'a>,
+    target: &Stmt<'a>,
+    replacement: &Stmt<'a>,
+) -> Option<Stmt<'a>> {
+    if std::ptr::eq(stmt, target) {
+        return Some(replacement.clone());
+    }
+
+    let replace_in = |stmt: &Stmt<'a>| -> Option<&'a Stmt<'a>> {
+        replace(arena, stmt, target, replacement).map(|new| &*arena.alloc(new))
+    };
+
+    match stmt {
+        Stmt::Let(symbol, expr, layout, cont) => {
+            let cont = replace_in(cont)?;
+            Some(Stmt::Let(*symbol, expr.clone(), *layout, cont))
+        }
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let mut new_branches = None;
+            for (index, (_, _, branch)) in branches.iter().enumerate() {
+                if let Some(new_branch) = replace(arena, branch, target, replacement) {
+                    let mut copied = Vec::from_iter_in(branches.iter().cloned(), arena);
+                    copied[index].2 = new_branch;
+                    new_branches = Some(copied.into_bump_slice());
+                    break;
+                }
+            }
+
+            let (branches, default_branch) = match new_branches {
+                Some(new_branches) => (new_branches, default_branch.clone()),
+                None => {
+                    let default_stmt = replace_in(default_branch.1)?;
+                    (*branches, (default_branch.0.clone(), default_stmt))
+                }
+            };
+
+            Some(Stmt::Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches,
+                default_branch,
+                ret_layout: *ret_layout,
+            })
+        }
+        Stmt::Refcounting(modify, cont) => Some(Stmt::Refcounting(*modify, replace_in(cont)?)),
+        Stmt::Expect {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => Some(Stmt::Expect {
+            condition: *condition,
+            region: *region,
+            lookups,
+            variables,
+            remainder: replace_in(remainder)?,
+        }),
+        Stmt::ExpectFx {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => Some(Stmt::ExpectFx {
+            condition: *condition,
+            region: *region,
+            lookups,
+            variables,
+            remainder: replace_in(remainder)?,
+        }),
+        Stmt::Dbg {
+            source_location,
+            source,
+            symbol,
+            variable,
+            remainder,
+        } => Some(Stmt::Dbg {
+            source_location,
+            source,
+            symbol: *symbol,
+            variable: *variable,
+            remainder: replace_in(remainder)?,
+        }),
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let (body, remainder) = match replace_in(body) {
+                Some(new_body) => (new_body, *remainder),
+                None => (*body, replace_in(remainder)?),
+            };
+
+            Some(Stmt::Join {
+                id: *id,
+                parameters,
+                body,
+                remainder,
+            })
+        }
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => None,
+    }
+}
+
+/// The symbols and join points a statement defines and uses.
+#[derive(Default)]
+struct Usage {
+    defined: MutSet<Symbol>,
+    used: std::vec::Vec<Symbol>,
+    seen: MutSet<Symbol>,
+    joins: MutSet<JoinPointId>,
+    jumps: std::vec::Vec<JoinPointId>,
+}
+
+impl Usage {
+    fn use_symbol(&mut self, symbol: Symbol) {
+        if self.seen.insert(symbol) {
+            self.used.push(symbol);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let(symbol, expr, _, cont) => {
+                self.defined.insert(*symbol);
+                self.expr(expr);
+                self.stmt(cont);
+            }
+            Stmt::Switch {
+                cond_symbol,
+                branches,
+                default_branch,
+                ..
+            } => {
+                self.use_symbol(*cond_symbol);
+                for (_, _, branch) in branches.iter() {
+                    self.stmt(branch);
+                }
+                self.stmt(default_branch.1);
+            }
+            Stmt::Ret(symbol) => self.use_symbol(*symbol),
+            Stmt::Refcounting(modify, cont) => {
+                match modify {
+                    ModifyRc::Inc(symbol, _)
+                    | ModifyRc::Dec(symbol)
+                    | ModifyRc::DecRef(symbol)
+                    | ModifyRc::Free(symbol) => self.use_symbol(*symbol),
+                }
+                self.stmt(cont);
+            }
+            Stmt::Expect {
+                condition,
+                lookups,
+                remainder,
+                ..
+            }
+            | Stmt::ExpectFx {
+                condition,
+                lookups,
+                remainder,
+                ..
+            } => {
+                self.use_symbol(*condition);
+                for lookup in lookups.iter() {
+                    self.use_symbol(*lookup);
+                }
+                self.stmt(remainder);
+            }
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                self.use_symbol(*symbol);
+                self.stmt(remainder);
+            }
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                self.joins.insert(*id);
+                for param in parameters.iter() {
+                    self.defined.insert(param.symbol);
+                }
+                self.stmt(body);
+                self.stmt(remainder);
+            }
+            Stmt::Jump(id, arguments) => {
+                self.jumps.push(*id);
+                for argument in arguments.iter() {
+                    self.use_symbol(*argument);
+                }
+            }
+            Stmt::Crash(symbol, _) => self.use_symbol(*symbol),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(_)
+            | Expr::NullPointer
+            | Expr::EmptyArray
+            | Expr::FunctionPointer { .. }
+            | Expr::RuntimeErrorFunction(_) => {}
+            Expr::Call(call) => {
+                match &call.call_type {
+                    CallType::ByPointer { pointer, .. } => self.use_symbol(*pointer),
+                    CallType::HigherOrder(higher_order) => {
+                        self.use_symbol(higher_order.passed_function.captured_environment)
+                    }
+                    CallType::ByName { .. }
+                    | CallType::Foreign { .. }
+                    | CallType::LowLevel { .. } => {}
+                }
+                for argument in call.arguments.iter() {
+                    self.use_symbol(*argument);
+                }
+            }
+            Expr::Tag {
+                arguments, reuse, ..
+            } => {
+                for argument in arguments.iter() {
+                    self.use_symbol(*argument);
+                }
+                if let Some(token) = reuse {
+                    self.use_symbol(token.symbol);
+                }
+            }
+            Expr::Struct(fields) => {
+                for field in fields.iter() {
+                    self.use_symbol(*field);
+                }
+            }
+            Expr::StructAtIndex { structure, .. }
+            | Expr::GetTagId { structure, .. }
+            | Expr::UnionAtIndex { structure, .. }
+            | Expr::GetElementPointer { structure, .. } => self.use_symbol(*structure),
+            Expr::Array { elems, .. } => {
+                for elem in elems.iter() {
+                    if let ListLiteralElement::Symbol(symbol) = elem {
+                        self.use_symbol(*symbol);
+                    }
+                }
+            }
+            Expr::ErasedMake { value, callee } => {
+                if let Some(value) = value {
+                    self.use_symbol(*value);
+                }
+                self.use_symbol(*callee);
+            }
+            Expr::ErasedLoad { symbol, .. }
+            | Expr::Reset { symbol, .. }
+            | Expr::ResetRef { symbol, .. } => self.use_symbol(*symbol),
+            Expr::Alloca { initializer, .. } => {
+                if let Some(initializer) = initializer {
+                    self.use_symbol(*initializer);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_mono::ir::{JoinPointId, Literal, Param};
+    use roc_mono::layout::Layout;
+
+    struct Test<'a> {
+        arena: &'a Bump,
+        ident_ids: IdentIds,
+    }
+
+    impl<'a> Test<'a> {
+        const HOME: ModuleId = ModuleId::ATTR;
+
+        fn symbol(&mut self) -> Symbol {
+            Symbol::new(Self::HOME, self.ident_ids.gen_unique())
+        }
+
+        /// `first = 0`, followed by `count - 1` more literals, ending in `end`
+        fn let_chain(&mut self, first: Symbol, count: usize, end: Stmt<'a>) -> Stmt<'a> {
+            let mut stmt = end;
+            for i in (0..count).rev() {
+                let symbol = if i == 0 { first } else { self.symbol() };
+                let literal = Expr::Literal(Literal::Int((i as i128).to_ne_bytes()));
+                stmt = Stmt::Let(symbol, literal, Layout::I64, self.arena.alloc(stmt));
+            }
+            stmt
+        }
+
+        fn proc(&mut self, body: Stmt<'a>) -> MutMap<(Symbol, ProcLayout<'a>), Proc<'a>> {
+            let name = self.symbol();
+            let proc_layout = ProcLayout {
+                arguments: &[],
+                result: Layout::I64,
+                niche: Niche::NONE,
+            };
+            let proc = Proc {
+                name: LambdaName::no_niche(name),
+                args: &[],
+                body,
+                closure_data_layout: None,
+                ret_layout: Layout::I64,
+                is_self_recursive: SelfRecursive::NotSelfRecursive,
+                is_erased: false,
+            };
+            MutMap::from_iter([((name, proc_layout), proc)])
+        }
+
+        fn split(
+            &mut self,
+            max_stmts: usize,
+            procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+        ) {
+            split_oversized_procs(
+                self.arena,
+                &mut self.ident_ids,
+                Self::HOME,
+                max_stmts,
+                procs,
+            );
+        }
+    }
+
+    #[test]
+    fn split_let_chain() {
+        let arena = Bump::new();
+        let mut test = Test {
+            arena: &arena,
+            ident_ids: IdentIds::default(),
+        };
+
+        let first = test.symbol();
+        let body = test.let_chain(first, 50, Stmt::Ret(first));
+        let mut procs = test.proc(body);
+        let original = procs.keys().next().unwrap().0;
+        test.split(10, &mut procs);
+
+        assert!(procs.len() > 1);
+        for proc in procs.values() {
+            assert!(stmt_count(&proc.body) <= 10, "{:?}", proc.body);
+        }
+
+        // Each outlined tail ends up returning `first`, so it has to be passed all the way down
+        for proc in procs.values().filter(|proc| proc.name.name() != original) {
+            assert_eq!(proc.args, [(Layout::I64, first)]);
+        }
+    }
+
+    #[test]
+    fn split_switch_branches() {
+        let arena = Bump::new();
+        let mut test = Test {
+            arena: &arena,
+            ident_ids: IdentIds::default(),
+        };
+
+        let cond = test.symbol();
+        let branch_results = [test.symbol(), test.symbol(), test.symbol()];
+        let branches = Vec::from_iter_in(
+            branch_results[..2].iter().enumerate().map(|(i, result)| {
+                let branch = test.let_chain(*result, 8, Stmt::Ret(cond));
+                (i as u64, roc_mono::ir::BranchInfo::None, branch)
+            }),
+            &arena,
+        );
+        let default = test.let_chain(branch_results[2], 8, Stmt::Ret(branch_results[2]));
+        let switch = Stmt::Switch {
+            cond_symbol: cond,
+            cond_layout: Layout::I64,
+            branches: branches.into_bump_slice(),
+            default_branch: (roc_mono::ir::BranchInfo::None, arena.alloc(default)),
+            ret_layout: Layout::I64,
+        };
+        let body = test.let_chain(cond, 1, switch);
+        let mut procs = test.proc(body);
+        test.split(12, &mut procs);
+
+        for proc in procs.values() {
+            assert!(stmt_count(&proc.body) <= 12, "{:?}", proc.body);
+        }
+
+        // The branches that return `cond` take it as an argument, the default branch takes nothing
+        let mut arg_counts = std::vec::Vec::from_iter(procs.values().map(|proc| proc.args.len()));
+        arg_counts.sort();
+        assert_eq!(arg_counts, [0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn keep_jumps_to_outer_join_points() {
+        let arena = Bump::new();
+        let mut test = Test {
+            arena: &arena,
+            ident_ids: IdentIds::default(),
+        };
+
+        let id = JoinPointId(test.symbol());
+        let param = test.symbol();
+        let first = test.symbol();
+        let remainder = test.let_chain(first, 20, Stmt::Jump(id, arena.alloc_slice_copy(&[first])));
+        let body = Stmt::Join {
+            id,
+            parameters: arena.alloc_slice_copy(&[Param {
+                symbol: param,
+                layout: Layout::I64,
+            }]),
+            body: arena.alloc(Stmt::Ret(param)),
+            remainder: arena.alloc(remainder),
+        };
+        let mut procs = test.proc(body.clone());
+        test.split(5, &mut procs);
+
+        assert_eq!(procs.len(), 1);
+        assert_eq!(procs.values().next().unwrap().body, body);
+    }
+}
@@ -4,10 +4,12 @@ use bumpalo::Bump;
 use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
 use roc_module::symbol::Symbol;
-use roc_mono::layout::{InLayout, STLayoutInterner};
+use roc_mono::layout::{InLayout, LayoutInterner};
 
 use crate::code_builder::CodeBuilder;
-use crate::layout::{stack_memory_arg_types, ReturnMethod, StackMemoryFormat, WasmLayout};
+use crate::layout::{
+    stack_memory_arg_types, ReturnField, ReturnMethod, StackMemoryFormat, WasmLayout,
+};
 use crate::{copy_memory, CopyMemoryConfig, PTR_TYPE};
 use roc_wasm_module::{round_up_to_alignment, Align, LocalId, ValueType};
 
@@ -60,7 +62,10 @@ pub enum AddressValue {
 /// including the VM stack, local variables, and linear memory
 #[derive(Debug)]
 pub struct Storage<'a> {
-    pub return_var: Option<LocalId>,
+    /// Locals holding the return value until the stack frame is popped. More than one for multi-value returns.
+    pub return_vars: Vec<'a, LocalId>,
+    /// Where each return var comes from in the returned struct, for multi-value returns only
+    pub return_fields: &'a [ReturnField],
     pub arg_types: Vec<'a, ValueType>,
     pub local_types: Vec<'a, ValueType>,
     pub symbol_layouts: MutMap<Symbol, InLayout<'a>>,
@@ -72,7 +77,8 @@ pub struct Storage<'a> {
 impl<'a> Storage<'a> {
     pub fn new(arena: &'a Bump) -> Self {
         Storage {
-            return_var: None,
+            return_vars: Vec::with_capacity_in(1, arena),
+            return_fields: &[],
             arg_types: Vec::with_capacity_in(8, arena),
             local_types: Vec::with_capacity_in(32, arena),
             symbol_layouts: MutMap::default(),
@@ -83,7 +89,8 @@ impl<'a> Storage<'a> {
     }
 
     pub fn clear(&mut self) {
-        self.return_var = None;
+        self.return_vars.clear();
+        self.return_fields = &[];
         self.arg_types.clear();
         self.local_types.clear();
         self.symbol_layouts.clear();
@@ -135,7 +142,7 @@ impl<'a> Storage<'a> {
     /// Data structures are stored in memory, with an offset and size in the stack frame.
     pub fn allocate_var(
         &mut self,
-        interner: &STLayoutInterner<'a>,
+        interner: &impl LayoutInterner<'a>,
         layout: InLayout<'a>,
         symbol: Symbol,
         kind: StoredVarKind,
@@ -188,7 +195,7 @@ impl<'a> Storage<'a> {
     /// stack frame, because it's a lot easier to keep track of the data flow.
     pub fn allocate_args(
         &mut self,
-        interner: &STLayoutInterner<'a>,
+        interner: &impl LayoutInterner<'a>,
         args: &[(InLayout<'a>, Symbol)],
         code_builder: &mut CodeBuilder,
         arena: &'a Bump,
@@ -223,11 +230,13 @@ impl<'a> Storage<'a> {
                     let location = match format {
                         Int128 | Decimal => {
                             // passed as two i64's but stored in the stack frame
-                            wide_number_args.push(local_index);
-                            let loc =
-                                StackMemoryLocation::FrameOffset(self.stack_frame_size as u32);
-                            self.stack_frame_size += size as i32;
-                            loc
+                            let offset = round_up_to_alignment!(
+                                self.stack_frame_size,
+                                alignment_bytes as i32
+                            );
+                            self.stack_frame_size = offset + size as i32;
+                            wide_number_args.push((local_index, offset as u32, alignment_bytes));
+                            StackMemoryLocation::FrameOffset(offset as u32)
                         }
                         DataStructure => {
                             if size == 0 {
@@ -261,17 +270,16 @@ impl<'a> Storage<'a> {
             self.stack_frame_pointer = Some(frame_ptr);
             self.local_types.push(PTR_TYPE);
 
-            let mut offset = 0;
-            for arg_index in wide_number_args.iter().copied() {
+            for (arg_index, offset, alignment_bytes) in wide_number_args.iter().copied() {
+                let align = Align::from(alignment_bytes);
+
                 code_builder.get_local(frame_ptr);
                 code_builder.get_local(LocalId(arg_index));
-                code_builder.i64_store(Align::Bytes8, offset);
+                code_builder.i64_store(align, offset);
 
                 code_builder.get_local(frame_ptr);
                 code_builder.get_local(LocalId(arg_index + 1));
-                code_builder.i64_store(Align::Bytes8, offset + 8);
-
-                offset += 16;
+                code_builder.i64_store(align, offset + 8);
             }
         }
     }
@@ -299,7 +307,7 @@ impl<'a> Storage<'a> {
                 location,
                 format,
                 size,
-                ..
+                alignment_bytes,
             } => {
                 if size == 0 {
                     return;
@@ -316,9 +324,10 @@ impl<'a> Storage<'a> {
                     }
                 } else {
                     // It's one of the 128-bit numbers, all of which we load as two i64's
-                    code_builder.i64_load(Align::Bytes8, offset);
+                    let align = Align::from(alignment_bytes);
+                    code_builder.i64_load(align, offset);
                     code_builder.get_local(local_id);
-                    code_builder.i64_load(Align::Bytes8, offset + 8);
+                    code_builder.i64_load(align, offset + 8);
                 }
             }
         }
@@ -0,0 +1,126 @@
+//! Deduplicates the string literals a module stores in its data segment.
+//!
+//! Every big string literal used to get its own copy, even when the same text appeared
+//! many times in the program. Generated code such as parsers repeats the same keywords and error
+//! messages over and over, so we remember the literals we have already stored and point new ones
+//! at the existing bytes.
+//!
+//! A literal that is a suffix of a stored literal shares its bytes too. It becomes a seamless
+//! slice of the stored literal, so that decrementing its refcount finds the stored literal's
+//! (immortal) refcount. Sharing depends on the order literals are generated in: a literal is only
+//! matched against literals stored before it.
+//!
+//! There is deliberately no table of the literals' offsets and lengths, such as a passive data
+//! segment. Generated code refers to each literal by its address, so nothing would read the table,
+//! and it would only make the module bigger.
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+
+/// Where to find the bytes of a string literal in the data section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PooledStr {
+    /// The literal was stored with its own refcount, at this offset in the segment
+    Elements(u32),
+    /// The literal is the end of a bigger literal that was stored at `parent_elements`
+    Suffix { elements: u32, parent_elements: u32 },
+}
+
+#[derive(Debug)]
+pub struct StringPool<'a> {
+    /// Bytes and element offset of each literal with its own refcount
+    stored: Vec<'a, (&'a [u8], u32)>,
+}
+
+impl<'a> StringPool<'a> {
+    pub fn new(arena: &'a Bump) -> Self {
+        StringPool {
+            stored: Vec::new_in(arena),
+        }
+    }
+
+    /// Find stored bytes that `bytes` can share, preferring an exact match
+    pub fn find(&self, bytes: &[u8]) -> Option<PooledStr> {
+        let mut suffix = None;
+
+        for (stored, elements_addr) in self.stored.iter() {
+            if *stored == bytes {
+                return Some(PooledStr::Elements(*elements_addr));
+            }
+
+            if suffix.is_none() && stored.ends_with(bytes) {
+                suffix = Some(PooledStr::Suffix {
+                    elements: elements_addr + (stored.len() - bytes.len()) as u32,
+                    parent_elements: *elements_addr,
+                });
+            }
+        }
+
+        suffix
+    }
+
+    /// Record that `bytes` were stored in the segment at `elements_addr`
+    pub fn insert(&mut self, bytes: &'a [u8], elements_addr: u32) {
+        self.stored.push((bytes, elements_addr));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_pool_finds_nothing() {
+        let arena = Bump::new();
+        let pool = StringPool::new(&arena);
+
+        assert_eq!(pool.find(b"a string literal"), None);
+    }
+
+    #[test]
+    fn exact_match() {
+        let arena = Bump::new();
+        let mut pool = StringPool::new(&arena);
+        pool.insert(b"first string literal", 1028);
+        pool.insert(b"second string literal", 1052);
+
+        assert_eq!(
+            pool.find(b"second string literal"),
+            Some(PooledStr::Elements(1052))
+        );
+    }
+
+    #[test]
+    fn suffix_match() {
+        let arena = Bump::new();
+        let mut pool = StringPool::new(&arena);
+        pool.insert(b"unexpected end of input", 1028);
+
+        assert_eq!(
+            pool.find(b"end of input"),
+            Some(PooledStr::Suffix {
+                elements: 1028 + 11,
+                parent_elements: 1028
+            })
+        );
+    }
+
+    #[test]
+    fn exact_match_wins_over_earlier_suffix_match() {
+        let arena = Bump::new();
+        let mut pool = StringPool::new(&arena);
+        pool.insert(b"unexpected end of input", 1028);
+        pool.insert(b"end of input", 1056);
+
+        assert_eq!(pool.find(b"end of input"), Some(PooledStr::Elements(1056)));
+    }
+
+    #[test]
+    fn prefix_is_not_shared() {
+        let arena = Bump::new();
+        let mut pool = StringPool::new(&arena);
+        pool.insert(b"unexpected end of input", 1028);
+
+        assert_eq!(pool.find(b"unexpected end"), None);
+    }
+}
@@ -25,18 +25,18 @@ macro_rules! wasm32_sized_primitive {
 wasm32_sized_primitive!(u8, i8, u16, i16, u32, i32, char, u64, i64, f32, f64, bool,);
 wasm32_sized_primitive!(RocOrder,);
 
-macro_rules! wasm32_16byte_aligned8 {
+macro_rules! wasm32_sized_128bit {
     ($($type_name:ident ,)+) => {
         $(
             impl Wasm32Sized for $type_name {
                 const SIZE_OF_WASM: usize = 16;
-                const ALIGN_OF_WASM: usize = 8;
+                const ALIGN_OF_WASM: usize = crate::TARGET.i128_alignment_bytes();
             }
         )*
     }
 }
 
-wasm32_16byte_aligned8!(i128, u128, I128, U128, RocDec,);
+wasm32_sized_128bit!(i128, u128, I128, U128, RocDec,);
 
 impl Wasm32Sized for () {
     const SIZE_OF_WASM: usize = 0;
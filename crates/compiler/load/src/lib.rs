@@ -18,12 +18,16 @@ const SKIP_SUBS_CACHE: bool = {
 
 pub use roc_load_internal::docs;
 pub use roc_load_internal::file::{
-    ExecutionMode, ExpectMetadata, LoadConfig, LoadResult, LoadStart, LoadingProblem, Phase,
-    Threading,
+    report_loading_problem, ExecutionMode, ExpectMetadata, LoadConfig, LoadResult, LoadStart,
+    LoadingProblem, Phase, Threading,
+};
+pub use roc_load_internal::incremental::{
+    Incremental, IncrementalCache, ReuseStats, SharedIncrementalCache,
 };
 pub use roc_load_internal::module::{
     CheckedModule, EntryPoint, Expectations, ExposedToHost, LoadedModule, MonomorphizedModule,
 };
+pub use roc_load_internal::module_graph::{DepsFormat, ModuleGraph, ModuleNode};
 pub use roc_solve::FunctionKind;
 
 #[allow(clippy::too_many_arguments)]
@@ -126,10 +130,48 @@ pub fn load_and_monomorphize<'a>(
     opt_main_path: Option<PathBuf>,
     roc_cache_dir: RocCacheDir<'_>,
     load_config: LoadConfig,
+) -> Result<MonomorphizedModule<'a>, LoadMonomorphizedError<'a>> {
+    load_and_monomorphize_help(
+        arena,
+        filename,
+        opt_main_path,
+        roc_cache_dir,
+        load_config,
+        None,
+    )
+}
+
+/// Like [load_and_monomorphize], but only solving the modules that changed since an earlier
+/// load of the same program, or that import a module whose interface did.
+pub fn load_and_monomorphize_incremental<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    roc_cache_dir: RocCacheDir<'_>,
+    load_config: LoadConfig,
+    incremental: Incremental<'a>,
+) -> Result<MonomorphizedModule<'a>, LoadMonomorphizedError<'a>> {
+    load_and_monomorphize_help(
+        arena,
+        filename,
+        opt_main_path,
+        roc_cache_dir,
+        load_config,
+        Some(incremental),
+    )
+}
+
+fn load_and_monomorphize_help<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    roc_cache_dir: RocCacheDir<'_>,
+    load_config: LoadConfig,
+    incremental: Option<Incremental<'a>>,
 ) -> Result<MonomorphizedModule<'a>, LoadMonomorphizedError<'a>> {
     use LoadResult::*;
 
-    let load_start = LoadStart::from_path(
+    let mut load_start = LoadStart::from_path(
         arena,
         filename,
         opt_main_path,
@@ -138,6 +180,10 @@ pub fn load_and_monomorphize<'a>(
         load_config.palette,
     )?;
 
+    if let Some(incremental) = incremental {
+        load_start = load_start.with_incremental(incremental);
+    }
+
     let exposed_types = ExposedByModule::default();
 
     match load(arena, load_start, exposed_types, roc_cache_dir, load_config)? {
@@ -152,10 +198,48 @@ pub fn load_and_typecheck<'a>(
     opt_main_path: Option<PathBuf>,
     roc_cache_dir: RocCacheDir<'_>,
     load_config: LoadConfig,
+) -> Result<LoadedModule, LoadingProblem<'a>> {
+    load_and_typecheck_help(
+        arena,
+        filename,
+        opt_main_path,
+        roc_cache_dir,
+        load_config,
+        None,
+    )
+}
+
+/// Like [load_and_typecheck], but only solving the modules that changed since an earlier
+/// load of the same program, or that import a module whose interface did.
+pub fn load_and_typecheck_incremental<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    roc_cache_dir: RocCacheDir<'_>,
+    load_config: LoadConfig,
+    incremental: Incremental<'a>,
+) -> Result<LoadedModule, LoadingProblem<'a>> {
+    load_and_typecheck_help(
+        arena,
+        filename,
+        opt_main_path,
+        roc_cache_dir,
+        load_config,
+        Some(incremental),
+    )
+}
+
+fn load_and_typecheck_help<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    roc_cache_dir: RocCacheDir<'_>,
+    load_config: LoadConfig,
+    incremental: Option<Incremental<'a>>,
 ) -> Result<LoadedModule, LoadingProblem<'a>> {
     use LoadResult::*;
 
-    let load_start = LoadStart::from_path(
+    let mut load_start = LoadStart::from_path(
         arena,
         filename,
         opt_main_path,
@@ -164,6 +248,10 @@ pub fn load_and_typecheck<'a>(
         load_config.palette,
     )?;
 
+    if let Some(incremental) = incremental {
+        load_start = load_start.with_incremental(incremental);
+    }
+
     let exposed_types = ExposedByModule::default();
 
     match load(arena, load_start, exposed_types, roc_cache_dir, load_config)? {
@@ -22,14 +22,15 @@ mod test_reporting {
     use roc_problem::Severity;
     use roc_region::all::LineInfo;
     use roc_reporting::report::{
-        can_problem, parse_problem, type_problem, RenderTarget, Report, ANSI_STYLE_CODES,
-        DEFAULT_PALETTE,
+        can_problem, parse_problem, type_problem, RenderTarget, Report, ReportTitle,
+        ANSI_STYLE_CODES, DEFAULT_PALETTE,
     };
     use roc_reporting::report::{RocDocAllocator, RocDocBuilder};
     use roc_solve::FunctionKind;
     use roc_solve_problem::TypeError;
     use roc_test_utils_dir::TmpDir;
     use roc_types::subs::Subs;
+    use std::collections::HashSet;
     use std::path::PathBuf;
 
     fn filename_from_string(str: &str) -> PathBuf {
@@ -41,7 +42,7 @@ mod test_reporting {
 
     fn to_simple_report(doc: RocDocBuilder) -> Report {
         Report {
-            title: "".to_string(),
+            title: ReportTitle::Custom(String::new()),
             doc,
             filename: filename_from_string(r"/code/proj/Main.roc"),
             severity: Severity::RuntimeError,
@@ -480,7 +481,7 @@ mod test_reporting {
         "
         ),
         @r"
-    ── NOT EXPOSED in /code/proj/Main.roc ──────────────────────────────────────────
+    ── NOT EXPOSED [E119] in /code/proj/Main.roc ───────────────────────────────────
 
     The List module does not expose `isempty`:
 
@@ -507,7 +508,7 @@ mod test_reporting {
         "
         ),
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `y` is not used anywhere in your code.
 
@@ -532,7 +533,7 @@ mod test_reporting {
        "
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
     The `i` name is first defined here:
 
@@ -564,7 +565,7 @@ mod test_reporting {
        "
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
     The `Booly` name is first defined here:
 
@@ -596,7 +597,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     Using != and == together requires parentheses, to clarify how they
     should be grouped.
@@ -625,7 +626,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-        ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+        ── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
         Nothing is named `bar` in this scope.
 
@@ -649,7 +650,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
     Nothing is named `true` in this scope.
 
@@ -681,7 +682,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     Using more than one == like this requires parentheses, to clarify how
     things should be grouped.
@@ -707,7 +708,7 @@ mod test_reporting {
          "#
         ),
         @r#"
-    ── UNUSED ARGUMENT in /code/proj/Main.roc ──────────────────────────────────────
+    ── UNUSED ARGUMENT [E111] in /code/proj/Main.roc ───────────────────────────────
 
     `box` doesn't use `htmlChildren`.
 
@@ -720,7 +721,7 @@ mod test_reporting {
     at the start of a variable name is a way of saying that the variable
     is not used.
 
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `y` is not used anywhere in your code.
 
@@ -804,7 +805,7 @@ mod test_reporting {
             ),
             indoc!(
                 r"
-                <cyan>── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────<reset>
+                <cyan>── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────<reset>
 
                 Nothing is named `theAdmin` in this scope.
 
@@ -830,7 +831,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `if` condition needs to be a Bool:
 
@@ -856,7 +857,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `if` guard condition needs to be a Bool:
 
@@ -881,7 +882,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `if` has an `else` branch with a different type from its `then` branch:
 
@@ -908,7 +909,7 @@ mod test_reporting {
              "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 3rd branch of this `if` does not match all the previous branches:
 
@@ -938,7 +939,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 2nd branch of this `when` does not match all the previous branches:
 
@@ -976,7 +977,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -1020,7 +1021,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This list contains elements with different types:
 
@@ -1047,7 +1048,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This list contains elements with different types:
 
@@ -1080,7 +1081,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     I cannot update the `.foo` field like this:
 
@@ -1110,7 +1111,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1135,7 +1136,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1161,7 +1162,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1174,7 +1175,7 @@ mod test_reporting {
 
         List ∞ -> *
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
 
@@ -1201,7 +1202,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -1237,7 +1238,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -1271,7 +1272,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1297,7 +1298,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1335,7 +1336,7 @@ mod test_reporting {
         // against that extra variable, rather than possibly having to translate a `Type`
         // again.
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1348,7 +1349,7 @@ mod test_reporting {
 
         List ∞ -> List *
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
 
@@ -1375,7 +1376,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `f`:
 
@@ -1388,7 +1389,7 @@ mod test_reporting {
 
         List ∞ -> List *
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
 
@@ -1416,7 +1417,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `f` has an unexpected type:
 
@@ -1450,7 +1451,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `f` has an unexpected type:
 
@@ -1487,7 +1488,7 @@ mod test_reporting {
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `f` has an unexpected type:
 
@@ -1524,7 +1525,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the `then` branch of this `if` expression:
 
@@ -1558,7 +1559,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -1591,7 +1592,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -1623,7 +1624,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [E004] in /code/proj/Main.roc ─────────────────────────────────
 
     The `x` value is not a function, but it was given 1 argument:
 
@@ -1645,7 +1646,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [E004] in /code/proj/Main.roc ─────────────────────────────────
 
     The `f` function expects 1 argument, but it got 2 instead:
 
@@ -1667,7 +1668,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO FEW ARGS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── TOO FEW ARGS [E005] in /code/proj/Main.roc ──────────────────────────────────
 
     The `f` function expects 2 arguments, but it got only 1:
 
@@ -1688,7 +1689,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -1717,7 +1718,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 2nd pattern in this `when` does not match the previous ones:
 
@@ -1743,7 +1744,7 @@ mod test_reporting {
              "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -1771,7 +1772,7 @@ mod test_reporting {
              "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -1800,7 +1801,7 @@ mod test_reporting {
              "
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
     Nothing is named `foo` in this scope.
 
@@ -1856,7 +1857,7 @@ mod test_reporting {
         ),
         // Just putting this here. We should probably handle or-patterns better
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 2nd pattern in this branch does not match the previous ones:
 
@@ -1884,7 +1885,7 @@ mod test_reporting {
         ),
         // Maybe this should specifically say the pattern doesn't work?
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -1912,7 +1913,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of this definition:
 
@@ -1943,7 +1944,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This integer pattern is malformed:
 
@@ -1964,7 +1965,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This float pattern is malformed:
 
@@ -1985,7 +1986,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This hex integer pattern is malformed:
 
@@ -2006,7 +2007,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This octal integer pattern is malformed:
 
@@ -2027,7 +2028,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This binary integer pattern is malformed:
 
@@ -2049,7 +2050,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -2099,7 +2100,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the `else` branch of this `if` expression:
 
@@ -2133,7 +2134,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2168,7 +2169,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2204,7 +2205,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
     Nothing is named `ok` in this scope.
 
@@ -2235,7 +2236,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `ok` is not used anywhere in your code.
 
@@ -2245,7 +2246,7 @@ mod test_reporting {
     If you didn't intend on using `ok` then remove it so future readers of
     your code don't wonder why it is there.
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2277,7 +2278,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+    ── CIRCULAR DEFINITION [E117] in /code/proj/Main.roc ───────────────────────────
 
     `f` is defined directly in terms of itself:
 
@@ -2304,7 +2305,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+    ── CIRCULAR DEFINITION [E117] in /code/proj/Main.roc ───────────────────────────
 
     The `foo` definition is causing a very tricky infinite loop:
 
@@ -2332,7 +2333,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `x` record doesn’t have a `foo` field:
 
@@ -2354,7 +2355,7 @@ mod test_reporting {
         ),
         // TODO also suggest fields with the correct type
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `x` record doesn’t have a `foo` field:
 
@@ -2387,7 +2388,7 @@ mod test_reporting {
         ),
         // TODO also suggest fields with the correct type
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `r` record doesn’t have a `foo` field:
 
@@ -2415,7 +2416,7 @@ mod test_reporting {
         ),
         // TODO also suggest fields with the correct type
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `x` record doesn’t have a `foo` field:
 
@@ -2445,7 +2446,7 @@ mod test_reporting {
         ),
         // TODO also suggest fields with the correct type
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -2470,7 +2471,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -2498,7 +2499,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -2526,7 +2527,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2560,7 +2561,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2603,7 +2604,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This pattern does not cover all the possibilities:
 
@@ -2618,7 +2619,7 @@ mod test_reporting {
     matching in function arguments, put a `when` in the function body to
     account for all possibilities.
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -2655,7 +2656,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -2686,7 +2687,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2713,7 +2714,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2741,7 +2742,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2770,7 +2771,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2799,7 +2800,7 @@ mod test_reporting {
         ),
         // Tip: Looks like a record field guard is not exhaustive. Learn more about record pattern matches at TODO.
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2828,7 +2829,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2854,7 +2855,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -2881,7 +2882,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [E007] in /code/proj/Main.roc ─────────────────────────────
 
     The 2nd pattern is redundant:
 
@@ -2909,7 +2910,7 @@ mod test_reporting {
         ),
         // de-aliases the alias to give a better error message
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `f` has an unexpected type:
 
@@ -2947,7 +2948,7 @@ mod test_reporting {
         ),
         // should not report Bar as unused!
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `Foo` alias is self-recursive in an invalid way:
 
@@ -2973,7 +2974,7 @@ mod test_reporting {
         ),
         // should not report Bar as unused!
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `Foo` alias is self-recursive in an invalid way:
 
@@ -2993,7 +2994,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [E114] in /code/proj/Main.roc ──────────────────────────
 
     This record defines the `.x` field twice!
 
@@ -3017,7 +3018,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [E114] in /code/proj/Main.roc ──────────────────────────
 
     This record defines the `.x` field twice!
 
@@ -3045,7 +3046,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [E114] in /code/proj/Main.roc ──────────────────────────
 
     This record defines the `.x` field twice!
 
@@ -3080,7 +3081,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [E114] in /code/proj/Main.roc ──────────────────────────
 
     This record defines the `.x` field twice!
 
@@ -3113,7 +3114,7 @@ mod test_reporting {
             "#
         ),
         @r"
-    ── DUPLICATE FIELD NAME in /code/proj/Main.roc ─────────────────────────────────
+    ── DUPLICATE FIELD NAME [E114] in /code/proj/Main.roc ──────────────────────────
 
     This record type defines the `.foo` field twice!
 
@@ -3141,7 +3142,7 @@ mod test_reporting {
             "#
         ),
         @r"
-    ── DUPLICATE TAG NAME in /code/proj/Main.roc ───────────────────────────────────
+    ── DUPLICATE TAG NAME [E115] in /code/proj/Main.roc ────────────────────────────
 
     This tag union type defines the `Foo` tag twice!
 
@@ -3170,7 +3171,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── NAMING PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── NAMING PROBLEM [E102] in /code/proj/Main.roc ────────────────────────────────
 
     This annotation does not match the definition immediately following
     it:
@@ -3206,7 +3207,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This definition of `MyAlias` has an unexpected pattern:
 
@@ -3215,7 +3216,7 @@ mod test_reporting {
 
     Only type variables like `a` or `value` can occur in this position.
 
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `MyAlias` is not used anywhere in your code.
 
@@ -3238,7 +3239,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This definition of `Age` has an unexpected pattern:
 
@@ -3260,7 +3261,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY TYPE ARGUMENTS in /code/proj/Main.roc ──────────────────────────────
+    ── TOO MANY TYPE ARGUMENTS [E155] in /code/proj/Main.roc ───────────────────────
 
     The `Num` opaque expects 1 type argument, but it got 2 instead:
 
@@ -3282,7 +3283,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY TYPE ARGUMENTS in /code/proj/Main.roc ──────────────────────────────
+    ── TOO MANY TYPE ARGUMENTS [E155] in /code/proj/Main.roc ───────────────────────
 
     The `Num` opaque expects 1 type argument, but it got 2 instead:
 
@@ -3306,7 +3307,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO FEW TYPE ARGUMENTS in /code/proj/Main.roc ───────────────────────────────
+    ── TOO FEW TYPE ARGUMENTS [E156] in /code/proj/Main.roc ────────────────────────
 
     The `Pair` alias expects 2 type arguments, but it got 1 instead:
 
@@ -3330,7 +3331,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TOO MANY TYPE ARGUMENTS in /code/proj/Main.roc ──────────────────────────────
+    ── TOO MANY TYPE ARGUMENTS [E155] in /code/proj/Main.roc ───────────────────────
 
     The `Pair` alias expects 2 type arguments, but it got 3 instead:
 
@@ -3353,7 +3354,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── UNUSED TYPE ALIAS PARAMETER in /code/proj/Main.roc ──────────────────────────
+    ── UNUSED TYPE ALIAS PARAMETER [E107] in /code/proj/Main.roc ───────────────────
 
     The `a` type parameter is not used in the `Foo` alias definition:
 
@@ -3375,7 +3376,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── ARGUMENTS BEFORE EQUALS in tmp/elm_function_syntax/Test.roc ─────────────────
+    ── ARGUMENTS BEFORE EQUALS [E204] in tmp/elm_function_syntax/Test.roc ──────────
 
     I am partway through parsing a definition, but I got stuck here:
 
@@ -3403,7 +3404,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -3452,7 +3453,7 @@ mod test_reporting {
         // TODO render tag unions across multiple lines
         // TODO do not show recursion var if the recursion var does not render on the surface of a type
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -3506,7 +3507,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This integer literal is too big:
 
@@ -3518,7 +3519,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This integer literal is too small:
 
@@ -3530,7 +3531,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This integer literal is too big:
 
@@ -3542,7 +3543,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This integer literal is too small:
 
@@ -3554,7 +3555,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -3583,7 +3584,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This float literal is too big:
 
@@ -3595,7 +3596,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This float literal is too small:
 
@@ -3628,7 +3629,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This integer literal contains an invalid digit:
 
@@ -3640,7 +3641,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This hex integer literal contains an invalid digit:
 
@@ -3652,7 +3653,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This octal integer literal contains an invalid digit:
 
@@ -3664,7 +3665,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This binary integer literal contains an invalid digit:
 
@@ -3694,7 +3695,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This hex integer literal contains no digits:
 
@@ -3706,7 +3707,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This octal integer literal contains no digits:
 
@@ -3718,7 +3719,7 @@ mod test_reporting {
 
     Tip: Learn more about number literals at TODO
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This binary integer literal contains no digits:
 
@@ -3742,7 +3743,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This float literal contains an invalid digit:
 
@@ -3773,7 +3774,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This expression cannot be updated:
 
@@ -3782,7 +3783,7 @@ mod test_reporting {
 
     Only variables can be updated with record update syntax.
 
-    ── MODULE NOT IMPORTED in /code/proj/Main.roc ──────────────────────────────────
+    ── MODULE NOT IMPORTED [E120] in /code/proj/Main.roc ───────────────────────────
 
     The `Test` module is not imported:
 
@@ -3797,7 +3798,7 @@ mod test_reporting {
         Dict
         Hash
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This expression cannot be updated:
 
@@ -3816,7 +3817,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── MODULE NOT IMPORTED in /code/proj/Main.roc ──────────────────────────────────
+    ── MODULE NOT IMPORTED [E120] in /code/proj/Main.roc ───────────────────────────
 
     The `Foo` module is not imported:
 
@@ -3841,7 +3842,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to + has an unexpected type:
 
@@ -3869,7 +3870,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 1st argument to `f` is weird:
 
@@ -3898,7 +3899,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of this definition:
 
@@ -3929,7 +3930,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 1st argument to `f` is weird:
 
@@ -3962,7 +3963,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -3995,7 +3996,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -4026,7 +4027,7 @@ mod test_reporting {
                 "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to this function has an unexpected type:
 
@@ -4060,7 +4061,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -4094,7 +4095,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -4122,7 +4123,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── BAD OPTIONAL VALUE in /code/proj/Main.roc ───────────────────────────────────
+    ── BAD OPTIONAL VALUE [E159] in /code/proj/Main.roc ────────────────────────────
 
     This record uses an optional value for the `.y` field in an incorrect
     context!
@@ -4159,7 +4160,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [E007] in /code/proj/Main.roc ─────────────────────────────
 
     The 3rd pattern is redundant:
 
@@ -4204,7 +4205,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── UNUSED ARGUMENT in /code/proj/Main.roc ──────────────────────────────────────
+    ── UNUSED ARGUMENT [E111] in /code/proj/Main.roc ───────────────────────────────
 
     `f` doesn't use `foo`.
 
@@ -4226,7 +4227,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am trying to parse a qualified name here:
 
@@ -4247,7 +4248,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am trying to parse a qualified name here:
 
@@ -4267,7 +4268,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am trying to parse a record field access here:
 
@@ -4289,7 +4290,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/type_annotation_double_colon/Test.roc ───────────────
+    ── UNKNOWN OPERATOR [E205] in tmp/type_annotation_double_colon/Test.roc ────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -4321,7 +4322,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── STATEMENT AFTER EXPRESSION in tmp/double_equals_in_def/Test.roc ─────────────
+    ── STATEMENT AFTER EXPRESSION [E215] in tmp/double_equals_in_def/Test.roc ──────
 
     I just finished parsing an expression with a series of definitions,
 
@@ -4350,7 +4351,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TAG UNION TYPE in tmp/tag_union_open/Test.roc ────────────────────
+    ── UNFINISHED TAG UNION TYPE [E255] in tmp/tag_union_open/Test.roc ─────────────
 
     I am partway through parsing a tag union type, but I got stuck here:
 
@@ -4372,7 +4373,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TAG UNION TYPE in tmp/tag_union_end/Test.roc ─────────────────────
+    ── UNFINISHED TAG UNION TYPE [E255] in tmp/tag_union_end/Test.roc ──────────────
 
     I am partway through parsing a tag union type, but I got stuck here:
 
@@ -4394,7 +4395,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── WEIRD TAG NAME in tmp/tag_union_lowercase_tag_name/Test.roc ─────────────────
+    ── WEIRD TAG NAME [E256] in tmp/tag_union_lowercase_tag_name/Test.roc ──────────
 
     I am partway through parsing a tag union type, but I got stuck here:
 
@@ -4415,7 +4416,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── WEIRD TAG NAME in tmp/tag_union_second_lowercase_tag_name/Test.roc ──────────
+    ── WEIRD TAG NAME [E256] in tmp/tag_union_second_lowercase_tag_name/Test.roc ───
 
     I am partway through parsing a tag union type, but I got stuck here:
 
@@ -4436,7 +4437,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_open/Test.roc ─────────────────────
+    ── UNFINISHED RECORD TYPE [E252] in tmp/record_type_open/Test.roc ──────────────
 
     I am partway through parsing a record type, but I got stuck here:
 
@@ -4459,7 +4460,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_open_indent/Test.roc ──────────────
+    ── UNFINISHED RECORD TYPE [E252] in tmp/record_type_open_indent/Test.roc ───────
 
     I am partway through parsing a record type, but I got stuck here:
 
@@ -4482,7 +4483,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_end/Test.roc ──────────────────────
+    ── UNFINISHED RECORD TYPE [E252] in tmp/record_type_end/Test.roc ───────────────
 
     I am partway through parsing a record type, but I got stuck here:
 
@@ -4504,7 +4505,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_keyword_field_name/Test.roc ───────
+    ── UNFINISHED RECORD TYPE [E252] in ...record_type_keyword_field_name/Test.roc ─
 
     I just started parsing a record type, but I got stuck on this field
     name:
@@ -4526,7 +4527,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED RECORD TYPE in tmp/record_type_missing_comma/Test.roc ────────────
+    ── UNFINISHED RECORD TYPE [E252] in tmp/record_type_missing_comma/Test.roc ─────
 
     I am partway through parsing a record type, but I got stuck here:
 
@@ -4543,7 +4544,7 @@ mod test_reporting {
         record_type_tab,
         "f : { foo \t }",
         @r###"
-    ── TAB CHARACTER in tmp/record_type_tab/Test.roc ───────────────────────────────
+    ── TAB CHARACTER [E281] in tmp/record_type_tab/Test.roc ────────────────────────
 
     I encountered a tab character:
 
@@ -4558,7 +4559,7 @@ mod test_reporting {
         comment_with_tab,
         "# comment with a \t char\n4",
         @r###"
-    ── TAB CHARACTER in tmp/comment_with_tab/Test.roc ──────────────────────────────
+    ── TAB CHARACTER [E281] in tmp/comment_with_tab/Test.roc ───────────────────────
 
     I encountered a tab character:
 
@@ -4573,7 +4574,7 @@ mod test_reporting {
         comment_with_control_character,
         "# comment with a \x07 char\n",
         @r###"
-    ── ASCII CONTROL CHARACTER in tmp/comment_with_control_character/Test.roc ──────
+    ── ASCII CONTROL CHARACTER [E282] in ...omment_with_control_character/Test.roc ─
 
     I encountered an ASCII control character:
 
@@ -4588,7 +4589,7 @@ mod test_reporting {
         record_type_carriage_return,
         "f : { \r foo }",
         @r"
-    ── MISPLACED CARRIAGE RETURN in tmp/record_type_carriage_return/Test.roc ───────
+    ── MISPLACED CARRIAGE RETURN [E283] in ...record_type_carriage_return/Test.roc ─
 
     I encountered a stray carriage return (\r):
 
@@ -4608,7 +4609,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/type_in_parens_start/Test.roc ─────────────────
+    ── UNFINISHED PARENTHESES [E230] in tmp/type_in_parens_start/Test.roc ──────────
 
     I am partway through parsing a type in parentheses, but I got stuck
     here:
@@ -4631,7 +4632,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/type_in_parens_end/Test.roc ───────────────────
+    ── UNFINISHED PARENTHESES [E230] in tmp/type_in_parens_end/Test.roc ────────────
 
     I am partway through parsing a type in parentheses, but I got stuck
     here:
@@ -4656,7 +4657,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am confused by this type name:
 
@@ -4667,7 +4668,7 @@ mod test_reporting {
     qualified by a module name, like Bool or Http.Request.Request.
     "
     );
-    //                ── DOUBLE DOT ──────────────────────────────────────────────────────────────────
+    //                ── DOUBLE DOT [E257] ───────────────────────────────────────────────────────────
     //
     //                I encountered two dots in a row:
     //
@@ -4686,7 +4687,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am confused by this type name:
 
@@ -4697,7 +4698,7 @@ mod test_reporting {
     qualified by a module name, like Bool or Http.Request.Request.
     "
     );
-    //                ── TRAILING DOT ────────────────────────────────────────────────────────────────
+    //                ── TRAILING DOT [E258] ─────────────────────────────────────────────────────────
     //
     //                I encountered a dot with nothing after it:
     //
@@ -4715,7 +4716,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TYPE in tmp/type_apply_stray_dot/Test.roc ────────────────────────
+    ── UNFINISHED TYPE [E249] in tmp/type_apply_stray_dot/Test.roc ─────────────────
 
     I just started parsing a type, but I got stuck here:
 
@@ -4736,7 +4737,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am confused by this type name:
 
@@ -4747,7 +4748,7 @@ mod test_reporting {
     qualified by a module name, like Bool or Http.Request.Request.
     "
     );
-    //                ── WEIRD QUALIFIED NAME ────────────────────────────────────────────────────────
+    //                ── WEIRD QUALIFIED NAME [E259] ─────────────────────────────────────────────────
     //
     //                I encountered a number at the start of a qualified name segment:
     //
@@ -4767,7 +4768,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am confused by this type name:
 
@@ -4792,7 +4793,7 @@ mod test_reporting {
     //         "
     //     ),
     //     @r#"
-    // ── MISSING FINAL EXPRESSION in tmp/def_missing_final_expression/Test.roc ───────
+    // ── MISSING FINAL EXPRESSION [E208] in ...def_missing_final_expression/Test.roc ─
 
     // I am partway through parsing a definition, but I got stuck here:
 
@@ -4820,7 +4821,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── INDENT ENDS AFTER EXPRESSION in tmp/expression_indentation_end/Test.roc ─────
+    ── INDENT ENDS AFTER EXPRESSION [E212] in ...pression_indentation_end/Test.roc ─
 
     I am partway through parsing an expression, but I got stuck here:
 
@@ -4846,7 +4847,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED INLINE ALIAS in tmp/type_inline_alias/Test.roc ───────────────────
+    ── UNFINISHED INLINE ALIAS [E250] in tmp/type_inline_alias/Test.roc ────────────
 
     I just started parsing an inline type alias, but I got stuck here:
 
@@ -4868,7 +4869,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── DOUBLE COMMA in tmp/type_double_comma/Test.roc ──────────────────────────────
+    ── DOUBLE COMMA [E248] in tmp/type_double_comma/Test.roc ───────────────────────
 
     I just started parsing a function argument type, but I encountered two
     commas in a row:
@@ -4891,7 +4892,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TYPE in tmp/type_argument_no_arrow/Test.roc ──────────────────────
+    ── UNFINISHED TYPE [E249] in tmp/type_argument_no_arrow/Test.roc ───────────────
 
     I am partway through parsing a type, but I got stuck here:
 
@@ -4914,7 +4915,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED TYPE in tmp/type_argument_arrow_then_nothing/Test.roc ────────────
+    ── UNFINISHED TYPE [E249] in tmp/type_argument_arrow_then_nothing/Test.roc ─────
 
     I just started parsing a type, but I got stuck here:
 
@@ -4933,7 +4934,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── UNFINISHED IMPORT in tmp/unfinished_import/Test.roc ─────────────────────────
+    ── UNFINISHED IMPORT [E237] in tmp/unfinished_import/Test.roc ──────────────────
 
     I was partway through parsing an `import`, but I got stuck here:
 
@@ -4961,7 +4962,7 @@ mod test_reporting {
             import Menu { x = 4 }
             "
         ),@r###"
-    ── RECORD PARSE PROBLEM in tmp/weird_import_params_record/Test.roc ─────────────
+    ── RECORD PARSE PROBLEM [E216] in tmp/weird_import_params_record/Test.roc ──────
 
     I am partway through parsing a record, but I got stuck here:
 
@@ -4982,7 +4983,7 @@ mod test_reporting {
             }
             "
         ),@r###"
-    ── OLD-STYLE RECORD BUILDER IN MODULE PARAMS in ...r_in_module_params/Test.roc ─
+    ── OLD-STYLE RECORD BUILDER IN MODULE PARAMS [E232] in ...dule_params/Test.roc ─
 
     I was partway through parsing module params, but I got stuck here:
 
@@ -5003,7 +5004,7 @@ mod test_reporting {
             import Menu { myParams & echo: echoFn }
             "
         ),@r###"
-    ── RECORD UPDATE IN MODULE PARAMS in ...ecord_update_in_module_params/Test.roc ─
+    ── RECORD UPDATE IN MODULE PARAMS [E233] in ...pdate_in_module_params/Test.roc ─
 
     I was partway through parsing module params, but I got stuck here:
 
@@ -5023,7 +5024,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── UNFINISHED IMPORT in tmp/unfinished_import_as_or_exposing/Test.roc ──────────
+    ── UNFINISHED IMPORT [E237] in tmp/unfinished_import_as_or_exposing/Test.roc ───
 
     I was partway through parsing an `import`, but I got stuck here:
 
@@ -5052,7 +5053,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── UNFINISHED IMPORT in tmp/unfinished_import_alias/Test.roc ───────────────────
+    ── UNFINISHED IMPORT [E237] in tmp/unfinished_import_alias/Test.roc ────────────
 
     I was partway through parsing an `import`, but I got stuck here:
 
@@ -5071,7 +5072,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── LOWERCASE ALIAS in tmp/lowercase_import_alias/Test.roc ──────────────────────
+    ── LOWERCASE ALIAS [E235] in tmp/lowercase_import_alias/Test.roc ───────────────
 
     This import is using a lowercase alias:
 
@@ -5090,7 +5091,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── UNFINISHED IMPORT in tmp/unfinished_import_exposing/Test.roc ────────────────
+    ── UNFINISHED IMPORT [E237] in tmp/unfinished_import_exposing/Test.roc ─────────
 
     I was partway through parsing an `import`, but I got stuck here:
 
@@ -5108,7 +5109,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── WEIRD EXPOSING in tmp/unfinished_import_exposing_name/Test.roc ──────────────
+    ── WEIRD EXPOSING [E236] in tmp/unfinished_import_exposing_name/Test.roc ───────
 
     I'm partway through parsing an exposing list, but I got stuck here:
 
@@ -5128,7 +5129,7 @@ mod test_reporting {
             "#
         ),
         @r###"
-    ── UNFINISHED IMPORT in tmp/unfinished_ingested_file_name/Test.roc ─────────────
+    ── UNFINISHED IMPORT [E237] in tmp/unfinished_ingested_file_name/Test.roc ──────
 
     I was partway through parsing an `import`, but I got stuck here:
 
@@ -5149,7 +5150,7 @@ mod test_reporting {
             "#
         ),
         @r###"
-    ── UNFINISHED TYPE in tmp/ingested_file_import_ann_syntax_err/Test.roc ─────────
+    ── UNFINISHED TYPE [E249] in tmp/ingested_file_import_ann_syntax_err/Test.roc ──
 
     I am partway through parsing a type, but I got stuck here:
 
@@ -5174,7 +5175,7 @@ mod test_reporting {
             "#
         ),
         @r###"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `myDict` definition:
 
@@ -5209,7 +5210,7 @@ mod test_reporting {
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `myDict` definition:
 
@@ -5241,7 +5242,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── IF GUARD NO CONDITION in tmp/if_guard_without_condition/Test.roc ────────────
+    ── IF GUARD NO CONDITION [E239] in tmp/if_guard_without_condition/Test.roc ─────
 
     I just started parsing an if guard, but there is no guard condition:
 
@@ -5266,7 +5267,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED PATTERN in tmp/empty_or_pattern/Test.roc ─────────────────────────
+    ── UNFINISHED PATTERN [E242] in tmp/empty_or_pattern/Test.roc ──────────────────
 
     I just started parsing a pattern, but I got stuck here:
 
@@ -5291,7 +5292,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── MISSING ARROW in tmp/pattern_binds_keyword/Test.roc ─────────────────────────
+    ── MISSING ARROW [E218] in tmp/pattern_binds_keyword/Test.roc ──────────────────
 
     I am partway through parsing a `when` expression, but got stuck here:
 
@@ -5327,7 +5328,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED WHEN in tmp/when_missing_arrow/Test.roc ──────────────────────────
+    ── UNFINISHED WHEN [E240] in tmp/when_missing_arrow/Test.roc ───────────────────
 
     I was partway through parsing a `when` expression, but I got stuck here:
 
@@ -5360,7 +5361,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED ARGUMENT LIST in tmp/lambda_double_comma/Test.roc ────────────────
+    ── UNFINISHED ARGUMENT LIST [E219] in tmp/lambda_double_comma/Test.roc ─────────
 
     I am partway through parsing a function argument list, but I got stuck
     at this comma:
@@ -5381,7 +5382,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED ARGUMENT LIST in tmp/lambda_leading_comma/Test.roc ───────────────
+    ── UNFINISHED ARGUMENT LIST [E219] in tmp/lambda_leading_comma/Test.roc ────────
 
     I am partway through parsing a function argument list, but I got stuck
     at this comma:
@@ -5397,7 +5398,7 @@ mod test_reporting {
     // this should get better with time
     // TODO this formerly gave
     //
-    //                ── UNFINISHED WHEN ─────────────────────────────────────────────────────────────
+    //                ── UNFINISHED WHEN [E240] ──────────────────────────────────────────────────────
     //
     //                I was partway through parsing a `when` expression, but I got stuck here:
     //
@@ -5428,7 +5429,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── UNKNOWN OPERATOR in tmp/when_outdented_branch/Test.roc ──────────────────────
+    ── UNKNOWN OPERATOR [E205] in tmp/when_outdented_branch/Test.roc ───────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -5458,7 +5459,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── UNEXPECTED ARROW in tmp/when_over_indented_underscore/Test.roc ──────────────
+    ── UNEXPECTED ARROW [E241] in tmp/when_over_indented_underscore/Test.roc ───────
 
     I am parsing a `when` expression right now, but this arrow is confusing
     me:
@@ -5496,7 +5497,7 @@ mod test_reporting {
             "
         ),
         @r###"
-    ── UNEXPECTED ARROW in tmp/when_over_indented_int/Test.roc ─────────────────────
+    ── UNEXPECTED ARROW [E241] in tmp/when_over_indented_int/Test.roc ──────────────
 
     I am parsing a `when` expression right now, but this arrow is confusing
     me:
@@ -5537,7 +5538,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED IF in tmp/if_outdented_then/Test.roc ─────────────────────────────
+    ── UNFINISHED IF [E238] in tmp/if_outdented_then/Test.roc ──────────────────────
 
     I was partway through parsing an `if` expression, but I got stuck here:
 
@@ -5557,7 +5558,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED IF in tmp/if_missing_else/Test.roc ───────────────────────────────
+    ── UNFINISHED IF [E238] in tmp/if_missing_else/Test.roc ────────────────────────
 
     I was partway through parsing an `if` expression, but I got stuck here:
 
@@ -5576,7 +5577,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED LIST in tmp/list_double_comma/Test.roc ───────────────────────────
+    ── UNFINISHED LIST [E231] in tmp/list_double_comma/Test.roc ────────────────────
 
     I am partway through started parsing a list, but I got stuck here:
 
@@ -5596,7 +5597,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── UNFINISHED LIST in tmp/list_without_end/Test.roc ────────────────────────────
+    ── UNFINISHED LIST [E231] in tmp/list_without_end/Test.roc ─────────────────────
 
     I am partway through started parsing a list, but I got stuck here:
 
@@ -5622,7 +5623,7 @@ mod test_reporting {
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This float literal contains an invalid digit:
 
@@ -5640,7 +5641,7 @@ mod test_reporting {
         unicode_not_hex,
         r#""abc\u(zzzz)def""#,
         @r#"
-    ── WEIRD CODE POINT in tmp/unicode_not_hex/Test.roc ────────────────────────────
+    ── WEIRD CODE POINT [E222] in tmp/unicode_not_hex/Test.roc ─────────────────────
 
     I am partway through parsing a unicode code point, but I got stuck
     here:
@@ -5658,7 +5659,7 @@ mod test_reporting {
         unicode_too_large,
         r#""abc\u(110000)def""#,
         @r#"
-    ── INVALID UNICODE in /code/proj/Main.roc ──────────────────────────────────────
+    ── INVALID UNICODE [E116] in /code/proj/Main.roc ───────────────────────────────
 
     This unicode code point is invalid:
 
@@ -5673,7 +5674,7 @@ mod test_reporting {
         weird_escape,
         r#""abc\qdef""#,
         @r###"
-    ── WEIRD ESCAPE in tmp/weird_escape/Test.roc ───────────────────────────────────
+    ── WEIRD ESCAPE [E221] in tmp/weird_escape/Test.roc ────────────────────────────
 
     I was partway through parsing a  string literal, but I got stuck here:
 
@@ -5697,7 +5698,7 @@ mod test_reporting {
         single_quote_too_long,
         r"'abcdef'",
         @r#"
-    ── INVALID SCALAR in tmp/single_quote_too_long/Test.roc ────────────────────────
+    ── INVALID SCALAR [E225] in tmp/single_quote_too_long/Test.roc ─────────────────
 
     I am part way through parsing this scalar literal (character literal),
     but it's too long to fit in a U32 so it's not a valid scalar.
@@ -5714,7 +5715,7 @@ mod test_reporting {
         single_no_end,
         r#""there is no end"#,
         @r#"
-    ── ENDLESS STRING in tmp/single_no_end/Test.roc ────────────────────────────────
+    ── ENDLESS STRING [E226] in tmp/single_no_end/Test.roc ─────────────────────────
 
     I cannot find the end of this string:
 
@@ -5730,7 +5731,7 @@ mod test_reporting {
         multi_no_end,
         r#""""there is no end"#,
         @r#"
-    ── ENDLESS STRING in tmp/multi_no_end/Test.roc ─────────────────────────────────
+    ── ENDLESS STRING [E226] in tmp/multi_no_end/Test.roc ──────────────────────────
 
     I cannot find the end of this block string:
 
@@ -5746,7 +5747,7 @@ mod test_reporting {
         multi_insufficient_indent,
         "    \"\"\"\n  testing\n    \"\"\"", // 4 space indent on the start, 2 space on the `testing` line
         @r#"
-    ── INSUFFICIENT INDENT IN MULTI-LINE STRING in ...insufficient_indent/Test.roc ─
+    ── INSUFFICIENT INDENT IN MULTI-LINE STRING [E228] in ...cient_indent/Test.roc ─
 
     This multiline string is not sufficiently indented:
 
@@ -5768,7 +5769,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── INDENT ENDS AFTER EXPRESSION in tmp/dbg_without_final_expression/Test.roc ───
+    ── INDENT ENDS AFTER EXPRESSION [E212] in ...without_final_expression/Test.roc ─
 
     I am partway through parsing a dbg statement, but I got stuck here:
 
@@ -5790,7 +5791,7 @@ mod test_reporting {
             "
         ),
         @r#"
-    ── INDENT ENDS AFTER EXPRESSION in ...expect_without_final_expression/Test.roc ─
+    ── INDENT ENDS AFTER EXPRESSION [E212] in ...without_final_expression/Test.roc ─
 
     I am partway through parsing an expect statement, but I got stuck
     here:
@@ -5816,7 +5817,7 @@ mod test_reporting {
             "#,
             ),
             @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `if` has an `else` branch with a different type from its `then` branch:
 
@@ -5842,7 +5843,7 @@ mod test_reporting {
                 $name,
                 &format!(r#"if Bool.true then "abc" else 1 {} 2"#, $op),
                 |golden| assert_eq!(golden, format!(
-r#"── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+r#"── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
 This `if` has an `else` branch with a different type from its `then` branch:
 
@@ -5885,7 +5886,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `foo` record doesn’t have a `if` field:
 
@@ -5904,7 +5905,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── NOT EXPOSED in /code/proj/Main.roc ──────────────────────────────────────────
+    ── NOT EXPOSED [E119] in /code/proj/Main.roc ───────────────────────────────────
 
     The Num module does not expose `if`:
 
@@ -5928,7 +5929,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am trying to parse a record field access here:
 
@@ -5947,7 +5948,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am very confused by this field access:
 
@@ -5966,7 +5967,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am very confused by this field access
 
@@ -5987,7 +5988,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── NAMING PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── NAMING PROBLEM [E102] in /code/proj/Main.roc ────────────────────────────────
 
     I am trying to parse an identifier here:
 
@@ -6021,7 +6022,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/case_of/Test.roc ────────────────────────────────────
+    ── UNKNOWN OPERATOR [E205] in tmp/case_of/Test.roc ─────────────────────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -6054,7 +6055,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
     Nothing is named `bar` in this scope.
 
@@ -6079,7 +6080,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/invalid_operator/Test.roc ───────────────────────────
+    ── UNKNOWN OPERATOR [E205] in tmp/invalid_operator/Test.roc ────────────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -6104,7 +6105,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/double_plus/Test.roc ────────────────────────────────
+    ── UNKNOWN OPERATOR [E205] in tmp/double_plus/Test.roc ─────────────────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -6131,7 +6132,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r#"
-    ── UNKNOWN OPERATOR in tmp/inline_hastype/Test.roc ─────────────────────────────
+    ── UNKNOWN OPERATOR [E205] in tmp/inline_hastype/Test.roc ──────────────────────
 
     This looks like an operator, but it's not one I recognize!
 
@@ -6160,7 +6161,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── SYNTAX PROBLEM in tmp/wild_case_arrow/Test.roc ──────────────────────────────
+    ── SYNTAX PROBLEM [E201] in tmp/wild_case_arrow/Test.roc ───────────────────────
 
     I got stuck here:
 
@@ -6189,7 +6190,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r"
-                ── WEIRD PROVIDES in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD PROVIDES [E272] in /code/proj/Main.roc ────────────────────────────────
 
                 I am partway through parsing a provides list, but I got stuck here:
 
@@ -6224,7 +6225,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r"
-                ── WEIRD PROVIDES in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD PROVIDES [E272] in /code/proj/Main.roc ────────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6250,7 +6251,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r#"
-                ── WEIRD PROVIDES in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD PROVIDES [E272] in /code/proj/Main.roc ────────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6277,7 +6278,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r#"
-                ── WEIRD PROVIDES in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD PROVIDES [E272] in /code/proj/Main.roc ────────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6303,7 +6304,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r#"
-                ── WEIRD MODULE PARAMS in /code/proj/Main.roc ──────────────────────────────────
+                ── WEIRD MODULE PARAMS [E273] in /code/proj/Main.roc ───────────────────────────
 
                 I am partway through parsing a module header, but I got stuck here:
 
@@ -6339,7 +6340,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r#"
-                ── BAD REQUIRES in /code/proj/Main.roc ─────────────────────────────────────────
+                ── BAD REQUIRES [E278] in /code/proj/Main.roc ──────────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6367,7 +6368,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r"
-                ── WEIRD IMPORTS in /code/proj/Main.roc ────────────────────────────────────────
+                ── WEIRD IMPORTS [E275] in /code/proj/Main.roc ─────────────────────────────────
 
                 I am partway through parsing a header, but I got stuck here:
 
@@ -6392,7 +6393,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r"
-                ── WEIRD EXPOSES in /code/proj/Main.roc ────────────────────────────────────────
+                ── WEIRD EXPOSES [E274] in /code/proj/Main.roc ─────────────────────────────────
 
                 I am partway through parsing an `exposes` list, but I got stuck here:
 
@@ -6419,7 +6420,7 @@ All branches in an `if` must have the same type!
             ),
             indoc!(
                 r#"
-                ── WEIRD APP NAME in /code/proj/Main.roc ───────────────────────────────────────
+                ── WEIRD APP NAME [E267] in /code/proj/Main.roc ────────────────────────────────
 
                 I am partway through parsing a header, but got stuck here:
 
@@ -6443,7 +6444,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [E004] in /code/proj/Main.roc ─────────────────────────────────
 
     This value is not a function, but it was given 2 arguments:
 
@@ -6464,7 +6465,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [E004] in /code/proj/Main.roc ─────────────────────────────────
 
     This value is not a function, but it was given 2 arguments:
 
@@ -6486,7 +6487,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -6512,7 +6513,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/pattern_in_parens_open/Test.roc ───────────────
+    ── UNFINISHED PARENTHESES [E230] in tmp/pattern_in_parens_open/Test.roc ────────
 
     I am partway through parsing a pattern in parentheses, but I got stuck
     here:
@@ -6535,7 +6536,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/pattern_in_parens_end_comma/Test.roc ──────────
+    ── UNFINISHED PARENTHESES [E230] in tmp/pattern_in_parens_end_comma/Test.roc ───
 
     I am partway through parsing a pattern in parentheses, but I got stuck
     here:
@@ -6558,7 +6559,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/pattern_in_parens_end/Test.roc ────────────────
+    ── UNFINISHED PARENTHESES [E230] in tmp/pattern_in_parens_end/Test.roc ─────────
 
     I am partway through parsing a pattern in parentheses, but I got stuck
     here:
@@ -6582,7 +6583,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── UNFINISHED FUNCTION in tmp/unfinished_closure_pattern_in_parens/Test.roc ────
+    ── UNFINISHED FUNCTION [E220] in ...inished_closure_pattern_in_parens/Test.roc ─
 
     I was partway through parsing a  function, but I got stuck here:
 
@@ -6602,7 +6603,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── UNFINISHED PARENTHESES in tmp/pattern_in_parens_indent_open/Test.roc ────────
+    ── UNFINISHED PARENTHESES [E230] in tmp/pattern_in_parens_indent_open/Test.roc ─
 
     I am partway through parsing a pattern in parentheses, but I got stuck
     here:
@@ -6627,7 +6628,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `map` has an unexpected type:
 
@@ -6655,7 +6656,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `expect` condition needs to be a Bool:
 
@@ -6682,7 +6683,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to * has an unexpected type:
 
@@ -6697,7 +6698,7 @@ All branches in an `if` must have the same type!
 
         Num *
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `mult` definition:
 
@@ -6726,7 +6727,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to * has an unexpected type:
 
@@ -6741,7 +6742,7 @@ All branches in an `if` must have the same type!
 
         Num a
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `mult` definition:
 
@@ -6776,7 +6777,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
     This alias has the same name as a builtin:
 
@@ -6786,7 +6787,7 @@ All branches in an `if` must have the same type!
     All builtin aliases are in scope by default, so I need this alias to
     have a different name!
 
-    ── TOO FEW TYPE ARGUMENTS in /code/proj/Main.roc ───────────────────────────────
+    ── TOO FEW TYPE ARGUMENTS [E156] in /code/proj/Main.roc ────────────────────────
 
     The `Result` alias expects 2 type arguments, but it got 1 instead:
 
@@ -6814,7 +6815,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
     This alias has the same name as a builtin:
 
@@ -6824,7 +6825,7 @@ All branches in an `if` must have the same type!
     All builtin aliases are in scope by default, so I need this alias to
     have a different name!
 
-    ── TOO MANY TYPE ARGUMENTS in /code/proj/Main.roc ──────────────────────────────
+    ── TOO MANY TYPE ARGUMENTS [E155] in /code/proj/Main.roc ───────────────────────
 
     The `Result` alias expects 2 type arguments, but it got 3 instead:
 
@@ -6846,7 +6847,7 @@ All branches in an `if` must have the same type!
         ),
         // TODO: We should tell the user that we inferred `_` as `a`
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -6880,7 +6881,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -6912,7 +6913,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -6952,7 +6953,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `inner` definition:
 
@@ -6981,7 +6982,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── NOT AN INLINE ALIAS in tmp/error_inline_alias_not_an_alias/Test.roc ─────────
+    ── NOT AN INLINE ALIAS [E261] in tmp/error_inline_alias_not_an_alias/Test.roc ──
 
     The inline type after this `as` is not a type alias:
 
@@ -7001,7 +7002,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── QUALIFIED ALIAS NAME in tmp/error_inline_alias_qualified/Test.roc ───────────
+    ── QUALIFIED ALIAS NAME [E262] in tmp/error_inline_alias_qualified/Test.roc ────
 
     This type alias has a qualified name:
 
@@ -7021,7 +7022,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE ARGUMENT NOT LOWERCASE in ..._inline_alias_argument_uppercase/Test.roc ─
+    ── TYPE ARGUMENT NOT LOWERCASE [E263] in ..._alias_argument_uppercase/Test.roc ─
 
     This alias type argument is not lowercase:
 
@@ -7045,7 +7046,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `isEmpty` has an unexpected type:
 
@@ -7081,7 +7082,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `c` has an unexpected type:
 
@@ -7114,7 +7115,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `F` alias is self-recursive in an invalid way:
 
@@ -7137,7 +7138,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `F` alias is self-recursive in an invalid way:
 
@@ -7159,7 +7160,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `F` alias is self-recursive in an invalid way:
 
@@ -7184,7 +7185,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 1st argument to `job` is weird:
 
@@ -7219,7 +7220,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `job` definition:
 
@@ -7250,7 +7251,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── NESTED DATATYPE in /code/proj/Main.roc ──────────────────────────────────────
+    ── NESTED DATATYPE [E122] in /code/proj/Main.roc ───────────────────────────────
 
     `Nested` is a nested datatype. Here is one recursive usage of it:
 
@@ -7278,7 +7279,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── NESTED DATATYPE in /code/proj/Main.roc ──────────────────────────────────────
+    ── NESTED DATATYPE [E122] in /code/proj/Main.roc ───────────────────────────────
 
     `Nested` is a nested datatype. Here is one recursive usage of it:
 
@@ -7326,7 +7327,7 @@ All branches in an `if` must have the same type!
 
                     let real = format!(indoc!(
                         r"
-                        ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+                        ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
                         This 1st argument to `use` has an unexpected type:
 
@@ -7392,7 +7393,7 @@ All branches in an `if` must have the same type!
 
                     let real = format!(indoc!(
                         r"
-                        ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+                        ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
                         The branches of this `when` expression don't match the condition:
 
@@ -7443,7 +7444,7 @@ All branches in an `if` must have the same type!
         ),
         // TODO: link to number suffixes
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This integer literal contains an invalid digit:
 
@@ -7466,7 +7467,7 @@ All branches in an `if` must have the same type!
         ),
         // TODO: link to number suffixes
         @r"
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     This integer literal contains an invalid digit:
 
@@ -7488,7 +7489,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CONFLICTING NUMBER SUFFIX in /code/proj/Main.roc ────────────────────────────
+    ── CONFLICTING NUMBER SUFFIX [E123] in /code/proj/Main.roc ─────────────────────
 
     This number literal is an integer, but it has a float suffix:
 
@@ -7505,7 +7506,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CONFLICTING NUMBER SUFFIX in /code/proj/Main.roc ────────────────────────────
+    ── CONFLICTING NUMBER SUFFIX [E123] in /code/proj/Main.roc ─────────────────────
 
     This number literal is a float, but it has an integer suffix:
 
@@ -7518,7 +7519,7 @@ All branches in an `if` must have the same type!
         u8_overflow,
         "256u8",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7534,7 +7535,7 @@ All branches in an `if` must have the same type!
         negative_u8,
         "-1u8",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7550,7 +7551,7 @@ All branches in an `if` must have the same type!
         u16_overflow,
         "65536u16",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7566,7 +7567,7 @@ All branches in an `if` must have the same type!
         negative_u16,
         "-1u16",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7582,7 +7583,7 @@ All branches in an `if` must have the same type!
         u32_overflow,
         "4_294_967_296u32",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7598,7 +7599,7 @@ All branches in an `if` must have the same type!
         negative_u32,
         "-1u32",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7614,7 +7615,7 @@ All branches in an `if` must have the same type!
         u64_overflow,
         "18_446_744_073_709_551_616u64",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7630,7 +7631,7 @@ All branches in an `if` must have the same type!
         negative_u64,
         "-1u64",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7646,7 +7647,7 @@ All branches in an `if` must have the same type!
         negative_u128,
         "-1u128",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7662,7 +7663,7 @@ All branches in an `if` must have the same type!
         i8_overflow,
         "128i8",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7678,7 +7679,7 @@ All branches in an `if` must have the same type!
         i8_underflow,
         "-129i8",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7694,7 +7695,7 @@ All branches in an `if` must have the same type!
         i16_overflow,
         "32768i16",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7710,7 +7711,7 @@ All branches in an `if` must have the same type!
         i16_underflow,
         "-32769i16",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7726,7 +7727,7 @@ All branches in an `if` must have the same type!
         i32_overflow,
         "2_147_483_648i32",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7742,7 +7743,7 @@ All branches in an `if` must have the same type!
         i32_underflow,
         "-2_147_483_649i32",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7758,7 +7759,7 @@ All branches in an `if` must have the same type!
         i64_overflow,
         "9_223_372_036_854_775_808i64",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7774,7 +7775,7 @@ All branches in an `if` must have the same type!
         i64_underflow,
         "-9_223_372_036_854_775_809i64",
         @r"
-    ── NUMBER UNDERFLOWS SUFFIX in /code/proj/Main.roc ─────────────────────────────
+    ── NUMBER UNDERFLOWS SUFFIX [E125] in /code/proj/Main.roc ──────────────────────
 
     This integer literal underflows the type indicated by its suffix:
 
@@ -7790,7 +7791,7 @@ All branches in an `if` must have the same type!
         i128_overflow,
         "170_141_183_460_469_231_731_687_303_715_884_105_728i128",
         @r"
-    ── NUMBER OVERFLOWS SUFFIX in /code/proj/Main.roc ──────────────────────────────
+    ── NUMBER OVERFLOWS SUFFIX [E124] in /code/proj/Main.roc ───────────────────────
 
     This integer literal overflows the type indicated by its suffix:
 
@@ -7812,7 +7813,7 @@ All branches in an `if` must have the same type!
         // TODO: this error message could be improved, e.g. something like "This argument can
         // be used as ... because of its literal value"
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `get` has an unexpected type:
 
@@ -7838,7 +7839,7 @@ All branches in an `if` must have the same type!
              "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `get` has an unexpected type:
 
@@ -7865,7 +7866,7 @@ All branches in an `if` must have the same type!
              "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `get` has an unexpected type:
 
@@ -7892,7 +7893,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -7923,7 +7924,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `R` alias is self-recursive in an invalid way:
 
@@ -7946,7 +7947,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `R` alias is self-recursive in an invalid way:
 
@@ -7970,7 +7971,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `Foo` alias is recursive in an invalid way:
 
@@ -8005,7 +8006,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
     This alias has the same name as a builtin:
 
@@ -8025,7 +8026,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── OPAQUE TYPE NOT DEFINED in /code/proj/Main.roc ──────────────────────────────
+    ── OPAQUE TYPE NOT DEFINED [E126] in /code/proj/Main.roc ───────────────────────
 
     The opaque type Age referenced here is not defined:
 
@@ -8046,7 +8047,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── OPAQUE TYPE NOT DEFINED in /code/proj/Main.roc ──────────────────────────────
+    ── OPAQUE TYPE NOT DEFINED [E126] in /code/proj/Main.roc ───────────────────────
 
     The opaque type Age referenced here is not defined:
 
@@ -8060,7 +8061,7 @@ All branches in an `if` must have the same type!
 
     Note: It looks like there are no opaque types declared in this scope yet!
 
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `Age` is not used anywhere in your code.
 
@@ -8083,7 +8084,7 @@ All branches in an `if` must have the same type!
         // and checking it during can. The reason the error appears is because it is parsed as
         // Apply(Error(OtherModule), [@Age, 21])
         @r"
-    ── OPAQUE TYPE NOT DEFINED in /code/proj/Main.roc ──────────────────────────────
+    ── OPAQUE TYPE NOT DEFINED [E126] in /code/proj/Main.roc ───────────────────────
 
     The opaque type Age referenced here is not defined:
 
@@ -8092,7 +8093,7 @@ All branches in an `if` must have the same type!
 
     Note: It looks like there are no opaque types declared in this scope yet!
 
-    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+    ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
     I am trying to parse a qualified name here:
 
@@ -8119,7 +8120,7 @@ All branches in an `if` must have the same type!
         // `@Age` can be linked to the declaration of `Age` inside `age`, and a suggestion to
         // raise that declaration to the outer scope.
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `Age` is not used anywhere in your code.
 
@@ -8129,7 +8130,7 @@ All branches in an `if` must have the same type!
     If you didn't intend on using `Age` then remove it so future readers of
     your code don't wonder why it is there.
 
-    ── OPAQUE TYPE NOT DEFINED in /code/proj/Main.roc ──────────────────────────────
+    ── OPAQUE TYPE NOT DEFINED [E126] in /code/proj/Main.roc ───────────────────────
 
     The opaque type Age referenced here is not defined:
 
@@ -8150,7 +8151,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── MODULE NOT IMPORTED in /code/proj/Main.roc ──────────────────────────────────
+    ── MODULE NOT IMPORTED [E120] in /code/proj/Main.roc ───────────────────────────
 
     The `Task` module is not imported:
 
@@ -8182,7 +8183,7 @@ All branches in an `if` must have the same type!
         // TODO(opaques): error could be improved by saying that the opaque definition demands
         // that the argument be a U8, and linking to the definition!
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -8211,7 +8212,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -8241,7 +8242,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `v` definition:
 
@@ -8277,7 +8278,7 @@ All branches in an `if` must have the same type!
         // TODO(opaques): error could be improved by saying that the user-provided pattern
         // probably wants to change "Age" to "@Age"!
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 1st argument to `f` is weird:
 
@@ -8310,7 +8311,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 2nd pattern in this `when` does not match the previous ones:
 
@@ -8341,7 +8342,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -8379,7 +8380,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -8412,7 +8413,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `y` has an unexpected type:
 
@@ -8445,7 +8446,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -8469,7 +8470,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── INVALID_EXTENSION_TYPE in /code/proj/Main.roc ───────────────────────────────
+    ── INVALID_EXTENSION_TYPE [E130] in /code/proj/Main.roc ────────────────────────
 
     This record extension type is invalid:
 
@@ -8490,7 +8491,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── INVALID_EXTENSION_TYPE in /code/proj/Main.roc ───────────────────────────────
+    ── INVALID_EXTENSION_TYPE [E130] in /code/proj/Main.roc ────────────────────────
 
     This tag union extension type is invalid:
 
@@ -8517,7 +8518,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
     Nothing is named `UnknownType` in this scope.
 
@@ -8531,7 +8532,7 @@ All branches in an `if` must have the same type!
         Unsigned16
         Unsigned64
 
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
     Nothing is named `UnknownType` in this scope.
 
@@ -8558,7 +8559,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── UNFINISHED ABILITY in tmp/ability_first_demand_not_indented_enough/Test.roc ─
+    ── UNFINISHED ABILITY [E284] in ...y_first_demand_not_indented_enough/Test.roc ─
 
     I was partway through parsing an ability definition, but I got stuck
     here:
@@ -8583,7 +8584,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-        ── UNFINISHED ABILITY in tmp/ability_demands_not_indented_with_first/Test.roc ──
+        ── UNFINISHED ABILITY [E284] in ...ty_demands_not_indented_with_first/Test.roc ─
 
         I was partway through parsing an ability definition, but I got stuck
         here:
@@ -8606,7 +8607,7 @@ All branches in an `if` must have the same type!
                 "
         ),
         @r"
-        ── UNFINISHED ABILITY in tmp/ability_demand_value_has_args/Test.roc ────────────
+        ── UNFINISHED ABILITY [E284] in tmp/ability_demand_value_has_args/Test.roc ─────
 
         I was partway through parsing an ability definition, but I got stuck
         here:
@@ -8630,7 +8631,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── UNFINISHED ABILITY in tmp/ability_non_signature_expression/Test.roc ─────────
+    ── UNFINISHED ABILITY [E284] in tmp/ability_non_signature_expression/Test.roc ──
 
     I was partway through parsing an ability definition, but I got stuck
     here:
@@ -8653,7 +8654,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── WILDCARD NOT ALLOWED HERE in /code/proj/Main.roc ────────────────────────────
+    ── WILDCARD NOT ALLOWED HERE [E109] in /code/proj/Main.roc ─────────────────────
 
     The definition of `I` includes a wildcard (`*`) type variable:
 
@@ -8675,7 +8676,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── UNDERSCORE NOT ALLOWED HERE in /code/proj/Main.roc ──────────────────────────
+    ── UNDERSCORE NOT ALLOWED HERE [E110] in /code/proj/Main.roc ───────────────────
 
     The definition of `I` includes an inferred (`_`) type:
 
@@ -8696,7 +8697,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── WILDCARD NOT ALLOWED HERE in /code/proj/Main.roc ────────────────────────────
+    ── WILDCARD NOT ALLOWED HERE [E109] in /code/proj/Main.roc ─────────────────────
 
     The definition of `I` includes a wildcard (`*`) type variable:
 
@@ -8718,7 +8719,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── WILDCARD NOT ALLOWED HERE in /code/proj/Main.roc ────────────────────────────
+    ── WILDCARD NOT ALLOWED HERE [E109] in /code/proj/Main.roc ─────────────────────
 
     The definition of `I` includes 2 wildcard (`*`) type variables. Here is
     one of them:
@@ -8741,7 +8742,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── UNDERSCORE NOT ALLOWED HERE in /code/proj/Main.roc ──────────────────────────
+    ── UNDERSCORE NOT ALLOWED HERE [E110] in /code/proj/Main.roc ───────────────────
 
     The definition of `I` includes an inferred (`_`) type:
 
@@ -8762,7 +8763,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── UNDECLARED TYPE VARIABLE in /code/proj/Main.roc ─────────────────────────────
+    ── UNDECLARED TYPE VARIABLE [E108] in /code/proj/Main.roc ──────────────────────
 
     The definition of `I` includes an undeclared type variable:
 
@@ -8787,7 +8788,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── ABILITY HAS TYPE VARIABLES in /code/proj/Main.roc ───────────────────────────
+    ── ABILITY HAS TYPE VARIABLES [E131] in /code/proj/Main.roc ────────────────────
 
     The definition of the `MHash` ability includes type variables:
 
@@ -8797,7 +8798,7 @@ All branches in an `if` must have the same type!
     Abilities cannot depend on type variables, but their member values
     can!
 
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `MHash` is not used anywhere in your code.
 
@@ -8819,7 +8820,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── IMPLEMENTS CLAUSE IS NOT AN ABILITY in /code/proj/Main.roc ──────────────────
+    ── IMPLEMENTS CLAUSE IS NOT AN ABILITY [E132] in /code/proj/Main.roc ───────────
 
     The type referenced in this "implements" clause is not an ability:
 
@@ -8838,7 +8839,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-        ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+        ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
         The `a` name is first defined here:
 
@@ -8867,7 +8868,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-        ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+        ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
         The `Ability` name is first defined here:
 
@@ -8894,7 +8895,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-        ── ABILITY MEMBER MISSING IMPLEMENTS CLAUSE in /code/proj/Main.roc ─────────────
+        ── ABILITY MEMBER MISSING IMPLEMENTS CLAUSE [E134] in /code/proj/Main.roc ──────
 
         The definition of the ability member `ab` does not include an `implements`
         clause binding a type variable to the ability `Ability`:
@@ -8909,7 +8910,7 @@ All branches in an `if` must have the same type!
 
         Otherwise, the function does not need to be part of the ability!
 
-        ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+        ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
         `Ability` is not used anywhere in your code.
 
@@ -8931,7 +8932,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-        ── ABILITY MEMBER BINDS MULTIPLE VARIABLES in /code/proj/Main.roc ──────────────
+        ── ABILITY MEMBER BINDS MULTIPLE VARIABLES [E135] in /code/proj/Main.roc ───────
 
         The definition of the ability member `eq` includes multiple variables
         bound to the `MEq`` ability:`
@@ -8959,7 +8960,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── ILLEGAL IMPLEMENTS CLAUSE in /code/proj/Main.roc ────────────────────────────
+    ── ILLEGAL IMPLEMENTS CLAUSE [E133] in /code/proj/Main.roc ─────────────────────
 
     An `implements` clause is not allowed here:
 
@@ -8969,7 +8970,7 @@ All branches in an `if` must have the same type!
     `implements` clauses can only be specified on the top-level type
     annotations.
 
-    ── ABILITY MEMBER MISSING IMPLEMENTS CLAUSE in /code/proj/Main.roc ─────────────
+    ── ABILITY MEMBER MISSING IMPLEMENTS CLAUSE [E134] in /code/proj/Main.roc ──────
 
     The definition of the ability member `hash` does not include an
     `implements` clause binding a type variable to the ability `MHash`:
@@ -9000,7 +9001,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-        ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+        ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
         Something is off with this specialization of `hash`:
 
@@ -9033,7 +9034,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9059,7 +9060,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `hash` is not used anywhere in your code.
 
@@ -9088,7 +9089,7 @@ All branches in an `if` must have the same type!
         ),
         // TODO: the error message here could be seriously improved!
         @r"
-    ── OVERLOADED SPECIALIZATION in /code/proj/Main.roc ────────────────────────────
+    ── OVERLOADED SPECIALIZATION [E152] in /code/proj/Main.roc ─────────────────────
 
     This ability member specialization is already claimed to specialize
     another opaque type:
@@ -9101,7 +9102,7 @@ All branches in an `if` must have the same type!
     Ability specializations can only provide implementations for one
     opaque type, since all opaque types are different!
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This specialization of `hash` is overly general:
 
@@ -9141,7 +9142,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── OVERLOADED SPECIALIZATION in /code/proj/Main.roc ────────────────────────────
+    ── OVERLOADED SPECIALIZATION [E152] in /code/proj/Main.roc ─────────────────────
 
     This ability member specialization is already claimed to specialize
     another opaque type:
@@ -9172,7 +9173,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with this specialization of `eq`:
 
@@ -9207,7 +9208,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `hash` definition:
 
@@ -9248,7 +9249,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -9261,7 +9262,7 @@ All branches in an `if` must have the same type!
 
     Only builtin abilities can have generated implementations!
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -9286,7 +9287,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-        ── ABILITY NOT ON TOP-LEVEL in /code/proj/Main.roc ─────────────────────────────
+        ── ABILITY NOT ON TOP-LEVEL [E136] in /code/proj/Main.roc ──────────────────────
 
         This ability definition is not on the top-level of a module:
 
@@ -9314,7 +9315,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `hashable` definition:
 
@@ -9360,7 +9361,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── ABILITY USED AS TYPE in /code/proj/Main.roc ─────────────────────────────────
+    ── ABILITY USED AS TYPE [E138] in /code/proj/Main.roc ──────────────────────────
 
     You are attempting to use the ability `MHash` as a type directly:
 
@@ -9374,7 +9375,7 @@ All branches in an `if` must have the same type!
 
         a implements MHash
 
-    ── ABILITY USED AS TYPE in /code/proj/Main.roc ─────────────────────────────────
+    ── ABILITY USED AS TYPE [E138] in /code/proj/Main.roc ──────────────────────────
 
     You are attempting to use the ability `MHash` as a type directly:
 
@@ -9404,7 +9405,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -9457,7 +9458,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-        ── WEIRD IMPORTS in tmp/imports_missing_comma/Test.roc ─────────────────────────
+        ── WEIRD IMPORTS [E275] in tmp/imports_missing_comma/Test.roc ──────────────────
 
         I am partway through parsing a imports list, but I got stuck here:
 
@@ -9482,7 +9483,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-        ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+        ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
         This `when` does not cover all the possibilities:
 
@@ -9529,7 +9530,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-        ── SPECIALIZATION NOT ON TOP-LEVEL in /code/proj/Main.roc ──────────────────────
+        ── SPECIALIZATION NOT ON TOP-LEVEL [E137] in /code/proj/Main.roc ───────────────
 
         This specialization of the `default` ability member is in a nested
         scope:
@@ -9554,7 +9555,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to == has an unexpected type:
 
@@ -9590,7 +9591,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `remove` has an unexpected type:
 
@@ -9611,7 +9612,7 @@ All branches in an `if` must have the same type!
     change the type annotation to be more specific? Maybe change the code
     to be more general?
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `new`:
 
@@ -9624,7 +9625,7 @@ All branches in an `if` must have the same type!
 
         { set : Set ∞ }
 
-    ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
+    ── CIRCULAR TYPE [E002] in /code/proj/Main.roc ─────────────────────────────────
 
     I'm inferring a weird self-referential type for `goal`:
 
@@ -9654,7 +9655,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-        ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+        ── CIRCULAR DEFINITION [E117] in /code/proj/Main.roc ───────────────────────────
 
         The `t1` definition is causing a very tricky infinite loop:
 
@@ -9682,7 +9683,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -9710,7 +9711,7 @@ All branches in an `if` must have the same type!
         // TODO: this error message is quite unfortunate. We should remove the duplication, and
         // also support regions that point to things in other modules. See also https://github.com/roc-lang/roc/issues/3056.
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -9747,7 +9748,7 @@ All branches in an `if` must have the same type!
                 "#
         ),
         @r"
-            ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+            ── CIRCULAR DEFINITION [E117] in /code/proj/Main.roc ───────────────────────────
 
             The `t1` definition is causing a very tricky infinite loop:
 
@@ -9777,7 +9778,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── IMPLEMENTATION NOT FOUND in /code/proj/Main.roc ─────────────────────────────
+    ── IMPLEMENTATION NOT FOUND [E140] in /code/proj/Main.roc ──────────────────────
 
     An implementation of `eq` could not be found in this scope:
 
@@ -9788,7 +9789,7 @@ All branches in an `if` must have the same type!
     another variable that implements this ability member, like
     { eq: myeq }
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9815,7 +9816,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+    ── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
     Nothing is named `aMEq` in this scope.
 
@@ -9829,7 +9830,7 @@ All branches in an `if` must have the same type!
         myMEq
         eq
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9856,7 +9857,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── OPTIONAL ABILITY IMPLEMENTATION in /code/proj/Main.roc ──────────────────────
+    ── OPTIONAL ABILITY IMPLEMENTATION [E143] in /code/proj/Main.roc ───────────────
 
     Ability implementations cannot be optional:
 
@@ -9867,7 +9868,7 @@ All branches in an `if` must have the same type!
 
 
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9894,7 +9895,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── OPTIONAL ABILITY IMPLEMENTATION in /code/proj/Main.roc ──────────────────────
+    ── OPTIONAL ABILITY IMPLEMENTATION [E143] in /code/proj/Main.roc ───────────────
 
     Ability implementations cannot be optional:
 
@@ -9907,7 +9908,7 @@ All branches in an `if` must have the same type!
     record of implementations. For example,    implements [Encoding] will
     attempt to derive `Encoding`
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     This type does not fully implement the `Encoding` ability:
 
@@ -9932,7 +9933,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── QUALIFIED ABILITY IMPLEMENTATION in /code/proj/Main.roc ─────────────────────
+    ── QUALIFIED ABILITY IMPLEMENTATION [E144] in /code/proj/Main.roc ──────────────
 
     This ability implementation is qualified:
 
@@ -9942,7 +9943,7 @@ All branches in an `if` must have the same type!
     Custom implementations must be defined in the local scope, and
     unqualified.
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -9967,7 +9968,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── ABILITY IMPLEMENTATION NOT IDENTIFIER in /code/proj/Main.roc ────────────────
+    ── ABILITY IMPLEMENTATION NOT IDENTIFIER [E145] in /code/proj/Main.roc ─────────
 
     This ability implementation is not an identifier:
 
@@ -9979,7 +9980,7 @@ All branches in an `if` must have the same type!
 
     Tip: consider defining this expression as a variable.
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     This type does not fully implement the `MEq` ability:
 
@@ -10006,7 +10007,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── DUPLICATE IMPLEMENTATION in /code/proj/Main.roc ─────────────────────────────
+    ── DUPLICATE IMPLEMENTATION [E146] in /code/proj/Main.roc ──────────────────────
 
     This ability member implementation is duplicate:
 
@@ -10034,7 +10035,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── NOT AN ABILITY in /code/proj/Main.roc ───────────────────────────────────────
+    ── NOT AN ABILITY [E142] in /code/proj/Main.roc ────────────────────────────────
 
     This identifier is not an ability in scope:
 
@@ -10057,7 +10058,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── ILLEGAL DERIVE in /code/proj/Main.roc ───────────────────────────────────────
+    ── ILLEGAL DERIVE [E139] in /code/proj/Main.roc ────────────────────────────────
 
     This ability cannot be derived:
 
@@ -10080,7 +10081,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Encoding` ability for `A`:
 
@@ -10105,7 +10106,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Encoding` ability for `A`:
 
@@ -10157,7 +10158,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
     The `main` name is first defined here:
 
@@ -10172,7 +10173,7 @@ All branches in an `if` must have the same type!
     Since these variables have the same name, it's easy to use the wrong
     one by accident. Give one of them a new name.
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10206,7 +10207,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-        ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+        ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
         Something is off with the body of the `withOpen` definition:
 
@@ -10241,7 +10242,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression is used in an unexpected way:
 
@@ -10288,7 +10289,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `foo` has an unexpected type:
 
@@ -10333,7 +10334,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to == has an unexpected type:
 
@@ -10358,7 +10359,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to == has an unexpected type:
 
@@ -10395,7 +10396,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the 2nd branch of this `when` expression:
 
@@ -10430,7 +10431,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `map` has an unexpected type:
 
@@ -10456,7 +10457,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-        ── NAME NOT BOUND IN ALL PATTERNS in /code/proj/Main.roc ───────────────────────
+        ── NAME NOT BOUND IN ALL PATTERNS [E150] in /code/proj/Main.roc ────────────────
 
         `x` is not bound in all patterns of this `when` branch
 
@@ -10467,7 +10468,7 @@ All branches in an `if` must have the same type!
         of the branch. Otherwise, the program would crash when it tries to use
         an identifier that wasn't bound!
 
-        ── NAME NOT BOUND IN ALL PATTERNS in /code/proj/Main.roc ───────────────────────
+        ── NAME NOT BOUND IN ALL PATTERNS [E150] in /code/proj/Main.roc ────────────────
 
         `y` is not bound in all patterns of this `when` branch
 
@@ -10478,7 +10479,7 @@ All branches in an `if` must have the same type!
         of the branch. Otherwise, the program would crash when it tries to use
         an identifier that wasn't bound!
 
-        ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+        ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
         `y` is not used in this `when` branch.
 
@@ -10499,7 +10500,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-        ── NAME NOT BOUND IN ALL PATTERNS in /code/proj/Main.roc ───────────────────────
+        ── NAME NOT BOUND IN ALL PATTERNS [E150] in /code/proj/Main.roc ────────────────
 
         `x` is not bound in all patterns of this `when` branch
 
@@ -10522,7 +10523,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-        ── NAME NOT BOUND IN ALL PATTERNS in /code/proj/Main.roc ───────────────────────
+        ── NAME NOT BOUND IN ALL PATTERNS [E150] in /code/proj/Main.roc ────────────────
 
         `rest` is not bound in all patterns of this `when` branch
 
@@ -10545,7 +10546,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -10571,7 +10572,7 @@ All branches in an `if` must have the same type!
         |golden| pretty_assertions::assert_eq!(
             golden,
             indoc!(
-                r"── UNRECOGNIZED NAME in /code/proj/Main.roc ────────────────────────────────────
+                r"── UNRECOGNIZED NAME [E101] in /code/proj/Main.roc ─────────────────────────────
 
                 Nothing is named `foo` in this scope.
 
@@ -10608,7 +10609,7 @@ All branches in an `if` must have the same type!
         |golden| pretty_assertions::assert_eq!(
             golden,
             indoc!(
-                r"── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+                r"── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
                 An underscore is being used as a variable here:
 
@@ -10634,7 +10635,7 @@ All branches in an `if` must have the same type!
         |golden| pretty_assertions::assert_eq!(
             golden,
             indoc!(
-                r"── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+                r"── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
                 This variable's name starts with an underscore:
 
@@ -10667,7 +10668,7 @@ All branches in an `if` must have the same type!
             golden,
             indoc!(
                 r"
-                ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+                ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
                 This variable's name starts with an underscore:
 
@@ -10694,7 +10695,7 @@ All branches in an `if` must have the same type!
             golden,
             indoc!(
                 r"
-                ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+                ── SYNTAX PROBLEM [E201] in /code/proj/Main.roc ────────────────────────────────
 
                 Underscores are not allowed in identifier names:
 
@@ -10721,7 +10722,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── BAD OLD-STYLE RECORD BUILDER in ...nal_field_in_old_record_builder/Test.roc ─
+    ── BAD OLD-STYLE RECORD BUILDER [E210] in ...ld_in_old_record_builder/Test.roc ─
 
     I am partway through parsing a record builder, and I found an optional
     field:
@@ -10750,7 +10751,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── BAD RECORD UPDATE in tmp/record_update_old_builder/Test.roc ─────────────────
+    ── BAD RECORD UPDATE [E211] in tmp/record_update_old_builder/Test.roc ──────────
 
     I am partway through parsing a record update, and I found an old-style
     record builder field:
@@ -10776,7 +10777,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── MULTIPLE OLD-STYLE RECORD BUILDERS in /code/proj/Main.roc ───────────────────
+    ── MULTIPLE OLD-STYLE RECORD BUILDERS [E161] in /code/proj/Main.roc ────────────
 
     This function is applied to multiple old-style record builders:
 
@@ -10798,7 +10799,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── UNAPPLIED OLD-STYLE RECORD BUILDER in /code/proj/Main.roc ───────────────────
+    ── UNAPPLIED OLD-STYLE RECORD BUILDER [E162] in /code/proj/Main.roc ────────────
 
     This old-style record builder was not applied to a function:
 
@@ -10823,7 +10824,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [E004] in /code/proj/Main.roc ─────────────────────────────────
 
     This value is not a function, but it was given 1 argument:
 
@@ -10855,7 +10856,7 @@ All branches in an `if` must have the same type!
     //         "#
     //     ),
     //     @r#"
-    // ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    // ── TOO MANY ARGS [E004] in /code/proj/Main.roc ─────────────────────────────────
 
     // This value is an opaque type, so it cannot be called with an argument:
 
@@ -10874,7 +10875,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── EMPTY RECORD BUILDER in /code/proj/Main.roc ─────────────────────────────────
+    ── EMPTY RECORD BUILDER [E163] in /code/proj/Main.roc ──────────────────────────
     
     This record builder has no fields:
     
@@ -10895,7 +10896,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── NOT ENOUGH FIELDS IN RECORD BUILDER in /code/proj/Main.roc ──────────────────
+    ── NOT ENOUGH FIELDS IN RECORD BUILDER [E164] in /code/proj/Main.roc ───────────
 
     This record builder only has one field:
     
@@ -10918,7 +10919,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── OPTIONAL FIELD IN RECORD BUILDER in /code/proj/Main.roc ─────────────────────
+    ── OPTIONAL FIELD IN RECORD BUILDER [E165] in /code/proj/Main.roc ──────────────
     
     Optional fields are not allowed to be used in record builders.
     
@@ -10955,7 +10956,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [E004] in /code/proj/Main.roc ─────────────────────────────────
 
     The `xyz` value is not a function, but it was given 3 arguments:
 
@@ -10983,7 +10984,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r###"
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -10995,7 +10996,7 @@ All branches in an `if` must have the same type!
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -11007,7 +11008,7 @@ All branches in an `if` must have the same type!
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -11019,7 +11020,7 @@ All branches in an `if` must have the same type!
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -11049,7 +11050,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -11061,7 +11062,7 @@ All branches in an `if` must have the same type!
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -11073,7 +11074,7 @@ All branches in an `if` must have the same type!
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -11085,7 +11086,7 @@ All branches in an `if` must have the same type!
     functional, assignments that don't introduce variables cannot affect a
     program's behavior!
 
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
+    ── UNNECESSARY DEFINITION [E151] in /code/proj/Main.roc ────────────────────────
 
     This destructure assignment doesn't introduce any new variables:
 
@@ -11113,7 +11114,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `hash` is not used anywhere in your code.
 
@@ -11140,7 +11141,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── WRONG SPECIALIZATION TYPE in /code/proj/Main.roc ────────────────────────────
+    ── WRONG SPECIALIZATION TYPE [E011] in /code/proj/Main.roc ─────────────────────
 
     This specialization of `hash` is not for the expected type:
 
@@ -11163,7 +11164,7 @@ All branches in an `if` must have the same type!
                 "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `x` definition:
 
@@ -11193,7 +11194,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── CYCLIC ALIAS [E003] in /code/proj/Main.roc ──────────────────────────────────
 
     The `Recursive` opaque is self-recursive in an invalid way:
 
@@ -11215,7 +11216,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Decoding` ability for `A`:
 
@@ -11240,7 +11241,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Decoding` ability for `A`:
 
@@ -11297,7 +11298,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11330,7 +11331,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11360,7 +11361,7 @@ All branches in an `if` must have the same type!
             "
         ),
     @r#"
-    ── UNUSED ARGUMENT in /code/proj/Main.roc ──────────────────────────────────────
+    ── UNUSED ARGUMENT [E111] in /code/proj/Main.roc ───────────────────────────────
 
     This function doesn't use `x`.
 
@@ -11392,7 +11393,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -11425,7 +11426,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -11462,7 +11463,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the `then` branch of this `if` expression:
 
@@ -11494,7 +11495,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
+    ── UNUSED DEFINITION [E103] in /code/proj/Main.roc ─────────────────────────────
 
     `foo` is not used in this `when` branch.
 
@@ -11562,7 +11563,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r###"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11593,7 +11594,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r###"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -11659,7 +11660,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -11687,7 +11688,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -11714,7 +11715,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNMATCHABLE PATTERN in /code/proj/Main.roc ──────────────────────────────────
+    ── UNMATCHABLE PATTERN [E008] in /code/proj/Main.roc ───────────────────────────
 
     The 2nd pattern will never be matched:
 
@@ -11741,7 +11742,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNMATCHABLE PATTERN in /code/proj/Main.roc ──────────────────────────────────
+    ── UNMATCHABLE PATTERN [E008] in /code/proj/Main.roc ───────────────────────────
 
     The 2nd pattern will never be matched:
 
@@ -11753,7 +11754,7 @@ All branches in an `if` must have the same type!
     It's impossible to create a value of this shape, so this pattern can
     be safely removed!
 
-    ── UNMATCHABLE PATTERN in /code/proj/Main.roc ──────────────────────────────────
+    ── UNMATCHABLE PATTERN [E008] in /code/proj/Main.roc ───────────────────────────
 
     The 3rd pattern will never be matched:
 
@@ -11778,7 +11779,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+    ── DUPLICATE NAME [E118] in /code/proj/Main.roc ────────────────────────────────
 
     This opaque type has the same name as a builtin:
 
@@ -11802,7 +11803,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r###"
-    ── UNUSED IMPORT in /code/proj/Main.roc ────────────────────────────────────────
+    ── UNUSED IMPORT [E104] in /code/proj/Main.roc ─────────────────────────────────
 
     List is imported but not used.
 
@@ -11813,6 +11814,145 @@ All branches in an `if` must have the same type!
     "###
     );
 
+    #[test]
+    fn error_codes() {
+        use roc_reporting::code::{explain, ReportKind};
+
+        let mut previous = "E000";
+        let mut titles = HashSet::new();
+
+        for kind in ReportKind::ALL {
+            let code = kind.code();
+
+            assert!(
+                code.len() == 4 && code.starts_with('E') && code[1..].parse::<u16>().is_ok(),
+                "{code} is not of the form E123"
+            );
+
+            // Codes only ever go up, so that a new report can't accidentally reuse an old code.
+            assert!(code > previous, "{code} comes after {previous}");
+            assert!(
+                titles.insert(kind.title()),
+                "{} has two codes",
+                kind.title()
+            );
+            assert_eq!(ReportKind::from_code(code), Some(*kind));
+
+            previous = code;
+        }
+
+        assert!(explain("e001")
+            .unwrap()
+            .starts_with("E001: TYPE MISMATCH\n"));
+        assert!(explain("E999").is_none());
+    }
+
+    #[test]
+    fn every_report_kind_has_an_explanation() {
+        use roc_reporting::code::{explanation, ReportKind};
+
+        for kind in ReportKind::ALL {
+            assert!(
+                explanation(*kind).is_some(),
+                "{} ({}) has no explanation for `roc explain`",
+                kind.code(),
+                kind.title()
+            );
+        }
+    }
+
+    #[test]
+    fn json_diagnostics() {
+        let src = indoc!(
+            r#"
+            app "test" imports [] provides [main] to "./platform"
+
+            import List exposing [concat]
+
+            main : Str
+            main = 1
+            "#
+        );
+
+        let arena = Bump::new();
+        let (_, loaded) = run_load_and_infer("json_diagnostics", &arena, src);
+        let mut loaded = loaded.expect("failed to load");
+
+        let (mut json, problems) = roc_reporting::json::problems_json(
+            &loaded.sources,
+            &loaded.interns,
+            &mut loaded.can_problems,
+            &mut loaded.type_problems,
+        );
+
+        assert_eq!((problems.errors, problems.warnings), (1, 1));
+
+        for diagnostic in json["diagnostics"].as_array_mut().unwrap() {
+            let file = diagnostic["file"].take();
+            assert!(file.as_str().unwrap().ends_with("Test.roc"), "{file}");
+        }
+
+        insta::assert_snapshot!(serde_json::to_string_pretty(&json).unwrap(), @r###"
+    {
+      "diagnostics": [
+        {
+          "code": "E001",
+          "file": null,
+          "fixes": [],
+          "message": "Something is off with the body of the `main` definition:\n\n5│  main : Str\n6│  main = 1\n           ^\n\nThe body is a number of type:\n\n    Num *\n\nBut the type annotation on `main` says it should be:\n\n    Str",
+          "region": {
+            "end": {
+              "column": 8,
+              "line": 5
+            },
+            "start": {
+              "column": 7,
+              "line": 5
+            }
+          },
+          "severity": "error",
+          "title": "TYPE MISMATCH"
+        },
+        {
+          "code": "E104",
+          "file": null,
+          "fixes": [
+            {
+              "message": "Remove this import",
+              "region": {
+                "end": {
+                  "column": 29,
+                  "line": 2
+                },
+                "start": {
+                  "column": 0,
+                  "line": 2
+                }
+              },
+              "replacement": ""
+            }
+          ],
+          "message": "List is imported but not used.\n\n3│  import List exposing [concat]\n    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^\n\nSince List isn't used, you don't need to import it.",
+          "region": {
+            "end": {
+              "column": 29,
+              "line": 2
+            },
+            "start": {
+              "column": 0,
+              "line": 2
+            }
+          },
+          "severity": "warning",
+          "title": "UNUSED IMPORT"
+        }
+      ],
+      "errors": 1,
+      "warnings": 1
+    }
+    "###);
+    }
+
     test_report!(
         #[ignore = "https://github.com/roc-lang/roc/issues/4096"]
         unnecessary_builtin_module_import,
@@ -11854,7 +11994,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-    ── UNRECOGNIZED PACKAGE in tmp/unknown_shorthand_no_deps/Test.roc ──────────────
+    ── UNRECOGNIZED PACKAGE [E311] in tmp/unknown_shorthand_no_deps/Test.roc ───────
 
     This module is trying to import from `foo`:
 
@@ -11879,7 +12019,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-    ── UNRECOGNIZED PACKAGE in tmp/unknown_shorthand_in_app/Test.roc ───────────────
+    ── UNRECOGNIZED PACKAGE [E311] in tmp/unknown_shorthand_in_app/Test.roc ────────
 
     This module is trying to import from `foo`:
 
@@ -11906,7 +12046,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-    [1;36m── FILE NOT FOUND in tmp/import_qualified_builtin/../../tests/Bool.roc ─────────[0m
+    [1;36m── FILE NOT FOUND [E301] in tmp/import_qualified_builtin/../../tests/Bool.roc ──[0m
 
     I am looking for this file, but it's not there:
 
@@ -11928,7 +12068,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── CIRCULAR DEFINITION in /code/proj/Main.roc ──────────────────────────────────
+    ── CIRCULAR DEFINITION [E117] in /code/proj/Main.roc ───────────────────────────
 
     `main` is defined directly in terms of itself:
 
@@ -11950,7 +12090,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `if` condition needs to be a Bool:
 
@@ -11976,7 +12116,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This `if` condition needs to be a Bool:
 
@@ -12004,7 +12144,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Hash` ability for `A`:
 
@@ -12029,7 +12169,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Hash` ability for `A`:
 
@@ -12112,7 +12252,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12139,7 +12279,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12185,7 +12325,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12221,7 +12361,7 @@ All branches in an `if` must have the same type!
             "
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `shiftRightZfBy` has an unexpected type:
 
@@ -12236,7 +12376,7 @@ All branches in an `if` must have the same type!
 
         U8
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `shiftRightBy` has an unexpected type:
 
@@ -12251,7 +12391,7 @@ All branches in an `if` must have the same type!
 
         U8
 
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `shiftLeftBy` has an unexpected type:
 
@@ -12279,7 +12419,7 @@ All branches in an `if` must have the same type!
             "
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `contains` has an unexpected type:
 
@@ -12306,7 +12446,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Eq` ability for `A`:
 
@@ -12331,7 +12471,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -12361,7 +12501,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Eq` ability for `A`:
 
@@ -12387,7 +12527,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Eq` ability for `A`:
 
@@ -12415,7 +12555,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Eq` ability for `A`:
 
@@ -12498,7 +12638,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12525,7 +12665,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12554,7 +12694,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12577,7 +12717,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12617,7 +12757,7 @@ All branches in an `if` must have the same type!
              "#
         ),
         @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12655,7 +12795,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── NOT EXPOSED in /code/proj/Main.roc ──────────────────────────────────────────
+    ── NOT EXPOSED [E119] in /code/proj/Main.roc ───────────────────────────────────
 
     The Bool module does not expose `structuralEq`:
 
@@ -12669,7 +12809,7 @@ All branches in an `if` must have the same type!
         Bool.false
         Bool.isEq
 
-    ── NOT EXPOSED in /code/proj/Main.roc ──────────────────────────────────────────
+    ── NOT EXPOSED [E119] in /code/proj/Main.roc ───────────────────────────────────
 
     The Bool module does not expose `structuralNotEq`:
 
@@ -12697,7 +12837,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -12722,7 +12862,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The 1st argument to `foo` is weird:
 
@@ -12751,7 +12891,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Hash` ability for `F`:
 
@@ -12762,7 +12902,7 @@ All branches in an `if` must have the same type!
 
     Tip: You can define a custom implementation of `Hash` for `F`.
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Eq` ability for `F`:
 
@@ -12773,7 +12913,7 @@ All branches in an `if` must have the same type!
 
     Tip: You can define a custom implementation of `Eq` for `F`.
 
-    ── INCOMPLETE ABILITY IMPLEMENTATION in /code/proj/Main.roc ────────────────────
+    ── INCOMPLETE ABILITY IMPLEMENTATION [E009] in /code/proj/Main.roc ─────────────
 
     I can't derive an implementation of the `Encoding` ability for `F`:
 
@@ -12796,7 +12936,7 @@ All branches in an `if` must have the same type!
             "
         ),
     @r"
-    ── DUPLICATE BOUND ABILITY in /code/proj/Main.roc ──────────────────────────────
+    ── DUPLICATE BOUND ABILITY [E149] in /code/proj/Main.roc ───────────────────────
 
     I already saw that this type variable is bound to the `Hash` ability
     once before:
@@ -12822,7 +12962,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `g` has an unexpected type:
 
@@ -12859,7 +12999,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `g` has an unexpected type:
 
@@ -12897,7 +13037,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 1st argument to `g` has an unexpected type:
 
@@ -12930,7 +13070,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNFINISHED LIST PATTERN in tmp/list_pattern_not_terminated/Test.roc ─────────
+    ── UNFINISHED LIST PATTERN [E245] in tmp/list_pattern_not_terminated/Test.roc ──
 
     I am partway through parsing a list pattern, but I got stuck here:
 
@@ -12951,7 +13091,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── INCORRECT REST PATTERN in tmp/list_pattern_weird_rest_pattern/Test.roc ──────
+    ── INCORRECT REST PATTERN [E246] in ...ist_pattern_weird_rest_pattern/Test.roc ─
 
     It looks like you may trying to write a list rest pattern, but it's
     not the form I expect:
@@ -12973,7 +13113,7 @@ All branches in an `if` must have the same type!
             "
         ),
     @r"
-    ── UNNECESSARY WILDCARD in /code/proj/Main.roc ─────────────────────────────────
+    ── UNNECESSARY WILDCARD [E153] in /code/proj/Main.roc ──────────────────────────
 
     This type annotation has a wildcard type variable (`*`) that isn't
     needed.
@@ -13000,7 +13140,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── MULTIPLE LIST REST PATTERNS in /code/proj/Main.roc ──────────────────────────
+    ── MULTIPLE LIST REST PATTERNS [E154] in /code/proj/Main.roc ───────────────────
 
     This list pattern match has multiple rest patterns:
 
@@ -13010,7 +13150,7 @@ All branches in an `if` must have the same type!
     I only support compiling list patterns with one .. pattern! Can you
     remove this additional one?
 
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13034,7 +13174,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── MULTIPLE LIST REST PATTERNS in /code/proj/Main.roc ──────────────────────────
+    ── MULTIPLE LIST REST PATTERNS [E154] in /code/proj/Main.roc ───────────────────
 
     This list pattern match has multiple rest patterns:
 
@@ -13044,7 +13184,7 @@ All branches in an `if` must have the same type!
     I only support compiling list patterns with one .. pattern! Can you
     remove this additional one?
 
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13068,7 +13208,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This list element doesn't match the types of other elements in the
     pattern:
@@ -13095,7 +13235,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     The branches of this `when` expression don't match the condition:
 
@@ -13128,7 +13268,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13168,7 +13308,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13212,7 +13352,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [E007] in /code/proj/Main.roc ─────────────────────────────
 
     The 2nd pattern is redundant:
 
@@ -13278,7 +13418,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13305,7 +13445,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13348,7 +13488,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13375,7 +13515,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13424,7 +13564,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13455,7 +13595,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13487,7 +13627,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13536,7 +13676,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -13570,7 +13710,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [E007] in /code/proj/Main.roc ─────────────────────────────
 
     The 3rd pattern is redundant:
 
@@ -13598,7 +13738,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [E007] in /code/proj/Main.roc ─────────────────────────────
 
     The 3rd pattern is redundant:
 
@@ -13626,7 +13766,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [E007] in /code/proj/Main.roc ─────────────────────────────
 
     The 3rd pattern is redundant:
 
@@ -13654,7 +13794,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+    ── REDUNDANT PATTERN [E007] in /code/proj/Main.roc ─────────────────────────────
 
     The 2nd pattern is redundant:
 
@@ -13693,7 +13833,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -13724,7 +13864,7 @@ All branches in an `if` must have the same type!
             "
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This value passed to `crash` is not a string:
 
@@ -13749,7 +13889,7 @@ All branches in an `if` must have the same type!
             "
         ),
     @r"
-    ── UNAPPLIED CRASH in /code/proj/Main.roc ──────────────────────────────────────
+    ── UNAPPLIED CRASH [E157] in /code/proj/Main.roc ───────────────────────────────
 
     This `crash` doesn't have a message given to it:
 
@@ -13770,7 +13910,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── OVERAPPLIED CRASH in /code/proj/Main.roc ────────────────────────────────────
+    ── OVERAPPLIED CRASH [E158] in /code/proj/Main.roc ─────────────────────────────
 
     This `crash` has too many values given to it:
 
@@ -13806,7 +13946,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -13848,7 +13988,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── DEFINITION ONLY USED IN RECURSION in /code/proj/Main.roc ────────────────────
+    ── DEFINITION ONLY USED IN RECURSION [E148] in /code/proj/Main.roc ─────────────
 
     This definition is only used in recursion with itself:
 
@@ -13881,7 +14021,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── DEFINITIONs ONLY USED IN RECURSION in /code/proj/Main.roc ───────────────────
+    ── DEFINITION ONLY USED IN RECURSION [E148] in /code/proj/Main.roc ─────────────
 
     These 2 definitions are only used in mutual recursion with themselves:
 
@@ -13917,7 +14057,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── DEFINITION ONLY USED IN RECURSION in /code/proj/Main.roc ────────────────────
+    ── DEFINITION ONLY USED IN RECURSION [E148] in /code/proj/Main.roc ─────────────
 
     This definition is only used in recursion with itself:
 
@@ -13953,7 +14093,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r#"
-    ── DEFINITIONs ONLY USED IN RECURSION in /code/proj/Main.roc ───────────────────
+    ── DEFINITION ONLY USED IN RECURSION [E148] in /code/proj/Main.roc ─────────────
 
     These 2 definitions are only used in mutual recursion with themselves:
 
@@ -13992,7 +14132,7 @@ All branches in an `if` must have the same type!
             {one, str}
         "#),
     @r#"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This 2nd argument to `concat` has an unexpected type:
 
@@ -14023,7 +14163,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the `else` branch of this `if` expression:
 
@@ -14060,7 +14200,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the `else` branch of this `if` expression:
 
@@ -14096,7 +14236,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `main` definition:
 
@@ -14134,7 +14274,7 @@ All branches in an `if` must have the same type!
             "#
         ),
     @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `main` definition:
 
@@ -14204,7 +14344,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r###"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -14244,7 +14384,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     This expression has a type that does not implement the abilities it's expected to:
 
@@ -14284,7 +14424,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+    ── UNSAFE PATTERN [E006] in /code/proj/Main.roc ────────────────────────────────
 
     This `when` does not cover all the possibilities:
 
@@ -14332,7 +14472,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r#"
-    ── TOO MANY ARGS in /code/proj/Main.roc ────────────────────────────────────────
+    ── TOO MANY ARGS [E004] in /code/proj/Main.roc ─────────────────────────────────
 
     The `parser` value is an opaque type, so it cannot be called with an
     argument:
@@ -14356,7 +14496,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -14387,7 +14527,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -14421,7 +14561,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -14455,7 +14595,7 @@ All branches in an `if` must have the same type!
             "#
         ),
         @r"
-    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+    ── TYPE MISMATCH [E001] in /code/proj/Main.roc ─────────────────────────────────
 
     Something is off with the body of the `f` definition:
 
@@ -14483,7 +14623,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TOO FEW ARGS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── TOO FEW ARGS [E005] in /code/proj/Main.roc ──────────────────────────────────
 
     The `sub` function expects 2 arguments, but it got only 1:
 
@@ -14503,7 +14643,7 @@ All branches in an `if` must have the same type!
             "
         ),
         @r"
-    ── TOO FEW ARGS in /code/proj/Main.roc ─────────────────────────────────────────
+    ── TOO FEW ARGS [E005] in /code/proj/Main.roc ──────────────────────────────────
 
     The `sub` function expects 2 arguments, but it got only 1:
 
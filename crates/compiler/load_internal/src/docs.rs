@@ -7,6 +7,7 @@ use roc_module::symbol::{IdentIds, ModuleId, ModuleIds, Symbol};
 use roc_parse::ast::AssignedField;
 use roc_parse::ast::{self, ExtractSpaces, TypeHeader};
 use roc_parse::ast::{CommentOrNewline, TypeDef, ValueDef};
+use roc_region::all::Region;
 
 // Documentation generation requirements
 
@@ -40,6 +41,10 @@ pub enum DocEntry {
 pub struct DocDef {
     pub name: String,
     pub symbol: Symbol,
+    /// The whole def, in the module it is defined in.
+    pub region: Region,
+    /// Just the name of the def.
+    pub name_region: Region,
     pub type_vars: Vec<String>,
     pub type_annotation: TypeAnnotation,
     pub docs: Option<String>,
@@ -219,6 +224,8 @@ fn generate_entry_docs(
                             let doc_def = DocDef {
                                 name,
                                 symbol: Symbol::new(home, ident_id),
+                                region: defs.regions[index],
+                                name_region: loc_pattern.region,
                                 type_annotation: type_to_docs(false, loc_ann.value),
                                 type_vars: Vec::new(),
                                 docs,
@@ -241,6 +248,8 @@ fn generate_entry_docs(
                                 type_annotation: type_to_docs(false, ann_type.value),
                                 type_vars: Vec::new(),
                                 symbol: Symbol::new(home, ident_id),
+                                region: defs.regions[index],
+                                name_region: ann_pattern.region,
                                 docs,
                             };
                             doc_entries.push(DocEntry::DocDef(doc_def));
@@ -257,6 +266,8 @@ fn generate_entry_docs(
                                 type_annotation: TypeAnnotation::NoTypeAnn,
                                 type_vars: Vec::new(),
                                 symbol: Symbol::new(home, ident_id),
+                                region: defs.regions[index],
+                                name_region: pattern.region,
                                 docs,
                             };
                             doc_entries.push(DocEntry::DocDef(doc_def));
@@ -294,6 +305,8 @@ fn generate_entry_docs(
                                 type_annotation: TypeAnnotation::NoTypeAnn,
                                 type_vars: Vec::new(),
                                 symbol: Symbol::new(home, ident_id),
+                                region: defs.regions[index],
+                                name_region: loc_expr.region,
                                 docs,
                             };
                             doc_entries.push(DocEntry::DocDef(doc_def));
@@ -333,6 +346,8 @@ fn generate_entry_docs(
                         type_vars,
                         docs,
                         symbol: Symbol::new(home, ident_id),
+                        region: defs.regions[index],
+                        name_region: name.region,
                     };
                     doc_entries.push(DocEntry::DocDef(doc_def));
                 }
@@ -356,6 +371,8 @@ fn generate_entry_docs(
                         type_vars,
                         docs,
                         symbol: Symbol::new(home, ident_id),
+                        region: defs.regions[index],
+                        name_region: name.region,
                     };
                     doc_entries.push(DocEntry::DocDef(doc_def));
                 }
@@ -394,6 +411,8 @@ fn generate_entry_docs(
                         name: name.value.to_string(),
                         type_annotation: TypeAnnotation::Ability { members },
                         symbol: Symbol::new(home, ident_id),
+                        region: defs.regions[index],
+                        name_region: name.region,
                         type_vars,
                         docs,
                     };
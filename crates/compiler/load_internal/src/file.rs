@@ -1,6 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::docs::ModuleDocumentation;
+use crate::incremental::{reuse_layout_interner, Incremental, IncrementalState};
 use crate::module::{
     CheckedModule, ConstrainedModule, EntryPoint, Expectations, ExposedToHost,
     FoundSpecializationsModule, LateSpecializationsModule, LoadedModule, ModuleHeader,
@@ -37,6 +38,7 @@ use roc_module::symbol::{
     IdentIds, IdentIdsByModule, Interns, ModuleId, ModuleIds, PQModuleName, PackageModuleIds,
     PackageQualified, Symbol,
 };
+use roc_mono::borrow::BorrowInference;
 use roc_mono::ir::{
     CapturedSymbols, ExternalSpecializations, GlueLayouts, HostExposedLambdaSets, PartialProc,
     Proc, ProcLayout, Procs, ProcsBase, UpdateModeIds, UsageTrackingMap,
@@ -44,7 +46,7 @@ use roc_mono::ir::{
 use roc_mono::layout::{
     GlobalLayoutInterner, LambdaName, Layout, LayoutCache, LayoutProblem, Niche, STLayoutInterner,
 };
-use roc_mono::reset_reuse;
+use roc_mono::reset_reuse::{self, ReuseReport};
 use roc_mono::{drop_specialization, inc_dec};
 use roc_packaging::cache::RocCacheDir;
 use roc_parse::ast::{self, CommentOrNewline, ExtractSpaces, Spaced, ValueDef};
@@ -333,6 +335,16 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
+                if let Some(incremental) = state.incremental.as_mut() {
+                    let imports = available_modules.keys().copied();
+
+                    if let Some(types) =
+                        incremental.reusable_types(module_id, imports, &state.module_cache.sources)
+                    {
+                        state.cached_types.lock().insert(module_id, types);
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 let checkmate = if roc_checkmate::is_checkmate_enabled() {
                     Some(roc_checkmate::Collector::new())
@@ -603,6 +615,7 @@ enum Msg<'a> {
         solved_subs: Solved<Subs>,
         module_timing: ModuleTiming,
         toplevel_expects: ToplevelExpects,
+        toplevel_defs: VecMap<Symbol, Region>,
         expectations: Option<Expectations>,
     },
     MadeSpecializations {
@@ -617,6 +630,7 @@ enum Msg<'a> {
         module_timing: ModuleTiming,
         subs: Subs,
         expectations: Option<Expectations>,
+        symbol_regions: MutMap<Symbol, Region>,
     },
 
     /// The task is to only typecheck AND monomorphize modules
@@ -709,8 +723,11 @@ struct State<'a> {
     pub module_cache: ModuleCache<'a>,
     pub dependencies: Dependencies<'a>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    pub reuse_report: ReuseReport<'a>,
+    pub symbol_regions: MutMap<Symbol, Region>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     pub toplevel_expects: MutMap<ModuleId, ToplevelExpects>,
+    pub toplevel_defs: MutMap<ModuleId, VecMap<Symbol, Region>>,
     pub exposed_to_host: ExposedToHost,
 
     /// This is the "final" list of IdentIds, after canonicalization and constraint gen
@@ -749,6 +766,9 @@ struct State<'a> {
     // cached types (used for builtin modules, could include packages in the future too)
     cached_types: CachedTypeState,
 
+    /// Set when this load may reuse the types solved by an earlier load of the same program
+    incremental: Option<IncrementalState>,
+
     layout_interner: GlobalLayoutInterner<'a>,
 }
 
@@ -760,6 +780,7 @@ impl<'a> State<'a> {
     }
 
     fn new(
+        arena: &'a Bump,
         root_id: ModuleId,
         root_path: PathBuf,
         root_type: RootType,
@@ -771,6 +792,7 @@ impl<'a> State<'a> {
         ident_ids_by_module: SharedIdentIdsByModule,
         arc_shorthands: Arc<Mutex<MutMap<&'a str, ShorthandPath>>>,
         cached_types: MutMap<ModuleId, TypeState>,
+        incremental: Option<Incremental<'a>>,
         render: RenderTarget,
         palette: Palette,
         number_of_workers: usize,
@@ -779,6 +801,17 @@ impl<'a> State<'a> {
         let cache_dir = roc_packaging::cache::roc_cache_dir();
         let dependencies = Dependencies::new(exec_mode.goal_phase());
 
+        let (incremental, layout_interner) = match incremental {
+            None => (None, None),
+            Some(Incremental {
+                cache,
+                layout_interner,
+            }) => (
+                Some(IncrementalState::new(cache)),
+                layout_interner.map(|interner| reuse_layout_interner(arena, interner)),
+            ),
+        };
+
         Self {
             root_id,
             root_path,
@@ -793,8 +826,11 @@ impl<'a> State<'a> {
             module_cache: ModuleCache::default(),
             dependencies,
             procedures: MutMap::default(),
+            reuse_report: ReuseReport::default(),
+            symbol_regions: MutMap::default(),
             host_exposed_lambda_sets: std::vec::Vec::new(),
             toplevel_expects: MutMap::default(),
+            toplevel_defs: MutMap::default(),
             exposed_to_host: ExposedToHost::default(),
             exposed_modules: &[],
             exposed_types,
@@ -813,7 +849,9 @@ impl<'a> State<'a> {
             exec_mode,
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
             world_abilities: Default::default(),
-            layout_interner: GlobalLayoutInterner::with_capacity(128, target),
+            incremental,
+            layout_interner: layout_interner
+                .unwrap_or_else(|| GlobalLayoutInterner::with_capacity(128, target)),
         }
     }
 }
@@ -1099,6 +1137,7 @@ pub struct LoadStart<'a> {
     root_type: RootType,
     opt_platform_shorthand: Option<&'a str>,
     src_dir: PathBuf,
+    incremental: Option<Incremental<'a>>,
 }
 
 #[derive(Debug, Clone)]
@@ -1180,6 +1219,7 @@ impl<'a> LoadStart<'a> {
             root_msg: header_output.msg,
             root_type,
             opt_platform_shorthand: header_output.opt_platform_shorthand,
+            incremental: None,
         })
     }
 
@@ -1236,8 +1276,16 @@ impl<'a> LoadStart<'a> {
             root_msg,
             root_type,
             opt_platform_shorthand: opt_platform_id,
+            incremental: None,
         })
     }
+
+    /// Picks up where an earlier load of the same program left off, only solving the modules
+    /// that changed since, or that import a module whose interface did.
+    pub fn with_incremental(mut self, incremental: Incremental<'a>) -> Self {
+        self.incremental = Some(incremental);
+        self
+    }
 }
 
 fn handle_root_type<'a>(
@@ -1519,6 +1567,7 @@ pub fn load_single_threaded<'a>(
         root_type,
         src_dir,
         opt_platform_shorthand,
+        incremental,
         ..
     } = load_start;
 
@@ -1530,6 +1579,7 @@ pub fn load_single_threaded<'a>(
 
     let number_of_workers = 1;
     let mut state = State::new(
+        arena,
         root_id,
         root_path,
         root_type,
@@ -1541,6 +1591,7 @@ pub fn load_single_threaded<'a>(
         ident_ids_by_module,
         arc_shorthands,
         cached_types,
+        incremental,
         render,
         palette,
         number_of_workers,
@@ -1903,6 +1954,7 @@ fn load_multi_threaded<'a>(
         root_type,
         src_dir,
         opt_platform_shorthand,
+        incremental,
         ..
     } = load_start;
 
@@ -1929,6 +1981,7 @@ fn load_multi_threaded<'a>(
     );
 
     let mut state = State::new(
+        arena,
         root_id,
         root_path,
         root_type,
@@ -1940,6 +1993,7 @@ fn load_multi_threaded<'a>(
         ident_ids_by_module,
         arc_shorthands,
         cached_types,
+        incremental,
         render,
         palette,
         num_workers,
@@ -2181,6 +2235,16 @@ fn update<'a>(
             let home = header.module_id;
             let mut work = MutSet::default();
 
+            if let Some(incremental) = &state.incremental {
+                incremental.record_source(home, &header.module_path);
+            }
+
+            if roc_tracing::profile::is_enabled() {
+                let name =
+                    profile_module_name(&state.arc_modules.lock(), home, &header.module_path);
+                roc_tracing::profile::name_module(home, name);
+            }
+
             // Only lock shorthands if this header has packages
             if !header.packages.is_empty() {
                 let mut shorthands = state.arc_shorthands.lock();
@@ -2426,6 +2490,16 @@ fn update<'a>(
             log!("solved types for {:?}", module_id);
             module_timing.end_time = Instant::now();
 
+            if let Some(incremental) = state.incremental.as_mut() {
+                incremental.solved(
+                    module_id,
+                    &state.module_cache.sources,
+                    &solved_module,
+                    &solved_subs,
+                    &abilities_store,
+                );
+            }
+
             state
                 .module_cache
                 .type_problems
@@ -2624,16 +2698,33 @@ fn update<'a>(
             layout_cache,
             module_timing,
             toplevel_expects,
+            toplevel_defs,
             expectations,
         } => {
             log!("found specializations for {:?}", module_id);
 
             let subs = solved_subs.into_inner();
 
-            if !toplevel_expects.pure.is_empty() || !toplevel_expects.fx.is_empty() {
+            if !toplevel_expects.pure.is_empty()
+                || !toplevel_expects.fx.is_empty()
+                || !toplevel_expects.properties.is_empty()
+                || !toplevel_expects.benches.is_empty()
+            {
                 state.toplevel_expects.insert(module_id, toplevel_expects);
             }
 
+            // Only the app's own modules are reported, not builtins or the modules of packages
+            let is_app_module = module_id == state.root_id
+                || (!module_id.is_builtin()
+                    && matches!(
+                        state.module_cache.module_names.get(&module_id),
+                        Some(PQModuleName::Unqualified(_))
+                    ));
+
+            if is_app_module && !toplevel_defs.is_empty() {
+                state.toplevel_defs.insert(module_id, toplevel_defs);
+            }
+
             state
                 .module_cache
                 .top_level_thunks
@@ -2675,6 +2766,7 @@ fn update<'a>(
             module_timing,
             layout_cache,
             expectations,
+            symbol_regions,
             ..
         } => {
             debug_assert!(
@@ -2688,6 +2780,7 @@ fn update<'a>(
             let _ = layout_cache;
 
             state.procedures.extend(procedures);
+            state.symbol_regions.extend(symbol_regions);
             state
                 .host_exposed_lambda_sets
                 .extend(host_exposed_lambda_sets);
@@ -2822,6 +2915,7 @@ fn update<'a>(
                         arena,
                         &layout_interner,
                         &mut state.procedures,
+                        BorrowInference::from_env(),
                     );
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_REFCOUNT);
@@ -2840,7 +2934,7 @@ fn update<'a>(
                         ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION
                     );
 
-                    reset_reuse::insert_reset_reuse_operations(
+                    state.reuse_report = reset_reuse::insert_reset_reuse_operations(
                         arena,
                         &layout_interner,
                         module_id,
@@ -3190,7 +3284,10 @@ fn finish_specialization<'a>(
 
     let State {
         toplevel_expects,
+        toplevel_defs,
         procedures,
+        reuse_report,
+        symbol_regions,
         host_exposed_lambda_sets,
         module_cache,
         platform_data,
@@ -3227,11 +3324,14 @@ fn finish_specialization<'a>(
         interns,
         layout_interner,
         procedures,
+        reuse_report,
+        symbol_regions,
         host_exposed_lambda_sets,
         entry_point,
         sources,
         timings: state.timings,
         toplevel_expects,
+        toplevel_defs,
         glue_layouts: GlueLayouts { getters: vec![] },
         uses_prebuilt_platform,
     })
@@ -4745,55 +4845,40 @@ fn run_solve<'a>(
     let loc_dbgs = std::mem::take(&mut module.loc_dbgs);
     let module = module;
 
-    let solve_result = {
-        if module_id.is_builtin() {
-            match cached_types.lock().remove(&module_id) {
-                None => run_solve_solve(
-                    exposed_for_module,
-                    types,
-                    constraints,
-                    constraint,
-                    function_kind,
-                    pending_derives,
-                    var_store,
-                    module,
-                    derived_module,
-                    //
-                    #[cfg(debug_assertions)]
-                    checkmate,
-                ),
-                Some(TypeState {
-                    subs,
-                    exposed_vars_by_symbol,
-                    abilities,
-                    solved_implementations,
-                }) => SolveResult {
-                    solved: Solved(subs),
-                    solved_implementations,
-                    exposed_vars_by_symbol,
-                    problems: vec![],
-                    abilities_store: abilities,
+    // Builtins are solved ahead of time, and an incremental load can have the types of
+    // unchanged modules from an earlier load
+    let cached = cached_types.lock().remove(&module_id);
 
-                    #[cfg(debug_assertions)]
-                    checkmate: None,
-                },
-            }
-        } else {
-            run_solve_solve(
-                exposed_for_module,
-                types,
-                constraints,
-                constraint,
-                function_kind,
-                pending_derives,
-                var_store,
-                module,
-                derived_module,
-                //
-                #[cfg(debug_assertions)]
-                checkmate,
-            )
-        }
+    let solve_result = match cached {
+        None => run_solve_solve(
+            exposed_for_module,
+            types,
+            constraints,
+            constraint,
+            function_kind,
+            pending_derives,
+            var_store,
+            module,
+            derived_module,
+            //
+            #[cfg(debug_assertions)]
+            checkmate,
+        ),
+        Some(TypeState {
+            subs,
+            exposed_vars_by_symbol,
+            abilities,
+            solved_implementations,
+        }) => SolveResult {
+            solved: Solved(subs),
+            solved_implementations,
+            exposed_vars_by_symbol,
+            problems: vec![],
+            abilities_store: abilities,
+
+            #[cfg(debug_assertions)]
+            checkmate: None,
+        },
     };
 
     let SolveResult {
@@ -5473,6 +5558,7 @@ fn make_specializations<'a>(
         exposed_by_module,
         derived_module: &derived_module,
         struct_indexing: UsageTrackingMap::default(),
+        symbol_regions: MutMap::default(),
     };
 
     let mut procs = Procs::new_in(arena);
@@ -5506,6 +5592,7 @@ fn make_specializations<'a>(
 
     // Turn `Bytes.Decode.IdentId(238)` into `Bytes.Decode.238`, we rely on this in mono tests
     mono_env.home.register_debug_idents(mono_env.ident_ids);
+    let symbol_regions = mono_env.symbol_regions;
 
     let make_specializations_end = Instant::now();
     module_timing
@@ -5524,6 +5611,21 @@ fn make_specializations<'a>(
         expectations,
         external_specializations_requested,
         module_timing,
+        symbol_regions,
+    }
+}
+
+/// Top-level values named like `benchSort` (`bench` followed by an uppercase letter or a
+/// digit) are benchmarks. There is no other way to mark one, so when expects are built, every
+/// value named like this is exposed to the host, whether or not it was meant as a benchmark.
+/// `roc bench --help` spells out the rule.
+fn is_bench_name(name: &str) -> bool {
+    match name.strip_prefix("bench") {
+        Some(rest) => rest
+            .chars()
+            .next()
+            .map_or(false, |c| c.is_ascii_uppercase() || c.is_ascii_digit()),
+        None => false,
     }
 }
 
@@ -5548,6 +5650,7 @@ fn build_pending_specializations<'a>(
 
     let mut module_thunks = bumpalo::collections::Vec::new_in(arena);
     let mut toplevel_expects = ToplevelExpects::default();
+    let mut toplevel_defs = VecMap::default();
 
     let mut procs_base = ProcsBase {
         partial_procs: BumpMap::default(),
@@ -5576,6 +5679,7 @@ fn build_pending_specializations<'a>(
         exposed_by_module,
         derived_module: &derived_module,
         struct_indexing: UsageTrackingMap::default(),
+        symbol_regions: MutMap::default(),
     };
 
     let layout_cache_snapshot = layout_cache.snapshot();
@@ -5595,11 +5699,27 @@ fn build_pending_specializations<'a>(
         let symbol = declarations.symbols[index].value;
         let expr_var = declarations.variables[index];
 
+        let tag = declarations.declarations[index];
         let is_host_exposed = exposed_to_host.top_level_values.contains_key(&symbol);
 
+        // Like other expects, property expects are only ever called by the test runner
+        let property_region = match declarations.properties.get(&index) {
+            Some(region) if build_expects => Some(*region),
+            _ => None,
+        };
+        let is_host_exposed = is_host_exposed || property_region.is_some();
+
+        let is_bench = build_expects
+            && matches!(tag, Value)
+            && is_bench_name(mono_env.ident_ids.get_name(symbol.ident_id()).unwrap_or(""));
+        let is_host_exposed = is_host_exposed || is_bench;
+
+        if matches!(tag, Value | Function(_) | Recursive(_) | TailRecursive(_)) {
+            toplevel_defs.insert(symbol, declarations.symbols[index].region);
+        }
+
         let body = declarations.expressions[index].clone();
 
-        let tag = declarations.declarations[index];
         match tag {
             Value => {
                 // If this is an exposed symbol, we need to
@@ -5638,6 +5758,11 @@ fn build_pending_specializations<'a>(
                     );
                 }
 
+                if is_bench {
+                    let region = declarations.symbols[index].region;
+                    toplevel_expects.benches.insert(symbol, region);
+                }
+
                 match body.value {
                     roc_can::expr::Expr::RecordAccessor(accessor_data) => {
                         let fresh_record_symbol = mono_env.unique_symbol();
@@ -5664,6 +5789,7 @@ fn build_pending_specializations<'a>(
                             // This is a top-level definition, so it cannot capture anything
                             captured_symbols: CapturedSymbols::None,
                             body: body.value,
+                            body_region: body.region,
                             body_var: expr_var,
                             // This is a 0-arity thunk, so it cannot be recursive
                             is_self_recursive: false,
@@ -5718,6 +5844,10 @@ fn build_pending_specializations<'a>(
                     );
                 }
 
+                if let Some(region) = property_region {
+                    toplevel_expects.properties.insert(symbol, region);
+                }
+
                 let is_recursive = matches!(tag, Recursive(_) | TailRecursive(_));
 
                 register_toplevel_function_into_procs_base(
@@ -5800,6 +5930,7 @@ fn build_pending_specializations<'a>(
                     // This is a top-level definition, so it cannot capture anything
                     captured_symbols: CapturedSymbols::None,
                     body: body.value,
+                    body_region: body.region,
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
@@ -5868,6 +5999,7 @@ fn build_pending_specializations<'a>(
                     // This is a top-level definition, so it cannot capture anything
                     captured_symbols: CapturedSymbols::None,
                     body: body.value,
+                    body_region: body.region,
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
@@ -5940,6 +6072,7 @@ fn build_pending_specializations<'a>(
                     // This is a top-level definition, so it cannot capture anything
                     captured_symbols: CapturedSymbols::None,
                     body: body.value,
+                    body_region: body.region,
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
@@ -5973,6 +6106,7 @@ fn build_pending_specializations<'a>(
         procs_base,
         module_timing,
         toplevel_expects,
+        toplevel_defs,
         expectations,
     }
 }
@@ -6057,6 +6191,7 @@ fn load_derived_partial_procs<'a>(
             exposed_by_module,
             derived_module,
             struct_indexing: UsageTrackingMap::default(),
+            symbol_regions: MutMap::default(),
         };
 
         let partial_proc = match derived_expr {
@@ -6091,6 +6226,7 @@ fn load_derived_partial_procs<'a>(
                     // This is a top-level definition, so it cannot capture anything
                     captured_symbols: CapturedSymbols::None,
                     body: derived_expr,
+                    body_region: Region::zero(),
                     body_var: derived_expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
@@ -6114,6 +6250,41 @@ fn load_derived_partial_procs<'a>(
         load_derived_procs_end.duration_since(load_derived_procs_start);
 }
 
+/// Times a task for `--profile-compiler`, in the lane of the module it works on.
+fn profile_span(task: &BuildTask) -> Option<roc_tracing::profile::ProfileSpan> {
+    use roc_tracing::profile::{module_span, span};
+    use BuildTask::*;
+
+    match task {
+        // The module doesn't have an id until its header is read
+        LoadModule { .. } => span("load module"),
+        Parse { header, .. } => module_span("parse", header.module_id),
+        CanonicalizeAndConstrain { parsed, .. } => {
+            module_span("canonicalize and constrain", parsed.module_id)
+        }
+        Solve { module, .. } => module_span("solve", module.module_id),
+        BuildPendingSpecializations { module_id, .. } => {
+            module_span("find specializations", *module_id)
+        }
+        MakeSpecializations { module_id, .. } => module_span("make specializations", *module_id),
+    }
+}
+
+fn profile_module_name(module_ids: &PackageModuleIds, home: ModuleId, path: &Path) -> String {
+    match module_ids.get_name(home) {
+        Some(PackageQualified::Qualified(package, name)) => format!("{package}.{}", name.as_str()),
+        Some(PackageQualified::Unqualified(name)) if !name.as_str().is_empty() => {
+            name.as_str().to_string()
+        }
+        // The app module has no name of its own
+        _ => path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+    }
+}
+
 fn run_task<'a>(
     task: BuildTask<'a>,
     arena: &'a Bump,
@@ -6124,6 +6295,8 @@ fn run_task<'a>(
 ) -> Result<(), ChannelProblem> {
     use BuildTask::*;
 
+    let _profile_span = profile_span(&task);
+
     let msg_result = match task {
         LoadModule {
             module_name,
@@ -6313,6 +6486,7 @@ fn to_import_cycle_report(
     filename: PathBuf,
     render: RenderTarget,
 ) -> String {
+    use roc_reporting::code::ReportKind;
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
@@ -6355,7 +6529,7 @@ fn to_import_cycle_report(
     let report = Report {
         filename,
         doc,
-        title: "IMPORT CYCLE".to_string(),
+        title: ReportKind::ImportCycle.into(),
         severity: Severity::RuntimeError,
     };
 
@@ -6373,6 +6547,7 @@ fn to_incorrect_module_name_report<'a>(
     src: &'a [u8],
     render: RenderTarget,
 ) -> String {
+    use roc_reporting::code::ReportKind;
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
@@ -6406,7 +6581,7 @@ fn to_incorrect_module_name_report<'a>(
     let report = Report {
         filename,
         doc,
-        title: "INCORRECT MODULE NAME".to_string(),
+        title: ReportKind::IncorrectModuleName.into(),
         severity,
     };
 
@@ -6425,6 +6600,7 @@ fn to_no_platform_package_report(
     src: &[u8],
     render: RenderTarget,
 ) -> String {
+    use roc_reporting::code::ReportKind;
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
     let severity = Severity::RuntimeError;
@@ -6454,7 +6630,7 @@ fn to_no_platform_package_report(
     let report = Report {
         filename,
         doc,
-        title: "UNSPECIFIED PLATFORM".to_string(),
+        title: ReportKind::UnspecifiedPlatform.into(),
         severity,
     };
 
@@ -6473,6 +6649,7 @@ fn to_multiple_platform_packages_report(
     src: &[u8],
     render: RenderTarget,
 ) -> String {
+    use roc_reporting::code::ReportKind;
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
     let severity = Severity::RuntimeError;
@@ -6498,7 +6675,7 @@ fn to_multiple_platform_packages_report(
     let report = Report {
         filename,
         doc,
-        title: "MULTIPLE PLATFORMS".to_string(),
+        title: ReportKind::MultiplePlatforms.into(),
         severity,
     };
 
@@ -6519,6 +6696,7 @@ fn to_unrecognized_package_shorthand_report(
     available: AvailableShorthands,
     render: RenderTarget,
 ) -> String {
+    use roc_reporting::code::ReportKind;
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
@@ -6605,7 +6783,7 @@ fn to_unrecognized_package_shorthand_report(
     let report = Report {
         filename,
         doc,
-        title: "UNRECOGNIZED PACKAGE".to_string(),
+        title: ReportKind::UnrecognizedPackage.into(),
         severity,
     };
 
@@ -6664,6 +6842,7 @@ fn report_cannot_run(
     filename: PathBuf,
     platform_path: &PlatformPath,
 ) -> String {
+    use roc_reporting::code::ReportKind;
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
     use PlatformPath::*;
@@ -6688,7 +6867,7 @@ fn report_cannot_run(
                 Report {
                     filename,
                     doc,
-                    title: "NO PLATFORM".to_string(),
+                    title: ReportKind::NoPlatform.into(),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6703,7 +6882,7 @@ fn report_cannot_run(
                 Report {
                     filename,
                     doc,
-                    title: "NO PLATFORM".to_string(),
+                    title: ReportKind::NoPlatform.into(),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6718,7 +6897,7 @@ fn report_cannot_run(
                 Report {
                     filename,
                     doc,
-                    title: "NO PLATFORM".to_string(),
+                    title: ReportKind::NoPlatform.into(),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6733,7 +6912,7 @@ fn report_cannot_run(
                 Report {
                     filename,
                     doc,
-                    title: "NO PLATFORM".to_string(),
+                    title: ReportKind::NoPlatform.into(),
                     severity: Severity::RuntimeError,
                 }
             }
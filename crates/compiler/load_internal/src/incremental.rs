@@ -0,0 +1,684 @@
+//! Reusing the types solved by one load of a program in the next load of the same program,
+//! as `roc dev --watch` and `roc test --watch` do after every change.
+//!
+//! Once a module is solved, its types are kept together with a hash of its source and a hash
+//! of its interface: the types, aliases and abilities other modules can see. The next load
+//! doesn't solve a module again if its source is unchanged and every module it imports still
+//! has the interface it had back then. So an edit inside a function body only re-solves the
+//! edited module, while changing what a module exposes re-solves the modules importing it too,
+//! and so on downstream for as long as interfaces keep changing.
+//!
+//! Modules are still parsed and canonicalized on every load; canonicalizing the same source
+//! against the same interfaces produces the same variables, which the cached types rely on.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bumpalo::Bump;
+use parking_lot::Mutex;
+use roc_can::abilities::{AbilitiesStore, ResolvedImpl};
+use roc_can::module::TypeState;
+use roc_collections::{MutMap, MutSet, VecMap};
+use roc_module::ident::TagName;
+use roc_module::symbol::ModuleId;
+use roc_mono::layout::GlobalLayoutInterner;
+use roc_solve::module::{Solved, SolvedModule};
+use roc_types::num::NumericRange;
+use roc_types::subs::{
+    Content, FlatType, GetSubsSlice, LambdaSet, Subs, TagExt, UnionTags, Variable,
+};
+use roc_types::types::{self, AliasCommon, AliasVar, OptAbleType, Type, TypeExtension, Uls};
+
+pub type SharedIncrementalCache = Arc<Mutex<IncrementalCache>>;
+
+/// Types solved by earlier loads of a program, by the path of the module they belong to.
+#[derive(Debug, Default)]
+pub struct IncrementalCache {
+    modules: MutMap<PathBuf, CachedModule>,
+    /// Every source file the loads have read, for a watcher to keep an eye on
+    sources: BTreeSet<PathBuf>,
+    last_load: ReuseStats,
+}
+
+impl IncrementalCache {
+    pub fn new_shared() -> SharedIncrementalCache {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &Path> {
+        self.sources.iter().map(PathBuf::as_path)
+    }
+
+    /// How many modules the most recent load solved, and how many it could skip.
+    pub fn last_load(&self) -> ReuseStats {
+        self.last_load
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReuseStats {
+    pub reused: usize,
+    pub solved: usize,
+}
+
+#[derive(Debug)]
+struct CachedModule {
+    module_id: ModuleId,
+    source_hash: u64,
+    /// The modules it imports, by id and path, with their interface hashes when it was solved
+    imports: Vec<(ModuleId, PathBuf, u64)>,
+    /// `None` if solving it reported problems; reusing the types would lose those
+    types: Option<TypeState>,
+}
+
+/// What a load can start from, other than the source files: whatever an earlier load of
+/// the same program left behind.
+pub struct Incremental<'a> {
+    pub cache: SharedIncrementalCache,
+    /// The interner of the previous load. Its layouts are dropped before this load starts, see
+    /// [reuse_layout_interner], so it only saves allocating the interner's tables again.
+    pub layout_interner: Option<GlobalLayoutInterner<'a>>,
+}
+
+/// Drops every layout but the reserved ones from the interner of the previous load, so that the
+/// interner doesn't keep growing with each load of a watch or daemon session. Nothing refers to
+/// the layouts of the previous load anymore: the cache only keeps types.
+pub(crate) fn reuse_layout_interner<'a>(
+    arena: &'a Bump,
+    mut layout_interner: GlobalLayoutInterner<'a>,
+) -> GlobalLayoutInterner<'a> {
+    // With outstanding forks, the interner is left as it is, which is still correct.
+    let _ = layout_interner.retain(arena, std::iter::empty());
+
+    layout_interner
+}
+
+/// The bookkeeping of a single incremental load.
+#[derive(Debug)]
+pub(crate) struct IncrementalState {
+    cache: SharedIncrementalCache,
+    /// The interface hashes of the modules solved so far
+    interfaces: MutMap<ModuleId, u64>,
+    /// The (non-builtin) modules that each module being solved imports
+    imports: MutMap<ModuleId, Vec<ModuleId>>,
+    reusing: MutSet<ModuleId>,
+}
+
+impl IncrementalState {
+    pub(crate) fn new(cache: SharedIncrementalCache) -> Self {
+        cache.lock().last_load = ReuseStats::default();
+
+        Self {
+            cache,
+            interfaces: MutMap::default(),
+            imports: MutMap::default(),
+            reusing: MutSet::default(),
+        }
+    }
+
+    pub(crate) fn record_source(&self, module_id: ModuleId, path: &Path) {
+        if !module_id.is_builtin() {
+            self.cache.lock().sources.insert(path.to_path_buf());
+        }
+    }
+
+    /// Called right before a module is solved, once all its imports are. Returns its types
+    /// from an earlier load, if nothing they depend on has changed since.
+    pub(crate) fn reusable_types(
+        &mut self,
+        module_id: ModuleId,
+        imports: impl Iterator<Item = ModuleId>,
+        sources: &MutMap<ModuleId, (PathBuf, &str)>,
+    ) -> Option<TypeState> {
+        let imports: Vec<_> = imports.filter(|id| !id.is_builtin()).collect();
+
+        let types = sources.get(&module_id).and_then(|(path, src)| {
+            let cache = self.cache.lock();
+            let cached = cache.modules.get(path)?;
+
+            let unchanged = cached.module_id == module_id
+                && cached.source_hash == hash_source(src)
+                && cached.imports.len() == imports.len()
+                && cached
+                    .imports
+                    .iter()
+                    .all(|(id, import_path, interface_hash)| {
+                        imports.contains(id)
+                            && sources.get(id).map(|(path, _)| path) == Some(import_path)
+                            && self.interfaces.get(id) == Some(interface_hash)
+                    });
+
+            match unchanged {
+                true => cached.types.clone(),
+                false => None,
+            }
+        });
+
+        self.imports.insert(module_id, imports);
+
+        if types.is_some() {
+            self.reusing.insert(module_id);
+        }
+
+        types
+    }
+
+    /// Called once a module is solved, or its types were reused.
+    pub(crate) fn solved(
+        &mut self,
+        module_id: ModuleId,
+        sources: &MutMap<ModuleId, (PathBuf, &str)>,
+        solved_module: &SolvedModule,
+        solved_subs: &Solved<Subs>,
+        abilities_store: &AbilitiesStore,
+    ) {
+        if module_id.is_builtin() {
+            return;
+        }
+
+        self.interfaces.insert(
+            module_id,
+            interface_hash(module_id, solved_module, abilities_store),
+        );
+
+        let mut cache = self.cache.lock();
+
+        if self.reusing.remove(&module_id) {
+            cache.last_load.reused += 1;
+
+            return;
+        }
+
+        cache.last_load.solved += 1;
+
+        let Some((path, src)) = sources.get(&module_id) else {
+            return;
+        };
+
+        let subs = solved_subs.inner();
+
+        // Derived implementations get their symbols in the order they happen to be asked
+        // for, which can differ from one load to the next.
+        let reusable = solved_module.problems.is_empty()
+            && !subs.mentions_module(ModuleId::DERIVED_GEN)
+            && !subs.mentions_module(ModuleId::DERIVED_SYNTH);
+
+        let types = reusable.then(|| TypeState {
+            subs: subs.clone(),
+            exposed_vars_by_symbol: solved_module.exposed_vars_by_symbol.clone(),
+            abilities: abilities_store.clone(),
+            solved_implementations: solved_module.solved_implementations.clone(),
+        });
+
+        let imports = self.imports.remove(&module_id).unwrap_or_default();
+        let imports = (imports.into_iter())
+            .filter_map(|id| {
+                let (import_path, _) = sources.get(&id)?;
+                let interface_hash = *self.interfaces.get(&id)?;
+
+                Some((id, import_path.clone(), interface_hash))
+            })
+            .collect();
+
+        cache.modules.insert(
+            path.clone(),
+            CachedModule {
+                module_id,
+                source_hash: hash_source(src),
+                imports,
+                types,
+            },
+        );
+    }
+}
+
+fn hash_source(src: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    src.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+/// Hashes everything about a solved module that the modules importing it can depend on.
+///
+/// Every part is written out field by field, see [InterfaceHasher], so two loads agree on the
+/// hash exactly when they agree on the interface, and not on how it happens to be printed.
+fn interface_hash(
+    home: ModuleId,
+    solved_module: &SolvedModule,
+    abilities_store: &AbilitiesStore,
+) -> u64 {
+    let exposed = &solved_module.exposed_types;
+    let mut state = InterfaceHasher {
+        hasher: DefaultHasher::new(),
+        storage_subs: exposed.storage_subs.as_inner(),
+        module_vars: MutMap::default(),
+    };
+
+    for (symbol, var) in exposed.stored_vars_by_symbol.iter() {
+        symbol.hash(&mut state.hasher);
+        state.stored_var(*var);
+    }
+
+    // The storage subs only hold what was imported into them from the exposed types, in
+    // the order it was reached, so equal interfaces give equal storage subs.
+    for index in 0..state.storage_subs.len() {
+        // Safe, since the index is in bounds
+        let var = unsafe { Variable::from_index(index as u32) };
+        let root = state.storage_subs.get_root_key_without_compacting(var);
+
+        state.stored_var(root);
+        state.content(state.storage_subs.get_content_without_compacting(var));
+    }
+
+    let mut implementations: Vec<_> = solved_module.solved_implementations.iter().collect();
+    implementations.sort_by_key(|(key, _)| **key);
+
+    for (key, resolved) in implementations {
+        key.hash(&mut state.hasher);
+        std::mem::discriminant(resolved).hash(&mut state.hasher);
+
+        if let ResolvedImpl::Impl(specialization) = resolved {
+            specialization.symbol.hash(&mut state.hasher);
+
+            for (region, var) in specialization.specialization_lambda_sets.iter() {
+                region.hash(&mut state.hasher);
+                state.linked_var(*var, &exposed.stored_specialization_lambda_set_vars);
+            }
+        }
+    }
+
+    let mut members: Vec<_> = abilities_store
+        .root_ability_members()
+        .iter()
+        .filter(|(member, _)| member.module_id() == home)
+        .collect();
+    members.sort_by_key(|(member, _)| **member);
+
+    for (member, data) in members {
+        (member, data.parent_ability).hash(&mut state.hasher);
+        state.linked_var(data.signature_var(), &exposed.stored_ability_member_vars);
+    }
+
+    let mut aliases: Vec<_> = solved_module.aliases.iter().collect();
+    aliases.sort_by_key(|(symbol, _)| **symbol);
+
+    for (symbol, (exposed, alias)) in aliases {
+        (symbol, exposed, alias.kind).hash(&mut state.hasher);
+
+        for loc_var in alias.type_variables.iter() {
+            let AliasVar {
+                name,
+                var,
+                opt_bound_abilities,
+            } = &loc_var.value;
+
+            (name, opt_bound_abilities).hash(&mut state.hasher);
+            state.module_var(*var);
+        }
+
+        alias.lambda_set_variables.len().hash(&mut state.hasher);
+        for lambda_set in alias.lambda_set_variables.iter() {
+            state.typ(lambda_set.as_inner());
+        }
+
+        alias
+            .infer_ext_in_output_variables
+            .len()
+            .hash(&mut state.hasher);
+        for var in alias.infer_ext_in_output_variables.iter() {
+            state.module_var(*var);
+        }
+
+        state.typ(&alias.typ);
+
+        // By now the recursion variables have all been seen in the type, so this only depends
+        // on where they are in it.
+        let mut recursion_variables: Vec<_> = (alias.recursion_variables.iter())
+            .map(|var| state.module_vars.get(var).copied())
+            .collect();
+        recursion_variables.sort();
+        recursion_variables.hash(&mut state.hasher);
+    }
+
+    state.hasher.finish()
+}
+
+/// Writes the parts of an interface to a hasher, one field at a time.
+///
+/// Variables of the storage subs are written as they are, since the storage subs are numbered
+/// by the exposed types alone. Aliases and ability implementations use the module's own
+/// variables, which shift whenever a body needs more or fewer of them, so those are written
+/// as the order in which the hasher first saw them instead.
+struct InterfaceHasher<'s> {
+    hasher: DefaultHasher,
+    storage_subs: &'s Subs,
+    module_vars: MutMap<Variable, u32>,
+}
+
+impl InterfaceHasher<'_> {
+    fn stored_var(&mut self, var: Variable) {
+        var.index().hash(&mut self.hasher);
+    }
+
+    fn stored_vars(&mut self, vars: &[Variable]) {
+        vars.len().hash(&mut self.hasher);
+
+        for var in vars {
+            self.stored_var(*var);
+        }
+    }
+
+    fn module_var(&mut self, var: Variable) {
+        // The reserved variables are the same in every module
+        if (var.index() as usize) < Variable::NUM_RESERVED_VARS {
+            (false, var.index()).hash(&mut self.hasher);
+        } else {
+            let next = self.module_vars.len() as u32;
+            let number = *self.module_vars.entry(var).or_insert(next);
+
+            (true, number).hash(&mut self.hasher);
+        }
+    }
+
+    /// A variable of the module that was copied into the storage subs for the importers.
+    fn linked_var(&mut self, var: Variable, stored: &VecMap<Variable, Variable>) {
+        match stored.get(&var) {
+            Some(stored_var) => {
+                true.hash(&mut self.hasher);
+                self.stored_var(*stored_var);
+            }
+            None => {
+                false.hash(&mut self.hasher);
+                self.module_var(var);
+            }
+        }
+    }
+
+    fn content(&mut self, content: &Content) {
+        let subs = self.storage_subs;
+
+        std::mem::discriminant(content).hash(&mut self.hasher);
+
+        match content {
+            Content::FlexVar(opt_name) => {
+                opt_name.map(|name| &subs[name]).hash(&mut self.hasher);
+            }
+            Content::RigidVar(name) => {
+                subs[*name].hash(&mut self.hasher);
+            }
+            Content::FlexAbleVar(opt_name, abilities) => {
+                opt_name.map(|name| &subs[name]).hash(&mut self.hasher);
+                subs.get_subs_slice(*abilities).hash(&mut self.hasher);
+            }
+            Content::RigidAbleVar(name, abilities) => {
+                subs[*name].hash(&mut self.hasher);
+                subs.get_subs_slice(*abilities).hash(&mut self.hasher);
+            }
+            Content::RecursionVar {
+                structure,
+                opt_name,
+            } => {
+                self.stored_var(*structure);
+                opt_name.map(|name| &subs[name]).hash(&mut self.hasher);
+            }
+            Content::LambdaSet(LambdaSet {
+                solved,
+                recursion_var,
+                unspecialized,
+                ambient_function,
+            }) => {
+                solved.len().hash(&mut self.hasher);
+                for (symbol, captures) in solved.iter_from_subs(subs) {
+                    symbol.hash(&mut self.hasher);
+                    self.stored_vars(captures);
+                }
+
+                recursion_var
+                    .into_variable()
+                    .map(|var| var.index())
+                    .hash(&mut self.hasher);
+
+                unspecialized.len().hash(&mut self.hasher);
+                for Uls(var, member, region) in subs.get_subs_slice(*unspecialized) {
+                    (member, region).hash(&mut self.hasher);
+                    self.stored_var(*var);
+                }
+
+                self.stored_var(*ambient_function);
+            }
+            Content::Structure(flat_type) => self.flat_type(flat_type),
+            Content::Alias(symbol, variables, real_var, kind) => {
+                (symbol, kind).hash(&mut self.hasher);
+                (
+                    variables.type_variables_len,
+                    variables.lambda_set_variables_len,
+                )
+                    .hash(&mut self.hasher);
+                self.stored_vars(subs.get_subs_slice(variables.all_variables()));
+                self.stored_var(*real_var);
+            }
+            Content::RangedNumber(range) => self.range(range),
+            Content::ErasedLambda | Content::Error => {}
+        }
+    }
+
+    fn flat_type(&mut self, flat_type: &FlatType) {
+        let subs = self.storage_subs;
+
+        std::mem::discriminant(flat_type).hash(&mut self.hasher);
+
+        match flat_type {
+            FlatType::Apply(symbol, arguments) => {
+                symbol.hash(&mut self.hasher);
+                self.stored_vars(subs.get_subs_slice(*arguments));
+            }
+            FlatType::Func(arguments, closure, ret) => {
+                self.stored_vars(subs.get_subs_slice(*arguments));
+                self.stored_var(*closure);
+                self.stored_var(*ret);
+            }
+            FlatType::Record(fields, ext) => {
+                fields.len().hash(&mut self.hasher);
+                for (name, var, field) in fields.iter_all() {
+                    (&subs[name], &subs[field]).hash(&mut self.hasher);
+                    self.stored_var(subs[var]);
+                }
+                self.stored_var(*ext);
+            }
+            FlatType::Tuple(elems, ext) => {
+                elems.len().hash(&mut self.hasher);
+                for (index, var) in elems.iter_all() {
+                    subs[index].hash(&mut self.hasher);
+                    self.stored_var(subs[var]);
+                }
+                self.stored_var(*ext);
+            }
+            FlatType::TagUnion(tags, ext) => {
+                self.tags(tags);
+                self.tag_ext(ext);
+            }
+            FlatType::FunctionOrTagUnion(tag_names, symbols, ext) => {
+                subs.get_subs_slice(*tag_names).hash(&mut self.hasher);
+                subs.get_subs_slice(*symbols).hash(&mut self.hasher);
+                self.tag_ext(ext);
+            }
+            FlatType::RecursiveTagUnion(rec, tags, ext) => {
+                self.stored_var(*rec);
+                self.tags(tags);
+                self.tag_ext(ext);
+            }
+            FlatType::EmptyRecord | FlatType::EmptyTuple | FlatType::EmptyTagUnion => {}
+        }
+    }
+
+    fn tags(&mut self, tags: &UnionTags) {
+        tags.len().hash(&mut self.hasher);
+
+        for (tag_name, payload) in tags.iter_from_subs(self.storage_subs) {
+            tag_name.hash(&mut self.hasher);
+            self.stored_vars(payload);
+        }
+    }
+
+    fn tag_ext(&mut self, ext: &TagExt) {
+        std::mem::discriminant(ext).hash(&mut self.hasher);
+        self.stored_var(ext.var());
+    }
+
+    fn range(&mut self, range: &NumericRange) {
+        std::mem::discriminant(range).hash(&mut self.hasher);
+
+        match range {
+            NumericRange::IntAtLeastSigned(width)
+            | NumericRange::IntAtLeastEitherSign(width)
+            | NumericRange::NumAtLeastSigned(width)
+            | NumericRange::NumAtLeastEitherSign(width) => {
+                std::mem::discriminant(width).hash(&mut self.hasher)
+            }
+        }
+    }
+
+    fn typ(&mut self, typ: &Type) {
+        std::mem::discriminant(typ).hash(&mut self.hasher);
+
+        match typ {
+            Type::Function(arguments, closure, ret) => {
+                self.types(arguments);
+                self.typ(closure);
+                self.typ(ret);
+            }
+            Type::Record(fields, ext) => {
+                let mut fields: Vec<_> = fields.iter().collect();
+                fields.sort_by_key(|(name, _)| *name);
+
+                fields.len().hash(&mut self.hasher);
+                for (name, field) in fields {
+                    name.hash(&mut self.hasher);
+                    std::mem::discriminant(field).hash(&mut self.hasher);
+                    self.typ(field.as_inner());
+                }
+                self.type_ext(ext);
+            }
+            Type::Tuple(elems, ext) => {
+                let mut elems: Vec<_> = elems.iter().collect();
+                elems.sort_by_key(|(index, _)| *index);
+
+                elems.len().hash(&mut self.hasher);
+                for (index, elem) in elems {
+                    index.hash(&mut self.hasher);
+                    self.typ(elem);
+                }
+                self.type_ext(ext);
+            }
+            Type::TagUnion(tags, ext) => {
+                self.type_tags(tags);
+                self.type_ext(ext);
+            }
+            Type::FunctionOrTagUnion(tag_name, symbol, ext) => {
+                (tag_name, symbol).hash(&mut self.hasher);
+                self.type_ext(ext);
+            }
+            Type::ClosureTag {
+                name,
+                captures,
+                ambient_function,
+            } => {
+                name.hash(&mut self.hasher);
+                self.types(captures);
+                self.module_var(*ambient_function);
+            }
+            Type::UnspecializedLambdaSet {
+                unspecialized: Uls(var, member, region),
+            } => {
+                (member, region).hash(&mut self.hasher);
+                self.module_var(*var);
+            }
+            Type::DelayedAlias(AliasCommon {
+                symbol,
+                type_arguments,
+                lambda_set_variables,
+                infer_ext_in_output_types,
+            }) => {
+                symbol.hash(&mut self.hasher);
+                self.alias_arguments(type_arguments.iter().map(|loc| &loc.value));
+                self.lambda_sets(lambda_set_variables);
+                self.types(infer_ext_in_output_types);
+            }
+            Type::Alias {
+                symbol,
+                type_arguments,
+                lambda_set_variables,
+                infer_ext_in_output_types,
+                actual,
+                kind,
+            } => {
+                (symbol, kind).hash(&mut self.hasher);
+                self.alias_arguments(type_arguments.iter());
+                self.lambda_sets(lambda_set_variables);
+                self.types(infer_ext_in_output_types);
+                self.typ(actual);
+            }
+            Type::RecursiveTagUnion(rec, tags, ext) => {
+                self.module_var(*rec);
+                self.type_tags(tags);
+                self.type_ext(ext);
+            }
+            Type::Apply(symbol, arguments, _region) => {
+                symbol.hash(&mut self.hasher);
+                arguments.len().hash(&mut self.hasher);
+                for argument in arguments {
+                    self.typ(&argument.value);
+                }
+            }
+            Type::Variable(var) => self.module_var(*var),
+            Type::RangedNumber(range) => self.range(range),
+            Type::EmptyRec | Type::EmptyTagUnion | Type::Error => {}
+        }
+    }
+
+    fn types(&mut self, types: &[Type]) {
+        types.len().hash(&mut self.hasher);
+
+        for typ in types {
+            self.typ(typ);
+        }
+    }
+
+    fn type_tags(&mut self, tags: &[(TagName, Vec<Type>)]) {
+        tags.len().hash(&mut self.hasher);
+
+        for (tag_name, payload) in tags {
+            tag_name.hash(&mut self.hasher);
+            self.types(payload);
+        }
+    }
+
+    fn type_ext(&mut self, ext: &TypeExtension) {
+        std::mem::discriminant(ext).hash(&mut self.hasher);
+
+        if let TypeExtension::Open(ext, openness) = ext {
+            std::mem::discriminant(openness).hash(&mut self.hasher);
+            self.typ(ext);
+        }
+    }
+
+    fn alias_arguments<'t>(&mut self, arguments: impl ExactSizeIterator<Item = &'t OptAbleType>) {
+        arguments.len().hash(&mut self.hasher);
+
+        for OptAbleType { typ, opt_abilities } in arguments {
+            opt_abilities.hash(&mut self.hasher);
+            self.typ(typ);
+        }
+    }
+
+    fn lambda_sets(&mut self, lambda_sets: &[types::LambdaSet]) {
+        lambda_sets.len().hash(&mut self.hasher);
+
+        for lambda_set in lambda_sets {
+            self.typ(lambda_set.as_inner());
+        }
+    }
+}
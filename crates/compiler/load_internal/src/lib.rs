@@ -6,8 +6,10 @@
 use roc_module::symbol::ModuleId;
 pub mod docs;
 pub mod file;
+pub mod incremental;
 pub mod module;
 mod module_cache;
+pub mod module_graph;
 
 #[cfg(target_family = "wasm")]
 mod wasm_instant;
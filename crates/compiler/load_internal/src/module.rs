@@ -13,9 +13,11 @@ use roc_module::symbol::{
 };
 use roc_mono::ir::{GlueLayouts, HostExposedLambdaSets, LambdaSetId, Proc, ProcLayout, ProcsBase};
 use roc_mono::layout::{LayoutCache, STLayoutInterner};
+use roc_mono::reset_reuse::ReuseReport;
+use roc_mono::source_locations::SourceLocations;
 use roc_parse::ast::{CommentOrNewline, Defs, TypeAnnotation};
 use roc_parse::header::{HeaderType, PackageName};
-use roc_region::all::{Loc, Region};
+use roc_region::all::{LineColumn, LineInfo, Loc, Region};
 use roc_solve::module::Solved;
 use roc_solve_problem::TypeError;
 use roc_types::subs::{ExposedTypesStorageSubs, Subs, VarStore, Variable};
@@ -168,6 +170,10 @@ pub(crate) struct LateSpecializationsModule<'a> {
 pub struct ToplevelExpects {
     pub pure: VecMap<Symbol, Region>,
     pub fx: VecMap<Symbol, Region>,
+    /// Functions that the test runner calls with generated arguments
+    pub properties: VecMap<Symbol, Region>,
+    /// Top-level values named like `benchSort`, which `roc bench` times
+    pub benches: VecMap<Symbol, Region>,
 }
 
 #[derive(Debug)]
@@ -179,8 +185,14 @@ pub struct MonomorphizedModule<'a> {
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    /// Which heap allocations in `procedures` reuse the memory of a dropped value, and why not
+    pub reuse_report: ReuseReport<'a>,
+    /// Where the values of symbols in `procedures` were written; see [MonomorphizedModule::source_locations]
+    pub symbol_regions: MutMap<Symbol, Region>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     pub toplevel_expects: MutMap<ModuleId, ToplevelExpects>,
+    /// The top-level defs of the app's own modules, for reporting the ones that are never used
+    pub toplevel_defs: MutMap<ModuleId, VecMap<Symbol, Region>>,
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
@@ -190,6 +202,100 @@ pub struct MonomorphizedModule<'a> {
     pub glue_layouts: GlueLayouts<'a>,
 }
 
+impl<'a> MonomorphizedModule<'a> {
+    /// For pointing reports about `procedures` at the source they were lowered from.
+    pub fn source_locations(&self) -> SourceLocations {
+        SourceLocations::new(self.symbol_regions.clone(), &self.sources)
+    }
+
+    /// Remove the specializations that the host can not call, directly or indirectly.
+    pub fn remove_unreachable_procs(&mut self) {
+        let mut roots = Vec::new();
+
+        if let EntryPoint::Executable {
+            exposed_to_host, ..
+        } = self.entry_point
+        {
+            roots.extend(exposed_to_host.iter().map(|(symbol, _)| *symbol));
+        }
+
+        let exposed = &self.exposed_to_host;
+        roots.extend(exposed.top_level_values.keys().copied());
+        roots.extend(exposed.closure_types.iter().copied());
+        roots.extend(exposed.lambda_sets.iter().map(|(symbol, _)| *symbol));
+        roots.extend(exposed.getters.iter().copied());
+
+        for (lambda_name, symbol, lambda_set) in self.host_exposed_lambda_sets.iter() {
+            roots.extend([lambda_name.name(), *symbol, lambda_set.symbol]);
+        }
+
+        for expects in self.toplevel_expects.values() {
+            roots.extend(expects.pure.keys().copied());
+            roots.extend(expects.fx.keys().copied());
+            roots.extend(expects.properties.keys().copied());
+            roots.extend(expects.benches.keys().copied());
+        }
+
+        roots.extend(self.glue_layouts.getters.iter().map(|(symbol, _)| *symbol));
+
+        roc_mono::dead_code::remove_unreachable_procs(&mut self.procedures, roots);
+    }
+
+    /// List the top-level defs of the app's own modules that no procedure is specialized for.
+    /// Call this after [Self::remove_unreachable_procs], so defs that are only used by dead code
+    /// are listed too.
+    pub fn render_unused_defs(&self) -> String {
+        use std::fmt::Write;
+
+        let used: MutSet<Symbol> = self.procedures.keys().map(|(symbol, _)| *symbol).collect();
+
+        let mut modules = Vec::with_capacity(self.toplevel_defs.len());
+        let mut total = 0;
+
+        for (module_id, defs) in self.toplevel_defs.iter() {
+            let mut unused: Vec<_> = defs
+                .iter()
+                .filter(|(symbol, _)| !used.contains(symbol))
+                .collect();
+
+            if unused.is_empty() {
+                continue;
+            }
+
+            unused.sort_by_key(|(_, region)| region.start());
+            total += unused.len();
+
+            let (path, src) = &self.sources[module_id];
+            let line_info = LineInfo::new(src);
+
+            let mut block = format!("{}\n", path.display());
+
+            for (symbol, region) in unused {
+                let LineColumn { line, column } = line_info.convert_pos(region.start());
+                let name = symbol.fully_qualified(&self.interns, self.module_id);
+
+                let _ = writeln!(block, "    {}:{} `{name}`", line + 1, column + 1);
+            }
+
+            modules.push(block);
+        }
+
+        modules.sort();
+
+        let mut buf = match total {
+            1 => "1 top-level definition is never used\n".to_string(),
+            _ => format!("{total} top-level definitions are never used\n"),
+        };
+
+        for block in modules {
+            buf.push('\n');
+            buf.push_str(&block);
+        }
+
+        buf
+    }
+}
+
 #[derive(Debug)]
 pub struct ParsedModule<'a> {
     pub module_id: ModuleId,
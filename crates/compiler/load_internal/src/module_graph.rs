@@ -0,0 +1,182 @@
+//! The modules of a program and which of them import which, as `roc check --emit-deps` prints
+//! them: to visualize the architecture of a large code base, or to have CI enforce it.
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use roc_module::symbol::ModuleId;
+use serde_json::{json, Value};
+
+use crate::module::LoadedModule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepsFormat {
+    /// A Graphviz digraph
+    Dot,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleNode {
+    pub name: String,
+    pub path: PathBuf,
+    pub lines: usize,
+    pub defs: usize,
+    /// From reading the file to solving its types
+    pub build_time: Duration,
+}
+
+/// The modules with a source file, builtins excluded, sorted by name. Every edge goes from a
+/// module to one it imports, by index into `modules`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleGraph {
+    pub modules: Vec<ModuleNode>,
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl ModuleGraph {
+    pub fn from_loaded(loaded: &LoadedModule) -> Self {
+        let mut ids: Vec<(String, ModuleId)> = (loaded.sources.iter())
+            .filter(|(id, _)| !id.is_builtin())
+            .map(|(id, (path, _))| {
+                let name = loaded.interns.module_name(*id).as_str();
+
+                // The app module has no name of its own
+                let name = match name.is_empty() {
+                    true => path.file_stem().unwrap_or_default().to_string_lossy(),
+                    false => name.into(),
+                };
+
+                (name.into_owned(), *id)
+            })
+            .collect();
+        ids.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let index_of = |id: &ModuleId| ids.iter().position(|(_, other)| other == id);
+
+        let modules = (ids.iter())
+            .map(|(name, id)| {
+                let (path, src) = &loaded.sources[id];
+
+                // Only the root module's declarations aren't kept with the rest
+                let decls = (loaded.declarations_by_id.get(id))
+                    .or_else(|| Some(&loaded.typechecked.get(id)?.decls));
+
+                ModuleNode {
+                    name: name.clone(),
+                    path: path.clone(),
+                    lines: src.lines().count(),
+                    defs: decls.map_or(0, |decls| decls.len()),
+                    build_time: (loaded.timings.get(id))
+                        .map_or(Duration::ZERO, |timing| timing.total()),
+                }
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+
+        for (from, (_, id)) in ids.iter().enumerate() {
+            let imports = loaded.imports.get(id).into_iter().flatten();
+            let mut targets: Vec<usize> = imports.filter_map(index_of).collect();
+            targets.sort_unstable();
+
+            edges.extend(targets.into_iter().map(|to| (from, to)));
+        }
+
+        Self { modules, edges }
+    }
+
+    pub fn render(&self, format: DepsFormat) -> String {
+        match format {
+            DepsFormat::Dot => self.to_dot(),
+            DepsFormat::Json => format!("{}\n", self.to_json()),
+        }
+    }
+
+    /// ```dot
+    /// digraph modules {
+    ///     "Main" [label="Main\n12 lines, 3 defs, 1.4 ms"];
+    ///     "Dep" [label="Dep\n4 lines, 1 def, 0.6 ms"];
+    ///
+    ///     "Main" -> "Dep";
+    /// }
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut buf = String::from("digraph modules {\n");
+
+        for module in self.modules.iter() {
+            let ModuleNode {
+                name, lines, defs, ..
+            } = module;
+
+            writeln!(
+                buf,
+                "    {name:?} [label=\"{name}\\n{lines} {}, {defs} {}, {:.1} ms\"];",
+                plural(*lines, "line"),
+                plural(*defs, "def"),
+                millis(module.build_time),
+            )
+            .unwrap();
+        }
+
+        if !self.edges.is_empty() {
+            buf.push('\n');
+        }
+
+        for (from, to) in self.edges.iter() {
+            let from = &self.modules[*from].name;
+            let to = &self.modules[*to].name;
+
+            writeln!(buf, "    {from:?} -> {to:?};").unwrap();
+        }
+
+        buf.push_str("}\n");
+
+        buf
+    }
+
+    /// ```json
+    /// {
+    ///   "modules": [
+    ///     { "name": "Dep", "path": "Dep.roc", "lines": 4, "defs": 1, "build_time_ms": 0.6 },
+    ///     { "name": "Main", "path": "Main.roc", "lines": 12, "defs": 3, "build_time_ms": 1.4 }
+    ///   ],
+    ///   "edges": [{ "from": "Main", "to": "Dep" }]
+    /// }
+    /// ```
+    pub fn to_json(&self) -> Value {
+        let modules: Vec<_> = (self.modules.iter())
+            .map(|module| {
+                json!({
+                    "name": module.name,
+                    "path": module.path,
+                    "lines": module.lines,
+                    "defs": module.defs,
+                    "build_time_ms": millis(module.build_time),
+                })
+            })
+            .collect();
+
+        let edges: Vec<_> = (self.edges.iter())
+            .map(|(from, to)| {
+                json!({
+                    "from": self.modules[*from].name,
+                    "to": self.modules[*to].name,
+                })
+            })
+            .collect();
+
+        json!({ "modules": modules, "edges": edges })
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => noun.to_string(),
+        _ => format!("{noun}s"),
+    }
+}
@@ -21,7 +21,11 @@ use roc_load_internal::docs::DocDef;
 use roc_load_internal::file::{
     ExecutionMode, LoadConfig, LoadResult, LoadStart, LoadingProblem, Threading,
 };
+use roc_load_internal::incremental::{
+    Incremental, IncrementalCache, ReuseStats, SharedIncrementalCache,
+};
 use roc_load_internal::module::LoadedModule;
+use roc_load_internal::module_graph::ModuleGraph;
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_packaging::cache::RocCacheDir;
@@ -487,6 +491,44 @@ fn load_docs() {
     assert_eq!(expected, all_docs);
 }
 
+#[test]
+fn load_doc_regions() {
+    let subs_by_module = Default::default();
+    let loaded_module = load_fixture("no_deps", "Docs", subs_by_module);
+    let src = std::fs::read_to_string(fixtures_dir().join("no_deps").join("Docs.roc")).unwrap();
+
+    let module_docs = loaded_module
+        .docs_by_module
+        .get(&loaded_module.module_id)
+        .expect("module should have docs");
+
+    let snippet = |region: roc_region::all::Region| {
+        &src[region.start().offset as usize..region.end().offset as usize]
+    };
+
+    let regions = module_docs
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            roc_load_internal::docs::DocEntry::DocDef(doc_def) => Some(doc_def),
+            _ => None,
+        })
+        .map(|doc_def| (snippet(doc_def.name_region), snippet(doc_def.region)))
+        .collect::<Vec<_>>();
+
+    let expected = vec![
+        ("User", "User : { name : Str }"),
+        (
+            "makeUser",
+            "makeUser : Str -> User\nmakeUser = \\name ->\n    { name }",
+        ),
+        ("getName", "getName = \\a -> a.name"),
+        ("getNameExposed", "getNameExposed = getName"),
+    ];
+
+    assert_eq!(expected, regions);
+}
+
 #[test]
 fn import_alias() {
     let subs_by_module = Default::default();
@@ -705,7 +747,7 @@ fn parse_problem() {
             report,
             indoc!(
                 "
-                    ── UNFINISHED LIST in tmp/parse_problem/Main.roc ───────────────────────────────
+                    ── UNFINISHED LIST [E231] in tmp/parse_problem/Main.roc ────────────────────────
 
                     I am partway through started parsing a list, but I got stuck here:
 
@@ -913,7 +955,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
         err,
         indoc!(
             r"
-                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE in ...d_outside_defining_module/Main.roc ─
+                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE [E127] in ...de_defining_module/Main.roc ─
 
                 The unwrapped opaque type Age referenced here:
 
@@ -927,7 +969,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
 
                 Note: Opaque types can only be wrapped and unwrapped in the module they are defined in!
 
-                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE in ...d_outside_defining_module/Main.roc ─
+                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE [E127] in ...de_defining_module/Main.roc ─
 
                 The unwrapped opaque type Age referenced here:
 
@@ -941,7 +983,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
 
                 Note: Opaque types can only be wrapped and unwrapped in the module they are defined in!
 
-                ── UNUSED IMPORT in ...aque_wrapped_unwrapped_outside_defining_module/Main.roc ─
+                ── UNUSED IMPORT [E104] in ...apped_unwrapped_outside_defining_module/Main.roc ─
 
                 Age is imported but not used.
 
@@ -1028,7 +1070,7 @@ fn unused_imports() {
         err,
         indoc!(
             r"
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E104] in tmp/unused_imports/Main.roc ─────────────────────────
 
             Dep2 is imported but not used.
 
@@ -1037,7 +1079,7 @@ fn unused_imports() {
 
             Since Dep2 isn't used, you don't need to import it.
 
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E104] in tmp/unused_imports/Main.roc ─────────────────────────
 
             Dep2 is imported but not used.
 
@@ -1046,7 +1088,7 @@ fn unused_imports() {
 
             Since Dep2 isn't used, you don't need to import it.
 
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E104] in tmp/unused_imports/Main.roc ─────────────────────────
 
             Dep2 is imported but not used.
 
@@ -1055,7 +1097,7 @@ fn unused_imports() {
 
             Since Dep2 isn't used, you don't need to import it.
 
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E104] in tmp/unused_imports/Main.roc ─────────────────────────
 
             Dep1 is imported but not used.
 
@@ -1064,7 +1106,7 @@ fn unused_imports() {
 
             Since Dep1 isn't used, you don't need to import it.
 
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E104] in tmp/unused_imports/Main.roc ─────────────────────────
 
             `Dep3.Three` is not used in this module.
 
@@ -1130,7 +1172,7 @@ fn explicit_builtin_import() {
         err,
         indoc!(
             r"
-            ── EXPLICIT BUILTIN IMPORT in tmp/explicit_builtin_import/Main.roc ─────────────
+            ── EXPLICIT BUILTIN IMPORT [E106] in tmp/explicit_builtin_import/Main.roc ──────
             
             The builtin Bool was imported here:
             
@@ -1165,7 +1207,7 @@ fn explicit_builtin_import_empty_exposing() {
         err,
         indoc!(
             r"
-            ── EXPLICIT BUILTIN IMPORT in tmp/empty_exposing_builtin_import/Main.roc ───────
+            ── EXPLICIT BUILTIN IMPORT [E106] in ...empty_exposing_builtin_import/Main.roc ─
             
             The builtin Bool was imported here:
             
@@ -1204,7 +1246,7 @@ fn explicit_builtin_type_import() {
         err,
         indoc!(
             r"
-            ── EXPLICIT BUILTIN IMPORT in tmp/explicit_builtin_type_import/Main.roc ────────
+            ── EXPLICIT BUILTIN IMPORT [E106] in tmp/explicit_builtin_type_import/Main.roc ─
             
             `Dict.Dict` was imported here:
             
@@ -1254,7 +1296,7 @@ fn import_shadows_symbol() {
         err,
         indoc!(
             r"
-            ── DUPLICATE NAME in tmp/import_shadows_symbol/Main.roc ────────────────────────
+            ── DUPLICATE NAME [E118] in tmp/import_shadows_symbol/Main.roc ─────────────────
 
             This import exposes `One.one`:
 
@@ -1268,7 +1310,7 @@ fn import_shadows_symbol() {
 
             You can rename it, or use the qualified name: `One.one`
 
-            ── UNUSED IMPORT in tmp/import_shadows_symbol/Main.roc ─────────────────────────
+            ── UNUSED IMPORT [E104] in tmp/import_shadows_symbol/Main.roc ──────────────────
 
             One is imported but not used.
 
@@ -1302,7 +1344,7 @@ fn ingested_file_import_shadows_symbol() {
         err,
         indoc!(
             r#"
-            ── DUPLICATE NAME in tmp/ingested_import_shadows_symbol/Main.roc ───────────────
+            ── DUPLICATE NAME [E118] in tmp/ingested_import_shadows_symbol/Main.roc ────────
 
             The `name` name is first defined here:
 
@@ -1395,7 +1437,7 @@ fn duplicate_alias() {
         err,
         indoc!(
             r"
-            ── IMPORT NAME CONFLICT in tmp/duplicate_alias/Main.roc ────────────────────────
+            ── IMPORT NAME CONFLICT [E105] in tmp/duplicate_alias/Main.roc ─────────────────
 
             Two was imported as D:
 
@@ -1460,7 +1502,7 @@ fn alias_using_module_name() {
         err,
         indoc!(
             r"
-            ── IMPORT NAME CONFLICT in tmp/alias_using_module_name/Main.roc ────────────────
+            ── IMPORT NAME CONFLICT [E105] in tmp/alias_using_module_name/Main.roc ─────────
 
             Two was imported as One:
 
@@ -1515,7 +1557,7 @@ fn alias_using_builtin_name() {
         err,
         indoc!(
             r"
-            ── IMPORT NAME CONFLICT in tmp/alias_using_builtin_name/Main.roc ───────────────
+            ── IMPORT NAME CONFLICT [E105] in tmp/alias_using_builtin_name/Main.roc ────────
 
             BoolExtra was imported as Bool:
 
@@ -1603,7 +1645,7 @@ fn issue_2863_module_type_does_not_exist() {
                 report,
                 indoc!(
                     "
-                        ── UNRECOGNIZED NAME in tmp/issue_2863_module_type_does_not_exist/main.roc ─────
+                        ── UNRECOGNIZED NAME [E101] in ...sue_2863_module_type_does_not_exist/main.roc ─
 
                         Nothing is named `DoesNotExist` in this scope.
 
@@ -1681,7 +1723,7 @@ fn module_cyclic_import_itself() {
         err,
         indoc!(
             r"
-            ── IMPORT CYCLE in tmp/module_cyclic_import_itself/Age.roc ─────────────────────
+            ── IMPORT CYCLE [E306] in tmp/module_cyclic_import_itself/Age.roc ──────────────
 
             I can't compile Age because it depends on itself through the following
             chain of module imports:
@@ -1730,7 +1772,7 @@ fn module_cyclic_import_transitive() {
         err,
         indoc!(
             r"
-            ── IMPORT CYCLE in tmp/module_cyclic_import_transitive/Age.roc ─────────────────
+            ── IMPORT CYCLE [E306] in tmp/module_cyclic_import_transitive/Age.roc ──────────
 
             I can't compile Age because it depends on itself through the following
             chain of module imports:
@@ -1765,7 +1807,7 @@ fn non_roc_file_extension() {
 
     let expected = indoc!(
         r"
-        ── NOT A ROC FILE in tmp/non_roc_file_extension/main.md ────────────────────────
+        ── NOT A ROC FILE [E303] in tmp/non_roc_file_extension/main.md ─────────────────
 
         I expected a file with extension `.roc` or without extension.
         Instead I received a file with extension `.md`."
@@ -1795,7 +1837,7 @@ fn roc_file_no_extension() {
 
     let expected = indoc!(
         r"
-        ── NOT A ROC FILE in tmp/roc_file_no_extension/main ────────────────────────────
+        ── NOT A ROC FILE [E303] in tmp/roc_file_no_extension/main ─────────────────────
 
         I expected a file with either:
         - extension `.roc`
@@ -1810,3 +1852,258 @@ fn roc_file_no_extension() {
 
     assert_eq!(err, expected, "\n{}", err);
 }
+
+/// Writes the files to the directory, then loads its Main.roc with the given incremental cache.
+fn load_incrementally(
+    dir: &TmpDir,
+    cache: &SharedIncrementalCache,
+    files: &[(&str, &str)],
+) -> (LoadedModule, ReuseStats) {
+    for (name, src) in files {
+        std::fs::write(dir.path().join(name), src).unwrap();
+    }
+
+    let arena = Bump::new();
+    let load_start = LoadStart::from_path(
+        &arena,
+        dir.path().join("Main.roc"),
+        None,
+        RenderTarget::Generic,
+        RocCacheDir::Disallowed,
+        DEFAULT_PALETTE,
+    )
+    .unwrap()
+    .with_incremental(Incremental {
+        cache: cache.clone(),
+        layout_interner: None,
+    });
+    let load_config = LoadConfig {
+        target: TARGET,
+        function_kind: FunctionKind::LambdaSet,
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Check,
+    };
+
+    let loaded = match roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(),
+        RocCacheDir::Disallowed,
+        load_config,
+    ) {
+        Ok(LoadResult::TypeChecked(module)) => module,
+        _ => unreachable!(),
+    };
+
+    let stats = cache.lock().last_load();
+
+    (loaded, stats)
+}
+
+#[test]
+fn incremental_load_only_solves_what_changed() {
+    let dir = TmpDir::new("tmp/incremental_load_only_solves_what_changed");
+    let cache = IncrementalCache::new_shared();
+
+    let load = |dep: &str| {
+        let main = indoc!(
+            r"
+            module [greeting]
+
+            import Dep
+
+            greeting = Dep.name
+            "
+        );
+
+        load_incrementally(&dir, &cache, &[("Dep.roc", dep), ("Main.roc", main)])
+    };
+
+    let (loaded, stats) = load("module [name]\n\nname = \"Dep\"\n");
+    assert_eq!(
+        stats,
+        ReuseStats {
+            reused: 0,
+            solved: 2
+        }
+    );
+    expect_types(loaded, hashmap! { "greeting" => "Str" });
+
+    let (loaded, stats) = load("module [name]\n\nname = \"Dep\"\n");
+    assert_eq!(
+        stats,
+        ReuseStats {
+            reused: 2,
+            solved: 0
+        }
+    );
+    expect_types(loaded, hashmap! { "greeting" => "Str" });
+
+    // Same interface, so Main can keep its types
+    let (loaded, stats) = load("module [name]\n\nname = \"Changed\"\n");
+    assert_eq!(
+        stats,
+        ReuseStats {
+            reused: 1,
+            solved: 1
+        }
+    );
+    expect_types(loaded, hashmap! { "greeting" => "Str" });
+
+    let (loaded, stats) = load("module [name]\n\nname = 42u8\n");
+    assert_eq!(
+        stats,
+        ReuseStats {
+            reused: 0,
+            solved: 2
+        }
+    );
+    expect_types(loaded, hashmap! { "greeting" => "U8" });
+
+    let sources: Vec<_> = cache
+        .lock()
+        .sources()
+        .map(|path| path.file_name().unwrap().to_owned())
+        .collect();
+    assert_eq!(sources, ["Dep.roc", "Main.roc"]);
+}
+
+#[test]
+fn incremental_load_hashes_interfaces_not_bodies() {
+    let dir = TmpDir::new("tmp/incremental_load_hashes_interfaces_not_bodies");
+    let cache = IncrementalCache::new_shared();
+
+    let load = |greet: &str, id: &str| {
+        let dep = format!(
+            indoc!(
+                r"
+                module [greet, Id, id]
+
+                {}
+
+                Id := U64 implements [Eq {{ isEq: idEq }}]
+
+                idEq = \@Id a, @Id b -> a == b
+
+                {}
+                "
+            ),
+            greet, id
+        );
+        let main = indoc!(
+            r"
+            module [greeting, same]
+
+            import Dep
+
+            greeting = Dep.greet {}
+
+            same = Dep.id 1 == Dep.id 1
+            "
+        );
+
+        load_incrementally(&dir, &cache, &[("Dep.roc", &dep), ("Main.roc", main)])
+    };
+
+    let greet = "greet : {} -> Str\ngreet = \\{} -> \"Hi\"";
+    let id = "id : U64 -> Id\nid = \\n -> @Id n";
+
+    let (_, stats) = load(greet, id);
+    assert_eq!(
+        stats,
+        ReuseStats {
+            reused: 0,
+            solved: 2
+        }
+    );
+
+    // A body that needs many more type variables, ahead of the ability implementation and
+    // the alias, doesn't change the interface.
+    let (loaded, stats) = load(
+        "greet : {} -> Str\ngreet = \\{} ->\n    words = List.map [\"H\", \"i\"] \\w -> Str.concat w \"\"\n    Str.joinWith words \"\"",
+        id,
+    );
+    assert_eq!(
+        stats,
+        ReuseStats {
+            reused: 1,
+            solved: 1
+        }
+    );
+    expect_types(loaded, hashmap! { "greeting" => "Str", "same" => "Bool" });
+
+    // Changing a signature does, even though Main still type checks the same way.
+    let (loaded, stats) = load(greet, "id : U8 -> Id\nid = \\n -> @Id (Num.toU64 n)");
+    assert_eq!(
+        stats,
+        ReuseStats {
+            reused: 0,
+            solved: 2
+        }
+    );
+    expect_types(loaded, hashmap! { "greeting" => "Str", "same" => "Bool" });
+}
+
+#[test]
+fn module_graph() {
+    let modules = vec![
+        (
+            "Dep.roc",
+            indoc!(
+                r#"
+                module [name, greeting]
+
+                name = "Dep"
+
+                greeting = "Hi"
+                "#
+            ),
+        ),
+        (
+            "Other.roc",
+            indoc!(
+                r"
+                module [twice]
+
+                import Dep
+
+                twice = Str.concat Dep.name Dep.name
+                "
+            ),
+        ),
+        (
+            "Main.roc",
+            indoc!(
+                r"
+                module [both]
+
+                import Dep
+                import Other
+
+                both = Str.concat Dep.greeting Other.twice
+                "
+            ),
+        ),
+    ];
+
+    let loaded = multiple_modules("module_graph", modules).unwrap();
+    let graph = ModuleGraph::from_loaded(&loaded);
+
+    let stats: Vec<_> = (graph.modules.iter())
+        .map(|module| (module.name.as_str(), module.lines, module.defs))
+        .collect();
+    assert_eq!(stats, [("Dep", 6, 2), ("Main", 7, 1), ("Other", 6, 1)]);
+    assert_eq!(graph.edges, [(1, 0), (1, 2), (2, 0)]);
+
+    let edges = &graph.to_json()["edges"];
+    assert_eq!(edges[0], serde_json::json!({ "from": "Main", "to": "Dep" }));
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph modules {\n    \"Dep\" [label=\"Dep\\n6 lines, 2 defs, "));
+    assert!(dot.ends_with(
+        "\n\n    \"Main\" -> \"Dep\";\n    \"Main\" -> \"Other\";\n    \"Other\" -> \"Dep\";\n}\n"
+    ));
+}
@@ -1,9 +1,13 @@
+use strum_macros::EnumString;
+
 use crate::symbol::Symbol;
 
 /// Low-level operations that get translated directly into e.g. LLVM instructions.
 /// These are always wrapped when exposed to end users, and can only make it
 /// into an Expr when added directly by can::builtins
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+///
+/// Parsing from a string goes by variant name, i.e. the way the [Debug] impl prints an op.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString)]
 pub enum LowLevel {
     StrConcat,
     StrJoinWith,
@@ -162,6 +162,18 @@ impl Symbol {
         u64::from_ne_bytes(self.to_ne_bytes())
     }
 
+    /// The inverse of [Symbol::as_u64]. Returns `None` if the module id part is zero.
+    pub fn from_u64(value: u64) -> Option<Self> {
+        let bytes = value.to_ne_bytes();
+        let ident_id = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let module_id = u32::from_ne_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+
+        Some(Symbol {
+            ident_id,
+            module_id: NonZeroU32::new(module_id)?,
+        })
+    }
+
     pub fn fully_qualified(self, interns: &Interns, home: ModuleId) -> ModuleName {
         let module_id = self.module_id();
 
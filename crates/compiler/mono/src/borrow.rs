@@ -33,6 +33,16 @@ impl BorrowSignature {
         Self(len as _)
     }
 
+    fn owned(len: usize) -> Self {
+        let mut signature = BorrowSignature::new(len);
+
+        for i in 0..len {
+            signature.set(i, Ownership::Owned);
+        }
+
+        signature
+    }
+
     fn from_layouts<'a>(
         interner: &impl LayoutInterner<'a>,
         layouts: impl ExactSizeIterator<Item = &'a InLayout<'a>>,
@@ -95,6 +105,28 @@ impl std::ops::Index<usize> for BorrowSignature {
     }
 }
 
+/// Whether to infer which arguments a procedure only borrows.
+///
+/// A borrowed argument is neither incremented by the caller nor decremented by the callee, which
+/// removes the refcount updates around most calls that only read a list or string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorrowInference {
+    Enabled,
+    /// Every argument is passed owned.
+    Disabled,
+}
+
+impl BorrowInference {
+    /// Disabled only by the `ROC_NO_BORROW_INFERENCE` debug flag, so always enabled in release builds.
+    pub fn from_env() -> Self {
+        if roc_debug_flags::dbg_set!(roc_debug_flags::ROC_NO_BORROW_INFERENCE) {
+            BorrowInference::Disabled
+        } else {
+            BorrowInference::Enabled
+        }
+    }
+}
+
 pub(crate) struct BorrowSignatures<'a> {
     pub(crate) procs: MutMap<(Symbol, ProcLayout<'a>), BorrowSignature>,
 }
@@ -103,7 +135,20 @@ pub(crate) fn infer_borrow_signatures<'a>(
     arena: &'a Bump,
     interner: &impl LayoutInterner<'a>,
     procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    borrow_inference: BorrowInference,
 ) -> BorrowSignatures<'a> {
+    if borrow_inference == BorrowInference::Disabled {
+        return BorrowSignatures {
+            procs: procs
+                .values()
+                .map(|proc| {
+                    let key = (proc.name.name(), proc.proc_layout(arena));
+                    (key, BorrowSignature::owned(proc.args.len()))
+                })
+                .collect(),
+        };
+    }
+
     let mut borrow_signatures: BorrowSignatures = BorrowSignatures {
         procs: procs
             .iter()
@@ -0,0 +1,698 @@
+//! Evaluates calls whose arguments are all constants at compile time, replacing them with literals.
+//!
+//! This covers arithmetic and comparisons on numbers, boolean logic, `Str.concat` and
+//! `Num.toStr` of literals, and `List.len` of list literals. Number literals used by list literals
+//! are moved into the list literal, so that backends can store the whole list as a constant.
+//! Literals that are no longer used afterwards are removed.
+//!
+//! The pass runs after refcounting, so it only removes uses of values that are not refcounted
+//! (numbers and bools) or that live in static memory (string literals). Anything that would crash
+//! at runtime, like an integer overflow, is left for the program to crash on.
+
+use bumpalo::collections::{String, Vec};
+use bumpalo::Bump;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_collections::MutMap;
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::Symbol;
+use roc_std::RocDec;
+
+use crate::ir::{CallType, Expr, ListLiteralElement, Literal, ModifyRc, Proc, ProcLayout, Stmt};
+use crate::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner};
+
+/// How much work we do for each procedure: every folded call costs 1, plus the length of any
+/// string it creates. This bounds both compile time and the size of the literals we create, for
+/// example when a string is concatenated with itself over and over.
+const FUEL_PER_PROC: usize = 1 << 16;
+
+pub fn fold_constants<'a>(
+    arena: &'a Bump,
+    interner: &STLayoutInterner<'a>,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    for proc in procs.values_mut() {
+        let mut env = Env {
+            arena,
+            interner,
+            constants: MutMap::default(),
+            fuel: FUEL_PER_PROC,
+            folded: false,
+        };
+
+        let body = env.fold_stmt(&proc.body);
+
+        if env.folded {
+            let mut uses = MutMap::default();
+            count_uses(body, &mut uses);
+
+            proc.body = remove_unused_constants(arena, body, &uses).clone();
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Constant<'a> {
+    Literal(Literal<'a>, InLayout<'a>),
+    /// A list literal with this many elements
+    List(usize),
+}
+
+#[derive(Clone, Copy)]
+enum Number {
+    Int(IntWidth, i128),
+    Float(FloatWidth, f64),
+    Dec(RocDec),
+}
+
+struct Env<'a, 'i> {
+    arena: &'a Bump,
+    interner: &'i STLayoutInterner<'a>,
+    constants: MutMap<Symbol, Constant<'a>>,
+    fuel: usize,
+    folded: bool,
+}
+
+impl<'a, 'i> Env<'a, 'i> {
+    fn fold_stmt(&mut self, stmt: &Stmt<'a>) -> &'a Stmt<'a> {
+        // Let-chains can be very long, so we walk them with a loop rather than recursion
+        let mut lets = std::vec::Vec::new();
+        let mut tail = stmt;
+
+        while let Stmt::Let(symbol, expr, layout, continuation) = tail {
+            lets.push((*symbol, self.fold_expr(*symbol, expr, *layout), *layout));
+            tail = continuation;
+        }
+
+        let mut result = self.fold_tail(tail);
+
+        for (symbol, expr, layout) in lets.into_iter().rev() {
+            result = self.arena.alloc(Stmt::Let(symbol, expr, layout, result));
+        }
+
+        result
+    }
+
+    fn fold_tail(&mut self, stmt: &Stmt<'a>) -> &'a Stmt<'a> {
+        let arena = self.arena;
+
+        let new_stmt = match stmt {
+            Stmt::Let(..) => return self.fold_stmt(stmt),
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let branches = Vec::from_iter_in(
+                    branches.iter().map(|(id, info, branch)| {
+                        (*id, info.clone(), self.fold_stmt(branch).clone())
+                    }),
+                    arena,
+                );
+
+                Stmt::Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches: branches.into_bump_slice(),
+                    default_branch: (default_branch.0.clone(), self.fold_stmt(default_branch.1)),
+                    ret_layout: *ret_layout,
+                }
+            }
+            Stmt::Refcounting(modify, continuation) => {
+                Stmt::Refcounting(*modify, self.fold_stmt(continuation))
+            }
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::Expect {
+                condition: *condition,
+                region: *region,
+                lookups,
+                variables,
+                remainder: self.fold_stmt(remainder),
+            },
+            Stmt::ExpectFx {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => Stmt::ExpectFx {
+                condition: *condition,
+                region: *region,
+                lookups,
+                variables,
+                remainder: self.fold_stmt(remainder),
+            },
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable,
+                remainder,
+            } => Stmt::Dbg {
+                source_location,
+                source,
+                symbol: *symbol,
+                variable: *variable,
+                remainder: self.fold_stmt(remainder),
+            },
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => Stmt::Join {
+                id: *id,
+                parameters,
+                body: self.fold_stmt(body),
+                remainder: self.fold_stmt(remainder),
+            },
+            Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => stmt.clone(),
+        };
+
+        arena.alloc(new_stmt)
+    }
+
+    fn fold_expr(&mut self, symbol: Symbol, expr: &Expr<'a>, layout: InLayout<'a>) -> Expr<'a> {
+        match expr {
+            Expr::Literal(literal) => {
+                self.constants
+                    .insert(symbol, Constant::Literal(*literal, layout));
+
+                expr.clone()
+            }
+            Expr::EmptyArray => {
+                self.constants.insert(symbol, Constant::List(0));
+
+                expr.clone()
+            }
+            Expr::Array { elem_layout, elems } => {
+                self.constants.insert(symbol, Constant::List(elems.len()));
+
+                let inlined = Vec::from_iter_in(
+                    elems.iter().map(|elem| match elem {
+                        ListLiteralElement::Symbol(elem_symbol) => {
+                            match self.constants.get(elem_symbol) {
+                                Some(Constant::Literal(literal, _)) if is_number(literal) => {
+                                    ListLiteralElement::Literal(*literal)
+                                }
+                                _ => *elem,
+                            }
+                        }
+                        ListLiteralElement::Literal(_) => *elem,
+                    }),
+                    self.arena,
+                );
+
+                if inlined.as_slice() == *elems {
+                    expr.clone()
+                } else {
+                    self.folded = true;
+
+                    Expr::Array {
+                        elem_layout: *elem_layout,
+                        elems: inlined.into_bump_slice(),
+                    }
+                }
+            }
+            Expr::Call(call) if self.fuel > 0 => {
+                let op = match call.call_type.clone().replace_lowlevel_wrapper() {
+                    CallType::LowLevel { op, .. } => op,
+                    _ => return expr.clone(),
+                };
+
+                match self.eval(op, call.arguments, layout) {
+                    Some(literal) => {
+                        self.folded = true;
+                        self.fuel = self.fuel.saturating_sub(match literal {
+                            Literal::Str(string) => 1 + string.len(),
+                            _ => 1,
+                        });
+                        self.constants
+                            .insert(symbol, Constant::Literal(literal, layout));
+
+                        Expr::Literal(literal)
+                    }
+                    None => expr.clone(),
+                }
+            }
+            _ => expr.clone(),
+        }
+    }
+
+    fn eval(
+        &self,
+        op: LowLevel,
+        arguments: &[Symbol],
+        ret_layout: InLayout<'a>,
+    ) -> Option<Literal<'a>> {
+        use LowLevel::*;
+
+        match (op, arguments) {
+            (NumAdd | NumAddWrap | NumSub | NumSubWrap | NumMul | NumMulWrap, [a, b]) => {
+                let answer = match (self.number(*a)?, self.number(*b)?) {
+                    (Number::Int(width, a), Number::Int(_, b)) => {
+                        let answer = match op {
+                            NumAdd => a.checked_add(b).and_then(|n| fit(width, n)),
+                            NumSub => a.checked_sub(b).and_then(|n| fit(width, n)),
+                            NumMul => a.checked_mul(b).and_then(|n| fit(width, n)),
+                            NumAddWrap => Some(wrap(width, a.wrapping_add(b))),
+                            NumSubWrap => Some(wrap(width, a.wrapping_sub(b))),
+                            _ => Some(wrap(width, a.wrapping_mul(b))),
+                        };
+
+                        Number::Int(width, answer?)
+                    }
+                    (Number::Float(width, a), Number::Float(_, b)) => {
+                        let answer = match width {
+                            FloatWidth::F32 => {
+                                let (a, b) = (a as f32, b as f32);
+                                (match op {
+                                    NumAdd => a + b,
+                                    NumSub => a - b,
+                                    _ => a * b,
+                                }) as f64
+                            }
+                            FloatWidth::F64 => match op {
+                                NumAdd => a + b,
+                                NumSub => a - b,
+                                _ => a * b,
+                            },
+                        };
+
+                        Number::Float(width, answer)
+                    }
+                    (Number::Dec(a), Number::Dec(b)) => Number::Dec(match op {
+                        NumAdd => a.checked_add(b)?,
+                        NumSub => a.checked_sub(b)?,
+                        _ => a.checked_mul(b)?,
+                    }),
+                    _ => return None,
+                };
+
+                number_literal(answer)
+            }
+            (NumDivFrac, [a, b]) => match (self.number(*a)?, self.number(*b)?) {
+                (Number::Dec(a), Number::Dec(b)) => {
+                    Some(Literal::Decimal(a.checked_div(b)?.to_ne_bytes()))
+                }
+                (Number::Float(FloatWidth::F64, a), Number::Float(_, b)) => {
+                    number_literal(Number::Float(FloatWidth::F64, a / b))
+                }
+                (Number::Float(FloatWidth::F32, a), Number::Float(_, b)) => {
+                    number_literal(Number::Float(FloatWidth::F32, (a as f32 / b as f32) as f64))
+                }
+                _ => None,
+            },
+            (NumLt | NumLte | NumGt | NumGte, [a, b]) => {
+                let ordering = match (self.number(*a)?, self.number(*b)?) {
+                    (Number::Int(_, a), Number::Int(_, b)) => a.cmp(&b),
+                    (Number::Float(_, a), Number::Float(_, b)) => a.partial_cmp(&b)?,
+                    (Number::Dec(a), Number::Dec(b)) => dec_bits(a).cmp(&dec_bits(b)),
+                    _ => return None,
+                };
+
+                Some(Literal::Bool(match op {
+                    NumLt => ordering.is_lt(),
+                    NumLte => ordering.is_le(),
+                    NumGt => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                }))
+            }
+            (Eq | NotEq, [a, b]) => {
+                let equal = match (self.literal(*a)?, self.literal(*b)?) {
+                    (Literal::Int(a), Literal::Int(b)) => a == b,
+                    (Literal::U128(a), Literal::U128(b)) => a == b,
+                    (Literal::Decimal(a), Literal::Decimal(b)) => a == b,
+                    (Literal::Str(a), Literal::Str(b)) => a == b,
+                    (Literal::Bool(a), Literal::Bool(b)) => a == b,
+                    (Literal::Byte(a), Literal::Byte(b)) => a == b,
+                    _ => return None,
+                };
+
+                Some(Literal::Bool(equal == (op == Eq)))
+            }
+            (And | Or, [a, b]) => match (self.literal(*a)?, self.literal(*b)?) {
+                (Literal::Bool(a), Literal::Bool(b)) => {
+                    Some(Literal::Bool(if op == And { a && b } else { a || b }))
+                }
+                _ => None,
+            },
+            (Not, [a]) => match self.literal(*a)? {
+                Literal::Bool(a) => Some(Literal::Bool(!a)),
+                _ => None,
+            },
+            (StrConcat, [a, b]) => match (self.literal(*a)?, self.literal(*b)?) {
+                (Literal::Str(a), Literal::Str(b)) if a.len() + b.len() <= self.fuel => {
+                    let mut string = String::with_capacity_in(a.len() + b.len(), self.arena);
+                    string.push_str(a);
+                    string.push_str(b);
+
+                    Some(Literal::Str(string.into_bump_str()))
+                }
+                _ => None,
+            },
+            (NumToStr | StrFromInt, [a]) => match self.number(*a)? {
+                Number::Int(_, n) => Some(Literal::Str(
+                    String::from_str_in(&n.to_string(), self.arena).into_bump_str(),
+                )),
+                _ => None,
+            },
+            (ListLenU64 | ListLenUsize, [list]) => match self.constants.get(list)? {
+                Constant::List(len) => {
+                    let width = self.int_width(ret_layout)?;
+
+                    number_literal(Number::Int(width, *len as i128))
+                }
+                Constant::Literal(..) => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn literal(&self, symbol: Symbol) -> Option<Literal<'a>> {
+        match self.constants.get(&symbol)? {
+            Constant::Literal(literal, _) => Some(*literal),
+            Constant::List(_) => None,
+        }
+    }
+
+    fn number(&self, symbol: Symbol) -> Option<Number> {
+        let Constant::Literal(literal, layout) = self.constants.get(&symbol)? else {
+            return None;
+        };
+
+        match (literal, self.interner.get_repr(*layout)) {
+            // We leave U128 alone, since its values don't all fit in an i128
+            (Literal::Int(bytes), LayoutRepr::Builtin(Builtin::Int(width)))
+                if width != IntWidth::U128 =>
+            {
+                Some(Number::Int(width, i128::from_ne_bytes(*bytes)))
+            }
+            (Literal::Float(f), LayoutRepr::Builtin(Builtin::Float(width))) => {
+                Some(Number::Float(width, *f))
+            }
+            (Literal::Decimal(bytes), LayoutRepr::Builtin(Builtin::Decimal)) => {
+                Some(Number::Dec(RocDec::from_ne_bytes(*bytes)))
+            }
+            _ => None,
+        }
+    }
+
+    fn int_width(&self, layout: InLayout<'a>) -> Option<IntWidth> {
+        match self.interner.get_repr(layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) if width != IntWidth::U128 => Some(width),
+            _ => None,
+        }
+    }
+}
+
+fn is_number(literal: &Literal) -> bool {
+    matches!(
+        literal,
+        Literal::Int(_) | Literal::U128(_) | Literal::Float(_) | Literal::Decimal(_)
+    )
+}
+
+/// Floats that aren't finite are left for the program to compute, so that every backend's
+/// NaN and infinity handling still applies.
+fn number_literal<'a>(number: Number) -> Option<Literal<'a>> {
+    match number {
+        Number::Int(_, n) => Some(Literal::Int(n.to_ne_bytes())),
+        Number::Float(_, f) if f.is_finite() => Some(Literal::Float(f)),
+        Number::Float(..) => None,
+        Number::Dec(dec) => Some(Literal::Decimal(dec.to_ne_bytes())),
+    }
+}
+
+fn dec_bits(dec: RocDec) -> i128 {
+    i128::from_ne_bytes(dec.to_ne_bytes())
+}
+
+fn int_bounds(width: IntWidth) -> (i128, i128) {
+    let bits = width.stack_size() * 8;
+
+    match (width.is_signed(), bits) {
+        (true, 128) => (i128::MIN, i128::MAX),
+        (true, _) => (-(1 << (bits - 1)), (1 << (bits - 1)) - 1),
+        (false, _) => (0, (1 << bits) - 1),
+    }
+}
+
+/// `None` if `n` does not fit in `width`
+fn fit(width: IntWidth, n: i128) -> Option<i128> {
+    let (min, max) = int_bounds(width);
+
+    (min..=max).contains(&n).then_some(n)
+}
+
+/// Wraps `n` around to fit in `width`, which must not be `U128`
+fn wrap(width: IntWidth, n: i128) -> i128 {
+    let bits = width.stack_size() * 8;
+
+    if bits == 128 {
+        return n;
+    }
+
+    let unsigned = n & ((1 << bits) - 1);
+
+    if width.is_signed() && unsigned >> (bits - 1) == 1 {
+        unsigned - (1 << bits)
+    } else {
+        unsigned
+    }
+}
+
+fn count_uses(stmt: &Stmt<'_>, uses: &mut MutMap<Symbol, usize>) {
+    let mut add = |symbol: &Symbol| *uses.entry(*symbol).or_insert(0) += 1;
+    let mut stack = vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Let(_, expr, _, continuation) => {
+                expr_symbols(expr, &mut add);
+                stack.push(continuation);
+            }
+            Stmt::Switch {
+                cond_symbol,
+                branches,
+                default_branch,
+                ..
+            } => {
+                add(cond_symbol);
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Ret(symbol) | Stmt::Crash(symbol, _) => add(symbol),
+            Stmt::Refcounting(modify, continuation) => {
+                match modify {
+                    ModifyRc::Inc(symbol, _)
+                    | ModifyRc::Dec(symbol)
+                    | ModifyRc::DecRef(symbol)
+                    | ModifyRc::Free(symbol) => add(symbol),
+                }
+                stack.push(continuation);
+            }
+            Stmt::Expect {
+                condition,
+                lookups,
+                remainder,
+                ..
+            }
+            | Stmt::ExpectFx {
+                condition,
+                lookups,
+                remainder,
+                ..
+            } => {
+                add(condition);
+                lookups.iter().for_each(&mut add);
+                stack.push(remainder);
+            }
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                add(symbol);
+                stack.push(remainder);
+            }
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Jump(_, arguments) => arguments.iter().for_each(&mut add),
+        }
+    }
+}
+
+fn expr_symbols(expr: &Expr<'_>, add: &mut impl FnMut(&Symbol)) {
+    match expr {
+        Expr::Call(call) => {
+            call.arguments.iter().for_each(&mut *add);
+
+            match &call.call_type {
+                CallType::ByPointer { pointer, .. } => add(pointer),
+                CallType::HigherOrder(higher_order) => {
+                    add(&higher_order.passed_function.captured_environment)
+                }
+                CallType::ByName { .. } | CallType::Foreign { .. } | CallType::LowLevel { .. } => {}
+            }
+        }
+        Expr::Tag {
+            arguments, reuse, ..
+        } => {
+            arguments.iter().for_each(&mut *add);
+
+            if let Some(reuse) = reuse {
+                add(&reuse.symbol);
+            }
+        }
+        Expr::Struct(fields) => fields.iter().for_each(add),
+        Expr::StructAtIndex { structure, .. }
+        | Expr::GetTagId { structure, .. }
+        | Expr::UnionAtIndex { structure, .. }
+        | Expr::GetElementPointer { structure, .. } => add(structure),
+        Expr::Array { elems, .. } => elems
+            .iter()
+            .filter_map(ListLiteralElement::to_symbol)
+            .for_each(|symbol| add(&symbol)),
+        Expr::ErasedMake { value, callee } => {
+            if let Some(value) = value {
+                add(value);
+            }
+            add(callee);
+        }
+        Expr::ErasedLoad { symbol, .. }
+        | Expr::Reset { symbol, .. }
+        | Expr::ResetRef { symbol, .. } => add(symbol),
+        Expr::Alloca { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                add(initializer);
+            }
+        }
+        Expr::Literal(_)
+        | Expr::NullPointer
+        | Expr::EmptyArray
+        | Expr::FunctionPointer { .. }
+        | Expr::RuntimeErrorFunction(_) => {}
+    }
+}
+
+/// Removes the literals that nothing uses anymore. Only the let-chains are rebuilt, since that is
+/// where literals are defined.
+fn remove_unused_constants<'a>(
+    arena: &'a Bump,
+    stmt: &Stmt<'a>,
+    uses: &MutMap<Symbol, usize>,
+) -> &'a Stmt<'a> {
+    let is_unused = |symbol: &Symbol| !uses.contains_key(symbol);
+
+    let mut lets = std::vec::Vec::new();
+    let mut tail = stmt;
+
+    while let Stmt::Let(symbol, expr, layout, continuation) = tail {
+        if !(is_unused(symbol) && matches!(expr, Expr::Literal(_))) {
+            lets.push((*symbol, expr, *layout));
+        }
+        tail = continuation;
+    }
+
+    let new_tail = match tail {
+        Stmt::Let(..) => unreachable!(),
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => arena.alloc(Stmt::Switch {
+            cond_symbol: *cond_symbol,
+            cond_layout: *cond_layout,
+            branches: Vec::from_iter_in(
+                branches.iter().map(|(id, info, branch)| {
+                    (
+                        *id,
+                        info.clone(),
+                        remove_unused_constants(arena, branch, uses).clone(),
+                    )
+                }),
+                arena,
+            )
+            .into_bump_slice(),
+            default_branch: (
+                default_branch.0.clone(),
+                remove_unused_constants(arena, default_branch.1, uses),
+            ),
+            ret_layout: *ret_layout,
+        }),
+        Stmt::Refcounting(modify, continuation) => arena.alloc(Stmt::Refcounting(
+            *modify,
+            remove_unused_constants(arena, continuation, uses),
+        )),
+        Stmt::Expect {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => arena.alloc(Stmt::Expect {
+            condition: *condition,
+            region: *region,
+            lookups,
+            variables,
+            remainder: remove_unused_constants(arena, remainder, uses),
+        }),
+        Stmt::ExpectFx {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => arena.alloc(Stmt::ExpectFx {
+            condition: *condition,
+            region: *region,
+            lookups,
+            variables,
+            remainder: remove_unused_constants(arena, remainder, uses),
+        }),
+        Stmt::Dbg {
+            source_location,
+            source,
+            symbol,
+            variable,
+            remainder,
+        } => arena.alloc(Stmt::Dbg {
+            source_location,
+            source,
+            symbol: *symbol,
+            variable: *variable,
+            remainder: remove_unused_constants(arena, remainder, uses),
+        }),
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => arena.alloc(Stmt::Join {
+            id: *id,
+            parameters,
+            body: remove_unused_constants(arena, body, uses),
+            remainder: remove_unused_constants(arena, remainder, uses),
+        }),
+        Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => arena.alloc(tail.clone()),
+    };
+
+    let mut result: &'a Stmt<'a> = new_tail;
+
+    for (symbol, expr, layout) in lets.into_iter().rev() {
+        result = arena.alloc(Stmt::Let(symbol, expr.clone(), layout, result));
+    }
+
+    result
+}
@@ -0,0 +1,160 @@
+//! Coverage points for `roc test --coverage`.
+//!
+//! While a backend generates code, it asks for a counter at the entry of every proc and at the
+//! start of every branch of a `Switch`, and emits an increment of that counter. Each point
+//! remembers where in the source it is, so that the counters can be mapped back to the procs and
+//! branches the tests never ran.
+//!
+//! Builtins aren't instrumented, and neither are branches that can't be traced back to the source,
+//! such as the ones the IR makes up for a decision tree.
+
+use roc_collections::MutMap;
+use roc_module::symbol::Symbol;
+
+use crate::ir::Stmt;
+use crate::source_locations::{SourceLocation, SourceLocations};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CoverageKind {
+    ProcEntry,
+    Branch,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoveragePoint {
+    pub kind: CoverageKind,
+    pub proc_name: Symbol,
+    pub location: SourceLocation,
+}
+
+#[derive(Debug)]
+pub struct Coverage {
+    source_locations: SourceLocations,
+    /// The point of each counter, by index
+    points: Vec<CoveragePoint>,
+    /// The proc the backend is generating code for, if it is instrumented
+    current_proc: Option<Symbol>,
+}
+
+impl Coverage {
+    pub fn new(source_locations: SourceLocations) -> Self {
+        Self {
+            source_locations,
+            points: Vec::new(),
+            current_proc: None,
+        }
+    }
+
+    /// The number of counters handed out
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Starts on the body of a proc. Returns the counter for entering it, unless the proc
+    /// isn't instrumented.
+    pub fn proc_entry(&mut self, proc_name: Symbol) -> Option<u32> {
+        self.current_proc = None;
+
+        if proc_name.is_builtin() {
+            return None;
+        }
+
+        let location = self.source_locations.locate_first([proc_name])?;
+        self.current_proc = Some(proc_name);
+
+        Some(self.add_point(CoverageKind::ProcEntry, proc_name, location))
+    }
+
+    /// Returns the counter for taking `branch` of a `Switch` in the current proc, unless the
+    /// proc isn't instrumented or the branch can't be traced back to the source.
+    pub fn branch(&mut self, branch: &Stmt) -> Option<u32> {
+        let proc_name = self.current_proc?;
+        let location = self.source_locations.locate_first(branch_symbols(branch))?;
+
+        Some(self.add_point(CoverageKind::Branch, proc_name, location))
+    }
+
+    fn add_point(
+        &mut self,
+        kind: CoverageKind,
+        proc_name: Symbol,
+        location: SourceLocation,
+    ) -> u32 {
+        let counter = self.points.len() as u32;
+        self.points.push(CoveragePoint {
+            kind,
+            proc_name,
+            location,
+        });
+
+        counter
+    }
+
+    /// Sums up the final values of the counters. A point that got several counters, e.g. a proc
+    /// with several specializations, ran if any of them is nonzero.
+    pub fn summarize(&self, counters: &[u64]) -> CoverageSummary<'_> {
+        debug_assert_eq!(counters.len(), self.points.len());
+
+        let mut ran: MutMap<(CoverageKind, &SourceLocation), (&CoveragePoint, bool)> =
+            MutMap::default();
+
+        for (point, count) in self.points.iter().zip(counters) {
+            let (_, point_ran) = ran
+                .entry((point.kind, &point.location))
+                .or_insert((point, false));
+            *point_ran |= *count > 0;
+        }
+
+        let points = ran.len();
+        let mut missed: Vec<&CoveragePoint> = ran
+            .into_values()
+            .filter_map(|(point, point_ran)| (!point_ran).then_some(point))
+            .collect();
+        missed.sort_by(|a, b| (&a.location, a.kind).cmp(&(&b.location, b.kind)));
+
+        CoverageSummary { points, missed }
+    }
+}
+
+#[derive(Debug)]
+pub struct CoverageSummary<'a> {
+    /// The number of distinct points
+    pub points: usize,
+    /// The points that never ran, in source order
+    pub missed: Vec<&'a CoveragePoint>,
+}
+
+/// The symbols a branch binds before it returns or branches again. The value it returns comes
+/// first, because lowering a `when` or an `if` records the region of the whole branch for it.
+fn branch_symbols(mut stmt: &Stmt) -> Vec<Symbol> {
+    let mut bound = Vec::new();
+
+    loop {
+        match stmt {
+            Stmt::Let(symbol, _, _, next) => {
+                bound.push(*symbol);
+                stmt = next;
+            }
+            Stmt::Refcounting(_, next) => stmt = next,
+            Stmt::Expect { remainder, .. }
+            | Stmt::ExpectFx { remainder, .. }
+            | Stmt::Dbg { remainder, .. }
+            | Stmt::Join { remainder, .. } => stmt = remainder,
+            Stmt::Ret(symbol) => {
+                bound.insert(0, *symbol);
+                break;
+            }
+            Stmt::Jump(_, arguments) => {
+                bound.splice(0..0, arguments.iter().copied());
+                break;
+            }
+            Stmt::Switch { .. } | Stmt::Crash(..) => break,
+        }
+    }
+
+    bound
+}
@@ -0,0 +1,136 @@
+//! Removes specializations that can never be called.
+//!
+//! Specializations are made on demand, so most procedures are used by the time the IR is done.
+//! But a specialization can still end up unused, for example when constant folding removes the
+//! only call to a lowlevel wrapper like `Num.add`. We walk the call graph from the procedures the
+//! host can call, and drop the rest so the backends don't generate code for them.
+
+use roc_collections::{MutMap, MutSet};
+use roc_module::symbol::Symbol;
+
+use crate::ir::{CallType, Expr, Proc, ProcLayout, Stmt};
+
+/// Keep only the procedures that are reachable from `roots`. Every specialization of a root
+/// symbol is kept.
+pub fn remove_unreachable_procs<'a>(
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    roots: impl IntoIterator<Item = Symbol>,
+) {
+    let mut specializations: MutMap<Symbol, std::vec::Vec<ProcLayout<'a>>> = MutMap::default();
+
+    for (symbol, layout) in procs.keys() {
+        specializations.entry(*symbol).or_default().push(*layout);
+    }
+
+    let mut reachable = Reachable {
+        specializations,
+        reachable: MutSet::default(),
+        pending: std::vec::Vec::new(),
+    };
+
+    for root in roots {
+        reachable.mark_all(root);
+    }
+
+    while let Some(key) = reachable.pending.pop() {
+        let mut stack = vec![&procs[&key].body];
+
+        while let Some(stmt) = stack.pop() {
+            match stmt {
+                Stmt::Let(_, expr, _, continuation) => {
+                    reachable.mark_expr(expr);
+                    stack.push(continuation);
+                }
+                Stmt::Switch {
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                    stack.push(default_branch.1);
+                }
+                Stmt::Refcounting(_, continuation) => stack.push(continuation),
+                Stmt::Expect { remainder, .. }
+                | Stmt::ExpectFx { remainder, .. }
+                | Stmt::Dbg { remainder, .. } => stack.push(remainder),
+                Stmt::Join {
+                    body, remainder, ..
+                } => {
+                    stack.push(body);
+                    stack.push(remainder);
+                }
+                Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => {}
+            }
+        }
+    }
+
+    let Reachable { reachable, .. } = reachable;
+    procs.retain(|key, _| reachable.contains(key));
+}
+
+struct Reachable<'a> {
+    specializations: MutMap<Symbol, std::vec::Vec<ProcLayout<'a>>>,
+    reachable: MutSet<(Symbol, ProcLayout<'a>)>,
+    /// Reachable procedures whose bodies we have not walked yet
+    pending: std::vec::Vec<(Symbol, ProcLayout<'a>)>,
+}
+
+impl<'a> Reachable<'a> {
+    fn mark(&mut self, key: (Symbol, ProcLayout<'a>)) {
+        if self.reachable.insert(key) {
+            self.pending.push(key);
+        }
+    }
+
+    fn mark_all(&mut self, symbol: Symbol) {
+        let layouts = self
+            .specializations
+            .get(&symbol)
+            .cloned()
+            .unwrap_or_default();
+
+        for layout in layouts {
+            self.mark((symbol, layout));
+        }
+    }
+
+    fn mark_expr(&mut self, expr: &Expr<'a>) {
+        match expr {
+            Expr::Call(call) => match &call.call_type {
+                CallType::ByName {
+                    name,
+                    ret_layout,
+                    arg_layouts,
+                    ..
+                } => {
+                    let layout = ProcLayout {
+                        arguments: arg_layouts,
+                        result: *ret_layout,
+                        niche: name.niche(),
+                    };
+
+                    let is_specialized = self
+                        .specializations
+                        .get(&name.name())
+                        .is_some_and(|layouts| layouts.contains(&layout));
+
+                    if is_specialized {
+                        self.mark((name.name(), layout));
+                    } else {
+                        self.mark_all(name.name());
+                    }
+                }
+                // The backends find the procedure passed to a higher-order lowlevel in different
+                // ways, so we keep all of its specializations.
+                CallType::HigherOrder(higher_order) => {
+                    self.mark_all(higher_order.passed_function.name.name())
+                }
+                CallType::ByPointer { .. }
+                | CallType::Foreign { .. }
+                | CallType::LowLevel { .. } => {}
+            },
+            Expr::FunctionPointer { lambda_name } => self.mark_all(lambda_name.name()),
+            _ => {}
+        }
+    }
+}
@@ -14,6 +14,7 @@ use roc_error_macros::internal_error;
 use roc_module::low_level::LowLevel;
 use roc_module::{low_level::LowLevelWrapperType, symbol::Symbol};
 
+use crate::borrow::BorrowInference;
 use crate::ir::ErasedField;
 use crate::{
     ir::{
@@ -26,14 +27,20 @@ use crate::{
 
 /**
 Insert the reference count operations for procedures.
+Unless borrow inference is disabled, arguments that a procedure only borrows are not refcounted.
 */
 pub fn insert_inc_dec_operations<'a>(
     arena: &'a Bump,
     layout_interner: &STLayoutInterner<'a>,
     procedures: &mut HashMap<(Symbol, ProcLayout<'a>), Proc<'a>, BuildHasherDefault<WyHash>>,
+    borrow_inference: BorrowInference,
 ) {
-    let borrow_signatures =
-        crate::borrow::infer_borrow_signatures(arena, layout_interner, procedures);
+    let borrow_signatures = crate::borrow::infer_borrow_signatures(
+        arena,
+        layout_interner,
+        procedures,
+        borrow_inference,
+    );
     let borrow_signatures = arena.alloc(borrow_signatures);
 
     // All calls to lowlevels are wrapped in another function to help with type inference and return/parameter layouts.
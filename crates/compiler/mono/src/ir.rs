@@ -27,7 +27,7 @@ use roc_late_solve::storage::{ExternalModuleStorage, ExternalModuleStorageSnapsh
 use roc_late_solve::{resolve_ability_specialization, AbilitiesView, Resolved, UnificationFailed};
 use roc_module::ident::{ForeignSymbol, Lowercase, TagName};
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
-use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_module::symbol::{IdentIds, Interns, ModuleId, Symbol};
 use roc_problem::can::{RuntimeError, ShadowKind};
 use roc_region::all::{Loc, Region};
 use roc_std::RocDec;
@@ -42,11 +42,13 @@ use ven_pretty::{text, BoxAllocator, DocAllocator, DocBuilder};
 use pattern::{from_can_pattern, store_pattern, Pattern};
 
 pub use literal::{ListLiteralElement, Literal};
+pub use parse::{parse_procs, ParseError};
 
 mod boxed;
 mod decision_tree;
 mod erased;
 mod literal;
+mod parse;
 mod pattern;
 
 #[inline(always)]
@@ -139,7 +141,10 @@ pub struct SingleEntryPoint<'a> {
 #[derive(Debug, Clone, Copy)]
 pub enum EntryPoint<'a> {
     Single(SingleEntryPoint<'a>),
-    Expects { symbols: &'a [Symbol] },
+    /// Top-level expects and benchmarks: thunks, with the layouts they were specialized at
+    Expects {
+        symbols: &'a [(Symbol, ProcLayout<'a>)],
+    },
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -208,6 +213,7 @@ pub struct PartialProc<'a> {
     pub pattern_symbols: &'a [Symbol],
     pub captured_symbols: CapturedSymbols<'a>,
     pub body: roc_can::expr::Expr,
+    pub body_region: Region,
     pub body_var: Variable,
     pub is_self_recursive: bool,
 }
@@ -224,6 +230,7 @@ impl<'a> PartialProc<'a> {
         ret_var: Variable,
     ) -> PartialProc<'a> {
         let number_of_arguments = loc_args.len();
+        let body_region = loc_body.region;
 
         match patterns_to_when(env, loc_args, ret_var, loc_body) {
             Ok((_, pattern_symbols, body)) => {
@@ -237,6 +244,7 @@ impl<'a> PartialProc<'a> {
                     pattern_symbols,
                     captured_symbols,
                     body: body.value,
+                    body_region: body.region,
                     body_var: ret_var,
                     is_self_recursive,
                 }
@@ -254,6 +262,7 @@ impl<'a> PartialProc<'a> {
                     pattern_symbols: pattern_symbols.into_bump_slice(),
                     captured_symbols: CapturedSymbols::None,
                     body: roc_can::expr::Expr::RuntimeError(error.value),
+                    body_region,
                     body_var: ret_var,
                     is_self_recursive: false,
                 }
@@ -408,6 +417,39 @@ impl<'a> Proc<'a> {
     }
 }
 
+/// The procedures sorted by name and layout, for backends to emit them in.
+///
+/// Iterating the map directly goes by hash, and both the symbols and the layouts it is keyed on
+/// are numbered in the order that modules happened to finish loading on their threads. Emitting
+/// in that order would make the output differ between two builds of the same program.
+pub fn procedures_in_stable_order<'a, I>(
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    interns: &Interns,
+    interner: &I,
+) -> std::vec::Vec<((Symbol, ProcLayout<'a>), Proc<'a>)>
+where
+    I: LayoutInterner<'a>,
+{
+    let mut procedures: std::vec::Vec<_> = procedures.into_iter().collect();
+
+    procedures.sort_by_cached_key(|((symbol, layout), _)| {
+        let layout = format!(
+            "{:?} -> {:?} {:?}",
+            interner.dbg_stable_iter(layout.arguments),
+            interner.dbg_stable(layout.result),
+            layout.niche.dbg_stable(interner)
+        );
+
+        (
+            symbol.module_string(interns).as_str().to_string(),
+            symbol.as_str(interns).to_string(),
+            layout,
+        )
+    });
+
+    procedures
+}
+
 /// A host-exposed function must be specialized; it's a seed for subsequent specializations
 #[derive(Clone, Debug)]
 pub struct HostSpecializations<'a> {
@@ -1107,6 +1149,7 @@ impl<'a> Procs<'a> {
                                         pattern_symbols,
                                         captured_symbols,
                                         body: body.value,
+                                        body_region: body.region,
                                         body_var: ret_var,
                                         is_self_recursive,
                                     };
@@ -1135,6 +1178,7 @@ impl<'a> Procs<'a> {
                                     pattern_symbols,
                                     captured_symbols,
                                     body: body.value,
+                                    body_region: body.region,
                                     body_var: ret_var,
                                     is_self_recursive,
                                 };
@@ -1372,6 +1416,9 @@ pub struct Env<'a, 'i> {
     pub exposed_by_module: &'i ExposedByModule,
     pub derived_module: &'i SharedDerivedModule,
     pub struct_indexing: UsageTrackingMap<(Symbol, u64), Symbol>,
+    /// Where in the source the values bound to symbols (and the bodies of procedures) come
+    /// from, so that reports about the IR can point at the user's code.
+    pub symbol_regions: MutMap<Symbol, Region>,
 }
 
 impl<'a, 'i> Env<'a, 'i> {
@@ -1538,6 +1585,8 @@ pub enum Stmt<'a> {
         /// what happens after the expect
         remainder: &'a Stmt<'a>,
     },
+    /// Every backend calls `roc_dbg(loc: *const RocStr, msg: *const RocStr, src: *const RocStr)`
+    /// here, where `msg` is the (already inspected) value of `symbol`.
     Dbg {
         /// The location this dbg is in source as a printable string.
         source_location: &'a str,
@@ -1565,6 +1614,8 @@ pub enum Stmt<'a> {
 }
 
 /// Source of crash, and its runtime representation to roc_panic.
+///
+/// Every backend calls `roc_panic(msg: *const RocStr, tag: u32)`, with the tag as this enum's value.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u32)]
 pub enum CrashTag {
@@ -2467,6 +2518,8 @@ fn from_can_let<'a>(
     }
 
     if let roc_can::pattern::Pattern::Identifier(symbol) = &def.loc_pattern.value {
+        env.symbol_regions.insert(*symbol, def.loc_expr.region);
+
         return match def.loc_expr.value {
             Closure(closure_data) => {
                 register_capturing_closure(env, procs, layout_cache, *symbol, closure_data);
@@ -3559,6 +3612,11 @@ fn specialize_proc_help<'a>(
     let body = partial_proc.body.clone();
     let body_var = partial_proc.body_var;
 
+    if partial_proc.body_region != Region::zero() {
+        env.symbol_regions
+            .insert(lambda_name.name(), partial_proc.body_region);
+    }
+
     let mut specialized_body = from_can(env, body_var, body, procs, layout_cache);
 
     let specialized_proc = match specialized {
@@ -6460,7 +6518,7 @@ fn convert_tag_union<'a>(
             }
         }
         Wrapped(variant) => {
-            let (tag_id, _) = variant.tag_name_to_id(&tag_name);
+            let (tag_id, argument_layouts) = variant.tag_name_to_id(&tag_name);
 
             let field_symbols_temp = sorted_field_symbols(env, procs, layout_cache, args);
 
@@ -6600,11 +6658,19 @@ fn convert_tag_union<'a>(
                         temp.into_bump_slice()
                     };
 
-                    let tag = Expr::Tag {
-                        tag_layout: union_layout,
-                        tag_id: tag_id as _,
-                        arguments: field_symbols,
-                        reuse: None,
+                    let tag = if union_layout.is_nullable_pointer_niche(
+                        &layout_cache.interner,
+                        tag_id,
+                        argument_layouts,
+                    ) {
+                        boxed::cast_nullable_pointer_niche(&field_symbols[0])
+                    } else {
+                        Expr::Tag {
+                            tag_layout: union_layout,
+                            tag_id: tag_id as _,
+                            arguments: field_symbols,
+                            reuse: None,
+                        }
                     };
 
                     (tag, union_layout)
@@ -6962,8 +7028,13 @@ pub fn from_can<'a>(
                 "invalid condition type in if expression"
             );
 
+            let else_region = final_else.region;
             let mut stmt = from_can(env, branch_var, final_else.value, procs, layout_cache);
 
+            if let Some(symbol) = returned_value(&stmt) {
+                env.symbol_regions.insert(symbol, else_region);
+            }
+
             for (loc_cond, loc_then) in branches.into_iter().rev() {
                 let branching_symbol = possible_reuse_symbol_or_specialize(
                     env,
@@ -6974,6 +7045,10 @@ pub fn from_can<'a>(
                 );
                 let then = from_can(env, branch_var, loc_then.value, procs, layout_cache);
 
+                if let Some(symbol) = returned_value(&then) {
+                    env.symbol_regions.insert(symbol, loc_then.region);
+                }
+
                 stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
                 stmt = assign_to_symbol(
@@ -7208,7 +7283,7 @@ fn to_opt_branches<'a>(
 ) -> std::vec::Vec<(
     Pattern<'a>,
     Option<Loc<roc_can::expr::Expr>>,
-    roc_can::expr::Expr,
+    Loc<roc_can::expr::Expr>,
 )> {
     debug_assert!(!branches.is_empty());
 
@@ -7253,14 +7328,21 @@ fn to_opt_branches<'a>(
                     };
 
                     // TODO remove clone?
-                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr.value));
+                    opt_branches.push((
+                        mono_pattern,
+                        when_branch.guard.clone(),
+                        Loc::at(when_branch.value.region, loc_expr.value),
+                    ));
                 }
                 Err(runtime_error) => {
                     // TODO remove clone?
                     opt_branches.push((
                         Pattern::Underscore,
                         when_branch.guard.clone(),
-                        roc_can::expr::Expr::RuntimeError(runtime_error),
+                        Loc::at(
+                            when_branch.value.region,
+                            roc_can::expr::Expr::RuntimeError(runtime_error),
+                        ),
                     ));
                 }
             }
@@ -7273,7 +7355,9 @@ fn to_opt_branches<'a>(
         opt_branches.push((
             Pattern::Underscore,
             None,
-            roc_can::expr::Expr::RuntimeError(roc_problem::can::RuntimeError::NonExhaustivePattern),
+            Loc::at_zero(roc_can::expr::Expr::RuntimeError(
+                roc_problem::can::RuntimeError::NonExhaustivePattern,
+            )),
         ));
     }
 
@@ -7314,7 +7398,7 @@ fn from_can_when<'a>(
     let arena = env.arena;
     let it = opt_branches
         .into_iter()
-        .filter_map(|(pattern, opt_guard, can_expr)| {
+        .filter_map(|(pattern, opt_guard, loc_expr)| {
             // If the pattern has a void layout we can drop it; however, we must still perform the
             // work of building the body, because that may contain specializations we must
             // discover for use elsewhere. See
@@ -7332,13 +7416,31 @@ fn from_can_when<'a>(
             };
 
             let branch_stmt = match join_point {
-                None => from_can(env, expr_var, can_expr, procs, layout_cache),
+                None => {
+                    let stmt = from_can(env, expr_var, loc_expr.value, procs, layout_cache);
+
+                    if let Some(symbol) = returned_value(&stmt) {
+                        env.symbol_regions.insert(symbol, loc_expr.region);
+                    }
+
+                    stmt
+                }
                 Some(id) => {
                     let symbol = env.unique_symbol();
                     let arguments = bumpalo::vec![in env.arena; symbol].into_bump_slice();
                     let jump = env.arena.alloc(Stmt::Jump(id, arguments));
 
-                    with_hole(env, can_expr, expr_var, procs, layout_cache, symbol, jump)
+                    env.symbol_regions.insert(symbol, loc_expr.region);
+
+                    with_hole(
+                        env,
+                        loc_expr.value,
+                        expr_var,
+                        procs,
+                        layout_cache,
+                        symbol,
+                        jump,
+                    )
                 }
             };
 
@@ -7381,6 +7483,22 @@ fn from_can_when<'a>(
     )
 }
 
+/// The symbol a branch returns, if the branch binds it itself. Values from outside the branch
+/// already have a region of their own.
+fn returned_value(mut stmt: &Stmt) -> Option<Symbol> {
+    let mut bound = std::vec::Vec::new();
+
+    while let Stmt::Let(symbol, _, _, next) = stmt {
+        bound.push(*symbol);
+        stmt = next;
+    }
+
+    match stmt {
+        Stmt::Ret(symbol) if bound.contains(symbol) => Some(*symbol),
+        _ => None,
+    }
+}
+
 /// A functor to generate IR for a guard under a `when` branch.
 /// Used in the decision tree compiler, after building a decision tree and converting into IR.
 ///
@@ -8472,15 +8590,19 @@ fn assign_to_symbol<'a>(
             )
         }
         Value(_symbol) => result,
-        NotASymbol => with_hole(
-            env,
-            loc_arg.value,
-            arg_var,
-            procs,
-            layout_cache,
-            symbol,
-            env.arena.alloc(result),
-        ),
+        NotASymbol => {
+            env.symbol_regions.insert(symbol, loc_arg.region);
+
+            with_hole(
+                env,
+                loc_arg.value,
+                arg_var,
+                procs,
+                layout_cache,
+                symbol,
+                env.arena.alloc(result),
+            )
+        }
     }
 }
 
@@ -1,8 +1,9 @@
+use roc_module::low_level::LowLevel;
 use roc_module::symbol::Symbol;
 
 use crate::layout::{InLayout, UnionLayout};
 
-use super::Expr;
+use super::{Call, CallType, Expr, UpdateModeId};
 
 pub fn box_<'a>(symbol: &'a Symbol, element_layout: &'a InLayout<'a>) -> Expr<'a> {
     Expr::Tag {
@@ -39,3 +40,15 @@ pub fn unbox_nullable<'a>(symbol: Symbol, element_layout: &'a InLayout<'a>) -> E
         index: 0,
     }
 }
+
+/// Converts between a `Box a` and a `[Some (Box a), None]`-shaped union that uses the box as its
+/// allocation, see [UnionLayout::is_nullable_pointer_niche].
+pub fn cast_nullable_pointer_niche(symbol: &Symbol) -> Expr<'_> {
+    Expr::Call(Call {
+        call_type: CallType::LowLevel {
+            op: LowLevel::PtrCast,
+            update_mode: UpdateModeId::BACKEND_DUMMY,
+        },
+        arguments: std::slice::from_ref(symbol),
+    })
+}
@@ -0,0 +1,1420 @@
+//! Parses the textual form of the mono IR that [Proc::to_pretty] prints, so backend tests can be
+//! written against hand-authored IR and the `test_mono` fixtures can be read back in.
+//!
+//! The printed form leaves out some layouts: those of procedure arguments, of join point
+//! parameters, and of the arguments of calls. Procedure arguments may be annotated as
+//! `Test.1: I64`, the way the printer does when `ROC_PRINT_IR_AFTER_SPECIALIZATION` is set;
+//! everything else is inferred from the `let`s, calls and jumps that use the symbols.
+//!
+//! Whatever else the printer leaves out gets a neutral default: calls have no niche, branches
+//! have no [BranchInfo], `dbg` and `expect` carry no source information, and crashes are
+//! attributed to the user. Higher-order lowlevels are not supported, since their passed function
+//! is not printed.
+
+use bumpalo::collections::Vec as AVec;
+use bumpalo::Bump;
+use roc_collections::all::MutMap;
+use roc_module::ident::{ForeignSymbol, ModuleName};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{Interns, ModuleIds, Symbol};
+use roc_region::all::Region;
+use roc_std::RocDec;
+use roc_types::subs::Variable;
+
+use crate::layout::{
+    Builtin, FunctionPointer, InLayout, LambdaName, Layout, LayoutInterner, LayoutRepr,
+    TagIdIntType, UnionLayout,
+};
+
+use super::{
+    symbol_to_doc_string, BranchInfo, Call, CallSpecId, CallType, CrashTag, ErasedField, Expr,
+    JoinPointId, ListLiteralElement, Literal, ModifyRc, Param, Proc, ReuseToken, SelfRecursive,
+    Stmt, UpdateModeId,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The 1-based line of the source the error was found on.
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Parses procedures in the format of [Proc::to_pretty], one after the other.
+///
+/// Symbols are looked up by module name in `module_ids`; `Test` is the app module, as that is
+/// how the printer shows it.
+pub fn parse_procs<'a, I>(
+    arena: &'a Bump,
+    interner: &mut I,
+    module_ids: &ModuleIds,
+    src: &str,
+) -> Result<std::vec::Vec<Proc<'a>>, ParseError>
+where
+    I: LayoutInterner<'a>,
+{
+    let lines = src
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(index, text)| {
+            let trimmed = text.trim_start_matches(' ');
+            Line {
+                number: index + 1,
+                indent: text.len() - trimmed.len(),
+                text: trimmed.trim_end(),
+            }
+        })
+        .collect();
+
+    let mut parser = Parser {
+        arena,
+        interner,
+        module_ids,
+        lines,
+        pos: 0,
+        rec_depth: 0,
+        hints: MutMap::default(),
+    };
+
+    let mut procs = std::vec::Vec::new();
+    while parser.pos < parser.lines.len() {
+        procs.push(parser.proc()?);
+    }
+
+    infer_layouts(&mut procs);
+
+    procs
+        .iter()
+        .map(|proc| {
+            Resolver {
+                arena,
+                interner: &*parser.interner,
+                proc,
+            }
+            .proc()
+        })
+        .collect()
+}
+
+struct Line<'s> {
+    number: usize,
+    indent: usize,
+    text: &'s str,
+}
+
+struct Cursor<'s> {
+    line: usize,
+    rest: &'s str,
+}
+
+impl<'s> Cursor<'s> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.rest.strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, prefix: &str) -> Result<(), ParseError> {
+        if self.eat(prefix) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{prefix}`, found `{}`", self.rest)))
+        }
+    }
+
+    fn end(&self) -> Result<(), ParseError> {
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format!("unexpected `{}`", self.rest)))
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'s str {
+        let end = self.rest.find(|c| !pred(c)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        taken
+    }
+
+    /// A run of characters up to the next delimiter, like a symbol or a literal.
+    fn token(&mut self) -> &'s str {
+        self.take_while(|c| !c.is_whitespace() && !",;:(){}[]<>".contains(c))
+    }
+
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T, ParseError> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        digits
+            .parse()
+            .map_err(|_| self.error(format!("expected a number, found `{}`", self.rest)))
+    }
+
+    /// Whether only the `;` that closes an expression is left on the line.
+    fn at_expr_end(&self) -> bool {
+        self.rest == ";"
+    }
+}
+
+struct RawProc<'a> {
+    line: usize,
+    name: Symbol,
+    args: &'a [Symbol],
+    ret_layout: Option<InLayout<'a>>,
+    body: &'a RawStmt<'a>,
+    /// The layouts of the symbols bound in the procedure, as far as they are known.
+    layouts: MutMap<Symbol, InLayout<'a>>,
+}
+
+/// A [Stmt] whose layouts that the printer leaves out have not been inferred yet.
+enum RawStmt<'a> {
+    Let(Symbol, RawExpr<'a>, InLayout<'a>, &'a RawStmt<'a>),
+    Refcounting(ModifyRc, &'a RawStmt<'a>),
+    Dbg(Symbol, &'a RawStmt<'a>),
+    Expect(Symbol, &'a RawStmt<'a>),
+    ExpectFx(Symbol, &'a RawStmt<'a>),
+    Ret(Symbol),
+    Switch {
+        cond: Symbol,
+        branches: &'a [(u64, RawStmt<'a>)],
+        default: &'a RawStmt<'a>,
+    },
+    Join {
+        id: JoinPointId,
+        params: &'a [Symbol],
+        body: &'a RawStmt<'a>,
+        remainder: &'a RawStmt<'a>,
+    },
+    Jump(JoinPointId, &'a [Symbol]),
+    Crash(Symbol),
+}
+
+enum RawExpr<'a> {
+    /// An expression that needs no layout but the one of the `let` it is bound in.
+    Done(Expr<'a>),
+    CallByName(Symbol, &'a [Symbol]),
+    CallByPtr(Symbol, &'a [Symbol]),
+    StructAtIndex {
+        index: u64,
+        structure: Symbol,
+    },
+    GetTagId(Symbol),
+    UnionAtIndex {
+        structure: Symbol,
+        tag_id: TagIdIntType,
+        index: u64,
+    },
+    GetElementPointer {
+        structure: Symbol,
+        indices: &'a [u64],
+    },
+    Alloca(Option<Symbol>),
+}
+
+struct Parser<'a, 's, 'i, I> {
+    arena: &'a Bump,
+    interner: &'i mut I,
+    module_ids: &'i ModuleIds,
+    lines: std::vec::Vec<Line<'s>>,
+    pos: usize,
+    /// How many recursive unions the layout being parsed is nested in.
+    rec_depth: usize,
+    /// Layouts of symbols in the current procedure, learned from the structures they are put in.
+    hints: MutMap<Symbol, InLayout<'a>>,
+}
+
+impl<'a, 's, 'i, I> Parser<'a, 's, 'i, I>
+where
+    I: LayoutInterner<'a>,
+{
+    fn next_line(&mut self, indent: usize) -> Result<Cursor<'s>, ParseError> {
+        let Some(line) = self.lines.get(self.pos) else {
+            return Err(ParseError {
+                line: self.lines.last().map_or(1, |line| line.number),
+                message: "unexpected end of input".into(),
+            });
+        };
+
+        if line.indent != indent {
+            return Err(ParseError {
+                line: line.number,
+                message: format!(
+                    "expected a line indented by {indent} spaces, found {}",
+                    line.indent
+                ),
+            });
+        }
+
+        self.pos += 1;
+
+        Ok(Cursor {
+            line: line.number,
+            rest: line.text,
+        })
+    }
+
+    fn proc(&mut self) -> Result<RawProc<'a>, ParseError> {
+        let mut c = self.next_line(0)?;
+        let line = c.line;
+
+        let mut ret_layout = None;
+        let name = if c.eat("procedure : ") {
+            let name = self.symbol(&mut c)?;
+            c.expect(" ")?;
+            ret_layout = Some(self.layout(&mut c)?);
+            c.end()?;
+
+            c = self.next_line(0)?;
+            c.expect("procedure = ")?;
+            if self.symbol(&mut c)? != name {
+                let name = symbol_to_doc_string(name, false);
+                return Err(c.error(format!("expected the definition of `{name}`")));
+            }
+            name
+        } else {
+            c.expect("procedure ")?;
+            self.symbol(&mut c)?
+        };
+        c.expect(" (")?;
+
+        let mut annotations = MutMap::default();
+        let mut args = AVec::new_in(self.arena);
+        if !c.eat("):") {
+            loop {
+                let arg = self.symbol(&mut c)?;
+                if c.eat(": ") {
+                    annotations.insert(arg, self.layout(&mut c)?);
+                }
+                args.push(arg);
+
+                if c.eat("):") {
+                    break;
+                }
+                c.expect(", ")?;
+            }
+        }
+        c.end()?;
+
+        let body = self.stmt(4)?;
+        let mut layouts = std::mem::take(&mut self.hints);
+        layouts.extend(annotations);
+
+        if let Some(line) = self.lines.get(self.pos) {
+            if line.indent != 0 {
+                return Err(ParseError {
+                    line: line.number,
+                    message: "expected the end of the procedure".into(),
+                });
+            }
+        }
+
+        Ok(RawProc {
+            line,
+            name,
+            args: args.into_bump_slice(),
+            ret_layout,
+            body: self.arena.alloc(body),
+            layouts,
+        })
+    }
+
+    fn stmt(&mut self, indent: usize) -> Result<RawStmt<'a>, ParseError> {
+        let mut c = self.next_line(indent)?;
+
+        macro_rules! followed_by {
+            ($make:expr) => {{
+                let symbol = self.symbol(&mut c)?;
+                c.expect(";")?;
+                c.end()?;
+                let rest = self.stmt(indent)?;
+                $make(symbol, &*self.arena.alloc(rest))
+            }};
+        }
+
+        let stmt = if c.eat("let ") {
+            let symbol = self.symbol(&mut c)?;
+            c.expect(" : ")?;
+            let layout = self.layout(&mut c)?;
+            c.expect(" = ")?;
+            let expr = self.expr(&mut c, layout)?;
+            c.expect(";")?;
+            c.end()?;
+
+            let rest = self.stmt(indent)?;
+            RawStmt::Let(symbol, expr, layout, self.arena.alloc(rest))
+        } else if c.eat("inc ") {
+            let count = if c.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+                let count = c.number()?;
+                c.expect(" ")?;
+                count
+            } else {
+                1
+            };
+            followed_by!(|s, rest| RawStmt::Refcounting(ModifyRc::Inc(s, count), rest))
+        } else if c.eat("dec ") {
+            followed_by!(|s, rest| RawStmt::Refcounting(ModifyRc::Dec(s), rest))
+        } else if c.eat("decref ") {
+            followed_by!(|s, rest| RawStmt::Refcounting(ModifyRc::DecRef(s), rest))
+        } else if c.eat("free ") {
+            followed_by!(|s, rest| RawStmt::Refcounting(ModifyRc::Free(s), rest))
+        } else if c.eat("dbg ") {
+            followed_by!(RawStmt::Dbg)
+        } else if c.eat("expect-fx ") {
+            followed_by!(RawStmt::ExpectFx)
+        } else if c.eat("expect ") {
+            followed_by!(RawStmt::Expect)
+        } else if c.eat("ret ") {
+            let symbol = self.symbol(&mut c)?;
+            c.expect(";")?;
+            c.end()?;
+            RawStmt::Ret(symbol)
+        } else if c.eat("Crash ") {
+            let symbol = self.symbol(&mut c)?;
+            c.end()?;
+            RawStmt::Crash(symbol)
+        } else if c.eat("jump ") {
+            let id = JoinPointId(self.symbol(&mut c)?);
+            let mut args = AVec::new_in(self.arena);
+            while c.eat(" ") {
+                args.push(self.symbol(&mut c)?);
+            }
+            c.expect(";")?;
+            c.end()?;
+            RawStmt::Jump(id, args.into_bump_slice())
+        } else if c.eat("if ") {
+            let cond = self.symbol(&mut c)?;
+            c.expect(" then")?;
+            c.end()?;
+            let pass = self.stmt(indent + 4)?;
+            let mut c = self.next_line(indent)?;
+            c.expect("else")?;
+            c.end()?;
+            let fail = self.stmt(indent + 4)?;
+
+            RawStmt::Switch {
+                cond,
+                branches: self.arena.alloc([(1, pass)]),
+                default: self.arena.alloc(fail),
+            }
+        } else if c.eat("switch ") {
+            let cond = self.symbol(&mut c)?;
+            c.expect(":")?;
+            c.end()?;
+
+            let mut branches = AVec::new_in(self.arena);
+            let default = loop {
+                let mut c = self.next_line(indent + 4)?;
+                if c.eat("default:") {
+                    c.end()?;
+                    break self.stmt(indent + 8)?;
+                }
+                c.expect("case ")?;
+                let value = c.number()?;
+                c.expect(":")?;
+                c.end()?;
+                branches.push((value, self.stmt(indent + 8)?));
+            };
+
+            RawStmt::Switch {
+                cond,
+                branches: branches.into_bump_slice(),
+                default: self.arena.alloc(default),
+            }
+        } else if c.eat("joinpoint ") {
+            let id = JoinPointId(self.symbol(&mut c)?);
+            let mut params = AVec::new_in(self.arena);
+            while c.eat(" ") {
+                params.push(self.symbol(&mut c)?);
+            }
+            c.expect(":")?;
+            c.end()?;
+            let body = self.stmt(indent + 4)?;
+            let mut c = self.next_line(indent)?;
+            c.expect("in")?;
+            c.end()?;
+            let remainder = self.stmt(indent)?;
+
+            RawStmt::Join {
+                id,
+                params: params.into_bump_slice(),
+                body: self.arena.alloc(body),
+                remainder: self.arena.alloc(remainder),
+            }
+        } else {
+            return Err(c.error(format!("expected a statement, found `{}`", c.rest)));
+        };
+
+        Ok(stmt)
+    }
+
+    fn expr(
+        &mut self,
+        c: &mut Cursor<'s>,
+        layout: InLayout<'a>,
+    ) -> Result<RawExpr<'a>, ParseError> {
+        use RawExpr::*;
+
+        let expr = if c.eat("CallByName ") {
+            let name = self.symbol(c)?;
+            CallByName(name, self.spaced_symbols(c)?)
+        } else if c.eat("CallByPtr ") {
+            let pointer = self.symbol(c)?;
+            CallByPtr(pointer, self.spaced_symbols(c)?)
+        } else if c.eat("lowlevel ") {
+            let name = c.token();
+            if c.rest.starts_with(" {") {
+                return Err(c.error(format!(
+                    "the higher-order lowlevel `{name}` cannot be parsed, its passed function is not printed"
+                )));
+            }
+            let op: LowLevel = name
+                .parse()
+                .map_err(|_| c.error(format!("unknown lowlevel `{name}`")))?;
+            if op.is_higher_order() {
+                return Err(c.error(format!(
+                    "the higher-order lowlevel `{name}` cannot be parsed, its passed function is not printed"
+                )));
+            }
+
+            Done(Expr::Call(Call {
+                call_type: CallType::LowLevel {
+                    op,
+                    update_mode: UpdateModeId::BACKEND_DUMMY,
+                },
+                arguments: self.spaced_symbols(c)?,
+            }))
+        } else if c.eat("foreign ") {
+            let name = self.string(c)?;
+
+            Done(Expr::Call(Call {
+                call_type: CallType::Foreign {
+                    foreign_symbol: ForeignSymbol::from(name),
+                    ret_layout: layout,
+                },
+                arguments: self.spaced_symbols(c)?,
+            }))
+        } else if c.rest.starts_with("TagId(") {
+            let (tag_id, arguments) = self.tag(c)?;
+            let tag_layout = self.union_layout(c, layout)?;
+            self.hint_tag(tag_layout, tag_id, arguments);
+
+            Done(Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+                reuse: None,
+            })
+        } else if c.eat("Reuse ") {
+            let symbol = self.symbol(c)?;
+            c.expect(" ")?;
+            let update_mode = Self::update_mode(c)?;
+            c.expect(" ")?;
+            let (tag_id, arguments) = self.tag(c)?;
+            let tag_layout = self.union_layout(c, layout)?;
+            self.hint_tag(tag_layout, tag_id, arguments);
+
+            Done(Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+                reuse: Some(ReuseToken {
+                    symbol,
+                    update_tag_id: true,
+                    update_mode,
+                }),
+            })
+        } else if c.eat("NullPointer") {
+            Done(Expr::NullPointer)
+        } else if c.eat("Reset { symbol: ") {
+            let symbol = self.symbol(c)?;
+            c.expect(", id: ")?;
+            let update_mode = Self::update_mode(c)?;
+            c.expect(" }")?;
+
+            Done(Expr::Reset {
+                symbol,
+                update_mode,
+            })
+        } else if c.eat("ResetRef { symbol: ") {
+            let symbol = self.symbol(c)?;
+            c.expect(", id: ")?;
+            let update_mode = Self::update_mode(c)?;
+            c.expect(" }")?;
+
+            Done(Expr::ResetRef {
+                symbol,
+                update_mode,
+            })
+        } else if c.eat("Struct {") {
+            let mut fields = AVec::new_in(self.arena);
+            if !c.eat("}") {
+                loop {
+                    fields.push(self.symbol(c)?);
+                    if c.eat("}") {
+                        break;
+                    }
+                    c.expect(", ")?;
+                }
+            }
+
+            if let LayoutRepr::Struct(field_layouts) = self.interner.get_repr(layout) {
+                self.hints
+                    .extend(fields.iter().copied().zip(field_layouts.iter().copied()));
+            }
+
+            Done(Expr::Struct(fields.into_bump_slice()))
+        } else if c.eat("Array []") {
+            Done(Expr::EmptyArray)
+        } else if c.eat("Array [") {
+            let mut elems = AVec::new_in(self.arena);
+            loop {
+                let elem = if Self::at_literal(c) {
+                    ListLiteralElement::Literal(self.literal(c)?)
+                } else {
+                    ListLiteralElement::Symbol(self.symbol(c)?)
+                };
+                elems.push(elem);
+                if c.eat("]") {
+                    break;
+                }
+                c.expect(", ")?;
+            }
+            let elem_layout = match self.interner.get_repr(layout) {
+                LayoutRepr::Builtin(Builtin::List(elem_layout)) => elem_layout,
+                _ => return Err(c.error("an `Array` must be bound with a `List` layout")),
+            };
+            for elem in elems.iter() {
+                if let ListLiteralElement::Symbol(symbol) = elem {
+                    self.hints.insert(*symbol, elem_layout);
+                }
+            }
+
+            Done(Expr::Array {
+                elem_layout,
+                elems: elems.into_bump_slice(),
+            })
+        } else if c.eat("StructAtIndex ") {
+            let index = c.number()?;
+            c.expect(" ")?;
+
+            StructAtIndex {
+                index,
+                structure: self.symbol(c)?,
+            }
+        } else if c.eat("ErrorFunction ") {
+            let message = c.rest.strip_suffix(';').unwrap_or(c.rest);
+            c.rest = &c.rest[message.len()..];
+
+            Done(Expr::RuntimeErrorFunction(self.arena.alloc_str(message)))
+        } else if c.eat("GetTagId ") {
+            GetTagId(self.symbol(c)?)
+        } else if c.eat("ErasedMake { value: ") {
+            let value = if c.eat("<null>") {
+                None
+            } else {
+                Some(self.symbol(c)?)
+            };
+            c.expect(", callee: ")?;
+            let callee = self.symbol(c)?;
+            c.expect(" }")?;
+
+            Done(Expr::ErasedMake { value, callee })
+        } else if c.eat("ErasedLoad ") {
+            let symbol = self.symbol(c)?;
+            let field = if c.eat(" .ValuePtr") {
+                ErasedField::ValuePtr
+            } else if c.eat(" .Value") {
+                ErasedField::Value
+            } else {
+                c.expect(" .Callee")?;
+                ErasedField::Callee
+            };
+
+            Done(Expr::ErasedLoad { symbol, field })
+        } else if c.eat("FunctionPointer ") {
+            Done(Expr::FunctionPointer {
+                lambda_name: LambdaName::no_niche(self.symbol(c)?),
+            })
+        } else if c.eat("UnionAtIndex (Id ") {
+            let tag_id = c.number()?;
+            c.expect(") (Index ")?;
+            let index = c.number()?;
+            c.expect(") ")?;
+
+            UnionAtIndex {
+                structure: self.symbol(c)?,
+                tag_id,
+                index,
+            }
+        } else if c.eat("GetElementPointer (Indices [") {
+            let mut indices = AVec::new_in(self.arena);
+            if !c.eat("]") {
+                loop {
+                    indices.push(c.number()?);
+                    if c.eat("]") {
+                        break;
+                    }
+                    c.expect(", ")?;
+                }
+            }
+            c.expect(") ")?;
+
+            GetElementPointer {
+                structure: self.symbol(c)?,
+                indices: indices.into_bump_slice(),
+            }
+        } else if c.eat("Alloca ") {
+            if c.eat("<uninitialized>") {
+                Alloca(None)
+            } else {
+                Alloca(Some(self.symbol(c)?))
+            }
+        } else if Self::at_literal(c) {
+            Done(Expr::Literal(self.literal(c)?))
+        } else {
+            return Err(c.error(format!("expected an expression, found `{}`", c.rest)));
+        };
+
+        Ok(expr)
+    }
+
+    /// The arguments that follow the head of an expression, each preceded by a space.
+    fn spaced_symbols(&mut self, c: &mut Cursor<'s>) -> Result<&'a [Symbol], ParseError> {
+        let mut symbols = AVec::new_in(self.arena);
+        // Heads that print with a trailing space have nothing after it when there are no
+        // arguments.
+        while c.eat(" ") && !c.at_expr_end() {
+            symbols.push(self.symbol(c)?);
+        }
+
+        Ok(symbols.into_bump_slice())
+    }
+
+    fn tag(&mut self, c: &mut Cursor<'s>) -> Result<(TagIdIntType, &'a [Symbol]), ParseError> {
+        c.expect("TagId(")?;
+        let tag_id = c.number()?;
+        c.expect(")")?;
+
+        Ok((tag_id, self.spaced_symbols(c)?))
+    }
+
+    fn hint_tag(&mut self, tag_layout: UnionLayout<'a>, tag_id: TagIdIntType, args: &[Symbol]) {
+        // The fields of recursive unions may be recursion pointers, which are not what the
+        // arguments are bound as.
+        if let UnionLayout::NonRecursive(tags) = tag_layout {
+            if let Some(fields) = tags.get(tag_id as usize) {
+                self.hints
+                    .extend(args.iter().copied().zip(fields.iter().copied()));
+            }
+        }
+    }
+
+    fn update_mode(c: &mut Cursor<'s>) -> Result<UpdateModeId, ParseError> {
+        c.expect("UpdateModeId { id: ")?;
+        let id = c.number()?;
+        c.expect(" }")?;
+
+        Ok(UpdateModeId { id })
+    }
+
+    fn union_layout(
+        &self,
+        c: &Cursor<'s>,
+        layout: InLayout<'a>,
+    ) -> Result<UnionLayout<'a>, ParseError> {
+        match self.interner.get_repr(layout) {
+            LayoutRepr::Union(union_layout) => Ok(union_layout),
+            _ => Err(c.error("a tag must be bound with a union layout")),
+        }
+    }
+
+    fn at_literal(c: &Cursor<'s>) -> bool {
+        let starts_numeric = c
+            .peek()
+            .is_some_and(|ch| ch.is_ascii_digit() || ch == '-' || ch == '"');
+        let token = c.rest.split(|ch: char| ",;]".contains(ch)).next();
+
+        starts_numeric || matches!(token, Some("true" | "false" | "NaNf64" | "inff64"))
+    }
+
+    fn literal(&mut self, c: &mut Cursor<'s>) -> Result<Literal<'a>, ParseError> {
+        if c.peek() == Some('"') {
+            return Ok(Literal::Str(self.string(c)?));
+        }
+
+        let token = c.take_while(|ch| !",;]".contains(ch) && !ch.is_whitespace());
+        let invalid = || c.error(format!("invalid literal `{token}`"));
+
+        let literal = if token == "true" {
+            Literal::Bool(true)
+        } else if token == "false" {
+            Literal::Bool(false)
+        } else if let Some(int) = token.strip_suffix("i64") {
+            let int: i128 = int.parse().map_err(|_| invalid())?;
+            Literal::Int(int.to_ne_bytes())
+        } else if let Some(int) = token.strip_suffix("u128") {
+            let int: u128 = int.parse().map_err(|_| invalid())?;
+            Literal::U128(int.to_ne_bytes())
+        } else if let Some(byte) = token.strip_suffix("u8") {
+            Literal::Byte(byte.parse().map_err(|_| invalid())?)
+        } else if let Some(float) = token.strip_suffix("f64") {
+            Literal::Float(float.parse().map_err(|_| invalid())?)
+        } else if let Some(dec) = token.strip_suffix("dec") {
+            let dec = RocDec::from_str(dec).ok_or_else(invalid)?;
+            Literal::Decimal(dec.to_ne_bytes())
+        } else {
+            return Err(invalid());
+        };
+
+        Ok(literal)
+    }
+
+    /// A string in the format of its [Debug] impl, which is how string literals are printed.
+    fn string(&mut self, c: &mut Cursor<'s>) -> Result<&'a str, ParseError> {
+        c.expect("\"")?;
+
+        let mut string = String::new();
+        let mut chars = c.rest.char_indices();
+        let end = loop {
+            let Some((index, ch)) = chars.next() else {
+                return Err(c.error("unterminated string literal"));
+            };
+            match ch {
+                '"' => break index,
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, ch)| ch) {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some(ch @ ('\\' | '"' | '\'')) => ch,
+                        Some('u') => {
+                            let hex: String = chars
+                                .by_ref()
+                                .map(|(_, ch)| ch)
+                                .skip_while(|ch| *ch == '{')
+                                .take_while(|ch| *ch != '}')
+                                .collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| c.error(format!("invalid escape `\\u{{{hex}}}`")))?
+                        }
+                        _ => return Err(c.error("invalid escape in string literal")),
+                    };
+                    string.push(escaped);
+                }
+                ch => string.push(ch),
+            }
+        };
+        c.rest = &c.rest[end + 1..];
+
+        Ok(self.arena.alloc_str(&string))
+    }
+
+    fn symbol(&mut self, c: &mut Cursor<'s>) -> Result<Symbol, ParseError> {
+        let token = c.token();
+        let invalid = || c.error(format!("expected a symbol like `Test.1`, found `{token}`"));
+
+        let (module, ident) = token.rsplit_once('.').ok_or_else(invalid)?;
+        let ident: u32 = ident.parse().map_err(|_| invalid())?;
+        let module_name = match module {
+            "Test" => ModuleName::APP,
+            other => other,
+        };
+        let module_id = self
+            .module_ids
+            .get_id(&module_name.into())
+            .ok_or_else(|| c.error(format!("unknown module `{module}`")))?;
+
+        Ok(Interns::from_index(module_id, ident))
+    }
+
+    /// A layout in the format of [LayoutInterner::to_doc_top].
+    fn layout(&mut self, c: &mut Cursor<'s>) -> Result<InLayout<'a>, ParseError> {
+        if c.eat("*self") {
+            if self.rec_depth == 0 {
+                return Err(c.error("`*self` outside of a recursive union"));
+            }
+            // Resolved by `insert_recursive` to the innermost recursive union.
+            return Ok(self
+                .interner
+                .insert_direct_no_semantic(LayoutRepr::RecursivePointer(Layout::VOID)));
+        }
+
+        if c.eat("?Erased") {
+            return Ok(Layout::ERASED);
+        }
+
+        if c.eat("Ptr(") {
+            let inner = self.layout(c)?;
+            c.expect(")")?;
+            return Ok(self
+                .interner
+                .insert_direct_no_semantic(LayoutRepr::Ptr(inner)));
+        }
+
+        if c.eat("FunPtr((") {
+            let mut args = AVec::new_in(self.arena);
+            if !c.eat(")") {
+                loop {
+                    args.push(self.layout(c)?);
+                    if c.eat(")") {
+                        break;
+                    }
+                    c.expect(", ")?;
+                }
+            }
+            c.expect(" -> ")?;
+            let ret = self.layout(c)?;
+            c.expect(")")?;
+
+            let function_pointer = FunctionPointer {
+                args: args.into_bump_slice(),
+                ret,
+            };
+            return Ok(self
+                .interner
+                .insert_direct_no_semantic(LayoutRepr::FunctionPointer(function_pointer)));
+        }
+
+        if c.eat("{") {
+            let mut fields = AVec::new_in(self.arena);
+            if !c.eat("}") {
+                loop {
+                    fields.push(self.layout(c)?);
+                    if c.eat("}") {
+                        break;
+                    }
+                    c.expect(", ")?;
+                }
+            }
+            return Ok(self
+                .interner
+                .insert_direct_no_semantic(LayoutRepr::struct_(fields.into_bump_slice())));
+        }
+
+        if c.eat("[") {
+            return self.union(c);
+        }
+
+        let word = c.take_while(|ch| ch.is_ascii_alphanumeric());
+        let layout = match word {
+            "I128" => Layout::I128,
+            "I64" => Layout::I64,
+            "I32" => Layout::I32,
+            "I16" => Layout::I16,
+            "I8" => Layout::I8,
+            "U128" => Layout::U128,
+            "U64" => Layout::U64,
+            "U32" => Layout::U32,
+            "U16" => Layout::U16,
+            "U8" => Layout::U8,
+            "Float64" => Layout::F64,
+            "Float32" => Layout::F32,
+            "Int1" => Layout::BOOL,
+            "Decimal" => Layout::DEC,
+            "Str" => Layout::STR,
+            "List" => {
+                c.expect(" ")?;
+                let elem = self.layout(c)?;
+                self.interner
+                    .insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(elem)))
+            }
+            _ => return Err(c.error(format!("expected a layout, found `{word}{}`", c.rest))),
+        };
+
+        Ok(layout)
+    }
+
+    /// A union layout, after its opening `[`.
+    fn union(&mut self, c: &mut Cursor<'s>) -> Result<InLayout<'a>, ParseError> {
+        #[derive(PartialEq)]
+        enum Kind {
+            NonRecursive,
+            Recursive,
+            NonNullableUnwrapped,
+            NullableUnwrapped,
+            NullableWrapped,
+        }
+
+        let kind = if c.eat("<rnnu>") {
+            Kind::NonNullableUnwrapped
+        } else if c.eat("<rnu>") {
+            Kind::NullableUnwrapped
+        } else if c.eat("<rnw>") {
+            Kind::NullableWrapped
+        } else if c.eat("<r>") {
+            Kind::Recursive
+        } else {
+            Kind::NonRecursive
+        };
+
+        let is_recursive = kind != Kind::NonRecursive;
+        if is_recursive {
+            self.rec_depth += 1;
+        }
+
+        let mut nullable_id = None;
+        let mut tags: AVec<&'a [InLayout<'a>]> = AVec::new_in(self.arena);
+        if !c.eat("]") {
+            loop {
+                if c.eat("<null>") {
+                    nullable_id = Some(tags.len());
+                } else {
+                    c.expect("C")?;
+                    let mut fields = AVec::new_in(self.arena);
+                    while c.eat(" ") {
+                        if matches!(c.peek(), Some(',' | ']')) {
+                            break;
+                        }
+                        fields.push(self.layout(c)?);
+                    }
+                    tags.push(fields.into_bump_slice());
+                }
+
+                if c.eat("]") {
+                    break;
+                }
+                c.expect(", ")?;
+            }
+        }
+
+        if is_recursive {
+            self.rec_depth -= 1;
+        }
+
+        let union_layout = match (kind, nullable_id, tags.as_slice()) {
+            (Kind::NonRecursive, None, _) => UnionLayout::NonRecursive(tags.into_bump_slice()),
+            (Kind::Recursive, None, _) => UnionLayout::Recursive(tags.into_bump_slice()),
+            (Kind::NonNullableUnwrapped, None, [fields]) => {
+                UnionLayout::NonNullableUnwrapped(fields)
+            }
+            (Kind::NullableUnwrapped, Some(nullable_id), [fields]) => {
+                UnionLayout::NullableUnwrapped {
+                    nullable_id: nullable_id == 0,
+                    other_fields: fields,
+                }
+            }
+            (Kind::NullableWrapped, Some(nullable_id), _) => UnionLayout::NullableWrapped {
+                nullable_id: nullable_id as u16,
+                other_tags: tags.into_bump_slice(),
+            },
+            _ => return Err(c.error("malformed union layout")),
+        };
+
+        let repr = LayoutRepr::Union(union_layout);
+        if is_recursive {
+            let normalized = Layout::no_semantic(repr.direct());
+            Ok(self.interner.insert_recursive(self.arena, normalized))
+        } else {
+            Ok(self.interner.insert_direct_no_semantic(repr))
+        }
+    }
+}
+
+/// Fills in the layouts of procedure arguments, join point parameters and procedure returns from
+/// the calls, jumps and `ret`s that use them, until nothing changes anymore.
+fn infer_layouts(procs: &mut [RawProc<'_>]) {
+    let mut by_name: MutMap<Symbol, std::vec::Vec<usize>> = MutMap::default();
+    for (index, proc) in procs.iter().enumerate() {
+        by_name.entry(proc.name).or_default().push(index);
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for index in 0..procs.len() {
+            let body = procs[index].body;
+            let mut joins = MutMap::default();
+            propagate(procs, &by_name, index, &mut joins, body, &mut changed);
+
+            let proc = &mut procs[index];
+            if proc.ret_layout.is_none() {
+                proc.ret_layout = ret_layout(&proc.layouts, proc.body);
+                changed |= proc.ret_layout.is_some();
+            }
+        }
+    }
+}
+
+/// The layout of the first symbol returned whose layout is known.
+fn ret_layout<'a>(
+    layouts: &MutMap<Symbol, InLayout<'a>>,
+    stmt: &RawStmt<'a>,
+) -> Option<InLayout<'a>> {
+    match stmt {
+        RawStmt::Ret(symbol) => layouts.get(symbol).copied(),
+        RawStmt::Let(_, _, _, rest)
+        | RawStmt::Refcounting(_, rest)
+        | RawStmt::Dbg(_, rest)
+        | RawStmt::Expect(_, rest)
+        | RawStmt::ExpectFx(_, rest) => ret_layout(layouts, rest),
+        RawStmt::Switch {
+            branches, default, ..
+        } => branches
+            .iter()
+            .find_map(|(_, branch)| ret_layout(layouts, branch))
+            .or_else(|| ret_layout(layouts, default)),
+        RawStmt::Join {
+            body, remainder, ..
+        } => ret_layout(layouts, body).or_else(|| ret_layout(layouts, remainder)),
+        RawStmt::Jump(..) | RawStmt::Crash(_) => None,
+    }
+}
+
+/// The procedure a call goes to, if it can be told apart yet. Calls name a procedure but not its
+/// layout, so specializations that share a name are told apart by the layouts known so far.
+fn callee<'a>(
+    procs: &[RawProc<'a>],
+    candidates: &[usize],
+    caller: usize,
+    args: &[Symbol],
+    ret: InLayout<'a>,
+) -> Option<usize> {
+    let caller = &procs[caller];
+    let mut matching = candidates.iter().copied().filter(|&candidate| {
+        let proc = &procs[candidate];
+        let args_match = proc.args.iter().zip(args).all(|(param, arg)| {
+            match (proc.layouts.get(param), caller.layouts.get(arg)) {
+                (Some(param), Some(arg)) => param == arg,
+                _ => true,
+            }
+        });
+
+        proc.args.len() == args.len()
+            && !matches!(proc.ret_layout, Some(l) if l != ret)
+            && args_match
+    });
+
+    let callee = matching.next()?;
+    matching.next().is_none().then_some(callee)
+}
+
+fn propagate<'a>(
+    procs: &mut [RawProc<'a>],
+    by_name: &MutMap<Symbol, std::vec::Vec<usize>>,
+    index: usize,
+    joins: &mut MutMap<JoinPointId, &'a [Symbol]>,
+    stmt: &'a RawStmt<'a>,
+    changed: &mut bool,
+) {
+    let mut pass = |procs: &mut [RawProc<'a>], from: &[Symbol], callee: usize, to: &[Symbol]| {
+        for (arg, param) in from.iter().zip(to) {
+            if procs[callee].layouts.contains_key(param) {
+                continue;
+            }
+            if let Some(&layout) = procs[index].layouts.get(arg) {
+                procs[callee].layouts.insert(*param, layout);
+                *changed = true;
+            }
+        }
+    };
+
+    match stmt {
+        RawStmt::Let(symbol, expr, layout, rest) => {
+            procs[index].layouts.insert(*symbol, *layout);
+            if let RawExpr::CallByName(name, args) = expr {
+                let candidates = by_name.get(name).map_or(&[][..], |c| c.as_slice());
+                if let Some(callee) = callee(procs, candidates, index, args, *layout) {
+                    let params = procs[callee].args;
+                    pass(procs, args, callee, params);
+
+                    if procs[callee].ret_layout.is_none() {
+                        procs[callee].ret_layout = Some(*layout);
+                        *changed = true;
+                    }
+                }
+            }
+            propagate(procs, by_name, index, joins, rest, changed);
+        }
+        RawStmt::Jump(id, args) => {
+            if let Some(params) = joins.get(id) {
+                pass(procs, args, index, params);
+            }
+        }
+        RawStmt::Join {
+            id,
+            params,
+            body,
+            remainder,
+        } => {
+            joins.insert(*id, params);
+            propagate(procs, by_name, index, joins, body, changed);
+            propagate(procs, by_name, index, joins, remainder, changed);
+        }
+        RawStmt::Switch {
+            branches, default, ..
+        } => {
+            for (_, branch) in branches.iter() {
+                propagate(procs, by_name, index, joins, branch, changed);
+            }
+            propagate(procs, by_name, index, joins, default, changed);
+        }
+        RawStmt::Refcounting(_, rest)
+        | RawStmt::Dbg(_, rest)
+        | RawStmt::Expect(_, rest)
+        | RawStmt::ExpectFx(_, rest) => {
+            propagate(procs, by_name, index, joins, rest, changed);
+        }
+        RawStmt::Ret(_) | RawStmt::Crash(_) => {}
+    }
+}
+
+/// Turns a [RawProc] into a [Proc], once all the layouts it needs are known.
+struct Resolver<'a, 'r, I> {
+    arena: &'a Bump,
+    interner: &'r I,
+    proc: &'r RawProc<'a>,
+}
+
+impl<'a, 'r, I> Resolver<'a, 'r, I>
+where
+    I: LayoutInterner<'a>,
+{
+    fn error(&self, message: String) -> ParseError {
+        ParseError {
+            line: self.proc.line,
+            message: format!(
+                "in procedure `{}`: {message}",
+                symbol_to_doc_string(self.proc.name, false)
+            ),
+        }
+    }
+
+    fn layout_of(&self, symbol: Symbol) -> Result<InLayout<'a>, ParseError> {
+        self.proc.layouts.get(&symbol).copied().ok_or_else(|| {
+            let symbol = symbol_to_doc_string(symbol, false);
+            self.error(format!(
+                "cannot infer the layout of `{symbol}`, annotate it as `{symbol}: <layout>`"
+            ))
+        })
+    }
+
+    fn layouts_of(&self, symbols: &[Symbol]) -> Result<&'a [InLayout<'a>], ParseError> {
+        let layouts = symbols
+            .iter()
+            .map(|symbol| self.layout_of(*symbol))
+            .collect::<Result<std::vec::Vec<_>, _>>()?;
+
+        Ok(self.arena.alloc_slice_copy(&layouts))
+    }
+
+    fn union_of(&self, structure: Symbol) -> Result<UnionLayout<'a>, ParseError> {
+        match self.interner.chase_recursive(self.layout_of(structure)?) {
+            LayoutRepr::Union(union_layout) => Ok(union_layout),
+            _ => Err(self.error(format!(
+                "`{}` is not a union",
+                symbol_to_doc_string(structure, false)
+            ))),
+        }
+    }
+
+    fn proc(&self) -> Result<Proc<'a>, ParseError> {
+        let args = self
+            .proc
+            .args
+            .iter()
+            .map(|arg| Ok((self.layout_of(*arg)?, *arg)))
+            .collect::<Result<std::vec::Vec<_>, ParseError>>()?;
+
+        let ret_layout = self.proc.ret_layout.ok_or_else(|| {
+            self.error(
+                "cannot infer the return layout, annotate it as `procedure : <name> <layout>`"
+                    .into(),
+            )
+        })?;
+
+        Ok(Proc {
+            name: LambdaName::no_niche(self.proc.name),
+            args: self.arena.alloc_slice_copy(&args),
+            body: self.stmt(self.proc.body, ret_layout)?,
+            closure_data_layout: None,
+            ret_layout,
+            is_self_recursive: SelfRecursive::NotSelfRecursive,
+            is_erased: false,
+        })
+    }
+
+    fn stmt(&self, stmt: &RawStmt<'a>, ret_layout: InLayout<'a>) -> Result<Stmt<'a>, ParseError> {
+        let arena = self.arena;
+        let rest = |rest| -> Result<&'a Stmt<'a>, ParseError> {
+            Ok(arena.alloc(self.stmt(rest, ret_layout)?))
+        };
+
+        let stmt = match stmt {
+            RawStmt::Let(symbol, expr, layout, remainder) => Stmt::Let(
+                *symbol,
+                self.expr(expr, *layout)?,
+                *layout,
+                rest(remainder)?,
+            ),
+            RawStmt::Refcounting(modify, remainder) => Stmt::Refcounting(*modify, rest(remainder)?),
+            RawStmt::Dbg(symbol, remainder) => Stmt::Dbg {
+                source_location: "",
+                source: "",
+                symbol: *symbol,
+                variable: Variable::NULL,
+                remainder: rest(remainder)?,
+            },
+            RawStmt::Expect(condition, remainder) => Stmt::Expect {
+                condition: *condition,
+                region: Region::zero(),
+                lookups: &[],
+                variables: &[],
+                remainder: rest(remainder)?,
+            },
+            RawStmt::ExpectFx(condition, remainder) => Stmt::ExpectFx {
+                condition: *condition,
+                region: Region::zero(),
+                lookups: &[],
+                variables: &[],
+                remainder: rest(remainder)?,
+            },
+            RawStmt::Ret(symbol) => Stmt::Ret(*symbol),
+            RawStmt::Switch {
+                cond,
+                branches,
+                default,
+            } => {
+                let mut resolved = AVec::with_capacity_in(branches.len(), arena);
+                for (value, branch) in branches.iter() {
+                    resolved.push((*value, BranchInfo::None, self.stmt(branch, ret_layout)?));
+                }
+
+                Stmt::Switch {
+                    cond_symbol: *cond,
+                    cond_layout: self.layout_of(*cond)?,
+                    branches: resolved.into_bump_slice(),
+                    default_branch: (BranchInfo::None, rest(default)?),
+                    ret_layout,
+                }
+            }
+            RawStmt::Join {
+                id,
+                params,
+                body,
+                remainder,
+            } => {
+                let params = params
+                    .iter()
+                    .map(|symbol| {
+                        Ok(Param {
+                            symbol: *symbol,
+                            layout: self.layout_of(*symbol)?,
+                        })
+                    })
+                    .collect::<Result<std::vec::Vec<_>, ParseError>>()?;
+
+                Stmt::Join {
+                    id: *id,
+                    parameters: arena.alloc_slice_copy(&params),
+                    body: rest(body)?,
+                    remainder: rest(remainder)?,
+                }
+            }
+            RawStmt::Jump(id, args) => Stmt::Jump(*id, args),
+            RawStmt::Crash(symbol) => Stmt::Crash(*symbol, CrashTag::User),
+        };
+
+        Ok(stmt)
+    }
+
+    fn expr(&self, expr: &RawExpr<'a>, layout: InLayout<'a>) -> Result<Expr<'a>, ParseError> {
+        let expr = match expr {
+            RawExpr::Done(expr) => expr.clone(),
+            RawExpr::CallByName(name, arguments) => Expr::Call(Call {
+                call_type: CallType::ByName {
+                    name: LambdaName::no_niche(*name),
+                    ret_layout: layout,
+                    arg_layouts: self.layouts_of(arguments)?,
+                    specialization_id: CallSpecId::BACKEND_DUMMY,
+                },
+                arguments,
+            }),
+            RawExpr::CallByPtr(pointer, arguments) => Expr::Call(Call {
+                call_type: CallType::ByPointer {
+                    pointer: *pointer,
+                    ret_layout: layout,
+                    arg_layouts: self.layouts_of(arguments)?,
+                },
+                arguments,
+            }),
+            RawExpr::StructAtIndex { index, structure } => {
+                match self.interner.get_repr(self.layout_of(*structure)?) {
+                    LayoutRepr::Struct(field_layouts) => Expr::StructAtIndex {
+                        index: *index,
+                        field_layouts,
+                        structure: *structure,
+                    },
+                    _ => {
+                        return Err(self.error(format!(
+                            "`{}` is not a struct",
+                            symbol_to_doc_string(*structure, false)
+                        )))
+                    }
+                }
+            }
+            RawExpr::GetTagId(structure) => Expr::GetTagId {
+                structure: *structure,
+                union_layout: self.union_of(*structure)?,
+            },
+            RawExpr::UnionAtIndex {
+                structure,
+                tag_id,
+                index,
+            } => Expr::UnionAtIndex {
+                structure: *structure,
+                tag_id: *tag_id,
+                union_layout: self.union_of(*structure)?,
+                index: *index,
+            },
+            RawExpr::GetElementPointer { structure, indices } => Expr::GetElementPointer {
+                structure: *structure,
+                union_layout: self.union_of(*structure)?,
+                indices,
+            },
+            RawExpr::Alloca(initializer) => {
+                let element_layout = match self.interner.get_repr(layout) {
+                    LayoutRepr::Ptr(element_layout) => element_layout,
+                    _ => match initializer {
+                        Some(initializer) => self.layout_of(*initializer)?,
+                        None => {
+                            return Err(self.error(
+                                "an uninitialized `Alloca` must be bound with a `Ptr` layout"
+                                    .into(),
+                            ))
+                        }
+                    },
+                };
+
+                Expr::Alloca {
+                    element_layout,
+                    initializer: *initializer,
+                }
+            }
+        };
+
+        Ok(expr)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ir::{substitute_in_exprs, Env, Expr, Procs, Stmt};
+use crate::ir::{boxed, substitute_in_exprs, Env, Expr, Procs, Stmt};
 use crate::layout::{
     self, Builtin, InLayout, Layout, LayoutCache, LayoutInterner, LayoutProblem, LayoutRepr,
     TagIdIntType, UnionLayout, WrappedVariant,
@@ -1640,6 +1640,13 @@ fn store_tag_pattern<'a>(
 
     let mut is_productive = false;
 
+    let is_nullable_pointer_niche = match arguments {
+        [(_, arg_layout)] => {
+            union_layout.is_nullable_pointer_niche(&layout_cache.interner, tag_id, &[*arg_layout])
+        }
+        _ => false,
+    };
+
     for (index, (argument, arg_layout)) in arguments.iter().enumerate().rev() {
         let mut arg_layout = *arg_layout;
 
@@ -1648,11 +1655,15 @@ fn store_tag_pattern<'a>(
             arg_layout = layout_cache.put_in_direct_no_semantic(LayoutRepr::Union(union_layout));
         }
 
-        let load = Expr::UnionAtIndex {
-            index: index as u64,
-            structure,
-            tag_id,
-            union_layout,
+        let load = if is_nullable_pointer_niche {
+            boxed::cast_nullable_pointer_niche(env.arena.alloc(structure))
+        } else {
+            Expr::UnionAtIndex {
+                index: index as u64,
+                structure,
+                tag_id,
+                union_layout,
+            }
         };
 
         match argument {
@@ -1,5 +1,4 @@
 use crate::ir::Parens;
-use crate::layout::intern::NeedsRecursionPointerFixup;
 use bitvec::vec::BitVec;
 use bumpalo::collections::Vec;
 use bumpalo::Bump;
@@ -32,7 +31,9 @@ mod semantic;
 
 pub use erased::Erased;
 pub use intern::{
-    GlobalLayoutInterner, InLayout, LayoutInterner, STLayoutInterner, TLLayoutInterner,
+    GlobalLayoutInterner, InLayout, InternerStats, LayoutInterner, LayoutInternerObserver,
+    LayoutRemap, NeedsRecursionPointerFixup, STLayoutInterner, SnapshotError, TLLayoutInterner,
+    VariantCounts,
 };
 pub use semantic::SemanticRepr;
 
@@ -466,6 +467,9 @@ macro_rules! cached {
 pub type TagIdIntType = u16;
 pub const MAX_ENUM_SIZE: usize = std::mem::size_of::<TagIdIntType>() * 8;
 const GENERATE_NULLABLE: bool = true;
+/// Store `[Some (Box a), None]`-shaped unions as a nullable pointer, see
+/// [UnionLayout::is_nullable_pointer_niche].
+const GENERATE_NULLABLE_POINTER_NICHE: bool = true;
 
 #[derive(Debug, Clone, Copy)]
 pub enum LayoutProblem {
@@ -965,17 +969,14 @@ impl<'a> UnionLayout<'a> {
     }
 
     fn stores_tag_id_in_pointer_bits(tags: &[&[InLayout<'a>]], target: Target) -> bool {
-        tags.len() < target.ptr_width() as usize
+        tags.len() as u64 <= target.tag_id_pointer_mask()
     }
 
-    pub const POINTER_MASK_32BIT: usize = 0b0000_0111;
-    pub const POINTER_MASK_64BIT: usize = 0b0000_0011;
-
     pub fn tag_id_pointer_bits_and_mask(target: Target) -> (usize, usize) {
-        match target.ptr_width() {
-            PtrWidth::Bytes8 => (3, Self::POINTER_MASK_64BIT),
-            PtrWidth::Bytes4 => (2, Self::POINTER_MASK_32BIT),
-        }
+        (
+            target.tag_id_pointer_bits() as usize,
+            target.tag_id_pointer_mask() as usize,
+        )
     }
 
     // i.e. it is not implicit and not stored in the pointer bits
@@ -1020,6 +1021,37 @@ impl<'a> UnionLayout<'a> {
         }
     }
 
+    /// Is this the nullable pointer niche of a `[Some (Box a), None]`-shaped union, where the
+    /// `Box a` payload of `tag_id` is not stored in the union, but is the union itself?
+    ///
+    /// The allocation of such a union is the allocation of its box, and the nullary tag is the
+    /// null pointer. Hence the payload must be read and written with a pointer cast, never with
+    /// `UnionAtIndex` or `Tag`.
+    pub fn is_nullable_pointer_niche<I>(
+        &self,
+        interner: &I,
+        tag_id: TagIdIntType,
+        field_layouts: &[InLayout<'a>],
+    ) -> bool
+    where
+        I: LayoutInterner<'a>,
+    {
+        match (self, field_layouts) {
+            (
+                UnionLayout::NullableUnwrapped {
+                    nullable_id,
+                    other_fields: [inner],
+                },
+                [boxed],
+            ) if *nullable_id != (tag_id != 0) => matches!(
+                interner.get_repr(*boxed),
+                LayoutRepr::Union(UnionLayout::NonNullableUnwrapped([boxed_inner]))
+                    if boxed_inner == inner
+            ),
+            _ => false,
+        }
+    }
+
     fn tags_alignment_bytes<I>(interner: &I, tags: &[&'a [InLayout<'a>]]) -> u32
     where
         I: LayoutInterner<'a>,
@@ -3070,15 +3102,12 @@ impl<'a> Builtin<'a> {
     pub fn stack_size(&self, target: Target) -> u32 {
         use Builtin::*;
 
-        let ptr_width = target.ptr_width() as u32;
-
         match self {
             Int(int) => int.stack_size(),
             Float(float) => float.stack_size(),
             Bool => Builtin::I1_SIZE,
             Decimal => Builtin::DECIMAL_SIZE,
-            Str => Builtin::STR_WORDS * ptr_width,
-            List(_) => Builtin::LIST_WORDS * ptr_width,
+            Str | List(_) => target.str_list_size() as u32,
         }
     }
 
@@ -3086,7 +3115,7 @@ impl<'a> Builtin<'a> {
         use std::mem::align_of;
         use Builtin::*;
 
-        let ptr_width = target.ptr_width() as u32;
+        let ptr_alignment = target.ptr_alignment_bytes() as u32;
 
         // for our data structures, what counts is the alignment of the `( ptr, len )` tuple, and
         // since both of those are one pointer size, the alignment of that structure is a pointer
@@ -3095,14 +3124,14 @@ impl<'a> Builtin<'a> {
             Int(int_width) => int_width.alignment_bytes(target),
             Float(float_width) => float_width.alignment_bytes(target),
             Bool => align_of::<bool>() as u32,
-            Decimal => IntWidth::I128.alignment_bytes(target),
+            Decimal => target.i128_alignment_bytes() as u32,
             // we often treat these as i128 (64-bit systems)
             // or i64 (32-bit systems).
             //
             // In webassembly, For that to be safe
             // they must be aligned to allow such access
-            List(_) => ptr_width,
-            Str => ptr_width,
+            List(_) => ptr_alignment,
+            Str => ptr_alignment,
         }
     }
 
@@ -3199,7 +3228,7 @@ impl<'a> Builtin<'a> {
             Builtin::Int(int_width) => int_width.alignment_bytes(target).max(ptr_width),
             Builtin::Float(float_width) => float_width.alignment_bytes(target).max(ptr_width),
             Builtin::Bool => (core::mem::align_of::<bool>() as u32).max(ptr_width),
-            Builtin::Decimal => IntWidth::I128.alignment_bytes(target).max(ptr_width),
+            Builtin::Decimal => (target.i128_alignment_bytes() as u32).max(ptr_width),
         };
 
         allocation.max(ptr_width)
@@ -3890,6 +3919,13 @@ where
                 Vec::with_capacity_in(tags_list.len(), env.arena);
             let mut has_any_arguments = false;
 
+            let nullable_pointer = find_nullable_pointer_niche(
+                env.subs,
+                tags_list
+                    .iter()
+                    .map(|(name, arguments)| (*name, *arguments)),
+            );
+
             let mut inhabited_tag_ids = BitVec::<usize>::repeat(true, num_tags);
 
             for &&(tag_name, arguments) in tags_list.iter() {
@@ -3971,8 +4007,25 @@ where
                     Cacheable(UnionVariant::ByteUnion(tag_names), cache_criteria)
                 }
                 _ => {
-                    let variant = WrappedVariant::NonRecursive {
-                        sorted_tag_layouts: answer,
+                    // an erroneous box is dropped from the payload, leaving nothing to point to
+                    let nullable_pointer = nullable_pointer
+                        .filter(|nullable_id| answer[!nullable_id as usize].1.len() == 1);
+
+                    let variant = match nullable_pointer {
+                        Some(nullable_id) => {
+                            let (other_name, other_fields) = answer.remove(!nullable_id as usize);
+                            let (nullable_name, _) = answer.remove(0);
+
+                            WrappedVariant::NullableUnwrapped {
+                                nullable_id,
+                                nullable_name,
+                                other_name,
+                                other_fields,
+                            }
+                        }
+                        None => WrappedVariant::NonRecursive {
+                            sorted_tag_layouts: answer,
+                        },
                     };
 
                     Cacheable(UnionVariant::Wrapped(variant), cache_criteria)
@@ -4023,6 +4076,50 @@ where
     }
 }
 
+/// A non-recursive union of one nullary tag and one tag whose only payload is a `Box` does not
+/// need a tag id: the box pointer is never null, so the null pointer can represent the nullary
+/// tag. Returns whether the nullary tag is the second (`true`) or the first (`false`) tag.
+///
+/// Floats have no such niche: every NaN bit pattern is a valid `F32` or `F64` value in Roc.
+fn find_nullable_pointer_niche<'a, L, I>(subs: &Subs, tags: I) -> Option<bool>
+where
+    I: ExactSizeIterator<Item = (&'a L, &'a [Variable])>,
+    L: Into<TagOrClosure> + Clone + 'a,
+{
+    if !GENERATE_NULLABLE_POINTER_NICHE || tags.len() != 2 {
+        return None;
+    }
+
+    let mut nullable_id = None;
+    let mut boxed_payloads = 0;
+
+    for (index, (name, variables)) in tags.enumerate() {
+        if !matches!(name.clone().into(), TagOrClosure::Tag(_)) {
+            return None;
+        }
+
+        match variables {
+            [] => nullable_id = Some(index == 1),
+            [var] if is_box(subs, *var) => boxed_payloads += 1,
+            _ => return None,
+        }
+    }
+
+    if boxed_payloads == 1 {
+        nullable_id
+    } else {
+        None
+    }
+}
+
+fn is_box(subs: &Subs, var: Variable) -> bool {
+    match subs.get_content_without_compacting(var) {
+        Content::Structure(FlatType::Apply(Symbol::BOX_BOX_TYPE, _)) => true,
+        Content::Alias(_, _, actual, _) => is_box(subs, *actual),
+        _ => false,
+    }
+}
+
 fn union_sorted_tags_help<'a, L>(
     env: &mut Env<'a, '_>,
     mut tags_vec: std::vec::Vec<(L, std::vec::Vec<Variable>)>,
@@ -4102,12 +4199,21 @@ where
             let mut nullable = None;
             let mut inhabited_tag_ids = BitVec::<usize>::repeat(true, num_tags);
 
-            // only recursive tag unions can be nullable
+            // only recursive tag unions can be nullable, unless the pointer comes from a box
             let is_recursive = opt_rec_var.is_some();
             if is_recursive && GENERATE_NULLABLE {
                 nullable = find_nullable_tag(tags_vec.iter().map(|(a, b)| (a, b.as_slice())));
             }
 
+            let nullable_pointer = if is_recursive {
+                None
+            } else {
+                find_nullable_pointer_niche(
+                    env.subs,
+                    tags_vec.iter().map(|(a, b)| (a, b.as_slice())),
+                )
+            };
+
             for (index, (tag_name, arguments)) in tags_vec.into_iter().enumerate() {
                 // reserve space for the tag discriminant
                 if matches!(nullable, Some((i, _)) if i  as usize == index) {
@@ -4232,6 +4338,18 @@ where
                         WrappedVariant::Recursive {
                             sorted_tag_layouts: answer,
                         }
+                    } else if let Some(nullable_id) = nullable_pointer
+                        .filter(|nullable_id| answer[!nullable_id as usize].1.len() == 1)
+                    {
+                        let (other_name, other_fields) = answer.remove(!nullable_id as usize);
+                        let (nullable_name, _) = answer.remove(0);
+
+                        WrappedVariant::NullableUnwrapped {
+                            nullable_id,
+                            nullable_name,
+                            other_name,
+                            other_fields,
+                        }
                     } else {
                         WrappedVariant::NonRecursive {
                             sorted_tag_layouts: answer,
@@ -4387,6 +4505,26 @@ where
                     env.cache.put_in(layout)
                 }
 
+                NullableUnwrapped {
+                    nullable_id,
+                    other_fields: [boxed],
+                    ..
+                } => {
+                    // the nullable pointer niche: the union is the allocation of its box
+                    let inner = match env.cache.get_repr(*boxed) {
+                        LayoutRepr::Union(UnionLayout::NonNullableUnwrapped([inner])) => inner,
+                        other => internal_error!("nullable pointer niche of a non-box {other:?}"),
+                    };
+
+                    let repr = LayoutRepr::Union(UnionLayout::NullableUnwrapped {
+                        nullable_id,
+                        other_fields: env.arena.alloc([*inner]),
+                    });
+
+                    env.cache
+                        .put_in(Layout::new(repr.direct(), compute_semantic()))
+                }
+
                 Recursive { .. }
                 | NullableWrapped { .. }
                 | NullableUnwrapped { .. }
@@ -4769,6 +4907,10 @@ impl<'a> LayoutIds<'a> {
 /// Compare two fields when sorting them for code gen.
 /// This is called by both code gen and glue, so that
 /// their field orderings agree.
+///
+/// Ordering by descending alignment means every field starts out aligned, so a struct only
+/// needs padding at its end. Struct sizes are computed by adding up field sizes, which relies on
+/// this. Tag payloads are sorted by alignment in the same way.
 #[inline(always)]
 pub fn cmp_fields<'a, L: Ord, I>(
     interner: &I,
@@ -4792,21 +4934,20 @@ mod test {
 
     #[test]
     fn width_and_alignment_union_empty_struct() {
+        let arena = &Bump::new();
         let mut interner = STLayoutInterner::with_capacity(4, Target::LinuxX64);
 
-        let lambda_set = LambdaSet {
-            args: &(&[] as &[InLayout]),
-            ret: Layout::VOID,
-            set: &(&[(Symbol::LIST_MAP, &[] as &[InLayout])] as &[(Symbol, &[InLayout])]),
-            representation: Layout::UNIT,
-            full_layout: Layout::VOID,
-        };
+        let lambda_set = interner.insert_lambda_set(
+            arena,
+            &(&[] as &[InLayout]),
+            Layout::VOID,
+            &(&[(Symbol::LIST_MAP, &[] as &[InLayout])] as &[(Symbol, &[InLayout])]),
+            NeedsRecursionPointerFixup(false),
+            Layout::UNIT,
+        );
 
         let a = &[Layout::UNIT] as &[_];
-        let b = &[interner.insert(Layout {
-            repr: LayoutRepr::LambdaSet(lambda_set).direct(),
-            semantic: SemanticRepr::NONE,
-        })] as &[_];
+        let b = &[lambda_set.full_layout] as &[_];
         let tt = [a, b];
 
         let repr = LayoutRepr::Union(UnionLayout::NonRecursive(&tt));
@@ -4842,4 +4983,82 @@ mod test {
         let interner = STLayoutInterner::with_capacity(4, Target::LinuxX64);
         assert_eq!(interner.alignment_bytes(Layout::U128), 16);
     }
+
+    #[test]
+    fn layouts_agree_with_target() {
+        use strum::IntoEnumIterator;
+
+        for target in Target::iter() {
+            let interner = STLayoutInterner::with_capacity(4, target);
+            let ptr_size = target.ptr_size() as u32;
+            let ptr_alignment = target.ptr_alignment_bytes() as u32;
+
+            for layout in [Layout::STR, Layout::LIST_U8] {
+                assert_eq!(interner.stack_size(layout), Builtin::STR_WORDS * ptr_size);
+                assert_eq!(interner.stack_size(layout), target.str_list_size() as u32);
+                assert_eq!(interner.alignment_bytes(layout), ptr_alignment);
+            }
+            assert_eq!(interner.stack_size(Layout::OPAQUE_PTR), ptr_size);
+            assert_eq!(interner.alignment_bytes(Layout::OPAQUE_PTR), ptr_alignment);
+
+            // the tag id bits are exactly the ones pointer alignment leaves free
+            let (bits, mask) = UnionLayout::tag_id_pointer_bits_and_mask(target);
+            assert_eq!(1 << bits, ptr_alignment as usize, "{target}");
+            assert_eq!(mask + 1, ptr_alignment as usize, "{target}");
+
+            let tags = vec![&[] as &[InLayout]; mask + 1];
+            assert!(UnionLayout::Recursive(&tags[..mask]).stores_tag_id_in_pointer(target));
+            assert!(!UnionLayout::Recursive(&tags).stores_tag_id_in_pointer(target));
+        }
+    }
+
+    #[test]
+    fn sorted_fields_only_pad_at_the_end() {
+        let interner = STLayoutInterner::with_capacity(4, Target::LinuxX64);
+
+        let mut fields = [("a", Layout::U8), ("b", Layout::U64), ("c", Layout::U16)];
+        fields.sort_by(|(label1, layout1), (label2, layout2)| {
+            cmp_fields(&interner, label1, *layout1, label2, *layout2)
+        });
+        let layouts = fields.map(|(_, layout)| layout);
+        assert_eq!(layouts, [Layout::U64, Layout::U16, Layout::U8]);
+
+        let mut offset = 0;
+        for layout in layouts {
+            assert_eq!(offset % interner.alignment_bytes(layout), 0);
+            offset += interner.stack_size(layout);
+        }
+        assert_eq!(LayoutRepr::Struct(&layouts).stack_size(&interner), 16);
+    }
+
+    #[test]
+    fn i128_alignment_agrees_with_target() {
+        use strum::IntoEnumIterator;
+
+        for target in Target::iter() {
+            let mut interner = STLayoutInterner::with_capacity(4, target);
+            let align = interner.align_of_i128();
+            assert_eq!(align, target.i128_alignment_bytes() as u32);
+
+            for layout in [Layout::I128, Layout::U128, Layout::DEC] {
+                assert_eq!(interner.stack_size(layout), 16, "{target}");
+                assert_eq!(interner.alignment_bytes(layout), align, "{target}");
+            }
+
+            // a byte followed by a wide number is padded up to the wide number's alignment
+            let record =
+                interner.insert_direct_no_semantic(LayoutRepr::Struct(&[Layout::U8, Layout::I128]));
+            assert_eq!(interner.alignment_bytes(record), align, "{target}");
+            assert_eq!(interner.stack_size(record), align + 16, "{target}");
+
+            // a union stores its tag id after the data, padded to the same alignment
+            let tags = [&[Layout::DEC] as &[_], &[Layout::U8]];
+            let union_layout = UnionLayout::NonRecursive(&tags);
+            assert_eq!(
+                union_layout.data_size_and_alignment(&interner),
+                (16 + align, align),
+                "{target}"
+            );
+        }
+    }
 }
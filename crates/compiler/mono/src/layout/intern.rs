@@ -1,4 +1,4 @@
-use std::{cell::RefCell, hash::BuildHasher, marker::PhantomData, sync::Arc};
+use std::{cell::RefCell, hash::BuildHasher, marker::PhantomData, ops::Range, sync::Arc};
 
 use bumpalo::Bump;
 use parking_lot::{Mutex, RwLock};
@@ -7,8 +7,10 @@ use roc_collections::{default_hasher, BumpMap};
 use roc_module::symbol::Symbol;
 use roc_target::Target;
 
-use crate::layout::LayoutRepr;
+use crate::layout::{Builtin, LayoutRepr};
 
+use self::owned::OwnedArena;
+use super::semantic::SemanticParts;
 use super::{LambdaSet, Layout, LayoutWrapper, SeenRecPtrs, SemanticRepr, UnionLayout};
 
 macro_rules! cache_interned_layouts {
@@ -22,8 +24,10 @@ macro_rules! cache_interned_layouts {
 
         fn fill_reserved_layouts(interner: &mut STLayoutInterner<'_>) {
             assert!(interner.is_empty());
+            // Bypass `insert`, whose fast paths hand out these slots before they are filled.
             $(
-            interner.insert($layout);
+            interner.map.insert($layout, Layout::$name);
+            interner.vec.push($layout);
             )*
         }
 
@@ -39,6 +43,9 @@ macro_rules! cache_interned_layouts {
 
         const _ASSERT_NON_REDUNDANT_CONSTANTS: () =
             assert!(_are_constants_in_order_non_redundant() == $total_constants);
+
+        /// How many layouts every interner starts out with.
+        const RESERVED_LAYOUTS: usize = $total_constants;
     }
 }
 
@@ -73,9 +80,34 @@ cache_interned_layouts! {
     18, ERASED, pub, nosema!(LayoutRepr::ERASED)
     19, NAKED_RECURSIVE_PTR,  pub(super), nosema!(LayoutRepr::RecursivePointer(Layout::VOID))
     20, STR_PTR, pub, nosema!(LayoutRepr::Ptr(Layout::STR))
-    21, LIST_U8, pub, nosema!(LayoutRepr::Builtin(crate::layout::Builtin::List(Layout::U8)))
+    21, LIST_U8, pub, nosema!(LayoutRepr::Builtin(Builtin::List(Layout::U8)))
+    22, LIST_STR, pub, nosema!(LayoutRepr::Builtin(Builtin::List(Layout::STR)))
+    23, LIST_I64, pub, nosema!(LayoutRepr::Builtin(Builtin::List(Layout::I64)))
+    24, LIST_DEC, pub, nosema!(LayoutRepr::Builtin(Builtin::List(Layout::DEC)))
+
+    ; 25
+}
 
-    ; 22
+/// The reserved slot of a list of one of the most common element layouts, if `layout` is one.
+///
+/// These lists dominate insertions in real programs, so `insert` looks them up here before
+/// hashing.
+#[inline(always)]
+fn reserved_list(layout: &Layout) -> Option<InLayout<'static>> {
+    match layout.repr {
+        LayoutWrapper::Direct(LayoutRepr::Builtin(Builtin::List(element)))
+            if layout.semantic == SemanticRepr::NONE =>
+        {
+            match element {
+                Layout::U8 => Some(Layout::LIST_U8),
+                Layout::STR => Some(Layout::LIST_STR),
+                Layout::I64 => Some(Layout::LIST_I64),
+                Layout::DEC => Some(Layout::LIST_DEC),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
 }
 
 macro_rules! impl_to_from_int_width {
@@ -205,6 +237,11 @@ pub trait LayoutInterner<'a>: Sized {
 
     fn target(&self) -> Target;
 
+    /// Alignment of the 128-bit builtins (`I128`, `U128` and `Dec`) on this interner's target.
+    fn align_of_i128(&self) -> u32 {
+        self.target().i128_alignment_bytes() as u32
+    }
+
     fn alignment_bytes(&self, layout: InLayout<'a>) -> u32 {
         self.get_repr(layout).alignment_bytes(self)
     }
@@ -285,24 +322,21 @@ pub trait LayoutInterner<'a>: Sized {
     ///     that the recorded layout of `x` is at a different depth than that determined when we
     ///     index the recorded layout of `f` at 0. Hence the two layouts may have different
     ///     interned representations, even if they are in fact isomorphic.
+    ///
+    /// Recursive pointers are followed, so two copies of a recursive layout that close their
+    /// cycles at different points are equivalent. The `full_layout` of a lambda set is ignored.
     fn equiv(&self, l1: InLayout<'a>, l2: InLayout<'a>) -> bool {
-        std::thread_local! {
-            static SCRATCHPAD: RefCell<Option<Vec<(InLayout<'static>, InLayout<'static>)>>> = RefCell::new(Some(Vec::with_capacity(64)));
-        }
-
-        SCRATCHPAD.with(|f| {
-            // SAFETY: the promotion to lifetime 'a only lasts during equivalence-checking; the
-            // scratchpad stack is cleared after every use.
-            let mut stack: Vec<(InLayout<'a>, InLayout<'a>)> =
-                unsafe { std::mem::transmute(f.take().unwrap()) };
-
-            let answer = equiv::equivalent(&mut stack, self, l1, l2);
-            stack.clear();
+        equiv::with_scratchpad(|scratchpad| {
+            equiv::equivalent(scratchpad, self, l1, l2, equiv::LambdaSets::Compare)
+        })
+    }
 
-            let stack: Vec<(InLayout<'static>, InLayout<'static>)> =
-                unsafe { std::mem::transmute(stack) };
-            f.replace(Some(stack));
-            answer
+    /// Like [equiv][LayoutInterner::equiv], but every lambda set, at any depth, is replaced by
+    /// its runtime representation first. Equivalent layouts then have the same memory layout, and
+    /// a value of one can be used wherever the other is expected.
+    fn equiv_runtime(&self, l1: InLayout<'a>, l2: InLayout<'a>) -> bool {
+        equiv::with_scratchpad(|scratchpad| {
+            equiv::equivalent(scratchpad, self, l1, l2, equiv::LambdaSets::UseRuntimeRepr)
         })
     }
 
@@ -474,6 +508,9 @@ impl std::fmt::Debug for InLayout<'_> {
             Layout::NAKED_RECURSIVE_PTR => f.write_str("InLayout(NAKED_RECURSIVE_PTR)"),
             Layout::STR_PTR => f.write_str("InLayout(STR_PTR)"),
             Layout::LIST_U8 => f.write_str("InLayout(LIST_U8)"),
+            Layout::LIST_STR => f.write_str("InLayout(LIST_STR)"),
+            Layout::LIST_I64 => f.write_str("InLayout(LIST_I64)"),
+            Layout::LIST_DEC => f.write_str("InLayout(LIST_DEC)"),
             _ => f.debug_tuple("InLayout").field(&self.0).finish(),
         }
     }
@@ -524,8 +561,10 @@ impl<'a> InLayout<'a> {
 
 /// A concurrent interner, suitable for usage between threads.
 ///
-/// The interner does not currently maintain its own arena; you will have to supply
-/// values-to-be-interned as allocated in an independent arena.
+/// By default the interner does not maintain its own arena; you will have to supply
+/// values-to-be-interned as allocated in an independent arena, which must then outlive the
+/// interner. [GlobalLayoutInterner::with_owned_arena] creates an interner that copies values into
+/// an arena of its own instead.
 ///
 /// If you need a concurrent global interner, you'll likely want each thread to take a
 /// [TLLayoutInterner] via [GlobalLayoutInterner::fork], for caching purposes.
@@ -541,6 +580,36 @@ struct GlobalLayoutInternerInner<'a> {
     normalized_lambda_set_map: Mutex<BumpMap<LambdaSet<'a>, LambdaSet<'a>>>,
     vec: RwLock<Vec<Layout<'a>>>,
     target: Target,
+    /// Always locked last.
+    owned_arena: Mutex<Option<OwnedArena>>,
+    #[cfg(debug_assertions)]
+    observer: RwLock<Option<Arc<dyn LayoutInternerObserver<'a> + 'a>>>,
+}
+
+/// Watches a [GlobalLayoutInterner] as it grows, so that external tools (layout visualizers,
+/// type inspectors) can mirror its contents without patching this crate.
+///
+/// Each callback fires once per new slot, after the interner has released its locks, so an
+/// observer may look layouts up in the interner. Layouts that were already interned, and layouts
+/// interned into a [STLayoutInterner], are not reported.
+///
+/// Observers can only be attached in debug builds; see [GlobalLayoutInterner::set_observer].
+pub trait LayoutInternerObserver<'a>: std::fmt::Debug + Send + Sync {
+    /// A plain layout was interned into the slot `interned`.
+    fn on_insert(&self, _layout: Layout<'a>, _interned: InLayout<'a>) {}
+
+    /// A lambda set was interned. Its layout lives in the slot `lambda_set.full_layout`.
+    fn on_lambda_set(&self, _lambda_set: LambdaSet<'a>) {}
+
+    /// A recursive layout was interned into the slot `interned`. `normalized` is the layout as it
+    /// was given to the interner; `full_layout` has its recursion pointers resolved to `interned`.
+    fn on_recursive(
+        &self,
+        _normalized: Layout<'a>,
+        _full_layout: Layout<'a>,
+        _interned: InLayout<'a>,
+    ) {
+    }
 }
 
 /// A derivative of a [GlobalLayoutInterner] interner that provides caching desirable for
@@ -572,6 +641,7 @@ pub struct STLayoutInterner<'a> {
     normalized_lambda_set_map: BumpMap<LambdaSet<'a>, LambdaSet<'a>>,
     vec: Vec<Layout<'a>>,
     target: Target,
+    owned_arena: Option<OwnedArena>,
 }
 
 /// Interner constructed with an exclusive lock over [GlobalLayoutInterner]
@@ -580,6 +650,7 @@ struct LockedGlobalInterner<'a, 'r> {
     normalized_lambda_set_map: &'r mut BumpMap<LambdaSet<'a>, LambdaSet<'a>>,
     vec: &'r mut Vec<Layout<'a>>,
     target: Target,
+    owned_arena: &'r Option<OwnedArena>,
 }
 
 /// Generic hasher for a value, to be used by all interners.
@@ -612,6 +683,21 @@ impl<'a> GlobalLayoutInterner<'a> {
         STLayoutInterner::with_capacity(cap, target).into_global()
     }
 
+    /// Creates a new global interner with the given capacity, that copies every layout it stores
+    /// into an arena of its own. Nothing it keeps points into the arenas that layouts were built
+    /// in, and [TLLayoutInterner::insert_borrowed] accepts layouts from arenas that are dropped
+    /// before the interner.
+    ///
+    /// # Safety
+    ///
+    /// Layouts taken out of the interner point into its arena, so they must not be used once the
+    /// interner, its forks, and the [STLayoutInterner] it may be unwrapped into are all dropped.
+    pub unsafe fn with_owned_arena(cap: usize, target: Target) -> Self {
+        let mut interner = STLayoutInterner::with_capacity(cap, target);
+        interner.owned_arena = Some(OwnedArena::default());
+        interner.into_global()
+    }
+
     /// Creates a derivative [TLLayoutInterner] pointing back to this global interner.
     pub fn fork(&self) -> TLLayoutInterner<'a> {
         TLLayoutInterner {
@@ -623,6 +709,41 @@ impl<'a> GlobalLayoutInterner<'a> {
         }
     }
 
+    /// Attaches an observer that is told about every layout interned from now on, replacing any
+    /// previous observer. Pass [None] to detach it.
+    ///
+    /// The observer is dropped when the interner is collapsed with [GlobalLayoutInterner::unwrap].
+    #[cfg(debug_assertions)]
+    pub fn set_observer(&self, observer: Option<Arc<dyn LayoutInternerObserver<'a> + 'a>>) {
+        *self.0.observer.write() = observer;
+    }
+
+    /// Runs `notify` against the attached observer, if there is one.
+    /// Never call this while holding one of the interner's locks.
+    #[inline(always)]
+    fn notify(&self, notify: impl FnOnce(&dyn LayoutInternerObserver<'a>)) {
+        #[cfg(debug_assertions)]
+        {
+            // Clone the observer out so that it can intern layouts itself.
+            let observer = self.0.observer.read().clone();
+            if let Some(observer) = observer {
+                notify(observer.as_ref());
+            }
+        }
+
+        #[cfg(not(debug_assertions))]
+        let _ = notify;
+    }
+
+    /// Copies out the layouts in the slots `range`, so they can be reported without holding
+    /// the lock on `vec`.
+    fn layouts_in(&self, range: Range<usize>) -> Vec<(Layout<'a>, InLayout<'a>)> {
+        let vec = self.0.vec.read();
+        range
+            .map(|index| (vec[index], InLayout(index, Default::default())))
+            .collect()
+    }
+
     /// Collapses a shared [GlobalLayoutInterner] into a [STLayoutInterner].
     ///
     /// Returns an [Err] with `self` if there are outstanding references to the [GlobalLayoutInterner].
@@ -632,6 +753,8 @@ impl<'a> GlobalLayoutInterner<'a> {
             normalized_lambda_set_map,
             vec,
             target,
+            owned_arena,
+            ..
         } = match Arc::try_unwrap(self.0) {
             Ok(inner) => inner,
             Err(li) => return Err(Self(li)),
@@ -639,29 +762,82 @@ impl<'a> GlobalLayoutInterner<'a> {
         let map = Mutex::into_inner(map);
         let normalized_lambda_set_map = Mutex::into_inner(normalized_lambda_set_map);
         let vec = RwLock::into_inner(vec);
+        let owned_arena = Mutex::into_inner(owned_arena);
         Ok(STLayoutInterner {
             map,
             normalized_lambda_set_map,
             vec,
             target,
+            owned_arena,
         })
     }
 
+    /// Drops every layout that cannot be reached from `roots`, so that an interner that lives
+    /// across many compilations (in an editor or watch session) does not keep the layouts of all
+    /// of them around.
+    ///
+    /// The reserved layouts, like [Layout::U8], are always kept and keep their indices. The other
+    /// surviving layouts move down to fill the gaps, so any [InLayout] still held elsewhere must be
+    /// translated with the returned [LayoutRemap]. Surviving layouts whose children moved are
+    /// rebuilt in `arena`, or in the interner's own arena if it has one.
+    ///
+    /// Returns [None], leaving the interner untouched, if it has outstanding forks, since their
+    /// caches would go stale.
+    pub fn retain(
+        &mut self,
+        arena: &'a Bump,
+        roots: impl Iterator<Item = InLayout<'a>>,
+    ) -> Option<LayoutRemap<'a>> {
+        let inner = Arc::get_mut(&mut self.0)?;
+        let arena = match inner.owned_arena.get_mut() {
+            Some(owned_arena) => owned_arena.arena(),
+            None => arena,
+        };
+
+        Some(retain::retain(
+            arena,
+            inner.map.get_mut(),
+            inner.normalized_lambda_set_map.get_mut(),
+            inner.vec.get_mut(),
+            roots,
+        ))
+    }
+
+    /// Summarizes what the interner holds so far.
+    pub fn stats(&self) -> InternerStats {
+        // Take the locks one at a time; the numbers only need to be roughly consistent.
+        let map_capacity = self.0.map.lock().capacity();
+        let lambda_set_map_capacity = self.0.normalized_lambda_set_map.lock().capacity();
+        let vec = self.0.vec.read();
+
+        InternerStats::new(&vec, vec.capacity(), map_capacity, lambda_set_map_capacity)
+    }
+
     /// Interns a value with a pre-computed hash.
     /// Prefer calling this when possible, especially from [TLLayoutInterner], to avoid
     /// re-computing hashes.
-    fn insert_hashed(&self, value: Layout<'a>, hash: u64) -> InLayout<'a> {
+    ///
+    /// Also returns the value as the interner keeps it, which is a copy in owned arena mode.
+    fn insert_hashed(&self, value: Layout<'a>, hash: u64) -> (Layout<'a>, InLayout<'a>) {
         let mut map = self.0.map.lock();
-        let (_, interned) = map
+        let mut is_new = false;
+        let (&mut value, &mut interned) = map
             .raw_entry_mut()
             .from_key_hashed_nocheck(hash, &value)
             .or_insert_with(|| {
                 let mut vec = self.0.vec.write();
+                let value = owned::layout(self.0.owned_arena.lock().as_ref(), value);
                 let interned = InLayout(vec.len(), Default::default());
                 vec.push(value);
+                is_new = true;
                 (value, interned)
             });
-        *interned
+        drop(map);
+
+        if is_new {
+            self.notify(|observer| observer.on_insert(value, interned));
+        }
+        (value, interned)
     }
 
     fn get_or_insert_hashed_normalized_lambda_set(
@@ -672,12 +848,13 @@ impl<'a> GlobalLayoutInterner<'a> {
         normalized_hash: u64,
     ) -> WrittenGlobalLambdaSet<'a> {
         let mut normalized_lambda_set_map = self.0.normalized_lambda_set_map.lock();
-        if let Some((_, &full_lambda_set)) = normalized_lambda_set_map
+        if let Some((&normalized, &full_lambda_set)) = normalized_lambda_set_map
             .raw_entry()
             .from_key_hashed_nocheck(normalized_hash, &normalized)
         {
             let full_layout = self.0.vec.read()[full_lambda_set.full_layout.0];
             return WrittenGlobalLambdaSet {
+                normalized,
                 full_lambda_set,
                 full_layout,
             };
@@ -688,6 +865,8 @@ impl<'a> GlobalLayoutInterner<'a> {
         // in.
         let mut map = self.0.map.lock();
         let mut vec = self.0.vec.write();
+        let owned_arena = self.0.owned_arena.lock();
+        let normalized = owned::lambda_set(owned_arena.as_ref(), normalized);
 
         let slot = unsafe { InLayout::from_index(vec.len()) };
         vec.push(Layout::VOID_NAKED);
@@ -698,8 +877,10 @@ impl<'a> GlobalLayoutInterner<'a> {
                 normalized_lambda_set_map: &mut normalized_lambda_set_map,
                 vec: &mut vec,
                 target: self.0.target,
+                owned_arena: &owned_arena,
             };
-            reify::reify_lambda_set_captures(arena, &mut interner, slot, normalized.set)
+            let set = reify::reify_lambda_set_captures(arena, &mut interner, slot, normalized.set);
+            owned::lambda_set_captures(owned_arena.as_ref(), set)
         } else {
             normalized.set
         };
@@ -725,7 +906,19 @@ impl<'a> GlobalLayoutInterner<'a> {
         debug_assert!(_old_normalized.is_none());
 
         let full_layout = vec[full_lambda_set.full_layout.0];
+
+        // Anything after our slot was interned while reifying the captures.
+        let reified = slot.0 + 1..vec.len();
+        drop((map, vec, normalized_lambda_set_map, owned_arena));
+        self.notify(|observer| {
+            for (layout, interned) in self.layouts_in(reified) {
+                observer.on_insert(layout, interned);
+            }
+            observer.on_lambda_set(full_lambda_set);
+        });
+
         WrittenGlobalLambdaSet {
+            normalized,
             full_lambda_set,
             full_layout,
         }
@@ -738,12 +931,13 @@ impl<'a> GlobalLayoutInterner<'a> {
         normalized_hash: u64,
     ) -> WrittenGlobalRecursive<'a> {
         let mut map = self.0.map.lock();
-        if let Some((_, &interned)) = map
+        if let Some((&normalized, &interned)) = map
             .raw_entry()
             .from_key_hashed_nocheck(normalized_hash, &normalized)
         {
             let full_layout = self.0.vec.read()[interned.0];
             return WrittenGlobalRecursive {
+                normalized,
                 interned_layout: interned,
                 full_layout,
             };
@@ -751,6 +945,8 @@ impl<'a> GlobalLayoutInterner<'a> {
 
         let mut vec = self.0.vec.write();
         let mut normalized_lambda_set_map = self.0.normalized_lambda_set_map.lock();
+        let owned_arena = self.0.owned_arena.lock();
+        let normalized = owned::layout(owned_arena.as_ref(), normalized);
 
         let slot = unsafe { InLayout::from_index(vec.len()) };
         vec.push(Layout::VOID_NAKED);
@@ -760,18 +956,32 @@ impl<'a> GlobalLayoutInterner<'a> {
             normalized_lambda_set_map: &mut normalized_lambda_set_map,
             vec: &mut vec,
             target: self.0.target,
+            owned_arena: &owned_arena,
         };
         let full_layout = reify::reify_recursive_layout(arena, &mut interner, slot, normalized);
+        let full_layout = owned::layout(owned_arena.as_ref(), full_layout);
 
         vec[slot.0] = full_layout;
 
         let _old = map.insert(normalized, slot);
         debug_assert!(_old.is_none());
 
+        // Without any recursion pointers, the normalized layout is already the full one.
         let _old_full_layout = map.insert(full_layout, slot);
-        debug_assert!(_old_full_layout.is_none());
+        debug_assert!(_old_full_layout.is_none() || full_layout == normalized);
+
+        // Anything after our slot was interned while reifying the layout.
+        let reified = slot.0 + 1..vec.len();
+        drop((map, vec, normalized_lambda_set_map, owned_arena));
+        self.notify(|observer| {
+            for (layout, interned) in self.layouts_in(reified) {
+                observer.on_insert(layout, interned);
+            }
+            observer.on_recursive(normalized, full_layout, slot);
+        });
 
         WrittenGlobalRecursive {
+            normalized,
             interned_layout: slot,
             full_layout,
         }
@@ -787,17 +997,47 @@ impl<'a> GlobalLayoutInterner<'a> {
     }
 }
 
+/// What the global interner keeps for a lambda set. Thread-local interners cache these, rather than
+/// the values they were given, so that they never point into the arenas of their callers.
 struct WrittenGlobalLambdaSet<'a> {
+    normalized: LambdaSet<'a>,
     full_lambda_set: LambdaSet<'a>,
     full_layout: Layout<'a>,
 }
 
+/// What the global interner keeps for a recursive layout, see [WrittenGlobalLambdaSet].
 struct WrittenGlobalRecursive<'a> {
+    normalized: Layout<'a>,
     interned_layout: InLayout<'a>,
     full_layout: Layout<'a>,
 }
 
 impl<'a> TLLayoutInterner<'a> {
+    /// Summarizes what the global interner this was forked from holds so far.
+    pub fn stats(&self) -> InternerStats {
+        self.parent.stats()
+    }
+
+    /// Interns a layout built in an arena that does not live as long as the interner, such as an
+    /// arena for a single module. This is only possible for an interner made with
+    /// [GlobalLayoutInterner::with_owned_arena], since it keeps a copy of the layout instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interner does not have an arena of its own.
+    pub fn insert_borrowed<'b>(&mut self, value: Layout<'b>) -> InLayout<'a> {
+        if self.parent.0.owned_arena.lock().is_none() {
+            roc_error_macros::internal_error!(
+                "only interners with an owned arena can intern borrowed layouts"
+            );
+        }
+
+        // Lookups only compare against the value, and on a miss the global interner copies it
+        // into its own arena, so `value` is not kept beyond this call.
+        let value = unsafe { std::mem::transmute::<Layout<'b>, Layout<'a>>(value) };
+        self.insert(value)
+    }
+
     /// Records an interned value in thread-specific storage, for faster access on lookups.
     fn record(&self, key: Layout<'a>, interned: InLayout<'a>) {
         let mut vec = self.vec.borrow_mut();
@@ -809,17 +1049,23 @@ impl<'a> TLLayoutInterner<'a> {
 
 impl<'a> LayoutInterner<'a> for TLLayoutInterner<'a> {
     fn insert(&mut self, value: Layout<'a>) -> InLayout<'a> {
+        if let Some(interned) = reserved_list(&value) {
+            return interned;
+        }
+        if cfg!(debug_assertions) {
+            verify::insert(self, value);
+        }
         let global = &self.parent;
         let hash = hash(value);
         let (&mut value, &mut interned) = self
             .map
             .raw_entry_mut()
             .from_key_hashed_nocheck(hash, &value)
-            .or_insert_with(|| {
-                let interned = global.insert_hashed(value, hash);
-                (value, interned)
-            });
+            .or_insert_with(|| global.insert_hashed(value, hash));
         self.record(value, interned);
+        if cfg!(debug_assertions) {
+            verify::inserted(value, interned);
+        }
         interned
     }
 
@@ -853,6 +1099,7 @@ impl<'a> LayoutInterner<'a> for TLLayoutInterner<'a> {
             .from_key_hashed_nocheck(normalized_hash, &normalized)
             .or_insert_with(|| {
                 let WrittenGlobalLambdaSet {
+                    normalized,
                     full_lambda_set,
                     full_layout,
                 } = global.get_or_insert_hashed_normalized_lambda_set(
@@ -883,6 +1130,9 @@ impl<'a> LayoutInterner<'a> for TLLayoutInterner<'a> {
         // - If not, allocate a slot and compute the recursive layout with the recursion pointer
         //   resolving to the new slot.
         // - Point the resolved and normalized layout to the new slot.
+        if cfg!(debug_assertions) {
+            verify::insert_recursive(normalized_layout);
+        }
         let global = &self.parent;
         let normalized_hash = hash(normalized_layout);
         let mut new_interned_full_layout = None;
@@ -892,6 +1142,7 @@ impl<'a> LayoutInterner<'a> for TLLayoutInterner<'a> {
             .from_key_hashed_nocheck(normalized_hash, &normalized_layout)
             .or_insert_with(|| {
                 let WrittenGlobalRecursive {
+                    normalized,
                     interned_layout,
                     full_layout,
                 } = global.get_or_insert_hashed_normalized_recursive(
@@ -904,7 +1155,7 @@ impl<'a> LayoutInterner<'a> for TLLayoutInterner<'a> {
                 // reference.
                 new_interned_full_layout = Some(full_layout);
 
-                (normalized_layout, interned_layout)
+                (normalized, interned_layout)
             });
         if let Some(full_layout) = new_interned_full_layout {
             self.record(full_layout, interned);
@@ -934,6 +1185,7 @@ impl<'a> STLayoutInterner<'a> {
             normalized_lambda_set_map: BumpMap::with_capacity_and_hasher(cap, default_hasher()),
             vec: Vec::with_capacity(cap),
             target,
+            owned_arena: None,
         };
         fill_reserved_layouts(&mut interner);
         interner
@@ -949,35 +1201,261 @@ impl<'a> STLayoutInterner<'a> {
             normalized_lambda_set_map,
             vec,
             target,
+            owned_arena,
         } = self;
         GlobalLayoutInterner(Arc::new(GlobalLayoutInternerInner {
             map: Mutex::new(map),
             normalized_lambda_set_map: Mutex::new(normalized_lambda_set_map),
             vec: RwLock::new(vec),
             target,
+            owned_arena: Mutex::new(owned_arena),
+            #[cfg(debug_assertions)]
+            observer: RwLock::new(None),
         }))
     }
 
     pub fn is_empty(&self) -> bool {
         self.vec.is_empty()
     }
+
+    /// Writes the interned layouts to a versioned binary snapshot that
+    /// [STLayoutInterner::deserialize] can restore.
+    ///
+    /// Layouts keep their indices, so an [InLayout] from this interner refers to the same layout
+    /// in the restored one. Symbols in lambda sets are stored as-is, so they are only meaningful
+    /// alongside the module and ident ids of the build that wrote the snapshot.
+    pub fn serialize(&self) -> Vec<u8> {
+        snapshot::write(self)
+    }
+
+    /// Restores an interner from a snapshot written by [STLayoutInterner::serialize], allocating
+    /// the restored layouts in `arena`.
+    pub fn deserialize(arena: &'a Bump, bytes: &[u8]) -> Result<Self, SnapshotError> {
+        snapshot::read(arena, bytes)
+    }
+
+    /// Summarizes what the interner holds.
+    pub fn stats(&self) -> InternerStats {
+        InternerStats::new(
+            &self.vec,
+            self.vec.capacity(),
+            self.map.capacity(),
+            self.normalized_lambda_set_map.capacity(),
+        )
+    }
+}
+
+/// Where the layouts that survived [GlobalLayoutInterner::retain] ended up.
+#[derive(Debug)]
+pub struct LayoutRemap<'a> {
+    new_indices: Vec<Option<InLayout<'a>>>,
+}
+
+impl<'a> LayoutRemap<'a> {
+    /// The new index of `layout`, or [None] if it was dropped.
+    pub fn get(&self, layout: InLayout<'a>) -> Option<InLayout<'a>> {
+        self.new_indices.get(layout.0).copied().flatten()
+    }
+
+    /// How many layouts were dropped.
+    pub fn dropped(&self) -> usize {
+        self.new_indices
+            .iter()
+            .filter(|index| index.is_none())
+            .count()
+    }
+}
+
+/// How big an interner has grown, for keeping an eye on it in large builds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InternerStats {
+    /// Interned layouts, including the reserved ones.
+    pub entries: usize,
+    /// Interned lambda sets.
+    pub lambda_sets: usize,
+    /// Interned recursive tag unions.
+    pub recursive_layouts: usize,
+    /// Interned layouts by their [LayoutRepr] variant.
+    pub variants: VariantCounts,
+    /// Arena memory the interned layouts point to: field and tag slices, lambda set captures,
+    /// and the names kept for semantic representations. A slice shared by several layouts is
+    /// counted once for each of them.
+    pub arena_bytes: usize,
+    /// Memory held by the interner's own tables, not counting the hash maps' control bytes.
+    pub table_bytes: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VariantCounts {
+    pub builtin: usize,
+    pub struct_: usize,
+    pub ptr: usize,
+    pub union: usize,
+    pub lambda_set: usize,
+    pub recursive_pointer: usize,
+    pub function_pointer: usize,
+    pub erased: usize,
+    /// Layouts that wrap another interned layout, rather than a [LayoutRepr] of their own.
+    pub newtype: usize,
+}
+
+impl InternerStats {
+    fn new(
+        vec: &[Layout<'_>],
+        vec_capacity: usize,
+        map_capacity: usize,
+        lambda_set_map_capacity: usize,
+    ) -> Self {
+        use std::mem::{size_of, size_of_val};
+
+        let mut stats = InternerStats {
+            entries: vec.len(),
+            table_bytes: vec_capacity * size_of::<Layout>()
+                + map_capacity * size_of::<(Layout, InLayout)>()
+                + lambda_set_map_capacity * size_of::<(LambdaSet, LambdaSet)>(),
+            ..Default::default()
+        };
+
+        let tags = |tags: &[&[InLayout]]| {
+            size_of_val(tags) + tags.iter().map(|tag| size_of_val(*tag)).sum::<usize>()
+        };
+        let names = |names: &[&str]| {
+            size_of_val(names) + names.iter().map(|name| name.len()).sum::<usize>()
+        };
+
+        for layout in vec {
+            let variants = &mut stats.variants;
+
+            stats.arena_bytes += match layout.repr {
+                LayoutWrapper::Newtype(_) => {
+                    variants.newtype += 1;
+                    0
+                }
+                LayoutWrapper::Direct(repr) => match repr {
+                    LayoutRepr::Builtin(_) => {
+                        variants.builtin += 1;
+                        0
+                    }
+                    LayoutRepr::Struct(fields) => {
+                        variants.struct_ += 1;
+                        size_of_val(fields)
+                    }
+                    LayoutRepr::Ptr(_) => {
+                        variants.ptr += 1;
+                        0
+                    }
+                    LayoutRepr::Union(union_layout) => {
+                        variants.union += 1;
+                        if union_layout.is_recursive() {
+                            stats.recursive_layouts += 1;
+                        }
+                        match union_layout {
+                            UnionLayout::NonRecursive(tags_) | UnionLayout::Recursive(tags_) => {
+                                tags(tags_)
+                            }
+                            UnionLayout::NullableWrapped { other_tags, .. } => tags(other_tags),
+                            UnionLayout::NonNullableUnwrapped(fields)
+                            | UnionLayout::NullableUnwrapped {
+                                other_fields: fields,
+                                ..
+                            } => size_of_val(fields),
+                        }
+                    }
+                    LayoutRepr::LambdaSet(lambda_set) => {
+                        variants.lambda_set += 1;
+                        stats.lambda_sets += 1;
+                        let LambdaSet { args, set, .. } = lambda_set;
+                        size_of::<&[InLayout]>()
+                            + size_of_val(*args)
+                            + size_of::<&[(Symbol, &[InLayout])]>()
+                            + size_of_val(*set)
+                            + set
+                                .iter()
+                                .map(|(_, captures)| size_of_val(*captures))
+                                .sum::<usize>()
+                    }
+                    LayoutRepr::RecursivePointer(_) => {
+                        variants.recursive_pointer += 1;
+                        0
+                    }
+                    LayoutRepr::FunctionPointer(function_pointer) => {
+                        variants.function_pointer += 1;
+                        size_of_val(function_pointer.args)
+                    }
+                    LayoutRepr::Erased(_) => {
+                        variants.erased += 1;
+                        0
+                    }
+                },
+            };
+
+            stats.arena_bytes += match layout.semantic.parts() {
+                SemanticParts::None | SemanticParts::Tuple(_) => 0,
+                SemanticParts::Record(fields) => names(fields),
+                SemanticParts::TagUnion(tags) => names(tags),
+                SemanticParts::Lambdas(lambdas) => size_of_val(lambdas),
+            };
+        }
+
+        stats
+    }
+}
+
+/// Why a layout interner snapshot could not be restored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The bytes do not start with the snapshot magic number.
+    NotASnapshot,
+    /// The snapshot was written in a different version of the format.
+    UnsupportedVersion(u32),
+    /// The snapshot ends in the middle of a value.
+    UnexpectedEnd,
+    /// The value at `offset` is not valid.
+    Malformed { offset: usize, what: &'static str },
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::NotASnapshot => write!(f, "not a layout snapshot"),
+            SnapshotError::UnsupportedVersion(version) => write!(
+                f,
+                "layout snapshot version {version} is not supported, expected version {}",
+                snapshot::VERSION
+            ),
+            SnapshotError::UnexpectedEnd => write!(f, "layout snapshot ends unexpectedly"),
+            SnapshotError::Malformed { offset, what } => {
+                write!(f, "invalid {what} at offset {offset} of layout snapshot")
+            }
+        }
+    }
 }
 
 macro_rules! st_impl {
     ($($lt:lifetime)? $interner:ident) => {
         impl<'a$(, $lt)?> LayoutInterner<'a> for $interner<'a$(, $lt)?> {
             fn insert(&mut self, value: Layout<'a>) -> InLayout<'a> {
+                if let Some(interned) = reserved_list(&value) {
+                    return interned;
+                }
+                if cfg!(debug_assertions) {
+                    verify::insert(self, value);
+                }
                 let hash = hash(value);
-                let (_, interned) = self
+                let (_, &mut interned) = self
                     .map
                     .raw_entry_mut()
                     .from_key_hashed_nocheck(hash, &value)
                     .or_insert_with(|| {
+                        let value = owned::layout(self.owned_arena.as_ref(), value);
                         let interned = InLayout(self.vec.len(), Default::default());
                         self.vec.push(value);
                         (value, interned)
                     });
-                *interned
+                if cfg!(debug_assertions) {
+                    verify::inserted(value, interned);
+                }
+                interned
             }
 
             fn insert_lambda_set(
@@ -1002,21 +1480,27 @@ macro_rules! st_impl {
                 }
 
                 // This lambda set must be new to the interner, reserve a slot and fill it in.
+                let normalized_lambda_set =
+                    owned::lambda_set(self.owned_arena.as_ref(), normalized_lambda_set);
                 let slot = unsafe { InLayout::from_index(self.vec.len()) };
                 self.vec.push(Layout::VOID_NAKED);
 
                 let set = if needs_recursive_fixup.0 {
-                    reify::reify_lambda_set_captures(arena, self, slot, set)
+                    let set = reify::reify_lambda_set_captures(
+                        arena,
+                        self,
+                        slot,
+                        normalized_lambda_set.set,
+                    );
+                    owned::lambda_set_captures(self.owned_arena.as_ref(), set)
                 } else {
-                    set
+                    normalized_lambda_set.set
                 };
 
                 let lambda_set = LambdaSet {
-                    args,
-                    ret,
                     set,
-                    representation,
                     full_layout: slot,
+                    ..normalized_lambda_set
                 };
                 let lay = Layout {
                     repr: LayoutRepr::LambdaSet(lambda_set).direct(),
@@ -1044,15 +1528,20 @@ macro_rules! st_impl {
                 //     inserted lambda set
                 //   - if so, use that one immediately
                 //   - otherwise, allocate a new slot, update the recursive layout, and intern
+                if cfg!(debug_assertions) {
+                    verify::insert_recursive(normalized_layout);
+                }
                 if let Some(in_layout) = self.map.get(&normalized_layout) {
                     return *in_layout;
                 }
 
                 // This recursive layout must be new to the interner, reserve a slot and fill it in.
+                let normalized_layout = owned::layout(self.owned_arena.as_ref(), normalized_layout);
                 let slot = unsafe { InLayout::from_index(self.vec.len()) };
                 self.vec.push(Layout::VOID_NAKED);
                 let full_layout =
                     reify::reify_recursive_layout(arena, self, slot, normalized_layout);
+                let full_layout = owned::layout(self.owned_arena.as_ref(), full_layout);
                 self.vec[slot.0] = full_layout;
 
                 self.map.insert(normalized_layout, slot);
@@ -1273,312 +1762,615 @@ mod reify {
     }
 }
 
-mod equiv {
-    use crate::layout::{self, LayoutRepr, UnionLayout};
+mod owned {
+    use bumpalo::Bump;
+    use roc_module::symbol::Symbol;
 
-    use super::{InLayout, LayoutInterner};
+    use crate::layout::semantic::SemanticParts;
+    use crate::layout::{
+        FunctionPointer, LambdaSet, Layout, LayoutRepr, LayoutWrapper, SemanticRepr, UnionLayout,
+    };
 
-    pub fn equivalent<'a>(
-        stack: &mut Vec<(InLayout<'a>, InLayout<'a>)>,
-        interner: &impl LayoutInterner<'a>,
-        l1: InLayout<'a>,
-        l2: InLayout<'a>,
-    ) -> bool {
-        stack.push((l1, l2));
+    use super::InLayout;
 
-        macro_rules! equiv_fields {
-            ($fields1:expr, $fields2:expr) => {{
-                if $fields1.len() != $fields2.len() {
-                    return false;
-                }
-                stack.extend($fields1.iter().copied().zip($fields2.iter().copied()));
-            }};
+    /// The arena of an interner made with [super::GlobalLayoutInterner::with_owned_arena].
+    /// The interner copies everything it stores in here.
+    #[derive(Debug, Default)]
+    pub struct OwnedArena(Bump);
+
+    impl OwnedArena {
+        /// Lends out the arena for as long as the interned layouts live, which the safety contract
+        /// of [super::GlobalLayoutInterner::with_owned_arena] makes the caller responsible for.
+        /// A [Bump] is not [Sync], so only use this while holding the interner's lock on it.
+        pub fn arena<'a>(&self) -> &'a Bump {
+            unsafe { &*(&self.0 as *const Bump) }
         }
+    }
 
-        macro_rules! equiv_unions {
-            ($tags1:expr, $tags2:expr) => {{
-                if $tags1.len() != $tags2.len() {
-                    return false;
-                }
-                for (payloads1, payloads2) in $tags1.iter().zip($tags2) {
-                    equiv_fields!(payloads1, payloads2)
-                }
-            }};
+    /// Copies the slices `layout` points to into `owned_arena`, if there is one.
+    pub fn layout<'a>(owned_arena: Option<&OwnedArena>, layout: Layout<'a>) -> Layout<'a> {
+        let Some(owned_arena) = owned_arena else {
+            return layout;
+        };
+        let arena = owned_arena.arena();
+
+        let Layout { repr, semantic } = layout;
+        let repr = match repr {
+            LayoutWrapper::Direct(repr) => copy_repr(arena, repr).direct(),
+            LayoutWrapper::Newtype(inner) => inner.newtype(),
+        };
+
+        Layout::new(repr, copy_semantic(arena, semantic))
+    }
+
+    /// Copies the slices `lambda_set` points to into `owned_arena`, if there is one.
+    pub fn lambda_set<'a>(
+        owned_arena: Option<&OwnedArena>,
+        lambda_set: LambdaSet<'a>,
+    ) -> LambdaSet<'a> {
+        match owned_arena {
+            Some(owned_arena) => copy_lambda_set(owned_arena.arena(), lambda_set),
+            None => lambda_set,
         }
+    }
 
-        while let Some((l1, l2)) = stack.pop() {
-            if l1 == l2 {
-                continue;
+    /// Copies the captures of a lambda set into `owned_arena`, if there is one.
+    pub fn lambda_set_captures<'a>(
+        owned_arena: Option<&OwnedArena>,
+        set: &'a &'a [(Symbol, &'a [InLayout<'a>])],
+    ) -> &'a &'a [(Symbol, &'a [InLayout<'a>])] {
+        match owned_arena {
+            Some(owned_arena) => {
+                let arena = owned_arena.arena();
+                arena.alloc(copy_captures(arena, set))
             }
-            use LayoutRepr::*;
-            match (interner.get_repr(l1), interner.get_repr(l2)) {
-                (RecursivePointer(rec), _) => stack.push((rec, l2)),
-                (_, RecursivePointer(rec)) => stack.push((l1, rec)),
-                (Builtin(b1), Builtin(b2)) => {
-                    use crate::layout::Builtin::*;
-                    match (b1, b2) {
-                        (List(e1), List(e2)) => stack.push((e1, e2)),
-                        (b1, b2) => {
-                            if b1 != b2 {
-                                return false;
-                            }
-                        }
-                    }
-                }
-                (Struct(fl1), Struct(fl2)) => {
-                    equiv_fields!(fl1, fl2)
-                }
-                (Ptr(b1), Ptr(b2)) => stack.push((b1, b2)),
-                (Union(u1), Union(u2)) => {
-                    use UnionLayout::*;
-                    match (u1, u2) {
-                        (NonRecursive(tags1), NonRecursive(tags2)) => equiv_unions!(tags1, tags2),
-                        (Recursive(tags1), Recursive(tags2)) => equiv_unions!(tags1, tags2),
-                        (NonNullableUnwrapped(fields1), NonNullableUnwrapped(fields2)) => {
-                            equiv_fields!(fields1, fields2)
-                        }
-                        (
-                            NullableWrapped {
-                                nullable_id: null_id1,
-                                other_tags: tags1,
-                            },
-                            NullableWrapped {
-                                nullable_id: null_id2,
-                                other_tags: tags2,
-                            },
-                        ) => {
-                            if null_id1 != null_id2 {
-                                return false;
-                            }
-                            equiv_unions!(tags1, tags2)
-                        }
-                        (
-                            NullableUnwrapped {
-                                nullable_id: null_id1,
-                                other_fields: fields1,
-                            },
-                            NullableUnwrapped {
-                                nullable_id: null_id2,
-                                other_fields: fields2,
-                            },
-                        ) => {
-                            if null_id1 != null_id2 {
-                                return false;
-                            }
-                            equiv_fields!(fields1, fields2)
-                        }
-                        _ => return false,
-                    }
-                }
-                (
-                    LambdaSet(layout::LambdaSet {
-                        args: args1,
-                        ret: ret1,
-                        set: set1,
-                        representation: repr1,
-                        full_layout: _,
-                    }),
-                    LambdaSet(layout::LambdaSet {
-                        args: args2,
-                        ret: ret2,
-                        set: set2,
-                        representation: repr2,
-                        full_layout: _,
-                    }),
-                ) => {
-                    for ((fn1, captures1), (fn2, captures2)) in (**set1).iter().zip(*set2) {
-                        if fn1 != fn2 {
-                            return false;
-                        }
-                        equiv_fields!(captures1, captures2);
-                    }
-                    equiv_fields!(args1, args2);
-                    stack.push((ret1, ret2));
-                    stack.push((repr1, repr2));
-                }
-                _ => return false,
+            None => set,
+        }
+    }
+
+    fn copy_repr<'a>(arena: &'a Bump, repr: LayoutRepr<'a>) -> LayoutRepr<'a> {
+        match repr {
+            LayoutRepr::Builtin(builtin) => LayoutRepr::Builtin(builtin),
+            LayoutRepr::Struct(field_layouts) => {
+                LayoutRepr::Struct(arena.alloc_slice_copy(field_layouts))
+            }
+            LayoutRepr::Ptr(lay) => LayoutRepr::Ptr(lay),
+            LayoutRepr::Union(un) => LayoutRepr::Union(copy_union(arena, un)),
+            LayoutRepr::LambdaSet(ls) => LayoutRepr::LambdaSet(copy_lambda_set(arena, ls)),
+            LayoutRepr::RecursivePointer(l) => LayoutRepr::RecursivePointer(l),
+            LayoutRepr::FunctionPointer(FunctionPointer { args, ret }) => {
+                LayoutRepr::FunctionPointer(FunctionPointer {
+                    args: arena.alloc_slice_copy(args),
+                    ret,
+                })
             }
+            LayoutRepr::Erased(e) => LayoutRepr::Erased(e),
         }
+    }
 
-        true
+    fn copy_union<'a>(arena: &'a Bump, union: UnionLayout<'a>) -> UnionLayout<'a> {
+        let copy_tags = |tags: &[&[InLayout<'a>]]| -> &'a [&'a [InLayout<'a>]] {
+            arena.alloc_slice_fill_iter(tags.iter().map(|tag| &*arena.alloc_slice_copy(tag)))
+        };
+
+        match union {
+            UnionLayout::NonRecursive(tags) => UnionLayout::NonRecursive(copy_tags(tags)),
+            UnionLayout::Recursive(tags) => UnionLayout::Recursive(copy_tags(tags)),
+            UnionLayout::NonNullableUnwrapped(fields) => {
+                UnionLayout::NonNullableUnwrapped(arena.alloc_slice_copy(fields))
+            }
+            UnionLayout::NullableWrapped {
+                nullable_id,
+                other_tags,
+            } => UnionLayout::NullableWrapped {
+                nullable_id,
+                other_tags: copy_tags(other_tags),
+            },
+            UnionLayout::NullableUnwrapped {
+                nullable_id,
+                other_fields,
+            } => UnionLayout::NullableUnwrapped {
+                nullable_id,
+                other_fields: arena.alloc_slice_copy(other_fields),
+            },
+        }
+    }
+
+    fn copy_lambda_set<'a>(arena: &'a Bump, lambda_set: LambdaSet<'a>) -> LambdaSet<'a> {
+        let LambdaSet {
+            args,
+            ret,
+            set,
+            representation,
+            full_layout,
+        } = lambda_set;
+
+        LambdaSet {
+            args: arena.alloc(&*arena.alloc_slice_copy(args)),
+            ret,
+            set: arena.alloc(copy_captures(arena, set)),
+            representation,
+            full_layout,
+        }
+    }
+
+    fn copy_captures<'a>(
+        arena: &'a Bump,
+        set: &[(Symbol, &[InLayout<'a>])],
+    ) -> &'a [(Symbol, &'a [InLayout<'a>])] {
+        arena.alloc_slice_fill_iter(
+            set.iter()
+                .map(|(lambda, captures)| (*lambda, &*arena.alloc_slice_copy(captures))),
+        )
+    }
+
+    fn copy_semantic<'a>(arena: &'a Bump, semantic: SemanticRepr<'a>) -> SemanticRepr<'a> {
+        let copy_names = |names: &[&str]| -> &'a [&'a str] {
+            arena.alloc_slice_fill_iter(names.iter().map(|name| &*arena.alloc_str(name)))
+        };
+
+        SemanticRepr::from_parts(match semantic.parts() {
+            SemanticParts::Record(fields) => SemanticParts::Record(copy_names(fields)),
+            SemanticParts::TagUnion(tags) => SemanticParts::TagUnion(copy_names(tags)),
+            SemanticParts::Lambdas(lambdas) => {
+                SemanticParts::Lambdas(arena.alloc_slice_copy(lambdas))
+            }
+            parts @ (SemanticParts::None | SemanticParts::Tuple(_)) => parts,
+        })
     }
 }
 
-pub mod dbg_deep {
+mod retain {
+    use bumpalo::{collections::Vec as BumpVec, Bump};
+    use roc_collections::{default_hasher, BumpMap};
     use roc_module::symbol::Symbol;
 
-    use crate::layout::{Builtin, Erased, LambdaSet, LayoutRepr, UnionLayout};
-
-    use super::{InLayout, LayoutInterner};
+    use crate::layout::{
+        Builtin, FunctionPointer, LambdaSet, Layout, LayoutRepr, LayoutWrapper, UnionLayout,
+    };
 
-    pub struct Dbg<'a, 'r, I: LayoutInterner<'a>>(pub &'r I, pub InLayout<'a>);
+    use super::{InLayout, LayoutRemap, RESERVED_LAYOUTS};
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for Dbg<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let repr = self.0.get_repr(self.1);
-            let semantic = self.0.get_semantic(self.1);
+    pub fn retain<'a>(
+        arena: &'a Bump,
+        map: &mut BumpMap<Layout<'a>, InLayout<'a>>,
+        normalized_lambda_set_map: &mut BumpMap<LambdaSet<'a>, LambdaSet<'a>>,
+        vec: &mut Vec<Layout<'a>>,
+        roots: impl Iterator<Item = InLayout<'a>>,
+    ) -> LayoutRemap<'a> {
+        let live = mark(arena, map, normalized_lambda_set_map, vec, roots);
+
+        let mut next = 0;
+        let new_indices = live
+            .iter()
+            .map(|&live| {
+                live.then(|| {
+                    next += 1;
+                    InLayout(next - 1, Default::default())
+                })
+            })
+            .collect();
+        let remap = LayoutRemap { new_indices };
+
+        let mut translate = |layout| match remap.get(layout) {
+            Some(layout) => layout,
+            None => roc_error_macros::internal_error!(
+                "retained layout refers to the dropped layout {layout:?}"
+            ),
+        };
 
-            f.debug_struct("Layout")
-                .field("repr", &DbgRepr(self.0, &repr))
-                .field("semantic", &semantic)
-                .finish()
+        let mut new_vec = Vec::with_capacity(next);
+        for (layout, live) in vec.iter().zip(live) {
+            if live {
+                new_vec.push(map_layout(arena, *layout, &mut translate));
+            }
         }
-    }
 
-    pub struct DbgFields<'a, 'r, I: LayoutInterner<'a>>(pub &'r I, pub &'a [InLayout<'a>]);
+        let mut new_map = BumpMap::with_capacity_and_hasher(next, default_hasher());
+        for (layout, interned) in map.iter() {
+            if let Some(interned) = remap.get(*interned) {
+                new_map.insert(map_layout(arena, *layout, &mut translate), interned);
+            }
+        }
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgFields<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.debug_list()
-                .entries(self.1.iter().map(|l| Dbg(self.0, *l)))
-                .finish()
+        let mut new_normalized_lambda_set_map = BumpMap::default();
+        for (normalized, full) in normalized_lambda_set_map.iter() {
+            if remap.get(full.full_layout).is_some() {
+                new_normalized_lambda_set_map.insert(
+                    map_lambda_set(arena, *normalized, &mut translate),
+                    map_lambda_set(arena, *full, &mut translate),
+                );
+            }
         }
+
+        *vec = new_vec;
+        *map = new_map;
+        *normalized_lambda_set_map = new_normalized_lambda_set_map;
+
+        remap
     }
 
-    struct DbgRepr<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'r LayoutRepr<'a>);
+    /// Finds the slots reachable from the reserved layouts and `roots`.
+    fn mark<'a>(
+        arena: &'a Bump,
+        map: &BumpMap<Layout<'a>, InLayout<'a>>,
+        normalized_lambda_set_map: &BumpMap<LambdaSet<'a>, LambdaSet<'a>>,
+        vec: &[Layout<'a>],
+        roots: impl Iterator<Item = InLayout<'a>>,
+    ) -> Vec<bool> {
+        let mut live = vec![false; vec.len()];
+        let mut stack: Vec<_> = (0..RESERVED_LAYOUTS)
+            .map(|index| InLayout(index, Default::default()))
+            .chain(roots)
+            .collect();
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgRepr<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self.1 {
-                LayoutRepr::Builtin(b) => f
-                    .debug_tuple("Builtin")
-                    .field(&DbgBuiltin(self.0, *b))
-                    .finish(),
-                LayoutRepr::Struct(field_layouts) => f
-                    .debug_struct("Struct")
-                    .field("fields", &DbgFields(self.0, field_layouts))
-                    .finish(),
-                LayoutRepr::Ptr(b) => f.debug_tuple("Ptr").field(&Dbg(self.0, *b)).finish(),
-                LayoutRepr::Union(un) => f
-                    .debug_tuple("Union")
-                    .field(&DbgUnion(self.0, *un))
-                    .finish(),
-                LayoutRepr::LambdaSet(ls) => f
-                    .debug_tuple("LambdaSet")
-                    .field(&DbgLambdaSet(self.0, *ls))
-                    .finish(),
-                LayoutRepr::RecursivePointer(rp) => {
-                    f.debug_tuple("RecursivePointer").field(&rp.0).finish()
+        loop {
+            while let Some(layout) = stack.pop() {
+                if !std::mem::replace(&mut live[layout.0], true) {
+                    map_layout(arena, vec[layout.0], &mut |child| {
+                        stack.push(child);
+                        child
+                    });
                 }
-                LayoutRepr::FunctionPointer(fp) => f
-                    .debug_struct("FunctionPointer")
-                    .field("args", &fp.args)
-                    .field("ret", &fp.ret)
-                    .finish(),
-                LayoutRepr::Erased(Erased) => f.debug_struct("?Erased").finish(),
+            }
+
+            // A key that leads to a live slot has to stay in the map, or the slot's layout would
+            // be interned a second time. So whatever the key refers to has to live as well.
+            let mut keep_key = |child: InLayout<'a>| {
+                if !live[child.0] {
+                    stack.push(child);
+                }
+                child
+            };
+            for (layout, interned) in map.iter() {
+                if live[interned.0] {
+                    map_layout(arena, *layout, &mut keep_key);
+                }
+            }
+            for (normalized, full) in normalized_lambda_set_map.iter() {
+                if live[full.full_layout.0] {
+                    map_lambda_set(arena, *normalized, &mut keep_key);
+                }
+            }
+
+            if stack.is_empty() {
+                return live;
             }
         }
     }
 
-    struct DbgTags<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'a [&'a [InLayout<'a>]]);
+    /// Rebuilds `layout` with `f` applied to each layout it refers to. Slices are only copied
+    /// into `arena` when `f` changes one of their elements.
+    fn map_layout<'a>(
+        arena: &'a Bump,
+        layout: Layout<'a>,
+        f: &mut impl FnMut(InLayout<'a>) -> InLayout<'a>,
+    ) -> Layout<'a> {
+        let Layout { repr, semantic } = layout;
+        let repr = match repr {
+            LayoutWrapper::Direct(repr) => map_repr(arena, repr, f).direct(),
+            LayoutWrapper::Newtype(inner) => f(inner).newtype(),
+        };
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgTags<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.debug_list()
-                .entries(self.1.iter().map(|l| DbgFields(self.0, l)))
-                .finish()
+        Layout::new(repr, semantic)
+    }
+
+    fn map_repr<'a>(
+        arena: &'a Bump,
+        repr: LayoutRepr<'a>,
+        f: &mut impl FnMut(InLayout<'a>) -> InLayout<'a>,
+    ) -> LayoutRepr<'a> {
+        match repr {
+            LayoutRepr::Builtin(Builtin::List(elem)) => LayoutRepr::Builtin(Builtin::List(f(elem))),
+            LayoutRepr::Builtin(builtin) => LayoutRepr::Builtin(builtin),
+            LayoutRepr::Struct(field_layouts) => {
+                LayoutRepr::Struct(map_slice(arena, field_layouts, &mut *f))
+            }
+            LayoutRepr::Ptr(lay) => LayoutRepr::Ptr(f(lay)),
+            LayoutRepr::Union(un) => LayoutRepr::Union(map_union(arena, un, f)),
+            LayoutRepr::LambdaSet(ls) => LayoutRepr::LambdaSet(map_lambda_set(arena, ls, f)),
+            LayoutRepr::RecursivePointer(l) => LayoutRepr::RecursivePointer(f(l)),
+            LayoutRepr::FunctionPointer(FunctionPointer { args, ret }) => {
+                LayoutRepr::FunctionPointer(FunctionPointer {
+                    args: map_slice(arena, args, &mut *f),
+                    ret: f(ret),
+                })
+            }
+            LayoutRepr::Erased(e) => LayoutRepr::Erased(e),
         }
     }
 
-    struct DbgBuiltin<'a, 'r, I: LayoutInterner<'a>>(&'r I, Builtin<'a>);
+    fn map_union<'a>(
+        arena: &'a Bump,
+        union: UnionLayout<'a>,
+        f: &mut impl FnMut(InLayout<'a>) -> InLayout<'a>,
+    ) -> UnionLayout<'a> {
+        let mut map_tags = |tags: &'a [&'a [InLayout<'a>]]| {
+            map_slice(arena, tags, |tag| map_slice(arena, tag, &mut *f))
+        };
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgBuiltin<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self.1 {
-                Builtin::Int(w) => f.debug_tuple("Int").field(&w).finish(),
-                Builtin::Float(w) => f.debug_tuple("Frac").field(&w).finish(),
-                Builtin::Bool => f.debug_tuple("Bool").finish(),
-                Builtin::Decimal => f.debug_tuple("Decimal").finish(),
-                Builtin::Str => f.debug_tuple("Str").finish(),
-                Builtin::List(e) => f.debug_tuple("List").field(&Dbg(self.0, e)).finish(),
+        match union {
+            UnionLayout::NonRecursive(tags) => UnionLayout::NonRecursive(map_tags(tags)),
+            UnionLayout::Recursive(tags) => UnionLayout::Recursive(map_tags(tags)),
+            UnionLayout::NonNullableUnwrapped(fields) => {
+                UnionLayout::NonNullableUnwrapped(map_slice(arena, fields, &mut *f))
             }
+            UnionLayout::NullableWrapped {
+                nullable_id,
+                other_tags,
+            } => UnionLayout::NullableWrapped {
+                nullable_id,
+                other_tags: map_tags(other_tags),
+            },
+            UnionLayout::NullableUnwrapped {
+                nullable_id,
+                other_fields,
+            } => UnionLayout::NullableUnwrapped {
+                nullable_id,
+                other_fields: map_slice(arena, other_fields, &mut *f),
+            },
         }
     }
 
-    struct DbgUnion<'a, 'r, I: LayoutInterner<'a>>(&'r I, UnionLayout<'a>);
+    fn map_lambda_set<'a>(
+        arena: &'a Bump,
+        lambda_set: LambdaSet<'a>,
+        f: &mut impl FnMut(InLayout<'a>) -> InLayout<'a>,
+    ) -> LambdaSet<'a> {
+        let LambdaSet {
+            args,
+            ret,
+            set,
+            representation,
+            full_layout,
+        } = lambda_set;
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgUnion<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self.1 {
-                UnionLayout::NonRecursive(payloads) => f
-                    .debug_tuple("NonRecursive")
-                    .field(&DbgTags(self.0, payloads))
-                    .finish(),
-                UnionLayout::Recursive(payloads) => f
-                    .debug_tuple("Recursive")
-                    .field(&DbgTags(self.0, payloads))
-                    .finish(),
-                UnionLayout::NonNullableUnwrapped(fields) => f
-                    .debug_tuple("NonNullableUnwrapped")
-                    .field(&DbgFields(self.0, fields))
-                    .finish(),
-                UnionLayout::NullableWrapped {
-                    nullable_id,
-                    other_tags,
-                } => f
-                    .debug_struct("NullableWrapped")
-                    .field("nullable_id", &nullable_id)
-                    .field("other_tags", &DbgTags(self.0, other_tags))
-                    .finish(),
-                UnionLayout::NullableUnwrapped {
-                    nullable_id,
-                    other_fields,
-                } => f
-                    .debug_struct("NullableUnwrapped")
-                    .field("nullable_id", &nullable_id)
-                    .field("other_tags", &DbgFields(self.0, other_fields))
-                    .finish(),
+        let new_args = map_slice(arena, args, &mut *f);
+        let new_set = map_slice(arena, set, |(lambda, captures): (Symbol, _)| {
+            (lambda, map_slice(arena, captures, &mut *f))
+        });
+
+        LambdaSet {
+            args: if new_args == *args {
+                args
+            } else {
+                arena.alloc(new_args)
+            },
+            ret: f(ret),
+            set: if new_set == *set {
+                set
+            } else {
+                arena.alloc(new_set)
+            },
+            representation: f(representation),
+            full_layout: f(full_layout),
+        }
+    }
+
+    fn map_slice<'a, T: Copy + PartialEq>(
+        arena: &'a Bump,
+        items: &'a [T],
+        mut f: impl FnMut(T) -> T,
+    ) -> &'a [T] {
+        let mut mapped: Option<BumpVec<T>> = None;
+        for (i, &item) in items.iter().enumerate() {
+            let new_item = f(item);
+            match &mut mapped {
+                Some(mapped) => mapped.push(new_item),
+                None if new_item != item => {
+                    let mut copy = BumpVec::with_capacity_in(items.len(), arena);
+                    copy.extend_from_slice(&items[..i]);
+                    copy.push(new_item);
+                    mapped = Some(copy);
+                }
+                None => {}
             }
         }
+
+        match mapped {
+            Some(mapped) => mapped.into_bump_slice(),
+            None => items,
+        }
     }
+}
 
-    struct DbgLambdaSet<'a, 'r, I: LayoutInterner<'a>>(&'r I, LambdaSet<'a>);
+mod equiv {
+    use std::cell::RefCell;
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgLambdaSet<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let LambdaSet {
-                args,
-                ret,
-                set,
-                representation,
-                full_layout,
-            } = self.1;
+    use roc_collections::MutSet;
 
-            f.debug_struct("LambdaSet")
-                .field("args", &DbgFields(self.0, args))
-                .field("ret", &Dbg(self.0, ret))
-                .field("set", &DbgCapturesSet(self.0, set))
-                .field("representation", &Dbg(self.0, representation))
-                .field("full_layout", &full_layout)
-                .finish()
+    use crate::layout::{self, LayoutRepr, UnionLayout};
+
+    use super::{InLayout, LayoutInterner};
+
+    #[derive(Default)]
+    pub struct Scratchpad<'a> {
+        stack: Vec<(InLayout<'a>, InLayout<'a>)>,
+        /// Pairs already assumed to be equivalent. Meeting one again means we went around a
+        /// recursive layout, and nothing along the way disproved the assumption.
+        assumed: MutSet<(InLayout<'a>, InLayout<'a>)>,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    pub enum LambdaSets {
+        Compare,
+        UseRuntimeRepr,
+    }
+
+    pub fn with_scratchpad<'a, T>(f: impl FnOnce(&mut Scratchpad<'a>) -> T) -> T {
+        std::thread_local! {
+            static SCRATCHPAD: RefCell<Option<Scratchpad<'static>>> = const { RefCell::new(None) };
         }
+
+        SCRATCHPAD.with(|cell| {
+            // SAFETY: the promotion to lifetime 'a only lasts during equivalence-checking; the
+            // scratchpad is cleared after every use.
+            let mut scratchpad: Scratchpad<'a> =
+                unsafe { std::mem::transmute(cell.take().unwrap_or_default()) };
+
+            let answer = f(&mut scratchpad);
+            scratchpad.stack.clear();
+            scratchpad.assumed.clear();
+
+            let scratchpad: Scratchpad<'static> = unsafe { std::mem::transmute(scratchpad) };
+            cell.replace(Some(scratchpad));
+            answer
+        })
     }
 
-    struct DbgCapturesSet<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'a [(Symbol, &'a [InLayout<'a>])]);
+    pub fn equivalent<'a>(
+        scratchpad: &mut Scratchpad<'a>,
+        interner: &impl LayoutInterner<'a>,
+        l1: InLayout<'a>,
+        l2: InLayout<'a>,
+        lambda_sets: LambdaSets,
+    ) -> bool {
+        let Scratchpad { stack, assumed } = scratchpad;
+        stack.push((l1, l2));
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgCapturesSet<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.debug_list()
-                .entries(
-                    self.1
-                        .iter()
-                        .map(|(sym, captures)| (sym, DbgFields(self.0, captures))),
-                )
-                .finish()
+        macro_rules! equiv_fields {
+            ($fields1:expr, $fields2:expr) => {{
+                if $fields1.len() != $fields2.len() {
+                    return false;
+                }
+                stack.extend($fields1.iter().copied().zip($fields2.iter().copied()));
+            }};
+        }
+
+        macro_rules! equiv_unions {
+            ($tags1:expr, $tags2:expr) => {{
+                if $tags1.len() != $tags2.len() {
+                    return false;
+                }
+                for (payloads1, payloads2) in $tags1.iter().zip($tags2) {
+                    equiv_fields!(payloads1, payloads2)
+                }
+            }};
+        }
+
+        while let Some((mut l1, mut l2)) = stack.pop() {
+            if lambda_sets == LambdaSets::UseRuntimeRepr {
+                l1 = interner.runtime_representation_in(l1);
+                l2 = interner.runtime_representation_in(l2);
+            }
+            if l1 == l2 || !assumed.insert((l1, l2)) {
+                continue;
+            }
+            use LayoutRepr::*;
+            match (interner.get_repr(l1), interner.get_repr(l2)) {
+                (RecursivePointer(rec), _) => stack.push((rec, l2)),
+                (_, RecursivePointer(rec)) => stack.push((l1, rec)),
+                (Builtin(b1), Builtin(b2)) => {
+                    use crate::layout::Builtin::*;
+                    match (b1, b2) {
+                        (List(e1), List(e2)) => stack.push((e1, e2)),
+                        (b1, b2) => {
+                            if b1 != b2 {
+                                return false;
+                            }
+                        }
+                    }
+                }
+                (Struct(fl1), Struct(fl2)) => {
+                    equiv_fields!(fl1, fl2)
+                }
+                (Ptr(b1), Ptr(b2)) => stack.push((b1, b2)),
+                (Union(u1), Union(u2)) => {
+                    use UnionLayout::*;
+                    match (u1, u2) {
+                        (NonRecursive(tags1), NonRecursive(tags2)) => equiv_unions!(tags1, tags2),
+                        (Recursive(tags1), Recursive(tags2)) => equiv_unions!(tags1, tags2),
+                        (NonNullableUnwrapped(fields1), NonNullableUnwrapped(fields2)) => {
+                            equiv_fields!(fields1, fields2)
+                        }
+                        (
+                            NullableWrapped {
+                                nullable_id: null_id1,
+                                other_tags: tags1,
+                            },
+                            NullableWrapped {
+                                nullable_id: null_id2,
+                                other_tags: tags2,
+                            },
+                        ) => {
+                            if null_id1 != null_id2 {
+                                return false;
+                            }
+                            equiv_unions!(tags1, tags2)
+                        }
+                        (
+                            NullableUnwrapped {
+                                nullable_id: null_id1,
+                                other_fields: fields1,
+                            },
+                            NullableUnwrapped {
+                                nullable_id: null_id2,
+                                other_fields: fields2,
+                            },
+                        ) => {
+                            if null_id1 != null_id2 {
+                                return false;
+                            }
+                            equiv_fields!(fields1, fields2)
+                        }
+                        _ => return false,
+                    }
+                }
+                (
+                    LambdaSet(layout::LambdaSet {
+                        args: args1,
+                        ret: ret1,
+                        set: set1,
+                        representation: repr1,
+                        full_layout: _,
+                    }),
+                    LambdaSet(layout::LambdaSet {
+                        args: args2,
+                        ret: ret2,
+                        set: set2,
+                        representation: repr2,
+                        full_layout: _,
+                    }),
+                ) => {
+                    if set1.len() != set2.len() {
+                        return false;
+                    }
+                    for ((fn1, captures1), (fn2, captures2)) in (**set1).iter().zip(*set2) {
+                        if fn1 != fn2 {
+                            return false;
+                        }
+                        equiv_fields!(captures1, captures2);
+                    }
+                    equiv_fields!(args1, args2);
+                    stack.push((ret1, ret2));
+                    stack.push((repr1, repr2));
+                }
+                (
+                    FunctionPointer(layout::FunctionPointer {
+                        args: args1,
+                        ret: ret1,
+                    }),
+                    FunctionPointer(layout::FunctionPointer {
+                        args: args2,
+                        ret: ret2,
+                    }),
+                ) => {
+                    equiv_fields!(args1, args2);
+                    stack.push((ret1, ret2));
+                }
+                (Erased(_), Erased(_)) => {}
+                _ => return false,
+            }
         }
+
+        true
     }
 }
 
-/// Provides a stable debug output
-///
-/// The debug output defined in `dbg_deep` uses the `Symbol` `std::fmt::Debug` instance, which uses
-/// interned string names to make the output easier to interpret. That is useful for manual
-/// debugging, but the interned strings are not stable in a multi-threaded context (e.g. when
-/// running `cargo test`). The output of this module is always stable.
-pub mod dbg_stable {
+pub mod dbg_deep {
     use roc_module::symbol::Symbol;
 
-    use crate::layout::{Builtin, Erased, LambdaSet, LayoutRepr, SemanticRepr, UnionLayout};
+    use crate::layout::{Builtin, Erased, LambdaSet, LayoutRepr, UnionLayout};
 
     use super::{InLayout, LayoutInterner};
 
@@ -1589,441 +2381,2266 @@ pub mod dbg_stable {
             let repr = self.0.get_repr(self.1);
             let semantic = self.0.get_semantic(self.1);
 
-            struct ConsistentSemanticRepr<'a>(SemanticRepr<'a>);
+            f.debug_struct("Layout")
+                .field("repr", &DbgRepr(self.0, &repr))
+                .field("semantic", &semantic)
+                .finish()
+        }
+    }
 
-            impl std::fmt::Debug for ConsistentSemanticRepr<'_> {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    self.0.fmt_consistent(f)
+    pub struct DbgFields<'a, 'r, I: LayoutInterner<'a>>(pub &'r I, pub &'a [InLayout<'a>]);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgFields<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list()
+                .entries(self.1.iter().map(|l| Dbg(self.0, *l)))
+                .finish()
+        }
+    }
+
+    struct DbgRepr<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'r LayoutRepr<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgRepr<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self.1 {
+                LayoutRepr::Builtin(b) => f
+                    .debug_tuple("Builtin")
+                    .field(&DbgBuiltin(self.0, *b))
+                    .finish(),
+                LayoutRepr::Struct(field_layouts) => f
+                    .debug_struct("Struct")
+                    .field("fields", &DbgFields(self.0, field_layouts))
+                    .finish(),
+                LayoutRepr::Ptr(b) => f.debug_tuple("Ptr").field(&Dbg(self.0, *b)).finish(),
+                LayoutRepr::Union(un) => f
+                    .debug_tuple("Union")
+                    .field(&DbgUnion(self.0, *un))
+                    .finish(),
+                LayoutRepr::LambdaSet(ls) => f
+                    .debug_tuple("LambdaSet")
+                    .field(&DbgLambdaSet(self.0, *ls))
+                    .finish(),
+                LayoutRepr::RecursivePointer(rp) => {
+                    f.debug_tuple("RecursivePointer").field(&rp.0).finish()
                 }
+                LayoutRepr::FunctionPointer(fp) => f
+                    .debug_struct("FunctionPointer")
+                    .field("args", &fp.args)
+                    .field("ret", &fp.ret)
+                    .finish(),
+                LayoutRepr::Erased(Erased) => f.debug_struct("?Erased").finish(),
             }
+        }
+    }
 
-            f.debug_struct("Layout")
-                .field("repr", &DbgRepr(self.0, &repr))
-                .field("semantic", &ConsistentSemanticRepr(semantic))
+    struct DbgTags<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'a [&'a [InLayout<'a>]]);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgTags<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list()
+                .entries(self.1.iter().map(|l| DbgFields(self.0, l)))
                 .finish()
         }
     }
 
-    pub struct DbgFields<'a, 'r, I: LayoutInterner<'a>>(pub &'r I, pub &'a [InLayout<'a>]);
+    struct DbgBuiltin<'a, 'r, I: LayoutInterner<'a>>(&'r I, Builtin<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgBuiltin<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self.1 {
+                Builtin::Int(w) => f.debug_tuple("Int").field(&w).finish(),
+                Builtin::Float(w) => f.debug_tuple("Frac").field(&w).finish(),
+                Builtin::Bool => f.debug_tuple("Bool").finish(),
+                Builtin::Decimal => f.debug_tuple("Decimal").finish(),
+                Builtin::Str => f.debug_tuple("Str").finish(),
+                Builtin::List(e) => f.debug_tuple("List").field(&Dbg(self.0, e)).finish(),
+            }
+        }
+    }
+
+    struct DbgUnion<'a, 'r, I: LayoutInterner<'a>>(&'r I, UnionLayout<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgUnion<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self.1 {
+                UnionLayout::NonRecursive(payloads) => f
+                    .debug_tuple("NonRecursive")
+                    .field(&DbgTags(self.0, payloads))
+                    .finish(),
+                UnionLayout::Recursive(payloads) => f
+                    .debug_tuple("Recursive")
+                    .field(&DbgTags(self.0, payloads))
+                    .finish(),
+                UnionLayout::NonNullableUnwrapped(fields) => f
+                    .debug_tuple("NonNullableUnwrapped")
+                    .field(&DbgFields(self.0, fields))
+                    .finish(),
+                UnionLayout::NullableWrapped {
+                    nullable_id,
+                    other_tags,
+                } => f
+                    .debug_struct("NullableWrapped")
+                    .field("nullable_id", &nullable_id)
+                    .field("other_tags", &DbgTags(self.0, other_tags))
+                    .finish(),
+                UnionLayout::NullableUnwrapped {
+                    nullable_id,
+                    other_fields,
+                } => f
+                    .debug_struct("NullableUnwrapped")
+                    .field("nullable_id", &nullable_id)
+                    .field("other_tags", &DbgFields(self.0, other_fields))
+                    .finish(),
+            }
+        }
+    }
+
+    struct DbgLambdaSet<'a, 'r, I: LayoutInterner<'a>>(&'r I, LambdaSet<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgLambdaSet<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let LambdaSet {
+                args,
+                ret,
+                set,
+                representation,
+                full_layout,
+            } = self.1;
+
+            f.debug_struct("LambdaSet")
+                .field("args", &DbgFields(self.0, args))
+                .field("ret", &Dbg(self.0, ret))
+                .field("set", &DbgCapturesSet(self.0, set))
+                .field("representation", &Dbg(self.0, representation))
+                .field("full_layout", &full_layout)
+                .finish()
+        }
+    }
+
+    struct DbgCapturesSet<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'a [(Symbol, &'a [InLayout<'a>])]);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgCapturesSet<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list()
+                .entries(
+                    self.1
+                        .iter()
+                        .map(|(sym, captures)| (sym, DbgFields(self.0, captures))),
+                )
+                .finish()
+        }
+    }
+}
+
+/// Provides a stable debug output
+///
+/// The debug output defined in `dbg_deep` uses the `Symbol` `std::fmt::Debug` instance, which uses
+/// interned string names to make the output easier to interpret. That is useful for manual
+/// debugging, but the interned strings are not stable in a multi-threaded context (e.g. when
+/// running `cargo test`). The output of this module is always stable.
+pub mod dbg_stable {
+    use roc_module::symbol::Symbol;
+
+    use crate::layout::{Builtin, Erased, LambdaSet, LayoutRepr, SemanticRepr, UnionLayout};
+
+    use super::{InLayout, LayoutInterner};
+
+    pub struct Dbg<'a, 'r, I: LayoutInterner<'a>>(pub &'r I, pub InLayout<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for Dbg<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let repr = self.0.get_repr(self.1);
+            let semantic = self.0.get_semantic(self.1);
+
+            struct ConsistentSemanticRepr<'a>(SemanticRepr<'a>);
+
+            impl std::fmt::Debug for ConsistentSemanticRepr<'_> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    self.0.fmt_consistent(f)
+                }
+            }
+
+            f.debug_struct("Layout")
+                .field("repr", &DbgRepr(self.0, &repr))
+                .field("semantic", &ConsistentSemanticRepr(semantic))
+                .finish()
+        }
+    }
+
+    pub struct DbgFields<'a, 'r, I: LayoutInterner<'a>>(pub &'r I, pub &'a [InLayout<'a>]);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgFields<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list()
+                .entries(self.1.iter().map(|l| Dbg(self.0, *l)))
+                .finish()
+        }
+    }
+
+    struct DbgRepr<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'r LayoutRepr<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgRepr<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self.1 {
+                LayoutRepr::Builtin(b) => f
+                    .debug_tuple("Builtin")
+                    .field(&DbgBuiltin(self.0, *b))
+                    .finish(),
+                LayoutRepr::Struct(field_layouts) => f
+                    .debug_struct("Struct")
+                    .field("fields", &DbgFields(self.0, field_layouts))
+                    .finish(),
+                LayoutRepr::Ptr(b) => f.debug_tuple("Ptr").field(&Dbg(self.0, *b)).finish(),
+                LayoutRepr::Union(un) => f
+                    .debug_tuple("Union")
+                    .field(&DbgUnion(self.0, *un))
+                    .finish(),
+                LayoutRepr::LambdaSet(ls) => f
+                    .debug_tuple("LambdaSet")
+                    .field(&DbgLambdaSet(self.0, *ls))
+                    .finish(),
+                LayoutRepr::RecursivePointer(rp) => {
+                    f.debug_tuple("RecursivePointer").field(&rp.0).finish()
+                }
+                LayoutRepr::FunctionPointer(fp) => f
+                    .debug_struct("FunctionPointer")
+                    .field("args", &fp.args)
+                    .field("ret", &fp.ret)
+                    .finish(),
+                LayoutRepr::Erased(Erased) => f.debug_struct("?Erased").finish(),
+            }
+        }
+    }
+
+    struct DbgTags<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'a [&'a [InLayout<'a>]]);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgTags<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list()
+                .entries(self.1.iter().map(|l| DbgFields(self.0, l)))
+                .finish()
+        }
+    }
+
+    struct DbgBuiltin<'a, 'r, I: LayoutInterner<'a>>(&'r I, Builtin<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgBuiltin<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self.1 {
+                Builtin::Int(w) => f.debug_tuple("Int").field(&w).finish(),
+                Builtin::Float(w) => f.debug_tuple("Frac").field(&w).finish(),
+                Builtin::Bool => f.debug_tuple("Bool").finish(),
+                Builtin::Decimal => f.debug_tuple("Decimal").finish(),
+                Builtin::Str => f.debug_tuple("Str").finish(),
+                Builtin::List(e) => f.debug_tuple("List").field(&Dbg(self.0, e)).finish(),
+            }
+        }
+    }
+
+    struct DbgUnion<'a, 'r, I: LayoutInterner<'a>>(&'r I, UnionLayout<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgUnion<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self.1 {
+                UnionLayout::NonRecursive(payloads) => f
+                    .debug_tuple("NonRecursive")
+                    .field(&DbgTags(self.0, payloads))
+                    .finish(),
+                UnionLayout::Recursive(payloads) => f
+                    .debug_tuple("Recursive")
+                    .field(&DbgTags(self.0, payloads))
+                    .finish(),
+                UnionLayout::NonNullableUnwrapped(fields) => f
+                    .debug_tuple("NonNullableUnwrapped")
+                    .field(&DbgFields(self.0, fields))
+                    .finish(),
+                UnionLayout::NullableWrapped {
+                    nullable_id,
+                    other_tags,
+                } => f
+                    .debug_struct("NullableWrapped")
+                    .field("nullable_id", &nullable_id)
+                    .field("other_tags", &DbgTags(self.0, other_tags))
+                    .finish(),
+                UnionLayout::NullableUnwrapped {
+                    nullable_id,
+                    other_fields,
+                } => f
+                    .debug_struct("NullableUnwrapped")
+                    .field("nullable_id", &nullable_id)
+                    .field("other_tags", &DbgFields(self.0, other_fields))
+                    .finish(),
+            }
+        }
+    }
+
+    struct DbgLambdaSet<'a, 'r, I: LayoutInterner<'a>>(&'r I, LambdaSet<'a>);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgLambdaSet<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let LambdaSet {
+                args,
+                ret,
+                set,
+                representation,
+                full_layout,
+            } = self.1;
+
+            f.debug_struct("LambdaSet")
+                .field("args", &DbgFields(self.0, args))
+                .field("ret", &Dbg(self.0, ret))
+                .field("set", &DbgCapturesSet(self.0, set))
+                .field("representation", &Dbg(self.0, representation))
+                .field("full_layout", &full_layout)
+                .finish()
+        }
+    }
+
+    struct DbgCapturesSet<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'a [(Symbol, &'a [InLayout<'a>])]);
+
+    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgCapturesSet<'a, 'r, I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list()
+                .entries(
+                    self.1
+                        .iter()
+                        .map(|(sym, captures)| (sym.as_u64(), DbgFields(self.0, captures))),
+                )
+                .finish()
+        }
+    }
+}
+
+/// Reading and writing [STLayoutInterner] snapshots.
+///
+/// A snapshot is laid out as
+///
+/// ```text
+/// magic        b"ROCLAYT\0"
+/// version      u32
+/// target       str
+/// layouts      len, then that many layouts, in the order they were interned
+/// map          len, then that many (layout, index) pairs
+/// lambda sets  len, then that many (normalized lambda set, index) pairs
+/// ```
+///
+/// Lengths, layout indices, and other integers are little-endian u32s. A str is its length
+/// followed by its UTF-8 bytes, and a symbol is its [Symbol::as_u64] as a little-endian u64.
+/// Enums start with a one byte tag, and bools are one byte.
+/// Checks that layouts are well-formed as they are interned. Run in debug builds, so that a
+/// malformed layout is reported where it is made, rather than as a miscompile in a backend.
+mod verify {
+    use roc_error_macros::internal_error;
+
+    use crate::layout::{Layout, LayoutRepr, LayoutWrapper, UnionLayout};
+
+    use super::{InLayout, LayoutInterner};
+
+    /// Checks a layout passed to `insert`.
+    pub fn insert<'a>(interner: &impl LayoutInterner<'a>, layout: Layout<'a>) {
+        match layout.repr {
+            LayoutWrapper::Direct(LayoutRepr::RecursivePointer(target)) => {
+                recursion_target(interner, layout, target)
+            }
+            LayoutWrapper::Direct(LayoutRepr::Union(union_layout)) => {
+                variants(layout, union_layout)
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the slot that `insert` put a layout in.
+    pub fn inserted<'a>(layout: Layout<'a>, interned: InLayout<'a>) {
+        if let LayoutWrapper::Direct(LayoutRepr::LambdaSet(lambda_set)) = layout.repr {
+            if lambda_set.full_layout != interned {
+                internal_error!(
+                    "Malformed layout: a lambda set claims to be interned at {:?}, but it is at {interned:?}. Lambda sets must be interned with `insert_lambda_set`.",
+                    lambda_set.full_layout
+                );
+            }
+        }
+    }
+
+    /// Checks a normalized layout passed to `insert_recursive`.
+    pub fn insert_recursive(layout: Layout<'_>) {
+        if let LayoutWrapper::Direct(LayoutRepr::Union(union_layout)) = layout.repr {
+            variants(layout, union_layout)
+        }
+    }
+
+    fn recursion_target<'a>(
+        interner: &impl LayoutInterner<'a>,
+        layout: Layout<'a>,
+        target: InLayout<'a>,
+    ) {
+        // A naked pointer, which `insert_recursive` will point at the union.
+        if target == Layout::VOID {
+            return;
+        }
+
+        // A slot reserved by `insert_recursive`, whose union is being filled in right now.
+        let target_layout = interner.get(target);
+        if target_layout == Layout::VOID_NAKED {
+            return;
+        }
+
+        match interner.get_repr(target) {
+            LayoutRepr::Union(union_layout) if union_layout.is_recursive() => {}
+            _ => internal_error!(
+                "Malformed layout {layout:?}: the recursion pointer refers to {target:?}, which is not a recursive union but {target_layout:?}"
+            ),
+        }
+    }
+
+    fn variants<'a>(layout: Layout<'a>, union_layout: UnionLayout<'a>) {
+        let has_no_variants = match union_layout {
+            UnionLayout::Recursive(tags) => tags.is_empty(),
+            UnionLayout::NullableWrapped { other_tags, .. } => other_tags.is_empty(),
+            UnionLayout::NonRecursive(_)
+            | UnionLayout::NonNullableUnwrapped(_)
+            | UnionLayout::NullableUnwrapped { .. } => false,
+        };
+        if has_no_variants {
+            internal_error!("Malformed layout {layout:?}: a recursive union must have variants");
+        }
+    }
+}
+
+mod snapshot {
+    use std::str::FromStr;
+
+    use bumpalo::Bump;
+    use roc_builtins::bitcode::{FloatWidth, IntWidth};
+    use roc_collections::{default_hasher, BumpMap};
+    use roc_module::symbol::Symbol;
+    use roc_target::Target;
+
+    use crate::layout::semantic::SemanticParts;
+    use crate::layout::{
+        Builtin, Erased, FunctionPointer, LambdaSet, Layout, LayoutRepr, LayoutWrapper,
+        SemanticRepr, UnionLayout,
+    };
+
+    use super::{InLayout, STLayoutInterner, SnapshotError};
+
+    const MAGIC: &[u8; 8] = b"ROCLAYT\0";
+
+    /// Bump this whenever the encoding of anything in a snapshot changes.
+    pub(super) const VERSION: u32 = 1;
+
+    /// Indexed by the discriminant of each width.
+    const INT_WIDTHS: [IntWidth; 10] = [
+        IntWidth::U8,
+        IntWidth::U16,
+        IntWidth::U32,
+        IntWidth::U64,
+        IntWidth::U128,
+        IntWidth::I8,
+        IntWidth::I16,
+        IntWidth::I32,
+        IntWidth::I64,
+        IntWidth::I128,
+    ];
+    const FLOAT_WIDTHS: [FloatWidth; 2] = [FloatWidth::F32, FloatWidth::F64];
+
+    pub(super) fn write(interner: &STLayoutInterner<'_>) -> Vec<u8> {
+        let mut writer = Writer { bytes: Vec::new() };
+
+        writer.bytes.extend(MAGIC);
+        writer.u32(VERSION);
+        writer.str(&interner.target.to_string());
+
+        writer.list(&interner.vec, |writer, layout| writer.layout(layout));
+
+        // Sort the maps, so that the same interner always gives the same snapshot.
+        let mut map: Vec<_> = interner
+            .map
+            .iter()
+            .map(|(layout, interned)| (*interned, *layout))
+            .collect();
+        map.sort();
+        writer.list(&map, |writer, (interned, layout)| {
+            writer.layout(layout);
+            writer.index(interned);
+        });
+
+        let mut lambda_sets: Vec<_> = interner
+            .normalized_lambda_set_map
+            .iter()
+            .map(|(normalized, lambda_set)| (lambda_set.full_layout, *normalized))
+            .collect();
+        lambda_sets.sort();
+        writer.list(&lambda_sets, |writer, (interned, normalized)| {
+            writer.lambda_set(normalized);
+            writer.index(interned);
+        });
+
+        writer.bytes
+    }
+
+    pub(super) fn read<'a>(
+        arena: &'a Bump,
+        bytes: &[u8],
+    ) -> Result<STLayoutInterner<'a>, SnapshotError> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(SnapshotError::NotASnapshot);
+        }
+
+        let mut reader = Reader {
+            arena,
+            bytes,
+            offset: MAGIC.len(),
+            layout_count: 0,
+        };
+
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let target_offset = reader.offset;
+        let target = Target::from_str(reader.str()?)
+            .map_err(|_| reader.malformed(target_offset, "target"))?;
+
+        let layouts_offset = reader.offset;
+        reader.layout_count = reader.len()?;
+        let mut vec = Vec::with_capacity(reader.capacity(reader.layout_count));
+        for _ in 0..reader.layout_count {
+            vec.push(reader.layout()?);
+        }
+
+        // Constants like `Layout::U8` refer to the reserved layouts by index.
+        let reserved = STLayoutInterner::with_capacity(0, target).vec;
+        if !vec.starts_with(&reserved) {
+            return Err(reader.malformed(layouts_offset, "reserved layouts"));
+        }
+
+        let len = reader.len()?;
+        let mut map = BumpMap::with_capacity_and_hasher(reader.capacity(len), default_hasher());
+        for _ in 0..len {
+            let layout = reader.layout()?;
+            map.insert(layout, reader.index()?);
+        }
+
+        let len = reader.len()?;
+        let mut normalized_lambda_set_map =
+            BumpMap::with_capacity_and_hasher(reader.capacity(len), default_hasher());
+        for _ in 0..len {
+            let normalized = reader.lambda_set()?;
+            let index_offset = reader.offset;
+            let interned = reader.index()?;
+            match vec[interned.0].repr {
+                LayoutWrapper::Direct(LayoutRepr::LambdaSet(lambda_set)) => {
+                    normalized_lambda_set_map.insert(normalized, lambda_set);
+                }
+                _ => return Err(reader.malformed(index_offset, "lambda set index")),
+            }
+        }
+
+        if reader.offset != bytes.len() {
+            return Err(reader.malformed(reader.offset, "trailing bytes"));
+        }
+
+        Ok(STLayoutInterner {
+            map,
+            normalized_lambda_set_map,
+            vec,
+            target,
+            owned_arena: None,
+        })
+    }
+
+    struct Writer {
+        bytes: Vec<u8>,
+    }
+
+    impl Writer {
+        fn u8(&mut self, value: u8) {
+            self.bytes.push(value);
+        }
+
+        fn u32(&mut self, value: u32) {
+            self.bytes.extend(value.to_le_bytes());
+        }
+
+        fn len(&mut self, len: usize) {
+            let len = u32::try_from(len).unwrap_or_else(|_| {
+                roc_error_macros::internal_error!("{len} is too large for a layout snapshot")
+            });
+            self.u32(len);
+        }
+
+        fn list<T: Copy>(&mut self, items: &[T], mut write: impl FnMut(&mut Self, T)) {
+            self.len(items.len());
+            for item in items {
+                write(self, *item);
+            }
+        }
+
+        fn str(&mut self, value: &str) {
+            self.len(value.len());
+            self.bytes.extend(value.as_bytes());
+        }
+
+        fn symbol(&mut self, symbol: Symbol) {
+            self.bytes.extend(symbol.as_u64().to_le_bytes());
+        }
+
+        fn index(&mut self, layout: InLayout<'_>) {
+            self.len(layout.0);
+        }
+
+        fn indices(&mut self, layouts: &[InLayout<'_>]) {
+            self.list(layouts, Self::index);
+        }
+
+        fn tags(&mut self, tags: &[&[InLayout<'_>]]) {
+            self.list(tags, Self::indices);
+        }
+
+        fn layout(&mut self, layout: Layout<'_>) {
+            match layout.repr {
+                LayoutWrapper::Direct(repr) => {
+                    self.u8(0);
+                    self.repr(repr);
+                }
+                LayoutWrapper::Newtype(inner) => {
+                    self.u8(1);
+                    self.index(inner);
+                }
+            }
+
+            match layout.semantic.parts() {
+                SemanticParts::None => self.u8(0),
+                SemanticParts::Record(fields) => {
+                    self.u8(1);
+                    self.list(fields, Self::str);
+                }
+                SemanticParts::Tuple(size) => {
+                    self.u8(2);
+                    self.len(size);
+                }
+                SemanticParts::TagUnion(tags) => {
+                    self.u8(3);
+                    self.list(tags, Self::str);
+                }
+                SemanticParts::Lambdas(lambdas) => {
+                    self.u8(4);
+                    self.list(lambdas, Self::symbol);
+                }
+            }
+        }
+
+        fn repr(&mut self, repr: LayoutRepr<'_>) {
+            match repr {
+                LayoutRepr::Builtin(builtin) => {
+                    self.u8(0);
+                    match builtin {
+                        Builtin::Int(width) => {
+                            self.u8(0);
+                            self.u8(width as u8);
+                        }
+                        Builtin::Float(width) => {
+                            self.u8(1);
+                            self.u8(width as u8);
+                        }
+                        Builtin::Bool => self.u8(2),
+                        Builtin::Decimal => self.u8(3),
+                        Builtin::Str => self.u8(4),
+                        Builtin::List(element) => {
+                            self.u8(5);
+                            self.index(element);
+                        }
+                    }
+                }
+                LayoutRepr::Struct(fields) => {
+                    self.u8(1);
+                    self.indices(fields);
+                }
+                LayoutRepr::Ptr(inner) => {
+                    self.u8(2);
+                    self.index(inner);
+                }
+                LayoutRepr::Union(union_layout) => {
+                    self.u8(3);
+                    match union_layout {
+                        UnionLayout::NonRecursive(tags) => {
+                            self.u8(0);
+                            self.tags(tags);
+                        }
+                        UnionLayout::Recursive(tags) => {
+                            self.u8(1);
+                            self.tags(tags);
+                        }
+                        UnionLayout::NonNullableUnwrapped(fields) => {
+                            self.u8(2);
+                            self.indices(fields);
+                        }
+                        UnionLayout::NullableWrapped {
+                            nullable_id,
+                            other_tags,
+                        } => {
+                            self.u8(3);
+                            self.len(nullable_id as usize);
+                            self.tags(other_tags);
+                        }
+                        UnionLayout::NullableUnwrapped {
+                            nullable_id,
+                            other_fields,
+                        } => {
+                            self.u8(4);
+                            self.u8(nullable_id as u8);
+                            self.indices(other_fields);
+                        }
+                    }
+                }
+                LayoutRepr::LambdaSet(lambda_set) => {
+                    self.u8(4);
+                    self.lambda_set(lambda_set);
+                }
+                LayoutRepr::RecursivePointer(inner) => {
+                    self.u8(5);
+                    self.index(inner);
+                }
+                LayoutRepr::FunctionPointer(FunctionPointer { args, ret }) => {
+                    self.u8(6);
+                    self.indices(args);
+                    self.index(ret);
+                }
+                LayoutRepr::Erased(Erased) => self.u8(7),
+            }
+        }
+
+        fn lambda_set(&mut self, lambda_set: LambdaSet<'_>) {
+            let LambdaSet {
+                args,
+                ret,
+                set,
+                representation,
+                full_layout,
+            } = lambda_set;
+
+            self.indices(args);
+            self.index(ret);
+            self.list(set, |writer, (symbol, captures)| {
+                writer.symbol(symbol);
+                writer.indices(captures);
+            });
+            self.index(representation);
+            self.index(full_layout);
+        }
+    }
+
+    struct Reader<'a, 'b> {
+        arena: &'a Bump,
+        bytes: &'b [u8],
+        offset: usize,
+        /// Every layout index in the snapshot must be below this.
+        layout_count: usize,
+    }
+
+    impl<'a, 'b> Reader<'a, 'b> {
+        fn malformed(&self, offset: usize, what: &'static str) -> SnapshotError {
+            SnapshotError::Malformed { offset, what }
+        }
+
+        /// The error for a one byte tag that was just read.
+        fn bad_tag(&self, what: &'static str) -> SnapshotError {
+            self.malformed(self.offset - 1, what)
+        }
+
+        /// How much to preallocate for `len` values, without trusting `len` further than the
+        /// remaining bytes could possibly go.
+        fn capacity(&self, len: usize) -> usize {
+            len.min(self.bytes.len() - self.offset)
+        }
+
+        fn take<const N: usize>(&mut self) -> Result<[u8; N], SnapshotError> {
+            let bytes = self
+                .bytes
+                .get(self.offset..self.offset + N)
+                .ok_or(SnapshotError::UnexpectedEnd)?;
+            self.offset += N;
+            Ok(bytes.try_into().unwrap())
+        }
+
+        fn u8(&mut self) -> Result<u8, SnapshotError> {
+            let [byte] = self.take()?;
+            Ok(byte)
+        }
+
+        fn u32(&mut self) -> Result<u32, SnapshotError> {
+            Ok(u32::from_le_bytes(self.take()?))
+        }
+
+        fn len(&mut self) -> Result<usize, SnapshotError> {
+            Ok(self.u32()? as usize)
+        }
+
+        fn list<T>(
+            &mut self,
+            mut read: impl FnMut(&mut Self) -> Result<T, SnapshotError>,
+        ) -> Result<&'a [T], SnapshotError> {
+            let len = self.len()?;
+            let mut items =
+                bumpalo::collections::Vec::with_capacity_in(self.capacity(len), self.arena);
+            for _ in 0..len {
+                items.push(read(self)?);
+            }
+            Ok(items.into_bump_slice())
+        }
+
+        fn str(&mut self) -> Result<&'a str, SnapshotError> {
+            let len = self.len()?;
+            let offset = self.offset;
+            let bytes = self
+                .bytes
+                .get(offset..offset + len)
+                .ok_or(SnapshotError::UnexpectedEnd)?;
+            self.offset += len;
+
+            let value = std::str::from_utf8(bytes).map_err(|_| self.malformed(offset, "string"))?;
+            Ok(self.arena.alloc_str(value))
+        }
+
+        fn symbol(&mut self) -> Result<Symbol, SnapshotError> {
+            let value = u64::from_le_bytes(self.take()?);
+            Symbol::from_u64(value).ok_or_else(|| self.malformed(self.offset - 8, "symbol"))
+        }
+
+        fn index(&mut self) -> Result<InLayout<'a>, SnapshotError> {
+            let index = self.len()?;
+            if index >= self.layout_count {
+                return Err(self.malformed(self.offset - 4, "layout index"));
+            }
+            Ok(unsafe { InLayout::from_index(index) })
+        }
+
+        fn indices(&mut self) -> Result<&'a [InLayout<'a>], SnapshotError> {
+            self.list(Self::index)
+        }
+
+        fn tags(&mut self) -> Result<&'a [&'a [InLayout<'a>]], SnapshotError> {
+            self.list(Self::indices)
+        }
+
+        fn layout(&mut self) -> Result<Layout<'a>, SnapshotError> {
+            let repr = match self.u8()? {
+                0 => self.repr()?.direct(),
+                1 => self.index()?.newtype(),
+                _ => return Err(self.bad_tag("layout wrapper")),
+            };
+
+            let semantic = match self.u8()? {
+                0 => SemanticParts::None,
+                1 => SemanticParts::Record(self.list(Self::str)?),
+                2 => SemanticParts::Tuple(self.len()?),
+                3 => SemanticParts::TagUnion(self.list(Self::str)?),
+                4 => SemanticParts::Lambdas(self.list(Self::symbol)?),
+                _ => return Err(self.bad_tag("semantic representation")),
+            };
+
+            Ok(Layout {
+                repr,
+                semantic: SemanticRepr::from_parts(semantic),
+            })
+        }
+
+        fn repr(&mut self) -> Result<LayoutRepr<'a>, SnapshotError> {
+            let repr = match self.u8()? {
+                0 => LayoutRepr::Builtin(match self.u8()? {
+                    0 => {
+                        let width = INT_WIDTHS.get(self.u8()? as usize).copied();
+                        Builtin::Int(width.ok_or_else(|| self.bad_tag("int width"))?)
+                    }
+                    1 => {
+                        let width = FLOAT_WIDTHS.get(self.u8()? as usize).copied();
+                        Builtin::Float(width.ok_or_else(|| self.bad_tag("float width"))?)
+                    }
+                    2 => Builtin::Bool,
+                    3 => Builtin::Decimal,
+                    4 => Builtin::Str,
+                    5 => Builtin::List(self.index()?),
+                    _ => return Err(self.bad_tag("builtin")),
+                }),
+                1 => LayoutRepr::Struct(self.indices()?),
+                2 => LayoutRepr::Ptr(self.index()?),
+                3 => LayoutRepr::Union(match self.u8()? {
+                    0 => UnionLayout::NonRecursive(self.tags()?),
+                    1 => UnionLayout::Recursive(self.tags()?),
+                    2 => UnionLayout::NonNullableUnwrapped(self.indices()?),
+                    3 => {
+                        let nullable_id = self.u32()?;
+                        let nullable_id = u16::try_from(nullable_id)
+                            .map_err(|_| self.malformed(self.offset - 4, "nullable id"))?;
+                        UnionLayout::NullableWrapped {
+                            nullable_id,
+                            other_tags: self.tags()?,
+                        }
+                    }
+                    4 => {
+                        let nullable_id = match self.u8()? {
+                            0 => false,
+                            1 => true,
+                            _ => return Err(self.bad_tag("nullable id")),
+                        };
+                        UnionLayout::NullableUnwrapped {
+                            nullable_id,
+                            other_fields: self.indices()?,
+                        }
+                    }
+                    _ => return Err(self.bad_tag("union layout")),
+                }),
+                4 => LayoutRepr::LambdaSet(self.lambda_set()?),
+                5 => LayoutRepr::RecursivePointer(self.index()?),
+                6 => LayoutRepr::FunctionPointer(FunctionPointer {
+                    args: self.indices()?,
+                    ret: self.index()?,
+                }),
+                7 => LayoutRepr::Erased(Erased),
+                _ => return Err(self.bad_tag("layout")),
+            };
+
+            Ok(repr)
+        }
+
+        fn lambda_set(&mut self) -> Result<LambdaSet<'a>, SnapshotError> {
+            let args = self.indices()?;
+            let ret = self.index()?;
+            let set = self.list(|reader| Ok((reader.symbol()?, reader.indices()?)))?;
+            let representation = self.index()?;
+            let full_layout = self.index()?;
+
+            Ok(LambdaSet {
+                args: self.arena.alloc(args),
+                ret,
+                set: self.arena.alloc(set),
+                representation,
+                full_layout,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod insert_lambda_set {
+    use bumpalo::Bump;
+    use roc_module::symbol::Symbol;
+    use roc_target::Target;
+
+    use crate::layout::{LambdaSet, Layout, LayoutRepr, SemanticRepr};
+
+    use super::{GlobalLayoutInterner, InLayout, LayoutInterner, NeedsRecursionPointerFixup};
+
+    const TARGET: Target = Target::LinuxX64;
+    const TEST_SET: &&[(Symbol, &[InLayout])] =
+        &(&[(Symbol::ATTR_ATTR, &[Layout::UNIT] as &[_])] as &[_]);
+    const TEST_ARGS: &&[InLayout] = &(&[Layout::UNIT] as &[_]);
+    const TEST_RET: InLayout = Layout::UNIT;
+
+    const FIXUP: NeedsRecursionPointerFixup = NeedsRecursionPointerFixup(true);
+
+    #[test]
+    fn two_threads_write() {
+        for _ in 0..100 {
+            let mut arenas: Vec<_> = std::iter::repeat_with(Bump::new).take(10).collect();
+            let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+            let set = TEST_SET;
+            let repr = Layout::UNIT;
+            std::thread::scope(|s| {
+                let mut handles = Vec::with_capacity(10);
+                for arena in arenas.iter_mut() {
+                    let mut interner = global.fork();
+                    handles.push(s.spawn(move || {
+                        interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr)
+                    }))
+                }
+                let ins: Vec<LambdaSet> = handles.into_iter().map(|t| t.join().unwrap()).collect();
+                let interned = ins[0];
+                assert!(ins.iter().all(|in2| interned == *in2));
+            });
+        }
+    }
+
+    #[test]
+    fn insert_then_reintern() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let mut interner = global.fork();
+
+        let lambda_set =
+            interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, TEST_SET, FIXUP, Layout::UNIT);
+        let lambda_set_layout_in = interner.insert(Layout {
+            repr: LayoutRepr::LambdaSet(lambda_set).direct(),
+            semantic: SemanticRepr::NONE,
+        });
+        assert_eq!(lambda_set.full_layout, lambda_set_layout_in);
+    }
+
+    #[test]
+    fn write_global_then_single_threaded() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let set = TEST_SET;
+        let repr = Layout::UNIT;
+
+        let in1 = {
+            let mut interner = global.fork();
+            interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr)
+        };
+
+        let in2 = {
+            let mut st_interner = global.unwrap().unwrap();
+            st_interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr)
+        };
+
+        assert_eq!(in1, in2);
+    }
+
+    #[test]
+    fn write_single_threaded_then_global() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let mut st_interner = global.unwrap().unwrap();
+
+        let set = TEST_SET;
+        let repr = Layout::UNIT;
+
+        let in1 = st_interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr);
+
+        let global = st_interner.into_global();
+        let mut interner = global.fork();
+
+        let in2 = interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr);
+
+        assert_eq!(in1, in2);
+    }
+}
+
+#[cfg(test)]
+mod insert_recursive_layout {
+    use bumpalo::Bump;
+    use roc_target::Target;
+
+    use crate::layout::{Builtin, InLayout, Layout, LayoutRepr, SemanticRepr, UnionLayout};
+
+    use super::{GlobalLayoutInterner, LayoutInterner};
+
+    const TARGET: Target = Target::LinuxX64;
+
+    fn make_layout<'a>(arena: &'a Bump, interner: &mut impl LayoutInterner<'a>) -> Layout<'a> {
+        let list_rec = Layout {
+            repr: LayoutRepr::Builtin(Builtin::List(Layout::NAKED_RECURSIVE_PTR)).direct(),
+            semantic: SemanticRepr::NONE,
+        };
+        let repr = LayoutRepr::Union(UnionLayout::Recursive(&*arena.alloc([
+            &*arena.alloc([interner.insert(list_rec)]),
+            &*arena.alloc_slice_fill_iter([interner.insert_direct_no_semantic(
+                LayoutRepr::struct_(&*arena.alloc([Layout::NAKED_RECURSIVE_PTR])),
+            )]),
+        ])))
+        .direct();
+        Layout {
+            repr,
+            semantic: SemanticRepr::NONE,
+        }
+    }
+
+    fn get_rec_ptr_index<'a>(interner: &impl LayoutInterner<'a>, layout: InLayout<'a>) -> usize {
+        match interner.chase_recursive(layout) {
+            LayoutRepr::Union(UnionLayout::Recursive(&[&[l1], &[l2]])) => {
+                match (interner.get_repr(l1), interner.get_repr(l2)) {
+                    (LayoutRepr::Builtin(Builtin::List(l1)), LayoutRepr::Struct(&[l2])) => {
+                        match (interner.get_repr(l1), interner.get_repr(l2)) {
+                            (
+                                LayoutRepr::RecursivePointer(i1),
+                                LayoutRepr::RecursivePointer(i2),
+                            ) => {
+                                assert_eq!(i1, i2);
+                                assert_ne!(i1, Layout::VOID);
+                                i1.0
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn write_two_threads() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let layout = {
+            let mut interner = global.fork();
+            make_layout(arena, &mut interner)
+        };
+
+        let in1 = {
+            let mut interner = global.fork();
+            interner.insert_recursive(arena, layout)
+        };
+
+        let in2 = {
+            let mut interner = global.fork();
+            interner.insert_recursive(arena, layout)
+        };
+
+        assert_eq!(in1, in2);
+    }
+
+    #[test]
+    fn write_twice_thread_local_single_thread() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let mut interner = global.fork();
+        let layout = make_layout(arena, &mut interner);
+
+        let in1 = interner.insert_recursive(arena, layout);
+        let rec1 = get_rec_ptr_index(&interner, in1);
+        let in2 = interner.insert_recursive(arena, layout);
+        let rec2 = get_rec_ptr_index(&interner, in2);
+
+        assert_eq!(in1, in2);
+        assert_eq!(rec1, rec2);
+    }
+
+    #[test]
+    fn write_twice_single_thread() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let mut interner = GlobalLayoutInterner::unwrap(global).unwrap();
+        let layout = make_layout(arena, &mut interner);
+
+        let in1 = interner.insert_recursive(arena, layout);
+        let rec1 = get_rec_ptr_index(&interner, in1);
+        let in2 = interner.insert_recursive(arena, layout);
+        let rec2 = get_rec_ptr_index(&interner, in2);
+
+        assert_eq!(in1, in2);
+        assert_eq!(rec1, rec2);
+    }
+
+    #[test]
+    fn many_threads_read_write() {
+        for _ in 0..100 {
+            let mut arenas: Vec<_> = std::iter::repeat_with(Bump::new).take(10).collect();
+            let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+            std::thread::scope(|s| {
+                let mut handles = Vec::with_capacity(10);
+                for arena in arenas.iter_mut() {
+                    let mut interner = global.fork();
+                    let handle = s.spawn(move || {
+                        let layout = make_layout(arena, &mut interner);
+                        let in_layout = interner.insert_recursive(arena, layout);
+                        (in_layout, get_rec_ptr_index(&interner, in_layout))
+                    });
+                    handles.push(handle);
+                }
+                let ins: Vec<(InLayout, usize)> =
+                    handles.into_iter().map(|t| t.join().unwrap()).collect();
+                let interned = ins[0];
+                assert!(ins.iter().all(|in2| interned == *in2));
+            });
+        }
+    }
+
+    #[test]
+    fn insert_then_reintern() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let mut interner = global.fork();
+
+        let layout = make_layout(arena, &mut interner);
+        let interned_layout = interner.insert_recursive(arena, layout);
+        let full_layout = interner.get(interned_layout);
+        assert_ne!(layout, full_layout);
+        assert_eq!(interner.insert(full_layout), interned_layout);
+    }
+
+    #[test]
+    fn write_global_then_single_threaded() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let layout = {
+            let mut interner = global.fork();
+            make_layout(arena, &mut interner)
+        };
+
+        let in1: InLayout = {
+            let mut interner = global.fork();
+            interner.insert_recursive(arena, layout)
+        };
+
+        let in2 = {
+            let mut st_interner = global.unwrap().unwrap();
+            st_interner.insert_recursive(arena, layout)
+        };
+
+        assert_eq!(in1, in2);
+    }
+
+    #[test]
+    fn write_single_threaded_then_global() {
+        let arena = &Bump::new();
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let mut st_interner = global.unwrap().unwrap();
+
+        let layout = make_layout(arena, &mut st_interner);
+
+        let in1 = st_interner.insert_recursive(arena, layout);
+
+        let global = st_interner.into_global();
+        let mut interner = global.fork();
+
+        let in2 = interner.insert_recursive(arena, layout);
+
+        assert_eq!(in1, in2);
+    }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod observer {
+    use std::sync::Arc;
+
+    use bumpalo::Bump;
+    use parking_lot::Mutex;
+    use roc_module::symbol::Symbol;
+    use roc_target::Target;
+
+    use crate::layout::{Builtin, LambdaSet, Layout, LayoutRepr, SemanticRepr, UnionLayout};
+
+    use super::{
+        GlobalLayoutInterner, InLayout, LayoutInterner, LayoutInternerObserver,
+        NeedsRecursionPointerFixup,
+    };
+
+    const TARGET: Target = Target::LinuxX64;
+
+    #[derive(Debug, PartialEq)]
+    enum Event<'a> {
+        Insert(Layout<'a>, InLayout<'a>),
+        LambdaSet(LambdaSet<'a>),
+        Recursive(Layout<'a>, Layout<'a>, InLayout<'a>),
+    }
+
+    #[derive(Debug, Default)]
+    struct Recorder<'a>(Mutex<Vec<Event<'a>>>);
+
+    impl<'a> LayoutInternerObserver<'a> for Recorder<'a> {
+        fn on_insert(&self, layout: Layout<'a>, interned: InLayout<'a>) {
+            self.0.lock().push(Event::Insert(layout, interned));
+        }
+
+        fn on_lambda_set(&self, lambda_set: LambdaSet<'a>) {
+            self.0.lock().push(Event::LambdaSet(lambda_set));
+        }
+
+        fn on_recursive(
+            &self,
+            normalized: Layout<'a>,
+            full_layout: Layout<'a>,
+            interned: InLayout<'a>,
+        ) {
+            self.0
+                .lock()
+                .push(Event::Recursive(normalized, full_layout, interned));
+        }
+    }
+
+    fn observed<'a>() -> (GlobalLayoutInterner<'a>, Arc<Recorder<'a>>) {
+        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let recorder = Arc::new(Recorder::default());
+        global.set_observer(Some(recorder.clone()));
+        (global, recorder)
+    }
+
+    #[test]
+    fn insert_is_reported_once() {
+        let (global, recorder) = observed();
+        let mut interner = global.fork();
+
+        let layout = Layout {
+            repr: LayoutRepr::Builtin(Builtin::List(Layout::I16)).direct(),
+            semantic: SemanticRepr::NONE,
+        };
+        let in1 = interner.insert(layout);
+        let in2 = global.fork().insert(layout);
+
+        assert_eq!(in1, in2);
+        assert_eq!(*recorder.0.lock(), [Event::Insert(layout, in1)]);
+    }
+
+    #[test]
+    fn reserved_layouts_are_not_reported() {
+        let (global, recorder) = observed();
+        let mut interner = global.fork();
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgFields<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.debug_list()
-                .entries(self.1.iter().map(|l| Dbg(self.0, *l)))
-                .finish()
-        }
+        interner.insert(Layout::UNIT_NAKED);
+        assert!(recorder.0.lock().is_empty());
     }
 
-    struct DbgRepr<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'r LayoutRepr<'a>);
+    #[test]
+    fn lambda_set_is_reported() {
+        let arena = &Bump::new();
+        let (global, recorder) = observed();
+        let mut interner = global.fork();
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgRepr<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self.1 {
-                LayoutRepr::Builtin(b) => f
-                    .debug_tuple("Builtin")
-                    .field(&DbgBuiltin(self.0, *b))
-                    .finish(),
-                LayoutRepr::Struct(field_layouts) => f
-                    .debug_struct("Struct")
-                    .field("fields", &DbgFields(self.0, field_layouts))
-                    .finish(),
-                LayoutRepr::Ptr(b) => f.debug_tuple("Ptr").field(&Dbg(self.0, *b)).finish(),
-                LayoutRepr::Union(un) => f
-                    .debug_tuple("Union")
-                    .field(&DbgUnion(self.0, *un))
-                    .finish(),
-                LayoutRepr::LambdaSet(ls) => f
-                    .debug_tuple("LambdaSet")
-                    .field(&DbgLambdaSet(self.0, *ls))
-                    .finish(),
-                LayoutRepr::RecursivePointer(rp) => {
-                    f.debug_tuple("RecursivePointer").field(&rp.0).finish()
-                }
-                LayoutRepr::FunctionPointer(fp) => f
-                    .debug_struct("FunctionPointer")
-                    .field("args", &fp.args)
-                    .field("ret", &fp.ret)
-                    .finish(),
-                LayoutRepr::Erased(Erased) => f.debug_struct("?Erased").finish(),
-            }
-        }
-    }
+        let set: &&[(Symbol, &[InLayout])] =
+            &(&[(Symbol::ATTR_ATTR, &[Layout::UNIT] as &[_])] as &[_]);
+        let args: &&[InLayout] = &(&[Layout::UNIT] as &[_]);
+        const FIXUP: NeedsRecursionPointerFixup = NeedsRecursionPointerFixup(false);
 
-    struct DbgTags<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'a [&'a [InLayout<'a>]]);
+        let lambda_set =
+            interner.insert_lambda_set(arena, args, Layout::UNIT, set, FIXUP, Layout::UNIT);
+        interner.insert_lambda_set(arena, args, Layout::UNIT, set, FIXUP, Layout::UNIT);
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgTags<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.debug_list()
-                .entries(self.1.iter().map(|l| DbgFields(self.0, l)))
-                .finish()
-        }
+        assert_eq!(*recorder.0.lock(), [Event::LambdaSet(lambda_set)]);
     }
 
-    struct DbgBuiltin<'a, 'r, I: LayoutInterner<'a>>(&'r I, Builtin<'a>);
+    #[test]
+    fn recursive_is_reported_after_its_reified_parts() {
+        let arena = &Bump::new();
+        let (global, recorder) = observed();
+        let mut interner = global.fork();
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgBuiltin<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self.1 {
-                Builtin::Int(w) => f.debug_tuple("Int").field(&w).finish(),
-                Builtin::Float(w) => f.debug_tuple("Frac").field(&w).finish(),
-                Builtin::Bool => f.debug_tuple("Bool").finish(),
-                Builtin::Decimal => f.debug_tuple("Decimal").finish(),
-                Builtin::Str => f.debug_tuple("Str").finish(),
-                Builtin::List(e) => f.debug_tuple("List").field(&Dbg(self.0, e)).finish(),
+        let list_rec = Layout {
+            repr: LayoutRepr::Builtin(Builtin::List(Layout::NAKED_RECURSIVE_PTR)).direct(),
+            semantic: SemanticRepr::NONE,
+        };
+        let normalized = Layout {
+            repr: LayoutRepr::Union(UnionLayout::Recursive(
+                &*arena.alloc([&*arena.alloc([interner.insert(list_rec)]) as &[_]]),
+            ))
+            .direct(),
+            semantic: SemanticRepr::NONE,
+        };
+        recorder.0.lock().clear();
+
+        let interned = interner.insert_recursive(arena, normalized);
+        let full_layout = interner.get(interned);
+
+        let events = recorder.0.lock();
+        let (last, reified) = events.split_last().unwrap();
+        assert_eq!(*last, Event::Recursive(normalized, full_layout, interned));
+        assert!(!reified.is_empty());
+        for event in reified {
+            match event {
+                Event::Insert(layout, slot) => assert_eq!(interner.get(*slot), *layout),
+                other => panic!("unexpected {other:?}"),
             }
         }
     }
+}
 
-    struct DbgUnion<'a, 'r, I: LayoutInterner<'a>>(&'r I, UnionLayout<'a>);
+#[cfg(test)]
+mod snapshot_round_trip {
+    use bumpalo::Bump;
+    use roc_module::symbol::Symbol;
+    use roc_target::Target;
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgUnion<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self.1 {
-                UnionLayout::NonRecursive(payloads) => f
-                    .debug_tuple("NonRecursive")
-                    .field(&DbgTags(self.0, payloads))
-                    .finish(),
-                UnionLayout::Recursive(payloads) => f
-                    .debug_tuple("Recursive")
-                    .field(&DbgTags(self.0, payloads))
-                    .finish(),
-                UnionLayout::NonNullableUnwrapped(fields) => f
-                    .debug_tuple("NonNullableUnwrapped")
-                    .field(&DbgFields(self.0, fields))
-                    .finish(),
-                UnionLayout::NullableWrapped {
-                    nullable_id,
-                    other_tags,
-                } => f
-                    .debug_struct("NullableWrapped")
-                    .field("nullable_id", &nullable_id)
-                    .field("other_tags", &DbgTags(self.0, other_tags))
-                    .finish(),
-                UnionLayout::NullableUnwrapped {
-                    nullable_id,
-                    other_fields,
-                } => f
-                    .debug_struct("NullableUnwrapped")
-                    .field("nullable_id", &nullable_id)
-                    .field("other_tags", &DbgFields(self.0, other_fields))
-                    .finish(),
-            }
-        }
-    }
+    use crate::layout::{Builtin, FunctionPointer, Layout, LayoutRepr, SemanticRepr, UnionLayout};
 
-    struct DbgLambdaSet<'a, 'r, I: LayoutInterner<'a>>(&'r I, LambdaSet<'a>);
+    use super::{
+        InLayout, LayoutInterner, NeedsRecursionPointerFixup, STLayoutInterner, SnapshotError,
+    };
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgLambdaSet<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let LambdaSet {
-                args,
-                ret,
-                set,
-                representation,
-                full_layout,
-            } = self.1;
+    // Not the host, to check that the target survives the round trip.
+    const TARGET: Target = Target::LinuxArm64;
 
-            f.debug_struct("LambdaSet")
-                .field("args", &DbgFields(self.0, args))
-                .field("ret", &Dbg(self.0, ret))
-                .field("set", &DbgCapturesSet(self.0, set))
-                .field("representation", &Dbg(self.0, representation))
-                .field("full_layout", &full_layout)
-                .finish()
-        }
+    /// Interns one of every kind of layout, returning where they ended up.
+    fn populate<'a>(arena: &'a Bump, interner: &mut STLayoutInterner<'a>) -> Vec<InLayout<'a>> {
+        let list =
+            interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::I16)));
+        let record = interner.insert(Layout {
+            repr: LayoutRepr::Struct(arena.alloc([Layout::STR, list])).direct(),
+            semantic: SemanticRepr::record(arena.alloc(["name", "scores"])),
+        });
+        let tuple = interner.insert(Layout {
+            repr: LayoutRepr::Struct(arena.alloc([Layout::F32, Layout::DEC])).direct(),
+            semantic: SemanticRepr::tuple(2),
+        });
+        let newtype = interner.insert(Layout {
+            repr: record.newtype(),
+            semantic: SemanticRepr::NONE,
+        });
+        let union_layout = interner.insert(Layout {
+            repr: LayoutRepr::Union(UnionLayout::NullableWrapped {
+                nullable_id: 1,
+                other_tags: arena.alloc([&*arena.alloc([tuple]) as &[_], &[]]),
+            })
+            .direct(),
+            semantic: SemanticRepr::tag_union(arena.alloc(["A", "B", "C"])),
+        });
+        let function_pointer =
+            interner.insert_direct_no_semantic(LayoutRepr::FunctionPointer(FunctionPointer {
+                args: arena.alloc([list]),
+                ret: Layout::BOOL,
+            }));
+        let recursive = interner.insert_recursive(
+            arena,
+            Layout {
+                repr: LayoutRepr::Union(UnionLayout::Recursive(arena.alloc([
+                    &*arena.alloc([Layout::I64, Layout::NAKED_RECURSIVE_PTR]) as &[_],
+                    &[],
+                ])))
+                .direct(),
+                semantic: SemanticRepr::NONE,
+            },
+        );
+        let lambda_set = interner.insert_lambda_set(
+            arena,
+            arena.alloc(&*arena.alloc([Layout::UNIT]) as &[_]),
+            Layout::UNIT,
+            arena.alloc(
+                &*arena.alloc([(Symbol::ATTR_ATTR, &*arena.alloc([newtype]) as &[_])]) as &[_],
+            ),
+            NeedsRecursionPointerFixup(false),
+            Layout::UNIT,
+        );
+        let lambdas = interner.insert(Layout {
+            repr: LayoutRepr::Struct(arena.alloc([newtype])).direct(),
+            semantic: SemanticRepr::lambdas(arena.alloc([Symbol::ATTR_ATTR])),
+        });
+        let erased = interner.insert_direct_no_semantic(LayoutRepr::ERASED);
+
+        vec![
+            list,
+            record,
+            tuple,
+            newtype,
+            union_layout,
+            function_pointer,
+            recursive,
+            lambda_set.full_layout,
+            lambdas,
+            erased,
+        ]
     }
 
-    struct DbgCapturesSet<'a, 'r, I: LayoutInterner<'a>>(&'r I, &'a [(Symbol, &'a [InLayout<'a>])]);
+    #[test]
+    fn round_trip() {
+        let arena = &Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET);
+        populate(arena, &mut interner);
+        let bytes = interner.serialize();
+
+        let restored_arena = &Bump::new();
+        let restored = STLayoutInterner::deserialize(restored_arena, &bytes).unwrap();
+
+        assert_eq!(restored.target, TARGET);
+        assert_eq!(restored.vec, interner.vec);
+        assert_eq!(restored.map, interner.map);
+        assert_eq!(
+            restored.normalized_lambda_set_map,
+            interner.normalized_lambda_set_map
+        );
+        assert_eq!(restored.serialize(), bytes);
+    }
 
-    impl<'a, 'r, I: LayoutInterner<'a>> std::fmt::Debug for DbgCapturesSet<'a, 'r, I> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.debug_list()
-                .entries(
-                    self.1
-                        .iter()
-                        .map(|(sym, captures)| (sym.as_u64(), DbgFields(self.0, captures))),
-                )
-                .finish()
+    #[test]
+    fn restored_layouts_keep_their_indices() {
+        let arena = &Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET);
+        let interned = populate(arena, &mut interner);
+        let bytes = interner.serialize();
+
+        let restored_arena = &Bump::new();
+        let mut restored = STLayoutInterner::deserialize(restored_arena, &bytes).unwrap();
+        let len = restored.vec.len();
+
+        assert_eq!(populate(restored_arena, &mut restored), interned);
+        assert_eq!(restored.vec.len(), len);
+    }
+
+    #[test]
+    fn rejects_other_versions_and_truncation() {
+        let arena = &Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET);
+        populate(arena, &mut interner);
+        let mut bytes = interner.serialize();
+
+        for len in 0..bytes.len() {
+            let expected = if len < 8 {
+                SnapshotError::NotASnapshot
+            } else {
+                SnapshotError::UnexpectedEnd
+            };
+            let result = STLayoutInterner::deserialize(arena, &bytes[..len]);
+            assert_eq!(result.unwrap_err(), expected, "truncated to {len} bytes");
         }
+
+        bytes[8..12].copy_from_slice(&99u32.to_le_bytes());
+        let result = STLayoutInterner::deserialize(arena, &bytes);
+        assert_eq!(result.unwrap_err(), SnapshotError::UnsupportedVersion(99));
+    }
+
+    #[test]
+    fn rejects_out_of_range_indices() {
+        let arena = &Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET);
+        interner.insert_direct_no_semantic(LayoutRepr::Ptr(Layout::I16));
+        let mut bytes = interner.serialize();
+
+        // direct, pointer, index 9, no semantics
+        let encoded = [0, 2, 9, 0, 0, 0, 0];
+        let start = bytes
+            .windows(encoded.len())
+            .position(|window| window == encoded)
+            .unwrap();
+        bytes[start + 2] = 200;
+
+        let result = STLayoutInterner::deserialize(arena, &bytes);
+        assert_eq!(
+            result.unwrap_err(),
+            SnapshotError::Malformed {
+                offset: start + 2,
+                what: "layout index"
+            }
+        );
     }
 }
 
 #[cfg(test)]
-mod insert_lambda_set {
+mod stats {
     use bumpalo::Bump;
-    use roc_module::symbol::Symbol;
     use roc_target::Target;
 
-    use crate::layout::{LambdaSet, Layout, LayoutRepr, SemanticRepr};
+    use crate::layout::{Builtin, Layout, LayoutRepr, SemanticRepr, UnionLayout};
 
-    use super::{GlobalLayoutInterner, InLayout, LayoutInterner, NeedsRecursionPointerFixup};
+    use super::{
+        GlobalLayoutInterner, InternerStats, LayoutInterner, STLayoutInterner, RESERVED_LAYOUTS,
+    };
 
     const TARGET: Target = Target::LinuxX64;
-    const TEST_SET: &&[(Symbol, &[InLayout])] =
-        &(&[(Symbol::ATTR_ATTR, &[Layout::UNIT] as &[_])] as &[_]);
-    const TEST_ARGS: &&[InLayout] = &(&[Layout::UNIT] as &[_]);
-    const TEST_RET: InLayout = Layout::UNIT;
-
-    const FIXUP: NeedsRecursionPointerFixup = NeedsRecursionPointerFixup(true);
 
     #[test]
-    fn two_threads_write() {
-        for _ in 0..100 {
-            let mut arenas: Vec<_> = std::iter::repeat_with(Bump::new).take(10).collect();
-            let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-            let set = TEST_SET;
-            let repr = Layout::UNIT;
-            std::thread::scope(|s| {
-                let mut handles = Vec::with_capacity(10);
-                for arena in arenas.iter_mut() {
-                    let mut interner = global.fork();
-                    handles.push(s.spawn(move || {
-                        interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr)
-                    }))
-                }
-                let ins: Vec<LambdaSet> = handles.into_iter().map(|t| t.join().unwrap()).collect();
-                let interned = ins[0];
-                assert!(ins.iter().all(|in2| interned == *in2));
-            });
-        }
+    fn reserved_layouts() {
+        let stats = STLayoutInterner::with_capacity(0, TARGET).stats();
+
+        assert_eq!(stats.entries, RESERVED_LAYOUTS);
+        assert_eq!(stats.lambda_sets, 0);
+        assert_eq!(stats.recursive_layouts, 0);
+        assert_eq!(stats.arena_bytes, 0);
     }
 
     #[test]
-    fn insert_then_reintern() {
+    fn counts_variants_and_arena_bytes() {
         let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+        let mut interner = STLayoutInterner::with_capacity(0, TARGET);
+        let before = interner.stats();
+
+        let list =
+            interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::I16)));
+        interner.insert(Layout {
+            repr: LayoutRepr::Struct(arena.alloc([Layout::STR, list])).direct(),
+            semantic: SemanticRepr::record(arena.alloc(["name", "scores"])),
+        });
+        interner.insert_recursive(
+            arena,
+            Layout {
+                repr: LayoutRepr::Union(UnionLayout::Recursive(arena.alloc([
+                    &*arena.alloc([Layout::I64, Layout::NAKED_RECURSIVE_PTR]) as &[_],
+                    &[],
+                ])))
+                .direct(),
+                semantic: SemanticRepr::NONE,
+            },
+        );
+
+        let stats = interner.stats();
+        let word = std::mem::size_of::<usize>();
+
+        // The recursive union also interns a pointer back to itself.
+        assert_eq!(stats.entries, RESERVED_LAYOUTS + 4);
+        assert_eq!(stats.variants.builtin, before.variants.builtin + 1);
+        assert_eq!(stats.variants.struct_, before.variants.struct_ + 1);
+        assert_eq!(stats.variants.union, before.variants.union + 1);
+        assert_eq!(
+            stats.variants.recursive_pointer,
+            before.variants.recursive_pointer + 1
+        );
+        assert_eq!(stats.recursive_layouts, 1);
+        assert_eq!(
+            stats.arena_bytes,
+            // two struct fields, and two field names of 4 and 6 bytes
+            2 * word + 2 * 2 * word + 10
+            // two tags, with two fields and no fields
+            + 2 * 2 * word + 2 * word
+        );
+    }
+
+    #[test]
+    fn forks_report_the_global_interner() {
+        let global = GlobalLayoutInterner::with_capacity(0, TARGET);
         let mut interner = global.fork();
+        interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::I16)));
 
-        let lambda_set =
-            interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, TEST_SET, FIXUP, Layout::UNIT);
-        let lambda_set_layout_in = interner.insert(Layout {
-            repr: LayoutRepr::LambdaSet(lambda_set).direct(),
-            semantic: SemanticRepr::NONE,
-        });
-        assert_eq!(lambda_set.full_layout, lambda_set_layout_in);
+        let stats: InternerStats = global.fork().stats();
+
+        assert_eq!(stats, interner.stats());
+        assert_eq!(stats, global.stats());
+        assert_eq!(stats.entries, RESERVED_LAYOUTS + 1);
+    }
+}
+
+#[cfg(test)]
+mod reserved_lists {
+    use roc_target::Target;
+
+    use crate::layout::{Builtin, Layout, LayoutRepr};
+
+    use super::{GlobalLayoutInterner, InLayout, LayoutInterner, STLayoutInterner};
+
+    const TARGET: Target = Target::LinuxX64;
+
+    const LISTS: [(InLayout<'static>, InLayout<'static>); 4] = [
+        (Layout::U8, Layout::LIST_U8),
+        (Layout::STR, Layout::LIST_STR),
+        (Layout::I64, Layout::LIST_I64),
+        (Layout::DEC, Layout::LIST_DEC),
+    ];
+
+    fn check(interner: &mut impl LayoutInterner<'static>) {
+        for (element, reserved) in LISTS {
+            let repr = LayoutRepr::Builtin(Builtin::List(element));
+            assert_eq!(interner.insert_direct_no_semantic(repr), reserved);
+            assert_eq!(interner.get_repr(reserved), repr);
+        }
+
+        let other = LayoutRepr::Builtin(Builtin::List(Layout::I16));
+        let interned = interner.insert_direct_no_semantic(other);
+        assert!(!LISTS.iter().any(|&(_, reserved)| reserved == interned));
+        assert_eq!(interner.insert_direct_no_semantic(other), interned);
     }
 
     #[test]
-    fn write_global_then_single_threaded() {
-        let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-        let set = TEST_SET;
-        let repr = Layout::UNIT;
+    fn single_threaded() {
+        let mut interner = STLayoutInterner::with_capacity(0, TARGET);
+        check(&mut interner);
+        assert_eq!(interner.stats().entries, super::RESERVED_LAYOUTS + 1);
+    }
 
-        let in1 = {
-            let mut interner = global.fork();
-            interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr)
-        };
+    #[test]
+    fn thread_local() {
+        let global = GlobalLayoutInterner::with_capacity(0, TARGET);
+        check(&mut global.fork());
+        assert_eq!(global.stats().entries, super::RESERVED_LAYOUTS + 1);
+    }
+}
 
-        let in2 = {
-            let mut st_interner = global.unwrap().unwrap();
-            st_interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr)
+#[cfg(test)]
+mod retain_reachable {
+    use bumpalo::Bump;
+    use roc_module::symbol::Symbol;
+    use roc_target::Target;
+
+    use crate::layout::{Builtin, Layout, LayoutRepr, SemanticRepr, UnionLayout};
+
+    use super::{
+        GlobalLayoutInterner, LayoutInterner, NeedsRecursionPointerFixup, RESERVED_LAYOUTS,
+    };
+
+    const TARGET: Target = Target::LinuxX64;
+
+    #[test]
+    fn drops_unreachable_layouts() {
+        let arena = &Bump::new();
+        let mut global = GlobalLayoutInterner::with_capacity(0, TARGET);
+        let mut interner = global.fork();
+        let garbage =
+            interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::I16)));
+        let list =
+            interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::F64)));
+        let record = Layout {
+            repr: LayoutRepr::Struct(arena.alloc([list, Layout::STR])).direct(),
+            semantic: SemanticRepr::record(arena.alloc(["xs", "name"])),
         };
+        let root = interner.insert(record);
+        drop(interner);
 
-        assert_eq!(in1, in2);
+        let remap = global.retain(arena, [root].into_iter()).unwrap();
+
+        assert_eq!(remap.get(garbage), None);
+        assert_eq!(remap.get(Layout::STR), Some(Layout::STR));
+        assert_eq!(remap.get(list), Some(garbage));
+        assert_eq!(remap.get(root), Some(list));
+        assert_eq!(remap.dropped(), 1);
+        assert_eq!(global.stats().entries, RESERVED_LAYOUTS + 2);
+
+        let mut interner = global.fork();
+        let remapped = Layout {
+            repr: LayoutRepr::Struct(arena.alloc([garbage, Layout::STR])).direct(),
+            ..record
+        };
+        assert_eq!(interner.get(list), remapped);
+        assert_eq!(interner.insert(remapped), list);
     }
 
     #[test]
-    fn write_single_threaded_then_global() {
+    fn keeps_recursive_layouts_and_lambda_sets_internable() {
         let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-        let mut st_interner = global.unwrap().unwrap();
-
-        let set = TEST_SET;
-        let repr = Layout::UNIT;
+        let mut global = GlobalLayoutInterner::with_capacity(0, TARGET);
+        let mut interner = global.fork();
+        interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::I16)));
+
+        let normalized = Layout {
+            repr: LayoutRepr::Union(UnionLayout::Recursive(arena.alloc([
+                &*arena.alloc([Layout::I64, Layout::NAKED_RECURSIVE_PTR]) as &[_],
+                &[],
+            ])))
+            .direct(),
+            semantic: SemanticRepr::NONE,
+        };
+        let recursive = interner.insert_recursive(arena, normalized);
+        let args = &*arena.alloc(&*arena.alloc([recursive]) as &[_]);
+        let set = &*arena.alloc(
+            &*arena.alloc([(Symbol::ATTR_ATTR, &*arena.alloc([recursive]) as &[_])]) as &[_],
+        );
+        let lambda_set = interner.insert_lambda_set(
+            arena,
+            args,
+            Layout::UNIT,
+            set,
+            NeedsRecursionPointerFixup(false),
+            recursive,
+        );
+        drop(interner);
 
-        let in1 = st_interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr);
+        let remap = global
+            .retain(arena, [lambda_set.full_layout].into_iter())
+            .unwrap();
+        let recursive = remap.get(recursive).unwrap();
+        let full_layout = remap.get(lambda_set.full_layout).unwrap();
+        assert_eq!(remap.dropped(), 1);
 
-        let global = st_interner.into_global();
         let mut interner = global.fork();
+        assert_eq!(interner.insert_recursive(arena, normalized), recursive);
+        let args = &*arena.alloc(&*arena.alloc([recursive]) as &[_]);
+        let set = &*arena.alloc(
+            &*arena.alloc([(Symbol::ATTR_ATTR, &*arena.alloc([recursive]) as &[_])]) as &[_],
+        );
+        let reinserted = interner.insert_lambda_set(
+            arena,
+            args,
+            Layout::UNIT,
+            set,
+            NeedsRecursionPointerFixup(false),
+            recursive,
+        );
+        assert_eq!(reinserted.full_layout, full_layout);
+        assert_eq!(interner.chase_recursive_in(recursive), recursive);
+        assert_eq!(global.stats().entries, full_layout.index() + 1);
+    }
 
-        let in2 = interner.insert_lambda_set(arena, TEST_ARGS, TEST_RET, set, FIXUP, repr);
-
-        assert_eq!(in1, in2);
+    #[test]
+    fn refuses_while_forked() {
+        let arena = &Bump::new();
+        let mut global = GlobalLayoutInterner::with_capacity(0, TARGET);
+        let mut interner = global.fork();
+        let list =
+            interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::I16)));
+
+        assert!(global.retain(arena, std::iter::empty()).is_none());
+        assert_eq!(
+            interner.get(list).repr,
+            LayoutRepr::Builtin(Builtin::List(Layout::I16)).direct()
+        );
+
+        drop(interner);
+        let remap = global.retain(arena, std::iter::empty()).unwrap();
+        assert_eq!(remap.get(list), None);
+        assert_eq!(global.stats().entries, RESERVED_LAYOUTS);
     }
 }
 
 #[cfg(test)]
-mod insert_recursive_layout {
+mod owned_arena {
     use bumpalo::Bump;
+    use roc_module::symbol::Symbol;
     use roc_target::Target;
 
-    use crate::layout::{Builtin, InLayout, Layout, LayoutRepr, SemanticRepr, UnionLayout};
+    use crate::layout::{Layout, LayoutRepr, LayoutWrapper, SemanticRepr, UnionLayout};
 
-    use super::{GlobalLayoutInterner, LayoutInterner};
+    use super::{GlobalLayoutInterner, InLayout, LayoutInterner, NeedsRecursionPointerFixup};
 
     const TARGET: Target = Target::LinuxX64;
 
-    fn make_layout<'a>(arena: &'a Bump, interner: &mut impl LayoutInterner<'a>) -> Layout<'a> {
-        let list_rec = Layout {
-            repr: LayoutRepr::Builtin(Builtin::List(Layout::NAKED_RECURSIVE_PTR)).direct(),
-            semantic: SemanticRepr::NONE,
-        };
-        let repr = LayoutRepr::Union(UnionLayout::Recursive(&*arena.alloc([
-            &*arena.alloc([interner.insert(list_rec)]),
-            &*arena.alloc_slice_fill_iter([interner.insert_direct_no_semantic(
-                LayoutRepr::struct_(&*arena.alloc([Layout::NAKED_RECURSIVE_PTR])),
-            )]),
-        ])))
-        .direct();
+    fn record(arena: &Bump) -> Layout<'_> {
         Layout {
-            repr,
+            repr: LayoutRepr::Struct(arena.alloc([Layout::STR, Layout::U8])).direct(),
+            semantic: SemanticRepr::record(arena.alloc(["name", "age"])),
+        }
+    }
+
+    fn recursive(arena: &Bump) -> Layout<'_> {
+        Layout {
+            repr: LayoutRepr::Union(UnionLayout::Recursive(arena.alloc([
+                &*arena.alloc([Layout::I64, Layout::NAKED_RECURSIVE_PTR]) as &[_],
+                &[],
+            ])))
+            .direct(),
             semantic: SemanticRepr::NONE,
         }
     }
 
-    fn get_rec_ptr_index<'a>(interner: &impl LayoutInterner<'a>, layout: InLayout<'a>) -> usize {
-        match interner.chase_recursive(layout) {
-            LayoutRepr::Union(UnionLayout::Recursive(&[&[l1], &[l2]])) => {
-                match (interner.get_repr(l1), interner.get_repr(l2)) {
-                    (LayoutRepr::Builtin(Builtin::List(l1)), LayoutRepr::Struct(&[l2])) => {
-                        match (interner.get_repr(l1), interner.get_repr(l2)) {
-                            (
-                                LayoutRepr::RecursivePointer(i1),
-                                LayoutRepr::RecursivePointer(i2),
-                            ) => {
-                                assert_eq!(i1, i2);
-                                assert_ne!(i1, Layout::VOID);
-                                i1.0
-                            }
-                            _ => unreachable!(),
-                        }
-                    }
-                    _ => unreachable!(),
-                }
-            }
+    fn fields<'a>(layout: Layout<'a>) -> &'a [InLayout<'a>] {
+        match layout.repr {
+            LayoutWrapper::Direct(LayoutRepr::Struct(fields)) => fields,
             _ => unreachable!(),
         }
     }
 
     #[test]
-    fn write_two_threads() {
-        let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-        let layout = {
-            let mut interner = global.fork();
-            make_layout(arena, &mut interner)
-        };
+    fn outlives_the_arenas_of_borrowed_layouts() {
+        let global = unsafe { GlobalLayoutInterner::with_owned_arena(0, TARGET) };
+        let mut interner = global.fork();
 
-        let in1 = {
-            let mut interner = global.fork();
-            interner.insert_recursive(arena, layout)
-        };
+        let interned = {
+            let module_arena = Bump::new();
+            let layout = record(&module_arena);
+            let interned = interner.insert_borrowed(layout);
 
-        let in2 = {
-            let mut interner = global.fork();
-            interner.insert_recursive(arena, layout)
+            let stored = global.fork().get(interned);
+            assert!(!std::ptr::eq(fields(stored), fields(layout)));
+            interned
         };
 
-        assert_eq!(in1, in2);
+        let arena = Bump::new();
+        assert_eq!(interner.get(interned), record(&arena));
+        assert_eq!(global.fork().insert_borrowed(record(&arena)), interned);
     }
 
     #[test]
-    fn write_twice_thread_local_single_thread() {
-        let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
+    fn copies_recursive_layouts() {
+        let module_arena = Bump::new();
+        let global = unsafe { GlobalLayoutInterner::with_owned_arena(0, TARGET) };
         let mut interner = global.fork();
-        let layout = make_layout(arena, &mut interner);
 
-        let in1 = interner.insert_recursive(arena, layout);
-        let rec1 = get_rec_ptr_index(&interner, in1);
-        let in2 = interner.insert_recursive(arena, layout);
-        let rec2 = get_rec_ptr_index(&interner, in2);
+        let normalized = recursive(&module_arena);
+        let interned = interner.insert_recursive(&module_arena, normalized);
 
-        assert_eq!(in1, in2);
-        assert_eq!(rec1, rec2);
+        match (normalized.repr, interner.get(interned).repr) {
+            (
+                LayoutWrapper::Direct(LayoutRepr::Union(UnionLayout::Recursive(given))),
+                LayoutWrapper::Direct(LayoutRepr::Union(UnionLayout::Recursive(stored))),
+            ) => {
+                assert_eq!(given.len(), stored.len());
+                assert!(!std::ptr::eq(given, stored));
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            global.fork().insert_recursive(&module_arena, normalized),
+            interned
+        );
     }
 
     #[test]
-    fn write_twice_single_thread() {
-        let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-        let mut interner = GlobalLayoutInterner::unwrap(global).unwrap();
-        let layout = make_layout(arena, &mut interner);
+    fn copies_lambda_sets() {
+        let module_arena = Bump::new();
+        let global = unsafe { GlobalLayoutInterner::with_owned_arena(0, TARGET) };
+        let mut interner = global.fork();
 
-        let in1 = interner.insert_recursive(arena, layout);
-        let rec1 = get_rec_ptr_index(&interner, in1);
-        let in2 = interner.insert_recursive(arena, layout);
-        let rec2 = get_rec_ptr_index(&interner, in2);
+        let args = &*module_arena.alloc(&*module_arena.alloc([Layout::U8]) as &[_]);
+        let captures: &[_] = module_arena.alloc([Layout::STR]);
+        let set =
+            &*module_arena.alloc(&*module_arena.alloc([(Symbol::ATTR_ATTR, captures)]) as &[_]);
+        let lambda_set = interner.insert_lambda_set(
+            &module_arena,
+            args,
+            Layout::UNIT,
+            set,
+            NeedsRecursionPointerFixup(false),
+            Layout::UNIT,
+        );
 
-        assert_eq!(in1, in2);
-        assert_eq!(rec1, rec2);
+        assert_eq!(lambda_set.args, args);
+        assert!(!std::ptr::eq(*lambda_set.args, *args));
+        assert_eq!(lambda_set.set, set);
+        assert!(!std::ptr::eq(lambda_set.set[0].1, captures));
     }
 
     #[test]
-    fn many_threads_read_write() {
-        for _ in 0..100 {
-            let mut arenas: Vec<_> = std::iter::repeat_with(Bump::new).take(10).collect();
-            let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-            std::thread::scope(|s| {
-                let mut handles = Vec::with_capacity(10);
-                for arena in arenas.iter_mut() {
-                    let mut interner = global.fork();
-                    let handle = s.spawn(move || {
-                        let layout = make_layout(arena, &mut interner);
-                        let in_layout = interner.insert_recursive(arena, layout);
-                        (in_layout, get_rec_ptr_index(&interner, in_layout))
-                    });
-                    handles.push(handle);
-                }
-                let ins: Vec<(InLayout, usize)> =
-                    handles.into_iter().map(|t| t.join().unwrap()).collect();
-                let interned = ins[0];
-                assert!(ins.iter().all(|in2| interned == *in2));
-            });
-        }
+    fn unwrapped_interner_keeps_copying() {
+        let arena = Bump::new();
+        let global = unsafe { GlobalLayoutInterner::with_owned_arena(0, TARGET) };
+        let mut interner = global.unwrap().unwrap();
+
+        let layout = record(&arena);
+        let interned = interner.insert(layout);
+
+        assert_eq!(interner.get(interned), layout);
+        assert!(!std::ptr::eq(
+            fields(interner.get(interned)),
+            fields(layout)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod equivalence {
+    use bumpalo::Bump;
+    use roc_module::symbol::Symbol;
+    use roc_target::Target;
+
+    use crate::layout::{FunctionPointer, Layout, LayoutRepr, SemanticRepr, UnionLayout};
+
+    use super::{InLayout, LayoutInterner, NeedsRecursionPointerFixup, STLayoutInterner};
+
+    const TARGET: Target = Target::LinuxX64;
+
+    fn cons_list<'a>(
+        arena: &'a Bump,
+        interner: &mut STLayoutInterner<'a>,
+        elem: InLayout<'a>,
+        semantic: SemanticRepr<'a>,
+    ) -> InLayout<'a> {
+        let tags = arena.alloc([
+            &*arena.alloc([elem, Layout::NAKED_RECURSIVE_PTR]) as &[_],
+            &[],
+        ]);
+        interner.insert_recursive(
+            arena,
+            Layout {
+                repr: LayoutRepr::Union(UnionLayout::Recursive(tags)).direct(),
+                semantic,
+            },
+        )
     }
 
     #[test]
-    fn insert_then_reintern() {
+    fn recursive_layouts_closing_at_different_points() {
         let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-        let mut interner = global.fork();
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET);
 
-        let layout = make_layout(arena, &mut interner);
-        let interned_layout = interner.insert_recursive(arena, layout);
-        let full_layout = interner.get(interned_layout);
-        assert_ne!(layout, full_layout);
-        assert_eq!(interner.insert(full_layout), interned_layout);
+        let plain = cons_list(arena, &mut interner, Layout::I64, SemanticRepr::NONE);
+        let named = cons_list(
+            arena,
+            &mut interner,
+            Layout::I64,
+            SemanticRepr::tag_union(arena.alloc(["Cons", "Nil"])),
+        );
+        let other = cons_list(arena, &mut interner, Layout::I32, SemanticRepr::NONE);
+
+        assert_ne!(plain, named);
+        assert!(interner.equiv(plain, named));
+        assert!(interner.equiv(named, plain));
+        assert!(!interner.equiv(plain, other));
     }
 
     #[test]
-    fn write_global_then_single_threaded() {
+    fn lambda_sets_of_different_sizes() {
         let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-        let layout = {
-            let mut interner = global.fork();
-            make_layout(arena, &mut interner)
-        };
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET);
 
-        let in1: InLayout = {
-            let mut interner = global.fork();
-            interner.insert_recursive(arena, layout)
+        let mut lambda_set = |set: &'static [(Symbol, &'static [InLayout<'static>])]| {
+            interner
+                .insert_lambda_set(
+                    arena,
+                    arena.alloc(&[] as &[_]),
+                    Layout::UNIT,
+                    arena.alloc(set),
+                    NeedsRecursionPointerFixup(false),
+                    Layout::UNIT,
+                )
+                .full_layout
         };
+        let one = lambda_set(&[(Symbol::ATTR_ATTR, &[])]);
+        let two = lambda_set(&[(Symbol::ATTR_ATTR, &[]), (Symbol::ARG_1, &[])]);
 
-        let in2 = {
-            let mut st_interner = global.unwrap().unwrap();
-            st_interner.insert_recursive(arena, layout)
+        assert!(!interner.equiv(one, two));
+        assert!(!interner.equiv(two, one));
+    }
+
+    #[test]
+    fn function_pointers_compare_structurally() {
+        let arena = &Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET);
+
+        let record = interner.insert(Layout {
+            repr: LayoutRepr::Struct(arena.alloc([Layout::I64])).direct(),
+            semantic: SemanticRepr::record(arena.alloc(["a"])),
+        });
+        let tuple = interner.insert(Layout {
+            repr: LayoutRepr::Struct(arena.alloc([Layout::I64])).direct(),
+            semantic: SemanticRepr::tuple(1),
+        });
+        let mut function_pointer = |arg| {
+            interner.insert_direct_no_semantic(LayoutRepr::FunctionPointer(FunctionPointer {
+                args: arena.alloc([arg]),
+                ret: Layout::UNIT,
+            }))
         };
+        let takes_record = function_pointer(record);
+        let takes_tuple = function_pointer(tuple);
+        let takes_int = function_pointer(Layout::I64);
 
-        assert_eq!(in1, in2);
+        assert_ne!(takes_record, takes_tuple);
+        assert!(interner.equiv(takes_record, takes_tuple));
+        assert!(!interner.equiv(takes_record, takes_int));
     }
 
     #[test]
-    fn write_single_threaded_then_global() {
+    fn runtime_equiv_looks_through_lambda_sets() {
         let arena = &Bump::new();
-        let global = GlobalLayoutInterner::with_capacity(2, TARGET);
-        let mut st_interner = global.unwrap().unwrap();
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET);
 
-        let layout = make_layout(arena, &mut st_interner);
+        let lambda_set = interner.insert_lambda_set(
+            arena,
+            arena.alloc(&[] as &[_]),
+            Layout::UNIT,
+            arena.alloc(
+                &*arena.alloc([(Symbol::ATTR_ATTR, &*arena.alloc([Layout::I64]) as &[_])])
+                    as &[_],
+            ),
+            NeedsRecursionPointerFixup(false),
+            Layout::I64,
+        );
+        let with_closure = interner.insert_direct_no_semantic(LayoutRepr::struct_(
+            arena.alloc([lambda_set.full_layout, Layout::STR]),
+        ));
+        let with_capture = interner.insert_direct_no_semantic(LayoutRepr::struct_(
+            arena.alloc([Layout::I64, Layout::STR]),
+        ));
+
+        assert!(!interner.equiv(with_closure, with_capture));
+        assert!(interner.equiv_runtime(with_closure, with_capture));
+        assert!(interner.equiv_runtime(lambda_set.full_layout, Layout::I64));
+        assert!(!interner.equiv_runtime(with_closure, Layout::STR));
+    }
+}
 
-        let in1 = st_interner.insert_recursive(arena, layout);
+#[cfg(test)]
+mod random_layouts {
+    use bumpalo::Bump;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use roc_module::symbol::Symbol;
+    use roc_target::Target;
 
-        let global = st_interner.into_global();
-        let mut interner = global.fork();
+    use crate::layout::{Builtin, Layout, LayoutRepr, UnionLayout};
 
-        let in2 = interner.insert_recursive(arena, layout);
+    use super::{
+        GlobalLayoutInterner, InLayout, LayoutInterner, NeedsRecursionPointerFixup,
+        STLayoutInterner,
+    };
 
-        assert_eq!(in1, in2);
+    const TARGET: Target = Target::LinuxX64;
+    const THREADS: usize = 4;
+
+    const LEAVES: &[InLayout<'static>] = &[
+        Layout::UNIT,
+        Layout::BOOL,
+        Layout::U8,
+        Layout::I64,
+        Layout::U128,
+        Layout::F64,
+        Layout::DEC,
+        Layout::STR,
+    ];
+    const LAMBDAS: &[Symbol] = &[
+        Symbol::ATTR_ATTR,
+        Symbol::NUM_ADD,
+        Symbol::STR_CONCAT,
+        Symbol::LIST_MAP,
+    ];
+
+    /// An owned description of a layout tree, interned with [build].
+    #[derive(Debug, Clone)]
+    enum Shape {
+        Leaf(usize),
+        /// Points at the closest enclosing recursive union, or is a unit if there is none.
+        RecursivePointer,
+        List(Box<Shape>),
+        Ptr(Box<Shape>),
+        Struct(Vec<Shape>),
+        NonRecursive(Vec<Vec<Shape>>),
+        Recursive(Vec<Vec<Shape>>),
+        NullableUnwrapped(Vec<Shape>),
+        /// The captures of each lambda in the set.
+        LambdaSet(Vec<Vec<Shape>>),
+    }
+
+    fn shape() -> impl Strategy<Value = Shape> {
+        let leaf = prop_oneof![
+            4 => (0..LEAVES.len()).prop_map(Shape::Leaf),
+            1 => Just(Shape::RecursivePointer),
+        ];
+
+        leaf.prop_recursive(4, 48, 4, |inner| {
+            let fields = vec(inner.clone(), 0..4);
+            let tags = vec(fields.clone(), 1..4);
+            let lambdas = vec(fields.clone(), 1..=LAMBDAS.len());
+
+            prop_oneof![
+                inner.clone().prop_map(|elem| Shape::List(Box::new(elem))),
+                inner.prop_map(|elem| Shape::Ptr(Box::new(elem))),
+                fields.clone().prop_map(Shape::Struct),
+                tags.clone().prop_map(Shape::NonRecursive),
+                tags.prop_map(Shape::Recursive),
+                fields.prop_map(Shape::NullableUnwrapped),
+                lambdas.prop_map(Shape::LambdaSet),
+            ]
+        })
+    }
+
+    fn build<'a>(
+        arena: &'a Bump,
+        interner: &mut impl LayoutInterner<'a>,
+        shape: &Shape,
+        in_recursive: bool,
+    ) -> InLayout<'a> {
+        match shape {
+            Shape::Leaf(i) => LEAVES[*i],
+            Shape::RecursivePointer if in_recursive => Layout::NAKED_RECURSIVE_PTR,
+            Shape::RecursivePointer => Layout::UNIT,
+            Shape::List(elem) => {
+                let elem = build(arena, interner, elem, in_recursive);
+                interner.insert_direct_no_semantic(LayoutRepr::Builtin(Builtin::List(elem)))
+            }
+            Shape::Ptr(elem) => {
+                let elem = build(arena, interner, elem, in_recursive);
+                interner.insert_direct_no_semantic(LayoutRepr::Ptr(elem))
+            }
+            Shape::Struct(fields) => {
+                let fields = build_all(arena, interner, fields, in_recursive);
+                interner.insert_direct_no_semantic(LayoutRepr::Struct(fields))
+            }
+            Shape::NonRecursive(tags) => {
+                let tags = arena.alloc_slice_fill_iter(
+                    tags.iter()
+                        .map(|fields| build_all(arena, interner, fields, in_recursive)),
+                );
+                interner
+                    .insert_direct_no_semantic(LayoutRepr::Union(UnionLayout::NonRecursive(tags)))
+            }
+            Shape::Recursive(tags) => {
+                let tags = arena.alloc_slice_fill_iter(
+                    tags.iter()
+                        .map(|fields| build_all(arena, interner, fields, true)),
+                );
+                let union_layout = UnionLayout::Recursive(tags);
+                interner.insert_recursive(
+                    arena,
+                    Layout::no_semantic(LayoutRepr::Union(union_layout).direct()),
+                )
+            }
+            Shape::NullableUnwrapped(fields) => {
+                let union_layout = UnionLayout::NullableUnwrapped {
+                    nullable_id: true,
+                    other_fields: build_all(arena, interner, fields, true),
+                };
+                interner.insert_recursive(
+                    arena,
+                    Layout::no_semantic(LayoutRepr::Union(union_layout).direct()),
+                )
+            }
+            Shape::LambdaSet(lambdas) => {
+                // Captures never refer to an enclosing union, so no pointer fixup is needed.
+                let set = arena.alloc_slice_fill_iter(LAMBDAS.iter().zip(lambdas).map(
+                    |(lambda, captures)| (*lambda, build_all(arena, interner, captures, false)),
+                ));
+                let captures = arena.alloc_slice_fill_iter(set.iter().map(|(_, c)| *c));
+                let representation = interner.insert_direct_no_semantic(LayoutRepr::Union(
+                    UnionLayout::NonRecursive(captures),
+                ));
+                let args: &[InLayout] = arena.alloc([Layout::UNIT]);
+                let lambda_set = interner.insert_lambda_set(
+                    arena,
+                    arena.alloc(args),
+                    Layout::UNIT,
+                    arena.alloc(&*set),
+                    NeedsRecursionPointerFixup(false),
+                    representation,
+                );
+                lambda_set.full_layout
+            }
+        }
+    }
+
+    fn build_all<'a>(
+        arena: &'a Bump,
+        interner: &mut impl LayoutInterner<'a>,
+        shapes: &[Shape],
+        in_recursive: bool,
+    ) -> &'a [InLayout<'a>] {
+        let mut layouts = bumpalo::collections::Vec::with_capacity_in(shapes.len(), arena);
+        for shape in shapes {
+            layouts.push(build(arena, interner, shape, in_recursive));
+        }
+        layouts.into_bump_slice()
+    }
+
+    proptest! {
+        #[test]
+        fn insert_get_round_trips(shape in shape()) {
+            let arena = &Bump::new();
+            let mut interner = STLayoutInterner::with_capacity(4, TARGET);
+            let reserved = interner.vec.len();
+
+            let built = build(arena, &mut interner, &shape, false);
+            let len = interner.vec.len();
+
+            for index in reserved..len {
+                let layout = unsafe { InLayout::from_index(index) };
+                prop_assert_eq!(interner.insert(interner.get(layout)), layout);
+            }
+            prop_assert_eq!(build(arena, &mut interner, &shape, false), built);
+            prop_assert_eq!(interner.vec.len(), len);
+        }
+
+        #[test]
+        fn thread_local_and_global_agree(shape in shape()) {
+            let arena = &Bump::new();
+
+            let global = GlobalLayoutInterner::with_capacity(4, TARGET);
+            let (built, layout) = {
+                let mut interner = global.fork();
+                let built = build(arena, &mut interner, &shape, false);
+                (built, interner.get(built))
+            };
+
+            let mut interner = global.unwrap().unwrap();
+            let len = interner.vec.len();
+            prop_assert_eq!(interner.get(built), layout);
+            prop_assert_eq!(build(arena, &mut interner, &shape, false), built);
+            prop_assert_eq!(interner.vec.len(), len);
+
+            let global = interner.into_global();
+            let mut interner = global.fork();
+            prop_assert_eq!(build(arena, &mut interner, &shape, false), built);
+            prop_assert_eq!(interner.get(built), layout);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn insert_recursive_is_idempotent_across_threads(shape in shape()) {
+            let mut arenas: Vec<_> = std::iter::repeat_with(Bump::new).take(THREADS).collect();
+            let global = GlobalLayoutInterner::with_capacity(4, TARGET);
+
+            let built: Vec<_> = std::thread::scope(|s| {
+                let handles: Vec<_> = arenas
+                    .iter_mut()
+                    .map(|arena| {
+                        let mut interner = global.fork();
+                        let shape = &shape;
+                        s.spawn(move || build(arena, &mut interner, shape, false))
+                    })
+                    .collect();
+                handles.into_iter().map(|t| t.join().unwrap()).collect()
+            });
+            prop_assert!(built.iter().all(|in_layout| *in_layout == built[0]));
+
+            let arena = &Bump::new();
+            let mut interner = global.unwrap().unwrap();
+            prop_assert_eq!(build(arena, &mut interner, &shape, false), built[0]);
+        }
     }
 }
@@ -64,6 +64,39 @@ impl<'a> SemanticRepr<'a> {
     }
 }
 
+/// The contents of a [SemanticRepr], for taking one apart and putting it back together, as the
+/// layout interner does when it writes and reads snapshots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum SemanticParts<'a> {
+    None,
+    Record(&'a [&'a str]),
+    Tuple(usize),
+    TagUnion(&'a [&'a str]),
+    Lambdas(&'a [Symbol]),
+}
+
+impl<'a> SemanticRepr<'a> {
+    pub(super) fn parts(self) -> SemanticParts<'a> {
+        match self.0 {
+            Inner::None => SemanticParts::None,
+            Inner::Record(SemaRecord { fields }) => SemanticParts::Record(fields),
+            Inner::Tuple(SemaTuple { size }) => SemanticParts::Tuple(size),
+            Inner::TagUnion(SemaTagUnion { tags }) => SemanticParts::TagUnion(tags),
+            Inner::Lambdas(SemaLambdas { lambdas }) => SemanticParts::Lambdas(lambdas),
+        }
+    }
+
+    pub(super) fn from_parts(parts: SemanticParts<'a>) -> Self {
+        match parts {
+            SemanticParts::None => Self::NONE,
+            SemanticParts::Record(fields) => Self::record(fields),
+            SemanticParts::Tuple(size) => Self::tuple(size),
+            SemanticParts::TagUnion(tags) => Self::tag_union(tags),
+            SemanticParts::Lambdas(lambdas) => Self::lambdas(lambdas),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 struct SemaRecord<'a> {
     fields: &'a [&'a str],
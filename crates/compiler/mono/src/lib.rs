@@ -11,12 +11,16 @@
 
 pub mod borrow;
 pub mod code_gen_help;
+pub mod const_fold;
+pub mod coverage;
+pub mod dead_code;
 pub mod drop_specialization;
 pub mod inc_dec;
 pub mod ir;
 pub mod layout;
 pub mod low_level;
 pub mod reset_reuse;
+pub mod source_locations;
 pub mod tail_recursion;
 
 pub mod debug;
@@ -12,6 +12,7 @@ use crate::ir::{
     UpdateModeId, UpdateModeIds,
 };
 use crate::layout::{InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout};
+use crate::source_locations::SourceLocations;
 
 use bumpalo::Bump;
 
@@ -19,12 +20,13 @@ use bumpalo::collections::vec::Vec;
 use bumpalo::collections::CollectIn;
 use roc_collections::{MutMap, MutSet};
 use roc_module::low_level::LowLevel;
-use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_module::symbol::{IdentIds, Interns, ModuleId, Symbol};
 use roc_target::Target;
 
 /**
  Insert reset and reuse operations into the IR.
 To allow for the reuse of memory allocation when said memory is no longer used.
+Returns what was decided for every heap allocated tag.
  */
 pub fn insert_reset_reuse_operations<'a, 'i>(
     arena: &'a Bump,
@@ -34,13 +36,17 @@ pub fn insert_reset_reuse_operations<'a, 'i>(
     ident_ids: &'i mut IdentIds,
     update_mode_ids: &'i mut UpdateModeIds,
     procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
-) {
+) -> ReuseReport<'a> {
     let mut global_layouts = SymbolLayout::default();
     for (symbol, _layout) in procs.keys() {
         global_layouts.insert(*symbol, LayoutOption::GloballyDefined);
     }
 
-    for proc in procs.values_mut() {
+    let mut report = ReuseReport::default();
+
+    for (key, proc) in procs.iter_mut() {
+        let mut outcomes = MutMap::default();
+
         let new_proc = insert_reset_reuse_operations_proc(
             arena,
             layout_interner,
@@ -48,10 +54,147 @@ pub fn insert_reset_reuse_operations<'a, 'i>(
             home,
             ident_ids,
             update_mode_ids,
+            &mut outcomes,
             global_layouts.clone(),
             proc.clone(),
         );
         *proc = new_proc;
+
+        if !outcomes.is_empty() {
+            let mut outcomes: std::vec::Vec<_> = outcomes.into_iter().collect();
+            outcomes.sort_by_key(|(symbol, _)| *symbol);
+
+            report.procs.insert(*key, outcomes);
+        }
+    }
+
+    report
+}
+
+/**
+What happened to a heap allocated tag.
+Reused allocations still allocate when the dropped value turns out to be shared at runtime.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AllocationOutcome {
+    /// The tag is written into the memory of a dropped value.
+    /// That value is not known when the jumps to a join point pass different ones.
+    Reused { dropped: Option<Symbol> },
+    /// No value was dropped before the tag was allocated.
+    NothingDropped,
+    /// Values were dropped before the tag was allocated, but none of its size and alignment.
+    SizeMismatch {
+        needed: TokenLayout,
+        dropped: std::vec::Vec<TokenLayout>,
+    },
+}
+
+/**
+The reset and reuse decisions for every procedure that allocates a tag on the heap.
+The allocations of a procedure are identified by the symbol they are bound to.
+The report is made before constant folding and removing unreachable procedures,
+so it can list procedures that are not in the final program.
+*/
+#[derive(Debug, Default)]
+pub struct ReuseReport<'a> {
+    pub procs: MutMap<(Symbol, ProcLayout<'a>), std::vec::Vec<(Symbol, AllocationOutcome)>>,
+}
+
+impl<'a> ReuseReport<'a> {
+    /// Symbols of `home` are printed without their module name.
+    pub fn render(
+        &self,
+        interns: &Interns,
+        home: ModuleId,
+        source_locations: &SourceLocations,
+    ) -> String {
+        use std::fmt::Write;
+
+        let name = |symbol: Symbol| symbol.fully_qualified(interns, home);
+
+        // The compiler makes up most of the symbols that allocations are bound to, so they are
+        // pointed at by where their value is in the source.
+        let is_generated = |symbol: Symbol| {
+            symbol
+                .as_str(interns)
+                .starts_with(|c: char| c.is_ascii_digit())
+        };
+        let locate = |symbol: Symbol, proc_name: Symbol| {
+            source_locations
+                .locate(symbol, proc_name)
+                .unwrap_or_else(|| format!("`{}`", name(symbol)))
+        };
+
+        let mut reused = 0;
+        let mut total = 0;
+
+        // Specializations of a procedure share its name, so sort on the whole block.
+        let mut blocks: std::vec::Vec<String> = std::vec::Vec::with_capacity(self.procs.len());
+
+        for ((proc_name, _), outcomes) in self.procs.iter() {
+            let mut block = format!("{}\n", name(*proc_name));
+
+            for (symbol, outcome) in outcomes {
+                let symbol = if is_generated(*symbol) {
+                    locate(*symbol, *proc_name)
+                } else {
+                    format!("`{}` at {}", name(*symbol), locate(*symbol, *proc_name))
+                };
+                total += 1;
+
+                let _ = match outcome {
+                    AllocationOutcome::Reused { dropped } => {
+                        reused += 1;
+
+                        match dropped {
+                            Some(dropped) => writeln!(
+                                block,
+                                "    {symbol} reuses {}, if it is unique",
+                                if is_generated(*dropped) {
+                                    format!("the value at {}", locate(*dropped, *proc_name))
+                                } else {
+                                    format!("`{}`", name(*dropped))
+                                }
+                            ),
+                            None => writeln!(
+                                block,
+                                "    {symbol} reuses a value that depends on the jump to it, if it is unique"
+                            ),
+                        }
+                    }
+                    AllocationOutcome::NothingDropped => {
+                        writeln!(
+                            block,
+                            "    {symbol} allocates, no value was dropped before it"
+                        )
+                    }
+                    AllocationOutcome::SizeMismatch { needed, dropped } => {
+                        let dropped: std::vec::Vec<_> =
+                            dropped.iter().map(ToString::to_string).collect();
+                        writeln!(
+                            block,
+                            "    {symbol} allocates, it needs {needed} but only {} were dropped before it",
+                            dropped.join(", ")
+                        )
+                    }
+                };
+            }
+
+            blocks.push(block);
+        }
+
+        blocks.sort();
+
+        let mut buf = format!(
+            "{reused} of {total} heap allocated tags reuse the memory of a dropped value\n"
+        );
+
+        for block in blocks {
+            buf.push('\n');
+            buf.push_str(&block);
+        }
+
+        buf
     }
 }
 
@@ -62,6 +205,7 @@ fn insert_reset_reuse_operations_proc<'a, 'i>(
     home: ModuleId,
     ident_ids: &'i mut IdentIds,
     update_mode_ids: &'i mut UpdateModeIds,
+    outcomes: &mut MutMap<Symbol, AllocationOutcome>,
     mut symbol_layout: SymbolLayout<'a>,
     mut proc: Proc<'a>,
 ) -> Proc<'a> {
@@ -85,6 +229,7 @@ fn insert_reset_reuse_operations_proc<'a, 'i>(
         home,
         ident_ids,
         update_mode_ids,
+        outcomes,
         &mut env,
         arena.alloc(proc.body),
     );
@@ -102,6 +247,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
     home: ModuleId,
     ident_ids: &'i mut IdentIds,
     update_mode_ids: &'i mut UpdateModeIds,
+    outcomes: &mut MutMap<Symbol, AllocationOutcome>,
     environment: &mut ReuseEnvironment<'a>,
     stmt: &'a Stmt<'a>,
 ) -> &'a Stmt<'a> {
@@ -145,16 +291,23 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                         match can_reuse_union_layout_tag(*tag_layout, Option::Some(*tag_id)) {
                             // The tag is reusable.
                             Reuse::Reusable(union_layout) => {
+                                let token_layout =
+                                    get_reuse_layout_info(layout_interner, union_layout);
+
                                 // See if we have a token.
-                                match environment.pop_reuse_token(&get_reuse_layout_info(
-                                    layout_interner,
-                                    union_layout,
-                                )) {
+                                match environment.pop_reuse_token(&token_layout) {
                                     // We have a reuse token for this layout, use it.
                                     Some(TokenWithInLayout {
                                         token: mut reuse_token,
                                         inlayout: layout_info,
+                                        dropped,
                                     }) => {
+                                        // A join point body is evaluated more than once, the last evaluation is the one that is kept.
+                                        outcomes.insert(
+                                            *binding,
+                                            AllocationOutcome::Reused { dropped },
+                                        );
+
                                         if layout_info == layout {
                                             // The reuse token layout is the same, we can use it without casting.
                                             (
@@ -196,7 +349,23 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                                     }
 
                                     // We have no reuse token available, keep the old expression with a fresh allocation.
-                                    None => (None, expr.clone()),
+                                    None => {
+                                        let outcome = if environment.reuse_tokens.is_empty() {
+                                            AllocationOutcome::NothingDropped
+                                        } else {
+                                            let mut dropped: std::vec::Vec<_> =
+                                                environment.reuse_tokens.keys().copied().collect();
+                                            dropped.sort();
+
+                                            AllocationOutcome::SizeMismatch {
+                                                needed: token_layout,
+                                                dropped,
+                                            }
+                                        };
+                                        outcomes.insert(*binding, outcome);
+
+                                        (None, expr.clone())
+                                    }
                                 }
                             }
                             // We cannot reuse this tag id because it's a null pointer.
@@ -216,6 +385,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                 home,
                 ident_ids,
                 update_mode_ids,
+                outcomes,
                 environment,
                 current_stmt,
             );
@@ -277,6 +447,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                         home,
                         ident_ids,
                         update_mode_ids,
+                        outcomes,
                         &mut branch_env,
                         branch,
                     );
@@ -298,6 +469,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                     home,
                     ident_ids,
                     update_mode_ids,
+                    outcomes,
                     &mut branch_env,
                     branch,
                 );
@@ -491,6 +663,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                                         get_reuse_layout_info(layout_interner, union_layout),
                                         reuse_token,
                                         layout,
+                                        symbol,
                                     );
 
                                     Some((
@@ -519,6 +692,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                 home,
                 ident_ids,
                 update_mode_ids,
+                outcomes,
                 environment,
                 continuation,
             );
@@ -613,6 +787,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                 home,
                 ident_ids,
                 update_mode_ids,
+                outcomes,
                 environment,
                 remainder,
             );
@@ -638,6 +813,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                 home,
                 ident_ids,
                 update_mode_ids,
+                outcomes,
                 environment,
                 remainder,
             );
@@ -663,6 +839,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                 home,
                 ident_ids,
                 update_mode_ids,
+                outcomes,
                 environment,
                 remainder,
             );
@@ -700,6 +877,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                     home,
                     ident_ids,
                     update_mode_ids,
+                    outcomes,
                     &mut first_pass_environment,
                     remainder,
                 );
@@ -709,40 +887,64 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                 (first_pass_environment, first_pass_remainder)
             };
 
-            let max_reuse_tokens =
-                match first_pass_remainder_environment.get_jump_reuse_tokens(*joinpoint_id) {
-                    Some(all_reuse_maps) => {
-                        let all_token_layouts = all_reuse_maps
+            let max_reuse_tokens = match first_pass_remainder_environment
+                .get_jump_reuse_tokens(*joinpoint_id)
+            {
+                Some(all_reuse_maps) => {
+                    let all_token_layouts = all_reuse_maps
+                        .iter()
+                        .flat_map(|reuse_map| reuse_map.keys())
+                        // PERF: replace this collect with an unique iterator. To make sure every layout is only used once.
+                        .collect::<MutSet<_>>()
+                        .into_iter();
+                    let reuse_layouts_max_tokens = all_token_layouts.map(|token_layout| {
+                        // We get the tokens from the jump with the most tokens for this token layout.
+                        // So we have an inlayout for each token. And can cast when needed.
+                        let max_token_inlayouts = all_reuse_maps
                             .iter()
-                            .flat_map(|reuse_map| reuse_map.keys())
-                            // PERF: replace this collect with an unique iterator. To make sure every layout is only used once.
-                            .collect::<MutSet<_>>()
-                            .into_iter();
-                        let reuse_layouts_max_tokens = all_token_layouts.map(|token_layout| {
-                            // We get the tokens from the jump with the most tokens for this token layout.
-                            // So we have an inlayout for each token. And can cast when needed.
-                            let max_token_inlayouts = all_reuse_maps
+                            .filter_map(|reuse_map| reuse_map.get(token_layout))
+                            .max_by_key(|tokens| tokens.len())
+                            .expect("all layouts should be in at least one of the reuse maps");
+
+                        // Only keep track of the dropped value of a token if every jump that passes one, passes the same one.
+                        let agreed_tokens =
+                            max_token_inlayouts
                                 .iter()
-                                .filter_map(|reuse_map| reuse_map.get(token_layout))
-                                .max_by_key(|tokens| tokens.len())
-                                .expect("all layouts should be in at least one of the reuse maps");
-                            (token_layout, max_token_inlayouts)
-                        });
-                        Vec::from_iter_in(reuse_layouts_max_tokens, arena)
-                    }
-                    // Normally the remainder should always have jumps and this would not be None,
-                    // But for testing this might not be the case, so default to no available reuse tokens.
-                    None => Vec::new_in(arena),
-                };
+                                .enumerate()
+                                .map(|(index, token)| {
+                                    let agreed = all_reuse_maps
+                                        .iter()
+                                        .filter_map(|reuse_map| {
+                                            // Jumps pass the tokens from the top of their stack.
+                                            let tokens = reuse_map.get(token_layout)?;
+                                            let offset = max_token_inlayouts.len() - tokens.len();
+                                            let token = tokens.get(index.checked_sub(offset)?)?;
+                                            Some(token.dropped)
+                                        })
+                                        .all(|dropped| dropped == token.dropped);
+
+                                    TokenWithInLayout {
+                                        dropped: if agreed { token.dropped } else { None },
+                                        ..*token
+                                    }
+                                });
+
+                        (token_layout, Vec::from_iter_in(agreed_tokens, arena))
+                    });
+                    Vec::from_iter_in(reuse_layouts_max_tokens, arena)
+                }
+                // Normally the remainder should always have jumps and this would not be None,
+                // But for testing this might not be the case, so default to no available reuse tokens.
+                None => Vec::new_in(arena),
+            };
 
             let (first_pass_body_environment, first_pass_body, used_reuse_tokens) = {
                 // For each possibly available reuse token, create a reuse token to add to the join point environment.
                 let max_reuse_token_symbols = max_reuse_tokens
                     .iter()
-                    .copied()
                     .map(|(token_layout, tokens)| {
                         (
-                            *token_layout,
+                            **token_layout,
                             Vec::from_iter_in(
                                 tokens.iter().map(|token| TokenWithInLayout {
                                     token: ReuseToken {
@@ -752,6 +954,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                                         update_tag_id: true,
                                     },
                                     inlayout: token.inlayout,
+                                    dropped: token.dropped,
                                 }),
                                 arena,
                             ),
@@ -785,6 +988,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                     home,
                     ident_ids,
                     update_mode_ids,
+                    outcomes,
                     &mut first_pass_body_environment,
                     body,
                 );
@@ -869,6 +1073,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                     home,
                     ident_ids,
                     update_mode_ids,
+                    outcomes,
                     environment,
                     remainder,
                 );
@@ -950,6 +1155,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
                     home,
                     ident_ids,
                     update_mode_ids,
+                    outcomes,
                     &mut body_environment,
                     body,
                 );
@@ -1129,10 +1335,16 @@ type ReuseTokens<'a> = MutMap<TokenLayout, Vec<'a, TokenWithInLayout<'a>>>;
 /**
 Struct to to check whether two reuse layouts are interchangeable.
 */
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct TokenLayout {
-    size: u32,
-    alignment: u32,
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TokenLayout {
+    pub size: u32,
+    pub alignment: u32,
+}
+
+impl std::fmt::Display for TokenLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} bytes (align {})", self.size, self.alignment)
+    }
 }
 
 /**
@@ -1142,6 +1354,8 @@ Combines a reuse token with it's possible layout info.
 struct TokenWithInLayout<'a> {
     token: ReuseToken,
     inlayout: &'a InLayout<'a>,
+    // The value whose memory the token holds, if it is known.
+    dropped: Option<Symbol>,
 }
 
 type Tag = u16;
@@ -1243,10 +1457,12 @@ impl<'a> ReuseEnvironment<'a> {
         token_layout: TokenLayout,
         reuse_token: ReuseToken,
         layout: &'a InLayout<'a>,
+        dropped: Symbol,
     ) {
         let with_info = TokenWithInLayout {
             token: reuse_token,
             inlayout: layout,
+            dropped: Some(dropped),
         };
         self.reuse_tokens
             .entry(token_layout)
@@ -0,0 +1,70 @@
+//! Maps symbols in the IR back to where their values were written in the source, so that reports
+//! about the IR (heap profiles, reuse reports, test coverage) can point at the user's code.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use roc_collections::MutMap;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::{LineInfo, Region};
+
+#[derive(Debug, Clone, Default)]
+pub struct SourceLocations {
+    regions: MutMap<Symbol, Region>,
+    modules: MutMap<ModuleId, (PathBuf, LineInfo)>,
+}
+
+impl SourceLocations {
+    /// `regions` is what lowering to the IR recorded in [crate::ir::Env::symbol_regions], and
+    /// `sources` the path and source text of every module.
+    pub fn new(
+        regions: MutMap<Symbol, Region>,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    ) -> Self {
+        let modules = sources
+            .iter()
+            .map(|(module_id, (path, src))| (*module_id, (path.clone(), LineInfo::new(src))))
+            .collect();
+
+        Self { regions, modules }
+    }
+
+    /// `path:line:column` of the value bound to `symbol`, falling back to the start of the body
+    /// of `proc_name` for symbols the IR made up. Lines and columns start at 1.
+    pub fn locate(&self, symbol: Symbol, proc_name: Symbol) -> Option<String> {
+        self.locate_first([symbol, proc_name])
+            .map(|location| location.to_string())
+    }
+
+    /// Where the value of the first of `symbols` that has a known location was written.
+    pub fn locate_first(
+        &self,
+        symbols: impl IntoIterator<Item = Symbol>,
+    ) -> Option<SourceLocation> {
+        let (symbol, region) = symbols
+            .into_iter()
+            .find_map(|symbol| Some((symbol, *self.regions.get(&symbol)?)))?;
+        let (path, line_info) = self.modules.get(&symbol.module_id())?;
+        let position = line_info.convert_pos(region.start());
+
+        Some(SourceLocation {
+            path: path.clone(),
+            line: position.line + 1,
+            column: position.column + 1,
+        })
+    }
+}
+
+/// A position in a source file. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path.display(), self.line, self.column)
+    }
+}
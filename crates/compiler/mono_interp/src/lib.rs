@@ -0,0 +1,570 @@
+//! Runs the mono IR directly, without a backend or linker.
+//!
+//! The interpreter walks [`Stmt`]s and [`Expr`]s of the specialized procedures, with every
+//! value boxed up in a [`Value`]. It is much slower than compiled code, but it starts
+//! immediately, which is what the REPL and `roc check --run-expects` want for small programs.
+//!
+//! Reference counts are ignored: values are shared through Rust's `Rc`, and never mutated in
+//! place, so the refcounting statements in the IR have nothing to do.
+mod low_level;
+mod num;
+mod property;
+mod value;
+
+pub use property::{check_property, Counterexample, DEFAULT_SEED, MAX_SHRINKS, PROPERTY_CASES};
+pub use value::{Erased, Pointer, Value};
+
+use std::fmt;
+
+use roc_builtins::bitcode::FloatWidth;
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_mono::ir::{
+    CallType, CrashTag, ErasedField, Expr, JoinPointId, ListLiteralElement, Literal, Param, Proc,
+    ProcLayout, SelfRecursive, Stmt,
+};
+use roc_mono::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr, TagIdIntType, UnionLayout};
+use roc_region::all::Region;
+use roc_render_value::format_dbg;
+use roc_types::subs::Variable;
+
+/// Calls nested deeper than this are reported as a stack overflow. The interpreter keeps its
+/// call stack on the heap, so the limit only exists to stop runaway recursion.
+pub const MAX_CALL_DEPTH: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpError {
+    /// The program crashed, either through `crash` or in a builtin.
+    Crash {
+        message: String,
+        tag: CrashTag,
+    },
+    /// The program uses something the interpreter cannot do, such as calling into the host.
+    Unsupported(String),
+    StackOverflow,
+}
+
+impl fmt::Display for InterpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpError::Crash { message, .. } => write!(f, "{message}"),
+            InterpError::Unsupported(what) => {
+                write!(f, "The interpreter does not support {what}")
+            }
+            InterpError::StackOverflow => write!(f, "Stack overflow: calls nested too deeply"),
+        }
+    }
+}
+
+impl InterpError {
+    pub(crate) fn crash(message: impl Into<String>) -> Self {
+        InterpError::Crash {
+            message: message.into(),
+            tag: CrashTag::Roc,
+        }
+    }
+}
+
+/// An `expect` whose condition was false, with the values of the symbols it looks up.
+#[derive(Debug, Clone)]
+pub struct FailedExpect<'a> {
+    pub module_id: ModuleId,
+    pub region: Region,
+    pub lookups: Vec<(Symbol, Variable, Value<'a>)>,
+}
+
+pub struct Interpreter<'a, 'r, I: LayoutInterner<'a>> {
+    procedures: &'r MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    layout_interner: &'r I,
+    depth: usize,
+    failed_expects: Vec<FailedExpect<'a>>,
+    /// The output of `dbg` statements, when it is kept rather than printed to stderr.
+    dbg_output: Option<Vec<String>>,
+}
+
+struct Frame<'a, 'r> {
+    values: MutMap<Symbol, Value<'a>>,
+    join_points: MutMap<JoinPointId, (JoinParameters<'a, 'r>, &'r Stmt<'a>)>,
+    /// The next statement to run.
+    stmt: &'r Stmt<'a>,
+    /// The symbol waiting for the result of the procedure this frame is calling.
+    pending: Option<Symbol>,
+}
+
+impl<'a, 'r> Frame<'a, 'r> {
+    fn get(&self, symbol: Symbol) -> Value<'a> {
+        match self.values.get(&symbol) {
+            Some(value) => value.clone(),
+            None => internal_error!("symbol {symbol:?} is not defined"),
+        }
+    }
+
+    fn get_all(&self, symbols: &[Symbol]) -> Vec<Value<'a>> {
+        symbols.iter().map(|symbol| self.get(*symbol)).collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum JoinParameters<'a, 'r> {
+    Join(&'r [Param<'a>]),
+    /// A self-recursive procedure jumps back to its own start.
+    Proc(&'r [(InLayout<'a>, Symbol)]),
+}
+
+enum Evaluated<'a, 'r> {
+    Value(Value<'a>),
+    Call(Frame<'a, 'r>),
+}
+
+impl<'a, 'r, I: LayoutInterner<'a>> Interpreter<'a, 'r, I> {
+    pub fn new(
+        procedures: &'r MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+        layout_interner: &'r I,
+    ) -> Self {
+        Self {
+            procedures,
+            layout_interner,
+            depth: 0,
+            failed_expects: Vec::new(),
+            dbg_output: None,
+        }
+    }
+
+    /// Keeps the output of `dbg` statements for [`Interpreter::take_dbg_output`], instead of
+    /// printing it to stderr. This lets several interpreters run side by side without their
+    /// output getting mixed up.
+    pub fn capture_dbg_output(&mut self) {
+        self.dbg_output.get_or_insert_with(Vec::new);
+    }
+
+    /// The lines `dbg` statements printed since the last call, oldest first.
+    pub fn take_dbg_output(&mut self) -> Vec<String> {
+        self.dbg_output
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// The `expect`s that failed so far, oldest first.
+    pub fn failed_expects(&self) -> &[FailedExpect<'a>] {
+        &self.failed_expects
+    }
+
+    pub fn take_failed_expects(&mut self) -> Vec<FailedExpect<'a>> {
+        std::mem::take(&mut self.failed_expects)
+    }
+
+    /// Calls the procedure `name` with the given arguments, and returns its result.
+    pub fn call(
+        &mut self,
+        name: Symbol,
+        layout: ProcLayout<'a>,
+        arguments: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, InterpError> {
+        let frame = self.enter(name, layout, arguments);
+        let depth = self.depth;
+
+        let result = self.run(frame);
+
+        self.depth = depth;
+        result
+    }
+
+    fn enter(
+        &self,
+        name: Symbol,
+        layout: ProcLayout<'a>,
+        arguments: Vec<Value<'a>>,
+    ) -> Frame<'a, 'r> {
+        let proc = match self.procedures.get(&(name, layout)) {
+            Some(proc) => proc,
+            None => internal_error!("procedure {name:?} with layout {layout:?} does not exist"),
+        };
+
+        debug_assert_eq!(proc.args.len(), arguments.len());
+
+        let values = proc
+            .args
+            .iter()
+            .map(|(_, symbol)| *symbol)
+            .zip(arguments)
+            .collect();
+
+        let mut join_points = MutMap::default();
+
+        // Tail calls jump back to the start of the body, rebinding the arguments.
+        if let SelfRecursive::SelfRecursive(id) = proc.is_self_recursive {
+            join_points.insert(id, (JoinParameters::Proc(proc.args), &proc.body));
+        }
+
+        Frame {
+            values,
+            join_points,
+            stmt: &proc.body,
+            pending: None,
+        }
+    }
+
+    fn run(&mut self, frame: Frame<'a, 'r>) -> Result<Value<'a>, InterpError> {
+        let mut stack = vec![frame];
+        self.push_depth()?;
+
+        loop {
+            let frame = stack.last_mut().unwrap();
+            let stmt: &'r Stmt<'a> = frame.stmt;
+
+            match stmt {
+                Stmt::Let(symbol, expr, layout, continuation) => {
+                    match self.expr(frame, expr, *layout)? {
+                        Evaluated::Value(value) => {
+                            frame.values.insert(*symbol, value);
+                            frame.stmt = continuation;
+                        }
+                        Evaluated::Call(callee) => {
+                            frame.pending = Some(*symbol);
+                            frame.stmt = continuation;
+
+                            self.push_depth()?;
+                            stack.push(callee);
+                        }
+                    }
+                }
+                Stmt::Ret(symbol) => {
+                    let value = frame.get(*symbol);
+
+                    stack.pop();
+                    self.depth -= 1;
+
+                    match stack.last_mut() {
+                        None => return Ok(value),
+                        Some(caller) => {
+                            let symbol = caller.pending.take().unwrap();
+                            caller.values.insert(symbol, value);
+                        }
+                    }
+                }
+                Stmt::Switch {
+                    cond_symbol,
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    let condition = frame.get(*cond_symbol).as_switch_condition();
+
+                    frame.stmt = branches
+                        .iter()
+                        .find(|(value, _, _)| *value == condition)
+                        .map(|(_, _, branch)| branch)
+                        .unwrap_or(default_branch.1);
+                }
+                Stmt::Refcounting(_, continuation) => {
+                    frame.stmt = continuation;
+                }
+                Stmt::Expect {
+                    condition,
+                    region,
+                    lookups,
+                    variables,
+                    remainder,
+                }
+                | Stmt::ExpectFx {
+                    condition,
+                    region,
+                    lookups,
+                    variables,
+                    remainder,
+                } => {
+                    if !frame.get(*condition).as_bool() {
+                        let lookups = lookups
+                            .iter()
+                            .zip(variables.iter())
+                            .map(|(symbol, variable)| (*symbol, *variable, frame.get(*symbol)))
+                            .collect();
+
+                        self.failed_expects.push(FailedExpect {
+                            module_id: condition.module_id(),
+                            region: *region,
+                            lookups,
+                        });
+                    }
+
+                    frame.stmt = remainder;
+                }
+                Stmt::Dbg {
+                    source_location,
+                    source,
+                    symbol,
+                    remainder,
+                    ..
+                } => {
+                    let message = frame.get(*symbol);
+                    let line = format_dbg(source_location, source, message.as_str());
+
+                    match &mut self.dbg_output {
+                        Some(output) => output.push(line),
+                        None => eprintln!("{line}"),
+                    }
+
+                    frame.stmt = remainder;
+                }
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder,
+                } => {
+                    frame
+                        .join_points
+                        .insert(*id, (JoinParameters::Join(parameters), body));
+                    frame.stmt = remainder;
+                }
+                Stmt::Jump(id, arguments) => {
+                    let (parameters, body) = match frame.join_points.get(id) {
+                        Some(join_point) => *join_point,
+                        None => internal_error!("join point {id:?} is not defined"),
+                    };
+
+                    let arguments = frame.get_all(arguments);
+
+                    match parameters {
+                        JoinParameters::Join(parameters) => {
+                            for (parameter, argument) in parameters.iter().zip(arguments) {
+                                frame.values.insert(parameter.symbol, argument);
+                            }
+                        }
+                        JoinParameters::Proc(parameters) => {
+                            for ((_, symbol), argument) in parameters.iter().zip(arguments) {
+                                frame.values.insert(*symbol, argument);
+                            }
+                        }
+                    }
+
+                    frame.stmt = body;
+                }
+                Stmt::Crash(symbol, tag) => {
+                    return Err(InterpError::Crash {
+                        message: frame.get(*symbol).as_str().to_string(),
+                        tag: *tag,
+                    });
+                }
+            }
+        }
+    }
+
+    fn push_depth(&mut self) -> Result<(), InterpError> {
+        self.depth += 1;
+
+        if self.depth > MAX_CALL_DEPTH {
+            Err(InterpError::StackOverflow)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn expr(
+        &mut self,
+        frame: &Frame<'a, 'r>,
+        expr: &'r Expr<'a>,
+        layout: InLayout<'a>,
+    ) -> Result<Evaluated<'a, 'r>, InterpError> {
+        let value = match expr {
+            Expr::Literal(literal) => self.literal(literal, layout),
+            Expr::Call(call) => {
+                // The arguments of a higher-order call include the name of the passed function,
+                // which is not a value in scope.
+                let arguments = match call.call_type {
+                    CallType::HigherOrder(_) => Vec::new(),
+                    _ => frame.get_all(call.arguments),
+                };
+
+                match call.call_type.clone().replace_lowlevel_wrapper() {
+                    CallType::ByName {
+                        name,
+                        ret_layout,
+                        arg_layouts,
+                        ..
+                    } => {
+                        let proc_layout = ProcLayout {
+                            arguments: arg_layouts,
+                            result: ret_layout,
+                            niche: name.niche(),
+                        };
+
+                        return Ok(Evaluated::Call(self.enter(
+                            name.name(),
+                            proc_layout,
+                            arguments,
+                        )));
+                    }
+                    CallType::ByPointer {
+                        pointer,
+                        ret_layout,
+                        arg_layouts,
+                    } => {
+                        let name = match frame.get(pointer) {
+                            Value::FunctionPointer(name) => name,
+                            other => internal_error!("cannot call {other:?}"),
+                        };
+
+                        let proc_layout = ProcLayout {
+                            arguments: arg_layouts,
+                            result: ret_layout,
+                            niche: name.niche(),
+                        };
+
+                        return Ok(Evaluated::Call(self.enter(
+                            name.name(),
+                            proc_layout,
+                            arguments,
+                        )));
+                    }
+                    CallType::Foreign { foreign_symbol, .. } => {
+                        return Err(InterpError::Unsupported(format!(
+                            "calling the host function `{}`",
+                            foreign_symbol.as_str()
+                        )));
+                    }
+                    CallType::LowLevel { op, .. } => self.low_level(op, &arguments, layout)?,
+                    CallType::HigherOrder(higher_order) => {
+                        self.higher_order(frame, higher_order)?
+                    }
+                }
+            }
+            Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+                ..
+            } => {
+                if tag_layout.tag_is_null(*tag_id) {
+                    Value::Null
+                } else {
+                    let tag = Value::Tag(*tag_id, frame.get_all(arguments).into());
+
+                    match tag_layout {
+                        UnionLayout::NonRecursive(_) => tag,
+                        _ => Value::heap(tag),
+                    }
+                }
+            }
+            Expr::Struct(fields) => Value::structure(frame.get_all(fields)),
+            Expr::NullPointer => Value::Null,
+            Expr::StructAtIndex {
+                index, structure, ..
+            } => frame.get(*structure).as_fields()[*index as usize].clone(),
+            Expr::GetTagId {
+                structure,
+                union_layout,
+            } => {
+                let tag_id = match (frame.get(*structure), union_layout) {
+                    (Value::Null, _) => null_tag_id(union_layout),
+                    // Like the backends, only look at the pointer: this union may share its
+                    // allocation with a box, which was tagged when it was created as the box.
+                    (_, UnionLayout::NullableUnwrapped { nullable_id, .. }) => {
+                        !nullable_id as TagIdIntType
+                    }
+                    (value, _) => value.with_tag(|tag_id, _| tag_id),
+                };
+
+                self.int(layout, tag_id as i128)
+            }
+            Expr::UnionAtIndex {
+                structure, index, ..
+            } => frame
+                .get(*structure)
+                .with_tag(|_, fields| fields[*index as usize].clone()),
+            Expr::GetElementPointer {
+                structure, indices, ..
+            } => {
+                // The first index is the tag id, the rest are the path to the field.
+                let path: Vec<usize> = indices[1..].iter().map(|i| *i as usize).collect();
+
+                Value::Ptr(Pointer::field(&frame.get(*structure), &path))
+            }
+            Expr::Array { elem_layout, elems } => {
+                let elems = elems
+                    .iter()
+                    .map(|elem| match elem {
+                        ListLiteralElement::Literal(literal) => self.literal(literal, *elem_layout),
+                        ListLiteralElement::Symbol(symbol) => frame.get(*symbol),
+                    })
+                    .collect();
+
+                Value::list(elems)
+            }
+            Expr::EmptyArray => Value::list(Vec::new()),
+            Expr::ErasedMake { value, callee } => Value::Erased(std::rc::Rc::new(Erased {
+                value: value.map(|value| frame.get(value)),
+                callee: frame.get(*callee),
+            })),
+            Expr::ErasedLoad { symbol, field } => match frame.get(*symbol) {
+                Value::Erased(erased) => match field {
+                    ErasedField::Value | ErasedField::ValuePtr => {
+                        erased.value.clone().unwrap_or(Value::Null)
+                    }
+                    ErasedField::Callee => erased.callee.clone(),
+                },
+                other => internal_error!("expected an erased value, found {other:?}"),
+            },
+            Expr::FunctionPointer { lambda_name } => Value::FunctionPointer(*lambda_name),
+            Expr::Alloca { initializer, .. } => {
+                let initial = initializer.map_or(Value::Null, |symbol| frame.get(symbol));
+
+                Value::Ptr(Pointer::alloc(initial))
+            }
+            // Memory is never reused, so the reuse token is always the null pointer. A tag that
+            // would have reused it allocates instead.
+            Expr::Reset { .. } | Expr::ResetRef { .. } => Value::Null,
+            Expr::RuntimeErrorFunction(message) => {
+                return Err(InterpError::crash(*message));
+            }
+        };
+
+        Ok(Evaluated::Value(value))
+    }
+
+    fn literal(&self, literal: &Literal<'a>, layout: InLayout<'a>) -> Value<'a> {
+        match literal {
+            Literal::Int(bytes) => {
+                let n = i128::from_ne_bytes(*bytes);
+
+                match self.layout_interner.get_repr(layout) {
+                    LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => Value::F32(n as f32),
+                    LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => Value::F64(n as f64),
+                    LayoutRepr::Builtin(Builtin::Decimal) => Value::Dec(n * num::dec::ONE),
+                    _ => self.int(layout, n),
+                }
+            }
+            Literal::U128(bytes) => self.int(layout, i128::from_ne_bytes(*bytes)),
+            Literal::Float(f) => match self.layout_interner.get_repr(layout) {
+                LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => Value::F32(*f as f32),
+                LayoutRepr::Builtin(Builtin::Decimal) => {
+                    Value::Dec(num::dec::from_f64(*f).unwrap_or_default())
+                }
+                _ => Value::F64(*f),
+            },
+            Literal::Decimal(bytes) => Value::Dec(i128::from_ne_bytes(*bytes)),
+            Literal::Str(s) => Value::str(s),
+            Literal::Bool(b) => Value::Bool(*b),
+            Literal::Byte(b) => self.int(layout, *b as i128),
+        }
+    }
+
+    /// An integer of the given layout.
+    fn int(&self, layout: InLayout<'a>, n: i128) -> Value<'a> {
+        match self.layout_interner.get_repr(layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => Value::Int(width, num::normalize(width, n)),
+            LayoutRepr::Builtin(Builtin::Bool) => Value::Bool(n != 0),
+            other => internal_error!("expected an integer layout, found {other:?}"),
+        }
+    }
+}
+
+fn null_tag_id(union_layout: &UnionLayout) -> TagIdIntType {
+    match union_layout {
+        UnionLayout::NullableWrapped { nullable_id, .. } => *nullable_id,
+        UnionLayout::NullableUnwrapped { nullable_id, .. } => *nullable_id as TagIdIntType,
+        other => internal_error!("{other:?} has no null tag"),
+    }
+}
@@ -0,0 +1,909 @@
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_error_macros::internal_error;
+use roc_module::low_level::LowLevel;
+use roc_mono::ir::{HigherOrderLowLevel, ProcLayout};
+use roc_mono::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr};
+use roc_mono::low_level::HigherOrder;
+use roc_std::RocDec;
+
+use crate::num::{self, dec, Arith};
+use crate::{Frame, InterpError, Interpreter, Value};
+
+/// The tag ids of `[LT, EQ, GT]`, as `Num.compare` and `List.sortWith` use them.
+const EQ: i128 = 0;
+const GT: i128 = 1;
+const LT: i128 = 2;
+
+macro_rules! arguments {
+    ($args:expr, $($name:ident),+) => {
+        let [$($name),+] = $args else {
+            internal_error!("wrong number of arguments: {:?}", $args)
+        };
+    };
+}
+
+impl<'a, 'r, I: LayoutInterner<'a>> Interpreter<'a, 'r, I> {
+    pub(crate) fn low_level(
+        &mut self,
+        op: LowLevel,
+        args: &[Value<'a>],
+        ret_layout: InLayout<'a>,
+    ) -> Result<Value<'a>, InterpError> {
+        use LowLevel::*;
+
+        let value = match op {
+            StrConcat => {
+                arguments!(args, a, b);
+                Value::str(&format!("{}{}", a.as_str(), b.as_str()))
+            }
+            StrJoinWith => {
+                arguments!(args, list, separator);
+                let strings: Vec<&str> = list.as_list().iter().map(|s| &**s.as_str()).collect();
+                Value::str(&strings.join(separator.as_str()))
+            }
+            StrIsEmpty => {
+                arguments!(args, s);
+                Value::Bool(s.as_str().is_empty())
+            }
+            StrStartsWith => {
+                arguments!(args, s, prefix);
+                Value::Bool(s.as_str().starts_with(&**prefix.as_str()))
+            }
+            StrEndsWith => {
+                arguments!(args, s, suffix);
+                Value::Bool(s.as_str().ends_with(&**suffix.as_str()))
+            }
+            StrSplit => {
+                arguments!(args, s, delimiter);
+                let (s, delimiter) = (s.as_str(), delimiter.as_str());
+
+                if delimiter.is_empty() {
+                    Value::list(vec![Value::Str(s.clone())])
+                } else {
+                    Value::list(s.split(&**delimiter).map(Value::str).collect())
+                }
+            }
+            StrCountUtf8Bytes => {
+                arguments!(args, s);
+                self.int(ret_layout, s.as_str().len() as i128)
+            }
+            StrFromInt | StrFromFloat | NumToStr => {
+                arguments!(args, n);
+                Value::str(&num_to_string(n))
+            }
+            StrFromUtf8 => {
+                arguments!(args, bytes);
+                str_from_utf8(bytes)
+            }
+            StrToUtf8 => {
+                arguments!(args, s);
+                bytes_to_list(s.as_str().as_bytes())
+            }
+            StrRepeat => {
+                arguments!(args, s, n);
+                Value::str(&s.as_str().repeat(n.as_int() as usize))
+            }
+            StrTrim => {
+                arguments!(args, s);
+                Value::str(s.as_str().trim())
+            }
+            StrTrimStart => {
+                arguments!(args, s);
+                Value::str(s.as_str().trim_start())
+            }
+            StrTrimEnd => {
+                arguments!(args, s);
+                Value::str(s.as_str().trim_end())
+            }
+            StrToNum => {
+                arguments!(args, s);
+                self.str_to_num(s.as_str(), ret_layout)
+            }
+            StrGetUnsafe => {
+                arguments!(args, s, index);
+                let byte = s.as_str().as_bytes()[index.as_int() as usize];
+                Value::Int(IntWidth::U8, byte as i128)
+            }
+            StrSubstringUnsafe => {
+                arguments!(args, s, start, length);
+                let bytes = s.as_str().as_bytes();
+                let start = (start.as_int() as usize).min(bytes.len());
+                let end = start
+                    .saturating_add(length.as_int() as usize)
+                    .min(bytes.len());
+                Value::str(&String::from_utf8_lossy(&bytes[start..end]))
+            }
+            StrReserve | StrReleaseExcessCapacity => args[0].clone(),
+            StrWithCapacity => Value::str(""),
+
+            ListLenUsize | ListLenU64 | ListGetCapacity => {
+                arguments!(args, list);
+                self.int(ret_layout, list.as_list().len() as i128)
+            }
+            ListWithCapacity => Value::list(Vec::new()),
+            ListReserve | ListReleaseExcessCapacity | ListClone => args[0].clone(),
+            ListAppendUnsafe => {
+                arguments!(args, list, elem);
+                let mut elems = list.as_list().to_vec();
+                elems.push(elem.clone());
+                Value::list(elems)
+            }
+            ListPrepend => {
+                arguments!(args, list, elem);
+                let mut elems = Vec::with_capacity(list.as_list().len() + 1);
+                elems.push(elem.clone());
+                elems.extend(list.as_list().iter().cloned());
+                Value::list(elems)
+            }
+            ListGetUnsafe => {
+                arguments!(args, list, index);
+                list.as_list()[index.as_int() as usize].clone()
+            }
+            ListReplaceUnsafe => {
+                arguments!(args, list, index, elem);
+                let mut elems = list.as_list().to_vec();
+                let old = std::mem::replace(&mut elems[index.as_int() as usize], elem.clone());
+                let list = Value::list(elems);
+
+                // The record is sorted by alignment first, so `value` comes before `list`
+                // when its alignment is bigger than a pointer's.
+                match self.layout_interner.get_repr(ret_layout) {
+                    LayoutRepr::Struct([first, _])
+                        if matches!(
+                            self.layout_interner.get_repr(*first),
+                            LayoutRepr::Builtin(Builtin::List(_))
+                        ) =>
+                    {
+                        Value::structure(vec![list, old])
+                    }
+                    _ => Value::structure(vec![old, list]),
+                }
+            }
+            ListConcat => {
+                arguments!(args, a, b);
+                let mut elems = a.as_list().to_vec();
+                elems.extend(b.as_list().iter().cloned());
+                Value::list(elems)
+            }
+            ListSublist => {
+                arguments!(args, list, start, length);
+                let elems = list.as_list();
+                let start = (start.as_int() as u64 as usize).min(elems.len());
+                let end = start
+                    .saturating_add(length.as_int() as u64 as usize)
+                    .min(elems.len());
+                Value::list(elems[start..end].to_vec())
+            }
+            ListDropAt => {
+                arguments!(args, list, index);
+                let mut elems = list.as_list().to_vec();
+                let index = index.as_int() as u64 as usize;
+
+                if index < elems.len() {
+                    elems.remove(index);
+                }
+
+                Value::list(elems)
+            }
+            ListSwap => {
+                arguments!(args, list, i, j);
+                let mut elems = list.as_list().to_vec();
+                let (i, j) = (i.as_int() as u64 as usize, j.as_int() as u64 as usize);
+
+                if i < elems.len() && j < elems.len() {
+                    elems.swap(i, j);
+                }
+
+                Value::list(elems)
+            }
+            ListIsUnique => Value::Bool(true),
+            ListConcatUtf8 => {
+                arguments!(args, list, s);
+                let mut elems = list.as_list().to_vec();
+                elems.extend(
+                    s.as_str()
+                        .bytes()
+                        .map(|byte| Value::Int(IntWidth::U8, byte as i128)),
+                );
+                Value::list(elems)
+            }
+            ListIncref | ListDecref => Value::unit(),
+            ListSortWith => internal_error!("ListSortWith is a higher-order low-level"),
+
+            NumAdd => self.arith(args, Arith::Add, Overflow::Crash)?,
+            NumAddWrap => self.arith(args, Arith::Add, Overflow::Wrap)?,
+            NumAddChecked => self.arith(args, Arith::Add, Overflow::Report)?,
+            NumAddSaturated => self.arith(args, Arith::Add, Overflow::Saturate)?,
+            NumSub => self.arith(args, Arith::Sub, Overflow::Crash)?,
+            NumSubWrap => self.arith(args, Arith::Sub, Overflow::Wrap)?,
+            NumSubChecked => self.arith(args, Arith::Sub, Overflow::Report)?,
+            NumSubSaturated => self.arith(args, Arith::Sub, Overflow::Saturate)?,
+            NumMul => self.arith(args, Arith::Mul, Overflow::Crash)?,
+            NumMulWrap => self.arith(args, Arith::Mul, Overflow::Wrap)?,
+            NumMulChecked => self.arith(args, Arith::Mul, Overflow::Report)?,
+            NumMulSaturated => self.arith(args, Arith::Mul, Overflow::Saturate)?,
+
+            NumGt | NumGte | NumLt | NumLte => {
+                arguments!(args, a, b);
+                let ordering = compare_nums(a, b);
+                let answer = match op {
+                    NumGt => ordering == Some(Ordering::Greater),
+                    NumGte => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    NumLt => ordering == Some(Ordering::Less),
+                    _ => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                };
+                Value::Bool(answer)
+            }
+            NumCompare => {
+                arguments!(args, a, b);
+                let tag_id = match compare_nums(a, b) {
+                    Some(Ordering::Less) => LT,
+                    Some(Ordering::Greater) => GT,
+                    _ => EQ,
+                };
+                self.int(ret_layout, tag_id)
+            }
+
+            NumDivFrac => {
+                arguments!(args, a, b);
+                match (a, b) {
+                    (Value::F32(a), Value::F32(b)) => Value::F32(a / b),
+                    (Value::F64(a), Value::F64(b)) => Value::F64(a / b),
+                    (Value::Dec(a), Value::Dec(0)) if *a != 0 => {
+                        return Err(InterpError::crash("Decimal division by 0!"));
+                    }
+                    (Value::Dec(a), Value::Dec(b)) => match dec::checked_div(*a, *b) {
+                        Some(n) => Value::Dec(n),
+                        None => return Err(InterpError::crash("Decimal division overflow!")),
+                    },
+                    _ => internal_error!("cannot divide {a:?} by {b:?}"),
+                }
+            }
+            NumDivTruncUnchecked | NumDivCeilUnchecked | NumRemUnchecked => {
+                arguments!(args, a, b);
+                let (width, a, b) = int_operands(a, b);
+                let answer = match op {
+                    NumDivTruncUnchecked => num::div_trunc(width, a, b),
+                    NumDivCeilUnchecked => num::div_ceil(width, a, b),
+                    _ => num::rem(width, a, b),
+                };
+
+                match answer {
+                    Some(n) => Value::Int(width, n),
+                    None => return Err(InterpError::crash("Integer division by 0!")),
+                }
+            }
+            NumIsMultipleOf => {
+                arguments!(args, a, b);
+                let (width, a, b) = int_operands(a, b);
+                Value::Bool(match num::rem(width, a, b) {
+                    Some(remainder) => remainder == 0,
+                    None => a == 0,
+                })
+            }
+            NumAbs | NumNeg => {
+                arguments!(args, n);
+                let negate = op == NumNeg;
+
+                match n {
+                    Value::Int(width, n) => {
+                        let answer = if negate {
+                            num::checked_neg(*width, *n)
+                        } else {
+                            num::checked_abs(*width, *n)
+                        };
+
+                        match answer {
+                            Some(answer) => Value::Int(*width, answer),
+                            None if negate => {
+                                return Err(InterpError::crash(
+                                    "Integer negation overflowed because its argument is the minimum value",
+                                ))
+                            }
+                            None => {
+                                return Err(InterpError::crash(
+                                    "Integer absolute overflowed because its argument is the minimum value",
+                                ))
+                            }
+                        }
+                    }
+                    Value::F32(f) => Value::F32(if negate { -f } else { f.abs() }),
+                    Value::F64(f) => Value::F64(if negate { -f } else { f.abs() }),
+                    Value::Dec(n) => match (negate, n.checked_neg()) {
+                        (_, Some(neg)) if negate || *n < 0 => Value::Dec(neg),
+                        (false, _) if *n >= 0 => Value::Dec(*n),
+                        (true, _) => return Err(InterpError::crash("Decimal negation overflow!")),
+                        (false, _) => {
+                            return Err(InterpError::crash("Decimal absolute value overflow!"))
+                        }
+                    },
+                    other => internal_error!("cannot negate {other:?}"),
+                }
+            }
+            NumSin => float_op(args, f64::sin)?,
+            NumCos => float_op(args, f64::cos)?,
+            NumTan => float_op(args, f64::tan)?,
+            NumAtan => float_op(args, f64::atan)?,
+            NumAcos => float_op(args, f64::acos)?,
+            NumAsin => float_op(args, f64::asin)?,
+            NumSqrtUnchecked => float_op(args, f64::sqrt)?,
+            NumLogUnchecked => float_op(args, f64::ln)?,
+            NumPow => {
+                arguments!(args, a, b);
+                match (a, b) {
+                    (Value::F32(a), Value::F32(b)) => Value::F32(a.powf(*b)),
+                    (Value::Dec(a), Value::Dec(b)) => match dec::checked_pow(*a, *b) {
+                        Some(n) => Value::Dec(n),
+                        None => return Err(InterpError::crash("Decimal power overflowed!")),
+                    },
+                    _ => {
+                        let answer = to_f64(a).powf(to_f64(b));
+                        from_f64_like(a, answer)?
+                    }
+                }
+            }
+            NumPowInt => {
+                arguments!(args, base, exponent);
+                let (width, base, exponent) = int_operands(base, exponent);
+
+                match num::checked_pow(width, base, exponent) {
+                    Some(n) => Value::Int(width, n),
+                    None => return Err(InterpError::crash("Integer raised to power overflowed!")),
+                }
+            }
+            NumRound | NumFloor | NumCeiling => {
+                arguments!(args, n);
+                let width = self.int_width(ret_layout);
+
+                match n {
+                    Value::Dec(n) => {
+                        let whole = match op {
+                            NumRound => dec::round(*n),
+                            NumFloor => dec::floor(*n),
+                            _ => dec::ceiling(*n),
+                        };
+                        Value::Int(width, num::normalize(width, whole))
+                    }
+                    _ => {
+                        let f = to_f64(n);
+                        let whole = match op {
+                            NumRound => f.round(),
+                            NumFloor => f.floor(),
+                            _ => f.ceil(),
+                        };
+                        Value::Int(width, num::from_f64(width, whole))
+                    }
+                }
+            }
+            NumIsNan | NumIsInfinite | NumIsFinite => {
+                arguments!(args, n);
+                let f = match n {
+                    Value::Dec(_) => 0.0,
+                    _ => to_f64(n),
+                };
+                Value::Bool(match op {
+                    NumIsNan => f.is_nan(),
+                    NumIsInfinite => f.is_infinite(),
+                    _ => f.is_finite(),
+                })
+            }
+            NumToFrac | NumToFloatCast => {
+                arguments!(args, n);
+                match self.layout_interner.get_repr(ret_layout) {
+                    LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => {
+                        Value::F32(to_f64(n) as f32)
+                    }
+                    LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => Value::F64(to_f64(n)),
+                    LayoutRepr::Builtin(Builtin::Decimal) => {
+                        let converted = match n {
+                            Value::Int(IntWidth::U128, n) if *n < 0 => None,
+                            Value::Int(_, n) => dec::from_int(*n),
+                            Value::Dec(n) => Some(*n),
+                            other => dec::from_f64(to_f64(other)),
+                        };
+
+                        match converted {
+                            Some(n) => Value::Dec(n),
+                            None => {
+                                return Err(InterpError::crash(
+                                    "Decimal conversion from Integer failed!",
+                                ))
+                            }
+                        }
+                    }
+                    other => internal_error!("cannot convert a number to {other:?}"),
+                }
+            }
+            NumToFloatChecked => {
+                return Err(InterpError::Unsupported(
+                    "checked conversions to floats".to_string(),
+                ))
+            }
+
+            NumBitwiseAnd | NumBitwiseXor | NumBitwiseOr => {
+                arguments!(args, a, b);
+                let (width, a, b) = int_operands(a, b);
+                let answer = match op {
+                    NumBitwiseAnd => a & b,
+                    NumBitwiseXor => a ^ b,
+                    _ => a | b,
+                };
+                Value::Int(width, num::normalize(width, answer))
+            }
+            NumShiftLeftBy | NumShiftRightBy | NumShiftRightZfBy => {
+                arguments!(args, n, by);
+                let (Value::Int(width, n), by) = (n, by.as_int()) else {
+                    internal_error!("cannot shift {n:?}")
+                };
+                let answer = match op {
+                    NumShiftLeftBy => num::shift_left(*width, *n, by),
+                    NumShiftRightBy => num::shift_right(*width, *n, by),
+                    _ => num::shift_right_zero_fill(*width, *n, by),
+                };
+                Value::Int(*width, answer)
+            }
+            NumIntCast => {
+                arguments!(args, n);
+                let width = self.int_width(ret_layout);
+                Value::Int(width, num::normalize(width, n.as_int()))
+            }
+            NumToIntChecked => {
+                arguments!(args, n);
+                let Value::Int(from, n) = n else {
+                    internal_error!("cannot convert {n:?} to an integer")
+                };
+                let to = match self.layout_interner.get_repr(ret_layout) {
+                    LayoutRepr::Struct([value, _]) => self.int_width(*value),
+                    other => internal_error!("unexpected layout {other:?} for NumToIntChecked"),
+                };
+                let fits = num::fits(*from, to, *n);
+                let converted = if fits { num::normalize(to, *n) } else { 0 };
+
+                Value::structure(vec![Value::Int(to, converted), Value::Bool(!fits)])
+            }
+            NumCountLeadingZeroBits | NumCountTrailingZeroBits | NumCountOneBits => {
+                arguments!(args, n);
+                let Value::Int(width, n) = n else {
+                    internal_error!("cannot count the bits of {n:?}")
+                };
+                let count = match op {
+                    NumCountLeadingZeroBits => num::count_leading_zeros(*width, *n),
+                    NumCountTrailingZeroBits => num::count_trailing_zeros(*width, *n),
+                    _ => num::count_ones(*width, *n),
+                };
+                self.int(ret_layout, count as i128)
+            }
+            NumWithoutDecimalPoint => {
+                arguments!(args, n);
+                match n {
+                    Value::Dec(n) => Value::Int(IntWidth::I128, *n),
+                    other => internal_error!("expected a Dec, found {other:?}"),
+                }
+            }
+            NumWithDecimalPoint => {
+                arguments!(args, n);
+                Value::Dec(n.as_int())
+            }
+            NumF32ToParts => {
+                arguments!(args, f);
+                let Value::F32(f) = f else {
+                    internal_error!("expected an F32, found {f:?}")
+                };
+                let bits = f.to_bits();
+                Value::structure(vec![
+                    Value::Int(IntWidth::U32, (bits & 0x007F_FFFF) as i128),
+                    Value::Int(IntWidth::U8, ((bits >> 23) & 0xFF) as i128),
+                    Value::Bool(bits >> 31 == 1),
+                ])
+            }
+            NumF64ToParts => {
+                arguments!(args, f);
+                let Value::F64(f) = f else {
+                    internal_error!("expected an F64, found {f:?}")
+                };
+                let bits = f.to_bits();
+                Value::structure(vec![
+                    Value::Int(IntWidth::U64, (bits & 0x000F_FFFF_FFFF_FFFF) as i128),
+                    Value::Int(IntWidth::U16, ((bits >> 52) & 0x7FF) as i128),
+                    Value::Bool(bits >> 63 == 1),
+                ])
+            }
+            NumF32FromParts => {
+                arguments!(args, parts);
+                let [fraction, exponent, sign] = &**parts.as_fields() else {
+                    internal_error!("expected the parts of an F32, found {parts:?}")
+                };
+                let bits = (fraction.as_int() as u32 & 0x007F_FFFF)
+                    | ((exponent.as_int() as u32 & 0xFF) << 23)
+                    | ((sign.as_bool() as u32) << 31);
+                Value::F32(f32::from_bits(bits))
+            }
+            NumF64FromParts => {
+                arguments!(args, parts);
+                let [fraction, exponent, sign] = &**parts.as_fields() else {
+                    internal_error!("expected the parts of an F64, found {parts:?}")
+                };
+                let bits = (fraction.as_int() as u64 & 0x000F_FFFF_FFFF_FFFF)
+                    | ((exponent.as_int() as u64 & 0x7FF) << 52)
+                    | ((sign.as_bool() as u64) << 63);
+                Value::F64(f64::from_bits(bits))
+            }
+
+            Eq => {
+                arguments!(args, a, b);
+                Value::Bool(a.structurally_eq(b))
+            }
+            NotEq => {
+                arguments!(args, a, b);
+                Value::Bool(!a.structurally_eq(b))
+            }
+            And => {
+                arguments!(args, a, b);
+                Value::Bool(a.as_bool() && b.as_bool())
+            }
+            Or => {
+                arguments!(args, a, b);
+                Value::Bool(a.as_bool() || b.as_bool())
+            }
+            Not => {
+                arguments!(args, a);
+                Value::Bool(!a.as_bool())
+            }
+
+            PtrCast | PtrClearTagId => args[0].clone(),
+            PtrStore => {
+                arguments!(args, ptr, value);
+                ptr.as_ptr().store(value.clone());
+                Value::unit()
+            }
+            PtrLoad => {
+                arguments!(args, ptr);
+                ptr.as_ptr().load()
+            }
+            RefCountIncRcPtr | RefCountDecRcPtr | RefCountIncDataPtr | RefCountDecDataPtr => {
+                Value::unit()
+            }
+            RefCountIsUnique => Value::Bool(true),
+            BoxExpr => {
+                arguments!(args, value);
+                Value::heap(Value::Tag(0, Rc::new([value.clone()])))
+            }
+            UnboxExpr => {
+                arguments!(args, boxed);
+                boxed.with_tag(|_, fields| fields[0].clone())
+            }
+            DictPseudoSeed => self.int(ret_layout, 0x5eed_5eed),
+            Unreachable => return Err(InterpError::crash("Reached unreachable code")),
+
+            Hash | SetJmp | LongJmp | SetLongJmpBuffer => {
+                return Err(InterpError::Unsupported(format!("the low-level {op:?}")))
+            }
+        };
+
+        Ok(value)
+    }
+
+    pub(crate) fn higher_order(
+        &mut self,
+        frame: &Frame<'a, 'r>,
+        higher_order: &HigherOrderLowLevel<'a>,
+    ) -> Result<Value<'a>, InterpError> {
+        let passed = &higher_order.passed_function;
+
+        let proc_layout = ProcLayout {
+            arguments: passed.argument_layouts,
+            result: passed.return_layout,
+            niche: passed.name.niche(),
+        };
+
+        // The closure environment, if any, is the last argument of the passed function.
+        let captured = (passed.argument_layouts.len() > higher_order.op.function_arity())
+            .then(|| frame.get(passed.captured_environment));
+
+        match higher_order.op {
+            HigherOrder::ListSortWith { xs } => {
+                let list = frame.get(xs);
+                let mut compare = |a: &Value<'a>, b: &Value<'a>| {
+                    let mut arguments = vec![a.clone(), b.clone()];
+                    arguments.extend(captured.clone());
+
+                    self.call(passed.name.name(), proc_layout, arguments)
+                        .map(|ordering| ordering.as_int())
+                };
+
+                let sorted = merge_sort(list.as_list().to_vec(), &mut compare)?;
+
+                Ok(Value::list(sorted))
+            }
+        }
+    }
+
+    fn int_width(&self, layout: InLayout<'a>) -> IntWidth {
+        match self.layout_interner.get_repr(layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => width,
+            other => internal_error!("expected an integer layout, found {other:?}"),
+        }
+    }
+
+    fn str_to_num(&self, s: &str, ret_layout: InLayout<'a>) -> Value<'a> {
+        let num_layout = match self.layout_interner.get_repr(ret_layout) {
+            LayoutRepr::Struct([num_layout, _]) => *num_layout,
+            other => internal_error!("unexpected layout {other:?} for StrToNum"),
+        };
+
+        let parsed = match self.layout_interner.get_repr(num_layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => {
+                num::parse(width, s).map(|n| Value::Int(width, n))
+            }
+            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => s.parse().ok().map(Value::F32),
+            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => s.parse().ok().map(Value::F64),
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                RocDec::from_str(s).map(|dec| Value::Dec(i128::from_ne_bytes(dec.to_ne_bytes())))
+            }
+            other => internal_error!("cannot parse a number of layout {other:?}"),
+        };
+
+        match parsed {
+            Some(n) => Value::structure(vec![n, Value::Int(IntWidth::U8, 0)]),
+            None => {
+                let zero = match self.layout_interner.get_repr(num_layout) {
+                    LayoutRepr::Builtin(Builtin::Int(width)) => Value::Int(width, 0),
+                    LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => Value::F32(0.0),
+                    LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => Value::F64(0.0),
+                    _ => Value::Dec(0),
+                };
+
+                Value::structure(vec![zero, Value::Int(IntWidth::U8, 1)])
+            }
+        }
+    }
+
+    fn arith(
+        &self,
+        args: &[Value<'a>],
+        op: Arith,
+        overflow: Overflow,
+    ) -> Result<Value<'a>, InterpError> {
+        arguments!(args, a, b);
+
+        let (value, overflowed) = match (a, b) {
+            (Value::Int(width, a), Value::Int(_, b)) => {
+                let width = *width;
+                let answer = match overflow {
+                    Overflow::Wrap => Some(num::wrapping(width, op, *a, *b)),
+                    Overflow::Saturate => Some(num::saturating(width, op, *a, *b)),
+                    Overflow::Crash | Overflow::Report => num::checked(width, op, *a, *b),
+                };
+
+                match answer {
+                    Some(n) => (Value::Int(width, n), false),
+                    None => (Value::Int(width, num::wrapping(width, op, *a, *b)), true),
+                }
+            }
+            (Value::F32(a), Value::F32(b)) => {
+                let answer = match op {
+                    Arith::Add => a + b,
+                    Arith::Sub => a - b,
+                    Arith::Mul => a * b,
+                };
+                (Value::F32(answer), !answer.is_finite())
+            }
+            (Value::F64(a), Value::F64(b)) => {
+                let answer = match op {
+                    Arith::Add => a + b,
+                    Arith::Sub => a - b,
+                    Arith::Mul => a * b,
+                };
+                (Value::F64(answer), !answer.is_finite())
+            }
+            (Value::Dec(a), Value::Dec(b)) => {
+                let answer = match op {
+                    Arith::Add => a.checked_add(*b),
+                    Arith::Sub => a.checked_sub(*b),
+                    Arith::Mul => dec::checked_mul(*a, *b),
+                };
+
+                match (answer, overflow) {
+                    (Some(n), _) => (Value::Dec(n), false),
+                    (None, Overflow::Saturate) => {
+                        let negative = match op {
+                            Arith::Add => *a < 0,
+                            Arith::Sub => *a < 0,
+                            Arith::Mul => (*a < 0) != (*b < 0),
+                        };
+                        let bound = if negative { i128::MIN } else { i128::MAX };
+                        (Value::Dec(bound), false)
+                    }
+                    (None, _) => (Value::Dec(0), true),
+                }
+            }
+            _ => internal_error!("cannot do arithmetic on {a:?} and {b:?}"),
+        };
+
+        match overflow {
+            Overflow::Report => Ok(Value::structure(vec![value, Value::Bool(overflowed)])),
+            Overflow::Crash if overflowed => {
+                let is_dec = matches!(value, Value::Dec(_));
+                let message = match (is_dec, op) {
+                    (false, Arith::Add) => "Integer addition overflowed!",
+                    (false, Arith::Sub) => "Integer subtraction overflowed!",
+                    (false, Arith::Mul) => "Integer multiplication overflowed!",
+                    (true, Arith::Add) => "Decimal addition overflowed!",
+                    (true, Arith::Sub) => "Decimal subtraction overflowed!",
+                    (true, Arith::Mul) => "Decimal multiplication overflowed!",
+                };
+
+                // Floats overflow to infinity rather than crashing.
+                match value {
+                    Value::F32(_) | Value::F64(_) => Ok(value),
+                    _ => Err(InterpError::crash(message)),
+                }
+            }
+            _ => Ok(value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Overflow {
+    Crash,
+    Wrap,
+    Saturate,
+    /// Return the answer along with whether it overflowed.
+    Report,
+}
+
+fn int_operands(a: &Value, b: &Value) -> (IntWidth, i128, i128) {
+    match (a, b) {
+        (Value::Int(width, a), Value::Int(_, b)) => (*width, *a, *b),
+        _ => internal_error!("expected two integers, found {a:?} and {b:?}"),
+    }
+}
+
+fn compare_nums(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Int(width, a), Value::Int(_, b)) => Some(num::compare(*width, *a, *b)),
+        (Value::F32(a), Value::F32(b)) => a.partial_cmp(b),
+        (Value::F64(a), Value::F64(b)) => a.partial_cmp(b),
+        (Value::Dec(a), Value::Dec(b)) => Some(a.cmp(b)),
+        _ => internal_error!("cannot compare {a:?} and {b:?}"),
+    }
+}
+
+fn to_f64(n: &Value) -> f64 {
+    match n {
+        Value::Int(width, n) => num::to_f64(*width, *n),
+        Value::F32(f) => *f as f64,
+        Value::F64(f) => *f,
+        Value::Dec(n) => dec::to_f64(*n),
+        other => internal_error!("expected a number, found {other:?}"),
+    }
+}
+
+/// Converts `f` back to the type of `like`.
+fn from_f64_like<'a>(like: &Value<'a>, f: f64) -> Result<Value<'a>, InterpError> {
+    match like {
+        Value::F32(_) => Ok(Value::F32(f as f32)),
+        Value::F64(_) => Ok(Value::F64(f)),
+        Value::Dec(_) => match dec::from_f64(f) {
+            Some(n) => Ok(Value::Dec(n)),
+            None => Err(InterpError::crash("Decimal conversion from f64 failed!")),
+        },
+        other => internal_error!("expected a fraction, found {other:?}"),
+    }
+}
+
+fn float_op<'a>(args: &[Value<'a>], f: impl Fn(f64) -> f64) -> Result<Value<'a>, InterpError> {
+    arguments!(args, n);
+
+    match n {
+        Value::F32(x) => Ok(Value::F32(f(*x as f64) as f32)),
+        _ => from_f64_like(n, f(to_f64(n))),
+    }
+}
+
+fn num_to_string(n: &Value) -> String {
+    match n {
+        Value::Int(width, n) => num::to_string(*width, *n),
+        Value::F32(f) => f.to_string(),
+        Value::F64(f) => f.to_string(),
+        Value::Dec(n) => RocDec::new(*n).to_string(),
+        other => internal_error!("expected a number, found {other:?}"),
+    }
+}
+
+fn bytes_to_list<'a>(bytes: &[u8]) -> Value<'a> {
+    Value::list(
+        bytes
+            .iter()
+            .map(|byte| Value::Int(IntWidth::U8, *byte as i128))
+            .collect(),
+    )
+}
+
+/// Builds the `FromUtf8Result` record: `{ aByteIndex, bString, cIsOk, dProblemCode }`.
+fn str_from_utf8<'a>(list: &Value<'a>) -> Value<'a> {
+    let bytes: Vec<u8> = list.as_list().iter().map(|b| b.as_int() as u8).collect();
+
+    let (index, string, problem) = match std::str::from_utf8(&bytes) {
+        Ok(s) => (0, s, None),
+        Err(error) => {
+            let index = error.valid_up_to();
+            (
+                index,
+                "",
+                Some(utf8_problem(&bytes[index..], error.error_len())),
+            )
+        }
+    };
+
+    Value::structure(vec![
+        Value::Int(IntWidth::U64, index as i128),
+        Value::str(string),
+        Value::Bool(problem.is_none()),
+        Value::Int(IntWidth::U8, problem.unwrap_or(0) as i128),
+    ])
+}
+
+/// The tag id of the `Utf8ByteProblem` at the start of `rest`.
+fn utf8_problem(rest: &[u8], error_len: Option<usize>) -> u8 {
+    const CODEPOINT_TOO_LARGE: u8 = 0;
+    const ENCODES_SURROGATE_HALF: u8 = 1;
+    const EXPECTED_CONTINUATION: u8 = 2;
+    const INVALID_START_BYTE: u8 = 3;
+    const OVERLONG_ENCODING: u8 = 4;
+    const UNEXPECTED_END_OF_SEQUENCE: u8 = 5;
+
+    if error_len.is_none() {
+        return UNEXPECTED_END_OF_SEQUENCE;
+    }
+
+    let first = rest[0];
+    let second = rest.get(1).copied().unwrap_or(0);
+
+    match first {
+        0x80..=0xBF | 0xF8..=0xFF => INVALID_START_BYTE,
+        0xC0 | 0xC1 => OVERLONG_ENCODING,
+        0xE0 if (0x80..0xA0).contains(&second) => OVERLONG_ENCODING,
+        0xED if (0xA0..0xC0).contains(&second) => ENCODES_SURROGATE_HALF,
+        0xF0 if (0x80..0x90).contains(&second) => OVERLONG_ENCODING,
+        0xF4 if (0x90..0xC0).contains(&second) => CODEPOINT_TOO_LARGE,
+        0xF5..=0xF7 => CODEPOINT_TOO_LARGE,
+        _ => EXPECTED_CONTINUATION,
+    }
+}
+
+/// A stable merge sort, because the comparison can fail (by crashing) and `sort_by` cannot
+/// pass that on.
+fn merge_sort<'a>(
+    mut elems: Vec<Value<'a>>,
+    compare: &mut impl FnMut(&Value<'a>, &Value<'a>) -> Result<i128, InterpError>,
+) -> Result<Vec<Value<'a>>, InterpError> {
+    if elems.len() <= 1 {
+        return Ok(elems);
+    }
+
+    let right = elems.split_off(elems.len() / 2);
+    let left = merge_sort(elems, compare)?;
+    let right = merge_sort(right, compare)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        if compare(l, r)? == GT {
+            merged.push(right.next().unwrap());
+        } else {
+            merged.push(left.next().unwrap());
+        }
+    }
+
+    merged.extend(left);
+    merged.extend(right);
+
+    Ok(merged)
+}
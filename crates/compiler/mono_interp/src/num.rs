@@ -0,0 +1,324 @@
+//! Integer and `Dec` arithmetic.
+//!
+//! Integers are stored as an `i128` plus their width, so every operation first narrows its
+//! operands to the native Rust type of that width. That way overflow, wrapping and signedness
+//! come from Rust rather than from hand-written bit twiddling.
+
+// `with_int_type!` casts to and from `i128` for every width, including `i128` itself.
+#![allow(clippy::unnecessary_cast)]
+
+use std::cmp::Ordering;
+
+use roc_builtins::bitcode::IntWidth;
+
+/// Runs `$body` with `$T` bound to the native integer type of `$width`.
+macro_rules! with_int_type {
+    ($width:expr, $T:ident, $body:expr) => {
+        match $width {
+            IntWidth::U8 => {
+                type $T = u8;
+                $body
+            }
+            IntWidth::U16 => {
+                type $T = u16;
+                $body
+            }
+            IntWidth::U32 => {
+                type $T = u32;
+                $body
+            }
+            IntWidth::U64 => {
+                type $T = u64;
+                $body
+            }
+            IntWidth::U128 => {
+                type $T = u128;
+                $body
+            }
+            IntWidth::I8 => {
+                type $T = i8;
+                $body
+            }
+            IntWidth::I16 => {
+                type $T = i16;
+                $body
+            }
+            IntWidth::I32 => {
+                type $T = i32;
+                $body
+            }
+            IntWidth::I64 => {
+                type $T = i64;
+                $body
+            }
+            IntWidth::I128 => {
+                type $T = i128;
+                $body
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Arith {
+    Add,
+    Sub,
+    Mul,
+}
+
+/// Truncates `n` to `width`, then sign- or zero-extends it back to 128 bits.
+pub(crate) fn normalize(width: IntWidth, n: i128) -> i128 {
+    with_int_type!(width, T, n as T as i128)
+}
+
+pub(crate) fn checked(width: IntWidth, op: Arith, a: i128, b: i128) -> Option<i128> {
+    with_int_type!(width, T, {
+        let (a, b) = (a as T, b as T);
+        let answer = match op {
+            Arith::Add => a.checked_add(b),
+            Arith::Sub => a.checked_sub(b),
+            Arith::Mul => a.checked_mul(b),
+        };
+        answer.map(|n| n as i128)
+    })
+}
+
+pub(crate) fn wrapping(width: IntWidth, op: Arith, a: i128, b: i128) -> i128 {
+    with_int_type!(width, T, {
+        let (a, b) = (a as T, b as T);
+        let answer = match op {
+            Arith::Add => a.wrapping_add(b),
+            Arith::Sub => a.wrapping_sub(b),
+            Arith::Mul => a.wrapping_mul(b),
+        };
+        answer as i128
+    })
+}
+
+pub(crate) fn saturating(width: IntWidth, op: Arith, a: i128, b: i128) -> i128 {
+    with_int_type!(width, T, {
+        let (a, b) = (a as T, b as T);
+        let answer = match op {
+            Arith::Add => a.saturating_add(b),
+            Arith::Sub => a.saturating_sub(b),
+            Arith::Mul => a.saturating_mul(b),
+        };
+        answer as i128
+    })
+}
+
+pub(crate) fn compare(width: IntWidth, a: i128, b: i128) -> Ordering {
+    with_int_type!(width, T, (a as T).cmp(&(b as T)))
+}
+
+/// Truncating division. `None` if `b` is zero.
+pub(crate) fn div_trunc(width: IntWidth, a: i128, b: i128) -> Option<i128> {
+    with_int_type!(width, T, {
+        let (a, b) = (a as T, b as T);
+        (b != 0).then(|| a.wrapping_div(b) as i128)
+    })
+}
+
+/// Division rounding towards positive infinity. `None` if `b` is zero.
+pub(crate) fn div_ceil(width: IntWidth, a: i128, b: i128) -> Option<i128> {
+    with_int_type!(width, T, {
+        let (a, b) = (a as T, b as T);
+        let zero = 0 as T;
+
+        (b != zero).then(|| {
+            let quotient = a.wrapping_div(b);
+            let remainder = a.wrapping_rem(b);
+
+            if remainder != zero && ((remainder > zero) == (b > zero)) {
+                quotient.wrapping_add(1) as i128
+            } else {
+                quotient as i128
+            }
+        })
+    })
+}
+
+/// Remainder of truncating division. `None` if `b` is zero.
+pub(crate) fn rem(width: IntWidth, a: i128, b: i128) -> Option<i128> {
+    with_int_type!(width, T, {
+        let (a, b) = (a as T, b as T);
+        (b != 0).then(|| a.wrapping_rem(b) as i128)
+    })
+}
+
+/// `None` if `n` is the minimum value of a signed width.
+pub(crate) fn checked_neg(width: IntWidth, n: i128) -> Option<i128> {
+    if width.is_signed() {
+        with_int_type!(width, T, (n as T).checked_neg().map(|n| n as i128))
+    } else {
+        Some(wrapping(width, Arith::Sub, 0, n))
+    }
+}
+
+/// `None` if `n` is the minimum value of a signed width.
+pub(crate) fn checked_abs(width: IntWidth, n: i128) -> Option<i128> {
+    if width.is_signed() && compare(width, n, 0) == Ordering::Less {
+        checked_neg(width, n)
+    } else {
+        Some(n)
+    }
+}
+
+/// `None` on overflow, or for a negative exponent.
+pub(crate) fn checked_pow(width: IntWidth, base: i128, exponent: i128) -> Option<i128> {
+    if width.is_signed() && exponent < 0 {
+        return None;
+    }
+
+    let mut exponent = exponent as u128;
+    let mut base = base;
+    let mut answer = 1;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            answer = checked(width, Arith::Mul, answer, base)?;
+        }
+
+        exponent >>= 1;
+
+        if exponent > 0 {
+            base = checked(width, Arith::Mul, base, base)?;
+        }
+    }
+
+    Some(answer)
+}
+
+pub(crate) fn shift_left(width: IntWidth, n: i128, by: i128) -> i128 {
+    if by >= width.stack_size() as i128 * 8 {
+        0
+    } else {
+        normalize(width, n << by)
+    }
+}
+
+/// Shifts in copies of the top bit, whatever the signedness of `width`.
+pub(crate) fn shift_right(width: IntWidth, n: i128, by: i128) -> i128 {
+    let bits = width.stack_size() as i128 * 8;
+    let by = by.min(bits - 1);
+
+    // Sign-extend from the top bit of the width, shift, then narrow back.
+    let signed = (n << (128 - bits)) >> (128 - bits);
+    normalize(width, signed >> by)
+}
+
+/// Shifts in zeroes, whatever the signedness of `width`.
+pub(crate) fn shift_right_zero_fill(width: IntWidth, n: i128, by: i128) -> i128 {
+    let bits = width.stack_size() as i128 * 8;
+
+    if by >= bits {
+        0
+    } else {
+        let unsigned = (n as u128) & (u128::MAX >> (128 - bits));
+        normalize(width, (unsigned >> by) as i128)
+    }
+}
+
+pub(crate) fn count_leading_zeros(width: IntWidth, n: i128) -> u32 {
+    with_int_type!(width, T, (n as T).leading_zeros())
+}
+
+pub(crate) fn count_trailing_zeros(width: IntWidth, n: i128) -> u32 {
+    with_int_type!(width, T, (n as T).trailing_zeros())
+}
+
+pub(crate) fn count_ones(width: IntWidth, n: i128) -> u32 {
+    with_int_type!(width, T, (n as T).count_ones())
+}
+
+/// The smallest and largest values of `width`. `U128::MAX` keeps its bit pattern, like any
+/// other `U128`.
+pub(crate) fn bounds(width: IntWidth) -> (i128, i128) {
+    with_int_type!(width, T, (T::MIN as i128, T::MAX as i128))
+}
+
+/// Whether `n` survives being cast to `to`.
+pub(crate) fn fits(from: IntWidth, to: IntWidth, n: i128) -> bool {
+    let negative = from.is_signed() && n < 0;
+    let cast = normalize(to, n);
+    let cast_negative = to.is_signed() && cast < 0;
+
+    cast == n && negative == cast_negative
+}
+
+pub(crate) fn to_f64(width: IntWidth, n: i128) -> f64 {
+    match width {
+        IntWidth::U128 => n as u128 as f64,
+        _ => n as f64,
+    }
+}
+
+pub(crate) fn to_string(width: IntWidth, n: i128) -> String {
+    match width {
+        IntWidth::U128 => (n as u128).to_string(),
+        _ => n.to_string(),
+    }
+}
+
+/// Parses a number the way `Str.toI64` and friends do.
+pub(crate) fn parse(width: IntWidth, s: &str) -> Option<i128> {
+    with_int_type!(width, T, s.parse::<T>().ok().map(|n| n as i128))
+}
+
+/// Converts a float to an integer, saturating at the bounds of `width`.
+pub(crate) fn from_f64(width: IntWidth, f: f64) -> i128 {
+    with_int_type!(width, T, f as T as i128)
+}
+
+pub(crate) mod dec {
+    //! `Dec` is an `i128` scaled by 10^18. The arithmetic itself comes from `RocDec`, which
+    //! matches the builtins bit for bit.
+
+    use roc_std::RocDec;
+
+    /// The representation of `1.0`.
+    pub(crate) const ONE: i128 = 1_000_000_000_000_000_000;
+
+    pub(crate) fn checked_mul(a: i128, b: i128) -> Option<i128> {
+        RocDec::new(a).checked_mul(RocDec::new(b)).map(bits)
+    }
+
+    /// `None` on overflow or when dividing a nonzero number by zero.
+    pub(crate) fn checked_div(a: i128, b: i128) -> Option<i128> {
+        RocDec::new(a).checked_div(RocDec::new(b)).map(bits)
+    }
+
+    pub(crate) fn checked_pow(a: i128, b: i128) -> Option<i128> {
+        RocDec::new(a).checked_pow(RocDec::new(b)).map(bits)
+    }
+
+    pub(crate) fn from_int(n: i128) -> Option<i128> {
+        RocDec::from_int(n).map(bits)
+    }
+
+    pub(crate) fn from_f64(f: f64) -> Option<i128> {
+        RocDec::from_f64(f).map(bits)
+    }
+
+    pub(crate) fn to_f64(n: i128) -> f64 {
+        RocDec::new(n).to_f64()
+    }
+
+    /// The following return whole numbers, not `Dec`s.
+    pub(crate) fn floor(n: i128) -> i128 {
+        RocDec::new(n).floor()
+    }
+
+    pub(crate) fn ceiling(n: i128) -> i128 {
+        RocDec::new(n).ceiling()
+    }
+
+    /// Rounds half away from zero, like `Num.round` does for floats.
+    pub(crate) fn round(n: i128) -> i128 {
+        RocDec::new(n).round()
+    }
+
+    fn bits(dec: RocDec) -> i128 {
+        i128::from_ne_bytes(dec.to_ne_bytes())
+    }
+}
@@ -0,0 +1,373 @@
+//! Property expects, like `expect \n -> n + 0 == n`: generating arguments for them, and
+//! shrinking the arguments that make one fail down to a small counterexample.
+//!
+//! Generation is driven by a seeded random number generator, so a run can be repeated
+//! exactly. Values start out small and grow with each case, the way QuickCheck does it.
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_mono::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr, UnionLayout};
+
+use crate::num::{self, Arith};
+use crate::{InterpError, Value};
+
+/// How many sets of arguments a property is tried with, unless one of them makes it fail.
+pub const PROPERTY_CASES: u32 = 100;
+
+/// Stops shrinking a counterexample after it got smaller this many times.
+pub const MAX_SHRINKS: u32 = 1000;
+
+/// The seed used unless the caller picks one.
+pub const DEFAULT_SEED: u64 = 0x5eed_5eed_5eed_5eed;
+
+/// Lists and strings get at most this many elements.
+const MAX_LEN: u64 = 16;
+
+/// A set of arguments that makes a property fail.
+#[derive(Debug, Clone)]
+pub struct Counterexample<'a> {
+    pub arguments: Vec<Value<'a>>,
+    /// The case, counting from 1, that first failed
+    pub case: u32,
+    /// How many times the arguments were made smaller after that
+    pub shrinks: u32,
+}
+
+/// Tries a property with up to [`PROPERTY_CASES`] sets of generated arguments. `fails` runs it
+/// with a set of arguments, and says whether it failed (or crashed). Returns the smallest
+/// counterexample found, if any.
+pub fn check_property<'a>(
+    layout_interner: &impl LayoutInterner<'a>,
+    argument_layouts: &[InLayout<'a>],
+    seed: u64,
+    mut fails: impl FnMut(&[Value<'a>]) -> bool,
+) -> Result<Option<Counterexample<'a>>, InterpError> {
+    let mut rng = Rng::new(seed);
+
+    for case in 1..=PROPERTY_CASES {
+        let arguments = argument_layouts
+            .iter()
+            .map(|layout| arbitrary(&mut rng, layout_interner, *layout, case as u64))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if fails(&arguments) {
+            let (arguments, shrinks) =
+                shrink_arguments(layout_interner, argument_layouts, arguments, &mut fails);
+
+            return Ok(Some(Counterexample {
+                arguments,
+                case,
+                shrinks,
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Greedily replaces one argument at a time with a smaller value, as long as the property
+/// still fails with it.
+fn shrink_arguments<'a>(
+    layout_interner: &impl LayoutInterner<'a>,
+    argument_layouts: &[InLayout<'a>],
+    mut arguments: Vec<Value<'a>>,
+    fails: &mut impl FnMut(&[Value<'a>]) -> bool,
+) -> (Vec<Value<'a>>, u32) {
+    let mut shrinks = 0;
+
+    'shrinking: while shrinks < MAX_SHRINKS {
+        for (index, layout) in argument_layouts.iter().enumerate() {
+            for smaller in shrink(layout_interner, *layout, &arguments[index]) {
+                let mut candidate = arguments.clone();
+                candidate[index] = smaller;
+
+                if fails(&candidate) {
+                    arguments = candidate;
+                    shrinks += 1;
+
+                    continue 'shrinking;
+                }
+            }
+        }
+
+        break;
+    }
+
+    (arguments, shrinks)
+}
+
+/// A splitmix64 generator: tiny, and plenty random enough for picking test inputs.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..=max`
+    fn up_to(&mut self, max: u64) -> u64 {
+        match max.checked_add(1) {
+            Some(bound) => self.next_u64() % bound,
+            None => self.next_u64(),
+        }
+    }
+
+    fn one_in(&mut self, n: u64) -> bool {
+        self.next_u64() % n == 0
+    }
+}
+
+/// A random value of `layout`, roughly no bigger than `size`.
+fn arbitrary<'a>(
+    rng: &mut Rng,
+    layout_interner: &impl LayoutInterner<'a>,
+    layout: InLayout<'a>,
+    size: u64,
+) -> Result<Value<'a>, InterpError> {
+    let value = match layout_interner.get_repr(layout) {
+        LayoutRepr::Builtin(Builtin::Bool) => Value::Bool(rng.one_in(2)),
+        LayoutRepr::Builtin(Builtin::Int(width)) => {
+            Value::Int(width, arbitrary_int(rng, width, size))
+        }
+        LayoutRepr::Builtin(Builtin::Float(width)) => {
+            let f = arbitrary_float(rng, size);
+
+            match width {
+                FloatWidth::F32 => Value::F32(f as f32),
+                FloatWidth::F64 => Value::F64(f),
+            }
+        }
+        LayoutRepr::Builtin(Builtin::Decimal) => {
+            Value::Dec(num::dec::from_f64(arbitrary_float(rng, size)).unwrap_or_default())
+        }
+        LayoutRepr::Builtin(Builtin::Str) => {
+            const CHARS: &[char] = &[
+                'a', 'b', 'c', 'x', 'y', 'z', 'A', 'Z', '0', '1', '9', ' ', '-', '_', '\n', 'é',
+                'ß', '日', '🐦',
+            ];
+
+            let len = rng.up_to(size.min(MAX_LEN));
+            let string: String = (0..len)
+                .map(|_| CHARS[rng.up_to(CHARS.len() as u64 - 1) as usize])
+                .collect();
+
+            Value::str(&string)
+        }
+        LayoutRepr::Builtin(Builtin::List(element)) => {
+            let len = rng.up_to(size.min(MAX_LEN));
+            let elements = (0..len)
+                .map(|_| arbitrary(rng, layout_interner, element, size))
+                .collect::<Result<_, _>>()?;
+
+            Value::list(elements)
+        }
+        LayoutRepr::Struct(fields) => {
+            let fields = fields
+                .iter()
+                .map(|field| arbitrary(rng, layout_interner, *field, size))
+                .collect::<Result<_, _>>()?;
+
+            Value::structure(fields)
+        }
+        LayoutRepr::Union(UnionLayout::NonRecursive(tags)) if !tags.is_empty() => {
+            let tag_id = rng.up_to(tags.len() as u64 - 1) as usize;
+            let fields: Vec<_> = tags[tag_id]
+                .iter()
+                .map(|field| arbitrary(rng, layout_interner, *field, size))
+                .collect::<Result<_, _>>()?;
+
+            Value::Tag(tag_id as _, fields.into())
+        }
+        other => {
+            return Err(InterpError::Unsupported(format!(
+                "generating arguments of layout {other:?} for a property"
+            )))
+        }
+    };
+
+    Ok(value)
+}
+
+fn arbitrary_int(rng: &mut Rng, width: IntWidth, size: u64) -> i128 {
+    let (min, max) = num::bounds(width);
+
+    // The edges are where the bugs are
+    if rng.one_in(10) {
+        return [min, max, 0, 1][rng.up_to(3) as usize];
+    }
+
+    let magnitude = rng.up_to(size) as i128;
+    let n = if width.is_signed() && rng.one_in(2) {
+        -magnitude
+    } else {
+        magnitude
+    };
+
+    match num::fits(IntWidth::I128, width, n) {
+        true => n,
+        false if n < 0 => min,
+        false => max,
+    }
+}
+
+fn arbitrary_float(rng: &mut Rng, size: u64) -> f64 {
+    let whole = rng.up_to(size) as f64;
+    let fraction = rng.up_to(99) as f64 / 100.0;
+    let f = whole + fraction;
+
+    match rng.one_in(2) {
+        true => -f,
+        false => f,
+    }
+}
+
+/// Values a bit smaller than `value`, the most promising first.
+fn shrink<'a>(
+    layout_interner: &impl LayoutInterner<'a>,
+    layout: InLayout<'a>,
+    value: &Value<'a>,
+) -> Vec<Value<'a>> {
+    let mut smaller = Vec::new();
+
+    match value {
+        Value::Bool(true) => smaller.push(Value::Bool(false)),
+        Value::Int(width, n) if *n != 0 => {
+            let half = num::div_trunc(*width, *n, 2).unwrap_or(0);
+            let towards_zero = match width.is_signed() && *n < 0 {
+                true => n + 1,
+                false => num::wrapping(*width, Arith::Sub, *n, 1),
+            };
+
+            for m in [0, half, towards_zero] {
+                if m != *n
+                    && !smaller
+                        .iter()
+                        .any(|v| matches!(v, Value::Int(_, k) if *k == m))
+                {
+                    smaller.push(Value::Int(*width, m));
+                }
+            }
+        }
+        Value::F64(f) if *f != 0.0 => {
+            smaller.extend(shrink_float(*f).into_iter().map(Value::F64));
+        }
+        Value::F32(f) if *f != 0.0 => {
+            smaller.extend(
+                shrink_float(*f as f64)
+                    .into_iter()
+                    .map(|f| Value::F32(f as f32)),
+            );
+        }
+        Value::Dec(n) if *n != 0 => {
+            smaller.push(Value::Dec(0));
+
+            match num::dec::from_int(num::dec::round(*n)) {
+                Some(rounded) if rounded != *n => smaller.push(Value::Dec(rounded)),
+                _ => {}
+            }
+        }
+        Value::Str(s) if !s.is_empty() => {
+            let chars: Vec<char> = s.chars().collect();
+
+            smaller.push(Value::str(""));
+            smaller.push(Value::str(
+                &chars[..chars.len() / 2].iter().collect::<String>(),
+            ));
+            smaller.push(Value::str(&chars[1..].iter().collect::<String>()));
+            smaller.push(Value::str(
+                &chars[..chars.len() - 1].iter().collect::<String>(),
+            ));
+
+            // Simpler characters, one at a time
+            for (i, c) in chars.iter().enumerate() {
+                if *c != 'a' {
+                    let mut simpler = chars.clone();
+                    simpler[i] = 'a';
+                    smaller.push(Value::str(&simpler.iter().collect::<String>()));
+                }
+            }
+        }
+        Value::List(elements) if !elements.is_empty() => {
+            smaller.push(Value::list(Vec::new()));
+            smaller.push(Value::list(elements[..elements.len() / 2].to_vec()));
+
+            for i in 0..elements.len() {
+                let mut without = elements.to_vec();
+                without.remove(i);
+                smaller.push(Value::list(without));
+            }
+
+            if let LayoutRepr::Builtin(Builtin::List(element)) = layout_interner.get_repr(layout) {
+                for (i, old) in elements.iter().enumerate() {
+                    for new in shrink(layout_interner, element, old) {
+                        let mut elements = elements.to_vec();
+                        elements[i] = new;
+                        smaller.push(Value::list(elements));
+                    }
+                }
+            }
+        }
+        Value::Struct(fields) => {
+            if let LayoutRepr::Struct(field_layouts) = layout_interner.get_repr(layout) {
+                smaller.extend(
+                    shrink_fields(layout_interner, field_layouts, fields).map(Value::structure),
+                );
+            }
+        }
+        Value::Tag(tag_id, fields) => {
+            if let LayoutRepr::Union(UnionLayout::NonRecursive(tags)) =
+                layout_interner.get_repr(layout)
+            {
+                smaller.extend(
+                    shrink_fields(layout_interner, tags[*tag_id as usize], fields)
+                        .map(|fields| Value::Tag(*tag_id, fields.into())),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    smaller
+}
+
+fn shrink_float(f: f64) -> Vec<f64> {
+    let mut smaller = vec![0.0];
+
+    if f.trunc() != f {
+        smaller.push(f.trunc());
+    }
+
+    if f.abs() > 1.0 {
+        smaller.push(f / 2.0);
+    }
+
+    smaller
+}
+
+/// Copies of `fields` with one of them shrunk.
+fn shrink_fields<'a, 'v>(
+    layout_interner: &'v impl LayoutInterner<'a>,
+    field_layouts: &'v [InLayout<'a>],
+    fields: &'v [Value<'a>],
+) -> impl Iterator<Item = Vec<Value<'a>>> + 'v {
+    field_layouts
+        .iter()
+        .zip(fields)
+        .enumerate()
+        .flat_map(move |(i, (layout, old))| {
+            shrink(layout_interner, *layout, old)
+                .into_iter()
+                .map(move |new| {
+                    let mut fields = fields.to_vec();
+                    fields[i] = new;
+                    fields
+                })
+        })
+}
@@ -0,0 +1,211 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use roc_builtins::bitcode::IntWidth;
+use roc_error_macros::internal_error;
+use roc_mono::layout::{LambdaName, TagIdIntType};
+
+/// A runtime value. Unlike the backends, the interpreter does not lay values out in memory:
+/// every value carries just enough structure to run the IR and to be written out later,
+/// given its layout.
+#[derive(Clone, Debug)]
+pub enum Value<'a> {
+    Bool(bool),
+    /// An integer, sign- or zero-extended to 128 bits. `U128`s keep their bit pattern.
+    Int(IntWidth, i128),
+    F32(f32),
+    F64(f64),
+    /// A `Dec`, as its fixed-point representation.
+    Dec(i128),
+    Str(Rc<str>),
+    List(Rc<Vec<Value<'a>>>),
+    Struct(Rc<[Value<'a>]>),
+    /// A tag of a non-recursive union.
+    Tag(TagIdIntType, Rc<[Value<'a>]>),
+    /// A heap allocation: a tag of a recursive union, or a `Box`.
+    ///
+    /// Mutable, because tail recursion modulo cons writes the recursive field of a tag
+    /// after allocating it.
+    Heap(Rc<RefCell<Value<'a>>>),
+    /// The null tag of a nullable union, and the reuse token that never gets reused.
+    Null,
+    Ptr(Pointer<'a>),
+    FunctionPointer(LambdaName<'a>),
+    Erased(Rc<Erased<'a>>),
+}
+
+/// A type-erased value: an optional payload and a function pointer.
+#[derive(Clone, Debug)]
+pub struct Erased<'a> {
+    pub value: Option<Value<'a>>,
+    pub callee: Value<'a>,
+}
+
+/// Points at a heap allocation, or at a field inside one.
+#[derive(Clone, Debug)]
+pub struct Pointer<'a> {
+    cell: Rc<RefCell<Value<'a>>>,
+    path: Rc<[usize]>,
+}
+
+impl<'a> Value<'a> {
+    pub fn unit() -> Self {
+        Value::Struct(Rc::new([]))
+    }
+
+    pub fn str(s: &str) -> Self {
+        Value::Str(Rc::from(s))
+    }
+
+    pub fn list(elems: Vec<Value<'a>>) -> Self {
+        Value::List(Rc::new(elems))
+    }
+
+    pub fn structure(fields: Vec<Value<'a>>) -> Self {
+        Value::Struct(Rc::from(fields))
+    }
+
+    pub fn heap(value: Value<'a>) -> Self {
+        Value::Heap(Rc::new(RefCell::new(value)))
+    }
+
+    pub fn as_bool(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            other => internal_error!("expected a Bool, found {other:?}"),
+        }
+    }
+
+    pub fn as_int(&self) -> i128 {
+        match self {
+            Value::Int(_, n) => *n,
+            other => internal_error!("expected an integer, found {other:?}"),
+        }
+    }
+
+    pub fn as_str(&self) -> &Rc<str> {
+        match self {
+            Value::Str(s) => s,
+            other => internal_error!("expected a Str, found {other:?}"),
+        }
+    }
+
+    pub fn as_list(&self) -> &Rc<Vec<Value<'a>>> {
+        match self {
+            Value::List(elems) => elems,
+            other => internal_error!("expected a List, found {other:?}"),
+        }
+    }
+
+    pub fn as_fields(&self) -> &Rc<[Value<'a>]> {
+        match self {
+            Value::Struct(fields) => fields,
+            other => internal_error!("expected a struct, found {other:?}"),
+        }
+    }
+
+    pub fn as_ptr(&self) -> &Pointer<'a> {
+        match self {
+            Value::Ptr(ptr) => ptr,
+            other => internal_error!("expected a pointer, found {other:?}"),
+        }
+    }
+
+    /// The value as a `Switch` condition sees it.
+    pub fn as_switch_condition(&self) -> u64 {
+        match self {
+            Value::Bool(b) => *b as u64,
+            Value::Int(_, n) => *n as u64,
+            other => internal_error!("cannot switch on {other:?}"),
+        }
+    }
+
+    /// Runs `f` on the tag id and fields of a tag, following a heap allocation if needed.
+    pub fn with_tag<T>(&self, f: impl FnOnce(TagIdIntType, &[Value<'a>]) -> T) -> T {
+        match self {
+            Value::Tag(id, fields) => f(*id, fields),
+            Value::Heap(cell) => cell.borrow().with_tag(f),
+            other => internal_error!("expected a tag, found {other:?}"),
+        }
+    }
+
+    /// Structural equality, as the `Eq` low-level defines it.
+    pub fn structurally_eq(&self, other: &Self) -> bool {
+        use Value::*;
+
+        match (self, other) {
+            (Bool(a), Bool(b)) => a == b,
+            (Int(_, a), Int(_, b)) => a == b,
+            (F32(a), F32(b)) => a == b,
+            (F64(a), F64(b)) => a == b,
+            (Dec(a), Dec(b)) => a == b,
+            (Str(a), Str(b)) => a == b,
+            (List(a), List(b)) => fields_eq(a, b),
+            (Struct(a), Struct(b)) => fields_eq(a, b),
+            (Tag(id_a, a), Tag(id_b, b)) => id_a == id_b && fields_eq(a, b),
+            (Heap(a), Heap(b)) => Rc::ptr_eq(a, b) || a.borrow().structurally_eq(&b.borrow()),
+            (Null, Null) => true,
+            (Heap(_), Null) | (Null, Heap(_)) => false,
+            (a, b) => internal_error!("cannot compare {a:?} with {b:?}"),
+        }
+    }
+}
+
+fn fields_eq<'a>(a: &[Value<'a>], b: &[Value<'a>]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.structurally_eq(y))
+}
+
+impl<'a> Pointer<'a> {
+    /// A fresh allocation holding `value`.
+    pub fn alloc(value: Value<'a>) -> Self {
+        Self {
+            cell: Rc::new(RefCell::new(value)),
+            path: Rc::new([]),
+        }
+    }
+
+    /// Points at the field of the tag in `heap` found by following `path`.
+    pub fn field(heap: &Value<'a>, path: &[usize]) -> Self {
+        match heap {
+            Value::Heap(cell) => Self {
+                cell: cell.clone(),
+                path: Rc::from(path),
+            },
+            other => internal_error!("cannot point into {other:?}"),
+        }
+    }
+
+    pub fn load(&self) -> Value<'a> {
+        let mut value = self.cell.borrow().clone();
+
+        for index in self.path.iter() {
+            value = match value {
+                Value::Tag(_, fields) | Value::Struct(fields) => fields[*index].clone(),
+                other => internal_error!("cannot load field {index} of {other:?}"),
+            };
+        }
+
+        value
+    }
+
+    pub fn store(&self, value: Value<'a>) {
+        let mut target = self.cell.borrow_mut();
+        store_at(&mut target, &self.path, value);
+    }
+}
+
+fn store_at<'a>(target: &mut Value<'a>, path: &[usize], value: Value<'a>) {
+    let Some((index, rest)) = path.split_first() else {
+        *target = value;
+        return;
+    };
+
+    match target {
+        Value::Tag(_, fields) | Value::Struct(fields) => {
+            let mut updated = fields.to_vec();
+            store_at(&mut updated[*index], rest, value);
+            *fields = Rc::from(updated);
+        }
+        other => internal_error!("cannot store to field {index} of {other:?}"),
+    }
+}
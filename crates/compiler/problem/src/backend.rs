@@ -0,0 +1,165 @@
+//! Problems the development backends run into while generating code.
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use roc_debug_flags::{dbg_set, ROC_PANIC_ON_UNIMPLEMENTED};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DevBackend {
+    Assembly,
+    Wasm,
+}
+
+/// Something a development backend can't generate code for yet, for example a low-level
+/// operation on a layout it doesn't handle. The LLVM backend has no such gaps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unimplemented {
+    pub backend: DevBackend,
+    /// What the backend was asked to generate, in the backend's own words.
+    pub construct: String,
+}
+
+/// A program a development backend can't generate code for, because of a limit of the backend
+/// or of the target rather than a `todo!`, like a stack frame too large to address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackendFailure {
+    pub backend: DevBackend,
+    /// Why, in the backend's own words.
+    pub reason: String,
+}
+
+/// Runs a development backend, turning the `todo!`s and `unimplemented!`s it hits into an
+/// `Unimplemented` problem instead of a panic and a stack trace. Other panics are compiler bugs,
+/// so they go through as usual.
+///
+/// Set `ROC_PANIC_ON_UNIMPLEMENTED` to get the panic anyway.
+pub fn catch_unimplemented<T>(
+    backend: DevBackend,
+    generate: impl FnOnce() -> T,
+) -> Result<T, Unimplemented> {
+    if dbg_set!(ROC_PANIC_ON_UNIMPLEMENTED) {
+        return Ok(generate());
+    }
+
+    install_quiet_hook();
+
+    CATCHING.with(|catching| catching.set(catching.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(generate));
+    CATCHING.with(|catching| catching.set(catching.get() - 1));
+
+    result.map_err(|payload| match unimplemented_construct(payload.as_ref()) {
+        Some(construct) => Unimplemented { backend, construct },
+        None => panic::resume_unwind(payload),
+    })
+}
+
+thread_local! {
+    /// How many calls to `catch_unimplemented` the current thread is inside of
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+static INSTALL_QUIET_HOOK: Once = Once::new();
+
+/// The default hook would print the panic message and a backtrace before we get to report it.
+/// So once per process, we put a hook in front of it that skips the `todo!`s and
+/// `unimplemented!`s that `catch_unimplemented` reports, and hands everything else on.
+/// Swapping hooks on every call instead would race with panics on other threads.
+fn install_quiet_hook() {
+    INSTALL_QUIET_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            let catching = CATCHING.with(|catching| catching.get() > 0);
+
+            if !catching || unimplemented_construct(info.payload()).is_none() {
+                previous_hook(info)
+            }
+        }));
+    });
+}
+
+/// The construct from the message of a `todo!` or `unimplemented!` panic.
+fn unimplemented_construct(payload: &(dyn Any + Send)) -> Option<String> {
+    let message = match payload.downcast_ref::<&str>() {
+        Some(message) => *message,
+        None => payload.downcast_ref::<String>()?.as_str(),
+    };
+
+    ["not yet implemented", "not implemented"]
+        .into_iter()
+        .find_map(|prefix| {
+            let rest = message.strip_prefix(prefix)?;
+            match rest.strip_prefix(": ") {
+                Some(construct) => Some(construct.to_string()),
+                None if rest.is_empty() => Some(String::new()),
+                None => None,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn todo_becomes_unimplemented() {
+        let result = catch_unimplemented(DevBackend::Wasm, || -> u32 {
+            todo!("NumAbs: layout {:?}", "I128")
+        });
+
+        assert_eq!(
+            result,
+            Err(Unimplemented {
+                backend: DevBackend::Wasm,
+                construct: "NumAbs: layout \"I128\"".to_string(),
+            })
+        );
+
+        let result = catch_unimplemented(DevBackend::Assembly, || -> u32 { unimplemented!() });
+
+        assert_eq!(
+            result,
+            Err(Unimplemented {
+                backend: DevBackend::Assembly,
+                construct: String::new(),
+            })
+        );
+
+        assert_eq!(catch_unimplemented(DevBackend::Wasm, || 42), Ok(42));
+    }
+
+    #[test]
+    fn catches_on_many_threads_at_once() {
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    catch_unimplemented(DevBackend::Wasm, || -> u32 { todo!("thread {i}") })
+                })
+            })
+            .collect();
+
+        for (i, thread) in threads.into_iter().enumerate() {
+            assert_eq!(
+                thread.join().unwrap(),
+                Err(Unimplemented {
+                    backend: DevBackend::Wasm,
+                    construct: format!("thread {i}"),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn other_panics_go_through() {
+        let result = panic::catch_unwind(|| {
+            catch_unimplemented(DevBackend::Assembly, || -> u32 {
+                panic!("index out of bounds")
+            })
+        });
+
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"index out of bounds"));
+    }
+}
@@ -61,7 +61,7 @@ impl Architecture {
     }
 }
 
-#[derive(Debug, Copy, Clone, EnumIter, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, EnumIter, PartialEq, Eq, Hash)]
 pub enum Target {
     LinuxX32,
     LinuxX64,
@@ -122,6 +122,46 @@ impl Target {
         self.architecture().ptr_alignment_bytes()
     }
 
+    /// Number of low bits of a heap pointer that are free to store a tag id.
+    ///
+    /// Heap allocations are at least pointer-aligned, so these bits are always zero.
+    pub const fn tag_id_pointer_bits(&self) -> u32 {
+        self.ptr_alignment_bytes().trailing_zeros()
+    }
+
+    /// Mask selecting the tag id bits of a tagged heap pointer.
+    pub const fn tag_id_pointer_mask(&self) -> u64 {
+        (1 << self.tag_id_pointer_bits()) - 1
+    }
+
+    /// Size in bytes of a builtin Str or List value: a pointer and two lengths.
+    pub const fn str_list_size(&self) -> usize {
+        self.ptr_size() * 3
+    }
+
+    /// Alignment in bytes of 128-bit integers and decimals.
+    ///
+    /// The C ABI aligns these to 16 bytes wherever it supports them natively, according to
+    /// https://reviews.llvm.org/D28990#655487. Wasm has no 128-bit type, so they are a pair of
+    /// 64-bit words there.
+    pub const fn i128_alignment_bytes(&self) -> usize {
+        use Target::*;
+        match self {
+            LinuxX32 | LinuxX64 | LinuxArm64 | MacX64 | MacArm64 | WinX32 | WinX64 | WinArm64 => 16,
+            Wasm32 => 8,
+        }
+    }
+
+    /// The largest value, in bytes, that the C calling convention returns in registers.
+    /// Anything bigger is written to memory the caller provides.
+    pub const fn max_return_in_registers_size(&self) -> usize {
+        use Target::*;
+        match self {
+            LinuxX64 | MacX64 | LinuxArm64 | MacArm64 | WinArm64 => 16,
+            LinuxX32 | WinX32 | WinX64 | Wasm32 => 8,
+        }
+    }
+
     pub const fn object_file_ext(&self) -> &str {
         use Target::*;
         match self {
@@ -140,6 +180,16 @@ impl Target {
         }
     }
 
+    pub const fn dynamic_library_file_ext(&self) -> &str {
+        use Target::*;
+        match self {
+            LinuxX32 | LinuxX64 | LinuxArm64 => "so",
+            MacX64 | MacArm64 => "dylib",
+            WinX32 | WinX64 | WinArm64 => "dll",
+            Wasm32 => "wasm",
+        }
+    }
+
     pub const fn executable_file_ext(&self) -> Option<&str> {
         use Target::*;
         match self {
@@ -7,11 +7,21 @@ use std::path::PathBuf;
 use wasi_libc_sys::{WASI_COMPILER_RT_PATH, WASI_LIBC_PATH};
 
 const PLATFORM_FILENAME: &str = "wasm_test_platform";
+/// The same platform, for apps that bring their own allocator
+const APP_ALLOCATOR_PLATFORM_FILENAME: &str = "wasm_test_platform_app_allocator";
 
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
-    if feature_is_enabled("gen-wasm") || feature_is_enabled("gen-llvm-wasm") {
-        build_wasm_test_host();
+    if feature_is_enabled("gen-wasm")
+        || feature_is_enabled("gen-llvm-wasm")
+        || feature_is_enabled("gen-diff")
+    {
+        build_wasm_test_host(PLATFORM_FILENAME, &[], &[]);
+        build_wasm_test_host(
+            APP_ALLOCATOR_PLATFORM_FILENAME,
+            &["-DAPP_ALLOCATOR"],
+            &["--wrap=roc_alloc", "--wrap=roc_dealloc"],
+        );
         build_wasm_linking_test_host();
     }
 }
@@ -83,7 +93,7 @@ fn build_wasm_linking_test_host() {
     ]);
 }
 
-fn build_wasm_test_host() {
+fn build_wasm_test_host(filename: &str, c_flags: &[&str], link_flags: &[&str]) {
     let mut source_path = PathBuf::new()
         .join("src")
         .join("helpers")
@@ -94,15 +104,16 @@ fn build_wasm_test_host() {
     let out_dir = env::var("OUT_DIR").unwrap();
 
     // Create an object file with relocations
-    let platform_path = build_wasm_platform(&out_dir, source_path.to_str().unwrap());
+    let platform_path =
+        build_wasm_platform(&out_dir, filename, source_path.to_str().unwrap(), c_flags);
 
-    let mut outfile = PathBuf::from(&out_dir).join(PLATFORM_FILENAME);
+    let mut outfile = PathBuf::from(&out_dir).join(filename);
     outfile.set_extension("wasm");
 
     let builtins_host_tempfile = roc_bitcode::host_wasm_tempfile()
         .expect("failed to write host builtins object to tempfile");
 
-    run_zig(&[
+    let mut args = vec![
         "wasm-ld",
         builtins_host_tempfile.path().to_str().unwrap(),
         platform_path.to_str().unwrap(),
@@ -112,25 +123,35 @@ fn build_wasm_test_host() {
         outfile.to_str().unwrap(),
         "--no-entry",
         "--relocatable",
-    ]);
+    ];
+    args.extend_from_slice(link_flags);
+    run_zig(&args);
 
     // Extend the lifetime of the tempfile so it doesn't get dropped
     // (and thus deleted) before the Zig process is done using it!
     let _ = builtins_host_tempfile;
 }
 
-fn build_wasm_platform(out_dir: &str, source_path: &str) -> PathBuf {
-    let mut outfile = PathBuf::from(out_dir).join(PLATFORM_FILENAME);
+fn build_wasm_platform(
+    out_dir: &str,
+    filename: &str,
+    source_path: &str,
+    c_flags: &[&str],
+) -> PathBuf {
+    let mut outfile = PathBuf::from(out_dir).join(filename);
     outfile.set_extension("wasm");
 
-    run_zig(&[
+    let emit_bin = format!("-femit-bin={}", outfile.to_str().unwrap());
+    let mut args = vec![
         "build-lib",
         "-target",
         "wasm32-wasi-musl",
         "-lc",
         source_path,
-        &format!("-femit-bin={}", outfile.to_str().unwrap()),
-    ]);
+        &emit_bin,
+    ];
+    args.extend_from_slice(c_flags);
+    run_zig(&args);
 
     outfile
 }
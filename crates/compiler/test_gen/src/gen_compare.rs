@@ -190,6 +190,57 @@ fn record() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn record_of_integers() {
+    assert_evals_to!(
+        "{ x: 1i64, y: 2u32, z: 3u32 } == { x: 1i64, y: 2u32, z: 3u32 }",
+        true,
+        bool
+    );
+    assert_evals_to!(
+        "{ x: 1i64, y: 2u32, z: 3u32 } != { x: 1i64, y: 2u32, z: 4u32 }",
+        true,
+        bool
+    );
+    assert_evals_to!(
+        "{ a: 1u8, b: { c: 2u8, d: 3u16 }, e: 4dec } == { a: 1u8, b: { c: 2u8, d: 3u16 }, e: 4dec }",
+        true,
+        bool
+    );
+    assert_evals_to!(
+        "{ a: 1u8, b: { c: 2u8, d: 3u16 }, e: 4dec } == { a: 1u8, b: { c: 5u8, d: 3u16 }, e: 4dec }",
+        false,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn records_of_integers_with_same_size() {
+    // On Wasm, these share one comparison function per size and alignment
+    assert_evals_to!(
+        indoc!(
+            r#"
+                a = { x: 1u64, y: 2u64 } == { x: 1u64, y: 2u64 }
+                b = { p: 1u32, q: 2u32, r: 3u32, s: 4u32 } == { p: 1u32, q: 2u32, r: 3u32, s: 5u32 }
+                c = { x: 1i64, y: 2i64 } == { x: 1i64, y: 3i64 }
+
+                a && !b && !c
+                "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn record_of_integers_with_padding() {
+    assert_evals_to!("{ x: 1u64, y: 2u8 } == { x: 1u64, y: 2u8 }", true, bool);
+    assert_evals_to!("{ x: 1u64, y: 2u8 } == { x: 1u64, y: 3u8 }", false, bool);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn unit() {
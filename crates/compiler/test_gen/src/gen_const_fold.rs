@@ -0,0 +1,152 @@
+//! Programs whose results `fold_constants` can work out at compile time. Each one runs both with
+//! and without folding, so the literals the pass creates must match what the backends compute.
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_folds_to;
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_folds_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_folds_to;
+
+#[allow(unused_imports)]
+use indoc::indoc;
+#[allow(unused_imports)]
+use roc_std::{RocDec, RocList, RocStr};
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn dec_mul() {
+    assert_folds_to!(
+        indoc!(
+            r"
+                x : Dec
+                x = 1.5
+
+                y : Dec
+                y = 2.25
+
+                x * y
+            "
+        ),
+        RocDec::from_str("3.375").unwrap(),
+        RocDec
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn dec_div() {
+    assert_folds_to!(
+        indoc!(
+            r"
+                x : Dec
+                x = 10
+
+                y : Dec
+                y = 3
+
+                x / y
+            "
+        ),
+        RocDec::from_str("3.333333333333333333").unwrap(),
+        RocDec
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn f32_rounding() {
+    assert_folds_to!("0.1f32 + 0.2f32", 0.1f32 + 0.2f32, f32);
+    assert_folds_to!("1f32 / 3f32", 1f32 / 3f32, f32);
+    assert_folds_to!("0.1f32 * 3f32", 0.1f32 * 3f32, f32);
+    assert_folds_to!("16777216f32 + 1f32", 16777216f32, f32);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn num_to_str_of_each_int_width() {
+    assert_folds_to!("Num.toStr 255u8", RocStr::from("255"), RocStr);
+    assert_folds_to!("Num.toStr -128i8", RocStr::from("-128"), RocStr);
+    assert_folds_to!("Num.toStr 65535u16", RocStr::from("65535"), RocStr);
+    assert_folds_to!("Num.toStr -32768i16", RocStr::from("-32768"), RocStr);
+    assert_folds_to!(
+        "Num.toStr 4294967295u32",
+        RocStr::from("4294967295"),
+        RocStr
+    );
+    assert_folds_to!(
+        "Num.toStr -2147483648i32",
+        RocStr::from("-2147483648"),
+        RocStr
+    );
+    assert_folds_to!(
+        "Num.toStr 18446744073709551615u64",
+        RocStr::from("18446744073709551615"),
+        RocStr
+    );
+    assert_folds_to!(
+        "Num.toStr -9223372036854775808i64",
+        RocStr::from("-9223372036854775808"),
+        RocStr
+    );
+    assert_folds_to!(
+        "Num.toStr 340282366920938463463374607431768211455u128",
+        RocStr::from("340282366920938463463374607431768211455"),
+        RocStr
+    );
+    assert_folds_to!(
+        "Num.toStr -12345678901234567890123456789i128",
+        RocStr::from("-12345678901234567890123456789"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn str_concat() {
+    assert_folds_to!(
+        indoc!(
+            r#"
+                s = Str.concat "A long enough string " "to be heap-allocated"
+
+                [s, s, s]
+            "#
+        ),
+        RocList::from_slice(&[
+            RocStr::from("A long enough string to be heap-allocated"),
+            RocStr::from("A long enough string to be heap-allocated"),
+            RocStr::from("A long enough string to be heap-allocated"),
+        ]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn str_concat_is_immortal_literal() {
+    use crate::helpers::wasm::{assert_wasm_refcounts_help, EnvOptions};
+    use crate::helpers::{RefCount::*, RefCountLoc::*};
+
+    let src = indoc!(
+        r#"
+            s = Str.concat "A long enough string " "to be heap-allocated"
+
+            [s, s, s]
+        "#
+    );
+    let options = EnvOptions {
+        fold_constants: true,
+        ..EnvOptions::DEFAULT
+    };
+
+    // Without folding, `s` is allocated and the list holds 3 references to it. Folded, it's a
+    // literal in the data section that is never allocated or refcounted, so only the list is.
+    let refcounts = assert_wasm_refcounts_help::<RocList<RocStr>>(
+        src,
+        Default::default(),
+        &[AfterSize],
+        options,
+    );
+    assert_eq!(refcounts, Ok(vec![Live(1)]));
+}
@@ -0,0 +1,66 @@
+//! Differential tests: each program runs on both the dev and wasm backends,
+//! and the test fails if they produce different results.
+//!
+//! Run with `cargo test-gen-diff`.
+use crate::helpers::diff::{assert_backends_agree, assert_backends_agree_on_inputs};
+
+#[allow(unused_imports)]
+use indoc::indoc;
+#[allow(unused_imports)]
+use roc_std::{RocList, RocStr};
+
+#[test]
+fn int_arithmetic() {
+    assert_backends_agree!("1 + 2 * 3 - 4", 3, i64);
+}
+
+#[test]
+fn wrapping_add_i64() {
+    assert_backends_agree_on_inputs!(i64, 32, i64, |x| format!("Num.addWrap {x} 7i64"));
+}
+
+#[test]
+fn wrapping_mul_u8() {
+    assert_backends_agree_on_inputs!(u8, 32, u8, |x| format!("Num.mulWrap {x} 3u8"));
+}
+
+#[test]
+fn shift_right_i32() {
+    assert_backends_agree_on_inputs!(i32, 32, i32, |x| format!("Num.shiftRightBy {x} 3"));
+}
+
+#[test]
+fn compare_u64() {
+    assert_backends_agree_on_inputs!(u64, 32, bool, |x| format!("{x} > 9223372036854775808u64"));
+}
+
+#[test]
+fn int_to_str() {
+    assert_backends_agree_on_inputs!(i64, 16, RocStr, |x| format!("Num.toStr {x}"));
+}
+
+#[test]
+fn checked_division() {
+    assert_backends_agree_on_inputs!(i64, 16, i64, |x| {
+        indoc!(
+            r"
+            when Num.divTruncChecked 1000i64 X is
+                Ok n -> n
+                Err DivByZero -> -1
+            "
+        )
+        .replace('X', x)
+    });
+}
+
+#[test]
+fn list_of_inputs() {
+    assert_backends_agree_on_inputs!(u8, 8, RocList<u8>, |x| format!(
+        "List.repeat {x} 3 |> List.append 42"
+    ));
+}
+
+#[test]
+fn both_crash() {
+    assert_backends_agree!(r#"crash "hello crash""#, i64);
+}
@@ -5,7 +5,7 @@ use crate::helpers::llvm::assert_evals_to;
 use crate::helpers::dev::assert_evals_to;
 
 #[cfg(feature = "gen-wasm")]
-use crate::helpers::wasm::assert_evals_to;
+use crate::helpers::wasm::assert_evals_to_with_each_setup as assert_evals_to;
 
 use crate::helpers::with_larger_debug_stack;
 //use crate::assert_wasm_evals_to as assert_evals_to;
@@ -3920,7 +3920,7 @@ mod pattern_match {
     use crate::helpers::llvm::assert_evals_to;
 
     #[cfg(feature = "gen-wasm")]
-    use crate::helpers::wasm::assert_evals_to;
+    use crate::helpers::wasm::assert_evals_to_with_each_setup as assert_evals_to;
 
     #[cfg(feature = "gen-dev")]
     use crate::helpers::dev::assert_evals_to;
@@ -3912,6 +3912,68 @@ fn nan_f64() {
     assert_evals_to!(r"Num.nanF64", true, f64, |f: f64| f.is_nan());
 }
 
+#[cfg(feature = "gen-wasm")]
+const CANONICAL_NANS: crate::helpers::wasm::EnvOptions = crate::helpers::wasm::EnvOptions {
+    canonicalize_nans: true,
+    ..crate::helpers::wasm::EnvOptions::DEFAULT
+};
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn canonical_nan_f64() {
+    let bits = crate::helpers::wasm::assert_evals_to_help::<f64>(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            divide : F64, F64 -> F64
+            divide = \a, b -> a / b
+
+            main = divide 0 0
+            "#
+        ),
+        std::marker::PhantomData,
+        CANONICAL_NANS,
+    )
+    .map(f64::to_bits);
+
+    assert_eq!(bits, Ok(0x7ff8_0000_0000_0000));
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn canonical_nan_f32() {
+    let bits = crate::helpers::wasm::assert_evals_to_help::<f32>(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            root : F32 -> F32
+            root = \x -> Num.sqrt x
+
+            main = root -1
+            "#
+        ),
+        std::marker::PhantomData,
+        CANONICAL_NANS,
+    )
+    .map(f32::to_bits);
+
+    assert_eq!(bits, Ok(0x7fc0_0000));
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn canonical_nans_keep_other_floats() {
+    let result = crate::helpers::wasm::assert_evals_to_help::<f64>(
+        "1.5f64 / -3",
+        std::marker::PhantomData,
+        CANONICAL_NANS,
+    );
+
+    assert_eq!(result, Ok(-0.5));
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn infinity_f32() {
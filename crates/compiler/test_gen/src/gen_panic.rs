@@ -8,7 +8,7 @@ use crate::helpers::llvm::assert_evals_to;
 use crate::helpers::wasm::assert_evals_to;
 
 #[cfg(feature = "gen-dev")]
-use crate::helpers::dev::assert_evals_to;
+use crate::helpers::dev::{asm_evals_to, assert_evals_to, identity, EnvOptions};
 
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
@@ -86,3 +86,80 @@ fn crash_in_passed_closure() {
         RocList<u8>
     );
 }
+
+#[cfg(feature = "gen-dev")]
+const BRANCH_TARGETS: EnvOptions = EnvOptions {
+    atomic_refcounts: false,
+    hardening: roc_gen_dev::Hardening {
+        branch_targets: true,
+        retpolines: false,
+    },
+};
+
+#[cfg(all(feature = "gen-dev", target_arch = "x86_64"))]
+const RETPOLINES: EnvOptions = EnvOptions {
+    atomic_refcounts: false,
+    hardening: roc_gen_dev::Hardening {
+        branch_targets: false,
+        retpolines: true,
+    },
+};
+
+#[test]
+#[cfg(feature = "gen-dev")]
+fn branch_targets_passed_closure() {
+    asm_evals_to::<RocList<u8>, _, _>(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = List.map [1u8, 2, 3] \n -> n * 2
+            "#
+        ),
+        RocList::from_slice(&[2u8, 4, 6]),
+        identity,
+        false,
+        false,
+        BRANCH_TARGETS,
+    );
+}
+
+#[test]
+#[cfg(feature = "gen-dev")]
+#[should_panic = r#"User crash with message: "no new even primes""#]
+fn branch_targets_crash_in_passed_closure() {
+    asm_evals_to::<RocList<u8>, _, _>(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = List.map [1u8, 2, 3] \n -> if n == 2 then crash "no new even primes" else n
+            "#
+        ),
+        RocList::from_slice(&[1u8]),
+        identity,
+        false,
+        false,
+        BRANCH_TARGETS,
+    );
+}
+
+#[test]
+#[cfg(all(feature = "gen-dev", target_arch = "x86_64"))]
+#[should_panic = r#"User crash with message: "no new even primes""#]
+fn retpolines_crash_in_passed_closure() {
+    asm_evals_to::<RocList<u8>, _, _>(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = List.map [1u8, 2, 3] \n -> if n == 2 then crash "no new even primes" else n
+            "#
+        ),
+        RocList::from_slice(&[1u8]),
+        identity,
+        false,
+        false,
+        RETPOLINES,
+    );
+}
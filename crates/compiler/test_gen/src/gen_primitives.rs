@@ -5,7 +5,7 @@ use crate::helpers::llvm::assert_evals_to;
 use crate::helpers::dev::assert_evals_to;
 
 #[cfg(feature = "gen-wasm")]
-use crate::helpers::wasm::assert_evals_to;
+use crate::helpers::wasm::assert_evals_to_with_each_setup as assert_evals_to;
 
 use indoc::indoc;
 #[allow(unused_imports)]
@@ -4728,3 +4728,156 @@ fn issue_6139_prefixes() {
         RocList<RocStr>
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn dbg_returns_its_value() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x = 42
+
+            dbg x
+
+            dbg "a long string, which does not fit in a small str"
+
+            x + 2
+            "#
+        ),
+        44,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn procs_reached_only_indirectly_are_kept() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            double : I64 -> I64
+            double = \x -> x * 2
+
+            unused : I64 -> I64
+            unused = \x -> x + 1
+
+            apply : (I64 -> I64), I64 -> I64
+            apply = \f, x -> f x
+
+            main =
+                doubled = List.map [1, 2, 3] double
+                total = List.walk doubled 0 Num.add
+
+                apply (\x -> x + total) 1
+            "#
+        ),
+        13,
+        i64
+    );
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn patched_call_goes_to_new_proc() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        double : I64 -> I64
+        double = \x -> x * 2
+
+        triple : I64 -> I64
+        triple = \x -> x * 3
+
+        main = double 7 + triple 0
+        "#
+    );
+
+    let unpatched = crate::helpers::wasm::patch_and_run(src, std::marker::PhantomData, &[]);
+    assert_eq!(unpatched, Ok(14i64));
+
+    let patched =
+        crate::helpers::wasm::patch_and_run(src, std::marker::PhantomData, &[("double", "triple")]);
+    assert_eq!(patched, Ok(21i64));
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn oversized_procs_are_split() {
+    use crate::helpers::wasm::{assert_evals_to_help, setups, EnvOptions};
+    use std::marker::PhantomData;
+
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        step : I64, [Add I64, Mul I64, Neg] -> I64
+        step = \acc, op ->
+            when op is
+                Add n -> acc + n
+                Mul n -> acc * n
+                Neg -> -acc
+
+        main : I64
+        main =
+            a = step 1 (Add 2)
+            b = step a (Mul 3)
+            c = step b Neg
+            d = if c < 0 then step c (Add 100) else step c (Mul 2)
+            e = step d (Mul d)
+            f = List.walk [a, b, c, d, e] 0 Num.add
+
+            when f is
+                0 -> a
+                1 -> b
+                _ -> f + e
+        "#
+    );
+
+    // Far below the default, so that `main` and `step` are both split into several functions
+    for options in setups() {
+        let options = EnvOptions {
+            max_function_stmts: Some(10),
+            ..options
+        };
+        let result = assert_evals_to_help(src, PhantomData, options);
+        assert_eq!(result, Ok(16656i64), "With {options:?}");
+    }
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn heap_profiling_records_allocation_sites() {
+    use crate::helpers::wasm::{alloc_sites, assert_evals_to_help, EnvOptions};
+    use std::marker::PhantomData;
+
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        LinkedList : [Nil, Cons I64 LinkedList]
+
+        wrap : I64 -> LinkedList
+        wrap = \x -> Cons x Nil
+
+        main : I64
+        main =
+            when wrap 42 is
+                Cons x _ -> x
+                Nil -> 0
+        "#
+    );
+
+    assert_eq!(
+        alloc_sites(src, PhantomData::<i64>),
+        Ok(vec![("Test.roc:6:14".to_string(), "tag union".to_string())])
+    );
+
+    let options = EnvOptions {
+        heap_profiling: true,
+        ..EnvOptions::DEFAULT
+    };
+    assert_eq!(assert_evals_to_help(src, PhantomData, options), Ok(42i64));
+}
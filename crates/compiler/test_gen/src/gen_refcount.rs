@@ -1,6 +1,9 @@
 #[cfg(feature = "gen-wasm")]
 use crate::helpers::{wasm::assert_refcounts, RefCount::*, RefCountLoc::*};
 
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::{asm_evals_to, identity, EnvOptions};
+
 #[allow(unused_imports)]
 use indoc::indoc;
 
@@ -47,6 +50,28 @@ fn str_dealloc() {
     );
 }
 
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn str_literal_suffix_concat() {
+    // `child` is a seamless slice of `parent`'s bytes in the data section. Neither is allocated,
+    // so the only refcounts are for the concatenated string and the list.
+    assert_refcounts!(
+        indoc!(
+            r#"
+                parent = "The quick brown fox jumps over the lazy dog"
+                child = "jumps over the lazy dog"
+
+                [parent, Str.concat child "!"]
+            "#
+        ),
+        RocList<RocStr>,
+        &[
+            (StandardRC, Live(1)), // Str.concat child "!"
+            (AfterSize, Live(1))   // result
+        ]
+    );
+}
+
 #[test]
 #[cfg(feature = "gen-wasm")]
 fn str_to_utf8() {
@@ -754,6 +779,59 @@ fn boxed_str_dec() {
     );
 }
 
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn nullable_pointer_niche_dec() {
+    assert_refcounts!(
+        indoc!(
+            r#"
+                Maybe a : [Some (Box a), None]
+
+                s = Str.concat "A long enough string " "to be heap-allocated"
+
+                maybe : Maybe Str
+                maybe = Some (Box.box s)
+
+                when maybe is
+                    Some boxed -> Box.unbox boxed
+                    None -> ""
+            "#
+        ),
+        RocStr,
+        &[
+            (StandardRC, Live(1)),     // s
+            (StandardRC, Deallocated), // Some
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn nullable_pointer_niche_drop() {
+    assert_refcounts!(
+        indoc!(
+            r#"
+                Maybe a : [Some (Box a), None]
+
+                s = Str.concat "A long enough string " "to be heap-allocated"
+
+                maybe : Maybe Str
+                maybe = Some (Box.box s)
+
+                if Bool.false then
+                    ReturnTheUnion maybe
+                else
+                    DeallocateEverything
+            "#
+        ),
+        (i32, i32),
+        &[
+            (StandardRC, Deallocated), // s
+            (StandardRC, Deallocated), // Some
+        ]
+    );
+}
+
 #[test]
 #[cfg(feature = "gen-wasm")]
 fn non_nullable_unwrapped_alignment_8() {
@@ -857,3 +935,57 @@ fn basic_cli_parser() {
         ]
     );
 }
+
+#[cfg(feature = "gen-dev")]
+const ATOMIC_REFCOUNTS: EnvOptions = EnvOptions {
+    atomic_refcounts: true,
+    hardening: roc_gen_dev::Hardening {
+        branch_targets: false,
+        retpolines: false,
+    },
+};
+
+#[test]
+#[cfg(feature = "gen-dev")]
+fn atomic_refcounts_shared_str() {
+    asm_evals_to::<RocStr, _, _>(
+        indoc!(
+            r#"
+            s = Str.concat "A long enough string " "to be heap-allocated"
+
+            Str.joinWith [s, s, s] ", "
+            "#
+        ),
+        RocStr::from(
+            "A long enough string to be heap-allocated, \
+            A long enough string to be heap-allocated, \
+            A long enough string to be heap-allocated",
+        ),
+        identity,
+        false,
+        false,
+        ATOMIC_REFCOUNTS,
+    );
+}
+
+#[test]
+#[cfg(feature = "gen-dev")]
+fn atomic_refcounts_nested_lists() {
+    asm_evals_to::<i64, _, _>(
+        indoc!(
+            r#"
+            list = List.repeat 7 100
+            nested = [list, list, List.append list 8]
+
+            sum = List.walk nested 0 \total, inner -> total + List.sum inner
+
+            sum + Num.toI64 (List.len (List.concat list list))
+            "#
+        ),
+        3 * 700 + 8 + 200,
+        identity,
+        false,
+        false,
+        ATOMIC_REFCOUNTS,
+    );
+}
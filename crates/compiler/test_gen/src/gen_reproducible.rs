@@ -0,0 +1,182 @@
+//! Building the same program twice must give the same bytes, so that a binary can be checked
+//! against the source it claims to come from.
+//!
+//! The app imports several modules so that they load on different threads, and finish in a
+//! different order from one build to the next.
+use bumpalo::Bump;
+use roc_load::{ExecutionMode, LoadConfig, MonomorphizedModule, Threading};
+use roc_packaging::cache::RocCacheDir;
+use roc_solve::FunctionKind;
+use roc_target::Target;
+use std::path::Path;
+
+const MODULES: &[&str] = &["Alpha", "Beta", "Gamma", "Delta", "Epsilon"];
+
+fn write_app(dir: &Path) {
+    let mut main = String::from("app \"test\" provides [main] to \"./platform\"\n\n");
+    let mut calls = Vec::new();
+
+    for module in MODULES {
+        let src = indoc::formatdoc!(
+            r#"
+            interface {module}
+                exposes [describe]
+                imports []
+
+            describe : List I64 -> Str
+            describe = \numbers ->
+                labelled = List.map numbers (\n -> {{ n, label: "{module}" }})
+                big = List.keepIf labelled (\r -> r.n > 1)
+
+                Str.joinWith (List.map big (\r -> Str.concat r.label (Num.toStr r.n))) ","
+            "#
+        );
+
+        std::fs::write(dir.join(format!("{module}.roc")), src).unwrap();
+        main.push_str(&format!("import {module}\n"));
+        calls.push(format!("{module}.describe [1, 2, 3]"));
+    }
+
+    let calls = calls.join(", ");
+    main.push_str(&format!("\nmain = Str.joinWith [{calls}] \" \"\n"));
+    std::fs::write(dir.join("Main.roc"), main).unwrap();
+}
+
+fn load<'a>(arena: &'a Bump, dir: &Path, target: Target) -> MonomorphizedModule<'a> {
+    let load_config = LoadConfig {
+        target,
+        render: roc_reporting::report::RenderTarget::ColorTerminal,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        threading: Threading::AllAvailable,
+        exec_mode: ExecutionMode::Executable,
+        function_kind: FunctionKind::LambdaSet,
+    };
+
+    roc_load::load_and_monomorphize(
+        arena,
+        dir.join("Main.roc"),
+        None,
+        RocCacheDir::Disallowed,
+        load_config,
+    )
+    .unwrap_or_else(|_| panic!("failed to load the test app"))
+}
+
+#[cfg(feature = "gen-dev")]
+fn dev_object(dir: &Path) -> Vec<u8> {
+    let arena = Bump::new();
+    let target: Target = target_lexicon::Triple::host().into();
+
+    let MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        exposed_to_host,
+        mut layout_interner,
+        ..
+    } = load(&arena, dir, target);
+
+    let env = roc_gen_dev::Env {
+        arena: &arena,
+        module_id,
+        exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
+        lazy_literals: false,
+        mode: roc_gen_dev::AssemblyBackendMode::Binary,
+        atomic_refcounts: false,
+        hardening: Default::default(),
+        stack_maps: false,
+    };
+
+    roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures)
+        .unwrap_or_else(|error| panic!("{error}"))
+        .write()
+        .expect("failed to build output object")
+}
+
+#[cfg(feature = "gen-wasm")]
+fn wasm_module(dir: &Path, codegen_threads: usize) -> Vec<u8> {
+    let arena = Bump::new();
+
+    let MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        exposed_to_host,
+        mut layout_interner,
+        ..
+    } = load(&arena, dir, Target::Wasm32);
+
+    let env = roc_gen_wasm::Env {
+        arena: &arena,
+        module_id,
+        exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
+        stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        canonicalize_nans: false,
+        heap_profiling: None,
+        max_function_stmts: roc_gen_wasm::Env::DEFAULT_MAX_FUNCTION_STMTS,
+        max_inline_stmts: roc_gen_wasm::Env::DEFAULT_MAX_INLINE_STMTS,
+        multi_value_returns: true,
+        patchable_calls: false,
+        allocator: roc_gen_wasm::Allocator::Host,
+        codegen_threads,
+    };
+
+    let host_module = roc_gen_wasm::parse_host(&arena, crate::helpers::wasm::test_host_bytes())
+        .unwrap_or_else(|e| panic!("failed to parse the test host: {}", e.message));
+
+    roc_gen_wasm::build_app_binary(
+        &env,
+        &mut layout_interner,
+        &mut interns,
+        host_module,
+        procedures,
+    )
+}
+
+#[test]
+#[cfg(feature = "gen-dev")]
+fn dev_object_is_reproducible() {
+    let dir = tempfile::tempdir().unwrap();
+    write_app(dir.path());
+
+    let first = dev_object(dir.path());
+
+    for _ in 0..4 {
+        assert!(
+            dev_object(dir.path()) == first,
+            "building the same app twice gave different object files"
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn wasm_module_is_reproducible() {
+    let dir = tempfile::tempdir().unwrap();
+    write_app(dir.path());
+
+    let first = wasm_module(dir.path(), 4);
+
+    for _ in 0..4 {
+        assert!(
+            wasm_module(dir.path(), 4) == first,
+            "building the same app twice gave different Wasm modules"
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn wasm_module_does_not_depend_on_codegen_threads() {
+    let dir = tempfile::tempdir().unwrap();
+    write_app(dir.path());
+
+    let single_threaded = wasm_module(dir.path(), 1);
+
+    for codegen_threads in [2, 4, 16] {
+        assert!(
+            wasm_module(dir.path(), codegen_threads) == single_threaded,
+            "generating procs on {codegen_threads} threads gave a different Wasm module than on one"
+        );
+    }
+}
@@ -2327,3 +2327,61 @@ fn recursive_tag_id_in_allocation_eq() {
         bool
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn nullable_pointer_niche_build_and_match() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            Maybe a : [Some (Box a), None]
+
+            withDefault : Maybe a, a -> a
+            withDefault = \maybe, default ->
+                when maybe is
+                    Some boxed -> Box.unbox boxed
+                    None -> default
+
+            main =
+                some : Maybe I64
+                some = Some (Box.box 42)
+
+                none : Maybe I64
+                none = None
+
+                withDefault some 0 + withDefault none 1
+            "#
+        ),
+        43,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn nullable_pointer_niche_refcounted_payload() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            Maybe a : [Some (Box a), None]
+
+            main =
+                s = Str.concat "A long enough string " "to be heap-allocated"
+
+                maybes : List (Maybe Str)
+                maybes = [Some (Box.box s), None, Some (Box.box "short")]
+
+                List.walk maybes "" \state, maybe ->
+                    when maybe is
+                        Some boxed -> Str.concat state (Box.unbox boxed)
+                        None -> Str.concat state ", "
+            "#
+        ),
+        RocStr::from("A long enough string to be heap-allocated, short"),
+        RocStr
+    );
+}
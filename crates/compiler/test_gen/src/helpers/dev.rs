@@ -30,12 +30,22 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
+/// Options of [roc_gen_dev::Env] that are off by default, which a test can turn on
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvOptions {
+    pub atomic_refcounts: bool,
+    pub hardening: roc_gen_dev::Hardening,
+    /// Fold constants before code generation, as optimized builds do
+    pub fold_constants: bool,
+}
+
 #[allow(dead_code)]
 pub fn helper(
     arena: &bumpalo::Bump,
     src: &str,
     _leak: bool,
     lazy_literals: bool,
+    options: EnvOptions,
 ) -> (String, Vec<roc_problem::can::Problem>, Library) {
     use std::path::PathBuf;
 
@@ -75,6 +85,17 @@ pub fn helper(
 
     let mut loaded = loaded.expect("failed to load module");
 
+    if options.fold_constants {
+        roc_mono::const_fold::fold_constants(
+            arena,
+            &loaded.layout_interner,
+            &mut loaded.procedures,
+        );
+    }
+
+    // `roc build` drops unreachable specializations before code generation, so do the same here
+    loaded.remove_unreachable_procs();
+
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
         module_id,
@@ -193,11 +214,15 @@ pub fn helper(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Test,
+        atomic_refcounts: options.atomic_refcounts,
+        hardening: options.hardening,
+        stack_maps: false,
     };
 
     let target = target_lexicon::Triple::host().into();
     let module_object =
-        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures);
+        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures)
+            .unwrap_or_else(|error| panic!("{error}"));
 
     let module_out = module_object
         .write()
@@ -210,8 +235,6 @@ pub fn helper(
     let (mut child, dylib_path) = link(
         target,
         app_o_file.clone(),
-        // Long term we probably want a smarter way to link in zig builtins.
-        // With the current method all methods are kept and it adds about 100k to all outputs.
         &[
             app_o_file.to_str().unwrap(),
             builtins_host_tempfile.path().to_str().unwrap(),
@@ -317,12 +340,14 @@ impl<T: Sized> From<RocCallResult<T>> for Result<T, (String, CrashTag)> {
 }
 
 // only used in tests
+#[allow(dead_code)]
 pub(crate) fn asm_evals_to<T, U, F>(
     src: &str,
     expected: U,
     transform: F,
     leak: bool,
     lazy_literals: bool,
+    options: EnvOptions,
 ) where
     U: PartialEq + std::fmt::Debug,
     F: FnOnce(T) -> U,
@@ -331,7 +356,7 @@ pub(crate) fn asm_evals_to<T, U, F>(
 
     let arena = Bump::new();
     let (_main_fn_name, errors, lib) =
-        crate::helpers::dev::helper(&arena, src, leak, lazy_literals);
+        crate::helpers::dev::helper(&arena, src, leak, lazy_literals, options);
 
     let result = crate::helpers::dev::run_test_main::<T>(&lib);
 
@@ -360,6 +385,7 @@ pub(crate) fn asm_evals_to<T, U, F>(
     }
 }
 
+#[allow(dead_code)]
 pub(crate) fn identity<T>(x: T) -> T {
     x
 }
@@ -391,9 +417,34 @@ macro_rules! assert_evals_to {
             $transform,
             $leak,
             $lazy_literals,
+            Default::default(),
         );
     };
 }
 
+/// Like `assert_evals_to`, but checks the result both with and without constant folding.
+#[allow(unused_macros)]
+macro_rules! assert_folds_to {
+    ($src:expr, $expected:expr, $ty:ty) => {{
+        for fold_constants in [false, true] {
+            let options = $crate::helpers::dev::EnvOptions {
+                fold_constants,
+                ..Default::default()
+            };
+            $crate::helpers::dev::asm_evals_to::<$ty, _, _>(
+                $src,
+                $expected,
+                $crate::helpers::dev::identity,
+                true,
+                false,
+                options,
+            );
+        }
+    }};
+}
+
 #[allow(unused_imports)]
 pub(crate) use assert_evals_to;
+
+#[allow(unused_imports)]
+pub(crate) use assert_folds_to;
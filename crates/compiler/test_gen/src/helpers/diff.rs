@@ -0,0 +1,182 @@
+//! Differential testing between the dev backend and the wasm backend.
+//!
+//! The same Roc source is compiled by gen_dev (and run natively via a dylib) and by gen_wasm
+//! (and run in our wasm interpreter). Neither backend is treated as the source of truth: any
+//! difference in the result, or a crash in only one of them, is reported as a failure.
+//! This catches silent miscompiles that would pass a hand-written `assert_evals_to`
+//! because the expected value in the test was copied from a buggy backend.
+use crate::helpers::from_wasm32_memory::FromWasm32Memory;
+use roc_gen_wasm::wasm32_result::Wasm32Result;
+use roc_mono::ir::CrashTag;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// What happened when one backend ran a program.
+/// Crash messages are formatted the same way for both backends, so they can be compared.
+pub type Outcome<T> = Result<T, String>;
+
+fn crash_message(msg: &str, tag: CrashTag) -> String {
+    match tag {
+        CrashTag::Roc => format!(r#"Roc failed with message: "{msg}""#),
+        CrashTag::User => format!(r#"User crash with message: "{msg}""#),
+    }
+}
+
+pub fn run_dev<T>(src: &str) -> Outcome<T> {
+    let arena = bumpalo::Bump::new();
+    let (_main_fn_name, _errors, lib) =
+        crate::helpers::dev::helper(&arena, src, true, false, Default::default());
+
+    crate::helpers::dev::run_test_main::<T>(&lib).map_err(|(msg, tag)| crash_message(&msg, tag))
+}
+
+pub fn run_wasm<T>(src: &str) -> Outcome<T>
+where
+    T: FromWasm32Memory + Wasm32Result,
+{
+    // The wasm test dispatcher reports Roc crashes by panicking, with the same message format
+    // as `crash_message`. Turn that back into a value so it can be compared to the dev result.
+    let result = std::panic::catch_unwind(|| {
+        crate::helpers::wasm::assert_evals_to_help::<T>(src, PhantomData, Default::default())
+    });
+
+    match result {
+        Ok(outcome) => outcome,
+        Err(payload) => Err(match payload.downcast::<String>() {
+            Ok(msg) => *msg,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(msg) => msg.to_string(),
+                Err(_) => "wasm backend panicked with a non-string payload".to_string(),
+            },
+        }),
+    }
+}
+
+/// Run `src` on both backends and panic with both outcomes if they disagree.
+pub fn backends_agree<T>(src: &str) -> Outcome<T>
+where
+    T: FromWasm32Memory + Wasm32Result + PartialEq + Debug,
+{
+    let dev = run_dev::<T>(src);
+    let wasm = run_wasm::<T>(src);
+
+    if dev != wasm {
+        panic!(
+            "The dev and wasm backends disagree!\n\nSource:\n{src}\n\ndev:  {dev:?}\nwasm: {wasm:?}"
+        );
+    }
+
+    dev
+}
+
+/// A type whose values can be pasted into Roc source as a literal,
+/// and generated deterministically for differential tests.
+pub trait DiffInput: Copy + Debug {
+    /// Values most likely to expose bugs: zero, one, the extremes of the type, etc.
+    fn edge_cases() -> Vec<Self>;
+
+    /// Build a value from the output of the pseudo-random generator.
+    fn from_random_bits(bits: u64) -> Self;
+
+    /// The value as a Roc expression, with a type suffix so it is not defaulted to another type.
+    fn to_roc_literal(self) -> String;
+}
+
+macro_rules! diff_input_int {
+    ($($ty:ident),*) => {$(
+        impl DiffInput for $ty {
+            fn edge_cases() -> Vec<Self> {
+                let mut values = vec![0, 1, 2, $ty::MAX, $ty::MAX - 1, $ty::MIN];
+
+                if $ty::MIN != 0 {
+                    values.extend([$ty::MIN + 1, (0 as $ty).wrapping_sub(1)]);
+                }
+
+                values
+            }
+
+            fn from_random_bits(bits: u64) -> Self {
+                bits as $ty
+            }
+
+            fn to_roc_literal(self) -> String {
+                if self == $ty::MIN && $ty::MIN != 0 {
+                    // The literal for the positive half of MIN is out of range, so spell it out.
+                    format!("Num.min{}", capitalize(stringify!($ty)))
+                } else {
+                    format!("({}{})", self, stringify!($ty))
+                }
+            }
+        }
+    )*};
+}
+
+diff_input_int!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Deterministic test inputs: all the edge cases of the type, followed by `random_count`
+/// values from a fixed-seed xorshift generator. A fixed seed means a failure always reproduces.
+pub fn generate_inputs<I: DiffInput>(random_count: usize) -> Vec<I> {
+    let mut inputs = I::edge_cases();
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+
+    for _ in 0..random_count {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+
+        inputs.push(I::from_random_bits(state));
+    }
+
+    inputs
+}
+
+/// Instantiate a source template once per generated input, and check that the two backends
+/// agree on every one of them. `template` receives the input already rendered as a Roc literal.
+pub fn backends_agree_on_inputs<I, T, F>(random_count: usize, template: F)
+where
+    I: DiffInput,
+    T: FromWasm32Memory + Wasm32Result + PartialEq + Debug,
+    F: Fn(&str) -> String,
+{
+    for input in generate_inputs::<I>(random_count) {
+        let src = template(&input.to_roc_literal());
+
+        let _ = backends_agree::<T>(&src);
+    }
+}
+
+#[allow(unused_macros)]
+macro_rules! assert_backends_agree {
+    ($src:expr, $ty:ty) => {{
+        let _ = $crate::helpers::diff::backends_agree::<$ty>($src);
+    }};
+    ($src:expr, $expected:expr, $ty:ty) => {{
+        // Both backends must agree, and they must agree on the right answer.
+        match $crate::helpers::diff::backends_agree::<$ty>($src) {
+            Ok(actual) => assert_eq!(actual, $expected),
+            Err(msg) => panic!("Both backends crashed: {}", msg),
+        }
+    }};
+}
+
+#[allow(unused_macros)]
+macro_rules! assert_backends_agree_on_inputs {
+    ($input_ty:ty, $count:expr, $ty:ty, $template:expr) => {{
+        $crate::helpers::diff::backends_agree_on_inputs::<$input_ty, $ty, _>($count, $template);
+    }};
+}
+
+#[allow(unused_imports)]
+pub(crate) use assert_backends_agree;
+
+#[allow(unused_imports)]
+pub(crate) use assert_backends_agree_on_inputs;
@@ -98,6 +98,17 @@ fn create_llvm_module<'a>(
         Err(e) => panic!("{e:?}"),
     };
 
+    // Fold constants and remove unreachable specializations as `roc build` does, so that every
+    // backend is tested with only the procedures that the passes keep
+    if matches!(config.opt_level, OptLevel::Optimize | OptLevel::Size) {
+        roc_mono::const_fold::fold_constants(
+            arena,
+            &loaded.layout_interner,
+            &mut loaded.procedures,
+        );
+    }
+    loaded.remove_unreachable_procs();
+
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
         procedures,
@@ -230,6 +241,7 @@ fn create_llvm_module<'a>(
         mode: config.mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        coverage: None,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -543,6 +555,7 @@ pub(crate) fn llvm_evals_to<T, U, F>(
     transform: F,
     ignore_problems: bool,
     function_kind: FunctionKind,
+    opt_level: OptLevel,
 ) where
     U: PartialEq + std::fmt::Debug,
     F: FnOnce(T) -> U,
@@ -557,7 +570,7 @@ pub(crate) fn llvm_evals_to<T, U, F>(
         mode: LlvmBackendMode::GenTest,
         emit_debug_info: false,
         ignore_problems,
-        opt_level: crate::helpers::llvm::OPT_LEVEL,
+        opt_level,
     };
 
     let (main_fn_name, errors, lib) =
@@ -593,6 +606,7 @@ macro_rules! assert_llvm_evals_to {
             $transform,
             $ignore_problems,
             roc_load::FunctionKind::LambdaSet,
+            $crate::helpers::llvm::OPT_LEVEL,
         );
     };
 
@@ -657,16 +671,38 @@ macro_rules! assert_evals_to_erased {
             $crate::helpers::llvm::identity,
             false,
             roc_load::FunctionKind::Erased,
+            $crate::helpers::llvm::OPT_LEVEL,
         );
     }};
 }
 
+/// Like `assert_evals_to`, but checks the result both with and without constant folding, which
+/// only optimized builds do.
+macro_rules! assert_folds_to {
+    ($src:expr, $expected:expr, $ty:ty) => {{
+        for opt_level in [
+            roc_mono::ir::OptLevel::Normal,
+            roc_mono::ir::OptLevel::Optimize,
+        ] {
+            crate::helpers::llvm::llvm_evals_to::<$ty, _, _>(
+                $src,
+                $expected,
+                $crate::helpers::llvm::identity,
+                false,
+                roc_load::FunctionKind::LambdaSet,
+                opt_level,
+            );
+        }
+    }};
+}
+
 pub fn identity<T>(value: T) -> T {
     value
 }
 
 pub(crate) use assert_evals_to;
 pub(crate) use assert_evals_to_erased;
+pub(crate) use assert_folds_to;
 pub(crate) use assert_llvm_evals_to;
 #[cfg(feature = "gen-llvm-wasm")]
 pub(crate) use assert_wasm_evals_to;
@@ -2,12 +2,14 @@ extern crate bumpalo;
 
 pub mod platform_functions;
 
-#[cfg(feature = "gen-dev")]
+#[cfg(any(feature = "gen-dev", feature = "gen-diff"))]
 pub mod dev;
+#[cfg(feature = "gen-diff")]
+pub mod diff;
 pub mod from_wasm32_memory;
 #[cfg(feature = "gen-llvm")]
 pub mod llvm;
-#[cfg(any(feature = "gen-wasm", feature = "gen-llvm-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-llvm-wasm", feature = "gen-diff"))]
 pub mod wasm;
 
 #[allow(dead_code)]
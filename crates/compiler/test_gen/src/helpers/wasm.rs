@@ -9,7 +9,8 @@ use roc_packaging::cache::RocCacheDir;
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
 use roc_solve::FunctionKind;
 use roc_std::RocStr;
-use roc_wasm_interp::{wasi, ImportDispatcher, Instance, WasiDispatcher};
+use roc_wasm_interp::{ClosureDispatcher, Instance};
+use roc_wasm_module::parse::Parse;
 use roc_wasm_module::{Export, ExportType, Value, WasmModule};
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -22,6 +23,9 @@ macro_rules! host_bytes_path {
         // Should manually match build.rs. include_bytes! requires a string literal.
         concat!(env!("OUT_DIR"), "/wasm_test_platform.wasm")
     };
+    (app_allocator) => {
+        concat!(env!("OUT_DIR"), "/wasm_test_platform_app_allocator.wasm")
+    };
 }
 
 fn promote_expr_to_module(src: &str) -> String {
@@ -47,17 +51,80 @@ fn write_final_wasm() -> bool {
     DEBUG_SETTINGS.keep_test_binary
 }
 
+/// The test platform, with the builtins linked in.
+pub fn test_host_bytes() -> &'static [u8] {
+    include_bytes!(host_bytes_path!())
+}
+
+/// Path and bytes of the test platform for an allocator. Apart from `Host`, the platform leaves
+/// the allocation functions to the app.
+fn test_host(allocator: roc_gen_wasm::Allocator) -> (&'static str, &'static [u8]) {
+    match allocator {
+        roc_gen_wasm::Allocator::Host => (host_bytes_path!(), test_host_bytes()),
+        roc_gen_wasm::Allocator::Bump | roc_gen_wasm::Allocator::ArenaPerCall => (
+            host_bytes_path!(app_allocator),
+            include_bytes!(host_bytes_path!(app_allocator)),
+        ),
+    }
+}
+
+/// Every allocator the app can use, to run tests that allocate with each of them
+#[allow(dead_code)]
+pub const ALLOCATORS: [roc_gen_wasm::Allocator; 3] = [
+    roc_gen_wasm::Allocator::Host,
+    roc_gen_wasm::Allocator::Bump,
+    roc_gen_wasm::Allocator::ArenaPerCall,
+];
+
+/// Each of the `ALLOCATORS`, with and without inlining small procedures, for the tests that
+/// cover how code is generated rather than a single builtin
+#[allow(dead_code)]
+pub fn setups() -> impl Iterator<Item = EnvOptions> {
+    ALLOCATORS.into_iter().flat_map(|allocator| {
+        [false, true].map(|inline_small_procs| EnvOptions {
+            allocator,
+            inline_small_procs,
+            ..EnvOptions::DEFAULT
+        })
+    })
+}
+
+/// Options of [roc_gen_wasm::Env] that are off by default, which a test can turn on
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvOptions {
+    pub canonicalize_nans: bool,
+    pub patchable_calls: bool,
+    pub allocator: roc_gen_wasm::Allocator,
+    pub heap_profiling: bool,
+    /// Fold constants before code generation, as optimized builds do
+    pub fold_constants: bool,
+    /// Inline calls to small procedures, as optimized builds do
+    pub inline_small_procs: bool,
+    /// Split procedures with more statements than this, instead of `DEFAULT_MAX_FUNCTION_STMTS`
+    pub max_function_stmts: Option<usize>,
+}
+
+impl EnvOptions {
+    /// Same as `Default::default()`, for building options in a `const`
+    pub const DEFAULT: Self = EnvOptions {
+        canonicalize_nans: false,
+        patchable_calls: false,
+        allocator: roc_gen_wasm::Allocator::Host,
+        heap_profiling: false,
+        fold_constants: false,
+        inline_small_procs: false,
+        max_function_stmts: None,
+    };
+}
+
 #[allow(dead_code)]
 pub fn compile_to_wasm_bytes<'a, T: Wasm32Result>(
     arena: &'a bumpalo::Bump,
     src: &str,
     test_wrapper_type_info: PhantomData<T>,
+    options: EnvOptions,
 ) -> Vec<u8> {
-    let platform_bytes = include_bytes!(host_bytes_path!());
-    println!("Loading test host {}", host_bytes_path!());
-
-    let compiled_bytes =
-        compile_roc_to_wasm_bytes(arena, platform_bytes, src, test_wrapper_type_info);
+    let compiled_bytes = compile_roc_to_wasm_bytes(arena, src, test_wrapper_type_info, options);
 
     if write_final_wasm() {
         let build_dir_hash = crate::helpers::src_hash(src);
@@ -69,10 +136,13 @@ pub fn compile_to_wasm_bytes<'a, T: Wasm32Result>(
 
 fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
     arena: &'a bumpalo::Bump,
-    host_bytes: &[u8],
     src: &str,
     _test_wrapper_type_info: PhantomData<T>,
+    options: EnvOptions,
 ) -> Vec<u8> {
+    let (host_path, host_bytes) = test_host(options.allocator);
+    println!("Loading test host {host_path}");
+
     let filename = PathBuf::from("Test.roc");
     let src_dir = PathBuf::from("fake/test/path");
 
@@ -105,7 +175,22 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         load_config,
     );
 
-    let loaded = loaded.expect("failed to load module");
+    let mut loaded = loaded.expect("failed to load module");
+
+    if options.fold_constants {
+        roc_mono::const_fold::fold_constants(
+            arena,
+            &loaded.layout_interner,
+            &mut loaded.procedures,
+        );
+    }
+
+    // Only generate code for the procedures that `roc build` would keep
+    loaded.remove_unreachable_procs();
+
+    let source_locations = options
+        .heap_profiling
+        .then(|| &*arena.alloc(loaded.source_locations()));
 
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
@@ -130,14 +215,26 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         module_id,
         exposed_to_host,
         stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        canonicalize_nans: options.canonicalize_nans,
+        heap_profiling: source_locations,
+        max_function_stmts: options
+            .max_function_stmts
+            .unwrap_or(roc_gen_wasm::Env::DEFAULT_MAX_FUNCTION_STMTS),
+        max_inline_stmts: if options.inline_small_procs {
+            roc_gen_wasm::Env::DEFAULT_MAX_INLINE_STMTS
+        } else {
+            0
+        },
+        multi_value_returns: true,
+        patchable_calls: options.patchable_calls,
+        allocator: options.allocator,
+        codegen_threads: roc_gen_wasm::Env::available_codegen_threads(),
     };
 
     let host_module = roc_gen_wasm::parse_host(env.arena, host_bytes).unwrap_or_else(|e| {
         panic!(
             "I ran into a problem with the host object file, {} at offset 0x{:x}:\n{}",
-            host_bytes_path!(),
-            e.offset,
-            e.message
+            host_path, e.offset, e.message
         )
     });
 
@@ -176,32 +273,134 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
 }
 
 #[allow(dead_code)]
-pub fn assert_evals_to_help<T>(src: &str, phantom: PhantomData<T>) -> Result<T, String>
+pub fn assert_evals_to_help<T>(
+    src: &str,
+    phantom: PhantomData<T>,
+    options: EnvOptions,
+) -> Result<T, String>
 where
     T: FromWasm32Memory + Wasm32Result,
 {
     let arena = bumpalo::Bump::new();
 
-    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom, options);
 
     run_wasm_test_bytes::<T>(TEST_WRAPPER_NAME, wasm_bytes)
 }
 
-struct TestDispatcher<'a> {
-    wasi: WasiDispatcher<'a>,
+/// The contents of the custom section called `name`, if the module has one.
+/// `WasmModule::preload` skips custom sections it doesn't know, so this reads the raw bytes.
+#[allow(dead_code)]
+pub fn read_custom_section<'a>(wasm_bytes: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    let arena = Bump::new();
+    let mut cursor = 8; // magic number and version
+    while cursor < wasm_bytes.len() {
+        let section_id = wasm_bytes[cursor];
+        cursor += 1;
+        let section_size = u32::parse((), wasm_bytes, &mut cursor).ok()? as usize;
+        let section_end = cursor + section_size;
+        if section_id == 0 {
+            let section_name = <&str>::parse(&arena, wasm_bytes, &mut cursor).ok()?;
+            if section_name == name {
+                return Some(&wasm_bytes[cursor..section_end]);
+            }
+        }
+        cursor = section_end;
+    }
+    None
+}
+
+/// Compile with `heap_profiling`, and read back the (source location, kind) of each allocation
+/// site. The site with ID `i` is at index `i - 1`.
+#[allow(dead_code)]
+pub fn alloc_sites<T: Wasm32Result>(
+    src: &str,
+    phantom: PhantomData<T>,
+) -> Result<Vec<(String, String)>, String> {
+    let arena = Bump::new();
+    let options = EnvOptions {
+        heap_profiling: true,
+        ..EnvOptions::DEFAULT
+    };
+    let wasm_bytes = compile_to_wasm_bytes(&arena, src, phantom, options);
+
+    let section = read_custom_section(&wasm_bytes, roc_gen_wasm::ALLOC_SITES_SECTION_NAME)
+        .ok_or("The module has no allocation sites section")?;
+    let mut cursor = 0;
+    let count = u32::parse((), section, &mut cursor).map_err(|e| e.message)?;
+    let mut sites = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let location = <&str>::parse(&arena, section, &mut cursor).map_err(|e| e.message)?;
+        let kind = <&str>::parse(&arena, section, &mut cursor).map_err(|e| e.message)?;
+        sites.push((location.to_string(), kind.to_string()));
+    }
+    Ok(sites)
+}
+
+/// Compile with `patchable_calls`, then before running the test, make calls to each proc
+/// named on the left of `patches` go to the proc named on the right, with `PATCH_FN_NAME`.
+#[allow(dead_code)]
+pub fn patch_and_run<T>(
+    src: &str,
+    phantom: PhantomData<T>,
+    patches: &[(&str, &str)],
+) -> Result<T, String>
+where
+    T: FromWasm32Memory + Wasm32Result,
+{
+    let arena = Bump::new();
+    let options = EnvOptions {
+        patchable_calls: true,
+        ..EnvOptions::DEFAULT
+    };
+    let wasm_bytes = compile_to_wasm_bytes(&arena, src, phantom, options);
+
+    // (table index, proc name) pairs
+    let section = read_custom_section(&wasm_bytes, roc_gen_wasm::PATCHABLE_FNS_SECTION_NAME)
+        .ok_or("The module has no patchable procs section")?;
+    let mut cursor = 0;
+    let count = u32::parse((), section, &mut cursor).map_err(|e| e.message)?;
+    let mut table_indices = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let table_index = u32::parse((), section, &mut cursor).map_err(|e| e.message)?;
+        let name = <&str>::parse(&arena, section, &mut cursor).map_err(|e| e.message)?;
+        table_indices.push((table_index, name));
+    }
+    let table_index_of = |ident: &str| {
+        // The name is the proc's module, ident and layout ID, joined by underscores
+        let suffix = format!("_{ident}");
+        let found = table_indices.iter().find(|(_, name)| {
+            let without_layout_id = name.trim_end_matches(|c: char| c.is_ascii_digit());
+            without_layout_id.trim_end_matches('_').ends_with(&suffix)
+        });
+        found
+            .map(|(table_index, _)| *table_index as i32)
+            .ok_or_else(|| format!("No patchable proc called {ident} in {table_indices:?}"))
+    };
+
+    let module = WasmModule::preload(&arena, &wasm_bytes, false).map_err(|e| format!("{e:?}"))?;
+    let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
+    let mut inst = Instance::for_module(&arena, &module, test_dispatcher(), is_debug_mode)?;
+    for (from, to) in patches {
+        let args = [
+            Value::I32(table_index_of(from)?),
+            Value::I32(table_index_of(to)?),
+        ];
+        inst.call_export(roc_gen_wasm::PATCH_FN_NAME, args)?;
+    }
+
+    let addr = inst
+        .call_export(TEST_WRAPPER_NAME, [])?
+        .ok_or("No return address from Wasm test")?
+        .expect_i32()
+        .map_err(|e| format!("{e:?}"))?;
+    Ok(<T as FromWasm32Memory>::decode(&inst.memory, addr as u32))
 }
 
-impl<'a> ImportDispatcher for TestDispatcher<'a> {
-    fn dispatch(
-        &mut self,
-        module_name: &str,
-        function_name: &str,
-        arguments: &[Value],
-        memory: &mut [u8],
-    ) -> Option<Value> {
-        if module_name == wasi::MODULE_NAME {
-            self.wasi.dispatch(function_name, arguments, memory)
-        } else if module_name == "env" && function_name == "send_panic_msg_to_rust" {
+/// Imports of the test platform, on top of WASI
+fn test_dispatcher<'a>() -> ClosureDispatcher<'a> {
+    ClosureDispatcher::default()
+        .import("env", "send_panic_msg_to_rust", |arguments, memory| {
             let msg_ptr = arguments[0].expect_i32().unwrap();
             let panic_tag = arguments[1].expect_i32().unwrap();
             let roc_msg = RocStr::decode(memory, msg_ptr as _);
@@ -211,13 +410,14 @@ impl<'a> ImportDispatcher for TestDispatcher<'a> {
                 _ => format!(r#"Got an invald panic tag: "{panic_tag}""#),
             };
             panic!("{}", msg)
-        } else {
-            panic!(
-                "TestDispatcher does not implement {}.{}",
-                module_name, function_name
-            );
-        }
-    }
+        })
+        .import("env", "send_dbg_msg_to_rust", |arguments, memory| {
+            let loc = RocStr::decode(memory, arguments[0].expect_i32().unwrap() as _);
+            let msg = RocStr::decode(memory, arguments[1].expect_i32().unwrap() as _);
+            let src = RocStr::decode(memory, arguments[2].expect_i32().unwrap() as _);
+            eprintln!("[{loc}] {src} = {msg}");
+            None
+        })
 }
 
 pub(crate) fn run_wasm_test_bytes<T>(
@@ -242,9 +442,7 @@ pub(crate) fn run_wasm_test_module<'a, T>(
 where
     T: FromWasm32Memory + Wasm32Result,
 {
-    let dispatcher = TestDispatcher {
-        wasi: wasi::WasiDispatcher::default(),
-    };
+    let dispatcher = test_dispatcher();
     let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
     let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
     let opt_value = inst.call_export(test_wrapper_name, [])?;
@@ -259,21 +457,20 @@ pub fn assert_wasm_refcounts_help<T>(
     src: &str,
     phantom: PhantomData<T>,
     refcount_locs: &[RefCountLoc],
+    options: EnvOptions,
 ) -> Result<Vec<RefCount>, String>
 where
     T: FromWasm32Memory + Wasm32Result,
 {
     let arena = bumpalo::Bump::new();
 
-    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom, options);
 
     let require_relocatable = false;
     let module = WasmModule::preload(&arena, &wasm_bytes, require_relocatable)
         .map_err(|e| format!("{:?}", e))?;
 
-    let dispatcher = TestDispatcher {
-        wasi: wasi::WasiDispatcher::default(),
-    };
+    let dispatcher = test_dispatcher();
     let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
     let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
 
@@ -385,7 +582,7 @@ macro_rules! assert_evals_to {
     ($src:expr, $expected:expr, $ty:ty, $transform:expr, $ignore_problems: expr) => {{
         let phantom = std::marker::PhantomData;
         let _ = $ignore_problems; // Always ignore "problems"! One backend (LLVM) is enough to cover them.
-        match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom) {
+        match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom, Default::default()) {
             Err(msg) => panic!("{}", msg),
             Ok(actual) => {
                 assert_eq!($transform(actual), $expected)
@@ -394,11 +591,66 @@ macro_rules! assert_evals_to {
     }};
 }
 
+/// `assert_evals_to!`, once with each of the `setups`
+#[allow(unused_macros)]
+macro_rules! assert_evals_to_with_each_setup {
+    ($src:expr, $expected:expr, $ty:ty) => {
+        $crate::helpers::wasm::assert_evals_to_with_each_setup!(
+            $src,
+            $expected,
+            $ty,
+            $crate::helpers::wasm::identity,
+            false
+        )
+    };
+
+    ($src:expr, $expected:expr, $ty:ty, $transform:expr) => {
+        $crate::helpers::wasm::assert_evals_to_with_each_setup!(
+            $src, $expected, $ty, $transform, false
+        )
+    };
+
+    ($src:expr, $expected:expr, $ty:ty, $transform:expr, $ignore_problems: expr) => {{
+        let _ = $ignore_problems;
+        for options in $crate::helpers::wasm::setups() {
+            let phantom = std::marker::PhantomData;
+            match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom, options) {
+                Err(msg) => panic!("With {:?}: {}", options, msg),
+                Ok(actual) => {
+                    assert_eq!($transform(actual), $expected, "With {:?}", options)
+                }
+            }
+        }
+    }};
+}
+
 #[allow(dead_code)]
 pub fn identity<T>(value: T) -> T {
     value
 }
 
+/// Like `assert_evals_to`, but checks the result both with and without constant folding.
+#[allow(unused_macros)]
+macro_rules! assert_folds_to {
+    ($src:expr, $expected:expr, $ty:ty) => {{
+        for fold_constants in [false, true] {
+            let options = $crate::helpers::wasm::EnvOptions {
+                fold_constants,
+                ..Default::default()
+            };
+            let phantom = std::marker::PhantomData;
+            match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom, options) {
+                Err(msg) => panic!("With fold_constants = {}: {}", fold_constants, msg),
+                Ok(actual) => assert_eq!(
+                    actual, $expected,
+                    "With fold_constants = {}",
+                    fold_constants
+                ),
+            }
+        }
+    }};
+}
+
 #[allow(unused_macros)]
 macro_rules! assert_refcounts {
     // We need the result type to generate the test_wrapper, even though we ignore the value!
@@ -410,12 +662,18 @@ macro_rules! assert_refcounts {
             Vec<$crate::helpers::RefCountLoc>,
             Vec<$crate::helpers::RefCount>,
         ) = $expected.into_iter().map(|x| *x).unzip();
-        let result =
-            $crate::helpers::wasm::assert_wasm_refcounts_help::<$ty>($src, phantom, &refcount_locs);
-        match result {
-            Err(msg) => panic!("{:?}", msg),
-            Ok(actual_refcounts) => {
-                assert_eq!(actual_refcounts, expected_refcounts)
+        for options in $crate::helpers::wasm::setups() {
+            let result = $crate::helpers::wasm::assert_wasm_refcounts_help::<$ty>(
+                $src,
+                phantom,
+                &refcount_locs,
+                options,
+            );
+            match result {
+                Err(msg) => panic!("With {:?}: {:?}", options, msg),
+                Ok(actual_refcounts) => {
+                    assert_eq!(actual_refcounts, expected_refcounts, "With {:?}", options)
+                }
             }
         }
     }};
@@ -424,5 +682,11 @@ macro_rules! assert_refcounts {
 #[allow(unused_imports)]
 pub(crate) use assert_evals_to;
 
+#[allow(unused_imports)]
+pub(crate) use assert_evals_to_with_each_setup;
+
+#[allow(unused_imports)]
+pub(crate) use assert_folds_to;
+
 #[allow(unused_imports)]
 pub(crate) use assert_refcounts;
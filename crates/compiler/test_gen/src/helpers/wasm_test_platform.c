@@ -16,6 +16,27 @@ typedef struct
 Vector *rc_pointers;
 size_t rc_pointers_capacity;
 
+#ifdef APP_ALLOCATOR
+// The app brings its own roc_alloc and roc_dealloc, so this host leaves them undefined.
+// build.rs links it with `--wrap`, which sends the builtins' calls to them through the
+// __wrap_ versions below, so that refcount tests can still see every allocation.
+void *__real_roc_alloc(size_t size, unsigned int alignment);
+void __real_roc_dealloc(void *ptr, unsigned int alignment);
+#define ROC_ALLOC __wrap_roc_alloc
+#define ROC_DEALLOC __wrap_roc_dealloc
+#define ALLOCATE(size, alignment) __real_roc_alloc(size, alignment)
+#define FREE(ptr, alignment) __real_roc_dealloc(ptr, alignment)
+
+// malloc would share the heap with the app's allocator, which assumes it owns it
+#define MAX_REFCOUNT_TEST_ALLOCATIONS 1024
+size_t rc_pointers_storage[1 + MAX_REFCOUNT_TEST_ALLOCATIONS];
+#else
+#define ROC_ALLOC roc_alloc
+#define ROC_DEALLOC roc_dealloc
+#define ALLOCATE(size, alignment) malloc(size)
+#define FREE(ptr, alignment) free(ptr)
+#endif
+
 // The rust test passes us the max number of allocations it expects to make,
 // and we tell it where we're going to write the refcount pointers.
 // It won't actually read that memory until later, when the test is done.
@@ -23,7 +44,13 @@ Vector *init_refcount_test(size_t capacity)
 {
     rc_pointers_capacity = capacity;
 
+#ifdef APP_ALLOCATOR
+    if (capacity > MAX_REFCOUNT_TEST_ALLOCATIONS)
+        abort();
+    rc_pointers = (Vector *)rc_pointers_storage;
+#else
     rc_pointers = malloc((1 + capacity) * sizeof(size_t *));
+#endif
     rc_pointers->length = 0;
     for (size_t i = 0; i < capacity; ++i)
         rc_pointers->elements[i] = NULL;
@@ -53,9 +80,9 @@ size_t *alloc_ptr_to_rc_ptr(void *ptr, unsigned int alignment)
 
 //--------------------------
 
-void *roc_alloc(size_t size, unsigned int alignment)
+void *ROC_ALLOC(size_t size, unsigned int alignment)
 {
-    void *allocated = malloc(size);
+    void *allocated = ALLOCATE(size, alignment);
 
     if (rc_pointers)
     {
@@ -84,6 +111,7 @@ void *roc_alloc(size_t size, unsigned int alignment)
 
 //--------------------------
 
+#ifndef APP_ALLOCATOR
 void *roc_realloc(void *ptr, size_t new_size, size_t old_size,
                   unsigned int alignment)
 {
@@ -93,10 +121,11 @@ void *roc_realloc(void *ptr, size_t new_size, size_t old_size,
 #endif
     return realloc(ptr, new_size);
 }
+#endif
 
 //--------------------------
 
-void roc_dealloc(void *ptr, unsigned int alignment)
+void ROC_DEALLOC(void *ptr, unsigned int alignment)
 {
     if (rc_pointers)
     {
@@ -119,7 +148,7 @@ void roc_dealloc(void *ptr, unsigned int alignment)
 #if ENABLE_PRINTF
     printf("roc_dealloc deallocated %p with alignment %zd\n", ptr, alignment);
 #endif
-    free(ptr);
+    FREE(ptr, alignment);
 }
 
 //--------------------------
@@ -132,8 +161,12 @@ void roc_panic(void* msg, unsigned int panic_tag)
     exit(101);
 }
 
-// TODO: add a way to send dbg to rust.
-void roc_debug(void* loc, void* msg) {}
+extern void send_dbg_msg_to_rust(void* loc, void* msg, void* src);
+
+void roc_dbg(void* loc, void* msg, void* src)
+{
+    send_dbg_msg_to_rust(loc, msg, src);
+}
 
 //--------------------------
 
@@ -6,8 +6,11 @@
 
 pub mod gen_abilities;
 pub mod gen_compare;
+pub mod gen_const_fold;
 pub mod gen_definitions;
 pub mod gen_dict;
+#[cfg(feature = "gen-diff")]
+pub mod gen_diff;
 pub mod gen_erased;
 pub mod gen_list;
 pub mod gen_num;
@@ -15,6 +18,8 @@ pub mod gen_panic;
 pub mod gen_primitives;
 pub mod gen_records;
 pub mod gen_refcount;
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+pub mod gen_reproducible;
 pub mod gen_result;
 pub mod gen_set;
 pub mod gen_str;
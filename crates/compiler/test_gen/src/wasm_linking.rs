@@ -1,7 +1,7 @@
 #![cfg(feature = "gen-wasm")]
 
 use bumpalo::Bump;
-use roc_gen_wasm::Env;
+use roc_gen_wasm::{Allocator, Env};
 use roc_target::Target;
 use std::fs;
 use std::process::Command;
@@ -17,7 +17,7 @@ use roc_mono::ir::{
     Call, CallType, Expr, Literal, Proc, ProcLayout, SelfRecursive, Stmt, UpdateModeId,
 };
 use roc_mono::layout::{LambdaName, Layout, Niche, STLayoutInterner};
-use roc_wasm_interp::{wasi, ImportDispatcher, Instance, WasiDispatcher};
+use roc_wasm_interp::{ClosureDispatcher, Instance};
 use roc_wasm_module::{Value, WasmModule};
 
 const LINKING_TEST_HOST_WASM: &str = "build/wasm_linking_test_host.wasm";
@@ -159,6 +159,14 @@ impl<'a> BackendInputs<'a> {
             module_id,
             exposed_to_host,
             stack_bytes: Env::DEFAULT_STACK_BYTES,
+            canonicalize_nans: false,
+            heap_profiling: None,
+            max_function_stmts: Env::DEFAULT_MAX_FUNCTION_STMTS,
+            max_inline_stmts: 0,
+            multi_value_returns: false,
+            patchable_calls: false,
+            allocator: Allocator::Host,
+            codegen_threads: 1,
         };
 
         // Identifier stuff for the backend
@@ -179,36 +187,21 @@ impl<'a> BackendInputs<'a> {
     }
 }
 
-struct TestDispatcher<'a> {
-    wasi: WasiDispatcher<'a>,
-}
-
-impl ImportDispatcher for TestDispatcher<'_> {
-    fn dispatch(
-        &mut self,
-        module_name: &str,
-        function_name: &str,
-        arguments: &[Value],
-        memory: &mut [u8],
-    ) -> Option<Value> {
-        if module_name == wasi::MODULE_NAME {
-            self.wasi.dispatch(function_name, arguments, memory)
-        } else if module_name == "env" {
-            match function_name {
-                "js_called_directly_from_roc" => Some(Value::I32(0x01)),
-                "js_called_indirectly_from_roc" => Some(Value::I32(0x02)),
-                "js_called_directly_from_main" => Some(Value::I32(0x04)),
-                "js_called_indirectly_from_main" => Some(Value::I32(0x08)),
-                "js_unused" => Some(Value::I32(0x10)),
-                _ => panic!("Unknown import env.{}", function_name),
-            }
-        } else {
-            panic!(
-                "TestDispatcher does not implement {}.{}",
-                module_name, function_name
-            );
-        }
-    }
+fn test_dispatcher<'a>() -> ClosureDispatcher<'a> {
+    ClosureDispatcher::default()
+        .import("env", "js_called_directly_from_roc", |_, _| {
+            Some(Value::I32(0x01))
+        })
+        .import("env", "js_called_indirectly_from_roc", |_, _| {
+            Some(Value::I32(0x02))
+        })
+        .import("env", "js_called_directly_from_main", |_, _| {
+            Some(Value::I32(0x04))
+        })
+        .import("env", "js_called_indirectly_from_main", |_, _| {
+            Some(Value::I32(0x08))
+        })
+        .import("env", "js_unused", |_, _| Some(Value::I32(0x10)))
 }
 
 fn execute_wasm_module<'a>(arena: &'a Bump, orig_module: WasmModule<'a>) -> Result<i32, String> {
@@ -220,9 +213,7 @@ fn execute_wasm_module<'a>(arena: &'a Bump, orig_module: WasmModule<'a>) -> Resu
         WasmModule::preload(arena, &buffer, false).map_err(|e| format!("{:?}", e))?
     };
 
-    let dispatcher = TestDispatcher {
-        wasi: wasi::WasiDispatcher::default(),
-    };
+    let dispatcher = test_dispatcher();
     let is_debug_mode = false;
     let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
 
@@ -1184,3 +1184,75 @@ fn str_to_dec() {
         RocDec
     );
 }
+
+#[test]
+fn str_literal_suffix_of_another() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                parent = "The quick brown fox jumps over the lazy dog"
+                child = "jumps over the lazy dog"
+
+                [parent, child]
+            "#
+        ),
+        RocList::from_slice(&[
+            RocStr::from("The quick brown fox jumps over the lazy dog"),
+            RocStr::from("jumps over the lazy dog"),
+        ]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+fn str_literal_suffix_count_utf8_bytes() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                parent = "The quick brown fox jumps over the lazy dog"
+                child = "jumps over the lazy dog"
+
+                [Str.countUtf8Bytes parent, Str.countUtf8Bytes child]
+            "#
+        ),
+        RocList::from_slice(&[43, 23]),
+        RocList<u64>
+    );
+}
+
+#[test]
+fn str_literal_suffix_concat() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                parent = "The quick brown fox jumps over the lazy dog"
+                child = "jumps over the lazy dog"
+
+                [parent, Str.concat child "!"]
+            "#
+        ),
+        RocList::from_slice(&[
+            RocStr::from("The quick brown fox jumps over the lazy dog"),
+            RocStr::from("jumps over the lazy dog!"),
+        ]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+fn str_literal_suffix_drop_leaves_parent() {
+    // Dropping `child` decrements the refcount of the literal it is a slice of, which must be
+    // left alone, and must not write over `parent`'s bytes.
+    assert_evals_to!(
+        indoc!(
+            r#"
+                parent = "The quick brown fox jumps over the lazy dog"
+                child = "jumps over the lazy dog"
+
+                if Str.isEmpty parent then child else parent
+            "#
+        ),
+        RocStr::from("The quick brown fox jumps over the lazy dog"),
+        RocStr
+    );
+}
@@ -3608,3 +3608,607 @@ fn issue_6606_2() {
         "
     )
 }
+
+#[mono_test]
+fn nullable_pointer_niche() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        unwrapOr : [Some (Box U64), None], U64 -> U64
+        unwrapOr = \opt, default ->
+            when opt is
+                Some boxed -> Box.unbox boxed
+                None -> default
+
+        main =
+            unwrapOr (Some (Box.box 42)) 0 + unwrapOr None 1
+        "#
+    )
+}
+
+fn parse_ir<'a>(
+    arena: &'a Bump,
+    interner: &mut STLayoutInterner<'a>,
+    src: &str,
+) -> Result<Vec<Proc<'a>>, roc_mono::ir::ParseError> {
+    use roc_module::ident::ModuleName;
+    use roc_module::symbol::ModuleIds;
+
+    let mut module_ids = ModuleIds::default();
+    module_ids.get_or_insert(&ModuleName::APP.into());
+
+    roc_mono::ir::parse_procs(arena, interner, &module_ids, src)
+}
+
+#[test]
+fn generated_ir_round_trips() {
+    let mut paths: Vec<_> = std::fs::read_dir("generated")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+
+    let mut not_inferred = Vec::new();
+    for path in &paths {
+        let src = std::fs::read_to_string(path).unwrap();
+        let arena = Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(64, TARGET);
+
+        let procs = match parse_ir(&arena, &mut interner, &src) {
+            Ok(procs) => procs,
+            // Procedures that are only passed around in lambda sets, or to higher-order
+            // lowlevels, are never called in the printed IR, so nothing tells their layouts.
+            Err(error)
+                if error.message.contains("cannot infer")
+                    || error.message.contains("higher-order") =>
+            {
+                not_inferred.push(path);
+                continue;
+            }
+            Err(error) => panic!("{}: {error}", path.display()),
+        };
+
+        let printed = procs
+            .iter()
+            .map(|proc| proc.to_pretty(&interner, 200, false))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(printed, src, "{} does not round-trip", path.display());
+    }
+
+    assert!(
+        not_inferred.len() * 4 < paths.len(),
+        "too many fixtures could not be parsed: {not_inferred:#?}"
+    );
+}
+
+#[test]
+fn hand_written_ir_infers_layouts() {
+    use roc_mono::debug::check_procs;
+    use roc_mono::layout::{Layout, LayoutInterner};
+
+    let src = indoc!(
+        r#"
+        procedure Test.1 (Test.2, Test.3):
+            let Test.4 : I64 = StructAtIndex 0 Test.2;
+            joinpoint Test.5 Test.6:
+                let Test.7 : I64 = lowlevel NumAdd Test.6 Test.3;
+                ret Test.7;
+            in
+            jump Test.5 Test.4;
+
+        procedure Test.0 ():
+            let Test.8 : I64 = 1i64;
+            let Test.9 : Str = "hello";
+            let Test.10 : {I64, Str} = Struct {Test.8, Test.9};
+            let Test.11 : I64 = CallByName Test.1 Test.10 Test.8;
+            ret Test.11;
+        "#
+    );
+
+    let arena = Bump::new();
+    let mut interner = STLayoutInterner::with_capacity(64, TARGET);
+    let procs = parse_ir(&arena, &mut interner, src).unwrap();
+
+    let add = &procs[0];
+    assert_eq!(interner.dbg(add.args[0].0), "{I64, Str}");
+    assert_eq!(add.args[1].0, Layout::I64);
+    assert_eq!(add.ret_layout, Layout::I64);
+
+    let procedures: MutMap<_, _> = procs
+        .into_iter()
+        .map(|proc| ((proc.name.name(), proc.proc_layout(&arena)), proc))
+        .collect();
+    assert!(check_procs(&arena, &mut interner, &procedures).is_empty());
+}
+
+#[test]
+fn ir_without_layout_hints_is_rejected() {
+    let src = indoc!(
+        r"
+        procedure Test.1 (Test.2):
+            ret Test.2;
+        "
+    );
+
+    let arena = Bump::new();
+    let mut interner = STLayoutInterner::with_capacity(64, TARGET);
+    let error = parse_ir(&arena, &mut interner, src).unwrap_err();
+
+    assert_eq!(error.line, 1);
+    assert!(error.message.contains("annotate it as `Test.2: <layout>`"));
+
+    let annotated = "procedure Test.1 (Test.2: U8):\n    ret Test.2;\n";
+    let procs = parse_ir(&arena, &mut interner, annotated).unwrap();
+    assert_eq!(
+        procs[0].to_pretty(&interner, 200, false),
+        "procedure Test.1 (Test.2):\n    ret Test.2;\n"
+    );
+}
+
+#[test]
+fn borrow_inference_can_be_disabled() {
+    use roc_mono::borrow::BorrowInference;
+    use roc_mono::inc_dec::insert_inc_dec_operations;
+
+    let src = indoc!(
+        r#"
+        procedure Test.1 (Test.2):
+            let Test.3 : U64 = lowlevel StrCountUtf8Bytes Test.2;
+            ret Test.3;
+
+        procedure Test.0 ():
+            let Test.4 : Str = "a string that is too long to be stored inline";
+            let Test.5 : U64 = CallByName Test.1 Test.4;
+            let Test.6 : U64 = CallByName Test.1 Test.4;
+            let Test.7 : U64 = lowlevel NumAdd Test.5 Test.6;
+            ret Test.7;
+        "#
+    );
+
+    let print_with = |borrow_inference| {
+        let arena = Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(64, TARGET);
+        let procs = parse_ir(&arena, &mut interner, src).unwrap();
+
+        let mut procedures: MutMap<_, _> = procs
+            .into_iter()
+            .map(|proc| ((proc.name.name(), proc.proc_layout(&arena)), proc))
+            .collect();
+        insert_inc_dec_operations(&arena, &interner, &mut procedures, borrow_inference);
+
+        let mut printed: Vec<_> = procedures
+            .values()
+            .map(|proc| proc.to_pretty(&interner, 200, false))
+            .collect();
+        printed.sort();
+        printed.join("\n")
+    };
+
+    assert_eq!(
+        print_with(BorrowInference::Enabled),
+        indoc!(
+            r#"
+            procedure Test.0 ():
+                let Test.4 : Str = "a string that is too long to be stored inline";
+                let Test.5 : U64 = CallByName Test.1 Test.4;
+                let Test.6 : U64 = CallByName Test.1 Test.4;
+                dec Test.4;
+                let Test.7 : U64 = lowlevel NumAdd Test.5 Test.6;
+                ret Test.7;
+
+            procedure Test.1 (Test.2):
+                let Test.3 : U64 = lowlevel StrCountUtf8Bytes Test.2;
+                ret Test.3;
+            "#
+        )
+    );
+    assert_eq!(
+        print_with(BorrowInference::Disabled),
+        indoc!(
+            r#"
+            procedure Test.0 ():
+                let Test.4 : Str = "a string that is too long to be stored inline";
+                inc Test.4;
+                let Test.5 : U64 = CallByName Test.1 Test.4;
+                let Test.6 : U64 = CallByName Test.1 Test.4;
+                let Test.7 : U64 = lowlevel NumAdd Test.5 Test.6;
+                ret Test.7;
+
+            procedure Test.1 (Test.2):
+                let Test.3 : U64 = lowlevel StrCountUtf8Bytes Test.2;
+                dec Test.2;
+                ret Test.3;
+            "#
+        )
+    );
+}
+
+#[test]
+fn constant_calls_are_folded() {
+    use roc_mono::const_fold::fold_constants;
+
+    let src = indoc!(
+        r#"
+        procedure Test.1 (Test.2: I64):
+            let Test.3 : I64 = 40i64;
+            let Test.4 : I64 = 2i64;
+            let Test.5 : I64 = lowlevel NumAdd Test.3 Test.4;
+            let Test.6 : I64 = lowlevel NumMul Test.5 Test.2;
+            let Test.7 : Str = "the answer is ";
+            let Test.8 : Str = lowlevel NumToStr Test.5;
+            let Test.9 : Str = lowlevel StrConcat Test.7 Test.8;
+            let Test.10 : List I64 = Array [Test.3, Test.4, Test.6];
+            let Test.11 : U64 = lowlevel ListLenU64 Test.10;
+            let Test.12 : Int1 = lowlevel NumGt Test.5 Test.4;
+            let Test.13 : {Str, U64, Int1, List I64} = Struct {Test.9, Test.11, Test.12, Test.10};
+            ret Test.13;
+        "#
+    );
+
+    let arena = Bump::new();
+    let mut interner = STLayoutInterner::with_capacity(64, TARGET);
+    let procs = parse_ir(&arena, &mut interner, src).unwrap();
+
+    let mut procedures: MutMap<_, _> = procs
+        .into_iter()
+        .map(|proc| ((proc.name.name(), proc.proc_layout(&arena)), proc))
+        .collect();
+    fold_constants(&arena, &interner, &mut procedures);
+
+    let printed: Vec<_> = procedures
+        .values()
+        .map(|proc| proc.to_pretty(&interner, 200, false))
+        .collect();
+
+    assert_eq!(
+        printed.join("\n"),
+        indoc!(
+            r#"
+            procedure Test.1 (Test.2):
+                let Test.5 : I64 = 42i64;
+                let Test.6 : I64 = lowlevel NumMul Test.5 Test.2;
+                let Test.9 : Str = "the answer is 42";
+                let Test.10 : List I64 = Array [40i64, 2i64, Test.6];
+                let Test.11 : U64 = 3i64;
+                let Test.12 : Int1 = true;
+                let Test.13 : {Str, U64, Int1, List I64} = Struct {Test.9, Test.11, Test.12, Test.10};
+                ret Test.13;
+            "#
+        )
+    );
+}
+
+fn load_app<'a>(arena: &'a Bump, src: &'a str) -> roc_load::MonomorphizedModule<'a> {
+    use roc_packaging::cache::RocCacheDir;
+    use std::path::PathBuf;
+
+    let load_config = LoadConfig {
+        target: TARGET,
+        function_kind: FunctionKind::LambdaSet,
+        threading: Threading::Single,
+        render: roc_reporting::report::RenderTarget::Generic,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        exec_mode: ExecutionMode::Executable,
+    };
+    roc_load::load_and_monomorphize_from_str(
+        arena,
+        PathBuf::from("Test.roc"),
+        src,
+        PathBuf::from("fake/test/path"),
+        None,
+        RocCacheDir::Disallowed,
+        load_config,
+    )
+    .unwrap()
+}
+
+#[test]
+fn reuse_report_explains_allocations() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        LinkedList a : [Nil, Cons a (LinkedList a)]
+
+        map : LinkedList a, (a -> b) -> LinkedList b
+        map = \list, f ->
+            when list is
+                Nil -> Nil
+                Cons x xs -> Cons (f x) (map xs f)
+
+        swap : LinkedList I64 -> LinkedList I64
+        swap = \list ->
+            when list is
+                Cons x (Cons y rest) -> Cons y (Cons x rest)
+                _ -> list
+
+        bump : LinkedList I64 -> LinkedList I64
+        bump = \list ->
+            when list is
+                Nil -> Nil
+                Cons x rest -> Cons (x + 1) rest
+
+        Tree : [Leaf, Node Tree I64 Tree]
+
+        toTree : LinkedList I64 -> Tree
+        toTree = \list ->
+            when list is
+                Nil -> Leaf
+                Cons x rest -> Node Leaf x (toTree rest)
+
+        main =
+            list = Cons 1i64 Nil
+
+            { mapped: map list (\x -> x + 1), bumped: bump list, swapped: swap list, tree: toTree list }
+        "#
+    );
+
+    let arena = Bump::new();
+    let loaded = load_app(&arena, src);
+
+    assert_eq!(
+        loaded.reuse_report.render(
+            &loaded.interns,
+            loaded.module_id,
+            &loaded.source_locations()
+        ),
+        indoc!(
+            r#"
+            4 of 6 heap allocated tags reuse the memory of a dropped value
+
+            bump
+                Test.roc:21:24 reuses `list`, if it is unique
+
+            main
+                `list` at Test.roc:32:12 allocates, no value was dropped before it
+
+            map
+                Test.roc:9:22 reuses `list`, if it is unique
+
+            swap
+                Test.roc:14:33 reuses `list`, if it is unique
+                Test.roc:14:41 reuses a value that depends on the jump to it, if it is unique
+
+            toTree
+                Test.roc:29:24 allocates, it needs 24 bytes (align 8) but only 16 bytes (align 8) were dropped before it
+            "#
+        )
+    );
+}
+
+#[test]
+fn source_locations_point_at_values() {
+    use roc_module::symbol::Symbol;
+
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        LinkedList : [Nil, Cons I64 LinkedList]
+
+        wrap : I64 -> LinkedList
+        wrap = \x -> Cons x Nil
+
+        main =
+            list = wrap 42
+
+            when list is
+                Cons x _ -> x
+                Nil -> 0
+        "#
+    );
+
+    let arena = Bump::new();
+    let loaded = load_app(&arena, src);
+    let locations = loaded.source_locations();
+    let symbol = |name| {
+        let ident_ids = loaded.interns.all_ident_ids.get(&loaded.module_id).unwrap();
+        Symbol::new(loaded.module_id, ident_ids.get_id(name).unwrap())
+    };
+
+    assert_eq!(
+        locations.locate(symbol("list"), symbol("main")).as_deref(),
+        Some("Test.roc:9:12")
+    );
+    // Values the IR made up are located at the body of their procedure
+    assert_eq!(
+        locations
+            .locate(Symbol::ARG_CLOSURE, symbol("wrap"))
+            .as_deref(),
+        Some("Test.roc:6:14")
+    );
+}
+
+#[test]
+fn coverage_points_locate_procs_and_branches() {
+    use roc_module::symbol::Symbol;
+    use roc_mono::coverage::{Coverage, CoverageKind};
+    use roc_mono::ir::Stmt;
+
+    // Hands out counters the way a backend does
+    fn add_branches(coverage: &mut Coverage, stmt: &Stmt) {
+        match stmt {
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                let branches = branches.iter().map(|(_, _, branch)| branch);
+                for branch in branches.chain([default_branch.1]) {
+                    coverage.branch(branch);
+                    add_branches(coverage, branch);
+                }
+            }
+            Stmt::Let(_, _, _, next) | Stmt::Refcounting(_, next) => add_branches(coverage, next),
+            Stmt::Expect { remainder, .. }
+            | Stmt::ExpectFx { remainder, .. }
+            | Stmt::Dbg { remainder, .. } => add_branches(coverage, remainder),
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                add_branches(coverage, remainder);
+                add_branches(coverage, body);
+            }
+            Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => {}
+        }
+    }
+
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        classify : I64 -> Str
+        classify = \n ->
+            if n < 0 then
+                "negative"
+            else
+                "non-negative"
+
+        main = classify 42
+        "#
+    );
+
+    let arena = Bump::new();
+    let loaded = load_app(&arena, src);
+    let classify = {
+        let ident_ids = loaded.interns.all_ident_ids.get(&loaded.module_id).unwrap();
+        Symbol::new(loaded.module_id, ident_ids.get_id("classify").unwrap())
+    };
+
+    let mut coverage = Coverage::new(loaded.source_locations());
+    for ((proc_name, _), proc) in loaded.procedures.iter() {
+        coverage.proc_entry(*proc_name);
+        add_branches(&mut coverage, &proc.body);
+    }
+
+    let missed: Vec<_> = coverage
+        .summarize(&vec![0; coverage.len()])
+        .missed
+        .into_iter()
+        .filter(|point| point.proc_name == classify)
+        .map(|point| (point.kind, point.location.to_string()))
+        .collect();
+
+    assert_eq!(
+        missed,
+        [
+            (CoverageKind::ProcEntry, "Test.roc:5:5".to_string()),
+            (CoverageKind::Branch, "Test.roc:6:9".to_string()),
+            (CoverageKind::Branch, "Test.roc:8:9".to_string()),
+        ]
+    );
+    assert!(coverage
+        .summarize(&vec![1; coverage.len()])
+        .missed
+        .is_empty());
+}
+
+#[test]
+fn unreachable_procs_are_removed() {
+    use roc_mono::const_fold::fold_constants;
+
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        double = \x -> x * 2
+
+        quadruple = \x -> double (double x)
+
+        label = "The answer is "
+
+        main = Str.concat label (Num.toStr (40 + 2))
+        "#
+    );
+
+    let arena = Bump::new();
+    let mut loaded = load_app(&arena, src);
+
+    let proc_names = |loaded: &roc_load::MonomorphizedModule| {
+        let mut names: Vec<_> = loaded
+            .procedures
+            .keys()
+            .map(|(symbol, _)| symbol.fully_qualified(&loaded.interns, loaded.module_id))
+            .map(|name| name.to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        proc_names(&loaded),
+        ["Num.add", "Num.toStr", "Str.concat", "label", "main"]
+    );
+
+    // Folding `Num.toStr (40 + 2)` leaves nothing that calls `Num.add` or `Num.toStr`
+    fold_constants(&arena, &loaded.layout_interner, &mut loaded.procedures);
+    loaded.remove_unreachable_procs();
+
+    assert_eq!(proc_names(&loaded), ["Str.concat", "label", "main"]);
+    assert_eq!(
+        loaded.render_unused_defs(),
+        indoc!(
+            r#"
+            2 top-level definitions are never used
+
+            Test.roc
+                3:1 `double`
+                5:1 `quadruple`
+            "#
+        )
+    );
+}
+
+#[test]
+fn nullable_pointer_niche_drops_the_tag_id() {
+    use roc_mono::layout::LayoutInterner;
+
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        boxed : [Some (Box U64), None] -> U64
+        boxed = \opt ->
+            when opt is
+                Some b -> Box.unbox b
+                None -> 0
+
+        unboxed : [Some U64, None] -> U64
+        unboxed = \opt ->
+            when opt is
+                Some n -> n
+                None -> 0
+
+        twoBoxes : [Some (Box U64), Other (Box U64), None] -> U64
+        twoBoxes = \opt ->
+            when opt is
+                Some b | Other b -> Box.unbox b
+                None -> 0
+
+        main =
+            boxed (Some (Box.box 1)) + unboxed (Some 2) + twoBoxes (Other (Box.box 3))
+        "#
+    );
+
+    let arena = Bump::new();
+    let loaded = load_app(&arena, src);
+
+    let argument_size = |name: &str| {
+        let (_, proc_layout) = loaded
+            .procedures
+            .keys()
+            .find(|(symbol, _)| symbol.as_str(&loaded.interns) == name)
+            .unwrap();
+        loaded.layout_interner.stack_size(proc_layout.arguments[0])
+    };
+
+    // just the box pointer, with `None` as the null pointer
+    assert_eq!(argument_size("boxed"), 8);
+
+    // no niche: a tag id next to the payload, padded to its alignment
+    assert_eq!(argument_size("unboxed"), 16);
+    assert_eq!(argument_size("twoBoxes"), 16);
+}
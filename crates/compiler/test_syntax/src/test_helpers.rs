@@ -10,7 +10,7 @@ use roc_parse::{
 };
 use roc_test_utils::assert_multiline_str_eq;
 
-use roc_fmt::Buf;
+use roc_fmt::{Buf, FormatConfig};
 
 /// Source code to parse. Usually in the form of a test case.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -84,8 +84,12 @@ pub enum Output<'a> {
 
 impl<'a> Output<'a> {
     pub fn format(&self) -> InputOwned {
+        self.format_with_config(FormatConfig::default())
+    }
+
+    pub fn format_with_config(&self, config: FormatConfig) -> InputOwned {
         let arena = Bump::new();
-        let mut buf = Buf::new_in(&arena);
+        let mut buf = Buf::new_in_with_config(&arena, config);
         match self {
             Output::Header(header) => {
                 fmt_module(&mut buf, header);
@@ -210,6 +214,20 @@ impl<'a> Input<'a> {
         &self,
         handle_formatted_output: impl Fn(Input),
         check_idempotency: bool,
+    ) {
+        self.check_invariants_with_config(
+            FormatConfig::default(),
+            handle_formatted_output,
+            check_idempotency,
+        )
+    }
+
+    /// Like `check_invariants`, but formats with the given `config`.
+    pub fn check_invariants_with_config(
+        &self,
+        config: FormatConfig,
+        handle_formatted_output: impl Fn(Input),
+        check_idempotency: bool,
     ) {
         let arena = Bump::new();
 
@@ -217,7 +235,7 @@ impl<'a> Input<'a> {
             panic!("Unexpected parse failure when parsing this for formatting:\n\n{}\n\nParse error was:\n\n{:?}\n\n", self.as_str(), err);
         });
 
-        let output = actual.format();
+        let output = actual.format_with_config(config);
 
         handle_formatted_output(output.as_ref());
 
@@ -259,7 +277,7 @@ impl<'a> Input<'a> {
 
         // Now verify that the resultant formatting is _idempotent_ - i.e. that it doesn't change again if re-formatted
         if check_idempotency {
-            let reformatted = reparsed_ast.format();
+            let reformatted = reparsed_ast.format_with_config(config);
 
             if output != reformatted {
                 eprintln!("Formatting bug; formatting is not stable.\nOriginal code:\n{}\n\nFormatted code:\n{}\n\nAST:\n{:#?}\n\nReparsed AST:\n{:#?}\n\n",
@@ -6,7 +6,7 @@ mod test_fmt {
     use bumpalo::Bump;
     use roc_fmt::def::fmt_defs;
     use roc_fmt::module::fmt_module;
-    use roc_fmt::Buf;
+    use roc_fmt::{Buf, FormatConfig};
     use roc_parse::ast::{Defs, Module};
     use roc_parse::module::{self, parse_module_defs};
     use roc_parse::state::State;
@@ -29,6 +29,14 @@ mod test_fmt {
         Input::Expr(input.trim()).check_invariants(check_formatting(input.trim()), true)
     }
 
+    fn expr_formats_to_with_width(max_width: usize, input: &str, expected: &str) {
+        Input::Expr(input.trim()).check_invariants_with_config(
+            FormatConfig { max_width },
+            check_formatting(expected.trim()),
+            true,
+        )
+    }
+
     fn fmt_module_and_defs<'a>(
         arena: &Bump,
         src: &str,
@@ -6333,6 +6341,189 @@ mod test_fmt {
         );
     }
 
+    #[test]
+    fn reflow_long_list() {
+        expr_formats_to_with_width(
+            20,
+            indoc!(
+                r#"
+                names = ["Alice", "Bob", "Carol"]
+
+                names
+                "#
+            ),
+            indoc!(
+                r#"
+                names = [
+                    "Alice",
+                    "Bob",
+                    "Carol",
+                ]
+
+                names
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn reflow_long_record_and_nested_items() {
+        expr_formats_to_with_width(
+            30,
+            indoc!(
+                r"
+                point = { x: [1, 2, 3], y: [4, 5, 6], z: 7 }
+
+                point
+                "
+            ),
+            indoc!(
+                r"
+                point = {
+                    x: [1, 2, 3],
+                    y: [4, 5, 6],
+                    z: 7,
+                }
+
+                point
+                "
+            ),
+        );
+
+        expr_formats_to_with_width(
+            20,
+            indoc!(
+                r"
+                nums = [[1, 2, 3], [4, 5, 6, 7, 8, 9, 10, 11, 12]]
+
+                nums
+                "
+            ),
+            indoc!(
+                r"
+                nums = [
+                    [1, 2, 3],
+                    [
+                        4,
+                        5,
+                        6,
+                        7,
+                        8,
+                        9,
+                        10,
+                        11,
+                        12,
+                    ],
+                ]
+
+                nums
+                "
+            ),
+        );
+    }
+
+    #[test]
+    fn reflow_long_pipeline() {
+        expr_formats_to_with_width(
+            40,
+            indoc!(
+                r"
+                total = items |> List.map .price |> List.sum
+
+                total
+                "
+            ),
+            indoc!(
+                r"
+                total =
+                    items |> List.map .price |> List.sum
+
+                total
+                "
+            ),
+        );
+
+        expr_formats_to_with_width(
+            30,
+            indoc!(
+                r"
+                total = items |> List.map .price |> List.sum
+
+                total
+                "
+            ),
+            indoc!(
+                r"
+                total =
+                    items
+                    |> List.map .price
+                    |> List.sum
+
+                total
+                "
+            ),
+        );
+    }
+
+    #[test]
+    fn reflow_keeps_comments() {
+        expr_formats_to_with_width(
+            20,
+            indoc!(
+                r"
+                nums = [
+                    # the first few
+                    1, 2, [3, 4, 5, 6, 7, 8],
+                ]
+
+                nums
+                "
+            ),
+            indoc!(
+                r"
+                nums = [
+                    # the first few
+                    1,
+                    2,
+                    [
+                        3,
+                        4,
+                        5,
+                        6,
+                        7,
+                        8,
+                    ],
+                ]
+
+                nums
+                "
+            ),
+        );
+    }
+
+    #[test]
+    fn reflow_leaves_short_code_alone() {
+        expr_formats_to_with_width(
+            80,
+            indoc!(
+                r"
+                point = { x: 1, y: 2 }
+                total = items |> List.sum
+
+                [point, total]
+                "
+            ),
+            indoc!(
+                r"
+                point = { x: 1, y: 2 }
+                total = items |> List.sum
+
+                [point, total]
+                "
+            ),
+        );
+    }
+
     // this is a parse error atm
     //    #[test]
     //    fn multiline_apply() {
@@ -2135,6 +2135,13 @@ impl Subs {
         (var.index() as usize) < self.len()
     }
 
+    /// Whether any symbol stored in these subs, e.g. in a lambda set, comes from this module.
+    pub fn mentions_module(&self, module_id: ModuleId) -> bool {
+        self.symbol_names
+            .iter()
+            .any(|symbol| symbol.module_id() == module_id)
+    }
+
     pub fn snapshot(&mut self) -> SubsSnapshot {
         SubsSnapshot {
             utable_snapshot: self.utable.snapshot(),
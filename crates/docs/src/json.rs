@@ -0,0 +1,110 @@
+//! Describes a package's API as JSON instead of HTML, for tools like package indexes and editor
+//! plugins that would otherwise have to scrape the generated pages.
+use crate::type_annotation_to_html;
+use roc_collections::VecSet;
+use roc_load::docs::{DocEntry, ModuleDocumentation, TypeAnnotation};
+use roc_load::LoadedModule;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::{LineColumn, LineInfo};
+use serde_json::{json, Value};
+
+/// Every exposed def of every exposed module:
+///
+/// ```json
+/// {
+///   "modules": [{
+///     "name": "Str",
+///     "docs": "...",
+///     "defs": [{
+///       "name": "join",
+///       "type_vars": [],
+///       "type": "List Str, Str -> Str",
+///       "docs": "...",
+///       "region": { "file": "Str.roc", "start": { "line": 4, "column": 0 }, "end": ... }
+///     }]
+///   }]
+/// }
+/// ```
+///
+/// `type` and `docs` are null when the def has no annotation or doc comment.
+/// Lines and columns start at 0, like in the language server.
+pub fn package_json(
+    docs_by_module: &[(ModuleId, ModuleDocumentation)],
+    all_exposed_symbols: &VecSet<Symbol>,
+    loaded_module: &LoadedModule,
+) -> Value {
+    let modules = docs_by_module
+        .iter()
+        .map(|(module_id, module)| {
+            module_json(*module_id, module, all_exposed_symbols, loaded_module)
+        })
+        .collect::<Vec<_>>();
+
+    json!({ "modules": modules })
+}
+
+fn module_json(
+    module_id: ModuleId,
+    module: &ModuleDocumentation,
+    all_exposed_symbols: &VecSet<Symbol>,
+    loaded_module: &LoadedModule,
+) -> Value {
+    let (path, source) = loaded_module
+        .sources
+        .get(&module_id)
+        .unwrap_or_else(|| panic!("No source for exposed module {}", module.name));
+    let line_info = LineInfo::new(source);
+    let file = path.display().to_string();
+
+    let mut module_docs: Option<String> = None;
+    let mut defs = Vec::new();
+
+    for entry in &module.entries {
+        match entry {
+            DocEntry::ModuleDoc(docs) => match module_docs.as_mut() {
+                Some(module_docs) => {
+                    module_docs.push('\n');
+                    module_docs.push_str(docs);
+                }
+                None => module_docs = Some(docs.clone()),
+            },
+            DocEntry::DocDef(doc_def) if all_exposed_symbols.contains(&doc_def.symbol) => {
+                let type_ann = match &doc_def.type_annotation {
+                    TypeAnnotation::NoTypeAnn => None,
+                    type_ann => {
+                        let mut buf = String::new();
+
+                        type_annotation_to_html(0, &mut buf, type_ann, false, None);
+
+                        // Multiline annotations start on the line after the def's name.
+                        Some(buf.trim_start().to_string())
+                    }
+                };
+                let region = line_info.convert_region(doc_def.region);
+
+                defs.push(json!({
+                    "name": doc_def.name,
+                    "type_vars": doc_def.type_vars,
+                    "type": type_ann,
+                    "docs": doc_def.docs,
+                    "region": {
+                        "file": file,
+                        "start": position_json(region.start),
+                        "end": position_json(region.end),
+                    },
+                }));
+            }
+            DocEntry::DocDef(_) | DocEntry::DetachedDoc(_) => {}
+        }
+    }
+
+    json!({
+        "name": module.name,
+        "docs": module_docs,
+        "defs": defs,
+    })
+}
+
+fn position_json(position: LineColumn) -> Value {
+    json!({ "line": position.line, "column": position.column })
+}
@@ -5,7 +5,7 @@ extern crate roc_load;
 use bumpalo::Bump;
 use roc_can::scope::Scope;
 use roc_collections::VecSet;
-use roc_load::docs::{DocEntry, TypeAnnotation};
+use roc_load::docs::{DocDef, DocEntry, TypeAnnotation};
 use roc_load::docs::{ModuleDocumentation, RecordField};
 use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
@@ -15,11 +15,18 @@ use roc_parse::keyword;
 use roc_parse::state::State;
 use roc_problem::Severity;
 use roc_region::all::Region;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+mod json;
+mod search_index;
+
 const LINK_SVG: &str = include_str!("./static/link.svg");
 
+/// The type signature of every exposed def, keyed by its anchor (e.g. "Str#join").
+type Signatures = BTreeMap<String, String>;
+
 pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
     let mut loaded_module = load_module_for_docs(root_file);
     let exposed_module_docs = get_exposed_module_docs(&mut loaded_module);
@@ -114,15 +121,35 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
             render_sidebar(exposed_module_docs.iter().map(|(_, docs)| docs)).as_str(),
         );
 
-    let all_exposed_symbols = {
-        let mut set = VecSet::default();
+    let all_exposed_symbols = collect_exposed_symbols(&exposed_module_docs);
 
-        for (_, docs) in exposed_module_docs.iter() {
-            set.insert_all(docs.exposed_symbols.iter().copied());
-        }
+    let signatures = collect_signatures(&exposed_module_docs, &all_exposed_symbols);
 
-        set
-    };
+    // Write docs.json, so editors can show the same previews as the hover tooltips
+    {
+        let docs_json = serde_json::json!({ "signatures": signatures });
+
+        fs::write(build_dir.join("docs.json"), docs_json.to_string()).unwrap_or_else(|error| {
+            panic!("Attempted to write docs.json but failed with this error: {error}")
+        });
+    }
+
+    // Write search-index.json, which search.js loads to search the exposed defs
+    {
+        let search_index = search_index::build_search_index(
+            &exposed_module_docs,
+            &all_exposed_symbols,
+            &signatures,
+        );
+
+        fs::write(
+            build_dir.join("search-index.json"),
+            search_index.to_string(),
+        )
+        .unwrap_or_else(|error| {
+            panic!("Attempted to write search-index.json but failed with this error: {error}")
+        });
+    }
 
     // TODO fix: as is, this overrides an existing index.html
     // Write index.html for package (/index.html)
@@ -170,6 +197,7 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
                     module_docs,
                     &loaded_module,
                     &all_exposed_symbols,
+                    &signatures,
                 )
                 .as_str(),
             );
@@ -181,6 +209,25 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
     println!("🎉 Docs generated in {}", build_dir.display());
 }
 
+/// Writes a description of the package's API to `api.json` in `build_dir`, instead of HTML.
+pub fn generate_docs_json(root_file: PathBuf, build_dir: &Path) {
+    let mut loaded_module = load_module_for_docs(root_file);
+    let exposed_module_docs = get_exposed_module_docs(&mut loaded_module);
+    let all_exposed_symbols = collect_exposed_symbols(&exposed_module_docs);
+
+    let api_json = json::package_json(&exposed_module_docs, &all_exposed_symbols, &loaded_module);
+
+    fs::create_dir_all(build_dir).expect("TODO gracefully handle being unable to create build dir");
+
+    let path = build_dir.join("api.json");
+
+    fs::write(&path, api_json.to_string()).unwrap_or_else(|error| {
+        panic!("Attempted to write api.json but failed with this error: {error}")
+    });
+
+    println!("🎉 Docs generated in {}", path.display());
+}
+
 /// Gives only the module docs for modules that are exposed by the platform or package.
 fn get_exposed_module_docs(
     loaded_module: &mut LoadedModule,
@@ -199,6 +246,68 @@ fn get_exposed_module_docs(
     exposed_docs
 }
 
+fn collect_exposed_symbols(docs_by_module: &[(ModuleId, ModuleDocumentation)]) -> VecSet<Symbol> {
+    let mut set = VecSet::default();
+
+    for (_, docs) in docs_by_module.iter() {
+        set.insert_all(docs.exposed_symbols.iter().copied());
+    }
+
+    set
+}
+
+fn collect_signatures(
+    docs_by_module: &[(ModuleId, ModuleDocumentation)],
+    all_exposed_symbols: &VecSet<Symbol>,
+) -> Signatures {
+    let mut signatures = Signatures::new();
+
+    for (_, module) in docs_by_module.iter() {
+        for entry in &module.entries {
+            if let DocEntry::DocDef(doc_def) = entry {
+                if all_exposed_symbols.contains(&doc_def.symbol) {
+                    let mut signature = doc_def.name.clone();
+
+                    push_type_signature(&mut signature, doc_def, None);
+
+                    signatures.insert(format!("{}#{}", module.name, doc_def.name), signature);
+                }
+            }
+        }
+    }
+
+    signatures
+}
+
+/// What type signatures need to link each type name to its docs.
+struct TypeLinks<'a> {
+    all_exposed_symbols: &'a VecSet<Symbol>,
+    scope: &'a Scope,
+    interns: &'a Interns,
+}
+
+/// Pushes everything that follows a def's name in its header: type variables and annotation.
+/// With `links`, type names link to the docs of the module that defines them.
+fn push_type_signature(buf: &mut String, doc_def: &DocDef, links: Option<&TypeLinks>) {
+    for type_var in &doc_def.type_vars {
+        buf.push(' ');
+        buf.push_str(type_var.as_str());
+    }
+
+    let type_ann = &doc_def.type_annotation;
+
+    if !matches!(type_ann, TypeAnnotation::NoTypeAnn) {
+        // Ability declarations don't have ":" after the name, just `implements`
+        if !matches!(type_ann, TypeAnnotation::Ability { .. }) {
+            buf.push_str(" :");
+        }
+
+        buf.push(' ');
+
+        type_annotation_to_html(0, buf, type_ann, false, links);
+    }
+}
+
 fn page_title(package_name: &str, module_name: &str) -> String {
     format!("<title>{module_name} - {package_name}</title>")
 }
@@ -245,9 +354,15 @@ fn render_module_documentation(
     module: &ModuleDocumentation,
     root_module: &LoadedModule,
     all_exposed_symbols: &VecSet<Symbol>,
+    signatures: &Signatures,
 ) -> String {
     let mut buf = String::new();
     let module_name = module.name.as_str();
+    let type_links = TypeLinks {
+        all_exposed_symbols,
+        scope: &module.scope,
+        interns: &root_module.interns,
+    };
 
     push_html(&mut buf, "h2", vec![("class", "module-name")], {
         let mut link_buf = String::new();
@@ -270,24 +385,7 @@ fn render_module_documentation(
 
                     push_html(&mut content, "a", vec![("href", href.as_str())], LINK_SVG);
                     push_html(&mut content, "strong", vec![], def_name);
-
-                    for type_var in &doc_def.type_vars {
-                        content.push(' ');
-                        content.push_str(type_var.as_str());
-                    }
-
-                    let type_ann = &doc_def.type_annotation;
-
-                    if !matches!(type_ann, TypeAnnotation::NoTypeAnn) {
-                        // Ability declarations don't have ":" after the name, just `implements`
-                        if !matches!(type_ann, TypeAnnotation::Ability { .. }) {
-                            content.push_str(" :");
-                        }
-
-                        content.push(' ');
-
-                        type_annotation_to_html(0, &mut content, type_ann, false);
-                    }
+                    push_type_signature(&mut content, doc_def, Some(&type_links));
 
                     push_html(
                         &mut buf,
@@ -301,6 +399,7 @@ fn render_module_documentation(
                             &mut buf,
                             &root_module.filename(module_id),
                             all_exposed_symbols,
+                            signatures,
                             &module.scope,
                             docs,
                             root_module,
@@ -315,6 +414,7 @@ fn render_module_documentation(
                     &mut buf,
                     &root_module.filename(module_id),
                     all_exposed_symbols,
+                    signatures,
                     &module.scope,
                     docs,
                     root_module,
@@ -325,6 +425,7 @@ fn render_module_documentation(
                     &mut buf,
                     &root_module.filename,
                     all_exposed_symbols,
+                    signatures,
                     &module.scope,
                     docs,
                     root_module,
@@ -507,12 +608,36 @@ fn new_line(buf: &mut String) {
     buf.push('\n');
 }
 
+/// Pushes a type name, possibly qualified like `Dict.Dict`, as a link to its docs if we can find them.
+fn push_type_name(buf: &mut String, name: &str, links: Option<&TypeLinks>) {
+    let doc_url = links.and_then(|links| {
+        let (module_name, ident) = name.rsplit_once('.').unwrap_or(("", name));
+
+        doc_url(
+            links.all_exposed_symbols,
+            links.scope,
+            links.interns,
+            module_name,
+            ident,
+        )
+        .ok()
+    });
+
+    match doc_url {
+        Some(DocUrl { url, title }) => {
+            push_html(buf, "a", vec![("href", &url), ("title", &title)], name);
+        }
+        None => buf.push_str(name),
+    }
+}
+
 // html is written to buf
 fn type_annotation_to_html(
     indent_level: usize,
     buf: &mut String,
     type_ann: &TypeAnnotation,
     needs_parens: bool,
+    links: Option<&TypeLinks>,
 ) {
     let is_multiline = should_be_multiline(type_ann);
     match type_ann {
@@ -547,7 +672,7 @@ fn type_annotation_to_html(
 
                     for type_value in &tag.values {
                         buf.push(' ');
-                        type_annotation_to_html(next_indent_level, buf, type_value, true);
+                        type_annotation_to_html(next_indent_level, buf, type_value, true, links);
                     }
 
                     if is_multiline {
@@ -566,23 +691,23 @@ fn type_annotation_to_html(
                 buf.push(']');
             }
 
-            type_annotation_to_html(indent_level, buf, extension, true);
+            type_annotation_to_html(indent_level, buf, extension, true, links);
         }
         TypeAnnotation::BoundVariable(var_name) => {
             buf.push_str(var_name);
         }
         TypeAnnotation::Apply { name, parts } => {
             if parts.is_empty() {
-                buf.push_str(name);
+                push_type_name(buf, name, links);
             } else {
                 if needs_parens {
                     buf.push('(');
                 }
 
-                buf.push_str(name);
+                push_type_name(buf, name, links);
                 for part in parts {
                     buf.push(' ');
-                    type_annotation_to_html(indent_level, buf, part, true);
+                    type_annotation_to_html(indent_level, buf, part, true, links);
                 }
 
                 if needs_parens {
@@ -630,13 +755,25 @@ fn type_annotation_to_html(
                             type_annotation, ..
                         } => {
                             buf.push_str(" : ");
-                            type_annotation_to_html(next_indent_level, buf, type_annotation, false);
+                            type_annotation_to_html(
+                                next_indent_level,
+                                buf,
+                                type_annotation,
+                                false,
+                                links,
+                            );
                         }
                         RecordField::OptionalField {
                             type_annotation, ..
                         } => {
                             buf.push_str(" ? ");
-                            type_annotation_to_html(next_indent_level, buf, type_annotation, false);
+                            type_annotation_to_html(
+                                next_indent_level,
+                                buf,
+                                type_annotation,
+                                false,
+                                links,
+                            );
                         }
                         RecordField::LabelOnly { .. } => {}
                     }
@@ -659,7 +796,7 @@ fn type_annotation_to_html(
                 buf.push('}');
             }
 
-            type_annotation_to_html(indent_level, buf, extension, true);
+            type_annotation_to_html(indent_level, buf, extension, true, links);
         }
         TypeAnnotation::Function { args, output } => {
             let mut paren_is_open = false;
@@ -678,7 +815,7 @@ fn type_annotation_to_html(
                 }
 
                 let child_needs_parens = matches!(arg, TypeAnnotation::Function { .. });
-                type_annotation_to_html(indent_level, buf, arg, child_needs_parens);
+                type_annotation_to_html(indent_level, buf, arg, child_needs_parens, links);
 
                 if peekable_args.peek().is_some() {
                     buf.push_str(", ");
@@ -700,7 +837,7 @@ fn type_annotation_to_html(
                 next_indent_level += 1;
             }
 
-            type_annotation_to_html(next_indent_level, buf, output, false);
+            type_annotation_to_html(next_indent_level, buf, output, false, links);
             if needs_parens && paren_is_open {
                 buf.push(')');
             }
@@ -724,7 +861,13 @@ fn type_annotation_to_html(
                 buf.push_str(&member.name);
                 buf.push_str(" : ");
 
-                type_annotation_to_html(indent_level + 1, buf, &member.type_annotation, false);
+                type_annotation_to_html(
+                    indent_level + 1,
+                    buf,
+                    &member.type_annotation,
+                    false,
+                    links,
+                );
 
                 if !member.able_variables.is_empty() {
                     new_line(buf);
@@ -748,7 +891,7 @@ fn type_annotation_to_html(
 
                             buf.push(' ');
 
-                            type_annotation_to_html(indent_level + 2, buf, ann, false);
+                            type_annotation_to_html(indent_level + 2, buf, ann, false, links);
                         }
                     }
                 }
@@ -784,7 +927,7 @@ fn type_annotation_to_html(
                     indent(buf, next_indent_level);
                 }
 
-                type_annotation_to_html(next_indent_level, buf, elem, false);
+                type_annotation_to_html(next_indent_level, buf, elem, false, links);
 
                 if is_multiline {
                     if index < (elems_len - 1) {
@@ -801,10 +944,10 @@ fn type_annotation_to_html(
 
             buf.push(')');
 
-            type_annotation_to_html(indent_level, buf, extension, true);
+            type_annotation_to_html(indent_level, buf, extension, true, links);
         }
         TypeAnnotation::Where { ann, implements } => {
-            type_annotation_to_html(indent_level, buf, ann, false);
+            type_annotation_to_html(indent_level, buf, ann, false, links);
 
             new_line(buf);
             indent(buf, indent_level + 1);
@@ -837,12 +980,12 @@ fn type_annotation_to_html(
                         buf.push_str(" & ");
                     }
 
-                    type_annotation_to_html(indent_level, buf, ability, false);
+                    type_annotation_to_html(indent_level, buf, ability, false, links);
                 }
             }
         }
         TypeAnnotation::As { ann, name, vars } => {
-            type_annotation_to_html(indent_level, buf, ann, true);
+            type_annotation_to_html(indent_level, buf, ann, true, links);
             buf.push(' ');
             buf.push_str(name);
 
@@ -995,6 +1138,7 @@ fn markdown_to_html(
     buf: &mut String,
     filename: &Path,
     all_exposed_symbols: &VecSet<Symbol>,
+    signatures: &Signatures,
     scope: &Scope,
     markdown: &str,
     loaded_module: &LoadedModule,
@@ -1102,8 +1246,9 @@ fn markdown_to_html(
     let markdown_options =
         pulldown_cmark::Options::ENABLE_TABLES | pulldown_cmark::Options::ENABLE_HEADING_ATTRIBUTES;
 
-    let mut in_code_block: Option<CowStr> = None;
-    let mut to_highlight = String::new();
+    // The text of the Roc code block we're in, to highlight once we reach its end
+    let mut roc_code_block: Option<String> = None;
+    let base_url = base_url();
 
     let mut docs_parser = vec![];
     let parser = pulldown_cmark::Parser::new_with_broken_link_callback(
@@ -1133,50 +1278,65 @@ fn markdown_to_html(
 
                 docs_parser.push(event);
             }
-            Event::Start(CodeBlock(CodeBlockKind::Fenced(code_str))) => {
-                in_code_block = Some(code_str);
+            Event::Start(Link(LinkType::ShortcutUnknown, ref url, ref title)) => {
+                // Auto-links to documented defs carry their signature for hover previews
+                let signature = url
+                    .strip_prefix(base_url.as_str())
+                    .and_then(|anchor| signatures.get(anchor));
+
+                match signature {
+                    Some(signature) => {
+                        let mut anchor = String::new();
+
+                        anchor.push_str("<a href=\"");
+                        anchor.push_str(&escape_html_attribute(url));
+                        anchor.push_str("\" title=\"");
+                        anchor.push_str(&escape_html_attribute(title));
+                        anchor.push_str("\" data-type-signature=\"");
+                        anchor.push_str(&escape_html_attribute(signature));
+                        anchor.push_str("\">");
+
+                        docs_parser.push(Event::Html(CowStr::from(anchor)));
+                    }
+                    None => docs_parser.push(event),
+                }
             }
-            Event::End(CodeBlock(_)) => {
-                match in_code_block {
-                    Some(code_str) => {
-                        if code_str.contains("unchecked") {
+            Event::Start(CodeBlock(kind)) => {
+                let is_roc = match &kind {
+                    CodeBlockKind::Fenced(info) => {
+                        if info.contains("unchecked") {
                             // TODO HANDLE UNCHECKED
                         }
 
-                        if code_str.contains("repl") {
+                        if info.contains("repl") {
                             // TODO HANDLE REPL
                         }
 
                         // TODO HANDLE CHECKING BY DEFAULT
-                        let highlighted_html = roc_highlight::highlight_roc_code(&to_highlight);
-                        docs_parser.push(Event::Html(CowStr::from(highlighted_html)));
+                        is_roc_code_block(info)
                     }
-                    None => {
-                        // Indented code block
+                    CodeBlockKind::Indented => true,
+                };
 
-                        let highlighted_html = roc_highlight::highlight_roc_code(&to_highlight);
-                        docs_parser.push(Event::Html(CowStr::from(highlighted_html)));
-                    }
+                if is_roc {
+                    roc_code_block = Some(String::new());
+                } else {
+                    // Other languages are left for the markdown renderer to escape
+                    docs_parser.push(Event::Start(CodeBlock(kind)));
                 }
-
-                // Reset codeblock buffer
-                to_highlight = String::new();
-                in_code_block = None;
-
-                // Push Event::End(CodeBlock)
-                docs_parser.push(event);
             }
-            Event::Text(t) => {
-                match in_code_block {
-                    Some(_) => {
-                        // If we're in a code block, build up the string of text
-                        to_highlight.push_str(&t);
-                    }
-                    None => {
-                        docs_parser.push(Event::Text(t));
-                    }
+            Event::End(CodeBlock(kind)) => match roc_code_block.take() {
+                Some(code) => {
+                    // The highlighted html has its own <pre>, so there's no End(CodeBlock) to push
+                    let highlighted_html = roc_highlight::highlight_roc_code(&code);
+                    docs_parser.push(Event::Html(CowStr::from(highlighted_html)));
                 }
-            }
+                None => docs_parser.push(Event::End(CodeBlock(kind))),
+            },
+            Event::Text(t) => match &mut roc_code_block {
+                Some(code) => code.push_str(&t),
+                None => docs_parser.push(Event::Text(t)),
+            },
             Event::Html(html) => {
                 docs_parser.push(Event::Text(html));
             }
@@ -1189,6 +1349,33 @@ fn markdown_to_html(
     pulldown_cmark::html::push_html(buf, docs_parser.into_iter());
 }
 
+/// Whether a fenced code block's info string, like `roc`, `roc repl` or `sh`, says it's Roc code.
+/// Blocks that don't name a language are Roc code too.
+fn is_roc_code_block(info: &str) -> bool {
+    let language = info
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .next()
+        .unwrap_or_default();
+
+    language.is_empty() || language == "roc"
+}
+
+fn escape_html_attribute(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
 /// TODO: this should be moved into Reporting, and the markdown checking
 /// for docs should be part of `roc check`. Problems like these should
 /// be reported as `roc check` warnings and included in the total count
@@ -1199,6 +1386,7 @@ fn report_markdown_link_problem(
     link_markdown: &str,
     problem: LinkProblem,
 ) {
+    use roc_reporting::code::ReportKind;
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
@@ -1237,7 +1425,7 @@ fn report_markdown_link_problem(
         Report {
             filename,
             doc,
-            title: "INVALID DOCS LINK".to_string(),
+            title: ReportKind::InvalidDocsLink.into(),
             severity: Severity::Warning,
         }
     };
@@ -0,0 +1,52 @@
+//! Builds `search-index.json`, which the search box in `search.js` looks exposed defs up in.
+//! Having everything in one file lets the generated docs be searched without a server-side index.
+use crate::Signatures;
+use roc_collections::VecSet;
+use roc_load::docs::{DocEntry, ModuleDocumentation};
+use roc_module::symbol::{ModuleId, Symbol};
+
+/// One entry per exposed def, in the order the modules and their defs appear in the sidebar:
+///
+/// ```json
+/// { "name": "join", "module": "Str", "signature": "join : ...", "doc": "...", "url": "Str#join" }
+/// ```
+pub fn build_search_index(
+    docs_by_module: &[(ModuleId, ModuleDocumentation)],
+    all_exposed_symbols: &VecSet<Symbol>,
+    signatures: &Signatures,
+) -> serde_json::Value {
+    let mut entries = Vec::new();
+
+    for (_, module) in docs_by_module.iter() {
+        for entry in &module.entries {
+            if let DocEntry::DocDef(doc_def) = entry {
+                if all_exposed_symbols.contains(&doc_def.symbol) {
+                    let url = format!("{}#{}", module.name, doc_def.name);
+                    let signature = signatures.get(&url).map_or("", String::as_str);
+                    let doc = doc_def.docs.as_deref().map(first_paragraph);
+
+                    entries.push(serde_json::json!({
+                        "name": doc_def.name,
+                        "module": module.name,
+                        "signature": signature,
+                        "doc": doc.unwrap_or_default(),
+                        "url": url,
+                    }));
+                }
+            }
+        }
+    }
+
+    serde_json::Value::Array(entries)
+}
+
+/// The first paragraph of a doc comment, on one line. It stays Markdown; the search results
+/// show it as plain text.
+fn first_paragraph(docs: &str) -> String {
+    docs.trim_start()
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,11 +1,125 @@
 (() => {
     let sidebar = document.getElementById("sidebar-nav");
     let searchBox = document.getElementById("module-search");
+    let searchResults = document.getElementById("search-results");
+
+    // The exposed defs of every module, from search-index.json. Loaded the first time someone
+    // searches, so that pages which are only read don't have to download it.
+    let searchIndex = null;
+    let searchIndexRequested = false;
+
+    const MAX_SEARCH_RESULTS = 20;
+
+    function loadSearchIndex(onLoad) {
+        if (searchIndexRequested) {
+            return;
+        }
+
+        searchIndexRequested = true;
+
+        fetch(new URL("search-index.json", document.baseURI))
+            .then((response) => response.json())
+            .then((entries) => {
+                // Lowercase everything up front, so each keypress only has to compare strings.
+                searchIndex = entries.map((entry) => ({
+                    entry,
+                    name: entry.name.toLowerCase(),
+                    qualifiedName: `${entry.module}.${entry.name}`.toLowerCase(),
+                    signature: entry.signature.toLowerCase(),
+                    doc: entry.doc.toLowerCase(),
+                }));
+                onLoad();
+            })
+            .catch(() => {
+                // Without the index, searching still filters the sidebar.
+                searchIndexRequested = false;
+            });
+    }
+
+    // Lower ranks are better matches; null means no match at all.
+    function searchRank(item, text) {
+        if (item.name === text || item.qualifiedName === text) {
+            return 0;
+        } else if (item.name.startsWith(text)) {
+            return 1;
+        } else if (item.qualifiedName.includes(text)) {
+            return 2;
+        } else if (item.signature.includes(text)) {
+            return 3;
+        } else if (item.doc.includes(text)) {
+            return 4;
+        } else {
+            return null;
+        }
+    }
+
+    function renderSearchResults(text) {
+        if (searchResults == null) {
+            return;
+        }
+
+        searchResults.replaceChildren();
+
+        if (text === "" || searchIndex == null) {
+            searchResults.classList.add("hidden");
+            return;
+        }
+
+        let matches = [];
+
+        searchIndex.forEach((item, index) => {
+            let rank = searchRank(item, text);
+
+            if (rank !== null) {
+                matches.push({ item, rank, index });
+            }
+        });
+
+        // Ties keep the order of the sidebar.
+        matches.sort((a, b) => a.rank - b.rank || a.index - b.index);
+
+        matches.slice(0, MAX_SEARCH_RESULTS).forEach(({ item }) => {
+            let { entry } = item;
+            let link = document.createElement("a");
+            link.href = entry.url;
+
+            let name = document.createElement("span");
+            name.classList.add("search-result-name");
+            name.textContent = `${entry.module}.${entry.name}`;
+            link.appendChild(name);
+
+            if (entry.signature !== "") {
+                let signature = document.createElement("code");
+                signature.classList.add("search-result-signature");
+                signature.textContent = entry.signature;
+                link.appendChild(signature);
+            }
+
+            if (entry.doc !== "") {
+                let doc = document.createElement("span");
+                doc.classList.add("search-result-doc");
+                doc.textContent = entry.doc;
+                link.appendChild(doc);
+            }
+
+            let listItem = document.createElement("li");
+            listItem.appendChild(link);
+            searchResults.appendChild(listItem);
+        });
+
+        searchResults.classList.toggle("hidden", matches.length === 0);
+    }
 
     if (searchBox != null) {
         function search() {
             let text = searchBox.value.toLowerCase(); // Search is case-insensitive.
 
+            if (text !== "" && searchIndex == null) {
+                loadSearchIndex(search);
+            }
+
+            renderSearchResults(text);
+
             if (text === "") {
                 // Un-hide everything
                 sidebar
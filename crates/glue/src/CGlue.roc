@@ -0,0 +1,641 @@
+app [makeGlue] { pf: platform "../platform/main.roc" }
+
+import pf.Types exposing [Types]
+import pf.Shape exposing [Shape]
+import pf.File exposing [File]
+import pf.TypeId exposing [TypeId, typeIDtoU64]
+
+makeGlue : List Types -> Result (List File) Str
+makeGlue = \typesByArch ->
+    when List.first typesByArch is
+        Ok types ->
+            # Every target has the same shapes; only their sizes and alignments differ.
+            # C lays out the structs below the same way Roc does, so we declare them once
+            # and then check the layouts for each target separately.
+            content =
+                fileHeader
+                |> generateDeclarations types
+                |> generateEntryPoints types
+                |> \buf -> List.walk typesByArch buf generateLayoutChecks
+                |> Str.concat fileFooter
+
+            Ok [{ name: "roc_app.h", content }]
+
+        Err ListWasEmpty ->
+            Err "I can't generate C glue without any targets."
+
+DeclState : { buf : Str, declared : Set U64, deferred : Str }
+
+generateDeclarations : Str, Types -> Str
+generateDeclarations = \buf, types ->
+    # Pointers to these can appear before their definitions, e.g. in recursive tag unions.
+    forwardDeclarations =
+        Types.walkShapes types buf \accum, shape, _id ->
+            when shape is
+                TagUnion (Enumeration _) ->
+                    accum
+
+                TagUnion (Recursive { name }) ->
+                    Str.concat accum "struct $(name);\nunion union_$(name);\n"
+
+                TagUnion (NullableWrapped { name }) ->
+                    Str.concat accum "struct $(name);\nunion union_$(name);\n"
+
+                _ ->
+                    when structName shape is
+                        Ok name -> Str.concat accum "struct $(name);\n"
+                        Err NotAStruct -> accum
+
+    initial = { buf: Str.concat forwardDeclarations "\n", declared: Set.empty {}, deferred: "" }
+    state = Types.walkShapes types initial \accum, _shape, id -> declare accum types id
+
+    # The unions inside recursive tag unions hold their payloads by value, so they go last.
+    Str.concat state.buf state.deferred
+
+## Declares a type, after first declaring every type it holds by value.
+declare : DeclState, Types, TypeId -> DeclState
+declare = \state, types, id ->
+    key = typeIDtoU64 id
+
+    if Set.contains state.declared key then
+        state
+    else
+        shape = Types.shape types id
+        marked = { state & declared: Set.insert state.declared key }
+        withDeps = List.walk (valueDependencies shape) marked \accum, dep -> declare accum types dep
+
+        generateDeclaration withDeps types id shape
+
+valueDependencies : Shape -> List TypeId
+valueDependencies = \shape ->
+    when shape is
+        Struct { fields } ->
+            fieldIds fields
+
+        TagUnionPayload { fields } ->
+            fieldIds fields
+
+        TagUnion (NonRecursive { tags }) ->
+            List.keepOks tags \{ payload } ->
+                when payload is
+                    Some payloadId -> Ok payloadId
+                    None -> Err NoPayload
+
+        TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
+            List.map fields .id
+
+        RocResult okId errId ->
+            [okId, errId]
+
+        # A recursive tag union is only a pointer, but fields of this shape still hold it by value.
+        RecursivePointer content ->
+            [content]
+
+        _ ->
+            []
+
+generateDeclaration : DeclState, Types, TypeId, Shape -> DeclState
+generateDeclaration = \state, types, id, shape ->
+    when shape is
+        Struct { name, fields } ->
+            { state & buf: generateStruct state.buf types name (namedFields fields) }
+
+        TagUnionPayload { name, fields } ->
+            { state & buf: generateStruct state.buf types name (namedFields fields) }
+
+        TagUnion (Enumeration { name, tags, size }) ->
+            { state & buf: generateEnumeration state.buf name tags size }
+
+        TagUnion (NonRecursive { name, tags, discriminantSize }) ->
+            { state & buf: generateNonRecursiveTagUnion state.buf types name tags discriminantSize }
+
+        TagUnion (Recursive { name, tags, discriminantSize }) ->
+            generateRecursiveTagUnion state types name tags discriminantSize None
+
+        TagUnion (NullableWrapped { name, indexOfNullTag, tags, discriminantSize }) ->
+            generateRecursiveTagUnion state types name tags discriminantSize (Some indexOfNullTag)
+
+        TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload }) ->
+            payloadType = typeName types nonNullPayload
+            buf =
+                """
+                $(state.buf)
+                // NULL means `$(nullTag)`; anything else points to the payload of `$(nonNullTag)`.
+                struct $(name) {
+                    $(payloadType) *pointer;
+                };
+
+                """
+
+            { state & buf }
+
+        TagUnion (NonNullableUnwrapped { name, tagName, payload }) ->
+            payloadType = typeName types payload
+            buf =
+                """
+                $(state.buf)
+                // Points to the payload of `$(tagName)`, the only tag.
+                struct $(name) {
+                    $(payloadType) *pointer;
+                };
+
+                """
+
+            { state & buf }
+
+        TagUnion (SingleTagStruct { name, payload: HasNoClosure fields }) ->
+            indexedFields = List.mapWithIndex fields \{ id: fieldId }, index -> { name: "f$(Num.toStr index)", id: fieldId }
+
+            { state & buf: generateStruct state.buf types name indexedFields }
+
+        TagUnion (SingleTagStruct { name, payload: HasClosure _ }) ->
+            { state & buf: Str.concat state.buf "// TODO: $(name) has a closure in its payload, which C glue doesn't support yet.\n\n" }
+
+        RocResult okId errId ->
+            members =
+                [{ name: "err", id: errId }, { name: "ok", id: okId }]
+                |> List.keepIf \{ id: memberId } -> !(isUnit (Types.shape types memberId))
+                |> generateMembers types
+
+            { state & buf: generateTagged state.buf (typeName types id) members "    // 0 is `Err`, 1 is `Ok`\n    uint8_t discriminant;\n" }
+
+        _ ->
+            state
+
+fieldIds = \fields ->
+    when fields is
+        HasNoClosure xs -> List.map xs .id
+        HasClosure xs -> List.map xs .id
+
+namedFields = \fields ->
+    when fields is
+        HasNoClosure xs -> List.map xs \{ name, id } -> { name, id }
+        HasClosure xs -> List.map xs \{ name, id } -> { name, id }
+
+generateStruct : Str, Types, Str, List { name : Str, id : TypeId } -> Str
+generateStruct = \buf, types, name, fields ->
+    members =
+        fields
+        |> List.keepIf \{ id } -> !(isUnit (Types.shape types id))
+        |> List.map \{ name: fieldName, id } -> { name: escapeKW fieldName, id }
+        |> generateMembers types
+
+    """
+    $(buf)
+    struct $(name) {
+    $(members)};
+
+    """
+
+generateEnumeration : Str, Str, List Str, U32 -> Str
+generateEnumeration = \buf, name, tags, size ->
+    constants =
+        tags
+        |> List.mapWithIndex \tag, index -> "    $(name)_$(tag) = $(Num.toStr index),\n"
+        |> Str.joinWith ""
+
+    """
+    $(buf)
+    typedef $(discriminantType size) $(name);
+
+    enum {
+    $(constants)};
+
+    """
+
+generateNonRecursiveTagUnion : Str, Types, Str, List { name : Str, payload : [Some TypeId, None] }, U32 -> Str
+generateNonRecursiveTagUnion = \buf, types, name, tags, discriminantSize ->
+    members =
+        tags
+        |> List.keepOks \{ name: tagName, payload } ->
+            when payload is
+                Some payloadId if !(isUnit (Types.shape types payloadId)) -> Ok { name: tagName, id: payloadId }
+                _ -> Err NoPayload
+        |> generateMembers types
+
+    constructors =
+        tags
+        |> List.map \{ name: tagName, payload } ->
+            when payload is
+                Some payloadId if !(isUnit (Types.shape types payloadId)) ->
+                    """
+                    static inline struct $(name) $(name)_$(tagName)($(typeName types payloadId) payload) {
+                        struct $(name) answer;
+                        answer.payload.$(tagName) = payload;
+                        answer.discriminant = discriminant_$(name)_$(tagName);
+                        return answer;
+                    }
+
+                    """
+
+                _ ->
+                    """
+                    static inline struct $(name) $(name)_$(tagName)(void) {
+                        struct $(name) answer = { 0 };
+                        answer.discriminant = discriminant_$(name)_$(tagName);
+                        return answer;
+                    }
+
+                    """
+        |> Str.joinWith ""
+
+    buf
+    |> generateDiscriminant name (List.map tags .name)
+    |> generateTagged "struct $(name)" members "    $(discriminantType discriminantSize) discriminant;\n"
+    |> Str.concat constructors
+
+generateRecursiveTagUnion : DeclState, Types, Str, List { name : Str, payload : [Some TypeId, None] }, U32, [Some U16, None] -> DeclState
+generateRecursiveTagUnion = \state, types, name, tags, _discriminantSize, nullTagIndex ->
+    tagNames = List.map tags .name
+
+    (nullComment, nullCheck) =
+        when nullTagIndex is
+            Some index ->
+                nullTag = List.get tagNames (Num.toU64 index) |> Result.withDefault ""
+
+                (
+                    " NULL means `$(nullTag)`.",
+                    "    if (value.pointer == NULL) {\n        return discriminant_$(name)_$(nullTag);\n    }\n\n",
+                )
+
+            None ->
+                ("", "")
+
+    declarations =
+        state.buf
+        |> generateDiscriminant name tagNames
+        |> Str.concat
+            """
+            // Points to a `union union_$(name)`, with the tag id stored in its lowest bits.$(nullComment)
+            struct $(name) {
+                union union_$(name) *pointer;
+            };
+
+            static inline enum discriminant_$(name) $(name)_discriminant(struct $(name) value) {
+            $(nullCheck)    uintptr_t mask = sizeof(void *) == 8 ? 0x7 : 0x3;
+                return (enum discriminant_$(name))((uintptr_t)value.pointer & mask);
+            }
+
+            static inline union union_$(name) *$(name)_unmasked_pointer(struct $(name) value) {
+                uintptr_t mask = sizeof(void *) == 8 ? 0x7 : 0x3;
+                return (union union_$(name) *)((uintptr_t)value.pointer & ~mask);
+            }
+
+            """
+
+    members =
+        tags
+        |> List.keepOks \{ name: tagName, payload } ->
+            when payload is
+                Some payloadId if !(isUnit (Types.shape types payloadId)) -> Ok { name: tagName, id: payloadId }
+                _ -> Err NoPayload
+        |> generateMembers types
+
+    # The payloads hold `struct $(name)` by value, and may not have been declared yet.
+    deferred =
+        """
+        $(state.deferred)
+        union union_$(name) {
+        $(members)};
+
+        """
+
+    { state & buf: declarations, deferred }
+
+generateDiscriminant : Str, Str, List Str -> Str
+generateDiscriminant = \buf, name, tagNames ->
+    constants =
+        tagNames
+        |> List.mapWithIndex \tag, index -> "    discriminant_$(name)_$(tag) = $(Num.toStr index),\n"
+        |> Str.joinWith ""
+
+    """
+    $(buf)
+    enum discriminant_$(name) {
+    $(constants)};
+
+    """
+
+## A struct with a union of payloads, followed by whatever tells them apart.
+generateTagged : Str, Str, Str, Str -> Str
+generateTagged = \buf, cName, members, discriminant ->
+    payload =
+        if Str.isEmpty members then
+            ""
+        else
+            "    union {\n$(indentLines members)    } payload;\n"
+
+    """
+    $(buf)
+    $(cName) {
+    $(payload)$(discriminant)};
+
+    """
+
+generateMembers : List { name : Str, id : TypeId }, Types -> Str
+generateMembers = \members, types ->
+    members
+    |> List.map \{ name, id } -> "    $(typeName types id) $(name);\n"
+    |> Str.joinWith ""
+
+indentLines : Str -> Str
+indentLines = \lines ->
+    lines
+    |> Str.split "\n"
+    |> List.map \line -> if Str.isEmpty line then line else "    $(line)"
+    |> Str.joinWith "\n"
+
+generateEntryPoints : Str, Types -> Str
+generateEntryPoints = \buf, types ->
+    List.walk (Types.entryPoints types) buf \accum, T name id -> generateEntryPoint accum types name id
+
+generateEntryPoint : Str, Types, Str, TypeId -> Str
+generateEntryPoint = \buf, types, name, id ->
+    (params, sizeDeclaration) =
+        when Types.shape types id is
+            Function rocFn ->
+                args =
+                    List.walkWithIndex rocFn.args [] \accum, argId, index ->
+                        shape = Types.shape types argId
+                        indexStr = Num.toStr index
+
+                        if isUnit shape then
+                            accum
+                        else if isTrivial types shape then
+                            List.append accum "$(typeName types argId) arg$(indexStr)"
+                        else
+                            List.append accum "$(typeName types argId) *arg$(indexStr)"
+
+                when Types.shape types rocFn.ret is
+                    Function _ ->
+                        (
+                            List.prepend args "uint8_t *closure_data",
+                            "int64_t roc__$(name)_1_exposed_size(void);\n",
+                        )
+
+                    _ ->
+                        (List.prepend args "$(typeName types rocFn.ret) *ret", "")
+
+            _ ->
+                (["$(typeName types id) *ret"], "")
+
+    """
+    $(buf)
+    $(sizeDeclaration)void roc__$(name)_1_exposed_generic($(Str.joinWith params ", "));
+
+    """
+
+generateLayoutChecks : Str, Types -> Str
+generateLayoutChecks = \buf, types ->
+    arch = (Types.target types).architecture
+
+    checks =
+        Types.walkShapes types "" \accum, shape, id ->
+            when shape is
+                TagUnion (SingleTagStruct { payload: HasClosure _ }) ->
+                    accum
+
+                Struct _ | TagUnionPayload _ | TagUnion _ ->
+                    cName = typeName types id
+                    size = Num.toStr (Types.size types id)
+                    align = Num.toStr (Types.alignment types id)
+
+                    """
+                    $(accum)_Static_assert(sizeof($(cName)) == $(size), "$(cName) has the wrong size");
+                    _Static_assert(_Alignof($(cName)) == $(align), "$(cName) has the wrong alignment");
+
+                    """
+
+                _ ->
+                    accum
+
+    """
+    $(buf)
+    #if $(archCondition arch)
+    $(checks)#endif
+
+    """
+
+## Whether a value of this type can be passed to Roc directly, rather than behind a pointer.
+isTrivial : Types, Shape -> Bool
+isTrivial = \types, shape ->
+    when shape is
+        Unit | EmptyTagUnion | Bool | Num _ | TagUnion (Enumeration _) -> Bool.true
+        Struct { fields: HasNoClosure fields } | TagUnionPayload { fields: HasNoClosure fields } ->
+            List.all fields \{ id } -> isTrivial types (Types.shape types id)
+
+        TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
+            List.all fields \{ id } -> isTrivial types (Types.shape types id)
+
+        TagUnion (NonRecursive { tags }) ->
+            List.all tags \{ payload } ->
+                when payload is
+                    Some id -> isTrivial types (Types.shape types id)
+                    None -> Bool.true
+
+        RocResult okId errId ->
+            isTrivial types (Types.shape types okId) && isTrivial types (Types.shape types errId)
+
+        _ -> Bool.false
+
+isUnit : Shape -> Bool
+isUnit = \shape ->
+    when shape is
+        Unit | EmptyTagUnion -> Bool.true
+        _ -> Bool.false
+
+typeName : Types, TypeId -> Str
+typeName = \types, id ->
+    when Types.shape types id is
+        Unit | EmptyTagUnion -> "void"
+        Unsized -> "struct RocList"
+        RocStr -> "struct RocStr"
+        Bool -> "bool"
+        Num U8 -> "uint8_t"
+        Num U16 -> "uint16_t"
+        Num U32 -> "uint32_t"
+        Num U64 -> "uint64_t"
+        Num U128 -> "__uint128_t"
+        Num I8 -> "int8_t"
+        Num I16 -> "int16_t"
+        Num I32 -> "int32_t"
+        Num I64 -> "int64_t"
+        Num I128 -> "__int128_t"
+        Num F32 -> "float"
+        Num F64 -> "double"
+        Num Dec -> "struct RocDec"
+        RocDict _ _ -> crash "RocDict is not yet supported in C"
+        RocSet _ -> crash "RocSet is not yet supported in C"
+        RocList _ -> "struct RocList"
+        RocBox elem -> "$(typeName types elem) *"
+        RocResult _ _ -> "struct RocResult_$(Num.toStr (typeIDtoU64 id))"
+        RecursivePointer content -> typeName types content
+        TagUnion (Enumeration { name }) -> name
+        Function _ -> crash "Functions are not yet supported in C"
+        shape ->
+            when structName shape is
+                Ok name -> "struct $(name)"
+                Err NotAStruct -> crash "Unexpected shape in C glue"
+
+## The name of the C struct that represents this shape, if any.
+structName : Shape -> Result Str [NotAStruct]
+structName = \shape ->
+    when shape is
+        Struct { name } -> Ok name
+        TagUnionPayload { name } -> Ok name
+        TagUnion (NonRecursive { name }) -> Ok name
+        TagUnion (Recursive { name }) -> Ok name
+        TagUnion (NullableWrapped { name }) -> Ok name
+        TagUnion (NullableUnwrapped { name }) -> Ok name
+        TagUnion (NonNullableUnwrapped { name }) -> Ok name
+        TagUnion (SingleTagStruct { name }) -> Ok name
+        _ -> Err NotAStruct
+
+discriminantType : U32 -> Str
+discriminantType = \size ->
+    when size is
+        0 | 1 -> "uint8_t"
+        2 -> "uint16_t"
+        _ -> "uint32_t"
+
+archCondition = \arch ->
+    when arch is
+        Aarch32 -> "defined(__arm__) || defined(_M_ARM)"
+        Aarch64 -> "defined(__aarch64__) || defined(_M_ARM64)"
+        Wasm32 -> "defined(__wasm32__)"
+        X86x32 -> "defined(__i386__) || defined(_M_IX86)"
+        X86x64 -> "defined(__x86_64__) || defined(_M_X64)"
+
+escapeKW = \input ->
+    # Tag union payloads have numbered fields, and C doesn't allow those either.
+    if Set.contains reservedKeywords input || Result.isOk (Str.toU64 input) then
+        "f$(input)"
+    else
+        input
+
+reservedKeywords = Set.fromList [
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "true",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+]
+
+fileHeader =
+    """
+    // ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+    #ifndef ROC_APP_H
+    #define ROC_APP_H
+
+    #include <stdbool.h>
+    #include <stddef.h>
+    #include <stdint.h>
+
+    // Small strings keep their bytes inline, and set the highest bit of their last byte.
+    // Seamless slices store (a shifted pointer to) their original allocation instead of a capacity.
+    struct RocStr {
+        uint8_t *bytes;
+        size_t length;
+        size_t capacity_or_alloc_ptr;
+    };
+
+    struct RocList {
+        uint8_t *elements;
+        size_t length;
+        size_t capacity_or_alloc_ptr;
+    };
+
+    struct RocDec {
+        __int128_t value;
+    };
+
+    static inline bool roc_str_is_small(struct RocStr str) {
+        return (intptr_t)str.capacity_or_alloc_ptr < 0;
+    }
+
+    // The host must provide these.
+    void *roc_alloc(size_t size, uint32_t alignment);
+    void *roc_realloc(void *ptr, size_t new_size, size_t old_size, uint32_t alignment);
+    void roc_dealloc(void *ptr, uint32_t alignment);
+    void roc_panic(struct RocStr *msg, uint32_t tag_id);
+    void roc_dbg(struct RocStr *loc, struct RocStr *msg, struct RocStr *src);
+    void *roc_memset(void *ptr, int value, size_t count);
+
+    // Roc stores the reference count of a heap allocation in the word just before the data
+    // pointer it hands out (after masking off any tag id). A count of 0 marks read-only data.
+    #define ROC_REFCOUNT_ONE INTPTR_MIN
+
+    static inline intptr_t *roc_refcount_ptr(void *data) {
+        return (intptr_t *)data - 1;
+    }
+
+    static inline void roc_incref(void *data) {
+        intptr_t *refcount = roc_refcount_ptr(data);
+
+        if (*refcount != 0) {
+            *refcount += 1;
+        }
+    }
+
+    // Frees the allocation when this was the last reference. This does not decref whatever
+    // the allocation points to. Lists of refcounted elements also store their element count
+    // in front of the refcount, so pass `elements_refcounted` for those.
+    static inline void roc_decref(void *data, uint32_t alignment, bool elements_refcounted) {
+        intptr_t *refcount = roc_refcount_ptr(data);
+
+        if (*refcount == 0) {
+            return;
+        }
+
+        if (*refcount == ROC_REFCOUNT_ONE) {
+            size_t header_bytes = (elements_refcounted ? 2 : 1) * sizeof(intptr_t);
+            size_t extra_bytes = alignment > header_bytes ? alignment : header_bytes;
+            uint32_t min_alignment = (uint32_t)sizeof(intptr_t);
+
+            roc_dealloc((uint8_t *)data - extra_bytes, alignment > min_alignment ? alignment : min_alignment);
+        } else {
+            *refcount -= 1;
+        }
+    }
+
+
+    """
+
+fileFooter =
+    """
+    #endif // ROC_APP_H
+
+    """
@@ -315,11 +315,22 @@ generateStruct = \buf, types, id, name, structFields, visibility ->
 
     structType = Types.shape types id
 
+    sizeOfSelf = Num.toStr (Types.size types id)
+    alignOfSelf = Num.toStr (Types.alignment types id)
+
     buf
     |> generateDeriveStr types structType IncludeDebug
     |> Str.concat "#[repr($(repr))]\n$(pub)struct $(escapedName) {\n"
     |> generateStructFields types Public structFields
-    |> Str.concat "}\n\n"
+    |> Str.concat
+        """
+        }
+
+        const _SIZE_CHECK_$(escapedName): () = assert!(core::mem::size_of::<$(escapedName)>() == $(sizeOfSelf));
+        const _ALIGN_CHECK_$(escapedName): () = assert!(core::mem::align_of::<$(escapedName)>() == $(alignOfSelf));
+
+
+        """
     |> generateRocRefcounted types structType escapedName
 
 generateStructFields = \buf, types, visibility, structFields ->
@@ -614,6 +625,52 @@ generateConstructorFunction = \buf, types, tagUnionType, name, optPayload ->
                 }
             """
 
+generateFromImpls : Str, Types, Str, List { name : Str, payload : [Some TypeId, None] } -> Str
+generateFromImpls = \buf, types, tagUnionType, tags ->
+    payloadTypes =
+        List.keepOks tags \{ payload } ->
+            when payload is
+                Some payloadId -> Ok (typeName types payloadId)
+                None -> Err NoPayload
+
+    List.walk tags buf \accum, { name, payload } ->
+        when payload is
+            # `From` can only pick a tag when no other tag has the same payload type.
+            Some payloadId if List.countIf payloadTypes (\t -> t == typeName types payloadId) == 1 ->
+                accum
+                |> generateFromImpl tagUnionType name (typeName types payloadId) "payload"
+                |> \b ->
+                    when Types.shape types payloadId is
+                        RocStr ->
+                            generateFromImpl b tagUnionType name "&str" "roc_std::RocStr::from(payload)"
+
+                        RocList elem ->
+                            elemShape = Types.shape types elem
+                            isClone =
+                                when elemShape is
+                                    RocStr -> Bool.true
+                                    _ -> canDeriveCopy types elemShape
+
+                            if isClone then
+                                generateFromImpl b tagUnionType name "&[$(typeName types elem)]" "roc_std::RocList::from_slice(payload)"
+                            else
+                                b
+
+                        _ -> b
+
+            _ -> accum
+
+generateFromImpl = \buf, tagUnionType, tagName, fromType, toPayload ->
+    """
+    $(buf)
+    impl From<$(fromType)> for $(tagUnionType) {
+        fn from(payload: $(fromType)) -> Self {
+            Self::$(tagName)($(toPayload))
+        }
+    }
+
+    """
+
 generateDestructorFunctions : Str, Types, Str, List { name : Str, payload : [Some TypeId, None] } -> Str
 generateDestructorFunctions = \buf, types, tagUnionType, tags ->
     buf
@@ -784,6 +841,7 @@ generateNonRecursiveTagUnion = \buf, types, id, name, tags, discriminantSize, di
     |> deriveHashTagUnion types shape escapedName tags
     |> generateDestructorFunctions types escapedName tags
     |> generateConstructorFunctions types escapedName tags
+    |> generateFromImpls types escapedName tags
     |> \b ->
         if cannotSupportCopy types unionType then
             # A custom drop impl is only needed when we can't derive copy.
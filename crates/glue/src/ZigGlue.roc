@@ -1,35 +1,492 @@
-app "zig-glue"
-    packages { pf: "../platform/main.roc" }
-    imports [
-        pf.Types.{ Types },
-        pf.File.{ File },
-        "../../compiler/builtins/bitcode/src/list.zig" as rocStdList : Str,
-        "../../compiler/builtins/bitcode/src/str.zig" as rocStdStr : Str,
-        "../../compiler/builtins/bitcode/src/utils.zig" as rocStdUtils : Str,
-    ]
-    provides [makeGlue] to pf
+app [makeGlue] { pf: platform "../platform/main.roc" }
+
+import pf.Types exposing [Types]
+import pf.Shape exposing [Shape]
+import pf.File exposing [File]
+import pf.TypeId exposing [TypeId, typeIDtoU64]
+import "../../compiler/builtins/bitcode/src/list.zig" as rocStdList : Str
+import "../../compiler/builtins/bitcode/src/panic.zig" as rocStdPanic : Str
+import "../../compiler/builtins/bitcode/src/sort.zig" as rocStdSort : Str
+import "../../compiler/builtins/bitcode/src/str.zig" as rocStdStr : Str
+import "../../compiler/builtins/bitcode/src/utils.zig" as rocStdUtils : Str
 
 makeGlue : List Types -> Result (List File) Str
 makeGlue = \typesByArch ->
-    typesByArch
-    |> List.map convertTypesToFile
-    |> List.concat staticFiles
-    |> Ok
+    when List.first typesByArch is
+        Ok types ->
+            # Every target has the same shapes; only their sizes and alignments differ.
+            # Zig lays out extern structs the same way Roc does, so we declare them once
+            # and then check the layouts for each target separately.
+            content =
+                fileHeader
+                |> generateDeclarations types
+                |> generateEntryPoints types
+                |> \buf -> List.walk typesByArch buf generateLayoutChecks
+
+            Ok (List.append staticFiles { name: "roc_app.zig", content })
+
+        Err ListWasEmpty ->
+            Err "I can't generate Zig glue without any targets."
 
 ## These are always included, and don't depend on the specifics of the app.
 staticFiles : List File
 staticFiles = [
     { name: "list.zig", content: rocStdList },
+    { name: "panic.zig", content: rocStdPanic },
+    { name: "sort.zig", content: rocStdSort },
     { name: "str.zig", content: rocStdStr },
     { name: "utils.zig", content: rocStdUtils },
 ]
 
-convertTypesToFile : Types -> File
-convertTypesToFile = \_ -> { name: "main.zig", content }
+generateDeclarations : Str, Types -> Str
+generateDeclarations = \buf, types ->
+    # Zig resolves declarations lazily, so unlike C these can go in any order.
+    Types.walkShapes types buf \accum, shape, id -> generateDeclaration accum types id shape
+
+generateDeclaration : Str, Types, TypeId, Shape -> Str
+generateDeclaration = \buf, types, id, shape ->
+    when shape is
+        Struct { name, fields } ->
+            generateStruct buf types name (namedFields fields)
+
+        TagUnionPayload { name, fields } ->
+            generateStruct buf types name (namedFields fields)
+
+        TagUnion (Enumeration { name, tags, size }) ->
+            """
+            $(buf)
+            pub const $(name) = $(generateDiscriminant tags size);
+
+            """
+
+        TagUnion (NonRecursive { name, tags, discriminantSize }) ->
+            generateNonRecursiveTagUnion buf types name tags discriminantSize
+
+        TagUnion (Recursive { name, tags, discriminantSize }) ->
+            generateRecursiveTagUnion buf types name tags discriminantSize None
+
+        TagUnion (NullableWrapped { name, indexOfNullTag, tags, discriminantSize }) ->
+            generateRecursiveTagUnion buf types name tags discriminantSize (Some indexOfNullTag)
+
+        TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload }) ->
+            """
+            $(buf)
+            /// `null` means `$(nullTag)`; anything else points to the payload of `$(nonNullTag)`.
+            pub const $(name) = extern struct {
+                pointer: ?*$(typeName types nonNullPayload),
+            };
+
+            """
+
+        TagUnion (NonNullableUnwrapped { name, tagName, payload }) ->
+            """
+            $(buf)
+            /// Points to the payload of `$(tagName)`, the only tag.
+            pub const $(name) = extern struct {
+                pointer: *$(typeName types payload),
+            };
+
+            """
+
+        TagUnion (SingleTagStruct { name, payload: HasNoClosure fields }) ->
+            indexedFields = List.mapWithIndex fields \{ id: fieldId }, index -> { name: "f$(Num.toStr index)", id: fieldId }
+
+            generateStruct buf types name indexedFields
+
+        TagUnion (SingleTagStruct { name, payload: HasClosure _ }) ->
+            Str.concat buf "// TODO: $(name) has a closure in its payload, which Zig glue doesn't support yet.\n\n"
+
+        RocResult okId errId ->
+            members =
+                [{ name: "err", id: errId }, { name: "ok", id: okId }]
+                |> List.keepIf \{ id: memberId } -> !(isUnit (Types.shape types memberId))
+                |> generateMembers types
+
+            """
+            $(buf)
+            pub const $(typeName types id) = extern struct {
+            $(generatePayloadField members)    /// 0 is `Err`, 1 is `Ok`
+                discriminant: u8,
+            };
+
+            """
+
+        _ ->
+            buf
+
+namedFields = \fields ->
+    when fields is
+        HasNoClosure xs -> List.map xs \{ name, id } -> { name, id }
+        HasClosure xs -> List.map xs \{ name, id } -> { name, id }
+
+generateStruct : Str, Types, Str, List { name : Str, id : TypeId } -> Str
+generateStruct = \buf, types, name, fields ->
+    members =
+        fields
+        |> List.keepIf \{ id } -> !(isUnit (Types.shape types id))
+        |> List.map \{ name: fieldName, id } -> { name: escapeKW fieldName, id }
+        |> generateMembers types
+
+    """
+    $(buf)
+    pub const $(name) = extern struct {
+    $(members)};
+
+    """
+
+generateNonRecursiveTagUnion : Str, Types, Str, List { name : Str, payload : [Some TypeId, None] }, U32 -> Str
+generateNonRecursiveTagUnion = \buf, types, name, tags, discriminantSize ->
+    members = generateMembers (payloadMembers types tags) types
+    emptyPayload = if Str.isEmpty members then "" else ".payload = undefined, "
+
+    constructors =
+        tags
+        |> List.map \{ name: tagName, payload } ->
+            when payload is
+                Some payloadId if !(isUnit (Types.shape types payloadId)) ->
+                    """
+
+                        pub fn $(tagName)(payload: $(typeName types payloadId)) $(name) {
+                            return .{ .payload = .{ .$(tagName) = payload }, .discriminant = .$(tagName) };
+                        }
+
+                    """
+
+                _ ->
+                    """
+
+                        pub fn $(tagName)() $(name) {
+                            return .{ $(emptyPayload).discriminant = .$(tagName) };
+                        }
+
+                    """
+        |> Str.joinWith ""
+
+    """
+    $(buf)
+    pub const $(name) = extern struct {
+    $(generatePayloadField members)    discriminant: Discriminant,
+
+        pub const Discriminant = $(generateDiscriminant (List.map tags .name) discriminantSize);
+    $(constructors)};
 
-content =
     """
-    // ⚠️ GENERATED CODE ⚠️ 
-    //
-    // This package is generated by the `roc glue` CLI command
+
+generateRecursiveTagUnion : Str, Types, Str, List { name : Str, payload : [Some TypeId, None] }, U32, [Some U16, None] -> Str
+generateRecursiveTagUnion = \buf, types, name, tags, discriminantSize, nullTagIndex ->
+    tagNames = List.map tags .name
+
+    (nullComment, unwrapPointer) =
+        when nullTagIndex is
+            Some index ->
+                nullTag = List.get tagNames (Num.toU64 index) |> Result.withDefault ""
+
+                (" `null` means `$(nullTag)`.", "self.pointer orelse return .$(nullTag)")
+
+            None ->
+                ("", "self.pointer.?")
+
+    """
+    $(buf)
+    /// Points to a `$(name).Union`, with the tag id stored in its lowest bits.$(nullComment)
+    pub const $(name) = extern struct {
+        pointer: ?*Union,
+
+        pub const Discriminant = $(generateDiscriminant tagNames discriminantSize);
+
+        pub const Union = extern union {
+    $(indentLines (generateMembers (payloadMembers types tags) types))    };
+
+        const tag_mask: usize = if (@sizeOf(usize) == 8) 0b111 else 0b11;
+
+        pub fn discriminant(self: $(name)) Discriminant {
+            const pointer = $(unwrapPointer);
+
+            return @enumFromInt(@intFromPtr(pointer) & tag_mask);
+        }
+
+        pub fn unmaskedPointer(self: $(name)) ?*Union {
+            const pointer = self.pointer orelse return null;
+
+            return @ptrFromInt(@intFromPtr(pointer) & ~tag_mask);
+        }
+    };
+
+    """
+
+payloadMembers : Types, List { name : Str, payload : [Some TypeId, None] } -> List { name : Str, id : TypeId }
+payloadMembers = \types, tags ->
+    List.keepOks tags \{ name, payload } ->
+        when payload is
+            Some payloadId if !(isUnit (Types.shape types payloadId)) -> Ok { name, id: payloadId }
+            _ -> Err NoPayload
+
+generateDiscriminant : List Str, U32 -> Str
+generateDiscriminant = \tagNames, size ->
+    constants =
+        tagNames
+        |> List.mapWithIndex \tag, index -> "$(tag) = $(Num.toStr index)"
+        |> Str.joinWith ", "
+
+    "enum($(discriminantType size)) { $(constants) }"
+
+generatePayloadField : Str -> Str
+generatePayloadField = \members ->
+    if Str.isEmpty members then
+        ""
+    else
+        "    payload: extern union {\n$(indentLines members)    },\n"
+
+generateMembers : List { name : Str, id : TypeId }, Types -> Str
+generateMembers = \members, types ->
+    members
+    |> List.map \{ name, id } -> "    $(name): $(typeName types id),\n"
+    |> Str.joinWith ""
+
+indentLines : Str -> Str
+indentLines = \lines ->
+    lines
+    |> Str.split "\n"
+    |> List.map \line -> if Str.isEmpty line then line else "    $(line)"
+    |> Str.joinWith "\n"
+
+generateEntryPoints : Str, Types -> Str
+generateEntryPoints = \buf, types ->
+    List.walk (Types.entryPoints types) buf \accum, T name id -> generateEntryPoint accum types name id
+
+generateEntryPoint : Str, Types, Str, TypeId -> Str
+generateEntryPoint = \buf, types, name, id ->
+    (params, sizeDeclaration) =
+        when Types.shape types id is
+            Function rocFn ->
+                args =
+                    List.walkWithIndex rocFn.args [] \accum, argId, index ->
+                        shape = Types.shape types argId
+                        indexStr = Num.toStr index
+
+                        if isUnit shape then
+                            accum
+                        else if isTrivial types shape then
+                            List.append accum "arg$(indexStr): $(typeName types argId)"
+                        else
+                            List.append accum "arg$(indexStr): *$(typeName types argId)"
+
+                when Types.shape types rocFn.ret is
+                    Function _ ->
+                        (
+                            List.prepend args "closure_data: [*]u8",
+                            "pub extern fn roc__$(name)_1_exposed_size() i64;\n",
+                        )
+
+                    _ ->
+                        (List.prepend args "ret: *$(typeName types rocFn.ret)", "")
+
+            _ ->
+                (["ret: *$(typeName types id)"], "")
+
+    """
+    $(buf)
+    $(sizeDeclaration)pub extern fn roc__$(name)_1_exposed_generic($(Str.joinWith params ", ")) void;
+
+    """
+
+generateLayoutChecks : Str, Types -> Str
+generateLayoutChecks = \buf, types ->
+    arch = (Types.target types).architecture
+
+    checks =
+        Types.walkShapes types "" \accum, shape, id ->
+            when shape is
+                TagUnion (SingleTagStruct { payload: HasClosure _ }) ->
+                    accum
+
+                Struct _ | TagUnionPayload _ | TagUnion _ ->
+                    name = typeName types id
+                    size = Num.toStr (Types.size types id)
+                    align = Num.toStr (Types.alignment types id)
+
+                    """
+                    $(accum)        if (@sizeOf($(name)) != $(size)) @compileError("$(name) has the wrong size");
+                            if (@alignOf($(name)) != $(align)) @compileError("$(name) has the wrong alignment");
+
+                    """
+
+                _ ->
+                    accum
+
+    """
+    $(buf)
+    comptime {
+        if (builtin.cpu.arch == .$(archName arch)) {
+    $(checks)    }
+    }
+
+    """
+
+## Whether a value of this type can be passed to Roc directly, rather than behind a pointer.
+isTrivial : Types, Shape -> Bool
+isTrivial = \types, shape ->
+    when shape is
+        Unit | EmptyTagUnion | Bool | Num _ | TagUnion (Enumeration _) -> Bool.true
+        Struct { fields: HasNoClosure fields } | TagUnionPayload { fields: HasNoClosure fields } ->
+            List.all fields \{ id } -> isTrivial types (Types.shape types id)
+
+        TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
+            List.all fields \{ id } -> isTrivial types (Types.shape types id)
+
+        TagUnion (NonRecursive { tags }) ->
+            List.all tags \{ payload } ->
+                when payload is
+                    Some id -> isTrivial types (Types.shape types id)
+                    None -> Bool.true
+
+        RocResult okId errId ->
+            isTrivial types (Types.shape types okId) && isTrivial types (Types.shape types errId)
+
+        _ -> Bool.false
+
+isUnit : Shape -> Bool
+isUnit = \shape ->
+    when shape is
+        Unit | EmptyTagUnion -> Bool.true
+        _ -> Bool.false
+
+typeName : Types, TypeId -> Str
+typeName = \types, id ->
+    when Types.shape types id is
+        Unit | EmptyTagUnion -> "void"
+        Unsized -> "RocList"
+        RocStr -> "RocStr"
+        Bool -> "bool"
+        Num U8 -> "u8"
+        Num U16 -> "u16"
+        Num U32 -> "u32"
+        Num U64 -> "u64"
+        Num U128 -> "u128"
+        Num I8 -> "i8"
+        Num I16 -> "i16"
+        Num I32 -> "i32"
+        Num I64 -> "i64"
+        Num I128 -> "i128"
+        Num F32 -> "f32"
+        Num F64 -> "f64"
+        Num Dec -> "RocDec"
+        RocDict _ _ -> crash "RocDict is not yet supported in Zig"
+        RocSet _ -> crash "RocSet is not yet supported in Zig"
+        RocList _ -> "RocList"
+        RocBox elem -> "*$(typeName types elem)"
+        RocResult _ _ -> "RocResult_$(Num.toStr (typeIDtoU64 id))"
+        RecursivePointer content -> typeName types content
+        Struct { name } -> name
+        TagUnionPayload { name } -> name
+        TagUnion (Enumeration { name }) -> name
+        TagUnion (NonRecursive { name }) -> name
+        TagUnion (Recursive { name }) -> name
+        TagUnion (NullableWrapped { name }) -> name
+        TagUnion (NullableUnwrapped { name }) -> name
+        TagUnion (NonNullableUnwrapped { name }) -> name
+        TagUnion (SingleTagStruct { name }) -> name
+        Function _ -> crash "Functions are not yet supported in Zig"
+
+discriminantType : U32 -> Str
+discriminantType = \size ->
+    when size is
+        0 | 1 -> "u8"
+        2 -> "u16"
+        _ -> "u32"
+
+archName = \arch ->
+    when arch is
+        Aarch32 -> "arm"
+        Aarch64 -> "aarch64"
+        Wasm32 -> "wasm32"
+        X86x32 -> "x86"
+        X86x64 -> "x86_64"
+
+escapeKW = \input ->
+    # Tag union payloads have numbered fields, which Zig only allows in @"" identifiers.
+    if Set.contains reservedKeywords input || Result.isOk (Str.toU64 input) then
+        "@\"$(input)\""
+    else
+        input
+
+reservedKeywords = Set.fromList [
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "try",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+]
+
+fileHeader =
+    """
+    // ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+    const builtin = @import("builtin");
+    const utils = @import("utils.zig");
+
+    pub const RocStr = @import("str.zig").RocStr;
+    pub const RocList = @import("list.zig").RocList;
+
+    pub const RocDec = extern struct {
+        num: i128,
+    };
+
+    // The builtins above call `roc_alloc`, `roc_realloc`, `roc_dealloc`, `roc_panic`, `roc_dbg`
+    // and `roc_memset`, so the host must export those.
+
+    /// Increments the reference count of the heap allocation `data` points into (ignoring any
+    /// tag id in its lowest bits). Read-only data is left alone.
+    pub const incref = utils.increfDataPtrC;
+
+    /// Decrements the reference count of the heap allocation `data` points into, and frees it
+    /// when this was the last reference. This does not decref whatever the allocation points to.
+    /// Lists of refcounted elements also store their element count in front of the refcount,
+    /// so pass `elements_refcounted` for those.
+    pub const decref = utils.decrefDataPtrC;
+
     """
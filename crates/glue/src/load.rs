@@ -16,7 +16,7 @@ use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadedModule, LoadingPro
 use roc_mono::ir::{generate_glue_procs, CrashTag, GlueProc, OptLevel};
 use roc_mono::layout::{GlobalLayoutInterner, LayoutCache, LayoutInterner};
 use roc_packaging::cache::{self, RocCacheDir};
-use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_reporting::report::{to_backend_failure_report_string, RenderTarget, DEFAULT_PALETTE};
 use roc_target::{Architecture, Target, TargetFromTripleError::TripleUnsupported};
 use roc_types::subs::{Subs, Variable};
 use std::fs::File;
@@ -58,7 +58,15 @@ pub fn generate(
                 opt_level: OptLevel::Development,
                 emit_debug_info: false,
                 emit_llvm_ir: false,
+                emit_reuse_report: false,
+                warn_unused: false,
                 fuzz: false,
+                atomic_refcounts: false,
+                hardening: Default::default(),
+                canonicalize_nans: false,
+                patchable_calls: false,
+                wasm_allocator: Default::default(),
+                heap_profiling: false,
             };
 
             let load_config = standard_load_config(
@@ -91,6 +99,7 @@ pub fn generate(
                     RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
                     load_config,
                     Some(dylib_dir.path()),
+                    None,
                 ),
                 Err(_) => {
                     eprintln!("`roc glue` was unable to create a tempdir.");
@@ -190,6 +199,11 @@ pub fn generate(
                     handle_error_module(module, total_time, spec_path.as_os_str(), true)
                 }
                 Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
+                Err(BuildFileError::BackendFailure { filename, failure }) => {
+                    eprintln!("{}", to_backend_failure_report_string(filename, failure));
+
+                    Ok(1)
+                }
             };
 
             // Extend the lifetime of the tempdir to after we're done with everything,
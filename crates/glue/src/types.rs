@@ -2023,12 +2023,37 @@ fn tag_union_type_from_layout<'a>(
                 // A recursive tag union with only two variants, where one is empty.
                 // Optimizations: Use null for the empty variant AND don't store a tag ID for the other variant.
                 // e.g. `ConsList a : [Nil, Cons a (ConsList a)]`
+                //
+                // A non-recursive `[Some (Box a), None]`-shaped union gets this layout too, but
+                // there the union is the allocation of its box, so the pointer points to the `a`.
                 NullableUnwrapped {
                     nullable_id: null_represents_first_tag,
                     other_fields: _, // TODO use this!
                 } => {
-                    let mut tags =
-                        union_tags_to_types(&name, union_tags, subs, env, types, layout, true);
+                    let mut sorted_tags = sorted_union_tags(union_tags, subs);
+                    let non_null_index = !null_represents_first_tag as usize;
+
+                    if let [boxed_var] = sorted_tags[non_null_index].1[..] {
+                        let boxed_layout = env
+                            .layout_cache
+                            .from_var(env.arena, boxed_var, subs)
+                            .unwrap();
+
+                        if union_layout.is_nullable_pointer_niche(
+                            &env.layout_cache.interner,
+                            non_null_index as _,
+                            &[boxed_layout],
+                        ) {
+                            sorted_tags[non_null_index].1 = vec![box_element_var(subs, boxed_var)];
+                        }
+                    }
+
+                    let mut tags: Vec<_> = sorted_tags
+                        .into_iter()
+                        .map(|(tag_name, payload_vars)| {
+                            tag_to_type(&name, env, tag_name, &payload_vars, types, layout, true)
+                        })
+                        .collect();
                     // NullableUnwrapped tag unions should always have exactly 2 tags.
                     debug_assert_eq!(tags.len(), 2);
 
@@ -2170,19 +2195,8 @@ fn union_tags_to_types<'a>(
     layout: InLayout<'a>,
     is_recursive: bool,
 ) -> Vec<(String, Option<TypeId>)> {
-    let mut tags: Vec<(String, Vec<Variable>)> = union_tags
-        .iter_from_subs(subs)
-        .map(|(tag_name, payload_vars)| {
-            let name_str = tag_name.union_tag_name();
-
-            (name_str, payload_vars.to_vec())
-        })
-        .collect();
-
-    // Sort tags alphabetically by tag name
-    tags.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
-
-    tags.into_iter()
+    sorted_union_tags(union_tags, subs)
+        .into_iter()
         .map(|(tag_name, payload_vars)| {
             tag_to_type(
                 name,
@@ -2197,6 +2211,35 @@ fn union_tags_to_types<'a>(
         .collect()
 }
 
+fn sorted_union_tags(
+    union_tags: &UnionLabels<impl UnionTag>,
+    subs: &Subs,
+) -> Vec<(String, Vec<Variable>)> {
+    let mut tags: Vec<(String, Vec<Variable>)> = union_tags
+        .iter_from_subs(subs)
+        .map(|(tag_name, payload_vars)| {
+            let name_str = tag_name.union_tag_name();
+
+            (name_str, payload_vars.to_vec())
+        })
+        .collect();
+
+    // Sort tags alphabetically by tag name
+    tags.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+
+    tags
+}
+
+fn box_element_var(subs: &Subs, var: Variable) -> Variable {
+    match subs.get_content_without_compacting(var) {
+        Content::Structure(FlatType::Apply(Symbol::BOX_BOX_TYPE, args)) => {
+            subs.get_subs_slice(*args)[0]
+        }
+        Content::Alias(_, _, actual, _) => box_element_var(subs, *actual),
+        other => unreachable!("The payload of a nullable pointer niche was not a Box: {other:?}"),
+    }
+}
+
 fn single_tag_payload<'a>(
     union_tags: &'a UnionLabels<impl UnionTag>,
     subs: &'a Subs,
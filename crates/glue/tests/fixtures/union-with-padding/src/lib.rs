@@ -23,6 +23,13 @@ pub extern "C" fn rust_main() {
     assert!(tag_union.partial_cmp(&tag_union) == Some(Ordering::Equal)); // PartialOrd
     assert!(tag_union.cmp(&tag_union) == Ordering::Equal); // Ord
 
+    // Verify that tags whose payload type is unique can be built with From.
+
+    assert!(NonRecursive::from("small str") == NonRecursive::Foo("small str".into()));
+    assert!(NonRecursive::from(RocStr::from("small str")) == NonRecursive::Foo("small str".into()));
+    assert!(NonRecursive::from(123u128) == NonRecursive::Bar(123));
+    assert!(NonRecursive::from(456i32) == NonRecursive::Blah(456));
+
     println!(
         "tag_union was: {:?}\n`Foo \"small str\"` is: {:?}\n`Foo \"A long enough string to not be small\"` is: {:?}\n`Bar 123` is: {:?}\n`Baz` is: {:?}\n`Blah 456` is: {:?}",
         tag_union,
@@ -11,10 +11,11 @@ mod helpers;
 
 #[cfg(test)]
 mod glue_cli_run {
-    use crate::helpers::fixtures_dir;
+    use crate::helpers::{fixtures_dir, root_dir};
     use cli_utils::helpers::{has_error, run_glue, run_roc, Out};
     use std::fs;
     use std::path::{Path, PathBuf};
+    use std::process::Command;
 
     #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
     const TEST_LEGACY_LINKER: bool = true;
@@ -168,6 +169,77 @@ mod glue_cli_run {
         "#),
     }
 
+    #[test]
+    fn c_and_zig_glue() {
+        let glue_src = root_dir().join("crates").join("glue").join("src");
+
+        for fixture in [
+            "basic-recursive-union",
+            "nullable-wrapped",
+            "union-with-padding",
+        ] {
+            let platform_dir = fixtures_dir(fixture);
+            let platform_module_path = platform_dir.join("platform.roc");
+
+            let c_dir = platform_dir.join("test_glue_c");
+            let zig_dir = platform_dir.join("test_glue_zig");
+
+            for dir in [&c_dir, &zig_dir] {
+                if dir.exists() {
+                    fs::remove_dir_all(dir).unwrap();
+                }
+            }
+
+            run_glue_spec(
+                &glue_src.join("CGlue.roc"),
+                &c_dir,
+                &platform_module_path,
+                std::iter::empty(),
+            );
+
+            // The layout checks are static assertions, so this fails if C disagrees with Roc.
+            let cc_out = Command::new("cc")
+                .args(["-std=c11", "-fsyntax-only", "-x", "c"])
+                .arg(c_dir.join("roc_app.h"))
+                .output()
+                .unwrap();
+
+            assert!(
+                cc_out.status.success(),
+                "roc_app.h for {fixture} did not compile:\n{}",
+                String::from_utf8_lossy(&cc_out.stderr)
+            );
+
+            run_glue_spec(
+                &glue_src.join("ZigGlue.roc"),
+                &zig_dir,
+                &platform_module_path,
+                std::iter::empty(),
+            );
+
+            for file in ["roc_app.zig", "str.zig", "list.zig", "utils.zig"] {
+                assert!(
+                    zig_dir.join(file).exists(),
+                    "Zig glue for {fixture} is missing {file}"
+                );
+            }
+
+            // Like the C header, roc_app.zig checks its layouts at comptime. Analyzing it without
+            // emitting anything runs those checks, and the ones on the files it imports.
+            let zig_out = roc_command_utils::zig()
+                .args(["build-obj", "-fno-emit-bin", "roc_app.zig"])
+                .current_dir(&zig_dir)
+                .output()
+                .unwrap();
+
+            assert!(
+                zig_out.status.success(),
+                "roc_app.zig for {fixture} did not compile:\n{}",
+                String::from_utf8_lossy(&zig_out.stderr)
+            );
+        }
+    }
+
     fn check_for_tests(all_fixtures: &mut roc_collections::VecSet<String>) {
         use roc_collections::VecSet;
 
@@ -232,11 +304,20 @@ mod glue_cli_run {
             .join("RustGlue.roc");
 
         // Generate a fresh test_glue for this platform
+        run_glue_spec(&rust_glue_spec, &glue_dir, &platform_module_path, args)
+    }
+
+    fn run_glue_spec<'a, I: IntoIterator<Item = &'a str>>(
+        glue_spec: &Path,
+        glue_dir: &Path,
+        platform_module_path: &Path,
+        args: I,
+    ) -> Out {
         let parts : Vec<_> =
             // converting these all to String avoids lifetime issues
             std::iter::once("glue".to_string()).chain(
                 args.into_iter().map(|arg| arg.to_string()).chain([
-                    rust_glue_spec.to_str().unwrap().to_string(),
+                    glue_spec.to_str().unwrap().to_string(),
                     glue_dir.to_str().unwrap().to_string(),
                     platform_module_path.to_str().unwrap().to_string(),
                 ]),
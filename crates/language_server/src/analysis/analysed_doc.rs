@@ -3,18 +3,23 @@ use std::collections::HashMap;
 
 use bumpalo::Bump;
 
+use roc_load::docs::{DocDef, DocEntry};
 use roc_module::symbol::{ModuleId, Symbol};
 
 use roc_region::all::LineInfo;
 
 use tower_lsp::lsp_types::{
-    CompletionItem, Diagnostic, GotoDefinitionResponse, Hover, HoverContents, LanguageString,
-    Location, MarkedString, Position, Range, SemanticTokens, SemanticTokensResult, TextEdit, Url,
+    CompletionItem, Diagnostic, DocumentSymbol, GotoDefinitionResponse, Hover, HoverContents,
+    LanguageString, Location, MarkedString, Position, Range, SemanticTokens, SemanticTokensResult,
+    TextDocumentContentChangeEvent, TextEdit, Url,
 };
 
 use crate::{
     analysis::completion::{field_completion, get_completion_items, get_module_completion_items},
-    convert::{ToRange, ToRocPosition},
+    convert::{
+        docs::{IntoLspDocumentSymbol, IntoLspHover},
+        ToRange, ToRocPosition,
+    },
 };
 
 use super::{
@@ -32,6 +37,33 @@ pub struct AnalyzedDocument {
     pub analysis_result: AnalysisResult,
 }
 
+/// Converts an LSP position, whose character counts UTF-16 code units, to a byte offset.
+/// Positions past the end of a line or of the document are clamped to it.
+fn byte_offset(source: &str, position: Position) -> usize {
+    let mut line_start = 0;
+
+    for _ in 0..position.line {
+        match source[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return source.len(),
+        }
+    }
+
+    let line = &source[line_start..];
+    let line = &line[..line.find('\n').unwrap_or(line.len())];
+    let mut utf16_column = 0;
+
+    for (index, ch) in line.char_indices() {
+        if utf16_column >= position.character as usize {
+            return line_start + index;
+        }
+
+        utf16_column += ch.len_utf16();
+    }
+
+    line_start + line.len()
+}
+
 #[derive(Debug, Clone)]
 pub struct DocInfo {
     pub url: Url,
@@ -49,6 +81,30 @@ impl DocInfo {
         }
     }
 
+    /// Applies the changes of an incremental `textDocument/didChange`, in order.
+    /// A change without a range replaces the whole document.
+    pub fn apply_changes(
+        &self,
+        changes: Vec<TextDocumentContentChangeEvent>,
+        version: i32,
+    ) -> Self {
+        let mut source = self.source.clone();
+
+        for change in changes {
+            match change.range {
+                Some(Range { start, end }) => {
+                    let start = byte_offset(&source, start);
+                    let end = byte_offset(&source, end).max(start);
+
+                    source.replace_range(start..end, &change.text);
+                }
+                None => source = change.text,
+            }
+        }
+
+        Self::new(self.url.clone(), source, version)
+    }
+
     #[cfg(debug_assertions)]
     #[allow(unused)]
     fn debug_log_prefix(&self, offset: usize) {
@@ -175,7 +231,13 @@ impl AnalyzedDocument {
             ..
         } = self.module()?;
 
-        let (region, var) = roc_can::traverse::find_closest_type_at(pos, declarations)?;
+        let Some((region, var)) = roc_can::traverse::find_closest_type_at(pos, declarations) else {
+            // Type definitions have no type to show, but they can still have docs.
+            let doc_def = self
+                .doc_defs()?
+                .find(|doc_def| doc_def.name_region.contains_pos(pos))?;
+            return doc_def.into_lsp_hover(line_info);
+        };
 
         //TODO: Can this be integrated into "find closest type"? Is it worth it?
         let docs_opt = self.symbol_at(position).and_then(|symbol| {
@@ -204,6 +266,31 @@ impl AnalyzedDocument {
         })
     }
 
+    pub fn document_symbols(&self) -> Option<Vec<DocumentSymbol>> {
+        let symbols = self
+            .doc_defs()?
+            .map(|doc_def| doc_def.into_lsp_document_symbol(self.line_info()))
+            .collect();
+
+        Some(symbols)
+    }
+
+    /// The top level defs of this document, as the docs generator sees them.
+    fn doc_defs(&self) -> Option<impl Iterator<Item = &DocDef>> {
+        let AnalyzedModule {
+            module_id,
+            modules_info,
+            ..
+        } = self.module()?;
+
+        let docs = modules_info.get_docs(module_id)?;
+
+        Some(docs.entries.iter().filter_map(|entry| match entry {
+            DocEntry::DocDef(doc_def) => Some(doc_def),
+            DocEntry::DetachedDoc(_) | DocEntry::ModuleDoc(_) => None,
+        }))
+    }
+
     pub fn definition(&self, symbol: Symbol) -> Option<GotoDefinitionResponse> {
         let AnalyzedModule { declarations, .. } = self.module()?;
 
@@ -77,7 +77,7 @@ pub(crate) mod diag {
 
     use roc_problem::Severity;
     use roc_reporting::report::RocDocAllocator;
-    use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+    use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
 
     use super::ToRange;
 
@@ -205,13 +205,16 @@ pub(crate) mod diag {
             );
 
             let severity = report.severity.into_lsp_severity();
+            let code = report
+                .code()
+                .map(|code| NumberOrString::String(code.to_owned()));
             let mut msg = String::new();
             report.render_language_server(&mut msg, fmt.alloc);
 
             Some(Diagnostic {
                 range,
                 severity: Some(severity),
-                code: None,
+                code,
                 code_description: None,
                 source: None,
                 message: msg,
@@ -239,6 +242,9 @@ pub(crate) mod diag {
             )?;
 
             let severity = report.severity.into_lsp_severity();
+            let code = report
+                .code()
+                .map(|code| NumberOrString::String(code.to_owned()));
 
             let mut msg = String::new();
             report.render_language_server(&mut msg, fmt.alloc);
@@ -246,7 +252,7 @@ pub(crate) mod diag {
             Some(Diagnostic {
                 range,
                 severity: Some(severity),
-                code: None,
+                code,
                 code_description: None,
                 source: None,
                 message: msg,
@@ -257,3 +263,70 @@ pub(crate) mod diag {
         }
     }
 }
+
+pub(crate) mod docs {
+    use roc_load::docs::{DocDef, TypeAnnotation};
+    use roc_region::all::LineInfo;
+    use tower_lsp::lsp_types::{
+        DocumentSymbol, Hover, HoverContents, MarkupContent, MarkupKind, SymbolKind,
+    };
+
+    use super::ToRange;
+
+    pub trait IntoLspDocumentSymbol {
+        type Feed;
+
+        fn into_lsp_document_symbol(self, feed: &Self::Feed) -> DocumentSymbol;
+    }
+
+    impl IntoLspDocumentSymbol for &DocDef {
+        type Feed = LineInfo;
+
+        #[allow(deprecated)] // `deprecated` is deprecated in favor of `tags`
+        fn into_lsp_document_symbol(self, line_info: &LineInfo) -> DocumentSymbol {
+            let is_type = self.name.starts_with(char::is_uppercase);
+
+            let kind = match &self.type_annotation {
+                TypeAnnotation::Ability { .. } => SymbolKind::INTERFACE,
+                TypeAnnotation::TagUnion { .. } if is_type => SymbolKind::ENUM,
+                _ if is_type => SymbolKind::STRUCT,
+                TypeAnnotation::Function { .. } => SymbolKind::FUNCTION,
+                _ => SymbolKind::CONSTANT,
+            };
+
+            DocumentSymbol {
+                name: self.name.clone(),
+                detail: None,
+                kind,
+                tags: None,
+                deprecated: None,
+                range: self.region.to_range(line_info),
+                selection_range: self.name_region.to_range(line_info),
+                children: None,
+            }
+        }
+    }
+
+    pub trait IntoLspHover {
+        type Feed;
+
+        fn into_lsp_hover(self, feed: &Self::Feed) -> Option<Hover>;
+    }
+
+    impl IntoLspHover for &DocDef {
+        type Feed = LineInfo;
+
+        /// Shows the doc comment of the def, if it has one.
+        fn into_lsp_hover(self, line_info: &LineInfo) -> Option<Hover> {
+            let docs = self.docs.as_ref()?;
+
+            Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: docs.clone(),
+                }),
+                range: Some(self.name_region.to_range(line_info)),
+            })
+        }
+    }
+}
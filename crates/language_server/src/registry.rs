@@ -9,8 +9,8 @@ use std::{
 use tokio::sync::{Mutex, MutexGuard};
 
 use tower_lsp::lsp_types::{
-    CompletionResponse, Diagnostic, GotoDefinitionResponse, Hover, Position, SemanticTokensResult,
-    TextEdit, Url,
+    CompletionResponse, Diagnostic, DocumentSymbolResponse, GotoDefinitionResponse, Hover,
+    Position, SemanticTokensResult, TextDocumentContentChangeEvent, TextEdit, Url,
 };
 
 use crate::analysis::{AnalyzedDocument, DocInfo};
@@ -19,13 +19,14 @@ use crate::analysis::{AnalyzedDocument, DocInfo};
 pub(crate) struct DocumentPair {
     info: DocInfo,
     latest_document: OnceLock<Arc<AnalyzedDocument>>,
-    last_good_document: Arc<AnalyzedDocument>,
+    /// `None` until the first analysis of the document finishes.
+    last_good_document: Option<Arc<AnalyzedDocument>>,
 }
 
 impl DocumentPair {
     pub(crate) fn new(
         latest_doc: Arc<AnalyzedDocument>,
-        last_good_document: Arc<AnalyzedDocument>,
+        last_good_document: Option<Arc<AnalyzedDocument>>,
     ) -> Self {
         Self {
             info: latest_doc.doc_info.clone(),
@@ -88,21 +89,29 @@ impl Registry {
                         *old_doc = DocumentPair {
                             info: old_doc.info.clone(),
                             latest_document: old_doc.latest_document.clone(),
-                            last_good_document: document,
+                            last_good_document: Some(document),
                         };
                     }
                 } else if document.type_checked() {
-                    *old_doc = DocumentPair::new(document.clone(), document);
+                    *old_doc = DocumentPair::new(document.clone(), Some(document));
                 } else {
                     debug!(
                         "Document typechecking failed at version {:?}, not updating last_good_document",
                         &document.doc_info.version
                     );
-                    *old_doc = DocumentPair::new(document, old_doc.last_good_document.clone());
+                    // Until something type-checks, the first analysis is the best we have.
+                    let last_good_document = old_doc
+                        .last_good_document
+                        .clone()
+                        .unwrap_or_else(|| document.clone());
+                    *old_doc = DocumentPair::new(document, Some(last_good_document));
                 }
             }
             None => {
-                documents.insert(url.clone(), DocumentPair::new(document.clone(), document));
+                documents.insert(
+                    url.clone(),
+                    DocumentPair::new(document.clone(), Some(document)),
+                );
             }
         }
     }
@@ -136,10 +145,51 @@ impl Registry {
                     latest_document: OnceLock::new(),
                 };
             }
-            None => debug!("So existing docinfo for {:?} ", url.as_str()),
+            None => {
+                debug!(
+                    "Set the docInfo for new document {:?} to version:{:?}",
+                    url.as_str(),
+                    info.version
+                );
+                documents_lock.insert(
+                    url,
+                    DocumentPair {
+                        info,
+                        last_good_document: None,
+                        latest_document: OnceLock::new(),
+                    },
+                );
+            }
         }
     }
 
+    /// Applies the edits from an incremental change to the latest docInfo, and records the result.
+    /// Returns `None` if the document was never opened.
+    pub async fn apply_text_changes(
+        &self,
+        url: &Url,
+        changes: Vec<TextDocumentContentChangeEvent>,
+        version: i32,
+    ) -> Option<DocInfo> {
+        let mut documents_lock = self.documents.lock().await;
+        let pair = documents_lock.get_mut(url)?;
+        let info = pair.info.apply_changes(changes, version);
+
+        debug!(
+            "Applied edits to the docInfo for {:?}, now at version:{:?}",
+            url.as_str(),
+            info.version
+        );
+
+        *pair = DocumentPair {
+            info: info.clone(),
+            last_good_document: pair.last_good_document.clone(),
+            latest_document: OnceLock::new(),
+        };
+
+        Some(info)
+    }
+
     async fn document_info_by_url(&self, url: &Url) -> Option<DocInfo> {
         self.documents.lock().await.get(url).map(|a| a.info.clone())
     }
@@ -175,6 +225,11 @@ impl Registry {
         self.latest_document_by_url(url).await?.hover(position)
     }
 
+    pub async fn document_symbols(&self, url: &Url) -> Option<DocumentSymbolResponse> {
+        let symbols = self.latest_document_by_url(url).await?.document_symbols()?;
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
     pub async fn goto_definition(
         &self,
         url: &Url,
@@ -213,6 +268,7 @@ impl Registry {
 
         let completions = pair
             .last_good_document
+            .as_ref()?
             .completion_items(position, latest_doc_info)?;
 
         Some(CompletionResponse::Array(completions))
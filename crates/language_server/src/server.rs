@@ -64,14 +64,11 @@ impl RocServer {
     }
 
     pub fn capabilities() -> ServerCapabilities {
-        let text_document_sync = TextDocumentSyncCapability::Options(
-            // TODO: later on make this incremental
-            TextDocumentSyncOptions {
-                open_close: Some(true),
-                change: Some(TextDocumentSyncKind::FULL),
-                ..TextDocumentSyncOptions::default()
-            },
-        );
+        let text_document_sync = TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::INCREMENTAL),
+            ..TextDocumentSyncOptions::default()
+        });
         let hover_provider = HoverProviderCapability::Simple(true);
         let definition_provider = DefinitionOptions {
             work_done_progress_options: WorkDoneProgressOptions {
@@ -106,6 +103,7 @@ impl RocServer {
         ServerCapabilities {
             text_document_sync: Some(text_document_sync),
             hover_provider: Some(hover_provider),
+            document_symbol_provider: Some(OneOf::Left(true)),
             definition_provider: Some(OneOf::Right(definition_provider)),
             document_formatting_provider: Some(OneOf::Right(document_formatting_provider)),
             semantic_tokens_provider: Some(semantic_tokens_provider),
@@ -118,6 +116,22 @@ impl RocServer {
     async fn change(&self, fi: Url, text: String, version: i32) {
         let updating_result = self.state.change(&fi, text, version).await;
 
+        self.publish_diagnostics(fi, version, updating_result).await;
+    }
+
+    /// Records a set of edits to a document's content.
+    async fn edit(&self, fi: Url, changes: Vec<TextDocumentContentChangeEvent>, version: i32) {
+        let updating_result = self.state.edit(&fi, changes, version).await;
+
+        self.publish_diagnostics(fi, version, updating_result).await;
+    }
+
+    async fn publish_diagnostics(
+        &self,
+        fi: Url,
+        version: i32,
+        updating_result: std::result::Result<(), String>,
+    ) {
         //The analysis task can be cancelled by another change coming in which will update the watched variable
         if let Err(e) = updating_result {
             debug!("Cancelled change. Reason:{:?}", e);
@@ -154,6 +168,28 @@ impl RocServerState {
             .apply_doc_info_changes(fi.clone(), doc_info.clone())
             .await;
 
+        self.analyse(fi, doc_info).await
+    }
+
+    pub async fn edit(
+        &self,
+        fi: &Url,
+        changes: Vec<TextDocumentContentChangeEvent>,
+        version: i32,
+    ) -> std::result::Result<(), String> {
+        debug!("V{:?}:starting edit", version);
+        let doc_info = self
+            .registry
+            .apply_text_changes(fi, changes, version)
+            .await
+            .ok_or_else(|| format!("Edited document {} was never opened", fi))?;
+
+        self.analyse(fi, doc_info).await
+    }
+
+    async fn analyse(&self, fi: &Url, doc_info: DocInfo) -> std::result::Result<(), String> {
+        let version = doc_info.version;
+
         debug!(
             "V{:?}:finished updating docinfo, starting analysis ",
             version
@@ -243,15 +279,7 @@ impl LanguageServer for RocServer {
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let VersionedTextDocumentIdentifier { uri, version, .. } = params.text_document;
 
-        // NOTE: We specify that we expect full-content syncs in the server capabilities,
-        // so here we assume the only change passed is a change of the entire document's content.
-        let TextDocumentContentChangeEvent { text, .. } = params
-            .content_changes
-            .into_iter()
-            .last()
-            .expect("textDocument change event had no changes ");
-
-        self.change(uri, text, version).await;
+        self.edit(uri, params.content_changes, version).await;
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
@@ -276,6 +304,19 @@ impl LanguageServer for RocServer {
         unwind_async(self.state.registry.hover(&text_document.uri, position)).await
     }
 
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let DocumentSymbolParams {
+            text_document,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.document_symbols(&text_document.uri)).await
+    }
+
     async fn goto_definition(
         &self,
         params: GotoDefinitionParams,
@@ -612,4 +653,147 @@ mod tests {
         "#]]
         .assert_debug_eq(&actual);
     }
+
+    const DOCUMENTED_DEFS: &str = indoc! {r"
+        ## A user
+        User : { name : Str }
+
+        ## Greets a user
+        greet : User -> Str
+        greet = \user -> user.name
+        "};
+
+    #[tokio::test]
+    async fn test_document_symbols() {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + DOCUMENTED_DEFS).await;
+
+        let Some(DocumentSymbolResponse::Nested(symbols)) =
+            inner.registry.document_symbols(&url).await
+        else {
+            panic!("expected nested document symbols");
+        };
+
+        let actual = symbols
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind, symbol.selection_range))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            actual,
+            vec![
+                (
+                    "User".to_string(),
+                    SymbolKind::STRUCT,
+                    Range::new(Position::new(4, 0), Position::new(4, 4))
+                ),
+                (
+                    "greet".to_string(),
+                    SymbolKind::FUNCTION,
+                    Range::new(Position::new(7, 0), Position::new(7, 5))
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hover_type_def_docs() {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + DOCUMENTED_DEFS).await;
+
+        let hover = inner
+            .registry
+            .hover(&url, Position::new(4, 2))
+            .await
+            .expect("type def should have a hover");
+
+        let HoverContents::Markup(MarkupContent { kind, value }) = hover.contents else {
+            panic!("expected markdown hover contents");
+        };
+        assert_eq!(kind, MarkupKind::Markdown);
+        assert_eq!(value.trim(), "A user");
+        assert_eq!(
+            hover.range,
+            Some(Range::new(Position::new(4, 0), Position::new(4, 4)))
+        );
+    }
+
+    fn insert(line: u32, character: u32, text: &str) -> TextDocumentContentChangeEvent {
+        replace(
+            Range::new(
+                Position::new(line, character),
+                Position::new(line, character),
+            ),
+            text,
+        )
+    }
+
+    fn replace(range: Range, text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(range),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    /// Tests that incremental edits count columns in UTF-16 code units, and apply in order.
+    #[test]
+    fn test_apply_incremental_changes() {
+        let url = Url::parse("file:/Test.roc").unwrap();
+        let doc_info = DocInfo::new(url, "x = \"🎉\"\ny = 1\n".to_string(), 0);
+
+        let edited = doc_info.apply_changes(
+            vec![
+                insert(0, 7, "!"),
+                replace(Range::new(Position::new(1, 4), Position::new(1, 5)), "22"),
+                insert(2, 0, "z = y"),
+            ],
+            1,
+        );
+
+        assert_eq!(edited.source, "x = \"🎉!\"\ny = 22\nz = y");
+        assert_eq!(edited.version, 1);
+
+        let replaced = edited.apply_changes(
+            vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: "x = 1".to_string(),
+            }],
+            2,
+        );
+
+        assert_eq!(replaced.source, "x = 1");
+    }
+
+    /// Tests that completion sees the document after an incremental edit.
+    #[tokio::test]
+    async fn test_completion_after_incremental_edit() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+            main =
+              when a is
+                inn as outer -> 
+                  "#};
+
+        let (inner, url) = test_setup(doc).await;
+        let position = Position::new(6, 7);
+        let registry = &inner.registry;
+
+        inner.edit(&url, vec![insert(6, 6, "o")], 1).await.unwrap();
+        let comp1 = comp_labels(get_basic_completion_info(registry, &url, position).await);
+
+        let range = Range::new(Position::new(6, 6), Position::new(6, 7));
+        inner
+            .edit(&url, vec![replace(range, "i")], 2)
+            .await
+            .unwrap();
+        let comp2 = comp_labels(get_basic_completion_info(registry, &url, position).await);
+
+        assert_eq!(
+            [comp1, comp2],
+            [
+                Some(vec!["outer".to_string()]),
+                Some(vec!["inn".to_string(), "outer".to_string()]),
+            ]
+        );
+    }
 }
@@ -20,6 +20,7 @@ mod elf;
 mod macho;
 mod pe;
 mod util;
+mod wasm;
 
 mod generate_dylib;
 
@@ -437,7 +438,7 @@ fn preprocess(
         }
 
         (Architecture::Wasm32, _) => {
-            todo!("Roc does not yet support web assembly hosts!");
+            crate::wasm::preprocess_wasm(host_exe_path, preprocessed_path, verbose, time);
         }
         other => {
             internal_error!(
@@ -479,7 +480,9 @@ fn surgery(
         }
 
         (Architecture::Wasm32, _) => {
-            todo!("Roc does not yet support web assembly hosts!");
+            internal_error!(
+                "The wasm backend splices the app into the preprocessed host itself; there is no surgery step."
+            );
         }
         other => {
             internal_error!(
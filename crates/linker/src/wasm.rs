@@ -0,0 +1,104 @@
+use bumpalo::Bump;
+use roc_error_macros::internal_error;
+use roc_wasm_module::sections::{ExportType, ImportDesc};
+use roc_wasm_module::WasmModule;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::util::report_timing;
+
+/// Checks that a platform's `.wasm` host can have a Roc app spliced into it, and writes it to
+/// `preprocessed_path`.
+///
+/// Unlike the native targets there is no metadata file or surgery step. The wasm backend parses
+/// the preprocessed host itself and appends the app's code and data sections to it directly,
+/// so no wasm-ld invocation is needed when the app changes. All we do here is make sure that
+/// parse will succeed, so the platform author finds out now rather than on every `roc build`.
+pub(crate) fn preprocess_wasm(
+    host_path: &Path,
+    preprocessed_path: &Path,
+    verbose: bool,
+    time: bool,
+) {
+    let total_start = Instant::now();
+
+    let host_bytes = std::fs::read(host_path).unwrap_or_else(|e| {
+        internal_error!("Failed to read host file {}: {}", host_path.display(), e)
+    });
+
+    let arena = Bump::new();
+    let require_relocatable = true;
+    let host_module = match WasmModule::preload(&arena, &host_bytes, require_relocatable) {
+        Ok(module) => module,
+        Err(e) => internal_error!(
+            "I can't use {} as a WebAssembly host.\nAt byte offset 0x{:x}: {}",
+            host_path.display(),
+            e.offset,
+            e.message
+        ),
+    };
+
+    let parsing_duration = total_start.elapsed();
+
+    if verbose {
+        let (roc_imports, other_imports): (Vec<_>, Vec<_>) = host_module
+            .import
+            .imports
+            .iter()
+            .filter(|import| matches!(import.description, ImportDesc::Func { .. }))
+            .partition(|import| import.name.starts_with("roc__"));
+
+        println!(
+            "Found {} function imports to be provided by the app:",
+            roc_imports.len()
+        );
+        for import in roc_imports.iter() {
+            println!("\t{}", import.name);
+        }
+
+        println!(
+            "And {} function imports to be provided by the runtime:",
+            other_imports.len()
+        );
+        for import in other_imports.iter() {
+            println!("\t{}.{}", import.module, import.name);
+        }
+
+        let exported_fns: Vec<_> = host_module
+            .export
+            .exports
+            .iter()
+            .filter(|export| matches!(export.ty, ExportType::Func))
+            .collect();
+
+        println!("Found {} function exports:", exported_fns.len());
+        for export in exported_fns.iter() {
+            println!("\t{:>6}: {}", export.index, export.name);
+        }
+    }
+
+    let flushing_data_start = Instant::now();
+
+    std::fs::write(preprocessed_path, &host_bytes).unwrap_or_else(|e| {
+        internal_error!(
+            "Failed to write preprocessed host {}: {}",
+            preprocessed_path.display(),
+            e
+        )
+    });
+
+    let flushing_data_duration = flushing_data_start.elapsed();
+    let total_duration = total_start.elapsed();
+
+    if verbose || time {
+        println!();
+        println!("Timings");
+        report_timing("Host Parsing", parsing_duration);
+        report_timing("Flushing Data to Disk", flushing_data_duration);
+        report_timing(
+            "Other",
+            total_duration - parsing_duration - flushing_data_duration,
+        );
+        report_timing("Total", total_duration);
+    }
+}
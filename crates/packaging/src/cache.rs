@@ -265,6 +265,13 @@ pub fn roc_cache_dir() -> PathBuf {
     }
 }
 
+/// Where `roc repl` keeps its history: next to the packages directory in the Roc cache,
+/// so ~/.cache/roc/repl_history will be typical on UNIX.
+#[cfg(not(target_family = "wasm"))]
+pub fn roc_repl_history_file() -> PathBuf {
+    roc_cache_dir().with_file_name("repl_history")
+}
+
 /// WASI doesn't have a home directory, so just make the cache dir in the current directory
 /// https://github.com/WebAssembly/wasi-filesystem/issues/59
 #[cfg(target_family = "wasm")]
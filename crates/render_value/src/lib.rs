@@ -0,0 +1,62 @@
+//! Renders Roc values, given the memory they're in along with their layouts and types, in the
+//! same textual form as the Roc source code that would produce them. The REPL, `expect` failures
+//! and `dbg` all show values this way.
+use bumpalo::Bump;
+use roc_fmt::annotation::{Formattable, Newlines, Parens};
+use roc_parse::ast::Expr;
+use roc_std::RocDec;
+
+mod expr;
+
+pub use expr::{Scalar, ValueRenderer};
+
+/// The memory that values get rendered from, which isn't necessarily in this process's
+/// address space, or even as wide as its pointers.
+pub trait ValueMemory {
+    fn deref_bool(&self, addr: usize) -> bool;
+
+    fn deref_u8(&self, addr: usize) -> u8;
+    fn deref_u16(&self, addr: usize) -> u16;
+    fn deref_u32(&self, addr: usize) -> u32;
+    fn deref_u64(&self, addr: usize) -> u64;
+    fn deref_u128(&self, addr: usize) -> u128;
+    fn deref_usize(&self, addr: usize) -> usize;
+
+    fn deref_i8(&self, addr: usize) -> i8;
+    fn deref_i16(&self, addr: usize) -> i16;
+    fn deref_i32(&self, addr: usize) -> i32;
+    fn deref_i64(&self, addr: usize) -> i64;
+    fn deref_i128(&self, addr: usize) -> i128;
+    fn deref_isize(&self, addr: usize) -> isize;
+
+    fn deref_f32(&self, addr: usize) -> f32;
+    fn deref_f64(&self, addr: usize) -> f64;
+
+    fn deref_dec(&self, addr: usize) -> RocDec {
+        let bits = self.deref_i128(addr);
+        RocDec::new(bits)
+    }
+
+    fn deref_str(&self, addr: usize) -> &str;
+
+    fn deref_pointer_with_tag_id(&self, addr: usize) -> (u16, u64);
+}
+
+/// The canonical text of a rendered value
+pub fn format_value<'a>(arena: &'a Bump, value: &Expr<'_>) -> &'a str {
+    match value {
+        Expr::Closure(_, _) | Expr::MalformedClosure => "<function>",
+        _ => {
+            let mut buf = roc_fmt::Buf::new_in(arena);
+
+            value.format_with_options(&mut buf, Parens::NotNeeded, Newlines::Yes, 0);
+
+            buf.into_bump_str()
+        }
+    }
+}
+
+/// What `dbg` prints, given where it is, its source code, and the formatted value.
+pub fn format_dbg(location: &str, source: &str, value: &str) -> String {
+    format!("[{location}] {source} = {value}")
+}
@@ -11,19 +11,24 @@ use roc_load::{EntryPoint, MonomorphizedModule};
 use roc_mono::ir::OptLevel;
 use roc_mono::layout::STLayoutInterner;
 use roc_parse::ast::Expr;
+use roc_problem::backend::{BackendFailure, DevBackend};
+use roc_render_value::{format_value, ValueMemory};
 use roc_repl_eval::eval::jit_to_ast;
-use roc_repl_eval::gen::{format_answer, ReplOutput};
-use roc_repl_eval::{ReplApp, ReplAppMemory};
+use roc_repl_eval::gen::ReplOutput;
+use roc_repl_eval::ReplApp;
+use roc_reporting::report::to_backend_failure_report_string;
 use roc_std::RocStr;
 use roc_target::Target;
 use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 use roc_types::subs::Subs;
+use std::path::PathBuf;
 
+/// Evaluates the expression, or returns a report when the backend couldn't compile it.
 pub fn eval_llvm(
     mut loaded: MonomorphizedModule<'_>,
     target: Target,
     opt_level: OptLevel,
-) -> Option<ReplOutput> {
+) -> Result<Option<ReplOutput>, String> {
     let arena = Bump::new();
 
     debug_assert_eq!(loaded.exposed_to_host.top_level_values.len(), 1);
@@ -45,10 +50,14 @@ pub fn eval_llvm(
         DebugPrint::NOTHING,
     );
 
-    let (_, main_fn_layout) = *loaded
+    let Some((_, main_fn_layout)) = loaded
         .procedures
         .keys()
-        .find(|(s, _)| *s == main_fn_symbol)?;
+        .find(|(s, _)| *s == main_fn_symbol)
+        .copied()
+    else {
+        return Ok(None);
+    };
 
     let interns = loaded.interns.clone();
 
@@ -66,7 +75,7 @@ pub fn eval_llvm(
     ))]
     let (lib, main_fn_name, subs, layout_interner) =
         mono_module_to_dylib_asm(&arena, target, loaded, opt_level)
-            .expect("we produce a valid Dylib");
+            .map_err(|failure| to_backend_failure_report_string(PathBuf::from("REPL"), failure))?;
 
     let mut app = CliApp { lib };
 
@@ -82,12 +91,12 @@ pub fn eval_llvm(
         target,
     );
 
-    let expr_str = format_answer(&arena, expr).to_string();
+    let expr_str = format_value(&arena, &expr).to_string();
 
-    Some(ReplOutput {
+    Ok(Some(ReplOutput {
         expr: expr_str,
         expr_type: expr_type_str,
-    })
+    }))
 }
 
 struct CliApp {
@@ -134,7 +143,7 @@ macro_rules! deref_number {
     };
 }
 
-impl ReplAppMemory for CliMemory {
+impl ValueMemory for CliMemory {
     deref_number!(deref_bool, bool);
 
     deref_number!(deref_u8, u8);
@@ -217,6 +226,7 @@ fn mono_module_to_dylib_llvm<'a>(
         mode: LlvmBackendMode::GenTest, // so roc_panic is generated
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        coverage: None,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -285,7 +295,7 @@ fn mono_module_to_dylib_asm<'a>(
     target: Target,
     loaded: MonomorphizedModule<'a>,
     _opt_level: OptLevel,
-) -> Result<(libloading::Library, &'a str, Subs, STLayoutInterner<'a>), libloading::Error> {
+) -> Result<(libloading::Library, &'a str, Subs, STLayoutInterner<'a>), BackendFailure> {
     // let dir = std::env::temp_dir().join("roc_repl");
     let dir = tempfile::tempdir().unwrap();
 
@@ -309,10 +319,17 @@ fn mono_module_to_dylib_asm<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Repl,
+        atomic_refcounts: false,
+        hardening: Default::default(),
+        stack_maps: false,
     };
 
     let module_object =
-        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures);
+        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures)
+            .map_err(|error| BackendFailure {
+                backend: DevBackend::Assembly,
+                reason: error.to_string(),
+            })?;
 
     let module_out = module_object
         .write()
@@ -332,8 +349,6 @@ fn mono_module_to_dylib_asm<'a>(
     let (mut child, dylib_path) = roc_build::link::link(
         target,
         app_o_file.clone(),
-        // Long term we probably want a smarter way to link in zig builtins.
-        // With the current method all methods are kept and it adds about 100k to all outputs.
         &[
             app_o_file.to_str().unwrap(),
             builtins_host_tempfile.path().to_str().unwrap(),
@@ -347,7 +362,7 @@ fn mono_module_to_dylib_asm<'a>(
     // Load the dylib
     let path = dylib_path.as_path().to_str().unwrap();
 
-    let lib = unsafe { Library::new(path) }?;
+    let lib = unsafe { Library::new(path) }.expect("we produce a valid Dylib");
 
     Ok((lib, "test_main", subs, layout_interner))
 }
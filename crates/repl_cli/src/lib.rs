@@ -1,11 +1,14 @@
 //! Command Line Interface (CLI) functionality for the Read-Evaluate-Print-Loop (REPL).
 mod cli_gen;
+mod wasm_gen;
 
 use bumpalo::Bump;
 use const_format::concatcp;
 use roc_load::MonomorphizedModule;
 use roc_mono::ir::OptLevel;
+use roc_packaging::cache;
 use roc_repl_eval::gen::Problems;
+use roc_repl_eval::interp::eval_interp;
 use roc_repl_ui::colors::{CYAN, END_COL};
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
 use roc_repl_ui::{format_output, is_incomplete, CONT_PROMPT, PROMPT, SHORT_INSTRUCTIONS, TIPS};
@@ -13,11 +16,14 @@ use roc_reporting::report::{to_file_problem_report_string, ANSI_STYLE_CODES, DEF
 use roc_target::Target;
 use rustyline::highlight::{Highlighter, PromptInfo};
 use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
+use rustyline::Editor;
 use rustyline_derive::{Completer, Helper, Hinter};
 use std::borrow::Cow;
+use std::fs;
 use target_lexicon::Triple;
 
 use crate::cli_gen::eval_llvm;
+use crate::wasm_gen::eval_wasm;
 
 pub const WELCOME_MESSAGE: &str = concatcp!(
     "\n  The rockin' ",
@@ -34,19 +40,55 @@ pub struct ReplHelper {
     state: ReplState,
 }
 
-pub fn main() -> i32 {
+/// How the REPL runs the expressions it evaluates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplBackend {
+    /// Compiled for this machine, and called directly
+    #[default]
+    Native,
+    /// Compiled to WebAssembly like in the web REPL, and run in an interpreter
+    Wasm,
+    /// Not compiled at all, but run by the mono IR interpreter
+    Interp,
+}
+
+impl ReplBackend {
+    pub fn target(self) -> Target {
+        match self {
+            ReplBackend::Native | ReplBackend::Interp => Triple::host().into(),
+            ReplBackend::Wasm => Target::Wasm32,
+        }
+    }
+}
+
+pub fn main(backend: ReplBackend) -> i32 {
+    let mut editor = Editor::<ReplHelper>::new();
+    let repl_helper = ReplHelper::default();
+    editor.set_helper(Some(repl_helper));
+
+    // History is a convenience, so carry on without it if the file can't be read or written.
+    let history_file = cache::roc_repl_history_file();
+    let _ = editor.load_history(&history_file);
+
+    let exit_code = run(&mut editor, backend);
+
+    if let Some(dir) = history_file.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = editor.save_history(&history_file);
+
+    exit_code
+}
+
+fn run(editor: &mut Editor<ReplHelper>, backend: ReplBackend) -> i32 {
     use rustyline::error::ReadlineError;
-    use rustyline::Editor;
 
     // To debug rustyline:
     // <UNCOMMENT> env_logger::init();
     // <RUN WITH:> RUST_LOG=rustyline=debug cargo run repl 2> debug.log
     print!("{WELCOME_MESSAGE}{SHORT_INSTRUCTIONS}");
 
-    let mut editor = Editor::<ReplHelper>::new();
-    let repl_helper = ReplHelper::default();
-    editor.set_helper(Some(repl_helper));
-    let target = Triple::host().into();
+    let target = backend.target();
     let mut arena = Bump::new();
 
     loop {
@@ -54,7 +96,9 @@ pub fn main() -> i32 {
             Ok(line) => {
                 let line = line.trim();
 
-                editor.add_history_entry(line);
+                if !line.is_empty() {
+                    editor.add_history_entry(line);
+                }
 
                 let repl_state = &mut editor
                     .helper_mut()
@@ -64,13 +108,43 @@ pub fn main() -> i32 {
                 arena.reset();
                 match repl_state.step(&arena, line, target, DEFAULT_PALETTE) {
                     ReplAction::Eval { opt_mono, problems } => {
-                        let output = evaluate(opt_mono, problems, target);
+                        let output = match backend {
+                            ReplBackend::Native => evaluate(opt_mono, problems, target),
+                            ReplBackend::Wasm => evaluate_wasm(&arena, opt_mono, problems),
+                            ReplBackend::Interp => evaluate_interp(opt_mono, problems, target),
+                        };
                         // If there was no output, don't print a blank line!
                         // (This happens for something like a type annotation.)
                         if !output.is_empty() {
                             println!("{output}");
                         }
                     }
+                    ReplAction::Type {
+                        opt_output,
+                        problems,
+                    } => {
+                        println!("{}", format_output(ANSI_STYLE_CODES, opt_output, problems));
+                    }
+                    ReplAction::Load {
+                        filename,
+                        def_count,
+                        problems,
+                    } => {
+                        let loaded = problems.errors.is_empty();
+                        let output = format_output(ANSI_STYLE_CODES, None, problems);
+
+                        if !output.is_empty() {
+                            println!("{output}");
+                        }
+
+                        if loaded {
+                            println!(
+                                "\nLoaded {def_count} definition{} from {}\n",
+                                if def_count == 1 { "" } else { "s" },
+                                filename.display()
+                            );
+                        }
+                    }
                     ReplAction::Exit => {
                         return 0;
                     }
@@ -104,11 +178,40 @@ pub fn main() -> i32 {
 }
 
 pub fn evaluate(
+    opt_mono: Option<MonomorphizedModule<'_>>,
+    mut problems: Problems,
+    target: Target,
+) -> String {
+    let opt_output = match opt_mono.map(|mono| eval_llvm(mono, target, OptLevel::Normal)) {
+        Some(Ok(opt_output)) => opt_output,
+        Some(Err(report)) => {
+            // The backend couldn't compile this expression; report it like any other
+            // error so the session keeps going.
+            problems.errors.push(report);
+            None
+        }
+        None => None,
+    };
+    format_output(ANSI_STYLE_CODES, opt_output, problems)
+}
+
+/// Like [evaluate], for expressions loaded for [Target::Wasm32].
+pub fn evaluate_wasm<'a>(
+    arena: &'a Bump,
+    opt_mono: Option<MonomorphizedModule<'a>>,
+    problems: Problems,
+) -> String {
+    let opt_output = opt_mono.and_then(|mono| eval_wasm(arena, mono));
+    format_output(ANSI_STYLE_CODES, opt_output, problems)
+}
+
+/// Like [evaluate], but runs the expression with the mono IR interpreter.
+pub fn evaluate_interp(
     opt_mono: Option<MonomorphizedModule<'_>>,
     problems: Problems,
     target: Target,
 ) -> String {
-    let opt_output = opt_mono.and_then(|mono| eval_llvm(mono, target, OptLevel::Normal));
+    let opt_output = opt_mono.and_then(|mono| eval_interp(mono, target));
     format_output(ANSI_STYLE_CODES, opt_output, problems)
 }
 
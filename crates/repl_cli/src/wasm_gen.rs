@@ -0,0 +1,203 @@
+//! Evaluating expressions with the WebAssembly backend, like the web REPL does, except that the
+//! app runs in an interpreter in this process instead of in the browser.
+use bumpalo::Bump;
+use roc_error_macros::internal_error;
+use roc_load::MonomorphizedModule;
+use roc_parse::ast::Expr;
+use roc_render_value::{format_dbg, format_value, ValueMemory};
+use roc_repl_eval::eval::jit_to_ast;
+use roc_repl_eval::gen::ReplOutput;
+use roc_repl_eval::ReplApp;
+use roc_repl_wasm_gen::{CompiledExpr, WasmMemory, WRAPPER_NAME};
+use roc_target::Target;
+use roc_wasm_interp::{wasi, ImportDispatcher, Instance, Value, WasiDispatcher};
+use std::mem::size_of;
+
+/// The `mono` module must have been loaded for [Target::Wasm32].
+pub fn eval_wasm<'a>(arena: &'a Bump, mono: MonomorphizedModule<'a>) -> Option<ReplOutput> {
+    let CompiledExpr {
+        app_module_bytes,
+        expr_type,
+        main_fn_layout,
+        main_fn_var,
+        subs,
+        interns,
+        layout_interner,
+    } = roc_repl_wasm_gen::compile(arena, mono)?;
+
+    let dispatcher = AppDispatcher {
+        wasi: WasiDispatcher::default(),
+        panic_msg: None,
+    };
+
+    let is_debug_mode = false; // logs every instruction!
+    let instance = match Instance::from_bytes(arena, &app_module_bytes, dispatcher, is_debug_mode) {
+        Ok(instance) => instance,
+        Err(error) => {
+            internal_error!("The compiled expression is not a valid Wasm module: {error}")
+        }
+    };
+
+    let mut app = WasmReplApp { arena, instance };
+
+    // Run the app and transform the result value to an AST `Expr`
+    // Restore type constructor names, and other user-facing info that was erased during compilation.
+    let res_answer = jit_to_ast(
+        arena,
+        &mut app,
+        "", // main_fn_name is ignored (only passed to WasmReplApp methods)
+        main_fn_layout,
+        main_fn_var,
+        &subs,
+        &interns,
+        layout_interner.into_global().fork(),
+        Target::Wasm32,
+    );
+
+    // Transform the Expr to a string
+    let expr = format_value(arena, &res_answer).to_string();
+
+    Some(ReplOutput { expr, expr_type })
+}
+
+/// Implements the imports of the platform in roc_repl_wasm_gen, and the WASI ones that libc needs.
+struct AppDispatcher<'a> {
+    wasi: WasiDispatcher<'a>,
+    /// What the app passed to `roc_panic`, if it crashed
+    panic_msg: Option<String>,
+}
+
+impl<'a> ImportDispatcher for AppDispatcher<'a> {
+    fn dispatch(
+        &mut self,
+        module_name: &str,
+        function_name: &str,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Option<Value> {
+        if module_name == wasi::MODULE_NAME {
+            return self.wasi.dispatch(function_name, arguments, memory);
+        }
+
+        let memory = WasmMemory {
+            copied_bytes: memory,
+        };
+        let str_arg = |index: usize| {
+            let addr = arguments[index].expect_i32().unwrap() as u32;
+            memory.deref_str(addr as usize).to_string()
+        };
+
+        match (module_name, function_name) {
+            ("env", "send_panic_msg_to_js") => {
+                self.panic_msg = Some(str_arg(0));
+
+                None
+            }
+            ("env", "send_dbg_msg_to_js") => {
+                eprintln!("{}", format_dbg(&str_arg(0), &str_arg(2), &str_arg(1)));
+
+                None
+            }
+            _ => internal_error!(
+                "The REPL does not implement the import {module_name}.{function_name}"
+            ),
+        }
+    }
+}
+
+struct WasmReplApp<'a> {
+    arena: &'a Bump,
+    instance: Instance<'a, AppDispatcher<'a>>,
+}
+
+impl<'a> WasmReplApp<'a> {
+    /// Runs the main function, and copies the app's memory once it's done.
+    /// Returns the copy and the address of the result in it, or `None` if the app crashed.
+    fn run(&mut self) -> Option<(&'a WasmMemory<'a>, usize)> {
+        match self.instance.call_export(WRAPPER_NAME, []) {
+            Ok(Some(Value::I32(result_addr))) => {
+                let copied_bytes = self.arena.alloc_slice_copy(&self.instance.memory);
+                let memory = self.arena.alloc(WasmMemory { copied_bytes });
+
+                Some((memory, result_addr as u32 as usize))
+            }
+            Ok(result) => {
+                internal_error!("{WRAPPER_NAME} returned {result:?} instead of an address")
+            }
+            Err(error) => {
+                match self.instance.import_dispatcher.panic_msg.take() {
+                    Some(msg) => eprintln!("This Roc code crashed with: \"{msg}\""),
+                    None => eprintln!("This Roc code crashed:\n{error}"),
+                }
+
+                None
+            }
+        }
+    }
+}
+
+impl<'a> ReplApp<'a> for WasmReplApp<'a> {
+    type Memory = WasmMemory<'a>;
+
+    /// Run user code that returns a type with a `Builtin` layout
+    /// `Return` has to be laid out the same in wasm32 as here, so it can't contain a `usize`.
+    fn call_function<Return, F>(&mut self, _main_fn_name: &str, mut transform: F) -> Expr<'a>
+    where
+        F: FnMut(&'a Self::Memory, Return) -> Expr<'a>,
+        Self::Memory: 'a,
+    {
+        match self.run() {
+            Some((memory, result_addr)) => {
+                let result_bytes = &memory.copied_bytes[result_addr..][..size_of::<Return>()];
+                let result: Return = unsafe {
+                    let ptr = result_bytes.as_ptr() as *const Return;
+                    ptr.read_unaligned()
+                };
+
+                transform(memory, result)
+            }
+            None => Expr::REPL_RUNTIME_CRASH,
+        }
+    }
+
+    /// A list is three `usize`s here, but only three `u32`s in wasm32
+    fn call_function_returns_roc_list<F>(
+        &mut self,
+        _main_fn_name: &str,
+        mut transform: F,
+    ) -> Expr<'a>
+    where
+        F: FnMut(&'a Self::Memory, (usize, usize, usize)) -> Expr<'a>,
+        Self::Memory: 'a,
+    {
+        match self.run() {
+            Some((memory, addr)) => {
+                let list = (
+                    memory.deref_usize(addr),
+                    memory.deref_usize(addr + 4),
+                    memory.deref_usize(addr + 8),
+                );
+
+                transform(memory, list)
+            }
+            None => Expr::REPL_RUNTIME_CRASH,
+        }
+    }
+
+    /// Run user code that returns a struct or union, whose size is provided as an argument
+    /// _ret_bytes is compiled-in to the wrapper function of the app itself
+    fn call_function_dynamic_size<T, F>(
+        &mut self,
+        _main_fn_name: &str,
+        _ret_bytes: usize,
+        mut transform: F,
+    ) -> Option<T>
+    where
+        F: FnMut(&'a Self::Memory, usize) -> T,
+        Self::Memory: 'a,
+    {
+        let (memory, result_addr) = self.run()?;
+
+        Some(transform(memory, result_addr))
+    }
+}
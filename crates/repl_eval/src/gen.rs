@@ -1,18 +1,21 @@
 use bumpalo::Bump;
-use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_load::{
+    report_loading_problem, ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading,
+};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_problem::Severity;
 use roc_reporting::report::Palette;
 use std::path::PathBuf;
 
-use roc_fmt::annotation::Formattable;
-use roc_fmt::annotation::{Newlines, Parens};
-use roc_load::{LoadingProblem, MonomorphizedModule};
-use roc_parse::ast::Expr;
+use roc_collections::MutMap;
+use roc_load::{LoadedModule, LoadingProblem, MonomorphizedModule};
+use roc_module::symbol::{Interns, ModuleId, ModuleIds};
 use roc_region::all::LineInfo;
 use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
 use roc_solve::FunctionKind;
+use roc_solve_problem::TypeError;
 use roc_target::Target;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 
 #[derive(Debug)]
 pub struct ReplOutput {
@@ -20,19 +23,6 @@ pub struct ReplOutput {
     pub expr_type: String,
 }
 
-pub fn format_answer<'a>(arena: &'a Bump, answer: Expr<'_>) -> &'a str {
-    match answer {
-        Expr::Closure(_, _) | Expr::MalformedClosure => "<function>",
-        _ => {
-            let mut expr = roc_fmt::Buf::new_in(arena);
-
-            answer.format_with_options(&mut expr, Parens::NotNeeded, Newlines::Yes, 0);
-
-            expr.into_bump_str()
-        }
-    }
-}
-
 #[derive(Default, Debug)]
 pub struct Problems {
     pub errors: Vec<String>,
@@ -102,6 +92,131 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         ..
     } = &mut loaded;
 
+    let problems = collect_problems(
+        sources,
+        interns,
+        can_problems,
+        type_problems,
+        bytes_before_expr,
+        palette,
+    );
+
+    (Some(loaded), problems)
+}
+
+/// Infers the type of `expr` in the context of `defs`, without evaluating it.
+pub fn type_of_expr<'i, I: Iterator<Item = &'i str>>(
+    arena: &Bump,
+    defs: I,
+    expr: &str,
+    target: Target,
+    palette: Palette,
+) -> (Option<ReplOutput>, Problems) {
+    let (opt_mono, problems) = compile_to_mono(arena, defs, expr, target, palette);
+
+    let opt_output = opt_mono.map(|mut mono| {
+        let (_, main_fn_var) = mono
+            .exposed_to_host
+            .top_level_values
+            .iter()
+            .next()
+            .expect("the repl module exposes its output");
+
+        let expr_type = name_and_print_var(
+            *main_fn_var,
+            &mut mono.subs,
+            mono.module_id,
+            &mono.interns,
+            DebugPrint::NOTHING,
+        );
+
+        ReplOutput {
+            expr: expr.trim().to_string(),
+            expr_type,
+        }
+    });
+
+    (opt_output, problems)
+}
+
+/// Loads and type-checks the module at `filename` through the regular load pipeline,
+/// so that it can be brought into the repl only if it compiles.
+pub fn check_file(arena: &Bump, filename: PathBuf, target: Target, palette: Palette) -> Problems {
+    let loaded = roc_load::load_and_typecheck(
+        arena,
+        filename,
+        None,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        LoadConfig {
+            target,
+            function_kind: FunctionKind::LambdaSet,
+            render: roc_reporting::report::RenderTarget::ColorTerminal,
+            palette,
+            threading: Threading::Single,
+            exec_mode: ExecutionMode::Check,
+        },
+    );
+
+    let mut loaded = match loaded {
+        Ok(v) => v,
+        Err(problem) => {
+            return Problems {
+                errors: vec![loading_problem_report(problem, palette)],
+                warnings: Vec::new(),
+            };
+        }
+    };
+
+    let LoadedModule {
+        interns,
+        sources,
+        can_problems,
+        type_problems,
+        ..
+    } = &mut loaded;
+
+    collect_problems(sources, interns, can_problems, type_problems, 0, palette)
+}
+
+/// Renders a problem that stopped a file from loading. Problems that are not about the file
+/// itself, like a worker thread dying, get a short message instead of a full report.
+fn loading_problem_report(problem: LoadingProblem<'_>, palette: Palette) -> String {
+    match problem {
+        LoadingProblem::ErrJoiningWorkerThreads => {
+            "Internal error: analysis worker threads died".to_string()
+        }
+        LoadingProblem::ChannelProblem(problem) => {
+            format!("Internal error: message channel problem: {problem:?}")
+        }
+        LoadingProblem::TriedToImportAppModule => {
+            "This module imports an app module, which isn't allowed.".to_string()
+        }
+        LoadingProblem::UnexpectedHeader(header) => {
+            format!("Unexpected header: {header}")
+        }
+        LoadingProblem::CouldNotFindCacheDir => format!(
+            "Could not find the Roc cache directory {}",
+            cache::roc_cache_dir().display()
+        ),
+        problem => report_loading_problem(
+            problem,
+            ModuleIds::default(),
+            roc_reporting::report::RenderTarget::ColorTerminal,
+            palette,
+        ),
+    }
+}
+
+/// Renders the problems of every module, except for canonicalization problems whose regions
+/// end before `bytes_before_expr`.
+fn collect_problems(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    bytes_before_expr: usize,
+    palette: Palette,
+) -> Problems {
     let mut problems = Problems::default();
 
     let errors = &mut problems.errors;
@@ -117,7 +232,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             continue;
         }
 
-        let line_info = LineInfo::new(module_src);
+        let line_info = LineInfo::new(src);
         let src_lines: Vec<&str> = src.split('\n').collect();
 
         // Report parsing and canonicalization problems
@@ -165,7 +280,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         }
     }
 
-    (Some(loaded), problems)
+    problems
 }
 
 fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
@@ -0,0 +1,1213 @@
+//! Evaluates REPL input and top-level expects with the mono IR interpreter, instead of
+//! compiling them. The interpreter's values are laid out in a byte buffer the way compiled
+//! code would lay them out in memory, so they're rendered like any other app's values.
+use std::io::Write;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bumpalo::Bump;
+use roc_collections::{MutMap, VecMap};
+use roc_error_macros::internal_error;
+use roc_load::{Expectations, MonomorphizedModule, Threading};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::ir::{Proc, ProcLayout};
+use roc_mono::layout::{
+    Builtin, Discriminant, GlobalLayoutInterner, InLayout, Layout, LayoutCache, LayoutInterner,
+    LayoutRepr, UnionLayout,
+};
+use roc_mono_interp::{
+    check_property, FailedExpect, InterpError, Interpreter, Value, DEFAULT_SEED, PROPERTY_CASES,
+};
+use roc_parse::ast::Expr;
+use roc_region::all::Region;
+use roc_render_value::{format_value, ValueMemory, ValueRenderer};
+use roc_reporting::{error::expect::Renderer, report::RenderTarget};
+use roc_target::Target;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+
+use crate::eval::jit_to_ast;
+use crate::gen::ReplOutput;
+use crate::snapshot::{check_snapshot, SnapshotMode};
+use crate::ReplApp;
+
+pub fn eval_interp(loaded: MonomorphizedModule<'_>, target: Target) -> Option<ReplOutput> {
+    let arena = Bump::new();
+
+    let MonomorphizedModule {
+        module_id,
+        procedures,
+        interns,
+        mut subs,
+        exposed_to_host,
+        layout_interner,
+        ..
+    } = loaded;
+
+    debug_assert_eq!(exposed_to_host.top_level_values.len(), 1);
+    let (main_fn_symbol, main_fn_var) = exposed_to_host.top_level_values.iter().next().unwrap();
+    let main_fn_symbol = *main_fn_symbol;
+    let main_fn_var = *main_fn_var;
+
+    // pretty-print the expr type string for later.
+    let expr_type_str = name_and_print_var(
+        main_fn_var,
+        &mut subs,
+        module_id,
+        &interns,
+        DebugPrint::NOTHING,
+    );
+
+    let (_, main_fn_layout) = *procedures.keys().find(|(s, _)| *s == main_fn_symbol)?;
+
+    let layout_interner = layout_interner.into_global();
+    let tl_layout_interner = layout_interner.fork();
+    let mut interpreter = Interpreter::new(&procedures, &tl_layout_interner);
+
+    // A function has nothing to run; `jit_to_ast` renders it without calling the app.
+    let result = match main_fn_layout.arguments {
+        [] => interpreter.call(main_fn_symbol, main_fn_layout, Vec::new()),
+        _ => Ok(Value::unit()),
+    };
+
+    let mut app = InterpApp::new(&arena, &tl_layout_interner, target);
+    app.set_result(result, main_fn_layout.result);
+
+    let expr = jit_to_ast(
+        &arena,
+        &mut app,
+        "",
+        main_fn_layout,
+        main_fn_var,
+        &subs,
+        &interns,
+        layout_interner.fork(),
+        target,
+    );
+
+    let expr_str = format_value(&arena, &expr).to_string();
+
+    Some(ReplOutput {
+        expr: expr_str,
+        expr_type: expr_type_str,
+    })
+}
+
+/// Runs the top-level expects of every module with the interpreter, and renders the ones that
+/// fail. Returns the number of failed and passed expects.
+///
+/// Every expect gets an interpreter of its own, so they run in parallel, on as many threads as
+/// `threading` allows. Reports and `dbg` output are still written in source order.
+pub fn run_toplevel_expects<W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    loaded: MonomorphizedModule<'_>,
+    target: Target,
+    snapshot_mode: SnapshotMode,
+    threading: Threading,
+) -> std::io::Result<(usize, usize)> {
+    let MonomorphizedModule {
+        procedures,
+        interns,
+        layout_interner,
+        toplevel_expects,
+        expectations,
+        ..
+    } = loaded;
+
+    let mut expects: Vec<(Symbol, Region)> = toplevel_expects
+        .values()
+        .flat_map(|expects| {
+            (expects.pure.iter())
+                .chain(expects.fx.iter())
+                .chain(expects.properties.iter())
+        })
+        .map(|(symbol, region)| (*symbol, *region))
+        .collect();
+
+    // Report the expects in source order, module by module.
+    expects.sort_by_cached_key(|(symbol, region)| {
+        let module_name = symbol.module_id().to_ident_str(&interns).to_string();
+        (module_name, region.start().offset)
+    });
+
+    let worker_count = if cfg!(target_family = "wasm") {
+        // We cannot spawn threads in wasm
+        1
+    } else {
+        let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+        match threading {
+            Threading::Single => 1,
+            Threading::AllAvailable => available,
+            Threading::AtMost(at_most) => available.min(at_most),
+        }
+    };
+    let worker_count = worker_count.clamp(1, expects.len().max(1));
+
+    // Each worker renders into its own arena, which has to outlive the layout interner.
+    let mut arenas: Vec<Bump> = (0..worker_count).map(|_| Bump::new()).collect();
+    let layout_interner = layout_interner.into_global();
+
+    let env = ExpectEnv {
+        procedures: &procedures,
+        interns: &interns,
+        layout_interner: &layout_interner,
+        expectations: &expectations,
+        render_target,
+        target,
+        snapshot_mode,
+    };
+
+    let mut reports: Vec<Option<std::io::Result<ExpectReport>>> =
+        expects.iter().map(|_| None).collect();
+
+    let mut arenas = arenas.iter_mut();
+
+    if worker_count == 1 {
+        let arena = arenas.next().unwrap();
+
+        for (report, expect) in reports.iter_mut().zip(&expects) {
+            *report = Some(env.run(arena, *expect));
+        }
+    } else {
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::scope(|scope| {
+            for arena in arenas {
+                let (env, next, expects, sender) = (&env, &next, &expects, sender.clone());
+
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+
+                    let Some(expect) = expects.get(index) else {
+                        break;
+                    };
+
+                    // The receiver outlives the workers
+                    let _ = sender.send((index, env.run(arena, *expect)));
+                });
+            }
+        });
+
+        drop(sender);
+
+        for (index, report) in receiver {
+            reports[index] = Some(report);
+        }
+    }
+
+    let mut failed = 0;
+    let mut passed = 0;
+
+    for report in reports.into_iter().flatten() {
+        let report = report?;
+
+        writer.write_all(&report.output)?;
+
+        match report.passed {
+            true => passed += 1,
+            false => failed += 1,
+        }
+    }
+
+    Ok((failed, passed))
+}
+
+/// What one top-level expect produced, rendered up front so that expects can run on
+/// separate threads.
+struct ExpectReport {
+    passed: bool,
+    /// The expect's `dbg` output, followed by its failure report if it failed
+    output: Vec<u8>,
+}
+
+/// Everything the expects share, which is only ever read while they run.
+struct ExpectEnv<'a, 'r> {
+    procedures: &'r MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    interns: &'a Interns,
+    layout_interner: &'r GlobalLayoutInterner<'a>,
+    expectations: &'r VecMap<ModuleId, Expectations>,
+    render_target: RenderTarget,
+    target: Target,
+    snapshot_mode: SnapshotMode,
+}
+
+impl<'a, 'r> ExpectEnv<'a, 'r> {
+    fn run(
+        &self,
+        arena: &'a Bump,
+        (symbol, region): (Symbol, Region),
+    ) -> std::io::Result<ExpectReport> {
+        let mut output = Vec::new();
+
+        let Some((_, layout)) = self.procedures.keys().find(|(s, _)| *s == symbol) else {
+            return Ok(ExpectReport {
+                passed: true,
+                output,
+            });
+        };
+
+        let tl_layout_interner = self.layout_interner.fork();
+
+        let run_once = |arguments: Vec<Value<'a>>| {
+            let mut interpreter = Interpreter::new(self.procedures, &tl_layout_interner);
+            interpreter.capture_dbg_output();
+
+            let result = interpreter.call(symbol, *layout, arguments);
+
+            (
+                result,
+                interpreter.take_failed_expects(),
+                interpreter.take_dbg_output(),
+            )
+        };
+
+        // A property takes arguments, which we generate until some make it fail
+        let property = (!layout.arguments.is_empty()).then(|| {
+            check_property(
+                &tl_layout_interner,
+                layout.arguments,
+                DEFAULT_SEED,
+                |arguments| {
+                    let (result, failures, _) = run_once(arguments.to_vec());
+
+                    result.is_err() || !failures.is_empty()
+                },
+            )
+        });
+
+        let (result, failures, dbg_output) = match &property {
+            None => run_once(Vec::new()),
+            Some(Ok(None)) => {
+                return Ok(ExpectReport {
+                    passed: true,
+                    output,
+                })
+            }
+            Some(Ok(Some(counterexample))) => run_once(counterexample.arguments.clone()),
+            Some(Err(error)) => (Err(error.clone()), Vec::new(), Vec::new()),
+        };
+
+        for line in dbg_output {
+            writeln!(output, "{line}")?;
+        }
+
+        if result.is_ok() && failures.is_empty() {
+            return Ok(ExpectReport {
+                passed: true,
+                output,
+            });
+        }
+
+        let module_id = symbol.module_id();
+        let data = self.expectations.get(&module_id).unwrap();
+        let filename = data.path.to_owned();
+        let source = std::fs::read_to_string(&data.path).unwrap();
+
+        let renderer = Renderer::new(
+            arena,
+            self.interns,
+            self.render_target,
+            module_id,
+            filename,
+            &source,
+        );
+
+        // Failures whose values match their snapshots don't count. A property's counterexample
+        // is a bug to fix rather than a value to approve, so it never gets a snapshot.
+        let mut all_snapshotted = result.is_ok();
+        let snapshot_mode = match property {
+            None => self.snapshot_mode,
+            Some(_) => SnapshotMode::Off,
+        };
+
+        for failure in failures {
+            all_snapshotted &= render_failure(
+                &mut output,
+                &renderer,
+                arena,
+                self.expectations,
+                self.interns,
+                self.layout_interner,
+                self.target,
+                snapshot_mode,
+                region,
+                failure,
+            )?;
+        }
+
+        if let Some(Ok(Some(counterexample))) = &property {
+            writeln!(
+                output,
+                "This property failed on case {} of {}, and these arguments were shrunk {} times.",
+                counterexample.case, PROPERTY_CASES, counterexample.shrinks,
+            )?;
+        }
+
+        if all_snapshotted {
+            return Ok(ExpectReport {
+                passed: true,
+                output,
+            });
+        }
+
+        if let Err(error) = result {
+            let message = match error {
+                InterpError::Crash { message, .. } => message,
+                other => other.to_string(),
+            };
+
+            renderer.render_panic(&mut output, &message, region)?;
+        }
+
+        writeln!(output)?;
+
+        Ok(ExpectReport {
+            passed: false,
+            output,
+        })
+    }
+}
+
+/// Renders a failed expect, unless its values match its snapshot.
+/// Returns whether they did.
+#[allow(clippy::too_many_arguments)]
+fn render_failure<'a>(
+    writer: &mut impl std::io::Write,
+    renderer: &Renderer,
+    arena: &'a Bump,
+    expectations: &VecMap<ModuleId, Expectations>,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    target: Target,
+    snapshot_mode: SnapshotMode,
+    expect_region: Region,
+    failure: FailedExpect<'a>,
+) -> std::io::Result<bool> {
+    let FailedExpect {
+        module_id,
+        region: failure_region,
+        lookups,
+    } = failure;
+
+    let data = expectations.get(&module_id).unwrap();
+
+    let mut symbols = Vec::with_capacity(lookups.len());
+    let mut variables = Vec::with_capacity(lookups.len());
+    let mut values = Vec::with_capacity(lookups.len());
+
+    let mut layout_cache = LayoutCache::new(layout_interner.fork(), target);
+    let mut value_renderer =
+        ValueRenderer::new(arena, &data.subs, interns, layout_interner.fork(), target);
+
+    for (symbol, variable, value) in lookups {
+        let layout = layout_cache.from_var(arena, variable, &data.subs).unwrap();
+        let (memory, addr) =
+            InterpMemory::write(arena, &layout_cache.interner, target, &value, layout, 0);
+        let expr = value_renderer.render(memory, addr, layout, variable);
+
+        symbols.push(symbol);
+        variables.push(variable);
+        values.push(format_value(arena, &expr));
+    }
+
+    let snapshot = check_snapshot(
+        snapshot_mode,
+        &data.path,
+        renderer.source_text(failure_region),
+        &renderer.render_snapshot(&symbols, &values),
+    )?;
+
+    if !snapshot.passed() {
+        // Naming type variables changes the subs, which the other expects share
+        let mut subs = data.subs.clone();
+
+        renderer.render_failure(
+            writer,
+            &mut subs,
+            &symbols,
+            &variables,
+            &values,
+            Some(expect_region),
+            failure_region,
+        )?;
+    }
+
+    snapshot.write_note(writer)?;
+
+    Ok(snapshot.passed())
+}
+
+struct InterpApp<'a, 'r, I: LayoutInterner<'a>> {
+    arena: &'a Bump,
+    layout_interner: &'r I,
+    target: Target,
+    result: Result<(Value<'a>, InLayout<'a>), InterpError>,
+}
+
+impl<'a, 'r, I: LayoutInterner<'a>> InterpApp<'a, 'r, I> {
+    fn new(arena: &'a Bump, layout_interner: &'r I, target: Target) -> Self {
+        Self {
+            arena,
+            layout_interner,
+            target,
+            result: Ok((Value::unit(), Layout::UNIT)),
+        }
+    }
+
+    fn set_result(&mut self, result: Result<Value<'a>, InterpError>, layout: InLayout<'a>) {
+        self.result = result.map(|value| (value, layout));
+    }
+
+    /// Writes the result to memory, and returns the memory along with the result's address.
+    /// Reports a crash, and returns `None`, if the program crashed.
+    fn write_result(&self, min_size: usize) -> Option<(&'a InterpMemory<'a>, usize)> {
+        match &self.result {
+            Ok((value, layout)) => Some(InterpMemory::write(
+                self.arena,
+                self.layout_interner,
+                self.target,
+                value,
+                *layout,
+                min_size,
+            )),
+            Err(error) => {
+                eprintln!("This Roc code crashed with: \"{error}\"");
+                None
+            }
+        }
+    }
+}
+
+impl<'a, 'r, I: LayoutInterner<'a>> ReplApp<'a> for InterpApp<'a, 'r, I> {
+    type Memory = InterpMemory<'a>;
+
+    fn call_function<Return, F>(&mut self, _main_fn_name: &str, mut transform: F) -> Expr<'a>
+    where
+        F: FnMut(&'a Self::Memory, Return) -> Expr<'a>,
+        Self::Memory: 'a,
+    {
+        match self.write_result(size_of::<Return>()) {
+            Some((memory, addr)) => {
+                let result: Return = unsafe {
+                    let ptr: *const Return = memory.bytes[addr..].as_ptr().cast();
+                    ptr.read_unaligned()
+                };
+
+                transform(memory, result)
+            }
+            None => Expr::REPL_RUNTIME_CRASH,
+        }
+    }
+
+    fn call_function_dynamic_size<T, F>(
+        &mut self,
+        _main_fn_name: &str,
+        ret_bytes: usize,
+        mut transform: F,
+    ) -> Option<T>
+    where
+        F: FnMut(&'a Self::Memory, usize) -> T,
+        Self::Memory: 'a,
+    {
+        let (memory, addr) = self.write_result(ret_bytes)?;
+
+        Some(transform(memory, addr))
+    }
+}
+
+/// The interpreter's result, laid out in memory.
+struct InterpMemory<'a> {
+    bytes: &'a [u8],
+    tag_id_mask: usize,
+}
+
+impl<'a> InterpMemory<'a> {
+    /// Lays out the value in a memory of its own, at least `min_size` bytes past its address.
+    /// Returns the memory along with the value's address.
+    fn write<I: LayoutInterner<'a>>(
+        arena: &'a Bump,
+        layout_interner: &I,
+        target: Target,
+        value: &Value<'a>,
+        layout: InLayout<'a>,
+        min_size: usize,
+    ) -> (&'a Self, usize) {
+        let mut writer = MemoryWriter::new(layout_interner, target);
+        let addr = writer.write_new(value, layout);
+        writer.reserve(addr + min_size);
+
+        let memory = InterpMemory {
+            bytes: arena.alloc_slice_copy(&writer.bytes),
+            tag_id_mask: target.tag_id_pointer_mask() as usize,
+        };
+
+        (arena.alloc(memory), addr)
+    }
+}
+
+macro_rules! deref_number {
+    ($name: ident, $t: ty) => {
+        fn $name(&self, addr: usize) -> $t {
+            const N: usize = size_of::<$t>();
+            let mut array = [0; N];
+            array.copy_from_slice(&self.bytes[addr..][..N]);
+            <$t>::from_ne_bytes(array)
+        }
+    };
+}
+
+impl<'a> ValueMemory for InterpMemory<'a> {
+    fn deref_bool(&self, addr: usize) -> bool {
+        self.bytes[addr] != 0
+    }
+
+    deref_number!(deref_u8, u8);
+    deref_number!(deref_u16, u16);
+    deref_number!(deref_u32, u32);
+    deref_number!(deref_u64, u64);
+    deref_number!(deref_u128, u128);
+    deref_number!(deref_usize, usize);
+
+    deref_number!(deref_i8, i8);
+    deref_number!(deref_i16, i16);
+    deref_number!(deref_i32, i32);
+    deref_number!(deref_i64, i64);
+    deref_number!(deref_i128, i128);
+    deref_number!(deref_isize, isize);
+
+    deref_number!(deref_f32, f32);
+    deref_number!(deref_f64, f64);
+
+    fn deref_str(&self, addr: usize) -> &str {
+        // `MemoryWriter` never makes small strings or seamless slices.
+        let ptr = self.deref_usize(addr);
+        let len = self.deref_usize(addr + size_of::<usize>());
+
+        std::str::from_utf8(&self.bytes[ptr..][..len]).unwrap()
+    }
+
+    fn deref_pointer_with_tag_id(&self, addr: usize) -> (u16, u64) {
+        let addr_with_id = self.deref_usize(addr);
+
+        let tag_id = addr_with_id & self.tag_id_mask;
+        let data_addr = addr_with_id & !self.tag_id_mask;
+        (tag_id as _, data_addr as _)
+    }
+}
+
+struct MemoryWriter<'a, 'r, I: LayoutInterner<'a>> {
+    bytes: Vec<u8>,
+    layout_interner: &'r I,
+    target: Target,
+    _marker: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, 'r, I: LayoutInterner<'a>> MemoryWriter<'a, 'r, I> {
+    /// Heap allocations are at least this aligned, which leaves room for a tag id in the
+    /// low bits of pointers to them.
+    const MIN_ALIGNMENT: usize = 8;
+
+    fn new(layout_interner: &'r I, target: Target) -> Self {
+        debug_assert_eq!(target.ptr_width() as usize, size_of::<usize>());
+
+        Self {
+            // Nothing lives at address 0, so it can be the null pointer.
+            bytes: vec![0; Self::MIN_ALIGNMENT],
+            layout_interner,
+            target,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn reserve(&mut self, len: usize) {
+        if self.bytes.len() < len {
+            self.bytes.resize(len, 0);
+        }
+    }
+
+    fn alloc(&mut self, size: usize, alignment: usize) -> usize {
+        let addr = self
+            .bytes
+            .len()
+            .next_multiple_of(alignment.max(Self::MIN_ALIGNMENT));
+        self.reserve(addr + size);
+
+        addr
+    }
+
+    fn write_bytes(&mut self, addr: usize, bytes: &[u8]) {
+        self.bytes[addr..][..bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn write_usize(&mut self, addr: usize, n: usize) {
+        self.write_bytes(addr, &n.to_ne_bytes());
+    }
+
+    /// Allocates room for `value`, and writes it there.
+    fn write_new(&mut self, value: &Value<'a>, layout: InLayout<'a>) -> usize {
+        let (size, alignment) = self.layout_interner.stack_size_and_alignment(layout);
+        let addr = self.alloc(size as usize, alignment as usize);
+        self.write(addr, value, layout);
+
+        addr
+    }
+
+    fn write(&mut self, addr: usize, value: &Value<'a>, layout: InLayout<'a>) {
+        match (self.layout_interner.get_repr(layout), value) {
+            (LayoutRepr::Builtin(Builtin::Bool), Value::Bool(b)) => {
+                self.write_bytes(addr, &[*b as u8]);
+            }
+            (LayoutRepr::Builtin(Builtin::Int(width)), Value::Int(_, n)) => {
+                let size = width.stack_size() as usize;
+                self.write_bytes(addr, &n.to_ne_bytes()[..size]);
+            }
+            (LayoutRepr::Builtin(Builtin::Float(_)), Value::F32(f)) => {
+                self.write_bytes(addr, &f.to_ne_bytes());
+            }
+            (LayoutRepr::Builtin(Builtin::Float(_)), Value::F64(f)) => {
+                self.write_bytes(addr, &f.to_ne_bytes());
+            }
+            (LayoutRepr::Builtin(Builtin::Decimal), Value::Dec(n)) => {
+                self.write_bytes(addr, &n.to_ne_bytes());
+            }
+            (LayoutRepr::Builtin(Builtin::Str), Value::Str(s)) => {
+                let ptr = self.alloc(s.len(), 1);
+                self.write_bytes(ptr, s.as_bytes());
+                self.write_slice(addr, ptr, s.len());
+            }
+            (LayoutRepr::Builtin(Builtin::List(elem_layout)), Value::List(elems)) => {
+                let (elem_size, alignment) =
+                    self.layout_interner.stack_size_and_alignment(elem_layout);
+                let elem_size = elem_size as usize;
+                let ptr = self.alloc(elem_size * elems.len(), alignment as usize);
+
+                for (index, elem) in elems.iter().enumerate() {
+                    self.write(ptr + index * elem_size, elem, elem_layout);
+                }
+
+                self.write_slice(addr, ptr, elems.len());
+            }
+            (LayoutRepr::Struct(field_layouts), Value::Struct(fields)) => {
+                self.write_fields(addr, field_layouts, fields);
+            }
+            (LayoutRepr::Union(union_layout @ UnionLayout::NonRecursive(tags)), value) => value
+                .with_tag(|tag_id, fields| {
+                    self.write_fields(addr, tags[tag_id as usize], fields);
+
+                    let tag_id_offset = union_layout
+                        .data_size_without_tag_id(self.layout_interner)
+                        .unwrap();
+                    self.write_tag_id(addr + tag_id_offset as usize, union_layout, tag_id);
+                }),
+            (LayoutRepr::Union(_), Value::Null) => self.write_usize(addr, 0),
+            (LayoutRepr::Union(union_layout), Value::Heap(_)) => {
+                let ptr = self.write_heap_tag(union_layout, value);
+                self.write_usize(addr, ptr);
+            }
+            (LayoutRepr::RecursivePointer(inner), value) => self.write(addr, value, inner),
+            (
+                LayoutRepr::LambdaSet(_) | LayoutRepr::FunctionPointer(_) | LayoutRepr::Erased(_),
+                _,
+            ) => {
+                // Functions are rendered without looking at memory.
+            }
+            (repr, value) => internal_error!("cannot write {value:?} with the layout {repr:?}"),
+        }
+    }
+
+    /// Writes the pointer, length and capacity of a list or a big string.
+    fn write_slice(&mut self, addr: usize, ptr: usize, len: usize) {
+        let word = size_of::<usize>();
+
+        self.write_usize(addr, ptr);
+        self.write_usize(addr + word, len);
+        self.write_usize(addr + 2 * word, len);
+    }
+
+    fn write_fields(&mut self, addr: usize, field_layouts: &[InLayout<'a>], fields: &[Value<'a>]) {
+        let mut field_addr = addr;
+
+        for (field, field_layout) in fields.iter().zip(field_layouts) {
+            self.write(field_addr, field, *field_layout);
+            field_addr += self.layout_interner.stack_size(*field_layout) as usize;
+        }
+    }
+
+    fn write_tag_id(&mut self, addr: usize, union_layout: UnionLayout<'a>, tag_id: u16) {
+        match union_layout.discriminant() {
+            Discriminant::U0 => {}
+            Discriminant::U1 | Discriminant::U8 => self.write_bytes(addr, &[tag_id as u8]),
+            Discriminant::U16 => self.write_bytes(addr, &tag_id.to_ne_bytes()),
+        }
+    }
+
+    /// Allocates a tag of a recursive union, and returns the pointer to it, with the tag id
+    /// in its low bits if that is where this union keeps it.
+    fn write_heap_tag(&mut self, union_layout: UnionLayout<'a>, value: &Value<'a>) -> usize {
+        value.with_tag(|tag_id, fields| {
+            let field_layouts = match union_layout {
+                UnionLayout::Recursive(tags) => tags[tag_id as usize],
+                UnionLayout::NonNullableUnwrapped(field_layouts) => field_layouts,
+                UnionLayout::NullableWrapped {
+                    nullable_id,
+                    other_tags,
+                } => {
+                    let index = if tag_id > nullable_id {
+                        tag_id - 1
+                    } else {
+                        tag_id
+                    };
+                    other_tags[index as usize]
+                }
+                UnionLayout::NullableUnwrapped { other_fields, .. } => other_fields,
+                UnionLayout::NonRecursive(_) => {
+                    internal_error!("non-recursive unions are not on the heap")
+                }
+            };
+
+            let fields_size: u32 = field_layouts
+                .iter()
+                .map(|layout| self.layout_interner.stack_size(*layout))
+                .sum();
+
+            let has_tag_id = matches!(
+                union_layout,
+                UnionLayout::Recursive(_) | UnionLayout::NullableWrapped { .. }
+            );
+            let tag_id_in_pointer =
+                has_tag_id && union_layout.stores_tag_id_in_pointer(self.target);
+            let tag_id_offset = union_layout
+                .data_size_without_tag_id(self.layout_interner)
+                .unwrap_or(fields_size) as usize;
+
+            let size = if has_tag_id && !tag_id_in_pointer {
+                tag_id_offset + union_layout.discriminant().stack_size() as usize
+            } else {
+                tag_id_offset
+            };
+            let alignment = union_layout.allocation_alignment_bytes(self.layout_interner);
+
+            let ptr = self.alloc(size.max(fields_size as usize), alignment as usize);
+            self.write_fields(ptr, field_layouts, fields);
+
+            if tag_id_in_pointer {
+                ptr | tag_id as usize
+            } else {
+                if has_tag_id {
+                    self.write_tag_id(ptr + tag_id_offset, union_layout, tag_id);
+                }
+
+                ptr
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bumpalo::Bump;
+    use indoc::indoc;
+    use roc_load::{ExecutionMode, FunctionKind, LoadConfig, Threading};
+    use roc_packaging::cache::RocCacheDir;
+    use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+    use roc_target::Target;
+
+    use crate::gen::compile_to_mono;
+    use crate::snapshot::SnapshotMode;
+
+    fn eval(defs: &[&str], src: &str) -> (String, String) {
+        let arena = Bump::new();
+        let target = Target::LinuxX64;
+
+        let (loaded, problems) =
+            compile_to_mono(&arena, defs.iter().copied(), src, target, DEFAULT_PALETTE);
+        assert!(problems.errors.is_empty(), "{:?}", problems.errors);
+
+        let output = super::eval_interp(loaded.unwrap(), target).unwrap();
+
+        (output.expr, output.expr_type)
+    }
+
+    fn expect_success(src: &str, expected: &str, expected_type: &str) {
+        expect_success_with_defs(&[], src, expected, expected_type);
+    }
+
+    fn expect_success_with_defs(defs: &[&str], src: &str, expected: &str, expected_type: &str) {
+        assert_eq!(
+            eval(defs, src),
+            (expected.to_string(), expected_type.to_string())
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        expect_success("1 + 2", "3", "Num *");
+        expect_success("1.5 + 2.25", "3.75", "Frac *");
+        expect_success("Num.maxU64", "18446744073709551615", "U64");
+        expect_success("Num.toStr -10.75f64", "\"-10.75\"", "Str");
+    }
+
+    #[test]
+    fn dec_arithmetic_matches_the_builtins() {
+        expect_success("10dec / 3", "3.333333333333333333", "Dec");
+        expect_success("0dec / 0", "0", "Dec");
+        expect_success("Num.pow 1.1dec 2", "1.21", "Dec");
+        expect_success("Num.round -2.5dec", "-3", "Int *");
+    }
+
+    #[test]
+    fn strings_and_lists() {
+        expect_success(r#"Str.concat "hello " "world""#, r#""hello world""#, "Str");
+        expect_success(
+            "List.map [1, 2, 3] \\x -> x * 2",
+            "[2, 4, 6]",
+            "List (Num *)",
+        );
+        expect_success(
+            "List.sortWith [3, 1, 2] Num.compare",
+            "[1, 2, 3]",
+            "List (Num *)",
+        );
+    }
+
+    #[test]
+    fn floats_render_the_same_anywhere() {
+        expect_success("0f64 / 0", "Num.nanF64", "F64");
+        expect_success(
+            "{ x: 0f64 / 0, y: 1f32 / 0 }",
+            "{ x: Num.nanF64, y: Num.infinityF32 }",
+            "{ x : F64, y : F32 }",
+        );
+    }
+
+    #[test]
+    fn records_and_tags() {
+        expect_success(
+            "{ a: 1u8, b: \"x\" }",
+            "{ a: 1, b: \"x\" }",
+            "{ a : U8, b : Str }",
+        );
+        expect_success(
+            "Str.toU8 \"300\"",
+            "Err InvalidNumStr",
+            "Result U8 [InvalidNumStr]",
+        );
+    }
+
+    #[test]
+    fn recursive_tags() {
+        expect_success_with_defs(
+            &["countdown = \\n -> if n == 0 then Nil else Cons n (countdown (n - 1))"],
+            "countdown 2u8",
+            "Cons 2 (Cons 1 Nil)",
+            "[Cons U8 a, Nil] as a",
+        );
+    }
+
+    #[test]
+    fn nullable_pointer_niche() {
+        expect_success(
+            "[Some (Box.box 1u8), None]",
+            "[Some (Box.box 1), None]",
+            "List [None, Some (Box U8)]",
+        );
+        expect_success_with_defs(
+            &["unwrap = \\opt -> when opt is\n    Some b -> Box.unbox b\n    None -> 0u8"],
+            "[unwrap (Some (Box.box 7)), unwrap None]",
+            "[7, 0]",
+            "List U8",
+        );
+        expect_success(
+            "(Some (Box.box 1u8) == Some (Box.box 1), Some (Box.box 1u8) == None)",
+            "(Bool.true, Bool.false)",
+            "( Bool, Bool )*",
+        );
+    }
+
+    #[test]
+    fn deep_recursion() {
+        // Deeper than the Rust stack would allow, if the interpreter recursed on calls.
+        expect_success_with_defs(
+            &["sum = \\n -> if n == 0 then 0 else n + sum (n - 1)"],
+            "sum 50000u64",
+            "1250025000",
+            "U64",
+        );
+    }
+
+    fn run_expects(source: &str) -> (usize, usize, String) {
+        let src_dir = tempfile::tempdir().unwrap();
+
+        run_expects_in(src_dir.path(), source, SnapshotMode::Off)
+    }
+
+    fn run_expects_in(
+        src_dir: &std::path::Path,
+        source: &str,
+        snapshot_mode: SnapshotMode,
+    ) -> (usize, usize, String) {
+        let arena = Bump::new();
+        let target = Target::LinuxX64;
+
+        let filename = src_dir.join("Test.roc");
+        std::fs::write(&filename, source).unwrap();
+
+        let load_config = LoadConfig {
+            target,
+            function_kind: FunctionKind::LambdaSet,
+            render: RenderTarget::Generic,
+            palette: DEFAULT_PALETTE,
+            threading: Threading::Single,
+            exec_mode: ExecutionMode::Test,
+        };
+        let loaded = roc_load::load_and_monomorphize_from_str(
+            &arena,
+            filename,
+            source,
+            src_dir.to_path_buf(),
+            None,
+            RocCacheDir::Disallowed,
+            load_config,
+        )
+        .unwrap();
+
+        let mut writer = Vec::new();
+        let (failed, passed) = super::run_toplevel_expects(
+            &mut writer,
+            RenderTarget::Generic,
+            loaded,
+            target,
+            snapshot_mode,
+            Threading::AllAvailable,
+        )
+        .unwrap();
+
+        // The first line has the path of the temporary file in it.
+        let output = String::from_utf8(writer).unwrap();
+        let output = output.split_once('\n').map_or("", |(_, rest)| rest);
+
+        (failed, passed, output.trim().to_string())
+    }
+
+    #[test]
+    fn expects() {
+        let (failed, passed, output) = run_expects(indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = 0
+
+            expect
+                a = 1
+                b = 2
+
+                a == b
+
+            expect 1 == 1
+            "#
+        ));
+
+        assert_eq!((failed, passed), (1, 1));
+        assert_eq!(
+            output,
+            indoc!(
+                r"
+                This expectation failed:
+
+                5│>  expect
+                6│>      a = 1
+                7│>      b = 2
+                8│>
+                9│>      a == b
+
+                When it failed, these variables had these values:
+
+                `a` : `Num` `*`
+                `a` = 1
+
+                `b` : `Num` `*`
+                `b` = 2"
+            )
+        );
+    }
+
+    #[test]
+    fn expect_crash() {
+        let (failed, passed, output) = run_expects(indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = 0
+
+            expect
+                x : U8
+                x = 255
+
+                x + 1 == 0
+            "#
+        ));
+
+        assert_eq!((failed, passed), (1, 0));
+        assert!(output.contains("Integer addition overflowed!"), "{output}");
+    }
+
+    #[test]
+    fn expect_snapshots() {
+        let source = indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = 0
+
+            expect
+                big = List.range { start: At 0, end: Before 100 }
+
+                List.len big == 0
+            "#
+        );
+
+        let src_dir = tempfile::tempdir().unwrap();
+
+        let (failed, passed, output) =
+            run_expects_in(src_dir.path(), source, SnapshotMode::Compare);
+        assert_eq!((failed, passed), (1, 0));
+        assert!(output.contains("no snapshot of them"), "{output}");
+
+        let (failed, passed, _) = run_expects_in(src_dir.path(), source, SnapshotMode::Update);
+        assert_eq!((failed, passed), (0, 1));
+        let snapshot = only_snapshot(src_dir.path());
+        assert!(std::fs::read_to_string(&snapshot)
+            .unwrap()
+            .starts_with("big = [0, 1, 2"));
+
+        let (failed, passed, _) = run_expects_in(src_dir.path(), source, SnapshotMode::Compare);
+        assert_eq!((failed, passed), (0, 1));
+
+        std::fs::write(&snapshot, "big = []\n").unwrap();
+        let (failed, passed, output) =
+            run_expects_in(src_dir.path(), source, SnapshotMode::Compare);
+        assert_eq!((failed, passed), (1, 0));
+        assert!(output.contains("don't match the snapshot"), "{output}");
+    }
+
+    #[test]
+    fn expect_snapshots_survive_moving_the_expect() {
+        let source = indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = 0
+
+            expect
+                big = List.range { start: At 0, end: Before 100 }
+
+                List.len big == 0
+            "#
+        );
+
+        let src_dir = tempfile::tempdir().unwrap();
+        let (failed, passed, _) = run_expects_in(src_dir.path(), source, SnapshotMode::Update);
+        assert_eq!((failed, passed), (0, 1));
+        let snapshot = only_snapshot(src_dir.path());
+
+        // Lines added above the expect, and reindenting it, keep its snapshot
+        let moved = indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = 0
+
+            helper = \x -> x + 1
+
+            expect
+                big = List.range   { start: At 0, end: Before 100 }
+
+                List.len big  ==  0
+            "#
+        );
+        let (failed, passed, _) = run_expects_in(src_dir.path(), moved, SnapshotMode::Compare);
+        assert_eq!((failed, passed), (0, 1));
+        assert_eq!(only_snapshot(src_dir.path()), snapshot);
+
+        // Changing the condition needs a new snapshot
+        let changed = source.replace("List.len big == 0", "List.len big == 1");
+        let (failed, passed, output) =
+            run_expects_in(src_dir.path(), &changed, SnapshotMode::Compare);
+        assert_eq!((failed, passed), (1, 0));
+        assert!(output.contains("no snapshot of them"), "{output}");
+    }
+
+    fn only_snapshot(src_dir: &std::path::Path) -> std::path::PathBuf {
+        let mut entries = std::fs::read_dir(src_dir.join("snapshots"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+
+        assert_eq!(entries.len(), 1, "{entries:?}");
+        entries.pop().unwrap()
+    }
+
+    #[test]
+    fn expects_in_parallel() {
+        let (failed, passed, output) = run_expects(indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = 0
+
+            expect 1 == 2
+
+            expect
+                first = 1
+                dbg first
+
+                first == 1
+
+            expect
+                second = 2
+                dbg second
+
+                second == 3
+
+            expect 3 == 3
+            "#
+        ));
+
+        assert_eq!((failed, passed), (2, 2));
+
+        // The output is in source order, with each dbg next to the report of its expect.
+        let first_failure = output.find("5│  expect 1 == 2").unwrap();
+        let first_dbg = output.find("first = 1").unwrap();
+        let second_dbg = output.find("second = 2").unwrap();
+        let second_failure = output.find("`second` = 2").unwrap();
+
+        assert!(first_failure < first_dbg, "{output}");
+        assert!(first_dbg < second_dbg, "{output}");
+        assert!(second_dbg < second_failure, "{output}");
+    }
+
+    #[test]
+    fn property_expects() {
+        let (failed, passed, output) = run_expects(indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = 0
+
+            expect \n -> n + 0 == n
+
+            expect \a, b -> Num.addWrap a b == Num.addWrap b a
+
+            expect \n -> n < 10
+            "#
+        ));
+
+        assert_eq!((failed, passed), (1, 2));
+
+        // The counterexample is shrunk to the smallest failing argument.
+        assert!(output.contains("`n` = 10"), "{output}");
+        assert!(output.contains("This property failed on case"), "{output}");
+    }
+}
@@ -1,13 +1,15 @@
 //! Provides the functionality for the REPL to evaluate Roc expressions.
 use roc_parse::ast::Expr;
-use roc_std::RocDec;
+use roc_render_value::ValueMemory;
 use roc_target::Target;
 
 pub mod eval;
 pub mod gen;
+pub mod interp;
+pub mod snapshot;
 
 pub trait ReplApp<'a> {
-    type Memory: 'a + ReplAppMemory;
+    type Memory: 'a + ValueMemory;
 
     /// Run user code that returns a type with a `Builtin` layout
     /// Size of the return value is statically determined from its Rust type
@@ -33,7 +35,7 @@ pub trait ReplApp<'a> {
         transform: F,
     ) -> Option<T>
     where
-        F: Fn(&'a Self::Memory, usize) -> T,
+        F: FnMut(&'a Self::Memory, usize) -> T,
         Self::Memory: 'a,
     {
         let roc_str_width = match target.ptr_width() {
@@ -58,33 +60,3 @@ pub trait ReplApp<'a> {
         F: FnMut(&'a Self::Memory, usize) -> T,
         Self::Memory: 'a;
 }
-
-pub trait ReplAppMemory {
-    fn deref_bool(&self, addr: usize) -> bool;
-
-    fn deref_u8(&self, addr: usize) -> u8;
-    fn deref_u16(&self, addr: usize) -> u16;
-    fn deref_u32(&self, addr: usize) -> u32;
-    fn deref_u64(&self, addr: usize) -> u64;
-    fn deref_u128(&self, addr: usize) -> u128;
-    fn deref_usize(&self, addr: usize) -> usize;
-
-    fn deref_i8(&self, addr: usize) -> i8;
-    fn deref_i16(&self, addr: usize) -> i16;
-    fn deref_i32(&self, addr: usize) -> i32;
-    fn deref_i64(&self, addr: usize) -> i64;
-    fn deref_i128(&self, addr: usize) -> i128;
-    fn deref_isize(&self, addr: usize) -> isize;
-
-    fn deref_f32(&self, addr: usize) -> f32;
-    fn deref_f64(&self, addr: usize) -> f64;
-
-    fn deref_dec(&self, addr: usize) -> RocDec {
-        let bits = self.deref_i128(addr);
-        RocDec::new(bits)
-    }
-
-    fn deref_str(&self, addr: usize) -> &str;
-
-    fn deref_pointer_with_tag_id(&self, addr: usize) -> (u16, u64);
-}
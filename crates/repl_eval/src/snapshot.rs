@@ -0,0 +1,122 @@
+//! Snapshot ("golden file") testing for top-level expects.
+//!
+//! When an expect fails and its variables' values are too large to check by eye, `roc test`
+//! compares them against a snapshot file instead, and the expect passes if they match.
+//! `roc test --update-snapshots` writes the current values to the snapshot files.
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Values rendered shorter than this are always shown in the failure report instead.
+pub const MIN_SNAPSHOT_LEN: usize = 200;
+
+/// The directory next to a module where its snapshot files go
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+/// What to do with the values of a failed expect, when they're large enough for a snapshot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotMode {
+    /// Report every failed expect.
+    Off,
+    /// A failed expect passes if its values match its snapshot file.
+    Compare,
+    /// Write the values to the snapshot file, and let the expect pass.
+    Update,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// The values are small, or snapshots are off, so report the failure as usual.
+    NotSnapshotted,
+    /// The values match the snapshot file.
+    Matched,
+    /// The values were written to this snapshot file.
+    Updated(PathBuf),
+    /// There is no snapshot file yet.
+    Missing(PathBuf),
+    /// The snapshot file has different values.
+    Mismatched(PathBuf),
+}
+
+impl SnapshotOutcome {
+    /// Whether the failed expect counts as passed.
+    pub fn passed(&self) -> bool {
+        matches!(self, Self::Matched | Self::Updated(_))
+    }
+
+    /// Writes a line explaining the outcome, if there's anything to say.
+    pub fn write_note(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        match self {
+            Self::NotSnapshotted | Self::Matched => Ok(()),
+            Self::Updated(path) => {
+                writeln!(writer, "Updated the snapshot in {}", path.display())
+            }
+            Self::Missing(path) => writeln!(
+                writer,
+                "These values are too large to check here, and there's no snapshot of them in {} yet. Run `roc test --update-snapshots` to write it.",
+                path.display()
+            ),
+            Self::Mismatched(path) => writeln!(
+                writer,
+                "These values don't match the snapshot in {}. If they're right, run `roc test --update-snapshots` to update it.",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// The snapshot file for the expect with the condition `expect_source`, in the module at
+/// `module_path`. It's named after a hash of the condition, ignoring whitespace, so that editing
+/// code above the expect or reformatting it keeps the snapshot, while changing the condition
+/// starts a new one.
+pub fn snapshot_path(module_path: &Path, expect_source: &str) -> PathBuf {
+    let stem = module_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+
+    let mut hasher = blake3::Hasher::new();
+    for word in expect_source.split_whitespace() {
+        hasher.update(word.as_bytes());
+        hasher.update(b" ");
+    }
+    let hash = hasher.finalize().to_hex();
+
+    module_path
+        .with_file_name(SNAPSHOT_DIR)
+        .join(format!("{stem}-{}.snap", &hash[..16]))
+}
+
+/// Compares the rendered values of a failed expect against its snapshot file,
+/// or writes them to it in `SnapshotMode::Update`.
+pub fn check_snapshot(
+    mode: SnapshotMode,
+    module_path: &Path,
+    expect_source: &str,
+    rendered: &str,
+) -> io::Result<SnapshotOutcome> {
+    if mode == SnapshotMode::Off || rendered.len() < MIN_SNAPSHOT_LEN {
+        return Ok(SnapshotOutcome::NotSnapshotted);
+    }
+
+    let path = snapshot_path(module_path, expect_source);
+
+    match mode {
+        SnapshotMode::Off => unreachable!(),
+        SnapshotMode::Update => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+
+            std::fs::write(&path, rendered)?;
+
+            Ok(SnapshotOutcome::Updated(path))
+        }
+        SnapshotMode::Compare => match std::fs::read_to_string(&path) {
+            // Editors and git may change the line endings
+            Ok(stored) if stored.replace("\r\n", "\n") == rendered => Ok(SnapshotOutcome::Matched),
+            Ok(_) => Ok(SnapshotOutcome::Mismatched(path)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(SnapshotOutcome::Missing(path)),
+            Err(err) => Err(err),
+        },
+    }
+}
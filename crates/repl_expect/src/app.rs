@@ -1,7 +1,5 @@
-use roc_parse::ast::Expr;
-use roc_repl_eval::{ReplApp, ReplAppMemory};
+use roc_render_value::ValueMemory;
 use roc_std::RocStr;
-use roc_target::Target;
 
 pub(crate) struct ExpectMemory {
     pub(crate) start: *const u8,
@@ -16,7 +14,7 @@ macro_rules! deref_number {
     };
 }
 
-impl ReplAppMemory for ExpectMemory {
+impl ValueMemory for ExpectMemory {
     fn deref_bool(&self, addr: usize) -> bool {
         let ptr = unsafe { self.start.add(addr) };
         let value = unsafe { std::ptr::read_unaligned(ptr) };
@@ -81,68 +79,3 @@ impl ReplAppMemory for ExpectMemory {
         (tag_id as _, offset as _)
     }
 }
-
-pub(crate) struct ExpectReplApp<'a> {
-    pub(crate) memory: &'a ExpectMemory,
-    pub(crate) offset: usize,
-}
-
-impl<'a> ReplApp<'a> for ExpectReplApp<'a> {
-    type Memory = ExpectMemory;
-
-    /// Run user code that returns a type with a `Builtin` layout
-    /// Size of the return value is statically determined from its Rust type
-    /// The `transform` callback takes the app's memory and the returned value
-    /// _main_fn_name is always the same and we don't use it here
-    fn call_function<Return, F>(&mut self, _main_fn_name: &str, mut transform: F) -> Expr<'a>
-    where
-        F: FnMut(&'a Self::Memory, Return) -> Expr<'a>,
-        Self::Memory: 'a,
-    {
-        let result: Return = unsafe {
-            let ptr = self.memory.start.add(self.offset);
-            let ptr: *const Return = std::mem::transmute(ptr);
-            ptr.read()
-        };
-
-        transform(self.memory, result)
-    }
-
-    fn call_function_returns_roc_list<F>(&mut self, main_fn_name: &str, transform: F) -> Expr<'a>
-    where
-        F: FnMut(&'a Self::Memory, (usize, usize, usize)) -> Expr<'a>,
-        Self::Memory: 'a,
-    {
-        self.call_function(main_fn_name, transform)
-    }
-
-    fn call_function_returns_roc_str<T, F>(
-        &mut self,
-        _target: Target,
-        main_fn_name: &str,
-        transform: F,
-    ) -> Option<T>
-    where
-        F: Fn(&'a Self::Memory, usize) -> T,
-        Self::Memory: 'a,
-    {
-        self.call_function_dynamic_size(main_fn_name, 24, transform)
-    }
-
-    /// Run user code that returns a struct or union, whose size is provided as an argument
-    /// The `transform` callback takes the app's memory and the address of the returned value
-    /// _main_fn_name and _ret_bytes are only used for the CLI REPL. For Wasm they are compiled-in
-    /// to the test_wrapper function of the app itself
-    fn call_function_dynamic_size<T, F>(
-        &mut self,
-        _main_fn_name: &str,
-        _ret_bytes: usize,
-        mut transform: F,
-    ) -> Option<T>
-    where
-        F: FnMut(&'a Self::Memory, usize) -> T,
-        Self::Memory: 'a,
-    {
-        Some(transform(self.memory, self.offset))
-    }
-}
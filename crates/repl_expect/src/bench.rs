@@ -0,0 +1,610 @@
+//! Runs the benchmarks for `roc bench`: top-level values named like `benchSort`.
+//!
+//! Each benchmark is compiled with optimizations into the same dylib as the expects, and called
+//! over and over. After a warmup, calls are timed in samples of many iterations each, until the
+//! mean is known precisely enough or the time budget runs out. Whatever a benchmark computes is
+//! never freed, so benchmarks should return small values, like a number or a `Bool`.
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use roc_module::ident::ModuleName;
+use roc_module::symbol::Interns;
+use roc_mono::layout::{LayoutInterner, STLayoutInterner};
+use roc_std::RocStr;
+
+use crate::run::{ExpectMemory, ToplevelBench};
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    /// How long to call a benchmark before timing it
+    pub warmup: Duration,
+    /// Roughly how long to spend timing a benchmark
+    pub measurement: Duration,
+    pub min_samples: usize,
+    pub max_samples: usize,
+    /// Stop early once the standard error of the mean is at most this fraction of the mean
+    pub target_relative_error: f64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_millis(500),
+            measurement: Duration::from_secs(3),
+            min_samples: 10,
+            max_samples: 100,
+            target_relative_error: 0.005,
+        }
+    }
+}
+
+/// Statistics of the time one iteration took, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub iterations_per_sample: u64,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: f64,
+}
+
+impl Stats {
+    /// `samples` holds the time one iteration took in each sample. There must be at least one.
+    pub fn from_samples(samples: &[f64], iterations_per_sample: u64) -> Self {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+
+        let variance = match samples.len() {
+            1 => 0.0,
+            _ => samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0),
+        };
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let middle = sorted.len() / 2;
+        let median = match sorted.len() % 2 {
+            0 => (sorted[middle - 1] + sorted[middle]) / 2.0,
+            _ => sorted[middle],
+        };
+
+        Self {
+            samples: samples.len(),
+            iterations_per_sample,
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            min: sorted[0],
+        }
+    }
+
+    /// The standard error of the mean, as a fraction of the mean
+    pub fn relative_error(&self) -> f64 {
+        match self.mean {
+            mean if mean > 0.0 => self.std_dev / (self.samples as f64).sqrt() / mean,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Hardware counters per iteration, where the OS lets us read them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Counters {
+    pub instructions: Option<f64>,
+    pub cycles: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    /// Like `Dict.benchGet`, or just `benchSort` in an app module
+    pub name: String,
+    pub stats: Stats,
+    pub counters: Counters,
+}
+
+/// The median time of each benchmark in an earlier run, to compare against
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: HashMap<String, f64>,
+}
+
+impl Baseline {
+    const HEADER: &'static str = "# roc bench baseline: benchmark, then median ns per iteration";
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        std::fs::read_to_string(path).map(|contents| Self::parse(&contents))
+    }
+
+    pub fn write(path: &Path, results: &[BenchResult]) -> io::Result<()> {
+        std::fs::write(path, Self::render(results))
+    }
+
+    fn parse(contents: &str) -> Self {
+        let medians = contents
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let (name, median) = line.split_once('\t')?;
+
+                Some((name.to_string(), median.trim().parse().ok()?))
+            })
+            .collect();
+
+        Self { medians }
+    }
+
+    fn render(results: &[BenchResult]) -> String {
+        let mut contents = format!("{}\n", Self::HEADER);
+
+        for result in results {
+            contents.push_str(&format!("{}\t{:.1}\n", result.name, result.stats.median));
+        }
+
+        contents
+    }
+
+    pub fn median(&self, name: &str) -> Option<f64> {
+        self.medians.get(name).copied()
+    }
+}
+
+/// How a benchmark's median compares to its baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    /// Within the noise of the measurement
+    None,
+    /// The fraction of the baseline the benchmark got faster by
+    Faster(f64),
+    /// The fraction of the baseline the benchmark got slower by
+    Slower(f64),
+}
+
+impl Change {
+    /// Changes smaller than this fraction are always treated as noise
+    const MIN_CHANGE: f64 = 0.02;
+
+    pub fn between(baseline: f64, stats: &Stats) -> Self {
+        let change = (stats.median - baseline) / baseline;
+
+        // A change within two standard deviations could just be noise
+        let noise = (2.0 * stats.std_dev / stats.median).max(Self::MIN_CHANGE);
+
+        if change.abs() <= noise {
+            Change::None
+        } else if change < 0.0 {
+            Change::Faster(-change)
+        } else {
+            Change::Slower(change)
+        }
+    }
+}
+
+/// Runs the benchmarks of one module, writing each result as soon as it's known.
+/// Returns the benchmarks that finished, and how many crashed.
+pub fn run_benches<W: io::Write>(
+    writer: &mut W,
+    interns: &Interns,
+    layout_interner: &STLayoutInterner<'_>,
+    lib: &libloading::Library,
+    benches: &[ToplevelBench<'_>],
+    config: BenchConfig,
+    baseline: Option<&Baseline>,
+) -> io::Result<(Vec<BenchResult>, usize)> {
+    let mut results = Vec::with_capacity(benches.len());
+    let mut crashed = 0;
+
+    // inline expects write to this, even though nobody reads it
+    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
+    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+    memory.set_shared_buffer(lib);
+
+    for bench in benches {
+        let ident = bench.symbol.as_str(interns);
+        let name = match bench.symbol.module_string(interns).as_str() {
+            ModuleName::APP => ident.to_string(),
+            module => format!("{module}.{ident}"),
+        };
+
+        writeln!(writer, "{name}")?;
+
+        let (result_size, _) = layout_interner.stack_size_and_alignment(bench.result_layout);
+        let mut function = BenchFunction::new(lib, bench.name, result_size)?;
+
+        match measure(&mut function, config) {
+            Ok((stats, counters)) => {
+                let result = BenchResult {
+                    name,
+                    stats,
+                    counters,
+                };
+
+                write_result(writer, &result, baseline)?;
+                results.push(result);
+            }
+            Err(message) => {
+                writeln!(writer, "    crashed: {message}")?;
+                crashed += 1;
+            }
+        }
+    }
+
+    Ok((results, crashed))
+}
+
+fn write_result<W: io::Write>(
+    writer: &mut W,
+    result: &BenchResult,
+    baseline: Option<&Baseline>,
+) -> io::Result<()> {
+    let stats = &result.stats;
+
+    writeln!(
+        writer,
+        "    time:     {} median, {} mean ± {}, {} min ({} samples of {} iterations)",
+        format_ns(stats.median),
+        format_ns(stats.mean),
+        format_ns(stats.std_dev),
+        format_ns(stats.min),
+        stats.samples,
+        stats.iterations_per_sample,
+    )?;
+
+    if let Counters {
+        instructions: Some(instructions),
+        cycles: Some(cycles),
+    } = result.counters
+    {
+        writeln!(
+            writer,
+            "    counters: {instructions:.0} instructions, {cycles:.0} cycles per iteration ({:.2} per cycle)",
+            instructions / cycles,
+        )?;
+    }
+
+    match baseline.map(|baseline| baseline.median(&result.name)) {
+        None => {}
+        Some(None) => writeln!(writer, "    baseline: none for this benchmark")?,
+        Some(Some(median)) => {
+            let change = match Change::between(median, stats) {
+                Change::None => "no change".to_string(),
+                Change::Faster(by) => format!("{:.1}% faster", by * 100.0),
+                Change::Slower(by) => format!("{:.1}% slower", by * 100.0),
+            };
+
+            writeln!(
+                writer,
+                "    baseline: {} median, {change}",
+                format_ns(median)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats a number of nanoseconds with a unit that keeps it readable
+fn format_ns(ns: f64) -> String {
+    if ns < 1e3 {
+        format!("{ns:.1} ns")
+    } else if ns < 1e6 {
+        format!("{:.2} µs", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.2} ms", ns / 1e6)
+    } else {
+        format!("{:.2} s", ns / 1e9)
+    }
+}
+
+/// A benchmark in the dylib, with room for the value it computes
+struct BenchFunction<'lib> {
+    function: libloading::Symbol<'lib, unsafe extern "C" fn(*mut u8)>,
+    /// The benchmark writes a `RocCallResult` to this, with its value after the tag and the
+    /// error message. u128s make sure it's aligned for any value.
+    out: Vec<u128>,
+}
+
+impl<'lib> BenchFunction<'lib> {
+    fn new(lib: &'lib libloading::Library, name: &str, result_size: u32) -> io::Result<Self> {
+        let function = unsafe { lib.get(name.as_bytes()) }
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+
+        let header_size = 2 * std::mem::size_of::<u64>();
+        let words = (header_size + result_size as usize).div_ceil(std::mem::size_of::<u128>());
+
+        Ok(Self {
+            function,
+            out: vec![0; words],
+        })
+    }
+
+    /// Calls the benchmark `iterations` times, stopping at the first crash
+    fn call(&mut self, iterations: u64) -> Result<(), String> {
+        for _ in 0..iterations {
+            let out = self.out.as_mut_ptr().cast::<u8>();
+
+            unsafe {
+                (self.function)(out);
+
+                let tag = out.cast::<u64>().read();
+
+                if tag != 0 {
+                    let error_msg = out.add(8).cast::<*const RocStr>().read();
+
+                    return Err((*error_msg).as_str().to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn measure(
+    function: &mut BenchFunction<'_>,
+    config: BenchConfig,
+) -> Result<(Stats, Counters), String> {
+    // Warm up the caches and branch predictors, and estimate how long one iteration takes
+    let warmup_start = Instant::now();
+    let mut warmup_iterations = 0u64;
+
+    while warmup_iterations == 0 || warmup_start.elapsed() < config.warmup {
+        function.call(1)?;
+        warmup_iterations += 1;
+    }
+
+    let estimate = warmup_start.elapsed().as_nanos() as f64 / warmup_iterations as f64;
+
+    // Aim for samples long enough that the clock's resolution doesn't matter
+    let sample_budget = config.measurement.as_nanos() as f64 / config.max_samples as f64;
+    let iterations_per_sample = ((sample_budget / estimate.max(1.0)) as u64).max(1);
+
+    let mut counters = perf::Counters::open();
+    let mut samples = Vec::with_capacity(config.max_samples);
+    let measurement_start = Instant::now();
+
+    counters.enable();
+
+    let stats = loop {
+        let sample_start = Instant::now();
+        function.call(iterations_per_sample)?;
+        let elapsed = sample_start.elapsed().as_nanos() as f64;
+
+        samples.push(elapsed / iterations_per_sample as f64);
+
+        let stats = Stats::from_samples(&samples, iterations_per_sample);
+
+        let done = samples.len() >= config.max_samples
+            || (samples.len() >= config.min_samples
+                && (stats.relative_error() <= config.target_relative_error
+                    || measurement_start.elapsed() >= config.measurement));
+
+        if done {
+            break stats;
+        }
+    };
+
+    counters.disable();
+
+    let iterations = (stats.samples as u64 * iterations_per_sample) as f64;
+    let (instructions, cycles) = counters.read();
+
+    let counters = Counters {
+        instructions: instructions.map(|n| n as f64 / iterations),
+        cycles: cycles.map(|n| n as f64 / iterations),
+    };
+
+    Ok((stats, counters))
+}
+
+#[cfg(target_os = "linux")]
+mod perf {
+    //! Hardware counters through `perf_event_open`. Opening them fails in many containers and
+    //! VMs, or when `perf_event_paranoid` forbids it, in which case there are no counts.
+    use std::os::fd::{FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::io::AsRawFd;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+
+    const PERF_EVENT_IOC_ENABLE: u64 = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: u64 = 0x2401;
+
+    /// `disabled`, `exclude_kernel` and `exclude_hv`
+    const FLAGS: u64 = 1 | (1 << 5) | (1 << 6);
+
+    /// The first 112 bytes of `struct perf_event_attr`, which is `PERF_ATTR_SIZE_VER5`
+    #[repr(C)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        rest: [u64; 8],
+    }
+
+    struct Counter(OwnedFd);
+
+    impl Counter {
+        fn open(config: u64) -> Option<Self> {
+            let attr = PerfEventAttr {
+                type_: PERF_TYPE_HARDWARE,
+                size: std::mem::size_of::<PerfEventAttr>() as u32,
+                config,
+                sample_period: 0,
+                sample_type: 0,
+                read_format: 0,
+                flags: FLAGS,
+                rest: [0; 8],
+            };
+
+            // this process, on any CPU, in no group
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_perf_event_open,
+                    &attr as *const PerfEventAttr,
+                    0,
+                    -1,
+                    -1,
+                    0,
+                )
+            };
+
+            match fd {
+                -1 => None,
+                fd => Some(Self(unsafe { OwnedFd::from_raw_fd(fd as RawFd) })),
+            }
+        }
+
+        fn ioctl(&self, request: u64) {
+            unsafe { libc::ioctl(self.0.as_raw_fd(), request as _, 0) };
+        }
+
+        fn read(&self) -> Option<u64> {
+            let mut count = 0u64;
+            let read = unsafe {
+                libc::read(
+                    self.0.as_raw_fd(),
+                    &mut count as *mut u64 as *mut libc::c_void,
+                    std::mem::size_of::<u64>(),
+                )
+            };
+
+            (read == std::mem::size_of::<u64>() as isize).then_some(count)
+        }
+    }
+
+    pub(super) struct Counters {
+        instructions: Option<Counter>,
+        cycles: Option<Counter>,
+    }
+
+    impl Counters {
+        pub(super) fn open() -> Self {
+            Self {
+                instructions: Counter::open(PERF_COUNT_HW_INSTRUCTIONS),
+                cycles: Counter::open(PERF_COUNT_HW_CPU_CYCLES),
+            }
+        }
+
+        fn each(&self) -> impl Iterator<Item = &Counter> {
+            self.instructions.iter().chain(self.cycles.iter())
+        }
+
+        pub(super) fn enable(&mut self) {
+            self.each()
+                .for_each(|counter| counter.ioctl(PERF_EVENT_IOC_ENABLE));
+        }
+
+        pub(super) fn disable(&mut self) {
+            self.each()
+                .for_each(|counter| counter.ioctl(PERF_EVENT_IOC_DISABLE));
+        }
+
+        /// Instructions and cycles
+        pub(super) fn read(&self) -> (Option<u64>, Option<u64>) {
+            (
+                self.instructions.as_ref().and_then(Counter::read),
+                self.cycles.as_ref().and_then(Counter::read),
+            )
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod perf {
+    pub(super) struct Counters;
+
+    impl Counters {
+        pub(super) fn open() -> Self {
+            Self
+        }
+
+        pub(super) fn enable(&mut self) {}
+
+        pub(super) fn disable(&mut self) {}
+
+        pub(super) fn read(&self) -> (Option<u64>, Option<u64>) {
+            (None, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn result(name: &str, median: f64) -> BenchResult {
+        BenchResult {
+            name: name.to_string(),
+            stats: Stats::from_samples(&[median], 1),
+            counters: Counters::default(),
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from_samples(&[4.0, 1.0, 3.0, 2.0], 10);
+
+        assert_eq!(stats.samples, 4);
+        assert_eq!(stats.iterations_per_sample, 10);
+        assert_eq!(stats.mean, 2.5);
+        assert_eq!(stats.median, 2.5);
+        assert_eq!(stats.min, 1.0);
+        assert!((stats.std_dev - 1.2910).abs() < 1e-4);
+
+        assert_eq!(Stats::from_samples(&[3.0, 1.0, 2.0], 1).median, 2.0);
+        assert_eq!(Stats::from_samples(&[5.0], 1).relative_error(), 0.0);
+    }
+
+    #[test]
+    fn baseline_round_trip() {
+        let results = [
+            result("Main.benchSort", 1234.5),
+            result("Dict.benchGet", 7.0),
+        ];
+        let rendered = Baseline::render(&results);
+
+        assert!(rendered.starts_with('#'));
+
+        let baseline = Baseline::parse(&rendered);
+
+        assert_eq!(baseline.median("Main.benchSort"), Some(1234.5));
+        assert_eq!(baseline.median("Dict.benchGet"), Some(7.0));
+        assert_eq!(baseline.median("Main.benchMissing"), None);
+    }
+
+    #[test]
+    fn baseline_skips_bad_lines() {
+        let baseline = Baseline::parse("Main.benchA\t10\nnot a benchmark\nMain.benchB\tfast\n");
+
+        assert_eq!(baseline.median("Main.benchA"), Some(10.0));
+        assert_eq!(baseline.median("Main.benchB"), None);
+    }
+
+    #[test]
+    fn changes() {
+        let steady = Stats::from_samples(&[100.0, 100.0, 100.0], 1);
+        assert_eq!(Change::between(101.0, &steady), Change::None);
+        assert_eq!(Change::between(125.0, &steady), Change::Faster(0.2));
+        assert_eq!(Change::between(50.0, &steady), Change::Slower(1.0));
+
+        // The same change is noise when the samples are all over the place
+        let noisy = Stats::from_samples(&[50.0, 100.0, 150.0], 1);
+        assert_eq!(Change::between(125.0, &noisy), Change::None);
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_ns(12.34), "12.3 ns");
+        assert_eq!(format_ns(1_500.0), "1.50 µs");
+        assert_eq!(format_ns(2_500_000.0), "2.50 ms");
+        assert_eq!(format_ns(3e9), "3.00 s");
+    }
+}
@@ -2,12 +2,8 @@
 #[cfg(not(windows))]
 use {
     roc_module::symbol::Interns,
-    roc_mono::{
-        ir::ProcLayout,
-        layout::{GlobalLayoutInterner, LayoutCache, LayoutInterner, Niche},
-    },
-    roc_parse::ast::Expr,
-    roc_repl_eval::{eval::jit_to_ast, ReplAppMemory},
+    roc_mono::layout::{GlobalLayoutInterner, LayoutCache, LayoutInterner},
+    roc_render_value::{format_value, ValueMemory, ValueRenderer},
     roc_target::Target,
     roc_types::subs::{Subs, Variable},
 };
@@ -15,11 +11,15 @@ use {
 #[cfg(not(windows))]
 mod app;
 #[cfg(not(windows))]
+pub mod bench;
+#[cfg(not(windows))]
 pub mod run;
 
 #[cfg(not(windows))]
-use app::{ExpectMemory, ExpectReplApp};
+use app::ExpectMemory;
 
+/// Reads the values of an expect's lookups, and formats them. Returns the offset just past
+/// them, along with the values and their type variables.
 #[cfg(not(windows))]
 #[allow(clippy::too_many_arguments)]
 pub fn get_values<'a>(
@@ -31,61 +31,35 @@ pub fn get_values<'a>(
     start: *const u8,
     start_offset: usize,
     number_of_lookups: usize,
-) -> (usize, Vec<Expr<'a>>, Vec<Variable>) {
+) -> (usize, Vec<&'a str>, Vec<Variable>) {
     let mut result = Vec::with_capacity(number_of_lookups);
     let mut result_vars = Vec::with_capacity(number_of_lookups);
 
-    let memory = ExpectMemory { start };
-
-    let app = ExpectReplApp {
-        memory: arena.alloc(memory),
-        offset: start_offset,
-    };
+    let memory: &ExpectMemory = arena.alloc(ExpectMemory { start });
+    let mut layout_cache = LayoutCache::new(layout_interner.fork(), target);
+    let mut renderer = ValueRenderer::new(arena, subs, interns, layout_interner.fork(), target);
 
-    let app = arena.alloc(app);
+    let mut offset = start_offset;
 
     for i in 0..number_of_lookups {
         let size_of_lookup_header = 8 /* pointer to value */ + 4 /* type variable */;
 
-        let start = app
-            .memory
-            .deref_usize(start_offset + i * size_of_lookup_header);
-        let variable = app.memory.deref_u32(
+        let start = memory.deref_usize(start_offset + i * size_of_lookup_header);
+        let variable = memory.deref_u32(
             start_offset + i * size_of_lookup_header + 8, /* skip the pointer */
         );
         let variable = unsafe { Variable::from_index(variable) };
 
-        app.offset = start;
-
-        // TODO: pass layout_cache to jit_to_ast directly
-        let mut layout_cache = LayoutCache::new(layout_interner.fork(), target);
         let layout = layout_cache.from_var(arena, variable, subs).unwrap();
+        let expr = renderer.render(memory, start, layout, variable);
 
-        let proc_layout = ProcLayout {
-            arguments: &[],
-            result: layout,
-            niche: Niche::NONE,
-        };
-
-        let expr = jit_to_ast(
-            arena,
-            app,
-            "expect_repl_main_fn",
-            proc_layout,
-            variable,
-            subs,
-            interns,
-            layout_interner.fork(),
-            target,
-        );
+        offset = start + layout_cache.interner.stack_size_and_alignment(layout).0 as usize;
 
-        app.offset += layout_cache.interner.stack_size_and_alignment(layout).0 as usize;
-
-        result.push(expr);
+        result.push(format_value(arena, &expr));
         result_vars.push(variable);
     }
 
-    (app.offset, result, result_vars)
+    (offset, result, result_vars)
 }
 
 #[cfg(not(windows))]
@@ -102,7 +76,9 @@ mod test {
 
     use crate::run::expect_mono_module_to_dylib;
 
-    fn run_expect_test(source: &str, expected: &str) {
+    /// Runs the expects in `source`, in this process, or in child processes when there are
+    /// `max_workers`. Returns the output without ANSI escape codes, and the failed and passed counts.
+    fn run_expects(source: &str, max_workers: Option<usize>) -> (String, usize, usize) {
         let arena = bumpalo::Bump::new();
         let arena = &arena;
 
@@ -147,9 +123,15 @@ mod test {
 
         let interns = loaded.interns.clone();
 
-        let (dy_lib, expects_by_module, layout_interner) =
-            expect_mono_module_to_dylib(arena, target, loaded, opt_level, LlvmBackendMode::CliTest)
-                .unwrap();
+        let (dy_lib, expects_by_module, layout_interner) = expect_mono_module_to_dylib(
+            arena,
+            target,
+            loaded,
+            opt_level,
+            LlvmBackendMode::CliTest,
+            None,
+        )
+        .unwrap();
 
         let arena = &bumpalo::Bump::new();
         let interns = arena.alloc(interns);
@@ -165,21 +147,41 @@ mod test {
         unsafe { set_shared_buffer((shared_buffer.as_mut_ptr(), BUFFER_SIZE), &mut result) };
 
         let mut writer = Vec::with_capacity(1024);
+        let mut failed = 0;
+        let mut passed = 0;
 
         let global_layout_interner = layout_interner.into_global();
         for (_, expect_funcs) in expects_by_module {
-            let (_failed, _passed) = crate::run::run_expects_with_memory(
-                &mut writer,
-                RenderTarget::ColorTerminal,
-                arena,
-                interns,
-                &global_layout_interner,
-                &dy_lib,
-                &mut expectations,
-                expect_funcs,
-                &mut memory,
-            )
+            let (module_failed, module_passed) = match max_workers {
+                None => crate::run::run_expects_with_memory(
+                    &mut writer,
+                    RenderTarget::ColorTerminal,
+                    arena,
+                    interns,
+                    &global_layout_interner,
+                    &dy_lib,
+                    &mut expectations,
+                    expect_funcs,
+                    &mut memory,
+                    roc_repl_eval::snapshot::SnapshotMode::Off,
+                ),
+                Some(max_workers) => crate::run::run_toplevel_expects(
+                    &mut writer,
+                    RenderTarget::ColorTerminal,
+                    arena,
+                    interns,
+                    &global_layout_interner,
+                    &dy_lib,
+                    &mut expectations,
+                    expect_funcs,
+                    roc_repl_eval::snapshot::SnapshotMode::Off,
+                    max_workers,
+                ),
+            }
             .unwrap();
+
+            failed += module_failed;
+            passed += module_passed;
         }
 
         // Remove ANSI escape codes from the answer - for example:
@@ -187,7 +189,12 @@ mod test {
         //     Before: "42 \u{1b}[35m:\u{1b}[0m Num *"
         //     After:  "42 : Num *"
         let bytes = strip_ansi_escapes::strip(writer).unwrap();
-        let actual = String::from_utf8(bytes).unwrap();
+
+        (String::from_utf8(bytes).unwrap(), failed, passed)
+    }
+
+    fn run_expect_test(source: &str, expected: &str) {
+        let (actual, _failed, _passed) = run_expects(source, None);
 
         if !actual.is_empty() {
             // trim off the first line; it contains a path in a tempdir that
@@ -244,6 +251,37 @@ mod test {
         );
     }
 
+    #[test]
+    fn parallel_expects_report_failures_in_order() {
+        let (actual, failed, passed) = run_expects(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = 0
+
+                expect 1 == 1
+
+                expect 1 == 2
+
+                expect 2 == 2
+
+                expect 2 == 3
+
+                expect 3 == 3
+                "#
+            ),
+            Some(4),
+        );
+
+        assert_eq!((failed, passed), (2, 3));
+
+        let first = actual.find("expect 1 == 2").unwrap();
+        let second = actual.find("expect 2 == 3").unwrap();
+        assert!(first < second, "{actual}");
+        assert!(!actual.contains("expect 1 == 1"), "{actual}");
+    }
+
     #[test]
     fn lookup_integer() {
         run_expect_test(
@@ -1,4 +1,5 @@
 use std::{
+    cell::RefCell,
     os::unix::process::parent_id,
     sync::{
         atomic::{AtomicBool, AtomicU32},
@@ -21,10 +22,12 @@ use roc_gen_llvm::{
 use roc_load::{Expectations, MonomorphizedModule};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::{
+    coverage::Coverage,
     ir::OptLevel,
-    layout::{GlobalLayoutInterner, STLayoutInterner},
+    layout::{GlobalLayoutInterner, InLayout, STLayoutInterner},
 };
 use roc_region::all::Region;
+use roc_repl_eval::snapshot::{check_snapshot, SnapshotMode};
 use roc_reporting::{error::expect::Renderer, report::RenderTarget};
 use roc_target::Target;
 use roc_types::subs::Subs;
@@ -39,7 +42,6 @@ pub struct ExpectMemory<'a> {
 impl<'a> ExpectMemory<'a> {
     const SHM_SIZE: usize = 1024;
 
-    #[cfg(test)]
     pub(crate) fn from_slice(slice: &mut [u8]) -> Self {
         Self {
             ptr: slice.as_mut_ptr(),
@@ -107,7 +109,7 @@ impl<'a> ExpectMemory<'a> {
         }
     }
 
-    fn set_shared_buffer(&mut self, lib: &libloading::Library) {
+    pub(crate) fn set_shared_buffer(&mut self, lib: &libloading::Library) {
         let set_shared_buffer = run_roc_dylib!(lib, "set_shared_buffer", (*mut u8, usize), ());
         let mut result = RocCallResult::default();
         unsafe { set_shared_buffer((self.ptr, self.length), &mut result) };
@@ -124,6 +126,58 @@ impl<'a> ExpectMemory<'a> {
     }
 }
 
+/// The counters of `roc test --coverage`. They are mapped shared, so that the child processes
+/// expects run in count into them as well.
+pub struct CoverageCounters {
+    ptr: *mut u64,
+    len: usize,
+}
+
+impl CoverageCounters {
+    /// Maps `len` counters at zero, and points the library's counters at them.
+    pub fn new(lib: &libloading::Library, len: usize) -> std::io::Result<Self> {
+        // A mapping can't be empty
+        let size = len.max(1) * std::mem::size_of::<u64>();
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_WRITE | libc::PROT_READ,
+                libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        let ptr = ptr.cast::<u64>();
+
+        // Without any counters, nothing refers to the global and it isn't there
+        if len > 0 {
+            let global = unsafe {
+                lib.get::<*mut *mut u64>(roc_gen_llvm::llvm::build::COVERAGE_COUNTERS.as_bytes())
+            }
+            .map_err(std::io::Error::other)?;
+            unsafe { **global = ptr };
+        }
+
+        Ok(Self { ptr, len })
+    }
+
+    pub fn counts(&self) -> &[u64] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl Drop for CoverageCounters {
+    fn drop(&mut self) {
+        let size = self.len.max(1) * std::mem::size_of::<u64>();
+        unsafe { libc::munmap(self.ptr.cast(), size) };
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn run_inline_expects<'a, W: std::io::Write>(
     writer: &mut W,
@@ -148,9 +202,13 @@ pub fn run_inline_expects<'a, W: std::io::Write>(
         expectations,
         expects,
         &mut memory,
+        SnapshotMode::Off,
     )
 }
 
+/// Runs the pure expects in up to `max_workers` child processes at once, and the ones with
+/// effects one at a time, since they report failures to this process while they run.
+/// Reports and `dbg` output are still written in source order.
 #[allow(clippy::too_many_arguments)]
 pub fn run_toplevel_expects<'a, W: std::io::Write>(
     writer: &mut W,
@@ -161,11 +219,35 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
+    snapshot_mode: SnapshotMode,
+    max_workers: usize,
 ) -> std::io::Result<(usize, usize)> {
     let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
     let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
 
-    run_expects_with_memory(
+    if max_workers <= 1 {
+        return run_expects_with_memory(
+            writer,
+            render_target,
+            arena,
+            interns,
+            layout_interner,
+            lib,
+            expectations,
+            expects,
+            &mut memory,
+            snapshot_mode,
+        );
+    }
+
+    let ExpectFunctions { pure, fx, benches } = expects;
+    let fx_only = ExpectFunctions {
+        pure: BumpVec::new_in(arena),
+        fx,
+        benches,
+    };
+
+    let (mut failed, mut passed) = run_expects_with_memory(
         writer,
         render_target,
         arena,
@@ -173,9 +255,170 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
         layout_interner,
         lib,
         expectations,
-        expects,
+        fx_only,
         &mut memory,
-    )
+        snapshot_mode,
+    )?;
+
+    let outcomes = run_pure_expects_in_children(writer, lib, &pure, max_workers)?;
+
+    for (expect, outcome) in pure.into_iter().zip(outcomes) {
+        let expect_passed = match outcome {
+            ChildOutcome::Passed(stderr) => {
+                std::io::Write::write_all(&mut std::io::stderr(), &stderr)?;
+
+                true
+            }
+            // Pure expects do the same thing every time, so running it again here gives
+            // the values to render
+            ChildOutcome::Failed => run_expect_pure(
+                writer,
+                render_target,
+                arena,
+                interns,
+                layout_interner,
+                lib,
+                expectations,
+                &mut memory,
+                snapshot_mode,
+                expect,
+            )?,
+            ChildOutcome::Crashed(signal) => {
+                let module_id = expect.symbol.module_id();
+                let data = expectations.get_mut(&module_id).unwrap();
+                let filename = data.path.to_owned();
+                let source = std::fs::read_to_string(&data.path).unwrap();
+
+                let renderer =
+                    Renderer::new(arena, interns, render_target, module_id, filename, &source);
+                let message = format!("The test process was killed by signal {signal}");
+                renderer.render_panic(writer, &message, expect.region)?;
+                writeln!(writer)?;
+
+                false
+            }
+        };
+
+        match expect_passed {
+            true => passed += 1,
+            false => failed += 1,
+        }
+    }
+
+    Ok((failed, passed))
+}
+
+/// How a pure top-level expect went in its child process
+enum ChildOutcome {
+    /// It passed, and wrote this to stderr, e.g. with `dbg`
+    Passed(Vec<u8>),
+    /// It failed or panicked
+    Failed,
+    /// The child was killed by this signal, e.g. because the expect overflowed the stack
+    Crashed(i32),
+}
+
+/// Runs each expect in its own child process, up to `max_workers` at a time. Each child has its
+/// own copy of the dylib's state, including the buffer failures are written to, so they can't
+/// get in each other's way, and an expect that crashes can't take the test runner down with it.
+fn run_pure_expects_in_children<W: std::io::Write>(
+    writer: &mut W,
+    lib: &libloading::Library,
+    expects: &[ToplevelExpect<'_>],
+    max_workers: usize,
+) -> std::io::Result<Vec<ChildOutcome>> {
+    use std::io::{Read, Seek, SeekFrom, Write};
+    use std::os::fd::AsRawFd;
+
+    // Otherwise the children would print whatever is still buffered again
+    writer.flush()?;
+    std::io::stdout().flush()?;
+    std::io::stderr().flush()?;
+
+    let mut outcomes: Vec<Option<ChildOutcome>> = expects.iter().map(|_| None).collect();
+    // The pid, expect index and captured stderr of each running child
+    let mut running: Vec<(libc::pid_t, usize, std::fs::File)> = Vec::new();
+    let mut next = 0;
+
+    loop {
+        while running.len() < max_workers && next < expects.len() {
+            // Unlinked right away, so it is gone once the file is closed
+            let path = std::env::temp_dir()
+                .join(format!("roc_expect_stderr_{}_{next}", std::process::id()));
+            let stderr = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)?;
+            std::fs::remove_file(&path)?;
+
+            match unsafe { libc::fork() } {
+                0 => unsafe {
+                    // we are the child
+                    libc::dup2(stderr.as_raw_fd(), libc::STDERR_FILENO);
+
+                    // Skip the parent's exit handlers
+                    libc::_exit(run_expect_in_child(lib, expects[next]))
+                },
+                -1 => return Err(std::io::Error::last_os_error()),
+                pid => running.push((pid, next, stderr)),
+            }
+
+            next += 1;
+        }
+
+        if running.is_empty() {
+            break;
+        }
+
+        let mut status = 0;
+        let pid = unsafe { libc::waitpid(-1, &mut status, 0) };
+        if pid == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let Some(position) = running.iter().position(|(child, _, _)| *child == pid) else {
+            continue;
+        };
+        let (_, index, mut stderr) = running.swap_remove(position);
+
+        let outcome = if libc::WIFSIGNALED(status) {
+            ChildOutcome::Crashed(libc::WTERMSIG(status))
+        } else if libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0 {
+            let mut output = Vec::new();
+            stderr.seek(SeekFrom::Start(0))?;
+            stderr.read_to_end(&mut output)?;
+
+            ChildOutcome::Passed(output)
+        } else {
+            ChildOutcome::Failed
+        };
+
+        outcomes[index] = Some(outcome);
+    }
+
+    Ok(outcomes.into_iter().map(Option::unwrap).collect())
+}
+
+/// Runs a pure expect in a child process, and returns the exit code: 0 if it passed.
+fn run_expect_in_child(lib: &libloading::Library, expect: ToplevelExpect<'_>) -> i32 {
+    use roc_gen_llvm::try_run_jit_function;
+
+    // On the stack, because other threads of the parent may have held the allocator's lock
+    // when it forked
+    let mut buffer = [0u8; ExpectMemory::SHM_SIZE];
+    let mut memory = ExpectMemory::from_slice(&mut buffer);
+    memory.set_shared_buffer(lib);
+
+    let sequence = ExpectSequence::new(memory.ptr);
+
+    let result: Result<(), (String, _)> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+
+    match result {
+        Ok(()) if sequence.count_failures() == 0 => 0,
+        _ => 1,
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -189,6 +432,7 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
     memory: &mut ExpectMemory,
+    snapshot_mode: SnapshotMode,
 ) -> std::io::Result<(usize, usize)> {
     let mut failed = 0;
     let mut passed = 0;
@@ -203,6 +447,7 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             lib,
             expectations,
             memory,
+            snapshot_mode,
             expect,
         )?;
 
@@ -224,6 +469,7 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             lib,
             expectations,
             memory,
+            snapshot_mode,
             expect,
         )?;
 
@@ -246,6 +492,7 @@ fn run_expect_pure<'a, W: std::io::Write>(
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
     shared_memory: &mut ExpectMemory,
+    snapshot_mode: SnapshotMode,
     expect: ToplevelExpect<'_>,
 ) -> std::io::Result<bool> {
     use roc_gen_llvm::try_run_jit_function;
@@ -266,13 +513,18 @@ fn run_expect_pure<'a, W: std::io::Write>(
 
         let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
 
+        // Failures whose values match their snapshots don't count
+        let mut all_snapshotted = result.is_ok();
+
         if let Err((roc_panic_message, _roc_panic_tag)) = result {
             renderer.render_panic(writer, &roc_panic_message, expect.region)?;
         } else {
             let mut offset = ExpectSequence::START_OFFSET;
 
             for _ in 0..sequence.count_failures() {
-                offset = render_expect_failure(
+                let snapshotted;
+
+                (offset, snapshotted) = render_expect_failure(
                     writer,
                     &renderer,
                     arena,
@@ -280,12 +532,19 @@ fn run_expect_pure<'a, W: std::io::Write>(
                     expectations,
                     interns,
                     layout_interner,
+                    snapshot_mode,
                     shared_memory_ptr,
                     offset,
                 )?;
+
+                all_snapshotted &= snapshotted;
             }
         }
 
+        if all_snapshotted {
+            return Ok(true);
+        }
+
         writeln!(writer)?;
 
         Ok(false)
@@ -304,6 +563,7 @@ fn run_expect_fx<'a, W: std::io::Write>(
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
     parent_memory: &mut ExpectMemory,
+    snapshot_mode: SnapshotMode,
     expect: ToplevelExpect<'_>,
 ) -> std::io::Result<bool> {
     use signal_hook::{consts::signal::SIGCHLD, consts::signal::SIGUSR1, iterator::Signals};
@@ -354,8 +614,6 @@ fn run_expect_fx<'a, W: std::io::Write>(
                     }
                     SIGUSR1 => {
                         // this is the signal we use for an expect failure. Let's see what the child told us
-                        has_succeeded = false;
-
                         let frame =
                             ExpectFrame::at_offset(parent_memory.ptr, ExpectSequence::START_OFFSET);
                         let module_id = frame.module_id;
@@ -373,7 +631,7 @@ fn run_expect_fx<'a, W: std::io::Write>(
                             &source,
                         );
 
-                        render_expect_failure(
+                        let (_, snapshotted) = render_expect_failure(
                             writer,
                             &renderer,
                             arena,
@@ -381,9 +639,12 @@ fn run_expect_fx<'a, W: std::io::Write>(
                             expectations,
                             interns,
                             layout_interner,
+                            snapshot_mode,
                             parent_memory.ptr,
                             ExpectSequence::START_OFFSET,
                         )?;
+
+                        has_succeeded &= snapshotted;
                     }
                     _ => println!("received signal {sig}"),
                 }
@@ -421,7 +682,7 @@ pub fn render_expects_in_memory<'a>(
         &source,
     );
 
-    render_expect_failure(
+    let (offset, _) = render_expect_failure(
         writer,
         &renderer,
         arena,
@@ -429,9 +690,12 @@ pub fn render_expects_in_memory<'a>(
         expectations,
         interns,
         layout_interner,
+        SnapshotMode::Off,
         shared_ptr,
         ExpectSequence::START_OFFSET,
-    )
+    )?;
+
+    Ok(offset)
 }
 
 fn split_expect_lookups(subs: &Subs, lookups: &[ExpectLookup]) -> Vec<Symbol> {
@@ -455,6 +719,8 @@ fn split_expect_lookups(subs: &Subs, lookups: &[ExpectLookup]) -> Vec<Symbol> {
         .collect()
 }
 
+/// Renders the failed expect at `offset`, unless its values match its snapshot.
+/// Returns the offset of the next one, and whether the values matched.
 #[allow(clippy::too_many_arguments)]
 fn render_expect_failure<'a>(
     writer: &mut impl std::io::Write,
@@ -464,9 +730,10 @@ fn render_expect_failure<'a>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     interns: &'a Interns,
     layout_interner: &GlobalLayoutInterner<'a>,
+    snapshot_mode: SnapshotMode,
     start: *const u8,
     offset: usize,
-) -> std::io::Result<usize> {
+) -> std::io::Result<(usize, bool)> {
     // we always run programs as the host
     let target = target_lexicon::Triple::host().into();
 
@@ -485,7 +752,7 @@ fn render_expect_failure<'a>(
 
     let symbols = split_expect_lookups(&data.subs, current);
 
-    let (offset, expressions, variables) = crate::get_values(
+    let (offset, values, variables) = crate::get_values(
         target,
         arena,
         &data.subs,
@@ -496,17 +763,28 @@ fn render_expect_failure<'a>(
         symbols.len(),
     );
 
-    renderer.render_failure(
-        writer,
-        &mut data.subs,
-        &symbols,
-        &variables,
-        &expressions,
-        expect_region,
-        failure_region,
+    let snapshot = check_snapshot(
+        snapshot_mode,
+        &data.path,
+        renderer.source_text(failure_region),
+        &renderer.render_snapshot(&symbols, &values),
     )?;
 
-    Ok(offset)
+    if !snapshot.passed() {
+        renderer.render_failure(
+            writer,
+            &mut data.subs,
+            &symbols,
+            &variables,
+            &values,
+            expect_region,
+            failure_region,
+        )?;
+    }
+
+    snapshot.write_note(writer)?;
+
+    Ok((offset, snapshot.passed()))
 }
 
 struct ExpectSequence {
@@ -608,6 +886,16 @@ pub struct ToplevelExpect<'a> {
 pub struct ExpectFunctions<'a> {
     pub pure: BumpVec<'a, ToplevelExpect<'a>>,
     pub fx: BumpVec<'a, ToplevelExpect<'a>>,
+    pub benches: BumpVec<'a, ToplevelBench<'a>>,
+}
+
+#[derive(Debug)]
+pub struct ToplevelBench<'a> {
+    pub name: &'a str,
+    pub symbol: Symbol,
+    pub region: Region,
+    /// The layout of the value the benchmark computes
+    pub result_layout: InLayout<'a>,
 }
 
 pub fn expect_mono_module_to_dylib<'a>(
@@ -616,6 +904,7 @@ pub fn expect_mono_module_to_dylib<'a>(
     loaded: MonomorphizedModule<'a>,
     opt_level: OptLevel,
     mode: LlvmBackendMode,
+    coverage: Option<&RefCell<Coverage>>,
 ) -> Result<
     (
         libloading::Library,
@@ -657,6 +946,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        coverage,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -673,13 +963,25 @@ pub fn expect_mono_module_to_dylib<'a>(
                         .pure
                         .keys()
                         .copied()
-                        .chain(expects.fx.keys().copied()),
+                        .chain(expects.fx.keys().copied())
+                        .chain(expects.benches.keys().copied()),
                     env.arena,
                 ),
             )
         })
         .collect();
 
+    let bench_layouts: MutMap<Symbol, InLayout<'a>> = procedures
+        .keys()
+        .filter(|(symbol, layout)| {
+            layout.arguments.is_empty()
+                && toplevel_expects
+                    .get(&symbol.module_id())
+                    .map_or(false, |expects| expects.benches.contains_key(symbol))
+        })
+        .map(|(symbol, layout)| (*symbol, layout.result))
+        .collect();
+
     let expect_names = roc_gen_llvm::llvm::build::build_procedures_expose_expects(
         &env,
         &layout_interner,
@@ -692,6 +994,7 @@ pub fn expect_mono_module_to_dylib<'a>(
 
     for (module_id, expects) in toplevel_expects.into_iter() {
         let expect_names = expect_names.get(&module_id).unwrap();
+        let benches_start = expects.pure.len() + expects.fx.len();
 
         let expects_fx = bumpalo::collections::Vec::from_iter_in(
             expects
@@ -718,9 +1021,24 @@ pub fn expect_mono_module_to_dylib<'a>(
                 env.arena,
             );
 
+        let benches = bumpalo::collections::Vec::from_iter_in(
+            expects
+                .benches
+                .into_iter()
+                .zip(expect_names.iter().skip(benches_start))
+                .map(|((symbol, region), name)| ToplevelBench {
+                    symbol,
+                    region,
+                    name,
+                    result_layout: bench_layouts[&symbol],
+                }),
+            env.arena,
+        );
+
         let expect_funs = ExpectFunctions {
             pure: expects_pure,
             fx: expects_fx,
+            benches,
         };
 
         modules_expects.insert(module_id, expect_funs);
@@ -40,9 +40,15 @@ fn path_to_roc_binary() -> PathBuf {
 }
 
 pub fn repl_eval(input: &str) -> Out {
+    repl_eval_with_args(&[], input)
+}
+
+/// Like [repl_eval], with extra arguments to `roc repl`
+pub fn repl_eval_with_args(args: &[&str], input: &str) -> Out {
     let mut cmd = Command::new(path_to_roc_binary());
 
     cmd.arg("repl");
+    cmd.args(args);
 
     let mut child = cmd
         .stdin(Stdio::piped())
@@ -122,7 +128,12 @@ pub fn repl_eval(input: &str) -> Out {
 }
 
 pub fn expect_success(input: &str, expected: &str) {
-    let out = repl_eval(input.trim());
+    expect_success_with_args(&[], input, expected)
+}
+
+/// Like [expect_success], with extra arguments to `roc repl`
+pub fn expect_success_with_args(args: &[&str], input: &str, expected: &str) {
+    let out = repl_eval_with_args(args, input.trim());
 
     assert_multiline_str_eq!("", out.stderr.as_str());
 
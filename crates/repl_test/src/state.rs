@@ -1,9 +1,9 @@
 use bumpalo::Bump;
 use indoc::indoc;
-use roc_repl_cli::{evaluate, ReplHelper};
-use roc_repl_ui::is_incomplete;
+use roc_repl_cli::{evaluate, evaluate_interp, evaluate_wasm, ReplBackend, ReplHelper};
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
-use roc_reporting::report::DEFAULT_PALETTE;
+use roc_repl_ui::{format_output, is_incomplete};
+use roc_reporting::report::{ANSI_STYLE_CODES, DEFAULT_PALETTE};
 use rustyline::Editor;
 use target_lexicon::Triple;
 
@@ -61,7 +61,7 @@ fn exhaustiveness_problem() {
 
         let expected_error: &str = indoc!(
             r#"
-            ── UNSAFE PATTERN ──────────────────────────────────────────────────────────────
+            ── UNSAFE PATTERN [E006] ───────────────────────────────────────────────────────
 
             This when does not cover all the possibilities:
 
@@ -107,7 +107,7 @@ fn partial_record_definition() {
         input.push('\n');
         let expected_error: &str = indoc!(
             r#"
-            ── RECORD PARSE PROBLEM ────────────────────────────────────────────────────────
+            ── RECORD PARSE PROBLEM [E216] ─────────────────────────────────────────────────
 
             I am partway through parsing a record, but I got stuck here:
 
@@ -145,12 +145,183 @@ fn standalone_annotation() {
     assert!(matches!(action, ReplAction::Nothing));
 }
 
+#[test]
+fn open_brackets_and_blocks() {
+    assert!(is_incomplete("[1, 2,"));
+    assert!(is_incomplete("f = \\x ->"));
+    assert!(is_incomplete("f = \\x ->\n    y = x + 1"));
+    assert!(!is_incomplete("[1, 2,\n3]"));
+    assert!(!is_incomplete("\"([{\""));
+    assert!(!is_incomplete("'('"));
+    assert!(!is_incomplete("x = 1 # ["));
+}
+
+#[test]
+fn type_command() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    type_of(":type x + 1", &mut state, "x + 1 : Num *");
+    type_of(
+        ":t \\y -> Str.concat y \"!\"",
+        &mut state,
+        "\\y -> Str.concat y \"!\" : Str -> Str",
+    );
+}
+
+#[test]
+fn reset_command() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let action = state.step(&arena, ":reset", target, DEFAULT_PALETTE);
+    assert!(matches!(action, ReplAction::Nothing));
+
+    match state.step(&arena, ":type x", target, DEFAULT_PALETTE) {
+        ReplAction::Type { problems, .. } => {
+            assert!(problems.errors[0].contains("UNRECOGNIZED NAME"));
+        }
+        action => panic!("Unexpected action: {:?}", action),
+    }
+}
+
+#[test]
+fn load_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let filename = dir.path().join("Helpers.roc");
+
+    std::fs::write(
+        &filename,
+        indoc!(
+            r#"
+            module [double]
+
+            double : I64 -> I64
+            double = \n -> n * 2
+
+            expect double 2 == 4
+            "#
+        ),
+    )
+    .unwrap();
+
+    let mut state = ReplState::new();
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let line = format!(":load {}", filename.display());
+
+    match state.step(&arena, &line, target, DEFAULT_PALETTE) {
+        ReplAction::Load {
+            def_count,
+            problems,
+            ..
+        } => {
+            assert_eq!(def_count, 1);
+            assert!(problems.is_empty());
+        }
+        action => panic!("Unexpected action: {:?}", action),
+    }
+
+    complete("double 21", &mut state, "42 : I64");
+}
+
+#[test]
+fn load_command_parse_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let filename = dir.path().join("Broken.roc");
+
+    std::fs::write(
+        &filename,
+        indoc!(
+            r#"
+            module [double]
+
+            double : I64 -> I64
+            double = \n ->
+            "#
+        ),
+    )
+    .unwrap();
+
+    let mut state = ReplState::new();
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let line = format!(":load {}", filename.display());
+
+    match state.step(&arena, &line, target, DEFAULT_PALETTE) {
+        ReplAction::Load {
+            def_count,
+            problems,
+            ..
+        } => {
+            assert_eq!(def_count, 0);
+            assert_eq!(problems.errors.len(), 1);
+        }
+        action => panic!("Unexpected action: {:?}", action),
+    }
+
+    // Nothing from the broken file was brought in, and the session keeps going.
+    match state.step(&arena, ":type double", target, DEFAULT_PALETTE) {
+        ReplAction::Type { problems, .. } => {
+            assert!(problems.errors[0].contains("UNRECOGNIZED NAME"));
+        }
+        action => panic!("Unexpected action: {:?}", action),
+    }
+}
+
+#[test]
+fn wasm_backend() {
+    let mut state = ReplState::new();
+
+    complete_with(ReplBackend::Wasm, "x = 5", &mut state, "5 : Num *");
+    complete_with(ReplBackend::Wasm, "x + 1.5", &mut state, "6.5 : Frac *");
+    complete_with(
+        ReplBackend::Wasm,
+        "Str.concat \"Hello, \" \"World! Nice to see you again.\"",
+        &mut state,
+        "\"Hello, World! Nice to see you again.\" : Str",
+    );
+    complete_with(
+        ReplBackend::Wasm,
+        "[1, 2, 3]",
+        &mut state,
+        "[1, 2, 3] : List (Num *)",
+    );
+}
+
+#[test]
+fn interp_backend() {
+    let mut state = ReplState::new();
+
+    complete_with(ReplBackend::Interp, "x = 5", &mut state, "5 : Num *");
+    complete_with(ReplBackend::Interp, "x + 1.5", &mut state, "6.5 : Frac *");
+    complete_with(
+        ReplBackend::Interp,
+        "\"Hello\" |> Str.concat \", World!\"",
+        &mut state,
+        "\"Hello, World!\" : Str",
+    );
+    complete_with(
+        ReplBackend::Interp,
+        "List.map [1, 2, 3] \\n -> n * 2",
+        &mut state,
+        "[2, 4, 6] : List (Num *)",
+    );
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_start: &str) {
+    complete_with(ReplBackend::Native, input, state, expected_start)
+}
+
+fn complete_with(backend: ReplBackend, input: &str, state: &mut ReplState, expected_start: &str) {
     assert!(!is_incomplete(input));
     let arena = Bump::new();
-    let target = Triple::host().into();
+    let target = backend.target();
     let action = state.step(&arena, input, target, DEFAULT_PALETTE);
     let repl_helper = ReplHelper::default();
     let mut editor = Editor::<ReplHelper>::new();
@@ -158,7 +329,11 @@ fn complete(input: &str, state: &mut ReplState, expected_start: &str) {
 
     match action {
         ReplAction::Eval { opt_mono, problems } => {
-            let string = evaluate(opt_mono, problems, target);
+            let string = match backend {
+                ReplBackend::Native => evaluate(opt_mono, problems, target),
+                ReplBackend::Wasm => evaluate_wasm(&arena, opt_mono, problems),
+                ReplBackend::Interp => evaluate_interp(opt_mono, problems, target),
+            };
             let escaped =
                 std::string::String::from_utf8(strip_ansi_escapes::strip(string.trim()).unwrap())
                     .unwrap();
@@ -205,3 +380,27 @@ fn error(input: &str, state: &mut ReplState, expected_step_result: String) {
         }
     }
 }
+
+/// step the given `:type` command, then check the output with ANSI escape codes stripped.
+fn type_of(input: &str, state: &mut ReplState, expected: &str) {
+    assert!(!is_incomplete(input));
+    let arena = Bump::new();
+    let target = Triple::host().into();
+
+    match state.step(&arena, input, target, DEFAULT_PALETTE) {
+        ReplAction::Type {
+            opt_output,
+            problems,
+        } => {
+            let string = format_output(ANSI_STYLE_CODES, opt_output, problems);
+            let escaped =
+                std::string::String::from_utf8(strip_ansi_escapes::strip(string.trim()).unwrap())
+                    .unwrap();
+
+            assert_eq!(expected, escaped);
+        }
+        action => {
+            panic!("Unexpected action: {:?}", action);
+        }
+    }
+}
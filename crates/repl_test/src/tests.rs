@@ -598,6 +598,25 @@ fn multiline_string_non_wasm() {
     assert!(out.status.success());
 }
 
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn wasm_flag() {
+    use crate::cli::expect_success_with_args;
+
+    expect_success_with_args(&["--wasm"], "1 + 2", "3 : Num *");
+    expect_success_with_args(
+        &["--wasm"],
+        r#"Str.concat "Hello, " "World! Nice to see you again.""#,
+        r#""Hello, World! Nice to see you again." : Str"#,
+    );
+    expect_success_with_args(&["--wasm"], "[1, 2, 3]", "[1, 2, 3] : List (Num *)");
+    expect_success_with_args(
+        &["--wasm"],
+        "{ x: Foo 1 2 3, y : 4 }",
+        "{ x: Foo 1 2 3, y: 4 } : { x : [Foo (Num *) (Num *) (Num *)], y : Num * }",
+    );
+}
+
 #[cfg(feature = "wasm")]
 #[test]
 fn multiline_string_wasm() {
@@ -648,7 +667,7 @@ fn too_few_args() {
         "Num.add 2",
         indoc!(
             r"
-                ── TOO FEW ARGS ────────────────────────────────────────────────────────────────
+                ── TOO FEW ARGS [E005] ─────────────────────────────────────────────────────────
 
                 The add function expects 2 arguments, but it got only 1:
 
@@ -669,7 +688,7 @@ fn type_problem_function() {
         "Num.add 1 \"not a num\"",
         indoc!(
             r#"
-                ── TYPE MISMATCH ───────────────────────────────────────────────────────────────
+                ── TYPE MISMATCH [E001] ────────────────────────────────────────────────────────
 
                 This 2nd argument to add has an unexpected type:
 
@@ -695,7 +714,7 @@ fn type_problem_binary_operator() {
         "1 + \"\"",
         indoc!(
             r#"
-                ── TYPE MISMATCH ───────────────────────────────────────────────────────────────
+                ── TYPE MISMATCH [E001] ────────────────────────────────────────────────────────
 
                 This 2nd argument to + has an unexpected type:
 
@@ -721,7 +740,7 @@ fn type_problem_unary_operator() {
         "!\"not a bool\"",
         indoc!(
             r#"
-                ── TYPE MISMATCH ───────────────────────────────────────────────────────────────
+                ── TYPE MISMATCH [E001] ────────────────────────────────────────────────────────
 
                 This 1st argument to ! has an unexpected type:
 
@@ -747,7 +766,7 @@ fn type_problem_string_interpolation() {
         "\"This is not a string -> $(1)\"",
         indoc!(
             r#"
-                ── TYPE MISMATCH ───────────────────────────────────────────────────────────────
+                ── TYPE MISMATCH [E001] ────────────────────────────────────────────────────────
 
                 This argument to this string interpolation has an unexpected type:
 
@@ -773,7 +792,7 @@ fn list_drop_at_negative_index() {
         "List.dropAt [1, 2, 3] -1",
         indoc!(
             r#"
-            ── TYPE MISMATCH ───────────────────────────────────────────────────────────────
+            ── TYPE MISMATCH [E001] ────────────────────────────────────────────────────────
 
             This 2nd argument to dropAt has an unexpected type:
 
@@ -799,7 +818,7 @@ fn list_get_negative_index() {
         "List.get [1, 2, 3] -1",
         indoc!(
             r#"
-            ── TYPE MISMATCH ───────────────────────────────────────────────────────────────
+            ── TYPE MISMATCH [E001] ────────────────────────────────────────────────────────
 
             This 2nd argument to get has an unexpected type:
 
@@ -825,7 +844,7 @@ fn invalid_string_interpolation() {
         "\"$(123)\"",
         indoc!(
             r#"
-            ── TYPE MISMATCH ───────────────────────────────────────────────────────────────
+            ── TYPE MISMATCH [E001] ────────────────────────────────────────────────────────
 
             This argument to this string interpolation has an unexpected type:
 
@@ -1123,7 +1142,7 @@ fn parse_problem() {
         "add m n = m + n",
         indoc!(
             r#"
-                ── ARGUMENTS BEFORE EQUALS ─────────────────────────────────────────────────────
+                ── ARGUMENTS BEFORE EQUALS [E204] ──────────────────────────────────────────────
 
                 I am partway through parsing a definition, but I got stuck here:
 
@@ -1157,7 +1176,7 @@ fn issue_2343_complete_mono_with_shadowed_vars() {
         ),
         indoc!(
             r"
-                ── DUPLICATE NAME ──────────────────────────────────────────────────────────────
+                ── DUPLICATE NAME [E118] ───────────────────────────────────────────────────────
 
                 The b name is first defined here:
 
@@ -1568,7 +1587,7 @@ fn interpolation_with_nested_interpolation() {
         ),
         indoc!(
             r#"
-                ── SYNTAX PROBLEM ──────────────────────────────────────────────────────────────
+                ── SYNTAX PROBLEM [E201] ───────────────────────────────────────────────────────
 
                 This string interpolation is invalid:
 
@@ -1584,6 +1603,9 @@ fn interpolation_with_nested_interpolation() {
                 Enter an expression to evaluate, or a definition (like x = 1) to use later.
 
                   - ctrl-v + ctrl-j makes a newline
+                  - :type <expr> shows the type of an expression without running it
+                  - :load <file.roc> brings the definitions in a module into scope
+                  - :reset forgets all definitions
                   - :q quits
                   - :help shows this text again
             "#
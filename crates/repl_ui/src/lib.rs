@@ -38,6 +38,27 @@ pub const TIPS: &str = concatcp!(
             "  - ",
             END_COL,
             GREEN,
+            ":type <expr>",
+            END_COL,
+            " shows the type of an expression without running it\n",
+            CYAN,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":load <file.roc>",
+            END_COL,
+            " brings the definitions in a module into scope\n",
+            CYAN,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":reset",
+            END_COL,
+            " forgets all definitions\n",
+            CYAN,
+            "  - ",
+            END_COL,
+            GREEN,
             ":q",
             END_COL,
             " quits\n",
@@ -59,6 +80,11 @@ pub const PROMPT: &str = concatcp!(CYAN, "»", END_COL, " ");
 pub const CONT_PROMPT: &str = concatcp!(CYAN, "…", END_COL, " ");
 
 pub fn is_incomplete(input: &str) -> bool {
+    // Pressing Enter on a blank line always submits the input.
+    if !input.ends_with('\n') && (has_open_brackets(input) || has_open_block(input)) {
+        return true;
+    }
+
     let arena = Bump::new();
 
     match parse_src(&arena, input) {
@@ -84,10 +110,68 @@ pub fn is_incomplete(input: &str) -> bool {
                 false
             }
         }
-        ParseOutcome::Empty | ParseOutcome::Help | ParseOutcome::Exit | ParseOutcome::SyntaxErr => {
-            false
+        ParseOutcome::Type(expr) => is_incomplete(expr),
+        ParseOutcome::Empty
+        | ParseOutcome::Help
+        | ParseOutcome::Exit
+        | ParseOutcome::Reset
+        | ParseOutcome::Load(_)
+        | ParseOutcome::SyntaxErr => false,
+    }
+}
+
+/// Whether the input has more opening brackets than closing ones, outside of strings
+/// and comments.
+fn has_open_brackets(input: &str) -> bool {
+    let mut depth: usize = 0;
+    let mut in_str = false;
+    let mut chars = input.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if in_str => {
+                chars.next();
+            }
+            '"' => in_str = !in_str,
+            '\'' if !in_str => {
+                // Skip over a character literal, which might be a bracket or a quote.
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '\'' | '\n' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '#' if !in_str => {
+                // Skip to the end of the line.
+                chars.by_ref().find(|&ch| ch == '\n');
+            }
+            '(' | '[' | '{' if !in_str => depth += 1,
+            ')' | ']' | '}' if !in_str => depth = depth.saturating_sub(1),
+            _ => {}
         }
     }
+
+    depth > 0 || in_str
+}
+
+/// Whether the last line ends in something that needs an indented block after it, like
+/// `=` or `->`, or is itself part of an indented block.
+fn has_open_block(input: &str) -> bool {
+    let Some(last_line) = input.lines().last() else {
+        return false;
+    };
+
+    let continues_block = input.contains('\n') && last_line.starts_with(char::is_whitespace);
+    let opens_block = matches!(
+        last_line.split_whitespace().last(),
+        Some("=" | "->" | "<-" | "|>" | "then" | "else" | "is")
+    );
+
+    continues_block || opens_block
 }
 
 pub fn format_output(
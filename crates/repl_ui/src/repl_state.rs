@@ -6,11 +6,12 @@ use roc_collections::MutSet;
 use roc_load::MonomorphizedModule;
 use roc_parse::ast::{Defs, Expr, Pattern, StrLiteral, TypeDef, TypeHeader, ValueDef};
 use roc_parse::expr::parse_repl_defs_and_optional_expr;
+use roc_parse::module::{parse_header, parse_module_defs};
 use roc_parse::parser::EWhen;
 use roc_parse::parser::{EClosure, EExpr, EPattern};
 use roc_parse::state::State;
 use roc_region::all::Loc;
-use roc_repl_eval::gen::{compile_to_mono, Problems};
+use roc_repl_eval::gen::{check_file, compile_to_mono, type_of_expr, Problems, ReplOutput};
 use roc_reporting::report::Palette;
 use roc_target::Target;
 
@@ -40,6 +41,17 @@ pub enum ReplAction<'a> {
     },
     Exit,
     Help,
+    /// The result of `:type`. Nothing was evaluated.
+    Type {
+        opt_output: Option<ReplOutput>,
+        problems: Problems,
+    },
+    /// The result of `:load`. If there were errors, nothing was loaded.
+    Load {
+        filename: PathBuf,
+        def_count: usize,
+        problems: Problems,
+    },
     FileProblem {
         filename: PathBuf,
         error: io::ErrorKind,
@@ -66,6 +78,24 @@ impl ReplState {
         let src: &str = match parse_src(arena, line) {
             ParseOutcome::Empty | ParseOutcome::Help => return ReplAction::Help,
             ParseOutcome::Exit => return ReplAction::Exit,
+            ParseOutcome::Reset => {
+                self.past_defs.clear();
+                self.past_def_idents.clear();
+
+                return ReplAction::Nothing;
+            }
+            ParseOutcome::Type(expr) => {
+                let (opt_output, problems) =
+                    type_of_expr(arena, self.past_srcs(), expr, target, palette);
+
+                return ReplAction::Type {
+                    opt_output,
+                    problems,
+                };
+            }
+            ParseOutcome::Load(filename) => {
+                return self.load(arena, PathBuf::from(filename), target, palette);
+            }
             ParseOutcome::Incomplete | ParseOutcome::SyntaxErr => {
                 pending_past_def = None;
 
@@ -259,16 +289,7 @@ impl ReplState {
             }
         };
 
-        let (opt_mono, problems) = compile_to_mono(
-            arena,
-            self.past_defs.iter().map(|past_def| match past_def {
-                PastDef::Def { ident: _, src } => src.as_str(),
-                PastDef::Import(src) => src.as_str(),
-            }),
-            src,
-            target,
-            palette,
-        );
+        let (opt_mono, problems) = compile_to_mono(arena, self.past_srcs(), src, target, palette);
 
         if let Some((ident, src)) = pending_past_def {
             self.add_past_def(ident, src);
@@ -277,6 +298,119 @@ impl ReplState {
         ReplAction::Eval { opt_mono, problems }
     }
 
+    /// Brings the top-level defs and imports of a module file into scope, replacing any past
+    /// defs with the same names. The file goes through the regular load pipeline first, and
+    /// nothing is brought in if it has errors.
+    fn load<'a>(
+        &mut self,
+        arena: &'a Bump,
+        filename: PathBuf,
+        target: Target,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        let src = match fs::read_to_string(&filename) {
+            Ok(src) => arena.alloc_str(&src),
+            Err(err) => {
+                return ReplAction::FileProblem {
+                    filename,
+                    error: err.kind(),
+                }
+            }
+        };
+
+        let problems = check_file(arena, filename.clone(), target, palette);
+
+        if !problems.errors.is_empty() {
+            return ReplAction::Load {
+                filename,
+                def_count: 0,
+                problems,
+            };
+        }
+
+        // The file type-checked, so it parses too.
+        let (_, state) = parse_header(arena, State::new(src.as_bytes()))
+            .expect("a module that type-checked has a valid header");
+        let defs = parse_module_defs(arena, state, Defs::default())
+            .expect("a module that type-checked has valid defs");
+
+        let mut def_count = 0;
+
+        for def in defs.loc_defs() {
+            let (opt_ident, def_src) = match def {
+                Ok(td) => {
+                    let (TypeDef::Alias { header, .. }
+                    | TypeDef::Opaque { header, .. }
+                    | TypeDef::Ability { header, .. }) = td.value;
+
+                    (Some(header.name.value), &src[td.byte_range()])
+                }
+                Err(vd) => match vd.value {
+                    ValueDef::Annotation(
+                        Loc {
+                            value: Pattern::Identifier { ident },
+                            ..
+                        },
+                        _,
+                    ) => (Some(ident), &src[vd.byte_range()]),
+                    ValueDef::Body(
+                        Loc {
+                            value: Pattern::Identifier { ident },
+                            ..
+                        },
+                        _,
+                    )
+                    | ValueDef::AnnotatedBody {
+                        body_pattern:
+                            Loc {
+                                value: Pattern::Identifier { ident },
+                                ..
+                            },
+                        ..
+                    } => (Some(*ident), &src[vd.byte_range()]),
+                    ValueDef::ModuleImport(_) | ValueDef::IngestedFileImport(_) => {
+                        (None, &src[vd.byte_range()])
+                    }
+                    // Top-level expects and destructuring defs have nothing to bring into scope.
+                    _ => continue,
+                },
+            };
+
+            match opt_ident {
+                Some(ident) => {
+                    let ident = ident.trim_end().to_string();
+
+                    self.past_defs.retain(
+                        |past_def| !matches!(past_def, PastDef::Def { ident: past, .. } if *past == ident),
+                    );
+                    self.add_past_def(ident, def_src.to_string());
+
+                    def_count += 1;
+                }
+                None => {
+                    let import = PastDef::Import(def_src.to_string());
+
+                    if !self.past_defs.contains(&import) {
+                        self.past_defs.push(import);
+                    }
+                }
+            }
+        }
+
+        ReplAction::Load {
+            filename,
+            def_count,
+            problems,
+        }
+    }
+
+    fn past_srcs(&self) -> impl Iterator<Item = &str> {
+        self.past_defs.iter().map(|past_def| match past_def {
+            PastDef::Def { ident: _, src } => src.as_str(),
+            PastDef::Import(src) => src.as_str(),
+        })
+    }
+
     fn add_past_def(&mut self, ident: String, src: String) {
         let existing_idents = &mut self.past_def_idents;
 
@@ -294,6 +428,12 @@ pub enum ParseOutcome<'a> {
     Empty,
     Help,
     Exit,
+    /// `:type expr`
+    Type(&'a str),
+    /// `:reset`
+    Reset,
+    /// `:load path/to/File.roc`
+    Load(&'a str),
 }
 
 /// Special case some syntax errors to allow for multi-line inputs
@@ -309,9 +449,19 @@ fn parse_outcome_for_error(e: EExpr<'_>) -> ParseOutcome<'_> {
 }
 
 pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
+    // Meta-commands that take an argument. The argument keeps its case.
+    if let Some((command, arg)) = line.trim().split_once(char::is_whitespace) {
+        match command.to_lowercase().as_str() {
+            ":type" | ":t" => return ParseOutcome::Type(arg.trim()),
+            ":load" | ":l" => return ParseOutcome::Load(arg.trim()),
+            _ => {}
+        }
+    }
+
     match line.trim().to_lowercase().as_str() {
         "" => ParseOutcome::Empty,
         ":help" => ParseOutcome::Help,
+        ":reset" => ParseOutcome::Reset,
         // These are all common things beginners try.
         // Let people exit the repl easily!
         // If you really need to evaluate `exit` for some reason,
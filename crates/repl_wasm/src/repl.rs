@@ -1,28 +1,21 @@
-use bumpalo::{collections::vec::Vec, Bump};
+use bumpalo::Bump;
 use roc_reporting::report::{DEFAULT_PALETTE_HTML, HTML_STYLE_CODES};
 use std::{cell::RefCell, mem::size_of};
 
-use roc_collections::all::MutSet;
-use roc_gen_wasm::wasm32_result;
 use roc_load::MonomorphizedModule;
 use roc_parse::ast::Expr;
-use roc_repl_eval::{
-    eval::jit_to_ast,
-    gen::{format_answer, ReplOutput},
-    ReplApp, ReplAppMemory,
-};
+use roc_render_value::format_value;
+use roc_repl_eval::{eval::jit_to_ast, gen::ReplOutput, ReplApp};
 use roc_repl_ui::{
     format_output,
     repl_state::{ReplAction, ReplState},
     TIPS,
 };
+use roc_repl_wasm_gen::{CompiledExpr, WasmMemory};
 use roc_target::Target;
-use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 
 use crate::{js_create_app, js_get_result_and_memory, js_run_app};
 
-const WRAPPER_NAME: &str = "wrapper";
-
 // On the web, we keep the REPL state in a global variable, because `main` is not in our Rust code!
 // We return back to JS after every line of input. `main` is in the browser engine, running the JS event loop.
 std::thread_local! {
@@ -33,78 +26,6 @@ pub struct WasmReplApp<'a> {
     arena: &'a Bump,
 }
 
-/// A copy of the app's memory, made after running the main function
-/// The Wasm app ran in a separate address space from the compiler and the eval code.
-/// This means we can't simply dereference its pointers as if they were local, because
-/// an unrelated value may exist at the same-numbered address in our own address space!
-/// Instead we have dereferencing methods that index into the copied bytes.
-pub struct WasmMemory<'a> {
-    copied_bytes: &'a [u8],
-}
-
-macro_rules! deref_number {
-    ($name: ident, $t: ty) => {
-        fn $name(&self, address: usize) -> $t {
-            const N: usize = size_of::<$t>();
-            let mut array = [0; N];
-            array.copy_from_slice(&self.copied_bytes[address..][..N]);
-            <$t>::from_le_bytes(array)
-        }
-    };
-}
-
-impl<'a> ReplAppMemory for WasmMemory<'a> {
-    fn deref_bool(&self, address: usize) -> bool {
-        self.copied_bytes[address] != 0
-    }
-
-    deref_number!(deref_u8, u8);
-    deref_number!(deref_u16, u16);
-    deref_number!(deref_u32, u32);
-    deref_number!(deref_u64, u64);
-    deref_number!(deref_u128, u128);
-    deref_number!(deref_usize, usize);
-
-    deref_number!(deref_i8, i8);
-    deref_number!(deref_i16, i16);
-    deref_number!(deref_i32, i32);
-    deref_number!(deref_i64, i64);
-    deref_number!(deref_i128, i128);
-    deref_number!(deref_isize, isize);
-
-    deref_number!(deref_f32, f32);
-    deref_number!(deref_f64, f64);
-
-    fn deref_str(&self, addr: usize) -> &str {
-        // We can't use RocStr, we need our own small/big string logic.
-        // The first field is *not* a pointer. We can calculate a pointer for it, but only for big strings.
-        // If changing this code, remember it also runs in wasm32, not just the app.
-        let last_byte = self.copied_bytes[addr + 4 + 4 + 3] as i8;
-        let is_small = last_byte < 0;
-
-        let str_bytes = if is_small {
-            let len = (last_byte & 0x7f) as usize;
-            &self.copied_bytes[addr..][..len]
-        } else {
-            let chars_index = self.deref_usize(addr);
-            let seamless_slice_mask = u32::MAX as usize >> 1;
-            let len = self.deref_usize(addr + 4) & seamless_slice_mask;
-            &self.copied_bytes[chars_index..][..len]
-        };
-
-        unsafe { std::str::from_utf8_unchecked(str_bytes) }
-    }
-
-    fn deref_pointer_with_tag_id(&self, addr: usize) -> (u16, u64) {
-        let addr_with_id = self.deref_usize(addr);
-        let tag_id_mask = 0b11;
-
-        let tag_id = addr_with_id & tag_id_mask;
-        let data_addr = addr_with_id & !tag_id_mask;
-        (tag_id as _, data_addr as _)
-    }
-}
-
 impl<'a> WasmReplApp<'a> {
     /// Allocate a buffer to copy the app memory into
     /// Buffer is aligned to 64 bits to preserve the original alignment of all Wasm numbers
@@ -176,9 +97,6 @@ impl<'a> ReplApp<'a> for WasmReplApp<'a> {
     }
 }
 
-const PRE_LINKED_BINARY: &[u8] =
-    include_bytes!(concat!(env!("OUT_DIR"), "/pre_linked_binary.wasm")) as &[_];
-
 pub async fn entrypoint_from_js(src: String) -> String {
     // If our Rust code panics, redirect the error message to JS console.error
     // Also, our JS code overrides console.error to display the error message text (including stack trace) in the REPL output.
@@ -203,10 +121,14 @@ pub async fn entrypoint_from_js(src: String) -> String {
         ReplAction::Exit => {
             "To exit the web version of the REPL, just close the browser tab!".to_string()
         }
-        ReplAction::FileProblem { .. } => {
+        ReplAction::FileProblem { .. } | ReplAction::Load { .. } => {
             "The web version of the REPL cannot import files... for now!".to_string()
         }
         ReplAction::Nothing => String::new(),
+        ReplAction::Type {
+            opt_output,
+            problems,
+        } => format_output(HTML_STYLE_CODES, opt_output, problems),
         ReplAction::Eval { opt_mono, problems } => {
             let opt_output = match opt_mono {
                 Some(mono) => eval_wasm(arena, target, mono).await,
@@ -223,72 +145,15 @@ async fn eval_wasm<'a>(
     target: Target,
     mono: MonomorphizedModule<'a>,
 ) -> Option<ReplOutput> {
-    let MonomorphizedModule {
-        module_id,
-        procedures,
-        mut interns,
-        mut subs,
-        exposed_to_host,
-        mut layout_interner,
-        ..
-    } = mono;
-
-    debug_assert_eq!(exposed_to_host.top_level_values.len(), 1);
-    let (main_fn_symbol, main_fn_var) = exposed_to_host.top_level_values.iter().next().unwrap();
-    let main_fn_symbol = *main_fn_symbol;
-    let main_fn_var = *main_fn_var;
-
-    // pretty-print the expr type string for later.
-    let expr_type = name_and_print_var(
+    let CompiledExpr {
+        app_module_bytes,
+        expr_type,
+        main_fn_layout,
         main_fn_var,
-        &mut subs,
-        module_id,
-        &interns,
-        DebugPrint::NOTHING,
-    );
-
-    let (_, main_fn_layout) = *procedures.keys().find(|(s, _)| *s == main_fn_symbol)?;
-
-    let app_module_bytes = {
-        let env = roc_gen_wasm::Env {
-            arena,
-            module_id,
-            stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
-            exposed_to_host: exposed_to_host
-                .top_level_values
-                .keys()
-                .copied()
-                .collect::<MutSet<_>>(),
-        };
-
-        let (mut module, mut called_fns, main_fn_index) = {
-            let host_module = roc_gen_wasm::parse_host(env.arena, PRE_LINKED_BINARY).unwrap();
-            roc_gen_wasm::build_app_module(
-                &env,
-                &mut layout_interner,
-                &mut interns, // NOTE: must drop this mutable ref before jit_to_ast
-                host_module,
-                procedures,
-            )
-        };
-
-        wasm32_result::insert_wrapper_for_layout(
-            arena,
-            &layout_interner,
-            &mut module,
-            WRAPPER_NAME,
-            main_fn_index,
-            main_fn_layout.result,
-        );
-        called_fns.push(true);
-
-        module.eliminate_dead_code(env.arena, called_fns);
-
-        let mut buffer = Vec::with_capacity_in(module.size(), arena);
-        module.serialize(&mut buffer);
-
-        buffer
-    };
+        subs,
+        interns,
+        layout_interner,
+    } = roc_repl_wasm_gen::compile(arena, mono)?;
 
     // Send the compiled binary out to JS, which will asynchronously create an executable WebAssembly instance
     match js_create_app(&app_module_bytes).await {
@@ -318,7 +183,7 @@ async fn eval_wasm<'a>(
     );
 
     // Transform the Expr to a string
-    let expr = format_answer(arena, res_answer).to_string();
+    let expr = format_value(arena, &res_answer).to_string();
 
     Some(ReplOutput { expr, expr_type })
 }
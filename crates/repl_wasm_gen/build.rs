@@ -0,0 +1,84 @@
+use roc_command_utils::zig;
+use std::env;
+use std::path::PathBuf;
+
+use wasi_libc_sys::{WASI_COMPILER_RT_PATH, WASI_LIBC_PATH};
+
+const PLATFORM_FILENAME: &str = "repl_platform";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let source_path = format!("src/{PLATFORM_FILENAME}.c");
+    println!("cargo:rerun-if-changed={source_path}");
+
+    // Zig can produce *either* an object containing relocations OR an object containing libc code
+    // But we want both, so we have to compile twice with different flags, then link them
+
+    // Create an object file with relocations
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let platform_obj = build_wasm_platform(&out_dir, &source_path);
+
+    let mut pre_linked_binary_path = PathBuf::from(&out_dir).join("pre_linked_binary");
+    pre_linked_binary_path.set_extension("wasm");
+
+    let builtins_host_tempfile = roc_bitcode::host_wasm_tempfile()
+        .expect("failed to write host builtins object to tempfile");
+
+    run_zig(&[
+        "wasm-ld",
+        builtins_host_tempfile.path().to_str().unwrap(),
+        platform_obj.to_str().unwrap(),
+        WASI_COMPILER_RT_PATH,
+        WASI_LIBC_PATH,
+        "-o",
+        pre_linked_binary_path.to_str().unwrap(),
+        "--export-all",
+        "--no-entry",
+        "--relocatable",
+    ]);
+
+    // Extend the lifetime of the tempfile so it doesn't get dropped
+    // (and thus deleted) before the Zig process is done using it!
+    let _ = builtins_host_tempfile;
+}
+
+fn build_wasm_platform(out_dir: &str, source_path: &str) -> PathBuf {
+    let mut platform_obj = PathBuf::from(out_dir).join(PLATFORM_FILENAME);
+    platform_obj.set_extension("wasm");
+
+    run_zig(&[
+        "build-lib",
+        "-target",
+        "wasm32-wasi",
+        "-lc",
+        source_path,
+        &format!("-femit-bin={}", platform_obj.to_str().unwrap()),
+    ]);
+
+    platform_obj
+}
+
+// Run cargo with -vv to see commands printed out
+fn run_zig(args: &[&str]) {
+    let mut zig_cmd = zig();
+
+    let full_zig_cmd = zig_cmd.args(args);
+    println!("{full_zig_cmd:?}");
+
+    let zig_cmd_output = full_zig_cmd.output().unwrap();
+
+    if !zig_cmd_output.status.success() {
+        eprintln!(
+            "stdout:\n{}",
+            String::from_utf8_lossy(&zig_cmd_output.stdout)
+        );
+        eprintln!(
+            "stderr:\n{}",
+            String::from_utf8_lossy(&zig_cmd_output.stderr)
+        );
+        panic!("zig call failed with status {:?}", zig_cmd_output.status);
+    }
+
+    assert!(zig_cmd_output.stdout.is_empty(), "{zig_cmd_output:#?}");
+    assert!(zig_cmd_output.stderr.is_empty(), "{zig_cmd_output:#?}");
+}
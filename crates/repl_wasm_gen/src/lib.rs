@@ -0,0 +1,197 @@
+//! Compiles REPL expressions to WebAssembly apps, linked with the platform in repl_platform.c.
+//! The web REPL runs them in the browser, and `roc repl --wasm` runs them in an interpreter.
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_collections::all::MutSet;
+use roc_gen_wasm::wasm32_result;
+use roc_load::MonomorphizedModule;
+use roc_module::symbol::Interns;
+use roc_mono::ir::ProcLayout;
+use roc_mono::layout::STLayoutInterner;
+use roc_render_value::ValueMemory;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::subs::{Subs, Variable};
+use std::mem::size_of;
+
+/// The export that runs the expression, and returns the address of its value
+pub const WRAPPER_NAME: &str = "wrapper";
+
+/// The builtins and the platform in repl_platform.c, linked together by build.rs
+const PRE_LINKED_BINARY: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/pre_linked_binary.wasm")) as &[_];
+
+/// An expression compiled to a Wasm module, and what `jit_to_ast` needs to turn the value it
+/// returns back into Roc code
+pub struct CompiledExpr<'a> {
+    pub app_module_bytes: Vec<'a, u8>,
+    pub expr_type: String,
+    pub main_fn_layout: ProcLayout<'a>,
+    pub main_fn_var: Variable,
+    pub subs: Subs,
+    pub interns: Interns,
+    pub layout_interner: STLayoutInterner<'a>,
+}
+
+/// The `mono` module must have been loaded for `Target::Wasm32`.
+pub fn compile<'a>(arena: &'a Bump, mono: MonomorphizedModule<'a>) -> Option<CompiledExpr<'a>> {
+    let MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        mut subs,
+        exposed_to_host,
+        mut layout_interner,
+        ..
+    } = mono;
+
+    debug_assert_eq!(exposed_to_host.top_level_values.len(), 1);
+    let (main_fn_symbol, main_fn_var) = exposed_to_host.top_level_values.iter().next().unwrap();
+    let main_fn_symbol = *main_fn_symbol;
+    let main_fn_var = *main_fn_var;
+
+    // pretty-print the expr type string for later.
+    let expr_type = name_and_print_var(
+        main_fn_var,
+        &mut subs,
+        module_id,
+        &interns,
+        DebugPrint::NOTHING,
+    );
+
+    let (_, main_fn_layout) = *procedures.keys().find(|(s, _)| *s == main_fn_symbol)?;
+
+    let env = roc_gen_wasm::Env {
+        arena,
+        module_id,
+        stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        canonicalize_nans: false,
+        heap_profiling: None,
+        max_function_stmts: roc_gen_wasm::Env::DEFAULT_MAX_FUNCTION_STMTS,
+        max_inline_stmts: 0,
+        multi_value_returns: true,
+        patchable_calls: false,
+        allocator: roc_gen_wasm::Allocator::Host,
+        // We cannot spawn threads in wasm
+        codegen_threads: 1,
+        exposed_to_host: exposed_to_host
+            .top_level_values
+            .keys()
+            .copied()
+            .collect::<MutSet<_>>(),
+    };
+
+    let (mut module, mut called_fns, main_fn_index) = {
+        let host_module = roc_gen_wasm::parse_host(env.arena, PRE_LINKED_BINARY).unwrap();
+        roc_gen_wasm::build_app_module(
+            &env,
+            &mut layout_interner,
+            &mut interns, // NOTE: must drop this mutable ref before jit_to_ast
+            host_module,
+            procedures,
+        )
+    };
+
+    wasm32_result::insert_wrapper_for_layout(
+        arena,
+        &layout_interner,
+        &mut module,
+        WRAPPER_NAME,
+        main_fn_index,
+        main_fn_layout.result,
+    );
+    called_fns.push(true);
+
+    module.eliminate_dead_code(env.arena, called_fns);
+
+    let mut app_module_bytes = Vec::with_capacity_in(module.size(), arena);
+    module.serialize(&mut app_module_bytes);
+
+    Some(CompiledExpr {
+        app_module_bytes,
+        expr_type,
+        main_fn_layout,
+        main_fn_var,
+        subs,
+        interns,
+        layout_interner,
+    })
+}
+
+/// A copy of the app's memory, made after running the main function
+/// The Wasm app ran in a separate address space from the compiler and the eval code.
+/// This means we can't simply dereference its pointers as if they were local, because
+/// an unrelated value may exist at the same-numbered address in our own address space!
+/// Instead we have dereferencing methods that index into the copied bytes.
+/// A `usize` in it is only 4 bytes wide, even when the REPL itself isn't running in wasm32.
+pub struct WasmMemory<'a> {
+    pub copied_bytes: &'a [u8],
+}
+
+macro_rules! deref_number {
+    ($name: ident, $t: ty) => {
+        fn $name(&self, address: usize) -> $t {
+            const N: usize = size_of::<$t>();
+            let mut array = [0; N];
+            array.copy_from_slice(&self.copied_bytes[address..][..N]);
+            <$t>::from_le_bytes(array)
+        }
+    };
+}
+
+impl<'a> ValueMemory for WasmMemory<'a> {
+    fn deref_bool(&self, address: usize) -> bool {
+        self.copied_bytes[address] != 0
+    }
+
+    deref_number!(deref_u8, u8);
+    deref_number!(deref_u16, u16);
+    deref_number!(deref_u32, u32);
+    deref_number!(deref_u64, u64);
+    deref_number!(deref_u128, u128);
+
+    deref_number!(deref_i8, i8);
+    deref_number!(deref_i16, i16);
+    deref_number!(deref_i32, i32);
+    deref_number!(deref_i64, i64);
+    deref_number!(deref_i128, i128);
+
+    deref_number!(deref_f32, f32);
+    deref_number!(deref_f64, f64);
+
+    fn deref_usize(&self, address: usize) -> usize {
+        self.deref_u32(address) as usize
+    }
+
+    fn deref_isize(&self, address: usize) -> isize {
+        self.deref_i32(address) as isize
+    }
+
+    fn deref_str(&self, addr: usize) -> &str {
+        // We can't use RocStr, we need our own small/big string logic.
+        // The first field is *not* a pointer. We can calculate a pointer for it, but only for big strings.
+        // If changing this code, remember it also runs in wasm32, not just the app.
+        let last_byte = self.copied_bytes[addr + 4 + 4 + 3] as i8;
+        let is_small = last_byte < 0;
+
+        let str_bytes = if is_small {
+            let len = (last_byte & 0x7f) as usize;
+            &self.copied_bytes[addr..][..len]
+        } else {
+            let chars_index = self.deref_usize(addr);
+            let seamless_slice_mask = u32::MAX as usize >> 1;
+            let len = self.deref_usize(addr + 4) & seamless_slice_mask;
+            &self.copied_bytes[chars_index..][..len]
+        };
+
+        unsafe { std::str::from_utf8_unchecked(str_bytes) }
+    }
+
+    fn deref_pointer_with_tag_id(&self, addr: usize) -> (u16, u64) {
+        let addr_with_id = self.deref_usize(addr);
+        let tag_id_mask = 0b11;
+
+        let tag_id = addr_with_id & tag_id_mask;
+        let data_addr = addr_with_id & !tag_id_mask;
+        (tag_id as _, data_addr as _)
+    }
+}
@@ -3,7 +3,9 @@
 #include <string.h>
 
 /*
-    A bare-bones Roc "platform" for REPL code, providing heap allocation for builtins.
+    A bare-bones Roc "platform" for REPL code compiled to WebAssembly, providing heap allocation
+    for builtins. The imports below are implemented by JS in the web REPL, and by the interpreter
+    that runs the app in the CLI REPL.
 */
 
 // Enable/disable printf debugging. Leave disabled to avoid bloating .wasm files and slowing down tests.
@@ -67,8 +69,14 @@ void roc_panic(void *ptr, unsigned int panic_tag)
     abort();
 }
 
-// TODO: add a way to send dbg to js.
-void roc_debug(void* loc, void* msg) {}
+//--------------------------
+
+extern void send_dbg_msg_to_js(void *loc, void *msg, void *src);
+
+void roc_dbg(void *loc, void *msg, void *src)
+{
+    send_dbg_msg_to_js(loc, msg, src);
+}
 
 //--------------------------
 
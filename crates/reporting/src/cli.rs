@@ -8,6 +8,16 @@ use roc_solve_problem::TypeError;
 
 use crate::report::ANSI_STYLE_CODES;
 
+/// How commands like `roc check` print the problems they find.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colored reports, for people.
+    #[default]
+    Terminal,
+    /// A single JSON document, for editors and CI. See `crate::json::problems_json`.
+    Json,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Problems {
     pub fatally_errored: bool,
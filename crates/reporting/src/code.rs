@@ -0,0 +1,2336 @@
+//! Stable codes for every kind of report, so that problems are easy to search for, and so that
+//! `roc explain` can say more about a problem than fits in the report itself.
+
+/// Declares [ReportKind], with the code and the title of every kind of report.
+macro_rules! report_kinds {
+    ($($kind:ident = $code:literal $title:literal,)*) => {
+        /// Every kind of report that has a code. Codes are never renumbered or reused: a new kind
+        /// of report gets the next free code in its group, and a kind that goes away takes its
+        /// code with it.
+        ///
+        /// The groups are
+        ///
+        /// - E001..E099: type checking
+        /// - E101..E199: canonicalization (naming, scoping, abilities, ...)
+        /// - E201..E299: parsing
+        /// - E301..E399: loading modules and packages
+        /// - E401..E499: expects
+        /// - E501..E599: docs
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum ReportKind {
+            $($kind,)*
+        }
+
+        impl ReportKind {
+            /// Every kind of report, in the order of their codes.
+            pub const ALL: &'static [ReportKind] = &[$(ReportKind::$kind,)*];
+
+            /// The stable code of this kind of report, e.g. `E001` for a type mismatch.
+            pub fn code(self) -> &'static str {
+                match self {
+                    $(ReportKind::$kind => $code,)*
+                }
+            }
+
+            /// The title that reports of this kind are shown with, e.g. `TYPE MISMATCH`.
+            pub fn title(self) -> &'static str {
+                match self {
+                    $(ReportKind::$kind => $title,)*
+                }
+            }
+        }
+    };
+}
+
+report_kinds! {
+    TypeMismatch = "E001" "TYPE MISMATCH",
+    CircularType = "E002" "CIRCULAR TYPE",
+    CyclicAlias = "E003" "CYCLIC ALIAS",
+    TooManyArgs = "E004" "TOO MANY ARGS",
+    TooFewArgs = "E005" "TOO FEW ARGS",
+    UnsafePattern = "E006" "UNSAFE PATTERN",
+    RedundantPattern = "E007" "REDUNDANT PATTERN",
+    UnmatchablePattern = "E008" "UNMATCHABLE PATTERN",
+    IncompleteAbilityImplementation = "E009" "INCOMPLETE ABILITY IMPLEMENTATION",
+    IllegalSpecialization = "E010" "ILLEGAL SPECIALIZATION",
+    WrongSpecializationType = "E011" "WRONG SPECIALIZATION TYPE",
+    InvalidUtf8 = "E012" "INVALID UTF-8",
+    InvalidTypeForIngestedFile = "E013" "INVALID TYPE FOR INGESTED FILE",
+    UnrecognizedName = "E101" "UNRECOGNIZED NAME",
+    NamingProblem = "E102" "NAMING PROBLEM",
+    UnusedDefinition = "E103" "UNUSED DEFINITION",
+    UnusedImport = "E104" "UNUSED IMPORT",
+    ImportNameConflict = "E105" "IMPORT NAME CONFLICT",
+    ExplicitBuiltinImport = "E106" "EXPLICIT BUILTIN IMPORT",
+    UnusedTypeAliasParameter = "E107" "UNUSED TYPE ALIAS PARAMETER",
+    UndeclaredTypeVariable = "E108" "UNDECLARED TYPE VARIABLE",
+    WildcardNotAllowedHere = "E109" "WILDCARD NOT ALLOWED HERE",
+    UnderscoreNotAllowedHere = "E110" "UNDERSCORE NOT ALLOWED HERE",
+    UnusedArgument = "E111" "UNUSED ARGUMENT",
+    MissingDefinition = "E112" "MISSING DEFINITION",
+    UnknownGeneratesFunction = "E113" "UNKNOWN GENERATES FUNCTION",
+    DuplicateFieldName = "E114" "DUPLICATE FIELD NAME",
+    DuplicateTagName = "E115" "DUPLICATE TAG NAME",
+    InvalidUnicode = "E116" "INVALID UNICODE",
+    CircularDefinition = "E117" "CIRCULAR DEFINITION",
+    DuplicateName = "E118" "DUPLICATE NAME",
+    NotExposed = "E119" "NOT EXPOSED",
+    ModuleNotImported = "E120" "MODULE NOT IMPORTED",
+    IngestedFileError = "E121" "INGESTED FILE ERROR",
+    NestedDatatype = "E122" "NESTED DATATYPE",
+    ConflictingNumberSuffix = "E123" "CONFLICTING NUMBER SUFFIX",
+    NumberOverflowsSuffix = "E124" "NUMBER OVERFLOWS SUFFIX",
+    NumberUnderflowsSuffix = "E125" "NUMBER UNDERFLOWS SUFFIX",
+    OpaqueTypeNotDefined = "E126" "OPAQUE TYPE NOT DEFINED",
+    OpaqueTypeDeclaredOutsideScope = "E127" "OPAQUE TYPE DECLARED OUTSIDE SCOPE",
+    OpaqueTypeNotApplied = "E128" "OPAQUE TYPE NOT APPLIED",
+    OpaqueTypeAppliedToTooManyArgs = "E129" "OPAQUE TYPE APPLIED TO TOO MANY ARGS",
+    InvalidExtensionType = "E130" "INVALID_EXTENSION_TYPE",
+    AbilityHasTypeVariables = "E131" "ABILITY HAS TYPE VARIABLES",
+    ImplementsClauseIsNotAnAbility = "E132" "IMPLEMENTS CLAUSE IS NOT AN ABILITY",
+    IllegalImplementsClause = "E133" "ILLEGAL IMPLEMENTS CLAUSE",
+    AbilityMemberMissingImplementsClause = "E134" "ABILITY MEMBER MISSING IMPLEMENTS CLAUSE",
+    AbilityMemberBindsMultipleVariables = "E135" "ABILITY MEMBER BINDS MULTIPLE VARIABLES",
+    AbilityNotOnTopLevel = "E136" "ABILITY NOT ON TOP-LEVEL",
+    SpecializationNotOnTopLevel = "E137" "SPECIALIZATION NOT ON TOP-LEVEL",
+    AbilityUsedAsType = "E138" "ABILITY USED AS TYPE",
+    IllegalDerive = "E139" "ILLEGAL DERIVE",
+    ImplementationNotFound = "E140" "IMPLEMENTATION NOT FOUND",
+    NotAnAbilityMember = "E141" "NOT AN ABILITY MEMBER",
+    NotAnAbility = "E142" "NOT AN ABILITY",
+    OptionalAbilityImplementation = "E143" "OPTIONAL ABILITY IMPLEMENTATION",
+    QualifiedAbilityImplementation = "E144" "QUALIFIED ABILITY IMPLEMENTATION",
+    AbilityImplementationNotIdentifier = "E145" "ABILITY IMPLEMENTATION NOT IDENTIFIER",
+    DuplicateImplementation = "E146" "DUPLICATE IMPLEMENTATION",
+    UnnecessaryImplementations = "E147" "UNNECESSARY IMPLEMENTATIONS",
+    DefinitionOnlyUsedInRecursion = "E148" "DEFINITION ONLY USED IN RECURSION",
+    DuplicateBoundAbility = "E149" "DUPLICATE BOUND ABILITY",
+    NameNotBoundInAllPatterns = "E150" "NAME NOT BOUND IN ALL PATTERNS",
+    UnnecessaryDefinition = "E151" "UNNECESSARY DEFINITION",
+    OverloadedSpecialization = "E152" "OVERLOADED SPECIALIZATION",
+    UnnecessaryWildcard = "E153" "UNNECESSARY WILDCARD",
+    MultipleListRestPatterns = "E154" "MULTIPLE LIST REST PATTERNS",
+    TooManyTypeArguments = "E155" "TOO MANY TYPE ARGUMENTS",
+    TooFewTypeArguments = "E156" "TOO FEW TYPE ARGUMENTS",
+    UnappliedCrash = "E157" "UNAPPLIED CRASH",
+    OverappliedCrash = "E158" "OVERAPPLIED CRASH",
+    BadOptionalValue = "E159" "BAD OPTIONAL VALUE",
+    DegenerateBranch = "E160" "DEGENERATE BRANCH",
+    MultipleOldStyleRecordBuilders = "E161" "MULTIPLE OLD-STYLE RECORD BUILDERS",
+    UnappliedOldStyleRecordBuilder = "E162" "UNAPPLIED OLD-STYLE RECORD BUILDER",
+    EmptyRecordBuilder = "E163" "EMPTY RECORD BUILDER",
+    NotEnoughFieldsInRecordBuilder = "E164" "NOT ENOUGH FIELDS IN RECORD BUILDER",
+    OptionalFieldInRecordBuilder = "E165" "OPTIONAL FIELD IN RECORD BUILDER",
+    SyntaxProblem = "E201" "SYNTAX PROBLEM",
+    ParseProblem = "E202" "PARSE PROBLEM",
+    NotEndOfFile = "E203" "NOT END OF FILE",
+    ArgumentsBeforeEquals = "E204" "ARGUMENTS BEFORE EQUALS",
+    UnknownOperator = "E205" "UNKNOWN OPERATOR",
+    WeirdIdentifier = "E206" "WEIRD IDENTIFIER",
+    MissingExpression = "E207" "MISSING EXPRESSION",
+    MissingFinalExpression = "E208" "MISSING FINAL EXPRESSION",
+    BadBackpassingArrow = "E209" "BAD BACKPASSING ARROW",
+    BadOldStyleRecordBuilder = "E210" "BAD OLD-STYLE RECORD BUILDER",
+    BadRecordUpdate = "E211" "BAD RECORD UPDATE",
+    IndentEndsAfterExpression = "E212" "INDENT ENDS AFTER EXPRESSION",
+    TrailingOperator = "E213" "TRAILING OPERATOR",
+    UnexpectedComma = "E214" "UNEXPECTED COMMA",
+    StatementAfterExpression = "E215" "STATEMENT AFTER EXPRESSION",
+    RecordParseProblem = "E216" "RECORD PARSE PROBLEM",
+    WeirdArrow = "E217" "WEIRD ARROW",
+    MissingArrow = "E218" "MISSING ARROW",
+    UnfinishedArgumentList = "E219" "UNFINISHED ARGUMENT LIST",
+    UnfinishedFunction = "E220" "UNFINISHED FUNCTION",
+    WeirdEscape = "E221" "WEIRD ESCAPE",
+    WeirdCodePoint = "E222" "WEIRD CODE POINT",
+    EndlessFormat = "E223" "ENDLESS FORMAT",
+    EndlessScalar = "E224" "ENDLESS SCALAR",
+    InvalidScalar = "E225" "INVALID SCALAR",
+    EndlessString = "E226" "ENDLESS STRING",
+    ExpectedString = "E227" "EXPECTED STRING",
+    InsufficientIndentInMultiLineString = "E228" "INSUFFICIENT INDENT IN MULTI-LINE STRING",
+    EmptyParentheses = "E229" "EMPTY PARENTHESES",
+    UnfinishedParentheses = "E230" "UNFINISHED PARENTHESES",
+    UnfinishedList = "E231" "UNFINISHED LIST",
+    OldStyleRecordBuilderInModuleParams = "E232" "OLD-STYLE RECORD BUILDER IN MODULE PARAMS",
+    RecordUpdateInModuleParams = "E233" "RECORD UPDATE IN MODULE PARAMS",
+    RecordBuilderInModuleParams = "E234" "RECORD BUILDER IN MODULE PARAMS",
+    LowercaseAlias = "E235" "LOWERCASE ALIAS",
+    WeirdExposing = "E236" "WEIRD EXPOSING",
+    UnfinishedImport = "E237" "UNFINISHED IMPORT",
+    UnfinishedIf = "E238" "UNFINISHED IF",
+    IfGuardNoCondition = "E239" "IF GUARD NO CONDITION",
+    UnfinishedWhen = "E240" "UNFINISHED WHEN",
+    UnexpectedArrow = "E241" "UNEXPECTED ARROW",
+    UnfinishedPattern = "E242" "UNFINISHED PATTERN",
+    UnfinishedRecordPattern = "E243" "UNFINISHED RECORD PATTERN",
+    ProblemInRecordPattern = "E244" "PROBLEM IN RECORD PATTERN",
+    UnfinishedListPattern = "E245" "UNFINISHED LIST PATTERN",
+    IncorrectRestPattern = "E246" "INCORRECT REST PATTERN",
+    InvalidNumberLiteral = "E247" "INVALID NUMBER LITERAL",
+    DoubleComma = "E248" "DOUBLE COMMA",
+    UnfinishedType = "E249" "UNFINISHED TYPE",
+    UnfinishedInlineAlias = "E250" "UNFINISHED INLINE ALIAS",
+    BadTypeVariable = "E251" "BAD TYPE VARIABLE",
+    UnfinishedRecordType = "E252" "UNFINISHED RECORD TYPE",
+    ProblemInRecordType = "E253" "PROBLEM IN RECORD TYPE",
+    NeedMoreIndentation = "E254" "NEED MORE INDENTATION",
+    UnfinishedTagUnionType = "E255" "UNFINISHED TAG UNION TYPE",
+    WeirdTagName = "E256" "WEIRD TAG NAME",
+    DoubleDot = "E257" "DOUBLE DOT",
+    TrailingDot = "E258" "TRAILING DOT",
+    WeirdQualifiedName = "E259" "WEIRD QUALIFIED NAME",
+    EndOfFile = "E260" "END OF FILE",
+    NotAnInlineAlias = "E261" "NOT AN INLINE ALIAS",
+    QualifiedAliasName = "E262" "QUALIFIED ALIAS NAME",
+    TypeArgumentNotLowercase = "E263" "TYPE ARGUMENT NOT LOWERCASE",
+    IncompleteHeader = "E264" "INCOMPLETE HEADER",
+    MissingHeader = "E265" "MISSING HEADER",
+    WeirdModuleName = "E266" "WEIRD MODULE NAME",
+    WeirdAppName = "E267" "WEIRD APP NAME",
+    InvalidPackageName = "E268" "INVALID PACKAGE NAME",
+    InvalidPlatformName = "E269" "INVALID PLATFORM NAME",
+    WeirdGeneratedTypeName = "E270" "WEIRD GENERATED TYPE NAME",
+    WeirdGenerates = "E271" "WEIRD GENERATES",
+    WeirdProvides = "E272" "WEIRD PROVIDES",
+    WeirdModuleParams = "E273" "WEIRD MODULE PARAMS",
+    WeirdExposes = "E274" "WEIRD EXPOSES",
+    WeirdImports = "E275" "WEIRD IMPORTS",
+    MissingRequires = "E276" "MISSING REQUIRES",
+    BadRequiresRigids = "E277" "BAD REQUIRES RIGIDS",
+    BadRequires = "E278" "BAD REQUIRES",
+    MissingPackages = "E279" "MISSING PACKAGES",
+    WeirdPackagesList = "E280" "WEIRD PACKAGES LIST",
+    TabCharacter = "E281" "TAB CHARACTER",
+    AsciiControlCharacter = "E282" "ASCII CONTROL CHARACTER",
+    MisplacedCarriageReturn = "E283" "MISPLACED CARRIAGE RETURN",
+    UnfinishedAbility = "E284" "UNFINISHED ABILITY",
+    FileNotFound = "E301" "FILE NOT FOUND",
+    FilePermissionDenied = "E302" "FILE PERMISSION DENIED",
+    NotARocFile = "E303" "NOT A ROC FILE",
+    FileProblem = "E304" "FILE PROBLEM",
+    FileTooLarge = "E305" "FILE TOO LARGE",
+    ImportCycle = "E306" "IMPORT CYCLE",
+    IncorrectModuleName = "E307" "INCORRECT MODULE NAME",
+    UnspecifiedPlatform = "E308" "UNSPECIFIED PLATFORM",
+    MultiplePlatforms = "E309" "MULTIPLE PLATFORMS",
+    NoPlatform = "E310" "NO PLATFORM",
+    UnrecognizedPackage = "E311" "UNRECOGNIZED PACKAGE",
+    UnsupportedEncoding = "E312" "UNSUPPORTED ENCODING",
+    MultipleEncodings = "E313" "MULTIPLE ENCODINGS",
+    InvalidContentHash = "E314" "INVALID CONTENT HASH",
+    IoError = "E315" "IO ERROR",
+    HttpError = "E316" "HTTP ERROR",
+    InvalidExtensionSuffix = "E317" "INVALID EXTENSION SUFFIX",
+    InvalidExtension = "E318" "INVALID EXTENSION",
+    InvalidFragment = "E319" "INVALID FRAGMENT",
+    MissingPackageHash = "E320" "MISSING PACKAGE HASH",
+    HttpsMandatory = "E321" "HTTPS MANDATORY",
+    MisleadingCharacters = "E322" "MISLEADING CHARACTERS",
+    NotYetImplemented = "E323" "NOT YET IMPLEMENTED",
+    CodeGenFailed = "E324" "CODE GEN FAILED",
+    ExpectFailed = "E401" "EXPECT FAILED",
+    ExpectPanicked = "E402" "EXPECT PANICKED",
+    InvalidDocsLink = "E501" "INVALID DOCS LINK",
+}
+
+impl ReportKind {
+    /// The kind of report with this code. Codes are matched case-insensitively, so `e001` works
+    /// too.
+    pub fn from_code(code: &str) -> Option<ReportKind> {
+        ReportKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.code().eq_ignore_ascii_case(code))
+    }
+}
+
+/// The text `roc explain` prints for a code, or None if there is no such code.
+pub fn explain(code: &str) -> Option<String> {
+    let kind = ReportKind::from_code(code)?;
+
+    let body = explanation(kind).unwrap_or(
+        "There is no longer explanation for this code yet. The report itself describes the\n\
+         problem, and usually says how to fix it.",
+    );
+
+    Some(format!(
+        "{}: {}\n\n{}\n",
+        kind.code(),
+        kind.title(),
+        body.trim()
+    ))
+}
+
+/// The longer description of a kind of report that `roc explain` prints, with an example of
+/// code that causes it. Every kind has one, and test_reporting checks that it stays that way.
+pub fn explanation(kind: ReportKind) -> Option<&'static str> {
+    let text = match kind {
+        ReportKind::TypeMismatch => TYPE_MISMATCH,
+        ReportKind::CircularType => CIRCULAR_TYPE,
+        ReportKind::CyclicAlias => CYCLIC_ALIAS,
+        ReportKind::TooManyArgs => TOO_MANY_ARGS,
+        ReportKind::TooFewArgs => TOO_FEW_ARGS,
+        ReportKind::UnsafePattern => UNSAFE_PATTERN,
+        ReportKind::RedundantPattern => REDUNDANT_PATTERN,
+        ReportKind::UnmatchablePattern => UNMATCHABLE_PATTERN,
+        ReportKind::IncompleteAbilityImplementation => INCOMPLETE_ABILITY_IMPLEMENTATION,
+        ReportKind::IllegalSpecialization => ILLEGAL_SPECIALIZATION,
+        ReportKind::WrongSpecializationType => WRONG_SPECIALIZATION_TYPE,
+        ReportKind::InvalidUtf8 => INVALID_UTF8,
+        ReportKind::InvalidTypeForIngestedFile => INVALID_TYPE_FOR_INGESTED_FILE,
+        ReportKind::UnrecognizedName => UNRECOGNIZED_NAME,
+        ReportKind::NamingProblem => NAMING_PROBLEM,
+        ReportKind::UnusedDefinition => UNUSED_DEFINITION,
+        ReportKind::UnusedImport => UNUSED_IMPORT,
+        ReportKind::ImportNameConflict => IMPORT_NAME_CONFLICT,
+        ReportKind::ExplicitBuiltinImport => EXPLICIT_BUILTIN_IMPORT,
+        ReportKind::UnusedTypeAliasParameter => UNUSED_TYPE_ALIAS_PARAMETER,
+        ReportKind::UndeclaredTypeVariable => UNDECLARED_TYPE_VARIABLE,
+        ReportKind::WildcardNotAllowedHere => WILDCARD_NOT_ALLOWED_HERE,
+        ReportKind::UnderscoreNotAllowedHere => UNDERSCORE_NOT_ALLOWED_HERE,
+        ReportKind::UnusedArgument => UNUSED_ARGUMENT,
+        ReportKind::MissingDefinition => MISSING_DEFINITION,
+        ReportKind::UnknownGeneratesFunction => UNKNOWN_GENERATES_FUNCTION,
+        ReportKind::DuplicateFieldName => DUPLICATE_FIELD_NAME,
+        ReportKind::DuplicateTagName => DUPLICATE_TAG_NAME,
+        ReportKind::InvalidUnicode => INVALID_UNICODE,
+        ReportKind::CircularDefinition => CIRCULAR_DEFINITION,
+        ReportKind::DuplicateName => DUPLICATE_NAME,
+        ReportKind::NotExposed => NOT_EXPOSED,
+        ReportKind::ModuleNotImported => MODULE_NOT_IMPORTED,
+        ReportKind::IngestedFileError => INGESTED_FILE_ERROR,
+        ReportKind::NestedDatatype => NESTED_DATATYPE,
+        ReportKind::ConflictingNumberSuffix => CONFLICTING_NUMBER_SUFFIX,
+        ReportKind::NumberOverflowsSuffix => NUMBER_OVERFLOWS_SUFFIX,
+        ReportKind::NumberUnderflowsSuffix => NUMBER_UNDERFLOWS_SUFFIX,
+        ReportKind::OpaqueTypeNotDefined => OPAQUE_TYPE_NOT_DEFINED,
+        ReportKind::OpaqueTypeDeclaredOutsideScope => OPAQUE_TYPE_DECLARED_OUTSIDE_SCOPE,
+        ReportKind::OpaqueTypeNotApplied => OPAQUE_TYPE_NOT_APPLIED,
+        ReportKind::OpaqueTypeAppliedToTooManyArgs => OPAQUE_TYPE_APPLIED_TO_TOO_MANY_ARGS,
+        ReportKind::InvalidExtensionType => INVALID_EXTENSION_TYPE,
+        ReportKind::AbilityHasTypeVariables => ABILITY_HAS_TYPE_VARIABLES,
+        ReportKind::ImplementsClauseIsNotAnAbility => IMPLEMENTS_CLAUSE_IS_NOT_AN_ABILITY,
+        ReportKind::IllegalImplementsClause => ILLEGAL_IMPLEMENTS_CLAUSE,
+        ReportKind::AbilityMemberMissingImplementsClause => {
+            ABILITY_MEMBER_MISSING_IMPLEMENTS_CLAUSE
+        }
+        ReportKind::AbilityMemberBindsMultipleVariables => ABILITY_MEMBER_BINDS_MULTIPLE_VARIABLES,
+        ReportKind::AbilityNotOnTopLevel => ABILITY_NOT_ON_TOP_LEVEL,
+        ReportKind::SpecializationNotOnTopLevel => SPECIALIZATION_NOT_ON_TOP_LEVEL,
+        ReportKind::AbilityUsedAsType => ABILITY_USED_AS_TYPE,
+        ReportKind::IllegalDerive => ILLEGAL_DERIVE,
+        ReportKind::ImplementationNotFound => IMPLEMENTATION_NOT_FOUND,
+        ReportKind::NotAnAbilityMember => NOT_AN_ABILITY_MEMBER,
+        ReportKind::NotAnAbility => NOT_AN_ABILITY,
+        ReportKind::OptionalAbilityImplementation => OPTIONAL_ABILITY_IMPLEMENTATION,
+        ReportKind::QualifiedAbilityImplementation => QUALIFIED_ABILITY_IMPLEMENTATION,
+        ReportKind::AbilityImplementationNotIdentifier => ABILITY_IMPLEMENTATION_NOT_IDENTIFIER,
+        ReportKind::DuplicateImplementation => DUPLICATE_IMPLEMENTATION,
+        ReportKind::UnnecessaryImplementations => UNNECESSARY_IMPLEMENTATIONS,
+        ReportKind::DefinitionOnlyUsedInRecursion => DEFINITION_ONLY_USED_IN_RECURSION,
+        ReportKind::DuplicateBoundAbility => DUPLICATE_BOUND_ABILITY,
+        ReportKind::NameNotBoundInAllPatterns => NAME_NOT_BOUND_IN_ALL_PATTERNS,
+        ReportKind::UnnecessaryDefinition => UNNECESSARY_DEFINITION,
+        ReportKind::OverloadedSpecialization => OVERLOADED_SPECIALIZATION,
+        ReportKind::UnnecessaryWildcard => UNNECESSARY_WILDCARD,
+        ReportKind::MultipleListRestPatterns => MULTIPLE_LIST_REST_PATTERNS,
+        ReportKind::TooManyTypeArguments => TOO_MANY_TYPE_ARGUMENTS,
+        ReportKind::TooFewTypeArguments => TOO_FEW_TYPE_ARGUMENTS,
+        ReportKind::UnappliedCrash => UNAPPLIED_CRASH,
+        ReportKind::OverappliedCrash => OVERAPPLIED_CRASH,
+        ReportKind::BadOptionalValue => BAD_OPTIONAL_VALUE,
+        ReportKind::DegenerateBranch => DEGENERATE_BRANCH,
+        ReportKind::MultipleOldStyleRecordBuilders => MULTIPLE_OLD_STYLE_RECORD_BUILDERS,
+        ReportKind::UnappliedOldStyleRecordBuilder => UNAPPLIED_OLD_STYLE_RECORD_BUILDER,
+        ReportKind::EmptyRecordBuilder => EMPTY_RECORD_BUILDER,
+        ReportKind::NotEnoughFieldsInRecordBuilder => NOT_ENOUGH_FIELDS_IN_RECORD_BUILDER,
+        ReportKind::OptionalFieldInRecordBuilder => OPTIONAL_FIELD_IN_RECORD_BUILDER,
+        ReportKind::SyntaxProblem => SYNTAX_PROBLEM,
+        ReportKind::ParseProblem => PARSE_PROBLEM,
+        ReportKind::NotEndOfFile => NOT_END_OF_FILE,
+        ReportKind::ArgumentsBeforeEquals => ARGUMENTS_BEFORE_EQUALS,
+        ReportKind::UnknownOperator => UNKNOWN_OPERATOR,
+        ReportKind::WeirdIdentifier => WEIRD_IDENTIFIER,
+        ReportKind::MissingExpression => MISSING_EXPRESSION,
+        ReportKind::MissingFinalExpression => MISSING_FINAL_EXPRESSION,
+        ReportKind::BadBackpassingArrow => BAD_BACKPASSING_ARROW,
+        ReportKind::BadOldStyleRecordBuilder => BAD_OLD_STYLE_RECORD_BUILDER,
+        ReportKind::BadRecordUpdate => BAD_RECORD_UPDATE,
+        ReportKind::IndentEndsAfterExpression => INDENT_ENDS_AFTER_EXPRESSION,
+        ReportKind::TrailingOperator => TRAILING_OPERATOR,
+        ReportKind::UnexpectedComma => UNEXPECTED_COMMA,
+        ReportKind::StatementAfterExpression => STATEMENT_AFTER_EXPRESSION,
+        ReportKind::RecordParseProblem => RECORD_PARSE_PROBLEM,
+        ReportKind::WeirdArrow => WEIRD_ARROW,
+        ReportKind::MissingArrow => MISSING_ARROW,
+        ReportKind::UnfinishedArgumentList => UNFINISHED_ARGUMENT_LIST,
+        ReportKind::UnfinishedFunction => UNFINISHED_FUNCTION,
+        ReportKind::WeirdEscape => WEIRD_ESCAPE,
+        ReportKind::WeirdCodePoint => WEIRD_CODE_POINT,
+        ReportKind::EndlessFormat => ENDLESS_FORMAT,
+        ReportKind::EndlessScalar => ENDLESS_SCALAR,
+        ReportKind::InvalidScalar => INVALID_SCALAR,
+        ReportKind::EndlessString => ENDLESS_STRING,
+        ReportKind::ExpectedString => EXPECTED_STRING,
+        ReportKind::InsufficientIndentInMultiLineString => INSUFFICIENT_INDENT_IN_MULTI_LINE_STRING,
+        ReportKind::EmptyParentheses => EMPTY_PARENTHESES,
+        ReportKind::UnfinishedParentheses => UNFINISHED_PARENTHESES,
+        ReportKind::UnfinishedList => UNFINISHED_LIST,
+        ReportKind::OldStyleRecordBuilderInModuleParams => {
+            OLD_STYLE_RECORD_BUILDER_IN_MODULE_PARAMS
+        }
+        ReportKind::RecordUpdateInModuleParams => RECORD_UPDATE_IN_MODULE_PARAMS,
+        ReportKind::RecordBuilderInModuleParams => RECORD_BUILDER_IN_MODULE_PARAMS,
+        ReportKind::LowercaseAlias => LOWERCASE_ALIAS,
+        ReportKind::WeirdExposing => WEIRD_EXPOSING,
+        ReportKind::UnfinishedImport => UNFINISHED_IMPORT,
+        ReportKind::UnfinishedIf => UNFINISHED_IF,
+        ReportKind::IfGuardNoCondition => IF_GUARD_NO_CONDITION,
+        ReportKind::UnfinishedWhen => UNFINISHED_WHEN,
+        ReportKind::UnexpectedArrow => UNEXPECTED_ARROW,
+        ReportKind::UnfinishedPattern => UNFINISHED_PATTERN,
+        ReportKind::UnfinishedRecordPattern => UNFINISHED_RECORD_PATTERN,
+        ReportKind::ProblemInRecordPattern => PROBLEM_IN_RECORD_PATTERN,
+        ReportKind::UnfinishedListPattern => UNFINISHED_LIST_PATTERN,
+        ReportKind::IncorrectRestPattern => INCORRECT_REST_PATTERN,
+        ReportKind::InvalidNumberLiteral => INVALID_NUMBER_LITERAL,
+        ReportKind::DoubleComma => DOUBLE_COMMA,
+        ReportKind::UnfinishedType => UNFINISHED_TYPE,
+        ReportKind::UnfinishedInlineAlias => UNFINISHED_INLINE_ALIAS,
+        ReportKind::BadTypeVariable => BAD_TYPE_VARIABLE,
+        ReportKind::UnfinishedRecordType => UNFINISHED_RECORD_TYPE,
+        ReportKind::ProblemInRecordType => PROBLEM_IN_RECORD_TYPE,
+        ReportKind::NeedMoreIndentation => NEED_MORE_INDENTATION,
+        ReportKind::UnfinishedTagUnionType => UNFINISHED_TAG_UNION_TYPE,
+        ReportKind::WeirdTagName => WEIRD_TAG_NAME,
+        ReportKind::DoubleDot => DOUBLE_DOT,
+        ReportKind::TrailingDot => TRAILING_DOT,
+        ReportKind::WeirdQualifiedName => WEIRD_QUALIFIED_NAME,
+        ReportKind::EndOfFile => END_OF_FILE,
+        ReportKind::NotAnInlineAlias => NOT_AN_INLINE_ALIAS,
+        ReportKind::QualifiedAliasName => QUALIFIED_ALIAS_NAME,
+        ReportKind::TypeArgumentNotLowercase => TYPE_ARGUMENT_NOT_LOWERCASE,
+        ReportKind::IncompleteHeader => INCOMPLETE_HEADER,
+        ReportKind::MissingHeader => MISSING_HEADER,
+        ReportKind::WeirdModuleName => WEIRD_MODULE_NAME,
+        ReportKind::WeirdAppName => WEIRD_APP_NAME,
+        ReportKind::InvalidPackageName => INVALID_PACKAGE_NAME,
+        ReportKind::InvalidPlatformName => INVALID_PLATFORM_NAME,
+        ReportKind::WeirdGeneratedTypeName => WEIRD_GENERATED_TYPE_NAME,
+        ReportKind::WeirdGenerates => WEIRD_GENERATES,
+        ReportKind::WeirdProvides => WEIRD_PROVIDES,
+        ReportKind::WeirdModuleParams => WEIRD_MODULE_PARAMS,
+        ReportKind::WeirdExposes => WEIRD_EXPOSES,
+        ReportKind::WeirdImports => WEIRD_IMPORTS,
+        ReportKind::MissingRequires => MISSING_REQUIRES,
+        ReportKind::BadRequiresRigids => BAD_REQUIRES_RIGIDS,
+        ReportKind::BadRequires => BAD_REQUIRES,
+        ReportKind::MissingPackages => MISSING_PACKAGES,
+        ReportKind::WeirdPackagesList => WEIRD_PACKAGES_LIST,
+        ReportKind::TabCharacter => TAB_CHARACTER,
+        ReportKind::AsciiControlCharacter => ASCII_CONTROL_CHARACTER,
+        ReportKind::MisplacedCarriageReturn => MISPLACED_CARRIAGE_RETURN,
+        ReportKind::UnfinishedAbility => UNFINISHED_ABILITY,
+        ReportKind::FileNotFound => FILE_NOT_FOUND,
+        ReportKind::FilePermissionDenied => FILE_PERMISSION_DENIED,
+        ReportKind::NotARocFile => NOT_A_ROC_FILE,
+        ReportKind::FileProblem => FILE_PROBLEM,
+        ReportKind::FileTooLarge => FILE_TOO_LARGE,
+        ReportKind::ImportCycle => IMPORT_CYCLE,
+        ReportKind::IncorrectModuleName => INCORRECT_MODULE_NAME,
+        ReportKind::UnspecifiedPlatform => UNSPECIFIED_PLATFORM,
+        ReportKind::MultiplePlatforms => MULTIPLE_PLATFORMS,
+        ReportKind::NoPlatform => NO_PLATFORM,
+        ReportKind::UnrecognizedPackage => UNRECOGNIZED_PACKAGE,
+        ReportKind::UnsupportedEncoding => UNSUPPORTED_ENCODING,
+        ReportKind::MultipleEncodings => MULTIPLE_ENCODINGS,
+        ReportKind::InvalidContentHash => INVALID_CONTENT_HASH,
+        ReportKind::IoError => IO_ERROR,
+        ReportKind::HttpError => HTTP_ERROR,
+        ReportKind::InvalidExtensionSuffix => INVALID_EXTENSION_SUFFIX,
+        ReportKind::InvalidExtension => INVALID_EXTENSION,
+        ReportKind::InvalidFragment => INVALID_FRAGMENT,
+        ReportKind::MissingPackageHash => MISSING_PACKAGE_HASH,
+        ReportKind::HttpsMandatory => HTTPS_MANDATORY,
+        ReportKind::MisleadingCharacters => MISLEADING_CHARACTERS,
+        ReportKind::NotYetImplemented => NOT_YET_IMPLEMENTED,
+        ReportKind::CodeGenFailed => CODE_GEN_FAILED,
+        ReportKind::ExpectFailed => EXPECT_FAILED,
+        ReportKind::ExpectPanicked => EXPECT_PANICKED,
+        ReportKind::InvalidDocsLink => INVALID_DOCS_LINK,
+    };
+
+    Some(text)
+}
+
+const TYPE_MISMATCH: &str = r#"
+A value was used somewhere that needs a different type.
+
+For example, `Num.toStr` takes a number, so giving it a string is a type mismatch:
+
+    greeting = Num.toStr "hello"
+
+The report shows the value, the type it has, and the type that was needed where it was
+used. Usually either the value is wrong, or it was passed to the wrong function. Sometimes the
+type annotation is the thing that's wrong:
+
+    count : Str
+    count = 42
+
+Here either the annotation should say `Num *`, or the value should be a string.
+"#;
+
+const CIRCULAR_TYPE: &str = r#"
+A value would need a type that contains itself, which no type can.
+
+This usually comes from passing a function to itself:
+
+    f = \g -> g g
+
+Here `g` takes itself as an argument, so its type would have to be `(... -> a) -> a` with
+no end to the `...`. The report writes the repeating part as ∞. Check whether a function is
+being passed where the result of calling it was meant to be.
+"#;
+
+const CYCLIC_ALIAS: &str = r#"
+A type alias is defined in terms of itself in a way that would make it infinitely big:
+
+    Foo : { x : Foo }
+
+Aliases can only refer to themselves inside a tag union, and at least one of the tags has to
+stop the recursion:
+
+    Foo : [Node { x : Foo }, Leaf]
+"#;
+
+const TOO_MANY_ARGS: &str = r#"
+A function was called with more arguments than it takes:
+
+    add = \a, b -> a + b
+
+    add 1 2 3
+
+This often means two calls were meant to be separated by a pipe or by parentheses, like
+`add 1 (double 2)` instead of `add 1 double 2`.
+"#;
+
+const TOO_FEW_ARGS: &str = r#"
+A function was called with fewer arguments than it takes:
+
+    add = \a, b -> a + b
+
+    add 1
+
+Roc functions can't be partially applied, so every call has to pass all of the arguments. To
+pass the rest later, wrap the call in a function that takes them:
+
+    addOne = \b -> add 1 b
+"#;
+
+const UNSAFE_PATTERN: &str = r#"
+A `when` doesn't handle every possible value, so the program would crash on the ones it misses:
+
+    color : [Red, Green, Blue]
+
+    when color is
+        Red -> "red"
+        Green -> "green"
+
+The report lists the patterns that are missing. Add a branch for each of them, or a final
+`_ -> ...` branch that handles everything else.
+"#;
+
+const REDUNDANT_PATTERN: &str = r#"
+A branch of a `when` can never match, because the branches before it already handle every
+value it would:
+
+    when number is
+        _ -> "anything"
+        0 -> "zero"
+
+Branches are tried from top to bottom, so the `0` branch is never reached. Either remove it,
+or move it above the branch that covers it.
+"#;
+
+const UNMATCHABLE_PATTERN: &str = r#"
+A branch of a `when` matches values that can't exist, so it can never run:
+
+    result : Result Str []
+    result = Ok "done"
+
+    when result is
+        Ok str -> str
+        Err _ -> "failed"
+
+No value has the type `[]`, so `result` can never be an `Err`. The branch can be removed.
+"#;
+
+const INCOMPLETE_ABILITY_IMPLEMENTATION: &str = r#"
+A type says it implements an ability, but doesn't give an implementation for every one of
+the ability's members:
+
+    MEq implements
+        eq : a, a -> Bool where a implements MEq
+        le : a, a -> Bool where a implements MEq
+
+    Id := U64 implements [MEq {eq}]
+
+    eq = \@Id m, @Id n -> m == n
+
+Here `le` is missing. Add an implementation for it, like `MEq {eq, le}`, or derive the
+ability if it's a builtin one that Roc can derive for you.
+"#;
+
+const ILLEGAL_SPECIALIZATION: &str = r#"
+An implementation of an ability member is for a type that isn't opaque. Only opaque types,
+the ones defined with `:=`, can implement abilities with their own functions. Structural
+types like records and tag unions are the same type wherever they're written, so they can't
+have implementations of their own.
+
+Make sure the implementation takes the opaque type, unwrapping it with `@` where it needs
+the value inside:
+
+    Id := U64 implements [MHash { hash: hashId }]
+
+    hashId = \@Id n -> n
+"#;
+
+const WRONG_SPECIALIZATION_TYPE: &str = r#"
+An implementation of an ability member is listed for one opaque type, but its type says it's
+for another:
+
+    A := U64 implements [MHash { hash: hashA }]
+    B := U64
+
+    hashA = \@B n -> n
+
+Here `hashA` is listed as the implementation for `A`, but it takes a `B`. Change the
+implementation to use the opaque type it's listed for.
+"#;
+
+const INVALID_UTF8: &str = r#"
+A file was imported as a `Str`, but its contents aren't valid UTF-8:
+
+    import "logo.png" as logo : Str
+
+Roc strings are always UTF-8. If the file holds binary data, import it as bytes instead:
+
+    import "logo.png" as logo : List U8
+"#;
+
+const INVALID_TYPE_FOR_INGESTED_FILE: &str = r#"
+A file is imported with a type other than `Str` or `List U8`:
+
+    import "users.json" as users : List { name : Str }
+
+Imported files can only be read as text (`Str`) or as raw bytes (`List U8`). To get
+structured data out of a file, import its contents and decode them.
+"#;
+
+const UNRECOGNIZED_NAME: &str = r#"
+A name is used that isn't defined anywhere Roc can see:
+
+    main = greting
+
+The report suggests similar names that are in scope. Most of the time the name is
+misspelled, defined further into a nested scope than where it's used, or comes from a
+module that needs to be imported.
+"#;
+
+const NAMING_PROBLEM: &str = r#"
+A type annotation is followed by a definition of a different name:
+
+    main : Str
+    mian = "Hello"
+
+An annotation applies to the definition right below it, so both need the same name. If they
+aren't meant to go together, put a blank line or a comment between them.
+
+This report is also used for patterns that aren't valid names, like `user.name` on the left
+side of an `=`.
+"#;
+
+const UNUSED_DEFINITION: &str = r#"
+A definition is never used. This is only a warning, but it often means that something else
+was used by mistake:
+
+    main =
+        greeting = "Hello"
+        "Hi"
+
+Either use the definition, or remove it.
+"#;
+
+const UNUSED_IMPORT: &str = r#"
+A module is imported but nothing from it is used:
+
+    import pf.Stdout
+
+    main = ""
+
+This is only a warning. Remove the import, or use something from the module.
+"#;
+
+const IMPORT_NAME_CONFLICT: &str = r#"
+Two imports, or an import and the current module, would be known by the same name:
+
+    import json.Decode
+    import xml.Decode
+
+Then `Decode.field` could mean either module. Give one of them a different name with `as`:
+
+    import xml.Decode as XmlDecode
+"#;
+
+const EXPLICIT_BUILTIN_IMPORT: &str = r#"
+A builtin module, or a type from one, is imported explicitly:
+
+    import List
+    import Dict exposing [Dict]
+
+Builtin modules like `List`, `Str` and `Dict`, and the types they define, are always
+available. This is only a warning; the import can be removed.
+"#;
+
+const UNUSED_TYPE_ALIAS_PARAMETER: &str = r#"
+A type alias or opaque type has a type parameter that its definition doesn't use:
+
+    Pair a b : { first : a, second : a }
+
+Every parameter has to appear in the definition, otherwise nothing would say what it stands
+for. Use the parameter, or remove it.
+"#;
+
+const UNDECLARED_TYPE_VARIABLE: &str = r#"
+A type alias or opaque type uses a type variable it doesn't declare:
+
+    Box : { value : a }
+
+The type variables of an alias are listed before its `:` (or `:=`), so that every use of the
+alias says what they are:
+
+    Box a : { value : a }
+"#;
+
+const WILDCARD_NOT_ALLOWED_HERE: &str = r#"
+A type alias or opaque type uses a wildcard (`*`) type variable:
+
+    Items : List *
+
+An alias has to say exactly what it stands for, so wildcards aren't allowed in its
+definition. Use a named type variable, and declare it:
+
+    Items a : List a
+"#;
+
+const UNDERSCORE_NOT_ALLOWED_HERE: &str = r#"
+A type alias or opaque type uses an inferred (`_`) type:
+
+    Items : List _
+
+Roc can infer the types of values, but not what a type alias stands for. Write the type out,
+or use a type variable:
+
+    Items a : List a
+"#;
+
+const UNUSED_ARGUMENT: &str = r#"
+A function takes an argument it never uses:
+
+    greet = \name -> "Hello!"
+
+This is only a warning. If nothing needs to pass the argument, remove it. If the function
+has to take it, for example because it's passed to `List.map`, start the argument's name
+with an underscore to say that it's unused on purpose:
+
+    greet = \_name -> "Hello!"
+"#;
+
+const MISSING_DEFINITION: &str = r#"
+A module exposes a name that it doesn't define:
+
+    module [parse, render]
+
+    parse = \str -> ...
+
+Add a definition for `render`, or remove it from the list of exposed names.
+"#;
+
+const UNKNOWN_GENERATES_FUNCTION: &str = r#"
+A hosted module asks Roc to generate a function that Roc doesn't know how to generate. Only
+specific functions, like `after` and `map`, can be generated. Remove the function from the
+`generates ... with` list, or define it yourself.
+"#;
+
+const DUPLICATE_FIELD_NAME: &str = r#"
+A record, or a record type, has the same field twice:
+
+    user = { name: "Sam", age: 42, name: "Alex" }
+
+Only the last one would be used, so remove the others.
+"#;
+
+const DUPLICATE_TAG_NAME: &str = r#"
+A tag union type has the same tag twice:
+
+    Color : [Red, Green, Red]
+
+Only the last one would be used. Remove the others, or if they are meant to be different
+tags, give them different names.
+"#;
+
+const INVALID_UNICODE: &str = r#"
+A unicode escape in a string doesn't name a valid code point:
+
+    bad = "\u(110000)"
+
+The escape takes a hexadecimal number, like `\u(00E9)` for é. It can be at most `10FFFF`,
+and can't be a surrogate (`D800` to `DFFF`), since those aren't characters on their own.
+"#;
+
+const CIRCULAR_DEFINITION: &str = r#"
+A value is defined in terms of itself, either directly or through other definitions:
+
+    foo = bar
+
+    bar = foo
+
+Roc evaluates values strictly, so computing `foo` would never finish. If the value is
+supposed to be computed later, make it a function:
+
+    foo = \{} -> bar {}
+"#;
+
+const DUPLICATE_NAME: &str = r#"
+The same name is defined twice in the same scope:
+
+    main = 1
+
+    main = \n -> n + 2
+
+Roc doesn't allow shadowing, because it's easy to use the wrong one of the two by accident.
+Give one of them a different name.
+"#;
+
+const NOT_EXPOSED: &str = r#"
+A module doesn't expose the name that was used:
+
+    List.isempty [1, 2]
+
+Only the names in a module's exposes list can be used from other modules. The report
+suggests names the module does expose. Here the name is spelled `List.isEmpty`.
+"#;
+
+const MODULE_NOT_IMPORTED: &str = r#"
+A name is qualified with a module that isn't imported:
+
+    main = Json.encode {}
+
+Import the module at the top of the file first:
+
+    import Json
+
+If the module is imported under a different name with `as`, use that name instead.
+"#;
+
+const INGESTED_FILE_ERROR: &str = r#"
+A file imported into a module couldn't be read:
+
+    import "data.txt" as data : Str
+
+The path is relative to the module that imports it. The report says why the file couldn't be
+read; usually it doesn't exist, or can't be read with the current permissions.
+"#;
+
+const NESTED_DATATYPE: &str = r#"
+A recursive type refers to itself with different type arguments than it's defined with:
+
+    Nested a : [Chain a (Nested (List a)), Term]
+
+Roc only supports recursive types whose recursive uses have exactly the same arguments as
+the definition, here `Nested a`. Change the definition so that it does.
+"#;
+
+const CONFLICTING_NUMBER_SUFFIX: &str = r#"
+A number literal has a suffix of the wrong kind:
+
+    half = 0.5u8
+    mask = 0b101f64
+
+Integer suffixes like `u8` and `i64` can't go on numbers with a decimal point, and float
+suffixes like `f64` can't go on hexadecimal, octal or binary numbers. Use a suffix of the
+right kind, or write the number differently, like `5f64`.
+"#;
+
+const NUMBER_OVERFLOWS_SUFFIX: &str = r#"
+A number literal is too big for the type its suffix asks for:
+
+    tooBig = 300u8
+
+A `U8` holds values from 0 to 255. Either use a smaller number, or a suffix for a wider
+type like `u16`.
+"#;
+
+const NUMBER_UNDERFLOWS_SUFFIX: &str = r#"
+A number literal is too small for the type its suffix asks for:
+
+    tooSmall = -129i8
+
+An `I8` holds values from -128 to 127, and unsigned types like `U8` can't hold negative
+numbers at all. Either use a number in range, or a suffix for a wider type like `i16`.
+"#;
+
+const OPAQUE_TYPE_NOT_DEFINED: &str = r#"
+An opaque type is wrapped or unwrapped with `@`, but this module doesn't define an opaque
+type with that name:
+
+    age = @Age 42
+
+Define it with `:=` first:
+
+    Age := U8
+
+A type alias (defined with `:`) can't be used with `@`. Make it an opaque type instead.
+"#;
+
+const OPAQUE_TYPE_DECLARED_OUTSIDE_SCOPE: &str = r#"
+An opaque type from another module is wrapped or unwrapped with `@`:
+
+    import Age exposing [Age]
+
+    age = @Age 42
+
+Only the module that defines an opaque type can see inside it. Use the functions that module
+exposes to create and read values of the type.
+"#;
+
+const OPAQUE_TYPE_NOT_APPLIED: &str = r#"
+An opaque type is used with `@`, but isn't given a value to wrap:
+
+    Age := U8
+
+    toAge = @Age
+
+`@Age` isn't a function, so it can't be used on its own. Wrap a value right away, or write a
+function that does:
+
+    toAge = \n -> @Age n
+"#;
+
+const OPAQUE_TYPE_APPLIED_TO_TOO_MANY_ARGS: &str = r#"
+An opaque type is given more than one value to wrap:
+
+    Point := (I64, I64)
+
+    origin = @Point 0 0
+
+An opaque type always wraps exactly one value. Put several values in a tuple or a record:
+
+    origin = @Point (0, 0)
+"#;
+
+const INVALID_EXTENSION_TYPE: &str = r#"
+A record or tag union type is extended with a type that isn't a record or tag union:
+
+    Person : { name : Str }Str
+
+A record can only be extended with a type variable or another record, and a tag union with
+a type variable or another tag union:
+
+    Person a : { name : Str }a
+"#;
+
+const ABILITY_HAS_TYPE_VARIABLES: &str = r#"
+An ability is defined with type variables:
+
+    MHash a implements
+        hash : a -> U64 where a implements MHash
+
+Abilities don't take type variables; their members do. Remove them from the ability's name:
+
+    MHash implements
+        hash : a -> U64 where a implements MHash
+"#;
+
+const IMPLEMENTS_CLAUSE_IS_NOT_AN_ABILITY: &str = r#"
+An `implements` clause names a type that isn't an ability:
+
+    describe : a -> Str where a implements Str
+
+Only abilities, like `Eq`, `Hash` or `Inspect`, can come after `implements` in a type
+annotation.
+"#;
+
+const ILLEGAL_IMPLEMENTS_CLAUSE: &str = r#"
+An `implements` clause is nested inside a type annotation:
+
+    isEqual : (a -> Bool where a implements Eq) -> Bool
+
+`where ... implements` can only go at the end of a whole top-level annotation:
+
+    isEqual : (a -> Bool) -> Bool where a implements Eq
+"#;
+
+const ABILITY_MEMBER_MISSING_IMPLEMENTS_CLAUSE: &str = r#"
+A member of an ability doesn't say which of its type variables is the type implementing the
+ability:
+
+    MHash implements
+        hash : a -> U64
+
+Every member binds a type variable to its ability with a `where` clause:
+
+    MHash implements
+        hash : a -> U64 where a implements MHash
+
+If a function doesn't involve the implementing type, it doesn't need to be part of the
+ability.
+"#;
+
+const ABILITY_MEMBER_BINDS_MULTIPLE_VARIABLES: &str = r#"
+A member of an ability binds more than one type variable to its ability:
+
+    MEq implements
+        eq : a, b -> Bool where a implements MEq, b implements MEq
+
+Each member can only bind one type variable to the ability, so that Roc can tell from the
+types which implementation to use. Use the same variable for all of them:
+
+    MEq implements
+        eq : a, a -> Bool where a implements MEq
+"#;
+
+const ABILITY_NOT_ON_TOP_LEVEL: &str = r#"
+An ability is defined inside another definition:
+
+    main =
+        MHash implements
+            hash : a -> U64 where a implements MHash
+
+        ...
+
+Abilities can only be defined at the top level of a module. Move the definition there.
+"#;
+
+const SPECIALIZATION_NOT_ON_TOP_LEVEL: &str = r#"
+An implementation of an ability member is defined inside another definition. Implementations
+have to be defined at the top level of the module that defines the opaque type they're for.
+Move the definition there.
+"#;
+
+const ABILITY_USED_AS_TYPE: &str = r#"
+An ability is used as if it were a type:
+
+    hashAll : List MHash -> U64
+
+Abilities describe what a type can do, but they aren't types themselves. Use a type
+variable, and say that it implements the ability:
+
+    hashAll : List a -> U64 where a implements MHash
+"#;
+
+const ILLEGAL_DERIVE: &str = r#"
+An opaque type asks Roc to derive an ability that can't be derived:
+
+    Id := U64 implements [MHash]
+
+Only the builtin abilities can be derived. For other abilities, list the implementation of
+each member:
+
+    Id := U64 implements [MHash { hash: hashId }]
+"#;
+
+const IMPLEMENTATION_NOT_FOUND: &str = r#"
+An ability implementation names a value that doesn't exist:
+
+    Id := U64 implements [MHash { hash }]
+
+`{ hash }` is short for `{ hash: hash }`, so there has to be a value named `hash` in scope.
+Define it, or name the value that implements the member:
+
+    Id := U64 implements [MHash { hash: hashId }]
+"#;
+
+const NOT_AN_ABILITY_MEMBER: &str = r#"
+An opaque type lists an implementation for something that isn't a member of the ability:
+
+    Id := U64 implements [MEq { eq: eqId, notEq: notEqId }]
+
+Only the ability's own members can be listed. Remove the others, or check their spelling.
+"#;
+
+const NOT_AN_ABILITY: &str = r#"
+An opaque type says it implements something that isn't an ability:
+
+    Id := U64 implements [Equality]
+
+Check the spelling, and that the ability is defined in this module or imported into it.
+"#;
+
+const OPTIONAL_ABILITY_IMPLEMENTATION: &str = r#"
+An ability implementation is given as an optional field:
+
+    Id := U64 implements [MEq { eq ? eqId }]
+
+Implementations always have to be given, so use `:` instead of `?`. To have Roc derive a
+builtin ability, leave out the implementations altogether, like `implements [Eq]`.
+"#;
+
+const QUALIFIED_ABILITY_IMPLEMENTATION: &str = r#"
+An ability implementation is a value from another module:
+
+    Id := U64 implements [MEq { eq: Helpers.eqId }]
+
+Implementations have to be defined in the same module as the opaque type, and named without
+a module name. Define the implementation in this module.
+"#;
+
+const ABILITY_IMPLEMENTATION_NOT_IDENTIFIER: &str = r#"
+An ability implementation is an expression, rather than the name of a value:
+
+    Id := U64 implements [MEq { eq: \@Id a, @Id b -> a == b }]
+
+Only names can go there. Define the function separately, and use its name:
+
+    Id := U64 implements [MEq { eq: eqId }]
+
+    eqId = \@Id a, @Id b -> a == b
+"#;
+
+const DUPLICATE_IMPLEMENTATION: &str = r#"
+An ability member is implemented twice for the same type:
+
+    Id := U64 implements [MEq { eq: eqId, eq: otherEqId }]
+
+Each member has exactly one implementation per type. Remove one of them.
+"#;
+
+const UNNECESSARY_IMPLEMENTATIONS: &str = r#"
+A type lists implementations for members that aren't part of the ability it implements:
+
+    Id := U64 implements [MEq { eq: eqId, hash: hashId }]
+
+Remove the ones the ability doesn't have. If they belong to another ability, list that
+ability separately, like `implements [MEq { eq: eqId }, MHash { hash: hashId }]`.
+"#;
+
+const DEFINITION_ONLY_USED_IN_RECURSION: &str = r#"
+A definition is only used by itself, or a group of definitions only use each other, so
+nothing else in the program uses them:
+
+    countdown = \n -> if n == 0 then 0 else countdown (n - 1)
+
+This is only a warning. Remove the definitions if they aren't needed, or use or expose them
+if they are.
+"#;
+
+const DUPLICATE_BOUND_ABILITY: &str = r#"
+A `where` clause binds a type variable to the same ability twice:
+
+    describe : a -> Str where a implements Inspect & Inspect
+
+Remove the repeated ability.
+"#;
+
+const NAME_NOT_BOUND_IN_ALL_PATTERNS: &str = r#"
+A branch of a `when` with several patterns uses a name that only some of them define:
+
+    when shape is
+        Circle radius | Square side -> radius
+
+If `shape` were a `Square`, there would be no `radius`. Every pattern of a branch has to
+define the same names, so either split the branch in two, or use the same name in each:
+
+    when shape is
+        Circle size | Square size -> size
+"#;
+
+const UNNECESSARY_DEFINITION: &str = r#"
+A definition doesn't introduce any new names, so it can't affect the program:
+
+    Pair _ _ = Pair 0 1
+
+Since Roc is purely functional, a definition that binds no names has no effect. Remove it,
+or give the value a name if it was meant to be used.
+"#;
+
+const OVERLOADED_SPECIALIZATION: &str = r#"
+The same function is used to implement an ability member for two different opaque types:
+
+    A := U64 implements [MHash { hash: hashU64 }]
+    B := U64 implements [MHash { hash: hashU64 }]
+
+Every opaque type is a different type, so each one needs its own implementation. Define a
+separate function for each type.
+"#;
+
+const UNNECESSARY_WILDCARD: &str = r#"
+A tag union type ends in a `*` that doesn't change what it means:
+
+    color : [Red, Green]*
+    color = Red
+
+For constants and for what functions return, `[Red, Green]*` and `[Red, Green]` mean the
+same thing, so the `*` can be removed. (It does make a difference on function arguments.)
+"#;
+
+const MULTIPLE_LIST_REST_PATTERNS: &str = r#"
+A list pattern has more than one `..`:
+
+    when list is
+        [first, .., middle, .., last] -> middle
+        _ -> 0
+
+A list pattern can have at most one rest pattern. Remove the others.
+"#;
+
+const TOO_MANY_TYPE_ARGUMENTS: &str = r#"
+A type alias or opaque type is given more type arguments than it takes:
+
+    Pair a : (a, a)
+
+    origin : Pair I64 I64
+
+Give it one argument for each of its type variables. Often this is a missing pair of
+parentheses, like `List List Str` instead of `List (List Str)`.
+"#;
+
+const TOO_FEW_TYPE_ARGUMENTS: &str = r#"
+A type alias or opaque type is given fewer type arguments than it takes:
+
+    Pair a b : (a, b)
+
+    origin : Pair I64
+
+Give it one argument for each of its type variables, like `Pair I64 I64`.
+"#;
+
+const UNAPPLIED_CRASH: &str = r#"
+`crash` is used without a message:
+
+    fail = crash
+
+`crash` isn't a function, so it can't be stored or passed around. It has to be given a
+message right where it's used:
+
+    fail = \{} -> crash "This should never happen"
+"#;
+
+const OVERAPPLIED_CRASH: &str = r#"
+`crash` is given more than one value:
+
+    crash "Failed with code" code
+
+It takes exactly one message, which has to be a `Str`. Build the message first:
+
+    crash "Failed with code $(Num.toStr code)"
+"#;
+
+const BAD_OPTIONAL_VALUE: &str = r#"
+A record uses an optional value (`?`) outside of destructuring:
+
+    config = { verbose ? Bool.false }
+
+Default values with `?` only work when taking a record apart, like in a function argument:
+
+    run = \{ verbose ? Bool.false } -> ...
+
+To build a record, use `:`.
+"#;
+
+const DEGENERATE_BRANCH: &str = r#"
+A branch of a `when` uses a name that not all of its patterns define, so the branch can't
+run:
+
+    when shape is
+        Circle radius | Square side -> radius
+
+This is reported along with E150, which describes how to fix it.
+"#;
+
+const MULTIPLE_OLD_STYLE_RECORD_BUILDERS: &str = r#"
+A function is given more than one old-style record builder:
+
+    combine { a: <- parseA } { b: <- parseB }
+
+A function can take at most one. Merge them into a single record builder, or apply them
+separately.
+"#;
+
+const UNAPPLIED_OLD_STYLE_RECORD_BUILDER: &str = r#"
+An old-style record builder isn't passed straight to a function:
+
+    { a: <- parseA, b: <- parseB } |> combine
+
+The builder has to be an argument of a function call written out right there. The pipe
+operator can't be used:
+
+    combine { a: <- parseA, b: <- parseB }
+"#;
+
+const EMPTY_RECORD_BUILDER: &str = r#"
+A record builder has no fields:
+
+    { combine <- }
+
+A record builder combines the values of its fields, so it needs at least two of them. If
+there's nothing to combine, don't use a record builder.
+"#;
+
+const NOT_ENOUGH_FIELDS_IN_RECORD_BUILDER: &str = r#"
+A record builder has only one field:
+
+    { combine <- name: parseName }
+
+With one field there's nothing to combine. A record builder needs at least two fields; with
+one, use the value directly, mapping it into a record if needed.
+"#;
+
+const OPTIONAL_FIELD_IN_RECORD_BUILDER: &str = r#"
+A record builder has an optional field:
+
+    { combine <- name ? parseName, age: parseAge }
+
+A record builder needs a value for every field, so use `:` instead of `?`.
+"#;
+
+const SYNTAX_PROBLEM: &str = r#"
+Some code isn't written in a way Roc understands, and there's no more specific report for
+it. Common causes are operators that need parentheses to say how they group:
+
+    isValid = a == b == c
+
+and patterns in places that only accept names, like a tag on the left of a definition:
+
+    Ok value = parse input
+
+The report points at the problem. Use parentheses to group operators, and a `when` to take
+apart values that could have other shapes.
+"#;
+
+const PARSE_PROBLEM: &str = r#"
+The parser got stuck, and doesn't have a more specific explanation for why. The report shows
+where it stopped. The problem is usually right there or just before it, like a bracket that
+was never closed, or a line that's indented less than the code it belongs to.
+"#;
+
+const NOT_END_OF_FILE: &str = r#"
+The parser found something it didn't expect at the top level of a module, where only
+definitions can go:
+
+    main = "Hello")
+
+Usually this is a stray closing bracket, or a line that's indented as if it started a new
+definition.
+"#;
+
+const ARGUMENTS_BEFORE_EQUALS: &str = r#"
+A definition has arguments before its `=`, the way functions are written in some other
+languages:
+
+    increment n = n + 1
+
+In Roc, functions are values, written as lambdas:
+
+    increment = \n -> n + 1
+"#;
+
+const UNKNOWN_OPERATOR: &str = r#"
+An operator isn't one that Roc has:
+
+    total = count += 1
+
+Roc doesn't have operators like `+=`, `===` or `++`. The report suggests what to use instead
+when there's an equivalent, like `Str.concat` or `List.concat` for `++`.
+"#;
+
+const WEIRD_IDENTIFIER: &str = r#"
+A tag is qualified with a module name:
+
+    result = Result.Err "oops"
+
+Tags aren't defined in modules, so they're never qualified. Write the tag on its own, like
+`Err "oops"`. Qualified names look like `Str.concat`: a module name followed by a lowercase
+name.
+"#;
+
+const MISSING_EXPRESSION: &str = r#"
+An expression was expected, but there isn't one:
+
+    total =
+
+    average = total / 2
+
+Something has to come after every `=`, `->` and operator. Add the missing value.
+"#;
+
+const MISSING_FINAL_EXPRESSION: &str = r#"
+A block of definitions doesn't end in an expression:
+
+    main =
+        greeting = "Hello"
+
+The value of a block is the expression at its end, so after the definitions there has to be
+one:
+
+    main =
+        greeting = "Hello"
+        greeting
+"#;
+
+const BAD_BACKPASSING_ARROW: &str = r#"
+A `<-` is used where it can't be:
+
+    count <- 5
+
+`<-` is for backpassing, where the right side is a function call that takes a callback as
+its last argument:
+
+    text <- Task.await readFile
+
+To define a value, use `=`. To define a function, write a lambda like `\n -> n + 1`.
+"#;
+
+const BAD_OLD_STYLE_RECORD_BUILDER: &str = r#"
+An old-style record builder has an optional field:
+
+    combine { name ? parseName, age: <- parseAge }
+
+Optional fields with `?` only work when taking a record apart. Give the field a value with
+`:`, or build it with `: <-`.
+"#;
+
+const BAD_RECORD_UPDATE: &str = r#"
+A record update sets a field with an old-style record builder:
+
+    { config & port: <- parsePort }
+
+Record updates can only set fields to values:
+
+    { config & port: 8080 }
+"#;
+
+const INDENT_ENDS_AFTER_EXPRESSION: &str = r#"
+A block ends where the parser expected more of it:
+
+    main =
+        expect 1 + 1 == 2
+
+`expect` and `dbg` on their own line, like definitions, have to be followed by the rest of
+the block, ending in an expression that gives the block its value:
+
+    main =
+        expect 1 + 1 == 2
+        "done"
+"#;
+
+const TRAILING_OPERATOR: &str = r#"
+An operator isn't followed by the value it needs on its right:
+
+    total = price *
+
+Add the missing value, or remove the operator.
+"#;
+
+const UNEXPECTED_COMMA: &str = r#"
+A comma is somewhere commas can't go:
+
+    total = add 1, 2
+
+Function arguments are separated by spaces, like `add 1 2`. Commas only separate the items
+of lists, records and tuples, and the arguments of lambdas like `\a, b -> a + b`.
+"#;
+
+const STATEMENT_AFTER_EXPRESSION: &str = r#"
+A block has more lines after the expression that ends it:
+
+    main =
+        greeting = "Hello"
+        greeting
+        name = "World"
+
+The last expression of a block is its value, so nothing can come after it. Move the extra
+lines above it, or indent them differently if they belong somewhere else.
+"#;
+
+const RECORD_PARSE_PROBLEM: &str = r#"
+A record couldn't be parsed:
+
+    user = { name: "Sam" age: 42 }
+
+Fields are written as `name: value`, separated by commas:
+
+    user = { name: "Sam", age: 42 }
+"#;
+
+const WEIRD_ARROW: &str = r#"
+The arguments of a lambda aren't followed by an `->`:
+
+    increment = \n => n + 1
+
+A lambda's arguments are separated by commas, and followed by `->` and the body:
+
+    increment = \n -> n + 1
+"#;
+
+const MISSING_ARROW: &str = r#"
+An `->` is missing, either after the arguments of a lambda, or after the pattern of a
+`when` branch:
+
+    when color is
+        Red "red"
+
+Add the arrow:
+
+    when color is
+        Red -> "red"
+"#;
+
+const UNFINISHED_ARGUMENT_LIST: &str = r#"
+The argument list of a lambda has a comma with no argument before it:
+
+    add = \, b -> b
+
+Add the missing argument, or remove the comma.
+"#;
+
+const UNFINISHED_FUNCTION: &str = r#"
+A lambda doesn't have a body after its `->`:
+
+    increment = \n ->
+
+Add the expression the function returns after the arrow.
+"#;
+
+const WEIRD_ESCAPE: &str = r#"
+A string has a backslash escape that Roc doesn't know:
+
+    path = "C:\Users"
+
+The escapes are `\n`, `\r`, `\t`, `\"`, `\\` and unicode code points like `\u(00E9)`. To put
+a backslash in a string, write `\\`.
+"#;
+
+const WEIRD_CODE_POINT: &str = r#"
+A unicode escape in a string isn't written the way Roc expects:
+
+    letter = "\u00E9"
+
+The code point is a hexadecimal number in parentheses:
+
+    letter = "\u(00E9)"
+"#;
+
+const ENDLESS_FORMAT: &str = r#"
+A string interpolation isn't closed:
+
+    message = "The count is $(count"
+
+Close it with a `)` before the end of the string:
+
+    message = "The count is $(count)"
+"#;
+
+const ENDLESS_SCALAR: &str = r#"
+A character literal isn't closed:
+
+    letter = 'a
+
+Close it with a single quote:
+
+    letter = 'a'
+"#;
+
+const INVALID_SCALAR: &str = r#"
+A character literal (in single quotes) isn't a single character:
+
+    letter = 'ab'
+
+A character literal holds exactly one unicode scalar value, and can't contain string
+interpolations. For text, use a string in double quotes:
+
+    letters = "ab"
+"#;
+
+const ENDLESS_STRING: &str = r#"
+A string isn't closed:
+
+    greeting = "Hello
+
+Close it with a double quote on the same line. For text across several lines, use a
+multi-line string in triple quotes (`"""`).
+"#;
+
+const EXPECTED_STRING: &str = r#"
+A string was expected, but there is a character literal in single quotes instead:
+
+    import 'data.txt' as data : Str
+
+Use double quotes:
+
+    import "data.txt" as data : Str
+"#;
+
+const INSUFFICIENT_INDENT_IN_MULTI_LINE_STRING: &str = r#"
+A line of a multi-line string is indented less than its opening `"""`:
+
+    text =
+        """
+      Hello
+        """
+
+Every line has to be indented at least as much as the opening quotes. That much indentation
+is removed from each line of the string when it's compiled.
+"#;
+
+const EMPTY_PARENTHESES: &str = r#"
+A pair of parentheses has nothing between them:
+
+    result = ()
+
+Roc doesn't have a `()` value. To mean "nothing", use the empty record `{}`.
+"#;
+
+const UNFINISHED_PARENTHESES: &str = r#"
+A pair of parentheses isn't closed, or doesn't have an expression, pattern or type inside:
+
+    total = (price * count
+
+Close the parentheses, or fill in what they hold:
+
+    total = (price * count)
+"#;
+
+const UNFINISHED_LIST: &str = r#"
+A list isn't closed, or has a comma with no item before it:
+
+    numbers = [1, , 3
+
+List items are separated by single commas, and the list ends with a `]`:
+
+    numbers = [1, 2, 3]
+"#;
+
+const OLD_STYLE_RECORD_BUILDER_IN_MODULE_PARAMS: &str = r#"
+The params of an import use an old-style record builder field:
+
+    import Menu { echo: <- getEcho }
+
+Module params have to be a plain record:
+
+    import Menu { echo }
+"#;
+
+const RECORD_UPDATE_IN_MODULE_PARAMS: &str = r#"
+The params of an import are a record update:
+
+    import Menu { defaults & echo }
+
+Module params have to be a plain record:
+
+    import Menu { echo, read: defaults.read }
+"#;
+
+const RECORD_BUILDER_IN_MODULE_PARAMS: &str = r#"
+The params of an import are a record builder:
+
+    import Menu { combine <- echo: getEcho, read: getRead }
+
+Module params have to be a plain record:
+
+    import Menu { echo, read }
+"#;
+
+const LOWERCASE_ALIAS: &str = r#"
+An import gives a module a lowercase name:
+
+    import Json.Decode as decode
+
+Module names always start with an uppercase letter, and so do the names they're imported
+as:
+
+    import Json.Decode as Decode
+"#;
+
+const WEIRD_EXPOSING: &str = r#"
+The `exposing` list of an import couldn't be parsed:
+
+    import Svg exposing Path
+
+It's a list in square brackets of the types and values to use without the module name:
+
+    import Svg exposing [Path, arc]
+"#;
+
+const UNFINISHED_IMPORT: &str = r#"
+An `import` isn't finished:
+
+    import pf.
+
+Imports look like one of these:
+
+    import Dict
+    import pf.Stdout exposing [line]
+    import "data.txt" as data : Str
+"#;
+
+const UNFINISHED_IF: &str = r#"
+An `if` is missing a part. It needs a condition, a `then` branch and an `else` branch:
+
+    sign = if n < 0 then "negative"
+
+Roc has no `if` without `else`, because every expression needs a value:
+
+    sign = if n < 0 then "negative" else "positive"
+"#;
+
+const IF_GUARD_NO_CONDITION: &str = r#"
+A `when` branch has an `if` guard with no condition:
+
+    when n is
+        x if -> "guarded"
+
+Add a condition after the `if`, or remove the `if`:
+
+    when n is
+        x if x > 0 -> "positive"
+"#;
+
+const UNFINISHED_WHEN: &str = r#"
+A `when` is missing a part:
+
+    when color
+        Red -> "red"
+
+A `when` has the value to match, the `is` keyword, and one or more branches, each a pattern
+followed by `->` and an expression:
+
+    when color is
+        Red -> "red"
+        _ -> "other"
+"#;
+
+const UNEXPECTED_ARROW: &str = r#"
+A `when` branch has an `->` where the parser didn't expect one:
+
+    when color is
+        Red -> "red"
+          Green -> "green"
+
+This usually means a branch is indented more or less than the others. Line up the patterns
+of all the branches.
+"#;
+
+const UNFINISHED_PATTERN: &str = r#"
+A pattern was expected, but there isn't one:
+
+    when color is
+        -> "none"
+
+Add the pattern, like a tag, a name or `_` to match anything.
+"#;
+
+const UNFINISHED_RECORD_PATTERN: &str = r#"
+A record pattern isn't finished, or uses a keyword as a field name:
+
+    { name, age = user
+
+Record patterns are field names separated by commas in curly braces, and can't use keywords
+like `if` or `when` as field names:
+
+    { name, age } = user
+"#;
+
+const PROBLEM_IN_RECORD_PATTERN: &str = r#"
+A record pattern has something other than a field where a field was expected:
+
+    { name, 42 } = user
+
+Each field of a record pattern is a name, optionally followed by `:` and a pattern, or `?`
+and a default value:
+
+    { name, age: userAge, nickname ? "" } = user
+"#;
+
+const UNFINISHED_LIST_PATTERN: &str = r#"
+A list pattern isn't finished:
+
+    when list is
+        [first, second -> first
+
+Close it with a `]`:
+
+    when list is
+        [first, second] -> first
+"#;
+
+const INCORRECT_REST_PATTERN: &str = r#"
+A list pattern uses a rest pattern that isn't written the way Roc expects:
+
+    when list is
+        [first, ...] -> first
+
+A rest pattern, which matches zero or more items, is written as two dots:
+
+    when list is
+        [first, ..] -> first
+"#;
+
+const INVALID_NUMBER_LITERAL: &str = r#"
+A number literal isn't written the way Roc expects:
+
+    size = 12kb
+
+Numbers can have underscores, a decimal point, an exponent like `1e6`, a `0x`, `0o` or `0b`
+prefix, and a type suffix like `u8` or `f64`.
+"#;
+
+const DOUBLE_COMMA: &str = r#"
+A function type has two commas in a row:
+
+    add : I64,, I64 -> I64
+
+Remove one of them.
+"#;
+
+const UNFINISHED_TYPE: &str = r#"
+A type was expected, but there isn't one:
+
+    count :
+
+Add the type, like `count : U64`.
+"#;
+
+const UNFINISHED_INLINE_ALIAS: &str = r#"
+An `as` in a type isn't followed by the name of the alias it introduces:
+
+    Tree : [Node (List Tree), Leaf] as
+
+After `as` comes an uppercase alias name, like `as Tree`, followed by its type variables if
+it has any.
+"#;
+
+const BAD_TYPE_VARIABLE: &str = r#"
+A type variable was expected, but the parser found something else:
+
+    Box 1 : { value : a }
+
+Type variables are lowercase names, like `a` or `item`.
+"#;
+
+const UNFINISHED_RECORD_TYPE: &str = r#"
+A record type isn't finished, or uses a keyword as a field name:
+
+    User : { name : Str, age : U8
+
+Record types are fields with their types in curly braces, separated by commas. Keywords like
+`if` or `when` can't be field names:
+
+    User : { name : Str, age : U8 }
+"#;
+
+const PROBLEM_IN_RECORD_TYPE: &str = r#"
+A record type has something other than a field where a field was expected:
+
+    User : { name : Str, U8 }
+
+Each field of a record type is a lowercase name, a `:` and a type:
+
+    User : { name : Str, age : U8 }
+"#;
+
+const NEED_MORE_INDENTATION: &str = r#"
+The closing bracket of a multi-line type is indented less than the type it belongs to:
+
+    main : {
+        name : Str,
+    }
+
+Indent the closing bracket at least as far as the start of the type.
+"#;
+
+const UNFINISHED_TAG_UNION_TYPE: &str = r#"
+A tag union type isn't finished, or has something other than a tag in it:
+
+    Color : [Red, Green
+
+Tag unions are tags separated by commas in square brackets:
+
+    Color : [Red, Green]
+"#;
+
+const WEIRD_TAG_NAME: &str = r#"
+A tag union type has something other than a tag where a tag was expected:
+
+    Color : [Red, green]
+
+Tags start with an uppercase letter.
+"#;
+
+const DOUBLE_DOT: &str = r#"
+A qualified type name has two dots in a row:
+
+    parse : Str -> Json..Value
+
+Remove one of them.
+"#;
+
+const TRAILING_DOT: &str = r#"
+A qualified type name ends in a dot:
+
+    parse : Str -> Json.
+
+Add the name of the type after the dot, like `Json.Value`.
+"#;
+
+const WEIRD_QUALIFIED_NAME: &str = r#"
+A part of a qualified type name doesn't start with an uppercase letter:
+
+    parse : Str -> Json.2
+
+Every part of a qualified type name is uppercase, like `Json.Value`.
+"#;
+
+const END_OF_FILE: &str = r#"
+The file ended in the middle of a qualified type name:
+
+    parse : Str -> Json.
+
+Finish the name, like `Json.Value`.
+"#;
+
+const NOT_AN_INLINE_ALIAS: &str = r#"
+The `as` in a type is followed by something that isn't an alias name:
+
+    Tree : [Node (List Tree), Leaf] as tree
+
+An inline alias is an uppercase name followed by zero or more type variables, like
+`as Tree` or `as Tree a`.
+"#;
+
+const QUALIFIED_ALIAS_NAME: &str = r#"
+An inline type alias has a qualified name:
+
+    Tree : [Node (List Tree), Leaf] as Trees.Tree
+
+An alias introduces a new name in the current module, so it can't have a module name in
+front of it. Use `as Tree`.
+"#;
+
+const TYPE_ARGUMENT_NOT_LOWERCASE: &str = r#"
+An inline type alias has a type argument that isn't lowercase:
+
+    Tree : [Node (List Tree), Leaf] as Tree A
+
+Type arguments are type variables, which are lowercase, like `as Tree a`.
+"#;
+
+const INCOMPLETE_HEADER: &str = r#"
+The header at the top of a module isn't finished:
+
+    app [main] {
+
+The report shows where it stopped. Often a bracket isn't closed, or the indentation is off.
+"#;
+
+const MISSING_HEADER: &str = r#"
+A file's header doesn't start with a keyword Roc knows:
+
+    application [main] { pf: platform "..." }
+
+Headers start with a keyword that says what kind of module the file is, like `app`,
+`module`, `package` or `platform`. This report is also given for files that aren't UTF-8
+encoded.
+"#;
+
+const WEIRD_MODULE_NAME: &str = r#"
+The name of a module isn't valid, or doesn't match the path of its file:
+
+    interface parser exposes [parse] imports []
+
+Module names start with an uppercase letter, like `Parser`, and match the file they're
+defined in: `Parser` goes in `Parser.roc`, and `Json.Decode` in `Json/Decode.roc`.
+"#;
+
+const WEIRD_APP_NAME: &str = r#"
+The name of an app in its header isn't written the way Roc expects:
+
+    app hello provides [main] to pf
+
+An app name is a string in double quotes, like `app "hello"`.
+"#;
+
+const INVALID_PACKAGE_NAME: &str = r#"
+The name of a package in its header isn't written the way Roc expects:
+
+    package json exposes [Json] packages {}
+
+A package name is a string in double quotes, like `package "json"`.
+"#;
+
+const INVALID_PLATFORM_NAME: &str = r#"
+The name of a platform in its header isn't written the way Roc expects:
+
+    platform cli requires {} { main : Task {} [] } ...
+
+A platform name is a string in double quotes, like `platform "cli"`.
+"#;
+
+const WEIRD_GENERATED_TYPE_NAME: &str = r#"
+The `generates` clause of a hosted module names a type that doesn't start with an uppercase
+letter:
+
+    generates effect with [after, map]
+
+Type names start with an uppercase letter, like `generates Effect with [after, map]`.
+"#;
+
+const WEIRD_GENERATES: &str = r#"
+The `generates` clause of a hosted module couldn't be parsed:
+
+    generates Effect [after, map]
+
+It names the type to generate and the functions to generate for it:
+
+    generates Effect with [after, map]
+"#;
+
+const WEIRD_PROVIDES: &str = r#"
+The list of things an app or platform provides couldn't be parsed:
+
+    app "hello" provides main to pf
+
+It's a list in square brackets of the values and types the module provides, like `[main]`.
+"#;
+
+const WEIRD_MODULE_PARAMS: &str = r#"
+The params of a module header couldn't be parsed:
+
+    module { echo, read } [menu]
+
+A module that takes params lists them as a record pattern, followed by `->` and the list of
+exposed names:
+
+    module { echo, read } -> [menu]
+"#;
+
+const WEIRD_EXPOSES: &str = r#"
+The list of names a module exposes couldn't be parsed:
+
+    module [Animal, default tame]
+
+It's a list in square brackets of types and values, separated by commas:
+
+    module [Animal, default, tame]
+"#;
+
+const WEIRD_IMPORTS: &str = r#"
+The `imports` list of a header couldn't be parsed:
+
+    interface Parser exposes [parse] imports [Json exposing parse]
+
+Each import is a module name, optionally followed by the names it exposes in square
+brackets, like `imports [Json.{ parse }]`. In new code, use `import` statements below the
+header instead.
+"#;
+
+const MISSING_REQUIRES: &str = r#"
+A platform header doesn't have a `requires` clause where one is needed:
+
+    platform "cli" exposes [] packages {} ...
+
+A platform says what it needs the app to provide:
+
+    platform "cli"
+        requires {} { main : Task {} [] }
+"#;
+
+const BAD_REQUIRES_RIGIDS: &str = r#"
+The type variables in the first part of a platform's `requires` couldn't be parsed:
+
+    requires { model } { main : Program model }
+
+They're uppercase names in curly braces, separated by commas:
+
+    requires { Model } { main : Program Model }
+"#;
+
+const BAD_REQUIRES: &str = r#"
+The `requires` clause of a platform header couldn't be parsed:
+
+    requires { main : Task {} [] }
+
+It has two parts: the types the app provides, and the values with their types:
+
+    requires {} { main : Task {} [] }
+"#;
+
+const MISSING_PACKAGES: &str = r#"
+A header doesn't have a `packages` clause where one is needed:
+
+    platform "cli" requires {} { main : Str } exposes []
+
+Add the packages the module depends on, which can be empty:
+
+    packages {}
+"#;
+
+const WEIRD_PACKAGES_LIST: &str = r#"
+The list of packages in a header couldn't be parsed:
+
+    packages { json: "https://example.com/json.tar.br" pf: "..." }
+
+It's a record of package shorthands and their URLs or paths, separated by commas:
+
+    packages { json: "https://example.com/json.tar.br", pf: "..." }
+"#;
+
+const TAB_CHARACTER: &str = r#"
+A tab character appears in the code. Roc code is indented with spaces only, so tabs aren't
+allowed. Replace them with spaces; most editors can be set to insert spaces when the tab key
+is pressed.
+"#;
+
+const ASCII_CONTROL_CHARACTER: &str = r#"
+An ASCII control character, like a null byte or a bell, appears in the code. These aren't
+allowed anywhere in a Roc file. In strings, write them with a unicode escape instead, like
+`\u(0007)`.
+"#;
+
+const MISPLACED_CARRIAGE_RETURN: &str = r#"
+A carriage return (`\r`) appears in the code without a newline right after it. Roc accepts
+Windows line endings (`\r\n`), but not carriage returns on their own. This usually means the
+file was saved with old Mac line endings; save it with `\n` or `\r\n` line endings instead.
+"#;
+
+const UNFINISHED_ABILITY: &str = r#"
+An ability definition isn't finished:
+
+    MHash implements
+        hash a -> U64 where a implements MHash
+
+Each member of an ability is a name, a `:` and its type:
+
+    MHash implements
+        hash : a -> U64 where a implements MHash
+"#;
+
+const FILE_NOT_FOUND: &str = r#"
+A file that Roc needs doesn't exist. This is the file given on the command line, or a
+module that another module imports:
+
+    import Utils.Parser
+
+looks for `Utils/Parser.roc`, next to the module that imports it. Check the spelling of the
+path or module name, and that the file is where it's expected to be.
+"#;
+
+const FILE_PERMISSION_DENIED: &str = r#"
+A file that Roc needs exists, but the current user isn't allowed to read it. Check that it's
+the right file, and change its permissions if it is.
+"#;
+
+const NOT_A_ROC_FILE: &str = r#"
+The file given to `roc` isn't a Roc file. Roc files end in `.roc`, or have no extension and
+start with a shebang line that mentions `roc`, like:
+
+    #!/usr/bin/env roc
+
+Check that the right file was passed.
+"#;
+
+const FILE_PROBLEM: &str = r#"
+A file couldn't be read, for a reason other than not existing or not being readable. The
+report shows the error that the operating system gave.
+"#;
+
+const FILE_TOO_LARGE: &str = r#"
+A package or platform download is larger than Roc can handle (around 32 GB). Check that the
+URL is right. If it is, let the package's authors know.
+"#;
+
+const IMPORT_CYCLE: &str = r#"
+Modules import each other in a cycle:
+
+    # Parser.roc
+    import Lexer
+
+    # Lexer.roc
+    import Parser
+
+Roc needs to compile a module's imports before the module itself, so cycles aren't allowed.
+Move the definitions that both modules need into a third module that both import.
+"#;
+
+const INCORRECT_MODULE_NAME: &str = r#"
+A module's name doesn't match where it's imported from. A module imported as `Utils.Parser`
+has to be in `Utils/Parser.roc`, and say it's named `Utils.Parser` if its header has a name.
+Rename the module, or move its file.
+"#;
+
+const UNSPECIFIED_PLATFORM: &str = r#"
+An app doesn't say which platform it runs on:
+
+    app [main] { json: "https://..." }
+
+Exactly one of an app's packages has to be marked as its platform:
+
+    app [main] { pf: platform "https://...", json: "https://..." }
+"#;
+
+const MULTIPLE_PLATFORMS: &str = r#"
+An app marks more than one of its packages as a platform:
+
+    app [main] { cli: platform "https://...", web: platform "https://..." }
+
+An app runs on exactly one platform. Remove `platform` from all but one of them.
+"#;
+
+const NO_PLATFORM: &str = r#"
+The file given to `roc` (or `roc run`, `roc build` and so on) can't be run, because it isn't
+an app with a platform. Only `app` modules can be built and run; a `module`, `package`,
+`platform` or `hosted` file can be checked with `roc check` and tested with `roc test`.
+
+If the file is an app, check that its header names a platform:
+
+    app [main] { pf: platform "https://..." }
+"#;
+
+const UNRECOGNIZED_PACKAGE: &str = r#"
+An import uses a package shorthand that isn't defined:
+
+    import jsn.Decode
+
+The lowercase part before the dot is the name given to a package in the app or package
+header, like `json` in `{ json: "https://..." }`. Check the spelling, or add the package.
+
+When checking a module on its own, Roc looks for the app or package that defines the
+shorthands next to it; `--main` can point at a different one.
+"#;
+
+const UNSUPPORTED_ENCODING: &str = r#"
+A server sent a package with a content encoding Roc doesn't support. Check that the URL is
+right, and that the server is set up to send the file as it is.
+"#;
+
+const MULTIPLE_ENCODINGS: &str = r#"
+A server sent a package with more than one content encoding, and Roc only supports one.
+Check that the URL is right, and that the server is set up to send the file as it is.
+"#;
+
+const INVALID_CONTENT_HASH: &str = r#"
+A downloaded package doesn't match the hash in its URL. Package URLs end in a hash of their
+contents, so that Roc can tell when a file has been changed since the URL was written. That
+can mean the server was compromised, so Roc won't use the package.
+
+Check that the URL is right, and that it comes from the server you expect.
+"#;
+
+const IO_ERROR: &str = r#"
+Reading or writing a file failed while downloading a package. The report shows the error
+from the operating system. Check that there is enough disk space, and that Roc's cache
+directory can be written to.
+"#;
+
+const HTTP_ERROR: &str = r#"
+Downloading a package failed because of a network error. The report shows the error. Check
+the network connection and the URL, and try again.
+"#;
+
+const INVALID_EXTENSION_SUFFIX: &str = r#"
+A package URL points at a file with an extension Roc doesn't support:
+
+    "https://example.com/tE4xS_zLdmmxmHwHih9kHWQ7fsXtJr7W7h3425-eZFk.zip"
+
+Packages are distributed as compressed tarballs, like `.tar.br` or `.tar.gz`. Check that the
+URL is the one the package's authors published.
+"#;
+
+const INVALID_EXTENSION: &str = r#"
+A package URL doesn't point at a tarball:
+
+    "https://example.com/tE4xS_zLdmmxmHwHih9kHWQ7fsXtJr7W7h3425-eZFk"
+
+Packages are distributed as compressed tarballs, so the URL has to end in an extension like
+`.tar.br`. Check that the URL is the one the package's authors published.
+"#;
+
+const INVALID_FRAGMENT: &str = r#"
+The `#` part of a package URL doesn't point at a `.roc` file in the package:
+
+    "https://example.com/tE4xS_zLdmmxmHwHih9kHWQ7fsXtJr7W7h3425-eZFk.tar.br#.roc"
+
+When a URL has a fragment, it names the file in the package to use as its main module. Check
+that the file exists in the package.
+"#;
+
+const MISSING_PACKAGE_HASH: &str = r#"
+A package URL doesn't end in a hash of the package's contents:
+
+    "https://example.com/json.tar.br"
+
+Package files are named after a hash of their contents, so that Roc can tell if they've
+been tampered with. Without one, the package can't be checked, so Roc won't download it.
+A URL with a hash looks like:
+
+    "https://example.com/tE4xS_zLdmmxmHwHih9kHWQ7fsXtJr7W7h3425-eZFk.tar.br"
+"#;
+
+const HTTPS_MANDATORY: &str = r#"
+A package URL doesn't use HTTPS:
+
+    "http://example.com/tE4xS_zLdmmxmHwHih9kHWQ7fsXtJr7W7h3425-eZFk.tar.br"
+
+Roc only downloads packages over HTTPS. Use the `https://` URL instead.
+"#;
+
+const MISLEADING_CHARACTERS: &str = r#"
+A package URL contains characters that look like others without being the same, like the
+fraction slash `⁄` that looks like `/`. These can be used to make a URL look like it points
+somewhere it doesn't, so Roc won't download from it. Check that the URL is right.
+"#;
+
+const NOT_YET_IMPLEMENTED: &str = r#"
+The backend that's compiling the program doesn't support something it uses yet. This isn't
+a problem with the program. The dev backends (used by `roc dev` and `--dev`, and for
+WebAssembly) don't support all of Roc yet; building without `--dev` uses the LLVM backend,
+which does.
+"#;
+
+const CODE_GEN_FAILED: &str = r#"
+A dev backend couldn't generate code for the program, because the program runs into a limit of
+the backend or of the target. For example, a function can have a stack frame that's larger
+than the target can address, or take an argument the backend doesn't know how to pass yet.
+
+Building without `--dev` uses the LLVM backend, which doesn't have these limits.
+"#;
+
+const EXPECT_FAILED: &str = r#"
+An `expect` evaluated to `Bool.false`:
+
+    expect List.len [1, 2, 3] == 2
+
+The report shows the values of the names the expect uses, to help find out why. Fix the code
+being tested, or the expectation if it's the one that's wrong.
+"#;
+
+const EXPECT_PANICKED: &str = r#"
+An `expect` crashed while it ran, so it couldn't finish. This happens when the code being
+tested calls `crash`, or does something that crashes, like dividing an integer by zero or an
+integer overflow:
+
+    expect Num.maxU8 + 1 == 0
+
+The report shows the crash's message. Fix the crash in the code being tested.
+"#;
+
+const INVALID_DOCS_LINK: &str = r#"
+A link in a doc comment can't be resolved:
+
+    ## Parses a [Strng] into a [Value].
+
+A name in square brackets without a URL after it links to that type or value's docs. It has
+to be an uppercase type or lowercase value that is in scope and exposed by its module. Fix
+the name, or give the link a URL like `[Value](https://example.com)`.
+"#;
@@ -11,60 +11,11 @@ use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Loc, Region};
 use roc_types::types::AliasKind;
 use std::path::PathBuf;
 
+use crate::code::ReportKind;
 use crate::error::r#type::suggest;
 use crate::report::{to_file_problem_report, Annotation, Report, RocDocAllocator, RocDocBuilder};
 use ven_pretty::{text, DocAllocator};
 
-const SYNTAX_PROBLEM: &str = "SYNTAX PROBLEM";
-const NAMING_PROBLEM: &str = "NAMING PROBLEM";
-const UNRECOGNIZED_NAME: &str = "UNRECOGNIZED NAME";
-const UNUSED_DEF: &str = "UNUSED DEFINITION";
-const UNUSED_IMPORT: &str = "UNUSED IMPORT";
-const IMPORT_NAME_CONFLICT: &str = "IMPORT NAME CONFLICT";
-const EXPLICIT_BUILTIN_IMPORT: &str = "EXPLICIT BUILTIN IMPORT";
-const UNUSED_ALIAS_PARAM: &str = "UNUSED TYPE ALIAS PARAMETER";
-const UNDECLARED_TYPE_VARIABLE: &str = "UNDECLARED TYPE VARIABLE";
-const WILDCARD_NOT_ALLOWED: &str = "WILDCARD NOT ALLOWED HERE";
-const UNDERSCORE_NOT_ALLOWED: &str = "UNDERSCORE NOT ALLOWED HERE";
-const UNUSED_ARG: &str = "UNUSED ARGUMENT";
-const MISSING_DEFINITION: &str = "MISSING DEFINITION";
-const UNKNOWN_GENERATES_WITH: &str = "UNKNOWN GENERATES FUNCTION";
-const DUPLICATE_FIELD_NAME: &str = "DUPLICATE FIELD NAME";
-const DUPLICATE_TAG_NAME: &str = "DUPLICATE TAG NAME";
-const INVALID_UNICODE: &str = "INVALID UNICODE";
-pub const CIRCULAR_DEF: &str = "CIRCULAR DEFINITION";
-const DUPLICATE_NAME: &str = "DUPLICATE NAME";
-const VALUE_NOT_EXPOSED: &str = "NOT EXPOSED";
-const MODULE_NOT_IMPORTED: &str = "MODULE NOT IMPORTED";
-const INGESTED_FILE_ERROR: &str = "INGESTED FILE ERROR";
-const NESTED_DATATYPE: &str = "NESTED DATATYPE";
-const CONFLICTING_NUMBER_SUFFIX: &str = "CONFLICTING NUMBER SUFFIX";
-const NUMBER_OVERFLOWS_SUFFIX: &str = "NUMBER OVERFLOWS SUFFIX";
-const NUMBER_UNDERFLOWS_SUFFIX: &str = "NUMBER UNDERFLOWS SUFFIX";
-const OPAQUE_NOT_DEFINED: &str = "OPAQUE TYPE NOT DEFINED";
-const OPAQUE_DECLARED_OUTSIDE_SCOPE: &str = "OPAQUE TYPE DECLARED OUTSIDE SCOPE";
-const OPAQUE_NOT_APPLIED: &str = "OPAQUE TYPE NOT APPLIED";
-const OPAQUE_OVER_APPLIED: &str = "OPAQUE TYPE APPLIED TO TOO MANY ARGS";
-const INVALID_EXTENSION_TYPE: &str = "INVALID_EXTENSION_TYPE";
-const ABILITY_HAS_TYPE_VARIABLES: &str = "ABILITY HAS TYPE VARIABLES";
-const IMPLEMENTS_CLAUSE_IS_NOT_AN_ABILITY: &str = "IMPLEMENTS CLAUSE IS NOT AN ABILITY";
-const ILLEGAL_IMPLEMENTS_CLAUSE: &str = "ILLEGAL IMPLEMENTS CLAUSE";
-const ABILITY_MEMBER_MISSING_IMPLEMENTS_CLAUSE: &str = "ABILITY MEMBER MISSING IMPLEMENTS CLAUSE";
-const ABILITY_MEMBER_BINDS_MULTIPLE_VARIABLES: &str = "ABILITY MEMBER BINDS MULTIPLE VARIABLES";
-const ABILITY_NOT_ON_TOPLEVEL: &str = "ABILITY NOT ON TOP-LEVEL";
-const SPECIALIZATION_NOT_ON_TOPLEVEL: &str = "SPECIALIZATION NOT ON TOP-LEVEL";
-const ABILITY_USED_AS_TYPE: &str = "ABILITY USED AS TYPE";
-const ILLEGAL_DERIVE: &str = "ILLEGAL DERIVE";
-const IMPLEMENTATION_NOT_FOUND: &str = "IMPLEMENTATION NOT FOUND";
-const NOT_AN_ABILITY_MEMBER: &str = "NOT AN ABILITY MEMBER";
-const NOT_AN_ABILITY: &str = "NOT AN ABILITY";
-const OPTIONAL_ABILITY_IMPLEMENTATION: &str = "OPTIONAL ABILITY IMPLEMENTATION";
-const QUALIFIED_ABILITY_IMPLEMENTATION: &str = "QUALIFIED ABILITY IMPLEMENTATION";
-const ABILITY_IMPLEMENTATION_NOT_IDENTIFIER: &str = "ABILITY IMPLEMENTATION NOT IDENTIFIER";
-const DUPLICATE_IMPLEMENTATION: &str = "DUPLICATE IMPLEMENTATION";
-const UNNECESSARY_IMPLEMENTATIONS: &str = "UNNECESSARY IMPLEMENTATIONS";
-const INCOMPLETE_ABILITY_IMPLEMENTATION: &str = "INCOMPLETE ABILITY IMPLEMENTATION";
-
 pub fn can_problem<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
@@ -91,7 +42,7 @@ pub fn can_problem<'b>(
                     .append(alloc.reflow(line)),
             ]);
 
-            title = UNUSED_DEF.to_string();
+            title = ReportKind::UnusedDefinition.into();
         }
         Problem::UnusedImport(symbol, region) => {
             doc = alloc.stack([
@@ -107,7 +58,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = UNUSED_IMPORT.to_string();
+            title = ReportKind::UnusedImport.into();
         }
         Problem::UnusedModuleImport(module_id, region) => {
             doc = alloc.stack([
@@ -123,7 +74,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = UNUSED_IMPORT.to_string();
+            title = ReportKind::UnusedImport.into();
         }
         Problem::ImportNameConflict {
             name,
@@ -183,7 +134,7 @@ pub fn can_problem<'b>(
                     ])
                 },
             ]);
-            title = IMPORT_NAME_CONFLICT.to_string();
+            title = ReportKind::ImportNameConflict.into();
         }
 
         Problem::ExplicitBuiltinImport(module_id, region) => {
@@ -198,7 +149,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Tip: Learn more about builtins in the tutorial:\n\n<https://www.roc-lang.org/tutorial#builtin-modules>"),
             ]);
 
-            title = EXPLICIT_BUILTIN_IMPORT.to_string();
+            title = ReportKind::ExplicitBuiltinImport.into();
         }
 
         Problem::ExplicitBuiltinTypeImport(symbol, region) => {
@@ -216,7 +167,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Tip: Learn more about builtins in the tutorial:\n\n<https://www.roc-lang.org/tutorial#builtin-modules>"),
             ]);
 
-            title = EXPLICIT_BUILTIN_IMPORT.to_string();
+            title = ReportKind::ExplicitBuiltinImport.into();
         }
 
         Problem::ImportShadowsSymbol {
@@ -243,7 +194,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = DUPLICATE_NAME.to_string();
+            title = ReportKind::DuplicateName.into();
         }
 
         Problem::DefsOnlyUsedInRecursion(1, region) => {
@@ -255,7 +206,7 @@ pub fn can_problem<'b>(
                 ),
             ]);
 
-            title = "DEFINITION ONLY USED IN RECURSION".to_string();
+            title = ReportKind::DefinitionOnlyUsedInRecursion.into();
         }
         Problem::DefsOnlyUsedInRecursion(n, region) => {
             doc = alloc.stack([
@@ -270,7 +221,7 @@ pub fn can_problem<'b>(
                 ),
             ]);
 
-            title = "DEFINITIONs ONLY USED IN RECURSION".to_string();
+            title = ReportKind::DefinitionOnlyUsedInRecursion.into();
         }
         Problem::ExposedButNotDefined(symbol) => {
             doc = alloc.stack([
@@ -285,7 +236,7 @@ pub fn can_problem<'b>(
                     .append(alloc.reflow(".")),
             ]);
 
-            title = MISSING_DEFINITION.to_string();
+            title = ReportKind::MissingDefinition.into();
         }
         Problem::UnknownGeneratesWith(loc_ident) => {
             doc = alloc.stack([
@@ -299,7 +250,7 @@ pub fn can_problem<'b>(
                     .append(alloc.reflow("Learn more about hosted modules at TODO.")),
             ]);
 
-            title = UNKNOWN_GENERATES_WITH.to_string();
+            title = ReportKind::UnknownGeneratesFunction.into();
         }
         Problem::UnusedArgument(closure_symbol, is_anonymous, argument_symbol, region) => {
             let line = "\". Adding an underscore at the start of a variable name is a way of saying that the variable is not used.";
@@ -333,7 +284,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = UNUSED_ARG.to_string();
+            title = ReportKind::UnusedArgument.into();
         }
         Problem::UnusedBranchDef(symbol, region) => {
             doc = alloc.stack([
@@ -353,7 +304,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = UNUSED_DEF.to_string();
+            title = ReportKind::UnusedDefinition.into();
         }
         Problem::PrecedenceProblem(BothNonAssociative(region, left_bin_op, right_bin_op)) => {
             doc = alloc.stack([
@@ -381,7 +332,7 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(region), severity),
             ]);
 
-            title = SYNTAX_PROBLEM.to_string();
+            title = ReportKind::SyntaxProblem.into();
         }
         Problem::UnsupportedPattern(BadPattern::Unsupported(pattern_type), region) => {
             use roc_parse::pattern::PatternType::*;
@@ -411,7 +362,7 @@ pub fn can_problem<'b>(
                 alloc.concat(suggestion),
             ]);
 
-            title = SYNTAX_PROBLEM.to_string();
+            title = ReportKind::SyntaxProblem.into();
         }
         Problem::Shadowing {
             original_region,
@@ -422,7 +373,7 @@ pub fn can_problem<'b>(
                 report_shadowing(alloc, lines, original_region, shadow, kind, severity);
 
             doc = res_doc;
-            title = res_title.to_string();
+            title = res_title.into();
         }
         Problem::CyclicAlias(symbol, region, others, alias_kind) => {
             let answer = crate::error::r#type::cyclic_alias(
@@ -430,7 +381,7 @@ pub fn can_problem<'b>(
             );
 
             doc = answer.0;
-            title = answer.1;
+            title = answer.1.into();
         }
         Problem::PhantomTypeArgument {
             typ: alias,
@@ -457,7 +408,7 @@ pub fn can_problem<'b>(
                 )),
             ]);
 
-            title = UNUSED_ALIAS_PARAM.to_string();
+            title = ReportKind::UnusedTypeAliasParameter.into();
         }
         Problem::WildcardNotAllowed {
             typ: alias,
@@ -497,7 +448,7 @@ pub fn can_problem<'b>(
             ]));
             doc = alloc.stack(stack);
 
-            title = WILDCARD_NOT_ALLOWED.to_string();
+            title = ReportKind::WildcardNotAllowedHere.into();
         }
         Problem::UnderscoreNotAllowed {
             typ: alias,
@@ -538,7 +489,7 @@ pub fn can_problem<'b>(
             ]));
             doc = alloc.stack(stack);
 
-            title = UNDERSCORE_NOT_ALLOWED.to_string();
+            title = ReportKind::UnderscoreNotAllowedHere.into();
         }
         Problem::UndeclaredTypeVar {
             typ: alias,
@@ -584,11 +535,11 @@ pub fn can_problem<'b>(
             ])));
             doc = alloc.stack(stack);
 
-            title = UNDECLARED_TYPE_VARIABLE.to_string();
+            title = ReportKind::UndeclaredTypeVariable.into();
         }
         Problem::BadRecursion(entries) => {
             doc = to_circular_def_doc(alloc, lines, &entries, severity);
-            title = CIRCULAR_DEF.to_string();
+            title = ReportKind::CircularDefinition.into();
         }
         Problem::DuplicateRecordFieldValue {
             field_name,
@@ -622,7 +573,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = DUPLICATE_FIELD_NAME.to_string();
+            title = ReportKind::DuplicateFieldName.into();
         }
         Problem::InvalidOptionalValue {
             field_name,
@@ -670,7 +621,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = DUPLICATE_FIELD_NAME.to_string();
+            title = ReportKind::DuplicateFieldName.into();
         }
         Problem::DuplicateTag {
             tag_name,
@@ -704,7 +655,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = DUPLICATE_TAG_NAME.to_string();
+            title = ReportKind::DuplicateTagName.into();
         }
         Problem::SignatureDefMismatch {
             ref annotation_pattern,
@@ -721,7 +672,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Is it a typo? If not, put either a newline or comment between them."),
             ]);
 
-            title = NAMING_PROBLEM.to_string();
+            title = ReportKind::NamingProblem.into();
         }
         Problem::InvalidAliasRigid {
             alias_name: type_name,
@@ -743,7 +694,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = SYNTAX_PROBLEM.to_string();
+            title = ReportKind::SyntaxProblem.into();
         }
         Problem::InvalidHexadecimal(region) => {
             doc = alloc.stack([
@@ -759,7 +710,7 @@ pub fn can_problem<'b>(
                 alloc.reflow(r"Learn more about working with unicode in roc at TODO"),
             ]);
 
-            title = INVALID_UNICODE.to_string();
+            title = ReportKind::InvalidUnicode.into();
         }
         Problem::InvalidUnicodeCodePt(region) => {
             doc = alloc.stack([
@@ -768,7 +719,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Learn more about working with unicode in roc at TODO"),
             ]);
 
-            title = INVALID_UNICODE.to_string();
+            title = ReportKind::InvalidUnicode.into();
         }
         Problem::InvalidInterpolation(region) => {
             doc = alloc.stack([
@@ -778,13 +729,13 @@ pub fn can_problem<'b>(
                 alloc.reflow(r"You can learn more about string interpolation at <https://www.roc-lang.org/tutorial#string-interpolation>"),
             ]);
 
-            title = SYNTAX_PROBLEM.to_string();
+            title = ReportKind::SyntaxProblem.into();
         }
         Problem::RuntimeError(runtime_error) => {
             let answer = pretty_runtime_error(alloc, lines, runtime_error);
 
             doc = answer.0;
-            title = answer.1.to_string();
+            title = answer.1.into();
         }
         Problem::NestedDatatype {
             alias,
@@ -811,7 +762,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = NESTED_DATATYPE.to_string();
+            title = ReportKind::NestedDatatype.into();
         }
 
         Problem::InvalidExtensionType { region, kind } => {
@@ -838,7 +789,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
 
-            title = INVALID_EXTENSION_TYPE.to_string();
+            title = ReportKind::InvalidExtensionType.into();
         }
 
         Problem::AbilityHasTypeVariables {
@@ -856,7 +807,7 @@ pub fn can_problem<'b>(
                     "Abilities cannot depend on type variables, but their member values can!",
                 ),
             ]);
-            title = ABILITY_HAS_TYPE_VARIABLES.to_string();
+            title = ReportKind::AbilityHasTypeVariables.into();
         }
 
         Problem::ImplementsClauseIsNotAbility {
@@ -868,7 +819,7 @@ pub fn can_problem<'b>(
                 ),
                 alloc.region(lines.convert_region(clause_region), severity),
             ]);
-            title = IMPLEMENTS_CLAUSE_IS_NOT_AN_ABILITY.to_string();
+            title = ReportKind::ImplementsClauseIsNotAnAbility.into();
         }
 
         Problem::IllegalImplementsClause { region } => {
@@ -886,7 +837,7 @@ pub fn can_problem<'b>(
                     ),
                 ]),
             ]);
-            title = ILLEGAL_IMPLEMENTS_CLAUSE.to_string();
+            title = ReportKind::IllegalImplementsClause.into();
         }
 
         Problem::DuplicateImplementsAbility { ability, region } => {
@@ -903,7 +854,7 @@ pub fn can_problem<'b>(
                     alloc.reflow(" clause!"),
                 ]),
             ]);
-            title = "DUPLICATE BOUND ABILITY".to_string();
+            title = ReportKind::DuplicateBoundAbility.into();
         }
 
         Problem::AbilityMemberMissingImplementsClause {
@@ -937,7 +888,7 @@ pub fn can_problem<'b>(
                 alloc.concat([alloc
                     .reflow("Otherwise, the function does not need to be part of the ability!")]),
             ]);
-            title = ABILITY_MEMBER_MISSING_IMPLEMENTS_CLAUSE.to_string();
+            title = ReportKind::AbilityMemberMissingImplementsClause.into();
         }
 
         Problem::AbilityMemberMultipleBoundVars {
@@ -964,7 +915,7 @@ pub fn can_problem<'b>(
                     alloc.reflow("?"),
                 ])
             ]);
-            title = ABILITY_MEMBER_BINDS_MULTIPLE_VARIABLES.to_string();
+            title = ReportKind::AbilityMemberBindsMultipleVariables.into();
         }
 
         Problem::AbilityNotOnToplevel { region } => {
@@ -975,7 +926,7 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(region), severity),
                 alloc.reflow("Abilities can only be defined on the top-level of a Roc module."),
             ]);
-            title = ABILITY_NOT_ON_TOPLEVEL.to_string();
+            title = ReportKind::AbilityNotOnTopLevel.into();
         }
 
         Problem::AbilityUsedAsType(suggested_var_name, ability, region) => {
@@ -1002,7 +953,7 @@ pub fn can_problem<'b>(
                     alloc.symbol_unqualified(ability),
                 ])),
             ]);
-            title = ABILITY_USED_AS_TYPE.to_string();
+            title = ReportKind::AbilityUsedAsType.into();
         }
         Problem::NestedSpecialization(member, region) => {
             doc = alloc.stack([
@@ -1014,7 +965,7 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(region), severity),
                 alloc.reflow("Specializations can only be defined on the top-level of a module."),
             ]);
-            title = SPECIALIZATION_NOT_ON_TOPLEVEL.to_string();
+            title = ReportKind::SpecializationNotOnTopLevel.into();
         }
         Problem::IllegalDerivedAbility(region) => {
             doc = alloc.stack([
@@ -1025,7 +976,7 @@ pub fn can_problem<'b>(
                     .note("The builtin abilities are ")
                     .append(list_builtin_abilities(alloc)),
             ]);
-            title = ILLEGAL_DERIVE.to_string();
+            title = ReportKind::IllegalDerive.into();
         }
         Problem::NotAnAbility(region) => {
             doc = alloc.stack([
@@ -1033,7 +984,7 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(region), severity),
                 alloc.reflow("Only abilities can be implemented."),
             ]);
-            title = NOT_AN_ABILITY.to_string();
+            title = ReportKind::NotAnAbility.into();
         }
         Problem::NotAnAbilityMember {
             ability,
@@ -1047,7 +998,7 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(region), severity),
                 alloc.reflow("Only implementations for members an ability has can be specified in this location.")
             ]);
-            title = NOT_AN_ABILITY_MEMBER.to_string();
+            title = ReportKind::NotAnAbilityMember.into();
         }
         Problem::ImplementationNotFound { member, region } => {
             let member_str = member.as_str(alloc.interns);
@@ -1058,7 +1009,7 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(region), severity),
                 alloc.tip().append(alloc.concat([alloc.reflow("consider adding a value of name "), alloc.symbol_unqualified(member), alloc.reflow(" in this scope, or using another variable that implements this ability member, like "), alloc.type_str(&format!("{{ {member_str}: my{member_str} }}"))]))
             ]);
-            title = IMPLEMENTATION_NOT_FOUND.to_string();
+            title = ReportKind::ImplementationNotFound.into();
         }
         Problem::OptionalAbilityImpl { ability, region } => {
             let hint = if ability.is_builtin() {
@@ -1076,7 +1027,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Custom implementations must be supplied fully."),
                 hint,
             ]);
-            title = OPTIONAL_ABILITY_IMPLEMENTATION.to_string();
+            title = ReportKind::OptionalAbilityImplementation.into();
         }
         Problem::QualifiedAbilityImpl { region } => {
             doc = alloc.stack([
@@ -1086,7 +1037,7 @@ pub fn can_problem<'b>(
                     "Custom implementations must be defined in the local scope, and unqualified.",
                 ),
             ]);
-            title = QUALIFIED_ABILITY_IMPLEMENTATION.to_string();
+            title = ReportKind::QualifiedAbilityImplementation.into();
         }
         Problem::AbilityImplNotIdent { region } => {
             doc = alloc.stack([
@@ -1097,7 +1048,7 @@ pub fn can_problem<'b>(
                 ),
                 alloc.tip().append(alloc.reflow("consider defining this expression as a variable."))
             ]);
-            title = ABILITY_IMPLEMENTATION_NOT_IDENTIFIER.to_string();
+            title = ReportKind::AbilityImplementationNotIdentifier.into();
         }
         Problem::DuplicateImpl {
             original,
@@ -1111,7 +1062,7 @@ pub fn can_problem<'b>(
                 alloc
                     .reflow("Only one custom implementation can be defined for an ability member."),
             ]);
-            title = DUPLICATE_IMPLEMENTATION.to_string();
+            title = ReportKind::DuplicateImplementation.into();
         }
         Problem::ImplementsNonRequired {
             region,
@@ -1135,7 +1086,7 @@ pub fn can_problem<'b>(
                     ),
                 ),
             ]);
-            title = UNNECESSARY_IMPLEMENTATIONS.to_string();
+            title = ReportKind::UnnecessaryImplementations.into();
         }
         Problem::DoesNotImplementAbility {
             region,
@@ -1159,7 +1110,7 @@ pub fn can_problem<'b>(
                     ),
                 ),
             ]);
-            title = INCOMPLETE_ABILITY_IMPLEMENTATION.to_string();
+            title = ReportKind::IncompleteAbilityImplementation.into();
         }
         Problem::NotBoundInAllPatterns {
             unbound_symbol,
@@ -1179,7 +1130,7 @@ pub fn can_problem<'b>(
                     alloc.reflow(" branch must be bound in all patterns of the branch. Otherwise, the program would crash when it tries to use an identifier that wasn't bound!"),
                 ]),
             ]);
-            title = "NAME NOT BOUND IN ALL PATTERNS".to_string();
+            title = ReportKind::NameNotBoundInAllPatterns.into();
         }
         Problem::NoIdentifiersIntroduced(region) => {
             doc = alloc.stack([
@@ -1187,7 +1138,7 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(region), severity),
                 alloc.reflow("If you don't need to use the value on the right-hand-side of this assignment, consider removing the assignment. Since Roc is purely functional, assignments that don't introduce variables cannot affect a program's behavior!"),
             ]);
-            title = "UNNECESSARY DEFINITION".to_string();
+            title = ReportKind::UnnecessaryDefinition.into();
         }
         Problem::OverloadedSpecialization {
             ability_member,
@@ -1206,7 +1157,7 @@ pub fn can_problem<'b>(
                 ]),
                 alloc.reflow("Ability specializations can only provide implementations for one opaque type, since all opaque types are different!"),
             ]);
-            title = "OVERLOADED SPECIALIZATION".to_string();
+            title = ReportKind::OverloadedSpecialization.into();
         }
         Problem::UnnecessaryOutputWildcard { region } => {
             doc = alloc.stack([
@@ -1225,7 +1176,7 @@ pub fn can_problem<'b>(
                 ]),
                 alloc.reflow("You can safely remove this to make the code more concise without changing what it means."),
             ]);
-            title = "UNNECESSARY WILDCARD".to_string();
+            title = ReportKind::UnnecessaryWildcard.into();
         }
         Problem::MultipleListRestPattern { region } => {
             doc = alloc.stack([
@@ -1237,7 +1188,7 @@ pub fn can_problem<'b>(
                     alloc.reflow(" pattern! Can you remove this additional one?"),
                 ]),
             ]);
-            title = "MULTIPLE LIST REST PATTERNS".to_string();
+            title = ReportKind::MultipleListRestPatterns.into();
         }
         Problem::BadTypeArguments {
             symbol,
@@ -1273,9 +1224,9 @@ pub fn can_problem<'b>(
             ]);
 
             title = if type_got > alias_needs {
-                "TOO MANY TYPE ARGUMENTS".to_string()
+                ReportKind::TooManyTypeArguments.into()
             } else {
-                "TOO FEW TYPE ARGUMENTS".to_string()
+                ReportKind::TooFewTypeArguments.into()
             };
         }
         Problem::UnappliedCrash { region } => {
@@ -1289,7 +1240,7 @@ pub fn can_problem<'b>(
                     alloc.keyword("crash"), alloc.reflow(" can't be used as a value that's passed around, like functions can be - it must be applied immediately!"),
                 ])
             ]);
-            title = "UNAPPLIED CRASH".to_string();
+            title = ReportKind::UnappliedCrash.into();
         }
         Problem::OverAppliedCrash { region } => {
             doc = alloc.stack([
@@ -1304,7 +1255,7 @@ pub fn can_problem<'b>(
                     alloc.reflow(" must be given exactly one message to crash with."),
                 ]),
             ]);
-            title = "OVERAPPLIED CRASH".to_string();
+            title = ReportKind::OverappliedCrash.into();
         }
         Problem::FileProblem { filename, error } => {
             let report = to_file_problem_report(alloc, filename, error);
@@ -1347,7 +1298,7 @@ fn to_invalid_optional_value_report<'b>(
     );
 
     Report {
-        title: "BAD OPTIONAL VALUE".to_string(),
+        title: ReportKind::BadOptionalValue.into(),
         filename,
         doc,
         severity: Severity::RuntimeError,
@@ -1768,7 +1719,7 @@ fn report_shadowing<'b>(
     shadow: Loc<Ident>,
     kind: ShadowKind,
     severity: Severity,
-) -> (&'static str, RocDocBuilder<'b>) {
+) -> (ReportKind, RocDocBuilder<'b>) {
     let (what, what_plural, is_builtin) = match kind {
         ShadowKind::Variable => ("variable", "variables", false),
         ShadowKind::Alias(sym) => ("alias", "aliases", sym.is_builtin()),
@@ -1809,14 +1760,14 @@ fn report_shadowing<'b>(
         ])
     };
 
-    (DUPLICATE_NAME, doc)
+    (ReportKind::DuplicateName, doc)
 }
 
 fn pretty_runtime_error<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
     runtime_error: RuntimeError,
-) -> (RocDocBuilder<'b>, &'static str) {
+) -> (RocDocBuilder<'b>, ReportKind) {
     let doc;
     let title;
 
@@ -1856,11 +1807,11 @@ fn pretty_runtime_error<'b>(
                 underscored_suggestion_region,
                 severity,
             );
-            title = UNRECOGNIZED_NAME;
+            title = ReportKind::UnrecognizedName;
         }
         RuntimeError::CircularDef(entries) => {
             doc = to_circular_def_doc(alloc, lines, &entries, severity);
-            title = CIRCULAR_DEF;
+            title = ReportKind::CircularDefinition;
         }
         RuntimeError::MalformedPattern(problem, region) => {
             use roc_parse::ast::Base;
@@ -1874,7 +1825,7 @@ fn pretty_runtime_error<'b>(
                 MalformedBase(Base::Octal) => " octal integer ",
                 MalformedBase(Base::Decimal) => " integer ",
                 BadIdent(bad_ident) => {
-                    title = NAMING_PROBLEM;
+                    title = ReportKind::NamingProblem;
                     doc = to_bad_ident_pattern_report(alloc, lines, bad_ident, region, severity);
 
                     return (doc, title);
@@ -1911,7 +1862,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::UnsupportedPattern(_) => {
             todo!("unsupported patterns are currently not parsed!")
@@ -1952,7 +1903,7 @@ fn pretty_runtime_error<'b>(
                 did_you_mean,
             ]);
 
-            title = VALUE_NOT_EXPOSED;
+            title = ReportKind::NotExposed;
         }
 
         RuntimeError::ModuleNotImported {
@@ -1971,7 +1922,7 @@ fn pretty_runtime_error<'b>(
                 severity,
             );
 
-            title = MODULE_NOT_IMPORTED;
+            title = ReportKind::ModuleNotImported;
         }
         RuntimeError::ReadIngestedFileError {
             filename,
@@ -1981,7 +1932,7 @@ fn pretty_runtime_error<'b>(
             let report = to_file_problem_report(alloc, filename, error);
 
             doc = report.doc;
-            title = INGESTED_FILE_ERROR;
+            title = ReportKind::IngestedFileError;
         }
         RuntimeError::InvalidPrecedence(_, _) => {
             // do nothing, reported with PrecedenceProblem
@@ -1990,7 +1941,7 @@ fn pretty_runtime_error<'b>(
         RuntimeError::MalformedIdentifier(_box_str, bad_ident, surroundings) => {
             doc = to_bad_ident_expr_report(alloc, lines, bad_ident, surroundings, severity);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::MalformedTypeName(_box_str, surroundings) => {
             doc = alloc.stack([
@@ -2006,7 +1957,7 @@ fn pretty_runtime_error<'b>(
                 ]),
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::MalformedClosure(_) => {
             todo!("");
@@ -2043,7 +1994,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::InvalidFloat(FloatErrorKind::Error, region, _raw_str) => {
             let tip = alloc
@@ -2061,7 +2012,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::InvalidFloat(FloatErrorKind::IntSuffix, region, _raw_str) => {
             doc = alloc.stack([
@@ -2071,7 +2022,7 @@ fn pretty_runtime_error<'b>(
                 alloc.region(lines.convert_region(region), severity),
             ]);
 
-            title = CONFLICTING_NUMBER_SUFFIX;
+            title = ReportKind::ConflictingNumberSuffix;
         }
         RuntimeError::InvalidInt(error @ IntErrorKind::InvalidDigit, base, region, _raw_str)
         | RuntimeError::InvalidInt(error @ IntErrorKind::Empty, base, region, _raw_str) => {
@@ -2132,7 +2083,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::InvalidInt(error_kind @ IntErrorKind::Underflow, _base, region, _raw_str)
         | RuntimeError::InvalidInt(error_kind @ IntErrorKind::Overflow, _base, region, _raw_str) => {
@@ -2175,7 +2126,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::InvalidInt(IntErrorKind::FloatSuffix, _base, region, _raw_str) => {
             doc = alloc.stack([
@@ -2185,7 +2136,7 @@ fn pretty_runtime_error<'b>(
                 alloc.region(lines.convert_region(region), severity),
             ]);
 
-            title = CONFLICTING_NUMBER_SUFFIX;
+            title = ReportKind::ConflictingNumberSuffix;
         }
         RuntimeError::InvalidInt(
             IntErrorKind::OverflowsSuffix {
@@ -2209,7 +2160,7 @@ fn pretty_runtime_error<'b>(
                 ])),
             ]);
 
-            title = NUMBER_OVERFLOWS_SUFFIX;
+            title = ReportKind::NumberOverflowsSuffix;
         }
         RuntimeError::InvalidInt(
             IntErrorKind::UnderflowsSuffix {
@@ -2233,7 +2184,7 @@ fn pretty_runtime_error<'b>(
                 ])),
             ]);
 
-            title = NUMBER_UNDERFLOWS_SUFFIX;
+            title = ReportKind::NumberUnderflowsSuffix;
         }
         RuntimeError::InvalidOptionalValue {
             field_name,
@@ -2248,7 +2199,7 @@ fn pretty_runtime_error<'b>(
                 record_region,
             );
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::InvalidRecordUpdate { region } => {
             doc = alloc.stack([
@@ -2260,7 +2211,7 @@ fn pretty_runtime_error<'b>(
                 alloc.reflow("Only variables can be updated with record update syntax."),
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::InvalidHexadecimal(region) => {
             todo!(
@@ -2293,7 +2244,7 @@ fn pretty_runtime_error<'b>(
                 .append(alloc.module(symbol.module_id()))
                 .append(alloc.reflow(", but it was not defined anywhere in that module."))]);
 
-            title = MISSING_DEFINITION;
+            title = ReportKind::MissingDefinition;
         }
         RuntimeError::EmptySingleQuote(region) => {
             let tip = alloc
@@ -2306,7 +2257,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::MultipleCharsInSingleQuote(region) => {
             let tip = alloc
@@ -2322,7 +2273,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = ReportKind::SyntaxProblem;
         }
         RuntimeError::OpaqueNotDefined {
             usage:
@@ -2372,7 +2323,7 @@ fn pretty_runtime_error<'b>(
 
             doc = alloc.stack(stack);
 
-            title = OPAQUE_NOT_DEFINED;
+            title = ReportKind::OpaqueTypeNotDefined;
         }
         RuntimeError::OpaqueOutsideScope {
             opaque,
@@ -2393,7 +2344,7 @@ fn pretty_runtime_error<'b>(
                 ),
             ]);
 
-            title = OPAQUE_DECLARED_OUTSIDE_SCOPE;
+            title = ReportKind::OpaqueTypeDeclaredOutsideScope;
         }
         RuntimeError::OpaqueNotApplied(loc_ident) => {
             doc = alloc.stack([
@@ -2402,7 +2353,7 @@ fn pretty_runtime_error<'b>(
                 alloc.note("Opaque types always wrap exactly one argument!"),
             ]);
 
-            title = OPAQUE_NOT_APPLIED;
+            title = ReportKind::OpaqueTypeNotApplied;
         }
         RuntimeError::OpaqueAppliedToMultipleArgs(region) => {
             doc = alloc.stack([
@@ -2411,7 +2362,7 @@ fn pretty_runtime_error<'b>(
                 alloc.note("Opaque types always wrap exactly one argument!"),
             ]);
 
-            title = OPAQUE_OVER_APPLIED;
+            title = ReportKind::OpaqueTypeAppliedToTooManyArgs;
         }
         RuntimeError::DegenerateBranch(region) => {
             doc = alloc.stack([
@@ -2419,7 +2370,7 @@ fn pretty_runtime_error<'b>(
                 alloc.region(lines.convert_region(region), severity),
             ]);
 
-            title = "DEGENERATE BRANCH";
+            title = ReportKind::DegenerateBranch;
         }
         RuntimeError::MultipleOldRecordBuilders(region) => {
             let tip = alloc
@@ -2433,7 +2384,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = "MULTIPLE OLD-STYLE RECORD BUILDERS";
+            title = ReportKind::MultipleOldStyleRecordBuilders;
         }
         RuntimeError::UnappliedOldRecordBuilder(region) => {
             doc = alloc.stack([
@@ -2445,7 +2396,7 @@ fn pretty_runtime_error<'b>(
                 ),
             ]);
 
-            title = "UNAPPLIED OLD-STYLE RECORD BUILDER";
+            title = ReportKind::UnappliedOldStyleRecordBuilder;
         }
         RuntimeError::EmptyRecordBuilder(region) => {
             doc = alloc.stack([
@@ -2454,7 +2405,7 @@ fn pretty_runtime_error<'b>(
                 alloc.reflow("I need at least two fields to combine their values into a record."),
             ]);
 
-            title = "EMPTY RECORD BUILDER";
+            title = ReportKind::EmptyRecordBuilder;
         }
         RuntimeError::SingleFieldRecordBuilder(region) => {
             doc = alloc.stack([
@@ -2463,7 +2414,7 @@ fn pretty_runtime_error<'b>(
                 alloc.reflow("I need at least two fields to combine their values into a record."),
             ]);
 
-            title = "NOT ENOUGH FIELDS IN RECORD BUILDER";
+            title = ReportKind::NotEnoughFieldsInRecordBuilder;
         }
         RuntimeError::OptionalFieldInRecordBuilder {
             record: record_region,
@@ -2479,7 +2430,7 @@ fn pretty_runtime_error<'b>(
                 alloc.reflow("Record builders can only have required values for their fields."),
             ]);
 
-            title = "OPTIONAL FIELD IN RECORD BUILDER";
+            title = ReportKind::OptionalFieldInRecordBuilder;
         }
     }
 
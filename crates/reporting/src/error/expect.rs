@@ -2,7 +2,6 @@ use std::path::PathBuf;
 
 use bumpalo::Bump;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
-use roc_parse::ast::Expr;
 use roc_problem::Severity;
 use roc_region::all::{LineColumnRegion, LineInfo, Region};
 use roc_types::{
@@ -10,14 +9,15 @@ use roc_types::{
     types::{ErrorType, Polarity},
 };
 
+use crate::code::ReportKind;
 use crate::report::{RenderTarget, RocDocAllocator, RocDocBuilder};
 
 pub struct Renderer<'a> {
-    arena: &'a Bump,
     alloc: RocDocAllocator<'a>,
     filename: PathBuf,
     line_info: LineInfo,
     render_target: RenderTarget,
+    source: &'a str,
 }
 
 impl<'a> Renderer<'a> {
@@ -35,11 +35,11 @@ impl<'a> Renderer<'a> {
         let alloc = RocDocAllocator::new(source_lines.into_bump_slice(), module_id, interns);
 
         Self {
-            arena,
             alloc,
             line_info,
             filename,
             render_target,
+            source,
         }
     }
 
@@ -52,14 +52,9 @@ impl<'a> Renderer<'a> {
     fn render_lookup(
         &'a self,
         symbol: Symbol,
-        expr: &Expr<'_>,
+        value: &str,
         error_type: ErrorType,
     ) -> RocDocBuilder<'a> {
-        use roc_fmt::annotation::Formattable;
-
-        let mut buf = roc_fmt::Buf::new_in(self.arena);
-        expr.format(&mut buf, 0);
-
         self.alloc.vcat([
             self.alloc
                 .symbol_unqualified(symbol)
@@ -68,7 +63,7 @@ impl<'a> Renderer<'a> {
             self.alloc
                 .symbol_unqualified(symbol)
                 .append(" = ")
-                .append(buf.into_bump_str()),
+                .append(value.to_string()),
         ])
     }
 
@@ -79,20 +74,19 @@ impl<'a> Renderer<'a> {
 
         symbols: &[Symbol],
         variables: &[Variable],
-        expressions: &[Expr<'_>],
+        values: &[&str],
         severity: Severity,
     ) -> RocDocBuilder<'a> {
         use ven_pretty::DocAllocator;
 
-        let it =
-            symbols
-                .iter()
-                .zip(variables)
-                .zip(expressions)
-                .map(|((symbol, variable), expr)| {
-                    let error_type = subs.var_to_error_type(*variable, Polarity::OF_VALUE);
-                    self.render_lookup(*symbol, expr, error_type)
-                });
+        let it = symbols
+            .iter()
+            .zip(variables)
+            .zip(values)
+            .map(|((symbol, variable), value)| {
+                let error_type = subs.var_to_error_type(*variable, Polarity::OF_VALUE);
+                self.render_lookup(*symbol, value, error_type)
+            });
 
         if it.len() > 0 {
             self.alloc.stack([
@@ -140,7 +134,7 @@ impl<'a> Renderer<'a> {
         subs: &mut Subs,
         symbols: &[Symbol],
         variables: &[Variable],
-        expressions: &[Expr<'_>],
+        values: &[&str],
         expect_region: Option<Region>,
         failure_region: Region,
     ) -> std::io::Result<()>
@@ -151,17 +145,10 @@ impl<'a> Renderer<'a> {
         let severity = Severity::RuntimeError;
 
         let line_col_region = self.to_line_col_region(expect_region, failure_region);
-        let doc = self.render_lookups(
-            subs,
-            line_col_region,
-            symbols,
-            variables,
-            expressions,
-            severity,
-        );
+        let doc = self.render_lookups(subs, line_col_region, symbols, variables, values, severity);
 
         let report = Report {
-            title: "EXPECT FAILED".into(),
+            title: ReportKind::ExpectFailed.into(),
             doc,
             filename: self.filename.clone(),
             severity,
@@ -179,11 +166,10 @@ impl<'a> Renderer<'a> {
         write!(writer, "{buf}")
     }
 
-    #[allow(clippy::too_many_arguments)]
     pub fn render_dbg<W>(
         &self,
         writer: &mut W,
-        expressions: &[Expr<'_>],
+        value: &str,
         expect_region: Option<Region>,
         dbg_expr_region: Region,
     ) -> std::io::Result<()>
@@ -199,15 +185,27 @@ impl<'a> Renderer<'a> {
             line_col_region.start.column + 1
         )?;
 
-        let expr = expressions[0];
+        writeln!(writer, "{value}")
+    }
+
+    /// The values of an expect's variables as plain text, with one `name = value` line per
+    /// variable. This is what `roc test` keeps in a snapshot file.
+    pub fn render_snapshot(&self, symbols: &[Symbol], values: &[&str]) -> String {
+        let mut out = String::new();
 
-        let mut buf = roc_fmt::Buf::new_in(self.arena);
-        {
-            use roc_fmt::annotation::Formattable;
-            expr.format(&mut buf, 0);
+        for (symbol, value) in symbols.iter().zip(values) {
+            out.push_str(symbol.as_str(self.alloc.interns));
+            out.push_str(" = ");
+            out.push_str(value);
+            out.push('\n');
         }
 
-        writeln!(writer, "{}", buf.as_str())
+        out
+    }
+
+    /// The source code in a region
+    pub fn source_text(&self, region: Region) -> &'a str {
+        &self.source[region.start().offset as usize..region.end().offset as usize]
     }
 
     pub fn render_panic<W>(
@@ -233,7 +231,7 @@ impl<'a> Renderer<'a> {
         ]);
 
         let report = Report {
-            title: "EXPECT PANICKED".into(),
+            title: ReportKind::ExpectPanicked.into(),
             doc,
             filename: self.filename.clone(),
             severity,
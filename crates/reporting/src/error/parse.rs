@@ -3,6 +3,7 @@ use roc_problem::Severity;
 use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Position, Region};
 use std::path::PathBuf;
 
+use crate::code::ReportKind;
 use crate::report::{Report, RocDocAllocator, RocDocBuilder};
 use ven_pretty::DocAllocator;
 
@@ -64,7 +65,7 @@ fn to_syntax_report<'a>(
     let report = |doc| Report {
         filename: filename.clone(),
         doc,
-        title: "PARSE PROBLEM".to_string(),
+        title: ReportKind::ParseProblem.into(),
         severity,
     };
 
@@ -78,7 +79,7 @@ fn to_syntax_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "PARSE PROBLEM".to_string(),
+                title: ReportKind::ParseProblem.into(),
                 severity,
             }
         }
@@ -110,7 +111,7 @@ fn to_syntax_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "NOT END OF FILE".to_string(),
+                title: ReportKind::NotEndOfFile.into(),
                 severity,
             }
         }
@@ -123,7 +124,7 @@ fn to_syntax_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "PARSE PROBLEM".to_string(),
+                title: ReportKind::ParseProblem.into(),
                 severity,
             }
         }
@@ -133,7 +134,7 @@ fn to_syntax_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "PARSE PROBLEM".to_string(),
+                title: ReportKind::ParseProblem.into(),
                 severity,
             }
         }
@@ -212,7 +213,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "ARGUMENTS BEFORE EQUALS".to_string(),
+                title: ReportKind::ArgumentsBeforeEquals.into(),
                 severity,
             }
         }
@@ -327,7 +328,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNKNOWN OPERATOR".to_string(),
+                title: ReportKind::UnknownOperator.into(),
                 severity,
             }
         }
@@ -354,7 +355,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD IDENTIFIER".to_string(),
+                title: ReportKind::WeirdIdentifier.into(),
                 severity,
             }
         }
@@ -362,7 +363,7 @@ fn to_expr_report<'a>(
         EExpr::Start(pos) | EExpr::IndentStart(pos) => {
             let (title, expecting) = match &context {
                 Context::InNode { .. } | Context::InDef { .. } => (
-                    "MISSING EXPRESSION",
+                    ReportKind::MissingExpression,
                     alloc.concat([
                         alloc.reflow("I was expecting to see an expression like "),
                         alloc.parser_suggestion("42"),
@@ -372,7 +373,7 @@ fn to_expr_report<'a>(
                     ]),
                 ),
                 Context::InDefFinalExpr { .. } => (
-                    "MISSING FINAL EXPRESSION",
+                    ReportKind::MissingFinalExpression,
                     alloc.stack([
                         alloc.concat([
                             alloc.reflow("This definition is missing a final expression."),
@@ -436,7 +437,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: title.to_string(),
+                title: title.into(),
                 severity,
             }
         }
@@ -464,7 +465,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "MISSING FINAL EXPRESSION".to_string(),
+                title: ReportKind::MissingFinalExpression.into(),
                 severity,
             }
         }
@@ -495,7 +496,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "SYNTAX PROBLEM".to_string(),
+                title: ReportKind::SyntaxProblem.into(),
                 severity,
             }
         }
@@ -518,7 +519,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "ARGUMENTS BEFORE EQUALS".to_string(),
+                title: ReportKind::ArgumentsBeforeEquals.into(),
                 severity,
             }
         }
@@ -536,7 +537,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "BAD BACKPASSING ARROW".to_string(),
+                title: ReportKind::BadBackpassingArrow.into(),
                 severity,
             }
         }
@@ -560,7 +561,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "BAD OLD-STYLE RECORD BUILDER".to_string(),
+                title: ReportKind::BadOldStyleRecordBuilder.into(),
                 severity,
             }
         }
@@ -580,7 +581,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "BAD RECORD UPDATE".to_string(),
+                title: ReportKind::BadRecordUpdate.into(),
                 severity,
             }
         }
@@ -647,7 +648,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "INDENT ENDS AFTER EXPRESSION".to_string(),
+                title: ReportKind::IndentEndsAfterExpression.into(),
                 severity,
             }
         }
@@ -674,7 +675,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "TRAILING OPERATOR".to_string(),
+                title: ReportKind::TrailingOperator.into(),
                 severity,
             }
         }
@@ -691,7 +692,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNEXPECTED COMMA".to_string(),
+                title: ReportKind::UnexpectedComma.into(),
                 severity,
             }
         }
@@ -714,7 +715,7 @@ fn to_expr_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "STATEMENT AFTER EXPRESSION".to_string(),
+                title: ReportKind::StatementAfterExpression.into(),
                 severity,
             }
         }
@@ -743,7 +744,7 @@ fn to_record_report<'a>(
     Report {
         filename,
         doc,
-        title: "RECORD PARSE PROBLEM".to_string(),
+        title: ReportKind::RecordParseProblem.into(),
         severity,
     }
 }
@@ -780,7 +781,7 @@ fn to_lambda_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "WEIRD ARROW".to_string(),
+                    title: ReportKind::WeirdArrow.into(),
                     severity,
                 }
             }
@@ -802,7 +803,7 @@ fn to_lambda_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "MISSING ARROW".to_string(),
+                    title: ReportKind::MissingArrow.into(),
                     severity,
                 }
             }
@@ -827,7 +828,7 @@ fn to_lambda_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "WEIRD ARROW".to_string(),
+                    title: ReportKind::WeirdArrow.into(),
                     severity,
                 }
             }
@@ -849,7 +850,7 @@ fn to_lambda_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "MISSING ARROW".to_string(),
+                    title: ReportKind::MissingArrow.into(),
                     severity,
                 }
             }
@@ -873,7 +874,7 @@ fn to_lambda_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED ARGUMENT LIST".to_string(),
+                    title: ReportKind::UnfinishedArgumentList.into(),
                     severity,
                 }
             }
@@ -894,7 +895,7 @@ fn to_lambda_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "MISSING ARROW".to_string(),
+                    title: ReportKind::MissingArrow.into(),
                     severity,
                 }
             }
@@ -976,7 +977,7 @@ fn to_unfinished_lambda_report<'a>(
     Report {
         filename,
         doc,
-        title: "UNFINISHED FUNCTION".to_string(),
+        title: ReportKind::UnfinishedFunction.into(),
         severity,
     }
 }
@@ -1043,7 +1044,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD ESCAPE".to_string(),
+                title: ReportKind::WeirdEscape.into(),
                 severity,
             }
         }
@@ -1069,7 +1070,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD CODE POINT".to_string(),
+                title: ReportKind::WeirdCodePoint.into(),
                 severity,
             }
         }
@@ -1090,7 +1091,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "ENDLESS FORMAT".to_string(),
+                title: ReportKind::EndlessFormat.into(),
                 severity,
             }
         }
@@ -1113,7 +1114,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "ENDLESS SCALAR".to_string(),
+                title: ReportKind::EndlessScalar.into(),
                 severity,
             }
         }
@@ -1179,7 +1180,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "INVALID SCALAR".to_string(),
+                title: ReportKind::InvalidScalar.into(),
                 severity,
             }
         }
@@ -1202,7 +1203,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "ENDLESS STRING".to_string(),
+                title: ReportKind::EndlessString.into(),
                 severity,
             }
         }
@@ -1226,7 +1227,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "EXPECTED STRING".to_string(),
+                title: ReportKind::ExpectedString.into(),
                 severity,
             }
         }
@@ -1249,7 +1250,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "ENDLESS STRING".to_string(),
+                title: ReportKind::EndlessString.into(),
                 severity,
             }
         }
@@ -1270,7 +1271,7 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "INSUFFICIENT INDENT IN MULTI-LINE STRING".to_string(),
+                title: ReportKind::InsufficientIndentInMultiLineString.into(),
                 severity,
             }
         }
@@ -1312,7 +1313,7 @@ fn to_expr_in_parens_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "EMPTY PARENTHESES".to_string(),
+                title: ReportKind::EmptyParentheses.into(),
                 severity,
             }
         }
@@ -1336,7 +1337,7 @@ fn to_expr_in_parens_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED PARENTHESES".to_string(),
+                title: ReportKind::UnfinishedParentheses.into(),
                 severity,
             }
         }
@@ -1361,7 +1362,7 @@ fn to_expr_in_parens_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED PARENTHESES".to_string(),
+                title: ReportKind::UnfinishedParentheses.into(),
                 severity,
             }
         }
@@ -1415,7 +1416,7 @@ fn to_list_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED LIST".to_string(),
+                        title: ReportKind::UnfinishedList.into(),
                         severity,
                     }
                 }
@@ -1452,7 +1453,7 @@ fn to_list_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED LIST".to_string(),
+                        title: ReportKind::UnfinishedList.into(),
                         severity,
                     }
                 }
@@ -1576,7 +1577,7 @@ fn to_import_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "OLD-STYLE RECORD BUILDER IN MODULE PARAMS".to_string(),
+                title: ReportKind::OldStyleRecordBuilderInModuleParams.into(),
                 severity,
             }
         }
@@ -1593,7 +1594,7 @@ fn to_import_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "RECORD UPDATE IN MODULE PARAMS".to_string(),
+                title: ReportKind::RecordUpdateInModuleParams.into(),
                 severity,
             }
         }
@@ -1610,7 +1611,7 @@ fn to_import_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "RECORD BUILDER IN MODULE PARAMS".to_string(),
+                title: ReportKind::RecordBuilderInModuleParams.into(),
                 severity,
             }
         }
@@ -1639,7 +1640,7 @@ fn to_import_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "LOWERCASE ALIAS".to_string(),
+                title: ReportKind::LowercaseAlias.into(),
                 severity,
             }
         }
@@ -1674,7 +1675,7 @@ fn to_import_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD EXPOSING".to_string(),
+                title: ReportKind::WeirdExposing.into(),
                 severity,
             }
         }
@@ -1736,7 +1737,7 @@ fn to_unfinished_import_report<'a>(
     Report {
         filename,
         doc,
-        title: "UNFINISHED IMPORT".to_string(),
+        title: ReportKind::UnfinishedImport.into(),
         severity,
     }
 }
@@ -1849,7 +1850,7 @@ fn to_unfinished_if_report<'a>(
     Report {
         filename,
         doc,
-        title: "UNFINISHED IF".to_string(),
+        title: ReportKind::UnfinishedIf.into(),
         severity,
     }
 }
@@ -1886,7 +1887,7 @@ fn to_when_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "IF GUARD NO CONDITION".to_string(),
+                        title: ReportKind::IfGuardNoCondition.into(),
                         severity,
                     }
                 }
@@ -1918,7 +1919,7 @@ fn to_when_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "MISSING ARROW".to_string(),
+                title: ReportKind::MissingArrow.into(),
                 severity,
             }
         }
@@ -2073,7 +2074,7 @@ fn to_unfinished_when_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED WHEN".to_string(),
+                title: ReportKind::UnfinishedWhen.into(),
                 severity,
             }
         }
@@ -2115,7 +2116,7 @@ fn to_unexpected_arrow_report<'a>(
     Report {
         filename,
         doc,
-        title: "UNEXPECTED ARROW".to_string(),
+        title: ReportKind::UnexpectedArrow.into(),
         severity,
     }
 }
@@ -2192,7 +2193,7 @@ fn to_pattern_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED PATTERN".to_string(),
+                title: ReportKind::UnfinishedPattern.into(),
                 severity,
             }
         }
@@ -2238,7 +2239,7 @@ fn to_precord_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED RECORD PATTERN".to_string(),
+                    title: ReportKind::UnfinishedRecordPattern.into(),
                     severity,
                 }
             }
@@ -2259,7 +2260,7 @@ fn to_precord_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED RECORD PATTERN".to_string(),
+                    title: ReportKind::UnfinishedRecordPattern.into(),
                     severity,
                 }
             }
@@ -2284,7 +2285,7 @@ fn to_precord_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED RECORD PATTERN".to_string(),
+                        title: ReportKind::UnfinishedRecordPattern.into(),
                         severity,
                     }
                 }
@@ -2304,7 +2305,7 @@ fn to_precord_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED RECORD PATTERN".to_string(),
+                        title: ReportKind::UnfinishedRecordPattern.into(),
                         severity,
                     }
                 }
@@ -2329,7 +2330,7 @@ fn to_precord_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED RECORD PATTERN".to_string(),
+                    title: ReportKind::UnfinishedRecordPattern.into(),
                     severity,
                 }
             }
@@ -2354,7 +2355,7 @@ fn to_precord_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "PROBLEM IN RECORD PATTERN".to_string(),
+                    title: ReportKind::ProblemInRecordPattern.into(),
                     severity,
                 }
             }
@@ -2404,7 +2405,7 @@ fn to_plist_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED LIST PATTERN".to_string(),
+                title: ReportKind::UnfinishedListPattern.into(),
                 severity,
             }
         }
@@ -2426,7 +2427,7 @@ fn to_plist_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED LIST PATTERN".to_string(),
+                title: ReportKind::UnfinishedListPattern.into(),
                 severity,
             }
         }
@@ -2448,7 +2449,7 @@ fn to_plist_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "INCORRECT REST PATTERN".to_string(),
+                title: ReportKind::IncorrectRestPattern.into(),
                 severity,
             }
         }
@@ -2493,7 +2494,7 @@ fn to_pattern_in_parens_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED PARENTHESES".to_string(),
+                title: ReportKind::UnfinishedParentheses.into(),
                 severity,
             }
         }
@@ -2515,7 +2516,7 @@ fn to_pattern_in_parens_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "EMPTY PARENTHESES".to_string(),
+                title: ReportKind::EmptyParentheses.into(),
                 severity,
             }
         }
@@ -2539,7 +2540,7 @@ fn to_pattern_in_parens_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED PARENTHESES".to_string(),
+                title: ReportKind::UnfinishedParentheses.into(),
                 severity,
             }
         }
@@ -2568,7 +2569,7 @@ fn to_malformed_number_literal_report<'a>(
     Report {
         filename,
         doc,
-        title: "INVALID NUMBER LITERAL".to_string(),
+        title: ReportKind::InvalidNumberLiteral.into(),
         severity,
     }
 }
@@ -2609,7 +2610,7 @@ fn to_type_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "DOUBLE COMMA".to_string(),
+                        title: ReportKind::DoubleComma.into(),
                         severity,
                     }
                 }
@@ -2636,7 +2637,7 @@ fn to_type_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED TYPE".to_string(),
+                title: ReportKind::UnfinishedType.into(),
                 severity,
             }
         }
@@ -2654,7 +2655,7 @@ fn to_type_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED TYPE".to_string(),
+                title: ReportKind::UnfinishedType.into(),
                 severity,
             }
         }
@@ -2672,7 +2673,7 @@ fn to_type_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED TYPE".to_string(),
+                title: ReportKind::UnfinishedType.into(),
                 severity,
             }
         }
@@ -2690,7 +2691,7 @@ fn to_type_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED INLINE ALIAS".to_string(),
+                title: ReportKind::UnfinishedInlineAlias.into(),
                 severity,
             }
         }
@@ -2707,7 +2708,7 @@ fn to_type_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "BAD TYPE VARIABLE".to_string(),
+                title: ReportKind::BadTypeVariable.into(),
                 severity,
             }
         }
@@ -2746,7 +2747,7 @@ fn to_trecord_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED RECORD TYPE".to_string(),
+                    title: ReportKind::UnfinishedRecordType.into(),
                     severity,
                 }
             }
@@ -2771,7 +2772,7 @@ fn to_trecord_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED RECORD TYPE".to_string(),
+                    title: ReportKind::UnfinishedRecordType.into(),
                     severity,
                 }
             }
@@ -2796,7 +2797,7 @@ fn to_trecord_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED RECORD TYPE".to_string(),
+                        title: ReportKind::UnfinishedRecordType.into(),
                         severity,
                     }
                 }
@@ -2816,7 +2817,7 @@ fn to_trecord_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED RECORD TYPE".to_string(),
+                        title: ReportKind::UnfinishedRecordType.into(),
                         severity,
                     }
                 }
@@ -2841,7 +2842,7 @@ fn to_trecord_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED RECORD TYPE".to_string(),
+                    title: ReportKind::UnfinishedRecordType.into(),
                     severity,
                 }
             }
@@ -2866,7 +2867,7 @@ fn to_trecord_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "PROBLEM IN RECORD TYPE".to_string(),
+                    title: ReportKind::ProblemInRecordType.into(),
                     severity,
                 }
             }
@@ -2899,7 +2900,7 @@ fn to_trecord_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED RECORD TYPE".to_string(),
+                title: ReportKind::UnfinishedRecordType.into(),
                 severity,
             }
         }
@@ -2923,7 +2924,7 @@ fn to_trecord_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "NEED MORE INDENTATION".to_string(),
+                        title: ReportKind::NeedMoreIndentation.into(),
                         severity,
                     }
                 }
@@ -2952,7 +2953,7 @@ fn to_trecord_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED RECORD TYPE".to_string(),
+                        title: ReportKind::UnfinishedRecordType.into(),
                         severity,
                     }
                 }
@@ -3001,7 +3002,7 @@ fn to_ttag_union_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED TAG UNION TYPE".to_string(),
+                    title: ReportKind::UnfinishedTagUnionType.into(),
                     severity,
                 }
             }
@@ -3027,7 +3028,7 @@ fn to_ttag_union_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "WEIRD TAG NAME".to_string(),
+                    title: ReportKind::WeirdTagName.into(),
                     severity,
                 }
             }
@@ -3052,7 +3053,7 @@ fn to_ttag_union_report<'a>(
                 Report {
                     filename,
                     doc,
-                    title: "UNFINISHED TAG UNION TYPE".to_string(),
+                    title: ReportKind::UnfinishedTagUnionType.into(),
                     severity,
                 }
             }
@@ -3082,7 +3083,7 @@ fn to_ttag_union_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "WEIRD TAG NAME".to_string(),
+                        title: ReportKind::WeirdTagName.into(),
                         severity,
                     }
                 }
@@ -3102,7 +3103,7 @@ fn to_ttag_union_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED TAG UNION TYPE".to_string(),
+                        title: ReportKind::UnfinishedTagUnionType.into(),
                         severity,
                     }
                 }
@@ -3147,7 +3148,7 @@ fn to_tinparens_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED PARENTHESES".to_string(),
+                        title: ReportKind::UnfinishedParentheses.into(),
                         severity,
                     }
                 }
@@ -3169,7 +3170,7 @@ fn to_tinparens_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "WEIRD TAG NAME".to_string(),
+                        title: ReportKind::WeirdTagName.into(),
                         severity,
                     }
                 }
@@ -3196,7 +3197,7 @@ fn to_tinparens_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED PARENTHESES".to_string(),
+                        title: ReportKind::UnfinishedParentheses.into(),
                         severity,
                     }
                 }
@@ -3219,7 +3220,7 @@ fn to_tinparens_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "EMPTY PARENTHESES".to_string(),
+                title: ReportKind::EmptyParentheses.into(),
                 severity,
             }
         }
@@ -3245,7 +3246,7 @@ fn to_tinparens_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "WEIRD TAG NAME".to_string(),
+                        title: ReportKind::WeirdTagName.into(),
                         severity,
                     }
                 }
@@ -3265,7 +3266,7 @@ fn to_tinparens_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED PARENTHESES".to_string(),
+                        title: ReportKind::UnfinishedParentheses.into(),
                         severity,
                     }
                 }
@@ -3293,7 +3294,7 @@ fn to_tinparens_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "UNFINISHED PARENTHESES".to_string(),
+                title: ReportKind::UnfinishedParentheses.into(),
                 severity,
             }
         }
@@ -3317,7 +3318,7 @@ fn to_tinparens_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "NEED MORE INDENTATION".to_string(),
+                        title: ReportKind::NeedMoreIndentation.into(),
                         severity,
                     }
                 }
@@ -3342,7 +3343,7 @@ fn to_tinparens_report<'a>(
                     Report {
                         filename,
                         doc,
-                        title: "UNFINISHED PARENTHESES".to_string(),
+                        title: ReportKind::UnfinishedParentheses.into(),
                         severity,
                     }
                 }
@@ -3376,7 +3377,7 @@ fn to_tapply_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "DOUBLE DOT".to_string(),
+                title: ReportKind::DoubleDot.into(),
                 severity,
             }
         }
@@ -3398,7 +3399,7 @@ fn to_tapply_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "TRAILING DOT".to_string(),
+                title: ReportKind::TrailingDot.into(),
                 severity,
             }
         }
@@ -3420,7 +3421,7 @@ fn to_tapply_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD QUALIFIED NAME".to_string(),
+                title: ReportKind::WeirdQualifiedName.into(),
                 severity,
             }
         }
@@ -3442,7 +3443,7 @@ fn to_tapply_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD QUALIFIED NAME".to_string(),
+                title: ReportKind::WeirdQualifiedName.into(),
                 severity,
             }
         }
@@ -3460,7 +3461,7 @@ fn to_tapply_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "END OF FILE".to_string(),
+                title: ReportKind::EndOfFile.into(),
                 severity,
             }
         }
@@ -3501,7 +3502,7 @@ fn to_talias_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "NOT AN INLINE ALIAS".to_string(),
+                title: ReportKind::NotAnInlineAlias.into(),
                 severity,
             }
         }
@@ -3517,7 +3518,7 @@ fn to_talias_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "QUALIFIED ALIAS NAME".to_string(),
+                title: ReportKind::QualifiedAliasName.into(),
                 severity,
             }
         }
@@ -3533,7 +3534,7 @@ fn to_talias_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "TYPE ARGUMENT NOT LOWERCASE".to_string(),
+                title: ReportKind::TypeArgumentNotLowercase.into(),
                 severity,
             }
         }
@@ -3583,7 +3584,7 @@ fn to_header_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "INCOMPLETE HEADER".to_string(),
+                title: ReportKind::IncompleteHeader.into(),
                 severity,
             }
         }
@@ -3625,7 +3626,7 @@ fn to_header_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "MISSING HEADER".to_string(),
+                title: ReportKind::MissingHeader.into(),
                 severity,
             }
         }
@@ -3649,7 +3650,7 @@ fn to_header_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD MODULE NAME".to_string(),
+                title: ReportKind::WeirdModuleName.into(),
                 severity,
             }
         }
@@ -3676,7 +3677,7 @@ fn to_header_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD MODULE NAME".to_string(),
+                title: ReportKind::WeirdModuleName.into(),
                 severity,
             }
         }
@@ -3700,7 +3701,7 @@ fn to_header_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD APP NAME".to_string(),
+                title: ReportKind::WeirdAppName.into(),
                 severity,
             }
         }
@@ -3722,7 +3723,7 @@ fn to_header_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "INVALID PACKAGE NAME".to_string(),
+                title: ReportKind::InvalidPackageName.into(),
                 severity,
             }
         }
@@ -3745,7 +3746,7 @@ fn to_header_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "INVALID PLATFORM NAME".to_string(),
+                title: ReportKind::InvalidPlatformName.into(),
                 severity,
             }
         }
@@ -3768,7 +3769,7 @@ fn to_header_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD GENERATED TYPE NAME".to_string(),
+                title: ReportKind::WeirdGeneratedTypeName.into(),
                 severity,
             }
         }
@@ -3810,7 +3811,7 @@ fn to_generates_with_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD GENERATES".to_string(),
+                title: ReportKind::WeirdGenerates.into(),
                 severity,
             }
         }
@@ -3835,7 +3836,7 @@ fn to_generates_with_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD GENERATES".to_string(),
+                title: ReportKind::WeirdGenerates.into(),
                 severity,
             }
         }
@@ -3878,7 +3879,7 @@ fn to_provides_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD PROVIDES".to_string(),
+                title: ReportKind::WeirdProvides.into(),
                 severity,
             }
         }
@@ -3903,7 +3904,7 @@ fn to_provides_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD PROVIDES".to_string(),
+                title: ReportKind::WeirdProvides.into(),
                 severity,
             }
         }
@@ -3930,7 +3931,7 @@ fn to_provides_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD PROVIDES".to_string(),
+                title: ReportKind::WeirdProvides.into(),
                 severity,
             }
         }
@@ -3951,7 +3952,7 @@ fn to_provides_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD PROVIDES".to_string(),
+                title: ReportKind::WeirdProvides.into(),
                 severity,
             }
         }
@@ -3995,7 +3996,7 @@ fn to_params_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD MODULE PARAMS".to_string(),
+                title: ReportKind::WeirdModuleParams.into(),
                 severity,
             }
         }
@@ -4035,7 +4036,7 @@ fn to_exposes_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD EXPOSES".to_string(),
+                title: ReportKind::WeirdExposes.into(),
                 severity,
             }
         }
@@ -4060,7 +4061,7 @@ fn to_exposes_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD EXPOSES".to_string(),
+                title: ReportKind::WeirdExposes.into(),
                 severity,
             }
         }
@@ -4101,7 +4102,7 @@ fn to_imports_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD IMPORTS".to_string(),
+                title: ReportKind::WeirdImports.into(),
                 severity,
             }
         }
@@ -4126,7 +4127,7 @@ fn to_imports_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD IMPORTS".to_string(),
+                title: ReportKind::WeirdImports.into(),
                 severity,
             }
         }
@@ -4152,7 +4153,7 @@ fn to_imports_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD MODULE NAME".to_string(),
+                title: ReportKind::WeirdModuleName.into(),
                 severity,
             }
         }
@@ -4171,7 +4172,7 @@ fn to_imports_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD IMPORTS".to_string(),
+                title: ReportKind::WeirdImports.into(),
                 severity,
             }
         }
@@ -4212,7 +4213,7 @@ fn to_requires_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "MISSING REQUIRES".to_string(),
+                title: ReportKind::MissingRequires.into(),
                 severity,
             }
         }
@@ -4239,7 +4240,7 @@ fn to_requires_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "MISSING REQUIRES".to_string(),
+                title: ReportKind::MissingRequires.into(),
                 severity,
             }
         }
@@ -4268,7 +4269,7 @@ fn to_requires_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "BAD REQUIRES RIGIDS".to_string(),
+                title: ReportKind::BadRequiresRigids.into(),
                 severity,
             }
         }
@@ -4297,7 +4298,7 @@ fn to_requires_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "BAD REQUIRES".to_string(),
+                title: ReportKind::BadRequires.into(),
                 severity,
             }
         }
@@ -4336,7 +4337,7 @@ fn to_packages_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "MISSING PACKAGES".to_string(),
+                title: ReportKind::MissingPackages.into(),
                 severity,
             }
         }
@@ -4358,7 +4359,7 @@ fn to_packages_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "WEIRD PACKAGES LIST".to_string(),
+                title: ReportKind::WeirdPackagesList.into(),
                 severity,
             }
         }
@@ -4395,7 +4396,7 @@ fn to_space_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "TAB CHARACTER".to_string(),
+                title: ReportKind::TabCharacter.into(),
                 severity,
             }
         }
@@ -4412,7 +4413,7 @@ fn to_space_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "ASCII CONTROL CHARACTER".to_string(),
+                title: ReportKind::AsciiControlCharacter.into(),
                 severity,
             }
         }
@@ -4429,7 +4430,7 @@ fn to_space_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "MISPLACED CARRIAGE RETURN".to_string(),
+                title: ReportKind::MisplacedCarriageReturn.into(),
                 severity,
             }
         }
@@ -4511,7 +4512,7 @@ fn to_unfinished_ability_report<'a>(
     Report {
         filename,
         doc,
-        title: "UNFINISHED ABILITY".to_string(),
+        title: ReportKind::UnfinishedAbility.into(),
         severity,
     }
 }
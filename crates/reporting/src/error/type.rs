@@ -1,6 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
-use crate::error::canonicalize::{to_circular_def_doc, CIRCULAR_DEF};
+use crate::code::ReportKind;
+use crate::error::canonicalize::to_circular_def_doc;
 use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder};
 use itertools::EitherOrBoth;
 use itertools::Itertools;
@@ -45,9 +46,9 @@ pub fn type_problem<'b>(
     let severity = problem.severity();
 
     let report =
-        move |title: String, doc: RocDocBuilder<'b>, filename: PathBuf| -> Option<Report<'b>> {
+        move |kind: ReportKind, doc: RocDocBuilder<'b>, filename: PathBuf| -> Option<Report<'b>> {
             Some(Report {
-                title,
+                title: kind.into(),
                 filename,
                 doc,
                 severity,
@@ -71,7 +72,7 @@ pub fn type_problem<'b>(
             overall_type,
         )),
         UnexposedLookup(_, symbol) => {
-            let title = "UNRECOGNIZED NAME".to_string();
+            let title = ReportKind::UnrecognizedName;
             let doc = alloc
                 .stack(vec![alloc
                     .reflow("The ")
@@ -83,7 +84,7 @@ pub fn type_problem<'b>(
             report(title, doc, filename)
         }
         UnfulfilledAbility(incomplete) => {
-            let title = "INCOMPLETE ABILITY IMPLEMENTATION".to_string();
+            let title = ReportKind::IncompleteAbilityImplementation;
 
             let doc = report_unfulfilled_ability(alloc, lines, incomplete, severity);
 
@@ -108,7 +109,7 @@ pub fn type_problem<'b>(
             ];
 
             let report = Report {
-                title: "TYPE MISMATCH".to_string(),
+                title: ReportKind::TypeMismatch.into(),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -130,7 +131,7 @@ pub fn type_problem<'b>(
             ];
 
             let report = Report {
-                title: "TYPE MISMATCH".to_string(),
+                title: ReportKind::TypeMismatch.into(),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -140,7 +141,7 @@ pub fn type_problem<'b>(
         Exhaustive(problem) => Some(exhaustive_problem(alloc, lines, filename, problem)),
         CircularDef(entries) => {
             let doc = to_circular_def_doc(alloc, lines, &entries, severity);
-            let title = CIRCULAR_DEF.to_string();
+            let title = ReportKind::CircularDefinition.into();
 
             Some(Report {
                 title,
@@ -173,7 +174,7 @@ pub fn type_problem<'b>(
             ];
 
             Some(Report {
-                title: "ILLEGAL SPECIALIZATION".to_string(),
+                title: ReportKind::IllegalSpecialization.into(),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -202,7 +203,7 @@ pub fn type_problem<'b>(
             ];
 
             Some(Report {
-                title: "WRONG SPECIALIZATION TYPE".to_string(),
+                title: ReportKind::WrongSpecializationType.into(),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -218,7 +219,7 @@ pub fn type_problem<'b>(
                 text!(alloc, "{}", utf8_err),
             ];
             Some(Report {
-                title: "INVALID UTF-8".to_string(),
+                title: ReportKind::InvalidUtf8.into(),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -241,7 +242,7 @@ pub fn type_problem<'b>(
                 ]),
             ];
             Some(Report {
-                title: "INVALID TYPE FOR INGESTED FILE".to_string(),
+                title: ReportKind::InvalidTypeForIngestedFile.into(),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -448,7 +449,7 @@ pub fn cyclic_alias<'b>(
     others: Vec<Symbol>,
     alias_kind: AliasKind,
     severity: Severity,
-) -> (RocDocBuilder<'b>, String) {
+) -> (RocDocBuilder<'b>, ReportKind) {
     let when_is_recursion_legal =
         alloc.reflow("Recursion in ")
         .append(alloc.reflow(alias_kind.as_str()))
@@ -495,7 +496,7 @@ pub fn cyclic_alias<'b>(
         ])
     };
 
-    (doc, "CYCLIC ALIAS".to_string())
+    (doc, ReportKind::CyclicAlias)
 }
 
 fn report_mismatch<'b>(
@@ -537,7 +538,7 @@ fn report_mismatch<'b>(
     ];
 
     Report {
-        title: "TYPE MISMATCH".to_string(),
+        title: ReportKind::TypeMismatch.into(),
         filename,
         doc: alloc.stack(lines),
         severity,
@@ -581,7 +582,7 @@ fn report_bad_type<'b>(
     ];
 
     Report {
-        title: "TYPE MISMATCH".to_string(),
+        title: ReportKind::TypeMismatch.into(),
         filename,
         doc: alloc.stack(lines),
         severity,
@@ -665,7 +666,7 @@ fn to_expr_report<'b>(
 
             Report {
                 filename,
-                title: "TYPE MISMATCH".to_string(),
+                title: ReportKind::TypeMismatch.into(),
                 doc: alloc.stack([
                     alloc.text("This expression is used in an unexpected way:"),
                     alloc.region(lines.convert_region(expr_region), severity),
@@ -806,7 +807,7 @@ fn to_expr_report<'b>(
             };
 
             Report {
-                title: "TYPE MISMATCH".to_string(),
+                title: ReportKind::TypeMismatch.into(),
                 filename,
                 doc: alloc.stack([
                     alloc.text("Something is off with the ").append(thing),
@@ -1219,7 +1220,7 @@ fn to_expr_report<'b>(
 
                     Report {
                         filename,
-                        title: "TOO MANY ARGS".to_string(),
+                        title: ReportKind::TooManyArgs.into(),
                         doc,
                         severity,
                     }
@@ -1254,7 +1255,7 @@ fn to_expr_report<'b>(
 
                         Report {
                             filename,
-                            title: "TOO MANY ARGS".to_string(),
+                            title: ReportKind::TooManyArgs.into(),
                             doc: alloc.stack(lines),
                             severity,
                         }
@@ -1281,7 +1282,7 @@ fn to_expr_report<'b>(
 
                         Report {
                             filename,
-                            title: "TOO FEW ARGS".to_string(),
+                            title: ReportKind::TooFewArgs.into(),
                             doc: alloc.stack(lines),
                             severity,
                         }
@@ -1482,7 +1483,7 @@ fn to_expr_report<'b>(
                 ];
 
                 Report {
-                    title: "TYPE MISMATCH".to_string(),
+                    title: ReportKind::TypeMismatch.into(),
                     filename,
                     doc: alloc.stack(lines),
                     severity,
@@ -1520,7 +1521,7 @@ fn to_expr_report<'b>(
 
                 Report {
                     filename,
-                    title: "TYPE MISMATCH".to_string(),
+                    title: ReportKind::TypeMismatch.into(),
                     doc,
                     severity,
                 }
@@ -1556,7 +1557,7 @@ fn to_expr_report<'b>(
 
                 Report {
                     filename,
-                    title: "TYPE MISMATCH".to_string(),
+                    title: ReportKind::TypeMismatch.into(),
                     doc: alloc.stack(lines),
                     severity,
                 }
@@ -1969,7 +1970,7 @@ fn to_pattern_report<'b>(
 
             Report {
                 filename,
-                title: "TYPE MISMATCH".to_string(),
+                title: ReportKind::TypeMismatch.into(),
                 doc,
                 severity,
             }
@@ -2012,7 +2013,7 @@ fn to_pattern_report<'b>(
 
                 Report {
                     filename,
-                    title: "TYPE MISMATCH".to_string(),
+                    title: ReportKind::TypeMismatch.into(),
                     doc,
                     severity,
                 }
@@ -2094,7 +2095,7 @@ fn to_pattern_report<'b>(
                 };
                 Report {
                     filename,
-                    title: "TYPE MISMATCH".to_string(),
+                    title: ReportKind::TypeMismatch.into(),
                     doc,
                     severity,
                 }
@@ -2121,7 +2122,7 @@ fn to_pattern_report<'b>(
 
                 Report {
                     filename,
-                    title: "TYPE MISMATCH".to_string(),
+                    title: ReportKind::TypeMismatch.into(),
                     doc,
                     severity,
                 }
@@ -2205,7 +2206,7 @@ fn to_circular_report<'b>(
     overall_type: ErrorType,
 ) -> Report<'b> {
     Report {
-        title: "CIRCULAR TYPE".to_string(),
+        title: ReportKind::CircularType.into(),
         filename,
         doc: {
             alloc.stack([
@@ -4893,7 +4894,7 @@ fn report_record_field_typo<'b>(
 
     Report {
         filename,
-        title: "TYPE MISMATCH".to_string(),
+        title: ReportKind::TypeMismatch.into(),
         doc,
         severity,
     }
@@ -4930,7 +4931,7 @@ fn exhaustive_problem<'a>(
 
                 Report {
                     filename,
-                    title: "UNSAFE PATTERN".to_string(),
+                    title: ReportKind::UnsafePattern.into(),
                     doc,
                     severity,
                 }
@@ -4954,7 +4955,7 @@ fn exhaustive_problem<'a>(
 
                 Report {
                     filename,
-                    title: "UNSAFE PATTERN".to_string(),
+                    title: ReportKind::UnsafePattern.into(),
                     doc,
                     severity,
                 }
@@ -4978,7 +4979,7 @@ fn exhaustive_problem<'a>(
 
                 Report {
                     filename,
-                    title: "UNSAFE PATTERN".to_string(),
+                    title: ReportKind::UnsafePattern.into(),
                     doc,
                     severity,
                 }
@@ -5008,7 +5009,7 @@ fn exhaustive_problem<'a>(
 
             Report {
                 filename,
-                title: "REDUNDANT PATTERN".to_string(),
+                title: ReportKind::RedundantPattern.into(),
                 doc,
                 severity,
             }
@@ -5037,7 +5038,7 @@ fn exhaustive_problem<'a>(
 
             Report {
                 filename,
-                title: "UNMATCHABLE PATTERN".to_string(),
+                title: ReportKind::UnmatchablePattern.into(),
                 doc,
                 severity,
             }
@@ -0,0 +1,163 @@
+//! Renders problems as JSON instead of ANSI text, for editors and CI that annotate source code
+//! with them.
+use std::path::{Path, PathBuf};
+
+use roc_collections::MutMap;
+use roc_module::symbol::{Interns, ModuleId};
+use roc_problem::can::Problem;
+use roc_problem::Severity;
+use roc_region::all::{LineColumn, LineInfo, Region};
+use roc_solve_problem::TypeError;
+use serde_json::{json, Value};
+
+use crate::cli::Problems;
+use crate::report::{can_problem, type_problem, Report, RocDocAllocator};
+
+/// Every problem in every module, errors and warnings alike:
+///
+/// ```json
+/// {
+///   "diagnostics": [{
+///     "file": "main.roc",
+///     "region": { "start": { "line": 4, "column": 4 }, "end": { "line": 4, "column": 9 } },
+///     "severity": "warning",
+///     "code": "E104",
+///     "title": "UNUSED IMPORT",
+///     "message": "Json is imported but not used. ...",
+///     "fixes": [{
+///       "message": "Remove this import",
+///       "region": { "start": ..., "end": ... },
+///       "replacement": ""
+///     }]
+///   }],
+///   "errors": 0,
+///   "warnings": 1
+/// }
+/// ```
+///
+/// `region` is null for problems that aren't about any particular code. `severity` is either
+/// "error" or "warning", and `code` is the one `roc explain` takes. Lines and columns start at 0,
+/// like in the language server.
+pub fn problems_json(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    can_problems: &mut MutMap<ModuleId, Vec<Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+) -> (Value, Problems) {
+    let mut diagnostics = Vec::new();
+    let mut problems = Problems::default();
+
+    for (home, (module_path, src)) in sources.iter() {
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let alloc = RocDocAllocator::new(&src_lines, *home, interns);
+
+        for problem in type_problems.remove(home).unwrap_or_default() {
+            let region = problem.region();
+
+            if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+                count(&mut problems, report.severity);
+                diagnostics.push(diagnostic_json(report, &lines, region, Vec::new()));
+            }
+        }
+
+        for problem in can_problems.remove(home).unwrap_or_default() {
+            let region = problem.region();
+            let fixes = fixes_json(&problem, &lines);
+            let report = can_problem(&alloc, &lines, module_path.clone(), problem);
+
+            count(&mut problems, report.severity);
+            diagnostics.push(diagnostic_json(report, &lines, region, fixes));
+        }
+    }
+
+    let json = json!({
+        "diagnostics": diagnostics,
+        "errors": problems.errors,
+        "warnings": problems.warnings,
+    });
+
+    (json, problems)
+}
+
+/// A single error that stopped the compiler before it could report anything more precise,
+/// in the same shape as `problems_json`.
+pub fn loading_problem_json(file: Option<&Path>, message: &str) -> Value {
+    json!({
+        "diagnostics": [{
+            "file": file.map(|path| path.display().to_string()),
+            "region": null,
+            "severity": "error",
+            "code": null,
+            "title": null,
+            "message": message,
+            "fixes": [],
+        }],
+        "errors": 1,
+        "warnings": 0,
+    })
+}
+
+fn count(problems: &mut Problems, severity: Severity) {
+    match severity {
+        Severity::Warning => problems.warnings += 1,
+        Severity::RuntimeError => problems.errors += 1,
+        Severity::Fatal => {
+            problems.fatally_errored = true;
+            problems.errors += 1;
+        }
+    }
+}
+
+fn diagnostic_json(
+    report: Report<'_>,
+    lines: &LineInfo,
+    region: Option<Region>,
+    fixes: Vec<Value>,
+) -> Value {
+    let file = report.filename.display().to_string();
+    let code = report.code();
+    let title = report.title.to_string();
+    let severity = match report.severity {
+        Severity::Warning => "warning",
+        Severity::RuntimeError | Severity::Fatal => "error",
+    };
+
+    let mut message = String::new();
+    report.render_message(&mut message);
+
+    json!({
+        "file": file,
+        "region": region.map(|region| region_json(lines, region)),
+        "severity": severity,
+        "code": code,
+        "title": title,
+        "message": message.trim_end(),
+        "fixes": fixes,
+    })
+}
+
+/// Edits that would make the problem go away, for the problems where there's only one
+/// reasonable thing to do.
+fn fixes_json(problem: &Problem, lines: &LineInfo) -> Vec<Value> {
+    match problem {
+        Problem::UnusedModuleImport(_, region) | Problem::ExplicitBuiltinImport(_, region) => {
+            vec![json!({
+                "message": "Remove this import",
+                "region": region_json(lines, *region),
+                "replacement": "",
+            })]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn region_json(lines: &LineInfo, region: Region) -> Value {
+    let region = lines.convert_region(region);
+
+    json!({ "start": position_json(region.start), "end": position_json(region.end) })
+}
+
+fn position_json(position: LineColumn) -> Value {
+    json!({ "line": position.line, "column": position.column })
+}
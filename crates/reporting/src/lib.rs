@@ -4,5 +4,7 @@
 #![allow(clippy::large_enum_variant)]
 
 pub mod cli;
+pub mod code;
 pub mod error;
+pub mod json;
 pub mod report;
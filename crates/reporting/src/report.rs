@@ -1,6 +1,7 @@
 use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, ModuleIds, PQModuleName, PackageQualified, Symbol};
+use roc_problem::backend::{BackendFailure, DevBackend, Unimplemented};
 use roc_problem::Severity;
 use roc_region::all::LineColumnRegion;
 use std::path::{Path, PathBuf};
@@ -12,6 +13,7 @@ use byte_unit::Byte;
 #[cfg(not(target_family = "wasm"))]
 use roc_packaging::https::Problem;
 
+use crate::code::ReportKind;
 pub use crate::error::canonicalize::can_problem;
 pub use crate::error::parse::parse_problem;
 pub use crate::error::r#type::type_problem;
@@ -115,9 +117,46 @@ pub enum RenderTarget {
     LanguageServer,
 }
 
+/// What a report is called in its header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReportTitle {
+    /// A kind of report with a stable code, which is shown next to its title
+    Kind(ReportKind),
+    /// A title without a code
+    Custom(String),
+}
+
+impl ReportTitle {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ReportTitle::Kind(kind) => kind.title(),
+            ReportTitle::Custom(title) => title,
+        }
+    }
+
+    pub fn kind(&self) -> Option<ReportKind> {
+        match self {
+            ReportTitle::Kind(kind) => Some(*kind),
+            ReportTitle::Custom(_) => None,
+        }
+    }
+}
+
+impl From<ReportKind> for ReportTitle {
+    fn from(kind: ReportKind) -> Self {
+        ReportTitle::Kind(kind)
+    }
+}
+
+impl fmt::Display for ReportTitle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// A textual report.
 pub struct Report<'b> {
-    pub title: String,
+    pub title: ReportTitle,
     pub filename: PathBuf,
     pub doc: RocDocBuilder<'b>,
     pub severity: Severity,
@@ -164,27 +203,48 @@ impl<'b> Report<'b> {
             .expect(err_msg);
     }
 
+    /// The stable code for this kind of report, e.g. `E001`. See `roc explain`.
+    pub fn code(&self) -> Option<&'static str> {
+        self.title.kind().map(ReportKind::code)
+    }
+
     pub fn pretty(self, alloc: &'b RocDocAllocator<'b>) -> RocDocBuilder<'b> {
-        if self.title.is_empty() {
+        if self.title.as_str().is_empty() {
             self.doc
         } else {
+            let title = match self.title {
+                ReportTitle::Kind(kind) => format!("{} [{}]", kind.title(), kind.code()),
+                ReportTitle::Custom(title) => title,
+            };
+
             let header = if self.filename == PathBuf::from("replfile.roc") {
-                crate::report::pretty_header(&self.title)
+                crate::report::pretty_header(&title)
             } else {
-                crate::report::pretty_header_with_path(&self.title, &self.filename)
+                crate::report::pretty_header_with_path(&title, &self.filename)
             };
 
             alloc.stack([alloc.text(header).annotate(Annotation::Header), self.doc])
         }
     }
 
+    /// Render just the body of the report, without the header or any colors, for tools that
+    /// show the title and the file separately.
+    pub fn render_message(self, buf: &mut String) {
+        let err_msg = "<buffer is not a utf-8 encoded string>";
+
+        self.doc
+            .1
+            .render_raw(70, &mut CiWrite::new(buf))
+            .expect(err_msg)
+    }
+
     /// Render report for the language server, where the window is narrower.
     /// Path is not included, and the header is not emphasized with "─".
     pub fn render_language_server(self, buf: &mut String, alloc: &'b RocDocAllocator<'b>) {
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
         alloc
-            .stack([alloc.text(self.title), self.doc])
+            .stack([alloc.text(self.title.to_string()), self.doc])
             .1
             .render_raw(60, &mut CiWrite::new(buf))
             .expect(err_msg)
@@ -1213,7 +1273,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "UNSUPPORTED ENCODING".to_string(),
+                title: ReportKind::UnsupportedEncoding.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1248,7 +1308,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "MULTIPLE ENCODINGS".to_string(),
+                title: ReportKind::MultipleEncodings.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1283,7 +1343,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "INVALID CONTENT HASH".to_string(),
+                title: ReportKind::InvalidContentHash.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1300,7 +1360,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "NOTFOUND".to_string(),
+                title: ReportTitle::Custom("NOTFOUND".to_string()),
                 severity: Severity::Fatal,
             }
         }
@@ -1327,7 +1387,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "IO ERROR".to_string(),
+                title: ReportKind::IoError.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1354,7 +1414,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "IO ERROR".to_string(),
+                title: ReportKind::IoError.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1383,7 +1443,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "HTTP ERROR".to_string(),
+                title: ReportKind::HttpError.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1424,7 +1484,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "INVALID EXTENSION SUFFIX".to_string(),
+                title: ReportKind::InvalidExtensionSuffix.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1457,7 +1517,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "INVALID EXTENSION".to_string(),
+                title: ReportKind::InvalidExtension.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1495,7 +1555,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "INVALID FRAGMENT".to_string(),
+                title: ReportKind::InvalidFragment.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1533,7 +1593,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "MISSING PACKAGE HASH".to_string(),
+                title: ReportKind::MissingPackageHash.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1559,7 +1619,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "HTTPS MANDATORY".to_string(),
+                title: ReportKind::HttpsMandatory.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1602,7 +1662,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "MISLEADING CHARACTERS".to_string(),
+                title: ReportKind::MisleadingCharacters.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1632,7 +1692,7 @@ pub fn to_https_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "FILE TOO LARGE".to_string(),
+                title: ReportKind::FileTooLarge.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1679,7 +1739,7 @@ pub fn to_file_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "FILE NOT FOUND".to_string(),
+                title: ReportKind::FileNotFound.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1697,7 +1757,7 @@ pub fn to_file_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "FILE PERMISSION DENIED".to_string(),
+                title: ReportKind::FilePermissionDenied.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1733,7 +1793,7 @@ pub fn to_file_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "NOT A ROC FILE".to_string(),
+                title: ReportKind::NotARocFile.into(),
                 severity: Severity::Fatal,
             }
         }
@@ -1753,9 +1813,116 @@ pub fn to_file_problem_report<'b>(
             Report {
                 filename,
                 doc,
-                title: "FILE PROBLEM".to_string(),
+                title: ReportKind::FileProblem.into(),
                 severity: Severity::Fatal,
             }
         }
     }
 }
+
+pub fn to_unimplemented_report_string(filename: PathBuf, problem: Unimplemented) -> String {
+    let src_lines: Vec<&str> = Vec::new();
+    let mut module_ids = ModuleIds::default();
+    let module_id = module_ids.get_or_insert(&"find module name somehow?".into());
+    let interns = Interns::default();
+
+    let alloc = RocDocAllocator::new(&src_lines, module_id, &interns);
+
+    let mut buf = String::new();
+    let palette = DEFAULT_PALETTE;
+    let report = to_unimplemented_report(&alloc, filename, problem);
+    report.render_color_terminal(&mut buf, &alloc, &palette);
+
+    buf
+}
+
+pub fn to_unimplemented_report<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    filename: PathBuf,
+    problem: Unimplemented,
+) -> Report<'b> {
+    let backend = match problem.backend {
+        DevBackend::Assembly => "dev backend",
+        DevBackend::Wasm => "WebAssembly dev backend",
+    };
+
+    let construct = if problem.construct.is_empty() {
+        alloc.reflow("(no details available)")
+    } else {
+        alloc.string(problem.construct)
+    };
+
+    let doc = alloc.stack([
+        alloc.concat([
+            alloc.reflow("The "),
+            alloc.reflow(backend),
+            alloc.reflow(" doesn't know how to generate code for this yet:"),
+        ]),
+        construct.annotate(Annotation::Error).indent(4),
+        alloc.concat([
+            alloc.tip(),
+            alloc.reflow("Building without "),
+            alloc.keyword("--dev"),
+            alloc.reflow(" uses the LLVM backend, which supports all of Roc."),
+        ]),
+    ]);
+
+    Report {
+        filename,
+        doc,
+        title: ReportKind::NotYetImplemented.into(),
+        severity: Severity::Fatal,
+    }
+}
+
+pub fn to_backend_failure_report_string(filename: PathBuf, failure: BackendFailure) -> String {
+    let src_lines: Vec<&str> = Vec::new();
+    let mut module_ids = ModuleIds::default();
+    let module_id = module_ids.get_or_insert(&"find module name somehow?".into());
+    let interns = Interns::default();
+
+    let alloc = RocDocAllocator::new(&src_lines, module_id, &interns);
+
+    let mut buf = String::new();
+    let palette = DEFAULT_PALETTE;
+    let report = to_backend_failure_report(&alloc, filename, failure);
+    report.render_color_terminal(&mut buf, &alloc, &palette);
+
+    buf
+}
+
+pub fn to_backend_failure_report<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    filename: PathBuf,
+    failure: BackendFailure,
+) -> Report<'b> {
+    let backend = match failure.backend {
+        DevBackend::Assembly => "dev backend",
+        DevBackend::Wasm => "WebAssembly dev backend",
+    };
+
+    let doc = alloc.stack([
+        alloc.concat([
+            alloc.reflow("The "),
+            alloc.reflow(backend),
+            alloc.reflow(" couldn't generate code for this program:"),
+        ]),
+        alloc
+            .string(failure.reason)
+            .annotate(Annotation::Error)
+            .indent(4),
+        alloc.concat([
+            alloc.tip(),
+            alloc.reflow("Building without "),
+            alloc.keyword("--dev"),
+            alloc.reflow(" uses the LLVM backend, which doesn't have this limit."),
+        ]),
+    ]);
+
+    Report {
+        filename,
+        doc,
+        title: ReportKind::CodeGenFailed.into(),
+        severity: Severity::Fatal,
+    }
+}
@@ -16,6 +16,7 @@ mod roc_box;
 mod roc_list;
 mod roc_str;
 mod storage;
+mod u256;
 
 pub use roc_box::RocBox;
 pub use roc_list::{RocList, SendSafeRocList};
@@ -32,7 +33,12 @@ extern "C" {
         alignment: u32,
     ) -> *mut c_void;
     pub fn roc_dealloc(ptr: *mut c_void, alignment: u32);
+    /// Called when the app crashes. `c_ptr` points to a `RocStr` holding the message.
+    /// `tag_id` is 0 if Roc itself crashed (e.g. on integer overflow) and 1 for a `crash` in the app.
     pub fn roc_panic(c_ptr: *mut c_void, tag_id: u32);
+    /// Called for every `dbg`. Each argument points to a `RocStr`: `loc` is the source location
+    /// (`path:line`), `msg` is the value formatted with `Inspect`, and `src` is the source code
+    /// of the expression. None of them are owned by the host.
     pub fn roc_dbg(loc: *mut c_void, msg: *mut c_void, src: *mut c_void);
     pub fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut c_void;
 }
@@ -45,9 +51,11 @@ pub fn roc_alloc_refcounted<T>() -> *mut T {
 }
 
 fn roc_alloc_refcounted_help(mut size: usize, mut align: usize) -> *mut u8 {
-    let prefix = if align > 8 { 16 } else { 8 };
-    size += prefix;
+    // Same as the builtins' `allocateWithRefcount`: the refcount goes right before the data,
+    // at the start of a prefix that keeps the data aligned
     align = align.max(core::mem::size_of::<crate::Storage>());
+    let prefix = align;
+    size += prefix;
 
     unsafe {
         let allocation_ptr = roc_alloc(size, align as _) as *mut u8;
@@ -463,6 +471,157 @@ impl RocDec {
     pub fn to_str(&self) -> RocStr {
         RocStr::from(self.to_str_helper(&mut ArrayString::new()))
     }
+
+    // The arithmetic below gives the same results as the Zig builtins (`dec.zig`), so that a
+    // `Dec` computed by the compiler or the interpreter matches the one compiled code computes.
+    // Where the builtins crash, these return `None`.
+
+    pub fn from_int(num: i128) -> Option<Self> {
+        num.checked_mul(Self::ONE_POINT_ZERO).map(Self::new)
+    }
+
+    /// Truncates anything past the 18th decimal place.
+    pub fn from_f64(num: f64) -> Option<Self> {
+        let scaled = num * Self::ONE_POINT_ZERO as f64;
+
+        // NaN fails both comparisons
+        if scaled >= i128::MIN as f64 && scaled < i128::MAX as f64 {
+            Some(Self::new(scaled as i128))
+        } else {
+            None
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.as_i128() as f64 / Self::ONE_POINT_ZERO as f64
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.as_i128().checked_add(other.as_i128()).map(Self::new)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.as_i128().checked_sub(other.as_i128()).map(Self::new)
+    }
+
+    /// Rounds towards zero.
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.as_i128(), other.as_i128());
+
+        // Like the builtins, multiplying `MIN` only works by 0 or 1
+        if a == i128::MIN || b == i128::MIN {
+            let (min, rest) = if a == i128::MIN {
+                (self, b)
+            } else {
+                (other, a)
+            };
+
+            return match rest {
+                0 => Some(Self::new(0)),
+                Self::ONE_POINT_ZERO => Some(min),
+                _ => None,
+            };
+        }
+
+        let product = u256::mul_u128(a.unsigned_abs(), b.unsigned_abs());
+        let magnitude = u256::div_u256_by_u128(product, Self::ONE_POINT_ZERO as u128)?;
+
+        Self::with_sign(magnitude, (a < 0) != (b < 0))
+    }
+
+    /// Rounds towards zero. `None` when dividing a nonzero number by zero, but `0 / 0` is `0`,
+    /// like in the builtins.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.as_i128(), other.as_i128());
+
+        if a == 0 {
+            return Some(Self::new(0));
+        }
+
+        if b == 0 {
+            return None;
+        }
+
+        // These match the builtins, including `1 / MIN` giving `MIN`
+        if a == i128::MIN {
+            return (b == Self::ONE_POINT_ZERO).then_some(self);
+        }
+
+        if b == i128::MIN {
+            return (a == Self::ONE_POINT_ZERO).then_some(other);
+        }
+
+        let scaled = u256::mul_u128(a.unsigned_abs(), Self::ONE_POINT_ZERO as u128);
+        let magnitude = u256::div_u256_by_u128(scaled, b.unsigned_abs())?;
+
+        Self::with_sign(magnitude, (a < 0) != (b < 0))
+    }
+
+    /// Whole exponents use repeated multiplication, and others go through `f64`.
+    pub fn checked_pow(self, exponent: Self) -> Option<Self> {
+        let exponent = exponent.as_i128();
+
+        if exponent % Self::ONE_POINT_ZERO == 0 {
+            self.checked_pow_int(exponent / Self::ONE_POINT_ZERO)
+        } else {
+            let exponent = Self::new(exponent).to_f64();
+
+            Self::from_f64(self.to_f64().powf(exponent))
+        }
+    }
+
+    fn checked_pow_int(self, exponent: i128) -> Option<Self> {
+        if exponent == 0 {
+            Some(Self::new(Self::ONE_POINT_ZERO))
+        } else if exponent < 0 {
+            let power = self.checked_pow_int(exponent.checked_neg()?)?;
+
+            Self::new(Self::ONE_POINT_ZERO).checked_div(power)
+        } else if exponent % 2 == 0 {
+            let half_power = self.checked_pow_int(exponent / 2)?;
+
+            half_power.checked_mul(half_power)
+        } else {
+            self.checked_mul(self.checked_pow_int(exponent - 1)?)
+        }
+    }
+
+    /// The nearest whole number, rounding halfway cases away from zero.
+    pub fn round(&self) -> i128 {
+        let num = self.as_i128();
+        let truncated = num / Self::ONE_POINT_ZERO;
+        let fraction = (num % Self::ONE_POINT_ZERO).abs();
+
+        if fraction >= Self::ONE_POINT_ZERO / 2 {
+            truncated + num.signum()
+        } else {
+            truncated
+        }
+    }
+
+    /// The largest whole number less than or equal to this one.
+    pub fn floor(&self) -> i128 {
+        self.as_i128().div_euclid(Self::ONE_POINT_ZERO)
+    }
+
+    /// The smallest whole number greater than or equal to this one.
+    pub fn ceiling(&self) -> i128 {
+        let num = self.as_i128();
+        let truncated = num / Self::ONE_POINT_ZERO;
+
+        if num > 0 && num % Self::ONE_POINT_ZERO != 0 {
+            truncated + 1
+        } else {
+            truncated
+        }
+    }
+
+    fn with_sign(magnitude: u128, negative: bool) -> Option<Self> {
+        // The builtins only handle magnitudes that fit in an i128, so `MIN` is out of range
+        let magnitude = i128::try_from(magnitude).ok()?;
+
+        Some(Self::new(if negative { -magnitude } else { magnitude }))
+    }
 }
 
 impl From<i32> for RocDec {
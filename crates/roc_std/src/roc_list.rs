@@ -250,6 +250,21 @@ where
         }
     }
 
+    /// Store the number of elements in front of the refcount, so that a seamless slice can
+    /// decrement all of them when it frees the allocation. Only lists of refcounted elements
+    /// have room for it.
+    fn set_allocation_element_count(&self) {
+        if !T::is_refcounted() || self.is_seamless_slice() {
+            return;
+        }
+
+        if let Some(storage) = self.storage() {
+            if !storage.is_readonly() {
+                unsafe { self.ptr_to_refcount().sub(1).write(self.len()) }
+            }
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn ptr_to_refcount(&self) -> *mut usize {
         if self.is_seamless_slice() {
@@ -416,8 +431,14 @@ where
                 .as_ptr()
                 .cast::<ManuallyDrop<T>>();
 
-            let capacity_or_ref_ptr =
-                (self.ptr_to_first_elem() as usize) >> 1 | isize::MIN as usize;
+            // A slice of a slice points at the same allocation as the slice it came from
+            let capacity_or_ref_ptr = if self.is_seamless_slice() {
+                self.capacity_or_ref_ptr
+            } else {
+                self.set_allocation_element_count();
+
+                (self.ptr_to_first_elem() as usize) >> 1 | isize::MIN as usize
+            };
 
             let roc_list = RocList {
                 elements: NonNull::new(element_ptr as *mut ManuallyDrop<T>),
@@ -270,10 +270,17 @@ impl RocStr {
             let big = unsafe { &self.0.heap_allocated };
             let ptr = unsafe { (self.as_bytes().as_ptr() as *mut u8).add(range.start) };
 
+            // A slice of a slice points at the same allocation as the slice it came from
+            let capacity_or_alloc_ptr = if big.is_seamless_slice() {
+                big.capacity_or_alloc_ptr
+            } else {
+                (big.ptr_to_first_elem() as usize) >> 1
+            };
+
             let heap_allocated = ManuallyDrop::new(BigString {
                 elements: unsafe { NonNull::new_unchecked(ptr) },
                 length: (isize::MIN as usize) | (range.end - range.start),
-                capacity_or_alloc_ptr: (big.ptr_to_first_elem() as usize) >> 1,
+                capacity_or_alloc_ptr,
             });
 
             Some(RocStr(RocStrInner { heap_allocated }))
@@ -0,0 +1,53 @@
+//! Unsigned 256-bit arithmetic, for the intermediate results of `Dec` multiplication and division.
+
+/// A 256-bit number, as its high and low halves.
+pub(crate) type U256 = (u128, u128);
+
+pub(crate) fn mul_u128(a: u128, b: u128) -> U256 {
+    const HALF: u32 = 64;
+    let mask = u64::MAX as u128;
+
+    let (a_hi, a_lo) = (a >> HALF, a & mask);
+    let (b_hi, b_lo) = (b >> HALF, b & mask);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let middle = (lo_lo >> HALF) + (hi_lo & mask) + (lo_hi & mask);
+    let low = (middle << HALF) | (lo_lo & mask);
+    let high = hi_hi + (hi_lo >> HALF) + (lo_hi >> HALF) + (middle >> HALF);
+
+    (high, low)
+}
+
+/// Long division, one bit at a time, rounding towards zero. Returns `None` if the quotient does
+/// not fit in 128 bits.
+pub(crate) fn div_u256_by_u128((high, low): U256, divisor: u128) -> Option<u128> {
+    let mut quotient: U256 = (0, 0);
+    let mut remainder: u128 = 0;
+
+    for bit in (0..256).rev() {
+        let next = if bit >= 128 {
+            (high >> (bit - 128)) & 1
+        } else {
+            (low >> bit) & 1
+        };
+
+        let overflowed = remainder >> 127 == 1;
+        remainder = (remainder << 1) | next;
+
+        if overflowed || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+
+            if bit >= 128 {
+                quotient.0 |= 1 << (bit - 128);
+            } else {
+                quotient.1 |= 1 << bit;
+            }
+        }
+    }
+
+    (quotient.0 == 0).then_some(quotient.1)
+}
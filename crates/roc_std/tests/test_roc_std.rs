@@ -307,6 +307,78 @@ mod test_roc_std {
         assert_eq!(format!("{example}"), "3.141592653589793238");
     }
 
+    fn dec(string: &str) -> RocDec {
+        RocDec::from_str(string).unwrap()
+    }
+
+    #[test]
+    fn roc_dec_arithmetic() {
+        assert_eq!(dec("0.1").checked_add(dec("0.2")), Some(dec("0.3")));
+        assert_eq!(dec("1").checked_sub(dec("1.5")), Some(dec("-0.5")));
+        assert_eq!(dec("15").checked_mul(dec("2")), Some(dec("30")));
+        assert_eq!(dec("-1.5").checked_mul(dec("1.5")), Some(dec("-2.25")));
+        assert_eq!(dec("8").checked_div(dec("5")), Some(dec("1.6")));
+        assert_eq!(
+            dec("10").checked_div(dec("3")),
+            Some(dec("3.333333333333333333"))
+        );
+        assert_eq!(
+            dec("342").checked_div(dec("343")),
+            Some(dec("0.997084548104956268"))
+        );
+        assert_eq!(
+            dec("-2").checked_div(dec("3")),
+            Some(dec("-0.666666666666666666"))
+        );
+    }
+
+    #[test]
+    fn roc_dec_edge_cases_match_the_builtins() {
+        assert_eq!(RocDec::MAX.checked_add(dec("1")), None);
+        assert_eq!(RocDec::MIN.checked_mul(dec("0")), Some(dec("0")));
+        assert_eq!(RocDec::MIN.checked_mul(dec("1")), Some(RocDec::MIN));
+        assert_eq!(RocDec::MIN.checked_mul(dec("-1")), None);
+        assert_eq!(RocDec::MAX.checked_mul(dec("2")), None);
+        assert_eq!(dec("0").checked_div(dec("0")), Some(dec("0")));
+        assert_eq!(dec("1").checked_div(dec("0")), None);
+        assert_eq!(RocDec::MIN.checked_div(dec("1")), Some(RocDec::MIN));
+        assert_eq!(RocDec::MIN.checked_div(dec("2")), None);
+    }
+
+    #[test]
+    fn roc_dec_pow() {
+        assert_eq!(dec("3.1").checked_pow(dec("0")), Some(dec("1")));
+        assert_eq!(dec("3.1").checked_pow(dec("1")), Some(dec("3.1")));
+        assert_eq!(dec("0.5").checked_pow(dec("2")), Some(dec("0.25")));
+        assert_eq!(dec("2").checked_pow(dec("-2")), Some(dec("0.25")));
+        // Whole exponents don't go through f64, which would give 1.210000000000000188
+        assert_eq!(dec("1.1").checked_pow(dec("2")), Some(dec("1.21")));
+        assert_eq!(dec("0.5").checked_pow(dec("2.0")), Some(dec("0.25")));
+        assert_eq!(dec("4").checked_pow(dec("0.5")), Some(dec("2")));
+    }
+
+    #[test]
+    fn roc_dec_rounding() {
+        assert_eq!(dec("123.45").round(), 123);
+        assert_eq!(dec("-123.45").round(), -123);
+        assert_eq!(dec("0.5").round(), 1);
+        assert_eq!(dec("-0.5").round(), -1);
+        assert_eq!(dec("-0.00045").floor(), -1);
+        assert_eq!(dec("-2").floor(), -2);
+        assert_eq!(dec("0.00045").ceiling(), 1);
+        assert_eq!(dec("-1.5").ceiling(), -1);
+    }
+
+    #[test]
+    fn roc_dec_floats() {
+        assert_eq!(RocDec::from_f64(25.5), Some(dec("25.5")));
+        assert_eq!(RocDec::from_f64(1e308), None);
+        assert_eq!(RocDec::from_f64(f64::NAN), None);
+        assert_eq!(dec("-2.5").to_f64(), -2.5);
+        assert_eq!(RocDec::from_int(-7), Some(dec("-7")));
+        assert_eq!(RocDec::from_int(i128::MAX), None);
+    }
+
     #[test]
     fn safe_send_no_copy() {
         let x = RocStr::from("This is a long string but still unique. Yay!!!");
@@ -394,6 +466,50 @@ mod test_roc_std {
         assert_eq!(first.slice_range(0..3).as_str(), "cha");
     }
 
+    #[test]
+    fn slice_of_big_str_slice_shares_the_original_refcount() {
+        let example = RocStr::from("the quick brown fox jumps over the lazy dog");
+        let slice = example.slice_range(4..43);
+        let slice_of_slice = slice.slice_range(6..39);
+
+        assert_eq!(slice_of_slice.as_str(), "brown fox jumps over the lazy dog");
+        assert!(!example.is_unique());
+
+        drop(slice);
+        drop(slice_of_slice);
+        assert!(example.is_unique());
+    }
+
+    #[test]
+    fn slice_of_list_slice_shares_the_original_refcount() {
+        let example = RocList::from_slice(b"the quick brown fox jumps over the lazy dog");
+        let slice = example.slice_range(4..43);
+        let slice_of_slice = slice.slice_range(6..39);
+
+        assert_eq!(
+            slice_of_slice.as_slice(),
+            b"brown fox jumps over the lazy dog"
+        );
+        assert!(!example.is_unique());
+
+        drop(slice);
+        drop(slice_of_slice);
+        assert!(example.is_unique());
+    }
+
+    #[test]
+    fn list_slice_outliving_its_list_drops_every_element() {
+        let string = RocStr::from("a string that is too big to be a small string");
+        let list = RocList::from_slice(&[string.clone(), string.clone(), string.clone()]);
+        let slice = list.slice_range(1..2);
+
+        drop(list);
+        assert!(!string.is_unique());
+
+        drop(slice);
+        assert!(string.is_unique());
+    }
+
     #[test]
     fn roc_list_push() {
         let mut example = RocList::from_slice(&[1, 2, 3]);
@@ -31,6 +31,8 @@ macro_rules! setup_tracing {
     };
 }
 
+pub mod profile;
+
 pub use tracing::debug;
 pub use tracing::info;
 
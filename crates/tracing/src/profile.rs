@@ -0,0 +1,323 @@
+//! Records how long each phase of a compilation takes, for `--profile-compiler` to write out in
+//! the [Chrome trace event format], which chrome://tracing and Perfetto (ui.perfetto.dev) open.
+//!
+//! The trace has two processes: "Threads" has a lane per compiler thread, showing what each
+//! of them was busy with, and "Modules" has a lane per Roc module, showing how its phases
+//! went by while other modules were being worked on in parallel.
+//!
+//! Until [start] is called, spans are not recorded, and cost about as much as a branch.
+//!
+//! [Chrome trace event format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+static PROFILE: OnceLock<Profile> = OnceLock::new();
+
+thread_local! {
+    static THREAD_LANE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+const THREADS_PID: u32 = 1;
+const MODULES_PID: u32 = 2;
+
+struct Profile {
+    path: PathBuf,
+    start: Instant,
+    recorded: Mutex<Recorded>,
+}
+
+#[derive(Default)]
+struct Recorded {
+    spans: Vec<SpanRecord>,
+    /// By lane
+    thread_names: Vec<String>,
+    module_names: HashMap<u64, String>,
+    written: bool,
+}
+
+struct SpanRecord {
+    name: &'static str,
+    module: Option<u64>,
+    thread: usize,
+    start: Duration,
+    duration: Duration,
+}
+
+/// Starts recording spans, for [finish] to write to the given path. The profile is for the
+/// whole process, so only the first call does anything.
+pub fn start(path: PathBuf) {
+    let _ = PROFILE.set(Profile {
+        path,
+        start: Instant::now(),
+        recorded: Mutex::default(),
+    });
+}
+
+pub fn is_enabled() -> bool {
+    PROFILE.get().is_some()
+}
+
+/// Times a phase of the compilation as a whole, until the returned guard is dropped.
+#[must_use]
+pub fn span(name: &'static str) -> Option<ProfileSpan> {
+    PROFILE.get()?;
+
+    Some(ProfileSpan {
+        name,
+        module: None,
+        start: Instant::now(),
+    })
+}
+
+/// Times a phase of the compilation of a single module, until the returned guard is dropped.
+/// Any value that identifies the module will do, as long as [name_module] gets the same one.
+#[must_use]
+pub fn module_span(name: &'static str, module: impl Hash) -> Option<ProfileSpan> {
+    PROFILE.get()?;
+
+    Some(ProfileSpan {
+        name,
+        module: Some(module_key(module)),
+        start: Instant::now(),
+    })
+}
+
+/// The name to show for the lane of a module.
+pub fn name_module(module: impl Hash, name: impl Into<String>) {
+    if let Some(profile) = PROFILE.get() {
+        let mut recorded = profile.recorded.lock().unwrap();
+        recorded
+            .module_names
+            .insert(module_key(module), name.into());
+    }
+}
+
+fn module_key(module: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    module.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+pub struct ProfileSpan {
+    name: &'static str,
+    module: Option<u64>,
+    start: Instant,
+}
+
+impl Drop for ProfileSpan {
+    fn drop(&mut self) {
+        let end = Instant::now();
+
+        let Some(profile) = PROFILE.get() else {
+            return;
+        };
+
+        let mut recorded = profile.recorded.lock().unwrap();
+
+        let thread = THREAD_LANE.with(|lane| match lane.get() {
+            Some(thread) => thread,
+            None => {
+                let thread = recorded.thread_names.len();
+                let name = match std::thread::current().name() {
+                    Some(name) => name.to_string(),
+                    None => format!("thread {thread}"),
+                };
+
+                recorded.thread_names.push(name);
+                lane.set(Some(thread));
+
+                thread
+            }
+        });
+
+        recorded.spans.push(SpanRecord {
+            name: self.name,
+            module: self.module,
+            thread,
+            start: self.start.saturating_duration_since(profile.start),
+            duration: end.saturating_duration_since(self.start),
+        });
+    }
+}
+
+/// Writes the trace, if [start] was called and it hasn't been written yet. Spans that end
+/// afterwards are left out.
+pub fn finish() -> std::io::Result<()> {
+    let Some(profile) = PROFILE.get() else {
+        return Ok(());
+    };
+
+    let trace = {
+        let mut recorded = profile.recorded.lock().unwrap();
+
+        if recorded.written {
+            return Ok(());
+        }
+
+        recorded.written = true;
+
+        trace_json(&recorded)
+    };
+
+    std::fs::write(&profile.path, trace.to_string())
+}
+
+fn trace_json(recorded: &Recorded) -> Value {
+    let mut events = vec![
+        process_name(THREADS_PID, "Threads"),
+        process_name(MODULES_PID, "Modules"),
+    ];
+
+    for (thread, name) in recorded.thread_names.iter().enumerate() {
+        events.push(thread_name(THREADS_PID, thread as u64, name));
+    }
+
+    // A lane per module, in the order they got started on. (Chrome wants small lane ids.)
+    let mut first_starts: HashMap<u64, Duration> = HashMap::new();
+
+    for span in recorded.spans.iter() {
+        if let Some(module) = span.module {
+            let first_start = first_starts.entry(module).or_insert(span.start);
+            *first_start = span.start.min(*first_start);
+        }
+    }
+
+    let mut modules: Vec<(Duration, u64)> = (first_starts.into_iter())
+        .map(|(module, start)| (start, module))
+        .collect();
+    modules.sort_unstable();
+
+    let mut module_lanes: HashMap<u64, u64> = HashMap::new();
+
+    for (lane, (_, module)) in modules.into_iter().enumerate() {
+        let name = match recorded.module_names.get(&module) {
+            Some(name) => name.as_str(),
+            None => "(unnamed module)",
+        };
+
+        events.push(thread_name(MODULES_PID, lane as u64, name));
+        module_lanes.insert(module, lane as u64);
+    }
+
+    for span in recorded.spans.iter() {
+        let module = (span.module)
+            .and_then(|module| recorded.module_names.get(&module))
+            .map(String::as_str);
+
+        events.push(complete(span, THREADS_PID, span.thread as u64, module));
+
+        if let Some(key) = span.module {
+            events.push(complete(span, MODULES_PID, module_lanes[&key], module));
+        }
+    }
+
+    json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+}
+
+fn complete(span: &SpanRecord, pid: u32, tid: u64, module: Option<&str>) -> Value {
+    let mut event = json!({
+        "name": span.name,
+        "cat": "roc",
+        "ph": "X",
+        "ts": micros(span.start),
+        "dur": micros(span.duration),
+        "pid": pid,
+        "tid": tid,
+    });
+
+    if let Some(module) = module {
+        event["args"] = json!({ "module": module });
+    }
+
+    event
+}
+
+fn process_name(pid: u32, name: &str) -> Value {
+    json!({ "name": "process_name", "ph": "M", "pid": pid, "args": { "name": name } })
+}
+
+fn thread_name(pid: u32, tid: u64, name: &str) -> Value {
+    json!({ "name": "thread_name", "ph": "M", "pid": pid, "tid": tid, "args": { "name": name } })
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nested_spans_on_two_threads() {
+        let path = std::env::temp_dir().join(format!("roc_profile_{}.json", std::process::id()));
+        start(path.clone());
+
+        let threads: Vec<_> = ["first", "second"]
+            .into_iter()
+            .map(|name| {
+                std::thread::Builder::new()
+                    .name(name.to_string())
+                    .spawn(|| {
+                        let _outer = span("outer");
+                        std::thread::sleep(Duration::from_millis(2));
+                        let _inner = span("inner");
+                        std::thread::sleep(Duration::from_millis(2));
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        finish().unwrap();
+        let trace: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let events = trace["traceEvents"].as_array().unwrap();
+        let spans: Vec<&Value> = events.iter().filter(|event| event["ph"] == "X").collect();
+        assert_eq!(spans.len(), 4);
+
+        for tid in [0, 1] {
+            let on_thread = |name: &str| {
+                let found: Vec<_> = (spans.iter())
+                    .filter(|span| span["tid"] == tid && span["name"] == name)
+                    .collect();
+                assert_eq!(found.len(), 1, "{name} on thread {tid}");
+
+                assert_eq!(found[0]["pid"], THREADS_PID);
+                (
+                    found[0]["ts"].as_f64().unwrap(),
+                    found[0]["dur"].as_f64().unwrap(),
+                )
+            };
+
+            let (outer_ts, outer_dur) = on_thread("outer");
+            let (inner_ts, inner_dur) = on_thread("inner");
+
+            // The inner span starts after the outer one, and ends with it (give or take the
+            // order the guards were dropped in).
+            assert!(inner_dur >= 2_000.0 && outer_dur >= 4_000.0);
+            assert!(inner_ts >= outer_ts + 2_000.0);
+            assert!(inner_ts + inner_dur <= outer_ts + outer_dur);
+        }
+
+        let thread_names: Vec<_> = (events.iter())
+            .filter(|event| event["name"] == "thread_name" && event["pid"] == THREADS_PID)
+            .map(|event| event["args"]["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(thread_names.len(), 2);
+        assert!(thread_names.contains(&"first") && thread_names.contains(&"second"));
+    }
+}
@@ -4,7 +4,7 @@ use std::iter::{self, once, Iterator};
 
 use roc_wasm_module::opcodes::{MemoryInstruction, OpCode};
 use roc_wasm_module::parse::{Parse, SkipBytes};
-use roc_wasm_module::sections::{ImportDesc, MemorySection, SignatureParamsIter};
+use roc_wasm_module::sections::{ImportDesc, Limits, MemorySection, SignatureTypesIter};
 use roc_wasm_module::{ExportType, WasmModule};
 use roc_wasm_module::{Value, ValueType};
 
@@ -32,6 +32,8 @@ enum BlockType {
 struct Block {
     ty: BlockType,
     vstack: usize,
+    /// Number of values carried by a branch to this block
+    arity: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +56,9 @@ pub struct Instance<'a, I: ImportDispatcher> {
     pub(crate) value_store: ValueStore<'a>,
     /// Values of any global variables
     pub(crate) globals: Vec<'a, Value>,
+    /// Function index of each function table element, starting from the element section.
+    /// `table.set` can change it, so `call_indirect` looks functions up here.
+    pub(crate) table: Vec<'a, Option<u32>>,
     /// Index in the code section of the current instruction
     pub(crate) program_counter: usize,
     /// One entry per nested block. For loops, stores the address of the first instruction.
@@ -90,6 +95,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             previous_frames: Vec::new_in(arena),
             value_store: ValueStore::new(arena),
             globals: Vec::from_iter_in(globals, arena),
+            table: Vec::new_in(arena),
             program_counter,
             blocks: Vec::new_in(arena),
             branch_cache: bumpalo::vec![in arena; bumpalo::vec![in arena]],
@@ -128,6 +134,18 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         let globals = module.global.initial_values(arena);
 
+        let table = {
+            let table_size = match module.table.function_table.limits {
+                Limits::Min(min) | Limits::MinMax(min, _) => min,
+            }
+            .max(module.element.max_table_index());
+            let mut table = bumpalo::vec![in arena; None; table_size as usize];
+            for (table_index, fn_index) in module.element.table_entries() {
+                table[table_index as usize] = Some(fn_index);
+            }
+            table
+        };
+
         // We don't handle non-function import types (memories, tables, and globals),
         // and it's nice for lookups to assume they're all functions, so let's assert that.
         let all_imports_are_functions = module.import.imports.iter().all(|imp| imp.is_function());
@@ -158,6 +176,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             previous_frames: Vec::new_in(arena),
             value_store,
             globals,
+            table,
             program_counter: usize::MAX,
             blocks: Vec::new_in(arena),
             branch_cache,
@@ -172,7 +191,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
     where
         A: IntoIterator<Item = Value>,
     {
-        let (fn_index, param_type_iter, ret_type) =
+        let (fn_index, param_type_iter, return_count) =
             self.call_export_help_before_arg_load(self.module, fn_name)?;
         let n_args = param_type_iter.len();
 
@@ -186,7 +205,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             self.value_store.push(value);
         }
 
-        self.call_export_help_after_arg_load(self.module, fn_index, n_args, ret_type)
+        self.call_export_help_after_arg_load(self.module, fn_index, n_args, return_count)
     }
 
     pub fn call_export_from_cli(
@@ -208,7 +227,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         // Implement the "basic numbers" CLI
         // Check if the called Wasm function takes numeric arguments, and if so, try to parse them from the CLI.
-        let (fn_index, param_type_iter, ret_type) =
+        let (fn_index, param_type_iter, return_count) =
             self.call_export_help_before_arg_load(module, fn_name)?;
         let n_args = param_type_iter.len();
         for (value_bytes, value_type) in arg_strings
@@ -227,14 +246,48 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             self.value_store.push(value);
         }
 
-        self.call_export_help_after_arg_load(module, fn_index, n_args, ret_type)
+        self.call_export_help_after_arg_load(module, fn_index, n_args, return_count)
+    }
+
+    /// The `len` bytes of memory at `address`
+    pub fn read_memory(&self, address: u32, len: u32) -> Result<&[u8], String> {
+        let range = self.memory_range(address, len)?;
+        Ok(&self.memory[range])
+    }
+
+    /// Copies `bytes` into memory at `address`, for example to set up the arguments of an export
+    pub fn write_memory(&mut self, address: u32, bytes: &[u8]) -> Result<(), String> {
+        let range = self.memory_range(address, bytes.len() as u32)?;
+        self.memory[range].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn memory_range(&self, address: u32, len: u32) -> Result<std::ops::Range<usize>, String> {
+        let start = address as usize;
+        let end = start + len as usize;
+
+        if end > self.memory.len() {
+            return Err(format!(
+                "Memory access of {len} bytes at {address:#x} is out of bounds. The memory size is {:#x}",
+                self.memory.len()
+            ));
+        }
+
+        Ok(start..end)
+    }
+
+    /// The name of a function from the module's name section, if it has one
+    pub fn function_name(&self, fn_index: usize) -> Option<&'a str> {
+        (self.module.names.function_names.iter())
+            .find(|(index, _)| *index == fn_index as u32)
+            .map(|(_, name)| *name)
     }
 
     fn call_export_help_before_arg_load<'m>(
         &mut self,
         module: &'m WasmModule<'a>,
         fn_name: &str,
-    ) -> Result<(usize, SignatureParamsIter<'m>, Option<ValueType>), String> {
+    ) -> Result<(usize, SignatureTypesIter<'m>, usize), String> {
         let fn_index = {
             let mut export_iter = module.export.exports.iter();
             export_iter
@@ -274,7 +327,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             cursor
         };
 
-        let (param_type_iter, return_type) = {
+        let (param_type_iter, return_type_iter) = {
             let signature_index = module.function.signatures[internal_fn_index];
             module.types.look_up(signature_index)
         };
@@ -288,7 +341,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             );
         }
 
-        Ok((fn_index, param_type_iter, return_type))
+        Ok((fn_index, param_type_iter, return_type_iter.len()))
     }
 
     fn call_export_help_after_arg_load(
@@ -296,20 +349,21 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         module: &WasmModule<'a>,
         fn_index: usize,
         n_args: usize,
-        return_type: Option<ValueType>,
+        return_count: usize,
     ) -> Result<Option<Value>, String> {
         self.previous_frames.clear();
         self.blocks.clear();
         self.blocks.push(Block {
             ty: BlockType::Locals(fn_index),
             vstack: self.value_store.depth(),
+            arity: 0,
         });
         self.current_frame = Frame::enter(
             fn_index,
             0, // return_addr
             self.blocks.len(),
             n_args,
-            return_type,
+            return_count,
             &module.code.bytes,
             &mut self.value_store,
             &mut self.program_counter,
@@ -317,6 +371,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         self.blocks.push(Block {
             ty: BlockType::FunctionBody(fn_index),
             vstack: self.value_store.depth(),
+            arity: return_count,
         });
 
         loop {
@@ -327,7 +382,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 }
                 Err(e) => {
                     let file_offset = self.program_counter + module.code.section_offset as usize;
-                    let mut message = e.to_string_at(file_offset);
+                    let fn_name = self.function_name(self.current_frame.fn_index);
+                    let mut message = e.to_string_at(file_offset, fn_name);
                     self.debug_stack_trace(&mut message).unwrap();
                     return Err(message);
                 }
@@ -357,21 +413,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         let Frame {
             return_addr,
             body_block_index,
-            return_type,
+            return_count,
             ..
         } = self.current_frame;
 
-        // Throw away all locals and values except the return value
+        // Throw away all locals and values except the return values
         let locals_block_index = body_block_index - 1;
         let locals_block = &self.blocks[locals_block_index];
-        let new_stack_depth = if return_type.is_some() {
-            self.value_store
-                .set(locals_block.vstack, self.value_store.peek());
-            locals_block.vstack + 1
-        } else {
-            locals_block.vstack
-        };
-        self.value_store.truncate(new_stack_depth);
+        self.keep_top_values(locals_block.vstack, return_count);
 
         // Resume executing at the next instruction in the caller function
         let new_block_len = locals_block_index; // don't need a -1 because one is a length and the other is an index!
@@ -389,6 +438,16 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         }
     }
 
+    fn table_element(&self, element_index: u32) -> Result<Option<u32>, Error> {
+        match self.table.get(element_index as usize) {
+            Some(fn_ref) => Ok(*fn_ref),
+            None => Err(Error::TableAccessOutOfBounds(
+                element_index,
+                self.table.len() as u32,
+            )),
+        }
+    }
+
     fn get_load_address(&mut self, module: &WasmModule<'a>) -> Result<u32, Error> {
         // Alignment is not used in the execution steps from the spec! Maybe it's just an optimization hint?
         // https://webassembly.github.io/spec/core/exec/instructions.html#memory-instructions
@@ -428,18 +487,46 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         }
     }
 
+    /// Drop everything above `stack_depth`, except the top `count` values, which move down
+    fn keep_top_values(&mut self, stack_depth: usize, count: usize) {
+        let kept_start = self.value_store.depth() - count;
+        for i in 0..count {
+            let value = *self.value_store.get(kept_start + i).unwrap();
+            self.value_store.set(stack_depth + i, value);
+        }
+        self.value_store.truncate(stack_depth + count);
+    }
+
+    /// Parse the type of a block instruction, returning its parameter and result counts
+    fn fetch_block_type(&mut self, module: &WasmModule<'a>) -> (usize, usize) {
+        use roc_wasm_module::BlockType::*;
+
+        let block_type =
+            roc_wasm_module::BlockType::parse((), &module.code.bytes, &mut self.program_counter)
+                .unwrap();
+        self.write_debug(block_type);
+        match block_type {
+            NoResult => (0, 0),
+            Value(_) => (0, 1),
+            FunctionType(sig_index) => {
+                let (params, results) = module.types.look_up(sig_index);
+                (params.len(), results.len())
+            }
+        }
+    }
+
     fn do_break(&mut self, relative_blocks_outward: u32, module: &WasmModule<'a>) {
         let block_index = self.blocks.len() - 1 - relative_blocks_outward as usize;
-        let Block { ty, vstack } = self.blocks[block_index];
+        let Block { ty, vstack, arity } = self.blocks[block_index];
         match ty {
             BlockType::Loop(start_addr) => {
                 self.blocks.truncate(block_index + 1);
-                self.value_store.truncate(vstack);
+                self.keep_top_values(vstack, arity);
                 self.program_counter = start_addr;
             }
             BlockType::FunctionBody(_) | BlockType::Normal => {
                 self.break_forward(relative_blocks_outward, module);
-                self.value_store.truncate(vstack);
+                self.keep_top_values(vstack, arity);
             }
             BlockType::Locals(_) => unreachable!(),
         }
@@ -514,10 +601,11 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             );
         }
 
-        let (arg_type_iter, ret_type) = module.types.look_up(signature_index);
+        let (arg_type_iter, ret_type_iter) = module.types.look_up(signature_index);
         let n_args = arg_type_iter.len();
+        let return_count = ret_type_iter.len();
         if self.debug_string.is_some() {
-            self.debug_call(n_args, ret_type);
+            self.debug_call(n_args, ret_type_iter);
         }
 
         if let Some(import) = opt_import {
@@ -556,6 +644,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             self.blocks.push(Block {
                 ty: BlockType::Locals(fn_index),
                 vstack: self.value_store.depth() - n_args,
+                arity: 0,
             });
             let body_block_index = self.blocks.len();
 
@@ -564,7 +653,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 return_addr,
                 body_block_index,
                 n_args,
-                ret_type,
+                return_count,
                 &module.code.bytes,
                 &mut self.value_store,
                 &mut self.program_counter,
@@ -575,6 +664,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             self.blocks.push(Block {
                 ty: BlockType::FunctionBody(fn_index),
                 vstack: self.value_store.depth(),
+                arity: return_count,
             });
         }
         // self.debug_values_and_blocks("end do_call");
@@ -582,7 +672,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         Ok(())
     }
 
-    fn debug_call(&mut self, n_args: usize, return_type: Option<ValueType>) {
+    fn debug_call(&mut self, n_args: usize, return_types: SignatureTypesIter) {
         if let Some(debug_string) = self.debug_string.as_mut() {
             write!(debug_string, "         args=[").unwrap();
             let arg_iter = self
@@ -598,7 +688,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 }
                 write!(debug_string, "{arg:x?}").unwrap();
             }
-            writeln!(debug_string, "] return_type={return_type:?}").unwrap();
+            let return_types: std::vec::Vec<ValueType> = return_types.collect();
+            writeln!(debug_string, "] return_types={return_types:?}").unwrap();
         }
     }
 
@@ -626,25 +717,28 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             }
             NOP => {}
             BLOCK => {
-                self.fetch_immediate_u32(module); // blocktype (ignored)
+                let (n_params, n_results) = self.fetch_block_type(module);
                 self.blocks.push(Block {
                     ty: BlockType::Normal,
-                    vstack: self.value_store.depth(),
+                    vstack: self.value_store.depth() - n_params,
+                    arity: n_results,
                 });
             }
             LOOP => {
-                self.fetch_immediate_u32(module); // blocktype (ignored)
+                let (n_params, _) = self.fetch_block_type(module);
                 self.blocks.push(Block {
                     ty: BlockType::Loop(self.program_counter),
-                    vstack: self.value_store.depth(),
+                    vstack: self.value_store.depth() - n_params,
+                    arity: n_params,
                 });
             }
             IF => {
-                self.fetch_immediate_u32(module); // blocktype (ignored)
+                let (n_params, n_results) = self.fetch_block_type(module);
                 let condition = self.value_store.pop_i32()?;
                 self.blocks.push(Block {
                     ty: BlockType::Normal,
-                    vstack: self.value_store.depth(),
+                    vstack: self.value_store.depth() - n_params,
+                    arity: n_results,
                 });
                 if condition == 0 {
                     let addr = self.program_counter as u32;
@@ -748,7 +842,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 );
 
                 // Dereference the function pointer (look up the element index in the function table)
-                let fn_index = module.element.lookup(element_index).unwrap_or_else(|| {
+                let fn_index = self.table_element(element_index).ok().flatten();
+                let fn_index = fn_index.unwrap_or_else(|| {
                     panic!(
                         "Indirect function call failed. There is no function with element index {element_index}"
                     )
@@ -796,6 +891,30 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 let index = self.fetch_immediate_u32(module);
                 self.globals[index as usize] = self.value_store.pop();
             }
+            // There are no reference types in `Value`, so a funcref on the stack is an i32
+            // holding its function index, or -1 for null. Only table instructions make them.
+            TABLEGET => {
+                let table_index = self.fetch_immediate_u32(module);
+                assert_eq!(
+                    table_index, 0,
+                    "Table index {table_index} not supported at file offset {file_offset:#x}"
+                );
+                let element_index = self.value_store.pop_u32()?;
+                let fn_ref = self.table_element(element_index)?;
+                self.value_store
+                    .push(Value::I32(fn_ref.map_or(-1, |fn_index| fn_index as i32)));
+            }
+            TABLESET => {
+                let table_index = self.fetch_immediate_u32(module);
+                assert_eq!(
+                    table_index, 0,
+                    "Table index {table_index} not supported at file offset {file_offset:#x}"
+                );
+                let fn_ref = self.value_store.pop_i32()?;
+                let element_index = self.value_store.pop_u32()?;
+                self.table_element(element_index)?;
+                self.table[element_index as usize] = u32::try_from(fn_ref).ok();
+            }
             I32LOAD => {
                 let addr = self.get_load_address(module)? as usize;
                 let mut bytes = [0; 4];
@@ -1785,7 +1904,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         let mut print_blocks = |i| {
             block_str.clear();
-            while let Some((b, Block { vstack, ty })) = block {
+            while let Some((b, Block { vstack, ty, .. })) = block {
                 if *vstack > i {
                     break;
                 }
@@ -1855,14 +1974,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 self.module.types.look_up(signature_index).0.len()
             };
 
-            let fn_name = self
-                .module
-                .names
-                .function_names
-                .iter()
-                .find(|(idx, _)| *idx == *fn_index as u32)
-                .map(|(_, name)| *name)
-                .unwrap_or("");
+            let fn_name = self.function_name(*fn_index).unwrap_or("");
 
             // Function and address match wasm-objdump formatting, for easy copy & find
             writeln!(buffer, "func[{fn_index}]  {fn_name}")?;
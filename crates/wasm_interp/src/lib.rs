@@ -60,6 +60,65 @@ impl<'a> ImportDispatcher for DefaultImportDispatcher<'a> {
     }
 }
 
+/// The Rust code for an import, given its arguments and the instance's memory
+pub type ImportFn<'a> = Box<dyn FnMut(&[Value], &mut [u8]) -> Option<Value> + 'a>;
+
+/// Resolves imports to Rust closures, registered by module and function name.
+/// WASI imports that have no closure of their own go to `wasi`.
+pub struct ClosureDispatcher<'a> {
+    pub wasi: WasiDispatcher<'a>,
+    imports: std::vec::Vec<(&'a str, &'a str, ImportFn<'a>)>,
+}
+
+impl Default for ClosureDispatcher<'_> {
+    fn default() -> Self {
+        ClosureDispatcher::new(WasiDispatcher::default())
+    }
+}
+
+impl<'a> ClosureDispatcher<'a> {
+    pub fn new(wasi: WasiDispatcher<'a>) -> Self {
+        ClosureDispatcher {
+            wasi,
+            imports: std::vec::Vec::new(),
+        }
+    }
+
+    /// Run `f` for calls to the import `module_name.function_name`
+    pub fn import<F>(mut self, module_name: &'a str, function_name: &'a str, f: F) -> Self
+    where
+        F: FnMut(&[Value], &mut [u8]) -> Option<Value> + 'a,
+    {
+        self.imports
+            .retain(|(m, f, _)| (*m, *f) != (module_name, function_name));
+        self.imports.push((module_name, function_name, Box::new(f)));
+        self
+    }
+}
+
+impl<'a> ImportDispatcher for ClosureDispatcher<'a> {
+    fn dispatch(
+        &mut self,
+        module_name: &str,
+        function_name: &str,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Option<Value> {
+        let import =
+            (self.imports.iter_mut()).find(|(m, f, _)| *m == module_name && *f == function_name);
+
+        match import {
+            Some((_, _, f)) => f(arguments, memory),
+            None if module_name == wasi::MODULE_NAME => {
+                self.wasi.dispatch(function_name, arguments, memory)
+            }
+            None => {
+                panic!("ClosureDispatcher has no closure for {module_name}.{function_name}")
+            }
+        }
+    }
+}
+
 /// Errors that can happen while interpreting the program
 /// All of these cause a WebAssembly stack trace to be dumped
 #[derive(Debug, PartialEq)]
@@ -67,30 +126,41 @@ pub(crate) enum Error {
     Type(ValueType, ValueType),
     StackEmpty,
     MemoryAccessOutOfBounds(u32, u32),
+    TableAccessOutOfBounds(u32, u32),
     UnreachableOp,
 }
 
 impl Error {
-    pub fn to_string_at(&self, file_offset: usize) -> String {
+    /// Describes the error, given where the failing instruction is, and the name of its
+    /// function if the module has one for it.
+    pub fn to_string_at(&self, file_offset: usize, fn_name: Option<&str>) -> String {
+        let at = match fn_name {
+            Some(fn_name) => format!("file offset {file_offset:#x}, in function `{fn_name}`"),
+            None => format!("file offset {file_offset:#x}"),
+        };
+
         match self {
             Error::Type(expected, actual) => {
                 format!(
-                    "ERROR: I found a type mismatch at file offset {file_offset:#x}. Expected {expected:?}, but found {actual:?}.\n"
+                    "ERROR: I found a type mismatch at {at}. Expected {expected:?}, but found {actual:?}.\n"
                 )
             }
             Error::StackEmpty => {
+                format!("ERROR: I tried to pop a value from the stack at {at}, but it was empty.\n")
+            }
+            Error::MemoryAccessOutOfBounds(addr, memory_size) => {
                 format!(
-                    "ERROR: I tried to pop a value from the stack at file offset {file_offset:#x}, but it was empty.\n"
+                    "ERROR: A Wasm instruction at {} tried to access memory at {:#x} but the maximum address is {:#x}\n",
+                    at, addr, memory_size-1
                 )
             }
-            Error::MemoryAccessOutOfBounds(addr, memory_size) => {
+            Error::TableAccessOutOfBounds(index, table_size) => {
                 format!(
-                    "ERROR: A Wasm instruction at file offset {:#x} tried to access memory at {:#x} but the maximum address is {:#x}\n",
-                    file_offset, addr, memory_size-1
+                    "ERROR: A Wasm instruction at {at} tried to access function table element {index} but the table only has {table_size} elements\n"
                 )
             }
             Error::UnreachableOp => {
-                format!("WebAssembly `unreachable` instruction at file offset {file_offset:#x}.\n")
+                format!("WebAssembly `unreachable` instruction at {at}.\n")
             }
         }
     }
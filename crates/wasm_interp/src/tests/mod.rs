@@ -74,7 +74,7 @@ where
         module.code.function_offsets.push(0);
         module.add_function_signature(Signature {
             param_types: Vec::new_in(&arena),
-            ret_type: Some(ValueType::from(expected)),
+            ret_types: bumpalo::vec![in &arena; ValueType::from(expected)],
         });
         module.export.append(Export {
             name: "test",
@@ -5,12 +5,12 @@ use crate::tests::{
     const_value, create_exported_function_no_locals, create_exported_function_with_locals,
     default_state,
 };
-use crate::{DefaultImportDispatcher, ImportDispatcher, Instance};
+use crate::{ClosureDispatcher, DefaultImportDispatcher, ImportDispatcher, Instance};
 use bumpalo::{collections::Vec, Bump};
-use roc_wasm_module::sections::{Import, ImportDesc};
+use roc_wasm_module::sections::{Import, ImportDesc, MemorySection};
 use roc_wasm_module::{
-    opcodes::OpCode, sections::ElementSegment, Export, ExportType, SerialBuffer, Serialize,
-    Signature, Value, ValueType, WasmModule,
+    opcodes::OpCode, sections::ElementSegment, BlockType, Export, ExportType, SerialBuffer,
+    Serialize, Signature, Value, ValueType, WasmModule,
 };
 
 #[test]
@@ -97,7 +97,7 @@ fn test_loop_help(end: i32, expected: i32) {
 
     module.add_function_signature(Signature {
         param_types: Vec::new_in(&arena),
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     });
     module.export.append(Export {
         name: "test",
@@ -125,7 +125,7 @@ fn test_if_else_help(condition: i32, expected: i32) {
 
     let signature = Signature {
         param_types: bumpalo::vec![in &arena],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     };
     let local_types = [(1, ValueType::I32)];
     create_exported_function_with_locals(&mut module, "test", signature, &local_types, |buf| {
@@ -188,7 +188,7 @@ fn test_br() {
 
     let signature = Signature {
         param_types: bumpalo::vec![in &arena],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     };
     let local_types = [(1, ValueType::I32)];
     create_exported_function_with_locals(
@@ -288,7 +288,7 @@ fn test_br_if_help(condition: i32, expected: i32) {
 
     let signature = Signature {
         param_types: bumpalo::vec![in &arena],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     };
     let local_types = [(1, ValueType::I32)];
     create_exported_function_with_locals(
@@ -393,7 +393,7 @@ fn test_br_table_help(condition: i32, expected: i32) {
 
     let signature = Signature {
         param_types: bumpalo::vec![in &arena],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     };
     let local_types = [(1, ValueType::I32)];
     create_exported_function_with_locals(
@@ -527,7 +527,7 @@ fn test_call_import() {
     });
     module.types.insert(Signature {
         param_types: bumpalo::vec![in &arena; ValueType::I32],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     });
 
     // Function 1, which calls the import
@@ -536,7 +536,7 @@ fn test_call_import() {
     module.code.function_offsets.push(func0_offset);
     module.add_function_signature(Signature {
         param_types: Vec::new_in(&arena),
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     });
     module.export.append(Export {
         name: start_fn_name,
@@ -573,6 +573,108 @@ fn test_call_import() {
     assert_eq!(return_val, Value::I32(234));
 }
 
+#[test]
+fn test_call_closure_import() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    let start_fn_name = "test";
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    // Function 0 is the import
+    module.import.imports.push(Import {
+        module: "env",
+        name: "store",
+        description: ImportDesc::Func { signature_index: 0 },
+    });
+    module.types.insert(Signature {
+        param_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32],
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
+    });
+
+    // Function 1, which calls the import
+    module.code.function_count = 1;
+    let func0_offset = module.code.bytes.len() as u32;
+    module.code.function_offsets.push(func0_offset);
+    module.add_function_signature(Signature {
+        param_types: Vec::new_in(&arena),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
+    });
+    module.export.append(Export {
+        name: start_fn_name,
+        ty: ExportType::Func,
+        index: 1,
+    });
+    [
+        0, // no locals
+        OpCode::I32CONST as u8,
+        16, // address
+        OpCode::I32CONST as u8,
+        42, // value
+        OpCode::CALL as u8,
+        0, // function 0
+        OpCode::END as u8,
+    ]
+    .serialize(&mut module.code.bytes);
+
+    let calls = std::cell::Cell::new(0);
+    let dispatcher = ClosureDispatcher::default().import("env", "store", |arguments, memory| {
+        calls.set(calls.get() + 1);
+
+        let addr = arguments[0].expect_i32().unwrap() as usize;
+        let value = arguments[1].expect_i32().unwrap() as u8;
+        memory[addr] = value;
+
+        Some(Value::I32(addr as i32))
+    });
+
+    let mut inst = Instance::for_module(&arena, &module, dispatcher, false).unwrap();
+
+    let return_val = inst.call_export(start_fn_name, []).unwrap().unwrap();
+
+    assert_eq!(return_val, Value::I32(16));
+    assert_eq!(inst.read_memory(15, 3).unwrap(), &[0, 42, 0]);
+    assert!(inst.read_memory(u32::MAX, 1).is_err());
+
+    drop(inst);
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn test_trap_names_function() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    module.code.function_count = 1;
+    module.code.function_offsets.push(0);
+    module.add_function_signature(Signature {
+        param_types: Vec::new_in(&arena),
+        ret_types: Vec::new_in(&arena),
+    });
+    module.export.append(Export {
+        name: "test",
+        ty: ExportType::Func,
+        index: 0,
+    });
+    module.names.append_function(0, "roc_crash");
+    [
+        0, // no locals
+        OpCode::UNREACHABLE as u8,
+        OpCode::END as u8,
+    ]
+    .serialize(&mut module.code.bytes);
+
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+
+    let error = inst.call_export("test", []).unwrap_err();
+
+    assert!(
+        error.starts_with("WebAssembly `unreachable` instruction at file offset")
+            && error.contains("in function `roc_crash`"),
+        "{error}"
+    );
+}
+
 #[test]
 fn test_call_return_no_args() {
     let arena = Bump::new();
@@ -586,7 +688,7 @@ fn test_call_return_no_args() {
     module.code.function_offsets.push(func0_offset);
     module.add_function_signature(Signature {
         param_types: Vec::new_in(&arena),
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     });
     module.export.append(Export {
         name: start_fn_name,
@@ -616,7 +718,7 @@ fn test_call_return_no_args() {
     module.code.function_offsets.push(func1_offset);
     module.add_function_signature(Signature {
         param_types: Vec::new_in(&arena),
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     });
     [
         0, // no locals
@@ -650,7 +752,7 @@ fn test_call_return_with_args() {
     // Function 0: calculate 2+2
     let signature0 = Signature {
         param_types: bumpalo::vec![in &arena],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     };
     create_exported_function_no_locals(&mut module, "two_plus_two", signature0, |buf| {
         buf.push(OpCode::I32CONST as u8);
@@ -667,7 +769,7 @@ fn test_call_return_with_args() {
     module.code.function_offsets.push(func1_offset);
     module.add_function_signature(Signature {
         param_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     });
     [
         0, // no locals
@@ -682,7 +784,7 @@ fn test_call_return_with_args() {
 
     let signature0 = Signature {
         param_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     };
     create_exported_function_no_locals(&mut module, "add", signature0, |buf| {
         buf.push(OpCode::GETLOCAL as u8);
@@ -700,6 +802,56 @@ fn test_call_return_with_args() {
     assert_eq!(result, Value::I32(4));
 }
 
+#[test]
+fn test_call_return_multi_value() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    // Function 0: subtract the two values returned by function 1
+    let signature0 = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
+    };
+    create_exported_function_no_locals(&mut module, "test", signature0, |buf| {
+        buf.push(OpCode::CALL as u8);
+        buf.push(1);
+        buf.push(OpCode::I32SUB as u8);
+        buf.push(OpCode::END as u8);
+    });
+
+    // Function 1: return two values, by breaking out of a multi-value block
+    let multi_value = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32],
+    };
+    let block_type = BlockType::FunctionType(module.types.insert(multi_value));
+    let signature1 = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32],
+    };
+    create_exported_function_no_locals(&mut module, "pair", signature1, |buf| {
+        buf.push(OpCode::BLOCK as u8);
+        block_type.serialize(buf);
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(55); // dropped by the branch
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(10);
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(3);
+        buf.push(OpCode::BR as u8);
+        buf.push(0);
+        buf.push(OpCode::END as u8);
+        buf.push(OpCode::END as u8);
+    });
+
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    let result = inst.call_export("test", []).unwrap().unwrap();
+
+    assert_eq!(result, Value::I32(7));
+    assert!(inst.value_store.is_empty());
+}
+
 #[test]
 fn test_call_indirect_ok() {
     let result = test_call_indirect_help(0, 0);
@@ -734,7 +886,7 @@ fn test_call_indirect_help(table_index: u32, elem_index: u32) -> Value {
     // function 0: caller
     let signature0 = || Signature {
         param_types: bumpalo::vec![in &arena],
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
     };
     create_exported_function_no_locals(&mut module, start_fn_name, signature0(), |buf| {
         buf.append_u8(OpCode::I32CONST as u8);
@@ -755,7 +907,7 @@ fn test_call_indirect_help(table_index: u32, elem_index: u32) -> Value {
     // function 2: callee, wrong signature
     let signature1 = Signature {
         param_types: bumpalo::vec![in &arena],
-        ret_type: Some(ValueType::F32),
+        ret_types: bumpalo::vec![in &arena; ValueType::F32],
     };
     create_exported_function_no_locals(&mut module, "callee2", signature1, |buf| {
         buf.append_u8(OpCode::F32CONST as u8);
@@ -786,6 +938,63 @@ fn test_call_indirect_help(table_index: u32, elem_index: u32) -> Value {
     inst.call_export(start_fn_name, []).unwrap().unwrap()
 }
 
+#[test]
+fn test_table_set_changes_call_indirect() {
+    let result = test_table_get_set_help(1);
+    assert_eq!(result, Ok(Some(Value::I32(222))));
+}
+
+#[test]
+fn test_table_get_out_of_bounds() {
+    let result = test_table_get_set_help(2);
+    assert!(matches!(result, Err(message) if message.contains("function table element 2")));
+}
+
+/// Copies table element `src_elem_index` into element 0, then calls element 0
+fn test_table_get_set_help(src_elem_index: u32) -> Result<Option<Value>, String> {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    let signature = || Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_types: bumpalo::vec![in &arena; ValueType::I32],
+    };
+
+    // function 0: patch the table, then call through it
+    create_exported_function_no_locals(&mut module, "test", signature(), |buf| {
+        buf.append_u8(OpCode::I32CONST as u8);
+        buf.encode_u32(0);
+        buf.append_u8(OpCode::I32CONST as u8);
+        buf.encode_u32(src_elem_index);
+        buf.append_u8(OpCode::TABLEGET as u8);
+        buf.encode_u32(0);
+        buf.append_u8(OpCode::TABLESET as u8);
+        buf.encode_u32(0);
+        buf.append_u8(OpCode::I32CONST as u8);
+        buf.encode_u32(0);
+        buf.append_u8(OpCode::CALLINDIRECT as u8);
+        buf.encode_u32(0); // signature index
+        buf.encode_u32(0); // table index
+        buf.append_u8(OpCode::END as u8);
+    });
+
+    for (name, value) in [("callee1", 111), ("callee2", 222)] {
+        create_exported_function_no_locals(&mut module, name, signature(), |buf| {
+            buf.append_u8(OpCode::I32CONST as u8);
+            buf.encode_i32(value);
+            buf.append_u8(OpCode::END as u8);
+        });
+    }
+
+    module.element.segments.push(ElementSegment::new(&arena));
+    assert_eq!(module.element.get_or_insert_fn(1), 0);
+    assert_eq!(module.element.get_or_insert_fn(2), 1);
+
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), true).unwrap();
+    inst.call_export("test", [])
+}
+
 // #[test]
 // fn test_drop() {}
 
@@ -802,7 +1011,7 @@ fn test_select_help(first: Value, second: Value, condition: i32, expected: Value
     // Function 0: calculate 2+2
     let signature0 = Signature {
         param_types: bumpalo::vec![in &arena],
-        ret_type: Some(ValueType::from(expected)),
+        ret_types: bumpalo::vec![in &arena; ValueType::from(expected)],
     };
     create_exported_function_no_locals(&mut module, "test", signature0, |buf| {
         const_value(buf, first);
@@ -839,13 +1048,13 @@ fn test_set_get_local() {
     let return_addr = 0x1234;
     let return_block_depth = 0;
     let n_args = 0;
-    let ret_type = Some(ValueType::I32);
+    let return_count = 1;
     inst.current_frame = Frame::enter(
         fn_index,
         return_addr,
         return_block_depth,
         n_args,
-        ret_type,
+        return_count,
         &buffer,
         &mut inst.value_store,
         &mut cursor,
@@ -886,13 +1095,13 @@ fn test_tee_get_local() {
     let return_addr = 0x1234;
     let return_block_depth = 0;
     let n_args = 0;
-    let ret_type = Some(ValueType::I32);
+    let return_count = 1;
     inst.current_frame = Frame::enter(
         fn_index,
         return_addr,
         return_block_depth,
         n_args,
-        ret_type,
+        return_count,
         &buffer,
         &mut inst.value_store,
         &mut cursor,
@@ -17,9 +17,9 @@ use bumpalo::{collections::Vec, Bump};
 use self::linking::{IndexRelocType, LinkingSection, RelocationSection, WasmObjectSymbol};
 use self::parse::{Parse, ParseError};
 use self::sections::{
-    CodeSection, DataSection, ElementSection, ExportSection, FunctionSection, GlobalSection,
-    ImportDesc, ImportSection, MemorySection, NameSection, OpaqueSection, Section, SectionId,
-    TableSection, TypeSection,
+    CodeSection, CustomSection, DataSection, ElementSection, ExportSection, FunctionSection,
+    GlobalSection, ImportDesc, ImportSection, MemorySection, NameSection, OpaqueSection,
+    ProducersSection, Section, SectionId, TableSection, TargetFeaturesSection, TypeSection,
 };
 pub use self::serialize::{SerialBuffer, Serialize};
 
@@ -45,6 +45,9 @@ pub struct WasmModule<'a> {
     pub reloc_code: RelocationSection<'a>,
     pub reloc_data: RelocationSection<'a>,
     pub names: NameSection<'a>,
+    pub custom: Vec<'a, CustomSection<'a>>,
+    pub producers: ProducersSection<'a>,
+    pub target_features: TargetFeaturesSection<'a>,
 }
 
 impl<'a> WasmModule<'a> {
@@ -67,6 +70,9 @@ impl<'a> WasmModule<'a> {
             reloc_code: RelocationSection::new(arena, "reloc.CODE"),
             reloc_data: RelocationSection::new(arena, "reloc.DATA"),
             names: NameSection::new(arena),
+            custom: Vec::new_in(arena),
+            producers: ProducersSection::new(arena),
+            target_features: TargetFeaturesSection::new(arena),
         }
     }
 
@@ -96,6 +102,11 @@ impl<'a> WasmModule<'a> {
         self.code.serialize(buffer);
         self.data.serialize(buffer);
         self.names.serialize(buffer);
+        for section in self.custom.iter() {
+            section.serialize(buffer);
+        }
+        self.producers.serialize(buffer);
+        self.target_features.serialize(buffer);
     }
 
     /// Module size in bytes (assuming no linker data)
@@ -113,6 +124,13 @@ impl<'a> WasmModule<'a> {
             + self.code.size()
             + self.data.size()
             + self.names.size()
+            + self
+                .custom
+                .iter()
+                .map(|section| section.size())
+                .sum::<usize>()
+            + self.producers.size()
+            + self.target_features.size()
     }
 
     pub fn preload(
@@ -150,6 +168,8 @@ impl<'a> WasmModule<'a> {
         let mut reloc_code = RelocationSection::new(arena, "reloc.CODE");
         let mut reloc_data = RelocationSection::new(arena, "reloc.DATA");
         let mut names = NameSection::new(arena);
+        let mut producers = ProducersSection::new(arena);
+        let mut target_features = TargetFeaturesSection::new(arena);
 
         // Consume all remaining Custom sections
         while let Ok((section_name, section_end)) = Self::peek_custom_section(arena, bytes, cursor)
@@ -169,6 +189,12 @@ impl<'a> WasmModule<'a> {
                 "name" => {
                     names = NameSection::parse(arena, bytes, &mut cursor)?;
                 }
+                "producers" => {
+                    producers = ProducersSection::parse(arena, bytes, &mut cursor)?;
+                }
+                "target_features" => {
+                    target_features = TargetFeaturesSection::parse(arena, bytes, &mut cursor)?;
+                }
                 _ => {
                     cursor = section_end;
                 }
@@ -230,6 +256,9 @@ impl<'a> WasmModule<'a> {
             reloc_code,
             reloc_data,
             names,
+            custom: Vec::new_in(arena),
+            producers,
+            target_features,
         })
     }
 
@@ -743,6 +772,50 @@ impl Parse<()> for (u32, ValueType) {
     }
 }
 
+/// Type of a `block`, `loop` or `if`.
+/// Several results (or any parameters) need a signature from the type section.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BlockType {
+    NoResult,
+    Value(ValueType),
+    FunctionType(u32),
+}
+
+impl Serialize for BlockType {
+    fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
+        match self {
+            Self::NoResult => buffer.append_u8(ValueType::VOID),
+            Self::Value(ty) => ty.serialize(buffer),
+            // Encoded as a signed 33-bit integer, so that it can't be confused with a ValueType
+            Self::FunctionType(sig_index) => {
+                buffer.encode_i64(*sig_index as i64);
+            }
+        }
+    }
+}
+
+impl Parse<()> for BlockType {
+    fn parse(_: (), bytes: &[u8], cursor: &mut usize) -> Result<Self, ParseError> {
+        match bytes[*cursor] {
+            ValueType::VOID => {
+                *cursor += 1;
+                Ok(Self::NoResult)
+            }
+            0x7c..=0x7f => ValueType::parse((), bytes, cursor).map(Self::Value),
+            _ => {
+                let start = *cursor;
+                let sig_index = i64::parse((), bytes, cursor)?;
+                u32::try_from(sig_index)
+                    .map(Self::FunctionType)
+                    .map_err(|_| ParseError {
+                        offset: start,
+                        message: format!("Invalid block type {sig_index}"),
+                    })
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Value {
     I32(i32),
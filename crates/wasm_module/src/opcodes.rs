@@ -26,6 +26,8 @@ pub enum OpCode {
     TEELOCAL = 0x22,
     GETGLOBAL = 0x23,
     SETGLOBAL = 0x24,
+    TABLEGET = 0x25,
+    TABLESET = 0x26,
     I32LOAD = 0x28,
     I64LOAD = 0x29,
     F32LOAD = 0x2a,
@@ -218,6 +220,8 @@ pub const LOOKUP_TABLE: [Option<OpCode>; 256] = {
     result[0x22] = Some(TEELOCAL);
     result[0x23] = Some(GETGLOBAL);
     result[0x24] = Some(SETGLOBAL);
+    result[0x25] = Some(TABLEGET);
+    result[0x26] = Some(TABLESET);
     result[0x28] = Some(I32LOAD);
     result[0x29] = Some(I64LOAD);
     result[0x2a] = Some(F32LOAD);
@@ -444,7 +448,8 @@ fn immediates_for(op: OpCode) -> Result<OpImmediates, String> {
     let imm = match op {
         UNREACHABLE => NoImmediate,
         NOP => NoImmediate,
-        BLOCK | LOOP | IF => Byte1,
+        // A ValueType byte, or a signed LEB-128 type index for multi-value blocks
+        BLOCK | LOOP | IF => Leb64x1,
         ELSE => NoImmediate,
         END => NoImmediate,
         BR | BRIF => Leb32x1,
@@ -456,6 +461,7 @@ fn immediates_for(op: OpCode) -> Result<OpImmediates, String> {
         SELECT => NoImmediate,
         GETLOCAL | SETLOCAL | TEELOCAL => Leb32x1,
         GETGLOBAL | SETGLOBAL => Leb32x1,
+        TABLEGET | TABLESET => Leb32x1,
 
         I32LOAD | I64LOAD | F32LOAD | F64LOAD | I32LOAD8S | I32LOAD8U | I32LOAD16S | I32LOAD16U
         | I64LOAD8S | I64LOAD8U | I64LOAD16S | I64LOAD16U | I64LOAD32S | I64LOAD32U | I32STORE
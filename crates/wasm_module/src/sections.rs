@@ -198,7 +198,8 @@ pub fn update_section_size<T: SerialBuffer>(buffer: &mut T, header_indices: Sect
 #[derive(PartialEq, Eq, Debug)]
 pub struct Signature<'a> {
     pub param_types: Vec<'a, ValueType>,
-    pub ret_type: Option<ValueType>,
+    /// Usually zero or one value. More than one needs the "multivalue" feature.
+    pub ret_types: Vec<'a, ValueType>,
 }
 
 impl Signature<'_> {
@@ -209,18 +210,18 @@ impl<'a> Serialize for Signature<'a> {
     fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
         buffer.append_u8(Self::SEPARATOR);
         self.param_types.serialize(buffer);
-        self.ret_type.serialize(buffer);
+        self.ret_types.serialize(buffer);
     }
 }
 
 #[derive(Debug)]
-pub struct SignatureParamsIter<'a> {
+pub struct SignatureTypesIter<'a> {
     bytes: &'a [u8],
     index: usize,
     end: usize,
 }
 
-impl<'a> Iterator for SignatureParamsIter<'a> {
+impl<'a> Iterator for SignatureTypesIter<'a> {
     type Item = ValueType;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -240,9 +241,9 @@ impl<'a> Iterator for SignatureParamsIter<'a> {
     }
 }
 
-impl<'a> ExactSizeIterator for SignatureParamsIter<'a> {}
+impl<'a> ExactSizeIterator for SignatureTypesIter<'a> {}
 
-impl<'a> DoubleEndedIterator for SignatureParamsIter<'a> {
+impl<'a> DoubleEndedIterator for SignatureTypesIter<'a> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.end == 0 {
             None
@@ -299,23 +300,24 @@ impl<'a> TypeSection<'a> {
         self.bytes.is_empty()
     }
 
-    pub fn look_up(&'a self, sig_index: u32) -> (SignatureParamsIter<'a>, Option<ValueType>) {
+    /// Parameter types and result types of a signature
+    pub fn look_up(&'a self, sig_index: u32) -> (SignatureTypesIter<'a>, SignatureTypesIter<'a>) {
         let mut offset = self.offsets[sig_index as usize];
         offset += 1; // separator
-        let param_count = u32::parse((), &self.bytes, &mut offset).unwrap() as usize;
-        let params_iter = SignatureParamsIter {
-            bytes: &self.bytes[offset..][..param_count],
-            index: 0,
-            end: param_count,
-        };
-        offset += param_count;
+        let params_iter = self.types_iter_at(&mut offset);
+        let results_iter = self.types_iter_at(&mut offset);
+        (params_iter, results_iter)
+    }
 
-        let return_type = if self.bytes[offset] == 0 {
-            None
-        } else {
-            Some(ValueType::from(self.bytes[offset + 1]))
+    fn types_iter_at(&'a self, offset: &mut usize) -> SignatureTypesIter<'a> {
+        let count = u32::parse((), &self.bytes, offset).unwrap() as usize;
+        let iter = SignatureTypesIter {
+            bytes: &self.bytes[*offset..][..count],
+            index: 0,
+            end: count,
         };
-        (params_iter, return_type)
+        *offset += count;
+        iter
     }
 }
 
@@ -716,6 +718,7 @@ impl Parse<()> for TableSection {
                         message: "Only funcref tables are supported".into(),
                     })
                 } else {
+                    *cursor += 1; // ref type
                     let limits = Limits::parse((), module_bytes, cursor)?;
                     *cursor = range.end;
                     Ok(TableSection {
@@ -1328,6 +1331,14 @@ impl<'a> ElementSection<'a> {
         self.segments.iter().all(|seg| seg.fn_indices.is_empty())
     }
 
+    /// Each table index that the segments initialize, with the function index they put there
+    pub fn table_entries(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.segments.iter().flat_map(|seg| {
+            let offset = seg.offset.unwrap_i32() as u32;
+            (offset..).zip(seg.fn_indices.iter().copied())
+        })
+    }
+
     /// Look up a "function pointer" (element index) and return the function index.
     pub fn lookup(&self, element_index: u32) -> Option<u32> {
         self.segments.iter().find_map(|seg| {
@@ -1696,6 +1707,35 @@ impl Serialize for OpaqueSection<'_> {
     }
 }
 
+/*******************************************************************
+ *
+ * Custom section
+ *
+ *******************************************************************/
+
+/// A Custom section that we generate for consumers of the module, such as the host.
+/// We never need to read these back, so any Custom sections in a preloaded binary
+/// that we don't otherwise understand are dropped rather than parsed into this.
+#[derive(Debug)]
+pub struct CustomSection<'a> {
+    pub name: &'a str,
+    pub bytes: &'a [u8],
+}
+
+impl<'a> CustomSection<'a> {
+    pub fn size(&self) -> usize {
+        MAX_SIZE_SECTION_HEADER + MAX_SIZE_ENCODED_U32 + self.name.len() + self.bytes.len()
+    }
+}
+
+impl Serialize for CustomSection<'_> {
+    fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
+        let header_indices = write_custom_section_header(buffer, self.name);
+        buffer.append_slice(self.bytes);
+        update_section_size(buffer, header_indices);
+    }
+}
+
 /*******************************************************************
  *
  * Name section
@@ -1867,6 +1907,260 @@ impl<'a> Debug for NameSection<'a> {
     }
 }
 
+/*******************************************************************
+ *
+ * Producers section
+ * https://github.com/WebAssembly/tool-conventions/blob/main/ProducersSection.md
+ *
+ *******************************************************************/
+
+/// The languages and tools that made the module, each with a version.
+/// Optimizers and bundlers read it to find out what they're dealing with.
+#[derive(Debug)]
+pub struct ProducersSection<'a> {
+    /// Field name, and the (name, version) pairs for that field
+    pub fields: Vec<'a, (&'a str, Vec<'a, (&'a str, &'a str)>)>,
+}
+
+impl<'a> ProducersSection<'a> {
+    const NAME: &'static str = "producers";
+
+    pub const LANGUAGE: &'static str = "language";
+    pub const PROCESSED_BY: &'static str = "processed-by";
+    pub const SDK: &'static str = "sdk";
+
+    pub fn new(arena: &'a Bump) -> Self {
+        ProducersSection {
+            fields: Vec::new_in(arena),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn size(&self) -> usize {
+        let values = self.fields.iter().flat_map(|(_, values)| values.iter());
+        MAX_SIZE_SECTION_HEADER
+            + Self::NAME.len()
+            + self
+                .fields
+                .iter()
+                .map(|(field, _)| 2 * MAX_SIZE_ENCODED_U32 + field.len())
+                .sum::<usize>()
+            + values
+                .map(|(name, version)| 2 * MAX_SIZE_ENCODED_U32 + name.len() + version.len())
+                .sum::<usize>()
+    }
+
+    /// Add a producer to a field. A name can only appear once per field, so if it's already
+    /// there (say, from the host), its version is replaced.
+    pub fn add(&mut self, field: &'a str, name: &'a str, version: &'a str) {
+        let arena = self.fields.bump();
+        let values = match self.fields.iter_mut().position(|(f, _)| *f == field) {
+            Some(index) => &mut self.fields[index].1,
+            None => {
+                self.fields.push((field, Vec::new_in(arena)));
+                &mut self.fields.last_mut().unwrap().1
+            }
+        };
+
+        match values.iter_mut().find(|(n, _)| *n == name) {
+            Some(value) => value.1 = version,
+            None => values.push((name, version)),
+        }
+    }
+}
+
+impl<'a> Parse<&'a Bump> for ProducersSection<'a> {
+    fn parse(arena: &'a Bump, module_bytes: &[u8], cursor: &mut usize) -> Result<Self, ParseError> {
+        let cursor_start = *cursor;
+
+        if *cursor >= module_bytes.len() || module_bytes[*cursor] != SectionId::Custom as u8 {
+            return Ok(Self::new(arena));
+        }
+        *cursor += 1;
+
+        let section_size = u32::parse((), module_bytes, cursor)? as usize;
+        let section_end = *cursor + section_size;
+
+        let section_name = <&'a str>::parse(arena, module_bytes, cursor)?;
+        if section_name != Self::NAME {
+            *cursor = cursor_start;
+            return Ok(Self::new(arena));
+        }
+
+        let field_count = u32::parse((), module_bytes, cursor)?;
+        let mut section = ProducersSection {
+            fields: Vec::with_capacity_in(field_count as usize, arena),
+        };
+
+        for _ in 0..field_count {
+            let field = <&'a str>::parse(arena, module_bytes, cursor)?;
+            let value_count = u32::parse((), module_bytes, cursor)?;
+            let mut values = Vec::with_capacity_in(value_count as usize, arena);
+            for _ in 0..value_count {
+                let name = <&'a str>::parse(arena, module_bytes, cursor)?;
+                let version = <&'a str>::parse(arena, module_bytes, cursor)?;
+                values.push((name, version));
+            }
+            section.fields.push((field, values));
+        }
+
+        *cursor = section_end;
+
+        Ok(section)
+    }
+}
+
+impl<'a> Serialize for ProducersSection<'a> {
+    fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
+        if !self.is_empty() {
+            let header_indices = write_custom_section_header(buffer, Self::NAME);
+
+            buffer.encode_u32(self.fields.len() as u32);
+            for (field, values) in self.fields.iter() {
+                field.serialize(buffer);
+                values.serialize(buffer);
+            }
+
+            update_section_size(buffer, header_indices);
+        }
+    }
+}
+
+/*******************************************************************
+ *
+ * Target features section
+ * https://github.com/WebAssembly/tool-conventions/blob/main/Linking.md#target-features-section
+ *
+ *******************************************************************/
+
+/// Whether a module uses a Wasm feature. Tools only apply transformations that
+/// introduce a feature, like `wasm-opt` turning loops into `memory.copy`, if it's `Used`.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeaturePolicy {
+    Used = b'+',
+    Disallowed = b'-',
+    Required = b'=',
+}
+
+impl Parse<()> for FeaturePolicy {
+    fn parse(_: (), bytes: &[u8], cursor: &mut usize) -> Result<Self, ParseError> {
+        let policy = match bytes[*cursor] {
+            b'+' => Self::Used,
+            b'-' => Self::Disallowed,
+            b'=' => Self::Required,
+            _ => {
+                return Err(ParseError {
+                    offset: *cursor,
+                    message: format!("Unknown target feature policy {:#x}", bytes[*cursor]),
+                })
+            }
+        };
+        *cursor += 1;
+        Ok(policy)
+    }
+}
+
+impl Serialize for FeaturePolicy {
+    fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
+        buffer.append_u8(*self as u8);
+    }
+}
+
+#[derive(Debug)]
+pub struct TargetFeaturesSection<'a> {
+    pub features: Vec<'a, (FeaturePolicy, &'a str)>,
+}
+
+impl<'a> TargetFeaturesSection<'a> {
+    const NAME: &'static str = "target_features";
+
+    pub const BULK_MEMORY: &'static str = "bulk-memory";
+    pub const MULTIVALUE: &'static str = "multivalue";
+    pub const MUTABLE_GLOBALS: &'static str = "mutable-globals";
+    pub const NONTRAPPING_FPTOINT: &'static str = "nontrapping-fptoint";
+    pub const REFERENCE_TYPES: &'static str = "reference-types";
+    pub const SIGN_EXT: &'static str = "sign-ext";
+    pub const SIMD128: &'static str = "simd128";
+
+    pub fn new(arena: &'a Bump) -> Self {
+        TargetFeaturesSection {
+            features: Vec::new_in(arena),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    pub fn size(&self) -> usize {
+        MAX_SIZE_SECTION_HEADER
+            + Self::NAME.len()
+            + MAX_SIZE_ENCODED_U32
+            + self
+                .features
+                .iter()
+                .map(|(_, name)| 1 + MAX_SIZE_ENCODED_U32 + name.len())
+                .sum::<usize>()
+    }
+
+    /// Record that the module uses a feature, unless it's already there with another policy.
+    /// A host that disallows a feature keeps it disallowed.
+    pub fn use_feature(&mut self, name: &'a str) {
+        if !self.features.iter().any(|(_, n)| *n == name) {
+            self.features.push((FeaturePolicy::Used, name));
+        }
+    }
+}
+
+impl<'a> Parse<&'a Bump> for TargetFeaturesSection<'a> {
+    fn parse(arena: &'a Bump, module_bytes: &[u8], cursor: &mut usize) -> Result<Self, ParseError> {
+        let cursor_start = *cursor;
+
+        if *cursor >= module_bytes.len() || module_bytes[*cursor] != SectionId::Custom as u8 {
+            return Ok(Self::new(arena));
+        }
+        *cursor += 1;
+
+        let section_size = u32::parse((), module_bytes, cursor)? as usize;
+        let section_end = *cursor + section_size;
+
+        let section_name = <&'a str>::parse(arena, module_bytes, cursor)?;
+        if section_name != Self::NAME {
+            *cursor = cursor_start;
+            return Ok(Self::new(arena));
+        }
+
+        let count = u32::parse((), module_bytes, cursor)?;
+        let mut section = TargetFeaturesSection {
+            features: Vec::with_capacity_in(count as usize, arena),
+        };
+
+        for _ in 0..count {
+            let policy = FeaturePolicy::parse((), module_bytes, cursor)?;
+            let name = <&'a str>::parse(arena, module_bytes, cursor)?;
+            section.features.push((policy, name));
+        }
+
+        *cursor = section_end;
+
+        Ok(section)
+    }
+}
+
+impl<'a> Serialize for TargetFeaturesSection<'a> {
+    fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
+        if !self.is_empty() {
+            let header_indices = write_custom_section_header(buffer, Self::NAME);
+            self.features.serialize(buffer);
+            update_section_size(buffer, header_indices);
+        }
+    }
+}
+
 /*******************************************************************
  *
  * Unit tests
@@ -1898,15 +2192,19 @@ mod tests {
         let signatures = [
             Signature {
                 param_types: bumpalo::vec![in arena],
-                ret_type: None,
+                ret_types: bumpalo::vec![in arena],
             },
             Signature {
                 param_types: bumpalo::vec![in arena; I32, I64, F32, F64],
-                ret_type: None,
+                ret_types: bumpalo::vec![in arena],
             },
             Signature {
                 param_types: bumpalo::vec![in arena; I32, I32, I32],
-                ret_type: Some(I32),
+                ret_types: bumpalo::vec![in arena; I32],
+            },
+            Signature {
+                param_types: bumpalo::vec![in arena; I32],
+                ret_types: bumpalo::vec![in arena; I64, F64],
             },
         ];
         let capacity = signatures.len();
@@ -1920,5 +2218,133 @@ mod tests {
             section.insert(sig);
         }
         test_assert_types_preload(arena, &section);
+
+        let (params, results) = section.look_up(3);
+        assert_eq!(params.collect::<std::vec::Vec<_>>(), [I32]);
+        assert_eq!(results.collect::<std::vec::Vec<_>>(), [I64, F64]);
+    }
+
+    #[test]
+    fn test_block_type() {
+        use crate::BlockType;
+        let arena = &Bump::new();
+        let block_types = [
+            BlockType::NoResult,
+            BlockType::Value(ValueType::F64),
+            BlockType::FunctionType(3),
+            BlockType::FunctionType(0x40),
+            BlockType::FunctionType(100_000),
+        ];
+
+        let mut buffer = Vec::new_in(arena);
+        for block_type in block_types.iter() {
+            block_type.serialize(&mut buffer);
+        }
+
+        let mut cursor = 0;
+        for block_type in block_types {
+            assert_eq!(
+                BlockType::parse((), &buffer, &mut cursor).unwrap(),
+                block_type
+            );
+        }
+        assert_eq!(cursor, buffer.len());
+    }
+
+    #[test]
+    fn test_table_section() {
+        let arena = &Bump::new();
+        for limits in [Limits::Min(8), Limits::MinMax(3, 3)] {
+            let mut section = TableSection::new();
+            section.function_table.limits = limits;
+
+            let mut buffer = Vec::new_in(arena);
+            section.serialize(&mut buffer);
+
+            let mut cursor = 0;
+            let parsed = TableSection::parse((), &buffer, &mut cursor).unwrap();
+            assert_eq!(parsed.function_table.limits, section.function_table.limits);
+            assert_eq!(cursor, buffer.len());
+        }
+    }
+
+    #[test]
+    fn test_custom_section() {
+        let arena = &Bump::new();
+        let section = CustomSection {
+            name: "roc",
+            bytes: &[1, 2, 3],
+        };
+
+        let mut buffer = Vec::with_capacity_in(section.size(), arena);
+        section.serialize(&mut buffer);
+
+        assert_eq!(buffer[0], SectionId::Custom as u8);
+        let mut cursor = 1;
+        let size = u32::parse((), &buffer, &mut cursor).unwrap();
+        assert_eq!(cursor + size as usize, buffer.len());
+
+        let name = <&str>::parse(arena, &buffer, &mut cursor).unwrap();
+        assert_eq!(name, "roc");
+        assert_eq!(&buffer[cursor..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_producers_section() {
+        let arena = &Bump::new();
+        let mut section = ProducersSection::new(arena);
+        section.add(ProducersSection::LANGUAGE, "Zig", "0.11.0");
+        section.add(ProducersSection::PROCESSED_BY, "clang", "16.0.0");
+        section.add(ProducersSection::LANGUAGE, "Roc", "");
+        section.add(ProducersSection::LANGUAGE, "Zig", "0.12.0");
+
+        let mut buffer = Vec::with_capacity_in(section.size(), arena);
+        section.serialize(&mut buffer);
+        assert!(buffer.len() <= section.size());
+
+        let mut cursor = 0;
+        let parsed = ProducersSection::parse(arena, &buffer, &mut cursor).unwrap();
+        assert_eq!(cursor, buffer.len());
+
+        let fields = std::vec::Vec::from_iter(
+            parsed
+                .fields
+                .iter()
+                .map(|(field, values)| (*field, values.as_slice())),
+        );
+        assert_eq!(
+            fields,
+            [
+                ("language", [("Zig", "0.12.0"), ("Roc", "")].as_slice()),
+                ("processed-by", [("clang", "16.0.0")].as_slice()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_target_features_section() {
+        let arena = &Bump::new();
+        let mut section = TargetFeaturesSection::new(arena);
+        section
+            .features
+            .push((FeaturePolicy::Disallowed, "simd128"));
+        section.use_feature(TargetFeaturesSection::SIGN_EXT);
+        section.use_feature(TargetFeaturesSection::SIMD128);
+        section.use_feature(TargetFeaturesSection::SIGN_EXT);
+
+        let mut buffer = Vec::with_capacity_in(section.size(), arena);
+        section.serialize(&mut buffer);
+        assert!(buffer.len() <= section.size());
+
+        let mut cursor = 0;
+        let parsed = TargetFeaturesSection::parse(arena, &buffer, &mut cursor).unwrap();
+        assert_eq!(cursor, buffer.len());
+        assert_eq!(
+            parsed.features.as_slice(),
+            [
+                (FeaturePolicy::Disallowed, "simd128"),
+                (FeaturePolicy::Used, "sign-ext"),
+            ]
+        );
     }
 }
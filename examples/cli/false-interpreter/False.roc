@@ -109,7 +109,9 @@ interpretCtxLoop = \ctx ->
 
                                         Task.succeed (Step { popCtx & scopes: List.set ctx.scopes last newScope })
                                     else
-                                        newScope = { scope & whileInfo: Some { state: InBody, body, cond } }
+                                        newScope = { scope &
+                                            whileInfo: Some { state: InBody, body, cond },
+                                        }
 
                                         Task.succeed (Step { popCtx & scopes: List.append (List.set ctx.scopes last newScope) { data: None, buf: body, index: 0, whileInfo: None } })
 